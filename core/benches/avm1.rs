@@ -0,0 +1,53 @@
+//! Benchmarks of ActionScript 1 and 2 execution in a headless Ruffle instance.
+//!
+//! Every movie in `benches/swfs` runs its script on the first of two frames,
+//! so each iteration loops the movie once and runs the whole script again.
+
+use bencher::{benchmark_group, benchmark_main, Bencher};
+use ruffle_core::backend::navigator::NullNavigatorBackend;
+use ruffle_core::backend::render::NullRenderer;
+use ruffle_core::backend::storage::MemoryStorageBackend;
+use ruffle_core::backend::{
+    audio::NullAudioBackend, font::NullFontBackend, input::NullInputBackend,
+    video::NullVideoBackend,
+};
+use ruffle_core::tag_utils::SwfMovie;
+use ruffle_core::Player;
+
+/// Calls a function 2,000 times, which builds an object with a few properties
+/// and returns it, and sums up the properties of each object with `for..in`.
+fn properties(bench: &mut Bencher) {
+    run_swf(bench, "benches/swfs/avm1_properties.swf");
+}
+
+/// Sums 100,000 numbers in a `DefineFunction2` loop that only uses registers, and
+/// calls a two argument function 10,000 times. None of the functions use
+/// `this`, `arguments` or `super`, so they are all suppressed.
+fn function2(bench: &mut Bencher) {
+    run_swf(bench, "benches/swfs/avm1_function2.swf");
+}
+
+fn run_swf(bench: &mut Bencher, swf_path: &str) {
+    let movie = SwfMovie::from_path(swf_path).unwrap();
+    let player = Player::new(
+        Box::new(NullRenderer::new()),
+        Box::new(NullAudioBackend::new()),
+        Box::new(NullNavigatorBackend::new()),
+        Box::new(NullInputBackend::new()),
+        movie,
+        Box::new(MemoryStorageBackend::default()),
+        Box::new(NullFontBackend::new()),
+        Box::new(NullVideoBackend::new()),
+    )
+    .unwrap();
+    let mut player = player.lock().unwrap();
+    player.set_is_playing(true);
+
+    bench.iter(|| {
+        player.run_frame();
+        player.run_frame();
+    });
+}
+
+benchmark_group!(avm1, properties, function2);
+benchmark_main!(avm1);
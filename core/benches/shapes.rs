@@ -0,0 +1,165 @@
+//! Benchmarks of rendering many copies of a movie clip with a shape in it.
+//!
+//! Both movies in `benches/swfs` make 1,000 copies of a clip with
+//! `duplicateMovieClip` on their first frame, and lay them out in a grid.
+//! Each iteration creates a new player, runs that frame and renders it once.
+
+use bencher::{benchmark_group, benchmark_main, Bencher};
+use ruffle_core::backend::navigator::NullNavigatorBackend;
+use ruffle_core::backend::render::{
+    Bitmap, BitmapCacheHandle, BitmapHandle, BitmapInfo, Color, Letterbox, NullRenderer,
+    RenderBackend, ShapeHandle, Transform,
+};
+use ruffle_core::backend::storage::MemoryStorageBackend;
+use ruffle_core::backend::{
+    audio::NullAudioBackend, font::NullFontBackend, input::NullInputBackend,
+    video::NullVideoBackend,
+};
+use ruffle_core::shape_utils::DistilledShape;
+use ruffle_core::swf;
+use ruffle_core::tag_utils::SwfMovie;
+use ruffle_core::{Player, StageQuality};
+
+type Error = Box<dyn std::error::Error>;
+
+/// Duplicates a clip that shows a `DefineShape`.
+///
+/// Every copy uses the shape registered for the character, so rendering
+/// registers the shape once.
+fn duplicate_clips(bench: &mut Bencher) {
+    let movie = SwfMovie::from_path("benches/swfs/duplicate_clips.swf").unwrap();
+    bench.iter(|| assert_eq!(render_copies(&movie), 1));
+}
+
+/// Duplicates a clip drawn with the drawing API.
+///
+/// Every copy owns a copy of the drawing, so rendering registers a shape for
+/// each of them.
+fn duplicate_drawings(bench: &mut Bencher) {
+    let movie = SwfMovie::from_path("benches/swfs/duplicate_drawings.swf").unwrap();
+    bench.iter(|| assert_eq!(render_copies(&movie), 1000));
+}
+
+/// Runs the first frame of the movie and renders it, and returns the number of
+/// shapes registered with the renderer.
+fn render_copies(movie: &SwfMovie) -> usize {
+    let player = Player::new(
+        Box::new(CountingRenderer::default()),
+        Box::new(NullAudioBackend::new()),
+        Box::new(NullNavigatorBackend::new()),
+        Box::new(NullInputBackend::new()),
+        movie.clone(),
+        Box::new(MemoryStorageBackend::default()),
+        Box::new(NullFontBackend::new()),
+        Box::new(NullVideoBackend::new()),
+    )
+    .unwrap();
+    let mut player = player.lock().unwrap();
+    player.run_frame();
+    player.render();
+
+    player
+        .renderer()
+        .downcast_ref::<CountingRenderer>()
+        .unwrap()
+        .registered
+}
+
+/// A renderer that counts the shapes registered with it, but doesn't draw them.
+#[derive(Default)]
+struct CountingRenderer {
+    registered: usize,
+}
+
+impl RenderBackend for CountingRenderer {
+    fn set_viewport_dimensions(&mut self, _width: u32, _height: u32) {}
+    fn register_shape(&mut self, _shape: DistilledShape) -> ShapeHandle {
+        self.registered += 1;
+        ShapeHandle(self.registered - 1)
+    }
+    fn replace_shape(&mut self, _shape: DistilledShape, _handle: ShapeHandle) {}
+    fn register_glyph_shape(&mut self, _shape: &swf::Glyph) -> ShapeHandle {
+        ShapeHandle(0)
+    }
+    fn release_shape(&mut self, _shape: ShapeHandle) {}
+    fn release_bitmap(&mut self, _bitmap: BitmapHandle) {}
+    fn register_bitmap_jpeg(
+        &mut self,
+        id: swf::CharacterId,
+        data: &[u8],
+        jpeg_tables: Option<&[u8]>,
+    ) -> Result<BitmapInfo, Error> {
+        NullRenderer.register_bitmap_jpeg(id, data, jpeg_tables)
+    }
+    fn register_bitmap_jpeg_2(
+        &mut self,
+        id: swf::CharacterId,
+        data: &[u8],
+    ) -> Result<BitmapInfo, Error> {
+        NullRenderer.register_bitmap_jpeg_2(id, data)
+    }
+    fn register_bitmap_jpeg_3(
+        &mut self,
+        id: swf::CharacterId,
+        jpeg_data: &[u8],
+        alpha_data: &[u8],
+    ) -> Result<BitmapInfo, Error> {
+        NullRenderer.register_bitmap_jpeg_3(id, jpeg_data, alpha_data)
+    }
+    fn register_bitmap_png(
+        &mut self,
+        swf_tag: &swf::DefineBitsLossless,
+    ) -> Result<BitmapInfo, Error> {
+        NullRenderer.register_bitmap_png(swf_tag)
+    }
+    fn register_bitmap_rgba(
+        &mut self,
+        id: swf::CharacterId,
+        width: u32,
+        height: u32,
+        rgba: Vec<u8>,
+    ) -> Result<BitmapInfo, Error> {
+        NullRenderer.register_bitmap_rgba(id, width, height, rgba)
+    }
+    fn update_bitmap_rgba(
+        &mut self,
+        _bitmap: BitmapHandle,
+        _width: u32,
+        _height: u32,
+        _rgba: Vec<u8>,
+    ) -> Result<(), Error> {
+        Ok(())
+    }
+    fn set_quality(&mut self, _quality: StageQuality) {}
+    fn begin_frame(&mut self, _clear: Color) {}
+    fn render_bitmap(&mut self, _bitmap: BitmapHandle, _transform: &Transform, _smoothing: bool) {}
+    fn render_shape(&mut self, _shape: ShapeHandle, _transform: &Transform) {}
+    fn end_frame(&mut self) {}
+    fn draw_letterbox(&mut self, _letterbox: Letterbox) {}
+    fn push_mask(&mut self) {}
+    fn activate_mask(&mut self) {}
+    fn pop_mask(&mut self) {}
+    fn push_filters(&mut self, _filters: &[swf::Filter]) {}
+    fn pop_filters(&mut self) {}
+    fn push_blend_mode(&mut self, _blend_mode: swf::BlendMode) {}
+    fn pop_blend_mode(&mut self) {}
+    fn create_bitmap_cache(&mut self) -> BitmapCacheHandle {
+        BitmapCacheHandle(0)
+    }
+    fn render_bitmap_cache(&mut self, _cache: BitmapCacheHandle) -> bool {
+        false
+    }
+    fn begin_bitmap_cache(&mut self, _cache: BitmapCacheHandle) {}
+    fn end_bitmap_cache(&mut self) {}
+    fn release_bitmap_cache(&mut self, _cache: BitmapCacheHandle) {}
+    fn draw_rect(&mut self, _color: Color, _rect: &swf::Rectangle, _transform: &Transform) {}
+    fn begin_capture(&mut self, _width: u32, _height: u32, _clear: Color) -> bool {
+        false
+    }
+    fn end_capture(&mut self) -> Option<Bitmap> {
+        None
+    }
+}
+
+benchmark_group!(shapes, duplicate_clips, duplicate_drawings);
+benchmark_main!(shapes);
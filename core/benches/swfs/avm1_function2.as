@@ -0,0 +1,24 @@
+// SWF version 8, 2 frames.
+// Compiled with a constant pool. The functions keep their parameters and locals
+// in registers, and suppress this, arguments and super.
+
+// Frame 1
+function sumTo(n) {
+    var total = 0;
+    for (var i = 0; i < n; i++) {
+        total = total + i;
+    }
+    return total;
+}
+function add(a, b) {
+    return a + b;
+}
+function callAdd(n) {
+    var total = 0;
+    for (var i = 0; i < n; i++) {
+        total = add(total, i);
+    }
+    return total;
+}
+trace(sumTo(100000));
+trace(callAdd(10000));
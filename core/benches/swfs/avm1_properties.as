@@ -0,0 +1,19 @@
+// SWF version 8, 2 frames.
+// Compiled with a constant pool.
+
+// Frame 1
+function make(i) {
+    var o = new Object();
+    o.x = i;
+    o.y = i;
+    o["p" + i % 10] = i;
+    return o;
+}
+sum = 0;
+for (n = 0; n < 2000; n++) {
+    o = make(n);
+    for (k in o) {
+        sum = sum + o[k];
+    }
+}
+trace(sum);
@@ -0,0 +1,12 @@
+// SWF version 8, 2 frames.
+// The library has the "particle" movie clip of render/common_tess/benches/swfs/particles.swf,
+// which contains a 45x45 shape centered on its origin.
+
+// Frame 1
+this.attachMovie("particle", "p0", 0);
+for (i = 1; i < 1000; i++) {
+    p0.duplicateMovieClip("p" + i, i);
+    this["p" + i]._x = (i % 50) * 11;
+    this["p" + i]._y = int(i / 50) * 20;
+}
+trace(this.getNextHighestDepth());
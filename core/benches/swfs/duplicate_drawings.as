@@ -0,0 +1,17 @@
+// SWF version 8, 2 frames.
+
+// Frame 1
+this.createEmptyMovieClip("p0", 0);
+p0.beginFill(0xFF0000);
+p0.moveTo(-20, 0);
+p0.curveTo(-20, -20, 0, -20);
+p0.curveTo(20, -20, 20, 0);
+p0.curveTo(20, 20, 0, 20);
+p0.curveTo(-20, 20, -20, 0);
+p0.endFill();
+for (i = 1; i < 1000; i++) {
+    p0.duplicateMovieClip("p" + i, i);
+    this["p" + i]._x = (i % 50) * 11;
+    this["p" + i]._y = int(i / 50) * 20;
+}
+trace(this.getNextHighestDepth());
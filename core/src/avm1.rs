@@ -13,11 +13,13 @@ use crate::tag_utils::SwfSlice;
 #[macro_use]
 mod test_utils;
 
-#[macro_use]
 pub mod listeners;
 
 pub mod activation;
+pub mod amf;
+pub mod bitmap_data_object;
 pub mod color_transform_object;
+pub mod date_object;
 pub mod debug;
 pub mod error;
 mod fscommand;
@@ -30,8 +32,11 @@ pub mod script_object;
 pub mod shared_object;
 mod sound_object;
 mod stage_object;
+mod string;
+pub mod style_sheet_object;
 mod super_object;
-mod value;
+pub mod transform_object;
+pub mod value;
 mod value_object;
 pub mod xml_attributes_object;
 pub mod xml_idmap_object;
@@ -42,6 +47,7 @@ mod tests;
 
 use crate::avm1::activation::{Activation, ActivationIdentifier};
 use crate::avm1::error::Error;
+use crate::avm1::globals::as_broadcaster::{self, BroadcasterFunctions};
 use crate::avm1::listeners::SystemListener;
 pub use globals::SystemPrototypes;
 pub use object::{Object, ObjectPtr, TObject};
@@ -50,6 +56,7 @@ pub use script_object::ScriptObject;
 use smallvec::alloc::borrow::Cow;
 pub use sound_object::SoundObject;
 pub use stage_object::StageObject;
+pub use string::AvmString;
 pub use value::Value;
 
 macro_rules! avm_debug {
@@ -59,13 +66,19 @@ macro_rules! avm_debug {
     )
 }
 
+/// The number of actions that the scripts run by a single event may execute
+/// before they are aborted.
+///
+/// This roughly matches the 15 second script timeout of the Flash Player.
+pub const DEFAULT_MAX_ACTIONS: u64 = 150_000_000;
+
 pub struct Avm1<'gc> {
     /// The Flash Player version we're emulating.
     player_version: u8,
 
     /// The constant pool to use for new activations from code sources that
     /// don't close over the constant pool they were defined with.
-    constant_pool: GcCell<'gc, Vec<String>>,
+    constant_pool: GcCell<'gc, Vec<AvmString<'gc>>>,
 
     /// The global object.
     globals: Object<'gc>,
@@ -73,6 +86,9 @@ pub struct Avm1<'gc> {
     /// System builtins that we use internally to construct new objects.
     prototypes: globals::SystemPrototypes<'gc>,
 
+    /// The methods shared by every `AsBroadcaster`.
+    broadcaster_functions: BroadcasterFunctions<'gc>,
+
     /// System event listeners that will respond to native events (Mouse, Key, etc)
     system_listeners: listeners::SystemListeners<'gc>,
 
@@ -89,6 +105,17 @@ pub struct Avm1<'gc> {
     /// If a serious error has occured, or a user has requested it, the AVM may be halted.
     /// This will completely prevent any further actions from being executed.
     halted: bool,
+
+    /// The number of actions that may be run between two calls to
+    /// `reset_action_budget`.
+    max_actions: u64,
+
+    /// The number of actions run since the last call to `reset_action_budget`.
+    actions_run: u64,
+
+    /// Whether a script has been aborted for running too long, since the last
+    /// call to `take_timed_out`.
+    timed_out: bool,
 }
 
 unsafe impl<'gc> gc_arena::Collect for Avm1<'gc> {
@@ -98,6 +125,7 @@ unsafe impl<'gc> gc_arena::Collect for Avm1<'gc> {
         self.constant_pool.trace(cc);
         self.system_listeners.trace(cc);
         self.prototypes.trace(cc);
+        self.broadcaster_functions.trace(cc);
         self.display_properties.trace(cc);
         self.stack.trace(cc);
 
@@ -109,13 +137,15 @@ unsafe impl<'gc> gc_arena::Collect for Avm1<'gc> {
 
 impl<'gc> Avm1<'gc> {
     pub fn new(gc_context: MutationContext<'gc, '_>, player_version: u8) -> Self {
-        let (prototypes, globals, system_listeners) = create_globals(gc_context);
+        let (prototypes, globals, broadcaster_functions, system_listeners) =
+            create_globals(gc_context);
 
         Self {
             player_version,
             constant_pool: GcCell::allocate(gc_context, vec![]),
             globals,
             prototypes,
+            broadcaster_functions,
             system_listeners,
             display_properties: stage_object::DisplayPropertyMap::new(gc_context),
             stack: vec![],
@@ -126,6 +156,9 @@ impl<'gc> Avm1<'gc> {
                 Value::Undefined,
             ],
             halted: false,
+            max_actions: DEFAULT_MAX_ACTIONS,
+            actions_run: 0,
+            timed_out: false,
         }
     }
 
@@ -324,12 +357,9 @@ impl<'gc> Avm1<'gc> {
             active_clip,
         );
 
-        let listeners = activation.avm.system_listeners.get(listener);
-        let mut handlers = listeners.prepare_handlers(&mut activation, context, method);
-
-        for (listener, handler) in handlers.drain(..) {
-            let _ = handler.call(method, &mut activation, context, listener, None, &args);
-        }
+        let broadcaster = activation.avm.system_listeners.get(listener);
+        let _ =
+            as_broadcaster::broadcast_internal(&mut activation, context, broadcaster, method, args);
     }
 
     /// Halts the AVM, preventing execution of any further actions.
@@ -345,6 +375,38 @@ impl<'gc> Avm1<'gc> {
         }
     }
 
+    /// Set the number of actions that the scripts run by a single event may
+    /// execute before they are aborted.
+    pub fn set_max_actions(&mut self, max_actions: u64) {
+        self.max_actions = max_actions;
+    }
+
+    /// Start a new budget of actions, for the dispatch of another event.
+    pub fn reset_action_budget(&mut self) {
+        self.actions_run = 0;
+    }
+
+    /// Returns whether a script has been aborted for running too long since
+    /// the last call, and clears the flag.
+    pub fn take_timed_out(&mut self) -> bool {
+        std::mem::replace(&mut self.timed_out, false)
+    }
+
+    /// Count an action against the budget of the current event.
+    ///
+    /// Once the budget is spent, every further action fails with
+    /// `Error::ExecutionTimeout`, which unwinds the running script. Scripts
+    /// run for later events get a new budget.
+    fn count_action(&mut self) -> Result<(), Error<'gc>> {
+        self.actions_run += 1;
+        if self.actions_run > self.max_actions {
+            self.timed_out = true;
+            return Err(Error::ExecutionTimeout);
+        }
+
+        Ok(())
+    }
+
     fn push(&mut self, value: impl Into<Value<'gc>>) {
         let value = value.into();
         avm_debug!("Stack push {}: {:?}", self.stack.len(), value);
@@ -377,6 +439,11 @@ impl<'gc> Avm1<'gc> {
     pub fn prototypes(&self) -> &globals::SystemPrototypes<'gc> {
         &self.prototypes
     }
+
+    /// Obtain the methods shared by every `AsBroadcaster`.
+    pub fn broadcaster_functions(&self) -> BroadcasterFunctions<'gc> {
+        self.broadcaster_functions
+    }
 }
 
 pub fn root_error_handler<'gc>(
@@ -384,13 +451,18 @@ pub fn root_error_handler<'gc>(
     context: &mut UpdateContext<'_, 'gc, '_>,
     error: Error<'gc>,
 ) {
-    if let Error::ThrownValue(error) = error {
-        let string = error
-            .coerce_to_string(activation, context)
-            .unwrap_or_else(|_| Cow::Borrowed("undefined"));
-        log::info!(target: "avm_trace", "{}", string);
-    } else {
-        log::error!("Uncaught error: {:?}", error);
+    match error {
+        Error::ThrownValue(error) => {
+            let string = error
+                .coerce_to_string(activation, context)
+                .unwrap_or_else(|_| Cow::Borrowed("undefined"));
+            context.log.avm_trace(&string);
+        }
+        // The player reports a timeout once, after every script it aborted.
+        Error::ExecutionTimeout => {}
+        error => context
+            .log
+            .avm_warning(&format!("Uncaught error: {:?}", error)),
     }
 }
 
@@ -485,5 +557,8 @@ pub fn start_drag<'gc>(
         offset,
         constraint,
     };
+    // The object snaps to the mouse and into its constraints right away, rather than waiting
+    // for the mouse to move.
+    drag_object.update_position(context.gc_context, *context.mouse_position);
     *context.drag_object = Some(drag_object);
 }
@@ -5,11 +5,14 @@ use crate::avm1::property::Attribute;
 use crate::avm1::scope::Scope;
 use crate::avm1::value::f64_to_wrapping_u32;
 use crate::avm1::{
-    fscommand, globals, scope, skip_actions, start_drag, value_object, Avm1, ScriptObject, Value,
+    fscommand, globals, scope, skip_actions, start_drag, value_object, Avm1, AvmString,
+    ScriptObject, Value,
 };
-use crate::backend::navigator::{NavigationMethod, RequestOptions};
+use crate::backend::navigator::{NavigationMethod, NavigationTarget, RequestOptions};
 use crate::context::UpdateContext;
 use crate::display_object::{DisplayObject, MovieClip, TDisplayObject};
+use crate::stage::StageQuality;
+use crate::string_utils::swf_string_eq_ignore_case;
 use crate::tag_utils::SwfSlice;
 use enumset::EnumSet;
 use gc_arena::{Collect, Gc, GcCell, MutationContext};
@@ -68,6 +71,9 @@ impl<'gc> RegisterSet<'gc> {
     }
 }
 
+/// The maximum number of `with` blocks that may be nested in a scope chain.
+const MAX_WITH_DEPTH: usize = 15;
+
 #[derive(Debug, Clone)]
 pub enum ReturnType<'gc> {
     Implicit,
@@ -155,7 +161,7 @@ pub struct Activation<'a, 'gc: 'a> {
     scope: GcCell<'gc, Scope<'gc>>,
 
     /// The currently in use constant pool.
-    constant_pool: GcCell<'gc, Vec<String>>,
+    constant_pool: GcCell<'gc, Vec<AvmString<'gc>>>,
 
     /// The immutable value of `this`.
     this: Object<'gc>,
@@ -208,7 +214,7 @@ impl<'a, 'gc: 'a> Activation<'a, 'gc> {
         id: ActivationIdentifier<'a>,
         swf_version: u8,
         scope: GcCell<'gc, Scope<'gc>>,
-        constant_pool: GcCell<'gc, Vec<String>>,
+        constant_pool: GcCell<'gc, Vec<AvmString<'gc>>>,
         base_clip: DisplayObject<'gc>,
         this: Object<'gc>,
         arguments: Option<Object<'gc>>,
@@ -393,6 +399,7 @@ impl<'a, 'gc: 'a> Activation<'a, 'gc> {
             Ok(FrameControl::Return(ReturnType::Implicit))
         } else if let Some(action) = reader.read_action()? {
             avm_debug!("({}) Action: {:?}", self.id.depth(), action);
+            self.avm.count_action()?;
 
             let result = match action {
                 Action::Add => self.action_add(context),
@@ -527,7 +534,8 @@ impl<'a, 'gc: 'a> Activation<'a, 'gc> {
             };
             if let Err(e) = result {
                 match &e {
-                    Error::ThrownValue(_) => {}
+                    // Timeouts unwind every activation, and are reported once by the player.
+                    Error::ThrownValue(_) | Error::ExecutionTimeout => {}
                     e => log::error!("AVM1 error: {}", e),
                 }
                 if e.is_halting() {
@@ -573,10 +581,11 @@ impl<'a, 'gc: 'a> Activation<'a, 'gc> {
         if let Value::String(a) = a {
             let mut s = b.coerce_to_string(self, context)?.to_string();
             s.push_str(&a);
-            self.avm.push(s);
-        } else if let Value::String(mut b) = b {
-            b.push_str(&a.coerce_to_string(self, context)?);
-            self.avm.push(b);
+            self.avm.push(AvmString::new(context.gc_context, s));
+        } else if let Value::String(b) = b {
+            let mut s = b.to_string();
+            s.push_str(&a.coerce_to_string(self, context)?);
+            self.avm.push(AvmString::new(context.gc_context, s));
         } else {
             let result = b.coerce_to_f64(self, context)? + a.coerce_to_f64(self, context)?;
             self.avm.push(result);
@@ -604,7 +613,8 @@ impl<'a, 'gc: 'a> Activation<'a, 'gc> {
     ) -> Result<FrameControl<'gc>, Error<'gc>> {
         // TODO(Herschel): Results on incorrect operands?
         let val = (self.avm.pop().coerce_to_f64(self, context)? as u8) as char;
-        self.avm.push(val.to_string());
+        self.avm
+            .push(AvmString::new(context.gc_context, val.to_string()));
         Ok(FrameControl::Continue)
     }
 
@@ -765,10 +775,18 @@ impl<'a, 'gc: 'a> Activation<'a, 'gc> {
 
         let target_fn = self.get_variable(context, &fn_name)?;
 
-        let this = self
-            .target_clip_or_root()
-            .object()
-            .coerce_to_object(self, context);
+        // Functions found on the object of a `with` block are called on that object.
+        let with_object = self
+            .scope_cell()
+            .read()
+            .resolve_with_object(self, context, &fn_name);
+        let this = match with_object {
+            Some(object) => object,
+            None => self
+                .target_clip_or_root()
+                .object()
+                .coerce_to_object(self, context),
+        };
         let result = target_fn.call(&fn_name, self, context, this, None, &args)?;
         self.avm.push(result);
 
@@ -847,7 +865,10 @@ impl<'a, 'gc: 'a> Activation<'a, 'gc> {
     ) -> Result<FrameControl<'gc>, Error<'gc>> {
         self.avm.constant_pool = GcCell::allocate(
             context.gc_context,
-            constant_pool.iter().map(|s| (*s).to_string()).collect(),
+            constant_pool
+                .iter()
+                .map(|s| AvmString::new(context.gc_context, *s))
+                .collect(),
         );
         self.set_constant_pool(self.avm.constant_pool);
 
@@ -874,6 +895,7 @@ impl<'a, 'gc: 'a> Activation<'a, 'gc> {
         let scope = Scope::new_closure_scope(self.scope_cell(), context.gc_context);
         let constant_pool = self.constant_pool();
         let func = Avm1Function::from_df1(
+            context.gc_context,
             swf_version,
             actions,
             name,
@@ -893,6 +915,7 @@ impl<'a, 'gc: 'a> Activation<'a, 'gc> {
         if name == "" {
             self.avm.push(func_obj);
         } else {
+            let name = AvmString::new(context.gc_context, name.to_string());
             self.define(name, func_obj, context.gc_context);
         }
 
@@ -910,6 +933,7 @@ impl<'a, 'gc: 'a> Activation<'a, 'gc> {
         let scope = Scope::new_closure_scope(self.scope_cell(), context.gc_context);
         let constant_pool = self.constant_pool();
         let func = Avm1Function::from_df2(
+            context.gc_context,
             swf_version,
             func_data,
             action_func,
@@ -928,7 +952,8 @@ impl<'a, 'gc: 'a> Activation<'a, 'gc> {
         if action_func.name == "" {
             self.avm.push(func_obj);
         } else {
-            self.define(action_func.name, func_obj, context.gc_context);
+            let name = AvmString::new(context.gc_context, action_func.name.to_string());
+            self.define(name, func_obj, context.gc_context);
         }
 
         Ok(FrameControl::Continue)
@@ -942,12 +967,12 @@ impl<'a, 'gc: 'a> Activation<'a, 'gc> {
         // Otherwise, the property is set (including calling virtual setters).
         let value = self.avm.pop();
         let name_val = self.avm.pop();
-        let name = name_val.coerce_to_string(self, context)?;
+        let name = name_val.coerce_to_avm_string(self, context)?;
         let scope = self.scope_cell();
         scope
             .write(context.gc_context)
             .locals()
-            .set(&name, value, self, context)?;
+            .set(name, value, self, context)?;
         Ok(FrameControl::Continue)
     }
 
@@ -958,13 +983,13 @@ impl<'a, 'gc: 'a> Activation<'a, 'gc> {
         // If the property does not exist on the local object's prototype chain, it is created on the local object.
         // Otherwise, the property is unchanged.
         let name_val = self.avm.pop();
-        let name = name_val.coerce_to_string(self, context)?;
+        let name = name_val.coerce_to_avm_string(self, context)?;
         let scope = self.scope_cell();
         if !scope.read().locals().has_property(self, context, &name) {
             scope
                 .write(context.gc_context)
                 .locals()
-                .set(&name, Value::Undefined, self, context)?;
+                .set(name, Value::Undefined, self, context)?;
         }
         Ok(FrameControl::Continue)
     }
@@ -1038,11 +1063,11 @@ impl<'a, 'gc: 'a> Activation<'a, 'gc> {
         let name_value = self.avm.pop();
         let name = name_value.coerce_to_string(self, context)?;
         self.avm.push(Value::Null); // Sentinel that indicates end of enumeration
-        let object = self.resolve(&name, context)?;
+        let object = self.get_variable(context, &name)?;
 
         match object {
             Value::Object(ob) => {
-                for k in ob.get_keys(self).into_iter().rev() {
+                for k in ob.get_keys(self, context.gc_context).into_iter().rev() {
                     self.avm.push(k);
                 }
             }
@@ -1054,14 +1079,14 @@ impl<'a, 'gc: 'a> Activation<'a, 'gc> {
 
     fn action_enumerate_2(
         &mut self,
-        _context: &mut UpdateContext<'_, 'gc, '_>,
+        context: &mut UpdateContext<'_, 'gc, '_>,
     ) -> Result<FrameControl<'gc>, Error<'gc>> {
         let value = self.avm.pop();
 
         self.avm.push(Value::Null); // Sentinel that indicates end of enumeration
 
         if let Value::Object(object) = value {
-            for k in object.get_keys(self).into_iter().rev() {
+            for k in object.get_keys(self, context.gc_context).into_iter().rev() {
                 self.avm.push(k);
             }
         } else {
@@ -1206,42 +1231,26 @@ impl<'a, 'gc: 'a> Activation<'a, 'gc> {
         url: &str,
         target: &str,
     ) -> Result<FrameControl<'gc>, Error<'gc>> {
-        if target.starts_with("_level") && target.len() > 6 {
-            let url = url.to_string();
-            match target[6..].parse::<u32>() {
-                Ok(level_id) => {
-                    let fetch = context.navigator.fetch(&url, RequestOptions::get());
-                    let level = self.resolve_level(level_id, context);
-
-                    let process = context.load_manager.load_movie_into_clip(
-                        context.player.clone().unwrap(),
-                        level,
-                        fetch,
-                        None,
-                    );
-                    context.navigator.spawn_future(process);
-                }
-                Err(e) => log::warn!(
-                    "Couldn't parse level id {} for action_get_url: {}",
-                    target,
-                    e
-                ),
-            }
-
-            return Ok(FrameControl::Continue);
-        }
-
         if let Some(fscommand) = fscommand::parse(url) {
-            fscommand::handle(fscommand, self, context)?;
+            fscommand::handle(fscommand, target, self, context)?;
         } else {
-            context
-                .navigator
-                .navigate_to_url(url.to_owned(), Some(target.to_owned()), None);
+            self.get_url(context, url, target, None);
         }
 
         Ok(FrameControl::Continue)
     }
 
+    /// `GetURL2` is compiled from `getURL`, `loadMovie`, `loadMovieNum`,
+    /// `loadVariables` and `loadVariablesNum`, distinguished by its flags:
+    ///
+    /// * Neither flag: `getURL(url, window)`, or `loadMovieNum` for a
+    ///   `_levelN` window.
+    /// * `is_target_sprite`: `loadMovie(url, target)`.
+    /// * `is_load_vars`: `loadVariablesNum(url, level)`.
+    /// * Both flags: `loadVariables(url, target)`.
+    ///
+    /// `swf_method` sends the variables of the current clip along with the
+    /// request, in the query string or the body.
     fn action_get_url_2(
         &mut self,
         context: &mut UpdateContext<'_, 'gc, '_>,
@@ -1249,41 +1258,36 @@ impl<'a, 'gc: 'a> Activation<'a, 'gc> {
         is_target_sprite: bool,
         is_load_vars: bool,
     ) -> Result<FrameControl<'gc>, Error<'gc>> {
-        // TODO: Support `LoadVariablesFlag`, `LoadTargetFlag`
-        // TODO: What happens if there's only one string?
         let target = self.avm.pop();
         let url_val = self.avm.pop();
         let url = url_val.coerce_to_string(self, context)?;
+        let method = NavigationMethod::from_send_vars_method(swf_method);
 
         if let Some(fscommand) = fscommand::parse(&url) {
-            fscommand::handle(fscommand, self, context)?;
+            let args = target.coerce_to_string(self, context)?;
+            fscommand::handle(fscommand, &args, self, context)?;
             return Ok(FrameControl::Continue);
         }
 
-        let window_target = target.coerce_to_string(self, context)?;
-        let clip_target: Option<DisplayObject<'gc>> = if is_target_sprite {
-            if let Value::Object(target) = target {
-                target.as_display_object()
-            } else {
-                let start = self.target_clip_or_root();
-                self.resolve_target_display_object(context, start, target.clone())?
-            }
+        let window_target = target.coerce_to_string(self, context)?.into_owned();
+        if !is_target_sprite && !is_load_vars {
+            self.get_url(context, &url, &window_target, method);
+            return Ok(FrameControl::Continue);
+        }
+
+        // The target is a clip, or a level when `is_target_sprite` isn't set.
+        let level_id = parse_level_target(&window_target);
+        let clip_target = if is_target_sprite {
+            let start = self.target_clip_or_root();
+            self.resolve_target_display_object(context, start, target)?
         } else {
-            Some(self.target_clip_or_root())
+            level_id.and_then(|level_id| context.levels.get(&level_id).copied())
         };
 
         if is_load_vars {
             if let Some(clip_target) = clip_target {
-                let target_obj = clip_target
-                    .as_movie_clip()
-                    .unwrap()
-                    .object()
-                    .coerce_to_object(self, context);
-                let (url, opts) = self.locals_into_request_options(
-                    context,
-                    url,
-                    NavigationMethod::from_send_vars_method(swf_method),
-                );
+                let target_obj = clip_target.object().coerce_to_object(self, context);
+                let (url, opts) = self.locals_into_request_options(context, url, method);
                 let fetch = context.navigator.fetch(&url, opts);
                 let process = context.load_manager.load_form_into_object(
                     context.player.clone().unwrap(),
@@ -1292,38 +1296,34 @@ impl<'a, 'gc: 'a> Activation<'a, 'gc> {
                 );
 
                 context.navigator.spawn_future(process);
+            } else {
+                log::warn!("loadVariables: Target not found: {}", window_target);
             }
-
-            return Ok(FrameControl::Continue);
-        } else if is_target_sprite {
-            if let Some(clip_target) = clip_target {
-                let (url, opts) = self.locals_into_request_options(
-                    context,
-                    url,
-                    NavigationMethod::from_send_vars_method(swf_method),
-                );
-                let fetch = context.navigator.fetch(&url, opts);
-                let process = context.load_manager.load_movie_into_clip(
-                    context.player.clone().unwrap(),
-                    clip_target,
-                    fetch,
-                    None,
-                );
-                context.navigator.spawn_future(process);
+        } else if let Some(clip_target) = clip_target {
+            if url.is_empty() {
+                // `loadMovie("", target)` unloads the target clip.
+                if let Some(mut clip_target) = clip_target.as_movie_clip() {
+                    clip_target.unload_movie(context);
+                }
+                return Ok(FrameControl::Continue);
             }
 
-            return Ok(FrameControl::Continue);
-        } else {
-            let vars = match NavigationMethod::from_send_vars_method(swf_method) {
-                Some(method) => Some((method, self.locals_into_form_values(context))),
-                None => None,
-            };
-
-            context.navigator.navigate_to_url(
-                url.to_string(),
-                Some(window_target.to_string()),
-                vars,
+            let (url, opts) = self.locals_into_request_options(context, url, method);
+            let fetch = context.navigator.fetch_stream(&url, opts);
+            let process = context.load_manager.load_movie_into_clip(
+                context.player.clone().unwrap(),
+                clip_target,
+                fetch,
+                url.into_owned(),
+                None,
             );
+            context.navigator.spawn_future(process);
+        } else if let Some(level_id) = level_id {
+            // `loadMovie(url, "_levelN")` loads into the level, even if it doesn't exist yet.
+            let (url, opts) = self.locals_into_request_options(context, url, method);
+            self.load_movie_into_level(context, level_id, &url, opts);
+        } else {
+            log::warn!("loadMovie: Target not found: {}", window_target);
         }
 
         Ok(FrameControl::Continue)
@@ -1442,8 +1442,8 @@ impl<'a, 'gc: 'a> Activation<'a, 'gc> {
         for _ in 0..num_props {
             let value = self.avm.pop();
             let name_val = self.avm.pop();
-            let name = name_val.coerce_to_string(self, context)?;
-            object.set(&name, value, self, context)?;
+            let name = name_val.coerce_to_avm_string(self, context)?;
+            object.set(name, value, self, context)?;
         }
 
         self.avm.push(Value::Object(object.into()));
@@ -1554,7 +1554,9 @@ impl<'a, 'gc: 'a> Activation<'a, 'gc> {
         use std::convert::TryFrom;
         let result = char::try_from(self.avm.pop().coerce_to_f64(self, context)? as u32);
         match result {
-            Ok(val) => self.avm.push(val.to_string()),
+            Ok(val) => self
+                .avm
+                .push(AvmString::new(context.gc_context, val.to_string())),
             Err(e) => log::warn!("Couldn't parse char for action_mb_ascii_to_char: {}", e),
         }
         Ok(FrameControl::Continue)
@@ -1576,13 +1578,15 @@ impl<'a, 'gc: 'a> Activation<'a, 'gc> {
         &mut self,
         context: &mut UpdateContext<'_, 'gc, '_>,
     ) -> Result<FrameControl<'gc>, Error<'gc>> {
+        // SWFv4 mbsubstring
         // TODO(Herschel): Result with incorrect operands?
         let len = self.avm.pop().coerce_to_f64(self, context)? as usize;
         let start = self.avm.pop().coerce_to_f64(self, context)? as usize;
         let val = self.avm.pop();
         let s = val.coerce_to_string(self, context)?;
-        let result = s[len..len + start].to_string(); // TODO(Herschel): Flash uses UTF-16 internally.
-        self.avm.push(result);
+        // Unlike `substring`, this counts characters rather than bytes.
+        let result = s.chars().skip(start).take(len).collect::<String>();
+        self.avm.push(AvmString::new(context.gc_context, result));
         Ok(FrameControl::Continue)
     }
 
@@ -1590,9 +1594,10 @@ impl<'a, 'gc: 'a> Activation<'a, 'gc> {
         &mut self,
         context: &mut UpdateContext<'_, 'gc, '_>,
     ) -> Result<FrameControl<'gc>, Error<'gc>> {
+        // SWFv4 mblength
         // TODO(Herschel): Result with non-string operands?
         let val = self.avm.pop();
-        let len = val.coerce_to_string(self, context)?.len();
+        let len = val.coerce_to_string(self, context)?.chars().count();
         self.avm.push(len as f64);
         Ok(FrameControl::Continue)
     }
@@ -1796,7 +1801,7 @@ impl<'a, 'gc: 'a> Activation<'a, 'gc> {
 
     fn action_push(
         &mut self,
-        _context: &mut UpdateContext,
+        context: &mut UpdateContext<'_, 'gc, '_>,
         values: &[swf::avm1::types::Value],
     ) -> Result<FrameControl<'gc>, Error<'gc>> {
         for value in values {
@@ -1808,11 +1813,11 @@ impl<'a, 'gc: 'a> Activation<'a, 'gc> {
                 SwfValue::Int(v) => f64::from(*v).into(),
                 SwfValue::Float(v) => f64::from(*v).into(),
                 SwfValue::Double(v) => (*v).into(),
-                SwfValue::Str(v) => (*v).to_string().into(),
+                SwfValue::Str(v) => AvmString::new(context.gc_context, *v).into(),
                 SwfValue::Register(v) => self.current_register(*v),
                 SwfValue::ConstantPool(i) => {
                     if let Some(value) = self.constant_pool().read().get(*i as usize) {
-                        value.to_string().into()
+                        (*value).into()
                     } else {
                         log::warn!(
                             "ActionPush: Constant pool index {} out of range (len = {})",
@@ -1863,7 +1868,7 @@ impl<'a, 'gc: 'a> Activation<'a, 'gc> {
         let target_clip = self.resolve_target_display_object(context, start_clip, target)?;
 
         if let Some(target_clip) = target_clip.and_then(|o| o.as_movie_clip()) {
-            let _ = globals::movie_clip::remove_movie_clip_with_bias(target_clip, context, 0);
+            let _ = globals::movie_clip::remove_movie_clip_with_bias(target_clip, self, context, 0);
         } else {
             log::warn!("RemoveSprite: Source is not a movie clip");
         }
@@ -1882,10 +1887,10 @@ impl<'a, 'gc: 'a> Activation<'a, 'gc> {
     ) -> Result<FrameControl<'gc>, Error<'gc>> {
         let value = self.avm.pop();
         let name_val = self.avm.pop();
-        let name = name_val.coerce_to_string(self, context)?;
+        let name = name_val.coerce_to_avm_string(self, context)?;
 
         let object = self.avm.pop().coerce_to_object(self, context);
-        object.set(&name, value, self, context)?;
+        object.set(name, value, self, context)?;
 
         Ok(FrameControl::Continue)
     }
@@ -1920,8 +1925,8 @@ impl<'a, 'gc: 'a> Activation<'a, 'gc> {
         // Flash 4-style variable
         let value = self.avm.pop();
         let var_path_val = self.avm.pop();
-        let var_path = var_path_val.coerce_to_string(self, context)?;
-        self.set_variable(context, &var_path, value)?;
+        let var_path = var_path_val.coerce_to_avm_string(self, context)?;
+        self.set_variable(context, var_path, value)?;
         Ok(FrameControl::Continue)
     }
 
@@ -1955,9 +1960,15 @@ impl<'a, 'gc: 'a> Activation<'a, 'gc> {
         {
             new_target_clip = Some(clip);
         } else {
-            log::warn!("SetTarget failed: {} not found", target);
+            context
+                .log
+                .avm_warning(&format!("SetTarget failed: {} not found", target));
             // TODO: Emulate AVM1 trace error message.
-            log::info!(target: "avm_trace", "Target not found: Target=\"{}\" Base=\"{}\"", target, base_clip.path());
+            context.log.avm_trace(&format!(
+                "Target not found: Target=\"{}\" Base=\"{}\"",
+                target,
+                base_clip.path()
+            ));
 
             // When SetTarget has an invalid target, subsequent GetVariables act
             // as if they are targeting root, but subsequent Play/Stop/etc.
@@ -2034,25 +2045,23 @@ impl<'a, 'gc: 'a> Activation<'a, 'gc> {
         context: &mut UpdateContext<'_, 'gc, '_>,
     ) -> Result<FrameControl<'gc>, Error<'gc>> {
         let target = self.avm.pop();
+        // The remaining arguments are popped even if the target doesn't resolve,
+        // so an invalid target doesn't leave them behind on the stack.
+        let lock_center = self.avm.pop();
+        let constrain = self.avm.pop().as_bool(self.current_swf_version());
+        let mut args = vec![lock_center];
+        if constrain {
+            let y2 = self.avm.pop();
+            let x2 = self.avm.pop();
+            let y1 = self.avm.pop();
+            let x1 = self.avm.pop();
+            args.extend_from_slice(&[x1, y1, x2, y2]);
+        }
+
         let start_clip = self.target_clip_or_root();
         let display_object = self.resolve_target_display_object(context, start_clip, target)?;
         if let Some(display_object) = display_object {
-            let lock_center = self.avm.pop();
-            let constrain = self.avm.pop().as_bool(self.current_swf_version());
-            if constrain {
-                let y2 = self.avm.pop();
-                let x2 = self.avm.pop();
-                let y1 = self.avm.pop();
-                let x1 = self.avm.pop();
-                start_drag(
-                    display_object,
-                    self,
-                    context,
-                    &[lock_center, x1, y1, x2, y2],
-                );
-            } else {
-                start_drag(display_object, self, context, &[lock_center]);
-            };
+            start_drag(display_object, self, context, &args);
         } else {
             log::warn!("StartDrag: Invalid target");
         }
@@ -2079,7 +2088,7 @@ impl<'a, 'gc: 'a> Activation<'a, 'gc> {
         &mut self,
         context: &mut UpdateContext,
     ) -> Result<FrameControl<'gc>, Error<'gc>> {
-        context.audio.stop_all_sounds();
+        context.audio_manager.stop_all_sounds(context.audio);
         Ok(FrameControl::Continue)
     }
 
@@ -2105,7 +2114,7 @@ impl<'a, 'gc: 'a> Activation<'a, 'gc> {
         let a = self.avm.pop();
         let mut b = self.avm.pop().coerce_to_string(self, context)?.to_string();
         b.push_str(&a.coerce_to_string(self, context)?);
-        self.avm.push(b);
+        self.avm.push(AvmString::new(context.gc_context, b));
         Ok(FrameControl::Continue)
     }
 
@@ -2140,7 +2149,7 @@ impl<'a, 'gc: 'a> Activation<'a, 'gc> {
             .take(len)
             .map(|c| c as char)
             .collect::<String>();
-        self.avm.push(result);
+        self.avm.push(AvmString::new(context.gc_context, result));
         Ok(FrameControl::Continue)
     }
 
@@ -2214,9 +2223,14 @@ impl<'a, 'gc: 'a> Activation<'a, 'gc> {
 
     fn toggle_quality(
         &mut self,
-        _context: &mut UpdateContext,
+        context: &mut UpdateContext,
     ) -> Result<FrameControl<'gc>, Error<'gc>> {
-        // TODO(Herschel): Noop for now? Could chang anti-aliasing on render backend.
+        // Toggles between low and high quality, as with `toggleHighQuality()`.
+        context.stage.quality = if context.stage.quality == StageQuality::Low {
+            StageQuality::High
+        } else {
+            StageQuality::Low
+        };
         Ok(FrameControl::Continue)
     }
 
@@ -2243,7 +2257,10 @@ impl<'a, 'gc: 'a> Activation<'a, 'gc> {
         context: &mut UpdateContext<'_, 'gc, '_>,
     ) -> Result<FrameControl<'gc>, Error<'gc>> {
         let val = self.avm.pop();
-        let string = val.coerce_to_string(self, context)?;
+        let string = match val {
+            Value::String(string) => string,
+            val => AvmString::new(context.gc_context, val.coerce_to_string(self, context)?),
+        };
         self.avm.push(string);
         Ok(FrameControl::Continue)
     }
@@ -2260,7 +2277,7 @@ impl<'a, 'gc: 'a> Activation<'a, 'gc> {
         } else {
             val.coerce_to_string(self, context)?
         };
-        log::info!(target: "avm_trace", "{}", out);
+        context.log.avm_trace(&out);
         Ok(FrameControl::Continue)
     }
 
@@ -2275,13 +2292,13 @@ impl<'a, 'gc: 'a> Activation<'a, 'gc> {
 
     fn action_wait_for_frame(
         &mut self,
-        _context: &mut UpdateContext,
-        _frame: u16,
+        context: &mut UpdateContext<'_, 'gc, '_>,
+        frame: u16,
         num_actions_to_skip: u8,
         r: &mut Reader<'_>,
     ) -> Result<FrameControl<'gc>, Error<'gc>> {
-        // TODO(Herschel): Always true for now.
-        let loaded = true;
+        // The frame number is 0-based.
+        let loaded = self.is_frame_loaded(context, Some(frame.saturating_add(1)));
         if !loaded {
             // Note that the offset is given in # of actions, NOT in bytes.
             // Read the actions and toss them away.
@@ -2296,9 +2313,15 @@ impl<'a, 'gc: 'a> Activation<'a, 'gc> {
         num_actions_to_skip: u8,
         r: &mut Reader<'_>,
     ) -> Result<FrameControl<'gc>, Error<'gc>> {
-        // TODO(Herschel): Always true for now.
-        let _frame_num = self.avm.pop().coerce_to_f64(self, context)? as u16;
-        let loaded = true;
+        // Like GotoFrame2, the frame is either a 1-based frame number or a frame label.
+        let frame = match self.avm.pop() {
+            Value::String(label) => self
+                .target_clip()
+                .and_then(|clip| clip.as_movie_clip())
+                .and_then(|clip| clip.frame_label_to_number(&label)),
+            frame => Some(frame.coerce_to_f64(self, context)? as u16),
+        };
+        let loaded = self.is_frame_loaded(context, frame);
         if !loaded {
             // Note that the offset is given in # of actions, NOT in bytes.
             // Read the actions and toss them away.
@@ -2307,6 +2330,15 @@ impl<'a, 'gc: 'a> Activation<'a, 'gc> {
         Ok(FrameControl::Continue)
     }
 
+    /// Whether the given 1-based frame of the target clip has loaded, for `ifFrameLoaded`.
+    /// Frame labels that don't exist are never loaded.
+    fn is_frame_loaded(&self, context: &UpdateContext<'_, 'gc, '_>, frame: Option<u16>) -> bool {
+        match self.target_clip().and_then(|clip| clip.as_movie_clip()) {
+            Some(clip) => frame.map_or(false, |frame| frame <= clip.frames_loaded(context)),
+            None => true,
+        }
+    }
+
     #[allow(unused_variables)]
     fn action_throw(
         &mut self,
@@ -2332,7 +2364,18 @@ impl<'a, 'gc: 'a> Activation<'a, 'gc> {
             // Undefined/null with is ignored.
             Value::Undefined | Value::Null => {
                 // Mimic Flash's error output.
-                log::info!(target: "avm_trace", "Error: A 'with' action failed because the specified object did not exist.\n");
+                context.log.avm_trace(
+                    "Error: A 'with' action failed because the specified object did not exist.\n",
+                );
+                Ok(FrameControl::Continue)
+            }
+
+            // Flash refuses to nest `with` blocks too deeply, and skips the block.
+            _ if self.scope().with_depth() >= MAX_WITH_DEPTH => {
+                log::warn!(
+                    "With: the maximum nesting depth of {} has been exceeded",
+                    MAX_WITH_DEPTH
+                );
                 Ok(FrameControl::Continue)
             }
 
@@ -2357,6 +2400,9 @@ impl<'a, 'gc: 'a> Activation<'a, 'gc> {
         try_block: &TryBlock,
         parent_data: &SwfSlice,
     ) -> Result<FrameControl<'gc>, Error<'gc>> {
+        // A throw can leave values pushed by the try block, or by any function it called, on
+        // the shared stack. Discard them so the rest of this function sees its own values.
+        let stack_len = self.avm.stack.len();
         let mut result = self.run_actions(
             context,
             parent_data.to_subslice(try_block.try_actions).unwrap(),
@@ -2364,30 +2410,27 @@ impl<'a, 'gc: 'a> Activation<'a, 'gc> {
 
         if let Some((catch_vars, actions)) = &try_block.catch {
             if let Err(Error::ThrownValue(value)) = &result {
-                let mut activation = Activation::from_action(
-                    self.avm,
-                    self.id.child("[Catch]"),
-                    self.swf_version,
-                    self.scope,
-                    self.constant_pool,
-                    self.base_clip,
-                    self.this,
-                    self.arguments,
-                );
+                let value = value.to_owned();
+                self.avm.stack.truncate(stack_len);
 
+                // The catch block runs in this activation, so that it can see the local
+                // registers of the surrounding function.
                 match catch_vars {
                     CatchVar::Var(name) => {
-                        activation.set_variable(context, name, value.to_owned())?
-                    }
-                    CatchVar::Register(id) => {
-                        activation.set_current_register(*id, value.to_owned(), context)
+                        let name = AvmString::new(context.gc_context, name.to_string());
+                        self.set_variable(context, name, value)?
                     }
+                    CatchVar::Register(id) => self.set_current_register(*id, value, context),
                 }
 
-                result = activation.run_actions(context, parent_data.to_subslice(actions).unwrap());
+                result = self.run_actions(context, parent_data.to_subslice(actions).unwrap());
             }
         }
 
+        if result.is_err() {
+            self.avm.stack.truncate(stack_len);
+        }
+
         if let Some(actions) = try_block.finally {
             if let ReturnType::Explicit(value) =
                 self.run_actions(context, parent_data.to_subslice(actions).unwrap())?
@@ -2434,7 +2477,41 @@ impl<'a, 'gc: 'a> Activation<'a, 'gc> {
         }
     }
 
-    /// Convert the current locals pool into a set of form values.
+    /// Navigate to a URL on behalf of `getURL`, in the window or frame named
+    /// by `window`. A `_levelN` window loads the URL as a movie into that
+    /// level instead.
+    ///
+    /// With a `method`, the variables of the current clip are sent along.
+    /// `javascript:` URLs always run in the window containing the movie, and
+    /// never get variables.
+    pub fn get_url(
+        &mut self,
+        context: &mut UpdateContext<'_, 'gc, '_>,
+        url: &str,
+        window: &str,
+        method: Option<NavigationMethod>,
+    ) {
+        if let Some(level_id) = parse_level_target(window) {
+            let (url, opts) = self.locals_into_request_options(context, Cow::Borrowed(url), method);
+            self.load_movie_into_level(context, level_id, &url, opts);
+        } else if url
+            .get(..11)
+            .map_or(false, |scheme| scheme.eq_ignore_ascii_case("javascript:"))
+        {
+            context
+                .navigator
+                .navigate_to_url(url.to_owned(), NavigationTarget::SelfWindow, None);
+        } else {
+            let vars = method.map(|method| (method, self.locals_into_form_values(context)));
+            context.navigator.navigate_to_url(
+                url.to_owned(),
+                NavigationTarget::from_name(window),
+                vars,
+            );
+        }
+    }
+
+    /// Convert the variables of the current clip into a set of form values.
     ///
     /// This is necessary to support form submission from Flash via a couple of
     /// legacy methods, such as the `ActionGetURL2` opcode or `getURL` function.
@@ -2445,16 +2522,18 @@ impl<'a, 'gc: 'a> Activation<'a, 'gc> {
         context: &mut UpdateContext<'_, 'gc, '_>,
     ) -> HashMap<String, String> {
         let mut form_values = HashMap::new();
-        let scope = self.scope_cell();
-        let locals = scope.read().locals_cell();
-        let keys = locals.get_keys(self);
+        let locals = self
+            .target_clip_or_root()
+            .object()
+            .coerce_to_object(self, context);
+        let keys = locals.get_keys(self, context.gc_context);
 
         for k in keys {
             let v = locals.get(&k, self, context);
 
             //TODO: What happens if an error occurs inside a virtual property?
             form_values.insert(
-                k,
+                k.to_string(),
                 v.ok()
                     .unwrap_or_else(|| Value::Undefined)
                     .coerce_to_string(self, context)
@@ -2522,9 +2601,10 @@ impl<'a, 'gc: 'a> Activation<'a, 'gc> {
         target: Value<'gc>,
     ) -> Result<Option<DisplayObject<'gc>>, Error<'gc>> {
         // If the value you got was a display object, we can just toss it straight back.
+        // A dead reference to a removed clip doesn't resolve to anything.
         if let Value::Object(o) = target {
             if let Some(o) = o.as_display_object() {
-                return Ok(Some(o));
+                return Ok(Some(o).filter(|o| !o.dangling()));
             }
         }
 
@@ -2571,6 +2651,7 @@ impl<'a, 'gc: 'a> Activation<'a, 'gc> {
         };
 
         let case_sensitive = self.is_case_sensitive();
+        let mut first_element = !is_slash_path;
 
         // Iterate through each token in the path.
         while !path.is_empty() {
@@ -2620,18 +2701,25 @@ impl<'a, 'gc: 'a> Activation<'a, 'gc> {
                 // Guaranteed to be valid UTF-8.
                 let name = unsafe { std::str::from_utf8_unchecked(ident) };
 
-                // Get the value from the object.
-                // Resolves display object instances first, then local variables.
-                // This is the opposite of general GetMember property access!
-                if let Some(child) = object
+                // `this` and `_root` are only special as the first element of a relative path,
+                // such as `this/clip` or `_root.clip:foo`.
+                if first_element && self.is_name(name, "this") {
+                    self.this_cell().into()
+                } else if first_element && self.is_name(name, "_root") {
+                    root.object()
+                } else if let Some(child) = object
                     .as_display_object()
                     .and_then(|o| o.get_child_by_name(name, case_sensitive))
                 {
+                    // Get the value from the object.
+                    // Resolves display object instances first, then local variables.
+                    // This is the opposite of general GetMember property access!
                     child.object()
                 } else {
-                    object.get(&name, self, context).unwrap()
+                    object.get(&name, self, context)?
                 }
             };
+            first_element = false;
 
             // Resolve the value to an object while traversing the path.
             object = if let Value::Object(o) = val {
@@ -2644,56 +2732,46 @@ impl<'a, 'gc: 'a> Activation<'a, 'gc> {
         Ok(Some(object))
     }
 
+    /// Resolves a target path on the scope chain.
+    /// Each scope's locals are tried as the starting object in turn, and the first
+    /// object the path resolves to is returned.
+    fn resolve_target_path_on_scope_chain(
+        &mut self,
+        context: &mut UpdateContext<'_, 'gc, '_>,
+        root: DisplayObject<'gc>,
+        path: &str,
+    ) -> Result<Option<Object<'gc>>, Error<'gc>> {
+        let mut current_scope = Some(self.scope_cell());
+        while let Some(scope) = current_scope {
+            let locals = *scope.read().locals();
+            if let Some(object) = self.resolve_target_path(context, root, locals, path)? {
+                return Ok(Some(object));
+            }
+            current_scope = scope.read().parent_cell();
+        }
+
+        Ok(None)
+    }
+
     /// Resolves a path for text field variable binding.
     /// Returns the parent object that owns the variable, and the variable name.
     /// Returns `None` if the path does not yet point to a valid object.
-    /// TODO: This can probably be merged with some of the above `resolve_target_path` methods.
     pub fn resolve_text_field_variable_path<'s>(
         &mut self,
         context: &mut UpdateContext<'_, 'gc, '_>,
         text_field_parent: DisplayObject<'gc>,
         path: &'s str,
     ) -> Result<Option<(Object<'gc>, &'s str)>, Error<'gc>> {
-        // Resolve a variable path for a GetVariable action.
-        let start = text_field_parent;
-
-        // Find the right-most : or . in the path.
-        // If we have one, we must resolve as a target path.
-        // We also check for a / to skip some unnecessary work later.
-        let mut has_slash = false;
-        let mut var_iter = path.as_bytes().rsplitn(2, |c| match c {
-            b':' | b'.' => true,
-            b'/' => {
-                has_slash = true;
-                false
-            }
-            _ => false,
-        });
-
-        let b = var_iter.next();
-        let a = var_iter.next();
-        if let (Some(path), Some(var_name)) = (a, b) {
+        if let Some((path, var_name)) = split_variable_path(path) {
             // We have a . or :, so this is a path to an object plus a variable name.
-            // We resolve it directly on the targeted object.
-            let path = unsafe { std::str::from_utf8_unchecked(path) };
-            let var_name = unsafe { std::str::from_utf8_unchecked(var_name) };
-
-            let mut current_scope = Some(self.scope_cell());
-            while let Some(scope) = current_scope {
-                if let Some(object) =
-                    self.resolve_target_path(context, start.root(), *scope.read().locals(), path)?
-                {
-                    return Ok(Some((object, var_name)));
-                }
-                current_scope = scope.read().parent_cell();
-            }
-
-            return Ok(None);
+            let object =
+                self.resolve_target_path_on_scope_chain(context, text_field_parent.root(), path)?;
+            return Ok(object.map(|object| (object, var_name)));
         }
 
         // Finally! It's a plain old variable name.
         // Resolve using scope chain, as normal.
-        if let Value::Object(object) = start.object() {
+        if let Value::Object(object) = text_field_parent.object() {
             Ok(Some((object, path)))
         } else {
             Ok(None)
@@ -2727,34 +2805,15 @@ impl<'a, 'gc: 'a> Activation<'a, 'gc> {
         path: &'s str,
     ) -> Result<Value<'gc>, Error<'gc>> {
         // Resolve a variable path for a GetVariable action.
-        let start = self.target_clip_or_root();
-
-        // Find the right-most : or . in the path.
-        // If we have one, we must resolve as a target path.
-        // We also check for a / to skip some unnecessary work later.
-        let mut has_slash = false;
-        let mut var_iter = path.as_bytes().rsplitn(2, |c| match c {
-            b':' | b'.' => true,
-            b'/' => {
-                has_slash = true;
-                false
-            }
-            _ => false,
-        });
+        let root = self.target_clip_or_root().root();
 
-        let b = var_iter.next();
-        let a = var_iter.next();
-        if let (Some(path), Some(var_name)) = (a, b) {
+        if let Some((target, var_name)) = split_variable_path(path) {
             // We have a . or :, so this is a path to an object plus a variable name.
             // We resolve it directly on the targeted object.
-            let path = unsafe { std::str::from_utf8_unchecked(path) };
-            let var_name = unsafe { std::str::from_utf8_unchecked(var_name) };
-
             let mut current_scope = Some(self.scope_cell());
             while let Some(scope) = current_scope {
-                if let Some(object) =
-                    self.resolve_target_path(context, start.root(), *scope.read().locals(), path)?
-                {
+                let locals = *scope.read().locals();
+                if let Some(object) = self.resolve_target_path(context, root, locals, target)? {
                     if object.has_property(self, context, var_name) {
                         return Ok(object.get(var_name, self, context)?);
                     }
@@ -2766,16 +2825,9 @@ impl<'a, 'gc: 'a> Activation<'a, 'gc> {
         }
 
         // If it doesn't have a trailing variable, it can still be a slash path.
-        // We can skip this step if we didn't find a slash above.
-        if has_slash {
-            let mut current_scope = Some(self.scope_cell());
-            while let Some(scope) = current_scope {
-                if let Some(object) =
-                    self.resolve_target_path(context, start.root(), *scope.read().locals(), path)?
-                {
-                    return Ok(object.into());
-                }
-                current_scope = scope.read().parent_cell();
+        if path.contains('/') {
+            if let Some(object) = self.resolve_target_path_on_scope_chain(context, root, path)? {
+                return Ok(object.into());
             }
         }
 
@@ -2806,41 +2858,26 @@ impl<'a, 'gc: 'a> Activation<'a, 'gc> {
     ///
     /// If the string does not resolve as a path, the path is considered a normal variable
     /// name and is set on the scope chain as usual.
-    pub fn set_variable<'s>(
+    pub fn set_variable(
         &mut self,
         context: &mut UpdateContext<'_, 'gc, '_>,
-        path: &'s str,
+        path: impl Into<AvmString<'gc>>,
         value: Value<'gc>,
     ) -> Result<(), Error<'gc>> {
-        // Resolve a variable path for a GetVariable action.
-        let start = self.target_clip_or_root();
+        let path = path.into();
 
         // If the target clip is invalid, we default to root for the variable path.
         if path.is_empty() {
             return Ok(());
         }
 
-        // Find the right-most : or . in the path.
-        // If we have one, we must resolve as a target path.
-        let mut var_iter = path.as_bytes().rsplitn(2, |&c| c == b':' || c == b'.');
-        let b = var_iter.next();
-        let a = var_iter.next();
-
-        if let (Some(path), Some(var_name)) = (a, b) {
+        if let Some((target, var_name)) = split_variable_path(&path) {
             // We have a . or :, so this is a path to an object plus a variable name.
             // We resolve it directly on the targeted object.
-            let path = unsafe { std::str::from_utf8_unchecked(path) };
-            let var_name = unsafe { std::str::from_utf8_unchecked(var_name) };
-
-            let mut current_scope = Some(self.scope_cell());
-            while let Some(scope) = current_scope {
-                if let Some(object) =
-                    self.resolve_target_path(context, start.root(), *scope.read().locals(), path)?
-                {
-                    object.set(var_name, value, self, context)?;
-                    return Ok(());
-                }
-                current_scope = scope.read().parent_cell();
+            let root = self.target_clip_or_root().root();
+            if let Some(object) = self.resolve_target_path_on_scope_chain(context, root, target)? {
+                let var_name = AvmString::new(context.gc_context, var_name);
+                object.set(var_name, value, self, context)?;
             }
 
             return Ok(());
@@ -2882,6 +2919,65 @@ impl<'a, 'gc: 'a> Activation<'a, 'gc> {
         }
     }
 
+    /// Load a movie into a level, creating the level if it doesn't exist yet.
+    ///
+    /// An empty URL unloads the level instead.
+    pub fn load_movie_into_level(
+        &mut self,
+        context: &mut UpdateContext<'_, 'gc, '_>,
+        level_id: u32,
+        url: &str,
+        options: RequestOptions,
+    ) {
+        if url.is_empty() {
+            self.unload_level(level_id, context);
+            return;
+        }
+
+        let fetch = context.navigator.fetch_stream(url, options);
+        let level = self.resolve_level(level_id, context);
+        let process = context.load_manager.load_movie_into_clip(
+            context.player.clone().unwrap(),
+            level,
+            fetch,
+            url.to_string(),
+            None,
+        );
+        context.navigator.spawn_future(process);
+    }
+
+    /// Unload a level by ID, removing it from the stage.
+    ///
+    /// Unloading `_level0` clears the entire stage, including all other levels.
+    /// `_level0` itself is kept around as an empty movie so that there is
+    /// always a root to run and render.
+    pub fn unload_level(&mut self, level_id: u32, context: &mut UpdateContext<'_, 'gc, '_>) {
+        if level_id == 0 {
+            let levels: Vec<_> = context.levels.keys().copied().collect();
+            for level_id in levels.into_iter().filter(|id| *id != 0) {
+                self.unload_level(level_id, context);
+            }
+
+            if let Some(mut root) = context.levels.get(&0).and_then(|l| l.as_movie_clip()) {
+                root.unload_movie(context);
+            }
+
+            // A cursor hidden by the unloaded movie is shown again.
+            context.input.show_mouse();
+        } else if let Some(mut level) = context.levels.remove(&level_id) {
+            context.load_manager.cancel_movie_loads(level);
+            level.unload(context);
+            if let Some(level) = level.as_movie_clip() {
+                level.release_movie(context);
+            }
+
+            // Removing a level uncovers the levels beneath it.
+            if let Some(root) = context.levels.get(&0) {
+                root.set_dirty(true);
+            }
+        }
+    }
+
     /// The current target clip of the executing code.
     /// Actions that affect `root` after an invalid `tellTarget` will use this.
     ///
@@ -2906,6 +3002,16 @@ impl<'a, 'gc: 'a> Activation<'a, 'gc> {
         self.current_swf_version() > 6
     }
 
+    /// Returns whether an identifier refers to the given name, following the case
+    /// sensitivity rules of the running SWF version.
+    fn is_name(&self, name: &str, expected: &str) -> bool {
+        if self.is_case_sensitive() {
+            name == expected
+        } else {
+            swf_string_eq_ignore_case(name, expected)
+        }
+    }
+
     /// Resolve a particular named local variable within this activation.
     ///
     /// Because scopes are object chains, the same rules for `Object::get`
@@ -2915,11 +3021,11 @@ impl<'a, 'gc: 'a> Activation<'a, 'gc> {
         name: &str,
         context: &mut UpdateContext<'_, 'gc, '_>,
     ) -> Result<Value<'gc>, Error<'gc>> {
-        if name == "this" {
+        if self.is_name(name, "this") {
             return Ok(Value::Object(self.this_cell()));
         }
 
-        if name == "arguments" && self.arguments.is_some() {
+        if self.is_name(name, "arguments") && self.arguments.is_some() {
             return Ok(Value::Object(self.arguments.unwrap()));
         }
 
@@ -2930,11 +3036,11 @@ impl<'a, 'gc: 'a> Activation<'a, 'gc> {
 
     /// Check if a particular property in the scope chain is defined.
     pub fn is_defined(&mut self, context: &mut UpdateContext<'_, 'gc, '_>, name: &str) -> bool {
-        if name == "this" {
+        if self.is_name(name, "this") {
             return true;
         }
 
-        if name == "arguments" && self.arguments.is_some() {
+        if self.is_name(name, "arguments") && self.arguments.is_some() {
             return true;
         }
 
@@ -2986,7 +3092,12 @@ impl<'a, 'gc: 'a> Activation<'a, 'gc> {
     }
 
     /// Define a named local variable within this activation.
-    pub fn define(&self, name: &str, value: impl Into<Value<'gc>>, mc: MutationContext<'gc, '_>) {
+    pub fn define(
+        &self,
+        name: impl Into<AvmString<'gc>>,
+        value: impl Into<Value<'gc>>,
+        mc: MutationContext<'gc, '_>,
+    ) {
         self.scope().define(name, value, mc)
     }
 
@@ -3032,11 +3143,35 @@ impl<'a, 'gc: 'a> Activation<'a, 'gc> {
         }
     }
 
-    pub fn constant_pool(&self) -> GcCell<'gc, Vec<String>> {
+    pub fn constant_pool(&self) -> GcCell<'gc, Vec<AvmString<'gc>>> {
         self.constant_pool
     }
 
-    pub fn set_constant_pool(&mut self, constant_pool: GcCell<'gc, Vec<String>>) {
+    pub fn set_constant_pool(&mut self, constant_pool: GcCell<'gc, Vec<AvmString<'gc>>>) {
         self.constant_pool = constant_pool;
     }
 }
+
+/// Splits a variable path such as `a/b/c:foo` into the target path and the variable name.
+///
+/// The right-most `:` or `.` delimits the variable name, so the name itself can contain a
+/// slash. Returns `None` if the path has no variable segment.
+fn split_variable_path(path: &str) -> Option<(&str, &str)> {
+    let pos = path.rfind(|c| c == ':' || c == '.')?;
+    Some((&path[..pos], &path[pos + 1..]))
+}
+
+/// Parses a `_levelN` window target into its level ID.
+fn parse_level_target(target: &str) -> Option<u32> {
+    if target.starts_with("_level") && target.len() > 6 {
+        match target[6..].parse::<u32>() {
+            Ok(level_id) => Some(level_id),
+            Err(e) => {
+                log::warn!("Couldn't parse level id {}: {}", target, e);
+                None
+            }
+        }
+    } else {
+        None
+    }
+}
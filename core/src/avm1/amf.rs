@@ -0,0 +1,873 @@
+//! AMF0 serialization of AVM1 values
+//!
+//! AMF0 is the binary format that Flash Player uses to store and exchange
+//! ActionScript values, such as the data of local shared objects.
+
+use crate::avm1::activation::Activation;
+use crate::avm1::date_object::DateObject;
+use crate::avm1::{AvmString, Object, ScriptObject, TObject, Value};
+use crate::context::UpdateContext;
+use crate::display_object::TDisplayObject;
+use enumset::EnumSet;
+use thiserror::Error;
+
+const NUMBER: u8 = 0x00;
+const BOOLEAN: u8 = 0x01;
+const STRING: u8 = 0x02;
+const OBJECT: u8 = 0x03;
+const NULL: u8 = 0x05;
+const UNDEFINED: u8 = 0x06;
+const REFERENCE: u8 = 0x07;
+const ECMA_ARRAY: u8 = 0x08;
+const OBJECT_END: u8 = 0x09;
+const STRICT_ARRAY: u8 = 0x0A;
+const DATE: u8 = 0x0B;
+const LONG_STRING: u8 = 0x0C;
+const TYPED_OBJECT: u8 = 0x10;
+
+/// The largest array length or index that is always accepted when reading an ECMA array.
+///
+/// AVM1 arrays are stored densely, so a huge length or index in a few bytes of data
+/// would otherwise allocate gigabytes of memory. Larger lengths are accepted as long
+/// as the data is at least as long, since a dense array of that length could fit in it.
+const MAX_SPARSE_ARRAY_LENGTH: usize = 0xFFFF;
+
+/// The deepest that values may be nested inside objects and arrays.
+///
+/// Reading and writing values is recursive, so deeper nesting is an error rather than
+/// a stack overflow.
+const MAX_DEPTH: usize = 256;
+
+#[derive(Debug, Error, PartialEq)]
+pub enum Error {
+    #[error("Unexpected end of AMF data")]
+    UnexpectedEof,
+
+    #[error("Invalid AMF header")]
+    InvalidHeader,
+
+    #[error("AMF0 type {0:#x} is unsupported")]
+    UnsupportedType(u8),
+
+    #[error("Invalid AMF0 object reference {0}")]
+    InvalidReference(u16),
+
+    #[error("AMF0 values are nested more than {} levels deep", MAX_DEPTH)]
+    NestingTooDeep,
+}
+
+/// Serializes a sequence of values to AMF0, such as the arguments of a
+/// `LocalConnection` message.
+///
+/// The values are written with one writer, so an object shared between them is
+/// still shared when they are deserialized.
+pub fn serialize<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    values: &[Value<'gc>],
+) -> Result<Vec<u8>, Error> {
+    let mut writer = Amf0Writer::new();
+    for value in values {
+        writer.write_value(activation, context, value.clone())?;
+    }
+    Ok(writer.into_bytes())
+}
+
+/// Deserializes a sequence of values from AMF0, up to the end of the data.
+pub fn deserialize<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    data: &[u8],
+) -> Result<Vec<Value<'gc>>, Error> {
+    let mut reader = Amf0Reader::new(data);
+    let mut values = vec![];
+    while !reader.is_empty() {
+        values.push(reader.read_value(activation, context)?);
+    }
+    Ok(values)
+}
+
+/// Returns the properties of an object that are serialized.
+/// Functions and display objects can't be serialized, and are skipped.
+///
+/// Properties are returned in the order they were created, which is the reverse of
+/// their enumeration order.
+pub fn serializable_properties<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    object: Object<'gc>,
+) -> Vec<(AvmString<'gc>, Value<'gc>)> {
+    let mut properties = vec![];
+    for key in object
+        .get_keys(activation, context.gc_context)
+        .into_iter()
+        .rev()
+    {
+        if let Ok(value) = object.get(&key, activation, context) {
+            if let Value::Object(o) = value {
+                if !is_serializable(o) {
+                    continue;
+                }
+            }
+            properties.push((key, value));
+        }
+    }
+    properties
+}
+
+fn is_serializable(object: Object<'_>) -> bool {
+    object.as_executable().is_none() && object.as_display_object().is_none()
+}
+
+/// Returns the name that the class of an object was registered with using
+/// `Object.registerClass`.
+fn registered_class_name<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    object: Object<'gc>,
+) -> Option<String> {
+    let proto = object.proto()?;
+    let movie = activation
+        .base_clip()
+        .movie()
+        .unwrap_or_else(|| context.swf.clone());
+    let constructors: Vec<(String, Object<'gc>)> = context
+        .library
+        .library_for_movie(movie)?
+        .avm1_constructors()
+        .map(|(name, constructor)| (name.to_string(), constructor))
+        .collect();
+
+    for (name, constructor) in constructors {
+        if let Ok(Value::Object(prototype)) = constructor.get("prototype", activation, context) {
+            if Object::ptr_eq(prototype, proto) {
+                return Some(name);
+            }
+        }
+    }
+    None
+}
+
+/// Returns the prototype of the class registered with the given name using
+/// `Object.registerClass`.
+fn registered_class_prototype<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    class_name: &str,
+) -> Option<Object<'gc>> {
+    let movie = activation
+        .base_clip()
+        .movie()
+        .unwrap_or_else(|| context.swf.clone());
+    let constructor = context
+        .library
+        .library_for_movie(movie)?
+        .get_avm1_constructor(class_name)?;
+    match constructor.get("prototype", activation, context) {
+        Ok(Value::Object(prototype)) => Some(prototype),
+        _ => None,
+    }
+}
+
+/// Writes AMF0 data.
+///
+/// An object that is written more than once, such as an object that contains
+/// itself, is written in full only the first time. Later occurences are written
+/// as references to it.
+pub struct Amf0Writer<'gc> {
+    out: Vec<u8>,
+
+    /// The objects written so far, in the order of their reference indices.
+    objects: Vec<Object<'gc>>,
+
+    /// How deep the value being written is nested.
+    depth: usize,
+}
+
+impl<'gc> Amf0Writer<'gc> {
+    pub fn new() -> Self {
+        Self {
+            out: Vec::new(),
+            objects: Vec::new(),
+            depth: 0,
+        }
+    }
+
+    pub fn into_bytes(self) -> Vec<u8> {
+        self.out
+    }
+
+    pub fn write_bytes(&mut self, bytes: &[u8]) {
+        self.out.extend_from_slice(bytes);
+    }
+
+    pub fn write_u32(&mut self, n: u32) {
+        self.out.extend_from_slice(&n.to_be_bytes());
+    }
+
+    /// Writes a string with a 16-bit length, as used for property names.
+    /// Strings longer than 65535 bytes are truncated to the last whole character
+    /// that fits.
+    pub fn write_utf8(&mut self, string: &str) {
+        let mut len = string.len().min(u16::MAX.into());
+        while !string.is_char_boundary(len) {
+            len -= 1;
+        }
+        let bytes = &string.as_bytes()[..len];
+        self.out
+            .extend_from_slice(&(bytes.len() as u16).to_be_bytes());
+        self.out.extend_from_slice(bytes);
+    }
+
+    /// Writes a value.
+    ///
+    /// Values nested more than `MAX_DEPTH` levels deep can't be written.
+    pub fn write_value(
+        &mut self,
+        activation: &mut Activation<'_, 'gc>,
+        context: &mut UpdateContext<'_, 'gc, '_>,
+        value: Value<'gc>,
+    ) -> Result<(), Error> {
+        if self.depth >= MAX_DEPTH {
+            return Err(Error::NestingTooDeep);
+        }
+        self.depth += 1;
+        let result = self.write_nested_value(activation, context, value);
+        self.depth -= 1;
+        result
+    }
+
+    fn write_nested_value(
+        &mut self,
+        activation: &mut Activation<'_, 'gc>,
+        context: &mut UpdateContext<'_, 'gc, '_>,
+        value: Value<'gc>,
+    ) -> Result<(), Error> {
+        match value {
+            Value::Undefined => self.out.push(UNDEFINED),
+            Value::Null => self.out.push(NULL),
+            Value::Bool(b) => {
+                self.out.push(BOOLEAN);
+                self.out.push(b.into());
+            }
+            Value::Number(n) => {
+                self.out.push(NUMBER);
+                self.out.extend_from_slice(&n.to_be_bytes());
+            }
+            Value::String(s) => {
+                if s.len() > u16::MAX.into() {
+                    self.out.push(LONG_STRING);
+                    self.write_u32(s.len() as u32);
+                    self.write_bytes(s.as_bytes());
+                } else {
+                    self.out.push(STRING);
+                    self.write_utf8(&s);
+                }
+            }
+            Value::Object(object) => return self.write_object(activation, context, object),
+        }
+        Ok(())
+    }
+
+    fn write_object(
+        &mut self,
+        activation: &mut Activation<'_, 'gc>,
+        context: &mut UpdateContext<'_, 'gc, '_>,
+        object: Object<'gc>,
+    ) -> Result<(), Error> {
+        if !is_serializable(object) {
+            self.out.push(UNDEFINED);
+            return Ok(());
+        }
+
+        // Dates are written by value, and can't be referenced.
+        if let Some(date) = object.as_date_object() {
+            self.out.push(DATE);
+            self.out.extend_from_slice(&date.date_time().to_be_bytes());
+            // The time zone is reserved, and always zero.
+            self.out.extend_from_slice(&[0, 0]);
+            return Ok(());
+        }
+
+        if let Some(index) = self
+            .objects
+            .iter()
+            .position(|written| Object::ptr_eq(*written, object))
+        {
+            if index <= u16::MAX.into() {
+                self.out.push(REFERENCE);
+                self.out.extend_from_slice(&(index as u16).to_be_bytes());
+            } else {
+                log::warn!("AMF0: Too many objects to reference, writing undefined");
+                self.out.push(UNDEFINED);
+            }
+            return Ok(());
+        }
+        self.objects.push(object);
+
+        if activation.avm.prototypes.array.is_prototype_of(object) {
+            self.out.push(ECMA_ARRAY);
+            self.write_u32(object.length() as u32);
+        } else if let Some(class_name) = registered_class_name(activation, context, object) {
+            self.out.push(TYPED_OBJECT);
+            self.write_utf8(&class_name);
+        } else {
+            self.out.push(OBJECT);
+        }
+
+        for (key, value) in serializable_properties(activation, context, object) {
+            self.write_utf8(&key);
+            self.write_value(activation, context, value)?;
+        }
+        self.write_utf8("");
+        self.out.push(OBJECT_END);
+        Ok(())
+    }
+}
+
+impl<'gc> Default for Amf0Writer<'gc> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Reads AMF0 data.
+pub struct Amf0Reader<'a, 'gc> {
+    data: &'a [u8],
+
+    /// The largest array length or index that is accepted in this data.
+    max_array_length: usize,
+
+    /// The objects read so far, in the order of their reference indices.
+    objects: Vec<Object<'gc>>,
+
+    /// How deep the value being read is nested.
+    depth: usize,
+}
+
+impl<'a, 'gc> Amf0Reader<'a, 'gc> {
+    pub fn new(data: &'a [u8]) -> Self {
+        Self {
+            data,
+            max_array_length: MAX_SPARSE_ARRAY_LENGTH.max(data.len()),
+            objects: Vec::new(),
+            depth: 0,
+        }
+    }
+
+    /// Returns whether all of the data has been read.
+    pub fn is_empty(&self) -> bool {
+        self.data.is_empty()
+    }
+
+    pub fn read_bytes(&mut self, len: usize) -> Result<&'a [u8], Error> {
+        if self.data.len() < len {
+            return Err(Error::UnexpectedEof);
+        }
+        let (bytes, rest) = self.data.split_at(len);
+        self.data = rest;
+        Ok(bytes)
+    }
+
+    pub fn read_u8(&mut self) -> Result<u8, Error> {
+        Ok(self.read_bytes(1)?[0])
+    }
+
+    pub fn read_u16(&mut self) -> Result<u16, Error> {
+        let mut bytes = [0; 2];
+        bytes.copy_from_slice(self.read_bytes(2)?);
+        Ok(u16::from_be_bytes(bytes))
+    }
+
+    pub fn read_u32(&mut self) -> Result<u32, Error> {
+        let mut bytes = [0; 4];
+        bytes.copy_from_slice(self.read_bytes(4)?);
+        Ok(u32::from_be_bytes(bytes))
+    }
+
+    fn read_f64(&mut self) -> Result<f64, Error> {
+        let mut bytes = [0; 8];
+        bytes.copy_from_slice(self.read_bytes(8)?);
+        Ok(f64::from_be_bytes(bytes))
+    }
+
+    /// Reads a string with a 16-bit length, as used for property names.
+    pub fn read_utf8(&mut self) -> Result<String, Error> {
+        let len = self.read_u16()?.into();
+        Ok(String::from_utf8_lossy(self.read_bytes(len)?).into_owned())
+    }
+
+    fn read_long_utf8(&mut self) -> Result<String, Error> {
+        let len = self.read_u32()? as usize;
+        Ok(String::from_utf8_lossy(self.read_bytes(len)?).into_owned())
+    }
+
+    /// Reads a value.
+    ///
+    /// Values nested more than `MAX_DEPTH` levels deep are an error.
+    pub fn read_value(
+        &mut self,
+        activation: &mut Activation<'_, 'gc>,
+        context: &mut UpdateContext<'_, 'gc, '_>,
+    ) -> Result<Value<'gc>, Error> {
+        if self.depth >= MAX_DEPTH {
+            return Err(Error::NestingTooDeep);
+        }
+        self.depth += 1;
+        let value = self.read_nested_value(activation, context);
+        self.depth -= 1;
+        value
+    }
+
+    fn read_nested_value(
+        &mut self,
+        activation: &mut Activation<'_, 'gc>,
+        context: &mut UpdateContext<'_, 'gc, '_>,
+    ) -> Result<Value<'gc>, Error> {
+        let value = match self.read_u8()? {
+            NUMBER => self.read_f64()?.into(),
+            BOOLEAN => (self.read_u8()? != 0).into(),
+            STRING => AvmString::new(context.gc_context, self.read_utf8()?).into(),
+            LONG_STRING => AvmString::new(context.gc_context, self.read_long_utf8()?).into(),
+            NULL => Value::Null,
+            UNDEFINED => Value::Undefined,
+            OBJECT => {
+                let proto = activation.avm.prototypes.object;
+                self.read_object(activation, context, proto)?.into()
+            }
+            TYPED_OBJECT => {
+                let class_name = self.read_utf8()?;
+                let proto = registered_class_prototype(activation, context, &class_name)
+                    .unwrap_or_else(|| {
+                        log::warn!("AMF0: Class {} is not registered", class_name);
+                        activation.avm.prototypes.object
+                    });
+                self.read_object(activation, context, proto)?.into()
+            }
+            ECMA_ARRAY => {
+                let length = self.read_u32()? as usize;
+                let array: Object<'gc> =
+                    ScriptObject::array(context.gc_context, Some(activation.avm.prototypes.array))
+                        .into();
+                self.objects.push(array);
+                self.read_properties(activation, context, array, true)?;
+                if length > self.max_array_length {
+                    log::warn!("AMF0: Ignoring ECMA array length {}", length);
+                } else if array.length() < length {
+                    array.set_length(context.gc_context, length);
+                }
+                array.into()
+            }
+            STRICT_ARRAY => {
+                let length = self.read_u32()? as usize;
+                let array: Object<'gc> =
+                    ScriptObject::array(context.gc_context, Some(activation.avm.prototypes.array))
+                        .into();
+                self.objects.push(array);
+                for i in 0..length {
+                    let value = self.read_value(activation, context)?;
+                    array.set_array_element(i, value, context.gc_context);
+                }
+                array.into()
+            }
+            REFERENCE => {
+                let index = self.read_u16()?;
+                self.objects
+                    .get(usize::from(index))
+                    .copied()
+                    .ok_or(Error::InvalidReference(index))?
+                    .into()
+            }
+            DATE => {
+                let time = self.read_f64()?;
+                // The time zone is reserved, and ignored.
+                let _timezone = self.read_u16()?;
+                let date = DateObject::empty_date_object(
+                    context.gc_context,
+                    Some(activation.avm.prototypes.date),
+                );
+                date.set_date_time(context.gc_context, time);
+                Value::Object(date.into())
+            }
+            marker => return Err(Error::UnsupportedType(marker)),
+        };
+        Ok(value)
+    }
+
+    fn read_object(
+        &mut self,
+        activation: &mut Activation<'_, 'gc>,
+        context: &mut UpdateContext<'_, 'gc, '_>,
+        proto: Object<'gc>,
+    ) -> Result<Object<'gc>, Error> {
+        let object = ScriptObject::object(context.gc_context, Some(proto)).into();
+        self.objects.push(object);
+        self.read_properties(activation, context, object, false)?;
+        Ok(object)
+    }
+
+    /// Reads the properties of an object or ECMA array, up to the object end marker.
+    ///
+    /// Numeric keys of an ECMA array are stored as array elements, unless they are
+    /// too large to be stored densely.
+    fn read_properties(
+        &mut self,
+        activation: &mut Activation<'_, 'gc>,
+        context: &mut UpdateContext<'_, 'gc, '_>,
+        object: Object<'gc>,
+        is_array: bool,
+    ) -> Result<(), Error> {
+        loop {
+            let key = self.read_utf8()?;
+            if key.is_empty() && self.data.first() == Some(&OBJECT_END) {
+                self.read_u8()?;
+                return Ok(());
+            }
+
+            let value = self.read_value(activation, context)?;
+            match key.parse::<usize>() {
+                Ok(index) if is_array && index < self.max_array_length => {
+                    object.set_array_element(index, value, context.gc_context);
+                }
+                _ => object.define_value(
+                    context.gc_context,
+                    AvmString::new(context.gc_context, key),
+                    value,
+                    EnumSet::empty(),
+                ),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::avm1::error::Error as Avm1Error;
+    use crate::avm1::test_utils::with_avm;
+
+    /// An object with a number, a string, a boolean, a nested object, null and
+    /// undefined, in the order written by Flash Player:
+    /// `{ n: 1.5, s: "hi", b: true, o: { x: -2 }, z: null, u: undefined }`
+    const OBJECT_FIXTURE: &[u8] = &[
+        0x03, // object
+        0x00, 0x01, b'n', 0x00, 0x3F, 0xF8, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, //
+        0x00, 0x01, b's', 0x02, 0x00, 0x02, b'h', b'i', //
+        0x00, 0x01, b'b', 0x01, 0x01, //
+        0x00, 0x01, b'o', 0x03, //
+        0x00, 0x01, b'x', 0x00, 0xC0, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, //
+        0x00, 0x00, 0x09, //
+        0x00, 0x01, b'z', 0x05, //
+        0x00, 0x01, b'u', 0x06, //
+        0x00, 0x00, 0x09,
+    ];
+
+    /// An object referencing itself: `o = {}; o.self = o;`
+    const CYCLIC_FIXTURE: &[u8] = &[
+        0x03, // object
+        0x00, 0x04, b's', b'e', b'l', b'f', 0x07, 0x00, 0x00, //
+        0x00, 0x00, 0x09,
+    ];
+
+    /// An ECMA array as written for `a = [1, "two"]; a.name = "list";`
+    const ECMA_ARRAY_FIXTURE: &[u8] = &[
+        0x08, 0x00, 0x00, 0x00, 0x02, // ECMA array of length 2
+        0x00, 0x01, b'0', 0x00, 0x3F, 0xF0, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, //
+        0x00, 0x01, b'1', 0x02, 0x00, 0x03, b't', b'w', b'o', //
+        0x00, 0x04, b'n', b'a', b'm', b'e', 0x02, 0x00, 0x04, b'l', b'i', b's', b't', //
+        0x00, 0x00, 0x09,
+    ];
+
+    /// A strict array containing the same object twice.
+    const STRICT_ARRAY_FIXTURE: &[u8] = &[
+        0x0A, 0x00, 0x00, 0x00, 0x02, // strict array of length 2
+        0x03, 0x00, 0x00, 0x09, // empty object
+        0x07, 0x00, 0x01, // reference to the object
+    ];
+
+    #[test]
+    fn deserialize_object() {
+        with_avm(8, |activation, context, _this| -> Result<(), Avm1Error> {
+            let object = deserialize(activation, context, OBJECT_FIXTURE)
+                .unwrap()
+                .remove(0)
+                .coerce_to_object(activation, context);
+            assert_eq!(object.get("n", activation, context)?, 1.5.into());
+            assert_eq!(object.get("s", activation, context)?, "hi".into());
+            assert_eq!(object.get("b", activation, context)?, true.into());
+            assert_eq!(object.get("z", activation, context)?, Value::Null);
+            assert!(object.has_own_property(activation, context, "u"));
+            let nested = object
+                .get("o", activation, context)?
+                .coerce_to_object(activation, context);
+            assert_eq!(nested.get("x", activation, context)?, (-2).into());
+            Ok(())
+        });
+    }
+
+    #[test]
+    fn serialize_object() {
+        with_avm(8, |activation, context, _this| -> Result<(), Avm1Error> {
+            let values = deserialize(activation, context, OBJECT_FIXTURE).unwrap();
+            assert_eq!(
+                serialize(activation, context, &values).unwrap(),
+                OBJECT_FIXTURE
+            );
+            Ok(())
+        });
+    }
+
+    #[test]
+    fn cyclic_objects() {
+        with_avm(8, |activation, context, _this| -> Result<(), Avm1Error> {
+            let object =
+                ScriptObject::object(context.gc_context, Some(activation.avm.prototypes.object));
+            object.set("self", object.into(), activation, context)?;
+            assert_eq!(
+                serialize(activation, context, &[object.into()]).unwrap(),
+                CYCLIC_FIXTURE
+            );
+
+            let object = deserialize(activation, context, CYCLIC_FIXTURE)
+                .unwrap()
+                .remove(0)
+                .coerce_to_object(activation, context);
+            assert_eq!(object.get("self", activation, context)?, object.into());
+            Ok(())
+        });
+    }
+
+    #[test]
+    fn arrays() {
+        with_avm(8, |activation, context, _this| -> Result<(), Avm1Error> {
+            let array = deserialize(activation, context, ECMA_ARRAY_FIXTURE)
+                .unwrap()
+                .remove(0)
+                .coerce_to_object(activation, context);
+            assert!(activation.avm.prototypes.array.is_prototype_of(array));
+            assert_eq!(array.length(), 2);
+            assert_eq!(array.array_element(0), 1.into());
+            assert_eq!(array.array_element(1), "two".into());
+            assert_eq!(array.get("name", activation, context)?, "list".into());
+            assert_eq!(
+                serialize(activation, context, &[array.into()]).unwrap(),
+                ECMA_ARRAY_FIXTURE
+            );
+
+            let array = deserialize(activation, context, STRICT_ARRAY_FIXTURE)
+                .unwrap()
+                .remove(0)
+                .coerce_to_object(activation, context);
+            assert_eq!(array.length(), 2);
+            assert_eq!(array.array_element(0), array.array_element(1));
+            Ok(())
+        });
+    }
+
+    #[test]
+    fn long_strings() {
+        with_avm(8, |activation, context, _this| -> Result<(), Avm1Error> {
+            let string = AvmString::new(context.gc_context, "a".repeat(70000));
+            let bytes = serialize(activation, context, &[string.into()]).unwrap();
+            assert_eq!(&bytes[..5], &[0x0C, 0x00, 0x01, 0x11, 0x70]);
+            assert_eq!(
+                deserialize(activation, context, &bytes),
+                Ok(vec![string.into()])
+            );
+            Ok(())
+        });
+    }
+
+    #[test]
+    fn typed_objects() {
+        with_avm(8, |activation, context, _this| -> Result<(), Avm1Error> {
+            let object_proto = activation.avm.prototypes.object;
+            let constructor = ScriptObject::object(context.gc_context, Some(object_proto));
+            let prototype = ScriptObject::object(context.gc_context, Some(object_proto));
+            constructor.set("prototype", prototype.into(), activation, context)?;
+            context
+                .library
+                .library_for_movie_mut(context.swf.clone())
+                .register_avm1_constructor("Point", Some(constructor.into()));
+
+            let bytes = [
+                0x10, 0x00, 0x05, b'P', b'o', b'i', b'n', b't', // typed object
+                0x00, 0x01, b'x', 0x00, 0x40, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, //
+                0x00, 0x00, 0x09,
+            ];
+            let point = deserialize(activation, context, &bytes)
+                .unwrap()
+                .remove(0)
+                .coerce_to_object(activation, context);
+            assert!(prototype.is_prototype_of(point));
+            assert_eq!(point.get("x", activation, context)?, 2.into());
+            assert_eq!(
+                serialize(activation, context, &[point.into()]).unwrap(),
+                bytes
+            );
+            Ok(())
+        });
+    }
+
+    #[test]
+    fn dates() {
+        with_avm(8, |activation, context, _this| -> Result<(), Avm1Error> {
+            // `new Date(Date.UTC(2001, 8, 9, 1, 46, 40))`
+            let bytes = [
+                0x0B, 0x42, 0x6D, 0x1A, 0x94, 0xA2, 0x00, 0x00, 0x00, // date
+                0x00, 0x00, // time zone
+            ];
+            let date = deserialize(activation, context, &bytes)
+                .unwrap()
+                .remove(0)
+                .coerce_to_object(activation, context);
+            assert_eq!(
+                date.as_date_object().map(|date| date.date_time()),
+                Some(1_000_000_000_000.0)
+            );
+            assert!(activation.avm.prototypes.date.is_prototype_of(date));
+            assert_eq!(
+                serialize(activation, context, &[date.into()]).unwrap(),
+                bytes
+            );
+
+            // Dates inside objects are written by value, not as references.
+            let object =
+                ScriptObject::object(context.gc_context, Some(activation.avm.prototypes.object));
+            object.set("a", date.into(), activation, context)?;
+            object.set("b", date.into(), activation, context)?;
+            let bytes = serialize(activation, context, &[object.into()]).unwrap();
+            let object = deserialize(activation, context, &bytes)
+                .unwrap()
+                .remove(0)
+                .coerce_to_object(activation, context);
+            for key in &["a", "b"] {
+                let date = object
+                    .get(key, activation, context)?
+                    .coerce_to_object(activation, context);
+                assert_eq!(
+                    date.as_date_object().map(|date| date.date_time()),
+                    Some(1_000_000_000_000.0)
+                );
+            }
+            Ok(())
+        });
+    }
+
+    #[test]
+    fn huge_array_lengths() {
+        with_avm(8, |activation, context, _this| -> Result<(), Avm1Error> {
+            // An ECMA array claiming to be 4 billion elements long, with an element
+            // near the end.
+            let bytes = [
+                0x08, 0xFF, 0xFF, 0xFF, 0xFF, // ECMA array of length 0xFFFFFFFF
+                0x00, 0x0A, b'4', b'0', b'0', b'0', b'0', b'0', b'0', b'0', b'0', b'0', //
+                0x01, 0x01, //
+                0x00, 0x00, 0x09,
+            ];
+            let array = deserialize(activation, context, &bytes)
+                .unwrap()
+                .remove(0)
+                .coerce_to_object(activation, context);
+            assert_eq!(array.length(), 0);
+            assert_eq!(array.get("4000000000", activation, context)?, true.into());
+            Ok(())
+        });
+    }
+
+    #[test]
+    fn invalid_data() {
+        with_avm(8, |activation, context, _this| -> Result<(), Avm1Error> {
+            // Movie clips can't be deserialized.
+            assert_eq!(
+                deserialize(activation, context, &[0x04, 0x00, 0x00]),
+                Err(Error::UnsupportedType(0x04))
+            );
+            assert_eq!(
+                deserialize(activation, context, &[0x00, 0x3F]),
+                Err(Error::UnexpectedEof)
+            );
+            assert_eq!(
+                deserialize(activation, context, &[0x07, 0x00, 0x00]),
+                Err(Error::InvalidReference(0))
+            );
+            Ok(())
+        });
+    }
+
+    #[test]
+    fn shared_objects_between_values() {
+        with_avm(8, |activation, context, _this| -> Result<(), Avm1Error> {
+            let object =
+                ScriptObject::object(context.gc_context, Some(activation.avm.prototypes.object));
+            let bytes = serialize(
+                activation,
+                context,
+                &[object.into(), 1.into(), object.into()],
+            )
+            .unwrap();
+            assert_eq!(
+                bytes,
+                [
+                    0x03, 0x00, 0x00, 0x09, // empty object
+                    0x00, 0x3F, 0xF0, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, // 1
+                    0x07, 0x00, 0x00, // reference to the object
+                ]
+            );
+
+            let values = deserialize(activation, context, &bytes).unwrap();
+            assert_eq!(values.len(), 3);
+            assert_eq!(values[1], 1.into());
+            assert_eq!(values[0], values[2]);
+            Ok(())
+        });
+    }
+
+    #[test]
+    fn nesting_too_deep() {
+        with_avm(8, |activation, context, _this| -> Result<(), Avm1Error> {
+            // Arrays nested as deep as allowed, and one level deeper.
+            for &(depth, is_ok) in &[(MAX_DEPTH, true), (MAX_DEPTH + 1, false)] {
+                let mut bytes = vec![];
+                for _ in 0..depth - 1 {
+                    bytes.extend_from_slice(&[0x0A, 0x00, 0x00, 0x00, 0x01]);
+                }
+                bytes.push(0x05);
+                assert_eq!(
+                    deserialize(activation, context, &bytes).is_ok(),
+                    is_ok,
+                    "Reading {} levels",
+                    depth
+                );
+
+                let mut value = Value::Null;
+                for _ in 0..depth - 1 {
+                    let array = ScriptObject::array(
+                        context.gc_context,
+                        Some(activation.avm.prototypes.array),
+                    );
+                    array.set_array_element(0, value, context.gc_context);
+                    value = array.into();
+                }
+                let result = serialize(activation, context, &[value]);
+                if is_ok {
+                    assert!(result.is_ok(), "Writing {} levels", depth);
+                } else {
+                    assert_eq!(result, Err(Error::NestingTooDeep));
+                }
+            }
+            Ok(())
+        });
+    }
+
+    #[test]
+    fn long_property_names() {
+        let mut writer = Amf0Writer::new();
+        // 65534 bytes, then a 2 byte character that doesn't fit.
+        writer.write_utf8(&format!("{}\u{e9}", "a".repeat(65534)));
+        let bytes = writer.into_bytes();
+        assert_eq!(&bytes[..2], &[0xFF, 0xFE]);
+        assert_eq!(bytes.len(), 65536);
+        assert!(std::str::from_utf8(&bytes[2..]).is_ok());
+    }
+}
@@ -1,7 +1,7 @@
 use crate::avm1::error::Error;
 use crate::avm1::function::Executable;
 use crate::avm1::property::Attribute;
-use crate::avm1::{Object, ObjectPtr, ScriptObject, TObject, Value};
+use crate::avm1::{AvmString, Object, ObjectPtr, ScriptObject, TObject, Value};
 use crate::context::UpdateContext;
 use enumset::EnumSet;
 use gc_arena::{Collect, GcCell, MutationContext};
@@ -112,7 +112,7 @@ impl<'gc> TObject<'gc> for ColorTransformObject<'gc> {
 
     fn set(
         &self,
-        name: &str,
+        name: impl Into<AvmString<'gc>>,
         value: Value<'gc>,
         activation: &mut Activation<'_, 'gc>,
         context: &mut UpdateContext<'_, 'gc, '_>,
@@ -185,7 +185,7 @@ impl<'gc> TObject<'gc> for ColorTransformObject<'gc> {
     fn define_value(
         &self,
         gc_context: MutationContext<'gc, '_>,
-        name: &str,
+        name: impl Into<AvmString<'gc>>,
         value: Value<'gc>,
         attributes: EnumSet<Attribute>,
     ) {
@@ -207,7 +207,7 @@ impl<'gc> TObject<'gc> for ColorTransformObject<'gc> {
     fn add_property(
         &self,
         gc_context: MutationContext<'gc, '_>,
-        name: &str,
+        name: impl Into<AvmString<'gc>>,
         get: Executable<'gc>,
         set: Option<Executable<'gc>>,
         attributes: EnumSet<Attribute>,
@@ -220,7 +220,7 @@ impl<'gc> TObject<'gc> for ColorTransformObject<'gc> {
         &self,
         activation: &mut Activation<'_, 'gc>,
         gc_context: MutationContext<'gc, '_>,
-        name: &str,
+        name: impl Into<AvmString<'gc>>,
         get: Executable<'gc>,
         set: Option<Executable<'gc>>,
         attributes: EnumSet<Attribute>,
@@ -260,8 +260,12 @@ impl<'gc> TObject<'gc> for ColorTransformObject<'gc> {
         self.base().is_property_enumerable(activation, name)
     }
 
-    fn get_keys(&self, activation: &mut Activation<'_, 'gc>) -> Vec<String> {
-        self.base().get_keys(activation)
+    fn get_keys(
+        &self,
+        activation: &mut Activation<'_, 'gc>,
+        gc_context: MutationContext<'gc, '_>,
+    ) -> Vec<AvmString<'gc>> {
+        self.base().get_keys(activation, gc_context)
     }
 
     fn as_string(&self) -> Cow<str> {
@@ -123,7 +123,7 @@ impl<'a> VariableDumper<'a> {
         activation: &mut Activation<'_, 'gc>,
         context: &mut UpdateContext<'_, 'gc, '_>,
     ) {
-        let keys = object.get_keys(activation);
+        let keys = object.get_keys(activation, context.gc_context);
         if keys.is_empty() {
             self.output.push_str(" {}");
         } else {
@@ -172,7 +172,7 @@ impl<'a> VariableDumper<'a> {
         activation: &mut Activation<'_, 'gc>,
         context: &mut UpdateContext<'_, 'gc, '_>,
     ) {
-        let keys = object.get_keys(activation);
+        let keys = object.get_keys(activation, context.gc_context);
         if keys.is_empty() {
             return;
         }
@@ -267,12 +267,12 @@ mod tests {
     fn dump_string() {
         with_avm(19, |activation, context, _root| -> Result<(), Error> {
             assert_eq!(
-                VariableDumper::dump(&Value::String("".to_string()), " ", activation, context),
+                VariableDumper::dump(&Value::String("".into()), " ", activation, context),
                 "\"\""
             );
             assert_eq!(
                 VariableDumper::dump(
-                    &Value::String("HELLO WORLD".to_string()),
+                    &Value::String("HELLO WORLD".into()),
                     " ",
                     activation,
                     context
@@ -282,7 +282,7 @@ mod tests {
             assert_eq!(
                 VariableDumper::dump(
                     &Value::String(
-                        "Escape \"this\" string\nplease! \u{0008}\u{000C}\n\r\t\"\\".to_string()
+                        "Escape \"this\" string\nplease! \u{0008}\u{000C}\n\r\t\"\\".into()
                     ),
                     " ",
                     activation,
@@ -312,12 +312,7 @@ mod tests {
             let object = ScriptObject::object(context.gc_context, None);
             let child = ScriptObject::object(context.gc_context, None);
             object.set("self", object.into(), activation, context)?;
-            object.set(
-                "test",
-                Value::String("value".to_string()),
-                activation,
-                context,
-            )?;
+            object.set("test", Value::String("value".into()), activation, context)?;
             object.set("child", child.into(), activation, context)?;
             child.set("parent", object.into(), activation, context)?;
             child.set("age", Value::Number(6.0), activation, context)?;
@@ -354,12 +349,7 @@ mod tests {
             let object = ScriptObject::object(context.gc_context, None);
             let child = ScriptObject::object(context.gc_context, None);
             object.set("self", object.into(), activation, context)?;
-            object.set(
-                "test",
-                Value::String("value".to_string()),
-                activation,
-                context,
-            )?;
+            object.set("test", Value::String("value".into()), activation, context)?;
             object.set("child", child.into(), activation, context)?;
             child.set("parent", object.into(), activation, context)?;
             child.set("age", Value::Number(6.0), activation, context)?;
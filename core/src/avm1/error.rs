@@ -11,14 +11,18 @@ pub enum Error<'gc> {
 
     #[error("A script has thrown a custom error.")]
     ThrownValue(Value<'gc>),
+
+    #[error("A script has run for too long and has been aborted.")]
+    ExecutionTimeout,
 }
 
 impl Error<'_> {
     pub fn is_halting(&self) -> bool {
         match self {
             Error::PrototypeRecursionLimit => true,
-            Error::InvalidSwf(_) => true,
+            Error::InvalidSwf(_) => false,
             Error::ThrownValue(_) => false,
+            Error::ExecutionTimeout => false,
         }
     }
 }
@@ -13,14 +13,21 @@ pub fn parse(url: &str) -> Option<&str> {
     }
 }
 
-/// TODO: FSCommand URL handling
+/// Pass an FSCommand to the host, which may act on it.
 pub fn handle<'gc>(
-    fscommand: &str,
+    command: &str,
+    args: &str,
     _activation: &mut Activation,
-    _ac: &mut UpdateContext,
+    context: &mut UpdateContext,
 ) -> Result<(), Error<'gc>> {
-    log::warn!("Unhandled FSCommand: {}", fscommand);
+    let handled = match context.fs_command_handler.as_mut() {
+        Some(handler) => handler(command, args),
+        None => false,
+    };
+
+    if !handled {
+        log::warn!("Unhandled FSCommand: {} {}", command, args);
+    }
 
-    //This should be an error.
     Ok(())
 }
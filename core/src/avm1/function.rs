@@ -6,7 +6,7 @@ use crate::avm1::property::{Attribute, Attribute::*};
 use crate::avm1::scope::Scope;
 use crate::avm1::super_object::SuperObject;
 use crate::avm1::value::Value;
-use crate::avm1::{Object, ObjectPtr, ScriptObject, TObject, UpdateContext};
+use crate::avm1::{AvmString, Object, ObjectPtr, ScriptObject, TObject, UpdateContext};
 use crate::display_object::{DisplayObject, TDisplayObject};
 use crate::tag_utils::SwfSlice;
 use enumset::EnumSet;
@@ -66,13 +66,16 @@ pub struct Avm1Function<'gc> {
     /// The names of the function parameters and their register mappings.
     /// r0 indicates that no register shall be written and the parameter stored
     /// as a Variable instead.
-    params: Vec<(Option<u8>, String)>,
+    ///
+    /// The names are allocated once when the function is defined, so calls
+    /// don't have to copy them into a new string.
+    params: Vec<(Option<u8>, AvmString<'gc>)>,
 
     /// The scope the function was born into.
     scope: GcCell<'gc, Scope<'gc>>,
 
     /// The constant pool the function executes with.
-    constant_pool: GcCell<'gc, Vec<String>>,
+    constant_pool: GcCell<'gc, Vec<AvmString<'gc>>>,
 
     /// The base movie clip that the function was defined on.
     /// This is the movie clip that contains the bytecode.
@@ -85,12 +88,13 @@ impl<'gc> Avm1Function<'gc> {
     /// Parameters not specified in DefineFunction are filled with reasonable
     /// defaults.
     pub fn from_df1(
+        gc_context: MutationContext<'gc, '_>,
         swf_version: u8,
         actions: SwfSlice,
         name: &str,
         params: &[&str],
         scope: GcCell<'gc, Scope<'gc>>,
-        constant_pool: GcCell<'gc, Vec<String>>,
+        constant_pool: GcCell<'gc, Vec<AvmString<'gc>>>,
         base_clip: DisplayObject<'gc>,
     ) -> Self {
         let name = match name {
@@ -112,7 +116,10 @@ impl<'gc> Avm1Function<'gc> {
             suppress_this: false,
             preload_this: false,
             preload_global: false,
-            params: params.iter().map(|&s| (None, s.to_string())).collect(),
+            params: params
+                .iter()
+                .map(|&s| (None, intern_name(gc_context, constant_pool, s)))
+                .collect(),
             scope,
             constant_pool,
             base_clip,
@@ -121,11 +128,12 @@ impl<'gc> Avm1Function<'gc> {
 
     /// Construct a function from a DefineFunction2 action.
     pub fn from_df2(
+        gc_context: MutationContext<'gc, '_>,
         swf_version: u8,
         actions: SwfSlice,
         swf_function: &swf::avm1::types::Function,
         scope: GcCell<'gc, Scope<'gc>>,
-        constant_pool: GcCell<'gc, Vec<String>>,
+        constant_pool: GcCell<'gc, Vec<AvmString<'gc>>>,
         base_clip: DisplayObject<'gc>,
     ) -> Self {
         let name = match swf_function.name {
@@ -139,7 +147,7 @@ impl<'gc> Avm1Function<'gc> {
             register_index: r,
         } in &swf_function.params
         {
-            owned_params.push((*r, (*s).to_string()))
+            owned_params.push((*r, intern_name(gc_context, constant_pool, s)))
         }
 
         Avm1Function {
@@ -151,7 +159,7 @@ impl<'gc> Avm1Function<'gc> {
             preload_root: swf_function.preload_root,
             suppress_super: swf_function.suppress_super,
             preload_super: swf_function.preload_super,
-            suppress_arguments: swf_function.suppress_super,
+            suppress_arguments: swf_function.suppress_arguments,
             preload_arguments: swf_function.preload_arguments,
             suppress_this: swf_function.suppress_this,
             preload_this: swf_function.preload_this,
@@ -220,6 +228,10 @@ impl<'gc> Executable<'gc> {
     /// returns. If on-stack execution is possible, then this function returns
     /// a return value you must push onto the stack. Otherwise, you must
     /// create a new stack frame and execute the action data yourself.
+    ///
+    /// `callee` is the function object being called, if there is one. It is
+    /// exposed to the function as `arguments.callee`.
+    #[allow(clippy::too_many_arguments)]
     pub fn exec(
         &self,
         name: &str,
@@ -228,6 +240,7 @@ impl<'gc> Executable<'gc> {
         this: Object<'gc>,
         base_proto: Option<Object<'gc>>,
         args: &[Value<'gc>],
+        callee: Option<Object<'gc>>,
     ) -> Result<Value<'gc>, Error<'gc>> {
         match self {
             Executable::Native(nf) => nf(activation, ac, this, args),
@@ -236,27 +249,31 @@ impl<'gc> Executable<'gc> {
                     ac.gc_context,
                     Scope::new_local_scope(af.scope(), ac.gc_context),
                 );
-                let arguments =
-                    ScriptObject::object(ac.gc_context, Some(activation.avm.prototypes().object));
-                if !af.suppress_arguments {
-                    for i in 0..args.len() {
-                        arguments.define_value(
-                            ac.gc_context,
-                            &format!("{}", i),
-                            args.get(i).unwrap().clone(),
-                            DontDelete.into(),
-                        )
+                // `arguments` is an array, which also refers to this function and to the
+                // function that called it. Functions that suppress it and don't preload
+                // it into a register never see it, so it isn't built for them.
+                let argcell: Option<Object<'gc>> = if af.suppress_arguments && !af.preload_arguments
+                {
+                    None
+                } else {
+                    let arguments =
+                        ScriptObject::array(ac.gc_context, Some(activation.avm.prototypes().array));
+                    for (i, arg) in args.iter().enumerate() {
+                        arguments.set_array_element(i, arg.clone(), ac.gc_context);
                     }
-
+                    let caller = match activation.arguments {
+                        Some(caller_arguments) => caller_arguments.get("callee", activation, ac)?,
+                        None => Value::Null,
+                    };
                     arguments.define_value(
                         ac.gc_context,
-                        "length",
-                        args.len().into(),
+                        "callee",
+                        callee.map(Value::Object).unwrap_or(Value::Undefined),
                         DontDelete | DontEnum,
                     );
-                }
-
-                let argcell = arguments.into();
+                    arguments.define_value(ac.gc_context, "caller", caller, DontDelete | DontEnum);
+                    Some(arguments.into())
+                };
                 let super_object: Option<Object<'gc>> = if !af.suppress_super {
                     Some(
                         SuperObject::from_this_and_base_proto(
@@ -307,7 +324,7 @@ impl<'gc> Executable<'gc> {
                     af.constant_pool,
                     af.base_clip,
                     this,
-                    Some(argcell),
+                    if af.suppress_arguments { None } else { argcell },
                 );
 
                 frame.allocate_local_registers(af.register_count(), ac.gc_context);
@@ -322,9 +339,11 @@ impl<'gc> Executable<'gc> {
                 }
 
                 if af.preload_arguments {
-                    //TODO: What happens if you specify both suppress and
-                    //preload for arguments?
-                    frame.set_local_register(preload_r, argcell, ac.gc_context);
+                    // A suppressed `arguments` can still be preloaded; it is then only
+                    // reachable through its register.
+                    if let Some(argcell) = argcell {
+                        frame.set_local_register(preload_r, argcell, ac.gc_context);
+                    }
                     preload_r += 1;
                 }
 
@@ -371,7 +390,7 @@ impl<'gc> Executable<'gc> {
                             frame.set_local_register(*argreg, arg.clone(), ac.gc_context)
                         }
                         (Some(arg), Some((None, argname))) => {
-                            frame.define(argname, arg.clone(), ac.gc_context)
+                            frame.define(*argname, arg.clone(), ac.gc_context)
                         }
                         _ => {}
                     }
@@ -383,6 +402,24 @@ impl<'gc> Executable<'gc> {
     }
 }
 
+/// Returns the constant pool entry equal to `name`, or a new string if there
+/// is none.
+///
+/// Compilers put parameter names in the constant pool when the function body
+/// refers to them, so sharing the entry avoids a second copy of the name.
+fn intern_name<'gc>(
+    gc_context: MutationContext<'gc, '_>,
+    constant_pool: GcCell<'gc, Vec<AvmString<'gc>>>,
+    name: &str,
+) -> AvmString<'gc> {
+    constant_pool
+        .read()
+        .iter()
+        .find(|s| s.as_str() == name)
+        .copied()
+        .unwrap_or_else(|| AvmString::new(gc_context, name))
+}
+
 impl<'gc> From<NativeFunction<'gc>> for Executable<'gc> {
     fn from(nf: NativeFunction<'gc>) -> Self {
         Executable::Native(nf)
@@ -454,7 +491,22 @@ impl<'gc> FunctionObject<'gc> {
         fn_proto: Option<Object<'gc>>,
         prototype: Option<Object<'gc>>,
     ) -> Object<'gc> {
-        let function = Self::bare_function(context, function, fn_proto).into();
+        let function: Executable<'gc> = function.into();
+        let param_count = match &function {
+            Executable::Action(af) => Some(af.params.len()),
+            Executable::Native(_) => None,
+        };
+        let function: Object<'gc> = Self::bare_function(context, function, fn_proto).into();
+
+        // Script functions report how many parameters they declare.
+        if let Some(param_count) = param_count {
+            function.define_value(
+                context,
+                "length",
+                param_count.into(),
+                DontDelete | ReadOnly | DontEnum,
+            );
+        }
 
         if let Some(p) = prototype {
             p.define_value(
@@ -483,7 +535,7 @@ impl<'gc> TObject<'gc> for FunctionObject<'gc> {
 
     fn set(
         &self,
-        name: &str,
+        name: impl Into<AvmString<'gc>>,
         value: Value<'gc>,
         activation: &mut Activation<'_, 'gc>,
         context: &mut UpdateContext<'_, 'gc, '_>,
@@ -500,7 +552,15 @@ impl<'gc> TObject<'gc> for FunctionObject<'gc> {
         args: &[Value<'gc>],
     ) -> Result<Value<'gc>, Error<'gc>> {
         if let Some(exec) = self.as_executable() {
-            exec.exec(name, activation, context, this, base_proto, args)
+            exec.exec(
+                name,
+                activation,
+                context,
+                this,
+                base_proto,
+                args,
+                Some((*self).into()),
+            )
         } else {
             Ok(Value::Undefined)
         }
@@ -559,7 +619,7 @@ impl<'gc> TObject<'gc> for FunctionObject<'gc> {
     fn define_value(
         &self,
         gc_context: MutationContext<'gc, '_>,
-        name: &str,
+        name: impl Into<AvmString<'gc>>,
         value: Value<'gc>,
         attributes: EnumSet<Attribute>,
     ) {
@@ -580,7 +640,7 @@ impl<'gc> TObject<'gc> for FunctionObject<'gc> {
     fn add_property(
         &self,
         gc_context: MutationContext<'gc, '_>,
-        name: &str,
+        name: impl Into<AvmString<'gc>>,
         get: Executable<'gc>,
         set: Option<Executable<'gc>>,
         attributes: EnumSet<Attribute>,
@@ -593,7 +653,7 @@ impl<'gc> TObject<'gc> for FunctionObject<'gc> {
         &self,
         activation: &mut Activation<'_, 'gc>,
         gc_context: MutationContext<'gc, '_>,
-        name: &str,
+        name: impl Into<AvmString<'gc>>,
         get: Executable<'gc>,
         set: Option<Executable<'gc>>,
         attributes: EnumSet<Attribute>,
@@ -633,8 +693,12 @@ impl<'gc> TObject<'gc> for FunctionObject<'gc> {
         self.base.is_property_enumerable(activation, name)
     }
 
-    fn get_keys(&self, activation: &mut Activation<'_, 'gc>) -> Vec<String> {
-        self.base.get_keys(activation)
+    fn get_keys(
+        &self,
+        activation: &mut Activation<'_, 'gc>,
+        gc_context: MutationContext<'gc, '_>,
+    ) -> Vec<AvmString<'gc>> {
+        self.base.get_keys(activation, gc_context)
     }
 
     fn as_string(&self) -> Cow<str> {
@@ -2,6 +2,7 @@ use crate::avm1::activation::Activation;
 use crate::avm1::error::Error;
 use crate::avm1::fscommand;
 use crate::avm1::function::{Executable, FunctionObject};
+use crate::avm1::globals::as_broadcaster::BroadcasterFunctions;
 use crate::avm1::listeners::SystemListeners;
 use crate::avm1::{Object, ScriptObject, TObject, UpdateContext, Value};
 use crate::backend::navigator::NavigationMethod;
@@ -11,34 +12,52 @@ use rand::Rng;
 use std::f64;
 
 mod array;
+pub(crate) mod as_broadcaster;
+mod bitmap_data;
+mod bitmap_filter;
 pub(crate) mod boolean;
 pub(crate) mod button;
 mod color;
 mod color_transform;
+pub(crate) mod context_menu;
+mod context_menu_item;
+mod date;
 pub(crate) mod display_object;
 pub(crate) mod error;
+pub(crate) mod external_interface;
 mod function;
 mod key;
+mod load_vars;
+pub(crate) mod local_connection;
 mod math;
 mod matrix;
 pub(crate) mod mouse;
 pub(crate) mod movie_clip;
 mod movie_clip_loader;
+pub(crate) mod net_connection;
+pub(crate) mod net_stream;
 pub(crate) mod number;
 mod object;
 mod point;
+pub(crate) mod print_job;
 mod rectangle;
+pub(crate) mod selection;
 pub(crate) mod shared_object;
-mod sound;
+pub(crate) mod sound;
 mod stage;
 pub(crate) mod string;
+mod style_sheet;
 pub(crate) mod system;
 pub(crate) mod system_capabilities;
 pub(crate) mod system_ime;
 pub(crate) mod system_security;
 pub(crate) mod text_field;
 mod text_format;
+pub(crate) mod timer;
+mod transform;
+mod video;
 mod xml;
+pub(crate) mod xml_socket;
 
 #[allow(non_snake_case, unused_must_use)] //can't use errors yet
 pub fn getURL<'a, 'gc>(
@@ -51,25 +70,24 @@ pub fn getURL<'a, 'gc>(
     if let Some(url_val) = args.get(0) {
         let url = url_val.coerce_to_string(activation, context)?;
         if let Some(fscommand) = fscommand::parse(&url) {
-            fscommand::handle(fscommand, activation, context);
+            let args = match args.get(1) {
+                Some(args) => args.coerce_to_string(activation, context)?.to_string(),
+                None => String::new(),
+            };
+            fscommand::handle(fscommand, &args, activation, context);
             return Ok(Value::Undefined);
         }
 
-        let window = if let Some(window) = args.get(1) {
-            Some(window.coerce_to_string(activation, context)?.to_string())
-        } else {
-            None
+        let window = match args.get(1) {
+            Some(Value::Undefined) | None => String::new(),
+            Some(window) => window.coerce_to_string(activation, context)?.to_string(),
         };
         let method = match args.get(2) {
-            Some(Value::String(s)) if s == "GET" => Some(NavigationMethod::GET),
-            Some(Value::String(s)) if s == "POST" => Some(NavigationMethod::POST),
+            Some(Value::String(s)) => NavigationMethod::from_method_str(&s.to_ascii_uppercase()),
             _ => None,
         };
-        let vars_method = method.map(|m| (m, activation.locals_into_form_values(context)));
 
-        context
-            .navigator
-            .navigate_to_url(url.to_string(), window, vars_method);
+        activation.get_url(context, &url, &window, method);
     }
 
     Ok(Value::Undefined)
@@ -103,6 +121,128 @@ pub fn is_nan<'gc>(
     }
 }
 
+/// Skips the whitespace Flash allows in front of a number.
+fn trim_number_start(s: &str) -> &str {
+    s.trim_start_matches(|c| c == '\t' || c == '\n' || c == '\r' || c == ' ')
+}
+
+pub fn parse_int<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    action_context: &mut UpdateContext<'_, 'gc, '_>,
+    _this: Object<'gc>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let radix = match args.get(1) {
+        None | Some(Value::Undefined) => None,
+        Some(radix) => {
+            let radix = radix.coerce_to_i32(activation, action_context)?;
+            if !(2..=36).contains(&radix) {
+                return Ok(f64::NAN.into());
+            }
+            Some(radix as u32)
+        }
+    };
+
+    let string = args
+        .get(0)
+        .unwrap_or(&Value::Undefined)
+        .coerce_to_string(activation, action_context)?;
+    let mut string = trim_number_start(&string);
+
+    let is_negative = string.starts_with('-');
+    if is_negative || string.starts_with('+') {
+        string = &string[1..];
+    }
+
+    let has_hex_prefix = string.starts_with("0x") || string.starts_with("0X");
+    let radix = match radix {
+        Some(radix) => radix,
+        None if has_hex_prefix => 16,
+        // Unlike ECMAScript, a leading zero makes the number octal, as long as every digit is octal.
+        None if string.len() > 1
+            && string.starts_with('0')
+            && string.bytes().all(|c| (b'0'..=b'7').contains(&c)) =>
+        {
+            8
+        }
+        None => 10,
+    };
+    if radix == 16 && has_hex_prefix {
+        string = &string[2..];
+    }
+
+    let mut result: Option<f64> = None;
+    for c in string.chars() {
+        match c.to_digit(radix) {
+            Some(digit) => {
+                result = Some(result.unwrap_or(0.0) * f64::from(radix) + f64::from(digit));
+            }
+            // Parsing stops at the first invalid character.
+            None => break,
+        }
+    }
+
+    Ok(match result {
+        Some(n) if is_negative => (-n).into(),
+        Some(n) => n.into(),
+        None => f64::NAN.into(),
+    })
+}
+
+pub fn parse_float<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    action_context: &mut UpdateContext<'_, 'gc, '_>,
+    _this: Object<'gc>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let string = args
+        .get(0)
+        .unwrap_or(&Value::Undefined)
+        .coerce_to_string(activation, action_context)?;
+    let string = trim_number_start(&string);
+
+    // Find the longest prefix that is a decimal number, ignoring any trailing characters.
+    let bytes = string.as_bytes();
+    let mut end = 0;
+    if let Some(b'+') | Some(b'-') = bytes.get(end) {
+        end += 1;
+    }
+    let int_start = end;
+    while bytes.get(end).map_or(false, u8::is_ascii_digit) {
+        end += 1;
+    }
+    let mut has_digits = end > int_start;
+    if bytes.get(end) == Some(&b'.') {
+        let frac_start = end + 1;
+        let mut frac_end = frac_start;
+        while bytes.get(frac_end).map_or(false, u8::is_ascii_digit) {
+            frac_end += 1;
+        }
+        if has_digits || frac_end > frac_start {
+            has_digits = true;
+            end = frac_end;
+        }
+    }
+    if !has_digits {
+        return Ok(f64::NAN.into());
+    }
+    if let Some(b'e') | Some(b'E') = bytes.get(end) {
+        let mut exp_end = end + 1;
+        if let Some(b'+') | Some(b'-') = bytes.get(exp_end) {
+            exp_end += 1;
+        }
+        let exp_digits_start = exp_end;
+        while bytes.get(exp_end).map_or(false, u8::is_ascii_digit) {
+            exp_end += 1;
+        }
+        if exp_end > exp_digits_start {
+            end = exp_end;
+        }
+    }
+
+    Ok(string[..end].parse().unwrap_or(f64::NAN).into())
+}
+
 pub fn get_infinity<'gc>(
     activation: &mut Activation<'_, 'gc>,
     _action_context: &mut UpdateContext<'_, 'gc, '_>,
@@ -152,6 +292,14 @@ pub struct SystemPrototypes<'gc> {
     pub rectangle_constructor: Object<'gc>,
     pub shared_object: Object<'gc>,
     pub color_transform: Object<'gc>,
+    pub blur_filter: Object<'gc>,
+    pub glow_filter: Object<'gc>,
+    pub drop_shadow_filter: Object<'gc>,
+    pub bitmap_data: Object<'gc>,
+    pub transform: Object<'gc>,
+    pub date: Object<'gc>,
+    pub style_sheet: Object<'gc>,
+    pub video: Object<'gc>,
 }
 
 unsafe impl<'gc> gc_arena::Collect for SystemPrototypes<'gc> {
@@ -174,13 +322,26 @@ unsafe impl<'gc> gc_arena::Collect for SystemPrototypes<'gc> {
         self.rectangle.trace(cc);
         self.rectangle_constructor.trace(cc);
         self.shared_object.trace(cc);
+        self.blur_filter.trace(cc);
+        self.glow_filter.trace(cc);
+        self.drop_shadow_filter.trace(cc);
+        self.bitmap_data.trace(cc);
+        self.transform.trace(cc);
+        self.date.trace(cc);
+        self.style_sheet.trace(cc);
+        self.video.trace(cc);
     }
 }
 
 /// Initialize default global scope and builtins for an AVM1 instance.
 pub fn create_globals<'gc>(
     gc_context: MutationContext<'gc, '_>,
-) -> (SystemPrototypes<'gc>, Object<'gc>, SystemListeners<'gc>) {
+) -> (
+    SystemPrototypes<'gc>,
+    Object<'gc>,
+    BroadcasterFunctions<'gc>,
+    SystemListeners<'gc>,
+) {
     let object_proto = ScriptObject::object_cell(gc_context, None);
     let function_proto = function::create_proto(gc_context, object_proto);
 
@@ -191,6 +352,9 @@ pub fn create_globals<'gc>(
     let movie_clip_proto: Object<'gc> =
         movie_clip::create_proto(gc_context, object_proto, function_proto);
 
+    let load_vars_proto: Object<'gc> =
+        load_vars::create_proto(gc_context, object_proto, function_proto);
+
     let movie_clip_loader_proto: Object<'gc> =
         movie_clip_loader::create_proto(gc_context, object_proto, function_proto);
 
@@ -212,6 +376,29 @@ pub fn create_globals<'gc>(
 
     let xml_proto: Object<'gc> = xml::create_xml_proto(gc_context, xmlnode_proto, function_proto);
 
+    let xml_socket_proto: Object<'gc> =
+        xml_socket::create_proto(gc_context, object_proto, function_proto);
+
+    let local_connection_proto: Object<'gc> =
+        local_connection::create_proto(gc_context, object_proto, function_proto);
+
+    let print_job_proto: Object<'gc> =
+        print_job::create_proto(gc_context, object_proto, function_proto);
+
+    let net_connection_proto: Object<'gc> =
+        net_connection::create_proto(gc_context, object_proto, function_proto);
+
+    let net_stream_proto: Object<'gc> =
+        net_stream::create_proto(gc_context, object_proto, function_proto);
+
+    let video_proto: Object<'gc> = video::create_proto(gc_context, object_proto, function_proto);
+
+    let context_menu_proto: Object<'gc> =
+        context_menu::create_proto(gc_context, object_proto, function_proto);
+
+    let context_menu_item_proto: Object<'gc> =
+        context_menu_item::create_proto(gc_context, object_proto, function_proto);
+
     let string_proto: Object<'gc> = string::create_proto(gc_context, object_proto, function_proto);
     let number_proto: Object<'gc> = number::create_proto(gc_context, object_proto, function_proto);
     let boolean_proto: Object<'gc> =
@@ -222,6 +409,19 @@ pub fn create_globals<'gc>(
         rectangle::create_proto(gc_context, object_proto, function_proto);
     let color_transform_proto: Object<'gc> =
         color_transform::create_proto(gc_context, object_proto, function_proto);
+    let transform_proto: Object<'gc> =
+        transform::create_proto(gc_context, object_proto, function_proto);
+    let date_proto: Object<'gc> = date::create_proto(gc_context, object_proto, function_proto);
+    let style_sheet_proto: Object<'gc> =
+        style_sheet::create_proto(gc_context, object_proto, function_proto);
+    let bitmap_filter_proto: Object<'gc> =
+        bitmap_filter::create_proto(gc_context, object_proto, function_proto);
+    let blur_filter_proto = bitmap_filter::create_filter_proto(gc_context, bitmap_filter_proto);
+    let glow_filter_proto = bitmap_filter::create_filter_proto(gc_context, bitmap_filter_proto);
+    let drop_shadow_filter_proto =
+        bitmap_filter::create_filter_proto(gc_context, bitmap_filter_proto);
+    let bitmap_data_proto: Object<'gc> =
+        bitmap_data::create_proto(gc_context, object_proto, function_proto);
 
     //TODO: These need to be constructors and should also set `.prototype` on each one
     let object = object::create_object_object(gc_context, object_proto, function_proto);
@@ -256,6 +456,12 @@ pub fn create_globals<'gc>(
         Some(function_proto),
         Some(movie_clip_proto),
     );
+    let load_vars = FunctionObject::function(
+        gc_context,
+        Executable::Native(load_vars::constructor),
+        Some(function_proto),
+        Some(load_vars_proto),
+    );
     let movie_clip_loader = FunctionObject::function(
         gc_context,
         Executable::Native(movie_clip_loader::constructor),
@@ -274,6 +480,18 @@ pub fn create_globals<'gc>(
         Some(function_proto),
         Some(text_field_proto),
     );
+    let style_sheet = FunctionObject::function(
+        gc_context,
+        Executable::Native(style_sheet::constructor),
+        Some(function_proto),
+        Some(style_sheet_proto),
+    );
+    text_field.define_value(
+        gc_context,
+        "StyleSheet",
+        style_sheet.into(),
+        EnumSet::empty(),
+    );
     let text_format = FunctionObject::function(
         gc_context,
         Executable::Native(text_format::constructor),
@@ -293,6 +511,54 @@ pub fn create_globals<'gc>(
         Some(function_proto),
         Some(xml_proto),
     );
+    let xml_socket = FunctionObject::function(
+        gc_context,
+        Executable::Native(xml_socket::constructor),
+        Some(function_proto),
+        Some(xml_socket_proto),
+    );
+    let local_connection = FunctionObject::function(
+        gc_context,
+        Executable::Native(local_connection::constructor),
+        Some(function_proto),
+        Some(local_connection_proto),
+    );
+    let print_job = FunctionObject::function(
+        gc_context,
+        Executable::Native(print_job::constructor),
+        Some(function_proto),
+        Some(print_job_proto),
+    );
+    let net_connection = FunctionObject::function(
+        gc_context,
+        Executable::Native(net_connection::constructor),
+        Some(function_proto),
+        Some(net_connection_proto),
+    );
+    let net_stream = FunctionObject::function(
+        gc_context,
+        Executable::Native(net_stream::constructor),
+        Some(function_proto),
+        Some(net_stream_proto),
+    );
+    let video = FunctionObject::function(
+        gc_context,
+        Executable::Native(video::constructor),
+        Some(function_proto),
+        Some(video_proto),
+    );
+    let context_menu = FunctionObject::function(
+        gc_context,
+        Executable::Native(context_menu::constructor),
+        Some(function_proto),
+        Some(context_menu_proto),
+    );
+    let context_menu_item = FunctionObject::function(
+        gc_context,
+        Executable::Native(context_menu_item::constructor),
+        Some(function_proto),
+        Some(context_menu_item_proto),
+    );
     let string = string::create_string_object(gc_context, Some(string_proto), Some(function_proto));
     let number = number::create_number_object(gc_context, Some(number_proto), Some(function_proto));
     let boolean =
@@ -322,17 +588,116 @@ pub fn create_globals<'gc>(
         .into(),
         EnumSet::empty(),
     );
+    geom.define_value(
+        gc_context,
+        "Transform",
+        FunctionObject::function(
+            gc_context,
+            Executable::Native(transform::constructor),
+            Some(function_proto),
+            Some(transform_proto),
+        )
+        .into(),
+        EnumSet::empty(),
+    );
 
-    let listeners = SystemListeners::new(gc_context, Some(array_proto));
+    let filters = ScriptObject::object(gc_context, Some(object_proto));
+    flash.define_value(gc_context, "filters", filters.into(), EnumSet::empty());
+    filters.define_value(
+        gc_context,
+        "BitmapFilter",
+        FunctionObject::function(
+            gc_context,
+            Executable::Native(object::constructor),
+            Some(function_proto),
+            Some(bitmap_filter_proto),
+        )
+        .into(),
+        EnumSet::empty(),
+    );
+    filters.define_value(
+        gc_context,
+        "BlurFilter",
+        bitmap_filter::create_filter_object(
+            gc_context,
+            bitmap_filter::blur_filter_constructor,
+            blur_filter_proto,
+            function_proto,
+        )
+        .into(),
+        EnumSet::empty(),
+    );
+    filters.define_value(
+        gc_context,
+        "GlowFilter",
+        bitmap_filter::create_filter_object(
+            gc_context,
+            bitmap_filter::glow_filter_constructor,
+            glow_filter_proto,
+            function_proto,
+        )
+        .into(),
+        EnumSet::empty(),
+    );
+    filters.define_value(
+        gc_context,
+        "DropShadowFilter",
+        bitmap_filter::create_filter_object(
+            gc_context,
+            bitmap_filter::drop_shadow_filter_constructor,
+            drop_shadow_filter_proto,
+            function_proto,
+        )
+        .into(),
+        EnumSet::empty(),
+    );
+
+    let external = ScriptObject::object(gc_context, Some(object_proto));
+    flash.define_value(gc_context, "external", external.into(), EnumSet::empty());
+    external.define_value(
+        gc_context,
+        "ExternalInterface",
+        external_interface::create_external_interface_object(
+            gc_context,
+            Some(object_proto),
+            Some(function_proto),
+        )
+        .into(),
+        EnumSet::empty(),
+    );
+
+    let display = ScriptObject::object(gc_context, Some(object_proto));
+    flash.define_value(gc_context, "display", display.into(), EnumSet::empty());
+    display.define_value(
+        gc_context,
+        "BitmapData",
+        bitmap_data::create_bitmap_data_object(
+            gc_context,
+            Some(bitmap_data_proto),
+            Some(function_proto),
+        )
+        .into(),
+        EnumSet::empty(),
+    );
+
+    let (broadcaster_functions, as_broadcaster) =
+        as_broadcaster::create(gc_context, Some(object_proto), function_proto);
 
     let mut globals = ScriptObject::bare_object(gc_context);
     globals.define_value(gc_context, "flash", flash.into(), EnumSet::empty());
     globals.define_value(gc_context, "Array", array.into(), EnumSet::empty());
+    globals.define_value(
+        gc_context,
+        "AsBroadcaster",
+        as_broadcaster.into(),
+        EnumSet::empty(),
+    );
     globals.define_value(gc_context, "Button", button.into(), EnumSet::empty());
     globals.define_value(gc_context, "Color", color.into(), EnumSet::empty());
     globals.define_value(gc_context, "Error", error.into(), EnumSet::empty());
     globals.define_value(gc_context, "Object", object.into(), EnumSet::empty());
     globals.define_value(gc_context, "Function", function.into(), EnumSet::empty());
+    globals.define_value(gc_context, "LoadVars", load_vars.into(), EnumSet::empty());
     globals.define_value(gc_context, "MovieClip", movie_clip.into(), EnumSet::empty());
     globals.define_value(
         gc_context,
@@ -350,10 +715,41 @@ pub fn create_globals<'gc>(
     );
     globals.define_value(gc_context, "XMLNode", xmlnode.into(), EnumSet::empty());
     globals.define_value(gc_context, "XML", xml.into(), EnumSet::empty());
+    globals.define_value(gc_context, "XMLSocket", xml_socket.into(), EnumSet::empty());
+    globals.define_value(
+        gc_context,
+        "LocalConnection",
+        local_connection.into(),
+        EnumSet::empty(),
+    );
+    globals.define_value(gc_context, "PrintJob", print_job.into(), EnumSet::empty());
+    globals.define_value(
+        gc_context,
+        "NetConnection",
+        net_connection.into(),
+        EnumSet::empty(),
+    );
+    globals.define_value(gc_context, "NetStream", net_stream.into(), EnumSet::empty());
+    globals.define_value(gc_context, "Video", video.into(), EnumSet::empty());
+    globals.define_value(
+        gc_context,
+        "ContextMenu",
+        context_menu.into(),
+        EnumSet::empty(),
+    );
+    globals.define_value(
+        gc_context,
+        "ContextMenuItem",
+        context_menu_item.into(),
+        EnumSet::empty(),
+    );
     globals.define_value(gc_context, "String", string.into(), EnumSet::empty());
     globals.define_value(gc_context, "Number", number.into(), EnumSet::empty());
     globals.define_value(gc_context, "Boolean", boolean.into(), EnumSet::empty());
 
+    let date = date::create_date_object(gc_context, Some(date_proto), Some(function_proto));
+    globals.define_value(gc_context, "Date", date.into(), EnumSet::empty());
+
     let shared_object_proto = shared_object::create_proto(gc_context, object_proto, function_proto);
 
     let shared_obj = shared_object::create_shared_object_object(
@@ -375,7 +771,8 @@ pub fn create_globals<'gc>(
         gc_context,
         Some(object_proto),
         Some(function_proto),
-        &listeners.ime,
+        broadcaster_functions,
+        array_proto,
     );
 
     let system = system::create(
@@ -398,38 +795,41 @@ pub fn create_globals<'gc>(
         )),
         EnumSet::empty(),
     );
-    globals.define_value(
+
+    let mouse = mouse::create_mouse_object(
         gc_context,
-        "Mouse",
-        Value::Object(mouse::create_mouse_object(
-            gc_context,
-            Some(object_proto),
-            Some(function_proto),
-            &listeners.mouse,
-        )),
-        EnumSet::empty(),
+        Some(object_proto),
+        Some(function_proto),
+        broadcaster_functions,
+        array_proto,
     );
-    globals.define_value(
+    globals.define_value(gc_context, "Mouse", mouse.into(), EnumSet::empty());
+
+    let selection = selection::create_selection_object(
         gc_context,
-        "Key",
-        Value::Object(key::create_key_object(
-            gc_context,
-            Some(object_proto),
-            Some(function_proto),
-        )),
-        EnumSet::empty(),
+        Some(object_proto),
+        Some(function_proto),
+        broadcaster_functions,
+        array_proto,
     );
-    globals.define_value(
+    globals.define_value(gc_context, "Selection", selection.into(), EnumSet::empty());
+
+    let key = key::create_key_object(
         gc_context,
-        "Stage",
-        Value::Object(stage::create_stage_object(
-            gc_context,
-            Some(object_proto),
-            Some(array_proto),
-            Some(function_proto),
-        )),
-        EnumSet::empty(),
+        Some(object_proto),
+        Some(function_proto),
+        broadcaster_functions,
+        array_proto,
+    );
+    globals.define_value(gc_context, "Key", key.into(), EnumSet::empty());
+
+    let stage = stage::create_stage_object(
+        gc_context,
+        Some(object_proto),
+        broadcaster_functions,
+        array_proto,
     );
+    globals.define_value(gc_context, "Stage", stage.into(), EnumSet::empty());
     globals.force_set_function(
         "isNaN",
         is_nan,
@@ -437,6 +837,20 @@ pub fn create_globals<'gc>(
         EnumSet::empty(),
         Some(function_proto),
     );
+    globals.force_set_function(
+        "parseInt",
+        parse_int,
+        gc_context,
+        EnumSet::empty(),
+        Some(function_proto),
+    );
+    globals.force_set_function(
+        "parseFloat",
+        parse_float,
+        gc_context,
+        EnumSet::empty(),
+        Some(function_proto),
+    );
     globals.force_set_function(
         "getURL",
         getURL,
@@ -451,6 +865,34 @@ pub fn create_globals<'gc>(
         EnumSet::empty(),
         Some(function_proto),
     );
+    globals.force_set_function(
+        "setInterval",
+        timer::set_interval,
+        gc_context,
+        EnumSet::empty(),
+        Some(function_proto),
+    );
+    globals.force_set_function(
+        "clearInterval",
+        timer::clear_interval,
+        gc_context,
+        EnumSet::empty(),
+        Some(function_proto),
+    );
+    globals.force_set_function(
+        "setTimeout",
+        timer::set_timeout,
+        gc_context,
+        EnumSet::empty(),
+        Some(function_proto),
+    );
+    globals.force_set_function(
+        "clearTimeout",
+        timer::clear_timeout,
+        gc_context,
+        EnumSet::empty(),
+        Some(function_proto),
+    );
     globals.force_set_function(
         "ASSetPropFlags",
         object::as_set_prop_flags,
@@ -493,9 +935,24 @@ pub fn create_globals<'gc>(
             rectangle_constructor: rectangle,
             shared_object: shared_object_proto,
             color_transform: color_transform_proto,
+            blur_filter: blur_filter_proto,
+            glow_filter: glow_filter_proto,
+            drop_shadow_filter: drop_shadow_filter_proto,
+            bitmap_data: bitmap_data_proto,
+            transform: transform_proto,
+            date: date_proto,
+            style_sheet: style_sheet_proto,
+            video: video_proto,
         },
         globals.into(),
-        listeners,
+        broadcaster_functions,
+        SystemListeners {
+            key,
+            mouse,
+            stage,
+            ime: system_ime,
+            selection,
+        },
     )
 }
 
@@ -574,6 +1031,63 @@ mod tests {
         }
     );
 
+    test_method!(parse_int_function, "parseInt", setup,
+        [6, 19] => {
+            ["123"] => 123.0,
+            ["  \t\r\n42"] => 42.0,
+            ["-42"] => -42.0,
+            ["+42"] => 42.0,
+            ["12.9"] => 12.0,
+            ["12abc"] => 12.0,
+            ["abc"] => std::f64::NAN,
+            [""] => std::f64::NAN,
+            ["-"] => std::f64::NAN,
+            ["0x1F"] => 31.0,
+            ["0XfF"] => 255.0,
+            ["-0x10"] => -16.0,
+            ["0x"] => std::f64::NAN,
+            ["010"] => 8.0,
+            ["-0777"] => -511.0,
+            ["019"] => 19.0,
+            ["0"] => 0.0,
+            ["ff", 16] => 255.0,
+            ["0xff", 16] => 255.0,
+            ["0x10", 10] => 0.0,
+            ["101", 2] => 5.0,
+            ["z", 36] => 35.0,
+            ["12", 8] => 10.0,
+            ["12", 1] => std::f64::NAN,
+            ["12", 37] => std::f64::NAN,
+            ["12", Value::Undefined] => 12.0,
+            ["1e3"] => 1.0,
+            [123.9] => 123.0,
+            [] => std::f64::NAN
+        }
+    );
+
+    test_method!(parse_float_function, "parseFloat", setup,
+        [6, 19] => {
+            ["1.5"] => 1.5,
+            ["  \t\r\n1.5"] => 1.5,
+            ["-1.5"] => -1.5,
+            ["+.5"] => 0.5,
+            ["5."] => 5.0,
+            ["2.5abc"] => 2.5,
+            ["1e3"] => 1000.0,
+            ["1.5E-2"] => 0.015,
+            ["1e"] => 1.0,
+            ["1e+"] => 1.0,
+            ["12px"] => 12.0,
+            ["0x10"] => 0.0,
+            ["."] => std::f64::NAN,
+            ["-"] => std::f64::NAN,
+            ["abc"] => std::f64::NAN,
+            ["Infinity"] => std::f64::NAN,
+            [""] => std::f64::NAN,
+            [] => std::f64::NAN
+        }
+    );
+
     test_method!(number_function, "Number", setup,
         [5, 6] => {
             [true] => 1.0,
@@ -4,7 +4,7 @@ use crate::avm1::activation::Activation;
 use crate::avm1::error::Error;
 use crate::avm1::function::{Executable, FunctionObject};
 use crate::avm1::property::Attribute;
-use crate::avm1::{Object, ScriptObject, TObject, UpdateContext, Value};
+use crate::avm1::{AvmString, Object, ScriptObject, TObject, UpdateContext, Value};
 use enumset::EnumSet;
 use gc_arena::MutationContext;
 use smallvec::alloc::borrow::Cow;
@@ -19,7 +19,6 @@ const NUMERIC: i32 = 16;
 
 // Default ordering to return if comparison is invalid.
 // TODO: This won't work accurately in cases like NaN/undefined.
-// We need to actually match Flash's sorting algorithm and not use Rust's Vec::sort.
 const DEFAULT_ORDERING: Ordering = Ordering::Equal;
 
 // Compare function used by sort and sortOn.
@@ -30,7 +29,7 @@ type CompareFn<'a, 'gc> = Box<
             &mut UpdateContext<'_, 'gc, '_>,
             &Value<'gc>,
             &Value<'gc>,
-        ) -> Ordering,
+        ) -> Result<Ordering, Error<'gc>>,
 >;
 
 pub fn create_array_object<'gc>(
@@ -111,7 +110,7 @@ pub fn constructor<'gc>(
         for arg in args {
             this.define_value(
                 context.gc_context,
-                &length.to_string(),
+                AvmString::new(context.gc_context, length.to_string()),
                 arg.to_owned(),
                 EnumSet::empty(),
             );
@@ -246,15 +245,18 @@ pub fn join<'gc>(
         .unwrap_or_else(|| Cow::Borrowed(","));
     let values: Vec<Value<'gc>> = this.array();
 
-    Ok(values
-        .iter()
-        .map(|v| {
-            v.coerce_to_string(activation, context)
-                .unwrap_or_else(|_| Cow::Borrowed("undefined"))
-        })
-        .collect::<Vec<Cow<str>>>()
-        .join(&separator)
-        .into())
+    Ok(AvmString::new(
+        context.gc_context,
+        values
+            .iter()
+            .map(|v| {
+                v.coerce_to_string(activation, context)
+                    .unwrap_or_else(|_| Cow::Borrowed("undefined"))
+            })
+            .collect::<Vec<Cow<str>>>()
+            .join(&separator),
+    )
+    .into())
 }
 
 fn make_index_absolute(mut index: i32, length: usize) -> usize {
@@ -386,7 +388,7 @@ pub fn concat<'gc>(
             .unwrap_or(Value::Undefined);
         array.define_value(
             context.gc_context,
-            &length.to_string(),
+            AvmString::new(context.gc_context, length.to_string()),
             old,
             EnumSet::empty(),
         );
@@ -406,7 +408,7 @@ pub fn concat<'gc>(
                         .unwrap_or(Value::Undefined);
                     array.define_value(
                         context.gc_context,
-                        &length.to_string(),
+                        AvmString::new(context.gc_context, length.to_string()),
                         old,
                         EnumSet::empty(),
                     );
@@ -418,7 +420,7 @@ pub fn concat<'gc>(
         if !added {
             array.define_value(
                 context.gc_context,
-                &length.to_string(),
+                AvmString::new(context.gc_context, length.to_string()),
                 arg.clone(),
                 EnumSet::empty(),
             );
@@ -578,7 +580,7 @@ fn sort_with_function<'gc>(
         &mut UpdateContext<'_, 'gc, '_>,
         &Value<'gc>,
         &Value<'gc>,
-    ) -> Ordering,
+    ) -> Result<Ordering, Error<'gc>>,
     flags: i32,
 ) -> Result<Value<'gc>, Error<'gc>> {
     let length = this.length();
@@ -589,23 +591,22 @@ fn sort_with_function<'gc>(
     let unique_sort = (flags & UNIQUE_SORT) != 0;
     let return_indexed_array = (flags & RETURN_INDEXED_ARRAY) != 0;
 
+    // If the compare function throws, the error is passed on and the array is left untouched.
     let mut is_unique = true;
-    values.sort_unstable_by(|a, b| {
-        let mut ret = compare_fn(activation, context, &a.1, &b.1);
+    merge_sort_by::<_, Error<'gc>>(&mut values, &mut |a, b| {
+        let mut ret = compare_fn(activation, context, &a.1, &b.1)?;
         if descending {
             ret = ret.reverse();
         }
         if ret == Ordering::Equal {
             is_unique = false;
         }
-        ret
-    });
+        Ok(ret)
+    })?;
 
+    // Array.UNIQUESORT returns 0 without modifying the array if there is a duplicated value.
     if unique_sort && !is_unique {
-        // Check for uniqueness. Return 0 if there is a duplicated value.
-        if !is_unique {
-            return Ok(0.into());
-        }
+        return Ok(0.into());
     }
 
     if return_indexed_array {
@@ -717,20 +718,51 @@ pub fn create_proto<'gc>(
     array.into()
 }
 
+/// Sort `values` using a comparison that may fail.
+///
+/// Compare functions written in ActionScript are often not a consistent
+/// ordering, which this stable merge sort tolerates. Sorting stops at the
+/// first error, which is returned.
+fn merge_sort_by<T, E>(
+    values: &mut Vec<T>,
+    compare: &mut impl FnMut(&T, &T) -> Result<Ordering, E>,
+) -> Result<(), E> {
+    if values.len() <= 1 {
+        return Ok(());
+    }
+
+    let mut right = values.split_off(values.len() / 2);
+    merge_sort_by(values, compare)?;
+    merge_sort_by(&mut right, compare)?;
+
+    let left = std::mem::take(values);
+    values.reserve(left.len() + right.len());
+
+    let mut left = left.into_iter().peekable();
+    let mut right = right.into_iter().peekable();
+    // Equal values keep their original order.
+    while let (Some(l), Some(r)) = (left.peek(), right.peek()) {
+        if compare(l, r)? == Ordering::Greater {
+            values.extend(right.next());
+        } else {
+            values.extend(left.next());
+        }
+    }
+    values.extend(left);
+    values.extend(right);
+
+    Ok(())
+}
+
 fn sort_compare_string<'gc>(
     activation: &mut Activation<'_, 'gc>,
     context: &mut UpdateContext<'_, 'gc, '_>,
     a: &Value<'gc>,
     b: &Value<'gc>,
-) -> Ordering {
-    let a_str = a.coerce_to_string(activation, context);
-    let b_str = b.coerce_to_string(activation, context);
-    // TODO: Handle errors.
-    if let (Ok(a_str), Ok(b_str)) = (a_str, b_str) {
-        a_str.cmp(&b_str)
-    } else {
-        DEFAULT_ORDERING
-    }
+) -> Result<Ordering, Error<'gc>> {
+    let a_str = a.coerce_to_string(activation, context)?;
+    let b_str = b.coerce_to_string(activation, context)?;
+    Ok(a_str.cmp(&b_str))
 }
 
 fn sort_compare_string_ignore_case<'gc>(
@@ -738,15 +770,12 @@ fn sort_compare_string_ignore_case<'gc>(
     context: &mut UpdateContext<'_, 'gc, '_>,
     a: &Value<'gc>,
     b: &Value<'gc>,
-) -> Ordering {
-    let a_str = a.coerce_to_string(activation, context);
-    let b_str = b.coerce_to_string(activation, context);
-    // TODO: Handle errors.
-    if let (Ok(a_str), Ok(b_str)) = (a_str, b_str) {
-        crate::string_utils::swf_string_cmp_ignore_case(&a_str, &b_str)
-    } else {
-        DEFAULT_ORDERING
-    }
+) -> Result<Ordering, Error<'gc>> {
+    let a_str = a.coerce_to_string(activation, context)?;
+    let b_str = b.coerce_to_string(activation, context)?;
+    Ok(crate::string_utils::swf_string_cmp_ignore_case(
+        &a_str, &b_str,
+    ))
 }
 
 fn sort_compare_numeric<'gc>(
@@ -755,16 +784,16 @@ fn sort_compare_numeric<'gc>(
         &mut UpdateContext<'_, 'gc, '_>,
         &Value<'gc>,
         &Value<'gc>,
-    ) -> Ordering,
+    ) -> Result<Ordering, Error<'gc>>,
 ) -> impl FnMut(
     &mut Activation<'_, 'gc>,
     &mut UpdateContext<'_, 'gc, '_>,
     &Value<'gc>,
     &Value<'gc>,
-) -> Ordering {
+) -> Result<Ordering, Error<'gc>> {
     move |activation, context, a, b| {
         if let (Value::Number(a), Value::Number(b)) = (a, b) {
-            a.partial_cmp(b).unwrap_or(DEFAULT_ORDERING)
+            Ok(a.partial_cmp(b).unwrap_or(DEFAULT_ORDERING))
         } else {
             string_compare_fn(activation, context, a, b)
         }
@@ -780,22 +809,22 @@ fn sort_compare_fields<'a, 'gc: 'a>(
     &mut UpdateContext<'_, 'gc, '_>,
     &Value<'gc>,
     &Value<'gc>,
-) -> Ordering {
+) -> Result<Ordering, Error<'gc>> {
     use crate::avm1::value_object::ValueObject;
     move |activation, context, a, b| {
         for (field_name, compare_fn) in field_names.iter().zip(compare_fns.iter_mut()) {
             let a_object = ValueObject::boxed(activation, context, a.clone());
             let b_object = ValueObject::boxed(activation, context, b.clone());
-            let a_prop = a_object.get(field_name, activation, context).unwrap();
-            let b_prop = b_object.get(field_name, activation, context).unwrap();
+            let a_prop = a_object.get(field_name, activation, context)?;
+            let b_prop = b_object.get(field_name, activation, context)?;
 
-            let result = compare_fn(activation, context, &a_prop, &b_prop);
+            let result = compare_fn(activation, context, &a_prop, &b_prop)?;
             if result != Ordering::Equal {
-                return result;
+                return Ok(result);
             }
         }
         // Got through all fields; must be equal.
-        Ordering::Equal
+        Ok(Ordering::Equal)
     }
 }
 
@@ -807,16 +836,18 @@ fn sort_compare_custom<'gc>(
     a: &Value<'gc>,
     b: &Value<'gc>,
     compare_fn: &Value<'gc>,
-) -> Ordering {
-    // TODO: Handle errors.
+) -> Result<Ordering, Error<'gc>> {
     let args = [a.clone(), b.clone()];
-    let ret = compare_fn
-        .call("[Compare]", activation, context, this, None, &args)
-        .unwrap_or(Value::Undefined);
-    match ret {
-        Value::Number(n) if n > 0.0 => Ordering::Greater,
-        Value::Number(n) if n < 0.0 => Ordering::Less,
-        Value::Number(n) if n == 0.0 => Ordering::Equal,
-        _ => DEFAULT_ORDERING,
-    }
+    let ret = compare_fn.call("[Compare]", activation, context, this, None, &args)?;
+
+    // The result is converted to a number; anything that isn't a positive or
+    // negative number, such as `undefined`, means the values are equal.
+    let ret = ret.coerce_to_f64(activation, context)?;
+    Ok(if ret > 0.0 {
+        Ordering::Greater
+    } else if ret < 0.0 {
+        Ordering::Less
+    } else {
+        DEFAULT_ORDERING
+    })
 }
@@ -0,0 +1,240 @@
+//! `AsBroadcaster` impl
+
+use crate::avm1::activation::Activation;
+use crate::avm1::error::Error;
+use crate::avm1::function::{Executable, FunctionObject};
+use crate::avm1::object::search_prototype;
+use crate::avm1::property::Attribute;
+use crate::avm1::{Object, ScriptObject, TObject, UpdateContext, Value};
+use gc_arena::{Collect, MutationContext};
+
+/// The methods that `AsBroadcaster.initialize` installs on an object.
+///
+/// Every broadcaster shares the same function objects, just like in Flash.
+#[derive(Clone, Collect, Debug, Copy)]
+#[collect(no_drop)]
+pub struct BroadcasterFunctions<'gc> {
+    add_listener: Object<'gc>,
+    remove_listener: Object<'gc>,
+    broadcast_message: Object<'gc>,
+}
+
+impl<'gc> BroadcasterFunctions<'gc> {
+    /// Turn an object into a broadcaster with an empty `_listeners` array.
+    ///
+    /// Returns the new `_listeners` array.
+    pub fn initialize(
+        self,
+        gc_context: MutationContext<'gc, '_>,
+        broadcaster: Object<'gc>,
+        array_proto: Object<'gc>,
+    ) -> Object<'gc> {
+        let listeners: Object<'gc> = ScriptObject::array(gc_context, Some(array_proto)).into();
+
+        broadcaster.define_value(
+            gc_context,
+            "_listeners",
+            listeners.into(),
+            Attribute::DontEnum.into(),
+        );
+        broadcaster.define_value(
+            gc_context,
+            "addListener",
+            self.add_listener.into(),
+            Attribute::DontEnum.into(),
+        );
+        broadcaster.define_value(
+            gc_context,
+            "removeListener",
+            self.remove_listener.into(),
+            Attribute::DontEnum.into(),
+        );
+        broadcaster.define_value(
+            gc_context,
+            "broadcastMessage",
+            self.broadcast_message.into(),
+            Attribute::DontEnum.into(),
+        );
+
+        listeners
+    }
+}
+
+pub fn initialize<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    _this: Object<'gc>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    if let Some(Value::Object(broadcaster)) = args.get(0) {
+        activation.avm.broadcaster_functions().initialize(
+            context.gc_context,
+            *broadcaster,
+            activation.avm.prototypes().array,
+        );
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Remove a listener from a listeners array, returning whether it was found.
+fn remove_from_listeners<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    listeners: Object<'gc>,
+    listener: &Value<'gc>,
+) -> bool {
+    let length = listeners.length();
+    let index = match (0..length).find(|&i| listeners.array_element(i) == *listener) {
+        Some(index) => index,
+        None => return false,
+    };
+
+    let new_length = length - 1;
+    for i in index..new_length {
+        listeners.set_array_element(i, listeners.array_element(i + 1), context.gc_context);
+    }
+
+    listeners.delete_array_element(new_length, context.gc_context);
+    listeners.delete(activation, context.gc_context, &new_length.to_string());
+    listeners.set_length(context.gc_context, new_length);
+
+    true
+}
+
+pub fn add_listener<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Object<'gc>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let listener = args.get(0).cloned().unwrap_or(Value::Undefined);
+
+    if let Value::Object(listeners) = this.get("_listeners", activation, context)? {
+        // Adding a listener twice moves it to the end of the list.
+        remove_from_listeners(activation, context, listeners, &listener);
+
+        let length = listeners.length();
+        listeners.set_length(context.gc_context, length + 1);
+        listeners.set_array_element(length, listener, context.gc_context);
+    }
+
+    Ok(true.into())
+}
+
+pub fn remove_listener<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Object<'gc>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let listener = args.get(0).cloned().unwrap_or(Value::Undefined);
+
+    if let Value::Object(listeners) = this.get("_listeners", activation, context)? {
+        return Ok(remove_from_listeners(activation, context, listeners, &listener).into());
+    }
+
+    Ok(false.into())
+}
+
+pub fn broadcast_message<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Object<'gc>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    if let Some(method) = args.get(0) {
+        let method = method.coerce_to_string(activation, context)?;
+        broadcast_internal(activation, context, this, &method, &args[1..])?;
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Call `method` on every listener of a broadcaster, in order.
+///
+/// The listeners are read before any of them is called, so handlers may
+/// freely add or remove listeners (including themselves); those changes only
+/// take effect on the next broadcast. Listeners without such a method are
+/// skipped. Returns whether the broadcaster had any listeners.
+pub fn broadcast_internal<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    broadcaster: Object<'gc>,
+    method: &str,
+    args: &[Value<'gc>],
+) -> Result<bool, Error<'gc>> {
+    let listeners = match broadcaster.get("_listeners", activation, context)? {
+        Value::Object(listeners) => listeners,
+        _ => return Ok(false),
+    };
+
+    let snapshot: Vec<Value<'gc>> = (0..listeners.length())
+        .map(|i| listeners.array_element(i))
+        .collect();
+
+    for listener in &snapshot {
+        if let Value::Object(listener) = listener {
+            let (handler, base_proto) =
+                search_prototype(Some(*listener), method, activation, context, *listener)?;
+            if let Value::Object(_) = handler {
+                handler.call(method, activation, context, *listener, base_proto, args)?;
+            }
+        }
+    }
+
+    Ok(!snapshot.is_empty())
+}
+
+pub fn create<'gc>(
+    gc_context: MutationContext<'gc, '_>,
+    proto: Option<Object<'gc>>,
+    fn_proto: Object<'gc>,
+) -> (BroadcasterFunctions<'gc>, Object<'gc>) {
+    let mut object = ScriptObject::object(gc_context, proto);
+
+    object.force_set_function(
+        "initialize",
+        initialize,
+        gc_context,
+        Attribute::DontEnum | Attribute::DontDelete,
+        Some(fn_proto),
+    );
+
+    let functions = BroadcasterFunctions {
+        add_listener: FunctionObject::function(
+            gc_context,
+            Executable::Native(add_listener),
+            Some(fn_proto),
+            None,
+        ),
+        remove_listener: FunctionObject::function(
+            gc_context,
+            Executable::Native(remove_listener),
+            Some(fn_proto),
+            None,
+        ),
+        broadcast_message: FunctionObject::function(
+            gc_context,
+            Executable::Native(broadcast_message),
+            Some(fn_proto),
+            None,
+        ),
+    };
+
+    // `AsBroadcaster` itself carries the same methods that `initialize` installs.
+    for (name, function) in &[
+        ("addListener", functions.add_listener),
+        ("removeListener", functions.remove_listener),
+        ("broadcastMessage", functions.broadcast_message),
+    ] {
+        object.define_value(
+            gc_context,
+            *name,
+            (*function).into(),
+            Attribute::DontEnum | Attribute::DontDelete,
+        );
+    }
+
+    (functions, object.into())
+}
@@ -0,0 +1,637 @@
+//! flash.display.BitmapData object
+
+use crate::avm1::activation::Activation;
+use crate::avm1::bitmap_data_object::BitmapDataObject;
+use crate::avm1::error::Error;
+use crate::avm1::function::{Executable, FunctionObject};
+use crate::avm1::globals::color_transform::object_to_color_transform;
+use crate::avm1::globals::display_object::value_to_blend_mode;
+use crate::avm1::globals::matrix::object_to_matrix;
+use crate::avm1::globals::rectangle::{construct_new_rectangle, object_to_rectangle};
+use crate::avm1::property::Attribute::*;
+use crate::avm1::{Object, TObject, Value};
+use crate::bitmap_data::{BitmapData, DrawOptions};
+use crate::bounding_box::BoundingBox;
+use crate::character::Character;
+use crate::color_transform::ColorTransform;
+use crate::context::{RenderContext, UpdateContext};
+use crate::display_object::{DisplayObject, TDisplayObject};
+use crate::transform::{Transform, TransformStack};
+use crate::Color;
+use gc_arena::{GcCell, MutationContext};
+use swf::{Matrix, Twips};
+
+/// The largest width or height of a `BitmapData` in SWF versions before 10.
+const MAX_DIMENSION: i32 = 2880;
+
+pub fn constructor<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Object<'gc>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let width = args
+        .get(0)
+        .unwrap_or(&Value::Undefined)
+        .coerce_to_i32(activation, context)?;
+    let height = args
+        .get(1)
+        .unwrap_or(&Value::Undefined)
+        .coerce_to_i32(activation, context)?;
+    let transparent = args
+        .get(2)
+        .map_or(true, |v| v.as_bool(activation.current_swf_version()));
+    let fill_color = args
+        .get(3)
+        .unwrap_or(&Value::Number(f64::from(0xFFFF_FFFFu32)))
+        .coerce_to_u32(activation, context)?;
+
+    if let Some(bitmap_data) = this.as_bitmap_data_object() {
+        let is_valid_size = |size: i32| size > 0 && size <= MAX_DIMENSION;
+        let data = if is_valid_size(width) && is_valid_size(height) {
+            BitmapData::new(width as u32, height as u32, transparent, fill_color)
+        } else {
+            // Flash creates an unusable bitmap when given invalid dimensions.
+            log::warn!("BitmapData: Invalid size {}x{}", width, height);
+            let mut data = BitmapData::new(0, 0, transparent, 0);
+            data.dispose();
+            data
+        };
+        bitmap_data.set_bitmap_data(context.gc_context, data);
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Returns the pixels of `this`, or `None` if it is not a `BitmapData` or has been disposed.
+fn live_bitmap_data<'gc>(this: Object<'gc>) -> Option<GcCell<'gc, BitmapData>> {
+    this.as_bitmap_data_object()
+        .map(|object| object.bitmap_data())
+        .filter(|data| !data.read().disposed())
+}
+
+/// Reads the `x`, `y`, `width` and `height` of a `Rectangle`, rounded to whole pixels.
+fn value_to_rect<'gc>(
+    value: Value<'gc>,
+    activation: &mut Activation<'_, 'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+) -> Result<(i32, i32, i32, i32), Error<'gc>> {
+    let object = value.coerce_to_object(activation, context);
+    let x = object
+        .get("x", activation, context)?
+        .coerce_to_i32(activation, context)?;
+    let y = object
+        .get("y", activation, context)?
+        .coerce_to_i32(activation, context)?;
+    let width = object
+        .get("width", activation, context)?
+        .coerce_to_i32(activation, context)?;
+    let height = object
+        .get("height", activation, context)?
+        .coerce_to_i32(activation, context)?;
+    Ok((x, y, width, height))
+}
+
+/// Reads the `x` and `y` of a `Point`, rounded to whole pixels.
+fn value_to_point<'gc>(
+    value: Value<'gc>,
+    activation: &mut Activation<'_, 'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+) -> Result<(i32, i32), Error<'gc>> {
+    let object = value.coerce_to_object(activation, context);
+    let x = object
+        .get("x", activation, context)?
+        .coerce_to_i32(activation, context)?;
+    let y = object
+        .get("y", activation, context)?
+        .coerce_to_i32(activation, context)?;
+    Ok((x, y))
+}
+
+fn coerce_arg_to_i32<'gc>(
+    args: &[Value<'gc>],
+    index: usize,
+    activation: &mut Activation<'_, 'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+) -> Result<i32, Error<'gc>> {
+    args.get(index)
+        .unwrap_or(&Value::Undefined)
+        .coerce_to_i32(activation, context)
+}
+
+pub fn get_width<'gc>(
+    _activation: &mut Activation<'_, 'gc>,
+    _context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Object<'gc>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    if let Some(data) = live_bitmap_data(this) {
+        return Ok(data.read().width().into());
+    }
+    Ok((-1).into())
+}
+
+pub fn get_height<'gc>(
+    _activation: &mut Activation<'_, 'gc>,
+    _context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Object<'gc>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    if let Some(data) = live_bitmap_data(this) {
+        return Ok(data.read().height().into());
+    }
+    Ok((-1).into())
+}
+
+pub fn get_transparent<'gc>(
+    _activation: &mut Activation<'_, 'gc>,
+    _context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Object<'gc>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    if let Some(data) = live_bitmap_data(this) {
+        return Ok(data.read().transparent().into());
+    }
+    Ok((-1).into())
+}
+
+pub fn get_rectangle<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Object<'gc>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    if let Some(data) = live_bitmap_data(this) {
+        let (width, height) = {
+            let data = data.read();
+            (data.width(), data.height())
+        };
+        let args = [0.into(), 0.into(), width.into(), height.into()];
+        return Ok(construct_new_rectangle(&args, activation, context)?.into());
+    }
+    Ok((-1).into())
+}
+
+pub fn get_pixel<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Object<'gc>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    if let Some(data) = live_bitmap_data(this) {
+        let x = coerce_arg_to_i32(args, 0, activation, context)?;
+        let y = coerce_arg_to_i32(args, 1, activation, context)?;
+        let pixel = data.read().get_pixel32(x, y).unwrap_or(0);
+        return Ok((pixel & 0x00FF_FFFF).into());
+    }
+    Ok((-1).into())
+}
+
+pub fn get_pixel32<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Object<'gc>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    if let Some(data) = live_bitmap_data(this) {
+        let x = coerce_arg_to_i32(args, 0, activation, context)?;
+        let y = coerce_arg_to_i32(args, 1, activation, context)?;
+        let pixel = data.read().get_pixel32(x, y).unwrap_or(0);
+        // AVM1 returns the ARGB value as a signed integer.
+        return Ok((pixel as i32).into());
+    }
+    Ok((-1).into())
+}
+
+pub fn set_pixel<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Object<'gc>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    if let Some(data) = live_bitmap_data(this) {
+        let x = coerce_arg_to_i32(args, 0, activation, context)?;
+        let y = coerce_arg_to_i32(args, 1, activation, context)?;
+        let color = args
+            .get(2)
+            .unwrap_or(&Value::Undefined)
+            .coerce_to_u32(activation, context)?;
+        data.write(context.gc_context).set_pixel(x, y, color);
+    }
+    Ok(Value::Undefined)
+}
+
+pub fn set_pixel32<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Object<'gc>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    if let Some(data) = live_bitmap_data(this) {
+        let x = coerce_arg_to_i32(args, 0, activation, context)?;
+        let y = coerce_arg_to_i32(args, 1, activation, context)?;
+        let color = args
+            .get(2)
+            .unwrap_or(&Value::Undefined)
+            .coerce_to_u32(activation, context)?;
+        data.write(context.gc_context).set_pixel32(x, y, color);
+    }
+    Ok(Value::Undefined)
+}
+
+pub fn fill_rect<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Object<'gc>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    if let Some(data) = live_bitmap_data(this) {
+        let rect = match args.get(0) {
+            Some(rect @ Value::Object(_)) => rect.to_owned(),
+            _ => return Ok(Value::Undefined),
+        };
+        let (x, y, width, height) = value_to_rect(rect, activation, context)?;
+        let color = args
+            .get(1)
+            .unwrap_or(&Value::Undefined)
+            .coerce_to_u32(activation, context)?;
+        data.write(context.gc_context)
+            .fill_rect(x, y, width, height, color);
+    }
+    Ok(Value::Undefined)
+}
+
+pub fn copy_pixels<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Object<'gc>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    if let Some(data) = live_bitmap_data(this) {
+        let source = match args.get(0) {
+            Some(Value::Object(source)) => live_bitmap_data(*source),
+            _ => None,
+        };
+        let (source, rect, point) = match (source, args.get(1), args.get(2)) {
+            (Some(source), Some(rect @ Value::Object(_)), Some(point @ Value::Object(_))) => {
+                (source, rect.to_owned(), point.to_owned())
+            }
+            _ => return Ok(Value::Undefined),
+        };
+        let rect = value_to_rect(rect, activation, context)?;
+        let point = value_to_point(point, activation, context)?;
+
+        if let Some(Value::Object(_)) = args.get(3) {
+            log::warn!("BitmapData.copyPixels: alphaBitmap is not yet implemented");
+        }
+        let merge_alpha = args
+            .get(5)
+            .map_or(false, |v| v.as_bool(activation.current_swf_version()));
+
+        if std::ptr::eq(source.as_ptr(), data.as_ptr()) {
+            let source = source.read().clone();
+            data.write(context.gc_context)
+                .copy_pixels(&source, rect, point, merge_alpha);
+        } else {
+            data.write(context.gc_context)
+                .copy_pixels(&source.read(), rect, point, merge_alpha);
+        }
+    }
+    Ok(Value::Undefined)
+}
+
+pub fn draw<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Object<'gc>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    if let Some(data) = live_bitmap_data(this) {
+        let source = match args.get(0) {
+            Some(Value::Object(source)) => *source,
+            _ => return Ok(Value::Undefined),
+        };
+        let matrix = match args.get(1) {
+            Some(Value::Object(matrix)) => object_to_matrix(*matrix, activation, context)?,
+            _ => Matrix::identity(),
+        };
+        let color_transform = match args.get(2) {
+            Some(Value::Object(color_transform)) => {
+                object_to_color_transform(*color_transform, activation, context)?
+            }
+            _ => ColorTransform::default(),
+        };
+        let clip_rect = match args.get(4) {
+            Some(Value::Object(clip_rect)) => {
+                let rect = object_to_rectangle(*clip_rect, activation, context)?;
+                Some((
+                    rect.x_min.to_pixels().round() as i32,
+                    rect.y_min.to_pixels().round() as i32,
+                    rect.width().to_pixels().round() as i32,
+                    rect.height().to_pixels().round() as i32,
+                ))
+            }
+            _ => None,
+        };
+        let options = DrawOptions {
+            blend_mode: value_to_blend_mode(args.get(3)).unwrap_or(swf::BlendMode::Normal),
+            clip_rect,
+            smoothing: args
+                .get(5)
+                .map_or(false, |v| v.as_bool(activation.current_swf_version())),
+        };
+
+        let mut sources = vec![];
+        if let Some(source) = live_bitmap_data(source) {
+            sources.push((source, matrix, color_transform));
+        } else if let Some(display_object) = source.as_display_object() {
+            let size = (data.read().width(), data.read().height());
+            match render_display_object(context, display_object, matrix, color_transform, size) {
+                Some(image) => {
+                    data.write(context.gc_context).draw(
+                        &image,
+                        &Matrix::identity(),
+                        &ColorTransform::default(),
+                        &options,
+                    );
+                }
+                // Without a renderer that can draw offscreen, only bitmaps can be drawn.
+                None => collect_bitmaps(display_object, matrix, color_transform, &mut sources),
+            }
+        }
+
+        for (source, matrix, color_transform) in sources {
+            if std::ptr::eq(source.as_ptr(), data.as_ptr()) {
+                let source = source.read().clone();
+                data.write(context.gc_context)
+                    .draw(&source, &matrix, &color_transform, &options);
+            } else {
+                data.write(context.gc_context).draw(
+                    &source.read(),
+                    &matrix,
+                    &color_transform,
+                    &options,
+                );
+            }
+        }
+    }
+    Ok(Value::Undefined)
+}
+
+/// Renders a display object into a transparent image of the given size with the renderer,
+/// or returns `None` if the renderer can't render offscreen.
+///
+/// The object is drawn in its own coordinate space, transformed by the given matrix and
+/// color transform.
+fn render_display_object<'gc>(
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    display_object: DisplayObject<'gc>,
+    matrix: Matrix,
+    color_transform: ColorTransform,
+    (width, height): (u32, u32),
+) -> Option<BitmapData> {
+    let transparent = Color {
+        r: 0,
+        g: 0,
+        b: 0,
+        a: 0,
+    };
+    if width == 0 || height == 0 || !context.renderer.begin_capture(width, height, transparent) {
+        return None;
+    }
+
+    // The object applies its own matrix when it renders, so undo it.
+    let mut to_local = *display_object.matrix();
+    to_local.invert();
+    let mut transform_stack = TransformStack::new();
+    transform_stack.push(&Transform {
+        matrix: matrix * to_local,
+        color_transform,
+    });
+
+    // Culling is done in stage space, so the image is mapped back onto the stage.
+    let mut to_object = matrix;
+    to_object.invert();
+    let image_bounds = BoundingBox {
+        x_min: Twips::new(0),
+        y_min: Twips::new(0),
+        x_max: Twips::from_pixels(width.into()),
+        y_max: Twips::from_pixels(height.into()),
+        valid: true,
+    };
+
+    let mut render_context = RenderContext {
+        renderer: &mut *context.renderer,
+        library: &*context.library,
+        transform_stack: &mut transform_stack,
+        view_bounds: image_bounds.transform(&(display_object.local_to_global_matrix() * to_object)),
+        clip_depth_stack: vec![],
+    };
+    display_object.render(&mut render_context);
+
+    let image = context.renderer.end_capture()?;
+    Some(BitmapData::from_bitmap(image, false))
+}
+
+/// Finds the `BitmapData` pixels displayed by a display object and its children, along with
+/// the transforms to draw them with. This is how display objects are drawn when the renderer
+/// can't render offscreen.
+fn collect_bitmaps<'gc>(
+    display_object: DisplayObject<'gc>,
+    matrix: Matrix,
+    color_transform: ColorTransform,
+    sources: &mut Vec<(GcCell<'gc, BitmapData>, Matrix, ColorTransform)>,
+) {
+    if !display_object.visible() {
+        return;
+    }
+
+    if let Some(bitmap_data) = display_object
+        .as_bitmap()
+        .and_then(|bitmap| bitmap.bitmap_data())
+    {
+        if !bitmap_data.read().disposed() {
+            sources.push((bitmap_data, matrix, color_transform));
+        }
+    } else if display_object.first_child().is_none() {
+        log::warn!(
+            "BitmapData.draw: Drawing vector content is not yet implemented ({})",
+            display_object.id()
+        );
+    }
+
+    for child in display_object.children() {
+        collect_bitmaps(
+            child,
+            matrix * *child.matrix(),
+            color_transform * *child.color_transform(),
+            sources,
+        );
+    }
+}
+
+pub fn dispose<'gc>(
+    _activation: &mut Activation<'_, 'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Object<'gc>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    if let Some(data) = live_bitmap_data(this) {
+        data.write(context.gc_context).dispose();
+    }
+    Ok(Value::Undefined)
+}
+
+pub fn clone<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Object<'gc>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    if let Some(data) = live_bitmap_data(this) {
+        let copy = data.read().duplicate();
+        return Ok(BitmapDataObject::with_bitmap_data(
+            context.gc_context,
+            Some(activation.avm.prototypes.bitmap_data),
+            copy,
+        )
+        .into());
+    }
+    Ok((-1).into())
+}
+
+/// Implements the static `BitmapData.loadBitmap`, which decodes a bitmap from the library.
+pub fn load_bitmap<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    _this: Object<'gc>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let export_name = args
+        .get(0)
+        .unwrap_or(&Value::Undefined)
+        .coerce_to_string(activation, context)?;
+
+    let bitmap = activation
+        .base_clip()
+        .movie()
+        .and_then(|movie| context.library.library_for_movie(movie))
+        .and_then(|library| library.get_character_by_export_name(&export_name))
+        .and_then(|character| match character {
+            Character::Bitmap(bitmap) => Some(*bitmap),
+            _ => None,
+        });
+    let bitmap = match bitmap {
+        Some(bitmap) => bitmap,
+        None => {
+            log::warn!(
+                "BitmapData.loadBitmap: No bitmap exported as '{}'",
+                export_name
+            );
+            return Ok(Value::Undefined);
+        }
+    };
+
+    match bitmap.decode_bitmap_data() {
+        Ok(data) => Ok(BitmapDataObject::with_bitmap_data(
+            context.gc_context,
+            Some(activation.avm.prototypes.bitmap_data),
+            data,
+        )
+        .into()),
+        Err(e) => {
+            log::error!(
+                "BitmapData.loadBitmap: Unable to decode '{}': {}",
+                export_name,
+                e
+            );
+            Ok(Value::Undefined)
+        }
+    }
+}
+
+pub fn create_bitmap_data_object<'gc>(
+    gc_context: MutationContext<'gc, '_>,
+    bitmap_data_proto: Option<Object<'gc>>,
+    fn_proto: Option<Object<'gc>>,
+) -> Object<'gc> {
+    let bitmap_data = FunctionObject::function(
+        gc_context,
+        Executable::Native(constructor),
+        fn_proto,
+        bitmap_data_proto,
+    );
+    let mut object = bitmap_data.as_script_object().unwrap();
+
+    object.force_set_function("loadBitmap", load_bitmap, gc_context, DontEnum, fn_proto);
+
+    bitmap_data
+}
+
+pub fn create_proto<'gc>(
+    gc_context: MutationContext<'gc, '_>,
+    proto: Object<'gc>,
+    fn_proto: Object<'gc>,
+) -> Object<'gc> {
+    let bitmap_data_object = BitmapDataObject::empty_object(gc_context, Some(proto));
+    let mut object = bitmap_data_object.as_script_object().unwrap();
+
+    object.add_property(
+        gc_context,
+        "width",
+        Executable::Native(get_width),
+        None,
+        DontDelete | ReadOnly | DontEnum,
+    );
+    object.add_property(
+        gc_context,
+        "height",
+        Executable::Native(get_height),
+        None,
+        DontDelete | ReadOnly | DontEnum,
+    );
+    object.add_property(
+        gc_context,
+        "transparent",
+        Executable::Native(get_transparent),
+        None,
+        DontDelete | ReadOnly | DontEnum,
+    );
+    object.add_property(
+        gc_context,
+        "rectangle",
+        Executable::Native(get_rectangle),
+        None,
+        DontDelete | ReadOnly | DontEnum,
+    );
+
+    object.force_set_function("getPixel", get_pixel, gc_context, DontEnum, Some(fn_proto));
+    object.force_set_function(
+        "getPixel32",
+        get_pixel32,
+        gc_context,
+        DontEnum,
+        Some(fn_proto),
+    );
+    object.force_set_function("setPixel", set_pixel, gc_context, DontEnum, Some(fn_proto));
+    object.force_set_function(
+        "setPixel32",
+        set_pixel32,
+        gc_context,
+        DontEnum,
+        Some(fn_proto),
+    );
+    object.force_set_function("fillRect", fill_rect, gc_context, DontEnum, Some(fn_proto));
+    object.force_set_function(
+        "copyPixels",
+        copy_pixels,
+        gc_context,
+        DontEnum,
+        Some(fn_proto),
+    );
+    object.force_set_function("draw", draw, gc_context, DontEnum, Some(fn_proto));
+    object.force_set_function("dispose", dispose, gc_context, DontEnum, Some(fn_proto));
+    object.force_set_function("clone", clone, gc_context, DontEnum, Some(fn_proto));
+
+    bitmap_data_object.into()
+}
@@ -0,0 +1,325 @@
+//! flash.filters.BitmapFilter and its subclasses
+
+use crate::avm1::activation::Activation;
+use crate::avm1::error::Error;
+use crate::avm1::function::{Executable, FunctionObject, NativeFunction};
+use crate::avm1::{Object, ScriptObject, TObject, Value};
+use crate::context::UpdateContext;
+use enumset::EnumSet;
+use gc_arena::MutationContext;
+use swf::{BlurFilter, Color, DropShadowFilter, Filter, GlowFilter};
+
+/// Reads the numeric argument at `index`, or returns `default` if it is missing.
+fn arg_f64<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    args: &[Value<'gc>],
+    index: usize,
+    default: f64,
+) -> Result<f64, Error<'gc>> {
+    match args.get(index) {
+        Some(value) => value.coerce_to_f64(activation, context),
+        None => Ok(default),
+    }
+}
+
+/// Reads the boolean argument at `index`, or returns `false` if it is missing.
+fn arg_bool<'gc>(activation: &Activation<'_, 'gc>, args: &[Value<'gc>], index: usize) -> bool {
+    args.get(index)
+        .map(|v| v.as_bool(activation.current_swf_version()))
+        .unwrap_or(false)
+}
+
+/// Reads the numeric property `name` of `object`.
+fn get_f64<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    object: Object<'gc>,
+    name: &str,
+) -> Result<f64, Error<'gc>> {
+    object
+        .get(name, activation, context)?
+        .coerce_to_f64(activation, context)
+}
+
+/// Reads the boolean property `name` of `object`.
+fn get_bool<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    object: Object<'gc>,
+    name: &str,
+) -> Result<bool, Error<'gc>> {
+    Ok(object
+        .get(name, activation, context)?
+        .as_bool(activation.current_swf_version()))
+}
+
+/// Reads the `color` and `alpha` properties of `object` as a single color.
+fn get_color<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    object: Object<'gc>,
+) -> Result<Color, Error<'gc>> {
+    let rgb = object
+        .get("color", activation, context)?
+        .coerce_to_u32(activation, context)?;
+    let alpha = get_f64(activation, context, object, "alpha")?;
+    Ok(Color::from_rgb(
+        rgb,
+        (alpha.min(1.0).max(0.0) * 255.0) as u8,
+    ))
+}
+
+fn color_to_rgb(color: &Color) -> u32 {
+    (u32::from(color.r) << 16) | (u32::from(color.g) << 8) | u32::from(color.b)
+}
+
+fn quality_to_passes(quality: f64) -> u8 {
+    quality.min(15.0).max(0.0) as u8
+}
+
+/// Sets the properties of a filter object to match the given filter.
+fn set_filter_properties<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    object: Object<'gc>,
+    filter: &Filter,
+) -> Result<(), Error<'gc>> {
+    match filter {
+        Filter::BlurFilter(blur) => {
+            object.set("blurX", blur.blur_x.into(), activation, context)?;
+            object.set("blurY", blur.blur_y.into(), activation, context)?;
+            object.set("quality", blur.num_passes.into(), activation, context)?;
+        }
+        Filter::GlowFilter(glow) => {
+            object.set(
+                "color",
+                color_to_rgb(&glow.color).into(),
+                activation,
+                context,
+            )?;
+            let alpha = f64::from(glow.color.a) / 255.0;
+            object.set("alpha", alpha.into(), activation, context)?;
+            object.set("blurX", glow.blur_x.into(), activation, context)?;
+            object.set("blurY", glow.blur_y.into(), activation, context)?;
+            object.set("strength", glow.strength.into(), activation, context)?;
+            object.set("quality", glow.num_passes.into(), activation, context)?;
+            object.set("inner", glow.is_inner.into(), activation, context)?;
+            object.set("knockout", glow.is_knockout.into(), activation, context)?;
+        }
+        Filter::DropShadowFilter(shadow) => {
+            object.set("distance", shadow.distance.into(), activation, context)?;
+            let angle = shadow.angle.to_degrees();
+            object.set("angle", angle.into(), activation, context)?;
+            object.set(
+                "color",
+                color_to_rgb(&shadow.color).into(),
+                activation,
+                context,
+            )?;
+            let alpha = f64::from(shadow.color.a) / 255.0;
+            object.set("alpha", alpha.into(), activation, context)?;
+            object.set("blurX", shadow.blur_x.into(), activation, context)?;
+            object.set("blurY", shadow.blur_y.into(), activation, context)?;
+            object.set("strength", shadow.strength.into(), activation, context)?;
+            object.set("quality", shadow.num_passes.into(), activation, context)?;
+            object.set("inner", shadow.is_inner.into(), activation, context)?;
+            object.set("knockout", shadow.is_knockout.into(), activation, context)?;
+        }
+        _ => log::warn!("Unsupported filter {:?}", filter),
+    }
+    Ok(())
+}
+
+/// Implements `flash.filters.BlurFilter`
+pub fn blur_filter_constructor<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Object<'gc>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let filter = Filter::BlurFilter(Box::new(BlurFilter {
+        blur_x: arg_f64(activation, context, args, 0, 4.0)?,
+        blur_y: arg_f64(activation, context, args, 1, 4.0)?,
+        num_passes: quality_to_passes(arg_f64(activation, context, args, 2, 1.0)?),
+    }));
+    set_filter_properties(activation, context, this, &filter)?;
+    Ok(Value::Undefined)
+}
+
+/// Implements `flash.filters.GlowFilter`
+pub fn glow_filter_constructor<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Object<'gc>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let rgb = match args.get(0) {
+        Some(color) => color.coerce_to_u32(activation, context)?,
+        None => 0xFF0000,
+    };
+    let alpha = arg_f64(activation, context, args, 1, 1.0)?;
+    let filter = Filter::GlowFilter(Box::new(GlowFilter {
+        color: Color::from_rgb(rgb, (alpha.min(1.0).max(0.0) * 255.0) as u8),
+        blur_x: arg_f64(activation, context, args, 2, 6.0)?,
+        blur_y: arg_f64(activation, context, args, 3, 6.0)?,
+        strength: arg_f64(activation, context, args, 4, 2.0)? as f32,
+        num_passes: quality_to_passes(arg_f64(activation, context, args, 5, 1.0)?),
+        is_inner: arg_bool(activation, args, 6),
+        is_knockout: arg_bool(activation, args, 7),
+    }));
+    set_filter_properties(activation, context, this, &filter)?;
+    Ok(Value::Undefined)
+}
+
+/// Implements `flash.filters.DropShadowFilter`
+pub fn drop_shadow_filter_constructor<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Object<'gc>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let distance = arg_f64(activation, context, args, 0, 4.0)?;
+    let angle = arg_f64(activation, context, args, 1, 45.0)?;
+    let rgb = match args.get(2) {
+        Some(color) => color.coerce_to_u32(activation, context)?,
+        None => 0x000000,
+    };
+    let alpha = arg_f64(activation, context, args, 3, 1.0)?;
+    let filter = Filter::DropShadowFilter(Box::new(DropShadowFilter {
+        color: Color::from_rgb(rgb, (alpha.min(1.0).max(0.0) * 255.0) as u8),
+        blur_x: arg_f64(activation, context, args, 4, 4.0)?,
+        blur_y: arg_f64(activation, context, args, 5, 4.0)?,
+        angle: angle.to_radians(),
+        distance,
+        strength: arg_f64(activation, context, args, 6, 1.0)? as f32,
+        is_inner: arg_bool(activation, args, 8),
+        is_knockout: arg_bool(activation, args, 9),
+        num_passes: quality_to_passes(arg_f64(activation, context, args, 7, 1.0)?),
+    }));
+    set_filter_properties(activation, context, this, &filter)?;
+
+    // TODO: `hideObject` is not stored by `swf::DropShadowFilter`, so it isn't rendered.
+    let hide_object = arg_bool(activation, args, 10);
+    this.set("hideObject", hide_object.into(), activation, context)?;
+    Ok(Value::Undefined)
+}
+
+/// Converts a filter object into the filter it describes.
+///
+/// Returns `None` if the object is not a filter.
+pub fn object_to_filter<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    object: Object<'gc>,
+) -> Result<Option<Filter>, Error<'gc>> {
+    let prototypes = context.system_prototypes.clone();
+    let filter = if prototypes.blur_filter.is_prototype_of(object) {
+        Filter::BlurFilter(Box::new(BlurFilter {
+            blur_x: get_f64(activation, context, object, "blurX")?,
+            blur_y: get_f64(activation, context, object, "blurY")?,
+            num_passes: quality_to_passes(get_f64(activation, context, object, "quality")?),
+        }))
+    } else if prototypes.glow_filter.is_prototype_of(object) {
+        Filter::GlowFilter(Box::new(GlowFilter {
+            color: get_color(activation, context, object)?,
+            blur_x: get_f64(activation, context, object, "blurX")?,
+            blur_y: get_f64(activation, context, object, "blurY")?,
+            strength: get_f64(activation, context, object, "strength")? as f32,
+            num_passes: quality_to_passes(get_f64(activation, context, object, "quality")?),
+            is_inner: get_bool(activation, context, object, "inner")?,
+            is_knockout: get_bool(activation, context, object, "knockout")?,
+        }))
+    } else if prototypes.drop_shadow_filter.is_prototype_of(object) {
+        Filter::DropShadowFilter(Box::new(DropShadowFilter {
+            color: get_color(activation, context, object)?,
+            blur_x: get_f64(activation, context, object, "blurX")?,
+            blur_y: get_f64(activation, context, object, "blurY")?,
+            angle: get_f64(activation, context, object, "angle")?.to_radians(),
+            distance: get_f64(activation, context, object, "distance")?,
+            strength: get_f64(activation, context, object, "strength")? as f32,
+            is_inner: get_bool(activation, context, object, "inner")?,
+            is_knockout: get_bool(activation, context, object, "knockout")?,
+            num_passes: quality_to_passes(get_f64(activation, context, object, "quality")?),
+        }))
+    } else {
+        return Ok(None);
+    };
+    Ok(Some(filter))
+}
+
+/// Creates a new filter object describing the given filter.
+///
+/// Returns `None` for filters that are not yet supported in AVM1.
+pub fn filter_to_object<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    filter: &Filter,
+) -> Result<Option<Object<'gc>>, Error<'gc>> {
+    let proto = match filter {
+        Filter::BlurFilter(_) => context.system_prototypes.blur_filter,
+        Filter::GlowFilter(_) => context.system_prototypes.glow_filter,
+        Filter::DropShadowFilter(_) => context.system_prototypes.drop_shadow_filter,
+        _ => return Ok(None),
+    };
+    let object = proto.new(activation, context, proto, &[])?;
+    set_filter_properties(activation, context, object, filter)?;
+    if let Filter::DropShadowFilter(_) = filter {
+        object.set("hideObject", false.into(), activation, context)?;
+    }
+    Ok(Some(object))
+}
+
+fn clone<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Object<'gc>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    if let Some(filter) = object_to_filter(activation, context, this)? {
+        if let Some(cloned) = filter_to_object(activation, context, &filter)? {
+            if let Filter::DropShadowFilter(_) = filter {
+                let hide_object = this.get("hideObject", activation, context)?;
+                cloned.set("hideObject", hide_object, activation, context)?;
+            }
+            return Ok(cloned.into());
+        }
+    }
+    Ok(Value::Undefined)
+}
+
+/// Creates the `BitmapFilter` prototype shared by all filters.
+pub fn create_proto<'gc>(
+    gc_context: MutationContext<'gc, '_>,
+    proto: Object<'gc>,
+    fn_proto: Object<'gc>,
+) -> Object<'gc> {
+    let mut object = ScriptObject::object(gc_context, Some(proto));
+
+    object.force_set_function("clone", clone, gc_context, EnumSet::empty(), Some(fn_proto));
+
+    object.into()
+}
+
+/// Creates the prototype of a specific filter class, such as `BlurFilter`.
+pub fn create_filter_proto<'gc>(
+    gc_context: MutationContext<'gc, '_>,
+    bitmap_filter_proto: Object<'gc>,
+) -> Object<'gc> {
+    ScriptObject::object(gc_context, Some(bitmap_filter_proto)).into()
+}
+
+/// Creates a filter class constructor with the given native constructor.
+pub fn create_filter_object<'gc>(
+    gc_context: MutationContext<'gc, '_>,
+    constructor: NativeFunction<'gc>,
+    filter_proto: Object<'gc>,
+    fn_proto: Object<'gc>,
+) -> Object<'gc> {
+    FunctionObject::function(
+        gc_context,
+        Executable::Native(constructor),
+        Some(fn_proto),
+        Some(filter_proto),
+    )
+}
@@ -83,7 +83,7 @@ pub fn to_string<'gc>(
         // Must be a bool.
         // Boolean.prototype.toString.call(x) returns undefined for non-bools.
         if let Value::Bool(b) = vbox.unbox() {
-            return Ok(b.to_string().into());
+            return Ok((if b { "true" } else { "false" }).into());
         }
     }
 
@@ -2,8 +2,11 @@
 
 use crate::avm1::activation::Activation;
 use crate::avm1::error::Error;
+use crate::avm1::function::Executable;
 use crate::avm1::globals::display_object;
-use crate::avm1::{Object, ScriptObject, UpdateContext, Value};
+use crate::avm1::property::Attribute::*;
+use crate::avm1::{Object, ScriptObject, TObject, UpdateContext, Value};
+use crate::display_object::TDisplayObject;
 use gc_arena::MutationContext;
 
 pub fn create_proto<'gc>(
@@ -15,6 +18,18 @@ pub fn create_proto<'gc>(
 
     display_object::define_display_object_proto(gc_context, object, fn_proto);
 
+    object.add_property(
+        gc_context,
+        "trackAsMenu",
+        Executable::Native(track_as_menu),
+        Some(Executable::Native(set_track_as_menu)),
+        DontDelete | DontEnum,
+    );
+
+    // `tabEnabled` is left undefined, which includes the button in the tab order.
+    object.define_value(gc_context, "useHandCursor", true.into(), DontEnum.into());
+    object.define_value(gc_context, "enabled", true.into(), DontEnum.into());
+
     object.into()
 }
 
@@ -27,3 +42,31 @@ pub fn constructor<'gc>(
 ) -> Result<Value<'gc>, Error<'gc>> {
     Ok(Value::Undefined)
 }
+
+pub fn track_as_menu<'gc>(
+    _activation: &mut Activation<'_, 'gc>,
+    _context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Object<'gc>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    if let Some(button) = this.as_display_object().and_then(|o| o.as_button()) {
+        return Ok(button.is_track_as_menu().into());
+    }
+    Ok(Value::Undefined)
+}
+
+pub fn set_track_as_menu<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Object<'gc>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    if let Some(button) = this.as_display_object().and_then(|o| o.as_button()) {
+        let value = args
+            .get(0)
+            .unwrap_or(&Value::Undefined)
+            .as_bool(activation.current_swf_version());
+        button.set_track_as_menu(context.gc_context, value);
+    }
+    Ok(Value::Undefined)
+}
@@ -86,7 +86,9 @@ fn target<'gc>(
     // Undefined or empty target is no-op.
     if target != Value::Undefined && !matches!(&target, &Value::String(ref s) if s.is_empty()) {
         let start_clip = activation.target_clip_or_root();
-        activation.resolve_target_display_object(context, start_clip, target)
+        let target = activation.resolve_target_display_object(context, start_clip, target)?;
+        // Clips that have been removed from the stage can no longer be colored.
+        Ok(target.filter(|target| !target.removed()))
     } else {
         Ok(None)
     }
@@ -178,12 +180,12 @@ fn set_rgb<'gc>(
     this: Object<'gc>,
     args: &[Value<'gc>],
 ) -> Result<Value<'gc>, Error<'gc>> {
-    if let Some(target) = target(activation, context, this)? {
-        let mut color_transform = target.color_transform_mut(context.gc_context);
+    if let Some(mut target) = target(activation, context, this)? {
         let rgb = args
             .get(0)
             .unwrap_or(&Value::Undefined)
             .coerce_to_f64(activation, context)? as i32;
+        let mut color_transform = *target.color_transform();
         let r = (((rgb >> 16) & 0xff) as f32) / 255.0;
         let g = (((rgb >> 8) & 0xff) as f32) / 255.0;
         let b = ((rgb & 0xff) as f32) / 255.0;
@@ -194,6 +196,8 @@ fn set_rgb<'gc>(
         color_transform.r_add = r;
         color_transform.g_add = g;
         color_transform.b_add = b;
+        target.set_color_transform(context.gc_context, &color_transform);
+        target.invalidate_cached_bitmap();
     }
     Ok(Value::Undefined)
 }
@@ -241,8 +245,10 @@ fn set_transform<'gc>(
         Ok(())
     }
 
-    if let Some(target) = target(activation, context, this)? {
-        let mut color_transform = target.color_transform_mut(context.gc_context);
+    if let Some(mut target) = target(activation, context, this)? {
+        // Copy the color transform so that property getters on the transform object
+        // can't observe it while it is being modified.
+        let mut color_transform = *target.color_transform();
         let transform = args
             .get(0)
             .unwrap_or(&Value::Undefined)
@@ -303,6 +309,8 @@ fn set_transform<'gc>(
             "ab",
             &mut color_transform.a_add,
         )?;
+        target.set_color_transform(context.gc_context, &color_transform);
+        target.invalidate_cached_bitmap();
     }
 
     Ok(Value::Undefined)
@@ -3,7 +3,7 @@
 use crate::avm1::activation::Activation;
 use crate::avm1::error::Error;
 use crate::avm1::function::Executable;
-use crate::avm1::{Object, TObject, Value};
+use crate::avm1::{AvmString, Object, TObject, Value};
 use crate::context::UpdateContext;
 use enumset::EnumSet;
 use gc_arena::MutationContext;
@@ -78,8 +78,28 @@ pub fn constructor<'gc>(
     Ok(Value::Undefined)
 }
 
-// We'll need this soon!
-#[allow(dead_code)]
+/// Creates a `ColorTransform` object with the values of the given color transform.
+pub fn color_transform_to_object<'gc>(
+    color_transform: &ColorTransform,
+    activation: &mut Activation<'_, 'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+) -> Result<Object<'gc>, Error<'gc>> {
+    let args = [
+        color_transform.r_mult.into(),
+        color_transform.g_mult.into(),
+        color_transform.b_mult.into(),
+        color_transform.a_mult.into(),
+        (color_transform.r_add * 255.0).into(),
+        (color_transform.g_add * 255.0).into(),
+        (color_transform.b_add * 255.0).into(),
+        (color_transform.a_add * 255.0).into(),
+    ];
+    let proto = context.system_prototypes.color_transform;
+    let object = proto.new(activation, context, proto, &args)?;
+    let _ = constructor(activation, context, object, &args)?;
+    Ok(object)
+}
+
 pub fn object_to_color_transform<'gc>(
     object: Object<'gc>,
     activation: &mut Activation<'_, 'gc>,
@@ -99,16 +119,20 @@ pub fn object_to_color_transform<'gc>(
         .coerce_to_f64(activation, context)? as f32;
     let red_offset = object
         .get("redOffset", activation, context)?
-        .coerce_to_f64(activation, context)? as f32;
+        .coerce_to_f64(activation, context)? as f32
+        / 255.0;
     let green_offset = object
         .get("greenOffset", activation, context)?
-        .coerce_to_f64(activation, context)? as f32;
+        .coerce_to_f64(activation, context)? as f32
+        / 255.0;
     let blue_offset = object
         .get("blueOffset", activation, context)?
-        .coerce_to_f64(activation, context)? as f32;
+        .coerce_to_f64(activation, context)? as f32
+        / 255.0;
     let alpha_offset = object
         .get("alphaOffset", activation, context)?
-        .coerce_to_f64(activation, context)? as f32;
+        .coerce_to_f64(activation, context)? as f32
+        / 255.0;
 
     Ok(ColorTransform {
         r_mult: red_multiplier,
@@ -264,7 +288,7 @@ fn to_string<'gc>(
             this.get("alphaOffset", activation, context)?.coerce_to_string(activation, context)?
     );
 
-    Ok(Value::String(formatted))
+    Ok(AvmString::new(context.gc_context, formatted).into())
 }
 
 fn concat<'gc>(
@@ -0,0 +1,121 @@
+//! AVM1 ContextMenu object
+
+use crate::avm1::activation::Activation;
+use crate::avm1::error::Error;
+use crate::avm1::script_object::ScriptObject;
+use crate::avm1::{Object, TObject, UpdateContext, Value};
+use enumset::EnumSet;
+use gc_arena::MutationContext;
+
+/// The names of the `builtInItems` of a menu, which show or hide the items of
+/// the Flash Player's own menu.
+pub const BUILT_IN_ITEMS: &[&str] = &[
+    "print",
+    "forward_back",
+    "rewind",
+    "loop",
+    "play",
+    "quality",
+    "zoom",
+    "save",
+];
+
+/// `new ContextMenu(callbackFunction)`
+pub fn constructor<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Object<'gc>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let callback = args.get(0).cloned().unwrap_or(Value::Undefined);
+    this.set("onSelect", callback, activation, context)?;
+
+    let built_in_items =
+        ScriptObject::object(context.gc_context, Some(activation.avm.prototypes.object));
+    for name in BUILT_IN_ITEMS {
+        built_in_items.set(*name, true.into(), activation, context)?;
+    }
+    this.set("builtInItems", built_in_items.into(), activation, context)?;
+
+    let custom_items =
+        ScriptObject::array(context.gc_context, Some(activation.avm.prototypes.array));
+    this.set("customItems", custom_items.into(), activation, context)?;
+
+    Ok(Value::Undefined)
+}
+
+pub fn hide_built_in_items<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Object<'gc>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    if let Value::Object(built_in_items) = this.get("builtInItems", activation, context)? {
+        for name in BUILT_IN_ITEMS {
+            built_in_items.set(*name, false.into(), activation, context)?;
+        }
+    }
+    Ok(Value::Undefined)
+}
+
+/// Creates a new menu with the same callback, built-in items and copies of the custom items.
+pub fn copy<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Object<'gc>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let callback = this.get("onSelect", activation, context)?;
+    let copy: Object<'gc> = ScriptObject::object(context.gc_context, this.proto()).into();
+    constructor(activation, context, copy, &[callback])?;
+
+    if let (Value::Object(built_in_items), Value::Object(copied_built_in_items)) = (
+        this.get("builtInItems", activation, context)?,
+        copy.get("builtInItems", activation, context)?,
+    ) {
+        for name in BUILT_IN_ITEMS {
+            let value = built_in_items.get(name, activation, context)?;
+            copied_built_in_items.set(*name, value, activation, context)?;
+        }
+    }
+
+    if let (Value::Object(custom_items), Value::Object(copied_custom_items)) = (
+        this.get("customItems", activation, context)?,
+        copy.get("customItems", activation, context)?,
+    ) {
+        for i in 0..custom_items.length() {
+            let item = match custom_items.array_element(i) {
+                Value::Object(item) => item.call_method("copy", &[], activation, context)?,
+                item => item,
+            };
+            copied_custom_items.set_array_element(i, item, context.gc_context);
+        }
+    }
+
+    Ok(copy.into())
+}
+
+pub fn create_proto<'gc>(
+    gc_context: MutationContext<'gc, '_>,
+    proto: Object<'gc>,
+    fn_proto: Object<'gc>,
+) -> Object<'gc> {
+    let mut context_menu_proto = ScriptObject::object(gc_context, Some(proto));
+
+    context_menu_proto.force_set_function(
+        "hideBuiltInItems",
+        hide_built_in_items,
+        gc_context,
+        EnumSet::empty(),
+        Some(fn_proto),
+    );
+    context_menu_proto.force_set_function(
+        "copy",
+        copy,
+        gc_context,
+        EnumSet::empty(),
+        Some(fn_proto),
+    );
+
+    context_menu_proto.into()
+}
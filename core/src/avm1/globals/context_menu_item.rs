@@ -0,0 +1,85 @@
+//! AVM1 ContextMenuItem object
+
+use crate::avm1::activation::Activation;
+use crate::avm1::error::Error;
+use crate::avm1::script_object::ScriptObject;
+use crate::avm1::{AvmString, Object, TObject, UpdateContext, Value};
+use enumset::EnumSet;
+use gc_arena::MutationContext;
+
+/// `new ContextMenuItem(caption, callbackFunction, separatorBefore, enabled, visible)`
+pub fn constructor<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Object<'gc>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let caption = args
+        .get(0)
+        .unwrap_or(&Value::Undefined)
+        .coerce_to_string(activation, context)?;
+    let caption = AvmString::new(context.gc_context, caption);
+    let callback = args.get(1).cloned().unwrap_or(Value::Undefined);
+    let swf_version = activation.current_swf_version();
+    let flag = |index: usize, default: bool| {
+        args.get(index)
+            .map_or(default, |value| value.as_bool(swf_version))
+    };
+    let separator_before = flag(2, false);
+    let enabled = flag(3, true);
+    let visible = flag(4, true);
+
+    this.set("caption", caption.into(), activation, context)?;
+    this.set("onSelect", callback, activation, context)?;
+    this.set(
+        "separatorBefore",
+        separator_before.into(),
+        activation,
+        context,
+    )?;
+    this.set("enabled", enabled.into(), activation, context)?;
+    this.set("visible", visible.into(), activation, context)?;
+
+    Ok(Value::Undefined)
+}
+
+/// Creates a new item with the same properties.
+pub fn copy<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Object<'gc>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let mut args = Vec::with_capacity(5);
+    for name in &[
+        "caption",
+        "onSelect",
+        "separatorBefore",
+        "enabled",
+        "visible",
+    ] {
+        args.push(this.get(name, activation, context)?);
+    }
+
+    let copy: Object<'gc> = ScriptObject::object(context.gc_context, this.proto()).into();
+    constructor(activation, context, copy, &args)?;
+    Ok(copy.into())
+}
+
+pub fn create_proto<'gc>(
+    gc_context: MutationContext<'gc, '_>,
+    proto: Object<'gc>,
+    fn_proto: Object<'gc>,
+) -> Object<'gc> {
+    let mut context_menu_item_proto = ScriptObject::object(gc_context, Some(proto));
+
+    context_menu_item_proto.force_set_function(
+        "copy",
+        copy,
+        gc_context,
+        EnumSet::empty(),
+        Some(fn_proto),
+    );
+
+    context_menu_item_proto.into()
+}
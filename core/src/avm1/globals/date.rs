@@ -0,0 +1,662 @@
+//! AVM1 Date object
+//!
+//! Dates are stored as milliseconds since the Unix epoch in UTC, and broken
+//! into fields using the algorithms of the ECMAScript specification.
+
+use crate::avm1::activation::Activation;
+use crate::avm1::date_object::DateObject;
+use crate::avm1::error::Error;
+use crate::avm1::function::{Executable, FunctionObject};
+use crate::avm1::{AvmString, Object, TObject, Value};
+use crate::context::UpdateContext;
+use enumset::EnumSet;
+use gc_arena::MutationContext;
+
+const MS_PER_SECOND: f64 = 1000.0;
+const MS_PER_MINUTE: f64 = 60_000.0;
+const MS_PER_HOUR: f64 = 3_600_000.0;
+const MS_PER_DAY: f64 = 86_400_000.0;
+
+/// The number of days before the first day of each month, in a non-leap year.
+const DAYS_BEFORE_MONTH: [f64; 12] = [
+    0.0, 31.0, 59.0, 90.0, 120.0, 151.0, 181.0, 212.0, 243.0, 273.0, 304.0, 334.0,
+];
+
+const WEEKDAY_NAMES: [&str; 7] = ["Sun", "Mon", "Tue", "Wed", "Thu", "Fri", "Sat"];
+
+const MONTH_NAMES: [&str; 12] = [
+    "Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec",
+];
+
+/// The fields of a date, in the order they are passed to the `Date` constructor.
+const YEAR: usize = 0;
+const MONTH: usize = 1;
+const DATE: usize = 2;
+const HOURS: usize = 3;
+const MINUTES: usize = 4;
+const SECONDS: usize = 5;
+const MILLISECONDS: usize = 6;
+
+fn day(t: f64) -> f64 {
+    (t / MS_PER_DAY).floor()
+}
+
+fn is_leap_year(year: f64) -> bool {
+    year.rem_euclid(4.0) == 0.0 && (year.rem_euclid(100.0) != 0.0 || year.rem_euclid(400.0) == 0.0)
+}
+
+fn day_from_year(year: f64) -> f64 {
+    365.0 * (year - 1970.0) + ((year - 1969.0) / 4.0).floor() - ((year - 1901.0) / 100.0).floor()
+        + ((year - 1601.0) / 400.0).floor()
+}
+
+fn time_from_year(year: f64) -> f64 {
+    MS_PER_DAY * day_from_year(year)
+}
+
+fn year_from_time(t: f64) -> f64 {
+    if !t.is_finite() {
+        return f64::NAN;
+    }
+    let mut year = (t / (MS_PER_DAY * 365.2425)).floor() + 1970.0;
+    while time_from_year(year) > t {
+        year -= 1.0;
+    }
+    while time_from_year(year + 1.0) <= t {
+        year += 1.0;
+    }
+    year
+}
+
+/// Returns the number of days in the given year before the first day of the given month.
+fn days_before_month(year: f64, month: usize) -> f64 {
+    let leap_day = if month >= 2 && is_leap_year(year) {
+        1.0
+    } else {
+        0.0
+    };
+    DAYS_BEFORE_MONTH[month] + leap_day
+}
+
+fn month_from_time(t: f64) -> f64 {
+    if !t.is_finite() {
+        return f64::NAN;
+    }
+    let year = year_from_time(t);
+    let day_within_year = day(t) - day_from_year(year);
+    let month = (1..12)
+        .rev()
+        .find(|&month| days_before_month(year, month) <= day_within_year)
+        .unwrap_or(0);
+    month as f64
+}
+
+fn date_from_time(t: f64) -> f64 {
+    if !t.is_finite() {
+        return f64::NAN;
+    }
+    let year = year_from_time(t);
+    let month = month_from_time(t) as usize;
+    day(t) - day_from_year(year) - days_before_month(year, month) + 1.0
+}
+
+fn week_day(t: f64) -> f64 {
+    (day(t) + 4.0).rem_euclid(7.0)
+}
+
+fn hours_from_time(t: f64) -> f64 {
+    (t / MS_PER_HOUR).floor().rem_euclid(24.0)
+}
+
+fn minutes_from_time(t: f64) -> f64 {
+    (t / MS_PER_MINUTE).floor().rem_euclid(60.0)
+}
+
+fn seconds_from_time(t: f64) -> f64 {
+    (t / MS_PER_SECOND).floor().rem_euclid(60.0)
+}
+
+fn milliseconds_from_time(t: f64) -> f64 {
+    t.rem_euclid(MS_PER_SECOND)
+}
+
+/// Splits a time into its fields, in the order of the `Date` constructor's arguments.
+fn fields_from_time(t: f64) -> [f64; 7] {
+    [
+        year_from_time(t),
+        month_from_time(t),
+        date_from_time(t),
+        hours_from_time(t),
+        minutes_from_time(t),
+        seconds_from_time(t),
+        milliseconds_from_time(t),
+    ]
+}
+
+fn make_time(hours: f64, minutes: f64, seconds: f64, milliseconds: f64) -> f64 {
+    hours.trunc() * MS_PER_HOUR
+        + minutes.trunc() * MS_PER_MINUTE
+        + seconds.trunc() * MS_PER_SECOND
+        + milliseconds.trunc()
+}
+
+/// Returns the day number of the given date.
+/// Months outside of 0-11 roll over into the surrounding years.
+fn make_day(year: f64, month: f64, date: f64) -> f64 {
+    if !year.is_finite() || !month.is_finite() || !date.is_finite() {
+        return f64::NAN;
+    }
+    let year = year.trunc() + (month.trunc() / 12.0).floor();
+    let month = month.trunc().rem_euclid(12.0) as usize;
+    day_from_year(year) + days_before_month(year, month) + date.trunc() - 1.0
+}
+
+/// Returns the time of the given fields, in the order of the `Date` constructor's arguments.
+fn time_from_fields(fields: &[f64; 7]) -> f64 {
+    let day = make_day(fields[YEAR], fields[MONTH], fields[DATE]);
+    let time = make_time(
+        fields[HOURS],
+        fields[MINUTES],
+        fields[SECONDS],
+        fields[MILLISECONDS],
+    );
+    day * MS_PER_DAY + time
+}
+
+/// Limits a time to the range of valid dates, 100,000,000 days either side of the epoch.
+fn time_clip(t: f64) -> f64 {
+    if !t.is_finite() || t.abs() > 8.64e15 {
+        f64::NAN
+    } else {
+        t.trunc() + 0.0
+    }
+}
+
+/// Returns the offset of local time from UTC at the given UTC time, in milliseconds.
+fn local_offset(context: &UpdateContext<'_, '_, '_>, t: f64) -> f64 {
+    -f64::from(context.navigator.timezone_offset(t)) * MS_PER_MINUTE
+}
+
+/// Converts a UTC time to local time.
+fn local_time(context: &UpdateContext<'_, '_, '_>, t: f64) -> f64 {
+    t + local_offset(context, t)
+}
+
+/// Converts a local time to UTC.
+fn utc_time(context: &UpdateContext<'_, '_, '_>, t: f64) -> f64 {
+    t - local_offset(context, t - local_offset(context, t))
+}
+
+fn date_time(this: Object<'_>) -> f64 {
+    this.as_date_object()
+        .map(|date| date.date_time())
+        .unwrap_or(f64::NAN)
+}
+
+/// Reads the fields of a date from the arguments of `new Date` or `Date.UTC`.
+/// Two-digit years are in the 1900s.
+fn fields_from_args<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    args: &[Value<'gc>],
+) -> Result<[f64; 7], Error<'gc>> {
+    let mut fields = [f64::NAN, f64::NAN, 1.0, 0.0, 0.0, 0.0, 0.0];
+    for (field, arg) in fields.iter_mut().zip(args) {
+        *field = arg.coerce_to_f64(activation, context)?;
+    }
+    let year = fields[YEAR].trunc();
+    if (0.0..=99.0).contains(&year) {
+        fields[YEAR] = 1900.0 + year;
+    }
+    Ok(fields)
+}
+
+/// Implements `Date`
+pub fn constructor<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Object<'gc>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let time = match args {
+        [] => context.navigator.current_time().floor(),
+        [time] => time.coerce_to_f64(activation, context)?,
+        _ => {
+            let fields = fields_from_args(activation, context, args)?;
+            utc_time(context, time_from_fields(&fields))
+        }
+    };
+
+    if let Some(date) = this.as_date_object() {
+        date.set_date_time(context.gc_context, time_clip(time));
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `Date.UTC`
+fn utc<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    _this: Object<'gc>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let fields = fields_from_args(activation, context, args)?;
+    Ok(time_clip(time_from_fields(&fields)).into())
+}
+
+fn get_time<'gc>(
+    _activation: &mut Activation<'_, 'gc>,
+    _context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Object<'gc>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    Ok(date_time(this).into())
+}
+
+fn set_time<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Object<'gc>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let time = time_clip(
+        args.get(0)
+            .unwrap_or(&Value::Undefined)
+            .coerce_to_f64(activation, context)?,
+    );
+    if let Some(date) = this.as_date_object() {
+        date.set_date_time(context.gc_context, time);
+    }
+    Ok(time.into())
+}
+
+fn get_timezone_offset<'gc>(
+    _activation: &mut Activation<'_, 'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Object<'gc>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let time = date_time(this);
+    if time.is_nan() {
+        return Ok(f64::NAN.into());
+    }
+    Ok(context.navigator.timezone_offset(time).into())
+}
+
+/// Returns a field of a date, in local time or UTC.
+fn get_field<'gc>(
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Object<'gc>,
+    field: usize,
+    is_local: bool,
+) -> Value<'gc> {
+    let time = date_time(this);
+    let time = if is_local {
+        local_time(context, time)
+    } else {
+        time
+    };
+    fields_from_time(time)[field].into()
+}
+
+/// Sets consecutive fields of a date from arguments, in local time or UTC.
+/// Returns the new time of the date.
+fn set_fields<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Object<'gc>,
+    args: &[Value<'gc>],
+    first_field: usize,
+    max_fields: usize,
+    is_local: bool,
+) -> Result<Value<'gc>, Error<'gc>> {
+    let mut time = date_time(this);
+    // Setting the year of an invalid date starts from the epoch.
+    if time.is_nan() && first_field == YEAR {
+        time = 0.0;
+    }
+    if is_local {
+        time = local_time(context, time);
+    }
+
+    let mut fields = fields_from_time(time);
+    if args.is_empty() {
+        fields[first_field] = f64::NAN;
+    }
+    for (i, arg) in args.iter().take(max_fields).enumerate() {
+        fields[first_field + i] = arg.coerce_to_f64(activation, context)?;
+    }
+
+    let mut time = time_from_fields(&fields);
+    if is_local {
+        time = utc_time(context, time);
+    }
+    let time = time_clip(time);
+    if let Some(date) = this.as_date_object() {
+        date.set_date_time(context.gc_context, time);
+    }
+    Ok(time.into())
+}
+
+fn get_year<'gc>(
+    _activation: &mut Activation<'_, 'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Object<'gc>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let year = year_from_time(local_time(context, date_time(this)));
+    Ok((year - 1900.0).into())
+}
+
+fn set_year<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Object<'gc>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let mut year = args
+        .get(0)
+        .unwrap_or(&Value::Undefined)
+        .coerce_to_f64(activation, context)?
+        .trunc();
+    if (0.0..=99.0).contains(&year) {
+        year += 1900.0;
+    }
+    set_fields(activation, context, this, &[year.into()], YEAR, 1, true)
+}
+
+fn to_string<'gc>(
+    _activation: &mut Activation<'_, 'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Object<'gc>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let time = date_time(this);
+    if time.is_nan() {
+        return Ok("Invalid Date".into());
+    }
+
+    let offset = local_offset(context, time) / MS_PER_MINUTE;
+    let local = time + offset * MS_PER_MINUTE;
+    let fields = fields_from_time(local);
+    Ok(AvmString::new(
+        context.gc_context,
+        format!(
+            "{} {} {} {:02}:{:02}:{:02} GMT{}{:02}{:02} {}",
+            WEEKDAY_NAMES[week_day(local) as usize],
+            MONTH_NAMES[fields[MONTH] as usize],
+            fields[DATE],
+            fields[HOURS],
+            fields[MINUTES],
+            fields[SECONDS],
+            if offset < 0.0 { '-' } else { '+' },
+            (offset.abs() / 60.0).floor(),
+            offset.abs() % 60.0,
+            fields[YEAR],
+        ),
+    )
+    .into())
+}
+
+/// Defines getter methods of the `Date` prototype that return a field of the date.
+macro_rules! date_getters {
+    ($object: ident, $gc_context: ident, $fn_proto: ident, $($name: expr => [$field: expr, $is_local: expr],)*) => {
+        $(
+            $object.force_set_function(
+                $name,
+                |_activation, context, this, _args| -> Result<Value<'gc>, Error<'gc>> {
+                    Ok(get_field(context, this, $field, $is_local))
+                },
+                $gc_context,
+                EnumSet::empty(),
+                Some($fn_proto),
+            );
+        )*
+    };
+}
+
+/// Defines setter methods of the `Date` prototype that set up to `max_fields`
+/// consecutive fields of the date, starting with the given field.
+macro_rules! date_setters {
+    ($object: ident, $gc_context: ident, $fn_proto: ident, $($name: expr => [$field: expr, $max_fields: expr, $is_local: expr],)*) => {
+        $(
+            $object.force_set_function(
+                $name,
+                |activation, context, this, args| -> Result<Value<'gc>, Error<'gc>> {
+                    set_fields(activation, context, this, args, $field, $max_fields, $is_local)
+                },
+                $gc_context,
+                EnumSet::empty(),
+                Some($fn_proto),
+            );
+        )*
+    };
+}
+
+pub fn create_date_object<'gc>(
+    gc_context: MutationContext<'gc, '_>,
+    date_proto: Option<Object<'gc>>,
+    fn_proto: Option<Object<'gc>>,
+) -> Object<'gc> {
+    let date = FunctionObject::function(
+        gc_context,
+        Executable::Native(constructor),
+        fn_proto,
+        date_proto,
+    );
+    let mut object = date.as_script_object().unwrap();
+
+    object.force_set_function("UTC", utc, gc_context, EnumSet::empty(), fn_proto);
+
+    date
+}
+
+pub fn create_proto<'gc>(
+    gc_context: MutationContext<'gc, '_>,
+    proto: Object<'gc>,
+    fn_proto: Object<'gc>,
+) -> Object<'gc> {
+    let date = DateObject::empty_date_object(gc_context, Some(proto));
+    let mut object = date.as_script_object().unwrap();
+
+    object.force_set_function(
+        "getTime",
+        get_time,
+        gc_context,
+        EnumSet::empty(),
+        Some(fn_proto),
+    );
+    object.force_set_function(
+        "valueOf",
+        get_time,
+        gc_context,
+        EnumSet::empty(),
+        Some(fn_proto),
+    );
+    object.force_set_function(
+        "setTime",
+        set_time,
+        gc_context,
+        EnumSet::empty(),
+        Some(fn_proto),
+    );
+    object.force_set_function(
+        "getTimezoneOffset",
+        get_timezone_offset,
+        gc_context,
+        EnumSet::empty(),
+        Some(fn_proto),
+    );
+    object.force_set_function(
+        "getYear",
+        get_year,
+        gc_context,
+        EnumSet::empty(),
+        Some(fn_proto),
+    );
+    object.force_set_function(
+        "setYear",
+        set_year,
+        gc_context,
+        EnumSet::empty(),
+        Some(fn_proto),
+    );
+    object.force_set_function(
+        "toString",
+        to_string,
+        gc_context,
+        EnumSet::empty(),
+        Some(fn_proto),
+    );
+    object.force_set_function(
+        "getDay",
+        |_activation, context, this, _args| -> Result<Value<'gc>, Error<'gc>> {
+            Ok(week_day(local_time(context, date_time(this))).into())
+        },
+        gc_context,
+        EnumSet::empty(),
+        Some(fn_proto),
+    );
+    object.force_set_function(
+        "getUTCDay",
+        |_activation, _context, this, _args| -> Result<Value<'gc>, Error<'gc>> {
+            Ok(week_day(date_time(this)).into())
+        },
+        gc_context,
+        EnumSet::empty(),
+        Some(fn_proto),
+    );
+
+    date_getters!(
+        object,
+        gc_context,
+        fn_proto,
+        "getFullYear" => [YEAR, true],
+        "getMonth" => [MONTH, true],
+        "getDate" => [DATE, true],
+        "getHours" => [HOURS, true],
+        "getMinutes" => [MINUTES, true],
+        "getSeconds" => [SECONDS, true],
+        "getMilliseconds" => [MILLISECONDS, true],
+        "getUTCFullYear" => [YEAR, false],
+        "getUTCMonth" => [MONTH, false],
+        "getUTCDate" => [DATE, false],
+        "getUTCHours" => [HOURS, false],
+        "getUTCMinutes" => [MINUTES, false],
+        "getUTCSeconds" => [SECONDS, false],
+        "getUTCMilliseconds" => [MILLISECONDS, false],
+    );
+
+    date_setters!(
+        object,
+        gc_context,
+        fn_proto,
+        "setFullYear" => [YEAR, 3, true],
+        "setMonth" => [MONTH, 2, true],
+        "setDate" => [DATE, 1, true],
+        "setHours" => [HOURS, 4, true],
+        "setMinutes" => [MINUTES, 3, true],
+        "setSeconds" => [SECONDS, 2, true],
+        "setMilliseconds" => [MILLISECONDS, 1, true],
+        "setUTCFullYear" => [YEAR, 3, false],
+        "setUTCMonth" => [MONTH, 2, false],
+        "setUTCDate" => [DATE, 1, false],
+        "setUTCHours" => [HOURS, 4, false],
+        "setUTCMinutes" => [MINUTES, 3, false],
+        "setUTCSeconds" => [SECONDS, 2, false],
+        "setUTCMilliseconds" => [MILLISECONDS, 1, false],
+    );
+
+    date.into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::avm1::test_utils::with_avm;
+
+    /// Sunday, September 9th 2001, 01:46:40 UTC.
+    const TIME: f64 = 1_000_000_000_000.0;
+
+    fn setup<'gc>(
+        activation: &mut Activation<'_, 'gc>,
+        context: &mut UpdateContext<'_, 'gc, '_>,
+    ) -> Object<'gc> {
+        let date =
+            DateObject::empty_date_object(context.gc_context, Some(activation.avm.prototypes.date));
+        date.set_date_time(context.gc_context, TIME);
+        date.into()
+    }
+
+    fn setup_date_class<'gc>(
+        activation: &mut Activation<'_, 'gc>,
+        context: &mut UpdateContext<'_, 'gc, '_>,
+    ) -> Object<'gc> {
+        create_date_object(
+            context.gc_context,
+            Some(activation.avm.prototypes.date),
+            Some(activation.avm.prototypes.function),
+        )
+    }
+
+    test_method!(test_get_full_year, "getFullYear", setup, [6, 8] => { [] => 2001 });
+    test_method!(test_get_year, "getYear", setup, [6, 8] => { [] => 101 });
+    test_method!(test_get_month, "getMonth", setup, [6, 8] => { [] => 8 });
+    test_method!(test_get_date, "getDate", setup, [6, 8] => { [] => 9 });
+    test_method!(test_get_day, "getDay", setup, [6, 8] => { [] => 0 });
+    test_method!(test_get_utc_hours, "getUTCHours", setup, [6, 8] => { [] => 1 });
+    test_method!(test_get_minutes, "getMinutes", setup, [6, 8] => { [] => 46 });
+    test_method!(test_get_seconds, "getSeconds", setup, [6, 8] => { [] => 40 });
+    test_method!(test_get_time, "getTime", setup, [6, 8] => { [] => TIME });
+    test_method!(test_get_timezone_offset, "getTimezoneOffset", setup, [6, 8] => { [] => 0 });
+
+    test_method!(test_to_string, "toString", setup,
+        [6, 8] => {
+            [] => "Sun Sep 9 01:46:40 GMT+0000 2001"
+        }
+    );
+
+    // Months past December roll over into the next year.
+    test_method!(test_set_month, "setMonth", setup,
+        [6, 8] => {
+            [13] => 1_013_219_200_000.0
+        }
+    );
+
+    test_method!(test_set_hours, "setUTCHours", setup,
+        [6, 8] => {
+            [-1, 30] => 999_991_840_000.0
+        }
+    );
+
+    test_method!(test_utc, "UTC", setup_date_class,
+        [6, 8] => {
+            [2000, 0, 1] => 946_684_800_000.0,
+            [99, 0] => 915_148_800_000.0,
+            [2000] => f64::NAN
+        }
+    );
+
+    #[test]
+    fn constructor_fields() {
+        with_avm(8, |activation, context, _root| -> Result<(), Error> {
+            let date = setup(activation, context);
+
+            // The null navigator's clock is pinned to the epoch.
+            constructor(activation, context, date, &[])?;
+            assert_eq!(date_time(date), 0.0);
+
+            constructor(activation, context, date, &[TIME.into()])?;
+            assert_eq!(date_time(date), TIME);
+
+            constructor(activation, context, date, &[2004.into(), 12.into()])?;
+            assert_eq!(date_time(date), 1_104_537_600_000.0);
+
+            constructor(activation, context, date, &["invalid".into()])?;
+            assert!(date_time(date).is_nan());
+
+            Ok(())
+        });
+    }
+}
@@ -3,6 +3,9 @@
 use crate::avm1::activation::Activation;
 use crate::avm1::error::Error;
 use crate::avm1::function::Executable;
+use crate::avm1::globals::bitmap_filter;
+use crate::avm1::globals::rectangle::{object_to_rectangle, rectangle_to_object};
+use crate::avm1::globals::transform::create_transform;
 use crate::avm1::property::Attribute::*;
 use crate::avm1::{Object, ScriptObject, TObject, UpdateContext, Value};
 use crate::display_object::{DisplayObject, TDisplayObject};
@@ -25,7 +28,8 @@ macro_rules! with_display_object {
             $object.force_set_function(
                 $name,
                 |activation, context: &mut UpdateContext<'_, 'gc, '_>, this, args| -> Result<Value<'gc>, Error<'gc>> {
-                    if let Some(display_object) = this.as_display_object() {
+                    // Methods of a removed display object do nothing.
+                    if let Some(display_object) = this.as_display_object().filter(|o| !o.dangling()) {
                         return $fn(display_object, activation, context, args);
                     }
                     Ok(Value::Undefined)
@@ -76,6 +80,54 @@ pub fn define_display_object_proto<'gc>(
         Some(Executable::Native(overwrite_parent)),
         DontDelete | ReadOnly | DontEnum,
     );
+
+    object.add_property(
+        gc_context,
+        "filters",
+        Executable::Native(get_filters),
+        Some(Executable::Native(set_filters)),
+        DontDelete | DontEnum,
+    );
+
+    object.add_property(
+        gc_context,
+        "blendMode",
+        Executable::Native(get_blend_mode),
+        Some(Executable::Native(set_blend_mode)),
+        DontDelete | DontEnum,
+    );
+
+    object.add_property(
+        gc_context,
+        "cacheAsBitmap",
+        Executable::Native(get_cache_as_bitmap),
+        Some(Executable::Native(set_cache_as_bitmap)),
+        DontDelete | DontEnum,
+    );
+
+    object.add_property(
+        gc_context,
+        "opaqueBackground",
+        Executable::Native(get_opaque_background),
+        Some(Executable::Native(set_opaque_background)),
+        DontDelete | DontEnum,
+    );
+
+    object.add_property(
+        gc_context,
+        "scrollRect",
+        Executable::Native(get_scroll_rect),
+        Some(Executable::Native(set_scroll_rect)),
+        DontDelete | DontEnum,
+    );
+
+    object.add_property(
+        gc_context,
+        "transform",
+        Executable::Native(get_transform),
+        Some(Executable::Native(set_transform)),
+        DontDelete | DontEnum,
+    );
 }
 
 pub fn get_parent<'gc>(
@@ -106,6 +158,303 @@ pub fn get_depth<'gc>(
     }
 }
 
+/// Returns a new array of copies of the filters applied to this object.
+pub fn get_filters<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Object<'gc>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let filters = this
+        .as_display_object()
+        .map(|dobj| dobj.filters())
+        .unwrap_or_default();
+    let array = ScriptObject::array(context.gc_context, Some(activation.avm.prototypes.array));
+    let mut length = 0;
+    for filter in &filters {
+        if let Some(filter_object) = bitmap_filter::filter_to_object(activation, context, filter)? {
+            array.set_array_element(length, filter_object.into(), context.gc_context);
+            length += 1;
+        }
+    }
+    Ok(array.into())
+}
+
+/// Replaces the filters applied to this object with copies of the filters in the given array.
+pub fn set_filters<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Object<'gc>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    if let Some(mut dobj) = this.as_display_object() {
+        let mut filters = vec![];
+        if let Some(Value::Object(array)) = args.get(0) {
+            for value in array.array() {
+                if let Value::Object(filter_object) = value {
+                    if let Some(filter) =
+                        bitmap_filter::object_to_filter(activation, context, filter_object)?
+                    {
+                        filters.push(filter);
+                    }
+                }
+            }
+        }
+        dobj.set_filters(context.gc_context, filters);
+        dobj.invalidate_cached_bitmap();
+    }
+    Ok(Value::Undefined)
+}
+
+/// Returns the name of the blend mode of this object.
+pub fn get_blend_mode<'gc>(
+    _activation: &mut Activation<'_, 'gc>,
+    _context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Object<'gc>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    Ok(this
+        .as_display_object()
+        .map(|dobj| blend_mode_name(dobj.blend_mode()).into())
+        .unwrap_or(Value::Undefined))
+}
+
+/// Sets the blend mode of this object from either a blend mode name or its numeric value.
+/// Unrecognized values reset the blend mode to normal.
+pub fn set_blend_mode<'gc>(
+    _activation: &mut Activation<'_, 'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Object<'gc>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    if let Some(mut dobj) = this.as_display_object() {
+        dobj.set_blend_mode(
+            context.gc_context,
+            value_to_blend_mode(args.get(0)).unwrap_or(swf::BlendMode::Normal),
+        );
+        dobj.invalidate_cached_bitmap();
+    }
+    Ok(Value::Undefined)
+}
+
+/// Returns whether this object is cached as a bitmap.
+/// Objects with filters are always cached.
+pub fn get_cache_as_bitmap<'gc>(
+    _activation: &mut Activation<'_, 'gc>,
+    _context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Object<'gc>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    Ok(this
+        .as_display_object()
+        .map(|dobj| dobj.is_bitmap_cached().into())
+        .unwrap_or(Value::Undefined))
+}
+
+pub fn set_cache_as_bitmap<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Object<'gc>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    if let Some(mut dobj) = this.as_display_object() {
+        let value = args
+            .get(0)
+            .unwrap_or(&Value::Undefined)
+            .as_bool(activation.current_swf_version());
+        dobj.set_cache_as_bitmap(context.gc_context, value);
+        dobj.invalidate_cached_bitmap();
+    }
+    Ok(Value::Undefined)
+}
+
+/// Returns the background color of this object as an RGB number,
+/// or `undefined` if the object has no background.
+pub fn get_opaque_background<'gc>(
+    _activation: &mut Activation<'_, 'gc>,
+    _context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Object<'gc>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    Ok(this
+        .as_display_object()
+        .and_then(|dobj| dobj.opaque_background())
+        .map(|color| {
+            let rgb = (u32::from(color.r) << 16) | (u32::from(color.g) << 8) | u32::from(color.b);
+            rgb.into()
+        })
+        .unwrap_or(Value::Undefined))
+}
+
+/// Sets the background color of this object from an RGB number.
+/// Setting `null` or `undefined` removes the background.
+pub fn set_opaque_background<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Object<'gc>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    if let Some(mut dobj) = this.as_display_object() {
+        let color = match args.get(0) {
+            None | Some(Value::Undefined) | Some(Value::Null) => None,
+            Some(value) => {
+                let rgb = value.coerce_to_u32(activation, context)?;
+                Some(swf::Color::from_rgb(rgb, 255))
+            }
+        };
+        dobj.set_opaque_background(context.gc_context, color);
+        dobj.invalidate_cached_bitmap();
+    }
+    Ok(Value::Undefined)
+}
+
+/// Returns a new `Rectangle` with the values of the scroll rect of this object,
+/// or `undefined` if it has none.
+pub fn get_scroll_rect<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Object<'gc>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    if let Some(scroll_rect) = this.as_display_object().and_then(|dobj| dobj.scroll_rect()) {
+        Ok(rectangle_to_object(&scroll_rect, activation, context)?.into())
+    } else {
+        Ok(Value::Undefined)
+    }
+}
+
+/// Sets the scroll rect of this object from the `x`, `y`, `width` and `height` of the
+/// given object. Setting `null` or `undefined` removes the scroll rect.
+pub fn set_scroll_rect<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Object<'gc>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    if let Some(mut dobj) = this.as_display_object() {
+        let scroll_rect = if let Some(Value::Object(rect)) = args.get(0) {
+            Some(object_to_rectangle(*rect, activation, context)?)
+        } else {
+            None
+        };
+        dobj.set_scroll_rect(context.gc_context, scroll_rect);
+        dobj.invalidate_cached_bitmap();
+    }
+    Ok(Value::Undefined)
+}
+
+/// Returns a new `Transform` object for this object.
+pub fn get_transform<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Object<'gc>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    if let Some(dobj) = this.as_display_object() {
+        Ok(create_transform(activation, context, dobj).into())
+    } else {
+        Ok(Value::Undefined)
+    }
+}
+
+/// Copies the matrix and color transform of the object targeted by the given `Transform`.
+pub fn set_transform<'gc>(
+    _activation: &mut Activation<'_, 'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Object<'gc>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let source = match args.get(0) {
+        Some(Value::Object(transform)) => transform
+            .as_transform_object()
+            .and_then(|transform| transform.clip()),
+        _ => None,
+    };
+    if let (Some(mut dobj), Some(source)) = (this.as_display_object(), source) {
+        let matrix = *source.matrix();
+        let color_transform = *source.color_transform();
+        dobj.set_matrix(context.gc_context, &matrix);
+        dobj.set_color_transform(context.gc_context, &color_transform);
+        dobj.set_transformed_by_script(context.gc_context, true);
+        dobj.invalidate_cached_bitmap();
+    }
+    Ok(Value::Undefined)
+}
+
+fn blend_mode_name(blend_mode: swf::BlendMode) -> &'static str {
+    use swf::BlendMode;
+    match blend_mode {
+        BlendMode::Normal => "normal",
+        BlendMode::Layer => "layer",
+        BlendMode::Multiply => "multiply",
+        BlendMode::Screen => "screen",
+        BlendMode::Lighten => "lighten",
+        BlendMode::Darken => "darken",
+        BlendMode::Difference => "difference",
+        BlendMode::Add => "add",
+        BlendMode::Subtract => "subtract",
+        BlendMode::Invert => "invert",
+        BlendMode::Alpha => "alpha",
+        BlendMode::Erase => "erase",
+        BlendMode::Overlay => "overlay",
+        BlendMode::HardLight => "hardlight",
+    }
+}
+
+/// Reads a blend mode from either its name or its numeric value.
+pub fn value_to_blend_mode(value: Option<&Value<'_>>) -> Option<swf::BlendMode> {
+    match value {
+        Some(Value::String(name)) => blend_mode_from_name(name),
+        Some(Value::Number(n)) => blend_mode_from_number(*n),
+        _ => None,
+    }
+}
+
+fn blend_mode_from_name(name: &str) -> Option<swf::BlendMode> {
+    use swf::BlendMode;
+    let blend_mode = match name {
+        "normal" => BlendMode::Normal,
+        "layer" => BlendMode::Layer,
+        "multiply" => BlendMode::Multiply,
+        "screen" => BlendMode::Screen,
+        "lighten" => BlendMode::Lighten,
+        "darken" => BlendMode::Darken,
+        "difference" => BlendMode::Difference,
+        "add" => BlendMode::Add,
+        "subtract" => BlendMode::Subtract,
+        "invert" => BlendMode::Invert,
+        "alpha" => BlendMode::Alpha,
+        "erase" => BlendMode::Erase,
+        "overlay" => BlendMode::Overlay,
+        "hardlight" => BlendMode::HardLight,
+        _ => return None,
+    };
+    Some(blend_mode)
+}
+
+/// Numeric blend modes match the values used in `PlaceObject3` tags.
+fn blend_mode_from_number(n: f64) -> Option<swf::BlendMode> {
+    use swf::BlendMode;
+    let blend_mode = match n as i32 {
+        1 => BlendMode::Normal,
+        2 => BlendMode::Layer,
+        3 => BlendMode::Multiply,
+        4 => BlendMode::Screen,
+        5 => BlendMode::Lighten,
+        6 => BlendMode::Darken,
+        7 => BlendMode::Difference,
+        8 => BlendMode::Add,
+        9 => BlendMode::Subtract,
+        10 => BlendMode::Invert,
+        11 => BlendMode::Alpha,
+        12 => BlendMode::Erase,
+        13 => BlendMode::Overlay,
+        14 => BlendMode::HardLight,
+        _ => return None,
+    };
+    Some(blend_mode)
+}
+
 pub fn overwrite_root<'gc>(
     _activation: &mut Activation<'_, 'gc>,
     ac: &mut UpdateContext<'_, 'gc, '_>,
@@ -0,0 +1,270 @@
+//! `flash.external.ExternalInterface` impl
+
+use crate::avm1::activation::Activation;
+use crate::avm1::error::Error;
+use crate::avm1::function::Executable;
+use crate::avm1::property::Attribute;
+use crate::avm1::{AvmString, Object, ScriptObject, TObject, UpdateContext, Value};
+use crate::external::Value as ExternalValue;
+use enumset::EnumSet;
+use gc_arena::{Collect, MutationContext};
+use std::collections::{BTreeMap, HashMap};
+
+/// A function exposed to the host with `ExternalInterface.addCallback`.
+#[derive(Clone)]
+pub struct ExternalCallback<'gc> {
+    /// The object the method is called on.
+    this: Value<'gc>,
+
+    /// The method to call.
+    method: Object<'gc>,
+}
+
+/// The functions exposed to the host by AVM1 `ExternalInterface.addCallback`,
+/// by name.
+pub struct ExternalCallbacks<'gc> {
+    callbacks: HashMap<String, ExternalCallback<'gc>>,
+}
+
+unsafe impl<'gc> Collect for ExternalCallbacks<'gc> {
+    fn trace(&self, cc: gc_arena::CollectionContext) {
+        for callback in self.callbacks.values() {
+            callback.this.trace(cc);
+            callback.method.trace(cc);
+        }
+    }
+}
+
+impl<'gc> ExternalCallbacks<'gc> {
+    pub fn new() -> Self {
+        Self {
+            callbacks: HashMap::new(),
+        }
+    }
+
+    pub fn get(&self, name: &str) -> Option<ExternalCallback<'gc>> {
+        self.callbacks.get(name).cloned()
+    }
+
+    /// The names of all exposed functions.
+    pub fn names(&self) -> std::collections::hash_map::Keys<'_, String, ExternalCallback<'gc>> {
+        self.callbacks.keys()
+    }
+}
+
+impl<'gc> Default for ExternalCallbacks<'gc> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<'gc> ExternalCallback<'gc> {
+    /// Call this function on behalf of the host.
+    pub fn call(
+        self,
+        activation: &mut Activation<'_, 'gc>,
+        context: &mut UpdateContext<'_, 'gc, '_>,
+        args: &[ExternalValue],
+    ) -> ExternalValue {
+        let this = self.this.coerce_to_object(activation, context);
+        let args: Vec<Value<'gc>> = args
+            .iter()
+            .map(|arg| from_external_value(activation, context, arg))
+            .collect();
+
+        match self
+            .method
+            .call(
+                "[ExternalInterface]",
+                activation,
+                context,
+                this,
+                None,
+                &args,
+            )
+            .and_then(|value| to_external_value(activation, context, value))
+        {
+            Ok(value) => value,
+            Err(e) => {
+                log::warn!("ExternalInterface: Error in exposed function: {}", e);
+                ExternalValue::Undefined
+            }
+        }
+    }
+}
+
+/// Convert an AVM1 value for the host.
+///
+/// Arrays become lists, other objects become their enumerable properties and
+/// functions become `null`.
+pub fn to_external_value<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    value: Value<'gc>,
+) -> Result<ExternalValue, Error<'gc>> {
+    Ok(match value {
+        Value::Undefined => ExternalValue::Undefined,
+        Value::Null => ExternalValue::Null,
+        Value::Bool(value) => ExternalValue::Bool(value),
+        Value::Number(value) => ExternalValue::Number(value),
+        Value::String(value) => ExternalValue::String(value.to_string()),
+        Value::Object(object) if object.as_executable().is_some() => ExternalValue::Null,
+        Value::Object(object) if activation.avm.prototypes.array.is_prototype_of(object) => {
+            let mut values = Vec::with_capacity(object.length());
+            for i in 0..object.length() {
+                values.push(to_external_value(
+                    activation,
+                    context,
+                    object.array_element(i),
+                )?);
+            }
+            ExternalValue::List(values)
+        }
+        Value::Object(object) => {
+            let mut properties = BTreeMap::new();
+            for key in object.get_keys(activation, context.gc_context) {
+                let value = object.get(&key, activation, context)?;
+                properties.insert(
+                    key.to_string(),
+                    to_external_value(activation, context, value)?,
+                );
+            }
+            ExternalValue::Object(properties)
+        }
+    })
+}
+
+/// Convert a value from the host into a new AVM1 value.
+pub fn from_external_value<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    value: &ExternalValue,
+) -> Value<'gc> {
+    match value {
+        ExternalValue::Undefined => Value::Undefined,
+        ExternalValue::Null => Value::Null,
+        ExternalValue::Bool(value) => (*value).into(),
+        ExternalValue::Number(value) => (*value).into(),
+        ExternalValue::String(value) => AvmString::new(context.gc_context, value.clone()).into(),
+        ExternalValue::Object(properties) => {
+            let object =
+                ScriptObject::object(context.gc_context, Some(activation.avm.prototypes.object));
+            for (key, value) in properties {
+                let value = from_external_value(activation, context, value);
+                object.define_value(
+                    context.gc_context,
+                    AvmString::new(context.gc_context, key.clone()),
+                    value,
+                    EnumSet::empty(),
+                );
+            }
+            object.into()
+        }
+        ExternalValue::List(values) => {
+            let array =
+                ScriptObject::array(context.gc_context, Some(activation.avm.prototypes.array));
+            array.set_length(context.gc_context, values.len());
+            for (i, value) in values.iter().enumerate() {
+                let value = from_external_value(activation, context, value);
+                array.set_array_element(i, value, context.gc_context);
+            }
+            array.into()
+        }
+    }
+}
+
+pub fn available<'gc>(
+    _activation: &mut Activation<'_, 'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    _this: Object<'gc>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    Ok(context.external_interface.is_some().into())
+}
+
+pub fn add_callback<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    _this: Object<'gc>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let (name, this, method) = match args {
+        [name, this, Value::Object(method), ..] => (
+            name.coerce_to_string(activation, context)?.to_string(),
+            this.clone(),
+            *method,
+        ),
+        _ => return Ok(false.into()),
+    };
+
+    context
+        .external_callbacks
+        .callbacks
+        .insert(name.clone(), ExternalCallback { this, method });
+    if let Some(provider) = context.external_interface.as_mut() {
+        provider.on_callback_available(&name);
+    }
+
+    Ok(true.into())
+}
+
+pub fn call<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    _this: Object<'gc>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let name = match args.get(0) {
+        Some(name) => name.coerce_to_string(activation, context)?.to_string(),
+        None => return Ok(Value::Null),
+    };
+
+    if context.external_interface.is_none() {
+        return Ok(Value::Null);
+    }
+
+    let mut external_args = Vec::with_capacity(args.len().saturating_sub(1));
+    for arg in &args[1..] {
+        external_args.push(to_external_value(activation, context, arg.clone())?);
+    }
+
+    let result = match context.external_interface.as_mut() {
+        Some(provider) => provider.call_method(&name, &external_args),
+        None => ExternalValue::Null,
+    };
+    Ok(from_external_value(activation, context, &result))
+}
+
+pub fn create_external_interface_object<'gc>(
+    gc_context: MutationContext<'gc, '_>,
+    proto: Option<Object<'gc>>,
+    fn_proto: Option<Object<'gc>>,
+) -> Object<'gc> {
+    let mut object = ScriptObject::object(gc_context, proto);
+
+    object.add_property(
+        gc_context,
+        "available",
+        Executable::Native(available),
+        None,
+        Attribute::DontEnum | Attribute::DontDelete | Attribute::ReadOnly,
+    );
+
+    object.force_set_function(
+        "addCallback",
+        add_callback,
+        gc_context,
+        Attribute::DontEnum | Attribute::DontDelete | Attribute::ReadOnly,
+        fn_proto,
+    );
+
+    object.force_set_function(
+        "call",
+        call,
+        gc_context,
+        Attribute::DontEnum | Attribute::DontDelete | Attribute::ReadOnly,
+        fn_proto,
+    );
+
+    object.into()
+}
@@ -16,6 +16,20 @@ pub fn constructor<'gc>(
     Ok(Value::Undefined)
 }
 
+/// Resolve the `this` argument of `call` and `apply`.
+///
+/// `null` and `undefined` call the function on `_global`; primitives are boxed.
+fn this_arg<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    action_context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Option<&Value<'gc>>,
+) -> Object<'gc> {
+    match this {
+        None | Some(Value::Undefined) | Some(Value::Null) => activation.avm.globals,
+        Some(this) => this.coerce_to_object(activation, action_context),
+    }
+}
+
 /// Implements `Function.prototype.call`
 pub fn call<'gc>(
     activation: &mut Activation<'_, 'gc>,
@@ -23,21 +37,10 @@ pub fn call<'gc>(
     func: Object<'gc>,
     myargs: &[Value<'gc>],
 ) -> Result<Value<'gc>, Error<'gc>> {
-    let this = match myargs.get(0) {
-        Some(Value::Object(this)) => *this,
-        _ => activation.avm.globals,
-    };
-    let empty = [];
-    let args = match myargs.len() {
-        0 => &empty,
-        1 => &empty,
-        _ => &myargs[1..],
-    };
+    let this = this_arg(activation, action_context, myargs.get(0));
+    let args = myargs.get(1..).unwrap_or_default();
 
-    match func.as_executable() {
-        Some(exec) => exec.exec("[Anonymous]", activation, action_context, this, None, args),
-        _ => Ok(Value::Undefined),
-    }
+    func.call("[Anonymous]", activation, action_context, this, None, args)
 }
 
 /// Implements `Function.prototype.apply`
@@ -47,37 +50,33 @@ pub fn apply<'gc>(
     func: Object<'gc>,
     myargs: &[Value<'gc>],
 ) -> Result<Value<'gc>, Error<'gc>> {
-    let this = match myargs.get(0) {
-        Some(Value::Object(this)) => *this,
-        _ => activation.avm.globals,
-    };
+    let this = this_arg(activation, action_context, myargs.get(0));
+
+    // Anything other than an array-like object passes no arguments.
     let mut child_args = Vec::new();
-    let args_object = myargs.get(1).cloned().unwrap_or(Value::Undefined);
-    let length = match args_object {
-        Value::Object(a) => a
+    if let Some(Value::Object(args_object)) = myargs.get(1) {
+        let length = args_object
             .get("length", activation, action_context)?
-            .coerce_to_f64(activation, action_context)? as usize,
-        _ => 0,
-    };
+            .coerce_to_f64(activation, action_context)?;
+        let length = if length.is_finite() && length > 0.0 {
+            length as usize
+        } else {
+            0
+        };
 
-    while child_args.len() < length {
-        let args = args_object.coerce_to_object(activation, action_context);
-        let next_arg = args.get(&format!("{}", child_args.len()), activation, action_context)?;
-
-        child_args.push(next_arg);
+        for i in 0..length {
+            child_args.push(args_object.get(&i.to_string(), activation, action_context)?);
+        }
     }
 
-    match func.as_executable() {
-        Some(exec) => exec.exec(
-            "[Anonymous]",
-            activation,
-            action_context,
-            this,
-            None,
-            &child_args,
-        ),
-        _ => Ok(Value::Undefined),
-    }
+    func.call(
+        "[Anonymous]",
+        activation,
+        action_context,
+        this,
+        None,
+        &child_args,
+    )
 }
 
 /// Implements `Function.prototype.toString`
@@ -1,5 +1,6 @@
 use crate::avm1::activation::Activation;
 use crate::avm1::error::Error;
+use crate::avm1::globals::as_broadcaster::BroadcasterFunctions;
 use crate::avm1::property::Attribute;
 use crate::avm1::{Object, ScriptObject, TObject, UpdateContext, Value};
 use crate::events::KeyCode;
@@ -17,7 +18,7 @@ pub fn is_down<'gc>(
         .and_then(|v| v.coerce_to_f64(activation, context).ok())
         .and_then(|k| KeyCode::try_from(k as u8).ok())
     {
-        Ok(context.input.is_key_down(key).into())
+        Ok(context.keyboard.is_key_down(key).into())
     } else {
         Ok(false.into())
     }
@@ -29,17 +30,30 @@ pub fn get_code<'gc>(
     _this: Object<'gc>,
     _args: &[Value<'gc>],
 ) -> Result<Value<'gc>, Error<'gc>> {
-    let code: u8 = context.input.get_last_key_code().into();
+    let code: u8 = context.keyboard.last_key_code().into();
     Ok(code.into())
 }
 
+pub fn get_ascii<'gc>(
+    _activation: &mut Activation<'_, 'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    _this: Object<'gc>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    Ok(context.keyboard.last_key_ascii().into())
+}
+
 pub fn create_key_object<'gc>(
     gc_context: MutationContext<'gc, '_>,
     proto: Option<Object<'gc>>,
     fn_proto: Option<Object<'gc>>,
+    broadcaster_functions: BroadcasterFunctions<'gc>,
+    array_proto: Object<'gc>,
 ) -> Object<'gc> {
     let mut key = ScriptObject::object(gc_context, proto);
 
+    broadcaster_functions.initialize(gc_context, key.into(), array_proto);
+
     key.define_value(
         gc_context,
         "ALT",
@@ -171,5 +185,13 @@ pub fn create_key_object<'gc>(
         fn_proto,
     );
 
+    key.force_set_function(
+        "getAscii",
+        get_ascii,
+        gc_context,
+        Attribute::DontEnum | Attribute::DontDelete | Attribute::ReadOnly,
+        fn_proto,
+    );
+
     key.into()
 }
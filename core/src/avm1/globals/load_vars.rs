@@ -0,0 +1,395 @@
+//! AVM1 LoadVars object
+
+use crate::avm1::activation::Activation;
+use crate::avm1::error::Error;
+use crate::avm1::property::Attribute;
+use crate::avm1::script_object::ScriptObject;
+use crate::avm1::{AvmString, Object, TObject, UpdateContext, Value};
+use crate::backend::navigator::{NavigationMethod, NavigationTarget, RequestOptions};
+use enumset::EnumSet;
+use gc_arena::MutationContext;
+use url::form_urlencoded;
+
+/// The default value of `LoadVars.contentType`, used when sending variables.
+pub const DEFAULT_CONTENT_TYPE: &str = "application/x-www-form-urlencoded";
+
+/// Implements `LoadVars`
+pub fn constructor<'gc>(
+    _activation: &mut Activation<'_, 'gc>,
+    _context: &mut UpdateContext<'_, 'gc, '_>,
+    _this: Object<'gc>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    Ok(Value::Undefined)
+}
+
+/// The enumerable properties of an object, as name/value pairs to be sent as form data.
+fn object_into_form_values<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    object: Object<'gc>,
+) -> Result<Vec<(String, String)>, Error<'gc>> {
+    let mut form_values = Vec::new();
+    for key in object.get_keys(activation, context.gc_context) {
+        let value = object.get(&key, activation, context)?;
+        // Methods such as `onLoad` are not sent.
+        if let Value::Object(function) = value {
+            if function.as_executable().is_some() {
+                continue;
+            }
+        }
+        let value = value.coerce_to_string(activation, context)?.to_string();
+        form_values.push((key.to_string(), value));
+    }
+    Ok(form_values)
+}
+
+/// The HTTP headers added to an object with `addRequestHeader`.
+pub fn request_headers<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Object<'gc>,
+) -> Result<Vec<(String, String)>, Error<'gc>> {
+    let mut headers = vec![];
+    if let Value::Object(custom_headers) = this.get("_customHeaders", activation, context)? {
+        for header in custom_headers.array().chunks_exact(2) {
+            let name = header[0].coerce_to_string(activation, context)?.to_string();
+            let value = header[1].coerce_to_string(activation, context)?.to_string();
+            headers.push((name, value));
+        }
+    }
+    Ok(headers)
+}
+
+/// Implements `addRequestHeader`, shared by `LoadVars` and `XML`.
+pub fn add_request_header<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Object<'gc>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    // Headers can be given as a name and value, or as an array of alternating names and values.
+    let new_headers = match args {
+        [Value::Object(array), ..] => array.array(),
+        [name, value, ..] => vec![name.clone(), value.clone()],
+        _ => return Ok(Value::Undefined),
+    };
+
+    let custom_headers = match this.get("_customHeaders", activation, context)? {
+        Value::Object(custom_headers) => custom_headers,
+        _ => {
+            let custom_headers: Object<'gc> =
+                ScriptObject::array(context.gc_context, Some(activation.avm.prototypes.array))
+                    .into();
+            this.define_value(
+                context.gc_context,
+                "_customHeaders",
+                custom_headers.into(),
+                Attribute::DontEnum.into(),
+            );
+            custom_headers
+        }
+    };
+
+    for header in new_headers.chunks_exact(2) {
+        for value in header {
+            let value = value.coerce_to_string(activation, context)?;
+            custom_headers.set_array_element(
+                custom_headers.length(),
+                AvmString::new(context.gc_context, value).into(),
+                context.gc_context,
+            );
+        }
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Starts loading form data into a `LoadVars` object.
+fn spawn_load<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    target: Object<'gc>,
+    url: &str,
+    options: RequestOptions,
+) -> Result<(), Error<'gc>> {
+    target.define_value(
+        context.gc_context,
+        "loaded",
+        false.into(),
+        Attribute::DontEnum.into(),
+    );
+    target.define_value(
+        context.gc_context,
+        "_bytesLoaded",
+        0.into(),
+        Attribute::DontEnum.into(),
+    );
+    target.define_value(
+        context.gc_context,
+        "_bytesTotal",
+        Value::Undefined,
+        Attribute::DontEnum.into(),
+    );
+
+    let fetch = context.navigator.fetch(url, options);
+    let process = context.load_manager.load_form_into_load_vars(
+        context.player.clone().unwrap(),
+        target,
+        activation.target_clip_or_root(),
+        fetch,
+    );
+    context.navigator.spawn_future(process);
+
+    Ok(())
+}
+
+fn load<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Object<'gc>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let url = match args.get(0) {
+        Some(Value::Undefined) | Some(Value::Null) | None => return Ok(false.into()),
+        Some(url) => url.coerce_to_string(activation, context)?.to_string(),
+    };
+
+    let options = RequestOptions::get().with_headers(request_headers(activation, context, this)?);
+    spawn_load(activation, context, this, &url, options)?;
+
+    Ok(true.into())
+}
+
+fn send<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Object<'gc>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let url = match args.get(0) {
+        Some(Value::Undefined) | Some(Value::Null) | None => return Ok(false.into()),
+        Some(url) => url.coerce_to_string(activation, context)?.to_string(),
+    };
+    let target = match args.get(1) {
+        Some(Value::Undefined) | None => NavigationTarget::SelfWindow,
+        Some(window) => NavigationTarget::from_name(&window.coerce_to_string(activation, context)?),
+    };
+    let method = match args.get(2) {
+        Some(Value::Undefined) | None => NavigationMethod::POST,
+        Some(method) => {
+            NavigationMethod::from_method_str(&method.coerce_to_string(activation, context)?)
+                .unwrap_or(NavigationMethod::POST)
+        }
+    };
+
+    let vars = object_into_form_values(activation, context, this)?
+        .into_iter()
+        .collect();
+    context
+        .navigator
+        .navigate_to_url(url, target, Some((method, vars)));
+
+    Ok(true.into())
+}
+
+fn send_and_load<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Object<'gc>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let url = match args.get(0) {
+        Some(Value::Undefined) | Some(Value::Null) | None => return Ok(false.into()),
+        Some(url) => url.coerce_to_string(activation, context)?.to_string(),
+    };
+    let target = match args.get(1) {
+        Some(Value::Object(target)) => *target,
+        _ => return Ok(false.into()),
+    };
+    let method = match args.get(2) {
+        Some(Value::Undefined) | None => NavigationMethod::POST,
+        Some(method) => {
+            NavigationMethod::from_method_str(&method.coerce_to_string(activation, context)?)
+                .unwrap_or(NavigationMethod::POST)
+        }
+    };
+
+    let query_string = form_urlencoded::Serializer::new(String::new())
+        .extend_pairs(object_into_form_values(activation, context, this)?)
+        .finish();
+    let headers = request_headers(activation, context, this)?;
+    let (url, options) = match method {
+        NavigationMethod::GET if url.contains('?') => {
+            (format!("{}&{}", url, query_string), RequestOptions::get())
+        }
+        NavigationMethod::GET => (format!("{}?{}", url, query_string), RequestOptions::get()),
+        NavigationMethod::POST => {
+            let content_type = this
+                .get("contentType", activation, context)?
+                .coerce_to_string(activation, context)?
+                .to_string();
+            (
+                url,
+                RequestOptions::post(Some((query_string.into_bytes(), content_type))),
+            )
+        }
+    };
+    spawn_load(
+        activation,
+        context,
+        target,
+        &url,
+        options.with_headers(headers),
+    )?;
+
+    Ok(true.into())
+}
+
+fn decode<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Object<'gc>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    if let Some(data) = args.get(0) {
+        let data = data.coerce_to_string(activation, context)?;
+        let variables = context
+            .system
+            .decode_form_data(&data, activation.current_swf_version());
+        for (name, value) in variables {
+            this.set(
+                AvmString::new(context.gc_context, name),
+                AvmString::new(context.gc_context, value).into(),
+                activation,
+                context,
+            )?;
+        }
+    }
+
+    Ok(Value::Undefined)
+}
+
+fn get_bytes_loaded<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Object<'gc>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    this.get("_bytesLoaded", activation, context)
+}
+
+fn get_bytes_total<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Object<'gc>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    this.get("_bytesTotal", activation, context)
+}
+
+fn on_data<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Object<'gc>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    match args.get(0) {
+        Some(Value::Undefined) | None => {
+            this.call_method("onLoad", &[false.into()], activation, context)?;
+        }
+        Some(data) => {
+            this.call_method("decode", &[data.clone()], activation, context)?;
+            this.define_value(
+                context.gc_context,
+                "loaded",
+                true.into(),
+                Attribute::DontEnum.into(),
+            );
+            this.call_method("onLoad", &[true.into()], activation, context)?;
+        }
+    }
+
+    Ok(Value::Undefined)
+}
+
+fn to_string<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Object<'gc>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let query_string = form_urlencoded::Serializer::new(String::new())
+        .extend_pairs(object_into_form_values(activation, context, this)?)
+        .finish();
+    Ok(AvmString::new(context.gc_context, query_string).into())
+}
+
+pub fn create_proto<'gc>(
+    gc_context: MutationContext<'gc, '_>,
+    proto: Object<'gc>,
+    fn_proto: Object<'gc>,
+) -> Object<'gc> {
+    let mut load_vars_proto = ScriptObject::object(gc_context, Some(proto));
+
+    load_vars_proto.define_value(
+        gc_context,
+        "contentType",
+        DEFAULT_CONTENT_TYPE.into(),
+        Attribute::DontEnum.into(),
+    );
+
+    load_vars_proto.force_set_function(
+        "addRequestHeader",
+        add_request_header,
+        gc_context,
+        EnumSet::empty(),
+        Some(fn_proto),
+    );
+    load_vars_proto.force_set_function(
+        "decode",
+        decode,
+        gc_context,
+        EnumSet::empty(),
+        Some(fn_proto),
+    );
+    load_vars_proto.force_set_function(
+        "getBytesLoaded",
+        get_bytes_loaded,
+        gc_context,
+        EnumSet::empty(),
+        Some(fn_proto),
+    );
+    load_vars_proto.force_set_function(
+        "getBytesTotal",
+        get_bytes_total,
+        gc_context,
+        EnumSet::empty(),
+        Some(fn_proto),
+    );
+    load_vars_proto.force_set_function("load", load, gc_context, EnumSet::empty(), Some(fn_proto));
+    load_vars_proto.force_set_function(
+        "onData",
+        on_data,
+        gc_context,
+        EnumSet::empty(),
+        Some(fn_proto),
+    );
+    load_vars_proto.force_set_function("send", send, gc_context, EnumSet::empty(), Some(fn_proto));
+    load_vars_proto.force_set_function(
+        "sendAndLoad",
+        send_and_load,
+        gc_context,
+        EnumSet::empty(),
+        Some(fn_proto),
+    );
+    load_vars_proto.force_set_function(
+        "toString",
+        to_string,
+        gc_context,
+        EnumSet::empty(),
+        Some(fn_proto),
+    );
+
+    load_vars_proto.into()
+}
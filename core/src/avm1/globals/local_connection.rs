@@ -0,0 +1,245 @@
+//! AVM1 LocalConnection object
+
+use crate::avm1::activation::Activation;
+use crate::avm1::amf;
+use crate::avm1::error::Error;
+use crate::avm1::script_object::ScriptObject;
+use crate::avm1::{Object, TObject, UpdateContext, Value};
+use enumset::EnumSet;
+use gc_arena::{Collect, MutationContext};
+use std::collections::HashMap;
+
+/// Methods of a `LocalConnection` object that can't be invoked by `send`.
+const RESERVED_METHODS: &[&str] = &[
+    "send",
+    "connect",
+    "close",
+    "domain",
+    "allowDomain",
+    "allowInsecureDomain",
+    "onStatus",
+];
+
+/// A message sent with `LocalConnection.send`, waiting to be delivered.
+struct LocalConnectionMessage<'gc> {
+    /// The `LocalConnection` object that sent the message.
+    sender: Object<'gc>,
+
+    /// The connection name of the receiver.
+    connection_name: String,
+
+    /// The method to call on the receiver.
+    method_name: String,
+
+    /// The arguments of the method call, in AMF0.
+    /// The arguments are serialized when the message is sent, so the receiver
+    /// gets copies of any objects rather than the objects themselves.
+    args: Vec<u8>,
+}
+
+/// Tracks the connection names claimed by AVM1 `LocalConnection` objects in
+/// this player, and the messages sent between them.
+pub struct LocalConnections<'gc> {
+    /// The receiving `LocalConnection` objects, by lowercase connection name.
+    connections: HashMap<String, Object<'gc>>,
+
+    /// Messages sent since the last update.
+    messages: Vec<LocalConnectionMessage<'gc>>,
+}
+
+unsafe impl<'gc> Collect for LocalConnections<'gc> {
+    fn trace(&self, cc: gc_arena::CollectionContext) {
+        for object in self.connections.values() {
+            object.trace(cc);
+        }
+        for message in &self.messages {
+            message.sender.trace(cc);
+        }
+    }
+}
+
+impl<'gc> LocalConnections<'gc> {
+    pub fn new() -> Self {
+        Self {
+            connections: HashMap::new(),
+            messages: Vec::new(),
+        }
+    }
+
+    /// Claims a connection name for a `LocalConnection` object.
+    ///
+    /// Returns `false` if the name is already taken, or if the object is already
+    /// connected.
+    pub fn connect(&mut self, name: &str, object: Object<'gc>) -> bool {
+        let name = name.to_lowercase();
+        if self.connections.contains_key(&name)
+            || self
+                .connections
+                .values()
+                .any(|connection| Object::ptr_eq(*connection, object))
+        {
+            return false;
+        }
+        self.connections.insert(name, object);
+        true
+    }
+
+    /// Releases the connection name claimed by a `LocalConnection` object.
+    pub fn close(&mut self, object: Object<'gc>) {
+        self.connections
+            .retain(|_, connection| !Object::ptr_eq(*connection, object));
+    }
+
+    /// Returns whether there are messages waiting to be delivered.
+    pub fn has_messages(&self) -> bool {
+        !self.messages.is_empty()
+    }
+}
+
+impl<'gc> Default for LocalConnections<'gc> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Delivers the messages sent with `LocalConnection.send` since the last call.
+///
+/// The method of each message is called on the receiving `LocalConnection`
+/// object, and `onStatus` is then called on the sender to report whether there
+/// was a receiver.
+pub fn deliver_messages<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+) {
+    let messages = std::mem::take(&mut context.local_connections.messages);
+    for message in messages {
+        let receiver = context
+            .local_connections
+            .connections
+            .get(&message.connection_name.to_lowercase())
+            .copied();
+
+        let level = if let Some(receiver) = receiver {
+            let args = amf::deserialize(activation, context, &message.args).unwrap_or_else(|e| {
+                log::warn!("LocalConnection: Unable to read arguments: {}", e);
+                vec![]
+            });
+            let _ = receiver.call_method(&message.method_name, &args, activation, context);
+            "status"
+        } else {
+            "error"
+        };
+
+        let info = ScriptObject::object(context.gc_context, Some(activation.avm.prototypes.object));
+        info.define_value(context.gc_context, "level", level.into(), EnumSet::empty());
+        let _ = message
+            .sender
+            .call_method("onStatus", &[info.into()], activation, context);
+    }
+}
+
+/// Implements `LocalConnection`
+pub fn constructor<'gc>(
+    _activation: &mut Activation<'_, 'gc>,
+    _context: &mut UpdateContext<'_, 'gc, '_>,
+    _this: Object<'gc>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    Ok(Value::Undefined)
+}
+
+fn connect<'gc>(
+    _activation: &mut Activation<'_, 'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Object<'gc>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let name = match args.get(0) {
+        Some(Value::String(name)) => name,
+        _ => return Ok(false.into()),
+    };
+
+    // TODO: Names not starting with an underscore should be prefixed with the
+    // domain of the movie, once movies know where they were loaded from.
+    Ok(context.local_connections.connect(name, this).into())
+}
+
+fn send<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Object<'gc>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let (connection_name, method_name) = match args {
+        [Value::String(connection_name), Value::String(method_name), ..] => {
+            (connection_name.to_string(), method_name.to_string())
+        }
+        _ => return Ok(false.into()),
+    };
+    if RESERVED_METHODS.contains(&method_name.as_str()) {
+        return Ok(false.into());
+    }
+
+    let message_args = match amf::serialize(activation, context, &args[2..]) {
+        Ok(message_args) => message_args,
+        Err(e) => {
+            log::warn!("LocalConnection.send: Unable to write arguments: {}", e);
+            return Ok(false.into());
+        }
+    };
+
+    context
+        .local_connections
+        .messages
+        .push(LocalConnectionMessage {
+            sender: this,
+            connection_name,
+            method_name,
+            args: message_args,
+        });
+
+    Ok(true.into())
+}
+
+fn close<'gc>(
+    _activation: &mut Activation<'_, 'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Object<'gc>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    context.local_connections.close(this);
+
+    Ok(Value::Undefined)
+}
+
+pub fn create_proto<'gc>(
+    gc_context: MutationContext<'gc, '_>,
+    proto: Object<'gc>,
+    fn_proto: Object<'gc>,
+) -> Object<'gc> {
+    let mut local_connection_proto = ScriptObject::object(gc_context, Some(proto));
+
+    local_connection_proto.force_set_function(
+        "connect",
+        connect,
+        gc_context,
+        EnumSet::empty(),
+        Some(fn_proto),
+    );
+    local_connection_proto.force_set_function(
+        "send",
+        send,
+        gc_context,
+        EnumSet::empty(),
+        Some(fn_proto),
+    );
+    local_connection_proto.force_set_function(
+        "close",
+        close,
+        gc_context,
+        EnumSet::empty(),
+        Some(fn_proto),
+    );
+
+    local_connection_proto.into()
+}
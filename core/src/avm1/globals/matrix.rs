@@ -3,7 +3,8 @@
 use crate::avm1::activation::Activation;
 use crate::avm1::error::Error;
 use crate::avm1::function::{Executable, FunctionObject};
-use crate::avm1::{Object, ScriptObject, TObject, Value};
+use crate::avm1::globals::point::{point_to_object, value_to_point};
+use crate::avm1::{AvmString, Object, ScriptObject, TObject, Value};
 use crate::context::UpdateContext;
 use enumset::EnumSet;
 use gc_arena::MutationContext;
@@ -115,8 +116,6 @@ pub fn object_to_matrix<'gc>(
     Ok(Matrix { a, b, c, d, tx, ty })
 }
 
-// We'll need this soon!
-#[allow(dead_code)]
 pub fn matrix_to_object<'gc>(
     matrix: Matrix,
     activation: &mut Activation<'_, 'gc>,
@@ -275,6 +274,47 @@ fn translate<'gc>(
     Ok(Value::Undefined)
 }
 
+fn transform_point<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Object<'gc>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let point = value_to_point(
+        args.get(0).unwrap_or(&Value::Undefined).to_owned(),
+        activation,
+        context,
+    )?;
+    let matrix = object_to_matrix(this, activation, context)?;
+    let (x, y) = (point.0 as f32, point.1 as f32);
+    let out = (
+        f64::from(matrix.a * x + matrix.c * y) + matrix.tx.to_pixels(),
+        f64::from(matrix.b * x + matrix.d * y) + matrix.ty.to_pixels(),
+    );
+    Ok(point_to_object(out, activation, context)?.into())
+}
+
+/// Like `transformPoint`, but ignores the translation of the matrix.
+fn delta_transform_point<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Object<'gc>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let point = value_to_point(
+        args.get(0).unwrap_or(&Value::Undefined).to_owned(),
+        activation,
+        context,
+    )?;
+    let matrix = object_to_matrix(this, activation, context)?;
+    let (x, y) = (point.0 as f32, point.1 as f32);
+    let out = (
+        f64::from(matrix.a * x + matrix.c * y),
+        f64::from(matrix.b * x + matrix.d * y),
+    );
+    Ok(point_to_object(out, activation, context)?.into())
+}
+
 fn concat<'gc>(
     activation: &mut Activation<'_, 'gc>,
     context: &mut UpdateContext<'_, 'gc, '_>,
@@ -403,14 +443,17 @@ fn to_string<'gc>(
     let tx = this.get("tx", activation, context)?;
     let ty = this.get("ty", activation, context)?;
 
-    Ok(format!(
-        "(a={}, b={}, c={}, d={}, tx={}, ty={})",
-        a.coerce_to_string(activation, context)?,
-        b.coerce_to_string(activation, context)?,
-        c.coerce_to_string(activation, context)?,
-        d.coerce_to_string(activation, context)?,
-        tx.coerce_to_string(activation, context)?,
-        ty.coerce_to_string(activation, context)?
+    Ok(AvmString::new(
+        context.gc_context,
+        format!(
+            "(a={}, b={}, c={}, d={}, tx={}, ty={})",
+            a.coerce_to_string(activation, context)?,
+            b.coerce_to_string(activation, context)?,
+            c.coerce_to_string(activation, context)?,
+            d.coerce_to_string(activation, context)?,
+            tx.coerce_to_string(activation, context)?,
+            ty.coerce_to_string(activation, context)?
+        ),
     )
     .into())
 }
@@ -487,6 +530,22 @@ pub fn create_proto<'gc>(
         Some(fn_proto),
     );
 
+    object.force_set_function(
+        "transformPoint",
+        transform_point,
+        gc_context,
+        EnumSet::empty(),
+        Some(fn_proto),
+    );
+
+    object.force_set_function(
+        "deltaTransformPoint",
+        delta_transform_point,
+        gc_context,
+        EnumSet::empty(),
+        Some(fn_proto),
+    );
+
     object.force_set_function(
         "createBox",
         create_box,
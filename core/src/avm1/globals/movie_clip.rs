@@ -2,12 +2,14 @@
 
 use crate::avm1::activation::Activation;
 use crate::avm1::error::Error;
+use crate::avm1::function::Executable;
 use crate::avm1::globals::display_object::{self, AVM_DEPTH_BIAS, AVM_MAX_DEPTH};
-use crate::avm1::globals::matrix::gradient_object_to_matrix;
+use crate::avm1::globals::matrix::{gradient_object_to_matrix, object_to_matrix};
+use crate::avm1::globals::rectangle::{object_to_rectangle, rectangle_to_object};
 use crate::avm1::property::Attribute::*;
-use crate::avm1::{Object, ScriptObject, TObject, UpdateContext, Value};
+use crate::avm1::{AvmString, Object, ScriptObject, TObject, UpdateContext, Value};
 use crate::backend::navigator::NavigationMethod;
-use crate::display_object::{DisplayObject, EditText, MovieClip, TDisplayObject};
+use crate::display_object::{Bitmap, DisplayObject, EditText, MovieClip, TDisplayObject};
 use crate::prelude::*;
 use crate::shape_utils::DrawCommand;
 use crate::tag_utils::SwfSlice;
@@ -34,7 +36,8 @@ macro_rules! with_movie_clip {
                 $name,
                 |activation, context: &mut UpdateContext<'_, 'gc, '_>, this, args| -> Result<Value<'gc>, Error<'gc>> {
                     if let Some(display_object) = this.as_display_object() {
-                        if let Some(movie_clip) = display_object.as_movie_clip() {
+                        // Methods of a removed clip do nothing.
+                        if let Some(movie_clip) = display_object.as_movie_clip().filter(|mc| !mc.dangling()) {
                             return $fn(movie_clip, activation, context, args);
                         }
                     }
@@ -62,16 +65,18 @@ pub fn hit_test<'gc>(
             .get(2)
             .map(|v| v.as_bool(activation.current_swf_version()))
             .unwrap_or(false);
-        if shape {
-            log::warn!("Ignoring shape hittest and using bounding box instead. Shape based hit detection is not yet implemented. See https://github.com/ruffle-rs/ruffle/issues/177");
-        }
         if x.is_finite() && y.is_finite() {
             // The docs say the point is in "Stage coordinates", but actually they are in root coordinates.
             // root can be moved via _root._x etc., so we actually have to transform from root to world space.
             let point = movie_clip
                 .root()
                 .local_to_global((Twips::from_pixels(x), Twips::from_pixels(y)));
-            return Ok(movie_clip.hit_test(point).into());
+            let hit = if shape {
+                movie_clip.hit_test_shape(point)
+            } else {
+                movie_clip.hit_test(point)
+            };
+            return Ok(hit.into());
         }
     } else if args.len() == 1 {
         let other = args
@@ -103,6 +108,7 @@ pub fn create_proto<'gc>(
         gc_context,
         object,
         Some(fn_proto),
+        "attachBitmap" => attach_bitmap,
         "attachMovie" => attach_movie,
         "createEmptyMovieClip" => create_empty_movie_clip,
         "createTextField" => create_text_field,
@@ -110,6 +116,7 @@ pub fn create_proto<'gc>(
         "getBounds" => get_bounds,
         "getBytesLoaded" => get_bytes_loaded,
         "getBytesTotal" => get_bytes_total,
+        "getInstanceAtDepth" => get_instance_at_depth,
         "getNextHighestDepth" => get_next_highest_depth,
         "getRect" => get_rect,
         "globalToLocal" => global_to_local,
@@ -123,22 +130,61 @@ pub fn create_proto<'gc>(
         "play" => play,
         "prevFrame" => prev_frame,
         "removeMovieClip" => remove_movie_clip,
+        "setMask" => set_mask,
         "startDrag" => start_drag,
         "stop" => stop,
         "stopDrag" => stop_drag,
         "swapDepths" => swap_depths,
-        "toString" => to_string,
         "unloadMovie" => unload_movie,
         "beginFill" => begin_fill,
         "beginGradientFill" => begin_gradient_fill,
+        "beginBitmapFill" => begin_bitmap_fill,
         "moveTo" => move_to,
         "lineTo" => line_to,
         "curveTo" => curve_to,
         "endFill" => end_fill,
         "lineStyle" => line_style,
+        "lineGradientStyle" => line_gradient_style,
         "clear" => clear
     );
 
+    object.force_set_function(
+        "toString",
+        to_string,
+        gc_context,
+        DontDelete | ReadOnly | DontEnum,
+        Some(fn_proto),
+    );
+
+    object.add_property(
+        gc_context,
+        "hitArea",
+        Executable::Native(hit_area),
+        Some(Executable::Native(set_hit_area)),
+        DontDelete | DontEnum,
+    );
+
+    object.add_property(
+        gc_context,
+        "_lockroot",
+        Executable::Native(lock_root),
+        Some(Executable::Native(set_lock_root)),
+        DontDelete | DontEnum,
+    );
+
+    object.add_property(
+        gc_context,
+        "scale9Grid",
+        Executable::Native(scale_9_grid),
+        Some(Executable::Native(set_scale_9_grid)),
+        DontDelete | DontEnum,
+    );
+
+    // Clips with button event handlers show the hand cursor and respond to the mouse
+    // unless these are cleared.
+    object.define_value(gc_context, "useHandCursor", true.into(), DontEnum.into());
+    object.define_value(gc_context, "enabled", true.into(), DontEnum.into());
+
     object.into()
 }
 
@@ -268,89 +314,169 @@ fn begin_gradient_fill<'gc>(
     context: &mut UpdateContext<'_, 'gc, '_>,
     args: &[Value<'gc>],
 ) -> Result<Value<'gc>, Error<'gc>> {
-    if let (Some(method), Some(colors), Some(alphas), Some(ratios), Some(matrix)) = (
-        args.get(0),
-        args.get(1),
-        args.get(2),
-        args.get(3),
-        args.get(4),
-    ) {
-        let method = method.coerce_to_string(activation, context)?;
-        let colors = colors.coerce_to_object(activation, context).array();
-        let alphas = alphas.coerce_to_object(activation, context).array();
-        let ratios = ratios.coerce_to_object(activation, context).array();
-        let matrix_object = matrix.coerce_to_object(activation, context);
-        if colors.len() != alphas.len() || colors.len() != ratios.len() {
-            log::warn!(
-                "beginGradientFill() received different sized arrays for colors, alphas and ratios"
-            );
-            return Ok(Value::Undefined);
-        }
-        let mut records = Vec::with_capacity(colors.len());
-        for i in 0..colors.len() {
-            let ratio = ratios[i]
-                .coerce_to_f64(activation, context)?
-                .min(255.0)
-                .max(0.0);
-            let rgb = colors[i].coerce_to_u32(activation, context)?;
-            let alpha = alphas[i]
-                .coerce_to_f64(activation, context)?
-                .min(100.0)
-                .max(0.0);
-            records.push(GradientRecord {
-                ratio: ratio as u8,
-                color: Color::from_rgb(rgb, (alpha / 100.0 * 255.0) as u8),
-            });
-        }
-        let matrix = gradient_object_to_matrix(matrix_object, activation, context)?;
-        let spread = match args
-            .get(5)
-            .and_then(|v| v.coerce_to_string(activation, context).ok())
-            .as_deref()
-        {
-            Some("reflect") => GradientSpread::Reflect,
-            Some("repeat") => GradientSpread::Repeat,
-            _ => GradientSpread::Pad,
-        };
-        let interpolation = match args
-            .get(6)
-            .and_then(|v| v.coerce_to_string(activation, context).ok())
-            .as_deref()
+    if args.len() >= 5 {
+        if let Some(style) =
+            gradient_fill_style_from_args("beginGradientFill", activation, context, args)?
         {
-            Some("linearRGB") => GradientInterpolation::LinearRGB,
-            _ => GradientInterpolation::RGB,
-        };
+            movie_clip.set_fill_style(context, Some(style));
+        }
+    } else {
+        movie_clip.set_fill_style(context, None);
+    }
+    Ok(Value::Undefined)
+}
 
-        let gradient = Gradient {
-            matrix,
-            spread,
-            interpolation,
-            records,
-        };
-        let style = match method.as_ref() {
-            "linear" => FillStyle::LinearGradient(gradient),
-            "radial" => {
-                if let Some(focal_point) = args.get(7) {
-                    FillStyle::FocalGradient {
-                        gradient,
-                        focal_point: focal_point.coerce_to_f64(activation, context)? as f32,
-                    }
-                } else {
-                    FillStyle::RadialGradient(gradient)
+fn begin_bitmap_fill<'gc>(
+    movie_clip: MovieClip<'gc>,
+    activation: &mut Activation<'_, 'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let bitmap_data = match args.get(0) {
+        Some(Value::Object(object)) => object
+            .as_bitmap_data_object()
+            .map(|object| object.bitmap_data()),
+        _ => None,
+    };
+    let id = bitmap_data.and_then(|data| data.read().register(context.renderer));
+    if let Some((id, _)) = id {
+        // Bitmap fill matrices map from bitmap pixels to twips.
+        let matrix = match args.get(1) {
+            Some(Value::Object(matrix)) => {
+                let matrix = object_to_matrix(*matrix, activation, context)?;
+                Matrix {
+                    a: matrix.a * 20.0,
+                    b: matrix.b * 20.0,
+                    c: matrix.c * 20.0,
+                    d: matrix.d * 20.0,
+                    ..matrix
                 }
             }
-            other => {
-                log::warn!("beginGradientFill() received invalid fill type {:?}", other);
-                return Ok(Value::Undefined);
-            }
+            _ => Matrix::scale(20.0, 20.0),
         };
-        movie_clip.set_fill_style(context, Some(style));
+        let is_repeating = args
+            .get(2)
+            .map_or(true, |v| v.as_bool(activation.current_swf_version()));
+        let is_smoothed = args
+            .get(3)
+            .map_or(false, |v| v.as_bool(activation.current_swf_version()));
+        movie_clip.set_fill_style(
+            context,
+            Some(FillStyle::Bitmap {
+                id,
+                matrix,
+                is_smoothed,
+                is_repeating,
+            }),
+        );
     } else {
         movie_clip.set_fill_style(context, None);
     }
     Ok(Value::Undefined)
 }
 
+fn line_gradient_style<'gc>(
+    movie_clip: MovieClip<'gc>,
+    activation: &mut Activation<'_, 'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    if args.len() >= 5 {
+        if let Some(style) =
+            gradient_fill_style_from_args("lineGradientStyle", activation, context, args)?
+        {
+            movie_clip.set_line_fill_style(context, style);
+        }
+    }
+    Ok(Value::Undefined)
+}
+
+/// Parses the `(fillType, colors, alphas, ratios, matrix, spreadMethod, interpolationMethod,
+/// focalPointRatio)` arguments shared by `beginGradientFill` and `lineGradientStyle`.
+/// Returns `None` if the arguments do not describe a valid gradient.
+fn gradient_fill_style_from_args<'gc>(
+    method_name: &str,
+    activation: &mut Activation<'_, 'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    args: &[Value<'gc>],
+) -> Result<Option<FillStyle>, Error<'gc>> {
+    let (method, colors, alphas, ratios, matrix) = match args {
+        [method, colors, alphas, ratios, matrix, ..] => (method, colors, alphas, ratios, matrix),
+        _ => return Ok(None),
+    };
+    let method = method.coerce_to_string(activation, context)?;
+    let colors = colors.coerce_to_object(activation, context).array();
+    let alphas = alphas.coerce_to_object(activation, context).array();
+    let ratios = ratios.coerce_to_object(activation, context).array();
+    let matrix_object = matrix.coerce_to_object(activation, context);
+    if colors.len() != alphas.len() || colors.len() != ratios.len() {
+        log::warn!(
+            "{}() received different sized arrays for colors, alphas and ratios",
+            method_name
+        );
+        return Ok(None);
+    }
+    let mut records = Vec::with_capacity(colors.len());
+    for i in 0..colors.len() {
+        let ratio = ratios[i]
+            .coerce_to_f64(activation, context)?
+            .min(255.0)
+            .max(0.0);
+        let rgb = colors[i].coerce_to_u32(activation, context)?;
+        let alpha = alphas[i]
+            .coerce_to_f64(activation, context)?
+            .min(100.0)
+            .max(0.0);
+        records.push(GradientRecord {
+            ratio: ratio as u8,
+            color: Color::from_rgb(rgb, (alpha / 100.0 * 255.0) as u8),
+        });
+    }
+    let matrix = gradient_object_to_matrix(matrix_object, activation, context)?;
+    let spread = match args
+        .get(5)
+        .and_then(|v| v.coerce_to_string(activation, context).ok())
+        .as_deref()
+    {
+        Some("reflect") => GradientSpread::Reflect,
+        Some("repeat") => GradientSpread::Repeat,
+        _ => GradientSpread::Pad,
+    };
+    let interpolation = match args
+        .get(6)
+        .and_then(|v| v.coerce_to_string(activation, context).ok())
+        .as_deref()
+    {
+        Some("linearRGB") => GradientInterpolation::LinearRGB,
+        _ => GradientInterpolation::RGB,
+    };
+
+    let gradient = Gradient {
+        matrix,
+        spread,
+        interpolation,
+        records,
+    };
+    let style = match method.as_ref() {
+        "linear" => FillStyle::LinearGradient(gradient),
+        "radial" => {
+            if let Some(focal_point) = args.get(7) {
+                FillStyle::FocalGradient {
+                    gradient,
+                    focal_point: focal_point.coerce_to_f64(activation, context)? as f32,
+                }
+            } else {
+                FillStyle::RadialGradient(gradient)
+            }
+        }
+        other => {
+            log::warn!("{}() received invalid fill type {:?}", method_name, other);
+            return Ok(None);
+        }
+    };
+    Ok(Some(style))
+}
+
 fn move_to<'gc>(
     movie_clip: MovieClip<'gc>,
     activation: &mut Activation<'_, 'gc>,
@@ -437,30 +563,71 @@ fn clear<'gc>(
     Ok(Value::Undefined)
 }
 
-fn attach_movie<'gc>(
+fn attach_bitmap<'gc>(
     mut movie_clip: MovieClip<'gc>,
     activation: &mut Activation<'_, 'gc>,
     context: &mut UpdateContext<'_, 'gc, '_>,
     args: &[Value<'gc>],
 ) -> Result<Value<'gc>, Error<'gc>> {
-    let (export_name, new_instance_name, depth) = match &args[0..3] {
-        [export_name, new_instance_name, depth] => (
-            export_name.coerce_to_string(activation, context)?,
-            new_instance_name.coerce_to_string(activation, context)?,
-            depth
-                .coerce_to_i32(activation, context)?
-                .wrapping_add(AVM_DEPTH_BIAS),
-        ),
-        _ => {
-            log::error!("MovieClip.attachMovie: Too few parameters");
+    let bitmap_data = match args.get(0) {
+        Some(Value::Object(object)) => object
+            .as_bitmap_data_object()
+            .map(|object| object.bitmap_data()),
+        _ => None,
+    };
+    if let Some(bitmap_data) = bitmap_data {
+        let depth = args
+            .get(1)
+            .cloned()
+            .unwrap_or(Value::Undefined)
+            .coerce_to_i32(activation, context)?
+            .wrapping_add(AVM_DEPTH_BIAS);
+        if !is_valid_avm_depth(depth) {
             return Ok(Value::Undefined);
         }
-    };
-    let init_object = args.get(3);
 
+        // TODO: Support the `pixelSnapping` argument.
+        let smoothing = args.get(3).map_or(false, |value| {
+            value.as_bool(activation.current_swf_version())
+        });
+        let bitmap: DisplayObject<'gc> =
+            Bitmap::new_with_bitmap_data(context, bitmap_data, smoothing).into();
+        movie_clip.add_child_from_avm(context, bitmap, depth);
+    }
+    Ok(Value::Undefined)
+}
+
+/// Whether a clip can be created at the given biased depth by `attachMovie`,
+/// `createEmptyMovieClip`, `duplicateMovieClip` or `attachBitmap`.
+fn is_valid_avm_depth(depth: Depth) -> bool {
     // TODO: What is the derivation of this max value? It shows up a few times in the AVM...
     // 2^31 - 16777220
-    if depth < 0 || depth > AVM_MAX_DEPTH {
+    (0..=AVM_MAX_DEPTH).contains(&depth)
+}
+
+fn attach_movie<'gc>(
+    mut movie_clip: MovieClip<'gc>,
+    activation: &mut Activation<'_, 'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let export_name = args
+        .get(0)
+        .unwrap_or(&Value::Undefined)
+        .coerce_to_string(activation, context)?;
+    let new_instance_name = args
+        .get(1)
+        .unwrap_or(&Value::Undefined)
+        .coerce_to_string(activation, context)?;
+    let depth = args
+        .get(2)
+        .cloned()
+        .unwrap_or(Value::Undefined)
+        .coerce_to_i32(activation, context)?
+        .wrapping_add(AVM_DEPTH_BIAS);
+    let init_object = args.get(3);
+
+    if !is_valid_avm_depth(depth) {
         return Ok(Value::Undefined);
     }
 
@@ -497,18 +664,20 @@ fn create_empty_movie_clip<'gc>(
     context: &mut UpdateContext<'_, 'gc, '_>,
     args: &[Value<'gc>],
 ) -> Result<Value<'gc>, Error<'gc>> {
-    let (new_instance_name, depth) = match &args[0..2] {
-        [new_instance_name, depth] => (
-            new_instance_name.coerce_to_string(activation, context)?,
-            depth
-                .coerce_to_i32(activation, context)?
-                .wrapping_add(AVM_DEPTH_BIAS),
-        ),
-        _ => {
-            log::error!("MovieClip.attachMovie: Too few parameters");
-            return Ok(Value::Undefined);
-        }
-    };
+    let new_instance_name = args
+        .get(0)
+        .unwrap_or(&Value::Undefined)
+        .coerce_to_string(activation, context)?;
+    let depth = args
+        .get(1)
+        .cloned()
+        .unwrap_or(Value::Undefined)
+        .coerce_to_i32(activation, context)?
+        .wrapping_add(AVM_DEPTH_BIAS);
+
+    if !is_valid_avm_depth(depth) {
+        return Ok(Value::Undefined);
+    }
 
     // Create empty movie clip.
     let swf_movie = movie_clip
@@ -598,18 +767,16 @@ pub fn duplicate_movie_clip_with_bias<'gc>(
     args: &[Value<'gc>],
     depth_bias: i32,
 ) -> Result<Value<'gc>, Error<'gc>> {
-    let (new_instance_name, depth) = match &args[0..2] {
-        [new_instance_name, depth] => (
-            new_instance_name.coerce_to_string(activation, context)?,
-            depth
-                .coerce_to_i32(activation, context)?
-                .wrapping_add(depth_bias),
-        ),
-        _ => {
-            log::error!("MovieClip.attachMovie: Too few parameters");
-            return Ok(Value::Undefined);
-        }
-    };
+    let new_instance_name = args
+        .get(0)
+        .unwrap_or(&Value::Undefined)
+        .coerce_to_string(activation, context)?;
+    let depth = args
+        .get(1)
+        .cloned()
+        .unwrap_or(Value::Undefined)
+        .coerce_to_i32(activation, context)?
+        .wrapping_add(depth_bias);
     let init_object = args.get(2);
 
     // Can't duplicate the root!
@@ -619,18 +786,26 @@ pub fn duplicate_movie_clip_with_bias<'gc>(
         return Ok(Value::Undefined);
     };
 
-    // TODO: What is the derivation of this max value? It shows up a few times in the AVM...
-    // 2^31 - 16777220
-    if depth < 0 || depth > AVM_MAX_DEPTH {
+    if !is_valid_avm_depth(depth) {
         return Ok(Value::Undefined);
     }
 
-    if let Ok(mut new_clip) = context
-        .library
-        .library_for_movie(movie_clip.movie().unwrap())
-        .ok_or_else(|| "Movie is missing!".into())
-        .and_then(|l| l.instantiate_by_id(movie_clip.id(), context.gc_context))
-    {
+    let new_clip = if movie_clip.id() == 0 {
+        // Clips created by `createEmptyMovieClip` aren't in the library.
+        Ok(MovieClip::new(
+            SwfSlice::empty(movie_clip.movie().unwrap()),
+            context.gc_context,
+        )
+        .into())
+    } else {
+        context
+            .library
+            .library_for_movie(movie_clip.movie().unwrap())
+            .ok_or_else(|| "Movie is missing!".into())
+            .and_then(|l| l.instantiate_by_id(movie_clip.id(), context.gc_context))
+    };
+
+    if let Ok(mut new_clip) = new_clip {
         // Set name and attach to parent.
         new_clip.set_name(context.gc_context, &new_instance_name);
         parent.add_child_from_avm(context, new_clip, depth);
@@ -638,8 +813,17 @@ pub fn duplicate_movie_clip_with_bias<'gc>(
         // Copy display properties from previous clip to new clip.
         new_clip.set_matrix(context.gc_context, &*movie_clip.matrix());
         new_clip.set_color_transform(context.gc_context, &*movie_clip.color_transform());
-        // TODO: Any other properties we should copy...?
-        // Definitely not ScriptObject properties.
+
+        // Clip event handlers and drawing API graphics are duplicated as well.
+        // Script properties and the current frame are not; the duplicate starts on frame 1.
+        if let Some(new_mc) = new_clip.as_movie_clip() {
+            new_mc.set_clip_actions(context.gc_context, movie_clip.clip_actions().to_vec());
+            new_mc.set_drawing(context.gc_context, movie_clip.drawing().duplicate());
+            // A duplicate of the clip a movie was loaded into shares that movie.
+            if movie_clip.holds_movie() {
+                new_mc.hold_movie(context);
+            }
+        }
 
         let init_object = init_object.map(|v| v.coerce_to_object(activation, context));
         new_clip.post_instantiation(activation.avm, context, new_clip, init_object, true);
@@ -656,23 +840,45 @@ pub fn duplicate_movie_clip_with_bias<'gc>(
 }
 
 fn get_bytes_loaded<'gc>(
-    _movie_clip: MovieClip<'gc>,
+    movie_clip: MovieClip<'gc>,
     _activation: &mut Activation<'_, 'gc>,
-    _context: &mut UpdateContext<'_, 'gc, '_>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
     _args: &[Value<'gc>],
 ) -> Result<Value<'gc>, Error<'gc>> {
-    // TODO find a correct value
-    Ok(1.0.into())
+    Ok(movie_clip.bytes_loaded(context).into())
 }
 
 fn get_bytes_total<'gc>(
-    _movie_clip: MovieClip<'gc>,
+    movie_clip: MovieClip<'gc>,
     _activation: &mut Activation<'_, 'gc>,
-    _context: &mut UpdateContext<'_, 'gc, '_>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
     _args: &[Value<'gc>],
 ) -> Result<Value<'gc>, Error<'gc>> {
-    // TODO find a correct value
-    Ok(1.0.into())
+    Ok(movie_clip.bytes_total(context).into())
+}
+
+fn get_instance_at_depth<'gc>(
+    movie_clip: MovieClip<'gc>,
+    activation: &mut Activation<'_, 'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    if activation.current_swf_version() >= 7 {
+        let depth = if let Some(depth) = args.get(0) {
+            depth
+                .coerce_to_i32(activation, context)?
+                .wrapping_add(AVM_DEPTH_BIAS)
+        } else {
+            log::error!("MovieClip.getInstanceAtDepth: Too few parameters");
+            return Ok(Value::Undefined);
+        };
+        match movie_clip.child_by_depth(depth) {
+            Some(child) => Ok(child.object()),
+            None => Ok(Value::Undefined),
+        }
+    } else {
+        Ok(Value::Undefined)
+    }
 }
 
 fn get_next_highest_depth<'gc>(
@@ -787,16 +993,17 @@ fn prev_frame<'gc>(
 
 fn remove_movie_clip<'gc>(
     movie_clip: MovieClip<'gc>,
-    _activation: &mut Activation<'_, 'gc>,
+    activation: &mut Activation<'_, 'gc>,
     context: &mut UpdateContext<'_, 'gc, '_>,
     _args: &[Value<'gc>],
 ) -> Result<Value<'gc>, Error<'gc>> {
     // removeMovieClip method uses biased depth compared to RemoveSprite
-    remove_movie_clip_with_bias(movie_clip, context, AVM_DEPTH_BIAS)
+    remove_movie_clip_with_bias(movie_clip, activation, context, AVM_DEPTH_BIAS)
 }
 
 pub fn remove_movie_clip_with_bias<'gc>(
     movie_clip: MovieClip<'gc>,
+    activation: &mut Activation<'_, 'gc>,
     context: &mut UpdateContext<'_, 'gc, '_>,
     depth_bias: i32,
 ) -> Result<Value<'gc>, Error<'gc>> {
@@ -813,7 +1020,142 @@ pub fn remove_movie_clip_with_bias<'gc>(
             return Ok(Value::Undefined);
         };
 
-        parent.remove_child_from_avm(context, movie_clip.into());
+        parent.remove_child_from_avm(activation, context, movie_clip.into());
+    }
+    Ok(Value::Undefined)
+}
+
+fn set_mask<'gc>(
+    movie_clip: MovieClip<'gc>,
+    activation: &mut Activation<'_, 'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let mut movie_clip: DisplayObject<'gc> = movie_clip.into();
+    let masker = match args.get(0).cloned().unwrap_or(Value::Undefined) {
+        // Passing `null` or `undefined` removes the mask.
+        Value::Undefined | Value::Null => None,
+        mask => {
+            if let Some(masker) =
+                activation.resolve_target_display_object(context, movie_clip, mask)?
+            {
+                Some(masker)
+            } else {
+                log::warn!("MovieClip.setMask: Invalid mask");
+                return Ok(false.into());
+            }
+        }
+    };
+
+    movie_clip.clear_mask_relationships(context.gc_context);
+    if let Some(mut masker) = masker {
+        // A mask can only mask a single object at a time.
+        // Its own mask is kept, but ignored while it is used as a mask.
+        if let Some(mut prev_maskee) = masker.maskee() {
+            prev_maskee.set_masker(context.gc_context, None);
+        }
+        masker.set_maskee(context.gc_context, Some(movie_clip));
+        movie_clip.set_masker(context.gc_context, Some(masker));
+    }
+
+    Ok(true.into())
+}
+
+fn hit_area<'gc>(
+    _activation: &mut Activation<'_, 'gc>,
+    _context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Object<'gc>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    Ok(this
+        .as_display_object()
+        .and_then(|o| o.as_movie_clip())
+        .and_then(|movie_clip| movie_clip.hit_area())
+        .map_or(Value::Undefined, |hit_area| hit_area.object()))
+}
+
+fn set_hit_area<'gc>(
+    _activation: &mut Activation<'_, 'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Object<'gc>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    if let Some(movie_clip) = this.as_display_object().and_then(|o| o.as_movie_clip()) {
+        // Anything other than a display object clears the hit area.
+        let hit_area = match args.get(0) {
+            Some(Value::Object(object)) => object.as_display_object(),
+            _ => None,
+        };
+        movie_clip.set_hit_area(context.gc_context, hit_area);
+    }
+    Ok(Value::Undefined)
+}
+
+fn lock_root<'gc>(
+    _activation: &mut Activation<'_, 'gc>,
+    _context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Object<'gc>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    Ok(this
+        .as_display_object()
+        .map_or(Value::Undefined, |display_object| {
+            display_object.lock_root().into()
+        }))
+}
+
+fn set_lock_root<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Object<'gc>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    if let Some(mut display_object) = this.as_display_object() {
+        let lock_root = args
+            .get(0)
+            .unwrap_or(&Value::Undefined)
+            .as_bool(activation.current_swf_version());
+        display_object.set_lock_root(context.gc_context, lock_root);
+    }
+    Ok(Value::Undefined)
+}
+
+fn scale_9_grid<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Object<'gc>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let scaling_grid = this
+        .as_display_object()
+        .and_then(|display_object| display_object.as_movie_clip())
+        .and_then(|movie_clip| movie_clip.scaling_grid());
+    if let Some(scaling_grid) = scaling_grid {
+        Ok(rectangle_to_object(&scaling_grid, activation, context)?.into())
+    } else {
+        Ok(Value::Null)
+    }
+}
+
+/// Sets the 9-slice scaling grid from the `x`, `y`, `width` and `height` of the given
+/// object. Setting `null` or `undefined` removes the grid.
+fn set_scale_9_grid<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Object<'gc>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    if let Some(movie_clip) = this
+        .as_display_object()
+        .and_then(|display_object| display_object.as_movie_clip())
+    {
+        let scaling_grid = if let Some(Value::Object(rect)) = args.get(0) {
+            Some(object_to_rectangle(*rect, activation, context)?)
+        } else {
+            None
+        };
+        movie_clip.set_scaling_grid(context.gc_context, scaling_grid);
+        movie_clip.invalidate_cached_bitmap();
     }
     Ok(Value::Undefined)
 }
@@ -894,13 +1236,18 @@ fn swap_depths<'gc>(
     Ok(Value::Undefined)
 }
 
+/// Returns the path of the clip, or an empty string once the clip has been removed.
 fn to_string<'gc>(
-    movie_clip: MovieClip<'gc>,
     _activation: &mut Activation<'_, 'gc>,
-    _context: &mut UpdateContext<'_, 'gc, '_>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Object<'gc>,
     _args: &[Value<'gc>],
 ) -> Result<Value<'gc>, Error<'gc>> {
-    Ok(movie_clip.path().into())
+    match this.as_display_object().and_then(|o| o.as_movie_clip()) {
+        Some(movie_clip) if movie_clip.dangling() => Ok("".into()),
+        Some(movie_clip) => Ok(AvmString::new(context.gc_context, movie_clip.path()).into()),
+        None => Ok(Value::Undefined),
+    }
 }
 
 fn local_to_global<'gc>(
@@ -942,6 +1289,7 @@ fn get_bounds<'gc>(
         Some(Value::Object(o)) if o.as_display_object().is_some() => o.as_display_object(),
         Some(val) => {
             let path = val.coerce_to_string(activation, context)?;
+            let path = AvmString::new(context.gc_context, path);
             activation.resolve_target_display_object(context, movie_clip.into(), path.into())?
         }
         None => Some(movie_clip.into()),
@@ -1041,14 +1389,20 @@ fn load_movie<'gc>(
 ) -> Result<Value<'gc>, Error<'gc>> {
     let url_val = args.get(0).cloned().unwrap_or(Value::Undefined);
     let url = url_val.coerce_to_string(activation, context)?;
+    if url.is_empty() {
+        // Loading an empty URL unloads the clip instead.
+        return unload_movie(target, activation, context, &[]);
+    }
+
     let method = args.get(1).cloned().unwrap_or(Value::Undefined);
     let method = NavigationMethod::from_method_str(&method.coerce_to_string(activation, context)?);
     let (url, opts) = activation.locals_into_request_options(context, url, method);
-    let fetch = context.navigator.fetch(&url, opts);
+    let fetch = context.navigator.fetch_stream(&url, opts);
     let process = context.load_manager.load_movie_into_clip(
         context.player.clone().unwrap(),
         DisplayObject::MovieClip(target),
         fetch,
+        url.into_owned(),
         None,
     );
 
@@ -1086,8 +1440,7 @@ fn unload_movie<'gc>(
     context: &mut UpdateContext<'_, 'gc, '_>,
     _args: &[Value<'gc>],
 ) -> Result<Value<'gc>, Error<'gc>> {
-    target.unload(context);
-    target.replace_with_movie(context.gc_context, None);
+    target.unload_movie(context);
 
     Ok(Value::Undefined)
 }
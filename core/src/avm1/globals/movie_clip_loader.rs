@@ -99,7 +99,8 @@ pub fn broadcast_message<'gc>(
 ) -> Result<Value<'gc>, Error<'gc>> {
     let event_name_val = args.get(0).cloned().unwrap_or(Value::Undefined);
     let event_name = event_name_val.coerce_to_string(activation, context)?;
-    let call_args = &args[0..];
+    // Listeners receive the event's arguments without the event name.
+    let call_args = args.get(1..).unwrap_or_default();
 
     let listeners = this.get("_listeners", activation, context)?;
     if let Value::Object(listeners) = listeners {
@@ -130,11 +131,12 @@ pub fn load_clip<'gc>(
             .as_display_object()
             .and_then(|dobj| dobj.as_movie_clip())
         {
-            let fetch = context.navigator.fetch(&url, RequestOptions::get());
+            let fetch = context.navigator.fetch_stream(&url, RequestOptions::get());
             let process = context.load_manager.load_movie_into_clip(
                 context.player.clone().unwrap(),
                 DisplayObject::MovieClip(movieclip),
                 fetch,
+                url.to_string(),
                 Some(this),
             );
 
@@ -160,8 +162,7 @@ pub fn unload_clip<'gc>(
             .as_display_object()
             .and_then(|dobj| dobj.as_movie_clip())
         {
-            movieclip.unload(context);
-            movieclip.replace_with_movie(context.gc_context, None);
+            movieclip.unload_movie(context);
 
             return Ok(true.into());
         }
@@ -187,19 +188,13 @@ pub fn get_progress<'gc>(
             ret_obj.define_value(
                 context.gc_context,
                 "bytesLoaded",
-                movieclip
-                    .movie()
-                    .map(|mv| (mv.data().len() + 21).into())
-                    .unwrap_or(Value::Undefined),
+                movieclip.bytes_loaded(context).into(),
                 EnumSet::empty(),
             );
             ret_obj.define_value(
                 context.gc_context,
                 "bytesTotal",
-                movieclip
-                    .movie()
-                    .map(|mv| (mv.data().len() + 21).into())
-                    .unwrap_or(Value::Undefined),
+                movieclip.bytes_total(context).into(),
                 EnumSet::empty(),
             );
 
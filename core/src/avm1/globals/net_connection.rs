@@ -0,0 +1,117 @@
+//! AVM1 NetConnection object
+
+use crate::avm1::activation::Activation;
+use crate::avm1::error::Error;
+use crate::avm1::script_object::ScriptObject;
+use crate::avm1::{AvmString, Object, TObject, UpdateContext, Value};
+use crate::context::ActionType;
+use enumset::EnumSet;
+use gc_arena::MutationContext;
+
+/// Queues a call to the `onStatus` handler of a `NetConnection` or `NetStream`, with an
+/// info object describing the event.
+pub fn queue_status<'gc>(
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    object: Object<'gc>,
+    level: &'static str,
+    code: &'static str,
+) {
+    let info = ScriptObject::object(context.gc_context, Some(context.system_prototypes.object));
+    info.define_value(context.gc_context, "level", level.into(), EnumSet::empty());
+    info.define_value(context.gc_context, "code", code.into(), EnumSet::empty());
+    let level0 = *context.levels.get(&0).unwrap();
+    context.action_queue.queue_actions(
+        level0,
+        ActionType::Method {
+            object,
+            name: "onStatus",
+            args: vec![info.into()],
+        },
+        false,
+    );
+}
+
+pub fn constructor<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Object<'gc>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    this.set("isConnected", false.into(), activation, context)?;
+    Ok(Value::Undefined)
+}
+
+/// `connect(null)` opens a connection for playing FLV files over HTTP.
+/// Connections to media servers are not supported.
+pub fn connect<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Object<'gc>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    match args.get(0).unwrap_or(&Value::Undefined) {
+        Value::Null | Value::Undefined => {
+            this.set("isConnected", true.into(), activation, context)?;
+            this.set("uri", "null".into(), activation, context)?;
+            queue_status(context, this, "status", "NetConnection.Connect.Success");
+            Ok(true.into())
+        }
+        uri => {
+            let uri = uri.coerce_to_string(activation, context)?;
+            log::warn!(
+                "NetConnection.connect: Connecting to {} is not supported; only FLV files can be played",
+                uri
+            );
+            this.set("isConnected", false.into(), activation, context)?;
+            this.set(
+                "uri",
+                AvmString::new(context.gc_context, uri).into(),
+                activation,
+                context,
+            )?;
+            queue_status(context, this, "error", "NetConnection.Connect.Failed");
+            Ok(false.into())
+        }
+    }
+}
+
+pub fn close<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Object<'gc>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let was_connected = this
+        .get("isConnected", activation, context)?
+        .as_bool(activation.current_swf_version());
+    this.set("isConnected", false.into(), activation, context)?;
+    if was_connected {
+        queue_status(context, this, "status", "NetConnection.Connect.Closed");
+    }
+    Ok(Value::Undefined)
+}
+
+pub fn create_proto<'gc>(
+    gc_context: MutationContext<'gc, '_>,
+    proto: Object<'gc>,
+    fn_proto: Object<'gc>,
+) -> Object<'gc> {
+    let mut net_connection_proto = ScriptObject::object(gc_context, Some(proto));
+
+    net_connection_proto.force_set_function(
+        "connect",
+        connect,
+        gc_context,
+        EnumSet::empty(),
+        Some(fn_proto),
+    );
+    net_connection_proto.force_set_function(
+        "close",
+        close,
+        gc_context,
+        EnumSet::empty(),
+        Some(fn_proto),
+    );
+
+    net_connection_proto.into()
+}
@@ -0,0 +1,875 @@
+//! AVM1 NetStream object
+
+use crate::avm1::activation::Activation;
+use crate::avm1::amf::Amf0Reader;
+use crate::avm1::error::Error;
+use crate::avm1::function::{Executable, NativeFunction};
+use crate::avm1::globals::net_connection::queue_status;
+use crate::avm1::property::Attribute::*;
+use crate::avm1::script_object::ScriptObject;
+use crate::avm1::{Object, TObject, UpdateContext, Value};
+use crate::backend::audio::{AudioBackend, AudioManager, SoundHandle, SoundInstanceHandle};
+use crate::backend::navigator::RequestOptions;
+use crate::backend::video::{EncodedFrame, VideoStreamHandle};
+use crate::context::ActionType;
+use crate::display_object::{DisplayObject, Video};
+use crate::flv::{FlvReader, FlvTagData};
+use crate::loader::Handle;
+use enumset::EnumSet;
+use gc_arena::{Collect, MutationContext};
+use swf::{AudioCompression, SoundFormat, VideoCodec, VideoDeblocking};
+
+/// The default `bufferTime` of a stream, in seconds.
+const DEFAULT_BUFFER_TIME: f64 = 0.1;
+
+/// A video frame of an FLV file.
+struct VideoPacket {
+    /// The time at which the frame is shown, in milliseconds.
+    timestamp: f64,
+
+    codec: VideoCodec,
+    is_keyframe: bool,
+    data: Vec<u8>,
+}
+
+/// The audio and video of an FLV file, read as the file loads.
+struct FlvMedia {
+    /// The position in the file of the next tag to read.
+    position: usize,
+
+    video_frames: Vec<VideoPacket>,
+
+    audio_format: Option<SoundFormat>,
+    audio_data: Vec<u8>,
+
+    /// The audio track, registered with the audio backend from the audio that had
+    /// loaded at the time.
+    sound: Option<SoundHandle>,
+
+    /// The length of the audio data registered as `sound`.
+    sound_len: usize,
+
+    /// The time at which the audio track starts, in milliseconds.
+    sound_start: f64,
+
+    /// The time of the last tag that has loaded, in milliseconds.
+    duration: f64,
+
+    /// Whether the whole file has loaded.
+    is_complete: bool,
+
+    has_unsupported_codec: bool,
+}
+
+impl FlvMedia {
+    fn new() -> Self {
+        Self {
+            position: 0,
+            video_frames: Vec::new(),
+            audio_format: None,
+            audio_data: Vec::new(),
+            sound: None,
+            sound_len: 0,
+            sound_start: 0.0,
+            duration: 0.0,
+            is_complete: false,
+            has_unsupported_codec: false,
+        }
+    }
+
+    /// Reads the tags of the file that have loaded since the last call, given the data
+    /// of the file loaded so far.
+    ///
+    /// Returns the AMF0 data of the script data tags that were read.
+    fn read_tags<'a>(
+        &mut self,
+        data: &'a [u8],
+        is_complete: bool,
+    ) -> Result<Vec<&'a [u8]>, crate::flv::Error> {
+        self.is_complete = is_complete;
+        let mut reader = if self.position == 0 {
+            match FlvReader::new(data) {
+                Ok(reader) => reader,
+                Err(crate::flv::Error::UnexpectedEof) if !is_complete => return Ok(vec![]),
+                Err(e) => return Err(e),
+            }
+        } else {
+            FlvReader::resume(data, self.position)
+        };
+
+        let mut script_data = vec![];
+        loop {
+            let tag = match reader.read_tag() {
+                Ok(Some(tag)) => tag,
+                Ok(None) => break,
+                Err(e) => {
+                    // The rest of the tag has yet to load.
+                    if is_complete {
+                        log::warn!("NetStream: FLV file is truncated: {}", e);
+                    }
+                    break;
+                }
+            };
+            let timestamp = f64::from(tag.timestamp);
+            self.duration = self.duration.max(timestamp);
+            match tag.data {
+                FlvTagData::Audio {
+                    format: Some(format),
+                    data,
+                } => {
+                    // All audio packets of a file have the same format.
+                    if self.audio_format.is_none() {
+                        self.audio_format = Some(format);
+                        self.sound_start = timestamp;
+                    }
+                    self.audio_data.extend_from_slice(data);
+                }
+                FlvTagData::Video {
+                    codec: Some(codec),
+                    is_keyframe,
+                    data,
+                } => self.video_frames.push(VideoPacket {
+                    timestamp,
+                    codec,
+                    is_keyframe,
+                    data: data.to_vec(),
+                }),
+                FlvTagData::Audio { format: None, .. } | FlvTagData::Video { codec: None, .. } => {
+                    if !self.has_unsupported_codec {
+                        log::warn!("NetStream: FLV file uses a codec that is not supported");
+                        self.has_unsupported_codec = true;
+                    }
+                }
+                FlvTagData::ScriptData(data) => script_data.push(data),
+                FlvTagData::Unknown => (),
+            }
+        }
+        self.position = reader.position();
+        Ok(script_data)
+    }
+
+    /// Registers the audio that has loaded so far as the audio track, replacing the
+    /// track registered before.
+    fn update_sound(&mut self, audio: &mut dyn AudioBackend) {
+        // Once the audio has failed to register, it isn't tried again.
+        let is_registrable = self.sound.is_some() || self.sound_len == 0;
+        let format = match &self.audio_format {
+            Some(format) if is_registrable && self.audio_data.len() > self.sound_len => {
+                format.clone()
+            }
+            _ => return,
+        };
+        if let Some(sound) = self.sound.take() {
+            audio.release_sound(sound);
+        }
+        self.sound = register_sound(audio, format, self.audio_data.clone());
+        self.sound_len = self.audio_data.len();
+    }
+}
+
+/// Registers the audio track of an FLV file with the audio backend.
+fn register_sound(
+    audio: &mut dyn AudioBackend,
+    format: SoundFormat,
+    data: Vec<u8>,
+) -> Option<SoundHandle> {
+    let result = match format.compression {
+        AudioCompression::Mp3 => audio.register_mp3(&data),
+        AudioCompression::Uncompressed | AudioCompression::UncompressedUnknownEndian => {
+            let channels = if format.is_stereo { 2 } else { 1 };
+            let sample_size = if format.is_16_bit { 2 } else { 1 };
+            audio.register_sound(&swf::Sound {
+                id: 0,
+                num_samples: (data.len() / (channels * sample_size)) as u32,
+                format,
+                data,
+            })
+        }
+        // Each packet of the other formats is encoded separately, so the packets
+        // can't be joined into a single sound.
+        _ => Err(format!("{:?} audio is not supported", format.compression).into()),
+    };
+    match result {
+        Ok(sound) => Some(sound),
+        Err(e) => {
+            log::warn!("NetStream: Unable to play audio: {}", e);
+            None
+        }
+    }
+}
+
+/// The playback state of a `NetStream` object.
+struct NetStream<'gc> {
+    object: Object<'gc>,
+
+    /// The `Video`s that show the frames of this stream, attached with `attachVideo`.
+    videos: Vec<Video<'gc>>,
+
+    /// The loader of the FLV file passed to `play`, while it is loading.
+    loader: Option<Handle>,
+
+    media: Option<FlvMedia>,
+
+    /// The video stream that decodes the frames of the media.
+    video_stream: Option<VideoStreamHandle>,
+
+    /// The index of the next video frame to decode.
+    next_frame: usize,
+
+    /// The playback position, in milliseconds.
+    time: f64,
+
+    /// Whether the stream is playing, from `NetStream.Play.Start` to `NetStream.Play.Stop`.
+    is_playing: bool,
+
+    /// Whether playback is waiting for `bufferTime` seconds of the stream to load,
+    /// from `NetStream.Buffer.Empty` to `NetStream.Buffer.Full`.
+    is_buffering: bool,
+
+    is_paused: bool,
+
+    /// The playing audio track. While it plays, it drives the playback position,
+    /// which keeps the video in sync with it.
+    sound_instance: Option<SoundInstanceHandle>,
+
+    bytes_loaded: usize,
+    bytes_total: usize,
+
+    /// The number of seconds to buffer before playing, set by `setBufferTime`.
+    buffer_time: f64,
+}
+
+impl<'gc> NetStream<'gc> {
+    fn new(object: Object<'gc>) -> Self {
+        Self {
+            object,
+            videos: Vec::new(),
+            loader: None,
+            media: None,
+            video_stream: None,
+            next_frame: 0,
+            time: 0.0,
+            is_playing: false,
+            is_buffering: false,
+            is_paused: false,
+            sound_instance: None,
+            bytes_loaded: 0,
+            bytes_total: 0,
+            buffer_time: DEFAULT_BUFFER_TIME,
+        }
+    }
+
+    /// Starts playing the audio track from the current position, with all of the
+    /// audio that has loaded so far.
+    fn start_sound(&mut self, audio_manager: &mut AudioManager<'gc>, audio: &mut dyn AudioBackend) {
+        self.stop_sound(audio_manager, audio);
+        let (sound, sound_start) = match &mut self.media {
+            Some(media) => {
+                media.update_sound(audio);
+                match media.sound {
+                    Some(sound) => (sound, media.sound_start),
+                    None => return,
+                }
+            }
+            None => return,
+        };
+
+        // Start points are in 44.1kHz samples, regardless of the sound's sample rate.
+        let in_sample = ((self.time - sound_start) * 44.1).max(0.0) as u32;
+        self.sound_instance = audio_manager.start_sound(
+            audio,
+            sound,
+            &swf::SoundInfo {
+                event: swf::SoundEvent::Start,
+                in_sample: if in_sample > 0 { Some(in_sample) } else { None },
+                out_sample: None,
+                num_loops: 1,
+                envelope: None,
+            },
+            None,
+            None,
+        );
+    }
+
+    fn stop_sound(&mut self, audio_manager: &mut AudioManager<'gc>, audio: &mut dyn AudioBackend) {
+        if let Some(instance) = self.sound_instance.take() {
+            audio_manager.stop_sound(audio, instance);
+        }
+    }
+
+    /// Whether enough of the stream has loaded ahead of the playback position for it
+    /// to play.
+    fn is_buffered(&self) -> bool {
+        match &self.media {
+            Some(media) => {
+                media.is_complete || media.duration - self.time >= self.buffer_time * 1000.0
+            }
+            None => false,
+        }
+    }
+}
+
+/// Tracks the playback of AVM1 `NetStream` objects, and the `Video`s attached to them.
+pub struct NetStreams<'gc> {
+    streams: Vec<NetStream<'gc>>,
+}
+
+unsafe impl<'gc> Collect for NetStreams<'gc> {
+    fn trace(&self, cc: gc_arena::CollectionContext) {
+        for stream in &self.streams {
+            stream.object.trace(cc);
+            stream.videos.trace(cc);
+        }
+    }
+}
+
+impl<'gc> NetStreams<'gc> {
+    pub fn new() -> Self {
+        Self {
+            streams: Vec::new(),
+        }
+    }
+
+    fn get(&self, object: Object<'gc>) -> Option<&NetStream<'gc>> {
+        self.streams
+            .iter()
+            .find(|stream| Object::ptr_eq(stream.object, object))
+    }
+
+    fn get_mut(&mut self, object: Object<'gc>) -> Option<&mut NetStream<'gc>> {
+        self.streams
+            .iter_mut()
+            .find(|stream| Object::ptr_eq(stream.object, object))
+    }
+
+    /// Returns the state of a `NetStream` object, which is created when the stream
+    /// is first used.
+    fn get_or_insert(&mut self, object: Object<'gc>) -> &mut NetStream<'gc> {
+        match self
+            .streams
+            .iter()
+            .position(|stream| Object::ptr_eq(stream.object, object))
+        {
+            Some(index) => &mut self.streams[index],
+            None => {
+                self.streams.push(NetStream::new(object));
+                self.streams.last_mut().unwrap()
+            }
+        }
+    }
+}
+
+impl<'gc> Default for NetStreams<'gc> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Shows the frames of a `NetStream` in a `Video`, or stops showing any stream
+/// if `source` is `None`.
+pub fn attach_video<'gc>(
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    source: Option<Object<'gc>>,
+    video: Video<'gc>,
+) {
+    for stream in &mut context.net_streams.streams {
+        stream
+            .videos
+            .retain(|attached| !DisplayObject::ptr_eq((*attached).into(), video.into()));
+    }
+    if let Some(source) = source {
+        context.net_streams.get_or_insert(source).videos.push(video);
+    }
+}
+
+/// Stops a stream and discards its media.
+fn close_stream<'gc>(context: &mut UpdateContext<'_, 'gc, '_>, object: Object<'gc>) {
+    if let Some(stream) = context.net_streams.get_mut(object) {
+        stream.stop_sound(context.audio_manager, context.audio);
+        if let Some(video_stream) = stream.video_stream.take() {
+            context.video.unregister_video_stream(video_stream);
+        }
+        if let Some(sound) = stream.media.take().and_then(|media| media.sound) {
+            context.audio.release_sound(sound);
+        }
+        stream.loader = None;
+        stream.next_frame = 0;
+        stream.time = 0.0;
+        stream.is_playing = false;
+        stream.is_buffering = false;
+        stream.is_paused = false;
+        stream.bytes_loaded = 0;
+        stream.bytes_total = 0;
+    }
+}
+
+/// Reads the part of an FLV file that its loader has fetched so far, and starts playing it
+/// once `bufferTime` seconds of it have loaded.
+///
+/// `data` is `None` if the file could not be fetched. `bytes_total` is the size of the file,
+/// if known, and `is_complete` is set once the whole file has loaded.
+pub fn stream_data<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    object: Object<'gc>,
+    loader: Handle,
+    data: Option<&[u8]>,
+    bytes_total: Option<usize>,
+    is_complete: bool,
+) {
+    // The stream may have been closed or started playing another file since.
+    let stream = match context.net_streams.get_mut(object) {
+        Some(stream) if stream.loader == Some(loader) => stream,
+        _ => return,
+    };
+    if is_complete {
+        stream.loader = None;
+    }
+
+    let data = match data {
+        Some(data) => data,
+        None => {
+            queue_status(context, object, "error", "NetStream.Play.StreamNotFound");
+            return;
+        }
+    };
+    stream.bytes_loaded = data.len();
+    stream.bytes_total = bytes_total.unwrap_or(data.len());
+
+    let media = stream.media.get_or_insert_with(FlvMedia::new);
+    let script_data = match media.read_tags(data, is_complete) {
+        Ok(script_data) => script_data,
+        Err(e) => {
+            log::warn!("NetStream.play: Invalid FLV file: {}", e);
+            close_stream(context, object);
+            queue_status(
+                context,
+                object,
+                "error",
+                "NetStream.Play.FileStructureInvalid",
+            );
+            return;
+        }
+    };
+    if media.position == 0 {
+        // The header of the file has yet to load.
+        return;
+    }
+
+    if stream.video_stream.is_none() {
+        if let Some(frame) = media.video_frames.first() {
+            match context.video.register_video_stream(
+                frame.codec,
+                None,
+                VideoDeblocking::UseVideoPacketValue,
+            ) {
+                Ok(video_stream) => stream.video_stream = Some(video_stream),
+                Err(e) => {
+                    log::error!("NetStream.play: Unable to play video: {}", e);
+                    close_stream(context, object);
+                    queue_status(context, object, "error", "NetStream.Play.Failed");
+                    return;
+                }
+            }
+        }
+    }
+
+    if !stream.is_playing {
+        stream.is_playing = true;
+        stream.is_buffering = true;
+        queue_status(context, object, "status", "NetStream.Play.Start");
+    }
+
+    // Script data tags call a handler of the stream with a value, such as `onMetaData`.
+    for data in script_data {
+        let mut reader = Amf0Reader::new(data);
+        let name = reader.read_value(activation, context);
+        let value = reader.read_value(activation, context);
+        let name = match name {
+            Ok(Value::String(name)) if name == "onMetaData" => "onMetaData",
+            Ok(Value::String(name)) if name == "onCuePoint" => "onCuePoint",
+            _ => continue,
+        };
+        if let Ok(value) = value {
+            let level0 = *context.levels.get(&0).unwrap();
+            context.action_queue.queue_actions(
+                level0,
+                ActionType::Method {
+                    object,
+                    name,
+                    args: vec![value],
+                },
+                false,
+            );
+        }
+    }
+
+    let stream = match context.net_streams.get_mut(object) {
+        Some(stream) => stream,
+        None => return,
+    };
+    if stream.is_buffering {
+        if stream.is_buffered() {
+            stream.is_buffering = false;
+            if !stream.is_paused {
+                stream.start_sound(context.audio_manager, context.audio);
+            }
+            queue_status(context, object, "status", "NetStream.Buffer.Full");
+        }
+    } else if is_complete && stream.sound_instance.is_some() {
+        // The audio track carries on with the rest of the audio.
+        stream.start_sound(context.audio_manager, context.audio);
+    }
+}
+
+/// Advances the playing streams by `dt` milliseconds, showing the video frames that are due.
+///
+/// Returns whether any `Video`s were updated.
+pub fn update_net_streams<'gc>(context: &mut UpdateContext<'_, 'gc, '_>, dt: f64) -> bool {
+    let mut updated = false;
+    for index in 0..context.net_streams.streams.len() {
+        let stream = &mut context.net_streams.streams[index];
+        if !stream.is_playing || stream.is_buffering || stream.is_paused {
+            continue;
+        }
+        let media = match &stream.media {
+            Some(media) => media,
+            None => continue,
+        };
+
+        let position = match stream.sound_instance {
+            Some(instance) => context.audio.get_sound_position(instance),
+            None => None,
+        };
+        stream.time = match position {
+            Some(position) => media.sound_start + f64::from(position),
+            None => stream.time + dt,
+        };
+
+        // Playback waits while the rest of the stream loads.
+        let is_buffer_empty = !media.is_complete && stream.time >= media.duration;
+        if is_buffer_empty {
+            stream.time = media.duration;
+        }
+
+        // Every frame must be decoded, but only the last one is shown.
+        let mut frame = None;
+        while let Some(packet) = media.video_frames.get(stream.next_frame) {
+            if packet.timestamp > stream.time {
+                break;
+            }
+            stream.next_frame += 1;
+            if let Some(video_stream) = stream.video_stream {
+                let encoded = EncodedFrame {
+                    codec: packet.codec,
+                    data: &packet.data,
+                    is_keyframe: packet.is_keyframe,
+                };
+                match context
+                    .video
+                    .decode_video_stream_frame(video_stream, encoded)
+                {
+                    Ok(decoded) => frame = Some(decoded),
+                    Err(e) => log::warn!("NetStream: Unable to decode video frame: {}", e),
+                }
+            }
+        }
+
+        let is_finished = media.is_complete
+            && stream.next_frame >= media.video_frames.len()
+            && stream.time >= media.duration;
+        if is_finished {
+            stream.time = media.duration;
+            stream.is_playing = false;
+            stream.stop_sound(context.audio_manager, context.audio);
+        } else if is_buffer_empty {
+            stream.is_buffering = true;
+            stream.stop_sound(context.audio_manager, context.audio);
+        } else if position.is_none()
+            && stream.sound_instance.is_some()
+            && media.audio_data.len() > media.sound_len
+        {
+            // The audio track ran out before the rest of the audio loaded.
+            stream.start_sound(context.audio_manager, context.audio);
+        }
+
+        let object = stream.object;
+        if let Some(frame) = frame {
+            for video in stream.videos.clone() {
+                video.set_frame(context, &frame);
+            }
+            updated = true;
+        }
+        if is_finished {
+            queue_status(context, object, "status", "NetStream.Play.Stop");
+            queue_status(context, object, "status", "NetStream.Buffer.Empty");
+        } else if is_buffer_empty {
+            queue_status(context, object, "status", "NetStream.Buffer.Empty");
+        }
+    }
+    updated
+}
+
+pub fn constructor<'gc>(
+    _activation: &mut Activation<'_, 'gc>,
+    _context: &mut UpdateContext<'_, 'gc, '_>,
+    _this: Object<'gc>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    Ok(Value::Undefined)
+}
+
+/// `play(url)` fetches an FLV file and plays it as it loads.
+pub fn play<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Object<'gc>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let url = match args.get(0) {
+        Some(url) => url.coerce_to_string(activation, context)?.into_owned(),
+        None => return Ok(Value::Undefined),
+    };
+
+    context.net_streams.get_or_insert(this);
+    close_stream(context, this);
+
+    let fetch = context.navigator.fetch_stream(&url, RequestOptions::get());
+    let (loader, process) = context.load_manager.load_flv_into_net_stream(
+        context.player.clone().unwrap(),
+        this,
+        activation.target_clip_or_root(),
+        fetch,
+    );
+    context.net_streams.get_or_insert(this).loader = Some(loader);
+    context.navigator.spawn_future(process);
+
+    Ok(Value::Undefined)
+}
+
+/// `pause(flag)` pauses or resumes playback, or toggles it if `flag` is omitted.
+pub fn pause<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Object<'gc>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let swf_version = activation.current_swf_version();
+    let stream = match context.net_streams.get_mut(this) {
+        Some(stream) => stream,
+        None => return Ok(Value::Undefined),
+    };
+    let is_paused = match args.get(0) {
+        None | Some(Value::Undefined) => !stream.is_paused,
+        Some(flag) => flag.as_bool(swf_version),
+    };
+    if is_paused == stream.is_paused {
+        return Ok(Value::Undefined);
+    }
+
+    stream.is_paused = is_paused;
+    if is_paused {
+        stream.stop_sound(context.audio_manager, context.audio);
+    } else if stream.is_playing && !stream.is_buffering {
+        stream.start_sound(context.audio_manager, context.audio);
+    }
+
+    let code = if is_paused {
+        "NetStream.Pause.Notify"
+    } else {
+        "NetStream.Unpause.Notify"
+    };
+    queue_status(context, this, "status", code);
+    Ok(Value::Undefined)
+}
+
+/// `seek(offset)` moves playback to the keyframe closest before `offset` seconds.
+pub fn seek<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Object<'gc>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let offset = args
+        .get(0)
+        .unwrap_or(&Value::Undefined)
+        .coerce_to_f64(activation, context)?;
+    let stream = match context.net_streams.get_mut(this) {
+        Some(stream) => stream,
+        None => return Ok(Value::Undefined),
+    };
+    let media = match &stream.media {
+        Some(media) if media.position > 0 => media,
+        _ => return Ok(Value::Undefined),
+    };
+
+    let target = (offset * 1000.0).max(0.0).min(media.duration);
+    let keyframe = media
+        .video_frames
+        .iter()
+        .rposition(|frame| frame.is_keyframe && frame.timestamp <= target);
+    let (next_frame, time) = match keyframe {
+        Some(index) => (index, media.video_frames[index].timestamp),
+        None if media.video_frames.is_empty() => (0, target),
+        None => (0, 0.0),
+    };
+
+    stream.next_frame = next_frame;
+    stream.time = time;
+    stream.is_playing = true;
+    stream.is_buffering = !stream.is_buffered();
+    if stream.is_paused || stream.is_buffering {
+        stream.stop_sound(context.audio_manager, context.audio);
+    } else {
+        stream.start_sound(context.audio_manager, context.audio);
+    }
+
+    queue_status(context, this, "status", "NetStream.Seek.Notify");
+    Ok(Value::Undefined)
+}
+
+pub fn close<'gc>(
+    _activation: &mut Activation<'_, 'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Object<'gc>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    close_stream(context, this);
+    Ok(Value::Undefined)
+}
+
+pub fn set_buffer_time<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Object<'gc>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let buffer_time = args
+        .get(0)
+        .unwrap_or(&Value::Undefined)
+        .coerce_to_f64(activation, context)?;
+    context.net_streams.get_or_insert(this).buffer_time = buffer_time;
+    Ok(Value::Undefined)
+}
+
+/// The playback position, in seconds.
+fn time<'gc>(
+    _activation: &mut Activation<'_, 'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Object<'gc>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let time = context
+        .net_streams
+        .get(this)
+        .map_or(0.0, |stream| stream.time);
+    Ok((time / 1000.0).into())
+}
+
+fn bytes_loaded<'gc>(
+    _activation: &mut Activation<'_, 'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Object<'gc>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let bytes_loaded = context
+        .net_streams
+        .get(this)
+        .map_or(0, |stream| stream.bytes_loaded);
+    Ok(bytes_loaded.into())
+}
+
+fn bytes_total<'gc>(
+    _activation: &mut Activation<'_, 'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Object<'gc>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let bytes_total = context
+        .net_streams
+        .get(this)
+        .map_or(0, |stream| stream.bytes_total);
+    Ok(bytes_total.into())
+}
+
+/// The number of seconds of the stream that are buffered ahead of the playback position.
+fn buffer_length<'gc>(
+    _activation: &mut Activation<'_, 'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Object<'gc>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let buffer_length = match context.net_streams.get(this) {
+        Some(NetStream {
+            media: Some(media),
+            time,
+            ..
+        }) => ((media.duration - time) / 1000.0).max(0.0),
+        _ => 0.0,
+    };
+    Ok(buffer_length.into())
+}
+
+fn buffer_time<'gc>(
+    _activation: &mut Activation<'_, 'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Object<'gc>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let buffer_time = context
+        .net_streams
+        .get(this)
+        .map_or(DEFAULT_BUFFER_TIME, |stream| stream.buffer_time);
+    Ok(buffer_time.into())
+}
+
+pub fn create_proto<'gc>(
+    gc_context: MutationContext<'gc, '_>,
+    proto: Object<'gc>,
+    fn_proto: Object<'gc>,
+) -> Object<'gc> {
+    let mut net_stream_proto = ScriptObject::object(gc_context, Some(proto));
+
+    net_stream_proto.force_set_function("play", play, gc_context, EnumSet::empty(), Some(fn_proto));
+    net_stream_proto.force_set_function(
+        "pause",
+        pause,
+        gc_context,
+        EnumSet::empty(),
+        Some(fn_proto),
+    );
+    net_stream_proto.force_set_function("seek", seek, gc_context, EnumSet::empty(), Some(fn_proto));
+    net_stream_proto.force_set_function(
+        "close",
+        close,
+        gc_context,
+        EnumSet::empty(),
+        Some(fn_proto),
+    );
+    net_stream_proto.force_set_function(
+        "setBufferTime",
+        set_buffer_time,
+        gc_context,
+        EnumSet::empty(),
+        Some(fn_proto),
+    );
+
+    let properties: [(&str, NativeFunction<'gc>); 5] = [
+        ("time", time),
+        ("bytesLoaded", bytes_loaded),
+        ("bytesTotal", bytes_total),
+        ("bufferLength", buffer_length),
+        ("bufferTime", buffer_time),
+    ];
+    for (name, getter) in properties.iter() {
+        net_stream_proto.add_property(
+            gc_context,
+            *name,
+            Executable::Native(*getter),
+            None,
+            DontDelete | ReadOnly | DontEnum,
+        );
+    }
+
+    net_stream_proto.into()
+}
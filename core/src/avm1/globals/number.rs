@@ -5,7 +5,7 @@ use crate::avm1::error::Error;
 use crate::avm1::function::{Executable, FunctionObject};
 use crate::avm1::property::Attribute::*;
 use crate::avm1::value_object::ValueObject;
-use crate::avm1::{Object, TObject, Value};
+use crate::avm1::{AvmString, Object, TObject, Value};
 use crate::context::UpdateContext;
 use enumset::EnumSet;
 use gc_arena::MutationContext;
@@ -143,9 +143,11 @@ fn to_string<'gc>(
 
     if radix == 10 {
         // Output number as floating-point decimal.
-        Ok(Value::from(this)
-            .coerce_to_string(activation, context)?
-            .into())
+        Ok(AvmString::new(
+            context.gc_context,
+            Value::from(this).coerce_to_string(activation, context)?,
+        )
+        .into())
     } else if this > -2_147_483_648.0 && this < 2_147_483_648.0 {
         // Output truncated integer in specified base.
         let n = this as i32;
@@ -173,7 +175,7 @@ fn to_string<'gc>(
             i += 1;
         }
         let out: String = digits[..i].iter().rev().collect();
-        Ok(out.into())
+        Ok(AvmString::new(context.gc_context, out).into())
     } else {
         // NaN or large numbers.
         // Player version specific behavior:
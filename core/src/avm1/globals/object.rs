@@ -3,8 +3,8 @@ use crate::avm1::activation::Activation;
 use crate::avm1::error::Error;
 use crate::avm1::function::{Executable, FunctionObject};
 use crate::avm1::property::Attribute::{self, *};
-use crate::avm1::{Object, TObject, UpdateContext, Value};
-use crate::character::Character;
+use crate::avm1::{AvmString, Object, TObject, UpdateContext, Value};
+use crate::display_object::TDisplayObject;
 use enumset::EnumSet;
 use gc_arena::MutationContext;
 use std::borrow::Cow;
@@ -41,7 +41,7 @@ pub fn add_property<'gc>(
                         this.add_property_with_case(
                             activation,
                             context.gc_context,
-                            &name,
+                            AvmString::new(context.gc_context, name.to_string()),
                             get_func.clone(),
                             Some(set_func.clone()),
                             EnumSet::empty(),
@@ -53,7 +53,7 @@ pub fn add_property<'gc>(
                     this.add_property_with_case(
                         activation,
                         context.gc_context,
-                        &name,
+                        AvmString::new(context.gc_context, name.to_string()),
                         get_func.clone(),
                         None,
                         ReadOnly.into(),
@@ -142,24 +142,79 @@ pub fn register_class<'gc>(
     _this: Object<'gc>,
     args: &[Value<'gc>],
 ) -> Result<Value<'gc>, Error<'gc>> {
-    if let Some(class_name) = args.get(0).cloned() {
-        let class_name = class_name.coerce_to_string(activation, context)?;
-        if let Some(Character::MovieClip(movie_clip)) = context
-            .library
-            .library_for_movie_mut(context.swf.clone())
-            .get_character_by_export_name(&class_name)
-        {
-            if let Some(constructor) = args.get(1) {
-                movie_clip.set_avm1_constructor(
-                    context.gc_context,
-                    Some(constructor.coerce_to_object(activation, context)),
-                );
-            } else {
-                movie_clip.set_avm1_constructor(context.gc_context, None);
-            }
-        }
+    let (class_name, constructor) = match args {
+        [class_name, constructor, ..] => (class_name, constructor),
+        _ => return Ok(false.into()),
+    };
+
+    let class_name = class_name.coerce_to_string(activation, context)?;
+    let constructor = match constructor {
+        Value::Undefined | Value::Null => None,
+        constructor => Some(constructor.coerce_to_object(activation, context)),
+    };
+
+    let movie = activation
+        .base_clip()
+        .movie()
+        .unwrap_or_else(|| context.swf.clone());
+    context
+        .library
+        .library_for_movie_mut(movie)
+        .register_avm1_constructor(&class_name, constructor);
+
+    Ok(true.into())
+}
+
+/// Implements `Object.prototype.watch`
+fn watch<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Object<'gc>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let name = match args.get(0) {
+        Some(name) => name.coerce_to_string(activation, context)?,
+        None => return Ok(false.into()),
+    };
+    let callback = match args.get(1) {
+        Some(Value::Object(callback)) if callback.as_executable().is_some() => *callback,
+        _ => return Ok(false.into()),
+    };
+    let user_data = args.get(2).cloned().unwrap_or(Value::Undefined);
+
+    // Display object properties such as `_x` aren't stored on the script
+    // object, so they never notify the watcher.
+    if let Some(object) = this.as_script_object() {
+        object.watch(
+            activation,
+            context.gc_context,
+            AvmString::new(context.gc_context, name.to_string()),
+            callback,
+            user_data,
+        );
+        Ok(true.into())
+    } else {
+        Ok(false.into())
     }
-    Ok(Value::Undefined)
+}
+
+/// Implements `Object.prototype.unwatch`
+fn unwatch<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Object<'gc>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let name = match args.get(0) {
+        Some(name) => name.coerce_to_string(activation, context)?,
+        None => return Ok(false.into()),
+    };
+
+    Ok(this
+        .as_script_object()
+        .map(|object| object.unwatch(activation, context.gc_context, &name))
+        .unwrap_or(false)
+        .into())
 }
 
 /// Partially construct `Object.prototype`.
@@ -218,6 +273,20 @@ pub fn fill_proto<'gc>(
         DontDelete | DontEnum,
         Some(fn_proto),
     );
+    object_proto.as_script_object().unwrap().force_set_function(
+        "watch",
+        watch,
+        gc_context,
+        DontDelete | DontEnum,
+        Some(fn_proto),
+    );
+    object_proto.as_script_object().unwrap().force_set_function(
+        "unwatch",
+        unwatch,
+        gc_context,
+        DontDelete | DontEnum,
+        Some(fn_proto),
+    );
 }
 
 /// Implements `ASSetPropFlags`.
@@ -0,0 +1,282 @@
+//! AVM1 PrintJob object
+
+use crate::avm1::activation::Activation;
+use crate::avm1::error::Error;
+use crate::avm1::property::Attribute;
+use crate::avm1::script_object::ScriptObject;
+use crate::avm1::{Object, TObject, UpdateContext, Value};
+use crate::backend::input::PrintPageFormat;
+use crate::backend::render::Bitmap;
+use crate::bounding_box::BoundingBox;
+use crate::context::RenderContext;
+use crate::display_object::{DisplayObject, TDisplayObject};
+use crate::transform::{Transform, TransformStack};
+use crate::Color;
+use enumset::EnumSet;
+use gc_arena::{Collect, MutationContext};
+use swf::{Matrix, Twips};
+
+/// The resolution that vector pages are rendered at, relative to the 72 points per inch
+/// of the page. Pages printed with `printAsBitmap` are rendered at the screen resolution.
+const VECTOR_PRINT_SCALE: f32 = 4.0;
+
+/// A print job started by `PrintJob.start`, whose pages are collected by `addPage`
+/// until `send` passes them to the input backend.
+struct PrintJob<'gc> {
+    /// The `PrintJob` object that started the job.
+    object: Object<'gc>,
+
+    /// The paper that the pages are printed on.
+    format: PrintPageFormat,
+
+    pages: Vec<Bitmap>,
+}
+
+/// Tracks the print job of an AVM1 `PrintJob` object.
+/// As in Flash, only one job can be started at a time.
+pub struct PrintJobs<'gc> {
+    active: Option<PrintJob<'gc>>,
+}
+
+unsafe impl<'gc> Collect for PrintJobs<'gc> {
+    fn trace(&self, cc: gc_arena::CollectionContext) {
+        if let Some(job) = &self.active {
+            job.object.trace(cc);
+        }
+    }
+}
+
+impl<'gc> PrintJobs<'gc> {
+    pub fn new() -> Self {
+        Self { active: None }
+    }
+
+    /// Returns the job started by the given `PrintJob` object, if any.
+    fn get_mut(&mut self, object: Object<'gc>) -> Option<&mut PrintJob<'gc>> {
+        self.active
+            .as_mut()
+            .filter(|job| Object::ptr_eq(job.object, object))
+    }
+}
+
+impl<'gc> Default for PrintJobs<'gc> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+pub fn constructor<'gc>(
+    _activation: &mut Activation<'_, 'gc>,
+    _context: &mut UpdateContext<'_, 'gc, '_>,
+    _this: Object<'gc>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    Ok(Value::Undefined)
+}
+
+/// Asks the host to begin printing. Returns `false` if printing is unavailable,
+/// was cancelled, or another job is already in progress.
+pub fn start<'gc>(
+    _activation: &mut Activation<'_, 'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Object<'gc>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    if context.print_jobs.active.is_some() {
+        return Ok(false.into());
+    }
+
+    let format = match context.input.start_print_job() {
+        Some(format) => format,
+        None => return Ok(false.into()),
+    };
+
+    let attributes = Attribute::DontDelete | Attribute::ReadOnly;
+    let properties: [(&'static str, Value<'gc>); 5] = [
+        ("paperWidth", format.paper_width.into()),
+        ("paperHeight", format.paper_height.into()),
+        ("pageWidth", format.page_width.into()),
+        ("pageHeight", format.page_height.into()),
+        ("orientation", format.orientation.name().into()),
+    ];
+    for (name, value) in properties.iter() {
+        this.define_value(context.gc_context, *name, value.clone(), attributes);
+    }
+
+    context.print_jobs.active = Some(PrintJob {
+        object: this,
+        format,
+        pages: Vec::new(),
+    });
+    Ok(true.into())
+}
+
+/// Renders a display object into a new page.
+///
+/// The page shows the `printArea` of the target in its own coordinate space, which
+/// defaults to the bounds of the target. Returns `false` if the page can't be added.
+pub fn add_page<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Object<'gc>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let format = match context.print_jobs.get_mut(this) {
+        Some(job) => job.format,
+        None => return Ok(false.into()),
+    };
+
+    let target = match args.get(0) {
+        Some(Value::Number(level)) => context.levels.get(&(*level as u32)).copied(),
+        Some(target) => {
+            let start = activation.target_clip_or_root();
+            activation.resolve_target_display_object(context, start, target.clone())?
+        }
+        None => None,
+    };
+    let target = match target {
+        Some(target) => target,
+        None => return Ok(false.into()),
+    };
+
+    let print_area = match args.get(1) {
+        Some(Value::Object(area)) => {
+            let mut coordinate = |name| -> Result<Twips, Error<'gc>> {
+                let value = area
+                    .get(name, activation, context)?
+                    .coerce_to_f64(activation, context)?;
+                Ok(Twips::from_pixels(value))
+            };
+            BoundingBox {
+                x_min: coordinate("xMin")?,
+                y_min: coordinate("yMin")?,
+                x_max: coordinate("xMax")?,
+                y_max: coordinate("yMax")?,
+                valid: true,
+            }
+        }
+        _ => target.bounds(),
+    };
+
+    let print_as_bitmap = match args.get(2) {
+        Some(Value::Object(options)) => options
+            .get("printAsBitmap", activation, context)?
+            .as_bool(activation.current_swf_version()),
+        _ => false,
+    };
+
+    if let Some(frame) = args.get(3) {
+        if *frame != Value::Undefined {
+            log::warn!("PrintJob.addPage: Printing a different frame is not yet implemented");
+        }
+    }
+
+    match render_page(context, target, &print_area, &format, print_as_bitmap) {
+        Some(page) => {
+            if let Some(job) = context.print_jobs.get_mut(this) {
+                job.pages.push(page);
+            }
+            Ok(true.into())
+        }
+        None => Ok(false.into()),
+    }
+}
+
+/// Renders the given area of a display object into an image, cropped to the
+/// printable area of the page.
+fn render_page<'gc>(
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    target: DisplayObject<'gc>,
+    area: &BoundingBox,
+    format: &PrintPageFormat,
+    print_as_bitmap: bool,
+) -> Option<Bitmap> {
+    let scale = if print_as_bitmap {
+        1.0
+    } else {
+        VECTOR_PRINT_SCALE
+    };
+    // One pixel of the movie is printed as one point.
+    let width = area.width().to_pixels().min(f64::from(format.page_width));
+    let height = area.height().to_pixels().min(f64::from(format.page_height));
+    if !area.valid || width <= 0.0 || height <= 0.0 {
+        return None;
+    }
+
+    let white = Color {
+        r: 255,
+        g: 255,
+        b: 255,
+        a: 255,
+    };
+    let width = (width * f64::from(scale)).ceil() as u32;
+    let height = (height * f64::from(scale)).ceil() as u32;
+    if !context.renderer.begin_capture(width, height, white) {
+        return None;
+    }
+
+    // The target is drawn in its own coordinate space, so undo its matrix.
+    let mut to_local = *target.matrix();
+    to_local.invert();
+    let mut transform_stack = TransformStack::new();
+    transform_stack.push(&Transform {
+        matrix: Matrix::scale(scale, scale)
+            * Matrix::translate(Twips::new(-area.x_min.get()), Twips::new(-area.y_min.get()))
+            * to_local,
+        ..Default::default()
+    });
+
+    let mut render_context = RenderContext {
+        renderer: &mut *context.renderer,
+        library: &*context.library,
+        transform_stack: &mut transform_stack,
+        view_bounds: area.transform(&target.local_to_global_matrix()),
+        clip_depth_stack: vec![],
+    };
+    target.render(&mut render_context);
+
+    context.renderer.end_capture()
+}
+
+/// Passes the collected pages to the host to be printed, and ends the job.
+pub fn send<'gc>(
+    _activation: &mut Activation<'_, 'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Object<'gc>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    if let Some(job) = context.print_jobs.get_mut(this) {
+        let pages = std::mem::take(&mut job.pages);
+        context.print_jobs.active = None;
+        if !pages.is_empty() {
+            context.input.print(pages);
+        }
+    }
+    Ok(Value::Undefined)
+}
+
+pub fn create_proto<'gc>(
+    gc_context: MutationContext<'gc, '_>,
+    proto: Object<'gc>,
+    fn_proto: Object<'gc>,
+) -> Object<'gc> {
+    let mut print_job_proto = ScriptObject::object(gc_context, Some(proto));
+
+    print_job_proto.force_set_function(
+        "start",
+        start,
+        gc_context,
+        EnumSet::empty(),
+        Some(fn_proto),
+    );
+    print_job_proto.force_set_function(
+        "addPage",
+        add_page,
+        gc_context,
+        EnumSet::empty(),
+        Some(fn_proto),
+    );
+    print_job_proto.force_set_function("send", send, gc_context, EnumSet::empty(), Some(fn_proto));
+
+    print_job_proto.into()
+}
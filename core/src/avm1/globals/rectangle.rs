@@ -5,11 +5,13 @@ use crate::avm1::error::Error;
 use crate::avm1::function::{Executable, FunctionObject};
 use crate::avm1::globals::point::{construct_new_point, point_to_object, value_to_point};
 use crate::avm1::property::Attribute;
-use crate::avm1::{Object, ScriptObject, TObject, Value};
+use crate::avm1::{AvmString, Object, ScriptObject, TObject, Value};
+use crate::bounding_box::BoundingBox;
 use crate::context::UpdateContext;
 use enumset::EnumSet;
 use gc_arena::MutationContext;
 use std::f64::NAN;
+use swf::Twips;
 
 fn constructor<'gc>(
     activation: &mut Activation<'_, 'gc>,
@@ -52,6 +54,59 @@ fn constructor<'gc>(
     Ok(Value::Undefined)
 }
 
+pub fn construct_new_rectangle<'gc>(
+    args: &[Value<'gc>],
+    activation: &mut Activation<'_, 'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+) -> Result<Object<'gc>, Error<'gc>> {
+    let proto = context.system_prototypes.rectangle;
+    let object = proto.new(activation, context, proto, &args)?;
+    let _ = constructor(activation, context, object, &args)?;
+    Ok(object)
+}
+
+/// Creates a `Rectangle` covering the given bounds.
+pub fn rectangle_to_object<'gc>(
+    rectangle: &BoundingBox,
+    activation: &mut Activation<'_, 'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+) -> Result<Object<'gc>, Error<'gc>> {
+    let args = [
+        rectangle.x_min.to_pixels().into(),
+        rectangle.y_min.to_pixels().into(),
+        rectangle.width().to_pixels().into(),
+        rectangle.height().to_pixels().into(),
+    ];
+    construct_new_rectangle(&args, activation, context)
+}
+
+/// Reads the bounds described by the `x`, `y`, `width` and `height` of an object.
+pub fn object_to_rectangle<'gc>(
+    object: Object<'gc>,
+    activation: &mut Activation<'_, 'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+) -> Result<BoundingBox, Error<'gc>> {
+    let x = object
+        .get("x", activation, context)?
+        .coerce_to_f64(activation, context)?;
+    let y = object
+        .get("y", activation, context)?
+        .coerce_to_f64(activation, context)?;
+    let width = object
+        .get("width", activation, context)?
+        .coerce_to_f64(activation, context)?;
+    let height = object
+        .get("height", activation, context)?
+        .coerce_to_f64(activation, context)?;
+    Ok(BoundingBox {
+        x_min: Twips::from_pixels(x),
+        y_min: Twips::from_pixels(y),
+        x_max: Twips::from_pixels(x + width),
+        y_max: Twips::from_pixels(y + height),
+        valid: true,
+    })
+}
+
 fn to_string<'gc>(
     activation: &mut Activation<'_, 'gc>,
     context: &mut UpdateContext<'_, 'gc, '_>,
@@ -63,12 +118,15 @@ fn to_string<'gc>(
     let width = this.get("width", activation, context)?;
     let height = this.get("height", activation, context)?;
 
-    Ok(format!(
-        "(x={}, y={}, w={}, h={})",
-        x.coerce_to_string(activation, context)?,
-        y.coerce_to_string(activation, context)?,
-        width.coerce_to_string(activation, context)?,
-        height.coerce_to_string(activation, context)?
+    Ok(AvmString::new(
+        context.gc_context,
+        format!(
+            "(x={}, y={}, w={}, h={})",
+            x.coerce_to_string(activation, context)?,
+            y.coerce_to_string(activation, context)?,
+            width.coerce_to_string(activation, context)?,
+            height.coerce_to_string(activation, context)?
+        ),
     )
     .into())
 }
@@ -120,15 +178,13 @@ fn clone<'gc>(
     this: Object<'gc>,
     _args: &[Value<'gc>],
 ) -> Result<Value<'gc>, Error<'gc>> {
-    let proto = context.system_prototypes.rectangle;
     let args = [
         this.get("x", activation, context)?,
         this.get("y", activation, context)?,
         this.get("width", activation, context)?,
         this.get("height", activation, context)?,
     ];
-    let cloned = proto.new(activation, context, proto, &args)?;
-    let _ = constructor(activation, context, cloned, &args)?;
+    let cloned = construct_new_rectangle(&args, activation, context)?;
     Ok(cloned.into())
 }
 
@@ -0,0 +1,77 @@
+use crate::avm1::activation::Activation;
+use crate::avm1::error::Error;
+use crate::avm1::globals::as_broadcaster::BroadcasterFunctions;
+use crate::avm1::property::Attribute;
+use crate::avm1::{AvmString, Object, ScriptObject, UpdateContext, Value};
+use crate::display_object::TDisplayObject;
+use crate::focus_tracker;
+use gc_arena::MutationContext;
+
+pub fn get_focus<'gc>(
+    _activation: &mut Activation<'_, 'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    _this: Object<'gc>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    Ok(context.focus_tracker.get().map_or(Value::Null, |focus| {
+        AvmString::new(context.gc_context, focus.path()).into()
+    }))
+}
+
+pub fn set_focus<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    _this: Object<'gc>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    match args.get(0) {
+        None | Some(Value::Undefined) | Some(Value::Null) => {
+            focus_tracker::set_focus(context, None, false);
+            Ok(true.into())
+        }
+        Some(target) => {
+            let start = activation.target_clip_or_root();
+            let focus = activation
+                .resolve_target_display_object(context, start, target.clone())?
+                .filter(|focus| {
+                    focus.as_button().is_some()
+                        || focus.as_movie_clip().is_some()
+                        || focus.as_edit_text().is_some()
+                });
+            if focus.is_some() {
+                focus_tracker::set_focus(context, focus, true);
+            }
+            Ok(focus.is_some().into())
+        }
+    }
+}
+
+pub fn create_selection_object<'gc>(
+    gc_context: MutationContext<'gc, '_>,
+    proto: Option<Object<'gc>>,
+    fn_proto: Option<Object<'gc>>,
+    broadcaster_functions: BroadcasterFunctions<'gc>,
+    array_proto: Object<'gc>,
+) -> Object<'gc> {
+    let mut selection = ScriptObject::object(gc_context, proto);
+
+    broadcaster_functions.initialize(gc_context, selection.into(), array_proto);
+
+    selection.force_set_function(
+        "getFocus",
+        get_focus,
+        gc_context,
+        Attribute::DontEnum | Attribute::DontDelete | Attribute::ReadOnly,
+        fn_proto,
+    );
+
+    selection.force_set_function(
+        "setFocus",
+        set_focus,
+        gc_context,
+        Attribute::DontEnum | Attribute::DontDelete | Attribute::ReadOnly,
+        fn_proto,
+    );
+
+    selection.into()
+}
@@ -1,15 +1,14 @@
 use crate::avm1::activation::Activation;
+use crate::avm1::amf::{self, Amf0Reader, Amf0Writer};
 use crate::avm1::error::Error;
 use crate::avm1::function::{Executable, FunctionObject};
-use crate::avm1::{Object, TObject, Value};
+use crate::avm1::{AvmString, Object, TObject, Value};
 use crate::context::UpdateContext;
 use enumset::EnumSet;
 use gc_arena::MutationContext;
 
 use crate::avm1::shared_object::SharedObject;
 
-use json::JsonValue;
-
 pub fn delete_all<'gc>(
     _activation: &mut Activation<'_, 'gc>,
     _action_context: &mut UpdateContext<'_, 'gc, '_>,
@@ -30,105 +29,76 @@ pub fn get_disk_usage<'gc>(
     Ok(Value::Undefined)
 }
 
-/// Serialize an Object and any children to a JSON object
-/// It would be best if this was implemented via serde but due to avm and context it can't
-/// Undefined fields aren't serialized
-fn recursive_serialize<'gc>(
+/// The header of a `.sol` file, followed by the length of the rest of the file.
+const SOL_HEADER: [u8; 2] = [0x00, 0xBF];
+
+/// The signature following the length of a `.sol` file.
+const SOL_SIGNATURE: [u8; 10] = *b"TCSO\x00\x04\x00\x00\x00\x00";
+
+/// The AMF version of the data in a `.sol` file.
+const AMF0_VERSION: u32 = 0;
+
+/// Serializes the data object of a shared object to the contents of a `.sol` file.
+/// The properties of the data object are encoded in AMF0.
+fn serialize_sol<'gc>(
     activation: &mut Activation<'_, 'gc>,
-    action_context: &mut UpdateContext<'_, 'gc, '_>,
-    obj: Object<'gc>,
-    json_obj: &mut JsonValue,
-) {
-    for k in &obj.get_keys(activation) {
-        if let Ok(elem) = obj.get(k, activation, action_context) {
-            match elem {
-                Value::Undefined => {}
-                Value::Null => json_obj[k] = JsonValue::Null,
-                Value::Bool(b) => json_obj[k] = b.into(),
-                Value::Number(f) => json_obj[k] = f.into(),
-                Value::String(s) => json_obj[k] = s.into(),
-                Value::Object(o) => {
-                    // Don't attempt to serialize functions
-                    let function = activation.avm.prototypes.function;
-                    if !o
-                        .is_instance_of(activation, action_context, o, function)
-                        .unwrap_or_default()
-                    {
-                        let mut sub_data_json = JsonValue::new_object();
-                        recursive_serialize(activation, action_context, o, &mut sub_data_json);
-                        json_obj[k] = sub_data_json;
-                    }
-                }
-            }
-        }
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    name: &str,
+    data: Object<'gc>,
+) -> Result<Vec<u8>, amf::Error> {
+    let mut writer = Amf0Writer::new();
+    writer.write_bytes(&SOL_SIGNATURE);
+    writer.write_utf8(name);
+    writer.write_u32(AMF0_VERSION);
+    for (key, value) in amf::serializable_properties(activation, context, data) {
+        writer.write_utf8(&key);
+        writer.write_value(activation, context, value)?;
+        writer.write_bytes(&[0]);
     }
+    let body = writer.into_bytes();
+
+    let mut sol = SOL_HEADER.to_vec();
+    sol.extend_from_slice(&(body.len() as u32).to_be_bytes());
+    sol.extend(body);
+    Ok(sol)
 }
 
-/// Deserialize an Object and any children from a JSON object
-/// It would be best if this was implemented via serde but due to avm and context it can't
-/// Undefined fields aren't deserialized
-fn recursive_deserialize<'gc>(
-    json_obj: JsonValue,
+/// Reads the contents of a `.sol` file into the data object of a shared object.
+fn deserialize_sol<'gc>(
     activation: &mut Activation<'_, 'gc>,
-    object: Object<'gc>,
     context: &mut UpdateContext<'_, 'gc, '_>,
-) {
-    for entry in json_obj.entries() {
-        match entry.1 {
-            JsonValue::Null => {
-                object.define_value(context.gc_context, entry.0, Value::Null, EnumSet::empty());
-            }
-            JsonValue::Short(s) => {
-                let val: String = s.as_str().to_string();
-                object.define_value(
-                    context.gc_context,
-                    entry.0,
-                    Value::String(val),
-                    EnumSet::empty(),
-                );
-            }
-            JsonValue::String(s) => {
-                object.define_value(
-                    context.gc_context,
-                    entry.0,
-                    Value::String(s.clone()),
-                    EnumSet::empty(),
-                );
-            }
-            JsonValue::Number(f) => {
-                let val: f64 = f.clone().into();
-                object.define_value(
-                    context.gc_context,
-                    entry.0,
-                    Value::Number(val),
-                    EnumSet::empty(),
-                );
-            }
-            JsonValue::Boolean(b) => {
-                object.define_value(
-                    context.gc_context,
-                    entry.0,
-                    Value::Bool(*b),
-                    EnumSet::empty(),
-                );
-            }
-            JsonValue::Object(o) => {
-                let so = activation.avm.prototypes.object;
-                let obj = so.new(activation, context, so, &[]).unwrap();
-                let _ = crate::avm1::globals::object::constructor(activation, context, obj, &[])
-                    .unwrap();
-                recursive_deserialize(JsonValue::Object(o.clone()), activation, obj, context);
-
-                object.define_value(
-                    context.gc_context,
-                    entry.0,
-                    Value::Object(obj),
-                    EnumSet::empty(),
-                );
-            }
-            JsonValue::Array(_) => {}
-        }
+    sol: &[u8],
+    data: Object<'gc>,
+) -> Result<(), amf::Error> {
+    let mut reader = Amf0Reader::new(sol);
+    if reader.read_bytes(2)? != SOL_HEADER {
+        return Err(amf::Error::InvalidHeader);
+    }
+    let len = reader.read_u32()? as usize;
+    let mut reader = Amf0Reader::new(reader.read_bytes(len)?);
+    if reader.read_bytes(4)? != b"TCSO" {
+        return Err(amf::Error::InvalidHeader);
+    }
+    reader.read_bytes(6)?;
+    let _name = reader.read_utf8()?;
+    if reader.read_u32()? != AMF0_VERSION {
+        // TODO: Support AMF3 `.sol` files written by AVM2 movies.
+        return Err(amf::Error::InvalidHeader);
+    }
+
+    while !reader.is_empty() {
+        let key = reader.read_utf8()?;
+        let value = reader.read_value(activation, context)?;
+        reader.read_u8()?;
+        data.define_value(
+            context.gc_context,
+            AvmString::new(context.gc_context, key),
+            value,
+            EnumSet::empty(),
+        );
     }
+
+    Ok(())
 }
 
 pub fn get_local<'gc>(
@@ -168,9 +138,13 @@ pub fn get_local<'gc>(
     let _ = crate::avm1::globals::object::constructor(activation, action_context, data, &[])?;
 
     // Load the data object from storage if it existed prior
-    if let Some(saved) = action_context.storage.get_string(&name) {
-        if let Ok(json_data) = json::parse(&saved) {
-            recursive_deserialize(json_data, activation, data, action_context);
+    if let Some(saved) = action_context.storage.get(&name) {
+        if let Err(e) = deserialize_sol(activation, action_context, &saved, data) {
+            log::warn!(
+                "SharedObject.getLocal(): Unable to read saved data of {}: {}",
+                name,
+                e
+            );
         }
     }
 
@@ -308,7 +282,7 @@ pub fn clear<'gc>(
         .get("data", activation, action_context)?
         .coerce_to_object(activation, action_context);
 
-    for k in &data.get_keys(activation) {
+    for k in &data.get_keys(activation, action_context.gc_context) {
         data.delete(activation, action_context.gc_context, k);
     }
 
@@ -350,26 +324,43 @@ pub fn flush<'gc>(
         .get("data", activation, action_context)?
         .coerce_to_object(activation, action_context);
 
-    let mut data_json = JsonValue::new_object();
-    recursive_serialize(activation, action_context, data, &mut data_json);
-
     let this_obj = this.as_shared_object().unwrap();
     let name = this_obj.get_name();
-
-    Ok(action_context
-        .storage
-        .put_string(&name, data_json.dump())
-        .into())
+    match serialize_sol(activation, action_context, &name, data) {
+        Ok(sol) => Ok(action_context.storage.put(&name, &sol).into()),
+        Err(e) => {
+            log::warn!(
+                "SharedObject.flush(): Unable to write data of {}: {}",
+                name,
+                e
+            );
+            Ok(false.into())
+        }
+    }
 }
 
 pub fn get_size<'gc>(
-    _activation: &mut Activation<'_, 'gc>,
-    _action_context: &mut UpdateContext<'_, 'gc, '_>,
-    _this: Object<'gc>,
+    activation: &mut Activation<'_, 'gc>,
+    action_context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Object<'gc>,
     _args: &[Value<'gc>],
 ) -> Result<Value<'gc>, Error<'gc>> {
-    log::warn!("SharedObject.getSize() not implemented");
-    Ok(Value::Undefined)
+    let data = this
+        .get("data", activation, action_context)?
+        .coerce_to_object(activation, action_context);
+
+    let name = this.as_shared_object().unwrap().get_name();
+    match serialize_sol(activation, action_context, &name, data) {
+        Ok(sol) => Ok(sol.len().into()),
+        Err(e) => {
+            log::warn!(
+                "SharedObject.getSize(): Unable to write data of {}: {}",
+                name,
+                e
+            );
+            Ok(0.into())
+        }
+    }
 }
 
 pub fn send<'gc>(
@@ -1,13 +1,15 @@
 //! AVM1 Sound object
-//! TODO: Sound position, transform, loadSound
 
 use crate::avm1::activation::Activation;
 use crate::avm1::error::Error;
 use crate::avm1::function::Executable;
 use crate::avm1::property::Attribute::*;
-use crate::avm1::{Object, SoundObject, TObject, UpdateContext, Value};
+use crate::avm1::{AvmString, Object, ScriptObject, SoundObject, TObject, UpdateContext, Value};
+use crate::backend::audio::SoundTransform;
+use crate::backend::navigator::RequestOptions;
 use crate::character::Character;
 use crate::display_object::TDisplayObject;
+use enumset::EnumSet;
 use gc_arena::MutationContext;
 
 /// Implements `Sound`
@@ -86,7 +88,7 @@ pub fn create_proto<'gc>(
     );
 
     object.as_script_object().unwrap().force_set_function(
-        "get_transform",
+        "getTransform",
         get_transform,
         gc_context,
         DontDelete | ReadOnly | DontEnum,
@@ -94,7 +96,7 @@ pub fn create_proto<'gc>(
     );
 
     object.as_script_object().unwrap().force_set_function(
-        "get_volume",
+        "getVolume",
         get_volume,
         gc_context,
         DontDelete | ReadOnly | DontEnum,
@@ -102,7 +104,7 @@ pub fn create_proto<'gc>(
     );
 
     object.as_script_object().unwrap().force_set_function(
-        "load_sound",
+        "loadSound",
         load_sound,
         gc_context,
         DontDelete | ReadOnly | DontEnum,
@@ -118,7 +120,7 @@ pub fn create_proto<'gc>(
     );
 
     object.as_script_object().unwrap().force_set_function(
-        "set_pan",
+        "setPan",
         set_pan,
         gc_context,
         DontDelete | ReadOnly | DontEnum,
@@ -126,7 +128,7 @@ pub fn create_proto<'gc>(
     );
 
     object.as_script_object().unwrap().force_set_function(
-        "set_transform",
+        "setTransform",
         set_transform,
         gc_context,
         DontDelete | ReadOnly | DontEnum,
@@ -134,7 +136,7 @@ pub fn create_proto<'gc>(
     );
 
     object.as_script_object().unwrap().force_set_function(
-        "set_volume",
+        "setVolume",
         set_volume,
         gc_context,
         DontDelete | ReadOnly | DontEnum,
@@ -220,10 +222,13 @@ fn duration<'gc>(
 fn get_bytes_loaded<'gc>(
     activation: &mut Activation<'_, 'gc>,
     _context: &mut UpdateContext<'_, 'gc, '_>,
-    _this: Object<'gc>,
+    this: Object<'gc>,
     _args: &[Value<'gc>],
 ) -> Result<Value<'gc>, Error<'gc>> {
     if activation.current_swf_version() >= 6 {
+        if let Some(bytes_loaded) = this.as_sound_object().and_then(|o| o.bytes_loaded()) {
+            return Ok(bytes_loaded.into());
+        }
         log::warn!("Sound.getBytesLoaded: Unimplemented");
         Ok(1.into())
     } else {
@@ -234,10 +239,18 @@ fn get_bytes_loaded<'gc>(
 fn get_bytes_total<'gc>(
     activation: &mut Activation<'_, 'gc>,
     _context: &mut UpdateContext<'_, 'gc, '_>,
-    _this: Object<'gc>,
+    this: Object<'gc>,
     _args: &[Value<'gc>],
 ) -> Result<Value<'gc>, Error<'gc>> {
     if activation.current_swf_version() >= 6 {
+        if let Some(sound_object) = this.as_sound_object() {
+            // The total size is unknown until the loaded sound arrives.
+            if sound_object.bytes_loaded().is_some() {
+                return Ok(sound_object
+                    .bytes_total()
+                    .map_or(Value::Undefined, Value::from));
+            }
+        }
         log::warn!("Sound.getBytesTotal: Unimplemented");
         Ok(1.into())
     } else {
@@ -245,74 +258,181 @@ fn get_bytes_total<'gc>(
     }
 }
 
+/// The sound transform controlled by a `Sound` object.
+/// This is the transform of its target display object, or the global sound transform
+/// if the `Sound` has no target.
+fn sound_transform<'gc>(
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Object<'gc>,
+    method: &str,
+) -> Option<SoundTransform> {
+    if let Some(sound) = this.as_sound_object() {
+        Some(match sound.owner() {
+            Some(owner) => owner.sound_transform(),
+            None => context.audio_manager.global_sound_transform(),
+        })
+    } else {
+        log::warn!("{}: this is not a Sound", method);
+        None
+    }
+}
+
+/// Sets the sound transform controlled by a `Sound` object and updates the sounds affected by it.
+fn set_sound_transform<'gc>(
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Object<'gc>,
+    transform: SoundTransform,
+) {
+    match this.as_sound_object().and_then(|sound| sound.owner()) {
+        Some(mut owner) => {
+            owner.set_sound_transform(context.gc_context, transform);
+            context.audio_manager.update_sound_transforms(context.audio);
+        }
+        None => context
+            .audio_manager
+            .set_global_sound_transform(context.audio, transform),
+    }
+}
+
 fn get_pan<'gc>(
     _activation: &mut Activation<'_, 'gc>,
-    _context: &mut UpdateContext<'_, 'gc, '_>,
-    _this: Object<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Object<'gc>,
     _args: &[Value<'gc>],
 ) -> Result<Value<'gc>, Error<'gc>> {
-    log::warn!("Sound.getPan: Unimplemented");
-    Ok(0.into())
+    let transform = sound_transform(context, this, "Sound.getPan").unwrap_or_default();
+    Ok(transform.pan().into())
 }
 
 fn get_transform<'gc>(
-    _activation: &mut Activation<'_, 'gc>,
-    _context: &mut UpdateContext<'_, 'gc, '_>,
-    _this: Object<'gc>,
+    activation: &mut Activation<'_, 'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Object<'gc>,
     _args: &[Value<'gc>],
 ) -> Result<Value<'gc>, Error<'gc>> {
-    log::warn!("Sound.getTransform: Unimplemented");
-    Ok(Value::Undefined)
+    let transform = sound_transform(context, this, "Sound.getTransform").unwrap_or_default();
+    let object = ScriptObject::object(context.gc_context, Some(activation.avm.prototypes.object));
+    object.set("ll", transform.left_to_left.into(), activation, context)?;
+    object.set("lr", transform.left_to_right.into(), activation, context)?;
+    object.set("rl", transform.right_to_left.into(), activation, context)?;
+    object.set("rr", transform.right_to_right.into(), activation, context)?;
+    Ok(object.into())
 }
 
 fn get_volume<'gc>(
     _activation: &mut Activation<'_, 'gc>,
-    _context: &mut UpdateContext<'_, 'gc, '_>,
-    _this: Object<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Object<'gc>,
     _args: &[Value<'gc>],
 ) -> Result<Value<'gc>, Error<'gc>> {
-    log::warn!("Sound.getVolume: Unimplemented");
-    Ok(100.into())
+    let transform = sound_transform(context, this, "Sound.getVolume").unwrap_or_default();
+    Ok(transform.volume.into())
 }
 
 fn id3<'gc>(
     activation: &mut Activation<'_, 'gc>,
     _context: &mut UpdateContext<'_, 'gc, '_>,
-    _this: Object<'gc>,
+    this: Object<'gc>,
     _args: &[Value<'gc>],
 ) -> Result<Value<'gc>, Error<'gc>> {
     if activation.current_swf_version() >= 6 {
-        log::warn!("Sound.id3: Unimplemented");
+        if let Some(id3) = this.as_sound_object().and_then(|o| o.id3()) {
+            return Ok(id3.into());
+        }
     }
     Ok(Value::Undefined)
 }
 
+/// Creates the object for the `id3` property of a `Sound` from the ID3 tags of a loaded sound.
+pub fn create_id3_object<'gc>(
+    gc_context: MutationContext<'gc, '_>,
+    proto: Object<'gc>,
+    tags: &[(String, String)],
+) -> Object<'gc> {
+    let object = ScriptObject::object(gc_context, Some(proto));
+    for (id, value) in tags {
+        // The common tags are also available under descriptive names.
+        let name = match id.as_str() {
+            "TIT2" => Some("songname"),
+            "TPE1" => Some("artist"),
+            "TALB" => Some("album"),
+            "TYER" => Some("year"),
+            "COMM" => Some("comment"),
+            "TRCK" => Some("track"),
+            "TCON" => Some("genre"),
+            _ => None,
+        };
+        let value: Value<'gc> = AvmString::new(gc_context, value.clone()).into();
+        if let Some(name) = name {
+            object.define_value(gc_context, name, value.clone(), EnumSet::empty());
+        }
+        object.define_value(
+            gc_context,
+            AvmString::new(gc_context, id.clone()),
+            value,
+            EnumSet::empty(),
+        );
+    }
+    object.into()
+}
+
 fn load_sound<'gc>(
     activation: &mut Activation<'_, 'gc>,
-    _context: &mut UpdateContext<'_, 'gc, '_>,
-    _this: Object<'gc>,
-    _args: &[Value<'gc>],
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Object<'gc>,
+    args: &[Value<'gc>],
 ) -> Result<Value<'gc>, Error<'gc>> {
     if activation.current_swf_version() >= 6 {
-        log::warn!("Sound.loadSound: Unimplemented");
+        if let Some(sound_object) = this.as_sound_object() {
+            let url = args
+                .get(0)
+                .unwrap_or(&Value::Undefined)
+                .coerce_to_string(activation, context)?;
+            let is_streaming = args
+                .get(1)
+                .unwrap_or(&Value::Undefined)
+                .as_bool(activation.current_swf_version());
+
+            // Loading a new sound replaces the one playing on this object.
+            context
+                .audio_manager
+                .stop_sounds_with_avm1_object(context.audio, sound_object);
+            sound_object.set_sound(context.gc_context, None);
+            sound_object.set_id3(context.gc_context, None);
+            sound_object.set_load_progress(context.gc_context, Some(0), None);
+
+            let fetch = context.navigator.fetch_stream(&url, RequestOptions::get());
+            let process = context.load_manager.load_sound_into_object(
+                context.player.clone().unwrap(),
+                sound_object,
+                activation.target_clip_or_root(),
+                fetch,
+                is_streaming,
+            );
+            context.navigator.spawn_future(process);
+        } else {
+            log::warn!("Sound.loadSound: this is not a Sound");
+        }
     }
     Ok(Value::Undefined)
 }
 
 fn position<'gc>(
     activation: &mut Activation<'_, 'gc>,
-    _context: &mut UpdateContext<'_, 'gc, '_>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
     this: Object<'gc>,
     _args: &[Value<'gc>],
 ) -> Result<Value<'gc>, Error<'gc>> {
     if activation.current_swf_version() >= 6 {
         if let Some(sound_object) = this.as_sound_object() {
-            // TODO: The position is "sticky"; even if the sound is no longer playing, it should return
-            // the previous valid position.
-            // Needs some audio backend work for this.
             if sound_object.sound().is_some() {
-                if let Some(_sound_instance) = sound_object.sound_instance() {
-                    log::warn!("Sound.position: Unimplemented");
+                // The position is "sticky"; once the sound stops playing, the last valid position
+                // is returned.
+                if let Some(position) = sound_object
+                    .sound_instance()
+                    .and_then(|instance| context.audio.get_sound_position(instance))
+                {
+                    sound_object.set_position(context.gc_context, position);
                 }
                 return Ok(sound_object.position().into());
             }
@@ -324,32 +444,64 @@ fn position<'gc>(
 }
 
 fn set_pan<'gc>(
-    _activation: &mut Activation<'_, 'gc>,
-    _context: &mut UpdateContext<'_, 'gc, '_>,
-    _this: Object<'gc>,
-    _args: &[Value<'gc>],
+    activation: &mut Activation<'_, 'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Object<'gc>,
+    args: &[Value<'gc>],
 ) -> Result<Value<'gc>, Error<'gc>> {
-    log::warn!("Sound.setPan: Unimplemented");
+    if let Some(mut transform) = sound_transform(context, this, "Sound.setPan") {
+        let pan = args
+            .get(0)
+            .unwrap_or(&Value::Undefined)
+            .coerce_to_i32(activation, context)?;
+        transform.set_pan(pan);
+        set_sound_transform(context, this, transform);
+    }
     Ok(Value::Undefined)
 }
 
 fn set_transform<'gc>(
-    _activation: &mut Activation<'_, 'gc>,
-    _context: &mut UpdateContext<'_, 'gc, '_>,
-    _this: Object<'gc>,
-    _args: &[Value<'gc>],
+    activation: &mut Activation<'_, 'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Object<'gc>,
+    args: &[Value<'gc>],
 ) -> Result<Value<'gc>, Error<'gc>> {
-    log::warn!("Sound.setTransform: Unimplemented");
+    if let (Some(mut transform), Some(Value::Object(object))) = (
+        sound_transform(context, this, "Sound.setTransform"),
+        args.get(0),
+    ) {
+        // Only the channels present on the object are changed.
+        for (name, channel) in &mut [
+            ("ll", &mut transform.left_to_left),
+            ("lr", &mut transform.left_to_right),
+            ("rl", &mut transform.right_to_left),
+            ("rr", &mut transform.right_to_right),
+        ] {
+            if object.has_property(activation, context, name) {
+                **channel = object
+                    .get(name, activation, context)?
+                    .coerce_to_i32(activation, context)?;
+            }
+        }
+        set_sound_transform(context, this, transform);
+    }
     Ok(Value::Undefined)
 }
 
 fn set_volume<'gc>(
-    _activation: &mut Activation<'_, 'gc>,
-    _context: &mut UpdateContext<'_, 'gc, '_>,
-    _this: Object<'gc>,
-    _args: &[Value<'gc>],
+    activation: &mut Activation<'_, 'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Object<'gc>,
+    args: &[Value<'gc>],
 ) -> Result<Value<'gc>, Error<'gc>> {
-    log::warn!("Sound.setVolume: Unimplemented");
+    if let Some(mut transform) = sound_transform(context, this, "Sound.setVolume") {
+        let volume = args
+            .get(0)
+            .unwrap_or(&Value::Undefined)
+            .coerce_to_i32(activation, context)?;
+        transform.volume = volume;
+        set_sound_transform(context, this, transform);
+    }
     Ok(Value::Undefined)
 }
 
@@ -377,7 +529,8 @@ fn start<'gc>(
     use swf::{SoundEvent, SoundInfo};
     if let Some(sound_object) = this.as_sound_object() {
         if let Some(sound) = sound_object.sound() {
-            let sound_instance = context.audio.start_sound(
+            let sound_instance = context.audio_manager.start_sound(
+                context.audio,
                 sound,
                 &SoundInfo {
                     event: SoundEvent::Start,
@@ -390,9 +543,12 @@ fn start<'gc>(
                     num_loops: loops,
                     envelope: None,
                 },
+                sound_object.owner(),
+                Some(sound_object),
             );
-            if let Ok(sound_instance) = sound_instance {
+            if let Some(sound_instance) = sound_instance {
                 sound_object.set_sound_instance(context.gc_context, Some(sound_instance));
+                sound_object.set_position(context.gc_context, (start_offset * 1000.0) as u32);
             }
         } else {
             log::warn!("Sound.start: No sound is attached");
@@ -425,7 +581,9 @@ fn stop<'gc>(
                     .get_character_by_export_name(&name)
                 {
                     // Stop all sounds with the given name.
-                    context.audio.stop_sounds_with_handle(*sound);
+                    context
+                        .audio_manager
+                        .stop_sounds_with_handle(context.audio, *sound);
                 } else {
                     log::warn!("Sound.stop: Sound '{}' not found", name);
                 }
@@ -435,15 +593,14 @@ fn stop<'gc>(
                     name
                 )
             }
-        } else if let Some(_owner) = sound.owner() {
-            // Usage 2: Stop all sound running within a given clip.
-            // TODO: We just stop the last played sound for now.
-            if let Some(sound_instance) = sound.sound_instance() {
-                context.audio.stop_sound(sound_instance);
-            }
+        } else if sound.owner().is_some() {
+            // Usage 2: Stop all sounds started by this object.
+            context
+                .audio_manager
+                .stop_sounds_with_avm1_object(context.audio, sound);
         } else {
             // Usage 3: If there is no owner and no name, this call acts like `stopAllSounds()`.
-            context.audio.stop_all_sounds();
+            context.audio_manager.stop_all_sounds(context.audio);
         }
     } else {
         log::warn!("Sound.stop: this is not a Sound");
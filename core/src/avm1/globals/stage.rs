@@ -1,28 +1,23 @@
 //! Stage object
-//!
-//! TODO: This is a very rough stub with not much implementation.
+
 use crate::avm1::activation::Activation;
 use crate::avm1::error::Error;
 use crate::avm1::function::Executable;
+use crate::avm1::globals::as_broadcaster::BroadcasterFunctions;
 use crate::avm1::property::Attribute;
-use crate::avm1::{Object, ScriptObject, TObject, UpdateContext, Value};
+use crate::avm1::{AvmString, Object, ScriptObject, TObject, UpdateContext, Value};
+use crate::stage::{StageAlign, StageScaleMode};
 use gc_arena::MutationContext;
 
 pub fn create_stage_object<'gc>(
     gc_context: MutationContext<'gc, '_>,
     proto: Option<Object<'gc>>,
-    _array_proto: Option<Object<'gc>>,
-    fn_proto: Option<Object<'gc>>,
+    broadcaster_functions: BroadcasterFunctions<'gc>,
+    array_proto: Object<'gc>,
 ) -> Object<'gc> {
-    let mut stage = ScriptObject::object(gc_context, proto);
+    let stage = ScriptObject::object(gc_context, proto);
 
-    stage.force_set_function(
-        "addListener",
-        add_listener,
-        gc_context,
-        Attribute::DontEnum | Attribute::DontDelete | Attribute::ReadOnly,
-        fn_proto,
-    );
+    broadcaster_functions.initialize(gc_context, stage.into(), array_proto);
 
     stage.add_property(
         gc_context,
@@ -34,18 +29,18 @@ pub fn create_stage_object<'gc>(
 
     stage.add_property(
         gc_context,
-        "height",
-        Executable::Native(height),
-        None,
-        Attribute::DontEnum | Attribute::DontDelete | Attribute::ReadOnly,
+        "displayState",
+        Executable::Native(display_state),
+        Some(Executable::Native(set_display_state)),
+        Attribute::DontEnum | Attribute::DontDelete,
     );
 
-    stage.force_set_function(
-        "removeListener",
-        remove_listener,
+    stage.add_property(
         gc_context,
+        "height",
+        Executable::Native(height),
+        None,
         Attribute::DontEnum | Attribute::DontDelete | Attribute::ReadOnly,
-        fn_proto,
     );
 
     stage.add_property(
@@ -75,92 +70,119 @@ pub fn create_stage_object<'gc>(
     stage.into()
 }
 
-fn add_listener<'gc>(
-    _activation: &mut Activation<'_, 'gc>,
-    _context: &mut UpdateContext<'_, 'gc, '_>,
-    _this: Object<'gc>,
-    _args: &[Value<'gc>],
-) -> Result<Value<'gc>, Error<'gc>> {
-    log::warn!("Stage.addListener: unimplemented");
-    Ok(Value::Undefined)
+/// Returns the size of the stage in pixels, as reported by `Stage.width` and `Stage.height`.
+fn stage_size(context: &UpdateContext<'_, '_, '_>) -> (u32, u32) {
+    let movie_size = (
+        context.stage_size.0.to_pixels() as u32,
+        context.stage_size.1.to_pixels() as u32,
+    );
+    context.stage.stage_size(movie_size)
 }
 
 fn align<'gc>(
     _activation: &mut Activation<'_, 'gc>,
-    _context: &mut UpdateContext<'_, 'gc, '_>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
     _this: Object<'gc>,
     _args: &[Value<'gc>],
 ) -> Result<Value<'gc>, Error<'gc>> {
-    log::warn!("Stage.align: unimplemented");
-    Ok("".into())
+    Ok(AvmString::new(context.gc_context, context.stage.align.name()).into())
 }
 
 fn set_align<'gc>(
-    _activation: &mut Activation<'_, 'gc>,
-    _context: &mut UpdateContext<'_, 'gc, '_>,
+    activation: &mut Activation<'_, 'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
     _this: Object<'gc>,
-    _args: &[Value<'gc>],
+    args: &[Value<'gc>],
 ) -> Result<Value<'gc>, Error<'gc>> {
-    log::warn!("Stage.align: unimplemented");
+    let align = args
+        .get(0)
+        .unwrap_or(&Value::Undefined)
+        .coerce_to_string(activation, context)?;
+    context.stage.align = StageAlign::from_name(&align);
     Ok(Value::Undefined)
 }
 
-fn height<'gc>(
+fn display_state<'gc>(
     _activation: &mut Activation<'_, 'gc>,
     context: &mut UpdateContext<'_, 'gc, '_>,
     _this: Object<'gc>,
     _args: &[Value<'gc>],
 ) -> Result<Value<'gc>, Error<'gc>> {
-    Ok(context.stage_size.1.to_pixels().into())
+    Ok(AvmString::new(context.gc_context, context.stage.display_state.clone()).into())
+}
+
+fn set_display_state<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    _this: Object<'gc>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let display_state = args
+        .get(0)
+        .unwrap_or(&Value::Undefined)
+        .coerce_to_string(activation, context)?;
+    if display_state.eq_ignore_ascii_case("normal") {
+        context.stage.display_state = "normal".to_string();
+    } else if display_state.eq_ignore_ascii_case("fullScreen") {
+        log::warn!("Stage.displayState: Full screen mode is not supported");
+        context.stage.display_state = "fullScreen".to_string();
+    }
+    Ok(Value::Undefined)
 }
 
-fn remove_listener<'gc>(
+fn height<'gc>(
     _activation: &mut Activation<'_, 'gc>,
-    _context: &mut UpdateContext<'_, 'gc, '_>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
     _this: Object<'gc>,
     _args: &[Value<'gc>],
 ) -> Result<Value<'gc>, Error<'gc>> {
-    log::warn!("Stage.removeListener: unimplemented");
-    Ok("".into())
+    Ok(stage_size(context).1.into())
 }
 
 fn scale_mode<'gc>(
     _activation: &mut Activation<'_, 'gc>,
-    _context: &mut UpdateContext<'_, 'gc, '_>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
     _this: Object<'gc>,
     _args: &[Value<'gc>],
 ) -> Result<Value<'gc>, Error<'gc>> {
-    log::warn!("Stage.scaleMode: unimplemented");
-    Ok("noScale".into())
+    Ok(context.stage.scale_mode.name().into())
 }
 
 fn set_scale_mode<'gc>(
-    _activation: &mut Activation<'_, 'gc>,
-    _context: &mut UpdateContext<'_, 'gc, '_>,
+    activation: &mut Activation<'_, 'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
     _this: Object<'gc>,
-    _args: &[Value<'gc>],
+    args: &[Value<'gc>],
 ) -> Result<Value<'gc>, Error<'gc>> {
-    log::warn!("Stage.scaleMode: unimplemented");
+    let scale_mode = args
+        .get(0)
+        .unwrap_or(&Value::Undefined)
+        .coerce_to_string(activation, context)?;
+    if let Some(scale_mode) = StageScaleMode::from_name(&scale_mode) {
+        context.stage.scale_mode = scale_mode;
+    }
     Ok(Value::Undefined)
 }
 
 fn show_menu<'gc>(
     _activation: &mut Activation<'_, 'gc>,
-    _context: &mut UpdateContext<'_, 'gc, '_>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
     _this: Object<'gc>,
     _args: &[Value<'gc>],
 ) -> Result<Value<'gc>, Error<'gc>> {
-    log::warn!("Stage.showMenu: unimplemented");
-    Ok(true.into())
+    Ok(context.stage.show_menu.into())
 }
 
 fn set_show_menu<'gc>(
-    _activation: &mut Activation<'_, 'gc>,
-    _context: &mut UpdateContext<'_, 'gc, '_>,
+    activation: &mut Activation<'_, 'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
     _this: Object<'gc>,
-    _args: &[Value<'gc>],
+    args: &[Value<'gc>],
 ) -> Result<Value<'gc>, Error<'gc>> {
-    log::warn!("Stage.showMenu: unimplemented");
+    context.stage.show_menu = args
+        .get(0)
+        .unwrap_or(&Value::Undefined)
+        .as_bool(activation.current_swf_version());
     Ok(Value::Undefined)
 }
 
@@ -170,5 +192,5 @@ fn width<'gc>(
     _this: Object<'gc>,
     _args: &[Value<'gc>],
 ) -> Result<Value<'gc>, Error<'gc>> {
-    Ok(context.stage_size.0.to_pixels().into())
+    Ok(stage_size(context).0.into())
 }
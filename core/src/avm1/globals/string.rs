@@ -5,7 +5,7 @@ use crate::avm1::error::Error;
 use crate::avm1::function::{Executable, FunctionObject};
 use crate::avm1::property::Attribute::*;
 use crate::avm1::value_object::ValueObject;
-use crate::avm1::{Object, ScriptObject, TObject, Value};
+use crate::avm1::{AvmString, Object, ScriptObject, TObject, Value};
 use crate::context::UpdateContext;
 use crate::string_utils;
 use enumset::EnumSet;
@@ -20,14 +20,14 @@ pub fn string<'gc>(
 ) -> Result<Value<'gc>, Error<'gc>> {
     let value = match args.get(0).cloned() {
         Some(Value::String(s)) => s,
-        Some(v) => v.coerce_to_string(activation, ac)?.to_string(),
-        _ => String::new(),
+        Some(v) => AvmString::new(ac.gc_context, v.coerce_to_string(activation, ac)?),
+        _ => "".into(),
     };
 
     if let Some(mut vbox) = this.as_value_object() {
         let len = value.encode_utf16().count();
         vbox.set_length(ac.gc_context, len);
-        vbox.replace_value(ac.gc_context, value.clone().into());
+        vbox.replace_value(ac.gc_context, value.into());
     }
 
     Ok(value.into())
@@ -193,7 +193,7 @@ fn char_at<'gc>(
     } else {
         "".into()
     };
-    Ok(ret.into())
+    Ok(AvmString::new(context.gc_context, ret).into())
 }
 
 fn char_code_at<'gc>(
@@ -232,7 +232,7 @@ fn concat<'gc>(
         let s = arg.coerce_to_string(activation, context)?;
         ret.push_str(&s)
     }
-    Ok(ret.into())
+    Ok(AvmString::new(context.gc_context, ret).into())
 }
 
 fn from_char_code<'gc>(
@@ -242,16 +242,21 @@ fn from_char_code<'gc>(
     args: &[Value<'gc>],
 ) -> Result<Value<'gc>, Error<'gc>> {
     // TODO: Unpaired surrogates will be replace with Unicode replacement char.
-    let mut out = String::with_capacity(args.len());
+    let mut code_units = Vec::with_capacity(args.len());
     for arg in args {
         let i = arg.coerce_to_u16(activation, context)?;
         if i == 0 {
             // Stop at a null-terminator.
             break;
         }
-        out.push(utf16_code_unit_to_char(i));
+        code_units.push(i);
     }
-    Ok(out.into())
+    // Surrogate pairs passed as two separate codes combine into one character.
+    Ok(AvmString::new(
+        context.gc_context,
+        utf16_iter_to_string(code_units.into_iter()),
+    )
+    .into())
 }
 
 fn index_of<'gc>(
@@ -384,7 +389,7 @@ fn slice<'gc>(
                 .skip(start_index)
                 .take(end_index - start_index),
         );
-        Ok(ret.into())
+        Ok(AvmString::new(context.gc_context, ret).into())
     } else {
         Ok("".into())
     }
@@ -398,23 +403,47 @@ fn split<'gc>(
 ) -> Result<Value<'gc>, Error<'gc>> {
     let this_val = Value::from(this);
     let this = this_val.coerce_to_string(activation, context)?;
-    let delimiter_val = args.get(0).unwrap_or(&Value::Undefined);
-    let delimiter = delimiter_val.coerce_to_string(activation, context)?;
     let limit = match args.get(1) {
         None | Some(Value::Undefined) => std::usize::MAX,
         Some(n) => std::cmp::max(0, n.coerce_to_i32(activation, context)?) as usize,
     };
     let array = ScriptObject::array(context.gc_context, Some(activation.avm.prototypes.array));
+    let delimiter = match args.get(0) {
+        None | Some(Value::Undefined) => {
+            // Without a delimiter, the whole string is the only element.
+            if limit > 0 {
+                array.set_array_element(
+                    0,
+                    AvmString::new(context.gc_context, this.to_string()).into(),
+                    context.gc_context,
+                );
+            }
+            return Ok(array.into());
+        }
+        Some(delimiter) => delimiter.coerce_to_string(activation, context)?,
+    };
     if !delimiter.is_empty() {
         for (i, token) in this.split(delimiter.as_ref()).take(limit).enumerate() {
-            array.set_array_element(i, token.to_string().into(), context.gc_context);
+            array.set_array_element(
+                i,
+                AvmString::new(context.gc_context, token.to_string()).into(),
+                context.gc_context,
+            );
         }
     } else {
         // When using an empty "" delimiter, Rust's str::split adds an extra beginning and trailing item, but Flash does not.
         // e.g., split("foo", "") returns ["", "f", "o", "o", ""] in Rust but ["f, "o", "o"] in Flash.
-        // Special case this to match Flash's behavior.
-        for (i, token) in this.chars().take(limit).enumerate() {
-            array.set_array_element(i, token.to_string().into(), context.gc_context);
+        // Special case this to match Flash's behavior, splitting into UTF-16 code units like `charAt`.
+        for (i, token) in this.encode_utf16().take(limit).enumerate() {
+            array.set_array_element(
+                i,
+                AvmString::new(
+                    context.gc_context,
+                    utf16_code_unit_to_char(token).to_string(),
+                )
+                .into(),
+                context.gc_context,
+            );
         }
     }
     Ok(array.into())
@@ -438,13 +467,25 @@ fn substr<'gc>(
         this_len,
     );
 
-    let len = match args.get(1) {
+    // A negative length counts backwards from the end of the string.
+    let end_index = match args.get(1) {
         None | Some(Value::Undefined) => this_len,
-        Some(n) => string_index(n.coerce_to_i32(activation, context)?, this_len),
+        Some(n) => {
+            let len = n.coerce_to_i32(activation, context)?;
+            string_wrapping_index((start_index as i32).wrapping_add(len), this_len)
+        }
     };
 
-    let ret = utf16_iter_to_string(this.encode_utf16().skip(start_index).take(len));
-    Ok(ret.into())
+    if start_index < end_index {
+        let ret = utf16_iter_to_string(
+            this.encode_utf16()
+                .skip(start_index)
+                .take(end_index - start_index),
+        );
+        Ok(AvmString::new(context.gc_context, ret).into())
+    } else {
+        Ok("".into())
+    }
 }
 
 fn substring<'gc>(
@@ -479,7 +520,7 @@ fn substring<'gc>(
             .skip(start_index)
             .take(end_index - start_index),
     );
-    Ok(ret.into())
+    Ok(AvmString::new(context.gc_context, ret).into())
 }
 
 fn to_lower_case<'gc>(
@@ -490,11 +531,13 @@ fn to_lower_case<'gc>(
 ) -> Result<Value<'gc>, Error<'gc>> {
     let this_val = Value::from(this);
     let this = this_val.coerce_to_string(activation, context)?;
-    Ok(this
-        .chars()
-        .map(string_utils::swf_char_to_lowercase)
-        .collect::<String>()
-        .into())
+    Ok(AvmString::new(
+        context.gc_context,
+        this.chars()
+            .map(string_utils::swf_char_to_lowercase)
+            .collect::<String>(),
+    )
+    .into())
 }
 
 /// `String.toString` / `String.valueOf` impl
@@ -524,11 +567,13 @@ fn to_upper_case<'gc>(
 ) -> Result<Value<'gc>, Error<'gc>> {
     let this_val = Value::from(this);
     let this = this_val.coerce_to_string(activation, context)?;
-    Ok(this
-        .chars()
-        .map(string_utils::swf_char_to_uppercase)
-        .collect::<String>()
-        .into())
+    Ok(AvmString::new(
+        context.gc_context,
+        this.chars()
+            .map(string_utils::swf_char_to_uppercase)
+            .collect::<String>(),
+    )
+    .into())
 }
 
 /// Normalizes an  index paramter used in `String` functions such as `substring`.
@@ -558,12 +603,7 @@ fn string_wrapping_index(i: i32, len: usize) -> usize {
             len
         }
     } else {
-        let i = (-i) as usize;
-        if i <= len {
-            len - i
-        } else {
-            len
-        }
+        len.saturating_sub((-i64::from(i)) as usize)
     }
 }
 
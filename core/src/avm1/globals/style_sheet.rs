@@ -0,0 +1,290 @@
+//! `TextField.StyleSheet` impl
+
+use crate::avm1::activation::Activation;
+use crate::avm1::error::Error;
+use crate::avm1::property::Attribute;
+use crate::avm1::style_sheet_object::StyleSheetObject;
+use crate::avm1::{AvmString, Object, ScriptObject, TObject, Value};
+use crate::backend::navigator::RequestOptions;
+use crate::context::UpdateContext;
+use crate::html::{text_format_from_css, CssStyle};
+use enumset::EnumSet;
+use gc_arena::MutationContext;
+
+/// Implements `TextField.StyleSheet`
+pub fn constructor<'gc>(
+    _activation: &mut Activation<'_, 'gc>,
+    _context: &mut UpdateContext<'_, 'gc, '_>,
+    _this: Object<'gc>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    Ok(Value::Undefined)
+}
+
+/// Read an AVM1 style object into a `CssStyle`.
+fn css_style_from_object<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    object: Object<'gc>,
+) -> Result<CssStyle, Error<'gc>> {
+    let mut style = CssStyle::new();
+
+    for key in object.get_keys(activation, context.gc_context) {
+        let value = object.get(&key, activation, context)?;
+        let value = value.coerce_to_string(activation, context)?.into_owned();
+        style.insert(key.to_string(), value);
+    }
+
+    Ok(style)
+}
+
+pub fn parse_css<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Object<'gc>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    if let Some(style_sheet) = this.as_style_sheet_object() {
+        let css = args
+            .get(0)
+            .unwrap_or(&Value::Undefined)
+            .coerce_to_string(activation, context)?;
+
+        return Ok(style_sheet
+            .style_sheet_mut(context.gc_context)
+            .parse_css(&css)
+            .into());
+    }
+
+    Ok(false.into())
+}
+
+pub fn get_style<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Object<'gc>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    if let Some(style_sheet) = this.as_style_sheet_object() {
+        let name = args
+            .get(0)
+            .unwrap_or(&Value::Undefined)
+            .coerce_to_string(activation, context)?;
+
+        // Scripts get a copy of the style, so changes to it must be made
+        // through `setStyle`.
+        let style = match style_sheet.style_sheet().style(&name) {
+            Some(style) => style.clone(),
+            None => return Ok(Value::Null),
+        };
+
+        let object =
+            ScriptObject::object(context.gc_context, Some(activation.avm.prototypes.object));
+        for (key, value) in style {
+            object.set(
+                AvmString::new(context.gc_context, key),
+                AvmString::new(context.gc_context, value).into(),
+                activation,
+                context,
+            )?;
+        }
+
+        return Ok(object.into());
+    }
+
+    Ok(Value::Undefined)
+}
+
+pub fn set_style<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Object<'gc>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    if let Some(style_sheet) = this.as_style_sheet_object() {
+        let name = args
+            .get(0)
+            .unwrap_or(&Value::Undefined)
+            .coerce_to_string(activation, context)?
+            .into_owned();
+
+        // Setting a style to `null` or `undefined` removes it.
+        let style = match args.get(1) {
+            None | Some(Value::Undefined) | Some(Value::Null) => None,
+            Some(value) => {
+                let object = value.coerce_to_object(activation, context);
+                Some(css_style_from_object(activation, context, object)?)
+            }
+        };
+
+        style_sheet
+            .style_sheet_mut(context.gc_context)
+            .set_style(&name, style);
+    }
+
+    Ok(Value::Undefined)
+}
+
+pub fn get_style_names<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Object<'gc>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let array = ScriptObject::array(context.gc_context, Some(activation.avm.prototypes.array));
+
+    if let Some(style_sheet) = this.as_style_sheet_object() {
+        for (i, name) in style_sheet.style_sheet().style_names().enumerate() {
+            array.set_array_element(
+                i,
+                AvmString::new(context.gc_context, name).into(),
+                context.gc_context,
+            );
+        }
+    }
+
+    Ok(array.into())
+}
+
+pub fn clear<'gc>(
+    _activation: &mut Activation<'_, 'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Object<'gc>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    if let Some(style_sheet) = this.as_style_sheet_object() {
+        style_sheet.style_sheet_mut(context.gc_context).clear();
+    }
+
+    Ok(Value::Undefined)
+}
+
+pub fn transform<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    _this: Object<'gc>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let object = match args.get(0) {
+        None | Some(Value::Undefined) | Some(Value::Null) => return Ok(Value::Null),
+        Some(value) => value.coerce_to_object(activation, context),
+    };
+
+    let style = css_style_from_object(activation, context, object)?;
+    Ok(text_format_from_css(&style)
+        .as_avm1_object(activation, context)?
+        .into())
+}
+
+pub fn load<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Object<'gc>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let url = match args.get(0) {
+        Some(Value::Undefined) | Some(Value::Null) | None => return Ok(false.into()),
+        Some(url) => url.coerce_to_string(activation, context)?.into_owned(),
+    };
+
+    this.define_value(
+        context.gc_context,
+        "loaded",
+        false.into(),
+        Attribute::DontEnum.into(),
+    );
+
+    // Like `LoadVars`, the response is handed to `onData` as a string.
+    let fetch = context.navigator.fetch(&url, RequestOptions::get());
+    let process = context.load_manager.load_form_into_load_vars(
+        context.player.clone().unwrap(),
+        this,
+        activation.target_clip_or_root(),
+        fetch,
+    );
+    context.navigator.spawn_future(process);
+
+    Ok(true.into())
+}
+
+pub fn on_data<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Object<'gc>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let success = match args.get(0) {
+        None | Some(Value::Undefined) | Some(Value::Null) => false,
+        Some(css) => {
+            let css = css.coerce_to_string(activation, context)?;
+            this.as_style_sheet_object()
+                .map(|style_sheet| {
+                    style_sheet
+                        .style_sheet_mut(context.gc_context)
+                        .parse_css(&css)
+                })
+                .unwrap_or(false)
+        }
+    };
+
+    this.set("loaded", success.into(), activation, context)?;
+    this.call_method("onLoad", &[success.into()], activation, context)?;
+
+    Ok(Value::Undefined)
+}
+
+pub fn create_proto<'gc>(
+    gc_context: MutationContext<'gc, '_>,
+    proto: Object<'gc>,
+    fn_proto: Object<'gc>,
+) -> Object<'gc> {
+    let style_sheet = StyleSheetObject::empty_style_sheet_object(gc_context, Some(proto));
+    let mut object = style_sheet.as_script_object().unwrap();
+
+    object.force_set_function(
+        "parseCSS",
+        parse_css,
+        gc_context,
+        EnumSet::empty(),
+        Some(fn_proto),
+    );
+    object.force_set_function(
+        "getStyle",
+        get_style,
+        gc_context,
+        EnumSet::empty(),
+        Some(fn_proto),
+    );
+    object.force_set_function(
+        "setStyle",
+        set_style,
+        gc_context,
+        EnumSet::empty(),
+        Some(fn_proto),
+    );
+    object.force_set_function(
+        "getStyleNames",
+        get_style_names,
+        gc_context,
+        EnumSet::empty(),
+        Some(fn_proto),
+    );
+    object.force_set_function("clear", clear, gc_context, EnumSet::empty(), Some(fn_proto));
+    object.force_set_function(
+        "transform",
+        transform,
+        gc_context,
+        EnumSet::empty(),
+        Some(fn_proto),
+    );
+    object.force_set_function("load", load, gc_context, EnumSet::empty(), Some(fn_proto));
+    object.force_set_function(
+        "onData",
+        on_data,
+        gc_context,
+        EnumSet::empty(),
+        Some(fn_proto),
+    );
+
+    style_sheet.into()
+}
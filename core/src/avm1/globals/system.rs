@@ -8,8 +8,12 @@ use core::fmt;
 use enumset::{EnumSet, EnumSetType};
 use gc_arena::MutationContext;
 use num_enum::TryFromPrimitive;
+use percent_encoding::{AsciiSet, NON_ALPHANUMERIC};
 use std::convert::TryFrom;
 
+/// The characters that are escaped in `System.capabilities.serverString`.
+const SERVER_STRING_ESCAPES: &AsciiSet = &NON_ALPHANUMERIC.remove(b'.').remove(b'-').remove(b'_');
+
 /// Available cpu architectures
 pub enum CpuArchitecture {
     PowerPC,
@@ -137,6 +141,40 @@ pub enum Language {
 }
 
 impl Language {
+    /// Look up a language from a locale, such as `en-US` or `de_DE.UTF-8`.
+    pub fn from_language_code(code: &str) -> Self {
+        let code = code.to_lowercase().replace('_', "-");
+        let mut parts = code.split(|c| c == '-' || c == '.');
+        let language = parts.next().unwrap_or_default();
+        let region = parts.next().unwrap_or_default();
+
+        match language {
+            "cs" => Language::Czech,
+            "da" => Language::Danish,
+            "nl" => Language::Dutch,
+            "en" => Language::English,
+            "fi" => Language::Finnish,
+            "fr" => Language::French,
+            "de" => Language::German,
+            "hu" => Language::Hungarian,
+            "it" => Language::Italian,
+            "ja" => Language::Japanese,
+            "ko" => Language::Korean,
+            "no" | "nb" | "nn" => Language::Norwegian,
+            "pl" => Language::Polish,
+            "pt" => Language::Portuguese,
+            "ru" => Language::Russian,
+            "zh" if region == "tw" || region == "hk" || region == "hant" => {
+                Language::TraditionalChinese
+            }
+            "zh" => Language::SimplifiedChinese,
+            "es" => Language::Spanish,
+            "sv" => Language::Swedish,
+            "tr" => Language::Turkish,
+            _ => Language::Unknown,
+        }
+    }
+
     pub fn get_language_code(&self, player_version: u8) -> &str {
         match self {
             Language::Czech => "cs",
@@ -270,10 +308,17 @@ pub struct SystemProperties {
     pub cpu_architecture: CpuArchitecture,
     /// The highest supported h264 decoder level
     pub idc_level: String,
+    /// The version reported to content, such as `WIN 9,0,115,0`
+    /// If `None`, it is built from the platform and the emulated player version
+    pub version: Option<String>,
 }
 
 impl SystemProperties {
     pub fn get_version_string(&self, activation: &mut Activation) -> String {
+        if let Some(version) = &self.version {
+            return version.clone();
+        }
+
         format!(
             "{} {},0,0,0",
             self.manufacturer.get_platform_name(),
@@ -302,79 +347,201 @@ impl SystemProperties {
     }
 
     fn encode_string(&self, s: &str) -> String {
-        percent_encoding::utf8_percent_encode(s, percent_encoding::NON_ALPHANUMERIC).to_string()
+        percent_encoding::utf8_percent_encode(s, SERVER_STRING_ESCAPES).to_string()
     }
 
     pub fn get_server_string(&self, activation: &mut Activation) -> String {
-        url::form_urlencoded::Serializer::new(String::new())
-            .append_pair("A", self.encode_capability(SystemCapabilities::Audio))
-            .append_pair(
+        let player_version = activation.avm.player_version;
+        let pairs = [
+            (
+                "A",
+                self.encode_capability(SystemCapabilities::Audio)
+                    .to_string(),
+            ),
+            (
                 "SA",
-                self.encode_capability(SystemCapabilities::StreamingAudio),
-            )
-            .append_pair(
+                self.encode_capability(SystemCapabilities::StreamingAudio)
+                    .to_string(),
+            ),
+            (
                 "SV",
-                self.encode_capability(SystemCapabilities::StreamingVideo),
-            )
-            .append_pair(
+                self.encode_capability(SystemCapabilities::StreamingVideo)
+                    .to_string(),
+            ),
+            (
                 "EV",
-                self.encode_capability(SystemCapabilities::EmbeddedVideo),
-            )
-            .append_pair("MP3", self.encode_capability(SystemCapabilities::MP3))
-            .append_pair(
+                self.encode_capability(SystemCapabilities::EmbeddedVideo)
+                    .to_string(),
+            ),
+            (
+                "MP3",
+                self.encode_capability(SystemCapabilities::MP3).to_string(),
+            ),
+            (
                 "AE",
-                self.encode_capability(SystemCapabilities::AudioEncoder),
-            )
-            .append_pair(
+                self.encode_capability(SystemCapabilities::AudioEncoder)
+                    .to_string(),
+            ),
+            (
                 "VE",
-                self.encode_capability(SystemCapabilities::VideoEncoder),
-            )
-            .append_pair(
+                self.encode_capability(SystemCapabilities::VideoEncoder)
+                    .to_string(),
+            ),
+            (
                 "ACC",
-                self.encode_not_capability(SystemCapabilities::Accessibility),
-            )
-            .append_pair("PR", self.encode_capability(SystemCapabilities::Printing))
-            .append_pair(
+                self.encode_not_capability(SystemCapabilities::Accessibility)
+                    .to_string(),
+            ),
+            (
+                "PR",
+                self.encode_capability(SystemCapabilities::Printing)
+                    .to_string(),
+            ),
+            (
                 "SP",
-                self.encode_capability(SystemCapabilities::ScreenPlayback),
-            )
-            .append_pair(
+                self.encode_capability(SystemCapabilities::ScreenPlayback)
+                    .to_string(),
+            ),
+            (
                 "SB",
-                self.encode_capability(SystemCapabilities::ScreenBroadcast),
-            )
-            .append_pair("DEB", self.encode_capability(SystemCapabilities::Debugger))
-            .append_pair(
+                self.encode_capability(SystemCapabilities::ScreenBroadcast)
+                    .to_string(),
+            ),
+            (
+                "DEB",
+                self.encode_capability(SystemCapabilities::Debugger)
+                    .to_string(),
+            ),
+            ("V", self.get_version_string(activation)),
+            (
                 "M",
-                &self.encode_string(
-                    self.manufacturer
-                        .get_manufacturer_string(activation.avm.player_version)
-                        .as_str(),
-                ),
-            )
-            .append_pair(
+                self.manufacturer.get_manufacturer_string(player_version),
+            ),
+            (
                 "R",
-                &format!("{}x{}", self.screen_resolution.0, self.screen_resolution.1),
-            )
-            .append_pair("COL", &self.screen_color.to_string())
-            .append_pair("AR", &self.aspect_ratio.to_string())
-            .append_pair("OS", &self.encode_string(&self.os.to_string()))
-            .append_pair(
+                format!("{}x{}", self.screen_resolution.0, self.screen_resolution.1),
+            ),
+            ("DP", self.dpi.to_string()),
+            ("COL", self.screen_color.to_string()),
+            ("AR", format!("{:.1}", self.aspect_ratio)),
+            ("OS", self.os.to_string()),
+            (
                 "L",
-                self.language
-                    .get_language_code(activation.avm.player_version),
-            )
-            .append_pair("IME", self.encode_capability(SystemCapabilities::IME))
-            .append_pair("PT", &self.player_type.to_string())
-            .append_pair(
+                self.language.get_language_code(player_version).to_string(),
+            ),
+            (
+                "IME",
+                self.encode_capability(SystemCapabilities::IME).to_string(),
+            ),
+            ("PT", self.player_type.to_string()),
+            (
                 "AVD",
-                self.encode_not_capability(SystemCapabilities::AvHardware),
-            )
-            .append_pair(
+                self.encode_not_capability(SystemCapabilities::AvHardware)
+                    .to_string(),
+            ),
+            (
                 "LFD",
-                self.encode_not_capability(SystemCapabilities::LocalFileRead),
-            )
-            .append_pair("DP", &self.dpi.to_string())
-            .finish()
+                self.encode_not_capability(SystemCapabilities::LocalFileRead)
+                    .to_string(),
+            ),
+            (
+                "WD",
+                self.encode_not_capability(SystemCapabilities::WindowLess)
+                    .to_string(),
+            ),
+        ];
+
+        pairs
+            .iter()
+            .map(|(key, value)| format!("{}={}", key, self.encode_string(value)))
+            .collect::<Vec<_>>()
+            .join("&")
+    }
+
+    /// Whether text loaded by this movie is decoded with the system codepage
+    /// rather than as UTF-8.
+    ///
+    /// Flash 5 and earlier have no Unicode support, so their content always
+    /// uses the codepage.
+    fn uses_codepage(&self, swf_version: u8) -> bool {
+        self.use_codepage || swf_version <= 5
+    }
+
+    /// Decode text loaded from a file or the network, such as loaded
+    /// variables.
+    pub fn decode_string(&self, data: &[u8], swf_version: u8) -> String {
+        if self.uses_codepage(swf_version) {
+            data.iter().copied().map(windows_1252_char).collect()
+        } else {
+            String::from_utf8_lossy(data).into_owned()
+        }
+    }
+
+    /// Parse URL-encoded variables that have already been decoded with
+    /// `decode_string`.
+    ///
+    /// Percent-encoded bytes are decoded the same way as the text around them.
+    pub fn decode_form_data(&self, data: &str, swf_version: u8) -> Vec<(String, String)> {
+        data.split('&')
+            .filter(|pair| !pair.is_empty())
+            .map(|pair| {
+                let mut parts = pair.splitn(2, '=');
+                let name = parts.next().unwrap_or_default();
+                let value = parts.next().unwrap_or_default();
+                (
+                    self.decode_form_component(name, swf_version),
+                    self.decode_form_component(value, swf_version),
+                )
+            })
+            .collect()
+    }
+
+    fn decode_form_component(&self, component: &str, swf_version: u8) -> String {
+        let component = component.replace('+', " ");
+        if !self.uses_codepage(swf_version) {
+            return percent_encoding::percent_decode_str(&component)
+                .decode_utf8_lossy()
+                .into_owned();
+        }
+
+        let mut result = String::with_capacity(component.len());
+        let mut remaining = component.as_str();
+        while let Some(i) = remaining.find('%') {
+            result.push_str(&remaining[..i]);
+            let byte = remaining
+                .get(i + 1..i + 3)
+                .filter(|hex| hex.bytes().all(|b| b.is_ascii_hexdigit()))
+                .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+            match byte {
+                Some(byte) => {
+                    result.push(windows_1252_char(byte));
+                    remaining = &remaining[i + 3..];
+                }
+                None => {
+                    result.push('%');
+                    remaining = &remaining[i + 1..];
+                }
+            }
+        }
+        result.push_str(remaining);
+
+        result
+    }
+}
+
+/// Decode a byte of the system codepage, which we assume to be Windows-1252.
+fn windows_1252_char(byte: u8) -> char {
+    const HIGH_CONTROLS: [char; 32] = [
+        '\u{20AC}', '\u{81}', '\u{201A}', '\u{192}', '\u{201E}', '\u{2026}', '\u{2020}',
+        '\u{2021}', '\u{2C6}', '\u{2030}', '\u{160}', '\u{2039}', '\u{152}', '\u{8D}', '\u{17D}',
+        '\u{8F}', '\u{90}', '\u{2018}', '\u{2019}', '\u{201C}', '\u{201D}', '\u{2022}', '\u{2013}',
+        '\u{2014}', '\u{2DC}', '\u{2122}', '\u{161}', '\u{203A}', '\u{153}', '\u{9D}', '\u{17E}',
+        '\u{178}',
+    ];
+
+    match byte {
+        0x80..=0x9F => HIGH_CONTROLS[usize::from(byte - 0x80)],
+        _ => char::from(byte),
     }
 }
 
@@ -398,6 +565,7 @@ impl Default for SystemProperties {
             sandbox_type: SandboxType::LocalTrusted,
             cpu_architecture: CpuArchitecture::X86,
             idc_level: "5.1".into(),
+            version: None,
         }
     }
 }
@@ -565,3 +733,65 @@ pub fn create<'gc>(
 
     system.into()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn decode_loaded_variables() {
+        let mut system = SystemProperties::default();
+        let form = "name=caf%C3%A9&greeting=hello+world&bad=100%";
+
+        assert_eq!(
+            system.decode_form_data(form, 6),
+            vec![
+                ("name".to_string(), "café".to_string()),
+                ("greeting".to_string(), "hello world".to_string()),
+                ("bad".to_string(), "100%".to_string()),
+            ]
+        );
+        assert_eq!(
+            system.decode_string(b"caf\xE9 \x80", 6),
+            "caf\u{FFFD} \u{FFFD}"
+        );
+
+        // SWF5 content always uses the codepage.
+        assert_eq!(system.decode_string(b"caf\xE9 \x80", 5), "café €");
+        assert_eq!(
+            system.decode_form_data("name=caf%E9&euro=%80", 5),
+            vec![
+                ("name".to_string(), "café".to_string()),
+                ("euro".to_string(), "€".to_string()),
+            ]
+        );
+
+        system.use_codepage = true;
+        assert_eq!(system.decode_string(b"caf\xE9", 8), "café");
+        assert_eq!(
+            system.decode_form_data("name=caf%E9", 8),
+            vec![("name".to_string(), "café".to_string())]
+        );
+    }
+
+    #[test]
+    fn language_from_locale() {
+        assert_eq!(
+            Language::from_language_code("de_DE.UTF-8").get_language_code(8),
+            "de"
+        );
+        assert_eq!(
+            Language::from_language_code("zh-TW").get_language_code(8),
+            "zh-TW"
+        );
+        assert_eq!(
+            Language::from_language_code("zh").get_language_code(8),
+            "zh-CN"
+        );
+        assert_eq!(
+            Language::from_language_code("en-GB").get_language_code(6),
+            "en-US"
+        );
+        assert_eq!(Language::from_language_code("C").get_language_code(8), "xu");
+    }
+}
@@ -3,7 +3,7 @@ use crate::avm1::error::Error;
 use crate::avm1::function::Executable;
 use crate::avm1::globals::system::SystemCapabilities;
 use crate::avm1::object::Object;
-use crate::avm1::{ScriptObject, TObject, Value};
+use crate::avm1::{AvmString, ScriptObject, TObject, Value};
 use crate::context::UpdateContext;
 use enumset::EnumSet;
 use gc_arena::MutationContext;
@@ -82,7 +82,7 @@ pub fn get_player_type<'gc>(
     _this: Object<'gc>,
     _args: &[Value<'gc>],
 ) -> Result<Value<'gc>, Error<'gc>> {
-    Ok(context.system.player_type.to_string().into())
+    Ok(AvmString::new(context.gc_context, context.system.player_type.to_string()).into())
 }
 
 pub fn get_screen_color<'gc>(
@@ -91,7 +91,7 @@ pub fn get_screen_color<'gc>(
     _this: Object<'gc>,
     _args: &[Value<'gc>],
 ) -> Result<Value<'gc>, Error<'gc>> {
-    Ok(context.system.screen_color.to_string().into())
+    Ok(AvmString::new(context.gc_context, context.system.screen_color.to_string()).into())
 }
 
 pub fn get_language<'gc>(
@@ -100,11 +100,14 @@ pub fn get_language<'gc>(
     _this: Object<'gc>,
     _args: &[Value<'gc>],
 ) -> Result<Value<'gc>, Error<'gc>> {
-    Ok(context
-        .system
-        .language
-        .get_language_code(activation.avm.player_version)
-        .into())
+    Ok(AvmString::new(
+        context.gc_context,
+        context
+            .system
+            .language
+            .get_language_code(activation.avm.player_version),
+    )
+    .into())
 }
 
 pub fn get_screen_resolution_x<'gc>(
@@ -149,11 +152,14 @@ pub fn get_manufacturer<'gc>(
     _this: Object<'gc>,
     _args: &[Value<'gc>],
 ) -> Result<Value<'gc>, Error<'gc>> {
-    Ok(context
-        .system
-        .manufacturer
-        .get_manufacturer_string(activation.avm.player_version)
-        .into())
+    Ok(AvmString::new(
+        context.gc_context,
+        context
+            .system
+            .manufacturer
+            .get_manufacturer_string(activation.avm.player_version),
+    )
+    .into())
 }
 
 pub fn get_os_name<'gc>(
@@ -162,7 +168,7 @@ pub fn get_os_name<'gc>(
     _this: Object<'gc>,
     _args: &[Value<'gc>],
 ) -> Result<Value<'gc>, Error<'gc>> {
-    Ok(context.system.os.to_string().into())
+    Ok(AvmString::new(context.gc_context, context.system.os.to_string()).into())
 }
 
 pub fn get_version<'gc>(
@@ -171,7 +177,11 @@ pub fn get_version<'gc>(
     _this: Object<'gc>,
     _args: &[Value<'gc>],
 ) -> Result<Value<'gc>, Error<'gc>> {
-    Ok(context.system.get_version_string(activation).into())
+    Ok(AvmString::new(
+        context.gc_context,
+        context.system.get_version_string(activation),
+    )
+    .into())
 }
 
 pub fn get_server_string<'gc>(
@@ -180,7 +190,11 @@ pub fn get_server_string<'gc>(
     _this: Object<'gc>,
     _args: &[Value<'gc>],
 ) -> Result<Value<'gc>, Error<'gc>> {
-    Ok(context.system.get_server_string(activation).into())
+    Ok(AvmString::new(
+        context.gc_context,
+        context.system.get_server_string(activation),
+    )
+    .into())
 }
 
 pub fn get_cpu_architecture<'gc>(
@@ -189,7 +203,11 @@ pub fn get_cpu_architecture<'gc>(
     _this: Object<'gc>,
     _args: &[Value<'gc>],
 ) -> Result<Value<'gc>, Error<'gc>> {
-    Ok(context.system.cpu_architecture.to_string().into())
+    Ok(AvmString::new(
+        context.gc_context,
+        context.system.cpu_architecture.to_string(),
+    )
+    .into())
 }
 
 pub fn get_max_idc_level<'gc>(
@@ -198,7 +216,7 @@ pub fn get_max_idc_level<'gc>(
     _this: Object<'gc>,
     _args: &[Value<'gc>],
 ) -> Result<Value<'gc>, Error<'gc>> {
-    Ok(context.system.idc_level.clone().into())
+    Ok(AvmString::new(context.gc_context, context.system.idc_level.clone()).into())
 }
 
 pub fn create<'gc>(
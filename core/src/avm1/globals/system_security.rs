@@ -2,7 +2,7 @@ use crate::avm1::activation::Activation;
 use crate::avm1::error::Error;
 use crate::avm1::function::Executable;
 use crate::avm1::object::Object;
-use crate::avm1::{ScriptObject, TObject, Value};
+use crate::avm1::{AvmString, ScriptObject, TObject, Value};
 use crate::context::UpdateContext;
 use enumset::EnumSet;
 use gc_arena::MutationContext;
@@ -12,9 +12,10 @@ fn allow_domain<'gc>(
     _activation: &mut Activation<'_, 'gc>,
     _context: &mut UpdateContext<'_, 'gc, '_>,
     _this: Object<'gc>,
-    _args: &[Value<'gc>],
+    args: &[Value<'gc>],
 ) -> Result<Value<'gc>, Error<'gc>> {
-    log::warn!("System.security.allowDomain() not implemented");
+    // Ruffle doesn't restrict scripting between domains, so there is nothing to allow.
+    log::debug!("System.security.allowDomain({:?})", args);
     Ok(Value::Undefined)
 }
 
@@ -22,9 +23,10 @@ fn allow_insecure_domain<'gc>(
     _activation: &mut Activation<'_, 'gc>,
     _context: &mut UpdateContext<'_, 'gc, '_>,
     _this: Object<'gc>,
-    _args: &[Value<'gc>],
+    args: &[Value<'gc>],
 ) -> Result<Value<'gc>, Error<'gc>> {
-    log::warn!("System.security.allowInsecureDomain() not implemented");
+    // Ruffle doesn't restrict scripting between domains, so there is nothing to allow.
+    log::debug!("System.security.allowInsecureDomain({:?})", args);
     Ok(Value::Undefined)
 }
 
@@ -32,9 +34,10 @@ fn load_policy_file<'gc>(
     _activation: &mut Activation<'_, 'gc>,
     _context: &mut UpdateContext<'_, 'gc, '_>,
     _this: Object<'gc>,
-    _args: &[Value<'gc>],
+    args: &[Value<'gc>],
 ) -> Result<Value<'gc>, Error<'gc>> {
-    log::warn!("System.security.allowInsecureDomain() not implemented");
+    // Ruffle doesn't restrict loading data from other domains, so policy files aren't needed.
+    log::debug!("System.security.loadPolicyFile({:?})", args);
     Ok(Value::Undefined)
 }
 
@@ -54,7 +57,7 @@ fn get_sandbox_type<'gc>(
     _this: Object<'gc>,
     _args: &[Value<'gc>],
 ) -> Result<Value<'gc>, Error<'gc>> {
-    Ok(context.system.sandbox_type.to_string().into())
+    Ok(AvmString::new(context.gc_context, context.system.sandbox_type.to_string()).into())
 }
 
 fn get_choose_local_swf_path<'gc>(
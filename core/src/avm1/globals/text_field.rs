@@ -3,7 +3,7 @@ use crate::avm1::error::Error;
 use crate::avm1::function::Executable;
 use crate::avm1::globals::display_object;
 use crate::avm1::property::Attribute::*;
-use crate::avm1::{Object, ScriptObject, TObject, UpdateContext, Value};
+use crate::avm1::{AvmString, Object, ScriptObject, TObject, UpdateContext, Value};
 use crate::display_object::{AutoSizeMode, EditText, TDisplayObject};
 use crate::html::TextFormat;
 use gc_arena::MutationContext;
@@ -20,13 +20,13 @@ pub fn constructor<'gc>(
 
 pub fn get_text<'gc>(
     _activation: &mut Activation<'_, 'gc>,
-    _context: &mut UpdateContext<'_, 'gc, '_>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
     this: Object<'gc>,
     _args: &[Value<'gc>],
 ) -> Result<Value<'gc>, Error<'gc>> {
     if let Some(display_object) = this.as_display_object() {
         if let Some(text_field) = display_object.as_edit_text() {
-            return Ok(text_field.text().into());
+            return Ok(AvmString::new(context.gc_context, text_field.text()).into());
         }
     }
     Ok(Value::Undefined)
@@ -93,7 +93,7 @@ pub fn get_html_text<'gc>(
     if let Some(display_object) = this.as_display_object() {
         if let Some(text_field) = display_object.as_edit_text() {
             if let Ok(text) = text_field.html_text(context) {
-                return Ok(text.into());
+                return Ok(AvmString::new(context.gc_context, text).into());
             }
         }
     }
@@ -151,6 +151,348 @@ pub fn set_border<'gc>(
     Ok(Value::Undefined)
 }
 
+pub fn get_border_color<'gc>(
+    _activation: &mut Activation<'_, 'gc>,
+    _context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Object<'gc>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    if let Some(display_object) = this.as_display_object() {
+        if let Some(text_field) = display_object.as_edit_text() {
+            return Ok(text_field.border_color().into());
+        }
+    }
+
+    Ok(Value::Undefined)
+}
+
+pub fn set_border_color<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Object<'gc>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    if let Some(display_object) = this.as_display_object() {
+        if let Some(text_field) = display_object.as_edit_text() {
+            if let Some(value) = args.get(0) {
+                let rgb = value.coerce_to_u32(activation, context)?;
+                text_field.set_border_color(context.gc_context, rgb & 0xFFFFFF);
+            }
+        }
+    }
+    Ok(Value::Undefined)
+}
+
+pub fn get_background<'gc>(
+    _activation: &mut Activation<'_, 'gc>,
+    _context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Object<'gc>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    if let Some(display_object) = this.as_display_object() {
+        if let Some(text_field) = display_object.as_edit_text() {
+            return Ok(text_field.has_background().into());
+        }
+    }
+
+    Ok(Value::Undefined)
+}
+
+pub fn set_background<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Object<'gc>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    if let Some(display_object) = this.as_display_object() {
+        if let Some(text_field) = display_object.as_edit_text() {
+            if let Some(value) = args.get(0) {
+                let has_background = value.as_bool(activation.current_swf_version());
+                text_field.set_has_background(context.gc_context, has_background);
+            }
+        }
+    }
+    Ok(Value::Undefined)
+}
+
+pub fn get_background_color<'gc>(
+    _activation: &mut Activation<'_, 'gc>,
+    _context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Object<'gc>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    if let Some(display_object) = this.as_display_object() {
+        if let Some(text_field) = display_object.as_edit_text() {
+            return Ok(text_field.background_color().into());
+        }
+    }
+
+    Ok(Value::Undefined)
+}
+
+pub fn set_background_color<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Object<'gc>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    if let Some(display_object) = this.as_display_object() {
+        if let Some(text_field) = display_object.as_edit_text() {
+            if let Some(value) = args.get(0) {
+                let rgb = value.coerce_to_u32(activation, context)?;
+                text_field.set_background_color(context.gc_context, rgb & 0xFFFFFF);
+            }
+        }
+    }
+    Ok(Value::Undefined)
+}
+
+pub fn get_text_color<'gc>(
+    _activation: &mut Activation<'_, 'gc>,
+    _context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Object<'gc>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    if let Some(display_object) = this.as_display_object() {
+        if let Some(text_field) = display_object.as_edit_text() {
+            return Ok(text_field.text_color().into());
+        }
+    }
+
+    Ok(Value::Undefined)
+}
+
+pub fn set_text_color<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Object<'gc>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    if let Some(display_object) = this.as_display_object() {
+        if let Some(text_field) = display_object.as_edit_text() {
+            if let Some(value) = args.get(0) {
+                let rgb = value.coerce_to_u32(activation, context)?;
+                text_field.set_text_color(rgb & 0xFFFFFF, context);
+            }
+        }
+    }
+    Ok(Value::Undefined)
+}
+
+pub fn get_selectable<'gc>(
+    _activation: &mut Activation<'_, 'gc>,
+    _context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Object<'gc>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    if let Some(display_object) = this.as_display_object() {
+        if let Some(text_field) = display_object.as_edit_text() {
+            return Ok(text_field.is_selectable().into());
+        }
+    }
+
+    Ok(Value::Undefined)
+}
+
+pub fn set_selectable<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Object<'gc>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    if let Some(display_object) = this.as_display_object() {
+        if let Some(text_field) = display_object.as_edit_text() {
+            if let Some(value) = args.get(0) {
+                let is_selectable = value.as_bool(activation.current_swf_version());
+                text_field.set_selectable(context.gc_context, is_selectable);
+            }
+        }
+    }
+    Ok(Value::Undefined)
+}
+
+pub fn get_type<'gc>(
+    _activation: &mut Activation<'_, 'gc>,
+    _context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Object<'gc>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    if let Some(display_object) = this.as_display_object() {
+        if let Some(text_field) = display_object.as_edit_text() {
+            return Ok(if text_field.is_editable() {
+                "input".into()
+            } else {
+                "dynamic".into()
+            });
+        }
+    }
+
+    Ok(Value::Undefined)
+}
+
+pub fn set_type<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Object<'gc>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    if let Some(display_object) = this.as_display_object() {
+        if let Some(text_field) = display_object.as_edit_text() {
+            if let Some(value) = args.get(0) {
+                // Unknown types leave the field unchanged.
+                let field_type = value.coerce_to_string(activation, context)?;
+                if field_type.eq_ignore_ascii_case("input") {
+                    text_field.set_editable(context.gc_context, true);
+                } else if field_type.eq_ignore_ascii_case("dynamic") {
+                    text_field.set_editable(context.gc_context, false);
+                }
+            }
+        }
+    }
+    Ok(Value::Undefined)
+}
+
+pub fn get_max_chars<'gc>(
+    _activation: &mut Activation<'_, 'gc>,
+    _context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Object<'gc>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    if let Some(display_object) = this.as_display_object() {
+        if let Some(text_field) = display_object.as_edit_text() {
+            // A field without a limit returns null.
+            return Ok(match text_field.max_chars() {
+                0 => Value::Null,
+                max_chars => max_chars.into(),
+            });
+        }
+    }
+
+    Ok(Value::Undefined)
+}
+
+pub fn set_max_chars<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Object<'gc>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    if let Some(display_object) = this.as_display_object() {
+        if let Some(text_field) = display_object.as_edit_text() {
+            if let Some(value) = args.get(0) {
+                let max_chars = value.coerce_to_i32(activation, context)?.max(0);
+                text_field.set_max_chars(context.gc_context, max_chars);
+            }
+        }
+    }
+    Ok(Value::Undefined)
+}
+
+pub fn get_restrict<'gc>(
+    _activation: &mut Activation<'_, 'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Object<'gc>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    if let Some(display_object) = this.as_display_object() {
+        if let Some(text_field) = display_object.as_edit_text() {
+            return Ok(match text_field.restrict() {
+                Some(restrict) => AvmString::new(context.gc_context, restrict.to_string()).into(),
+                None => Value::Null,
+            });
+        }
+    }
+
+    Ok(Value::Undefined)
+}
+
+pub fn set_restrict<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Object<'gc>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    if let Some(display_object) = this.as_display_object() {
+        if let Some(text_field) = display_object.as_edit_text() {
+            let restrict = match args.get(0) {
+                None | Some(Value::Undefined) | Some(Value::Null) => None,
+                Some(v) => Some(v.coerce_to_string(activation, context)?.into_owned()),
+            };
+            text_field.set_restrict(context.gc_context, restrict);
+        }
+    }
+    Ok(Value::Undefined)
+}
+
+pub fn get_password<'gc>(
+    _activation: &mut Activation<'_, 'gc>,
+    _context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Object<'gc>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    if let Some(display_object) = this.as_display_object() {
+        if let Some(text_field) = display_object.as_edit_text() {
+            return Ok(text_field.is_password().into());
+        }
+    }
+
+    Ok(Value::Undefined)
+}
+
+pub fn set_password<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Object<'gc>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    if let Some(display_object) = this.as_display_object() {
+        if let Some(text_field) = display_object.as_edit_text() {
+            if let Some(value) = args.get(0) {
+                let is_password = value.as_bool(activation.current_swf_version());
+                text_field.set_password(context.gc_context, is_password);
+            }
+        }
+    }
+    Ok(Value::Undefined)
+}
+
+pub fn get_style_sheet<'gc>(
+    _activation: &mut Activation<'_, 'gc>,
+    _context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Object<'gc>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    if let Some(display_object) = this.as_display_object() {
+        if let Some(text_field) = display_object.as_edit_text() {
+            return Ok(text_field
+                .style_sheet()
+                .map(|style_sheet| Object::from(style_sheet).into())
+                .unwrap_or(Value::Undefined));
+        }
+    }
+
+    Ok(Value::Undefined)
+}
+
+pub fn set_style_sheet<'gc>(
+    _activation: &mut Activation<'_, 'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Object<'gc>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    if let Some(display_object) = this.as_display_object() {
+        if let Some(text_field) = display_object.as_edit_text() {
+            // Anything other than a `StyleSheet` removes the style sheet.
+            let style_sheet = match args.get(0) {
+                Some(Value::Object(object)) => object.as_style_sheet_object(),
+                _ => None,
+            };
+            text_field.set_style_sheet(style_sheet, context);
+        }
+    }
+    Ok(Value::Undefined)
+}
+
 pub fn get_embed_fonts<'gc>(
     _activation: &mut Activation<'_, 'gc>,
     _context: &mut UpdateContext<'_, 'gc, '_>,
@@ -294,7 +636,7 @@ pub fn set_multiline<'gc>(
 
 fn variable<'gc>(
     _activation: &mut Activation<'_, 'gc>,
-    _context: &mut UpdateContext<'_, 'gc, '_>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
     this: Object<'gc>,
     _args: &[Value<'gc>],
 ) -> Result<Value<'gc>, Error<'gc>> {
@@ -303,7 +645,7 @@ fn variable<'gc>(
         .and_then(|dobj| dobj.as_edit_text())
     {
         if let Some(variable) = etext.variable() {
-            return Ok(variable.to_string().into());
+            return Ok(AvmString::new(context.gc_context, variable.to_string()).into());
         }
     }
 
@@ -381,10 +723,10 @@ pub fn auto_size<'gc>(
         .and_then(|dobj| dobj.as_edit_text())
     {
         return Ok(match etext.autosize() {
-            AutoSizeMode::None => "none".to_string().into(),
-            AutoSizeMode::Left => "left".to_string().into(),
-            AutoSizeMode::Center => "center".to_string().into(),
-            AutoSizeMode::Right => "right".to_string().into(),
+            AutoSizeMode::None => "none".into(),
+            AutoSizeMode::Left => "left".into(),
+            AutoSizeMode::Center => "center".into(),
+            AutoSizeMode::Right => "right".into(),
         });
     }
 
@@ -517,6 +859,76 @@ pub fn attach_virtual_properties<'gc>(gc_context: MutationContext<'gc, '_>, obje
         Some(Executable::Native(set_border)),
         ReadOnly.into(),
     );
+    object.add_property(
+        gc_context,
+        "background",
+        Executable::Native(get_background),
+        Some(Executable::Native(set_background)),
+        ReadOnly.into(),
+    );
+    object.add_property(
+        gc_context,
+        "backgroundColor",
+        Executable::Native(get_background_color),
+        Some(Executable::Native(set_background_color)),
+        ReadOnly.into(),
+    );
+    object.add_property(
+        gc_context,
+        "borderColor",
+        Executable::Native(get_border_color),
+        Some(Executable::Native(set_border_color)),
+        ReadOnly.into(),
+    );
+    object.add_property(
+        gc_context,
+        "textColor",
+        Executable::Native(get_text_color),
+        Some(Executable::Native(set_text_color)),
+        ReadOnly.into(),
+    );
+    object.add_property(
+        gc_context,
+        "selectable",
+        Executable::Native(get_selectable),
+        Some(Executable::Native(set_selectable)),
+        ReadOnly.into(),
+    );
+    object.add_property(
+        gc_context,
+        "type",
+        Executable::Native(get_type),
+        Some(Executable::Native(set_type)),
+        ReadOnly.into(),
+    );
+    object.add_property(
+        gc_context,
+        "maxChars",
+        Executable::Native(get_max_chars),
+        Some(Executable::Native(set_max_chars)),
+        ReadOnly.into(),
+    );
+    object.add_property(
+        gc_context,
+        "restrict",
+        Executable::Native(get_restrict),
+        Some(Executable::Native(set_restrict)),
+        ReadOnly.into(),
+    );
+    object.add_property(
+        gc_context,
+        "password",
+        Executable::Native(get_password),
+        Some(Executable::Native(set_password)),
+        ReadOnly.into(),
+    );
+    object.add_property(
+        gc_context,
+        "styleSheet",
+        Executable::Native(get_style_sheet),
+        Some(Executable::Native(set_style_sheet)),
+        ReadOnly.into(),
+    );
     object.add_property(
         gc_context,
         "embedFonts",
@@ -583,6 +995,11 @@ fn set_text_format<'gc>(
     context: &mut UpdateContext<'_, 'gc, '_>,
     args: &[Value<'gc>],
 ) -> Result<Value<'gc>, Error<'gc>> {
+    // Fields formatted by a style sheet ignore `setTextFormat`.
+    if text_field.style_sheet().is_some() {
+        return Ok(Value::Undefined);
+    }
+
     let tf = args.last().cloned().unwrap_or(Value::Undefined);
 
     if let Value::Object(tf) = tf {
@@ -2,11 +2,11 @@
 
 use crate::avm1::activation::Activation;
 use crate::avm1::error::Error;
-use crate::avm1::{Object, ScriptObject, TObject, UpdateContext, Value};
+use crate::avm1::{AvmString, Object, ScriptObject, TObject, UpdateContext, Value};
 use gc_arena::MutationContext;
 
 fn map_defined_to_string<'gc>(
-    name: &str,
+    name: &'static str,
     this: Object<'gc>,
     activation: &mut Activation<'_, 'gc>,
     ac: &mut UpdateContext<'_, 'gc, '_>,
@@ -16,7 +16,7 @@ fn map_defined_to_string<'gc>(
         Some(Value::Undefined) => Value::Null,
         Some(Value::Null) => Value::Null,
         None => Value::Null,
-        Some(v) => v.coerce_to_string(activation, ac)?.into(),
+        Some(v) => AvmString::new(ac.gc_context, v.coerce_to_string(activation, ac)?).into(),
     };
 
     this.set(name, val, activation, ac)?;
@@ -25,7 +25,7 @@ fn map_defined_to_string<'gc>(
 }
 
 fn map_defined_to_number<'gc>(
-    name: &str,
+    name: &'static str,
     this: Object<'gc>,
     activation: &mut Activation<'_, 'gc>,
     ac: &mut UpdateContext<'_, 'gc, '_>,
@@ -44,7 +44,7 @@ fn map_defined_to_number<'gc>(
 }
 
 fn map_defined_to_bool<'gc>(
-    name: &str,
+    name: &'static str,
     this: Object<'gc>,
     activation: &mut Activation<'_, 'gc>,
     ac: &mut UpdateContext<'_, 'gc, '_>,
@@ -83,6 +83,17 @@ pub fn constructor<'gc>(
     map_defined_to_number("indent", this, activation, ac, args.get(11).cloned())?;
     map_defined_to_number("leading", this, activation, ac, args.get(12).cloned())?;
 
+    // The remaining properties can't be passed to the constructor, and start out unset.
+    for name in &[
+        "blockIndent",
+        "bullet",
+        "kerning",
+        "letterSpacing",
+        "tabStops",
+    ] {
+        this.set(*name, Value::Null, activation, ac)?;
+    }
+
     Ok(Value::Undefined)
 }
 
@@ -0,0 +1,276 @@
+//! AVM1 `setInterval`/`setTimeout` timers
+
+use crate::avm1::activation::Activation;
+use crate::avm1::error::Error;
+use crate::avm1::{Object, TObject, UpdateContext, Value};
+use gc_arena::Collect;
+use std::collections::BTreeMap;
+
+/// The shortest interval, in milliseconds, that a timer will fire at.
+/// Flash Player won't run timers more often than this.
+const MIN_INTERVAL: f64 = 10.0;
+
+/// How far behind, in milliseconds, a timer may fall before it skips the
+/// calls it missed, e.g. while the player was paused.
+const MAX_LAG: f64 = 1000.0;
+
+/// The function that a timer calls when it fires.
+enum TimerCallback<'gc> {
+    /// `setInterval(function, interval, ...)`
+    Function(Object<'gc>),
+
+    /// `setInterval(object, "method", interval, ...)`
+    ///
+    /// The method is looked up each time the timer fires.
+    Method {
+        this: Object<'gc>,
+        method_name: String,
+    },
+}
+
+/// A timer registered by `setInterval` or `setTimeout`.
+struct Timer<'gc> {
+    callback: TimerCallback<'gc>,
+
+    /// The extra arguments passed to the callback.
+    args: Vec<Value<'gc>>,
+
+    /// The time, in milliseconds, between calls.
+    interval: f64,
+
+    /// The time at which the timer fires next.
+    tick_time: f64,
+
+    /// Whether this is a `setTimeout` timer, which only fires once.
+    is_timeout: bool,
+}
+
+/// Tracks the timers registered by AVM1 `setInterval` and `setTimeout` calls.
+///
+/// Timers run on the player's wall clock rather than on frames, so they may
+/// fire several times between frames, or not at all.
+pub struct Timers<'gc> {
+    /// The active timers, by ID.
+    timers: BTreeMap<i32, Timer<'gc>>,
+
+    /// The ID of the most recently registered timer.
+    timer_counter: i32,
+
+    /// The time, in milliseconds, that the timers have been running for.
+    cur_time: f64,
+}
+
+unsafe impl<'gc> Collect for Timers<'gc> {
+    fn trace(&self, cc: gc_arena::CollectionContext) {
+        for timer in self.timers.values() {
+            match &timer.callback {
+                TimerCallback::Function(function) => function.trace(cc),
+                TimerCallback::Method { this, .. } => this.trace(cc),
+            }
+            timer.args.trace(cc);
+        }
+    }
+}
+
+impl<'gc> Timers<'gc> {
+    pub fn new() -> Self {
+        Self {
+            timers: BTreeMap::new(),
+            timer_counter: 0,
+            cur_time: 0.0,
+        }
+    }
+
+    /// Registers a timer, returning its ID.
+    fn add_timer(
+        &mut self,
+        callback: TimerCallback<'gc>,
+        interval: f64,
+        args: Vec<Value<'gc>>,
+        is_timeout: bool,
+    ) -> i32 {
+        let interval = if interval.is_nan() {
+            MIN_INTERVAL
+        } else {
+            interval.max(MIN_INTERVAL)
+        };
+        self.timer_counter = self.timer_counter.wrapping_add(1);
+        self.timers.insert(
+            self.timer_counter,
+            Timer {
+                callback,
+                args,
+                interval,
+                tick_time: self.cur_time + interval,
+                is_timeout,
+            },
+        );
+        self.timer_counter
+    }
+
+    /// Removes a timer, returning `false` if there was no timer with that ID.
+    pub fn remove_timer(&mut self, id: i32) -> bool {
+        self.timers.remove(&id).is_some()
+    }
+
+    /// Returns the time, in milliseconds, until the next timer is due to fire.
+    pub fn time_til_next_timer(&self) -> Option<f64> {
+        self.timers
+            .values()
+            .map(|timer| (timer.tick_time - self.cur_time).max(0.0))
+            .fold(None, |min, time| {
+                Some(min.map_or(time, |min: f64| min.min(time)))
+            })
+    }
+
+    /// Finds the timer that is due to fire next, if any are due, and schedules
+    /// its next call.
+    ///
+    /// The timer is rescheduled (or removed, for a timeout) before its
+    /// callback runs, so that a callback can clear its own timer.
+    fn next_due_timer(&mut self) -> Option<(TimerCallback<'gc>, Vec<Value<'gc>>)> {
+        let cur_time = self.cur_time;
+        let id = self
+            .timers
+            .iter()
+            .filter(|(_, timer)| timer.tick_time <= cur_time)
+            .min_by(|(_, a), (_, b)| a.tick_time.partial_cmp(&b.tick_time).unwrap())
+            .map(|(id, _)| *id)?;
+
+        let timer = self.timers.get_mut(&id).unwrap();
+        let callback = match &timer.callback {
+            TimerCallback::Function(function) => TimerCallback::Function(*function),
+            TimerCallback::Method { this, method_name } => TimerCallback::Method {
+                this: *this,
+                method_name: method_name.clone(),
+            },
+        };
+        let args = timer.args.clone();
+        if timer.is_timeout {
+            self.timers.remove(&id);
+        } else {
+            timer.tick_time = (timer.tick_time + timer.interval).max(cur_time - MAX_LAG);
+        }
+
+        Some((callback, args))
+    }
+}
+
+impl<'gc> Default for Timers<'gc> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Advances the timers by `dt` milliseconds, calling any that are due.
+///
+/// Timers with intervals shorter than `dt` fire once for each interval that
+/// elapsed. Returns whether any timers fired.
+pub fn update_timers<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    dt: f64,
+) -> bool {
+    context.timers.cur_time += dt;
+
+    let mut fired = false;
+    while let Some((callback, args)) = context.timers.next_due_timer() {
+        fired = true;
+        match callback {
+            TimerCallback::Function(function) => {
+                let this = activation.avm.globals;
+                let _ = function.call("[Timer Callback]", activation, context, this, None, &args);
+            }
+            TimerCallback::Method { this, method_name } => {
+                // Method timers keep firing even if `this` is a removed movie clip.
+                let _ = this.call_method(&method_name, &args, activation, context);
+            }
+        }
+    }
+    fired
+}
+
+/// Reads the callback, interval and arguments of a `setInterval` or
+/// `setTimeout` call, and registers the timer.
+fn create_timer<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    args: &[Value<'gc>],
+    is_timeout: bool,
+) -> Result<Value<'gc>, Error<'gc>> {
+    let (callback, rest) = match args {
+        [Value::Object(function), rest @ ..] if function.as_executable().is_some() => {
+            (TimerCallback::Function(*function), rest)
+        }
+        [Value::Object(this), method_name, rest @ ..] => {
+            let method_name = method_name.coerce_to_string(activation, context)?;
+            let callback = TimerCallback::Method {
+                this: *this,
+                method_name: method_name.into_owned(),
+            };
+            (callback, rest)
+        }
+        _ => return Ok(Value::Undefined),
+    };
+
+    let (interval, rest) = match rest {
+        [interval, rest @ ..] => (interval.coerce_to_f64(activation, context)?, rest),
+        [] => return Ok(Value::Undefined),
+    };
+
+    let id = context
+        .timers
+        .add_timer(callback, interval, rest.to_vec(), is_timeout);
+
+    Ok(id.into())
+}
+
+fn clear_timer<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let id = args
+        .get(0)
+        .unwrap_or(&Value::Undefined)
+        .coerce_to_i32(activation, context)?;
+    context.timers.remove_timer(id);
+
+    Ok(Value::Undefined)
+}
+
+pub fn set_interval<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    _this: Object<'gc>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    create_timer(activation, context, args, false)
+}
+
+pub fn clear_interval<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    _this: Object<'gc>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    clear_timer(activation, context, args)
+}
+
+pub fn set_timeout<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    _this: Object<'gc>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    create_timer(activation, context, args, true)
+}
+
+pub fn clear_timeout<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    _this: Object<'gc>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    clear_timer(activation, context, args)
+}
@@ -0,0 +1,171 @@
+//! flash.geom.Transform object
+
+use crate::avm1::activation::Activation;
+use crate::avm1::error::Error;
+use crate::avm1::function::Executable;
+use crate::avm1::globals::color_transform::{color_transform_to_object, object_to_color_transform};
+use crate::avm1::globals::matrix::{matrix_to_object, object_to_matrix};
+use crate::avm1::globals::rectangle::rectangle_to_object;
+use crate::avm1::property::Attribute::*;
+use crate::avm1::transform_object::TransformObject;
+use crate::avm1::{Object, TObject, Value};
+use crate::context::UpdateContext;
+use crate::display_object::{DisplayObject, TDisplayObject};
+use gc_arena::MutationContext;
+
+pub fn constructor<'gc>(
+    _activation: &mut Activation<'_, 'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Object<'gc>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let clip = match args.get(0) {
+        Some(Value::Object(clip)) => clip.as_display_object(),
+        _ => None,
+    };
+    if let Some(transform) = this.as_transform_object() {
+        transform.set_clip(context.gc_context, clip);
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Creates a new `Transform` object for the given display object.
+pub fn create_transform<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    clip: DisplayObject<'gc>,
+) -> Object<'gc> {
+    let transform = TransformObject::empty_transform_object(
+        context.gc_context,
+        Some(activation.avm.prototypes.transform),
+    );
+    transform.set_clip(context.gc_context, Some(clip));
+    transform.into()
+}
+
+fn clip<'gc>(this: Object<'gc>) -> Option<DisplayObject<'gc>> {
+    this.as_transform_object()
+        .and_then(|transform| transform.clip())
+}
+
+fn get_matrix<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Object<'gc>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    if let Some(clip) = clip(this) {
+        let matrix = *clip.matrix();
+        return Ok(matrix_to_object(matrix, activation, context)?.into());
+    }
+    Ok(Value::Undefined)
+}
+
+fn set_matrix<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Object<'gc>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    if let (Some(mut clip), Some(Value::Object(matrix))) = (clip(this), args.get(0)) {
+        let matrix = object_to_matrix(*matrix, activation, context)?;
+        clip.set_matrix(context.gc_context, &matrix);
+        clip.set_transformed_by_script(context.gc_context, true);
+        clip.invalidate_cached_bitmap();
+    }
+    Ok(Value::Undefined)
+}
+
+fn get_color_transform<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Object<'gc>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    if let Some(clip) = clip(this) {
+        let color_transform = *clip.color_transform();
+        return Ok(color_transform_to_object(&color_transform, activation, context)?.into());
+    }
+    Ok(Value::Undefined)
+}
+
+fn set_color_transform<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Object<'gc>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    if let (Some(mut clip), Some(Value::Object(color_transform))) = (clip(this), args.get(0)) {
+        let color_transform = object_to_color_transform(*color_transform, activation, context)?;
+        clip.set_color_transform(context.gc_context, &color_transform);
+        clip.invalidate_cached_bitmap();
+    }
+    Ok(Value::Undefined)
+}
+
+fn get_concatenated_matrix<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Object<'gc>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    if let Some(clip) = clip(this) {
+        let matrix = clip.local_to_global_matrix();
+        return Ok(matrix_to_object(matrix, activation, context)?.into());
+    }
+    Ok(Value::Undefined)
+}
+
+fn get_pixel_bounds<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Object<'gc>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    if let Some(clip) = clip(this) {
+        let bounds = clip.world_bounds();
+        return Ok(rectangle_to_object(&bounds, activation, context)?.into());
+    }
+    Ok(Value::Undefined)
+}
+
+pub fn create_proto<'gc>(
+    gc_context: MutationContext<'gc, '_>,
+    proto: Object<'gc>,
+    _fn_proto: Object<'gc>,
+) -> Object<'gc> {
+    let transform_object = TransformObject::empty_transform_object(gc_context, Some(proto));
+    let object = transform_object.as_script_object().unwrap();
+
+    object.add_property(
+        gc_context,
+        "matrix",
+        Executable::Native(get_matrix),
+        Some(Executable::Native(set_matrix)),
+        DontDelete | DontEnum,
+    );
+    object.add_property(
+        gc_context,
+        "colorTransform",
+        Executable::Native(get_color_transform),
+        Some(Executable::Native(set_color_transform)),
+        DontDelete | DontEnum,
+    );
+    object.add_property(
+        gc_context,
+        "concatenatedMatrix",
+        Executable::Native(get_concatenated_matrix),
+        None,
+        DontDelete | ReadOnly | DontEnum,
+    );
+    object.add_property(
+        gc_context,
+        "pixelBounds",
+        Executable::Native(get_pixel_bounds),
+        None,
+        DontDelete | ReadOnly | DontEnum,
+    );
+
+    transform_object.into()
+}
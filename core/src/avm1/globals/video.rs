@@ -0,0 +1,111 @@
+//! AVM1 Video object
+
+use crate::avm1::activation::Activation;
+use crate::avm1::error::Error;
+use crate::avm1::function::Executable;
+use crate::avm1::globals::net_stream;
+use crate::avm1::property::Attribute::*;
+use crate::avm1::script_object::ScriptObject;
+use crate::avm1::{Object, TObject, UpdateContext, Value};
+use crate::display_object::{TDisplayObject, Video};
+use enumset::EnumSet;
+use gc_arena::MutationContext;
+
+/// Videos can only be placed from the library, so the constructor does nothing.
+pub fn constructor<'gc>(
+    _activation: &mut Activation<'_, 'gc>,
+    _context: &mut UpdateContext<'_, 'gc, '_>,
+    _this: Object<'gc>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    Ok(Value::Undefined)
+}
+
+fn video_of(object: Object<'_>) -> Option<Video<'_>> {
+    object
+        .as_display_object()
+        .and_then(|display_object| display_object.as_video())
+}
+
+/// `attachVideo(source)` shows the frames of a `NetStream`, or nothing if `source` is `null`.
+pub fn attach_video<'gc>(
+    _activation: &mut Activation<'_, 'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Object<'gc>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    if let Some(video) = video_of(this) {
+        let source = match args.get(0) {
+            Some(Value::Object(source)) => Some(*source),
+            _ => None,
+        };
+        net_stream::attach_video(context, source, video);
+    }
+    Ok(Value::Undefined)
+}
+
+pub fn clear<'gc>(
+    _activation: &mut Activation<'_, 'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Object<'gc>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    if let Some(video) = video_of(this) {
+        video.clear(context);
+    }
+    Ok(Value::Undefined)
+}
+
+/// The width of the video's frames, rather than of the `Video` itself.
+fn width<'gc>(
+    _activation: &mut Activation<'_, 'gc>,
+    _context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Object<'gc>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    Ok(video_of(this).map_or(Value::Undefined, |video| video.frame_size().0.into()))
+}
+
+/// The height of the video's frames, rather than of the `Video` itself.
+fn height<'gc>(
+    _activation: &mut Activation<'_, 'gc>,
+    _context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Object<'gc>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    Ok(video_of(this).map_or(Value::Undefined, |video| video.frame_size().1.into()))
+}
+
+pub fn create_proto<'gc>(
+    gc_context: MutationContext<'gc, '_>,
+    proto: Object<'gc>,
+    fn_proto: Object<'gc>,
+) -> Object<'gc> {
+    let mut video_proto = ScriptObject::object(gc_context, Some(proto));
+
+    video_proto.force_set_function(
+        "attachVideo",
+        attach_video,
+        gc_context,
+        EnumSet::empty(),
+        Some(fn_proto),
+    );
+    video_proto.force_set_function("clear", clear, gc_context, EnumSet::empty(), Some(fn_proto));
+
+    video_proto.add_property(
+        gc_context,
+        "width",
+        Executable::Native(width),
+        None,
+        DontDelete | ReadOnly | DontEnum,
+    );
+    video_proto.add_property(
+        gc_context,
+        "height",
+        Executable::Native(height),
+        None,
+        DontDelete | ReadOnly | DontEnum,
+    );
+
+    video_proto.into()
+}
@@ -3,10 +3,11 @@
 use crate::avm1::activation::Activation;
 use crate::avm1::error::Error;
 use crate::avm1::function::Executable;
+use crate::avm1::globals::load_vars;
 use crate::avm1::property::Attribute::*;
 use crate::avm1::script_object::ScriptObject;
 use crate::avm1::xml_object::XMLObject;
-use crate::avm1::{Object, TObject, UpdateContext, Value};
+use crate::avm1::{AvmString, Object, TObject, UpdateContext, Value};
 use crate::backend::navigator::RequestOptions;
 use crate::xml;
 use crate::xml::{XMLDocument, XMLNode};
@@ -30,6 +31,9 @@ pub const XML_ATTRIBUTE_NOT_TERMINATED: f64 = -8.0;
 pub const XML_MISMATCHED_START: f64 = -9.0;
 pub const XML_MISMATCHED_END: f64 = -10.0;
 
+/// The default value of `XML.contentType`, used when sending XML documents.
+pub const XML_DEFAULT_CONTENT_TYPE: &str = "application/x-www-form-urlencoded";
+
 /// Returns true if a particular node can or cannot be exposed to AVM1.
 ///
 /// Our internal XML tree representation supports node types that AVM1 XML did
@@ -156,7 +160,7 @@ pub fn xmlnode_get_namespace_for_prefix<'gc>(
         args.get(0).map(|v| v.coerce_to_string(activation, ac)),
     ) {
         if let Some(uri) = xmlnode.lookup_uri_for_namespace(&prefix_string?) {
-            Ok(uri.into())
+            Ok(AvmString::new(ac.gc_context, uri).into())
         } else {
             Ok(Value::Null)
         }
@@ -176,7 +180,7 @@ pub fn xmlnode_get_prefix_for_namespace<'gc>(
         args.get(0).map(|v| v.coerce_to_string(activation, ac)),
     ) {
         if let Some(prefix) = xmlnode.lookup_namespace_for_uri(&uri_string?) {
-            Ok(prefix.into())
+            Ok(AvmString::new(ac.gc_context, prefix).into())
         } else {
             Ok(Value::Null)
         }
@@ -217,47 +221,49 @@ pub fn xmlnode_remove_node<'gc>(
 
 pub fn xmlnode_to_string<'gc>(
     _activation: &mut Activation<'_, 'gc>,
-    _ac: &mut UpdateContext<'_, 'gc, '_>,
+    ac: &mut UpdateContext<'_, 'gc, '_>,
     this: Object<'gc>,
     _args: &[Value<'gc>],
 ) -> Result<Value<'gc>, Error<'gc>> {
     if let Some(node) = this.as_xml_node() {
         let result = node.into_string(&mut is_as2_compatible);
 
-        return Ok(result
-            .unwrap_or_else(|e| {
+        return Ok(AvmString::new(
+            ac.gc_context,
+            result.unwrap_or_else(|e| {
                 log::warn!("XMLNode toString failed: {}", e);
                 "".to_string()
-            })
-            .into());
+            }),
+        )
+        .into());
     }
 
-    Ok("".to_string().into())
+    Ok("".into())
 }
 
 pub fn xmlnode_local_name<'gc>(
     _activation: &mut Activation<'_, 'gc>,
-    _ac: &mut UpdateContext<'_, 'gc, '_>,
+    ac: &mut UpdateContext<'_, 'gc, '_>,
     this: Object<'gc>,
     _args: &[Value<'gc>],
 ) -> Result<Value<'gc>, Error<'gc>> {
     Ok(this
         .as_xml_node()
         .and_then(|n| n.tag_name())
-        .map(|n| n.local_name().to_string().into())
+        .map(|n| AvmString::new(ac.gc_context, n.local_name().to_string()).into())
         .unwrap_or_else(|| Value::Null))
 }
 
 pub fn xmlnode_node_name<'gc>(
     _activation: &mut Activation<'_, 'gc>,
-    _ac: &mut UpdateContext<'_, 'gc, '_>,
+    ac: &mut UpdateContext<'_, 'gc, '_>,
     this: Object<'gc>,
     _args: &[Value<'gc>],
 ) -> Result<Value<'gc>, Error<'gc>> {
     Ok(this
         .as_xml_node()
         .and_then(|n| n.tag_name())
-        .map(|n| n.node_name().into())
+        .map(|n| AvmString::new(ac.gc_context, n.node_name()).into())
         .unwrap_or_else(|| Value::Null))
 }
 
@@ -283,20 +289,20 @@ pub fn xmlnode_node_type<'gc>(
 
 pub fn xmlnode_node_value<'gc>(
     _activation: &mut Activation<'_, 'gc>,
-    _ac: &mut UpdateContext<'_, 'gc, '_>,
+    ac: &mut UpdateContext<'_, 'gc, '_>,
     this: Object<'gc>,
     _args: &[Value<'gc>],
 ) -> Result<Value<'gc>, Error<'gc>> {
     Ok(this
         .as_xml_node()
         .and_then(|n| n.node_value())
-        .map(|n| n.into())
+        .map(|n| AvmString::new(ac.gc_context, n).into())
         .unwrap_or_else(|| Value::Null))
 }
 
 pub fn xmlnode_prefix<'gc>(
     _activation: &mut Activation<'_, 'gc>,
-    _ac: &mut UpdateContext<'_, 'gc, '_>,
+    ac: &mut UpdateContext<'_, 'gc, '_>,
     this: Object<'gc>,
     _args: &[Value<'gc>],
 ) -> Result<Value<'gc>, Error<'gc>> {
@@ -305,8 +311,8 @@ pub fn xmlnode_prefix<'gc>(
         .and_then(|n| n.tag_name())
         .map(|n| {
             n.prefix()
-                .map(|n| n.to_string().into())
-                .unwrap_or_else(|| "".to_string().into())
+                .map(|n| AvmString::new(ac.gc_context, n.to_string()).into())
+                .unwrap_or_else(|| "".into())
         })
         .unwrap_or_else(|| Value::Null))
 }
@@ -481,7 +487,7 @@ pub fn xmlnode_attributes<'gc>(
 
 pub fn xmlnode_namespace_uri<'gc>(
     _activation: &mut Activation<'_, 'gc>,
-    _ac: &mut UpdateContext<'_, 'gc, '_>,
+    ac: &mut UpdateContext<'_, 'gc, '_>,
     this: Object<'gc>,
     _args: &[Value<'gc>],
 ) -> Result<Value<'gc>, Error<'gc>> {
@@ -489,7 +495,7 @@ pub fn xmlnode_namespace_uri<'gc>(
         if let Some(name) = node.tag_name() {
             return Ok(node
                 .lookup_uri_for_namespace(name.prefix().unwrap_or(""))
-                .map(|s| s.into())
+                .map(|s| AvmString::new(ac.gc_context, s).into())
                 .unwrap_or_else(|| "".into()));
         }
 
@@ -699,7 +705,8 @@ pub fn xml_constructor<'gc>(
             xmlnode.introduce_script_object(ac.gc_context, this);
             this_node.swap(ac.gc_context, xmlnode);
 
-            if let Err(e) = this_node.replace_with_str(ac.gc_context, string, true) {
+            let ignore_white = ignore_white(activation, ac, this)?;
+            if let Err(e) = this_node.replace_with_str(ac.gc_context, string, true, ignore_white) {
                 log::warn!("Couldn't replace_with_str inside of XML constructor: {}", e);
             }
         }
@@ -716,6 +723,17 @@ pub fn xml_constructor<'gc>(
     Ok(Value::Undefined)
 }
 
+/// Whether whitespace-only text nodes should be discarded when parsing into an XML document.
+fn ignore_white<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    ac: &mut UpdateContext<'_, 'gc, '_>,
+    this: Object<'gc>,
+) -> Result<bool, Error<'gc>> {
+    Ok(this
+        .get("ignoreWhite", activation, ac)?
+        .as_bool(activation.current_swf_version()))
+}
+
 pub fn xml_create_element<'gc>(
     activation: &mut Activation<'_, 'gc>,
     ac: &mut UpdateContext<'_, 'gc, '_>,
@@ -796,7 +814,8 @@ pub fn xml_parse_xml<'gc>(
             }
         }
 
-        let result = node.replace_with_str(ac.gc_context, &xmlstring, true);
+        let ignore_white = ignore_white(activation, ac, this)?;
+        let result = node.replace_with_str(ac.gc_context, &xmlstring, true, ignore_white);
         if let Err(e) = result {
             log::warn!("XML parsing error: {}", e);
         }
@@ -822,7 +841,9 @@ pub fn xml_load<'gc>(
 
         this.set("loaded", false.into(), activation, ac)?;
 
-        let fetch = ac.navigator.fetch(&url, RequestOptions::get());
+        let options =
+            RequestOptions::get().with_headers(load_vars::request_headers(activation, ac, this)?);
+        let fetch = ac.navigator.fetch(&url, options);
         let target_clip = activation.target_clip_or_root();
         let process = ac.load_manager.load_xml_into_node(
             ac.player.clone().unwrap(),
@@ -839,6 +860,53 @@ pub fn xml_load<'gc>(
     }
 }
 
+pub fn xml_send_and_load<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    ac: &mut UpdateContext<'_, 'gc, '_>,
+    this: Object<'gc>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let url = args.get(0).cloned().unwrap_or(Value::Undefined);
+
+    if let Value::Null = url {
+        return Ok(Value::Undefined);
+    }
+
+    let target = match args.get(1) {
+        Some(Value::Object(target)) => *target,
+        _ => return Ok(Value::Undefined),
+    };
+
+    if let Some(target_node) = target.as_xml_node() {
+        let url = url.coerce_to_string(activation, ac)?;
+        let body = this
+            .call_method("toString", &[], activation, ac)?
+            .coerce_to_string(activation, ac)?
+            .to_string();
+        let content_type = this
+            .get("contentType", activation, ac)?
+            .coerce_to_string(activation, ac)?
+            .to_string();
+
+        target.set("loaded", false.into(), activation, ac)?;
+
+        let options = RequestOptions::post(Some((body.into_bytes(), content_type)))
+            .with_headers(load_vars::request_headers(activation, ac, this)?);
+        let fetch = ac.navigator.fetch(&url, options);
+        let target_clip = activation.target_clip_or_root();
+        let process = ac.load_manager.load_xml_into_node(
+            ac.player.clone().unwrap(),
+            target_node,
+            target_clip,
+            fetch,
+        );
+
+        ac.navigator.spawn_future(process);
+    }
+
+    Ok(Value::Undefined)
+}
+
 pub fn xml_on_data<'gc>(
     activation: &mut Activation<'_, 'gc>,
     ac: &mut UpdateContext<'_, 'gc, '_>,
@@ -851,7 +919,12 @@ pub fn xml_on_data<'gc>(
         this.call_method("onLoad", &[false.into()], activation, ac)?;
     } else {
         let src = src.coerce_to_string(activation, ac)?;
-        this.call_method("parseXML", &[src.into()], activation, ac)?;
+        this.call_method(
+            "parseXML",
+            &[AvmString::new(ac.gc_context, src).into()],
+            activation,
+            ac,
+        )?;
 
         this.set("loaded", true.into(), activation, ac)?;
 
@@ -863,7 +936,7 @@ pub fn xml_on_data<'gc>(
 
 pub fn xml_doc_type_decl<'gc>(
     _activation: &mut Activation<'_, 'gc>,
-    _ac: &mut UpdateContext<'_, 'gc, '_>,
+    ac: &mut UpdateContext<'_, 'gc, '_>,
     this: Object<'gc>,
     _args: &[Value<'gc>],
 ) -> Result<Value<'gc>, Error<'gc>> {
@@ -871,12 +944,14 @@ pub fn xml_doc_type_decl<'gc>(
         if let Some(doctype) = node.document().doctype() {
             let result = doctype.into_string(&mut |_| true);
 
-            return Ok(result
-                .unwrap_or_else(|e| {
+            return Ok(AvmString::new(
+                ac.gc_context,
+                result.unwrap_or_else(|e| {
                     log::warn!("Error occured when serializing DOCTYPE: {}", e);
                     "".to_string()
-                })
-                .into());
+                }),
+            )
+            .into());
         }
     }
 
@@ -885,7 +960,7 @@ pub fn xml_doc_type_decl<'gc>(
 
 pub fn xml_xml_decl<'gc>(
     _activation: &mut Activation<'_, 'gc>,
-    _ac: &mut UpdateContext<'_, 'gc, '_>,
+    ac: &mut UpdateContext<'_, 'gc, '_>,
     this: Object<'gc>,
     _args: &[Value<'gc>],
 ) -> Result<Value<'gc>, Error<'gc>> {
@@ -895,7 +970,7 @@ pub fn xml_xml_decl<'gc>(
         if let Err(e) = result {
             log::warn!("Could not generate XML declaration for document: {}", e);
         } else if let Ok(Some(result_str)) = result {
-            return Ok(result_str.into());
+            return Ok(AvmString::new(ac.gc_context, result_str).into());
         }
     }
 
@@ -980,6 +1055,13 @@ pub fn create_xml_proto<'gc>(
         None,
         ReadOnly.into(),
     );
+    xml_proto.define_value(gc_context, "ignoreWhite", false.into(), DontEnum.into());
+    xml_proto.define_value(
+        gc_context,
+        "contentType",
+        XML_DEFAULT_CONTENT_TYPE.into(),
+        DontEnum.into(),
+    );
     xml_proto.as_script_object().unwrap().force_set_function(
         "createElement",
         xml_create_element,
@@ -1008,6 +1090,20 @@ pub fn create_xml_proto<'gc>(
         EnumSet::empty(),
         Some(fn_proto),
     );
+    xml_proto.as_script_object().unwrap().force_set_function(
+        "sendAndLoad",
+        xml_send_and_load,
+        gc_context,
+        EnumSet::empty(),
+        Some(fn_proto),
+    );
+    xml_proto.as_script_object().unwrap().force_set_function(
+        "addRequestHeader",
+        load_vars::add_request_header,
+        gc_context,
+        EnumSet::empty(),
+        Some(fn_proto),
+    );
     xml_proto.as_script_object().unwrap().force_set_function(
         "onData",
         xml_on_data,
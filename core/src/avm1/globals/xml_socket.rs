@@ -0,0 +1,281 @@
+//! AVM1 XMLSocket object
+
+use crate::avm1::activation::Activation;
+use crate::avm1::error::Error;
+use crate::avm1::script_object::ScriptObject;
+use crate::avm1::{AvmString, Object, TObject, UpdateContext, Value};
+use crate::backend::navigator::{NavigatorBackend, SocketEvent, SocketHandle};
+use enumset::EnumSet;
+use gc_arena::{Collect, MutationContext};
+
+/// An open `XMLSocket` connection.
+struct XmlSocket<'gc> {
+    /// The backend handle of the connection.
+    handle: SocketHandle,
+
+    /// The `XMLSocket` object that opened the connection.
+    object: Object<'gc>,
+
+    /// Received data that does not yet form a complete message.
+    buffer: Vec<u8>,
+}
+
+/// Tracks the socket connections of AVM1 `XMLSocket` objects, and turns the
+/// events reported by the navigator backend into script callbacks.
+pub struct XmlSockets<'gc> {
+    sockets: Vec<XmlSocket<'gc>>,
+}
+
+unsafe impl<'gc> Collect for XmlSockets<'gc> {
+    fn trace(&self, cc: gc_arena::CollectionContext) {
+        for socket in &self.sockets {
+            socket.object.trace(cc);
+        }
+    }
+}
+
+impl<'gc> XmlSockets<'gc> {
+    pub fn new() -> Self {
+        Self {
+            sockets: Vec::new(),
+        }
+    }
+
+    /// Opens a connection for an `XMLSocket` object, closing its previous connection.
+    pub fn connect(
+        &mut self,
+        navigator: &mut dyn NavigatorBackend,
+        object: Object<'gc>,
+        host: &str,
+        port: u16,
+    ) {
+        self.close(navigator, object);
+        let handle = navigator.connect_socket(host, port);
+        self.sockets.push(XmlSocket {
+            handle,
+            object,
+            buffer: Vec::new(),
+        });
+    }
+
+    /// Sends data over the connection of an `XMLSocket` object.
+    /// Returns `false` if the object has no open connection.
+    pub fn send(
+        &mut self,
+        navigator: &mut dyn NavigatorBackend,
+        object: Object<'gc>,
+        data: Vec<u8>,
+    ) -> bool {
+        if let Some(socket) = self
+            .sockets
+            .iter()
+            .find(|socket| Object::ptr_eq(socket.object, object))
+        {
+            navigator.send_socket(socket.handle, data);
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Closes the connection of an `XMLSocket` object.
+    /// No callbacks are called for a connection closed by script.
+    pub fn close(&mut self, navigator: &mut dyn NavigatorBackend, object: Object<'gc>) {
+        self.sockets.retain(|socket| {
+            if Object::ptr_eq(socket.object, object) {
+                navigator.close_socket(socket.handle);
+                false
+            } else {
+                true
+            }
+        });
+    }
+
+    /// Polls the navigator backend for socket events.
+    ///
+    /// Returns the `XMLSocket` methods to call for the events, along with their arguments.
+    /// Received data is split into null-terminated messages, each of which is passed to
+    /// `onData`.
+    pub fn update(
+        &mut self,
+        gc_context: MutationContext<'gc, '_>,
+        navigator: &mut dyn NavigatorBackend,
+    ) -> Vec<(Object<'gc>, &'static str, Vec<Value<'gc>>)> {
+        let mut callbacks = vec![];
+        for event in navigator.poll_socket_events() {
+            let index = match self
+                .sockets
+                .iter()
+                .position(|socket| socket.handle == event.socket())
+            {
+                Some(index) => index,
+                // The socket was closed by script.
+                None => continue,
+            };
+
+            match event {
+                SocketEvent::Connected(_, success) => {
+                    let object = if success {
+                        self.sockets[index].object
+                    } else {
+                        self.sockets.remove(index).object
+                    };
+                    callbacks.push((object, "onConnect", vec![success.into()]));
+                }
+                SocketEvent::Data(_, data) => {
+                    let socket = &mut self.sockets[index];
+                    socket.buffer.extend(data);
+                    while let Some(end) = socket.buffer.iter().position(|&b| b == 0) {
+                        let message: Vec<u8> = socket.buffer.drain(..=end).collect();
+                        let message = AvmString::new(
+                            gc_context,
+                            String::from_utf8_lossy(&message[..end]).into_owned(),
+                        );
+                        callbacks.push((socket.object, "onData", vec![message.into()]));
+                    }
+                }
+                SocketEvent::Closed(_) => {
+                    let object = self.sockets.remove(index).object;
+                    callbacks.push((object, "onClose", vec![]));
+                }
+            }
+        }
+        callbacks
+    }
+}
+
+impl<'gc> Default for XmlSockets<'gc> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Implements `XMLSocket`
+pub fn constructor<'gc>(
+    _activation: &mut Activation<'_, 'gc>,
+    _context: &mut UpdateContext<'_, 'gc, '_>,
+    _this: Object<'gc>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    Ok(Value::Undefined)
+}
+
+fn connect<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Object<'gc>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let host = match args.get(0) {
+        Some(Value::Undefined) | Some(Value::Null) | None => {
+            // TODO: This should be the host that the movie was loaded from.
+            log::warn!("XMLSocket.connect: Connecting to the movie's host is unimplemented");
+            "localhost".to_string()
+        }
+        Some(host) => host.coerce_to_string(activation, context)?.to_string(),
+    };
+    let port = args
+        .get(1)
+        .unwrap_or(&Value::Undefined)
+        .coerce_to_i32(activation, context)?;
+
+    // Flash Player only allows connections to ports 1024 and above.
+    if port < 1024 || port > i32::from(u16::MAX) {
+        return Ok(false.into());
+    }
+
+    // TODO: Fetch and check the socket policy file of the host.
+    context
+        .xml_sockets
+        .connect(context.navigator, this, &host, port as u16);
+
+    Ok(true.into())
+}
+
+fn send<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Object<'gc>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    if let Some(data) = args.get(0) {
+        // Messages are terminated by a null byte.
+        let mut data = data
+            .coerce_to_string(activation, context)?
+            .to_string()
+            .into_bytes();
+        data.push(0);
+        if !context.xml_sockets.send(context.navigator, this, data) {
+            log::warn!("XMLSocket.send: Socket is not connected");
+        }
+    }
+
+    Ok(Value::Undefined)
+}
+
+fn close<'gc>(
+    _activation: &mut Activation<'_, 'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Object<'gc>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    context.xml_sockets.close(context.navigator, this);
+
+    Ok(Value::Undefined)
+}
+
+fn on_data<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Object<'gc>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    // By default, the message is parsed as an XML document and passed to `onXML`.
+    let globals = activation.avm.globals;
+    let constructor = globals
+        .get("XML", activation, context)?
+        .coerce_to_object(activation, context);
+    let prototype = constructor
+        .get("prototype", activation, context)?
+        .coerce_to_object(activation, context);
+    let args = [args.get(0).cloned().unwrap_or(Value::Undefined)];
+    let xml = prototype.new(activation, context, prototype, &args)?;
+    constructor.call("[ctor]", activation, context, xml, None, &args)?;
+
+    this.call_method("onXML", &[xml.into()], activation, context)?;
+
+    Ok(Value::Undefined)
+}
+
+pub fn create_proto<'gc>(
+    gc_context: MutationContext<'gc, '_>,
+    proto: Object<'gc>,
+    fn_proto: Object<'gc>,
+) -> Object<'gc> {
+    let mut xml_socket_proto = ScriptObject::object(gc_context, Some(proto));
+
+    xml_socket_proto.force_set_function(
+        "connect",
+        connect,
+        gc_context,
+        EnumSet::empty(),
+        Some(fn_proto),
+    );
+    xml_socket_proto.force_set_function("send", send, gc_context, EnumSet::empty(), Some(fn_proto));
+    xml_socket_proto.force_set_function(
+        "close",
+        close,
+        gc_context,
+        EnumSet::empty(),
+        Some(fn_proto),
+    );
+    xml_socket_proto.force_set_function(
+        "onData",
+        on_data,
+        gc_context,
+        EnumSet::empty(),
+        Some(fn_proto),
+    );
+
+    xml_socket_proto.into()
+}
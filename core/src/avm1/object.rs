@@ -4,15 +4,19 @@ use crate::avm1::error::Error;
 use crate::avm1::function::{Executable, FunctionObject};
 use crate::avm1::property::Attribute;
 use crate::avm1::shared_object::SharedObject;
+use crate::avm1::style_sheet_object::StyleSheetObject;
 use crate::avm1::super_object::SuperObject;
 use crate::avm1::value_object::ValueObject;
 
 use crate::avm1::activation::Activation;
+use crate::avm1::bitmap_data_object::BitmapDataObject;
 use crate::avm1::color_transform_object::ColorTransformObject;
+use crate::avm1::date_object::DateObject;
+use crate::avm1::transform_object::TransformObject;
 use crate::avm1::xml_attributes_object::XMLAttributesObject;
 use crate::avm1::xml_idmap_object::XMLIDMapObject;
 use crate::avm1::xml_object::XMLObject;
-use crate::avm1::{ScriptObject, SoundObject, StageObject, UpdateContext, Value};
+use crate::avm1::{AvmString, ScriptObject, SoundObject, StageObject, UpdateContext, Value};
 use crate::display_object::DisplayObject;
 use crate::xml::XMLNode;
 use enumset::EnumSet;
@@ -38,6 +42,10 @@ use std::fmt::Debug;
         FunctionObject(FunctionObject<'gc>),
         SharedObject(SharedObject<'gc>),
         ColorTransformObject(ColorTransformObject<'gc>),
+        BitmapDataObject(BitmapDataObject<'gc>),
+        TransformObject(TransformObject<'gc>),
+        DateObject(DateObject<'gc>),
+        StyleSheetObject(StyleSheetObject<'gc>),
     }
 )]
 pub trait TObject<'gc>: 'gc + Collect + Debug + Into<Object<'gc>> + Clone + Copy {
@@ -74,7 +82,7 @@ pub trait TObject<'gc>: 'gc + Collect + Debug + Into<Object<'gc>> + Clone + Copy
     /// Set a named property on this object, or it's prototype.
     fn set(
         &self,
-        name: &str,
+        name: impl Into<AvmString<'gc>>,
         value: Value<'gc>,
         activation: &mut Activation<'_, 'gc>,
         context: &mut UpdateContext<'_, 'gc, '_>,
@@ -200,7 +208,7 @@ pub trait TObject<'gc>: 'gc + Collect + Debug + Into<Object<'gc>> + Clone + Copy
     fn define_value(
         &self,
         gc_context: MutationContext<'gc, '_>,
-        name: &str,
+        name: impl Into<AvmString<'gc>>,
         value: Value<'gc>,
         attributes: EnumSet<Attribute>,
     );
@@ -233,7 +241,7 @@ pub trait TObject<'gc>: 'gc + Collect + Debug + Into<Object<'gc>> + Clone + Copy
     fn add_property(
         &self,
         gc_context: MutationContext<'gc, '_>,
-        name: &str,
+        name: impl Into<AvmString<'gc>>,
         get: Executable<'gc>,
         set: Option<Executable<'gc>>,
         attributes: EnumSet<Attribute>,
@@ -253,7 +261,7 @@ pub trait TObject<'gc>: 'gc + Collect + Debug + Into<Object<'gc>> + Clone + Copy
         &self,
         activation: &mut Activation<'_, 'gc>,
         gc_context: MutationContext<'gc, '_>,
-        name: &str,
+        name: impl Into<AvmString<'gc>>,
         get: Executable<'gc>,
         set: Option<Executable<'gc>>,
         attributes: EnumSet<Attribute>,
@@ -289,7 +297,11 @@ pub trait TObject<'gc>: 'gc + Collect + Debug + Into<Object<'gc>> + Clone + Copy
     fn is_property_enumerable(&self, activation: &mut Activation<'_, 'gc>, name: &str) -> bool;
 
     /// Enumerate the object.
-    fn get_keys(&self, activation: &mut Activation<'_, 'gc>) -> Vec<String>;
+    fn get_keys(
+        &self,
+        activation: &mut Activation<'_, 'gc>,
+        gc_context: MutationContext<'gc, '_>,
+    ) -> Vec<AvmString<'gc>>;
 
     /// Coerce the object into a string.
     fn as_string(&self) -> Cow<str>;
@@ -403,6 +415,26 @@ pub trait TObject<'gc>: 'gc + Collect + Debug + Into<Object<'gc>> + Clone + Copy
         None
     }
 
+    /// Get the underlying `BitmapDataObject`, if it exists
+    fn as_bitmap_data_object(&self) -> Option<BitmapDataObject<'gc>> {
+        None
+    }
+
+    /// Get the underlying `TransformObject`, if it exists
+    fn as_transform_object(&self) -> Option<TransformObject<'gc>> {
+        None
+    }
+
+    /// Get the underlying `DateObject`, if it exists
+    fn as_date_object(&self) -> Option<DateObject<'gc>> {
+        None
+    }
+
+    /// Get the underlying `StyleSheetObject`, if it exists
+    fn as_style_sheet_object(&self) -> Option<StyleSheetObject<'gc>> {
+        None
+    }
+
     fn as_ptr(&self) -> *const ObjectPtr;
 
     /// Check if this object is in the prototype chain of the specified test object.
@@ -2,7 +2,7 @@
 
 use crate::avm1::activation::Activation;
 use crate::avm1::error::Error;
-use crate::avm1::{Object, ScriptObject, TObject, UpdateContext, Value};
+use crate::avm1::{AvmString, Object, ScriptObject, TObject, UpdateContext, Value};
 use enumset::EnumSet;
 use gc_arena::{GcCell, MutationContext};
 use std::cell::Ref;
@@ -254,6 +254,37 @@ impl<'gc> Scope<'gc> {
         Ok(Value::Undefined)
     }
 
+    /// Find the object of the `with` scope that a name resolves to.
+    ///
+    /// Yields `None` if the name resolves to any other kind of scope, or
+    /// doesn't resolve at all.
+    pub fn resolve_with_object(
+        &self,
+        activation: &mut Activation<'_, 'gc>,
+        context: &mut UpdateContext<'_, 'gc, '_>,
+        name: &str,
+    ) -> Option<Object<'gc>> {
+        if self.locals().has_property(activation, context, name) {
+            return if self.class == ScopeClass::With {
+                Some(self.values)
+            } else {
+                None
+            };
+        }
+
+        if let Some(scope) = self.parent() {
+            return scope.resolve_with_object(activation, context, name);
+        }
+
+        None
+    }
+
+    /// Count the `with` scopes in this scope chain.
+    pub fn with_depth(&self) -> usize {
+        let depth = if self.class == ScopeClass::With { 1 } else { 0 };
+        depth + self.parent().map(|scope| scope.with_depth()).unwrap_or(0)
+    }
+
     /// Check if a particular property in the scope chain is defined.
     pub fn is_defined(
         &self,
@@ -280,13 +311,15 @@ impl<'gc> Scope<'gc> {
     /// If the value is not found, it is defined on this Target scope.
     pub fn set(
         &self,
-        name: &str,
+        name: impl Into<AvmString<'gc>>,
         value: Value<'gc>,
         activation: &mut Activation<'_, 'gc>,
         context: &mut UpdateContext<'_, 'gc, '_>,
         this: Object<'gc>,
     ) -> Result<(), Error<'gc>> {
-        if self.class == ScopeClass::Target || self.locals().has_property(activation, context, name)
+        let name = name.into();
+        if self.class == ScopeClass::Target
+            || self.locals().has_property(activation, context, &name)
         {
             // Value found on this object, so overwrite it.
             // Or we've hit the executing movie clip, so create it here.
@@ -309,7 +342,12 @@ impl<'gc> Scope<'gc> {
     /// stored (e.g. not virtual) properties on the lowest object in the scope
     /// chain. As a result, this function always force sets a property on the
     /// local object and does not traverse the scope chain.
-    pub fn define(&self, name: &str, value: impl Into<Value<'gc>>, mc: MutationContext<'gc, '_>) {
+    pub fn define(
+        &self,
+        name: impl Into<AvmString<'gc>>,
+        value: impl Into<Value<'gc>>,
+        mc: MutationContext<'gc, '_>,
+    ) {
         self.locals()
             .define_value(mc, name, value.into(), EnumSet::empty());
     }
@@ -2,7 +2,7 @@ use crate::avm1::activation::Activation;
 use crate::avm1::error::Error;
 use crate::avm1::function::{Executable, FunctionObject, NativeFunction};
 use crate::avm1::property::{Attribute, Property};
-use crate::avm1::{Object, ObjectPtr, TObject, UpdateContext, Value};
+use crate::avm1::{AvmString, Object, ObjectPtr, TObject, UpdateContext, Value};
 use crate::property_map::{Entry, PropertyMap};
 use core::fmt;
 use enumset::EnumSet;
@@ -18,16 +18,25 @@ pub enum ArrayStorage<'gc> {
     Properties { length: usize },
 }
 
+/// A callback registered with `Object.watch`.
+#[derive(Debug, Clone, Collect)]
+#[collect(no_drop)]
+pub struct Watcher<'gc> {
+    callback: Object<'gc>,
+    user_data: Value<'gc>,
+}
+
 #[derive(Debug, Copy, Clone, Collect)]
 #[collect(no_drop)]
 pub struct ScriptObject<'gc>(GcCell<'gc, ScriptObjectData<'gc>>);
 
 pub struct ScriptObjectData<'gc> {
     prototype: Option<Object<'gc>>,
-    values: PropertyMap<Property<'gc>>,
+    values: PropertyMap<'gc, Property<'gc>>,
     interfaces: Vec<Object<'gc>>,
     type_of: &'static str,
     array: ArrayStorage<'gc>,
+    watchers: PropertyMap<'gc, Watcher<'gc>>,
 }
 
 unsafe impl<'gc> Collect for ScriptObjectData<'gc> {
@@ -36,6 +45,7 @@ unsafe impl<'gc> Collect for ScriptObjectData<'gc> {
         self.values.trace(cc);
         self.array.trace(cc);
         self.interfaces.trace(cc);
+        self.watchers.trace(cc);
     }
 }
 
@@ -62,6 +72,7 @@ impl<'gc> ScriptObject<'gc> {
                 values: PropertyMap::new(),
                 array: ArrayStorage::Properties { length: 0 },
                 interfaces: vec![],
+                watchers: PropertyMap::new(),
             },
         ))
     }
@@ -78,6 +89,7 @@ impl<'gc> ScriptObject<'gc> {
                 values: PropertyMap::new(),
                 array: ArrayStorage::Vector(Vec::new()),
                 interfaces: vec![],
+                watchers: PropertyMap::new(),
             },
         ));
         object.sync_native_property("length", gc_context, Some(0.into()), false);
@@ -97,6 +109,7 @@ impl<'gc> ScriptObject<'gc> {
                 values: PropertyMap::new(),
                 array: ArrayStorage::Properties { length: 0 },
                 interfaces: vec![],
+                watchers: PropertyMap::new(),
             },
         ))
         .into()
@@ -116,6 +129,7 @@ impl<'gc> ScriptObject<'gc> {
                 values: PropertyMap::new(),
                 array: ArrayStorage::Properties { length: 0 },
                 interfaces: vec![],
+                watchers: PropertyMap::new(),
             },
         ))
     }
@@ -129,7 +143,7 @@ impl<'gc> ScriptObject<'gc> {
     /// user-defined function.
     pub fn force_set_function<A>(
         &mut self,
-        name: &str,
+        name: impl Into<AvmString<'gc>>,
         function: NativeFunction<'gc>,
         gc_context: MutationContext<'gc, '_>,
         attributes: A,
@@ -154,12 +168,12 @@ impl<'gc> ScriptObject<'gc> {
     #[allow(clippy::trivially_copy_pass_by_ref)]
     pub fn sync_native_property(
         &self,
-        name: &str,
+        name: impl Into<AvmString<'gc>>,
         gc_context: MutationContext<'gc, '_>,
         native_value: Option<Value<'gc>>,
         is_enumerable: bool,
     ) {
-        match self.0.write(gc_context).values.entry(name, false) {
+        match self.0.write(gc_context).values.entry(name.into(), false) {
             Entry::Occupied(mut entry) => {
                 if let Property::Stored { value, .. } = entry.get_mut() {
                     match native_value {
@@ -187,16 +201,89 @@ impl<'gc> ScriptObject<'gc> {
         }
     }
 
+    /// Call `callback` whenever the stored property `name` is set.
+    ///
+    /// The callback receives the property name, its old and new values, and
+    /// `user_data`. Whatever it returns is stored instead of the new value.
+    /// Only one watcher may exist per property; this replaces any other.
+    pub fn watch(
+        &self,
+        activation: &mut Activation<'_, 'gc>,
+        gc_context: MutationContext<'gc, '_>,
+        name: impl Into<AvmString<'gc>>,
+        callback: Object<'gc>,
+        user_data: Value<'gc>,
+    ) {
+        self.0.write(gc_context).watchers.insert(
+            name.into(),
+            Watcher {
+                callback,
+                user_data,
+            },
+            activation.is_case_sensitive(),
+        );
+    }
+
+    /// Remove the watcher of the property `name`.
+    ///
+    /// Returns `false` if the property wasn't being watched.
+    pub fn unwatch(
+        &self,
+        activation: &mut Activation<'_, 'gc>,
+        gc_context: MutationContext<'gc, '_>,
+        name: &str,
+    ) -> bool {
+        self.0
+            .write(gc_context)
+            .watchers
+            .remove(name, activation.is_case_sensitive())
+            .is_some()
+    }
+
+    /// Call the watcher of a property that is about to be set, if there is one.
+    ///
+    /// Yields the value that should actually be stored. Properties defined
+    /// with `addProperty` can't be watched.
+    fn call_watcher(
+        &self,
+        name: AvmString<'gc>,
+        value: Value<'gc>,
+        activation: &mut Activation<'_, 'gc>,
+        context: &mut UpdateContext<'_, 'gc, '_>,
+        this: Object<'gc>,
+    ) -> Result<Value<'gc>, Error<'gc>> {
+        let case_sensitive = activation.is_case_sensitive();
+        let (watcher, old_value) = {
+            let data = self.0.read();
+            let watcher = match data.watchers.get(&name, case_sensitive) {
+                Some(watcher) => watcher.clone(),
+                None => return Ok(value),
+            };
+            let old_value = match data.values.get(&name, case_sensitive) {
+                Some(Property::Stored { value, .. }) => value.clone(),
+                Some(Property::Virtual { .. }) => return Ok(value),
+                None => Value::Undefined,
+            };
+            (watcher, old_value)
+        };
+
+        let args = [name.into(), old_value, value, watcher.user_data];
+        watcher
+            .callback
+            .call("[Watcher]", activation, context, this, None, &args)
+    }
+
     #[allow(clippy::trivially_copy_pass_by_ref)]
     pub(crate) fn internal_set(
         &self,
-        name: &str,
+        name: impl Into<AvmString<'gc>>,
         value: Value<'gc>,
         activation: &mut Activation<'_, 'gc>,
         context: &mut UpdateContext<'_, 'gc, '_>,
         this: Object<'gc>,
         base_proto: Option<Object<'gc>>,
     ) -> Result<(), Error<'gc>> {
+        let name = name.into();
         if name == "__proto__" {
             self.0.write(context.gc_context).prototype =
                 Some(value.coerce_to_object(activation, context));
@@ -222,13 +309,13 @@ impl<'gc> ScriptObject<'gc> {
                 .0
                 .read()
                 .values
-                .contains_key(name, activation.is_case_sensitive());
+                .contains_key(&name, activation.is_case_sensitive());
             let mut worked = false;
 
             if is_vacant {
                 let mut proto: Option<Object<'gc>> = Some((*self).into());
                 while let Some(this_proto) = proto {
-                    if this_proto.has_own_virtual(activation, context, name) {
+                    if this_proto.has_own_virtual(activation, context, &name) {
                         break;
                     }
 
@@ -238,7 +325,7 @@ impl<'gc> ScriptObject<'gc> {
                 if let Some(this_proto) = proto {
                     worked = true;
                     if let Some(rval) =
-                        this_proto.call_setter(name, value.clone(), activation, context)
+                        this_proto.call_setter(&name, value.clone(), activation, context)
                     {
                         let _ = rval.exec(
                             "[Setter]",
@@ -247,6 +334,7 @@ impl<'gc> ScriptObject<'gc> {
                             this,
                             Some(this_proto),
                             &[value.clone()],
+                            None,
                         )?;
                     }
                 }
@@ -256,6 +344,7 @@ impl<'gc> ScriptObject<'gc> {
             //we'd resolve and return up there, but we have borrows that need
             //to end before we can do so.
             if !worked {
+                let value = self.call_watcher(name, value, activation, context, this)?;
                 let rval = match self
                     .0
                     .write(context.gc_context)
@@ -274,8 +363,15 @@ impl<'gc> ScriptObject<'gc> {
                 };
 
                 if let Some(rval) = rval {
-                    let _ =
-                        rval.exec("[Setter]", activation, context, this, base_proto, &[value])?;
+                    let _ = rval.exec(
+                        "[Setter]",
+                        activation,
+                        context,
+                        this,
+                        base_proto,
+                        &[value],
+                        None,
+                    )?;
                 }
             }
         }
@@ -326,6 +422,7 @@ impl<'gc> TObject<'gc> for ScriptObject<'gc> {
                 this,
                 Some((*self).into()),
                 &[],
+                None,
             )
         } else {
             Ok(Value::Undefined)
@@ -339,7 +436,7 @@ impl<'gc> TObject<'gc> for ScriptObject<'gc> {
     /// overrides that may need to interact with the underlying object.
     fn set(
         &self,
-        name: &str,
+        name: impl Into<AvmString<'gc>>,
         value: Value<'gc>,
         activation: &mut Activation<'_, 'gc>,
         context: &mut UpdateContext<'_, 'gc, '_>,
@@ -430,13 +527,13 @@ impl<'gc> TObject<'gc> for ScriptObject<'gc> {
     fn add_property(
         &self,
         gc_context: MutationContext<'gc, '_>,
-        name: &str,
+        name: impl Into<AvmString<'gc>>,
         get: Executable<'gc>,
         set: Option<Executable<'gc>>,
         attributes: EnumSet<Attribute>,
     ) {
         self.0.write(gc_context).values.insert(
-            name,
+            name.into(),
             Property::Virtual {
                 get,
                 set,
@@ -450,13 +547,13 @@ impl<'gc> TObject<'gc> for ScriptObject<'gc> {
         &self,
         activation: &mut Activation<'_, 'gc>,
         gc_context: MutationContext<'gc, '_>,
-        name: &str,
+        name: impl Into<AvmString<'gc>>,
         get: Executable<'gc>,
         set: Option<Executable<'gc>>,
         attributes: EnumSet<Attribute>,
     ) {
         self.0.write(gc_context).values.insert(
-            name,
+            name.into(),
             Property::Virtual {
                 get,
                 set,
@@ -469,14 +566,15 @@ impl<'gc> TObject<'gc> for ScriptObject<'gc> {
     fn define_value(
         &self,
         gc_context: MutationContext<'gc, '_>,
-        name: &str,
+        name: impl Into<AvmString<'gc>>,
         value: Value<'gc>,
         attributes: EnumSet<Attribute>,
     ) {
-        self.0
-            .write(gc_context)
-            .values
-            .insert(name, Property::Stored { value, attributes }, false);
+        self.0.write(gc_context).values.insert(
+            name.into(),
+            Property::Stored { value, attributes },
+            false,
+        );
     }
 
     fn set_attributes(
@@ -575,10 +673,14 @@ impl<'gc> TObject<'gc> for ScriptObject<'gc> {
     }
 
     /// Enumerate the object.
-    fn get_keys(&self, activation: &mut Activation<'_, 'gc>) -> Vec<String> {
+    fn get_keys(
+        &self,
+        activation: &mut Activation<'_, 'gc>,
+        gc_context: MutationContext<'gc, '_>,
+    ) -> Vec<AvmString<'gc>> {
         let proto_keys = self
             .proto()
-            .map_or_else(Vec::new, |p| p.get_keys(activation));
+            .map_or_else(Vec::new, |p| p.get_keys(activation, gc_context));
         let mut out_keys = vec![];
         let object = self.0.read();
 
@@ -592,7 +694,7 @@ impl<'gc> TObject<'gc> for ScriptObject<'gc> {
         // Then our own keys.
         out_keys.extend(self.0.read().values.iter().filter_map(move |(k, p)| {
             if p.is_enumerable() {
-                Some(k.to_string())
+                Some(k)
             } else {
                 None
             }
@@ -649,7 +751,12 @@ impl<'gc> TObject<'gc> for ScriptObject<'gc> {
         }
         if let Some(to_remove) = to_remove {
             for i in to_remove {
-                self.sync_native_property(&i.to_string(), gc_context, None, true);
+                self.sync_native_property(
+                    AvmString::new(gc_context, i.to_string()),
+                    gc_context,
+                    None,
+                    true,
+                );
             }
         }
         self.sync_native_property("length", gc_context, Some(new_length.into()), false);
@@ -696,7 +803,12 @@ impl<'gc> TObject<'gc> for ScriptObject<'gc> {
         value: Value<'gc>,
         gc_context: MutationContext<'gc, '_>,
     ) -> usize {
-        self.sync_native_property(&index.to_string(), gc_context, Some(value.clone()), true);
+        self.sync_native_property(
+            AvmString::new(gc_context, index.to_string()),
+            gc_context,
+            Some(value.clone()),
+            true,
+        );
         let mut adjust_length = false;
         let length = match &mut self.0.write(gc_context).array {
             ArrayStorage::Vector(vector) => {
@@ -729,18 +841,31 @@ mod tests {
     use super::*;
 
     use crate::avm1::activation::ActivationIdentifier;
+    use crate::avm1::globals::external_interface::ExternalCallbacks;
+    use crate::avm1::globals::local_connection::LocalConnections;
+    use crate::avm1::globals::net_stream::NetStreams;
+    use crate::avm1::globals::print_job::PrintJobs;
     use crate::avm1::globals::system::SystemProperties;
+    use crate::avm1::globals::timer::Timers;
+    use crate::avm1::globals::xml_socket::XmlSockets;
     use crate::avm1::property::Attribute::*;
     use crate::avm1::Avm1;
-    use crate::backend::audio::NullAudioBackend;
+    use crate::avm2::Avm2;
+    use crate::backend::audio::{AudioManager, NullAudioBackend};
+    use crate::backend::font::NullFontBackend;
     use crate::backend::input::NullInputBackend;
+    use crate::backend::log::NullLogBackend;
     use crate::backend::navigator::NullNavigatorBackend;
     use crate::backend::render::NullRenderer;
     use crate::backend::storage::MemoryStorageBackend;
+    use crate::backend::video::NullVideoBackend;
     use crate::display_object::MovieClip;
+    use crate::focus_tracker::FocusTracker;
+    use crate::keyboard::KeyboardState;
     use crate::library::Library;
     use crate::loader::LoadManager;
     use crate::prelude::*;
+    use crate::stage::StageProperties;
     use crate::tag_utils::{SwfMovie, SwfSlice};
     use gc_arena::rootless_arena;
     use rand::{rngs::SmallRng, SeedableRng};
@@ -773,6 +898,7 @@ mod tests {
                 rng: &mut SmallRng::from_seed([0u8; 16]),
                 action_queue: &mut crate::context::ActionQueue::new(),
                 audio: &mut NullAudioBackend::new(),
+                audio_manager: &mut AudioManager::new(),
                 input: &mut NullInputBackend::new(),
                 background_color: &mut Color {
                     r: 0,
@@ -782,17 +908,34 @@ mod tests {
                 },
                 library: &mut Library::default(),
                 navigator: &mut NullNavigatorBackend::new(),
+                xml_sockets: &mut XmlSockets::new(),
+                local_connections: &mut LocalConnections::new(),
+                timers: &mut Timers::new(),
+                external_interface: &mut None,
+                external_callbacks: &mut ExternalCallbacks::new(),
+                print_jobs: &mut PrintJobs::new(),
+                net_streams: &mut NetStreams::new(),
+                current_context_menu: &mut None,
+                fs_command_handler: &mut None,
                 renderer: &mut NullRenderer::new(),
                 system_prototypes: avm.prototypes().clone(),
+                avm2: &mut Avm2::new(gc_context),
                 mouse_hovered_object: None,
+                mouse_pressed_object: None,
                 mouse_position: &(Twips::new(0), Twips::new(0)),
+                keyboard: &KeyboardState::new(),
                 drag_object: &mut None,
+                focus_tracker: &mut FocusTracker::new(),
                 stage_size: (Twips::from_pixels(550.0), Twips::from_pixels(400.0)),
+                stage: &mut StageProperties::new((550, 400)),
                 player: None,
                 load_manager: &mut LoadManager::new(),
                 system: &mut SystemProperties::default(),
                 instance_counter: &mut 0,
                 storage: &mut MemoryStorageBackend::default(),
+                fonts: &mut NullFontBackend::new(),
+                video: &mut NullVideoBackend::new(),
+                log: &mut NullLogBackend::new(),
                 shared_objects: &mut HashMap::new(),
                 unbound_text_fields: &mut Vec::new(),
             };
@@ -1047,12 +1190,82 @@ mod tests {
                 DontEnum.into(),
             );
 
-            let keys: Vec<_> = object.get_keys(activation);
+            let keys: Vec<_> = object.get_keys(activation, context.gc_context);
             assert_eq!(keys.len(), 2);
-            assert_eq!(keys.contains(&"stored".to_string()), true);
-            assert_eq!(keys.contains(&"stored_hidden".to_string()), false);
-            assert_eq!(keys.contains(&"virtual".to_string()), true);
-            assert_eq!(keys.contains(&"virtual_hidden".to_string()), false);
+            assert_eq!(keys.contains(&"stored".into()), true);
+            assert_eq!(keys.contains(&"stored_hidden".into()), false);
+            assert_eq!(keys.contains(&"virtual".into()), true);
+            assert_eq!(keys.contains(&"virtual_hidden".into()), false);
+        })
+    }
+
+    #[test]
+    fn test_watch() {
+        // `undefined` only coerces to "undefined" from SWF 7 onwards.
+        with_object(7, |activation, context, object| {
+            let callback = FunctionObject::function(
+                context.gc_context,
+                Executable::Native(|activation, context, _this, args| {
+                    let mut result = vec![];
+                    for arg in args {
+                        result.push(arg.coerce_to_string(activation, context)?.into_owned());
+                    }
+                    Ok(AvmString::new(context.gc_context, result.join(" ")).into())
+                }),
+                None,
+                None,
+            );
+            let getter = Executable::Native(|_avm, _context, _this, _args| Ok("Virtual!".into()));
+
+            let script_object = object.as_script_object().unwrap();
+            script_object.add_property(
+                context.gc_context,
+                "virtual",
+                getter,
+                None,
+                EnumSet::empty(),
+            );
+            script_object.watch(
+                activation,
+                context.gc_context,
+                "watched",
+                callback,
+                "data".into(),
+            );
+            script_object.watch(
+                activation,
+                context.gc_context,
+                "virtual",
+                callback,
+                "data".into(),
+            );
+
+            object
+                .set("watched", "a".into(), activation, context)
+                .unwrap();
+            assert_eq!(
+                object.get("watched", activation, context).unwrap(),
+                "watched undefined a data".into()
+            );
+
+            // Properties with getters and setters can't be watched.
+            object
+                .set("virtual", "Ignored!".into(), activation, context)
+                .unwrap();
+            assert_eq!(
+                object.get("virtual", activation, context).unwrap(),
+                "Virtual!".into()
+            );
+
+            assert!(script_object.unwatch(activation, context.gc_context, "watched"));
+            assert!(!script_object.unwatch(activation, context.gc_context, "watched"));
+            object
+                .set("watched", "b".into(), activation, context)
+                .unwrap();
+            assert_eq!(
+                object.get("watched", activation, context).unwrap(),
+                "b".into()
+            );
         })
     }
 }
@@ -4,7 +4,7 @@ use crate::avm1::activation::Activation;
 use crate::avm1::error::Error;
 use crate::avm1::function::Executable;
 use crate::avm1::property::Attribute;
-use crate::avm1::{Object, ObjectPtr, ScriptObject, TObject, Value};
+use crate::avm1::{AvmString, Object, ObjectPtr, ScriptObject, TObject, Value};
 use crate::backend::audio::{SoundHandle, SoundInstanceHandle};
 use crate::context::UpdateContext;
 use crate::display_object::DisplayObject;
@@ -39,12 +39,23 @@ pub struct SoundObjectData<'gc> {
 
     /// Duration of the currently attached sound in milliseconds.
     duration: u32,
+
+    /// The number of bytes of a sound loaded by `loadSound` that have been loaded so far.
+    /// `None` if this object is not loading a sound.
+    bytes_loaded: Option<u32>,
+
+    /// The size of a sound loaded by `loadSound` in bytes, or `None` if it is not yet known.
+    bytes_total: Option<u32>,
+
+    /// The ID3 tags of a sound loaded by `loadSound`, exposed as the `id3` property.
+    id3: Option<Object<'gc>>,
 }
 
 unsafe impl<'gc> Collect for SoundObjectData<'gc> {
     fn trace(&self, cc: gc_arena::CollectionContext) {
         self.base.trace(cc);
         self.owner.trace(cc);
+        self.id3.trace(cc);
     }
 }
 
@@ -73,6 +84,9 @@ impl<'gc> SoundObject<'gc> {
                 owner: None,
                 position: 0,
                 duration: 0,
+                bytes_loaded: None,
+                bytes_total: None,
+                id3: None,
             },
         ))
     }
@@ -125,6 +139,33 @@ impl<'gc> SoundObject<'gc> {
         self.0.write(gc_context).position = position;
     }
 
+    pub fn bytes_loaded(self) -> Option<u32> {
+        self.0.read().bytes_loaded
+    }
+
+    pub fn bytes_total(self) -> Option<u32> {
+        self.0.read().bytes_total
+    }
+
+    pub fn set_load_progress(
+        self,
+        gc_context: MutationContext<'gc, '_>,
+        bytes_loaded: Option<u32>,
+        bytes_total: Option<u32>,
+    ) {
+        let mut write = self.0.write(gc_context);
+        write.bytes_loaded = bytes_loaded;
+        write.bytes_total = bytes_total;
+    }
+
+    pub fn id3(self) -> Option<Object<'gc>> {
+        self.0.read().id3
+    }
+
+    pub fn set_id3(self, gc_context: MutationContext<'gc, '_>, id3: Option<Object<'gc>>) {
+        self.0.write(gc_context).id3 = id3;
+    }
+
     fn base(self) -> ScriptObject<'gc> {
         self.0.read().base
     }
@@ -143,7 +184,7 @@ impl<'gc> TObject<'gc> for SoundObject<'gc> {
 
     fn set(
         &self,
-        name: &str,
+        name: impl Into<AvmString<'gc>>,
         value: Value<'gc>,
         activation: &mut Activation<'_, 'gc>,
         context: &mut UpdateContext<'_, 'gc, '_>,
@@ -207,7 +248,7 @@ impl<'gc> TObject<'gc> for SoundObject<'gc> {
     fn define_value(
         &self,
         gc_context: MutationContext<'gc, '_>,
-        name: &str,
+        name: impl Into<AvmString<'gc>>,
         value: Value<'gc>,
         attributes: EnumSet<Attribute>,
     ) {
@@ -229,7 +270,7 @@ impl<'gc> TObject<'gc> for SoundObject<'gc> {
     fn add_property(
         &self,
         gc_context: MutationContext<'gc, '_>,
-        name: &str,
+        name: impl Into<AvmString<'gc>>,
         get: Executable<'gc>,
         set: Option<Executable<'gc>>,
         attributes: EnumSet<Attribute>,
@@ -242,7 +283,7 @@ impl<'gc> TObject<'gc> for SoundObject<'gc> {
         &self,
         activation: &mut Activation<'_, 'gc>,
         gc_context: MutationContext<'gc, '_>,
-        name: &str,
+        name: impl Into<AvmString<'gc>>,
         get: Executable<'gc>,
         set: Option<Executable<'gc>>,
         attributes: EnumSet<Attribute>,
@@ -282,8 +323,12 @@ impl<'gc> TObject<'gc> for SoundObject<'gc> {
         self.base().is_property_enumerable(activation, name)
     }
 
-    fn get_keys(&self, activation: &mut Activation<'_, 'gc>) -> Vec<String> {
-        self.base().get_keys(activation)
+    fn get_keys(
+        &self,
+        activation: &mut Activation<'_, 'gc>,
+        gc_context: MutationContext<'gc, '_>,
+    ) -> Vec<AvmString<'gc>> {
+        self.base().get_keys(activation, gc_context)
     }
 
     fn as_string(&self) -> Cow<str> {
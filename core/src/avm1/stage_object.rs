@@ -5,10 +5,11 @@ use crate::avm1::error::Error;
 use crate::avm1::function::Executable;
 use crate::avm1::object::search_prototype;
 use crate::avm1::property::Attribute;
-use crate::avm1::{Object, ObjectPtr, ScriptObject, TDisplayObject, TObject, Value};
+use crate::avm1::{AvmString, Object, ObjectPtr, ScriptObject, TDisplayObject, TObject, Value};
 use crate::context::UpdateContext;
-use crate::display_object::{DisplayObject, EditText, MovieClip};
+use crate::display_object::{object_under_point, DisplayObject, EditText, MovieClip};
 use crate::property_map::PropertyMap;
+use crate::stage::StageQuality;
 use enumset::EnumSet;
 use gc_arena::{Collect, GcCell, MutationContext};
 use std::borrow::Cow;
@@ -134,7 +135,10 @@ impl<'gc> TObject<'gc> for StageObject<'gc> {
         let props = activation.avm.display_properties;
         let case_sensitive = activation.is_case_sensitive();
         // Property search order for DisplayObjects:
-        if self.has_own_property(activation, context, name) {
+        if obj.display_object.dangling() {
+            // 0) A reference to a removed clip is dead, and only sees its prototype
+            Ok(search_prototype(self.proto(), name, activation, context, (*self).into())?.0)
+        } else if self.has_own_property(activation, context, name) {
             // 1) Actual properties on the underlying object
             self.get_local(name, activation, context, (*self).into())
         } else if let Some(property) = props.read().get_by_name(&name) {
@@ -172,19 +176,25 @@ impl<'gc> TObject<'gc> for StageObject<'gc> {
 
     fn set(
         &self,
-        name: &str,
+        name: impl Into<AvmString<'gc>>,
         value: Value<'gc>,
         activation: &mut Activation<'_, 'gc>,
         context: &mut UpdateContext<'_, 'gc, '_>,
     ) -> Result<(), Error<'gc>> {
+        let name = name.into();
         let obj = self.0.read();
         let props = activation.avm.display_properties;
 
+        // Writes to a dead reference to a removed clip are ignored.
+        if obj.display_object.dangling() {
+            return Ok(());
+        }
+
         // Check if a text field is bound to this property and update the text if so.
         for binding in obj
             .text_field_bindings
             .iter()
-            .filter(|binding| binding.variable_name == name)
+            .filter(|binding| binding.variable_name == name.as_str())
         {
             let _ = binding.text_field.set_html_text(
                 value.coerce_to_string(activation, context)?.into_owned(),
@@ -192,7 +202,7 @@ impl<'gc> TObject<'gc> for StageObject<'gc> {
             );
         }
 
-        if obj.base.has_own_property(activation, context, name) {
+        if obj.base.has_own_property(activation, context, &name) {
             // 1) Actual proeprties on the underlying object
             obj.base.internal_set(
                 name,
@@ -278,7 +288,7 @@ impl<'gc> TObject<'gc> for StageObject<'gc> {
     fn define_value(
         &self,
         gc_context: MutationContext<'gc, '_>,
-        name: &str,
+        name: impl Into<AvmString<'gc>>,
         value: Value<'gc>,
         attributes: EnumSet<Attribute>,
     ) {
@@ -306,7 +316,7 @@ impl<'gc> TObject<'gc> for StageObject<'gc> {
     fn add_property(
         &self,
         gc_context: MutationContext<'gc, '_>,
-        name: &str,
+        name: impl Into<AvmString<'gc>>,
         get: Executable<'gc>,
         set: Option<Executable<'gc>>,
         attributes: EnumSet<Attribute>,
@@ -321,7 +331,7 @@ impl<'gc> TObject<'gc> for StageObject<'gc> {
         &self,
         activation: &mut Activation<'_, 'gc>,
         gc_context: MutationContext<'gc, '_>,
-        name: &str,
+        name: impl Into<AvmString<'gc>>,
         get: Executable<'gc>,
         set: Option<Executable<'gc>>,
         attributes: EnumSet<Attribute>,
@@ -402,16 +412,32 @@ impl<'gc> TObject<'gc> for StageObject<'gc> {
         self.0.read().base.is_property_enumerable(activation, name)
     }
 
-    fn get_keys(&self, activation: &mut Activation<'_, 'gc>) -> Vec<String> {
+    fn get_keys(
+        &self,
+        activation: &mut Activation<'_, 'gc>,
+        gc_context: MutationContext<'gc, '_>,
+    ) -> Vec<AvmString<'gc>> {
         // Keys from the underlying object are listed first, followed by
-        // child display objects in order from highest depth to lowest depth.
+        // child display objects, most recently added first.
         let obj = self.0.read();
-        let mut keys = obj.base.get_keys(activation);
-        keys.extend(
-            obj.display_object
-                .children()
-                .map(|child| child.name().to_string()),
-        );
+        let case_sensitive = activation.is_case_sensitive();
+        let mut keys = obj.base.get_keys(activation, gc_context);
+        let mut seen = PropertyMap::new();
+        for key in &keys {
+            seen.insert(*key, (), case_sensitive);
+        }
+
+        // Only children that are visible to ActionScript are enumerated, so shapes and
+        // static text are skipped. A name is only listed once, even if it's shared by
+        // a property or another child.
+        for child in obj.display_object.children() {
+            if let Value::Object(_) = child.object() {
+                let name = AvmString::new(gc_context, child.name().to_string());
+                if seen.insert(name, (), case_sensitive).is_none() {
+                    keys.push(name);
+                }
+            }
+        }
         keys
     }
 
@@ -528,9 +554,11 @@ impl<'gc> DisplayProperty<'gc> {
         this: DisplayObject<'gc>,
         value: Value<'gc>,
     ) -> Result<(), Error<'gc>> {
-        self.set
-            .map(|f| f(activation, context, this, value))
-            .unwrap_or(Ok(()))
+        if let Some(set) = self.set {
+            set(activation, context, this, value)?;
+            this.invalidate_cached_bitmap();
+        }
+        Ok(())
     }
 }
 
@@ -543,7 +571,7 @@ unsafe impl<'gc> Collect for DisplayProperty<'gc> {
 /// The map from key/index to function pointers for special display object properties.
 #[derive(Collect)]
 #[collect(no_drop)]
-pub struct DisplayPropertyMap<'gc>(PropertyMap<DisplayProperty<'gc>>);
+pub struct DisplayPropertyMap<'gc>(PropertyMap<'gc, DisplayProperty<'gc>>);
 
 impl<'gc> DisplayPropertyMap<'gc> {
     /// Creates the display property map.
@@ -596,12 +624,12 @@ impl<'gc> DisplayPropertyMap<'gc> {
 
     fn add_property(
         &mut self,
-        name: &str,
+        name: &'static str,
         get: DisplayGetter<'gc>,
         set: Option<DisplaySetter<'gc>>,
     ) {
         let prop = DisplayProperty { get, set };
-        self.0.insert(name, prop, false);
+        self.0.insert(name.into(), prop, false);
     }
 }
 
@@ -824,30 +852,30 @@ fn set_rotation<'gc>(
 
 fn target<'gc>(
     _activation: &mut Activation<'_, 'gc>,
-    _context: &mut UpdateContext<'_, 'gc, '_>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
     this: DisplayObject<'gc>,
 ) -> Result<Value<'gc>, Error<'gc>> {
-    Ok(this.slash_path().into())
+    Ok(AvmString::new(context.gc_context, this.slash_path()).into())
 }
 
 fn frames_loaded<'gc>(
     _activation: &mut Activation<'_, 'gc>,
-    _context: &mut UpdateContext<'_, 'gc, '_>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
     this: DisplayObject<'gc>,
 ) -> Result<Value<'gc>, Error<'gc>> {
     Ok(this
         .as_movie_clip()
-        .map(MovieClip::frames_loaded)
+        .map(|mc| mc.frames_loaded(context))
         .map(Value::from)
         .unwrap_or(Value::Undefined))
 }
 
 fn name<'gc>(
     _activation: &mut Activation<'_, 'gc>,
-    _context: &mut UpdateContext<'_, 'gc, '_>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
     this: DisplayObject<'gc>,
 ) -> Result<Value<'gc>, Error<'gc>> {
-    Ok((*this.name()).into())
+    Ok(AvmString::new(context.gc_context, this.name().to_string()).into())
 }
 
 fn set_name<'gc>(
@@ -863,11 +891,29 @@ fn set_name<'gc>(
 
 fn drop_target<'gc>(
     _activation: &mut Activation<'_, 'gc>,
-    _context: &mut UpdateContext<'_, 'gc, '_>,
-    _this: DisplayObject<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: DisplayObject<'gc>,
 ) -> Result<Value<'gc>, Error<'gc>> {
-    log::warn!("Unimplemented property _droptarget");
-    Ok("".into())
+    // The movie clip under the mouse, ignoring this clip itself, as a slash-syntax path.
+    // Shapes and other non-clip objects are reported as the clip containing them.
+    let point = *context.mouse_position;
+    let target = context
+        .levels
+        .values()
+        .rev()
+        .find_map(|level| object_under_point(*level, point, Some(this)))
+        .and_then(|target| {
+            if target.as_movie_clip().is_some() {
+                Some(target)
+            } else {
+                target.parent()
+            }
+        });
+    Ok(AvmString::new(
+        context.gc_context,
+        target.map_or_else(String::new, |target| target.slash_path()),
+    )
+    .into())
 }
 
 fn url<'gc>(
@@ -881,77 +927,89 @@ fn url<'gc>(
 
 fn high_quality<'gc>(
     _activation: &mut Activation<'_, 'gc>,
-    _context: &mut UpdateContext<'_, 'gc, '_>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
     _this: DisplayObject<'gc>,
 ) -> Result<Value<'gc>, Error<'gc>> {
-    log::warn!("Unimplemented property _highquality");
-    Ok(1.into())
+    let high_quality = match context.stage.quality {
+        StageQuality::Low => 0,
+        StageQuality::Medium | StageQuality::High => 1,
+        StageQuality::Best => 2,
+    };
+    Ok(high_quality.into())
 }
 
 fn set_high_quality<'gc>(
-    _activation: &mut Activation<'_, 'gc>,
-    _context: &mut UpdateContext<'_, 'gc, '_>,
+    activation: &mut Activation<'_, 'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
     _this: DisplayObject<'gc>,
-    _val: Value<'gc>,
+    val: Value<'gc>,
 ) -> Result<(), Error<'gc>> {
-    log::warn!("Unimplemented property _highquality");
+    context.stage.quality = match val.coerce_to_i32(activation, context)? {
+        n if n <= 0 => StageQuality::Low,
+        1 => StageQuality::High,
+        _ => StageQuality::Best,
+    };
     Ok(())
 }
 
 fn focus_rect<'gc>(
     _activation: &mut Activation<'_, 'gc>,
-    _context: &mut UpdateContext<'_, 'gc, '_>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
     _this: DisplayObject<'gc>,
 ) -> Result<Value<'gc>, Error<'gc>> {
-    log::warn!("Unimplemented property _focusrect");
-    Ok(Value::Null)
+    Ok(context.stage.focus_rect.into())
 }
 
 fn set_focus_rect<'gc>(
-    _activation: &mut Activation<'_, 'gc>,
-    _context: &mut UpdateContext<'_, 'gc, '_>,
+    activation: &mut Activation<'_, 'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
     _this: DisplayObject<'gc>,
-    _val: Value<'gc>,
+    val: Value<'gc>,
 ) -> Result<(), Error<'gc>> {
-    log::warn!("Unimplemented property _focusrect");
+    context.stage.focus_rect = val.as_bool(activation.current_swf_version());
     Ok(())
 }
 
 fn sound_buf_time<'gc>(
     _activation: &mut Activation<'_, 'gc>,
-    _context: &mut UpdateContext<'_, 'gc, '_>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
     _this: DisplayObject<'gc>,
 ) -> Result<Value<'gc>, Error<'gc>> {
-    log::warn!("Unimplemented property _soundbuftime");
-    Ok(5.into())
+    Ok(context.audio_manager.stream_buffer_time().into())
 }
 
 fn set_sound_buf_time<'gc>(
-    _activation: &mut Activation<'_, 'gc>,
-    _context: &mut UpdateContext<'_, 'gc, '_>,
+    activation: &mut Activation<'_, 'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
     _this: DisplayObject<'gc>,
-    _val: Value<'gc>,
+    val: Value<'gc>,
 ) -> Result<(), Error<'gc>> {
-    log::warn!("Unimplemented property _soundbuftime");
+    let seconds = val.coerce_to_i32(activation, context)?.max(0);
+    context
+        .audio_manager
+        .set_stream_buffer_time(context.audio, seconds);
     Ok(())
 }
 
 fn quality<'gc>(
     _activation: &mut Activation<'_, 'gc>,
-    _context: &mut UpdateContext<'_, 'gc, '_>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
     _this: DisplayObject<'gc>,
 ) -> Result<Value<'gc>, Error<'gc>> {
-    log::warn!("Unimplemented property _quality");
-    Ok("HIGH".into())
+    Ok(context.stage.quality.name().into())
 }
 
 fn set_quality<'gc>(
-    _activation: &mut Activation<'_, 'gc>,
-    _context: &mut UpdateContext<'_, 'gc, '_>,
+    activation: &mut Activation<'_, 'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
     _this: DisplayObject<'gc>,
-    _val: Value<'gc>,
+    val: Value<'gc>,
 ) -> Result<(), Error<'gc>> {
-    log::warn!("Unimplemented property _quality");
+    // Unknown quality names are ignored.
+    let name = val.coerce_to_string(activation, context)?;
+    if let Some(quality) = StageQuality::from_name(&name) {
+        context.stage.quality = quality;
+    }
     Ok(())
 }
 
@@ -0,0 +1,130 @@
+//! The string type used by AVM1 values.
+
+use gc_arena::{Collect, Gc, MutationContext};
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::ops::Deref;
+
+#[derive(Debug, Clone, Copy, Collect)]
+#[collect(no_drop)]
+enum Source<'gc> {
+    Owned(Gc<'gc, String>),
+    Static(&'static str),
+}
+
+/// An immutable, garbage-collected string.
+///
+/// Copying an `AvmString` never copies the string data, so strings that are
+/// created once and used many times (such as constant pool entries and
+/// built-in property names) are effectively interned. Equality checks between
+/// two handles to the same string only compare pointers.
+#[derive(Debug, Clone, Copy, Collect)]
+#[collect(no_drop)]
+pub struct AvmString<'gc> {
+    source: Source<'gc>,
+}
+
+impl<'gc> AvmString<'gc> {
+    pub fn new<S: Into<String>>(gc_context: MutationContext<'gc, '_>, string: S) -> Self {
+        Self {
+            source: Source::Owned(Gc::allocate(gc_context, string.into())),
+        }
+    }
+
+    pub fn as_str(&self) -> &str {
+        self
+    }
+
+    /// Returns true if both strings share the same underlying storage.
+    pub fn ptr_eq(a: AvmString<'gc>, b: AvmString<'gc>) -> bool {
+        match (a.source, b.source) {
+            (Source::Owned(a), Source::Owned(b)) => Gc::ptr_eq(a, b),
+            (Source::Static(a), Source::Static(b)) => std::ptr::eq(a, b),
+            _ => false,
+        }
+    }
+}
+
+impl Default for AvmString<'_> {
+    fn default() -> Self {
+        Self {
+            source: Source::Static(""),
+        }
+    }
+}
+
+impl<'gc> From<&'static str> for AvmString<'gc> {
+    fn from(str: &'static str) -> Self {
+        Self {
+            source: Source::Static(str),
+        }
+    }
+}
+
+impl Deref for AvmString<'_> {
+    type Target = str;
+
+    #[inline]
+    fn deref(&self) -> &str {
+        match &self.source {
+            Source::Owned(str) => str.deref(),
+            Source::Static(str) => str,
+        }
+    }
+}
+
+impl AsRef<str> for AvmString<'_> {
+    #[inline]
+    fn as_ref(&self) -> &str {
+        self
+    }
+}
+
+impl fmt::Display for AvmString<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self)
+    }
+}
+
+impl<'gc> PartialEq<AvmString<'gc>> for AvmString<'gc> {
+    #[inline]
+    fn eq(&self, other: &AvmString<'gc>) -> bool {
+        AvmString::ptr_eq(*self, *other) || self.as_str() == other.as_str()
+    }
+}
+
+impl Eq for AvmString<'_> {}
+
+impl Hash for AvmString<'_> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.as_str().hash(state)
+    }
+}
+
+macro_rules! impl_str_eq {
+    ($lhs:ty, $rhs: ty) => {
+        #[allow(unused_lifetimes)]
+        impl<'a, 'gc> PartialEq<$rhs> for $lhs {
+            #[inline]
+            fn eq(&self, other: &$rhs) -> bool {
+                let this: &str = self.as_ref();
+                let other: &str = other.as_ref();
+                this == other
+            }
+        }
+
+        #[allow(unused_lifetimes)]
+        impl<'a, 'gc> PartialEq<$lhs> for $rhs {
+            #[inline]
+            fn eq(&self, other: &$lhs) -> bool {
+                let this: &str = self.as_ref();
+                let other: &str = other.as_ref();
+                this == other
+            }
+        }
+    };
+}
+
+impl_str_eq! { AvmString<'gc>, str }
+impl_str_eq! { AvmString<'gc>, &'a str }
+impl_str_eq! { AvmString<'gc>, String }
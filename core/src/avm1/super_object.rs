@@ -6,7 +6,7 @@ use crate::avm1::function::Executable;
 use crate::avm1::object::search_prototype;
 use crate::avm1::property::Attribute;
 use crate::avm1::script_object::TYPE_OF_OBJECT;
-use crate::avm1::{Object, ObjectPtr, ScriptObject, TObject, Value};
+use crate::avm1::{AvmString, Object, ObjectPtr, ScriptObject, TObject, Value};
 use crate::context::UpdateContext;
 use crate::display_object::DisplayObject;
 use enumset::EnumSet;
@@ -91,9 +91,21 @@ impl<'gc> TObject<'gc> for SuperObject<'gc> {
         Ok(Value::Undefined)
     }
 
+    fn get(
+        &self,
+        name: &str,
+        activation: &mut Activation<'_, 'gc>,
+        context: &mut UpdateContext<'_, 'gc, '_>,
+    ) -> Result<Value<'gc>, Error<'gc>> {
+        // Properties of the child itself are never visible through `super`, only those of
+        // the superclass prototype chain. Getters still run with the child as `this`.
+        let child = self.0.read().child;
+        Ok(search_prototype(self.super_proto(), name, activation, context, child)?.0)
+    }
+
     fn set(
         &self,
-        _name: &str,
+        _name: impl Into<AvmString<'gc>>,
         _value: Value<'gc>,
         _activation: &mut Activation<'_, 'gc>,
         _context: &mut UpdateContext<'_, 'gc, '_>,
@@ -197,7 +209,7 @@ impl<'gc> TObject<'gc> for SuperObject<'gc> {
     fn define_value(
         &self,
         _gc_context: MutationContext<'gc, '_>,
-        _name: &str,
+        _name: impl Into<AvmString<'gc>>,
         _value: Value<'gc>,
         _attributes: EnumSet<Attribute>,
     ) {
@@ -217,7 +229,7 @@ impl<'gc> TObject<'gc> for SuperObject<'gc> {
     fn add_property(
         &self,
         _gc_context: MutationContext<'gc, '_>,
-        _name: &str,
+        _name: impl Into<AvmString<'gc>>,
         _get: Executable<'gc>,
         _set: Option<Executable<'gc>>,
         _attributes: EnumSet<Attribute>,
@@ -229,7 +241,7 @@ impl<'gc> TObject<'gc> for SuperObject<'gc> {
         &self,
         _activation: &mut Activation<'_, 'gc>,
         _gc_context: MutationContext<'gc, '_>,
-        _name: &str,
+        _name: impl Into<AvmString<'gc>>,
         _get: Executable<'gc>,
         _set: Option<Executable<'gc>>,
         _attributes: EnumSet<Attribute>,
@@ -274,7 +286,11 @@ impl<'gc> TObject<'gc> for SuperObject<'gc> {
         self.0.read().child.is_property_enumerable(activation, name)
     }
 
-    fn get_keys(&self, _activation: &mut Activation<'_, 'gc>) -> Vec<String> {
+    fn get_keys(
+        &self,
+        _activation: &mut Activation<'_, 'gc>,
+        _gc_context: MutationContext<'gc, '_>,
+    ) -> Vec<AvmString<'gc>> {
         vec![]
     }
 
@@ -1,17 +1,30 @@
 use crate::avm1::activation::{Activation, ActivationIdentifier};
 use crate::avm1::error::Error;
+use crate::avm1::globals::external_interface::ExternalCallbacks;
+use crate::avm1::globals::local_connection::LocalConnections;
+use crate::avm1::globals::net_stream::NetStreams;
+use crate::avm1::globals::print_job::PrintJobs;
 use crate::avm1::globals::system::SystemProperties;
-use crate::avm1::{Avm1, Object, UpdateContext};
-use crate::backend::audio::NullAudioBackend;
+use crate::avm1::globals::timer::Timers;
+use crate::avm1::globals::xml_socket::XmlSockets;
+use crate::avm1::{Avm1, Object, TObject, UpdateContext, Value};
+use crate::avm2::Avm2;
+use crate::backend::audio::{AudioManager, NullAudioBackend};
+use crate::backend::font::NullFontBackend;
 use crate::backend::input::NullInputBackend;
+use crate::backend::log::NullLogBackend;
 use crate::backend::navigator::NullNavigatorBackend;
 use crate::backend::render::NullRenderer;
 use crate::backend::storage::MemoryStorageBackend;
+use crate::backend::video::NullVideoBackend;
 use crate::context::ActionQueue;
 use crate::display_object::{MovieClip, TDisplayObject};
+use crate::focus_tracker::FocusTracker;
+use crate::keyboard::KeyboardState;
 use crate::library::Library;
 use crate::loader::LoadManager;
 use crate::prelude::*;
+use crate::stage::StageProperties;
 use crate::tag_utils::{SwfMovie, SwfSlice};
 use gc_arena::{rootless_arena, MutationContext};
 use rand::{rngs::SmallRng, SeedableRng};
@@ -50,6 +63,7 @@ where
             levels: &mut levels,
             rng: &mut SmallRng::from_seed([0u8; 16]),
             audio: &mut NullAudioBackend::new(),
+            audio_manager: &mut AudioManager::new(),
             input: &mut NullInputBackend::new(),
             action_queue: &mut ActionQueue::new(),
             background_color: &mut Color {
@@ -60,17 +74,34 @@ where
             },
             library: &mut Library::default(),
             navigator: &mut NullNavigatorBackend::new(),
+            xml_sockets: &mut XmlSockets::new(),
+            local_connections: &mut LocalConnections::new(),
+            timers: &mut Timers::new(),
+            external_interface: &mut None,
+            external_callbacks: &mut ExternalCallbacks::new(),
+            print_jobs: &mut PrintJobs::new(),
+            net_streams: &mut NetStreams::new(),
+            current_context_menu: &mut None,
+            fs_command_handler: &mut None,
             renderer: &mut NullRenderer::new(),
             system_prototypes: avm.prototypes().clone(),
+            avm2: &mut Avm2::new(gc_context),
             mouse_hovered_object: None,
+            mouse_pressed_object: None,
             mouse_position: &(Twips::new(0), Twips::new(0)),
+            keyboard: &KeyboardState::new(),
             drag_object: &mut None,
+            focus_tracker: &mut FocusTracker::new(),
             stage_size: (Twips::from_pixels(550.0), Twips::from_pixels(400.0)),
+            stage: &mut StageProperties::new((550, 400)),
             player: None,
             load_manager: &mut LoadManager::new(),
             system: &mut SystemProperties::default(),
             instance_counter: &mut 0,
             storage: &mut MemoryStorageBackend::default(),
+            fonts: &mut NullFontBackend::new(),
+            video: &mut NullVideoBackend::new(),
+            log: &mut NullLogBackend::new(),
             shared_objects: &mut HashMap::new(),
             unbound_text_fields: &mut Vec::new(),
         };
@@ -112,6 +143,28 @@ where
     rootless_arena(|gc_context| in_the_arena(swf_version, test, gc_context))
 }
 
+/// Constructs an object of the global class with the given name, like the `new` operator.
+/// The name can be a path from the global object, such as `flash.geom.Point`.
+pub fn construct<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    class_name: &str,
+    args: &[Value<'gc>],
+) -> Result<Object<'gc>, Error<'gc>> {
+    let mut constructor = activation.avm.globals;
+    for name in class_name.split('.') {
+        constructor = constructor
+            .get(name, activation, context)?
+            .coerce_to_object(activation, context);
+    }
+    let prototype = constructor
+        .get("prototype", activation, context)?
+        .coerce_to_object(activation, context);
+    let object = prototype.new(activation, context, prototype, args)?;
+    constructor.call("[ctor]", activation, context, object, None, args)?;
+    Ok(object)
+}
+
 macro_rules! test_method {
     ( $test: ident, $name: expr, $object: expr, $($versions: expr => { $([$($arg: expr),*] => $out: expr),* }),* ) => {
         #[test]
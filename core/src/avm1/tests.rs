@@ -1,17 +1,14 @@
 use crate::avm1::error::Error;
 use crate::avm1::test_utils::with_avm;
-use crate::avm1::TObject;
+use crate::avm1::{AvmString, TObject, Value};
+use crate::stage::StageQuality;
 
 #[test]
 fn locals_into_form_values() {
-    with_avm(19, |activation, context, _this| -> Result<(), Error> {
-        let my_locals = activation.scope().locals().to_owned();
-        my_locals
-            .set("value1", "string".into(), activation, context)
-            .unwrap();
-        my_locals
-            .set("value2", 2.0.into(), activation, context)
+    with_avm(19, |activation, context, this| -> Result<(), Error> {
+        this.set("value1", "string".into(), activation, context)
             .unwrap();
+        this.set("value2", 2.0.into(), activation, context).unwrap();
         let my_local_values = activation.locals_into_form_values(context);
 
         assert_eq!(my_local_values.len(), 2);
@@ -21,3 +18,1156 @@ fn locals_into_form_values() {
         Ok(())
     });
 }
+
+#[test]
+fn movie_clip_creation_with_missing_args() {
+    with_avm(8, |activation, context, this| -> Result<(), Error> {
+        this.call_method("createEmptyMovieClip", &[], activation, context)?;
+        let clip = this.get("undefined", activation, context)?;
+        assert!(matches!(clip, Value::Object(_)));
+
+        this.call_method(
+            "createEmptyMovieClip",
+            &["clip".into()],
+            activation,
+            context,
+        )?;
+        let clip = this.get("clip", activation, context)?;
+        assert!(matches!(clip, Value::Object(_)));
+
+        for method in &["attachMovie", "duplicateMovieClip"] {
+            this.call_method(method, &[], activation, context)?;
+            this.call_method(method, &["clip".into()], activation, context)?;
+        }
+
+        Ok(())
+    });
+}
+
+#[test]
+fn cached_bitmap_invalidated_by_child_changes() {
+    use crate::display_object::TDisplayObject;
+    use crate::transform::Transform;
+
+    with_avm(8, |activation, context, this| -> Result<(), Error> {
+        this.call_method(
+            "createEmptyMovieClip",
+            &["cached".into(), 1.0.into()],
+            activation,
+            context,
+        )?;
+        let cached = this
+            .get("cached", activation, context)?
+            .coerce_to_object(activation, context);
+        cached.set("cacheAsBitmap", true.into(), activation, context)?;
+        cached.call_method(
+            "createEmptyMovieClip",
+            &["child".into(), 1.0.into()],
+            activation,
+            context,
+        )?;
+        let child = cached
+            .get("child", activation, context)?
+            .coerce_to_object(activation, context);
+
+        let cached = cached.as_display_object().unwrap();
+        assert!(cached.is_bitmap_cached());
+
+        let child_changes: &[(&str, &[Value])] = &[
+            ("gotoAndStop", &[Value::Number(2.0)]),
+            ("lineTo", &[Value::Number(10.0), Value::Number(10.0)]),
+        ];
+        for (method, args) in child_changes {
+            cached.bitmap_cache().set_transform(Transform::default());
+            assert!(cached.bitmap_cache().is_valid_for(&Transform::default()));
+            child.call_method(method, args, activation, context)?;
+            assert!(
+                !cached.bitmap_cache().is_valid_for(&Transform::default()),
+                "{} should invalidate the parent's cached bitmap",
+                method
+            );
+        }
+
+        cached.bitmap_cache().set_transform(Transform::default());
+        child.set("_x", 10.0.into(), activation, context)?;
+        assert!(!cached.bitmap_cache().is_valid_for(&Transform::default()));
+
+        Ok(())
+    });
+}
+
+#[test]
+fn scroll_rect_clips_size() {
+    use crate::avm1::{Object, ScriptObject};
+
+    with_avm(8, |activation, context, this| -> Result<(), Error> {
+        this.call_method(
+            "createEmptyMovieClip",
+            &["pane".into(), 1.0.into()],
+            activation,
+            context,
+        )?;
+        let pane = this
+            .get("pane", activation, context)?
+            .coerce_to_object(activation, context);
+        pane.call_method("beginFill", &[0xFF0000.into()], activation, context)?;
+        for &(x, y) in &[(200.0, 0.0), (200.0, 200.0), (0.0, 200.0), (0.0, 0.0)] {
+            pane.call_method("lineTo", &[x.into(), y.into()], activation, context)?;
+        }
+
+        let rect = ScriptObject::object(context.gc_context, None);
+        rect.set("x", 10.0.into(), activation, context)?;
+        rect.set("y", 20.0.into(), activation, context)?;
+        rect.set("width", 100.0.into(), activation, context)?;
+        rect.set("height", 50.0.into(), activation, context)?;
+        pane.set("scrollRect", rect.into(), activation, context)?;
+
+        assert_eq!(
+            pane.get("_width", activation, context)?,
+            Value::Number(100.0)
+        );
+        assert_eq!(
+            pane.get("_height", activation, context)?,
+            Value::Number(50.0)
+        );
+
+        let scroll_rect = pane
+            .get("scrollRect", activation, context)?
+            .coerce_to_object(activation, context);
+        assert!(!Object::ptr_eq(scroll_rect, rect.into()));
+        assert_eq!(
+            scroll_rect.get("x", activation, context)?,
+            Value::Number(10.0)
+        );
+        assert_eq!(
+            scroll_rect.get("height", activation, context)?,
+            Value::Number(50.0)
+        );
+
+        pane.set("scrollRect", Value::Null, activation, context)?;
+        assert_eq!(
+            pane.get("scrollRect", activation, context)?,
+            Value::Undefined
+        );
+        assert_eq!(
+            pane.get("_width", activation, context)?,
+            Value::Number(200.0)
+        );
+
+        Ok(())
+    });
+}
+
+#[test]
+fn scale_9_grid_round_trip() {
+    use crate::avm1::ScriptObject;
+
+    with_avm(8, |activation, context, this| -> Result<(), Error> {
+        this.call_method(
+            "createEmptyMovieClip",
+            &["skin".into(), 1.0.into()],
+            activation,
+            context,
+        )?;
+        let skin = this
+            .get("skin", activation, context)?
+            .coerce_to_object(activation, context);
+        assert_eq!(skin.get("scale9Grid", activation, context)?, Value::Null);
+
+        let rect = ScriptObject::object(context.gc_context, None);
+        rect.set("x", 10.0.into(), activation, context)?;
+        rect.set("y", 20.0.into(), activation, context)?;
+        rect.set("width", 80.0.into(), activation, context)?;
+        rect.set("height", 60.0.into(), activation, context)?;
+        skin.set("scale9Grid", rect.into(), activation, context)?;
+
+        let grid = skin
+            .get("scale9Grid", activation, context)?
+            .coerce_to_object(activation, context);
+        assert_eq!(grid.get("x", activation, context)?, Value::Number(10.0));
+        assert_eq!(grid.get("y", activation, context)?, Value::Number(20.0));
+        assert_eq!(grid.get("width", activation, context)?, Value::Number(80.0));
+        assert_eq!(
+            grid.get("height", activation, context)?,
+            Value::Number(60.0)
+        );
+
+        skin.set("scale9Grid", Value::Undefined, activation, context)?;
+        assert_eq!(skin.get("scale9Grid", activation, context)?, Value::Null);
+
+        Ok(())
+    });
+}
+
+#[test]
+fn transform_matrix_moves_clip() {
+    with_avm(8, |activation, context, this| -> Result<(), Error> {
+        this.call_method(
+            "createEmptyMovieClip",
+            &["clip".into(), 1.0.into()],
+            activation,
+            context,
+        )?;
+        let clip = this
+            .get("clip", activation, context)?
+            .coerce_to_object(activation, context);
+        let transform = clip
+            .get("transform", activation, context)?
+            .coerce_to_object(activation, context);
+        let matrix = transform
+            .get("matrix", activation, context)?
+            .coerce_to_object(activation, context);
+        matrix.call_method(
+            "translate",
+            &[15.0.into(), 25.0.into()],
+            activation,
+            context,
+        )?;
+        assert_eq!(clip.get("_x", activation, context)?, Value::Number(0.0));
+
+        transform.set("matrix", matrix.into(), activation, context)?;
+        assert_eq!(clip.get("_x", activation, context)?, Value::Number(15.0));
+        assert_eq!(clip.get("_y", activation, context)?, Value::Number(25.0));
+
+        let concatenated = transform
+            .get("concatenatedMatrix", activation, context)?
+            .coerce_to_object(activation, context);
+        assert_eq!(
+            concatenated.get("tx", activation, context)?,
+            Value::Number(15.0)
+        );
+
+        Ok(())
+    });
+}
+
+#[test]
+fn timers_fire_by_elapsed_time() {
+    use crate::avm1::globals::timer;
+    use crate::avm1::{Object, ScriptObject};
+
+    with_avm(8, |activation, context, _this| -> Result<(), Error> {
+        let globals = activation.avm.globals;
+        let set_interval = globals
+            .get("setInterval", activation, context)?
+            .coerce_to_object(activation, context);
+        let clear_interval = globals
+            .get("clearInterval", activation, context)?
+            .coerce_to_object(activation, context);
+        let set_timeout = globals
+            .get("setTimeout", activation, context)?
+            .coerce_to_object(activation, context);
+
+        // An interval that clears itself the first time it fires.
+        let args = [globals.into(), "clearInterval".into(), 10.into(), 1.into()];
+        assert_eq!(
+            set_interval.call("setInterval", activation, context, globals, None, &args)?,
+            1.into()
+        );
+
+        let calls: Object =
+            ScriptObject::array(context.gc_context, Some(activation.avm.prototypes.array)).into();
+        let args = [calls.into(), "push".into(), 10.into(), "interval".into()];
+        assert_eq!(
+            set_interval.call("setInterval", activation, context, globals, None, &args)?,
+            2.into()
+        );
+        let args = [calls.into(), "push".into(), 25.into(), "timeout".into()];
+        assert_eq!(
+            set_timeout.call("setTimeout", activation, context, globals, None, &args)?,
+            3.into()
+        );
+
+        assert!(timer::update_timers(activation, context, 35.0));
+        assert_eq!(
+            calls.array(),
+            vec![
+                "interval".into(),
+                "interval".into(),
+                "timeout".into(),
+                "interval".into()
+            ]
+        );
+
+        assert!(timer::update_timers(activation, context, 40.0));
+        assert_eq!(calls.length(), 8);
+
+        let args = [2.into()];
+        clear_interval.call("clearInterval", activation, context, globals, None, &args)?;
+        assert!(!timer::update_timers(activation, context, 100.0));
+        assert_eq!(calls.length(), 8);
+        assert_eq!(context.timers.time_til_next_timer(), None);
+
+        Ok(())
+    });
+}
+
+#[test]
+fn array_sort_with_flags_and_compare_functions() {
+    use crate::avm1::activation::Activation;
+    use crate::avm1::function::{Executable, FunctionObject};
+    use crate::avm1::{Object, ScriptObject};
+    use crate::context::UpdateContext;
+
+    fn new_array<'gc>(
+        activation: &mut Activation<'_, 'gc>,
+        context: &mut UpdateContext<'_, 'gc, '_>,
+        values: &[Value<'gc>],
+    ) -> Object<'gc> {
+        let array: Object<'gc> =
+            ScriptObject::array(context.gc_context, Some(activation.avm.prototypes.array)).into();
+        for (i, value) in values.iter().enumerate() {
+            array.set_array_element(i, value.clone(), context.gc_context);
+        }
+        array
+    }
+
+    fn throwing_compare<'gc>(
+        _activation: &mut Activation<'_, 'gc>,
+        _context: &mut UpdateContext<'_, 'gc, '_>,
+        _this: Object<'gc>,
+        _args: &[Value<'gc>],
+    ) -> Result<Value<'gc>, Error<'gc>> {
+        Err(Error::ThrownValue("compare failed".into()))
+    }
+
+    fn non_numeric_compare<'gc>(
+        _activation: &mut Activation<'_, 'gc>,
+        _context: &mut UpdateContext<'_, 'gc, '_>,
+        _this: Object<'gc>,
+        _args: &[Value<'gc>],
+    ) -> Result<Value<'gc>, Error<'gc>> {
+        Ok("not a number".into())
+    }
+
+    fn always_greater_compare<'gc>(
+        _activation: &mut Activation<'_, 'gc>,
+        _context: &mut UpdateContext<'_, 'gc, '_>,
+        _this: Object<'gc>,
+        _args: &[Value<'gc>],
+    ) -> Result<Value<'gc>, Error<'gc>> {
+        Ok(1.into())
+    }
+
+    with_avm(8, |activation, context, _this| -> Result<(), Error> {
+        let fn_proto = Some(activation.avm.prototypes.function);
+
+        // Array.NUMERIC | Array.DESCENDING
+        let array = new_array(activation, context, &[10.into(), 2.into(), 33.into()]);
+        array.call_method("sort", &[18.into()], activation, context)?;
+        assert_eq!(array.array(), vec![33.into(), 10.into(), 2.into()]);
+
+        // Array.UNIQUESORT fails without modifying the array.
+        let array = new_array(activation, context, &["b".into(), "a".into(), "b".into()]);
+        let result = array.call_method("sort", &[4.into()], activation, context)?;
+        assert_eq!(result, 0.into());
+        assert_eq!(array.array(), vec!["b".into(), "a".into(), "b".into()]);
+
+        // Array.RETURNINDEXEDARRAY doesn't modify the array.
+        let array = new_array(activation, context, &["c".into(), "a".into(), "b".into()]);
+        let result = array
+            .call_method("sort", &[8.into()], activation, context)?
+            .coerce_to_object(activation, context);
+        assert_eq!(result.array(), vec![1.into(), 2.into(), 0.into()]);
+        assert_eq!(array.array(), vec!["c".into(), "a".into(), "b".into()]);
+
+        // sortOn compares each field in turn.
+        let mut items = vec![];
+        for &(name, age) in &[("b", 2), ("a", 2), ("c", 1)] {
+            let item =
+                ScriptObject::object(context.gc_context, Some(activation.avm.prototypes.object));
+            item.set("name", name.into(), activation, context)?;
+            item.set("age", age.into(), activation, context)?;
+            items.push(Value::Object(item.into()));
+        }
+        let array = new_array(activation, context, &items);
+        let fields: Value = new_array(activation, context, &["age".into(), "name".into()]).into();
+        array.call_method("sortOn", &[fields], activation, context)?;
+        assert_eq!(
+            array.array(),
+            vec![items[2].clone(), items[1].clone(), items[0].clone()]
+        );
+
+        // Compare functions that don't return a number treat every value as equal.
+        let array = new_array(activation, context, &[3.into(), 1.into(), 2.into()]);
+        let compare = FunctionObject::function(
+            context.gc_context,
+            Executable::Native(non_numeric_compare),
+            fn_proto,
+            None,
+        );
+        array.call_method("sort", &[compare.into()], activation, context)?;
+        assert_eq!(array.array(), vec![3.into(), 1.into(), 2.into()]);
+
+        // Inconsistent compare functions keep every element.
+        let array = new_array(
+            activation,
+            context,
+            &[3.into(), 1.into(), 2.into(), 5.into()],
+        );
+        let compare = FunctionObject::function(
+            context.gc_context,
+            Executable::Native(always_greater_compare),
+            fn_proto,
+            None,
+        );
+        array.call_method("sort", &[compare.into()], activation, context)?;
+        let mut sorted: Vec<f64> = array
+            .array()
+            .iter()
+            .map(|value| match value {
+                Value::Number(n) => *n,
+                _ => f64::NAN,
+            })
+            .collect();
+        sorted.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        assert_eq!(sorted, vec![1.0, 2.0, 3.0, 5.0]);
+
+        // Errors thrown by the compare function are passed on, and the array is left untouched.
+        let array = new_array(activation, context, &[3.into(), 1.into(), 2.into()]);
+        let compare = FunctionObject::function(
+            context.gc_context,
+            Executable::Native(throwing_compare),
+            fn_proto,
+            None,
+        );
+        let result = array.call_method("sort", &[compare.into()], activation, context);
+        assert!(matches!(result, Err(Error::ThrownValue(_))));
+        assert_eq!(array.array(), vec![3.into(), 1.into(), 2.into()]);
+
+        Ok(())
+    });
+}
+
+#[test]
+fn as_broadcaster_listeners() {
+    use crate::avm1::activation::Activation;
+    use crate::avm1::function::{FunctionObject, NativeFunction};
+    use crate::avm1::listeners::SystemListener;
+    use crate::avm1::{Object, ScriptObject};
+    use crate::context::UpdateContext;
+
+    /// Appends `name(args)` to `_global.log`.
+    fn record<'gc>(
+        activation: &mut Activation<'_, 'gc>,
+        context: &mut UpdateContext<'_, 'gc, '_>,
+        this: Object<'gc>,
+        args: &[Value<'gc>],
+    ) -> Result<Value<'gc>, Error<'gc>> {
+        let globals = activation.avm.global_object_cell();
+        let mut log = globals
+            .get("log", activation, context)?
+            .coerce_to_string(activation, context)?
+            .into_owned();
+        log.push_str(
+            &this
+                .get("name", activation, context)?
+                .coerce_to_string(activation, context)?,
+        );
+        let mut strings = vec![];
+        for arg in args {
+            strings.push(arg.coerce_to_string(activation, context)?.into_owned());
+        }
+        log.push_str(&format!("({})", strings.join(",")));
+        globals.set(
+            "log",
+            AvmString::new(context.gc_context, log).into(),
+            activation,
+            context,
+        )?;
+        Ok(Value::Undefined)
+    }
+
+    fn remove_self<'gc>(
+        activation: &mut Activation<'_, 'gc>,
+        context: &mut UpdateContext<'_, 'gc, '_>,
+        this: Object<'gc>,
+        args: &[Value<'gc>],
+    ) -> Result<Value<'gc>, Error<'gc>> {
+        let broadcaster = activation
+            .avm
+            .global_object_cell()
+            .get("broadcaster", activation, context)?
+            .coerce_to_object(activation, context);
+        broadcaster.call_method("removeListener", &[this.into()], activation, context)?;
+        record(activation, context, this, args)
+    }
+
+    fn new_listener<'gc>(
+        activation: &mut Activation<'_, 'gc>,
+        context: &mut UpdateContext<'_, 'gc, '_>,
+        name: &'static str,
+        handler: NativeFunction<'gc>,
+    ) -> Result<Object<'gc>, Error<'gc>> {
+        let listener: Object<'gc> =
+            ScriptObject::object(context.gc_context, Some(activation.avm.prototypes.object)).into();
+        listener.set("name", name.into(), activation, context)?;
+        let handler = FunctionObject::function(
+            context.gc_context,
+            handler,
+            Some(activation.avm.prototypes.function),
+            None,
+        );
+        listener.set("onEvent", handler.into(), activation, context)?;
+        Ok(listener)
+    }
+
+    fn listeners<'gc>(
+        activation: &mut Activation<'_, 'gc>,
+        context: &mut UpdateContext<'_, 'gc, '_>,
+        broadcaster: Object<'gc>,
+    ) -> Result<Vec<Value<'gc>>, Error<'gc>> {
+        Ok(broadcaster
+            .get("_listeners", activation, context)?
+            .coerce_to_object(activation, context)
+            .array())
+    }
+
+    with_avm(8, |activation, context, _this| -> Result<(), Error> {
+        let globals = activation.avm.global_object_cell();
+        let a = new_listener(activation, context, "a", record)?;
+        let r = new_listener(activation, context, "r", remove_self)?;
+        let c = new_listener(activation, context, "c", record)?;
+
+        let broadcaster: Object =
+            ScriptObject::object(context.gc_context, Some(activation.avm.prototypes.object)).into();
+        globals.set("broadcaster", broadcaster.into(), activation, context)?;
+        globals.set("log", "".into(), activation, context)?;
+        let as_broadcaster = globals
+            .get("AsBroadcaster", activation, context)?
+            .coerce_to_object(activation, context);
+        as_broadcaster.call_method("initialize", &[broadcaster.into()], activation, context)?;
+        for listener in &[a, r, c] {
+            broadcaster.call_method("addListener", &[(*listener).into()], activation, context)?;
+        }
+
+        // A listener removing itself doesn't stop the others from being called.
+        let args = ["onEvent".into(), 1.into(), "x".into()];
+        broadcaster.call_method("broadcastMessage", &args, activation, context)?;
+        assert_eq!(
+            globals.get("log", activation, context)?,
+            "a(1,x)r(1,x)c(1,x)".into()
+        );
+        assert_eq!(
+            listeners(activation, context, broadcaster)?,
+            vec![a.into(), c.into()]
+        );
+
+        // Adding a listener again moves it to the end.
+        broadcaster.call_method("addListener", &[a.into()], activation, context)?;
+        assert_eq!(
+            listeners(activation, context, broadcaster)?,
+            vec![c.into(), a.into()]
+        );
+        let removed =
+            broadcaster.call_method("removeListener", &[r.into()], activation, context)?;
+        assert_eq!(removed, false.into());
+
+        // `Key._listeners` is an ordinary array that scripts may replace.
+        let key = globals
+            .get("Key", activation, context)?
+            .coerce_to_object(activation, context);
+        key.call_method("addListener", &[a.into()], activation, context)?;
+        assert_eq!(listeners(activation, context, key)?, vec![a.into()]);
+        let replacement: Object =
+            ScriptObject::array(context.gc_context, Some(activation.avm.prototypes.array)).into();
+        replacement.set_array_element(0, c.into(), context.gc_context);
+        key.set("_listeners", replacement.into(), activation, context)?;
+
+        globals.set("log", "".into(), activation, context)?;
+        let base_clip = activation.base_clip();
+        activation.avm.notify_system_listeners(
+            base_clip,
+            8,
+            context,
+            SystemListener::Key,
+            "onEvent",
+            &[],
+        );
+        assert_eq!(globals.get("log", activation, context)?, "c()".into());
+
+        Ok(())
+    });
+}
+
+#[test]
+fn context_menu_items() {
+    use crate::avm1::test_utils::construct;
+    use crate::context_menu::{ContextMenuItem, ContextMenuState};
+    use crate::stage::StageQuality;
+
+    with_avm(8, |activation, context, this| -> Result<(), Error> {
+        let menu = construct(activation, context, "ContextMenu", &[])?;
+        let item = construct(
+            activation,
+            context,
+            "ContextMenuItem",
+            &["Foo".into(), Value::Undefined, true.into()],
+        )?;
+
+        let custom_items = menu
+            .get("customItems", activation, context)?
+            .coerce_to_object(activation, context);
+        custom_items.set_array_element(0, item.into(), context.gc_context);
+        this.set("menu", menu.into(), activation, context)?;
+
+        let target = activation.target_clip_or_root();
+        let foo = ContextMenuItem {
+            caption: "Foo".to_string(),
+            enabled: true,
+            separator_before: true,
+            checked: false,
+        };
+
+        // The single frame movie only has the quality items built in.
+        let state = ContextMenuState::build(activation, context, target)?;
+        let captions: Vec<_> = state
+            .items()
+            .iter()
+            .map(|item| item.caption.as_str())
+            .collect();
+        assert_eq!(
+            captions,
+            vec!["Foo", "Low Quality", "Medium Quality", "High Quality"]
+        );
+        state.run(1, activation, context)?;
+        assert_eq!(context.stage.quality, StageQuality::Low);
+
+        menu.call_method("hideBuiltInItems", &[], activation, context)?;
+        let state = ContextMenuState::build(activation, context, target)?;
+        assert_eq!(state.items(), &[foo.clone()]);
+
+        // Copies keep the custom items.
+        let copy = menu
+            .call_method("copy", &[], activation, context)?
+            .coerce_to_object(activation, context);
+        this.set("menu", copy.into(), activation, context)?;
+        let state = ContextMenuState::build(activation, context, target)?;
+        assert_eq!(state.items(), &[foo]);
+
+        Ok(())
+    });
+}
+
+/// Creates an empty clip with a filled 100x100 square at its origin.
+fn create_square_clip<'gc>(
+    parent: crate::avm1::Object<'gc>,
+    name: &'static str,
+    depth: f64,
+    activation: &mut crate::avm1::activation::Activation<'_, 'gc>,
+    context: &mut crate::context::UpdateContext<'_, 'gc, '_>,
+) -> Result<crate::avm1::Object<'gc>, Error<'gc>> {
+    parent.call_method(
+        "createEmptyMovieClip",
+        &[name.into(), depth.into()],
+        activation,
+        context,
+    )?;
+    let clip = parent
+        .get(name, activation, context)?
+        .coerce_to_object(activation, context);
+    clip.call_method("beginFill", &[0xff0000.into()], activation, context)?;
+    for &(x, y) in &[(100.0, 0.0), (100.0, 100.0), (0.0, 100.0), (0.0, 0.0)] {
+        clip.call_method("lineTo", &[x.into(), y.into()], activation, context)?;
+    }
+    clip.call_method("endFill", &[], activation, context)?;
+    Ok(clip)
+}
+
+#[test]
+fn object_under_point_in_nested_transformed_clips() {
+    use crate::display_object::{object_under_point, TDisplayObject};
+    use crate::prelude::Twips;
+
+    with_avm(8, |activation, context, this| -> Result<(), Error> {
+        // `outer` is rotated a quarter turn, covering (100, 0)-(200, 100) on the stage.
+        // `inner` sits diagonally off its corner, covering (0, 100)-(100, 150) on the stage.
+        let outer = create_square_clip(this, "outer", 1.0, activation, context)?;
+        outer.set("_x", 200.0.into(), activation, context)?;
+        outer.set("_rotation", 90.0.into(), activation, context)?;
+        let inner = create_square_clip(outer, "inner", 1.0, activation, context)?;
+        inner.set("_x", 100.0.into(), activation, context)?;
+        inner.set("_y", 100.0.into(), activation, context)?;
+        inner.set("_xscale", 50.0.into(), activation, context)?;
+
+        let root = this.as_display_object().unwrap();
+        let hit = |x: f64, y: f64| {
+            let point = (Twips::from_pixels(x), Twips::from_pixels(y));
+            object_under_point(root, point, None).map(|o| o.slash_path())
+        };
+
+        assert_eq!(hit(150.0, 50.0), Some("/outer".to_string()));
+        assert_eq!(hit(50.0, 125.0), Some("/outer/inner".to_string()));
+        // Inside the bounds of `outer`, but outside of both shapes.
+        assert_eq!(hit(150.0, 125.0), None);
+
+        // Fully transparent clips are still hit.
+        inner.set("_alpha", 0.0.into(), activation, context)?;
+        assert_eq!(hit(50.0, 125.0), Some("/outer/inner".to_string()));
+        inner.set("_alpha", 100.0.into(), activation, context)?;
+
+        // Invisible clips are skipped.
+        inner.set("_visible", false.into(), activation, context)?;
+        assert_eq!(hit(50.0, 125.0), None);
+
+        // A hit area replaces the clip's own shapes, even when it is invisible.
+        outer.set("hitArea", inner.into(), activation, context)?;
+        assert_eq!(hit(50.0, 125.0), Some("/outer".to_string()));
+        assert_eq!(hit(150.0, 50.0), None);
+        outer.set("hitArea", Value::Undefined, activation, context)?;
+        inner.set("_visible", true.into(), activation, context)?;
+
+        // Points outside a mask don't hit the masked clip, and the mask itself is never hit.
+        let mask = create_square_clip(this, "mask", 2.0, activation, context)?;
+        mask.set("_x", 120.0.into(), activation, context)?;
+        outer.call_method("setMask", &[mask.into()], activation, context)?;
+        assert_eq!(hit(150.0, 50.0), Some("/outer".to_string()));
+        assert_eq!(hit(110.0, 50.0), None);
+        assert_eq!(hit(210.0, 50.0), None);
+
+        // The query can exclude an object, as done for a dragged clip's `_droptarget`.
+        let outer = outer.as_display_object().unwrap();
+        let point = (Twips::from_pixels(150.0), Twips::from_pixels(50.0));
+        assert!(object_under_point(root, point, Some(outer)).is_none());
+
+        Ok(())
+    });
+}
+
+fn clip_position<'gc>(
+    clip: crate::avm1::Object<'gc>,
+    activation: &mut crate::avm1::activation::Activation<'_, 'gc>,
+    context: &mut crate::context::UpdateContext<'_, 'gc, '_>,
+) -> Result<(f64, f64), Error<'gc>> {
+    let x = clip.get("_x", activation, context)?;
+    let y = clip.get("_y", activation, context)?;
+    Ok((
+        x.coerce_to_f64(activation, context)?,
+        y.coerce_to_f64(activation, context)?,
+    ))
+}
+
+#[test]
+fn start_drag_constraints() {
+    with_avm(8, |activation, context, this| -> Result<(), Error> {
+        // The mouse is at the origin of the stage.
+        let clip = create_square_clip(this, "clip", 1.0, activation, context)?;
+
+        // With lockCenter, the clip jumps to the mouse, clamped into the rectangle.
+        clip.set("_x", 50.0.into(), activation, context)?;
+        clip.set("_y", 50.0.into(), activation, context)?;
+        let args = [true.into(), 10.into(), 20.into(), 100.into(), 200.into()];
+        clip.call_method("startDrag", &args, activation, context)?;
+        assert_eq!(clip_position(clip, activation, context)?, (10.0, 20.0));
+
+        // Without it, the clip keeps its grab offset, but is still clamped.
+        // Reversed bounds are swapped.
+        clip.set("_x", 300.0.into(), activation, context)?;
+        clip.set("_y", 300.0.into(), activation, context)?;
+        let args = [false.into(), 100.into(), 200.into(), 10.into(), 20.into()];
+        clip.call_method("startDrag", &args, activation, context)?;
+        assert_eq!(clip_position(clip, activation, context)?, (100.0, 200.0));
+
+        // Scripts can move the dragged clip until the mouse moves again.
+        clip.set("_x", 500.0.into(), activation, context)?;
+        assert_eq!(clip_position(clip, activation, context)?, (500.0, 200.0));
+        assert!(context.drag_object.is_some());
+
+        // Removing the clip ends the drag.
+        clip.call_method("removeMovieClip", &[], activation, context)?;
+        assert!(context.drag_object.is_none());
+
+        Ok(())
+    });
+}
+
+#[test]
+fn stage_globals_are_shared_by_all_clips() {
+    with_avm(8, |activation, context, this| -> Result<(), Error> {
+        let clip = create_square_clip(this, "clip", 1.0, activation, context)?;
+
+        clip.set("_quality", "low".into(), activation, context)?;
+        assert_eq!(context.stage.quality, StageQuality::Low);
+        assert_eq!(this.get("_quality", activation, context)?, "LOW".into());
+        assert_eq!(this.get("_highquality", activation, context)?, 0.into());
+
+        // Unknown quality names are ignored.
+        this.set("_quality", "ULTRA".into(), activation, context)?;
+        assert_eq!(context.stage.quality, StageQuality::Low);
+
+        clip.set("_highquality", 2.into(), activation, context)?;
+        assert_eq!(this.get("_quality", activation, context)?, "BEST".into());
+
+        clip.set("_focusrect", false.into(), activation, context)?;
+        assert!(!context.stage.focus_rect);
+        assert_eq!(this.get("_focusrect", activation, context)?, false.into());
+
+        clip.set("_soundbuftime", 10.into(), activation, context)?;
+        assert_eq!(context.audio_manager.stream_buffer_time(), 10);
+        assert_eq!(this.get("_soundbuftime", activation, context)?, 10.into());
+
+        Ok(())
+    });
+}
+
+#[test]
+fn lock_root_stops_root_lookups() {
+    use crate::avm1::activation::{Activation, ActivationIdentifier};
+    use crate::display_object::{DisplayObject, TDisplayObject};
+
+    with_avm(8, |activation, context, this| -> Result<(), Error> {
+        // `shell` stands in for a loader movie's container, and `game` for the movie
+        // loaded into it. `game` positions `player` relative to what it sees as `_root`.
+        let shell = create_square_clip(this, "shell", 1.0, activation, context)?;
+        shell.set("_x", 100.0.into(), activation, context)?;
+        let game = create_square_clip(shell, "game", 1.0, activation, context)?;
+        let player = create_square_clip(game, "player", 1.0, activation, context)?;
+        create_square_clip(this, "marker", 2.0, activation, context)?;
+        let game_marker = create_square_clip(game, "marker", 2.0, activation, context)?;
+
+        let root = this.as_display_object().unwrap();
+        let game_clip = game.as_display_object().unwrap();
+        let player_clip = player.as_display_object().unwrap();
+        assert!(DisplayObject::ptr_eq(player_clip.root(), root));
+
+        game.set("_lockroot", true.into(), activation, context)?;
+        assert_eq!(game.get("_lockroot", activation, context)?, true.into());
+        assert!(DisplayObject::ptr_eq(player_clip.root(), game_clip));
+        assert!(DisplayObject::ptr_eq(game_clip.root(), game_clip));
+
+        // Code running in the loaded movie sees it as `_root`, both as a variable
+        // and at the start of slash paths.
+        let globals = activation.avm.global_object_cell();
+        let mut child_activation = Activation::from_nothing(
+            activation.avm,
+            ActivationIdentifier::root("[Test]"),
+            8,
+            globals,
+            context.gc_context,
+            player_clip,
+        );
+        assert_eq!(
+            player.get("_root", &mut child_activation, context)?,
+            game.into()
+        );
+        let marker = child_activation.resolve_target_display_object(
+            context,
+            player_clip,
+            "/marker".into(),
+        )?;
+        assert!(DisplayObject::ptr_eq(
+            marker.unwrap(),
+            game_marker.as_display_object().unwrap()
+        ));
+
+        // `hitTest` takes points relative to the locked root, which is offset by `shell`.
+        let args = [50.0.into(), 50.0.into(), true.into()];
+        assert_eq!(
+            player.call_method("hitTest", &args, &mut child_activation, context)?,
+            true.into()
+        );
+        let args = [150.0.into(), 50.0.into(), true.into()];
+        assert_eq!(
+            player.call_method("hitTest", &args, &mut child_activation, context)?,
+            false.into()
+        );
+
+        // Clearing the flag makes `_root` the level again.
+        game.set("_lockroot", false.into(), &mut child_activation, context)?;
+        assert!(DisplayObject::ptr_eq(player_clip.root(), root));
+
+        Ok(())
+    });
+}
+
+fn tab_order_paths<'gc>(
+    activation: &mut crate::avm1::activation::Activation<'_, 'gc>,
+    context: &mut crate::context::UpdateContext<'_, 'gc, '_>,
+) -> Vec<String> {
+    use crate::display_object::TDisplayObject;
+
+    crate::focus_tracker::tab_order(activation, context)
+        .iter()
+        .map(|object| object.slash_path())
+        .collect()
+}
+
+#[test]
+fn tab_order_and_focus() {
+    use crate::focus_tracker::cycle_focus;
+
+    with_avm(8, |activation, context, this| -> Result<(), Error> {
+        let top_right = create_square_clip(this, "top_right", 1.0, activation, context)?;
+        top_right.set("_x", 200.0.into(), activation, context)?;
+        let bottom = create_square_clip(this, "bottom", 2.0, activation, context)?;
+        bottom.set("_y", 200.0.into(), activation, context)?;
+        let top_left = create_square_clip(this, "top_left", 3.0, activation, context)?;
+        let plain = create_square_clip(this, "plain", 4.0, activation, context)?;
+        plain.set("_y", 400.0.into(), activation, context)?;
+        for clip in &[top_right, bottom, top_left] {
+            clip.set("tabEnabled", true.into(), activation, context)?;
+        }
+
+        // Without tab indices, objects are ordered from top to bottom and left to right.
+        // Clips that aren't buttons need `tabEnabled` to take part.
+        assert_eq!(
+            tab_order_paths(activation, context),
+            vec!["/top_left", "/top_right", "/bottom"]
+        );
+
+        let selection = activation
+            .avm
+            .global_object_cell()
+            .get("Selection", activation, context)?
+            .coerce_to_object(activation, context);
+        cycle_focus(activation, context, false);
+        cycle_focus(activation, context, false);
+        assert_eq!(
+            selection.call_method("getFocus", &[], activation, context)?,
+            "_level0.top_right".into()
+        );
+        assert!(context.focus_tracker.highlight().is_some());
+        cycle_focus(activation, context, true);
+        cycle_focus(activation, context, true);
+        assert_eq!(
+            selection.call_method("getFocus", &[], activation, context)?,
+            "_level0.bottom".into()
+        );
+
+        // Once any object has a tab index, only indexed objects take part.
+        bottom.set("tabIndex", 1.into(), activation, context)?;
+        top_right.set("tabIndex", 2.into(), activation, context)?;
+        assert_eq!(
+            tab_order_paths(activation, context),
+            vec!["/bottom", "/top_right"]
+        );
+
+        // `tabChildren` hides the children of a clip.
+        this.set("tabChildren", false.into(), activation, context)?;
+        assert!(tab_order_paths(activation, context).is_empty());
+        this.set("tabChildren", Value::Undefined, activation, context)?;
+
+        // Scripts can move focus, and removing the focused object clears it.
+        let args = ["top_left".into()];
+        assert_eq!(
+            selection.call_method("setFocus", &args, activation, context)?,
+            true.into()
+        );
+        top_left.call_method("removeMovieClip", &[], activation, context)?;
+        assert_eq!(
+            selection.call_method("getFocus", &[], activation, context)?,
+            Value::Null
+        );
+
+        Ok(())
+    });
+}
+
+#[test]
+fn action_queue_runs_constructors_first() {
+    use crate::context::ActionType;
+    use crate::display_object::TDisplayObject;
+    use crate::tag_utils::SwfSlice;
+
+    with_avm(8, |_activation, context, _this| -> Result<(), Error> {
+        let root = *context.levels.get(&0).unwrap();
+        // Discard the root's own construct action, queued when it was instantiated.
+        while context.action_queue.pop_action().is_some() {}
+
+        let frame_actions = || ActionType::Normal {
+            bytecode: SwfSlice::empty(root.movie().unwrap()),
+        };
+        let construct = || ActionType::Construct {
+            constructor: None,
+            events: vec![],
+        };
+        context
+            .action_queue
+            .queue_actions(root, frame_actions(), false);
+        context.action_queue.queue_actions(root, construct(), false);
+        context
+            .action_queue
+            .queue_actions(root, frame_actions(), true);
+        context.action_queue.queue_actions(root, construct(), false);
+
+        // Constructors are drained first; actions of the same priority stay in queue order.
+        let mut popped = vec![];
+        while let Some(actions) = context.action_queue.pop_action() {
+            let is_construct = matches!(actions.action_type, ActionType::Construct { .. });
+            popped.push((is_construct, actions.is_unload));
+        }
+        assert_eq!(
+            popped,
+            vec![(true, false), (true, false), (false, false), (false, true)]
+        );
+
+        Ok(())
+    });
+}
+
+#[test]
+fn swf_version_case_sensitivity_and_coercions() {
+    use crate::avm1::ScriptObject;
+
+    for &version in &[5, 6, 7, 8] {
+        with_avm(version, |activation, context, this| -> Result<(), Error> {
+            let case_insensitive = version <= 6;
+
+            let object = ScriptObject::object(context.gc_context, None);
+            object.set("Foo", 1.into(), activation, context)?;
+            let expected = if case_insensitive {
+                1.into()
+            } else {
+                Value::Undefined
+            };
+            assert_eq!(object.get("fOO", activation, context)?, expected);
+            assert_eq!(
+                object.has_property(activation, context, "FOO"),
+                case_insensitive
+            );
+            assert_eq!(
+                object.delete(activation, context.gc_context, "foo"),
+                case_insensitive
+            );
+
+            // Display object properties are case insensitive in every version.
+            this.set("_X", 10.into(), activation, context)?;
+            assert_eq!(this.get("_x", activation, context)?, 10.into());
+
+            let expected = if case_insensitive {
+                Value::Object(activation.this_cell())
+            } else {
+                Value::Undefined
+            };
+            assert_eq!(activation.resolve("THIS", context)?, expected);
+
+            let undefined = Value::Undefined;
+            let expected = if case_insensitive { "" } else { "undefined" };
+            assert_eq!(undefined.coerce_to_string(activation, context)?, expected);
+            let number = undefined.coerce_to_f64(activation, context)?;
+            assert_eq!(number.is_nan(), !case_insensitive);
+
+            Ok(())
+        });
+    }
+}
+
+#[test]
+fn target_path_resolution() {
+    use crate::display_object::TDisplayObject;
+
+    for &version in &[6, 7] {
+        with_avm(version, |activation, context, root| -> Result<(), Error> {
+            let case_insensitive = version <= 6;
+
+            let mut clips = vec![];
+            for &(parent, name, depth) in &[(0, "clip", 1), (1, "child", 1), (0, "other", 2)] {
+                let parent = if parent == 0 { root } else { clips[parent - 1] };
+                parent.call_method(
+                    "createEmptyMovieClip",
+                    &[name.into(), depth.into()],
+                    activation,
+                    context,
+                )?;
+                let clip = parent
+                    .get(name, activation, context)?
+                    .coerce_to_object(activation, context);
+                clips.push(clip);
+            }
+            let (clip, child, other) = (clips[0], clips[1], clips[2]);
+            root.set("y", "root".into(), activation, context)?;
+            clip.set("w", "clip".into(), activation, context)?;
+            child.set("x", "child".into(), activation, context)?;
+            other.set("z", "other".into(), activation, context)?;
+
+            let (child_if_insensitive, clip_if_insensitive) = if case_insensitive {
+                ("child".into(), "clip".into())
+            } else {
+                (Value::Undefined, Value::Undefined)
+            };
+            let get_variable_cases = [
+                ("y", "root".into()),
+                ("/:y", "root".into()),
+                ("/clip/child:x", "child".into()),
+                ("/clip/child.x", "child".into()),
+                ("/clip/child/:x", "child".into()),
+                ("clip/child:x", "child".into()),
+                ("clip.child.x", "child".into()),
+                ("clip:child:x", "child".into()),
+                (":clip:child:x", "child".into()),
+                ("clip/child/..:w", "clip".into()),
+                ("clip/child/../../other:z", "other".into()),
+                ("clip.child._parent.w", "clip".into()),
+                ("this/clip:w", "clip".into()),
+                ("this.clip.w", "clip".into()),
+                ("_root.clip.w", "clip".into()),
+                ("_root/clip/child:x", "child".into()),
+                ("/", root.into()),
+                ("/clip", clip.into()),
+                ("clip/child", child.into()),
+                ("clip/child/", child.into()),
+                ("", Value::Undefined),
+                ("../y", Value::Undefined),
+                ("clip/nope", Value::Undefined),
+                ("nope/child:x", Value::Undefined),
+                ("/CLIP/Child:x", child_if_insensitive.clone()),
+                ("clip.child.X", child_if_insensitive),
+                ("THIS/clip:w", clip_if_insensitive.clone()),
+                ("_ROOT.clip.w", clip_if_insensitive),
+            ];
+
+            let root_clip = root.as_display_object().unwrap();
+            activation.run_with_child_frame_for_display_object(
+                "[Target Path Test]",
+                root_clip,
+                version,
+                context,
+                |activation, context| -> Result<(), Error> {
+                    for (path, expected) in get_variable_cases.iter() {
+                        assert_eq!(
+                            activation.get_variable(context, path)?,
+                            expected.clone(),
+                            "get_variable({:?}) in SWF{}",
+                            path,
+                            version
+                        );
+                    }
+
+                    activation.set_variable(context, "/clip/child:v", 1.into())?;
+                    activation.set_variable(context, "clip.child.v2", 2.into())?;
+                    activation.set_variable(context, "this:v3", 3.into())?;
+                    activation.set_variable(context, "nope:v4", 4.into())?;
+                    activation.set_variable(context, "foo/bar", 5.into())?;
+                    assert_eq!(child.get("v", activation, context)?, 1.into());
+                    assert_eq!(child.get("v2", activation, context)?, 2.into());
+                    assert_eq!(root.get("v3", activation, context)?, 3.into());
+                    assert_eq!(root.get("v4", activation, context)?, Value::Undefined);
+                    assert_eq!(root.get("foo/bar", activation, context)?, 5.into());
+
+                    let target_cases = [
+                        ("", Some(root)),
+                        ("_root", Some(root)),
+                        ("clip/child", Some(child)),
+                        ("_root.clip.child._parent", Some(clip)),
+                        ("../clip", None),
+                        ("clip/nope", None),
+                    ];
+                    for (path, expected) in target_cases.iter() {
+                        let target = activation
+                            .resolve_target_display_object(context, root_clip, (*path).into())?
+                            .map(|o| o.object());
+                        assert_eq!(
+                            target,
+                            expected.map(Value::from),
+                            "resolve_target_display_object({:?}) in SWF{}",
+                            path,
+                            version
+                        );
+                    }
+
+                    Ok(())
+                },
+            )
+        });
+    }
+}
@@ -1,5 +1,6 @@
 use crate::avm1::activation::Activation;
 use crate::avm1::error::Error;
+use crate::avm1::string::AvmString;
 use crate::avm1::value_object::ValueObject;
 use crate::avm1::{Object, TObject, UpdateContext};
 use std::borrow::Cow;
@@ -12,25 +13,19 @@ pub enum Value<'gc> {
     Null,
     Bool(bool),
     Number(f64),
-    String(String),
+    String(AvmString<'gc>),
     Object(Object<'gc>),
 }
 
-impl<'gc> From<String> for Value<'gc> {
-    fn from(string: String) -> Self {
+impl<'gc> From<AvmString<'gc>> for Value<'gc> {
+    fn from(string: AvmString<'gc>) -> Self {
         Value::String(string)
     }
 }
 
-impl<'gc> From<&str> for Value<'gc> {
-    fn from(string: &str) -> Self {
-        Value::String(string.to_owned())
-    }
-}
-
-impl<'gc> From<Cow<'_, str>> for Value<'gc> {
-    fn from(string: Cow<str>) -> Self {
-        Value::String(string.to_string())
+impl<'gc> From<&'static str> for Value<'gc> {
+    fn from(string: &'static str) -> Self {
+        Value::String(string.into())
     }
 }
 
@@ -99,8 +94,10 @@ impl<'gc> From<usize> for Value<'gc> {
 
 unsafe impl<'gc> gc_arena::Collect for Value<'gc> {
     fn trace(&self, cc: gc_arena::CollectionContext) {
-        if let Value::Object(object) = self {
-            object.trace(cc);
+        match self {
+            Value::String(string) => string.trace(cc),
+            Value::Object(object) => object.trace(cc),
+            _ => {}
         }
     }
 }
@@ -269,7 +266,7 @@ impl<'gc> Value<'gc> {
         let prim_other = other.to_primitive_num(activation, context)?;
 
         if let (Value::String(a), Value::String(b)) = (&prim_self, &prim_other) {
-            return Ok(a.to_string().bytes().lt(b.to_string().bytes()).into());
+            return Ok(a.bytes().lt(b.bytes()).into());
         }
 
         let num_self = prim_self.primitive_as_number(activation, context);
@@ -465,7 +462,7 @@ impl<'gc> Value<'gc> {
         Ok(match self {
             Value::Object(object) => {
                 match object.call_method("toString", &[], activation, context)? {
-                    Value::String(s) => Cow::Owned(s),
+                    Value::String(s) => Cow::Owned(s.to_string()),
                     _ => Cow::Borrowed("[type Object]"),
                 }
             }
@@ -480,7 +477,41 @@ impl<'gc> Value<'gc> {
             Value::Bool(true) => Cow::Borrowed("true"),
             Value::Bool(false) => Cow::Borrowed("false"),
             Value::Number(v) => f64_to_string(*v),
-            Value::String(v) => Cow::Borrowed(v),
+            Value::String(v) => Cow::Borrowed(v.as_str()),
+        })
+    }
+
+    /// Coerce to a string, like `coerce_to_string`, but as a GC string.
+    ///
+    /// String values are returned as they are, without copying them, so this
+    /// should be used for names that end up stored as property keys.
+    pub fn coerce_to_avm_string(
+        &self,
+        activation: &mut Activation<'_, 'gc>,
+        context: &mut UpdateContext<'_, 'gc, '_>,
+    ) -> Result<AvmString<'gc>, Error<'gc>> {
+        Ok(match self {
+            Value::String(v) => *v,
+            Value::Number(v) => match f64_to_string(*v) {
+                Cow::Borrowed(s) => s.into(),
+                Cow::Owned(s) => AvmString::new(context.gc_context, s),
+            },
+            Value::Object(object) => {
+                match object.call_method("toString", &[], activation, context)? {
+                    Value::String(s) => s,
+                    _ => "[type Object]".into(),
+                }
+            }
+            Value::Undefined => {
+                if activation.current_swf_version() >= 7 {
+                    "undefined".into()
+                } else {
+                    "".into()
+                }
+            }
+            Value::Null => "null".into(),
+            Value::Bool(true) => "true".into(),
+            Value::Bool(false) => "false".into(),
         })
     }
 
@@ -539,28 +570,60 @@ impl<'gc> Value<'gc> {
 
 /// Converts an `f64` to a String with (hopefully) the same output as Flash.
 /// For example, NAN returns `"NaN"`, and infinity returns `"Infinity"`.
+///
+/// Flash rounds to 15 significant digits, so `0.1 + 0.2` prints as `0.3`.
+/// Numbers of at least 1e15, or smaller than 1e-5, print in exponential notation.
 pub fn f64_to_string(n: f64) -> Cow<'static, str> {
     if n.is_nan() {
-        Cow::Borrowed("NaN")
+        return Cow::Borrowed("NaN");
     } else if n == std::f64::INFINITY {
-        Cow::Borrowed("Infinity")
+        return Cow::Borrowed("Infinity");
     } else if n == std::f64::NEG_INFINITY {
-        Cow::Borrowed("-Infinity")
-    } else if n != 0.0 && (n.abs() >= 1e15 || n.abs() < 1e-5) {
-        // Exponential notation.
-        // Cheating a bit here; Flash always put a sign in front of the exponent, e.g. 1e+15.
-        // Can't do this with rust format params, so shove it in there manually.
-        let mut s = format!("{:e}", n);
-        if let Some(i) = s.find('e') {
-            if s.as_bytes().get(i + 1) != Some(&b'-') {
-                s.insert(i + 1, '+');
-            }
+        return Cow::Borrowed("-Infinity");
+    } else if n == 0.0 {
+        // Includes -0.
+        return Cow::Borrowed("0");
+    }
+
+    // Round to 15 significant digits first, as rounding may carry into the next power of ten.
+    let formatted = format!("{:.14e}", n.abs());
+    let e = formatted.find('e').unwrap();
+    let exponent: i32 = formatted[e + 1..].parse().unwrap();
+    let digits: String = formatted[..e].chars().filter(|c| *c != '.').collect();
+    let digits = digits.trim_end_matches('0');
+
+    let mut out = String::with_capacity(24);
+    if n < 0.0 {
+        out.push('-');
+    }
+    if !(-5..15).contains(&exponent) {
+        // Exponential notation. Flash always puts a sign in front of the exponent, e.g. 1e+15.
+        out.push_str(&digits[..1]);
+        if digits.len() > 1 {
+            out.push('.');
+            out.push_str(&digits[1..]);
+        }
+        out.push('e');
+        if exponent > 0 {
+            out.push('+');
+        }
+        out.push_str(&exponent.to_string());
+    } else if exponent >= 0 {
+        let int_len = exponent as usize + 1;
+        if digits.len() > int_len {
+            out.push_str(&digits[..int_len]);
+            out.push('.');
+            out.push_str(&digits[int_len..]);
+        } else {
+            out.push_str(digits);
+            out.extend(std::iter::repeat('0').take(int_len - digits.len()));
         }
-        Cow::Owned(s)
     } else {
-        // Normal number.
-        Cow::Owned(n.to_string())
+        out.push_str("0.");
+        out.extend(std::iter::repeat('0').take((-exponent - 1) as usize));
+        out.push_str(digits);
     }
+    Cow::Owned(out)
 }
 
 /// Converts an `f64` to an `u16` with ECMAScript `ToUInt16` wrapping behavior.
@@ -632,7 +695,7 @@ mod test {
             );
             assert_eq!(null.to_primitive_num(activation, context).unwrap(), null);
 
-            let (protos, global, _) = create_globals(context.gc_context);
+            let (protos, global, _, _) = create_globals(context.gc_context);
             let vglobal = Value::Object(global);
 
             assert_eq!(
@@ -800,8 +863,8 @@ mod test {
     #[test]
     fn abstract_lt_str() {
         with_avm(8, |activation, context, _this| -> Result<(), Error> {
-            let a = Value::String("a".to_owned());
-            let b = Value::String("b".to_owned());
+            let a = Value::String("a".into());
+            let b = Value::String("b".into());
 
             assert_eq!(
                 a.abstract_lt(b, activation, context).unwrap(),
@@ -815,8 +878,8 @@ mod test {
     #[test]
     fn abstract_gt_str() {
         with_avm(8, |activation, context, _this| -> Result<(), Error> {
-            let a = Value::String("a".to_owned());
-            let b = Value::String("b".to_owned());
+            let a = Value::String("a".into());
+            let b = Value::String("b".into());
 
             assert_eq!(
                 b.abstract_lt(a, activation, context).unwrap(),
@@ -915,4 +978,30 @@ mod test {
         assert_eq!(f64_to_string(0.999e-5), "9.99e-6");
         assert_eq!(f64_to_string(-0.999e-5), "-9.99e-6");
     }
+
+    #[test]
+    fn f64_to_string_significant_digits() {
+        use super::f64_to_string;
+        let cases: &[(f64, &str)] = &[
+            (0.1 + 0.2, "0.3"),
+            (1.0 / 3.0, "0.333333333333333"),
+            (-2.0 / 3.0, "-0.666666666666667"),
+            (123.456, "123.456"),
+            (100.0, "100"),
+            (0.000123, "0.000123"),
+            (123456789012345.0, "123456789012345"),
+            (999999999999999.0, "999999999999999"),
+            (999999999999999.9, "1e+15"),
+            (123456789012345680.0, "1.23456789012346e+17"),
+            (1e21, "1e+21"),
+            (1.5e300, "1.5e+300"),
+            (std::f64::MAX, "1.79769313486232e+308"),
+            (5e-324, "4.94065645841247e-324"),
+            (1.23e-7, "1.23e-7"),
+            (4294967295.0, "4294967295"),
+        ];
+        for &(n, expected) in cases {
+            assert_eq!(f64_to_string(n), expected, "{:?}", n);
+        }
+    }
 }
@@ -0,0 +1,296 @@
+//! ActionScript Virtual Machine 2 (AS3) support
+
+use crate::avm2::activation::Activation;
+use crate::avm2::event_object::EventObject;
+use crate::avm2::events::{dispatch_event_to_target, Event};
+use crate::avm2::function::Executable;
+use crate::avm2::globals::{load_player_globals, SystemPrototypes};
+use crate::avm2::names::QName;
+use crate::avm2::object::TObject;
+use crate::avm2::scope::Scope;
+use crate::avm2::script::{Script, TranslationUnit};
+use crate::avm2::stage_object::StageObject;
+use crate::context::UpdateContext;
+use crate::display_object::{DisplayObject, TDisplayObject};
+use fnv::FnvHashMap;
+use gc_arena::{Collect, MutationContext};
+use std::rc::Rc;
+use swf::avm2::read::Reader;
+
+mod activation;
+mod array_object;
+mod class;
+mod class_object;
+mod dispatch_object;
+mod error;
+mod event_object;
+mod events;
+mod function;
+mod globals;
+mod input;
+mod method;
+mod names;
+mod object;
+mod primitive_object;
+mod property;
+mod scope;
+mod script;
+mod script_object;
+mod stage_object;
+mod value;
+
+pub use crate::avm1::AvmString;
+pub use crate::avm2::error::Error;
+pub use crate::avm2::input::handle_input_event;
+pub use crate::avm2::object::Object;
+pub use crate::avm2::value::Value;
+
+/// The state of an AVM2 interpreter.
+#[derive(Collect)]
+#[collect(no_drop)]
+pub struct Avm2<'gc> {
+    /// The global object.
+    globals: Object<'gc>,
+
+    /// System prototypes.
+    system_prototypes: SystemPrototypes<'gc>,
+
+    /// The script that defines each name on the global object.
+    ///
+    /// Looking a definition up runs its script's initializer, if it hasn't
+    /// run yet.
+    definitions: FnvHashMap<QName<'gc>, Script<'gc>>,
+
+    /// The `Stage` object, whose children are the levels of the player.
+    stage: Object<'gc>,
+
+    /// The objects listening to each event that is broadcast to every
+    /// listener, rather than dispatched on a single target, such as
+    /// `enterFrame`.
+    broadcast_list: FnvHashMap<AvmString<'gc>, Vec<Object<'gc>>>,
+
+    /// The interactive object under the mouse, which mouse events are
+    /// targeted at, or `None` for the stage.
+    mouse_target: Option<DisplayObject<'gc>>,
+
+    /// The object the mouse button was pressed over, which is clicked if
+    /// the button is released over it too.
+    mouse_down_target: Option<DisplayObject<'gc>>,
+
+    /// The object last clicked, and when, to detect double clicks.
+    last_click_target: Option<DisplayObject<'gc>>,
+    last_click_time: u64,
+}
+
+impl<'gc> Avm2<'gc> {
+    /// Construct a new AVM interpreter.
+    pub fn new(mc: MutationContext<'gc, '_>) -> Self {
+        let (globals, system_prototypes) = load_player_globals(mc);
+        let stage = StageObject::unbound(mc, system_prototypes.stage);
+
+        Self {
+            globals,
+            system_prototypes,
+            definitions: FnvHashMap::default(),
+            stage,
+            broadcast_list: FnvHashMap::default(),
+            mouse_target: None,
+            mouse_down_target: None,
+            last_click_target: None,
+            last_click_time: 0,
+        }
+    }
+
+    /// Return the current set of system prototypes.
+    pub fn prototypes(&self) -> SystemPrototypes<'gc> {
+        self.system_prototypes
+    }
+
+    /// Return the global object.
+    pub fn globals(&self) -> Object<'gc> {
+        self.globals
+    }
+
+    /// Whether any ABC code has been loaded.
+    pub fn has_scripts(&self) -> bool {
+        !self.definitions.is_empty()
+    }
+
+    /// Return the `Stage` object.
+    pub fn stage(&self) -> Object<'gc> {
+        self.stage
+    }
+
+    /// Register an object to receive a broadcast event, such as
+    /// `enterFrame`.
+    ///
+    /// Objects are only registered once for each event.
+    pub fn register_broadcast_listener(
+        context: &mut UpdateContext<'_, 'gc, '_>,
+        object: Object<'gc>,
+        event_type: AvmString<'gc>,
+    ) {
+        let listeners = context.avm2.broadcast_list.entry(event_type).or_default();
+        if !listeners.iter().any(|l| Object::ptr_eq(*l, object)) {
+            listeners.push(object);
+        }
+    }
+
+    /// Send a broadcast event, such as `enterFrame`, to every object that
+    /// has registered to receive it.
+    ///
+    /// Each object receives its own event, targeted at itself. Errors thrown
+    /// by listeners are logged, and don't stop the broadcast.
+    pub fn broadcast_event(context: &mut UpdateContext<'_, 'gc, '_>, event_type: &'static str) {
+        let key: AvmString<'gc> = event_type.into();
+        let listeners = context
+            .avm2
+            .broadcast_list
+            .get(&key)
+            .cloned()
+            .unwrap_or_default();
+        let event_proto = context.avm2.prototypes().event;
+
+        for object in listeners {
+            let event =
+                EventObject::from_event(context.gc_context, event_proto, Event::new(event_type));
+            event.event_mut(context.gc_context).set_target(object);
+            if let Err(e) =
+                dispatch_event_to_target(&mut Activation::from_nothing(), context, object, event)
+            {
+                log::error!("Unhandled AVM2 exception in {} handler: {}", event_type, e);
+            }
+        }
+    }
+
+    /// Load an ABC file embedded in a `DoAbc` tag.
+    ///
+    /// Every script in the file defines its traits on the global object.
+    /// Unless the tag asks for lazy initialization, the last script, which
+    /// is the file's entry point, is run immediately; the others run the
+    /// first time one of their definitions is looked up.
+    pub fn load_abc(
+        abc: &[u8],
+        lazy_init: bool,
+        context: &mut UpdateContext<'_, 'gc, '_>,
+    ) -> Result<(), Error<'gc>> {
+        let mut reader = Reader::new(abc);
+        let abc_file = Rc::new(reader.read()?);
+        let translation_unit = TranslationUnit::from_abc(abc_file.clone(), context.gc_context);
+        let globals = context.avm2.globals();
+        let scope = Scope::push_scope(None, globals, context.gc_context);
+
+        let mut last_script = None;
+        for script_index in 0..abc_file.scripts.len() as u32 {
+            let script = translation_unit.load_script(script_index, globals, context.gc_context)?;
+            for script_trait in script.traits() {
+                globals.install_trait(context, &script_trait, Some(scope), None)?;
+                context
+                    .avm2
+                    .definitions
+                    .insert(*script_trait.name(), script);
+            }
+
+            last_script = Some(script);
+        }
+
+        if let Some(script) = last_script.filter(|_| !lazy_init) {
+            Self::run_script_initializer(script, &mut Activation::from_nothing(), context)?;
+        }
+
+        Ok(())
+    }
+
+    /// Run the initializer of the script that defines a name on the global
+    /// object, unless it has already started running.
+    pub fn ensure_script_initialized(
+        name: &QName<'gc>,
+        activation: &mut Activation<'gc>,
+        context: &mut UpdateContext<'_, 'gc, '_>,
+    ) -> Result<(), Error<'gc>> {
+        match context.avm2.definitions.get(name).copied() {
+            Some(script) => Self::run_script_initializer(script, activation, context),
+            None => Ok(()),
+        }
+    }
+
+    fn run_script_initializer(
+        script: Script<'gc>,
+        activation: &mut Activation<'gc>,
+        context: &mut UpdateContext<'_, 'gc, '_>,
+    ) -> Result<(), Error<'gc>> {
+        if !script.begin_initialization(context.gc_context) {
+            return Ok(());
+        }
+
+        let globals = script.globals();
+        let scope = Scope::push_scope(None, globals, context.gc_context);
+        Executable::from_method(
+            script.init(),
+            Some(scope),
+            Some(globals),
+            None,
+            context.gc_context,
+        )
+        .exec(None, &[], activation, context)?;
+
+        Ok(())
+    }
+
+    /// Call a function from outside of any running script, such as a frame
+    /// script queued by a movie clip.
+    pub fn run_stack_frame_for_callable(
+        callable: Object<'gc>,
+        receiver: Option<Object<'gc>>,
+        args: &[Value<'gc>],
+        context: &mut UpdateContext<'_, 'gc, '_>,
+    ) -> Result<(), Error<'gc>> {
+        callable.call(receiver, args, &mut Activation::from_nothing(), context)?;
+
+        Ok(())
+    }
+
+    /// Construct an instance of the class named by a `SymbolClass` tag to
+    /// represent an existing display object, such as the document class of
+    /// the root movie.
+    ///
+    /// The instance is bound to the display object before its constructor
+    /// runs, so that the constructor can already use it as one.
+    pub fn construct_display_object(
+        class_name: &str,
+        mut display_object: DisplayObject<'gc>,
+        context: &mut UpdateContext<'_, 'gc, '_>,
+    ) -> Result<Object<'gc>, Error<'gc>> {
+        let mut activation = Activation::from_nothing();
+        let name = QName::from_qualified_name(class_name, context.gc_context);
+        Self::ensure_script_initialized(&name, &mut activation, context)?;
+
+        let globals = context.avm2.globals();
+        let class_object = globals
+            .get_property(globals, &name, &mut activation, context)?
+            .as_object()
+            .ok()
+            .and_then(|class_object| class_object.as_class_object())
+            .ok_or_else(|| Error::TypeError(format!("{} is not a class", class_name)))?;
+
+        let prototype = class_object.prototype();
+        let instance = match class_object.instance_allocator() {
+            Some(allocator) => allocator(prototype, &mut activation, context)?,
+            None => {
+                return Err(Error::TypeError(format!(
+                    "{} is not a display object class",
+                    class_name
+                )))
+            }
+        };
+        if let Object::StageObject(stage_object) = instance {
+            stage_object.set_display_object(context.gc_context, display_object);
+        }
+        display_object.set_object2(context.gc_context, instance);
+
+        class_object.install_instance_traits(instance, context)?;
+        class_object.call_init(instance, &[], &mut activation, context)?;
+
+        Ok(instance)
+    }
+}
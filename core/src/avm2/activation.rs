@@ -0,0 +1,1729 @@
+//! Activation frames
+
+use crate::avm1::value::{f64_to_wrapping_i32, f64_to_wrapping_u32};
+use crate::avm2::array_object::ArrayObject;
+use crate::avm2::class::Trait;
+use crate::avm2::class_object::ClassObject;
+use crate::avm2::function::{Executable, FunctionObject};
+use crate::avm2::method::BytecodeMethod;
+use crate::avm2::names::{Multiname, Namespace, QName};
+use crate::avm2::object::{Object, TObject};
+use crate::avm2::primitive_object::PrimitiveObject;
+use crate::avm2::scope::Scope;
+use crate::avm2::script::TranslationUnit;
+use crate::avm2::script_object::ScriptObject;
+use crate::avm2::value::{abc_default_value, Hint, Value};
+use crate::avm2::{Avm2, AvmString, Error};
+use crate::context::UpdateContext;
+use gc_arena::{Gc, GcCell};
+use std::io::Cursor;
+use swf::avm2::read::Reader;
+use swf::avm2::types::{
+    Class as AbcClass, Exception as AbcException, Index, Method as AbcMethod,
+    Multiname as AbcMultiname, Namespace as AbcNamespace, Op,
+};
+
+macro_rules! avm_debug {
+    ($($arg:tt)*) => (
+        #[cfg(feature = "avm_debug")]
+        log::debug!($($arg)*)
+    )
+}
+
+/// What the interpreter should do after executing an instruction.
+enum FrameControl<'gc> {
+    Continue,
+    Return(Value<'gc>),
+}
+
+/// The code reader of a method body.
+type CodeReader<'a> = Reader<Cursor<&'a [u8]>>;
+
+/// Move the reader by an offset relative to the end of the current
+/// instruction.
+fn jump(reader: &mut CodeReader<'_>, offset: i32) {
+    let position = reader.get_ref().position() as i64 + i64::from(offset);
+    reader.get_mut().set_position(position as u64);
+}
+
+/// The error thrown when a name can't be found on the scope chain.
+fn not_defined<'gc>(name: &Multiname<'gc>) -> Error<'gc> {
+    Error::ReferenceError(format!(
+        "Error #1065: Variable {} is not defined.",
+        name.local_name().unwrap_or_else(|| "*".into())
+    ))
+}
+
+/// Represents a single activation of a given AVM2 function or script.
+///
+/// An activation holds the local registers, operand stack and scope chain of
+/// one call to a method. Native methods are given the activation of their
+/// caller, which they can use to coerce values.
+pub struct Activation<'gc> {
+    /// The local registers of this method, starting with `this` and the
+    /// method's arguments.
+    local_registers: Vec<Value<'gc>>,
+
+    /// The operand stack of this method.
+    stack: Vec<Value<'gc>>,
+
+    /// The scope chain that this method closed over when it was created.
+    ///
+    /// Execution returns to this chain when an exception is caught.
+    outer_scope: Option<GcCell<'gc, Scope<'gc>>>,
+
+    /// The current scope chain, including the scopes pushed by this method.
+    scope: Option<GcCell<'gc, Scope<'gc>>>,
+
+    /// How many scopes this method has pushed onto the chain.
+    local_scope_depth: usize,
+
+    /// The superclass of the class that declared this method, which `super`
+    /// refers to.
+    superclass: Option<Object<'gc>>,
+}
+
+impl<'gc> Activation<'gc> {
+    /// Construct an activation that doesn't represent any particular method.
+    ///
+    /// This is used to call into AVM2 code from outside of it, and to give
+    /// native code a place to coerce values.
+    pub fn from_nothing() -> Self {
+        Self {
+            local_registers: Vec::new(),
+            stack: Vec::new(),
+            outer_scope: None,
+            scope: None,
+            local_scope_depth: 0,
+            superclass: None,
+        }
+    }
+
+    /// Construct an activation for a call to a method.
+    ///
+    /// Arguments are coerced to the types the method declares, using the
+    /// activation of the caller, and stored into the local registers along
+    /// with `this`.
+    pub fn from_method(
+        method: Gc<'gc, BytecodeMethod<'gc>>,
+        scope: Option<GcCell<'gc, Scope<'gc>>>,
+        this: Object<'gc>,
+        arguments: &[Value<'gc>],
+        superclass: Option<Object<'gc>>,
+        caller: &mut Activation<'gc>,
+        context: &mut UpdateContext<'_, 'gc, '_>,
+    ) -> Result<Self, Error<'gc>> {
+        let body = method
+            .body()
+            .ok_or_else(|| Error::TypeError("Cannot call a method that has no body".to_string()))?;
+        let abc_method: &AbcMethod = method.method();
+        let translation_unit = method.translation_unit();
+
+        let mut local_registers = Vec::with_capacity(body.num_locals as usize);
+        local_registers.push(Value::Object(this));
+
+        for (i, param) in abc_method.params.iter().enumerate() {
+            let argument = match (arguments.get(i), &param.default_value) {
+                (Some(argument), _) => argument.clone(),
+                (None, Some(default)) => {
+                    abc_default_value(translation_unit, default, context.gc_context)?
+                }
+                (None, None) => Value::Undefined,
+            };
+            let param_type = Multiname::from_abc_multiname_static(
+                translation_unit,
+                param.kind.clone(),
+                context.gc_context,
+            )?;
+
+            local_registers.push(argument.coerce_to_type(&param_type, caller, context)?);
+        }
+
+        let array_proto = context.avm2.prototypes().array;
+        if abc_method.needs_rest {
+            let rest = arguments
+                .get(abc_method.params.len()..)
+                .unwrap_or(&[])
+                .to_vec();
+            local_registers
+                .push(ArrayObject::from_elements(context.gc_context, array_proto, rest).into());
+        } else if abc_method.needs_arguments_object {
+            local_registers.push(
+                ArrayObject::from_elements(context.gc_context, array_proto, arguments.to_vec())
+                    .into(),
+            );
+        }
+
+        if local_registers.len() < body.num_locals as usize {
+            local_registers.resize(body.num_locals as usize, Value::Undefined);
+        }
+
+        Ok(Self {
+            local_registers,
+            stack: Vec::new(),
+            outer_scope: scope,
+            scope,
+            local_scope_depth: 0,
+            superclass,
+        })
+    }
+
+    /// Push a value onto the operand stack.
+    pub fn push(&mut self, value: impl Into<Value<'gc>>) {
+        let value = value.into();
+        avm_debug!("Stack push {}: {:?}", self.stack.len(), value);
+        self.stack.push(value);
+    }
+
+    /// Retrieve the top-most value on the operand stack.
+    #[allow(clippy::let_and_return)]
+    pub fn pop(&mut self) -> Value<'gc> {
+        let value = self.stack.pop().unwrap_or_else(|| {
+            log::warn!("Avm2::pop: Stack underflow");
+            Value::Undefined
+        });
+
+        avm_debug!("Stack pop {}: {:?}", self.stack.len(), value);
+
+        value
+    }
+
+    /// Pop a number of arguments off the stack, in the order they were
+    /// pushed.
+    ///
+    /// The count comes from the bytecode, so it is checked against the
+    /// stack before anything is allocated for it.
+    fn pop_args(&mut self, num_args: u32) -> Result<Vec<Value<'gc>>, Error<'gc>> {
+        let num_args = num_args as usize;
+        if num_args > self.stack.len() {
+            return Err(Error::VerifyError(
+                "Error #1024: Stack underflow occurred.".to_string(),
+            ));
+        }
+
+        let args = self.stack.split_off(self.stack.len() - num_args);
+        avm_debug!("Stack pop {}: {:?}", self.stack.len(), args);
+
+        Ok(args)
+    }
+
+    fn local_register(&self, id: u32) -> Result<Value<'gc>, Error<'gc>> {
+        self.local_registers
+            .get(id as usize)
+            .cloned()
+            .ok_or_else(|| Error::RangeError(format!("Out of bounds register read: {}", id)))
+    }
+
+    fn set_local_register(&mut self, id: u32, value: Value<'gc>) -> Result<(), Error<'gc>> {
+        match self.local_registers.get_mut(id as usize) {
+            Some(register) => {
+                *register = value;
+                Ok(())
+            }
+            None => Err(Error::RangeError(format!(
+                "Out of bounds register write: {}",
+                id
+            ))),
+        }
+    }
+
+    /// Run the code of a method in this activation, returning the value it
+    /// returns.
+    pub fn run_actions(
+        &mut self,
+        method: Gc<'gc, BytecodeMethod<'gc>>,
+        context: &mut UpdateContext<'_, 'gc, '_>,
+    ) -> Result<Value<'gc>, Error<'gc>> {
+        let body = method
+            .body()
+            .ok_or_else(|| Error::TypeError("Cannot call a method that has no body".to_string()))?;
+        let mut reader = Reader::new(Cursor::new(body.code.as_slice()));
+
+        loop {
+            let op_start = reader.get_ref().position();
+            if op_start >= body.code.len() as u64 {
+                // Running off the end of the method returns `undefined`.
+                return Ok(Value::Undefined);
+            }
+
+            let op = match reader.read_op()? {
+                Some(op) => op,
+                None => return Ok(Value::Undefined),
+            };
+            avm_debug!("Opcode: {:?}", op);
+
+            match self.do_op(method, &mut reader, op, op_start, context) {
+                Ok(FrameControl::Continue) => {}
+                Ok(FrameControl::Return(value)) => return Ok(value),
+                Err(Error::ThrownValue(value)) => {
+                    let handler = self.find_exception_handler(
+                        method,
+                        &body.exceptions,
+                        op_start,
+                        &value,
+                        context,
+                    )?;
+                    match handler {
+                        Some(target) => {
+                            self.stack.clear();
+                            self.scope = self.outer_scope;
+                            self.local_scope_depth = 0;
+                            self.push(value);
+                            reader.get_mut().set_position(u64::from(target));
+                        }
+                        None => return Err(Error::ThrownValue(value)),
+                    }
+                }
+                Err(e) => return Err(e),
+            }
+        }
+    }
+
+    /// Find the target of the innermost exception handler that catches a
+    /// value thrown at the given position.
+    fn find_exception_handler(
+        &mut self,
+        method: Gc<'gc, BytecodeMethod<'gc>>,
+        exceptions: &[AbcException],
+        position: u64,
+        thrown: &Value<'gc>,
+        context: &mut UpdateContext<'_, 'gc, '_>,
+    ) -> Result<Option<u32>, Error<'gc>> {
+        for exception in exceptions {
+            if position < u64::from(exception.from_offset)
+                || position >= u64::from(exception.to_offset)
+            {
+                continue;
+            }
+
+            if exception.type_name.0 == 0 {
+                return Ok(Some(exception.target_offset));
+            }
+
+            let type_name = Multiname::from_abc_multiname_static(
+                method.translation_unit(),
+                exception.type_name.clone(),
+                context.gc_context,
+            )?;
+            let class = self.resolve_type(&type_name, context)?;
+            if self.is_type(thrown, class, context)? {
+                return Ok(Some(exception.target_offset));
+            }
+        }
+
+        Ok(None)
+    }
+
+    fn do_op(
+        &mut self,
+        method: Gc<'gc, BytecodeMethod<'gc>>,
+        reader: &mut CodeReader<'_>,
+        op: Op,
+        op_start: u64,
+        context: &mut UpdateContext<'_, 'gc, '_>,
+    ) -> Result<FrameControl<'gc>, Error<'gc>> {
+        let translation_unit = method.translation_unit();
+
+        match op {
+            Op::PushByte { value } => self.op_push(Value::Integer(value as i8 as i32)),
+            Op::PushShort { value } => {
+                // The value is a sign-extended 30-bit integer.
+                self.op_push(Value::Integer(((value << 2) as i32) >> 2))
+            }
+            Op::PushInt { value } => self.op_push(translation_unit.pool_int(value.0)?.into()),
+            Op::PushUint { value } => self.op_push(translation_unit.pool_uint(value.0)?.into()),
+            Op::PushDouble { value } => self.op_push(translation_unit.pool_double(value.0)?.into()),
+            Op::PushString { value } => self.op_push(
+                translation_unit
+                    .pool_string(value.0, context.gc_context)?
+                    .into(),
+            ),
+            Op::PushNamespace { value } => self.op_push_namespace(translation_unit, value, context),
+            Op::PushTrue => self.op_push(true.into()),
+            Op::PushFalse => self.op_push(false.into()),
+            Op::PushNull => self.op_push(Value::Null),
+            Op::PushUndefined => self.op_push(Value::Undefined),
+            Op::PushNaN => self.op_push(std::f64::NAN.into()),
+            Op::Pop => self.op_pop(),
+            Op::Dup => self.op_dup(),
+            Op::Swap => self.op_swap(),
+            Op::GetLocal { index } => self.op_get_local(index),
+            Op::SetLocal { index } => self.op_set_local(index),
+            Op::Kill { index } => self.op_kill(index),
+            Op::IncLocal { index } => self.op_inc_local(index, 1.0, context),
+            Op::DecLocal { index } => self.op_inc_local(index, -1.0, context),
+            Op::IncLocalI { index } => self.op_inc_local_i(index, 1, context),
+            Op::DecLocalI { index } => self.op_inc_local_i(index, -1, context),
+            Op::PushScope | Op::PushWith => self.op_push_scope(context),
+            Op::PopScope => self.op_pop_scope(),
+            Op::GetScopeObject { index } => self.op_get_scope_object(index),
+            Op::GetGlobalScope => self.op_get_global_scope(context),
+            Op::GetGlobalSlot { index } => self.op_get_global_slot(index, context),
+            Op::SetGlobalSlot { index } => self.op_set_global_slot(index, context),
+            Op::GetSlot { index } => self.op_get_slot(index),
+            Op::SetSlot { index } => self.op_set_slot(index, context),
+            Op::FindProperty { index } => self.op_find_property(translation_unit, index, context),
+            Op::FindPropStrict { index } => {
+                self.op_find_prop_strict(translation_unit, index, context)
+            }
+            Op::GetLex { index } => self.op_get_lex(translation_unit, index, context),
+            Op::GetProperty { index } => self.op_get_property(translation_unit, index, context),
+            Op::SetProperty { index } => self.op_set_property(translation_unit, index, context),
+            Op::InitProperty { index } => self.op_init_property(translation_unit, index, context),
+            Op::DeleteProperty { index } => {
+                self.op_delete_property(translation_unit, index, context)
+            }
+            Op::GetSuper { index } => self.op_get_super(translation_unit, index, context),
+            Op::SetSuper { index } => self.op_set_super(translation_unit, index, context),
+            Op::In => self.op_in(context),
+            Op::Call { num_args } => self.op_call(num_args, context),
+            Op::CallProperty { index, num_args } => {
+                self.op_call_property(translation_unit, index, num_args, false, context)
+            }
+            Op::CallPropLex { index, num_args } => {
+                self.op_call_property(translation_unit, index, num_args, true, context)
+            }
+            Op::CallPropVoid { index, num_args } => {
+                self.op_call_property(translation_unit, index, num_args, false, context)?;
+                self.pop();
+                Ok(FrameControl::Continue)
+            }
+            Op::CallStatic { index, num_args } => {
+                self.op_call_static(translation_unit, index, num_args, context)
+            }
+            Op::CallSuper { index, num_args } => {
+                self.op_call_super(translation_unit, index, num_args, context)
+            }
+            Op::CallSuperVoid { index, num_args } => {
+                self.op_call_super(translation_unit, index, num_args, context)?;
+                self.pop();
+                Ok(FrameControl::Continue)
+            }
+            Op::Construct { num_args } => self.op_construct(num_args, context),
+            Op::ConstructProp { index, num_args } => {
+                self.op_construct_prop(translation_unit, index, num_args, context)
+            }
+            Op::ConstructSuper { num_args } => self.op_construct_super(num_args, context),
+            Op::NewObject { num_args } => self.op_new_object(num_args, context),
+            Op::NewArray { num_args } => self.op_new_array(num_args, context),
+            Op::NewFunction { index } => self.op_new_function(translation_unit, index, context),
+            Op::NewClass { index } => self.op_new_class(translation_unit, index, context),
+            Op::NewActivation => self.op_new_activation(method, context),
+            Op::NewCatch { index } => self.op_new_catch(method, index, context),
+            Op::Coerce { index } => self.op_coerce(translation_unit, index, context),
+            Op::CoerceA => Ok(FrameControl::Continue),
+            Op::CoerceS => self.op_coerce_s(context),
+            Op::ConvertB => self.op_convert_b(),
+            Op::ConvertD => self.op_convert_d(context),
+            Op::ConvertI => self.op_convert_i(context),
+            Op::ConvertU => self.op_convert_u(context),
+            Op::ConvertS => self.op_convert_s(context),
+            Op::ConvertO => self.op_convert_o(),
+            Op::IsType { index } => self.op_is_type(translation_unit, index, context),
+            Op::IsTypeLate => self.op_is_type_late(context),
+            Op::AsType { type_name } => self.op_as_type(translation_unit, type_name, context),
+            Op::AsTypeLate => self.op_as_type_late(context),
+            Op::InstanceOf => self.op_instance_of(context),
+            Op::TypeOf => self.op_type_of(),
+            Op::Add => self.op_add(context),
+            Op::AddI => self.op_int_binary(context, i32::wrapping_add),
+            Op::Subtract => self.op_number_binary(context, |a, b| a - b),
+            Op::SubtractI => self.op_int_binary(context, i32::wrapping_sub),
+            Op::Multiply => self.op_number_binary(context, |a, b| a * b),
+            Op::MultiplyI => self.op_int_binary(context, i32::wrapping_mul),
+            Op::Divide => self.op_number_binary(context, |a, b| a / b),
+            Op::Modulo => self.op_number_binary(context, |a, b| a % b),
+            Op::Negate => self.op_number_unary(context, |a| -a),
+            Op::NegateI => self.op_int_unary(context, i32::wrapping_neg),
+            Op::Increment => self.op_number_unary(context, |a| a + 1.0),
+            Op::IncrementI => self.op_int_unary(context, |a| a.wrapping_add(1)),
+            Op::Decrement => self.op_number_unary(context, |a| a - 1.0),
+            Op::DecrementI => self.op_int_unary(context, |a| a.wrapping_sub(1)),
+            Op::BitAnd => self.op_int_binary(context, |a, b| a & b),
+            Op::BitOr => self.op_int_binary(context, |a, b| a | b),
+            Op::BitXor => self.op_int_binary(context, |a, b| a ^ b),
+            Op::BitNot => self.op_int_unary(context, |a| !a),
+            Op::LShift => self.op_int_binary(context, |a, b| a.wrapping_shl(b as u32 & 0x1F)),
+            Op::RShift => self.op_int_binary(context, |a, b| a.wrapping_shr(b as u32 & 0x1F)),
+            Op::URShift => self.op_urshift(context),
+            Op::Not => self.op_not(),
+            Op::Equals => self.op_equals(context),
+            Op::StrictEquals => self.op_strict_equals(),
+            Op::LessThan => self.op_less_than(context),
+            Op::LessEquals => self.op_less_equals(context),
+            Op::GreaterThan => self.op_greater_than(context),
+            Op::GreaterEquals => self.op_greater_equals(context),
+            Op::Jump { offset } => {
+                jump(reader, offset);
+                Ok(FrameControl::Continue)
+            }
+            Op::IfTrue { offset } => {
+                let value = self.pop();
+                self.op_branch(reader, offset, value.coerce_to_boolean())
+            }
+            Op::IfFalse { offset } => {
+                let value = self.pop();
+                self.op_branch(reader, offset, !value.coerce_to_boolean())
+            }
+            Op::IfEq { offset } => {
+                let (value1, value2) = self.pop_pair();
+                let condition = value1.abstract_eq(&value2, self, context)?;
+                self.op_branch(reader, offset, condition)
+            }
+            Op::IfNe { offset } => {
+                let (value1, value2) = self.pop_pair();
+                let condition = !value1.abstract_eq(&value2, self, context)?;
+                self.op_branch(reader, offset, condition)
+            }
+            Op::IfStrictEq { offset } => {
+                let (value1, value2) = self.pop_pair();
+                self.op_branch(reader, offset, value1.strict_eq(&value2))
+            }
+            Op::IfStrictNe { offset } => {
+                let (value1, value2) = self.pop_pair();
+                self.op_branch(reader, offset, !value1.strict_eq(&value2))
+            }
+            Op::IfLt { offset } => {
+                let (value1, value2) = self.pop_pair();
+                let condition = value1.abstract_lt(&value2, self, context)? == Some(true);
+                self.op_branch(reader, offset, condition)
+            }
+            Op::IfNlt { offset } => {
+                let (value1, value2) = self.pop_pair();
+                let condition = value1.abstract_lt(&value2, self, context)? != Some(true);
+                self.op_branch(reader, offset, condition)
+            }
+            Op::IfLe { offset } => {
+                let (value1, value2) = self.pop_pair();
+                let condition = value2.abstract_lt(&value1, self, context)? == Some(false);
+                self.op_branch(reader, offset, condition)
+            }
+            Op::IfNle { offset } => {
+                let (value1, value2) = self.pop_pair();
+                let condition = value2.abstract_lt(&value1, self, context)? != Some(false);
+                self.op_branch(reader, offset, condition)
+            }
+            Op::IfGt { offset } => {
+                let (value1, value2) = self.pop_pair();
+                let condition = value2.abstract_lt(&value1, self, context)? == Some(true);
+                self.op_branch(reader, offset, condition)
+            }
+            Op::IfNgt { offset } => {
+                let (value1, value2) = self.pop_pair();
+                let condition = value2.abstract_lt(&value1, self, context)? != Some(true);
+                self.op_branch(reader, offset, condition)
+            }
+            Op::IfGe { offset } => {
+                let (value1, value2) = self.pop_pair();
+                let condition = value1.abstract_lt(&value2, self, context)? == Some(false);
+                self.op_branch(reader, offset, condition)
+            }
+            Op::IfNge { offset } => {
+                let (value1, value2) = self.pop_pair();
+                let condition = value1.abstract_lt(&value2, self, context)? != Some(false);
+                self.op_branch(reader, offset, condition)
+            }
+            Op::LookupSwitch {
+                default_offset,
+                case_offsets,
+            } => self.op_lookup_switch(reader, op_start, default_offset, &case_offsets, context),
+            Op::HasNext => self.op_has_next(context),
+            Op::HasNext2 {
+                object_register,
+                index_register,
+            } => self.op_has_next_2(object_register, index_register, context),
+            Op::NextName => self.op_next_name(context),
+            Op::NextValue => self.op_next_value(context),
+            Op::ReturnValue => Ok(FrameControl::Return(self.pop())),
+            Op::ReturnVoid => Ok(FrameControl::Return(Value::Undefined)),
+            Op::Throw => Err(Error::ThrownValue(self.pop())),
+            Op::Label
+            | Op::Nop
+            | Op::Debug { .. }
+            | Op::DebugFile { .. }
+            | Op::DebugLine { .. } => Ok(FrameControl::Continue),
+            Op::CallMethod { .. } => Err(Error::Unimplemented("callmethod")),
+            Op::CheckFilter
+            | Op::Dxns { .. }
+            | Op::DxnsLate
+            | Op::EscXAttr
+            | Op::EscXElem
+            | Op::GetDescendants { .. } => Err(Error::Unimplemented("E4X")),
+        }
+    }
+
+    fn op_push(&mut self, value: Value<'gc>) -> Result<FrameControl<'gc>, Error<'gc>> {
+        self.push(value);
+        Ok(FrameControl::Continue)
+    }
+
+    fn op_push_namespace(
+        &mut self,
+        translation_unit: TranslationUnit<'gc>,
+        value: Index<AbcNamespace>,
+        context: &mut UpdateContext<'_, 'gc, '_>,
+    ) -> Result<FrameControl<'gc>, Error<'gc>> {
+        let ns = Namespace::from_abc_namespace(translation_unit, value, context.gc_context)?;
+        self.push(ns);
+        Ok(FrameControl::Continue)
+    }
+
+    fn op_pop(&mut self) -> Result<FrameControl<'gc>, Error<'gc>> {
+        self.pop();
+        Ok(FrameControl::Continue)
+    }
+
+    fn op_dup(&mut self) -> Result<FrameControl<'gc>, Error<'gc>> {
+        let value = self.stack.last().cloned().unwrap_or(Value::Undefined);
+        self.push(value);
+        Ok(FrameControl::Continue)
+    }
+
+    fn op_swap(&mut self) -> Result<FrameControl<'gc>, Error<'gc>> {
+        let (value1, value2) = self.pop_pair();
+        self.push(value2);
+        self.push(value1);
+        Ok(FrameControl::Continue)
+    }
+
+    /// Pop the two operands of a binary operation, in the order they were
+    /// pushed.
+    fn pop_pair(&mut self) -> (Value<'gc>, Value<'gc>) {
+        let value2 = self.pop();
+        let value1 = self.pop();
+        (value1, value2)
+    }
+
+    fn op_get_local(&mut self, index: u32) -> Result<FrameControl<'gc>, Error<'gc>> {
+        let value = self.local_register(index)?;
+        self.push(value);
+        Ok(FrameControl::Continue)
+    }
+
+    fn op_set_local(&mut self, index: u32) -> Result<FrameControl<'gc>, Error<'gc>> {
+        let value = self.pop();
+        self.set_local_register(index, value)?;
+        Ok(FrameControl::Continue)
+    }
+
+    fn op_kill(&mut self, index: u32) -> Result<FrameControl<'gc>, Error<'gc>> {
+        self.set_local_register(index, Value::Undefined)?;
+        Ok(FrameControl::Continue)
+    }
+
+    fn op_inc_local(
+        &mut self,
+        index: u32,
+        amount: f64,
+        context: &mut UpdateContext<'_, 'gc, '_>,
+    ) -> Result<FrameControl<'gc>, Error<'gc>> {
+        let value = self
+            .local_register(index)?
+            .coerce_to_number(self, context)?;
+        self.set_local_register(index, Value::Number(value + amount))?;
+        Ok(FrameControl::Continue)
+    }
+
+    fn op_inc_local_i(
+        &mut self,
+        index: u32,
+        amount: i32,
+        context: &mut UpdateContext<'_, 'gc, '_>,
+    ) -> Result<FrameControl<'gc>, Error<'gc>> {
+        let value = self.local_register(index)?.coerce_to_i32(self, context)?;
+        self.set_local_register(index, Value::Integer(value.wrapping_add(amount)))?;
+        Ok(FrameControl::Continue)
+    }
+
+    fn op_push_scope(
+        &mut self,
+        context: &mut UpdateContext<'_, 'gc, '_>,
+    ) -> Result<FrameControl<'gc>, Error<'gc>> {
+        let object = self.pop().as_object()?;
+        self.scope = Some(Scope::push_scope(self.scope, object, context.gc_context));
+        self.local_scope_depth += 1;
+        Ok(FrameControl::Continue)
+    }
+
+    fn op_pop_scope(&mut self) -> Result<FrameControl<'gc>, Error<'gc>> {
+        if self.local_scope_depth == 0 {
+            return Err(Error::RangeError("Scope stack underflow".to_string()));
+        }
+
+        self.scope = self.scope.and_then(|scope| scope.read().parent());
+        self.local_scope_depth -= 1;
+        Ok(FrameControl::Continue)
+    }
+
+    /// Get an object on the scope stack of this method, where index zero is
+    /// the first scope the method pushed.
+    fn op_get_scope_object(&mut self, index: u8) -> Result<FrameControl<'gc>, Error<'gc>> {
+        let skip = self
+            .local_scope_depth
+            .checked_sub(index as usize + 1)
+            .ok_or_else(|| Error::RangeError(format!("Scope index {} out of range", index)))?;
+
+        let mut scope = self.scope;
+        for _ in 0..skip {
+            scope = scope.and_then(|scope| scope.read().parent());
+        }
+
+        match scope {
+            Some(scope) => {
+                let object = *scope.read().locals();
+                self.push(object);
+                Ok(FrameControl::Continue)
+            }
+            None => Err(Error::RangeError(format!(
+                "Scope index {} out of range",
+                index
+            ))),
+        }
+    }
+
+    /// The outermost object of the scope chain, which is the global object
+    /// of the script.
+    fn global_scope(&self, context: &mut UpdateContext<'_, 'gc, '_>) -> Object<'gc> {
+        let mut scope = match self.scope {
+            Some(scope) => scope,
+            None => return context.avm2.globals(),
+        };
+
+        loop {
+            let parent = scope.read().parent();
+            match parent {
+                Some(parent) => scope = parent,
+                None => break,
+            }
+        }
+
+        let globals = *scope.read().locals();
+        globals
+    }
+
+    fn op_get_global_scope(
+        &mut self,
+        context: &mut UpdateContext<'_, 'gc, '_>,
+    ) -> Result<FrameControl<'gc>, Error<'gc>> {
+        let globals = self.global_scope(context);
+        self.push(globals);
+        Ok(FrameControl::Continue)
+    }
+
+    fn op_get_global_slot(
+        &mut self,
+        index: u32,
+        context: &mut UpdateContext<'_, 'gc, '_>,
+    ) -> Result<FrameControl<'gc>, Error<'gc>> {
+        let value = self.global_scope(context).get_slot(index)?;
+        self.push(value);
+        Ok(FrameControl::Continue)
+    }
+
+    fn op_set_global_slot(
+        &mut self,
+        index: u32,
+        context: &mut UpdateContext<'_, 'gc, '_>,
+    ) -> Result<FrameControl<'gc>, Error<'gc>> {
+        let value = self.pop();
+        self.global_scope(context)
+            .set_slot(index, value, context.gc_context)?;
+        Ok(FrameControl::Continue)
+    }
+
+    fn op_get_slot(&mut self, index: u32) -> Result<FrameControl<'gc>, Error<'gc>> {
+        let object = self.pop().as_object()?;
+        let value = object.get_slot(index)?;
+        self.push(value);
+        Ok(FrameControl::Continue)
+    }
+
+    fn op_set_slot(
+        &mut self,
+        index: u32,
+        context: &mut UpdateContext<'_, 'gc, '_>,
+    ) -> Result<FrameControl<'gc>, Error<'gc>> {
+        let value = self.pop();
+        let object = self.pop().as_object()?;
+        object.set_slot(index, value, context.gc_context)?;
+        Ok(FrameControl::Continue)
+    }
+
+    /// Find the object on the scope chain that has a property with the given
+    /// name, falling back to the global object.
+    ///
+    /// Scripts are initialized the first time one of their definitions is
+    /// found on the global object.
+    fn find_definition(
+        &mut self,
+        name: &Multiname<'gc>,
+        context: &mut UpdateContext<'_, 'gc, '_>,
+    ) -> Result<Option<Object<'gc>>, Error<'gc>> {
+        let globals = context.avm2.globals();
+        let found = match self.scope {
+            Some(scope) => scope.read().find(name),
+            None => None,
+        };
+        let found = found.or_else(|| globals.resolve_multiname(name).map(|_| globals));
+
+        if let Some(object) = found {
+            if Object::ptr_eq(object, globals) {
+                if let Some(qname) = object.resolve_multiname(name) {
+                    Avm2::ensure_script_initialized(&qname, self, context)?;
+                }
+            }
+        }
+
+        Ok(found)
+    }
+
+    /// Find a class by name, as done for type annotations.
+    fn resolve_type(
+        &mut self,
+        name: &Multiname<'gc>,
+        context: &mut UpdateContext<'_, 'gc, '_>,
+    ) -> Result<Object<'gc>, Error<'gc>> {
+        let found = self.find_definition(name, context)?;
+        let qname = found.and_then(|object| Some((object, object.resolve_multiname(name)?)));
+
+        match qname {
+            Some((object, qname)) => object
+                .get_property(object, &qname, self, context)?
+                .as_object(),
+            None => Err(not_defined(name)),
+        }
+    }
+
+    fn op_find_property(
+        &mut self,
+        translation_unit: TranslationUnit<'gc>,
+        index: Index<AbcMultiname>,
+        context: &mut UpdateContext<'_, 'gc, '_>,
+    ) -> Result<FrameControl<'gc>, Error<'gc>> {
+        let name = Multiname::from_abc_multiname(translation_unit, index, self, context)?;
+        let object = match self.find_definition(&name, context)? {
+            Some(object) => object,
+            None => self.global_scope(context),
+        };
+        self.push(object);
+        Ok(FrameControl::Continue)
+    }
+
+    fn op_find_prop_strict(
+        &mut self,
+        translation_unit: TranslationUnit<'gc>,
+        index: Index<AbcMultiname>,
+        context: &mut UpdateContext<'_, 'gc, '_>,
+    ) -> Result<FrameControl<'gc>, Error<'gc>> {
+        let name = Multiname::from_abc_multiname(translation_unit, index, self, context)?;
+        match self.find_definition(&name, context)? {
+            Some(object) => {
+                self.push(object);
+                Ok(FrameControl::Continue)
+            }
+            None => Err(not_defined(&name)),
+        }
+    }
+
+    fn op_get_lex(
+        &mut self,
+        translation_unit: TranslationUnit<'gc>,
+        index: Index<AbcMultiname>,
+        context: &mut UpdateContext<'_, 'gc, '_>,
+    ) -> Result<FrameControl<'gc>, Error<'gc>> {
+        let name =
+            Multiname::from_abc_multiname_static(translation_unit, index, context.gc_context)?;
+        let object = match self.find_definition(&name, context)? {
+            Some(object) => object,
+            None => return Err(not_defined(&name)),
+        };
+        let value = self.get_property_by_multiname(object, &name, context)?;
+        self.push(value);
+        Ok(FrameControl::Continue)
+    }
+
+    /// Read a property of an object by multiname, which is `undefined` if the
+    /// object has no matching property.
+    fn get_property_by_multiname(
+        &mut self,
+        object: Object<'gc>,
+        name: &Multiname<'gc>,
+        context: &mut UpdateContext<'_, 'gc, '_>,
+    ) -> Result<Value<'gc>, Error<'gc>> {
+        match object.resolve_multiname(name) {
+            Some(qname) => object.get_property(object, &qname, self, context),
+            None => Ok(Value::Undefined),
+        }
+    }
+
+    fn op_get_property(
+        &mut self,
+        translation_unit: TranslationUnit<'gc>,
+        index: Index<AbcMultiname>,
+        context: &mut UpdateContext<'_, 'gc, '_>,
+    ) -> Result<FrameControl<'gc>, Error<'gc>> {
+        let name = Multiname::from_abc_multiname(translation_unit, index, self, context)?;
+        let object = self.pop().coerce_to_object(context)?;
+        let value = self.get_property_by_multiname(object, &name, context)?;
+        self.push(value);
+        Ok(FrameControl::Continue)
+    }
+
+    /// The name a property should be written to: either an existing property
+    /// matching the multiname, or a new dynamic property.
+    fn name_for_write(
+        object: Object<'gc>,
+        name: &Multiname<'gc>,
+    ) -> Result<QName<'gc>, Error<'gc>> {
+        if let Some(qname) = object.resolve_multiname(name) {
+            return Ok(qname);
+        }
+
+        match name.local_name() {
+            Some(local_name) => Ok(QName::dynamic_name(local_name)),
+            None => Err(Error::ReferenceError(
+                "Cannot write to a property without a name".to_string(),
+            )),
+        }
+    }
+
+    fn op_set_property(
+        &mut self,
+        translation_unit: TranslationUnit<'gc>,
+        index: Index<AbcMultiname>,
+        context: &mut UpdateContext<'_, 'gc, '_>,
+    ) -> Result<FrameControl<'gc>, Error<'gc>> {
+        let value = self.pop();
+        let name = Multiname::from_abc_multiname(translation_unit, index, self, context)?;
+        let object = self.pop().coerce_to_object(context)?;
+        let qname = Self::name_for_write(object, &name)?;
+        object.set_property(object, &qname, value, self, context)?;
+        Ok(FrameControl::Continue)
+    }
+
+    fn op_init_property(
+        &mut self,
+        translation_unit: TranslationUnit<'gc>,
+        index: Index<AbcMultiname>,
+        context: &mut UpdateContext<'_, 'gc, '_>,
+    ) -> Result<FrameControl<'gc>, Error<'gc>> {
+        let value = self.pop();
+        let name = Multiname::from_abc_multiname(translation_unit, index, self, context)?;
+        let object = self.pop().coerce_to_object(context)?;
+        let qname = Self::name_for_write(object, &name)?;
+        object.init_property(object, &qname, value, self, context)?;
+        Ok(FrameControl::Continue)
+    }
+
+    fn op_delete_property(
+        &mut self,
+        translation_unit: TranslationUnit<'gc>,
+        index: Index<AbcMultiname>,
+        context: &mut UpdateContext<'_, 'gc, '_>,
+    ) -> Result<FrameControl<'gc>, Error<'gc>> {
+        let name = Multiname::from_abc_multiname(translation_unit, index, self, context)?;
+        let object = self.pop().coerce_to_object(context)?;
+        let deleted = match object.resolve_multiname(&name) {
+            Some(qname) => object.delete_property(context.gc_context, &qname),
+            // Deleting a property that doesn't exist succeeds.
+            None => true,
+        };
+        self.push(deleted);
+        Ok(FrameControl::Continue)
+    }
+
+    /// The class object of the superclass, which `super` refers to.
+    fn superclass_object(&self) -> Result<ClassObject<'gc>, Error<'gc>> {
+        self.superclass
+            .and_then(|superclass| superclass.as_class_object())
+            .ok_or_else(|| Error::ReferenceError("This method has no superclass".to_string()))
+    }
+
+    fn op_get_super(
+        &mut self,
+        translation_unit: TranslationUnit<'gc>,
+        index: Index<AbcMultiname>,
+        context: &mut UpdateContext<'_, 'gc, '_>,
+    ) -> Result<FrameControl<'gc>, Error<'gc>> {
+        let name = Multiname::from_abc_multiname(translation_unit, index, self, context)?;
+        let receiver = self.pop().as_object()?;
+        let value = self
+            .superclass_object()?
+            .get_super_property(receiver, &name, self, context)?;
+        self.push(value);
+        Ok(FrameControl::Continue)
+    }
+
+    fn op_set_super(
+        &mut self,
+        translation_unit: TranslationUnit<'gc>,
+        index: Index<AbcMultiname>,
+        context: &mut UpdateContext<'_, 'gc, '_>,
+    ) -> Result<FrameControl<'gc>, Error<'gc>> {
+        let value = self.pop();
+        let name = Multiname::from_abc_multiname(translation_unit, index, self, context)?;
+        let receiver = self.pop().as_object()?;
+        self.superclass_object()?
+            .set_super_property(receiver, &name, value, self, context)?;
+        Ok(FrameControl::Continue)
+    }
+
+    fn op_in(
+        &mut self,
+        context: &mut UpdateContext<'_, 'gc, '_>,
+    ) -> Result<FrameControl<'gc>, Error<'gc>> {
+        let object = self.pop().as_object()?;
+        let name = self.pop().coerce_to_string(self, context)?;
+        self.push(object.has_property(&QName::dynamic_name(name)));
+        Ok(FrameControl::Continue)
+    }
+
+    /// Call a value as a function.
+    fn call_value(
+        &mut self,
+        function: Value<'gc>,
+        receiver: Option<Object<'gc>>,
+        arguments: &[Value<'gc>],
+        context: &mut UpdateContext<'_, 'gc, '_>,
+    ) -> Result<Value<'gc>, Error<'gc>> {
+        match function {
+            Value::Object(function) => function.call(receiver, arguments, self, context),
+            _ => Err(Error::TypeError(
+                "Error #1006: value is not a function.".to_string(),
+            )),
+        }
+    }
+
+    fn op_call(
+        &mut self,
+        num_args: u32,
+        context: &mut UpdateContext<'_, 'gc, '_>,
+    ) -> Result<FrameControl<'gc>, Error<'gc>> {
+        let args = self.pop_args(num_args)?;
+        let receiver = self.pop().as_object().ok();
+        let function = self.pop();
+        let value = self.call_value(function, receiver, &args, context)?;
+        self.push(value);
+        Ok(FrameControl::Continue)
+    }
+
+    /// Call a method of an object, by name.
+    ///
+    /// `lex` calls, used for functions that were looked up through the scope
+    /// chain, have no `this`.
+    fn op_call_property(
+        &mut self,
+        translation_unit: TranslationUnit<'gc>,
+        index: Index<AbcMultiname>,
+        num_args: u32,
+        lex: bool,
+        context: &mut UpdateContext<'_, 'gc, '_>,
+    ) -> Result<FrameControl<'gc>, Error<'gc>> {
+        let args = self.pop_args(num_args)?;
+        let name = Multiname::from_abc_multiname(translation_unit, index, self, context)?;
+        let object = self.pop().coerce_to_object(context)?;
+        let function = self.get_property_by_multiname(object, &name, context)?;
+        if let Value::Undefined = function {
+            return Err(Error::TypeError(format!(
+                "Error #1006: {} is not a function.",
+                name.local_name().unwrap_or_else(|| "*".into())
+            )));
+        }
+
+        let receiver = if lex { None } else { Some(object) };
+        let value = self.call_value(function, receiver, &args, context)?;
+        self.push(value);
+        Ok(FrameControl::Continue)
+    }
+
+    fn op_call_static(
+        &mut self,
+        translation_unit: TranslationUnit<'gc>,
+        index: Index<AbcMethod>,
+        num_args: u32,
+        context: &mut UpdateContext<'_, 'gc, '_>,
+    ) -> Result<FrameControl<'gc>, Error<'gc>> {
+        let args = self.pop_args(num_args)?;
+        let receiver = self.pop().as_object()?;
+        let method = translation_unit.load_method(index.0, context.gc_context)?;
+        let value = Executable::from_method(method, self.scope, None, None, context.gc_context)
+            .exec(Some(receiver), &args, self, context)?;
+        self.push(value);
+        Ok(FrameControl::Continue)
+    }
+
+    fn op_call_super(
+        &mut self,
+        translation_unit: TranslationUnit<'gc>,
+        index: Index<AbcMultiname>,
+        num_args: u32,
+        context: &mut UpdateContext<'_, 'gc, '_>,
+    ) -> Result<FrameControl<'gc>, Error<'gc>> {
+        let args = self.pop_args(num_args)?;
+        let name = Multiname::from_abc_multiname(translation_unit, index, self, context)?;
+        let receiver = self.pop().as_object()?;
+        let function = self
+            .superclass_object()?
+            .get_super_property(receiver, &name, self, context)?;
+        let value = self.call_value(function, Some(receiver), &args, context)?;
+        self.push(value);
+        Ok(FrameControl::Continue)
+    }
+
+    fn op_construct(
+        &mut self,
+        num_args: u32,
+        context: &mut UpdateContext<'_, 'gc, '_>,
+    ) -> Result<FrameControl<'gc>, Error<'gc>> {
+        let args = self.pop_args(num_args)?;
+        let constructor = self.pop().as_object()?;
+        let object = constructor.construct(&args, self, context)?;
+        self.push(object);
+        Ok(FrameControl::Continue)
+    }
+
+    fn op_construct_prop(
+        &mut self,
+        translation_unit: TranslationUnit<'gc>,
+        index: Index<AbcMultiname>,
+        num_args: u32,
+        context: &mut UpdateContext<'_, 'gc, '_>,
+    ) -> Result<FrameControl<'gc>, Error<'gc>> {
+        let args = self.pop_args(num_args)?;
+        let name = Multiname::from_abc_multiname(translation_unit, index, self, context)?;
+        let object = self.pop().coerce_to_object(context)?;
+        let constructor = self
+            .get_property_by_multiname(object, &name, context)?
+            .as_object()?;
+        let object = constructor.construct(&args, self, context)?;
+        self.push(object);
+        Ok(FrameControl::Continue)
+    }
+
+    fn op_construct_super(
+        &mut self,
+        num_args: u32,
+        context: &mut UpdateContext<'_, 'gc, '_>,
+    ) -> Result<FrameControl<'gc>, Error<'gc>> {
+        let args = self.pop_args(num_args)?;
+        let receiver = self.pop().as_object()?;
+        self.superclass_object()?
+            .call_init(receiver, &args, self, context)?;
+        Ok(FrameControl::Continue)
+    }
+
+    fn op_new_object(
+        &mut self,
+        num_args: u32,
+        context: &mut UpdateContext<'_, 'gc, '_>,
+    ) -> Result<FrameControl<'gc>, Error<'gc>> {
+        let properties = self.pop_args(num_args.saturating_mul(2))?;
+
+        let object = ScriptObject::object(context.gc_context, context.avm2.prototypes().object);
+        for pair in properties.chunks_exact(2) {
+            let name = pair[0].coerce_to_string(self, context)?;
+            object.set_property(
+                object,
+                &QName::dynamic_name(name),
+                pair[1].clone(),
+                self,
+                context,
+            )?;
+        }
+
+        self.push(object);
+        Ok(FrameControl::Continue)
+    }
+
+    fn op_new_array(
+        &mut self,
+        num_args: u32,
+        context: &mut UpdateContext<'_, 'gc, '_>,
+    ) -> Result<FrameControl<'gc>, Error<'gc>> {
+        let elements = self.pop_args(num_args)?;
+        let array = ArrayObject::from_elements(
+            context.gc_context,
+            context.avm2.prototypes().array,
+            elements,
+        );
+        self.push(array);
+        Ok(FrameControl::Continue)
+    }
+
+    fn op_new_function(
+        &mut self,
+        translation_unit: TranslationUnit<'gc>,
+        index: Index<AbcMethod>,
+        context: &mut UpdateContext<'_, 'gc, '_>,
+    ) -> Result<FrameControl<'gc>, Error<'gc>> {
+        let method = translation_unit.load_method(index.0, context.gc_context)?;
+        let prototypes = context.avm2.prototypes();
+        let function = FunctionObject::from_function(
+            context.gc_context,
+            method,
+            self.scope,
+            prototypes.function,
+            prototypes.object,
+        );
+        self.push(function);
+        Ok(FrameControl::Continue)
+    }
+
+    fn op_new_class(
+        &mut self,
+        translation_unit: TranslationUnit<'gc>,
+        index: Index<AbcClass>,
+        context: &mut UpdateContext<'_, 'gc, '_>,
+    ) -> Result<FrameControl<'gc>, Error<'gc>> {
+        let superclass = match self.pop() {
+            Value::Object(superclass) => Some(superclass),
+            _ => None,
+        };
+        let class = translation_unit.load_class(index.0, context.gc_context)?;
+        let class_object = ClassObject::from_class(self, context, class, superclass, self.scope)?;
+        self.push(class_object);
+        Ok(FrameControl::Continue)
+    }
+
+    /// Create the object that holds the local variables of a method which
+    /// has closures capturing them.
+    fn op_new_activation(
+        &mut self,
+        method: Gc<'gc, BytecodeMethod<'gc>>,
+        context: &mut UpdateContext<'_, 'gc, '_>,
+    ) -> Result<FrameControl<'gc>, Error<'gc>> {
+        let object = ScriptObject::bare_object(context.gc_context);
+        if let Some(body) = method.body() {
+            for abc_trait in body.traits.iter() {
+                let activation_trait = Trait::from_abc_trait(
+                    method.translation_unit(),
+                    abc_trait,
+                    context.gc_context,
+                )?;
+                object.install_trait(context, &activation_trait, self.scope, None)?;
+            }
+        }
+
+        self.push(object);
+        Ok(FrameControl::Continue)
+    }
+
+    /// Create the scope object that holds the variable of a `catch` block.
+    fn op_new_catch(
+        &mut self,
+        method: Gc<'gc, BytecodeMethod<'gc>>,
+        index: Index<AbcException>,
+        context: &mut UpdateContext<'_, 'gc, '_>,
+    ) -> Result<FrameControl<'gc>, Error<'gc>> {
+        let variable_name = method
+            .body()
+            .and_then(|body| body.exceptions.get(index.0 as usize))
+            .map(|exception| exception.variable_name.clone())
+            .ok_or_else(|| Error::InvalidReference("exception", index.0))?;
+
+        // Handlers without a variable, such as those of `finally` blocks, get an empty scope.
+        let object = ScriptObject::bare_object(context.gc_context);
+        if variable_name.0 != 0 {
+            let variable_name = QName::from_abc_multiname(
+                method.translation_unit(),
+                variable_name,
+                context.gc_context,
+            )?;
+            object.install_slot(
+                context.gc_context,
+                variable_name,
+                1,
+                Value::Undefined,
+                false,
+            );
+        }
+        self.push(object);
+        Ok(FrameControl::Continue)
+    }
+
+    fn op_coerce(
+        &mut self,
+        translation_unit: TranslationUnit<'gc>,
+        index: Index<AbcMultiname>,
+        context: &mut UpdateContext<'_, 'gc, '_>,
+    ) -> Result<FrameControl<'gc>, Error<'gc>> {
+        let type_name =
+            Multiname::from_abc_multiname_static(translation_unit, index, context.gc_context)?;
+        let value = self.pop().coerce_to_type(&type_name, self, context)?;
+        self.push(value);
+        Ok(FrameControl::Continue)
+    }
+
+    fn op_coerce_s(
+        &mut self,
+        context: &mut UpdateContext<'_, 'gc, '_>,
+    ) -> Result<FrameControl<'gc>, Error<'gc>> {
+        let value = match self.pop() {
+            Value::Undefined | Value::Null => Value::Null,
+            value => value.coerce_to_string(self, context)?.into(),
+        };
+        self.push(value);
+        Ok(FrameControl::Continue)
+    }
+
+    fn op_convert_b(&mut self) -> Result<FrameControl<'gc>, Error<'gc>> {
+        let value = self.pop().coerce_to_boolean();
+        self.push(value);
+        Ok(FrameControl::Continue)
+    }
+
+    fn op_convert_d(
+        &mut self,
+        context: &mut UpdateContext<'_, 'gc, '_>,
+    ) -> Result<FrameControl<'gc>, Error<'gc>> {
+        let value = self.pop().coerce_to_number(self, context)?;
+        self.push(value);
+        Ok(FrameControl::Continue)
+    }
+
+    fn op_convert_i(
+        &mut self,
+        context: &mut UpdateContext<'_, 'gc, '_>,
+    ) -> Result<FrameControl<'gc>, Error<'gc>> {
+        let value = self.pop().coerce_to_i32(self, context)?;
+        self.push(value);
+        Ok(FrameControl::Continue)
+    }
+
+    fn op_convert_u(
+        &mut self,
+        context: &mut UpdateContext<'_, 'gc, '_>,
+    ) -> Result<FrameControl<'gc>, Error<'gc>> {
+        let value = self.pop().coerce_to_u32(self, context)?;
+        self.push(value);
+        Ok(FrameControl::Continue)
+    }
+
+    fn op_convert_s(
+        &mut self,
+        context: &mut UpdateContext<'_, 'gc, '_>,
+    ) -> Result<FrameControl<'gc>, Error<'gc>> {
+        let value = self.pop().coerce_to_string(self, context)?;
+        self.push(value);
+        Ok(FrameControl::Continue)
+    }
+
+    fn op_convert_o(&mut self) -> Result<FrameControl<'gc>, Error<'gc>> {
+        let value = self.pop();
+        value.as_object()?;
+        self.push(value);
+        Ok(FrameControl::Continue)
+    }
+
+    /// Determine if a value is of the type represented by a class object.
+    ///
+    /// Numbers are of the `int` and `uint` types if they fit into them.
+    fn is_type(
+        &mut self,
+        value: &Value<'gc>,
+        class: Object<'gc>,
+        context: &mut UpdateContext<'_, 'gc, '_>,
+    ) -> Result<bool, Error<'gc>> {
+        let number = match value {
+            Value::Undefined | Value::Null => return Ok(false),
+            Value::Object(object) => {
+                return Ok(object.is_instance_of(class, self, context)?
+                    || self.implements_interface(*object, class, context)?);
+            }
+            Value::Number(n) => Some(*n),
+            Value::Integer(i) => Some(f64::from(*i)),
+            Value::Unsigned(u) => Some(f64::from(*u)),
+            _ => None,
+        };
+
+        if let (Some(number), Some(class_object)) = (number, class.as_class_object()) {
+            let class = class_object.class();
+            let class_name = class.read().name().local_name();
+            let is_builtin = class.read().name().namespace().is_public();
+            if is_builtin && class_name == "Number" {
+                return Ok(true);
+            } else if is_builtin && class_name == "int" {
+                return Ok(f64::from(f64_to_wrapping_i32(number)) == number);
+            } else if is_builtin && class_name == "uint" {
+                return Ok(f64::from(f64_to_wrapping_u32(number)) == number);
+            }
+        }
+
+        PrimitiveObject::from_primitive(value.clone(), context).is_instance_of(class, self, context)
+    }
+
+    /// Whether the class of an object, or one of its superclasses, implements
+    /// an interface, either directly or through another interface.
+    fn implements_interface(
+        &mut self,
+        object: Object<'gc>,
+        interface: Object<'gc>,
+        context: &mut UpdateContext<'_, 'gc, '_>,
+    ) -> Result<bool, Error<'gc>> {
+        let is_interface = interface
+            .as_class_object()
+            .map_or(false, |interface| interface.class().read().is_interface());
+        if !is_interface {
+            return Ok(false);
+        }
+
+        let constructor =
+            object.get_property(object, &QName::dynamic_name("constructor"), self, context)?;
+        let mut pending: Vec<ClassObject<'gc>> = constructor
+            .as_object()
+            .ok()
+            .and_then(|constructor| constructor.as_class_object())
+            .into_iter()
+            .collect();
+        while let Some(class_object) = pending.pop() {
+            if Object::ptr_eq(class_object.into(), interface) {
+                return Ok(true);
+            }
+
+            if let Some(superclass) = class_object.superclass().and_then(|s| s.as_class_object()) {
+                pending.push(superclass);
+            }
+
+            let interface_names = class_object.class().read().interfaces().to_vec();
+            for interface_name in interface_names.iter() {
+                if let Some(found) = self
+                    .resolve_type(interface_name, context)?
+                    .as_class_object()
+                {
+                    pending.push(found);
+                }
+            }
+        }
+
+        Ok(false)
+    }
+
+    fn op_is_type(
+        &mut self,
+        translation_unit: TranslationUnit<'gc>,
+        index: Index<AbcMultiname>,
+        context: &mut UpdateContext<'_, 'gc, '_>,
+    ) -> Result<FrameControl<'gc>, Error<'gc>> {
+        let value = self.pop();
+        let type_name =
+            Multiname::from_abc_multiname_static(translation_unit, index, context.gc_context)?;
+        let class = self.resolve_type(&type_name, context)?;
+        let is_type = self.is_type(&value, class, context)?;
+        self.push(is_type);
+        Ok(FrameControl::Continue)
+    }
+
+    fn op_is_type_late(
+        &mut self,
+        context: &mut UpdateContext<'_, 'gc, '_>,
+    ) -> Result<FrameControl<'gc>, Error<'gc>> {
+        let class = self.pop().as_object()?;
+        let value = self.pop();
+        let is_type = self.is_type(&value, class, context)?;
+        self.push(is_type);
+        Ok(FrameControl::Continue)
+    }
+
+    fn op_as_type(
+        &mut self,
+        translation_unit: TranslationUnit<'gc>,
+        type_name: Index<AbcMultiname>,
+        context: &mut UpdateContext<'_, 'gc, '_>,
+    ) -> Result<FrameControl<'gc>, Error<'gc>> {
+        let value = self.pop();
+        let type_name =
+            Multiname::from_abc_multiname_static(translation_unit, type_name, context.gc_context)?;
+        let class = self.resolve_type(&type_name, context)?;
+        let result = if self.is_type(&value, class, context)? {
+            value
+        } else {
+            Value::Null
+        };
+        self.push(result);
+        Ok(FrameControl::Continue)
+    }
+
+    fn op_as_type_late(
+        &mut self,
+        context: &mut UpdateContext<'_, 'gc, '_>,
+    ) -> Result<FrameControl<'gc>, Error<'gc>> {
+        let class = self.pop().as_object()?;
+        let value = self.pop();
+        let result = if self.is_type(&value, class, context)? {
+            value
+        } else {
+            Value::Null
+        };
+        self.push(result);
+        Ok(FrameControl::Continue)
+    }
+
+    fn op_instance_of(
+        &mut self,
+        context: &mut UpdateContext<'_, 'gc, '_>,
+    ) -> Result<FrameControl<'gc>, Error<'gc>> {
+        let constructor = self.pop().as_object()?;
+        let is_instance = match self.pop() {
+            Value::Object(object) => object.is_instance_of(constructor, self, context)?,
+            _ => false,
+        };
+        self.push(is_instance);
+        Ok(FrameControl::Continue)
+    }
+
+    fn op_type_of(&mut self) -> Result<FrameControl<'gc>, Error<'gc>> {
+        let type_of = self.pop().type_of();
+        self.push(type_of);
+        Ok(FrameControl::Continue)
+    }
+
+    fn op_add(
+        &mut self,
+        context: &mut UpdateContext<'_, 'gc, '_>,
+    ) -> Result<FrameControl<'gc>, Error<'gc>> {
+        let (value1, value2) = self.pop_pair();
+        let prim1 = value1.coerce_to_primitive(Hint::Number, self, context)?;
+        let prim2 = value2.coerce_to_primitive(Hint::Number, self, context)?;
+
+        let sum = match (&prim1, &prim2) {
+            (Value::String(_), _) | (_, Value::String(_)) => {
+                let mut string = prim1.coerce_to_string(self, context)?.to_string();
+                string.push_str(&prim2.coerce_to_string(self, context)?);
+                AvmString::new(context.gc_context, string).into()
+            }
+            _ => Value::Number(
+                prim1.coerce_to_number(self, context)? + prim2.coerce_to_number(self, context)?,
+            ),
+        };
+
+        self.push(sum);
+        Ok(FrameControl::Continue)
+    }
+
+    fn op_number_binary(
+        &mut self,
+        context: &mut UpdateContext<'_, 'gc, '_>,
+        op: fn(f64, f64) -> f64,
+    ) -> Result<FrameControl<'gc>, Error<'gc>> {
+        let (value1, value2) = self.pop_pair();
+        let value1 = value1.coerce_to_number(self, context)?;
+        let value2 = value2.coerce_to_number(self, context)?;
+        self.push(op(value1, value2));
+        Ok(FrameControl::Continue)
+    }
+
+    fn op_number_unary(
+        &mut self,
+        context: &mut UpdateContext<'_, 'gc, '_>,
+        op: fn(f64) -> f64,
+    ) -> Result<FrameControl<'gc>, Error<'gc>> {
+        let value = self.pop().coerce_to_number(self, context)?;
+        self.push(op(value));
+        Ok(FrameControl::Continue)
+    }
+
+    fn op_int_binary(
+        &mut self,
+        context: &mut UpdateContext<'_, 'gc, '_>,
+        op: fn(i32, i32) -> i32,
+    ) -> Result<FrameControl<'gc>, Error<'gc>> {
+        let (value1, value2) = self.pop_pair();
+        let value1 = value1.coerce_to_i32(self, context)?;
+        let value2 = value2.coerce_to_i32(self, context)?;
+        self.push(op(value1, value2));
+        Ok(FrameControl::Continue)
+    }
+
+    fn op_int_unary(
+        &mut self,
+        context: &mut UpdateContext<'_, 'gc, '_>,
+        op: fn(i32) -> i32,
+    ) -> Result<FrameControl<'gc>, Error<'gc>> {
+        let value = self.pop().coerce_to_i32(self, context)?;
+        self.push(op(value));
+        Ok(FrameControl::Continue)
+    }
+
+    fn op_urshift(
+        &mut self,
+        context: &mut UpdateContext<'_, 'gc, '_>,
+    ) -> Result<FrameControl<'gc>, Error<'gc>> {
+        let (value1, value2) = self.pop_pair();
+        let value1 = value1.coerce_to_u32(self, context)?;
+        let value2 = value2.coerce_to_u32(self, context)?;
+        self.push(value1 >> (value2 & 0x1F));
+        Ok(FrameControl::Continue)
+    }
+
+    fn op_not(&mut self) -> Result<FrameControl<'gc>, Error<'gc>> {
+        let value = !self.pop().coerce_to_boolean();
+        self.push(value);
+        Ok(FrameControl::Continue)
+    }
+
+    fn op_equals(
+        &mut self,
+        context: &mut UpdateContext<'_, 'gc, '_>,
+    ) -> Result<FrameControl<'gc>, Error<'gc>> {
+        let (value1, value2) = self.pop_pair();
+        let result = value1.abstract_eq(&value2, self, context)?;
+        self.push(result);
+        Ok(FrameControl::Continue)
+    }
+
+    fn op_strict_equals(&mut self) -> Result<FrameControl<'gc>, Error<'gc>> {
+        let (value1, value2) = self.pop_pair();
+        self.push(value1.strict_eq(&value2));
+        Ok(FrameControl::Continue)
+    }
+
+    fn op_less_than(
+        &mut self,
+        context: &mut UpdateContext<'_, 'gc, '_>,
+    ) -> Result<FrameControl<'gc>, Error<'gc>> {
+        let (value1, value2) = self.pop_pair();
+        let result = value1.abstract_lt(&value2, self, context)? == Some(true);
+        self.push(result);
+        Ok(FrameControl::Continue)
+    }
+
+    fn op_less_equals(
+        &mut self,
+        context: &mut UpdateContext<'_, 'gc, '_>,
+    ) -> Result<FrameControl<'gc>, Error<'gc>> {
+        let (value1, value2) = self.pop_pair();
+        let result = value2.abstract_lt(&value1, self, context)? == Some(false);
+        self.push(result);
+        Ok(FrameControl::Continue)
+    }
+
+    fn op_greater_than(
+        &mut self,
+        context: &mut UpdateContext<'_, 'gc, '_>,
+    ) -> Result<FrameControl<'gc>, Error<'gc>> {
+        let (value1, value2) = self.pop_pair();
+        let result = value2.abstract_lt(&value1, self, context)? == Some(true);
+        self.push(result);
+        Ok(FrameControl::Continue)
+    }
+
+    fn op_greater_equals(
+        &mut self,
+        context: &mut UpdateContext<'_, 'gc, '_>,
+    ) -> Result<FrameControl<'gc>, Error<'gc>> {
+        let (value1, value2) = self.pop_pair();
+        let result = value1.abstract_lt(&value2, self, context)? == Some(false);
+        self.push(result);
+        Ok(FrameControl::Continue)
+    }
+
+    fn op_branch(
+        &mut self,
+        reader: &mut CodeReader<'_>,
+        offset: i32,
+        condition: bool,
+    ) -> Result<FrameControl<'gc>, Error<'gc>> {
+        if condition {
+            jump(reader, offset);
+        }
+
+        Ok(FrameControl::Continue)
+    }
+
+    /// Jump to one of several cases; unlike other jumps, the offsets are
+    /// relative to the start of the instruction.
+    fn op_lookup_switch(
+        &mut self,
+        reader: &mut CodeReader<'_>,
+        op_start: u64,
+        default_offset: i32,
+        case_offsets: &[i32],
+        context: &mut UpdateContext<'_, 'gc, '_>,
+    ) -> Result<FrameControl<'gc>, Error<'gc>> {
+        let index = self.pop().coerce_to_i32(self, context)?;
+        let offset = if index >= 0 {
+            case_offsets
+                .get(index as usize)
+                .copied()
+                .unwrap_or(default_offset)
+        } else {
+            default_offset
+        };
+
+        let position = op_start as i64 + i64::from(offset);
+        reader.get_mut().set_position(position as u64);
+        Ok(FrameControl::Continue)
+    }
+
+    fn op_has_next(
+        &mut self,
+        context: &mut UpdateContext<'_, 'gc, '_>,
+    ) -> Result<FrameControl<'gc>, Error<'gc>> {
+        let index = self.pop().coerce_to_u32(self, context)?;
+        let next = match self.pop() {
+            Value::Object(object) => object.get_next_enumerant(index),
+            _ => 0,
+        };
+        self.push(next);
+        Ok(FrameControl::Continue)
+    }
+
+    /// Advance a `for..in` loop, which enumerates an object and then each of
+    /// its prototypes in turn.
+    fn op_has_next_2(
+        &mut self,
+        object_register: u32,
+        index_register: u32,
+        context: &mut UpdateContext<'_, 'gc, '_>,
+    ) -> Result<FrameControl<'gc>, Error<'gc>> {
+        let mut object = self.local_register(object_register)?;
+        let mut index = self
+            .local_register(index_register)?
+            .coerce_to_u32(self, context)?;
+
+        let has_next = loop {
+            let current = match object {
+                Value::Object(current) => current,
+                _ => break false,
+            };
+
+            let next = current.get_next_enumerant(index);
+            if next != 0 {
+                index = next;
+                break true;
+            }
+
+            object = current.proto().map(Value::Object).unwrap_or(Value::Null);
+            index = 0;
+        };
+
+        self.set_local_register(object_register, object)?;
+        self.set_local_register(index_register, index.into())?;
+        self.push(has_next);
+        Ok(FrameControl::Continue)
+    }
+
+    fn op_next_name(
+        &mut self,
+        context: &mut UpdateContext<'_, 'gc, '_>,
+    ) -> Result<FrameControl<'gc>, Error<'gc>> {
+        let index = self.pop().coerce_to_u32(self, context)?;
+        let object = self.pop().as_object()?;
+        let name = object
+            .get_enumerant_name(index, context.gc_context)
+            .unwrap_or(Value::Undefined);
+        self.push(name);
+        Ok(FrameControl::Continue)
+    }
+
+    fn op_next_value(
+        &mut self,
+        context: &mut UpdateContext<'_, 'gc, '_>,
+    ) -> Result<FrameControl<'gc>, Error<'gc>> {
+        let index = self.pop().coerce_to_u32(self, context)?;
+        let object = self.pop().as_object()?;
+        let value = object.get_enumerant_value(index, self, context)?;
+        self.push(value);
+        Ok(FrameControl::Continue)
+    }
+}
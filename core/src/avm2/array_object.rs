@@ -0,0 +1,178 @@
+//! Array object impl
+
+use crate::avm2::activation::Activation;
+use crate::avm2::names::QName;
+use crate::avm2::object::{Object, TObject};
+use crate::avm2::script_object::{ScriptObject, ScriptObjectData};
+use crate::avm2::value::Value;
+use crate::avm2::{AvmString, Error};
+use crate::context::UpdateContext;
+use gc_arena::{Collect, GcCell, MutationContext};
+
+/// An object holding a dense list of elements, indexed by number.
+///
+/// Array elements are stored separately from the object's other properties.
+/// Holes in the array are treated as `undefined` elements.
+#[derive(Collect, Debug, Clone, Copy)]
+#[collect(no_drop)]
+pub struct ArrayObject<'gc>(GcCell<'gc, ArrayObjectData<'gc>>);
+
+#[derive(Collect, Debug, Clone)]
+#[collect(no_drop)]
+pub struct ArrayObjectData<'gc> {
+    /// Base script object
+    base: ScriptObject<'gc>,
+
+    /// The elements of the array.
+    elements: Vec<Value<'gc>>,
+}
+
+impl<'gc> ArrayObject<'gc> {
+    /// Construct an array holding the given elements.
+    pub fn from_elements(
+        mc: MutationContext<'gc, '_>,
+        proto: Object<'gc>,
+        elements: Vec<Value<'gc>>,
+    ) -> Object<'gc> {
+        ArrayObject(GcCell::allocate(
+            mc,
+            ArrayObjectData {
+                base: ScriptObject::base_object(mc, Some(proto)),
+                elements,
+            },
+        ))
+        .into()
+    }
+
+    pub fn length(self) -> usize {
+        self.0.read().elements.len()
+    }
+
+    pub fn elements(self) -> Vec<Value<'gc>> {
+        self.0.read().elements.clone()
+    }
+
+    pub fn set_elements(self, mc: MutationContext<'gc, '_>, elements: Vec<Value<'gc>>) {
+        self.0.write(mc).elements = elements;
+    }
+
+    /// Resize the array, filling new elements with `undefined`.
+    pub fn set_length(self, mc: MutationContext<'gc, '_>, length: usize) {
+        self.0.write(mc).elements.resize(length, Value::Undefined);
+    }
+}
+
+/// The array index a property name refers to, if any.
+fn array_index(name: &QName<'_>) -> Option<usize> {
+    if !name.namespace().is_public() {
+        return None;
+    }
+
+    name.local_name().parse::<u32>().ok().map(|i| i as usize)
+}
+
+fn is_length(name: &QName<'_>) -> bool {
+    name.namespace().is_public() && &*name.local_name() == "length"
+}
+
+impl<'gc> TObject<'gc> for ArrayObject<'gc> {
+    fn base(&self) -> GcCell<'gc, ScriptObjectData<'gc>> {
+        self.0.read().base.base()
+    }
+
+    fn get_property_local(
+        &self,
+        receiver: Object<'gc>,
+        name: &QName<'gc>,
+        activation: &mut Activation<'gc>,
+        context: &mut UpdateContext<'_, 'gc, '_>,
+    ) -> Result<Value<'gc>, Error<'gc>> {
+        if let Some(index) = array_index(name) {
+            return Ok(self
+                .0
+                .read()
+                .elements
+                .get(index)
+                .cloned()
+                .unwrap_or(Value::Undefined));
+        }
+
+        if is_length(name) {
+            return Ok(Value::Unsigned(self.length() as u32));
+        }
+
+        let base = self.0.read().base;
+        base.get_property_local(receiver, name, activation, context)
+    }
+
+    fn set_property(
+        &self,
+        receiver: Object<'gc>,
+        name: &QName<'gc>,
+        value: Value<'gc>,
+        activation: &mut Activation<'gc>,
+        context: &mut UpdateContext<'_, 'gc, '_>,
+    ) -> Result<(), Error<'gc>> {
+        if let Some(index) = array_index(name) {
+            let mut write = self.0.write(context.gc_context);
+            if write.elements.len() <= index {
+                write.elements.resize(index + 1, Value::Undefined);
+            }
+            write.elements[index] = value;
+
+            return Ok(());
+        }
+
+        if is_length(name) {
+            let length = value.coerce_to_u32(activation, context)?;
+            self.set_length(context.gc_context, length as usize);
+
+            return Ok(());
+        }
+
+        let base = self.0.read().base;
+        base.set_property(receiver, name, value, activation, context)
+    }
+
+    fn has_own_property(&self, name: &QName<'gc>) -> bool {
+        if let Some(index) = array_index(name) {
+            return index < self.length();
+        }
+
+        is_length(name) || self.0.read().base.has_own_property(name)
+    }
+
+    fn get_next_enumerant(&self, last_index: u32) -> u32 {
+        let length = self.length() as u32;
+        if last_index < length {
+            return last_index + 1;
+        }
+
+        match self.0.read().base.get_next_enumerant(last_index - length) {
+            0 => 0,
+            index => index + length,
+        }
+    }
+
+    fn get_enumerant_name(
+        &self,
+        index: u32,
+        gc_context: MutationContext<'gc, '_>,
+    ) -> Option<Value<'gc>> {
+        let length = self.length() as u32;
+        if index == 0 {
+            None
+        } else if index <= length {
+            Some(AvmString::new(gc_context, (index - 1).to_string()).into())
+        } else {
+            self.0
+                .read()
+                .base
+                .get_enumerant_name(index - length, gc_context)
+        }
+    }
+
+    fn as_array_object(&self) -> Option<ArrayObject<'gc>> {
+        Some(*self)
+    }
+}
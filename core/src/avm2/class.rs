@@ -0,0 +1,385 @@
+//! AVM2 classes
+
+use crate::avm2::activation::Activation;
+use crate::avm2::method::Method;
+use crate::avm2::names::{Multiname, QName};
+use crate::avm2::object::Object;
+use crate::avm2::script::TranslationUnit;
+use crate::avm2::value::{abc_default_value, Value};
+use crate::avm2::Error;
+use crate::context::UpdateContext;
+use gc_arena::{Collect, GcCell, MutationContext};
+use std::fmt;
+use swf::avm2::types::{
+    Index, Multiname as AbcMultiname, Trait as AbcTrait, TraitKind as AbcTraitKind,
+};
+
+/// Allocates the object for a new instance of a class, given the prototype it
+/// should have.
+///
+/// The instance's traits are installed and its constructor is run afterwards.
+pub type AllocatorFn<'gc> = fn(
+    Object<'gc>,
+    &mut Activation<'gc>,
+    &mut UpdateContext<'_, 'gc, '_>,
+) -> Result<Object<'gc>, Error<'gc>>;
+
+/// The allocator of a class whose instances need a native representation,
+/// such as arrays. Subclasses inherit the allocator of their superclass.
+#[derive(Clone, Copy)]
+pub struct Allocator<'gc>(pub AllocatorFn<'gc>);
+
+unsafe impl<'gc> Collect for Allocator<'gc> {
+    #[inline]
+    fn needs_trace() -> bool {
+        false
+    }
+}
+
+impl fmt::Debug for Allocator<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_tuple("Allocator")
+            .field(&format!("{:p}", self.0))
+            .finish()
+    }
+}
+
+/// Represents a trait as loaded into the VM.
+///
+/// A trait is a property of an object that is declared ahead of time, rather
+/// than being set dynamically by a script.
+#[derive(Clone, Collect, Debug)]
+#[collect(no_drop)]
+pub struct Trait<'gc> {
+    /// The name of this trait.
+    name: QName<'gc>,
+
+    /// The kind of trait in use.
+    kind: TraitKind<'gc>,
+}
+
+/// The fields for a particular kind of trait.
+#[derive(Clone, Collect, Debug)]
+#[collect(no_drop)]
+pub enum TraitKind<'gc> {
+    /// A data field on an object instance that can be read from and written
+    /// to.
+    Slot {
+        slot_id: u32,
+        default_value: Value<'gc>,
+    },
+
+    /// A method on an object that can be called.
+    Method { disp_id: u32, method: Method<'gc> },
+
+    /// A getter property on an object that can be read.
+    Getter { disp_id: u32, method: Method<'gc> },
+
+    /// A setter property on an object that can be written.
+    Setter { disp_id: u32, method: Method<'gc> },
+
+    /// A class property on an object that can be used to construct more
+    /// objects.
+    ///
+    /// The slot starts out empty: the class object is built by `newclass`
+    /// and stored into it by the script's initializer.
+    Class {
+        slot_id: u32,
+        class: GcCell<'gc, Class<'gc>>,
+    },
+
+    /// A free function (not an instance method) that can be called.
+    Function { slot_id: u32, function: Method<'gc> },
+
+    /// A data field on an object that is always a particular value, and
+    /// cannot be overridden.
+    Const {
+        slot_id: u32,
+        default_value: Value<'gc>,
+    },
+}
+
+impl<'gc> Trait<'gc> {
+    /// Convert an ABC trait into a loaded trait.
+    pub fn from_abc_trait(
+        translation_unit: TranslationUnit<'gc>,
+        abc_trait: &AbcTrait,
+        mc: MutationContext<'gc, '_>,
+    ) -> Result<Self, Error<'gc>> {
+        let name = QName::from_abc_multiname(translation_unit, abc_trait.name.clone(), mc)?;
+
+        let kind = match &abc_trait.kind {
+            AbcTraitKind::Slot {
+                slot_id,
+                type_name,
+                value,
+            } => TraitKind::Slot {
+                slot_id: *slot_id,
+                default_value: match value {
+                    Some(value) => abc_default_value(translation_unit, value, mc)?,
+                    None => default_value_for_type(translation_unit, type_name.clone(), mc)?,
+                },
+            },
+            AbcTraitKind::Method { disp_id, method } => TraitKind::Method {
+                disp_id: *disp_id,
+                method: translation_unit.load_method(method.0, mc)?,
+            },
+            AbcTraitKind::Getter { disp_id, method } => TraitKind::Getter {
+                disp_id: *disp_id,
+                method: translation_unit.load_method(method.0, mc)?,
+            },
+            AbcTraitKind::Setter { disp_id, method } => TraitKind::Setter {
+                disp_id: *disp_id,
+                method: translation_unit.load_method(method.0, mc)?,
+            },
+            AbcTraitKind::Class { slot_id, class } => TraitKind::Class {
+                slot_id: *slot_id,
+                class: translation_unit.load_class(class.0, mc)?,
+            },
+            AbcTraitKind::Function { slot_id, function } => TraitKind::Function {
+                slot_id: *slot_id,
+                function: translation_unit.load_method(function.0, mc)?,
+            },
+            AbcTraitKind::Const {
+                slot_id,
+                type_name,
+                value,
+            } => TraitKind::Const {
+                slot_id: *slot_id,
+                default_value: match value {
+                    Some(value) => abc_default_value(translation_unit, value, mc)?,
+                    None => default_value_for_type(translation_unit, type_name.clone(), mc)?,
+                },
+            },
+        };
+
+        Ok(Trait { name, kind })
+    }
+
+    pub fn name(&self) -> &QName<'gc> {
+        &self.name
+    }
+
+    pub fn kind(&self) -> &TraitKind<'gc> {
+        &self.kind
+    }
+}
+
+/// The value a slot of the given type holds before anything is stored in it.
+///
+/// Numeric and boolean slots start out as zero, `NaN` or `false`; untyped
+/// slots are `undefined` and slots of every other type are `null`.
+fn default_value_for_type<'gc>(
+    translation_unit: TranslationUnit<'gc>,
+    type_name: Index<AbcMultiname>,
+    mc: MutationContext<'gc, '_>,
+) -> Result<Value<'gc>, Error<'gc>> {
+    if type_name.0 == 0 {
+        return Ok(Value::Undefined);
+    }
+
+    let type_name = Multiname::from_abc_multiname_static(translation_unit, type_name, mc)?;
+    Ok(if type_name.is_public_name("int") {
+        Value::Integer(0)
+    } else if type_name.is_public_name("uint") {
+        Value::Unsigned(0)
+    } else if type_name.is_public_name("Number") {
+        Value::Number(std::f64::NAN)
+    } else if type_name.is_public_name("Boolean") {
+        Value::Bool(false)
+    } else {
+        Value::Null
+    })
+}
+
+/// A loaded ABC Class which can be used to construct objects with.
+#[derive(Clone, Collect, Debug)]
+#[collect(no_drop)]
+pub struct Class<'gc> {
+    /// The name of the class.
+    name: QName<'gc>,
+
+    /// The name of this class's superclass.
+    super_class: Option<Multiname<'gc>>,
+
+    /// If the class can't be subclassed.
+    is_final: bool,
+
+    /// If the class is an interface.
+    is_interface: bool,
+
+    /// The list of interfaces this class implements.
+    interfaces: Vec<Multiname<'gc>>,
+
+    /// The instance initializer for this class.
+    ///
+    /// Must be called each time a new class instance is constructed.
+    instance_init: Method<'gc>,
+
+    /// Instance traits for a given class.
+    ///
+    /// These are accessed as normal instance properties; they should not be
+    /// present on prototypes, but instead should shadow any prototype
+    /// properties that would match.
+    instance_traits: Vec<Trait<'gc>>,
+
+    /// The class initializer for this class.
+    ///
+    /// Must be called once prior to any use of this class.
+    class_init: Method<'gc>,
+
+    /// Static traits for a given class.
+    ///
+    /// These are accessed as constructor properties.
+    class_traits: Vec<Trait<'gc>>,
+
+    /// The allocator for instances of this class, if they aren't ordinary
+    /// script objects.
+    instance_allocator: Option<Allocator<'gc>>,
+}
+
+impl<'gc> Class<'gc> {
+    /// Create a new class.
+    ///
+    /// This function is primarily intended for use by native code to define
+    /// builtin classes. The class has no traits; its methods are usually
+    /// defined on the prototype.
+    pub fn new(
+        name: QName<'gc>,
+        super_class: Option<Multiname<'gc>>,
+        instance_init: Method<'gc>,
+        class_init: Method<'gc>,
+        mc: MutationContext<'gc, '_>,
+    ) -> GcCell<'gc, Self> {
+        GcCell::allocate(
+            mc,
+            Self {
+                name,
+                super_class,
+                is_final: false,
+                is_interface: false,
+                interfaces: Vec::new(),
+                instance_init,
+                instance_traits: Vec::new(),
+                class_init,
+                class_traits: Vec::new(),
+                instance_allocator: None,
+            },
+        )
+    }
+
+    /// Construct a class from a `TranslationUnit` and its class index.
+    pub fn from_abc_index(
+        translation_unit: TranslationUnit<'gc>,
+        class_index: u32,
+        mc: MutationContext<'gc, '_>,
+    ) -> Result<GcCell<'gc, Self>, Error<'gc>> {
+        let abc = translation_unit.abc();
+        let abc_class = abc
+            .classes
+            .get(class_index as usize)
+            .ok_or_else(|| Error::InvalidReference("class", class_index))?;
+        let abc_instance = abc
+            .instances
+            .get(class_index as usize)
+            .ok_or_else(|| Error::InvalidReference("instance", class_index))?;
+
+        let name = QName::from_abc_multiname(translation_unit, abc_instance.name.clone(), mc)?;
+        let super_class = if abc_instance.super_name.0 == 0 {
+            None
+        } else {
+            Some(Multiname::from_abc_multiname_static(
+                translation_unit,
+                abc_instance.super_name.clone(),
+                mc,
+            )?)
+        };
+
+        let mut interfaces = Vec::with_capacity(abc_instance.interfaces.len());
+        for interface_name in abc_instance.interfaces.iter() {
+            interfaces.push(Multiname::from_abc_multiname_static(
+                translation_unit,
+                interface_name.clone(),
+                mc,
+            )?);
+        }
+
+        let instance_init = translation_unit.load_method(abc_instance.init_method.0, mc)?;
+        let class_init = translation_unit.load_method(abc_class.init_method.0, mc)?;
+
+        let mut instance_traits = Vec::with_capacity(abc_instance.traits.len());
+        for abc_trait in abc_instance.traits.iter() {
+            instance_traits.push(Trait::from_abc_trait(translation_unit, abc_trait, mc)?);
+        }
+
+        let mut class_traits = Vec::with_capacity(abc_class.traits.len());
+        for abc_trait in abc_class.traits.iter() {
+            class_traits.push(Trait::from_abc_trait(translation_unit, abc_trait, mc)?);
+        }
+
+        Ok(GcCell::allocate(
+            mc,
+            Self {
+                name,
+                super_class,
+                is_final: abc_instance.is_final,
+                is_interface: abc_instance.is_interface,
+                interfaces,
+                instance_init,
+                instance_traits,
+                class_init,
+                class_traits,
+                instance_allocator: None,
+            },
+        ))
+    }
+
+    pub fn name(&self) -> &QName<'gc> {
+        &self.name
+    }
+
+    pub fn super_class_name(&self) -> &Option<Multiname<'gc>> {
+        &self.super_class
+    }
+
+    pub fn is_final(&self) -> bool {
+        self.is_final
+    }
+
+    pub fn is_interface(&self) -> bool {
+        self.is_interface
+    }
+
+    pub fn interfaces(&self) -> &[Multiname<'gc>] {
+        &self.interfaces
+    }
+
+    pub fn instance_init(&self) -> Method<'gc> {
+        self.instance_init.clone()
+    }
+
+    pub fn instance_traits(&self) -> &[Trait<'gc>] {
+        &self.instance_traits
+    }
+
+    pub fn class_init(&self) -> Method<'gc> {
+        self.class_init.clone()
+    }
+
+    pub fn class_traits(&self) -> &[Trait<'gc>] {
+        &self.class_traits
+    }
+
+    pub fn instance_allocator(&self) -> Option<AllocatorFn<'gc>> {
+        self.instance_allocator.map(|a| a.0)
+    }
+
+    pub fn set_instance_allocator(&mut self, allocator: AllocatorFn<'gc>) {
+        self.instance_allocator = Some(Allocator(allocator));
+    }
+
+    /// Look for an instance trait with the given name.
+    pub fn lookup_instance_trait(&self, name: &QName<'gc>) -> Option<&Trait<'gc>> {
+        self.instance_traits.iter().find(|t| t.name() == name)
+    }
+}
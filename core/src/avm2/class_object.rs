@@ -0,0 +1,412 @@
+//! Class object impl
+
+use crate::avm2::activation::Activation;
+use crate::avm2::class::{AllocatorFn, Class, Trait, TraitKind};
+use crate::avm2::function::{Executable, FunctionObject};
+use crate::avm2::method::{Method, NativeMethod};
+use crate::avm2::names::{Multiname, QName};
+use crate::avm2::object::{Object, TObject};
+use crate::avm2::property::Attribute;
+use crate::avm2::scope::Scope;
+use crate::avm2::script_object::{ScriptObject, ScriptObjectData};
+use crate::avm2::value::Value;
+use crate::avm2::Error;
+use crate::context::UpdateContext;
+use gc_arena::{Collect, GcCell, MutationContext};
+
+/// An object that represents a class, and can be used to construct instances
+/// of it.
+#[derive(Collect, Debug, Clone, Copy)]
+#[collect(no_drop)]
+pub struct ClassObject<'gc>(GcCell<'gc, ClassObjectData<'gc>>);
+
+#[derive(Collect, Debug, Clone)]
+#[collect(no_drop)]
+pub struct ClassObjectData<'gc> {
+    /// Base script object
+    base: ScriptObject<'gc>,
+
+    /// The class this object was created from.
+    class: GcCell<'gc, Class<'gc>>,
+
+    /// The class object of the superclass, if any.
+    superclass: Option<Object<'gc>>,
+
+    /// The prototype of instances of this class.
+    prototype: Object<'gc>,
+
+    /// The scope that the class's methods close over, which includes the
+    /// class object itself.
+    scope: Option<GcCell<'gc, Scope<'gc>>>,
+
+    /// The instance initializer, which is run by `new` and by subclasses
+    /// calling `super()`.
+    instance_init: Executable<'gc>,
+
+    /// What calling the class as a function does, for builtin classes that
+    /// convert their argument, such as `String(value)`.
+    native_call: Option<Method<'gc>>,
+}
+
+impl<'gc> ClassObject<'gc> {
+    /// Construct a class object from a loaded class, as done by `newclass`.
+    ///
+    /// This installs the class's static traits and runs its class
+    /// initializer.
+    pub fn from_class(
+        activation: &mut Activation<'gc>,
+        context: &mut UpdateContext<'_, 'gc, '_>,
+        class: GcCell<'gc, Class<'gc>>,
+        superclass: Option<Object<'gc>>,
+        scope: Option<GcCell<'gc, Scope<'gc>>>,
+    ) -> Result<Object<'gc>, Error<'gc>> {
+        let object_proto = context.avm2.prototypes().object;
+        match superclass {
+            Some(superclass) => {
+                let superclass = superclass.as_class_object().map(|s| s.class());
+                if superclass.map_or(false, |s| s.read().is_final()) {
+                    return Err(Error::TypeError(format!(
+                        "Error #1103: Class {} cannot extend final base class.",
+                        class.read().name().local_name()
+                    )));
+                }
+            }
+            None => {
+                if let Some(super_name) = class.read().super_class_name() {
+                    return Err(Error::ReferenceError(format!(
+                        "Error #1014: Class {} could not be found.",
+                        super_name.local_name().unwrap_or_else(|| "*".into())
+                    )));
+                }
+            }
+        }
+
+        let super_proto = match superclass {
+            Some(superclass) => superclass
+                .get_property(
+                    superclass,
+                    &QName::dynamic_name("prototype"),
+                    activation,
+                    context,
+                )?
+                .as_object()?,
+            None => object_proto,
+        };
+        let prototype = ScriptObject::object(context.gc_context, super_proto);
+
+        // The instance initializer needs the class scope, which contains the
+        // class object, so it's filled in once the object exists.
+        let class_object = ClassObject(GcCell::allocate(
+            context.gc_context,
+            ClassObjectData {
+                base: ScriptObject::base_object(context.gc_context, Some(object_proto)),
+                class,
+                superclass,
+                prototype,
+                scope: None,
+                instance_init: Executable::Native(|_, _, _, _| Ok(Value::Undefined), None),
+                native_call: None,
+            },
+        ));
+        let class_scope = Some(Scope::push_scope(
+            scope,
+            class_object.into(),
+            context.gc_context,
+        ));
+        let instance_init = Executable::from_method(
+            class.read().instance_init(),
+            class_scope,
+            None,
+            superclass,
+            context.gc_context,
+        );
+        {
+            let mut write = class_object.0.write(context.gc_context);
+            write.scope = class_scope;
+            write.instance_init = instance_init;
+        }
+
+        class_object.link_prototype(context.gc_context);
+
+        for class_trait in class.read().class_traits().iter() {
+            class_object.install_trait(context, class_trait, class_scope, None)?;
+        }
+
+        let class_init = Executable::from_method(
+            class.read().class_init(),
+            class_scope,
+            None,
+            None,
+            context.gc_context,
+        );
+        class_init.exec(Some(class_object.into()), &[], activation, context)?;
+
+        Ok(class_object.into())
+    }
+
+    /// Construct a class object for a builtin class.
+    ///
+    /// Methods of builtin classes are defined on `prototype`, rather than as
+    /// traits.
+    pub fn from_builtin(
+        mc: MutationContext<'gc, '_>,
+        class: GcCell<'gc, Class<'gc>>,
+        superclass: Option<Object<'gc>>,
+        prototype: Object<'gc>,
+        object_proto: Object<'gc>,
+    ) -> Object<'gc> {
+        let instance_init =
+            Executable::from_method(class.read().instance_init(), None, None, superclass, mc);
+        let class_object = ClassObject(GcCell::allocate(
+            mc,
+            ClassObjectData {
+                base: ScriptObject::base_object(mc, Some(object_proto)),
+                class,
+                superclass,
+                prototype,
+                scope: None,
+                instance_init,
+                native_call: None,
+            },
+        ));
+
+        class_object.link_prototype(mc);
+
+        class_object.into()
+    }
+
+    /// Set what calling this class as a function does.
+    pub fn set_native_call(self, mc: MutationContext<'gc, '_>, native_call: NativeMethod<'gc>) {
+        self.0.write(mc).native_call = Some(native_call.into());
+    }
+
+    /// Define the `prototype` and `constructor` properties linking the class
+    /// object and its instance prototype.
+    fn link_prototype(self, mc: MutationContext<'gc, '_>) {
+        let prototype = self.prototype();
+
+        self.define_value(
+            mc,
+            QName::dynamic_name("prototype"),
+            prototype.into(),
+            Attribute::DontEnum | Attribute::DontDelete | Attribute::ReadOnly,
+        );
+        prototype.define_value(
+            mc,
+            QName::dynamic_name("constructor"),
+            self.into(),
+            Attribute::DontEnum.into(),
+        );
+    }
+
+    pub fn class(self) -> GcCell<'gc, Class<'gc>> {
+        self.0.read().class
+    }
+
+    pub fn superclass(self) -> Option<Object<'gc>> {
+        self.0.read().superclass
+    }
+
+    pub fn prototype(self) -> Object<'gc> {
+        self.0.read().prototype
+    }
+
+    pub fn scope(self) -> Option<GcCell<'gc, Scope<'gc>>> {
+        self.0.read().scope
+    }
+
+    /// The allocator for instances of this class, which is inherited from
+    /// the nearest superclass that has one.
+    pub fn instance_allocator(self) -> Option<AllocatorFn<'gc>> {
+        if let Some(allocator) = self.class().read().instance_allocator() {
+            return Some(allocator);
+        }
+
+        self.superclass()
+            .and_then(|superclass| superclass.as_class_object())
+            .and_then(|superclass| superclass.instance_allocator())
+    }
+
+    /// Install the instance traits of this class, and all of its
+    /// superclasses, onto an instance.
+    ///
+    /// Superclass traits are installed first, so that overriding traits
+    /// replace them.
+    pub fn install_instance_traits(
+        self,
+        instance: Object<'gc>,
+        context: &mut UpdateContext<'_, 'gc, '_>,
+    ) -> Result<(), Error<'gc>> {
+        if let Some(superclass) = self.superclass().and_then(|s| s.as_class_object()) {
+            superclass.install_instance_traits(instance, context)?;
+        }
+
+        let class = self.class();
+        let scope = self.scope();
+        let superclass = self.superclass();
+        for instance_trait in class.read().instance_traits().iter() {
+            instance.install_trait(context, instance_trait, scope, superclass)?;
+        }
+
+        Ok(())
+    }
+
+    /// Run this class's instance initializer on an instance.
+    ///
+    /// This is done both when constructing the class, and when a subclass
+    /// constructor calls `super()`.
+    pub fn call_init(
+        self,
+        instance: Object<'gc>,
+        arguments: &[Value<'gc>],
+        activation: &mut Activation<'gc>,
+        context: &mut UpdateContext<'_, 'gc, '_>,
+    ) -> Result<(), Error<'gc>> {
+        let instance_init = self.0.read().instance_init.clone();
+        instance_init.exec(Some(instance), arguments, activation, context)?;
+
+        Ok(())
+    }
+
+    /// Find an instance trait declared by this class, or one of its
+    /// superclasses, along with the class that declared it.
+    fn find_instance_trait(self, name: &Multiname<'gc>) -> Option<(Trait<'gc>, ClassObject<'gc>)> {
+        let local_name = name.local_name()?;
+        for ns in name.namespace_set() {
+            let qname = QName::new(*ns, local_name);
+            if let Some(found) = self.class().read().lookup_instance_trait(&qname) {
+                return Some((found.clone(), self));
+            }
+        }
+
+        self.superclass()
+            .and_then(|superclass| superclass.as_class_object())
+            .and_then(|superclass| superclass.find_instance_trait(name))
+    }
+
+    /// Read a property of an instance, as implemented by this class rather
+    /// than any subclass, as done by `super.name`.
+    pub fn get_super_property(
+        self,
+        receiver: Object<'gc>,
+        name: &Multiname<'gc>,
+        activation: &mut Activation<'gc>,
+        context: &mut UpdateContext<'_, 'gc, '_>,
+    ) -> Result<Value<'gc>, Error<'gc>> {
+        match self.find_instance_trait(name) {
+            Some((found, declarer)) => match found.kind() {
+                TraitKind::Method { method, .. } => Ok(FunctionObject::from_method(
+                    context.gc_context,
+                    method.clone(),
+                    declarer.scope(),
+                    context.avm2.prototypes().function,
+                    Some(receiver),
+                    declarer.superclass(),
+                )
+                .into()),
+                TraitKind::Getter { method, .. } => Executable::from_method(
+                    method.clone(),
+                    declarer.scope(),
+                    Some(receiver),
+                    declarer.superclass(),
+                    context.gc_context,
+                )
+                .exec(Some(receiver), &[], activation, context),
+                _ => receiver.get_property(receiver, found.name(), activation, context),
+            },
+            None => {
+                let prototype = self.prototype();
+                match prototype.resolve_multiname(name) {
+                    Some(qname) => prototype.get_property(receiver, &qname, activation, context),
+                    None => Ok(Value::Undefined),
+                }
+            }
+        }
+    }
+
+    /// Write a property of an instance, as implemented by this class rather
+    /// than any subclass, as done by `super.name = value`.
+    pub fn set_super_property(
+        self,
+        receiver: Object<'gc>,
+        name: &Multiname<'gc>,
+        value: Value<'gc>,
+        activation: &mut Activation<'gc>,
+        context: &mut UpdateContext<'_, 'gc, '_>,
+    ) -> Result<(), Error<'gc>> {
+        match self.find_instance_trait(name) {
+            Some((found, declarer)) => match found.kind() {
+                TraitKind::Setter { method, .. } => {
+                    Executable::from_method(
+                        method.clone(),
+                        declarer.scope(),
+                        Some(receiver),
+                        declarer.superclass(),
+                        context.gc_context,
+                    )
+                    .exec(Some(receiver), &[value], activation, context)?;
+
+                    Ok(())
+                }
+                _ => receiver.set_property(receiver, found.name(), value, activation, context),
+            },
+            None => {
+                let name = QName::dynamic_name(name.local_name().unwrap_or_default());
+                receiver.set_property(receiver, &name, value, activation, context)
+            }
+        }
+    }
+}
+
+impl<'gc> TObject<'gc> for ClassObject<'gc> {
+    fn base(&self) -> GcCell<'gc, ScriptObjectData<'gc>> {
+        self.0.read().base.base()
+    }
+
+    fn call(
+        &self,
+        _receiver: Option<Object<'gc>>,
+        arguments: &[Value<'gc>],
+        activation: &mut Activation<'gc>,
+        context: &mut UpdateContext<'_, 'gc, '_>,
+    ) -> Result<Value<'gc>, Error<'gc>> {
+        let native_call = self.0.read().native_call.clone();
+        match native_call {
+            Some(native_call) => {
+                Executable::from_method(native_call, None, None, None, context.gc_context)
+                    .exec(None, arguments, activation, context)
+            }
+            // Calling a class is a type cast, which we don't check.
+            None => Ok(arguments.get(0).cloned().unwrap_or(Value::Undefined)),
+        }
+    }
+
+    fn construct(
+        &self,
+        arguments: &[Value<'gc>],
+        activation: &mut Activation<'gc>,
+        context: &mut UpdateContext<'_, 'gc, '_>,
+    ) -> Result<Object<'gc>, Error<'gc>> {
+        if self.class().read().is_interface() {
+            return Err(Error::TypeError(format!(
+                "Error #1115: {} is not a constructor.",
+                self.class().read().name().local_name()
+            )));
+        }
+
+        let prototype = self.prototype();
+        let instance = match self.instance_allocator() {
+            Some(allocator) => allocator(prototype, activation, context)?,
+            None => ScriptObject::object(context.gc_context, prototype),
+        };
+
+        self.install_instance_traits(instance, context)?;
+        self.call_init(instance, arguments, activation, context)?;
+
+        Ok(instance)
+    }
+
+    fn as_class_object(&self) -> Option<ClassObject<'gc>> {
+        Some(*self)
+    }
+}
@@ -0,0 +1,56 @@
+//! Object representation for the listeners of an event dispatcher
+
+use crate::avm2::events::DispatchList;
+use crate::avm2::object::TObject;
+use crate::avm2::script_object::{ScriptObject, ScriptObjectData};
+use gc_arena::{Collect, GcCell, MutationContext};
+use std::cell::{Ref, RefMut};
+
+/// An object that holds the listeners registered on an event dispatcher.
+///
+/// These are hidden properties of dispatchers, and are never seen by
+/// scripts.
+#[derive(Collect, Debug, Clone, Copy)]
+#[collect(no_drop)]
+pub struct DispatchObject<'gc>(GcCell<'gc, DispatchObjectData<'gc>>);
+
+#[derive(Collect, Debug, Clone)]
+#[collect(no_drop)]
+pub struct DispatchObjectData<'gc> {
+    /// The base of this object.
+    base: ScriptObject<'gc>,
+
+    /// The listeners of the dispatcher.
+    dispatch: DispatchList<'gc>,
+}
+
+impl<'gc> DispatchObject<'gc> {
+    /// Create a dispatch list with no listeners.
+    pub fn empty_list(mc: MutationContext<'gc, '_>) -> DispatchObject<'gc> {
+        DispatchObject(GcCell::allocate(
+            mc,
+            DispatchObjectData {
+                base: ScriptObject::base_object(mc, None),
+                dispatch: DispatchList::new(),
+            },
+        ))
+    }
+
+    pub fn dispatch_list(&self) -> Ref<DispatchList<'gc>> {
+        Ref::map(self.0.read(), |read| &read.dispatch)
+    }
+
+    pub fn dispatch_list_mut(&self, mc: MutationContext<'gc, '_>) -> RefMut<DispatchList<'gc>> {
+        RefMut::map(self.0.write(mc), |write| &mut write.dispatch)
+    }
+}
+
+impl<'gc> TObject<'gc> for DispatchObject<'gc> {
+    fn base(&self) -> GcCell<'gc, ScriptObjectData<'gc>> {
+        self.0.read().base.base()
+    }
+
+    fn as_dispatch_object(&self) -> Option<DispatchObject<'gc>> {
+        Some(*self)
+    }
+}
@@ -0,0 +1,35 @@
+use crate::avm2::Value;
+use thiserror::Error;
+
+/// The variants that end in `Error` are named after the AS3 error classes they
+/// represent.
+#[allow(clippy::enum_variant_names)]
+#[derive(Error, Debug)]
+pub enum Error<'gc> {
+    #[error("Couldn't parse ABC. This may or may not be a bug in Ruffle, please help us by reporting it to https://github.com/ruffle-rs/ruffle/issues and include the swf that triggered it.")]
+    InvalidAbc(#[from] swf::error::Error),
+
+    #[error("The ABC references an invalid {0} (index {1})")]
+    InvalidReference(&'static str, u32),
+
+    #[error("TypeError: {0}")]
+    TypeError(String),
+
+    #[error("ReferenceError: {0}")]
+    ReferenceError(String),
+
+    #[error("RangeError: {0}")]
+    RangeError(String),
+
+    #[error("ArgumentError: {0}")]
+    ArgumentError(String),
+
+    #[error("VerifyError: {0}")]
+    VerifyError(String),
+
+    #[error("Unimplemented AVM2 feature: {0}")]
+    Unimplemented(&'static str),
+
+    #[error("A script has thrown a custom error.")]
+    ThrownValue(Value<'gc>),
+}
@@ -0,0 +1,64 @@
+//! Object representation for events
+
+use crate::avm2::events::Event;
+use crate::avm2::object::{Object, TObject};
+use crate::avm2::script_object::{ScriptObject, ScriptObjectData};
+use gc_arena::{Collect, GcCell, MutationContext};
+use std::cell::{Ref, RefMut};
+
+/// An object that holds the data of an event, such as `flash.events.Event`
+/// and its subclasses.
+#[derive(Collect, Debug, Clone, Copy)]
+#[collect(no_drop)]
+pub struct EventObject<'gc>(GcCell<'gc, EventObjectData<'gc>>);
+
+#[derive(Collect, Debug, Clone)]
+#[collect(no_drop)]
+pub struct EventObjectData<'gc> {
+    /// The base of this object.
+    base: ScriptObject<'gc>,
+
+    /// The event this object holds.
+    event: Event<'gc>,
+}
+
+impl<'gc> EventObject<'gc> {
+    /// Create an object holding the given event.
+    pub fn from_event(
+        mc: MutationContext<'gc, '_>,
+        proto: Object<'gc>,
+        event: Event<'gc>,
+    ) -> EventObject<'gc> {
+        EventObject(GcCell::allocate(
+            mc,
+            EventObjectData {
+                base: ScriptObject::base_object(mc, Some(proto)),
+                event,
+            },
+        ))
+    }
+
+    pub fn event(&self) -> Ref<Event<'gc>> {
+        Ref::map(self.0.read(), |read| &read.event)
+    }
+
+    pub fn event_mut(&self, mc: MutationContext<'gc, '_>) -> RefMut<Event<'gc>> {
+        RefMut::map(self.0.write(mc), |write| &mut write.event)
+    }
+
+    /// Replace the event this object holds, as done by the `Event`
+    /// constructor.
+    pub fn set_event(self, mc: MutationContext<'gc, '_>, event: Event<'gc>) {
+        self.0.write(mc).event = event;
+    }
+}
+
+impl<'gc> TObject<'gc> for EventObject<'gc> {
+    fn base(&self) -> GcCell<'gc, ScriptObjectData<'gc>> {
+        self.0.read().base.base()
+    }
+
+    fn as_event_object(&self) -> Option<EventObject<'gc>> {
+        Some(*self)
+    }
+}
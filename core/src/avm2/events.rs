@@ -0,0 +1,590 @@
+//! Core event structure and dispatch
+
+use crate::avm2::activation::Activation;
+use crate::avm2::dispatch_object::DispatchObject;
+use crate::avm2::event_object::EventObject;
+use crate::avm2::names::{Namespace, QName};
+use crate::avm2::object::{Object, TObject};
+use crate::avm2::property::Attribute;
+use crate::avm2::stage_object::StageObject;
+use crate::avm2::value::Value;
+use crate::avm2::{AvmString, Error};
+use crate::context::UpdateContext;
+use crate::display_object::{DisplayObject, TDisplayObject};
+use fnv::FnvHashMap;
+use gc_arena::Collect;
+use std::f64::NAN;
+
+/// Which phase of event dispatch is currently occurring.
+#[derive(Copy, Clone, Collect, Debug, PartialEq, Eq)]
+#[collect(require_static)]
+pub enum EventPhase {
+    /// The event has yet to be fired on the target and is descending the
+    /// ancestors of the event target.
+    Capturing,
+
+    /// The event is currently firing on the target.
+    AtTarget,
+
+    /// The event has already fired on the target and is ascending the
+    /// ancestors of the event target.
+    Bubbling,
+}
+
+impl From<EventPhase> for u32 {
+    /// The value of the phase in `flash.events.EventPhase`.
+    fn from(phase: EventPhase) -> u32 {
+        match phase {
+            EventPhase::Capturing => 1,
+            EventPhase::AtTarget => 2,
+            EventPhase::Bubbling => 3,
+        }
+    }
+}
+
+/// How far an event may continue to propagate.
+#[derive(Copy, Clone, Collect, Debug, PartialEq, Eq)]
+#[collect(require_static)]
+enum PropagationMode {
+    /// The event continues to every listener along its path.
+    Allow,
+
+    /// The event stops once the listeners on the current object have run.
+    Stop,
+
+    /// The event stops immediately, without running any more listeners.
+    StopImmediate,
+}
+
+/// Represents data fields of an event that can be fired on an object that
+/// implements `IEventDispatcher`.
+#[derive(Clone, Collect, Debug)]
+#[collect(no_drop)]
+pub struct Event<'gc> {
+    /// Whether or not the event "bubbles" - fires on it's parents after it
+    /// fires on the child.
+    bubbles: bool,
+
+    /// Whether or not the event has a default response that an event handler
+    /// can request to not occur.
+    cancelable: bool,
+
+    /// Whether or not the event's default response has been cancelled.
+    cancelled: bool,
+
+    /// Whether or not event propagation has stopped.
+    propagation: PropagationMode,
+
+    /// The object currently having it's event handlers invoked.
+    current_target: Option<Object<'gc>>,
+
+    /// The current event phase.
+    event_phase: EventPhase,
+
+    /// The object this event was dispatched on.
+    target: Option<Object<'gc>>,
+
+    /// The name of the event being triggered.
+    event_type: AvmString<'gc>,
+
+    /// The data specific to the kind of event.
+    event_data: EventData<'gc>,
+}
+
+/// The data of an event that is specific to its class, such as the position
+/// of the mouse for a `MouseEvent`.
+#[derive(Clone, Collect, Debug)]
+#[collect(no_drop)]
+pub enum EventData<'gc> {
+    Empty,
+    Mouse(MouseEventData<'gc>),
+    Keyboard(KeyboardEventData),
+}
+
+/// The data of a `MouseEvent`.
+#[derive(Clone, Collect, Debug)]
+#[collect(no_drop)]
+pub struct MouseEventData<'gc> {
+    /// Where the event happened, in the coordinate space of the target.
+    pub local_x: f64,
+    pub local_y: f64,
+
+    /// The other object involved in a change of hovered object, such as the
+    /// object the mouse moved to for `mouseOut`.
+    pub related_object: Option<Object<'gc>>,
+
+    pub ctrl_key: bool,
+    pub alt_key: bool,
+    pub shift_key: bool,
+
+    /// Whether the primary mouse button is held down.
+    pub button_down: bool,
+
+    /// How many lines to scroll for each unit the mouse wheel is rotated.
+    pub delta: i32,
+}
+
+impl<'gc> Default for MouseEventData<'gc> {
+    fn default() -> Self {
+        Self {
+            local_x: NAN,
+            local_y: NAN,
+            related_object: None,
+            ctrl_key: false,
+            alt_key: false,
+            shift_key: false,
+            button_down: false,
+            delta: 0,
+        }
+    }
+}
+
+/// The data of a `KeyboardEvent`.
+#[derive(Clone, Collect, Debug, Default)]
+#[collect(require_static)]
+pub struct KeyboardEventData {
+    /// The character value of the key, in the current character set.
+    pub char_code: u32,
+
+    /// The Flash virtual key code of the key.
+    pub key_code: u32,
+
+    /// Which of several keys with the same key code was pressed, such as the
+    /// left or right Shift key.
+    pub key_location: u32,
+
+    pub ctrl_key: bool,
+    pub alt_key: bool,
+    pub shift_key: bool,
+}
+
+impl<'gc> Event<'gc> {
+    /// Construct a new event of a given type.
+    pub fn new<S>(event_type: S) -> Self
+    where
+        S: Into<AvmString<'gc>>,
+    {
+        Event {
+            bubbles: false,
+            cancelable: false,
+            cancelled: false,
+            propagation: PropagationMode::Allow,
+            current_target: None,
+            event_phase: EventPhase::AtTarget,
+            target: None,
+            event_type: event_type.into(),
+            event_data: EventData::Empty,
+        }
+    }
+
+    /// A fresh copy of this event, with the same type and flags, that hasn't
+    /// been dispatched yet.
+    pub fn fresh_copy(&self) -> Self {
+        let mut event = Event::new(self.event_type);
+        event.bubbles = self.bubbles;
+        event.cancelable = self.cancelable;
+        event.event_data = self.event_data.clone();
+        event
+    }
+
+    pub fn event_type(&self) -> AvmString<'gc> {
+        self.event_type
+    }
+
+    pub fn is_bubbling(&self) -> bool {
+        self.bubbles
+    }
+
+    pub fn set_bubbles(&mut self, bubbling: bool) {
+        self.bubbles = bubbling;
+    }
+
+    pub fn is_cancelable(&self) -> bool {
+        self.cancelable
+    }
+
+    pub fn set_cancelable(&mut self, cancelable: bool) {
+        self.cancelable = cancelable;
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled
+    }
+
+    /// Cancel the event's default response, if it can be cancelled.
+    pub fn cancel(&mut self) {
+        if self.cancelable {
+            self.cancelled = true;
+        }
+    }
+
+    pub fn is_propagation_stopped(&self) -> bool {
+        self.propagation != PropagationMode::Allow
+    }
+
+    pub fn stop_propagation(&mut self) {
+        if self.propagation != PropagationMode::StopImmediate {
+            self.propagation = PropagationMode::Stop;
+        }
+    }
+
+    pub fn is_propagation_stopped_immediately(&self) -> bool {
+        self.propagation == PropagationMode::StopImmediate
+    }
+
+    pub fn stop_immediate_propagation(&mut self) {
+        self.propagation = PropagationMode::StopImmediate;
+    }
+
+    pub fn phase(&self) -> EventPhase {
+        self.event_phase
+    }
+
+    pub fn set_phase(&mut self, phase: EventPhase) {
+        self.event_phase = phase;
+    }
+
+    pub fn target(&self) -> Option<Object<'gc>> {
+        self.target
+    }
+
+    pub fn set_target(&mut self, target: Object<'gc>) {
+        self.target = Some(target);
+    }
+
+    pub fn current_target(&self) -> Option<Object<'gc>> {
+        self.current_target
+    }
+
+    pub fn set_current_target(&mut self, current_target: Object<'gc>) {
+        self.current_target = Some(current_target);
+    }
+
+    pub fn event_data(&self) -> &EventData<'gc> {
+        &self.event_data
+    }
+
+    pub fn event_data_mut(&mut self) -> &mut EventData<'gc> {
+        &mut self.event_data
+    }
+
+    pub fn set_event_data(&mut self, event_data: EventData<'gc>) {
+        self.event_data = event_data;
+    }
+}
+
+/// A listener registered on an event dispatcher.
+#[derive(Clone, Collect, Debug)]
+#[collect(no_drop)]
+struct EventHandler<'gc> {
+    /// The event handler to call.
+    handler: Object<'gc>,
+
+    /// The priority of the event handler. Handlers with a higher priority run
+    /// first.
+    priority: i32,
+
+    /// Whether or not this handler runs in the capture phase, rather than
+    /// the target and bubble phases.
+    use_capture: bool,
+}
+
+/// A set of handlers organized by event type, priority, and order added.
+#[derive(Clone, Collect, Debug, Default)]
+#[collect(no_drop)]
+pub struct DispatchList<'gc>(FnvHashMap<AvmString<'gc>, Vec<EventHandler<'gc>>>);
+
+impl<'gc> DispatchList<'gc> {
+    /// Construct a new dispatch list with no handlers.
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Add an event handler to this dispatch list.
+    ///
+    /// Handlers run in order of priority, then in the order they were added.
+    /// A handler that is already registered for the same event and phase
+    /// isn't added again.
+    pub fn add_event_listener(
+        &mut self,
+        event: impl Into<AvmString<'gc>>,
+        priority: i32,
+        handler: Object<'gc>,
+        use_capture: bool,
+    ) {
+        let handlers = self.0.entry(event.into()).or_default();
+        let is_registered = handlers
+            .iter()
+            .any(|h| h.use_capture == use_capture && Object::ptr_eq(h.handler, handler));
+        if is_registered {
+            return;
+        }
+
+        let position = handlers
+            .iter()
+            .position(|h| h.priority < priority)
+            .unwrap_or(handlers.len());
+        handlers.insert(
+            position,
+            EventHandler {
+                handler,
+                priority,
+                use_capture,
+            },
+        );
+    }
+
+    /// Remove an event handler from this dispatch list.
+    pub fn remove_event_listener(
+        &mut self,
+        event: impl Into<AvmString<'gc>>,
+        handler: Object<'gc>,
+        use_capture: bool,
+    ) {
+        if let Some(handlers) = self.0.get_mut(&event.into()) {
+            handlers
+                .retain(|h| h.use_capture != use_capture || !Object::ptr_eq(h.handler, handler));
+        }
+    }
+
+    /// Determine if there are any handlers registered for a given event.
+    pub fn has_event_listener(&self, event: impl Into<AvmString<'gc>>) -> bool {
+        self.0
+            .get(&event.into())
+            .map_or(false, |handlers| !handlers.is_empty())
+    }
+
+    /// The handlers for a given event and phase, in the order they should
+    /// run.
+    pub fn event_handlers(
+        &self,
+        event: impl Into<AvmString<'gc>>,
+        use_capture: bool,
+    ) -> Vec<Object<'gc>> {
+        self.0
+            .get(&event.into())
+            .map(|handlers| {
+                handlers
+                    .iter()
+                    .filter(|h| h.use_capture == use_capture)
+                    .map(|h| h.handler)
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+}
+
+/// The name of the hidden property holding an event dispatcher's listeners.
+fn dispatch_list_name<'gc>() -> QName<'gc> {
+    QName::new(
+        Namespace::Private("flash.events:EventDispatcher".into()),
+        "dispatch_list",
+    )
+}
+
+/// Get the listeners registered on an event dispatcher.
+///
+/// The list is created the first time it's needed, so that objects created
+/// for existing display objects don't need to run the `EventDispatcher`
+/// constructor.
+pub fn dispatch_list<'gc>(
+    activation: &mut Activation<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Object<'gc>,
+) -> Result<DispatchObject<'gc>, Error<'gc>> {
+    let name = dispatch_list_name();
+    if this.has_own_property(&name) {
+        if let Some(dispatch) = this
+            .get_property(this, &name, activation, context)?
+            .as_object()?
+            .as_dispatch_object()
+        {
+            return Ok(dispatch);
+        }
+    }
+
+    let dispatch = DispatchObject::empty_list(context.gc_context);
+    this.define_value(
+        context.gc_context,
+        name,
+        Object::from(dispatch).into(),
+        Attribute::DontEnum | Attribute::DontDelete,
+    );
+
+    Ok(dispatch)
+}
+
+/// The object that an event bubbles up to from a display object: its
+/// parent, or the stage for objects at the top of the display list.
+pub fn parent_object<'gc>(
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    display_object: DisplayObject<'gc>,
+) -> Option<Object<'gc>> {
+    match display_object.parent() {
+        Some(parent) => Some(StageObject::for_display_object(context, parent)),
+        None if is_level(context, display_object) => Some(context.avm2.stage()),
+        None => None,
+    }
+}
+
+/// Whether a display object is one of the levels shown on the stage.
+fn is_level<'gc>(context: &UpdateContext<'_, 'gc, '_>, display_object: DisplayObject<'gc>) -> bool {
+    context
+        .levels
+        .values()
+        .any(|level| DisplayObject::ptr_eq(*level, display_object))
+}
+
+/// Whether a display object is on the display list of the stage.
+pub fn is_on_stage<'gc>(
+    context: &UpdateContext<'_, 'gc, '_>,
+    display_object: DisplayObject<'gc>,
+) -> bool {
+    let mut top = display_object;
+    while let Some(parent) = top.parent() {
+        top = parent;
+    }
+
+    is_level(context, top)
+}
+
+/// Call the handlers registered on one object for the current phase of an
+/// event.
+pub fn dispatch_event_to_target<'gc>(
+    activation: &mut Activation<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    target: Object<'gc>,
+    event: EventObject<'gc>,
+) -> Result<(), Error<'gc>> {
+    let (event_type, use_capture) = {
+        let mut evt = event.event_mut(context.gc_context);
+        evt.set_current_target(target);
+        (evt.event_type(), evt.phase() == EventPhase::Capturing)
+    };
+
+    // Listeners added or removed while the event is being handled don't
+    // affect this dispatch.
+    let handlers = dispatch_list(activation, context, target)?
+        .dispatch_list()
+        .event_handlers(event_type, use_capture);
+    for handler in handlers {
+        if event.event().is_propagation_stopped_immediately() {
+            break;
+        }
+
+        handler.call(
+            Some(target),
+            &[Object::from(event).into()],
+            activation,
+            context,
+        )?;
+    }
+
+    Ok(())
+}
+
+/// Dispatch an event on an object, and its ancestors on the display list.
+///
+/// The event is first captured by the ancestors of the target, from the
+/// stage down; then handled by the target itself; and finally, if it
+/// bubbles, handled by the ancestors again from the parent up.
+///
+/// Returns `false` if the event was cancelled.
+pub fn dispatch_event<'gc>(
+    activation: &mut Activation<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Object<'gc>,
+    event: EventObject<'gc>,
+) -> Result<bool, Error<'gc>> {
+    let mut ancestors = Vec::new();
+    let mut parent = this
+        .as_display_object()
+        .and_then(|dobj| parent_object(context, dobj));
+    while let Some(ancestor) = parent {
+        ancestors.push(ancestor);
+        parent = ancestor
+            .as_display_object()
+            .and_then(|dobj| parent_object(context, dobj));
+    }
+
+    event.event_mut(context.gc_context).set_target(this);
+
+    event
+        .event_mut(context.gc_context)
+        .set_phase(EventPhase::Capturing);
+    for ancestor in ancestors.iter().rev() {
+        if event.event().is_propagation_stopped() {
+            break;
+        }
+
+        dispatch_event_to_target(activation, context, *ancestor, event)?;
+    }
+
+    event
+        .event_mut(context.gc_context)
+        .set_phase(EventPhase::AtTarget);
+    if !event.event().is_propagation_stopped() {
+        dispatch_event_to_target(activation, context, this, event)?;
+    }
+
+    event
+        .event_mut(context.gc_context)
+        .set_phase(EventPhase::Bubbling);
+    if event.event().is_bubbling() {
+        for ancestor in ancestors.iter() {
+            if event.event().is_propagation_stopped() {
+                break;
+            }
+
+            dispatch_event_to_target(activation, context, *ancestor, event)?;
+        }
+    }
+
+    let is_cancelled = event.event().is_cancelled();
+    Ok(!is_cancelled)
+}
+
+/// Dispatch a new event of the given type on an object.
+pub fn dispatch_new_event<'gc>(
+    activation: &mut Activation<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Object<'gc>,
+    event_type: &'static str,
+    bubbles: bool,
+) -> Result<bool, Error<'gc>> {
+    let mut event = Event::new(event_type);
+    event.set_bubbles(bubbles);
+    let event = EventObject::from_event(context.gc_context, context.avm2.prototypes().event, event);
+
+    dispatch_event(activation, context, this, event)
+}
+
+/// Dispatch a non-bubbling event of the given type to a display object, and
+/// each of its descendants that is represented by an AVM2 object, as done
+/// for `addedToStage` and `removedFromStage`.
+pub fn dispatch_event_to_subtree<'gc>(
+    activation: &mut Activation<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    display_object: DisplayObject<'gc>,
+    event_type: &'static str,
+) -> Result<(), Error<'gc>> {
+    if let Value::Object(object) = display_object.object2() {
+        dispatch_new_event(activation, context, object, event_type, false)?;
+    }
+
+    let children: Vec<_> = display_object.children().collect();
+    for child in children {
+        dispatch_event_to_subtree(activation, context, child, event_type)?;
+    }
+
+    Ok(())
+}
+
+/// Events that are sent to every registered display object, rather than
+/// dispatched on a single target.
+pub fn is_broadcast_event(event_type: &str) -> bool {
+    matches!(
+        event_type,
+        "enterFrame" | "exitFrame" | "frameConstructed" | "render"
+    )
+}
@@ -0,0 +1,266 @@
+//! AVM2 executables.
+
+use crate::avm2::activation::Activation;
+use crate::avm2::method::{BytecodeMethod, Method, NativeMethod};
+use crate::avm2::names::QName;
+use crate::avm2::object::{Object, TObject};
+use crate::avm2::property::Attribute;
+use crate::avm2::scope::Scope;
+use crate::avm2::script_object::{ScriptObject, ScriptObjectData};
+use crate::avm2::value::Value;
+use crate::avm2::Error;
+use crate::context::UpdateContext;
+use gc_arena::{Collect, CollectionContext, Gc, GcCell, MutationContext};
+use std::fmt;
+
+/// Represents code written in AVM2 bytecode that can be executed by some
+/// means.
+#[derive(Clone, Collect)]
+#[collect(no_drop)]
+pub struct BytecodeExecutable<'gc> {
+    /// The method code to execute from a given ABC file.
+    method: Gc<'gc, BytecodeMethod<'gc>>,
+
+    /// The scope stack to pull variables from.
+    scope: Option<GcCell<'gc, Scope<'gc>>>,
+
+    /// The receiver that this function is always called with.
+    ///
+    /// If `None`, then the receiver provided by the caller is used. A
+    /// `Some` value indicates a bound executable.
+    receiver: Option<Object<'gc>>,
+
+    /// The superclass of the class that declared this method, which is
+    /// what `super` refers to.
+    superclass: Option<Object<'gc>>,
+}
+
+/// Represents code that can be executed by some means.
+#[derive(Clone)]
+pub enum Executable<'gc> {
+    /// Code defined in Ruffle's binary, along with the receiver it is bound
+    /// to, if any.
+    Native(NativeMethod<'gc>, Option<Object<'gc>>),
+
+    /// Code defined in a loaded ABC file.
+    Action(Gc<'gc, BytecodeExecutable<'gc>>),
+}
+
+unsafe impl<'gc> Collect for Executable<'gc> {
+    fn trace(&self, cc: CollectionContext) {
+        match self {
+            Self::Action(be) => be.trace(cc),
+            Self::Native(_nf, receiver) => receiver.trace(cc),
+        }
+    }
+}
+
+impl<'gc> Executable<'gc> {
+    /// Convert a method into an executable.
+    pub fn from_method(
+        method: Method<'gc>,
+        scope: Option<GcCell<'gc, Scope<'gc>>>,
+        receiver: Option<Object<'gc>>,
+        superclass: Option<Object<'gc>>,
+        mc: MutationContext<'gc, '_>,
+    ) -> Self {
+        match method {
+            Method::Native(nf) => Self::Native(nf, receiver),
+            Method::Entry(method) => Self::Action(Gc::allocate(
+                mc,
+                BytecodeExecutable {
+                    method,
+                    scope,
+                    receiver,
+                    superclass,
+                },
+            )),
+        }
+    }
+
+    /// Execute a method.
+    ///
+    /// The function will either be called directly if it is a Rust builtin,
+    /// or executed in a new activation if it is ABC code. Either way, the
+    /// value it returns is yielded.
+    ///
+    /// `unbound_receiver` is the `this` the caller provided, which is ignored
+    /// by bound executables. If neither exists, `this` is the global object.
+    pub fn exec(
+        &self,
+        unbound_receiver: Option<Object<'gc>>,
+        arguments: &[Value<'gc>],
+        activation: &mut Activation<'gc>,
+        context: &mut UpdateContext<'_, 'gc, '_>,
+    ) -> Result<Value<'gc>, Error<'gc>> {
+        match self {
+            Executable::Native(nf, receiver) => nf(
+                activation,
+                context,
+                receiver.or(unbound_receiver),
+                arguments,
+            ),
+            Executable::Action(bm) => {
+                let receiver = bm
+                    .receiver
+                    .or(unbound_receiver)
+                    .unwrap_or_else(|| context.avm2.globals());
+                let mut callee_activation = Activation::from_method(
+                    bm.method,
+                    bm.scope,
+                    receiver,
+                    arguments,
+                    bm.superclass,
+                    activation,
+                    context,
+                )?;
+
+                callee_activation.run_actions(bm.method, context)
+            }
+        }
+    }
+}
+
+impl<'gc> fmt::Debug for Executable<'gc> {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Action(be) => fmt
+                .debug_struct("Executable::Action")
+                .field("method", &be.method)
+                .finish(),
+            Self::Native(nf, _) => fmt
+                .debug_tuple("Executable::Native")
+                .field(&format!("{:p}", nf))
+                .finish(),
+        }
+    }
+}
+
+/// An Object which can be called to execute its function code.
+#[derive(Collect, Debug, Clone, Copy)]
+#[collect(no_drop)]
+pub struct FunctionObject<'gc>(GcCell<'gc, FunctionObjectData<'gc>>);
+
+#[derive(Collect, Debug, Clone)]
+#[collect(no_drop)]
+pub struct FunctionObjectData<'gc> {
+    /// Base script object
+    base: ScriptObject<'gc>,
+
+    /// Executable code
+    exec: Executable<'gc>,
+}
+
+impl<'gc> FunctionObject<'gc> {
+    /// Construct a function from a method.
+    ///
+    /// Methods of objects are bound to their object by passing it as the
+    /// receiver.
+    pub fn from_method(
+        mc: MutationContext<'gc, '_>,
+        method: Method<'gc>,
+        scope: Option<GcCell<'gc, Scope<'gc>>>,
+        fn_proto: Object<'gc>,
+        receiver: Option<Object<'gc>>,
+        superclass: Option<Object<'gc>>,
+    ) -> Object<'gc> {
+        let exec = Executable::from_method(method, scope, receiver, superclass, mc);
+
+        FunctionObject(GcCell::allocate(
+            mc,
+            FunctionObjectData {
+                base: ScriptObject::base_object(mc, Some(fn_proto)),
+                exec,
+            },
+        ))
+        .into()
+    }
+
+    /// Construct a function closure, as done by `newfunction`.
+    ///
+    /// Closures can also be used as constructors, so they get a `prototype`
+    /// object of their own.
+    pub fn from_function(
+        mc: MutationContext<'gc, '_>,
+        method: Method<'gc>,
+        scope: Option<GcCell<'gc, Scope<'gc>>>,
+        fn_proto: Object<'gc>,
+        object_proto: Object<'gc>,
+    ) -> Object<'gc> {
+        let function = Self::from_method(mc, method, scope, fn_proto, None, None);
+        let prototype = ScriptObject::object(mc, object_proto);
+
+        prototype.define_value(
+            mc,
+            QName::dynamic_name("constructor"),
+            function.into(),
+            Attribute::DontEnum.into(),
+        );
+        function.define_value(
+            mc,
+            QName::dynamic_name("prototype"),
+            prototype.into(),
+            Attribute::DontEnum.into(),
+        );
+
+        function
+    }
+
+    /// Construct a builtin function from a native method.
+    pub fn from_builtin(
+        mc: MutationContext<'gc, '_>,
+        nf: NativeMethod<'gc>,
+        fn_proto: Object<'gc>,
+    ) -> Object<'gc> {
+        Self::from_method(mc, nf.into(), None, fn_proto, None, None)
+    }
+}
+
+impl<'gc> TObject<'gc> for FunctionObject<'gc> {
+    fn base(&self) -> GcCell<'gc, ScriptObjectData<'gc>> {
+        self.0.read().base.base()
+    }
+
+    fn call(
+        &self,
+        receiver: Option<Object<'gc>>,
+        arguments: &[Value<'gc>],
+        activation: &mut Activation<'gc>,
+        context: &mut UpdateContext<'_, 'gc, '_>,
+    ) -> Result<Value<'gc>, Error<'gc>> {
+        let exec = self.0.read().exec.clone();
+
+        exec.exec(receiver, arguments, activation, context)
+    }
+
+    fn construct(
+        &self,
+        arguments: &[Value<'gc>],
+        activation: &mut Activation<'gc>,
+        context: &mut UpdateContext<'_, 'gc, '_>,
+    ) -> Result<Object<'gc>, Error<'gc>> {
+        let this: Object<'gc> = (*self).into();
+        let prototype = match this.get_property(
+            this,
+            &QName::dynamic_name("prototype"),
+            activation,
+            context,
+        )? {
+            Value::Object(prototype) => prototype,
+            _ => context.avm2.prototypes().object,
+        };
+
+        let instance = ScriptObject::object(context.gc_context, prototype);
+        let result = self.call(Some(instance), arguments, activation, context)?;
+
+        // A constructor returning an object replaces the new instance.
+        match result {
+            Value::Object(object) => Ok(object),
+            _ => Ok(instance),
+        }
+    }
+
+    fn as_executable(&self) -> Option<Executable<'gc>> {
+        Some(self.0.read().exec.clone())
+    }
+}
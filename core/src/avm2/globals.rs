@@ -0,0 +1,582 @@
+//! Global scope built-ins
+
+use crate::avm2::activation::Activation;
+use crate::avm2::class::Class;
+use crate::avm2::class_object::ClassObject;
+use crate::avm2::function::FunctionObject;
+use crate::avm2::method::NativeMethod;
+use crate::avm2::names::{Namespace, QName};
+use crate::avm2::object::{Object, TObject};
+use crate::avm2::property::Attribute;
+use crate::avm2::script_object::ScriptObject;
+use crate::avm2::value::{string_to_number, Value};
+use crate::avm2::Error;
+use crate::context::UpdateContext;
+use gc_arena::{Collect, GcCell, MutationContext};
+use std::f64::NAN;
+
+mod array;
+mod boolean;
+mod flash;
+mod function;
+mod int;
+mod number;
+mod object;
+mod string;
+mod uint;
+
+/// This structure represents all system builtins' prototypes.
+///
+/// These are used to construct builtin objects, such as boxed primitives and
+/// array literals, without looking their classes up on the global object.
+#[derive(Clone, Copy, Collect)]
+#[collect(no_drop)]
+pub struct SystemPrototypes<'gc> {
+    pub object: Object<'gc>,
+    pub function: Object<'gc>,
+    pub string: Object<'gc>,
+    pub boolean: Object<'gc>,
+    pub number: Object<'gc>,
+    pub int: Object<'gc>,
+    pub uint: Object<'gc>,
+    pub array: Object<'gc>,
+    pub event: Object<'gc>,
+    pub mouse_event: Object<'gc>,
+    pub keyboard_event: Object<'gc>,
+    pub display_object: Object<'gc>,
+    pub shape: Object<'gc>,
+    pub movie_clip: Object<'gc>,
+    pub stage: Object<'gc>,
+    pub graphics: Object<'gc>,
+    pub loader_info: Object<'gc>,
+}
+
+/// Define a builtin method on an object.
+///
+/// Builtin methods are not enumerable.
+fn method<'gc>(
+    mc: MutationContext<'gc, '_>,
+    object: Object<'gc>,
+    name: &'static str,
+    nf: NativeMethod<'gc>,
+    fn_proto: Object<'gc>,
+) {
+    object.define_value(
+        mc,
+        QName::dynamic_name(name),
+        FunctionObject::from_builtin(mc, nf, fn_proto).into(),
+        Attribute::DontEnum.into(),
+    );
+}
+
+/// Define a builtin getter on an object.
+fn getter<'gc>(
+    mc: MutationContext<'gc, '_>,
+    object: Object<'gc>,
+    name: &'static str,
+    nf: NativeMethod<'gc>,
+    fn_proto: Object<'gc>,
+) {
+    object.install_getter(
+        mc,
+        QName::dynamic_name(name),
+        FunctionObject::from_builtin(mc, nf, fn_proto),
+    );
+}
+
+/// Define a builtin setter on an object.
+fn setter<'gc>(
+    mc: MutationContext<'gc, '_>,
+    object: Object<'gc>,
+    name: &'static str,
+    nf: NativeMethod<'gc>,
+    fn_proto: Object<'gc>,
+) {
+    object.install_setter(
+        mc,
+        QName::dynamic_name(name),
+        FunctionObject::from_builtin(mc, nf, fn_proto),
+    );
+}
+
+/// Define a builtin class on the global object, under the name of the class.
+fn class<'gc>(
+    mc: MutationContext<'gc, '_>,
+    globals: Object<'gc>,
+    class: GcCell<'gc, Class<'gc>>,
+    superclass: Option<Object<'gc>>,
+    prototype: Object<'gc>,
+    object_proto: Object<'gc>,
+) -> Object<'gc> {
+    let name = *class.read().name();
+    let class_object = ClassObject::from_builtin(mc, class, superclass, prototype, object_proto);
+
+    globals.install_slot(mc, name, 0, class_object.into(), true);
+
+    class_object
+}
+
+/// Define what calling a builtin class as a function does.
+fn set_native_call<'gc>(
+    mc: MutationContext<'gc, '_>,
+    class_object: Object<'gc>,
+    native_call: NativeMethod<'gc>,
+) {
+    if let Some(class_object) = class_object.as_class_object() {
+        class_object.set_native_call(mc, native_call);
+    }
+}
+
+/// Implements `trace`
+fn trace<'gc>(
+    activation: &mut Activation<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    _this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let mut message = String::new();
+    for (i, arg) in args.iter().enumerate() {
+        if i > 0 {
+            message.push(' ');
+        }
+
+        message.push_str(&arg.coerce_to_string(activation, context)?);
+    }
+
+    context.log.avm_trace(&message);
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `isNaN`
+fn is_nan<'gc>(
+    activation: &mut Activation<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    _this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let value = args
+        .get(0)
+        .unwrap_or(&Value::Undefined)
+        .coerce_to_number(activation, context)?;
+
+    Ok(value.is_nan().into())
+}
+
+/// Implements `isFinite`
+fn is_finite<'gc>(
+    activation: &mut Activation<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    _this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let value = args
+        .get(0)
+        .unwrap_or(&Value::Undefined)
+        .coerce_to_number(activation, context)?;
+
+    Ok(value.is_finite().into())
+}
+
+/// Implements `parseInt`
+fn parse_int<'gc>(
+    activation: &mut Activation<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    _this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let string = args
+        .get(0)
+        .unwrap_or(&Value::Undefined)
+        .coerce_to_string(activation, context)?;
+    let mut radix = match args.get(1) {
+        None | Some(Value::Undefined) => 0,
+        Some(radix) => radix.coerce_to_i32(activation, context)?,
+    };
+
+    let mut string = string.trim_start();
+    let is_negative = string.starts_with('-');
+    if is_negative || string.starts_with('+') {
+        string = &string[1..];
+    }
+
+    if (radix == 0 || radix == 16) && (string.starts_with("0x") || string.starts_with("0X")) {
+        string = &string[2..];
+        radix = 16;
+    } else if radix == 0 {
+        radix = 10;
+    }
+
+    if !(2..=36).contains(&radix) {
+        return Ok(NAN.into());
+    }
+
+    let mut result: Option<f64> = None;
+    for c in string.chars() {
+        match c.to_digit(radix as u32) {
+            Some(digit) => {
+                result = Some(result.unwrap_or(0.0) * f64::from(radix) + f64::from(digit))
+            }
+            None => break,
+        }
+    }
+
+    Ok(match result {
+        Some(result) if is_negative => -result,
+        Some(result) => result,
+        None => NAN,
+    }
+    .into())
+}
+
+/// Implements `parseFloat`
+fn parse_float<'gc>(
+    activation: &mut Activation<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    _this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let string = args
+        .get(0)
+        .unwrap_or(&Value::Undefined)
+        .coerce_to_string(activation, context)?;
+    let string = string.trim_start();
+
+    // Parse the longest prefix that is a valid number.
+    let mut end = 0;
+    let mut seen_digit = false;
+    let mut seen_dot = false;
+    let mut seen_exponent = false;
+    for (i, c) in string.char_indices() {
+        match c {
+            '0'..='9' => {
+                seen_digit = true;
+                end = i + 1;
+            }
+            '+' | '-' if i == 0 => {}
+            '+' | '-' if seen_exponent && string[..i].ends_with(|c| c == 'e' || c == 'E') => {}
+            '.' if !seen_dot && !seen_exponent => seen_dot = true,
+            'e' | 'E' if seen_digit && !seen_exponent => seen_exponent = true,
+            _ => break,
+        }
+    }
+
+    if !seen_digit {
+        let rest = string.trim_start_matches(|c| c == '+' || c == '-');
+        if rest.starts_with("Infinity") {
+            let infinity = if string.starts_with('-') {
+                -std::f64::INFINITY
+            } else {
+                std::f64::INFINITY
+            };
+            return Ok(infinity.into());
+        }
+
+        return Ok(NAN.into());
+    }
+
+    Ok(string_to_number(&string[..end]).into())
+}
+
+/// Construct the global object, along with all of the builtin classes and
+/// functions that live on it.
+pub fn load_player_globals<'gc>(
+    mc: MutationContext<'gc, '_>,
+) -> (Object<'gc>, SystemPrototypes<'gc>) {
+    let object_proto = ScriptObject::bare_object(mc);
+    let fn_proto = function::create_proto(mc, object_proto);
+    object::fill_proto(mc, object_proto, fn_proto);
+
+    let globals = ScriptObject::object(mc, object_proto);
+
+    let object_class = class(
+        mc,
+        globals,
+        object::create_class(mc),
+        None,
+        object_proto,
+        object_proto,
+    );
+    class(
+        mc,
+        globals,
+        function::create_class(mc),
+        Some(object_class),
+        fn_proto,
+        object_proto,
+    );
+
+    let string_proto = string::create_proto(mc, object_proto, fn_proto);
+    let string_class = class(
+        mc,
+        globals,
+        string::create_class(mc),
+        Some(object_class),
+        string_proto,
+        object_proto,
+    );
+    set_native_call(mc, string_class, string::native_call);
+
+    let boolean_proto = boolean::create_proto(mc, object_proto, fn_proto);
+    let boolean_class = class(
+        mc,
+        globals,
+        boolean::create_class(mc),
+        Some(object_class),
+        boolean_proto,
+        object_proto,
+    );
+    set_native_call(mc, boolean_class, boolean::native_call);
+
+    let number_proto = number::create_proto(mc, object_proto, fn_proto);
+    let number_class = class(
+        mc,
+        globals,
+        number::create_class(mc),
+        Some(object_class),
+        number_proto,
+        object_proto,
+    );
+    set_native_call(mc, number_class, number::native_call);
+    number::define_number_constants(mc, number_class);
+
+    let int_proto = int::create_proto(mc, object_proto, fn_proto);
+    let int_class = class(
+        mc,
+        globals,
+        int::create_class(mc),
+        Some(object_class),
+        int_proto,
+        object_proto,
+    );
+    set_native_call(mc, int_class, int::native_call);
+    number::define_constants(mc, int_class, i32::MIN.into(), i32::MAX.into());
+
+    let uint_proto = uint::create_proto(mc, object_proto, fn_proto);
+    let uint_class = class(
+        mc,
+        globals,
+        uint::create_class(mc),
+        Some(object_class),
+        uint_proto,
+        object_proto,
+    );
+    set_native_call(mc, uint_class, uint::native_call);
+    number::define_constants(mc, uint_class, 0u32.into(), u32::MAX.into());
+
+    let array_proto = array::create_proto(mc, object_proto, fn_proto);
+    class(
+        mc,
+        globals,
+        array::create_class(mc),
+        Some(object_class),
+        array_proto,
+        object_proto,
+    );
+
+    method(mc, globals, "trace", trace, fn_proto);
+    method(mc, globals, "isNaN", is_nan, fn_proto);
+    method(mc, globals, "isFinite", is_finite, fn_proto);
+    method(mc, globals, "parseInt", parse_int, fn_proto);
+    method(mc, globals, "parseFloat", parse_float, fn_proto);
+
+    let constants = Attribute::DontEnum | Attribute::DontDelete | Attribute::ReadOnly;
+    globals.define_value(mc, QName::dynamic_name("NaN"), NAN.into(), constants);
+    globals.define_value(
+        mc,
+        QName::dynamic_name("Infinity"),
+        std::f64::INFINITY.into(),
+        constants,
+    );
+    globals.define_value(
+        mc,
+        QName::dynamic_name("undefined"),
+        Value::Undefined,
+        constants,
+    );
+
+    // package `flash.events`
+    let event_dispatcher_proto =
+        flash::events::eventdispatcher::create_proto(mc, object_proto, fn_proto);
+    let event_dispatcher_class = class(
+        mc,
+        globals,
+        flash::events::eventdispatcher::create_class(mc),
+        Some(object_class),
+        event_dispatcher_proto,
+        object_proto,
+    );
+    let event_proto = flash::events::event::create_proto(mc, object_proto, fn_proto);
+    let event_class = class(
+        mc,
+        globals,
+        flash::events::event::create_class(mc),
+        Some(object_class),
+        event_proto,
+        object_proto,
+    );
+    flash::events::event::define_event_types(mc, event_class);
+    let event_phase_class = class(
+        mc,
+        globals,
+        flash::events::eventphase::create_class(mc),
+        Some(object_class),
+        ScriptObject::object(mc, object_proto),
+        object_proto,
+    );
+    flash::events::eventphase::define_phases(mc, event_phase_class);
+    let mouse_event_proto = flash::events::mouseevent::create_proto(mc, event_proto, fn_proto);
+    let mouse_event_class = class(
+        mc,
+        globals,
+        flash::events::mouseevent::create_class(mc),
+        Some(event_class),
+        mouse_event_proto,
+        object_proto,
+    );
+    flash::events::mouseevent::define_event_types(mc, mouse_event_class);
+    let keyboard_event_proto =
+        flash::events::keyboardevent::create_proto(mc, event_proto, fn_proto);
+    let keyboard_event_class = class(
+        mc,
+        globals,
+        flash::events::keyboardevent::create_class(mc),
+        Some(event_class),
+        keyboard_event_proto,
+        object_proto,
+    );
+    flash::events::keyboardevent::define_event_types(mc, keyboard_event_class);
+
+    // package `flash.display`
+    let display_object_proto =
+        flash::display::displayobject::create_proto(mc, event_dispatcher_proto, fn_proto);
+    let display_object_class = class(
+        mc,
+        globals,
+        flash::display::displayobject::create_class(mc),
+        Some(event_dispatcher_class),
+        display_object_proto,
+        object_proto,
+    );
+    let shape_proto = flash::display::shape::create_proto(mc, display_object_proto, fn_proto);
+    class(
+        mc,
+        globals,
+        flash::display::shape::create_class(mc),
+        Some(display_object_class),
+        shape_proto,
+        object_proto,
+    );
+    let interactive_object_proto =
+        flash::display::interactiveobject::create_proto(mc, display_object_proto, fn_proto);
+    let interactive_object_class = class(
+        mc,
+        globals,
+        flash::display::interactiveobject::create_class(mc),
+        Some(display_object_class),
+        interactive_object_proto,
+        object_proto,
+    );
+    let container_proto = flash::display::displayobjectcontainer::create_proto(
+        mc,
+        interactive_object_proto,
+        fn_proto,
+    );
+    let container_class = class(
+        mc,
+        globals,
+        flash::display::displayobjectcontainer::create_class(mc),
+        Some(interactive_object_class),
+        container_proto,
+        object_proto,
+    );
+    let sprite_proto = flash::display::sprite::create_proto(mc, container_proto, fn_proto);
+    let sprite_class = class(
+        mc,
+        globals,
+        flash::display::sprite::create_class(mc),
+        Some(container_class),
+        sprite_proto,
+        object_proto,
+    );
+    let movie_clip_proto = flash::display::movieclip::create_proto(mc, sprite_proto, fn_proto);
+    class(
+        mc,
+        globals,
+        flash::display::movieclip::create_class(mc),
+        Some(sprite_class),
+        movie_clip_proto,
+        object_proto,
+    );
+    let stage_proto = flash::display::stage::create_proto(mc, container_proto, fn_proto);
+    class(
+        mc,
+        globals,
+        flash::display::stage::create_class(mc),
+        Some(container_class),
+        stage_proto,
+        object_proto,
+    );
+
+    let graphics_proto = flash::display::graphics::create_proto(mc, object_proto, fn_proto);
+    class(
+        mc,
+        globals,
+        flash::display::graphics::create_class(mc),
+        Some(object_class),
+        graphics_proto,
+        object_proto,
+    );
+
+    let loader_info_proto =
+        flash::display::loaderinfo::create_proto(mc, event_dispatcher_proto, fn_proto);
+    class(
+        mc,
+        globals,
+        flash::display::loaderinfo::create_class(mc),
+        Some(event_dispatcher_class),
+        loader_info_proto,
+        object_proto,
+    );
+
+    (
+        globals,
+        SystemPrototypes {
+            object: object_proto,
+            function: fn_proto,
+            string: string_proto,
+            boolean: boolean_proto,
+            number: number_proto,
+            int: int_proto,
+            uint: uint_proto,
+            array: array_proto,
+            event: event_proto,
+            mouse_event: mouse_event_proto,
+            keyboard_event: keyboard_event_proto,
+            display_object: display_object_proto,
+            shape: shape_proto,
+            movie_clip: movie_clip_proto,
+            stage: stage_proto,
+            graphics: graphics_proto,
+            loader_info: loader_info_proto,
+        },
+    )
+}
+
+/// The name of a builtin class in the public namespace.
+fn public_name<'gc>(name: &'static str) -> QName<'gc> {
+    QName::new(Namespace::public(), name)
+}
+
+/// Coerce the first argument of a call, or `undefined` if there isn't one.
+fn first_arg<'gc>(args: &[Value<'gc>]) -> Value<'gc> {
+    args.get(0).cloned().unwrap_or(Value::Undefined)
+}
+
+/// Replace the value boxed by an instance of a primitive class, as done by
+/// the instance initializers of those classes.
+fn set_primitive<'gc>(mc: MutationContext<'gc, '_>, this: Option<Object<'gc>>, value: Value<'gc>) {
+    if let Some(Object::PrimitiveObject(this)) = this {
+        this.set_primitive(mc, value);
+    }
+}
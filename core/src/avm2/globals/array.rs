@@ -0,0 +1,333 @@
+//! `Array` class impl
+
+use crate::avm2::activation::Activation;
+use crate::avm2::array_object::ArrayObject;
+use crate::avm2::class::Class;
+use crate::avm2::globals::{method, public_name};
+use crate::avm2::method::Method;
+use crate::avm2::object::{Object, TObject};
+use crate::avm2::value::Value;
+use crate::avm2::{AvmString, Error};
+use crate::context::UpdateContext;
+use gc_arena::{GcCell, MutationContext};
+
+/// Implements `Array`'s instance initializer.
+///
+/// A single numeric argument is the length of the new array; any other
+/// arguments are its elements.
+fn instance_init<'gc>(
+    _activation: &mut Activation<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let array = match this.and_then(|this| this.as_array_object()) {
+        Some(array) => array,
+        None => return Ok(Value::Undefined),
+    };
+
+    match args {
+        [Value::Number(length)] => {
+            if *length < 0.0 || length.fract() != 0.0 || *length > f64::from(std::u32::MAX) {
+                return Err(Error::RangeError(format!(
+                    "Error #1005: Array index is not a positive integer ({}).",
+                    length
+                )));
+            }
+
+            array.set_length(context.gc_context, *length as usize);
+        }
+        [Value::Integer(length)] => {
+            if *length < 0 {
+                return Err(Error::RangeError(format!(
+                    "Error #1005: Array index is not a positive integer ({}).",
+                    length
+                )));
+            }
+
+            array.set_length(context.gc_context, *length as usize);
+        }
+        [Value::Unsigned(length)] => array.set_length(context.gc_context, *length as usize),
+        _ => array.set_elements(context.gc_context, args.to_vec()),
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `Array`'s class initializer.
+fn class_init<'gc>(
+    _activation: &mut Activation<'gc>,
+    _context: &mut UpdateContext<'_, 'gc, '_>,
+    _this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    Ok(Value::Undefined)
+}
+
+/// Allocates instances of `Array`.
+fn allocator<'gc>(
+    proto: Object<'gc>,
+    _activation: &mut Activation<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+) -> Result<Object<'gc>, Error<'gc>> {
+    Ok(ArrayObject::from_elements(
+        context.gc_context,
+        proto,
+        Vec::new(),
+    ))
+}
+
+/// The array `this` refers to.
+fn this_array(this: Option<Object<'_>>) -> Result<ArrayObject<'_>, Error<'_>> {
+    this.and_then(|this| this.as_array_object()).ok_or_else(|| {
+        Error::TypeError("Array method called on a value that isn't an Array".to_string())
+    })
+}
+
+/// Implements `Array.prototype.push`
+fn push<'gc>(
+    _activation: &mut Activation<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let array = this_array(this)?;
+    let mut elements = array.elements();
+    elements.extend_from_slice(args);
+    let length = elements.len();
+    array.set_elements(context.gc_context, elements);
+
+    Ok((length as u32).into())
+}
+
+/// Implements `Array.prototype.pop`
+fn pop<'gc>(
+    _activation: &mut Activation<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let array = this_array(this)?;
+    let mut elements = array.elements();
+    let popped = elements.pop().unwrap_or(Value::Undefined);
+    array.set_elements(context.gc_context, elements);
+
+    Ok(popped)
+}
+
+/// Implements `Array.prototype.shift`
+fn shift<'gc>(
+    _activation: &mut Activation<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let array = this_array(this)?;
+    let mut elements = array.elements();
+    if elements.is_empty() {
+        return Ok(Value::Undefined);
+    }
+
+    let shifted = elements.remove(0);
+    array.set_elements(context.gc_context, elements);
+
+    Ok(shifted)
+}
+
+/// Implements `Array.prototype.unshift`
+fn unshift<'gc>(
+    _activation: &mut Activation<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let array = this_array(this)?;
+    let mut elements = args.to_vec();
+    elements.extend(array.elements());
+    let length = elements.len();
+    array.set_elements(context.gc_context, elements);
+
+    Ok((length as u32).into())
+}
+
+/// Implements `Array.prototype.join`
+fn join<'gc>(
+    activation: &mut Activation<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let array = this_array(this)?;
+    let separator = match args.get(0) {
+        None | Some(Value::Undefined) => ",".into(),
+        Some(separator) => separator.coerce_to_string(activation, context)?,
+    };
+
+    let mut joined = String::new();
+    for (i, element) in array.elements().iter().enumerate() {
+        if i > 0 {
+            joined.push_str(&separator);
+        }
+
+        // Holes and nulls are printed as empty strings.
+        match element {
+            Value::Undefined | Value::Null => {}
+            element => joined.push_str(&element.coerce_to_string(activation, context)?),
+        }
+    }
+
+    Ok(AvmString::new(context.gc_context, joined).into())
+}
+
+/// Implements `Array.prototype.toString`
+fn to_string<'gc>(
+    activation: &mut Activation<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    join(activation, context, this, &[])
+}
+
+/// Implements `Array.prototype.indexOf`
+fn index_of<'gc>(
+    activation: &mut Activation<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let array = this_array(this)?;
+    let search = args.get(0).cloned().unwrap_or(Value::Undefined);
+    let start = match args.get(1) {
+        None | Some(Value::Undefined) => 0,
+        Some(start) => start.coerce_to_i32(activation, context)?.max(0) as usize,
+    };
+
+    let found = array
+        .elements()
+        .iter()
+        .enumerate()
+        .skip(start)
+        .find(|(_, element)| element.strict_eq(&search))
+        .map(|(i, _)| i as i32)
+        .unwrap_or(-1);
+
+    Ok(found.into())
+}
+
+/// Implements `Array.prototype.concat`
+fn concat<'gc>(
+    _activation: &mut Activation<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let mut elements = this_array(this)?.elements();
+    for arg in args {
+        match arg
+            .as_object()
+            .ok()
+            .and_then(|object| object.as_array_object())
+        {
+            Some(array) => elements.extend(array.elements()),
+            None => elements.push(arg.clone()),
+        }
+    }
+
+    Ok(ArrayObject::from_elements(
+        context.gc_context,
+        context.avm2.prototypes().array,
+        elements,
+    )
+    .into())
+}
+
+/// Implements `Array.prototype.slice`
+fn slice<'gc>(
+    activation: &mut Activation<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let elements = this_array(this)?.elements();
+    let length = elements.len();
+    let wrap_index = |i: i32| {
+        if i < 0 {
+            length.saturating_sub((-i64::from(i)) as usize)
+        } else {
+            (i as usize).min(length)
+        }
+    };
+
+    let start = match args.get(0) {
+        None | Some(Value::Undefined) => 0,
+        Some(start) => wrap_index(start.coerce_to_i32(activation, context)?),
+    };
+    let end = match args.get(1) {
+        None | Some(Value::Undefined) => length,
+        Some(end) => wrap_index(end.coerce_to_i32(activation, context)?),
+    };
+
+    let sliced = if start < end {
+        elements[start..end].to_vec()
+    } else {
+        Vec::new()
+    };
+
+    Ok(
+        ArrayObject::from_elements(context.gc_context, context.avm2.prototypes().array, sliced)
+            .into(),
+    )
+}
+
+/// Implements `Array.prototype.reverse`
+fn reverse<'gc>(
+    _activation: &mut Activation<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let array = this_array(this)?;
+    let mut elements = array.elements();
+    elements.reverse();
+    array.set_elements(context.gc_context, elements);
+
+    Ok(this.map(Value::Object).unwrap_or(Value::Undefined))
+}
+
+/// Create `Array.prototype`.
+pub fn create_proto<'gc>(
+    mc: MutationContext<'gc, '_>,
+    object_proto: Object<'gc>,
+    fn_proto: Object<'gc>,
+) -> Object<'gc> {
+    let array_proto = ArrayObject::from_elements(mc, object_proto, Vec::new());
+
+    method(mc, array_proto, "push", push, fn_proto);
+    method(mc, array_proto, "pop", pop, fn_proto);
+    method(mc, array_proto, "shift", shift, fn_proto);
+    method(mc, array_proto, "unshift", unshift, fn_proto);
+    method(mc, array_proto, "join", join, fn_proto);
+    method(mc, array_proto, "toString", to_string, fn_proto);
+    method(mc, array_proto, "indexOf", index_of, fn_proto);
+    method(mc, array_proto, "concat", concat, fn_proto);
+    method(mc, array_proto, "slice", slice, fn_proto);
+    method(mc, array_proto, "reverse", reverse, fn_proto);
+
+    array_proto
+}
+
+/// Construct `Array`'s class.
+pub fn create_class<'gc>(mc: MutationContext<'gc, '_>) -> GcCell<'gc, Class<'gc>> {
+    let class = Class::new(
+        public_name("Array"),
+        Some(public_name("Object").into()),
+        Method::Native(instance_init),
+        Method::Native(class_init),
+        mc,
+    );
+
+    class.write(mc).set_instance_allocator(allocator);
+
+    class
+}
@@ -0,0 +1,117 @@
+//! `Boolean` class impl
+
+use crate::avm2::activation::Activation;
+use crate::avm2::class::Class;
+use crate::avm2::globals::{first_arg, method, public_name, set_primitive};
+use crate::avm2::method::Method;
+use crate::avm2::object::{Object, TObject};
+use crate::avm2::primitive_object::PrimitiveObject;
+use crate::avm2::value::Value;
+use crate::avm2::Error;
+use crate::context::UpdateContext;
+use gc_arena::{GcCell, MutationContext};
+
+/// Implements `Boolean`'s instance initializer.
+fn instance_init<'gc>(
+    _activation: &mut Activation<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let value = first_arg(args).coerce_to_boolean();
+    set_primitive(context.gc_context, this, value.into());
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `Boolean`'s class initializer.
+fn class_init<'gc>(
+    _activation: &mut Activation<'gc>,
+    _context: &mut UpdateContext<'_, 'gc, '_>,
+    _this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    Ok(Value::Undefined)
+}
+
+/// Implements calling `Boolean` as a function, which converts its argument.
+pub fn native_call<'gc>(
+    _activation: &mut Activation<'gc>,
+    _context: &mut UpdateContext<'_, 'gc, '_>,
+    _this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    Ok(first_arg(args).coerce_to_boolean().into())
+}
+
+/// Allocates instances of `Boolean`.
+fn allocator<'gc>(
+    proto: Object<'gc>,
+    _activation: &mut Activation<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+) -> Result<Object<'gc>, Error<'gc>> {
+    Ok(PrimitiveObject::with_proto(
+        context.gc_context,
+        false.into(),
+        proto,
+    ))
+}
+
+/// The boolean value of `this`.
+fn this_boolean(this: Option<Object<'_>>) -> Result<bool, Error<'_>> {
+    match this.and_then(|this| this.as_primitive()) {
+        Some(Value::Bool(value)) => Ok(value),
+        _ => Err(Error::TypeError(
+            "Boolean method called on a value that isn't a Boolean".to_string(),
+        )),
+    }
+}
+
+/// Implements `Boolean.prototype.toString`
+fn to_string<'gc>(
+    _activation: &mut Activation<'gc>,
+    _context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    Ok(if this_boolean(this)? { "true" } else { "false" }.into())
+}
+
+/// Implements `Boolean.prototype.valueOf`
+fn value_of<'gc>(
+    _activation: &mut Activation<'gc>,
+    _context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    Ok(this_boolean(this)?.into())
+}
+
+/// Create `Boolean.prototype`.
+pub fn create_proto<'gc>(
+    mc: MutationContext<'gc, '_>,
+    object_proto: Object<'gc>,
+    fn_proto: Object<'gc>,
+) -> Object<'gc> {
+    let boolean_proto = PrimitiveObject::with_proto(mc, false.into(), object_proto);
+
+    method(mc, boolean_proto, "toString", to_string, fn_proto);
+    method(mc, boolean_proto, "valueOf", value_of, fn_proto);
+
+    boolean_proto
+}
+
+/// Construct `Boolean`'s class.
+pub fn create_class<'gc>(mc: MutationContext<'gc, '_>) -> GcCell<'gc, Class<'gc>> {
+    let class = Class::new(
+        public_name("Boolean"),
+        Some(public_name("Object").into()),
+        Method::Native(instance_init),
+        Method::Native(class_init),
+        mc,
+    );
+
+    class.write(mc).set_instance_allocator(allocator);
+
+    class
+}
@@ -0,0 +1,4 @@
+//! `flash` namespace
+
+pub mod display;
+pub mod events;
@@ -0,0 +1,11 @@
+//! `flash.display` namespace
+
+pub mod displayobject;
+pub mod displayobjectcontainer;
+pub mod graphics;
+pub mod interactiveobject;
+pub mod loaderinfo;
+pub mod movieclip;
+pub mod shape;
+pub mod sprite;
+pub mod stage;
@@ -0,0 +1,459 @@
+//! `flash.display.DisplayObject` builtin/prototype
+
+use crate::avm2::activation::Activation;
+use crate::avm2::class::Class;
+use crate::avm2::events::{is_on_stage, parent_object};
+use crate::avm2::globals::{first_arg, getter, setter};
+use crate::avm2::method::Method;
+use crate::avm2::names::{Namespace, QName};
+use crate::avm2::object::{Object, TObject};
+use crate::avm2::script_object::ScriptObject;
+use crate::avm2::stage_object::StageObject;
+use crate::avm2::value::Value;
+use crate::avm2::{AvmString, Error};
+use crate::context::UpdateContext;
+use crate::display_object::{DisplayObject, TDisplayObject};
+use gc_arena::{GcCell, MutationContext};
+
+/// Implements `flash.display.DisplayObject`'s instance constructor.
+fn instance_init<'gc>(
+    _activation: &mut Activation<'gc>,
+    _context: &mut UpdateContext<'_, 'gc, '_>,
+    _this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    Ok(Value::Undefined)
+}
+
+/// Implements `flash.display.DisplayObject`'s class constructor.
+fn class_init<'gc>(
+    _activation: &mut Activation<'gc>,
+    _context: &mut UpdateContext<'_, 'gc, '_>,
+    _this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    Ok(Value::Undefined)
+}
+
+/// Allocates instances of `DisplayObject` and its subclasses, which are
+/// bound to their display object once it exists.
+fn allocator<'gc>(
+    proto: Object<'gc>,
+    _activation: &mut Activation<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+) -> Result<Object<'gc>, Error<'gc>> {
+    Ok(StageObject::unbound(context.gc_context, proto))
+}
+
+/// The display object `this` is bound to, if any.
+fn this_display_object<'gc>(this: Option<Object<'gc>>) -> Option<DisplayObject<'gc>> {
+    this.and_then(|this| this.as_display_object())
+}
+
+/// Implements `DisplayObject.x`'s getter
+fn x<'gc>(
+    _activation: &mut Activation<'gc>,
+    _context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    Ok(this_display_object(this)
+        .map(|dobj| dobj.x().into())
+        .unwrap_or(Value::Undefined))
+}
+
+/// Implements `DisplayObject.x`'s setter
+fn set_x<'gc>(
+    activation: &mut Activation<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    if let Some(mut dobj) = this_display_object(this) {
+        let value = first_arg(args).coerce_to_number(activation, context)?;
+        dobj.set_x(context.gc_context, value);
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `DisplayObject.y`'s getter
+fn y<'gc>(
+    _activation: &mut Activation<'gc>,
+    _context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    Ok(this_display_object(this)
+        .map(|dobj| dobj.y().into())
+        .unwrap_or(Value::Undefined))
+}
+
+/// Implements `DisplayObject.y`'s setter
+fn set_y<'gc>(
+    activation: &mut Activation<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    if let Some(mut dobj) = this_display_object(this) {
+        let value = first_arg(args).coerce_to_number(activation, context)?;
+        dobj.set_y(context.gc_context, value);
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `DisplayObject.scaleX`'s getter
+fn scale_x<'gc>(
+    _activation: &mut Activation<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    Ok(this_display_object(this)
+        .map(|mut dobj| dobj.scale_x(context.gc_context).into())
+        .unwrap_or(Value::Undefined))
+}
+
+/// Implements `DisplayObject.scaleX`'s setter
+fn set_scale_x<'gc>(
+    activation: &mut Activation<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    if let Some(mut dobj) = this_display_object(this) {
+        let value = first_arg(args).coerce_to_number(activation, context)?;
+        dobj.set_scale_x(context.gc_context, value);
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `DisplayObject.scaleY`'s getter
+fn scale_y<'gc>(
+    _activation: &mut Activation<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    Ok(this_display_object(this)
+        .map(|mut dobj| dobj.scale_y(context.gc_context).into())
+        .unwrap_or(Value::Undefined))
+}
+
+/// Implements `DisplayObject.scaleY`'s setter
+fn set_scale_y<'gc>(
+    activation: &mut Activation<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    if let Some(mut dobj) = this_display_object(this) {
+        let value = first_arg(args).coerce_to_number(activation, context)?;
+        dobj.set_scale_y(context.gc_context, value);
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `DisplayObject.alpha`'s getter
+fn alpha<'gc>(
+    _activation: &mut Activation<'gc>,
+    _context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    Ok(this_display_object(this)
+        .map(|dobj| dobj.alpha().into())
+        .unwrap_or(Value::Undefined))
+}
+
+/// Implements `DisplayObject.alpha`'s setter
+fn set_alpha<'gc>(
+    activation: &mut Activation<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    if let Some(dobj) = this_display_object(this) {
+        let value = first_arg(args).coerce_to_number(activation, context)?;
+        dobj.set_alpha(context.gc_context, value);
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `DisplayObject.width`'s getter
+fn width<'gc>(
+    _activation: &mut Activation<'gc>,
+    _context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    Ok(this_display_object(this)
+        .map(|dobj| dobj.width().into())
+        .unwrap_or(Value::Undefined))
+}
+
+/// Implements `DisplayObject.width`'s setter
+fn set_width<'gc>(
+    activation: &mut Activation<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    if let Some(mut dobj) = this_display_object(this) {
+        let value = first_arg(args).coerce_to_number(activation, context)?;
+        dobj.set_width(context.gc_context, value);
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `DisplayObject.height`'s getter
+fn height<'gc>(
+    _activation: &mut Activation<'gc>,
+    _context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    Ok(this_display_object(this)
+        .map(|dobj| dobj.height().into())
+        .unwrap_or(Value::Undefined))
+}
+
+/// Implements `DisplayObject.height`'s setter
+fn set_height<'gc>(
+    activation: &mut Activation<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    if let Some(mut dobj) = this_display_object(this) {
+        let value = first_arg(args).coerce_to_number(activation, context)?;
+        dobj.set_height(context.gc_context, value);
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `DisplayObject.rotation`'s getter
+fn rotation<'gc>(
+    _activation: &mut Activation<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    Ok(this_display_object(this)
+        .map(|mut dobj| dobj.rotation(context.gc_context).to_degrees().into())
+        .unwrap_or(Value::Undefined))
+}
+
+/// Implements `DisplayObject.rotation`'s setter
+///
+/// The angle is in degrees, and is normalized into the range of [-180, 180].
+fn set_rotation<'gc>(
+    activation: &mut Activation<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    if let Some(mut dobj) = this_display_object(this) {
+        let mut degrees = first_arg(args).coerce_to_number(activation, context)?;
+        degrees %= 360.0;
+        if degrees < -180.0 {
+            degrees += 360.0
+        } else if degrees > 180.0 {
+            degrees -= 360.0
+        }
+        dobj.set_rotation(context.gc_context, degrees.to_radians());
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `DisplayObject.visible`'s getter
+fn visible<'gc>(
+    _activation: &mut Activation<'gc>,
+    _context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    Ok(this_display_object(this)
+        .map(|dobj| dobj.visible().into())
+        .unwrap_or(Value::Undefined))
+}
+
+/// Implements `DisplayObject.visible`'s setter
+fn set_visible<'gc>(
+    _activation: &mut Activation<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    if let Some(mut dobj) = this_display_object(this) {
+        dobj.set_visible(context.gc_context, first_arg(args).coerce_to_boolean());
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `DisplayObject.name`'s getter
+fn name<'gc>(
+    _activation: &mut Activation<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    Ok(this_display_object(this)
+        .map(|dobj| AvmString::new(context.gc_context, dobj.name().to_string()).into())
+        .unwrap_or(Value::Undefined))
+}
+
+/// Implements `DisplayObject.name`'s setter
+fn set_name<'gc>(
+    activation: &mut Activation<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    if let Some(mut dobj) = this_display_object(this) {
+        let name = first_arg(args).coerce_to_string(activation, context)?;
+        dobj.set_name(context.gc_context, &name);
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `DisplayObject.parent`
+///
+/// The levels of the player are children of the stage.
+fn parent<'gc>(
+    _activation: &mut Activation<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    Ok(this_display_object(this)
+        .and_then(|dobj| parent_object(context, dobj))
+        .map(Value::Object)
+        .unwrap_or(Value::Null))
+}
+
+/// Implements `DisplayObject.root`
+///
+/// This is the level that contains the object, or `null` for objects that
+/// aren't on the stage.
+fn root<'gc>(
+    _activation: &mut Activation<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    if let Some(mut dobj) = this_display_object(this) {
+        if is_on_stage(context, dobj) {
+            while let Some(parent) = dobj.parent() {
+                dobj = parent;
+            }
+
+            return Ok(StageObject::for_display_object(context, dobj).into());
+        }
+    }
+
+    Ok(Value::Null)
+}
+
+/// Implements `DisplayObject.stage`
+fn stage<'gc>(
+    _activation: &mut Activation<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    match this_display_object(this) {
+        Some(dobj) if is_on_stage(context, dobj) => Ok(context.avm2.stage().into()),
+        _ => Ok(Value::Null),
+    }
+}
+
+/// Implements `DisplayObject.loaderInfo`
+///
+/// This describes the movie of the level that contains the object, or is
+/// `null` for objects that aren't on the stage.
+fn loader_info<'gc>(
+    _activation: &mut Activation<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    if let Some(mut dobj) = this_display_object(this) {
+        if is_on_stage(context, dobj) {
+            while let Some(parent) = dobj.parent() {
+                dobj = parent;
+            }
+
+            return Ok(StageObject::loader_info(context, dobj).into());
+        }
+    }
+
+    Ok(Value::Null)
+}
+
+/// Create `DisplayObject.prototype`.
+pub fn create_proto<'gc>(
+    mc: MutationContext<'gc, '_>,
+    event_dispatcher_proto: Object<'gc>,
+    fn_proto: Object<'gc>,
+) -> Object<'gc> {
+    let display_object_proto = ScriptObject::object(mc, event_dispatcher_proto);
+
+    getter(mc, display_object_proto, "x", x, fn_proto);
+    setter(mc, display_object_proto, "x", set_x, fn_proto);
+    getter(mc, display_object_proto, "y", y, fn_proto);
+    setter(mc, display_object_proto, "y", set_y, fn_proto);
+    getter(mc, display_object_proto, "scaleX", scale_x, fn_proto);
+    setter(mc, display_object_proto, "scaleX", set_scale_x, fn_proto);
+    getter(mc, display_object_proto, "scaleY", scale_y, fn_proto);
+    setter(mc, display_object_proto, "scaleY", set_scale_y, fn_proto);
+    getter(mc, display_object_proto, "rotation", rotation, fn_proto);
+    setter(mc, display_object_proto, "rotation", set_rotation, fn_proto);
+    getter(mc, display_object_proto, "alpha", alpha, fn_proto);
+    setter(mc, display_object_proto, "alpha", set_alpha, fn_proto);
+    getter(mc, display_object_proto, "visible", visible, fn_proto);
+    setter(mc, display_object_proto, "visible", set_visible, fn_proto);
+    getter(mc, display_object_proto, "width", width, fn_proto);
+    setter(mc, display_object_proto, "width", set_width, fn_proto);
+    getter(mc, display_object_proto, "height", height, fn_proto);
+    setter(mc, display_object_proto, "height", set_height, fn_proto);
+    getter(mc, display_object_proto, "name", name, fn_proto);
+    setter(mc, display_object_proto, "name", set_name, fn_proto);
+    getter(mc, display_object_proto, "parent", parent, fn_proto);
+    getter(mc, display_object_proto, "root", root, fn_proto);
+    getter(mc, display_object_proto, "stage", stage, fn_proto);
+    getter(
+        mc,
+        display_object_proto,
+        "loaderInfo",
+        loader_info,
+        fn_proto,
+    );
+
+    display_object_proto
+}
+
+/// Construct `DisplayObject`'s class.
+pub fn create_class<'gc>(mc: MutationContext<'gc, '_>) -> GcCell<'gc, Class<'gc>> {
+    let class = Class::new(
+        QName::new(Namespace::package("flash.display"), "DisplayObject"),
+        Some(QName::new(Namespace::package("flash.events"), "EventDispatcher").into()),
+        Method::Native(instance_init),
+        Method::Native(class_init),
+        mc,
+    );
+
+    class.write(mc).set_instance_allocator(allocator);
+
+    class
+}
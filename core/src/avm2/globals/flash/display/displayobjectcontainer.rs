@@ -0,0 +1,474 @@
+//! `flash.display.DisplayObjectContainer` builtin/prototype
+
+use crate::avm2::activation::Activation;
+use crate::avm2::class::Class;
+use crate::avm2::events::{dispatch_event_to_subtree, dispatch_new_event, is_on_stage};
+use crate::avm2::globals::{first_arg, getter, method, setter};
+use crate::avm2::method::Method;
+use crate::avm2::names::{Namespace, QName};
+use crate::avm2::object::{Object, TObject};
+use crate::avm2::script_object::ScriptObject;
+use crate::avm2::stage_object::StageObject;
+use crate::avm2::value::Value;
+use crate::avm2::Error;
+use crate::context::UpdateContext;
+use crate::display_object::{DisplayObject, MovieClip, TDisplayObject};
+use gc_arena::{GcCell, MutationContext};
+
+/// Implements `flash.display.DisplayObjectContainer`'s instance constructor.
+fn instance_init<'gc>(
+    _activation: &mut Activation<'gc>,
+    _context: &mut UpdateContext<'_, 'gc, '_>,
+    _this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    Ok(Value::Undefined)
+}
+
+/// Implements `flash.display.DisplayObjectContainer`'s class constructor.
+fn class_init<'gc>(
+    _activation: &mut Activation<'gc>,
+    _context: &mut UpdateContext<'_, 'gc, '_>,
+    _this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    Ok(Value::Undefined)
+}
+
+/// The movie clip whose display list `this` refers to.
+fn this_container<'gc>(this: Option<Object<'gc>>) -> Option<MovieClip<'gc>> {
+    this.and_then(|this| this.as_display_object())
+        .and_then(|dobj| dobj.as_movie_clip())
+}
+
+/// The display object passed as an argument.
+pub fn child_arg<'gc>(args: &[Value<'gc>], index: usize) -> Result<DisplayObject<'gc>, Error<'gc>> {
+    args.get(index)
+        .and_then(|child| child.as_object().ok())
+        .and_then(|child| child.as_display_object())
+        .ok_or_else(|| {
+            Error::TypeError("Error #2007: Parameter child must be non-null.".to_string())
+        })
+}
+
+/// The error for a display object that isn't a child of the caller.
+pub fn not_a_child_error<'gc>() -> Error<'gc> {
+    Error::ArgumentError(
+        "Error #2025: The supplied DisplayObject must be a child of the caller.".to_string(),
+    )
+}
+
+/// The error for a child index that is out of bounds.
+pub fn index_error<'gc>() -> Error<'gc> {
+    Error::RangeError("Error #2006: The supplied index is out of bounds.".to_string())
+}
+
+/// The level of the player that a display object is, if any.
+pub fn level_of<'gc>(
+    context: &UpdateContext<'_, 'gc, '_>,
+    display_object: DisplayObject<'gc>,
+) -> Option<u32> {
+    context
+        .levels
+        .iter()
+        .find(|(_, level)| DisplayObject::ptr_eq(**level, display_object))
+        .map(|(level_id, _)| *level_id)
+}
+
+/// Take a display object off the display list it's on, if any, as done
+/// before adding it elsewhere.
+///
+/// This dispatches `removed` to the object, and `removedFromStage` to it and
+/// its descendants if it was on the stage.
+pub fn remove_from_display_list<'gc>(
+    activation: &mut Activation<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    child: DisplayObject<'gc>,
+) -> Result<(), Error<'gc>> {
+    if child.parent().is_none() {
+        match level_of(context, child) {
+            None => return Ok(()),
+            Some(0) => {
+                log::warn!("The root movie can't be removed from the stage");
+                return Ok(());
+            }
+            Some(_) => {}
+        }
+    }
+
+    let child_object = StageObject::for_display_object(context, child);
+    dispatch_new_event(activation, context, child_object, "removed", true)?;
+    if is_on_stage(context, child) {
+        dispatch_event_to_subtree(activation, context, child, "removedFromStage")?;
+    }
+
+    // Event handlers may have moved the child already.
+    if let Some(parent) = child.parent().and_then(|parent| parent.as_movie_clip()) {
+        parent.take_child(context, child);
+    } else if let Some(level_id) = level_of(context, child).filter(|level_id| *level_id != 0) {
+        context.levels.remove(&level_id);
+    }
+
+    Ok(())
+}
+
+/// Dispatch the events for a display object that was just added to a
+/// display list: `added`, and `addedToStage` to it and its descendants if it
+/// is now on the stage.
+pub fn dispatch_added_events<'gc>(
+    activation: &mut Activation<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    child: DisplayObject<'gc>,
+) -> Result<(), Error<'gc>> {
+    let child_object = StageObject::for_display_object(context, child);
+    dispatch_new_event(activation, context, child_object, "added", true)?;
+    if is_on_stage(context, child) {
+        dispatch_event_to_subtree(activation, context, child, "addedToStage")?;
+    }
+
+    Ok(())
+}
+
+/// Add a child to a movie clip at the given index, taking it off any other
+/// display list first.
+fn add_child_to_container<'gc>(
+    activation: &mut Activation<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    container: MovieClip<'gc>,
+    child: DisplayObject<'gc>,
+    index: usize,
+) -> Result<Value<'gc>, Error<'gc>> {
+    let mut ancestor = Some(DisplayObject::from(container));
+    while let Some(dobj) = ancestor {
+        if DisplayObject::ptr_eq(dobj, child) {
+            return Err(Error::ArgumentError(
+                "Error #2150: An object cannot be added as a child to one of it's children (or children's children, etc.).".to_string(),
+            ));
+        }
+
+        ancestor = dobj.parent();
+    }
+
+    if index > container.num_children() {
+        return Err(index_error());
+    }
+
+    // Re-adding a child to the same container counts the index without it.
+    remove_from_display_list(activation, context, child)?;
+    let index = index.min(container.num_children());
+    container.insert_child_at_index(context, child, index);
+    dispatch_added_events(activation, context, child)?;
+
+    Ok(StageObject::for_display_object(context, child).into())
+}
+
+/// Implements `DisplayObjectContainer.addChild`
+fn add_child<'gc>(
+    activation: &mut Activation<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    if let Some(container) = this_container(this) {
+        let child = child_arg(args, 0)?;
+        let index = container.num_children();
+
+        return add_child_to_container(activation, context, container, child, index);
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `DisplayObjectContainer.addChildAt`
+fn add_child_at<'gc>(
+    activation: &mut Activation<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    if let Some(container) = this_container(this) {
+        let child = child_arg(args, 0)?;
+        let index = args
+            .get(1)
+            .cloned()
+            .unwrap_or(Value::Undefined)
+            .coerce_to_i32(activation, context)?;
+        if index < 0 {
+            return Err(index_error());
+        }
+
+        return add_child_to_container(activation, context, container, child, index as usize);
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `DisplayObjectContainer.removeChild`
+fn remove_child<'gc>(
+    activation: &mut Activation<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    if let Some(container) = this_container(this) {
+        let child = child_arg(args, 0)?;
+        if container.child_index(child).is_none() {
+            return Err(not_a_child_error());
+        }
+
+        remove_from_display_list(activation, context, child)?;
+
+        return Ok(StageObject::for_display_object(context, child).into());
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `DisplayObjectContainer.removeChildAt`
+fn remove_child_at<'gc>(
+    activation: &mut Activation<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    if let Some(container) = this_container(this) {
+        let index = first_arg(args).coerce_to_i32(activation, context)?;
+        let child = if index < 0 {
+            None
+        } else {
+            container.child_by_index(index as usize)
+        }
+        .ok_or_else(index_error)?;
+
+        remove_from_display_list(activation, context, child)?;
+
+        return Ok(StageObject::for_display_object(context, child).into());
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `DisplayObjectContainer.getChildAt`
+fn get_child_at<'gc>(
+    activation: &mut Activation<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    if let Some(container) = this_container(this) {
+        let index = first_arg(args).coerce_to_i32(activation, context)?;
+        let child = if index < 0 {
+            None
+        } else {
+            container.child_by_index(index as usize)
+        }
+        .ok_or_else(index_error)?;
+
+        return Ok(StageObject::for_display_object(context, child).into());
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `DisplayObjectContainer.getChildIndex`
+fn get_child_index<'gc>(
+    _activation: &mut Activation<'gc>,
+    _context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    if let Some(container) = this_container(this) {
+        let child = child_arg(args, 0)?;
+        let index = container.child_index(child).ok_or_else(not_a_child_error)?;
+
+        return Ok((index as u32).into());
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `DisplayObjectContainer.setChildIndex`
+fn set_child_index<'gc>(
+    activation: &mut Activation<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    if let Some(container) = this_container(this) {
+        let child = child_arg(args, 0)?;
+        let index = args
+            .get(1)
+            .cloned()
+            .unwrap_or(Value::Undefined)
+            .coerce_to_i32(activation, context)?;
+        if container.child_index(child).is_none() {
+            return Err(not_a_child_error());
+        }
+        if index < 0 || index as usize >= container.num_children() {
+            return Err(index_error());
+        }
+
+        // Moving a child within its container doesn't dispatch any events.
+        container.take_child(context, child);
+        container.insert_child_at_index(context, child, index as usize);
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `DisplayObjectContainer.getChildByName`
+fn get_child_by_name<'gc>(
+    activation: &mut Activation<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    if let Some(container) = this_container(this) {
+        let name = first_arg(args).coerce_to_string(activation, context)?;
+
+        return Ok(match container.get_child_by_name(&name, true) {
+            Some(child) => StageObject::for_display_object(context, child).into(),
+            None => Value::Null,
+        });
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `DisplayObjectContainer.contains`
+///
+/// A container contains itself, as well as all of its descendants.
+fn contains<'gc>(
+    _activation: &mut Activation<'gc>,
+    _context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    if let Some(container) = this_container(this) {
+        let mut ancestor = Some(child_arg(args, 0)?);
+        while let Some(dobj) = ancestor {
+            if DisplayObject::ptr_eq(dobj, container.into()) {
+                return Ok(true.into());
+            }
+
+            ancestor = dobj.parent();
+        }
+
+        return Ok(false.into());
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `DisplayObjectContainer.numChildren`
+fn num_children<'gc>(
+    _activation: &mut Activation<'gc>,
+    _context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    Ok(this_container(this)
+        .map(|container| (container.num_children() as u32).into())
+        .unwrap_or(Value::Undefined))
+}
+
+/// Implements `DisplayObjectContainer.mouseChildren`'s getter
+fn mouse_children<'gc>(
+    _activation: &mut Activation<'gc>,
+    _context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    Ok(this
+        .and_then(|this| this.as_display_object())
+        .map(|dobj| dobj.mouse_children().into())
+        .unwrap_or(Value::Undefined))
+}
+
+/// Implements `DisplayObjectContainer.mouseChildren`'s setter
+fn set_mouse_children<'gc>(
+    _activation: &mut Activation<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    if let Some(mut dobj) = this.and_then(|this| this.as_display_object()) {
+        dobj.set_mouse_children(context.gc_context, first_arg(args).coerce_to_boolean());
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Create `DisplayObjectContainer.prototype`.
+pub fn create_proto<'gc>(
+    mc: MutationContext<'gc, '_>,
+    interactive_object_proto: Object<'gc>,
+    fn_proto: Object<'gc>,
+) -> Object<'gc> {
+    let container_proto = ScriptObject::object(mc, interactive_object_proto);
+
+    method(mc, container_proto, "addChild", add_child, fn_proto);
+    method(mc, container_proto, "addChildAt", add_child_at, fn_proto);
+    method(mc, container_proto, "removeChild", remove_child, fn_proto);
+    method(
+        mc,
+        container_proto,
+        "removeChildAt",
+        remove_child_at,
+        fn_proto,
+    );
+    method(mc, container_proto, "getChildAt", get_child_at, fn_proto);
+    method(
+        mc,
+        container_proto,
+        "getChildIndex",
+        get_child_index,
+        fn_proto,
+    );
+    method(
+        mc,
+        container_proto,
+        "setChildIndex",
+        set_child_index,
+        fn_proto,
+    );
+    method(
+        mc,
+        container_proto,
+        "getChildByName",
+        get_child_by_name,
+        fn_proto,
+    );
+    method(mc, container_proto, "contains", contains, fn_proto);
+    getter(mc, container_proto, "numChildren", num_children, fn_proto);
+    getter(
+        mc,
+        container_proto,
+        "mouseChildren",
+        mouse_children,
+        fn_proto,
+    );
+    setter(
+        mc,
+        container_proto,
+        "mouseChildren",
+        set_mouse_children,
+        fn_proto,
+    );
+
+    container_proto
+}
+
+/// Construct `DisplayObjectContainer`'s class.
+pub fn create_class<'gc>(mc: MutationContext<'gc, '_>) -> GcCell<'gc, Class<'gc>> {
+    Class::new(
+        QName::new(
+            Namespace::package("flash.display"),
+            "DisplayObjectContainer",
+        ),
+        Some(QName::new(Namespace::package("flash.display"), "InteractiveObject").into()),
+        Method::Native(instance_init),
+        Method::Native(class_init),
+        mc,
+    )
+}
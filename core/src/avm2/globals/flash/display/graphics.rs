@@ -0,0 +1,549 @@
+//! `flash.display.Graphics` builtin/prototype
+
+use crate::avm2::activation::Activation;
+use crate::avm2::class::Class;
+use crate::avm2::globals::{first_arg, method};
+use crate::avm2::method::Method;
+use crate::avm2::names::{Namespace, QName};
+use crate::avm2::object::{Object, TObject};
+use crate::avm2::script_object::ScriptObject;
+use crate::avm2::value::Value;
+use crate::avm2::Error;
+use crate::context::UpdateContext;
+use crate::display_object::TDisplayObject;
+use crate::drawing::Drawing;
+use crate::shape_utils::DrawCommand;
+use gc_arena::{GcCell, MutationContext};
+use std::f64::NAN;
+use swf::{
+    Color, FillStyle, Gradient, GradientInterpolation, GradientRecord, GradientSpread,
+    LineCapStyle, LineJoinStyle, LineStyle, Matrix, Twips,
+};
+
+/// Implements `flash.display.Graphics`'s instance constructor.
+///
+/// Graphics objects only come from the `graphics` property of the display
+/// objects they draw on.
+fn instance_init<'gc>(
+    _activation: &mut Activation<'gc>,
+    _context: &mut UpdateContext<'_, 'gc, '_>,
+    _this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    Err(Error::ArgumentError(
+        "Error #2012: Graphics class cannot be instantiated.".to_string(),
+    ))
+}
+
+/// Implements `flash.display.Graphics`'s class constructor.
+fn class_init<'gc>(
+    _activation: &mut Activation<'gc>,
+    _context: &mut UpdateContext<'_, 'gc, '_>,
+    _this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    Ok(Value::Undefined)
+}
+
+/// Change the drawing that `this` draws on, if any.
+fn with_drawing<'gc>(
+    this: Option<Object<'gc>>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    f: impl FnOnce(&mut Drawing),
+) {
+    if let Some(display_object) = this.and_then(|this| this.as_display_object()) {
+        if let Some(mut drawing) = display_object.as_drawing(context.gc_context) {
+            f(&mut *drawing);
+        }
+        display_object.invalidate_cached_bitmap();
+    }
+}
+
+/// Coerce an argument to a number, or `default` if it wasn't passed.
+fn number_arg<'gc>(
+    activation: &mut Activation<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    args: &[Value<'gc>],
+    index: usize,
+    default: f64,
+) -> Result<f64, Error<'gc>> {
+    match args.get(index) {
+        Some(value) => value.coerce_to_number(activation, context),
+        None => Ok(default),
+    }
+}
+
+/// Coerce an argument to a string, if one was passed and isn't `null`.
+fn string_arg<'gc>(
+    activation: &mut Activation<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    args: &[Value<'gc>],
+    index: usize,
+) -> Result<Option<String>, Error<'gc>> {
+    match args.get(index) {
+        None | Some(Value::Undefined) | Some(Value::Null) => Ok(None),
+        Some(value) => Ok(Some(
+            value.coerce_to_string(activation, context)?.to_string(),
+        )),
+    }
+}
+
+/// Convert an alpha from 0 to 1 into a color channel.
+fn alpha_to_u8(alpha: f64) -> u8 {
+    (alpha.min(1.0).max(0.0) * 255.0) as u8
+}
+
+/// Implements `Graphics.beginFill`
+fn begin_fill<'gc>(
+    activation: &mut Activation<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let rgb = first_arg(args).coerce_to_u32(activation, context)?;
+    let alpha = number_arg(activation, context, args, 1, 1.0)?;
+    let style = FillStyle::Color(Color::from_rgb(rgb, alpha_to_u8(alpha)));
+    with_drawing(this, context, |drawing| drawing.set_fill_style(Some(style)));
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `Graphics.beginGradientFill`
+fn begin_gradient_fill<'gc>(
+    activation: &mut Activation<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    if let Some(style) =
+        gradient_fill_style_from_args("beginGradientFill", activation, context, args)?
+    {
+        with_drawing(this, context, |drawing| drawing.set_fill_style(Some(style)));
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `Graphics.lineGradientStyle`
+fn line_gradient_style<'gc>(
+    activation: &mut Activation<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    if let Some(style) =
+        gradient_fill_style_from_args("lineGradientStyle", activation, context, args)?
+    {
+        with_drawing(this, context, |drawing| drawing.set_line_fill_style(style));
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// The elements of an array argument, or nothing if it isn't an array.
+fn array_elements<'gc>(value: Option<&Value<'gc>>) -> Vec<Value<'gc>> {
+    match value {
+        Some(Value::Object(object)) => object
+            .as_array_object()
+            .map(|array| array.elements())
+            .unwrap_or_default(),
+        _ => Vec::new(),
+    }
+}
+
+/// Convert a `flash.geom.Matrix` into the matrix of a gradient, which maps
+/// the gradient square onto the shape.
+fn object_to_matrix<'gc>(
+    object: Object<'gc>,
+    activation: &mut Activation<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+) -> Result<Matrix, Error<'gc>> {
+    let mut get = |name: &'static str| -> Result<f64, Error<'gc>> {
+        object
+            .get_property(object, &QName::dynamic_name(name), activation, context)?
+            .coerce_to_number(activation, context)
+    };
+
+    Ok(Matrix {
+        a: get("a")? as f32,
+        b: get("b")? as f32,
+        c: get("c")? as f32,
+        d: get("d")? as f32,
+        tx: Twips::from_pixels(get("tx")?),
+        ty: Twips::from_pixels(get("ty")?),
+    })
+}
+
+/// Parses the `(type, colors, alphas, ratios, matrix, spreadMethod, interpolationMethod,
+/// focalPointRatio)` arguments shared by `beginGradientFill` and `lineGradientStyle`.
+/// Returns `None` if the arguments do not describe a valid gradient.
+fn gradient_fill_style_from_args<'gc>(
+    method_name: &str,
+    activation: &mut Activation<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    args: &[Value<'gc>],
+) -> Result<Option<FillStyle>, Error<'gc>> {
+    let fill_type = first_arg(args).coerce_to_string(activation, context)?;
+    let colors = array_elements(args.get(1));
+    let alphas = array_elements(args.get(2));
+    let ratios = array_elements(args.get(3));
+    if colors.len() != alphas.len() || colors.len() != ratios.len() {
+        log::warn!(
+            "{}() received different sized arrays for colors, alphas and ratios",
+            method_name
+        );
+        return Ok(None);
+    }
+
+    let mut records = Vec::with_capacity(colors.len());
+    for ((color, alpha), ratio) in colors.iter().zip(&alphas).zip(&ratios) {
+        let rgb = color.coerce_to_u32(activation, context)?;
+        let alpha = alpha.coerce_to_number(activation, context)?;
+        let ratio = ratio
+            .coerce_to_number(activation, context)?
+            .min(255.0)
+            .max(0.0);
+        records.push(GradientRecord {
+            ratio: ratio as u8,
+            color: Color::from_rgb(rgb, alpha_to_u8(alpha)),
+        });
+    }
+
+    let matrix = match args.get(4) {
+        Some(Value::Object(matrix)) => object_to_matrix(*matrix, activation, context)?,
+        _ => Matrix::identity(),
+    };
+    let spread = match string_arg(activation, context, args, 5)?.as_deref() {
+        Some("reflect") => GradientSpread::Reflect,
+        Some("repeat") => GradientSpread::Repeat,
+        _ => GradientSpread::Pad,
+    };
+    let interpolation = match string_arg(activation, context, args, 6)?.as_deref() {
+        Some("linearRGB") => GradientInterpolation::LinearRGB,
+        _ => GradientInterpolation::RGB,
+    };
+    let focal_point = number_arg(activation, context, args, 7, 0.0)?;
+
+    let gradient = Gradient {
+        matrix,
+        spread,
+        interpolation,
+        records,
+    };
+    let style = match fill_type.as_ref() {
+        "linear" => FillStyle::LinearGradient(gradient),
+        "radial" if focal_point != 0.0 => FillStyle::FocalGradient {
+            gradient,
+            focal_point: focal_point.min(1.0).max(-1.0) as f32,
+        },
+        "radial" => FillStyle::RadialGradient(gradient),
+        other => {
+            log::warn!("{}() received invalid fill type {:?}", method_name, other);
+            return Ok(None);
+        }
+    };
+
+    Ok(Some(style))
+}
+
+/// Implements `Graphics.lineStyle`
+fn line_style<'gc>(
+    activation: &mut Activation<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let thickness = number_arg(activation, context, args, 0, NAN)?;
+    if thickness.is_nan() {
+        with_drawing(this, context, |drawing| drawing.set_line_style(None));
+        return Ok(Value::Undefined);
+    }
+
+    let rgb = match args.get(1) {
+        Some(color) => color.coerce_to_u32(activation, context)?,
+        None => 0,
+    };
+    let alpha = number_arg(activation, context, args, 2, 1.0)?;
+    let is_pixel_hinted = args.get(3).map_or(false, |v| v.coerce_to_boolean());
+    let (allow_scale_x, allow_scale_y) = match string_arg(activation, context, args, 4)?.as_deref()
+    {
+        Some("none") => (false, false),
+        Some("vertical") => (true, false),
+        Some("horizontal") => (false, true),
+        _ => (true, true),
+    };
+    let cap_style = match string_arg(activation, context, args, 5)?.as_deref() {
+        Some("none") => LineCapStyle::None,
+        Some("square") => LineCapStyle::Square,
+        _ => LineCapStyle::Round,
+    };
+    let join_style = match string_arg(activation, context, args, 6)?.as_deref() {
+        Some("miter") => {
+            let limit = number_arg(activation, context, args, 7, 3.0)?;
+            LineJoinStyle::Miter(limit.min(255.0).max(1.0) as f32)
+        }
+        Some("bevel") => LineJoinStyle::Bevel,
+        _ => LineJoinStyle::Round,
+    };
+
+    let style = LineStyle {
+        width: Twips::from_pixels(thickness.min(255.0).max(0.0)),
+        color: Color::from_rgb(rgb, alpha_to_u8(alpha)),
+        start_cap: cap_style,
+        end_cap: cap_style,
+        join_style,
+        fill_style: None,
+        allow_scale_x,
+        allow_scale_y,
+        is_pixel_hinted,
+        allow_close: false,
+    };
+    with_drawing(this, context, |drawing| drawing.set_line_style(Some(style)));
+
+    Ok(Value::Undefined)
+}
+
+/// Coerce the arguments of a drawing method to twips.
+fn twips_args<'gc>(
+    activation: &mut Activation<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    args: &[Value<'gc>],
+    count: usize,
+) -> Result<Vec<Twips>, Error<'gc>> {
+    let mut twips = Vec::with_capacity(count);
+    for index in 0..count {
+        twips.push(Twips::from_pixels(number_arg(
+            activation, context, args, index, NAN,
+        )?));
+    }
+
+    Ok(twips)
+}
+
+/// Implements `Graphics.moveTo`
+fn move_to<'gc>(
+    activation: &mut Activation<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    if let [x, y] = twips_args(activation, context, args, 2)?[..] {
+        with_drawing(this, context, |drawing| {
+            drawing.draw_command(DrawCommand::MoveTo { x, y })
+        });
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `Graphics.lineTo`
+fn line_to<'gc>(
+    activation: &mut Activation<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    if let [x, y] = twips_args(activation, context, args, 2)?[..] {
+        with_drawing(this, context, |drawing| {
+            drawing.draw_command(DrawCommand::LineTo { x, y })
+        });
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `Graphics.curveTo`
+fn curve_to<'gc>(
+    activation: &mut Activation<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    if let [x1, y1, x2, y2] = twips_args(activation, context, args, 4)?[..] {
+        with_drawing(this, context, |drawing| {
+            drawing.draw_command(DrawCommand::CurveTo { x1, y1, x2, y2 })
+        });
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `Graphics.drawRect`
+fn draw_rect<'gc>(
+    activation: &mut Activation<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    if let [x, y, width, height] = twips_args(activation, context, args, 4)?[..] {
+        with_drawing(this, context, |drawing| {
+            drawing.draw_rect(x, y, width, height)
+        });
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `Graphics.drawCircle`
+fn draw_circle<'gc>(
+    activation: &mut Activation<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    if let [x, y, radius] = twips_args(activation, context, args, 3)?[..] {
+        with_drawing(this, context, |drawing| {
+            drawing.draw_ellipse(x - radius, y - radius, radius * 2, radius * 2)
+        });
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `Graphics.drawEllipse`
+fn draw_ellipse<'gc>(
+    activation: &mut Activation<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    if let [x, y, width, height] = twips_args(activation, context, args, 4)?[..] {
+        with_drawing(this, context, |drawing| {
+            drawing.draw_ellipse(x, y, width, height)
+        });
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `Graphics.drawRoundRect`
+///
+/// The height of the corners defaults to their width.
+fn draw_round_rect<'gc>(
+    activation: &mut Activation<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let ellipse_width = number_arg(activation, context, args, 4, NAN)?;
+    let ellipse_height = match number_arg(activation, context, args, 5, NAN)? {
+        height if height.is_nan() => ellipse_width,
+        height => height,
+    };
+    if let [x, y, width, height] = twips_args(activation, context, args, 4)?[..] {
+        let ellipse_width = Twips::from_pixels(ellipse_width);
+        let ellipse_height = Twips::from_pixels(ellipse_height);
+        with_drawing(this, context, |drawing| {
+            drawing.draw_round_rect(x, y, width, height, ellipse_width, ellipse_height)
+        });
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `Graphics.endFill`
+fn end_fill<'gc>(
+    _activation: &mut Activation<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    with_drawing(this, context, |drawing| drawing.set_fill_style(None));
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `Graphics.clear`
+fn clear<'gc>(
+    _activation: &mut Activation<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    with_drawing(this, context, |drawing| drawing.clear());
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `Graphics.copyFrom`
+///
+/// The copy registers its own shapes with the renderer, so later changes to
+/// either drawing don't affect the other.
+fn copy_from<'gc>(
+    _activation: &mut Activation<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let source = match first_arg(args) {
+        Value::Object(source) => source.as_display_object(),
+        _ => None,
+    }
+    .ok_or_else(|| {
+        Error::TypeError("Error #2007: Parameter sourceGraphics must be non-null.".to_string())
+    })?;
+
+    let copy = source
+        .as_drawing(context.gc_context)
+        .map(|drawing| drawing.duplicate());
+    if let Some(copy) = copy {
+        with_drawing(this, context, |drawing| *drawing = copy);
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Create `Graphics.prototype`.
+pub fn create_proto<'gc>(
+    mc: MutationContext<'gc, '_>,
+    object_proto: Object<'gc>,
+    fn_proto: Object<'gc>,
+) -> Object<'gc> {
+    let graphics_proto = ScriptObject::object(mc, object_proto);
+
+    method(mc, graphics_proto, "beginFill", begin_fill, fn_proto);
+    method(
+        mc,
+        graphics_proto,
+        "beginGradientFill",
+        begin_gradient_fill,
+        fn_proto,
+    );
+    method(mc, graphics_proto, "lineStyle", line_style, fn_proto);
+    method(
+        mc,
+        graphics_proto,
+        "lineGradientStyle",
+        line_gradient_style,
+        fn_proto,
+    );
+    method(mc, graphics_proto, "moveTo", move_to, fn_proto);
+    method(mc, graphics_proto, "lineTo", line_to, fn_proto);
+    method(mc, graphics_proto, "curveTo", curve_to, fn_proto);
+    method(mc, graphics_proto, "drawRect", draw_rect, fn_proto);
+    method(mc, graphics_proto, "drawCircle", draw_circle, fn_proto);
+    method(mc, graphics_proto, "drawEllipse", draw_ellipse, fn_proto);
+    method(
+        mc,
+        graphics_proto,
+        "drawRoundRect",
+        draw_round_rect,
+        fn_proto,
+    );
+    method(mc, graphics_proto, "endFill", end_fill, fn_proto);
+    method(mc, graphics_proto, "clear", clear, fn_proto);
+    method(mc, graphics_proto, "copyFrom", copy_from, fn_proto);
+
+    graphics_proto
+}
+
+/// Construct `Graphics`'s class.
+pub fn create_class<'gc>(mc: MutationContext<'gc, '_>) -> GcCell<'gc, Class<'gc>> {
+    Class::new(
+        QName::new(Namespace::package("flash.display"), "Graphics"),
+        Some(QName::new(Namespace::public(), "Object").into()),
+        Method::Native(instance_init),
+        Method::Native(class_init),
+        mc,
+    )
+}
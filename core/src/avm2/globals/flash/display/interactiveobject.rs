@@ -0,0 +1,139 @@
+//! `flash.display.InteractiveObject` builtin/prototype
+
+use crate::avm2::activation::Activation;
+use crate::avm2::class::Class;
+use crate::avm2::globals::{first_arg, getter, setter};
+use crate::avm2::method::Method;
+use crate::avm2::names::{Namespace, QName};
+use crate::avm2::object::{Object, TObject};
+use crate::avm2::script_object::ScriptObject;
+use crate::avm2::value::Value;
+use crate::avm2::Error;
+use crate::context::UpdateContext;
+use crate::display_object::TDisplayObject;
+use gc_arena::{GcCell, MutationContext};
+
+/// Implements `flash.display.InteractiveObject`'s instance constructor.
+fn instance_init<'gc>(
+    _activation: &mut Activation<'gc>,
+    _context: &mut UpdateContext<'_, 'gc, '_>,
+    _this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    Ok(Value::Undefined)
+}
+
+/// Implements `flash.display.InteractiveObject`'s class constructor.
+fn class_init<'gc>(
+    _activation: &mut Activation<'gc>,
+    _context: &mut UpdateContext<'_, 'gc, '_>,
+    _this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    Ok(Value::Undefined)
+}
+
+/// Implements `InteractiveObject.mouseEnabled`'s getter
+fn mouse_enabled<'gc>(
+    _activation: &mut Activation<'gc>,
+    _context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    Ok(this
+        .and_then(|this| this.as_display_object())
+        .map(|dobj| dobj.mouse_enabled().into())
+        .unwrap_or(Value::Undefined))
+}
+
+/// Implements `InteractiveObject.mouseEnabled`'s setter
+fn set_mouse_enabled<'gc>(
+    _activation: &mut Activation<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    if let Some(mut dobj) = this.and_then(|this| this.as_display_object()) {
+        dobj.set_mouse_enabled(context.gc_context, first_arg(args).coerce_to_boolean());
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `InteractiveObject.doubleClickEnabled`'s getter
+fn double_click_enabled<'gc>(
+    _activation: &mut Activation<'gc>,
+    _context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    Ok(this
+        .and_then(|this| this.as_display_object())
+        .map(|dobj| dobj.double_click_enabled().into())
+        .unwrap_or(Value::Undefined))
+}
+
+/// Implements `InteractiveObject.doubleClickEnabled`'s setter
+fn set_double_click_enabled<'gc>(
+    _activation: &mut Activation<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    if let Some(mut dobj) = this.and_then(|this| this.as_display_object()) {
+        dobj.set_double_click_enabled(context.gc_context, first_arg(args).coerce_to_boolean());
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Create `InteractiveObject.prototype`.
+pub fn create_proto<'gc>(
+    mc: MutationContext<'gc, '_>,
+    display_object_proto: Object<'gc>,
+    fn_proto: Object<'gc>,
+) -> Object<'gc> {
+    let interactive_object_proto = ScriptObject::object(mc, display_object_proto);
+
+    getter(
+        mc,
+        interactive_object_proto,
+        "mouseEnabled",
+        mouse_enabled,
+        fn_proto,
+    );
+    setter(
+        mc,
+        interactive_object_proto,
+        "mouseEnabled",
+        set_mouse_enabled,
+        fn_proto,
+    );
+    getter(
+        mc,
+        interactive_object_proto,
+        "doubleClickEnabled",
+        double_click_enabled,
+        fn_proto,
+    );
+    setter(
+        mc,
+        interactive_object_proto,
+        "doubleClickEnabled",
+        set_double_click_enabled,
+        fn_proto,
+    );
+
+    interactive_object_proto
+}
+
+/// Construct `InteractiveObject`'s class.
+pub fn create_class<'gc>(mc: MutationContext<'gc, '_>) -> GcCell<'gc, Class<'gc>> {
+    Class::new(
+        QName::new(Namespace::package("flash.display"), "InteractiveObject"),
+        Some(QName::new(Namespace::package("flash.display"), "DisplayObject").into()),
+        Method::Native(instance_init),
+        Method::Native(class_init),
+        mc,
+    )
+}
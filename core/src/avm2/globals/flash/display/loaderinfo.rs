@@ -0,0 +1,127 @@
+//! `flash.display.LoaderInfo` builtin/prototype
+
+use crate::avm2::activation::Activation;
+use crate::avm2::class::Class;
+use crate::avm2::globals::getter;
+use crate::avm2::method::Method;
+use crate::avm2::names::{Namespace, QName};
+use crate::avm2::object::{Object, TObject};
+use crate::avm2::script_object::ScriptObject;
+use crate::avm2::value::Value;
+use crate::avm2::{AvmString, Error};
+use crate::context::UpdateContext;
+use crate::display_object::TDisplayObject;
+use crate::tag_utils::SwfMovie;
+use enumset::EnumSet;
+use gc_arena::{GcCell, MutationContext};
+use std::sync::Arc;
+
+/// Implements `flash.display.LoaderInfo`'s instance constructor.
+///
+/// Loader infos only come from the `loaderInfo` property of display objects.
+fn instance_init<'gc>(
+    _activation: &mut Activation<'gc>,
+    _context: &mut UpdateContext<'_, 'gc, '_>,
+    _this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    Err(Error::ArgumentError(
+        "Error #2012: LoaderInfo class cannot be instantiated.".to_string(),
+    ))
+}
+
+/// Implements `flash.display.LoaderInfo`'s class constructor.
+fn class_init<'gc>(
+    _activation: &mut Activation<'gc>,
+    _context: &mut UpdateContext<'_, 'gc, '_>,
+    _this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    Ok(Value::Undefined)
+}
+
+/// The movie that a loader info describes, which is the movie of the root
+/// display object it is bound to.
+fn this_movie<'gc>(this: Option<Object<'gc>>) -> Option<Arc<SwfMovie>> {
+    this.and_then(|this| this.as_display_object())
+        .and_then(|dobj| dobj.movie())
+}
+
+/// Implements `LoaderInfo.parameters`
+///
+/// These are the variables in the query string of the movie's URL, followed
+/// by its FlashVars, as strings.
+fn parameters<'gc>(
+    _activation: &mut Activation<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let object = ScriptObject::object(context.gc_context, context.avm2.prototypes().object);
+    if let Some(movie) = this_movie(this) {
+        for (name, value) in movie.parameters() {
+            object.define_value(
+                context.gc_context,
+                QName::dynamic_name(AvmString::new(context.gc_context, name.clone())),
+                AvmString::new(context.gc_context, value.clone()).into(),
+                EnumSet::empty(),
+            );
+        }
+    }
+
+    Ok(object.into())
+}
+
+/// Implements `LoaderInfo.url`
+fn url<'gc>(
+    _activation: &mut Activation<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    Ok(this_movie(this)
+        .and_then(|movie| {
+            movie
+                .url()
+                .map(|url| AvmString::new(context.gc_context, url.to_string()).into())
+        })
+        .unwrap_or(Value::Null))
+}
+
+/// Implements `LoaderInfo.swfVersion`
+fn swf_version<'gc>(
+    _activation: &mut Activation<'gc>,
+    _context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    Ok(this_movie(this)
+        .map(|movie| u32::from(movie.version()).into())
+        .unwrap_or(Value::Undefined))
+}
+
+/// Create `LoaderInfo.prototype`.
+pub fn create_proto<'gc>(
+    mc: MutationContext<'gc, '_>,
+    event_dispatcher_proto: Object<'gc>,
+    fn_proto: Object<'gc>,
+) -> Object<'gc> {
+    let loader_info_proto = ScriptObject::object(mc, event_dispatcher_proto);
+
+    getter(mc, loader_info_proto, "parameters", parameters, fn_proto);
+    getter(mc, loader_info_proto, "url", url, fn_proto);
+    getter(mc, loader_info_proto, "swfVersion", swf_version, fn_proto);
+
+    loader_info_proto
+}
+
+/// Construct `LoaderInfo`'s class.
+pub fn create_class<'gc>(mc: MutationContext<'gc, '_>) -> GcCell<'gc, Class<'gc>> {
+    Class::new(
+        QName::new(Namespace::package("flash.display"), "LoaderInfo"),
+        Some(QName::new(Namespace::package("flash.events"), "EventDispatcher").into()),
+        Method::Native(instance_init),
+        Method::Native(class_init),
+        mc,
+    )
+}
@@ -0,0 +1,157 @@
+//! `flash.display.MovieClip` builtin/prototype
+
+use crate::avm2::activation::Activation;
+use crate::avm2::class::Class;
+use crate::avm2::globals::flash::display::sprite;
+use crate::avm2::globals::{getter, method};
+use crate::avm2::method::Method;
+use crate::avm2::names::{Namespace, QName};
+use crate::avm2::object::{Object, TObject};
+use crate::avm2::script_object::ScriptObject;
+use crate::avm2::value::Value;
+use crate::avm2::Error;
+use crate::context::UpdateContext;
+use crate::display_object::{MovieClip, TDisplayObject};
+use gc_arena::{GcCell, MutationContext};
+
+/// Implements `flash.display.MovieClip`'s instance constructor.
+fn instance_init<'gc>(
+    activation: &mut Activation<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    sprite::instance_init(activation, context, this, args)
+}
+
+/// Implements `flash.display.MovieClip`'s class constructor.
+fn class_init<'gc>(
+    _activation: &mut Activation<'gc>,
+    _context: &mut UpdateContext<'_, 'gc, '_>,
+    _this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    Ok(Value::Undefined)
+}
+
+/// The movie clip `this` is bound to, if any.
+fn this_movie_clip<'gc>(this: Option<Object<'gc>>) -> Option<MovieClip<'gc>> {
+    this.and_then(|this| this.as_display_object())
+        .and_then(|dobj| dobj.as_movie_clip())
+}
+
+/// Implements `MovieClip.addFrameScript`
+///
+/// Arguments come in pairs of a zero-based frame index and the function to
+/// run when that frame is entered; `null` removes a frame's script.
+fn add_frame_script<'gc>(
+    activation: &mut Activation<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    if let Some(mc) = this_movie_clip(this) {
+        for pair in args.chunks_exact(2) {
+            let frame_id = (pair[0].coerce_to_u32(activation, context)? as u16).saturating_add(1);
+            let callable = pair[1].as_object().ok();
+
+            mc.register_frame_script(frame_id, callable, context);
+        }
+    } else {
+        log::error!("Attempted to add frame scripts to non-MovieClip this!");
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `MovieClip.stop`
+fn stop<'gc>(
+    _activation: &mut Activation<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    if let Some(mc) = this_movie_clip(this) {
+        mc.stop(context);
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `MovieClip.play`
+fn play<'gc>(
+    _activation: &mut Activation<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    if let Some(mc) = this_movie_clip(this) {
+        mc.play(context);
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `MovieClip.currentFrame`
+fn current_frame<'gc>(
+    _activation: &mut Activation<'gc>,
+    _context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    Ok(this_movie_clip(this)
+        .map(|mc| i32::from(mc.current_frame()).into())
+        .unwrap_or(Value::Undefined))
+}
+
+/// Implements `MovieClip.totalFrames`
+fn total_frames<'gc>(
+    _activation: &mut Activation<'gc>,
+    _context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    Ok(this_movie_clip(this)
+        .map(|mc| i32::from(mc.total_frames()).into())
+        .unwrap_or(Value::Undefined))
+}
+
+/// Create `MovieClip.prototype`.
+pub fn create_proto<'gc>(
+    mc: MutationContext<'gc, '_>,
+    sprite_proto: Object<'gc>,
+    fn_proto: Object<'gc>,
+) -> Object<'gc> {
+    let movie_clip_proto = ScriptObject::object(mc, sprite_proto);
+
+    method(
+        mc,
+        movie_clip_proto,
+        "addFrameScript",
+        add_frame_script,
+        fn_proto,
+    );
+    method(mc, movie_clip_proto, "stop", stop, fn_proto);
+    method(mc, movie_clip_proto, "play", play, fn_proto);
+    getter(
+        mc,
+        movie_clip_proto,
+        "currentFrame",
+        current_frame,
+        fn_proto,
+    );
+    getter(mc, movie_clip_proto, "totalFrames", total_frames, fn_proto);
+
+    movie_clip_proto
+}
+
+/// Construct `MovieClip`'s class.
+pub fn create_class<'gc>(mc: MutationContext<'gc, '_>) -> GcCell<'gc, Class<'gc>> {
+    Class::new(
+        QName::new(Namespace::package("flash.display"), "MovieClip"),
+        Some(QName::new(Namespace::package("flash.display"), "Sprite").into()),
+        Method::Native(instance_init),
+        Method::Native(class_init),
+        mc,
+    )
+}
@@ -0,0 +1,79 @@
+//! `flash.display.Sprite` builtin/prototype
+
+use crate::avm2::activation::Activation;
+use crate::avm2::class::Class;
+use crate::avm2::globals::flash::display::shape;
+use crate::avm2::globals::getter;
+use crate::avm2::method::Method;
+use crate::avm2::names::{Namespace, QName};
+use crate::avm2::object::{Object, TObject};
+use crate::avm2::script_object::ScriptObject;
+use crate::avm2::value::Value;
+use crate::avm2::Error;
+use crate::context::UpdateContext;
+use crate::display_object::{DisplayObject, MovieClip, TDisplayObject};
+use crate::tag_utils::SwfSlice;
+use gc_arena::{GcCell, MutationContext};
+
+/// Implements `flash.display.Sprite`'s instance constructor.
+///
+/// Sprites constructed by scripts, rather than placed by the timeline, are
+/// empty movie clips that aren't on any display list yet.
+pub fn instance_init<'gc>(
+    _activation: &mut Activation<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    if let Some(Object::StageObject(stage_object)) = this {
+        if stage_object.as_display_object().is_none() {
+            let mut movie_clip: DisplayObject<'gc> =
+                MovieClip::new(SwfSlice::empty(context.swf.clone()), context.gc_context).into();
+            movie_clip.set_default_instance_name(context);
+            movie_clip.set_object2(context.gc_context, stage_object.into());
+            stage_object.set_display_object(context.gc_context, movie_clip);
+        }
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `flash.display.Sprite`'s class constructor.
+fn class_init<'gc>(
+    _activation: &mut Activation<'gc>,
+    _context: &mut UpdateContext<'_, 'gc, '_>,
+    _this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    Ok(Value::Undefined)
+}
+
+/// Create `Sprite.prototype`.
+pub fn create_proto<'gc>(
+    mc: MutationContext<'gc, '_>,
+    container_proto: Object<'gc>,
+    fn_proto: Object<'gc>,
+) -> Object<'gc> {
+    let sprite_proto = ScriptObject::object(mc, container_proto);
+
+    getter(mc, sprite_proto, "graphics", shape::graphics, fn_proto);
+
+    sprite_proto
+}
+
+/// Construct `Sprite`'s class.
+pub fn create_class<'gc>(mc: MutationContext<'gc, '_>) -> GcCell<'gc, Class<'gc>> {
+    Class::new(
+        QName::new(Namespace::package("flash.display"), "Sprite"),
+        Some(
+            QName::new(
+                Namespace::package("flash.display"),
+                "DisplayObjectContainer",
+            )
+            .into(),
+        ),
+        Method::Native(instance_init),
+        Method::Native(class_init),
+        mc,
+    )
+}
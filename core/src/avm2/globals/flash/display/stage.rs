@@ -0,0 +1,186 @@
+//! `flash.display.Stage` builtin/prototype
+
+use crate::avm2::activation::Activation;
+use crate::avm2::class::Class;
+use crate::avm2::globals::flash::display::displayobjectcontainer::{
+    child_arg, dispatch_added_events, index_error, level_of, not_a_child_error,
+    remove_from_display_list,
+};
+use crate::avm2::globals::{first_arg, getter, method};
+use crate::avm2::method::Method;
+use crate::avm2::names::{Namespace, QName};
+use crate::avm2::object::Object;
+use crate::avm2::script_object::ScriptObject;
+use crate::avm2::stage_object::StageObject;
+use crate::avm2::value::Value;
+use crate::avm2::Error;
+use crate::context::UpdateContext;
+use crate::display_object::TDisplayObject;
+use gc_arena::{GcCell, MutationContext};
+
+/// Implements `flash.display.Stage`'s instance constructor.
+///
+/// There is only ever one stage, which the player creates.
+fn instance_init<'gc>(
+    _activation: &mut Activation<'gc>,
+    _context: &mut UpdateContext<'_, 'gc, '_>,
+    _this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    Err(Error::ArgumentError(
+        "Error #2012: Stage class cannot be instantiated.".to_string(),
+    ))
+}
+
+/// Implements `flash.display.Stage`'s class constructor.
+fn class_init<'gc>(
+    _activation: &mut Activation<'gc>,
+    _context: &mut UpdateContext<'_, 'gc, '_>,
+    _this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    Ok(Value::Undefined)
+}
+
+/// Implements `Stage.addChild`
+///
+/// The children of the stage are the levels of the player, so the child
+/// becomes a new level above all of the others.
+fn add_child<'gc>(
+    activation: &mut Activation<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    _this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let mut child = child_arg(args, 0)?;
+    remove_from_display_list(activation, context, child)?;
+
+    let level_id = context
+        .levels
+        .keys()
+        .next_back()
+        .map_or(0, |level_id| level_id + 1);
+    child.set_depth(context.gc_context, level_id as i32);
+    child.set_parent(context.gc_context, None);
+    context.levels.insert(level_id, child);
+    dispatch_added_events(activation, context, child)?;
+
+    Ok(StageObject::for_display_object(context, child).into())
+}
+
+/// Implements `Stage.removeChild`
+fn remove_child<'gc>(
+    activation: &mut Activation<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    _this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let child = child_arg(args, 0)?;
+    if level_of(context, child).is_none() {
+        return Err(not_a_child_error());
+    }
+
+    remove_from_display_list(activation, context, child)?;
+
+    Ok(StageObject::for_display_object(context, child).into())
+}
+
+/// Implements `Stage.getChildAt`
+fn get_child_at<'gc>(
+    activation: &mut Activation<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    _this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let index = first_arg(args).coerce_to_i32(activation, context)?;
+    let child = if index < 0 {
+        None
+    } else {
+        context.levels.values().nth(index as usize).copied()
+    }
+    .ok_or_else(index_error)?;
+
+    Ok(StageObject::for_display_object(context, child).into())
+}
+
+/// Implements `Stage.getChildIndex`
+fn get_child_index<'gc>(
+    _activation: &mut Activation<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    _this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let level_id = level_of(context, child_arg(args, 0)?).ok_or_else(not_a_child_error)?;
+    let index = context.levels.range(..level_id).count();
+
+    Ok((index as u32).into())
+}
+
+/// Implements `Stage.numChildren`
+fn num_children<'gc>(
+    _activation: &mut Activation<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    _this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    Ok((context.levels.len() as u32).into())
+}
+
+/// Implements `Stage.stageWidth`
+fn stage_width<'gc>(
+    _activation: &mut Activation<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    _this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    Ok(context.stage_size.0.to_pixels().into())
+}
+
+/// Implements `Stage.stageHeight`
+fn stage_height<'gc>(
+    _activation: &mut Activation<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    _this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    Ok(context.stage_size.1.to_pixels().into())
+}
+
+/// Create `Stage.prototype`.
+///
+/// The stage's display list is made up of the levels of the player, so it
+/// replaces the child methods of `DisplayObjectContainer`.
+pub fn create_proto<'gc>(
+    mc: MutationContext<'gc, '_>,
+    container_proto: Object<'gc>,
+    fn_proto: Object<'gc>,
+) -> Object<'gc> {
+    let stage_proto = ScriptObject::object(mc, container_proto);
+
+    method(mc, stage_proto, "addChild", add_child, fn_proto);
+    method(mc, stage_proto, "removeChild", remove_child, fn_proto);
+    method(mc, stage_proto, "getChildAt", get_child_at, fn_proto);
+    method(mc, stage_proto, "getChildIndex", get_child_index, fn_proto);
+    getter(mc, stage_proto, "numChildren", num_children, fn_proto);
+    getter(mc, stage_proto, "stageWidth", stage_width, fn_proto);
+    getter(mc, stage_proto, "stageHeight", stage_height, fn_proto);
+
+    stage_proto
+}
+
+/// Construct `Stage`'s class.
+pub fn create_class<'gc>(mc: MutationContext<'gc, '_>) -> GcCell<'gc, Class<'gc>> {
+    Class::new(
+        QName::new(Namespace::package("flash.display"), "Stage"),
+        Some(
+            QName::new(
+                Namespace::package("flash.display"),
+                "DisplayObjectContainer",
+            )
+            .into(),
+        ),
+        Method::Native(instance_init),
+        Method::Native(class_init),
+        mc,
+    )
+}
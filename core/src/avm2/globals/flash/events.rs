@@ -0,0 +1,7 @@
+//! `flash.events` namespace
+
+pub mod event;
+pub mod eventdispatcher;
+pub mod eventphase;
+pub mod keyboardevent;
+pub mod mouseevent;
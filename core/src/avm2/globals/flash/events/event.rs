@@ -0,0 +1,309 @@
+//! `flash.events.Event` builtin/prototype
+
+use crate::avm2::activation::Activation;
+use crate::avm2::class::Class;
+use crate::avm2::event_object::EventObject;
+use crate::avm2::events::Event;
+use crate::avm2::globals::{first_arg, getter, method};
+use crate::avm2::method::Method;
+use crate::avm2::names::{Namespace, QName};
+use crate::avm2::object::{Object, TObject};
+use crate::avm2::property::Attribute;
+use crate::avm2::value::Value;
+use crate::avm2::{AvmString, Error};
+use crate::context::UpdateContext;
+use gc_arena::{GcCell, MutationContext};
+
+/// Implements `flash.events.Event`'s instance constructor.
+pub fn instance_init<'gc>(
+    activation: &mut Activation<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    if let Some(this) = this.and_then(|this| this.as_event_object()) {
+        let mut event = Event::new(first_arg(args).coerce_to_string(activation, context)?);
+        event.set_bubbles(args.get(1).map_or(false, |v| v.coerce_to_boolean()));
+        event.set_cancelable(args.get(2).map_or(false, |v| v.coerce_to_boolean()));
+
+        this.set_event(context.gc_context, event);
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `flash.events.Event`'s class constructor.
+fn class_init<'gc>(
+    _activation: &mut Activation<'gc>,
+    _context: &mut UpdateContext<'_, 'gc, '_>,
+    _this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    Ok(Value::Undefined)
+}
+
+/// Allocates instances of `Event`.
+fn allocator<'gc>(
+    proto: Object<'gc>,
+    _activation: &mut Activation<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+) -> Result<Object<'gc>, Error<'gc>> {
+    Ok(EventObject::from_event(context.gc_context, proto, Event::new("")).into())
+}
+
+/// The event `this` refers to.
+fn this_event(this: Option<Object<'_>>) -> Result<EventObject<'_>, Error<'_>> {
+    this.and_then(|this| this.as_event_object()).ok_or_else(|| {
+        Error::TypeError("Event method called on a value that isn't an Event".to_string())
+    })
+}
+
+/// Implements `Event.type`
+fn event_type<'gc>(
+    _activation: &mut Activation<'gc>,
+    _context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    Ok(this_event(this)?.event().event_type().into())
+}
+
+/// Implements `Event.bubbles`
+fn bubbles<'gc>(
+    _activation: &mut Activation<'gc>,
+    _context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    Ok(this_event(this)?.event().is_bubbling().into())
+}
+
+/// Implements `Event.cancelable`
+fn cancelable<'gc>(
+    _activation: &mut Activation<'gc>,
+    _context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    Ok(this_event(this)?.event().is_cancelable().into())
+}
+
+/// Implements `Event.target`
+fn target<'gc>(
+    _activation: &mut Activation<'gc>,
+    _context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    Ok(this_event(this)?
+        .event()
+        .target()
+        .map(Value::Object)
+        .unwrap_or(Value::Null))
+}
+
+/// Implements `Event.currentTarget`
+fn current_target<'gc>(
+    _activation: &mut Activation<'gc>,
+    _context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    Ok(this_event(this)?
+        .event()
+        .current_target()
+        .map(Value::Object)
+        .unwrap_or(Value::Null))
+}
+
+/// Implements `Event.eventPhase`
+fn event_phase<'gc>(
+    _activation: &mut Activation<'gc>,
+    _context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    Ok(u32::from(this_event(this)?.event().phase()).into())
+}
+
+/// Implements `Event.stopPropagation`
+fn stop_propagation<'gc>(
+    _activation: &mut Activation<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    this_event(this)?
+        .event_mut(context.gc_context)
+        .stop_propagation();
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `Event.stopImmediatePropagation`
+fn stop_immediate_propagation<'gc>(
+    _activation: &mut Activation<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    this_event(this)?
+        .event_mut(context.gc_context)
+        .stop_immediate_propagation();
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `Event.preventDefault`
+fn prevent_default<'gc>(
+    _activation: &mut Activation<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    this_event(this)?.event_mut(context.gc_context).cancel();
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `Event.isDefaultPrevented`
+fn is_default_prevented<'gc>(
+    _activation: &mut Activation<'gc>,
+    _context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    Ok(this_event(this)?.event().is_cancelled().into())
+}
+
+/// Implements `Event.clone`
+fn clone<'gc>(
+    _activation: &mut Activation<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let event = this_event(this)?;
+    let proto = Object::from(event)
+        .proto()
+        .unwrap_or_else(|| context.avm2.prototypes().event);
+    let copy = event.event().fresh_copy();
+
+    Ok(Object::from(EventObject::from_event(context.gc_context, proto, copy)).into())
+}
+
+/// Implements `Event.toString`
+fn to_string<'gc>(
+    _activation: &mut Activation<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let event_object = this_event(this)?;
+    let event = event_object.event();
+
+    Ok(AvmString::new(
+        context.gc_context,
+        format!(
+            "[Event type=\"{}\" bubbles={} cancelable={} eventPhase={}]",
+            event.event_type(),
+            event.is_bubbling(),
+            event.is_cancelable(),
+            u32::from(event.phase())
+        ),
+    )
+    .into())
+}
+
+/// Create `Event.prototype`.
+pub fn create_proto<'gc>(
+    mc: MutationContext<'gc, '_>,
+    object_proto: Object<'gc>,
+    fn_proto: Object<'gc>,
+) -> Object<'gc> {
+    let event_proto: Object<'gc> = EventObject::from_event(mc, object_proto, Event::new("")).into();
+
+    getter(mc, event_proto, "type", event_type, fn_proto);
+    getter(mc, event_proto, "bubbles", bubbles, fn_proto);
+    getter(mc, event_proto, "cancelable", cancelable, fn_proto);
+    getter(mc, event_proto, "target", target, fn_proto);
+    getter(mc, event_proto, "currentTarget", current_target, fn_proto);
+    getter(mc, event_proto, "eventPhase", event_phase, fn_proto);
+    method(
+        mc,
+        event_proto,
+        "stopPropagation",
+        stop_propagation,
+        fn_proto,
+    );
+    method(
+        mc,
+        event_proto,
+        "stopImmediatePropagation",
+        stop_immediate_propagation,
+        fn_proto,
+    );
+    method(mc, event_proto, "preventDefault", prevent_default, fn_proto);
+    method(
+        mc,
+        event_proto,
+        "isDefaultPrevented",
+        is_default_prevented,
+        fn_proto,
+    );
+    method(mc, event_proto, "clone", clone, fn_proto);
+    method(mc, event_proto, "toString", to_string, fn_proto);
+
+    event_proto
+}
+
+/// Define the event type constants of `Event`, such as `Event.ENTER_FRAME`.
+pub fn define_event_types<'gc>(mc: MutationContext<'gc, '_>, class_object: Object<'gc>) {
+    const EVENT_TYPES: &[(&str, &str)] = &[
+        ("ACTIVATE", "activate"),
+        ("ADDED", "added"),
+        ("ADDED_TO_STAGE", "addedToStage"),
+        ("CANCEL", "cancel"),
+        ("CHANGE", "change"),
+        ("CLOSE", "close"),
+        ("COMPLETE", "complete"),
+        ("DEACTIVATE", "deactivate"),
+        ("ENTER_FRAME", "enterFrame"),
+        ("EXIT_FRAME", "exitFrame"),
+        ("FRAME_CONSTRUCTED", "frameConstructed"),
+        ("INIT", "init"),
+        ("OPEN", "open"),
+        ("REMOVED", "removed"),
+        ("REMOVED_FROM_STAGE", "removedFromStage"),
+        ("RENDER", "render"),
+        ("RESIZE", "resize"),
+        ("SCROLL", "scroll"),
+        ("SELECT", "select"),
+        ("UNLOAD", "unload"),
+    ];
+
+    let constants = Attribute::DontEnum | Attribute::DontDelete | Attribute::ReadOnly;
+    for (name, event_type) in EVENT_TYPES {
+        class_object.define_value(
+            mc,
+            QName::dynamic_name(*name),
+            (*event_type).into(),
+            constants,
+        );
+    }
+}
+
+/// Construct `Event`'s class.
+pub fn create_class<'gc>(mc: MutationContext<'gc, '_>) -> GcCell<'gc, Class<'gc>> {
+    let class = Class::new(
+        QName::new(Namespace::package("flash.events"), "Event"),
+        Some(QName::new(Namespace::public(), "Object").into()),
+        Method::Native(instance_init),
+        Method::Native(class_init),
+        mc,
+    );
+
+    class.write(mc).set_instance_allocator(allocator);
+
+    class
+}
@@ -0,0 +1,228 @@
+//! `flash.events.EventDispatcher` builtin/prototype
+
+use crate::avm2::activation::Activation;
+use crate::avm2::class::Class;
+use crate::avm2::event_object::EventObject;
+use crate::avm2::events::{dispatch_event, dispatch_list, is_broadcast_event, parent_object};
+use crate::avm2::globals::{first_arg, method};
+use crate::avm2::method::Method;
+use crate::avm2::names::{Namespace, QName};
+use crate::avm2::object::{Object, TObject};
+use crate::avm2::script_object::ScriptObject;
+use crate::avm2::value::Value;
+use crate::avm2::{Avm2, Error};
+use crate::context::UpdateContext;
+use gc_arena::{GcCell, MutationContext};
+
+/// Implements `flash.events.EventDispatcher`'s instance constructor.
+fn instance_init<'gc>(
+    _activation: &mut Activation<'gc>,
+    _context: &mut UpdateContext<'_, 'gc, '_>,
+    _this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    Ok(Value::Undefined)
+}
+
+/// Implements `flash.events.EventDispatcher`'s class constructor.
+fn class_init<'gc>(
+    _activation: &mut Activation<'gc>,
+    _context: &mut UpdateContext<'_, 'gc, '_>,
+    _this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    Ok(Value::Undefined)
+}
+
+/// Implements `EventDispatcher.addEventListener`
+fn add_event_listener<'gc>(
+    activation: &mut Activation<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    if let Some(this) = this {
+        let event_type = first_arg(args).coerce_to_string(activation, context)?;
+        let listener = args
+            .get(1)
+            .cloned()
+            .unwrap_or(Value::Undefined)
+            .as_object()?;
+        let use_capture = args.get(2).map_or(false, |v| v.coerce_to_boolean());
+        let priority = match args.get(3) {
+            Some(priority) => priority.coerce_to_i32(activation, context)?,
+            None => 0,
+        };
+
+        dispatch_list(activation, context, this)?
+            .dispatch_list_mut(context.gc_context)
+            .add_event_listener(event_type, priority, listener, use_capture);
+
+        // Broadcast events are sent to every display object that listens to them.
+        if is_broadcast_event(&event_type) && this.as_display_object().is_some() {
+            Avm2::register_broadcast_listener(context, this, event_type);
+        }
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `EventDispatcher.removeEventListener`
+fn remove_event_listener<'gc>(
+    activation: &mut Activation<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    if let Some(this) = this {
+        let event_type = first_arg(args).coerce_to_string(activation, context)?;
+        let listener = args
+            .get(1)
+            .cloned()
+            .unwrap_or(Value::Undefined)
+            .as_object()?;
+        let use_capture = args.get(2).map_or(false, |v| v.coerce_to_boolean());
+
+        dispatch_list(activation, context, this)?
+            .dispatch_list_mut(context.gc_context)
+            .remove_event_listener(event_type, listener, use_capture);
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `EventDispatcher.hasEventListener`
+fn has_event_listener<'gc>(
+    activation: &mut Activation<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    if let Some(this) = this {
+        let event_type = first_arg(args).coerce_to_string(activation, context)?;
+
+        return Ok(dispatch_list(activation, context, this)?
+            .dispatch_list()
+            .has_event_listener(event_type)
+            .into());
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `EventDispatcher.willTrigger`
+///
+/// This also checks the ancestors that the event would be captured or
+/// bubbled by.
+fn will_trigger<'gc>(
+    activation: &mut Activation<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let event_type = first_arg(args).coerce_to_string(activation, context)?;
+
+    let mut current = this;
+    while let Some(object) = current {
+        if dispatch_list(activation, context, object)?
+            .dispatch_list()
+            .has_event_listener(event_type)
+        {
+            return Ok(true.into());
+        }
+
+        current = object
+            .as_display_object()
+            .and_then(|dobj| parent_object(context, dobj));
+    }
+
+    Ok(false.into())
+}
+
+/// Implements `EventDispatcher.dispatchEvent`
+///
+/// An event that has already been dispatched is copied, so that the
+/// original keeps its target.
+fn dispatch_event_method<'gc>(
+    activation: &mut Activation<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let this = match this {
+        Some(this) => this,
+        None => return Ok(Value::Undefined),
+    };
+    let event_object = first_arg(args).as_object()?;
+    let mut event = event_object.as_event_object().ok_or_else(|| {
+        Error::TypeError("Error #1034: Type Coercion failed: not an Event.".to_string())
+    })?;
+
+    if event.event().target().is_some() {
+        let copy = event.event().fresh_copy();
+        let proto = event_object
+            .proto()
+            .unwrap_or_else(|| context.avm2.prototypes().event);
+        event = EventObject::from_event(context.gc_context, proto, copy);
+    }
+
+    Ok(dispatch_event(activation, context, this, event)?.into())
+}
+
+/// Create `EventDispatcher.prototype`.
+pub fn create_proto<'gc>(
+    mc: MutationContext<'gc, '_>,
+    object_proto: Object<'gc>,
+    fn_proto: Object<'gc>,
+) -> Object<'gc> {
+    let event_dispatcher_proto = ScriptObject::object(mc, object_proto);
+
+    method(
+        mc,
+        event_dispatcher_proto,
+        "addEventListener",
+        add_event_listener,
+        fn_proto,
+    );
+    method(
+        mc,
+        event_dispatcher_proto,
+        "removeEventListener",
+        remove_event_listener,
+        fn_proto,
+    );
+    method(
+        mc,
+        event_dispatcher_proto,
+        "hasEventListener",
+        has_event_listener,
+        fn_proto,
+    );
+    method(
+        mc,
+        event_dispatcher_proto,
+        "willTrigger",
+        will_trigger,
+        fn_proto,
+    );
+    method(
+        mc,
+        event_dispatcher_proto,
+        "dispatchEvent",
+        dispatch_event_method,
+        fn_proto,
+    );
+
+    event_dispatcher_proto
+}
+
+/// Construct `EventDispatcher`'s class.
+pub fn create_class<'gc>(mc: MutationContext<'gc, '_>) -> GcCell<'gc, Class<'gc>> {
+    Class::new(
+        QName::new(Namespace::package("flash.events"), "EventDispatcher"),
+        Some(QName::new(Namespace::public(), "Object").into()),
+        Method::Native(instance_init),
+        Method::Native(class_init),
+        mc,
+    )
+}
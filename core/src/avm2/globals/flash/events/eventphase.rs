@@ -0,0 +1,67 @@
+//! `flash.events.EventPhase` builtin/prototype
+
+use crate::avm2::activation::Activation;
+use crate::avm2::class::Class;
+use crate::avm2::events::EventPhase;
+use crate::avm2::method::Method;
+use crate::avm2::names::{Namespace, QName};
+use crate::avm2::object::{Object, TObject};
+use crate::avm2::property::Attribute;
+use crate::avm2::value::Value;
+use crate::avm2::Error;
+use crate::context::UpdateContext;
+use gc_arena::{GcCell, MutationContext};
+
+/// Implements `flash.events.EventPhase`'s instance constructor.
+fn instance_init<'gc>(
+    _activation: &mut Activation<'gc>,
+    _context: &mut UpdateContext<'_, 'gc, '_>,
+    _this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    Ok(Value::Undefined)
+}
+
+/// Implements `flash.events.EventPhase`'s class constructor.
+fn class_init<'gc>(
+    _activation: &mut Activation<'gc>,
+    _context: &mut UpdateContext<'_, 'gc, '_>,
+    _this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    Ok(Value::Undefined)
+}
+
+/// Define the phase constants of `EventPhase`.
+pub fn define_phases<'gc>(mc: MutationContext<'gc, '_>, class_object: Object<'gc>) {
+    let constants = Attribute::DontEnum | Attribute::DontDelete | Attribute::ReadOnly;
+    class_object.define_value(
+        mc,
+        QName::dynamic_name("CAPTURING_PHASE"),
+        u32::from(EventPhase::Capturing).into(),
+        constants,
+    );
+    class_object.define_value(
+        mc,
+        QName::dynamic_name("AT_TARGET"),
+        u32::from(EventPhase::AtTarget).into(),
+        constants,
+    );
+    class_object.define_value(
+        mc,
+        QName::dynamic_name("BUBBLING_PHASE"),
+        u32::from(EventPhase::Bubbling).into(),
+        constants,
+    );
+}
+
+/// Construct `EventPhase`'s class.
+pub fn create_class<'gc>(mc: MutationContext<'gc, '_>) -> GcCell<'gc, Class<'gc>> {
+    Class::new(
+        QName::new(Namespace::package("flash.events"), "EventPhase"),
+        Some(QName::new(Namespace::public(), "Object").into()),
+        Method::Native(instance_init),
+        Method::Native(class_init),
+        mc,
+    )
+}
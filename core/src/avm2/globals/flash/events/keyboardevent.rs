@@ -0,0 +1,351 @@
+//! `flash.events.KeyboardEvent` builtin/prototype
+
+use crate::avm2::activation::Activation;
+use crate::avm2::class::Class;
+use crate::avm2::event_object::EventObject;
+use crate::avm2::events::{EventData, KeyboardEventData};
+use crate::avm2::globals::flash::events::event;
+use crate::avm2::globals::{first_arg, getter, method, setter};
+use crate::avm2::method::Method;
+use crate::avm2::names::{Namespace, QName};
+use crate::avm2::object::{Object, TObject};
+use crate::avm2::property::Attribute;
+use crate::avm2::script_object::ScriptObject;
+use crate::avm2::value::Value;
+use crate::avm2::{AvmString, Error};
+use crate::context::UpdateContext;
+use gc_arena::{GcCell, MutationContext};
+
+/// Implements `flash.events.KeyboardEvent`'s instance constructor.
+///
+/// Like mouse events, keyboard events bubble unless told not to.
+fn instance_init<'gc>(
+    activation: &mut Activation<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let bubbles = args.get(1).map_or(true, |v| v.coerce_to_boolean());
+    let cancelable = args.get(2).map_or(false, |v| v.coerce_to_boolean());
+    event::instance_init(
+        activation,
+        context,
+        this,
+        &[first_arg(args), bubbles.into(), cancelable.into()],
+    )?;
+
+    if let Some(this) = this.and_then(|this| this.as_event_object()) {
+        let mut u32_arg = |index: usize| match args.get(index) {
+            Some(value) => value.coerce_to_u32(activation, context),
+            None => Ok(0),
+        };
+        let char_code = u32_arg(3)?;
+        let key_code = u32_arg(4)?;
+        let key_location = u32_arg(5)?;
+        let bool_arg = |index: usize| args.get(index).map_or(false, |v| v.coerce_to_boolean());
+        let data = KeyboardEventData {
+            char_code,
+            key_code,
+            key_location,
+            ctrl_key: bool_arg(6),
+            alt_key: bool_arg(7),
+            shift_key: bool_arg(8),
+        };
+
+        this.event_mut(context.gc_context)
+            .set_event_data(EventData::Keyboard(data));
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `flash.events.KeyboardEvent`'s class constructor.
+fn class_init<'gc>(
+    _activation: &mut Activation<'gc>,
+    _context: &mut UpdateContext<'_, 'gc, '_>,
+    _this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    Ok(Value::Undefined)
+}
+
+/// The keyboard event `this` refers to.
+fn this_keyboard_event(this: Option<Object<'_>>) -> Result<EventObject<'_>, Error<'_>> {
+    this.and_then(|this| this.as_event_object())
+        .filter(|event| matches!(event.event().event_data(), EventData::Keyboard(_)))
+        .ok_or_else(|| {
+            Error::TypeError(
+                "KeyboardEvent method called on a value that isn't a KeyboardEvent".to_string(),
+            )
+        })
+}
+
+/// Read the keyboard data of the event `this` refers to.
+fn keyboard_data(this: Option<Object<'_>>) -> Result<KeyboardEventData, Error<'_>> {
+    match this_keyboard_event(this)?.event().event_data() {
+        EventData::Keyboard(data) => Ok(data.clone()),
+        _ => unreachable!(),
+    }
+}
+
+/// Change the keyboard data of the event `this` refers to.
+fn set_keyboard_data<'gc>(
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    f: impl FnOnce(&mut KeyboardEventData),
+) -> Result<Value<'gc>, Error<'gc>> {
+    let event = this_keyboard_event(this)?;
+    if let EventData::Keyboard(data) = event.event_mut(context.gc_context).event_data_mut() {
+        f(data);
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `KeyboardEvent.charCode`
+fn char_code<'gc>(
+    _activation: &mut Activation<'gc>,
+    _context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    Ok(keyboard_data(this)?.char_code.into())
+}
+
+/// Implements `KeyboardEvent.charCode`'s setter
+fn set_char_code<'gc>(
+    activation: &mut Activation<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let value = first_arg(args).coerce_to_u32(activation, context)?;
+    set_keyboard_data(context, this, |data| data.char_code = value)
+}
+
+/// Implements `KeyboardEvent.keyCode`
+fn key_code<'gc>(
+    _activation: &mut Activation<'gc>,
+    _context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    Ok(keyboard_data(this)?.key_code.into())
+}
+
+/// Implements `KeyboardEvent.keyCode`'s setter
+fn set_key_code<'gc>(
+    activation: &mut Activation<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let value = first_arg(args).coerce_to_u32(activation, context)?;
+    set_keyboard_data(context, this, |data| data.key_code = value)
+}
+
+/// Implements `KeyboardEvent.keyLocation`
+fn key_location<'gc>(
+    _activation: &mut Activation<'gc>,
+    _context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    Ok(keyboard_data(this)?.key_location.into())
+}
+
+/// Implements `KeyboardEvent.keyLocation`'s setter
+fn set_key_location<'gc>(
+    activation: &mut Activation<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let value = first_arg(args).coerce_to_u32(activation, context)?;
+    set_keyboard_data(context, this, |data| data.key_location = value)
+}
+
+/// Implements `KeyboardEvent.ctrlKey`
+fn ctrl_key<'gc>(
+    _activation: &mut Activation<'gc>,
+    _context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    Ok(keyboard_data(this)?.ctrl_key.into())
+}
+
+/// Implements `KeyboardEvent.ctrlKey`'s setter
+fn set_ctrl_key<'gc>(
+    _activation: &mut Activation<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let value = first_arg(args).coerce_to_boolean();
+    set_keyboard_data(context, this, |data| data.ctrl_key = value)
+}
+
+/// Implements `KeyboardEvent.altKey`
+fn alt_key<'gc>(
+    _activation: &mut Activation<'gc>,
+    _context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    Ok(keyboard_data(this)?.alt_key.into())
+}
+
+/// Implements `KeyboardEvent.altKey`'s setter
+fn set_alt_key<'gc>(
+    _activation: &mut Activation<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let value = first_arg(args).coerce_to_boolean();
+    set_keyboard_data(context, this, |data| data.alt_key = value)
+}
+
+/// Implements `KeyboardEvent.shiftKey`
+fn shift_key<'gc>(
+    _activation: &mut Activation<'gc>,
+    _context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    Ok(keyboard_data(this)?.shift_key.into())
+}
+
+/// Implements `KeyboardEvent.shiftKey`'s setter
+fn set_shift_key<'gc>(
+    _activation: &mut Activation<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let value = first_arg(args).coerce_to_boolean();
+    set_keyboard_data(context, this, |data| data.shift_key = value)
+}
+
+/// Implements `KeyboardEvent.updateAfterEvent`
+///
+/// The stage is rendered after every input event anyway.
+fn update_after_event<'gc>(
+    _activation: &mut Activation<'gc>,
+    _context: &mut UpdateContext<'_, 'gc, '_>,
+    _this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    Ok(Value::Undefined)
+}
+
+/// Implements `KeyboardEvent.toString`
+fn to_string<'gc>(
+    _activation: &mut Activation<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let data = keyboard_data(this)?;
+    let event_object = this_keyboard_event(this)?;
+    let event = event_object.event();
+
+    Ok(AvmString::new(
+        context.gc_context,
+        format!(
+            "[KeyboardEvent type=\"{}\" bubbles={} cancelable={} eventPhase={} charCode={} keyCode={} keyLocation={} ctrlKey={} altKey={} shiftKey={}]",
+            event.event_type(),
+            event.is_bubbling(),
+            event.is_cancelable(),
+            u32::from(event.phase()),
+            data.char_code,
+            data.key_code,
+            data.key_location,
+            data.ctrl_key,
+            data.alt_key,
+            data.shift_key
+        ),
+    )
+    .into())
+}
+
+/// Create `KeyboardEvent.prototype`.
+pub fn create_proto<'gc>(
+    mc: MutationContext<'gc, '_>,
+    event_proto: Object<'gc>,
+    fn_proto: Object<'gc>,
+) -> Object<'gc> {
+    let keyboard_event_proto = ScriptObject::object(mc, event_proto);
+
+    getter(mc, keyboard_event_proto, "charCode", char_code, fn_proto);
+    setter(
+        mc,
+        keyboard_event_proto,
+        "charCode",
+        set_char_code,
+        fn_proto,
+    );
+    getter(mc, keyboard_event_proto, "keyCode", key_code, fn_proto);
+    setter(mc, keyboard_event_proto, "keyCode", set_key_code, fn_proto);
+    getter(
+        mc,
+        keyboard_event_proto,
+        "keyLocation",
+        key_location,
+        fn_proto,
+    );
+    setter(
+        mc,
+        keyboard_event_proto,
+        "keyLocation",
+        set_key_location,
+        fn_proto,
+    );
+    getter(mc, keyboard_event_proto, "ctrlKey", ctrl_key, fn_proto);
+    setter(mc, keyboard_event_proto, "ctrlKey", set_ctrl_key, fn_proto);
+    getter(mc, keyboard_event_proto, "altKey", alt_key, fn_proto);
+    setter(mc, keyboard_event_proto, "altKey", set_alt_key, fn_proto);
+    getter(mc, keyboard_event_proto, "shiftKey", shift_key, fn_proto);
+    setter(
+        mc,
+        keyboard_event_proto,
+        "shiftKey",
+        set_shift_key,
+        fn_proto,
+    );
+    method(
+        mc,
+        keyboard_event_proto,
+        "updateAfterEvent",
+        update_after_event,
+        fn_proto,
+    );
+    method(mc, keyboard_event_proto, "toString", to_string, fn_proto);
+
+    keyboard_event_proto
+}
+
+/// Define the event type constants of `KeyboardEvent`, such as
+/// `KeyboardEvent.KEY_DOWN`.
+pub fn define_event_types<'gc>(mc: MutationContext<'gc, '_>, class_object: Object<'gc>) {
+    let constants = Attribute::DontEnum | Attribute::DontDelete | Attribute::ReadOnly;
+    class_object.define_value(
+        mc,
+        QName::dynamic_name("KEY_DOWN"),
+        "keyDown".into(),
+        constants,
+    );
+    class_object.define_value(mc, QName::dynamic_name("KEY_UP"), "keyUp".into(), constants);
+}
+
+/// Construct `KeyboardEvent`'s class.
+pub fn create_class<'gc>(mc: MutationContext<'gc, '_>) -> GcCell<'gc, Class<'gc>> {
+    Class::new(
+        QName::new(Namespace::package("flash.events"), "KeyboardEvent"),
+        Some(QName::new(Namespace::package("flash.events"), "Event").into()),
+        Method::Native(instance_init),
+        Method::Native(class_init),
+        mc,
+    )
+}
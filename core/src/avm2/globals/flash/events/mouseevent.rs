@@ -0,0 +1,462 @@
+//! `flash.events.MouseEvent` builtin/prototype
+
+use crate::avm2::activation::Activation;
+use crate::avm2::class::Class;
+use crate::avm2::event_object::EventObject;
+use crate::avm2::events::{EventData, MouseEventData};
+use crate::avm2::globals::flash::events::event;
+use crate::avm2::globals::{first_arg, getter, method, setter};
+use crate::avm2::method::Method;
+use crate::avm2::names::{Namespace, QName};
+use crate::avm2::object::{Object, TObject};
+use crate::avm2::property::Attribute;
+use crate::avm2::script_object::ScriptObject;
+use crate::avm2::value::Value;
+use crate::avm2::{AvmString, Error};
+use crate::context::UpdateContext;
+use crate::display_object::TDisplayObject;
+use gc_arena::{GcCell, MutationContext};
+use std::f64::NAN;
+use swf::Twips;
+
+/// Implements `flash.events.MouseEvent`'s instance constructor.
+///
+/// Unlike other events, mouse events bubble unless told not to.
+fn instance_init<'gc>(
+    activation: &mut Activation<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let bubbles = args.get(1).map_or(true, |v| v.coerce_to_boolean());
+    let cancelable = args.get(2).map_or(false, |v| v.coerce_to_boolean());
+    event::instance_init(
+        activation,
+        context,
+        this,
+        &[first_arg(args), bubbles.into(), cancelable.into()],
+    )?;
+
+    if let Some(this) = this.and_then(|this| this.as_event_object()) {
+        let mut number_arg = |index: usize| match args.get(index) {
+            Some(value) => value.coerce_to_number(activation, context),
+            None => Ok(NAN),
+        };
+        let local_x = number_arg(3)?;
+        let local_y = number_arg(4)?;
+        let bool_arg = |index: usize| args.get(index).map_or(false, |v| v.coerce_to_boolean());
+        let data = MouseEventData {
+            local_x,
+            local_y,
+            related_object: args.get(5).and_then(|v| v.as_object().ok()),
+            ctrl_key: bool_arg(6),
+            alt_key: bool_arg(7),
+            shift_key: bool_arg(8),
+            button_down: bool_arg(9),
+            delta: match args.get(10) {
+                Some(delta) => delta.coerce_to_i32(activation, context)?,
+                None => 0,
+            },
+        };
+
+        this.event_mut(context.gc_context)
+            .set_event_data(EventData::Mouse(data));
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `flash.events.MouseEvent`'s class constructor.
+fn class_init<'gc>(
+    _activation: &mut Activation<'gc>,
+    _context: &mut UpdateContext<'_, 'gc, '_>,
+    _this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    Ok(Value::Undefined)
+}
+
+/// The mouse event `this` refers to.
+fn this_mouse_event(this: Option<Object<'_>>) -> Result<EventObject<'_>, Error<'_>> {
+    this.and_then(|this| this.as_event_object())
+        .filter(|event| matches!(event.event().event_data(), EventData::Mouse(_)))
+        .ok_or_else(|| {
+            Error::TypeError(
+                "MouseEvent method called on a value that isn't a MouseEvent".to_string(),
+            )
+        })
+}
+
+/// Read the mouse data of the event `this` refers to.
+fn mouse_data<'gc>(this: Option<Object<'gc>>) -> Result<MouseEventData<'gc>, Error<'gc>> {
+    match this_mouse_event(this)?.event().event_data() {
+        EventData::Mouse(data) => Ok(data.clone()),
+        _ => unreachable!(),
+    }
+}
+
+/// Change the mouse data of the event `this` refers to.
+fn set_mouse_data<'gc>(
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    f: impl FnOnce(&mut MouseEventData<'gc>),
+) -> Result<Value<'gc>, Error<'gc>> {
+    let event = this_mouse_event(this)?;
+    if let EventData::Mouse(data) = event.event_mut(context.gc_context).event_data_mut() {
+        f(data);
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// The position of the event on the stage, from its position in the
+/// coordinate space of its target.
+fn stage_position<'gc>(this: Option<Object<'gc>>) -> Result<(f64, f64), Error<'gc>> {
+    let data = mouse_data(this)?;
+    let target = this_mouse_event(this)?
+        .event()
+        .target()
+        .and_then(|target| target.as_display_object());
+
+    Ok(match target {
+        Some(target) => {
+            let (x, y) = target.local_to_global((
+                Twips::from_pixels(data.local_x),
+                Twips::from_pixels(data.local_y),
+            ));
+            (x.to_pixels(), y.to_pixels())
+        }
+        None => (data.local_x, data.local_y),
+    })
+}
+
+/// Implements `MouseEvent.localX`
+fn local_x<'gc>(
+    _activation: &mut Activation<'gc>,
+    _context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    Ok(mouse_data(this)?.local_x.into())
+}
+
+/// Implements `MouseEvent.localX`'s setter
+fn set_local_x<'gc>(
+    activation: &mut Activation<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let value = first_arg(args).coerce_to_number(activation, context)?;
+    set_mouse_data(context, this, |data| data.local_x = value)
+}
+
+/// Implements `MouseEvent.localY`
+fn local_y<'gc>(
+    _activation: &mut Activation<'gc>,
+    _context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    Ok(mouse_data(this)?.local_y.into())
+}
+
+/// Implements `MouseEvent.localY`'s setter
+fn set_local_y<'gc>(
+    activation: &mut Activation<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let value = first_arg(args).coerce_to_number(activation, context)?;
+    set_mouse_data(context, this, |data| data.local_y = value)
+}
+
+/// Implements `MouseEvent.ctrlKey`
+fn ctrl_key<'gc>(
+    _activation: &mut Activation<'gc>,
+    _context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    Ok(mouse_data(this)?.ctrl_key.into())
+}
+
+/// Implements `MouseEvent.ctrlKey`'s setter
+fn set_ctrl_key<'gc>(
+    _activation: &mut Activation<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let value = first_arg(args).coerce_to_boolean();
+    set_mouse_data(context, this, |data| data.ctrl_key = value)
+}
+
+/// Implements `MouseEvent.altKey`
+fn alt_key<'gc>(
+    _activation: &mut Activation<'gc>,
+    _context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    Ok(mouse_data(this)?.alt_key.into())
+}
+
+/// Implements `MouseEvent.altKey`'s setter
+fn set_alt_key<'gc>(
+    _activation: &mut Activation<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let value = first_arg(args).coerce_to_boolean();
+    set_mouse_data(context, this, |data| data.alt_key = value)
+}
+
+/// Implements `MouseEvent.shiftKey`
+fn shift_key<'gc>(
+    _activation: &mut Activation<'gc>,
+    _context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    Ok(mouse_data(this)?.shift_key.into())
+}
+
+/// Implements `MouseEvent.shiftKey`'s setter
+fn set_shift_key<'gc>(
+    _activation: &mut Activation<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let value = first_arg(args).coerce_to_boolean();
+    set_mouse_data(context, this, |data| data.shift_key = value)
+}
+
+/// Implements `MouseEvent.buttonDown`
+fn button_down<'gc>(
+    _activation: &mut Activation<'gc>,
+    _context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    Ok(mouse_data(this)?.button_down.into())
+}
+
+/// Implements `MouseEvent.buttonDown`'s setter
+fn set_button_down<'gc>(
+    _activation: &mut Activation<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let value = first_arg(args).coerce_to_boolean();
+    set_mouse_data(context, this, |data| data.button_down = value)
+}
+
+/// Implements `MouseEvent.delta`
+fn delta<'gc>(
+    _activation: &mut Activation<'gc>,
+    _context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    Ok(mouse_data(this)?.delta.into())
+}
+
+/// Implements `MouseEvent.delta`'s setter
+fn set_delta<'gc>(
+    activation: &mut Activation<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let value = first_arg(args).coerce_to_i32(activation, context)?;
+    set_mouse_data(context, this, |data| data.delta = value)
+}
+
+/// Implements `MouseEvent.relatedObject`
+fn related_object<'gc>(
+    _activation: &mut Activation<'gc>,
+    _context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    Ok(mouse_data(this)?
+        .related_object
+        .map(Value::Object)
+        .unwrap_or(Value::Null))
+}
+
+/// Implements `MouseEvent.relatedObject`'s setter
+fn set_related_object<'gc>(
+    _activation: &mut Activation<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let value = first_arg(args).as_object().ok();
+    set_mouse_data(context, this, |data| data.related_object = value)
+}
+
+/// Implements `MouseEvent.stageX`
+fn stage_x<'gc>(
+    _activation: &mut Activation<'gc>,
+    _context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    Ok(stage_position(this)?.0.into())
+}
+
+/// Implements `MouseEvent.stageY`
+fn stage_y<'gc>(
+    _activation: &mut Activation<'gc>,
+    _context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    Ok(stage_position(this)?.1.into())
+}
+
+/// Implements `MouseEvent.updateAfterEvent`
+///
+/// The stage is rendered after every input event anyway.
+fn update_after_event<'gc>(
+    _activation: &mut Activation<'gc>,
+    _context: &mut UpdateContext<'_, 'gc, '_>,
+    _this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    Ok(Value::Undefined)
+}
+
+/// Implements `MouseEvent.toString`
+fn to_string<'gc>(
+    _activation: &mut Activation<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let data = mouse_data(this)?;
+    let (stage_x, stage_y) = stage_position(this)?;
+    let event_object = this_mouse_event(this)?;
+    let event = event_object.event();
+
+    Ok(AvmString::new(
+        context.gc_context,
+        format!(
+            "[MouseEvent type=\"{}\" bubbles={} cancelable={} eventPhase={} localX={} localY={} stageX={} stageY={} relatedObject={} ctrlKey={} altKey={} shiftKey={} buttonDown={} delta={}]",
+            event.event_type(),
+            event.is_bubbling(),
+            event.is_cancelable(),
+            u32::from(event.phase()),
+            data.local_x,
+            data.local_y,
+            stage_x,
+            stage_y,
+            if data.related_object.is_some() { "[object]" } else { "null" },
+            data.ctrl_key,
+            data.alt_key,
+            data.shift_key,
+            data.button_down,
+            data.delta
+        ),
+    )
+    .into())
+}
+
+/// Create `MouseEvent.prototype`.
+pub fn create_proto<'gc>(
+    mc: MutationContext<'gc, '_>,
+    event_proto: Object<'gc>,
+    fn_proto: Object<'gc>,
+) -> Object<'gc> {
+    let mouse_event_proto = ScriptObject::object(mc, event_proto);
+
+    getter(mc, mouse_event_proto, "localX", local_x, fn_proto);
+    setter(mc, mouse_event_proto, "localX", set_local_x, fn_proto);
+    getter(mc, mouse_event_proto, "localY", local_y, fn_proto);
+    setter(mc, mouse_event_proto, "localY", set_local_y, fn_proto);
+    getter(mc, mouse_event_proto, "stageX", stage_x, fn_proto);
+    getter(mc, mouse_event_proto, "stageY", stage_y, fn_proto);
+    getter(
+        mc,
+        mouse_event_proto,
+        "relatedObject",
+        related_object,
+        fn_proto,
+    );
+    setter(
+        mc,
+        mouse_event_proto,
+        "relatedObject",
+        set_related_object,
+        fn_proto,
+    );
+    getter(mc, mouse_event_proto, "ctrlKey", ctrl_key, fn_proto);
+    setter(mc, mouse_event_proto, "ctrlKey", set_ctrl_key, fn_proto);
+    getter(mc, mouse_event_proto, "altKey", alt_key, fn_proto);
+    setter(mc, mouse_event_proto, "altKey", set_alt_key, fn_proto);
+    getter(mc, mouse_event_proto, "shiftKey", shift_key, fn_proto);
+    setter(mc, mouse_event_proto, "shiftKey", set_shift_key, fn_proto);
+    getter(mc, mouse_event_proto, "buttonDown", button_down, fn_proto);
+    setter(
+        mc,
+        mouse_event_proto,
+        "buttonDown",
+        set_button_down,
+        fn_proto,
+    );
+    getter(mc, mouse_event_proto, "delta", delta, fn_proto);
+    setter(mc, mouse_event_proto, "delta", set_delta, fn_proto);
+    method(
+        mc,
+        mouse_event_proto,
+        "updateAfterEvent",
+        update_after_event,
+        fn_proto,
+    );
+    method(mc, mouse_event_proto, "toString", to_string, fn_proto);
+
+    mouse_event_proto
+}
+
+/// Define the event type constants of `MouseEvent`, such as `MouseEvent.CLICK`.
+pub fn define_event_types<'gc>(mc: MutationContext<'gc, '_>, class_object: Object<'gc>) {
+    const EVENT_TYPES: &[(&str, &str)] = &[
+        ("CLICK", "click"),
+        ("DOUBLE_CLICK", "doubleClick"),
+        ("MOUSE_DOWN", "mouseDown"),
+        ("MOUSE_MOVE", "mouseMove"),
+        ("MOUSE_OUT", "mouseOut"),
+        ("MOUSE_OVER", "mouseOver"),
+        ("MOUSE_UP", "mouseUp"),
+        ("MOUSE_WHEEL", "mouseWheel"),
+        ("ROLL_OUT", "rollOut"),
+        ("ROLL_OVER", "rollOver"),
+    ];
+
+    let constants = Attribute::DontEnum | Attribute::DontDelete | Attribute::ReadOnly;
+    for (name, event_type) in EVENT_TYPES {
+        class_object.define_value(
+            mc,
+            QName::dynamic_name(*name),
+            (*event_type).into(),
+            constants,
+        );
+    }
+}
+
+/// Construct `MouseEvent`'s class.
+pub fn create_class<'gc>(mc: MutationContext<'gc, '_>) -> GcCell<'gc, Class<'gc>> {
+    Class::new(
+        QName::new(Namespace::package("flash.events"), "MouseEvent"),
+        Some(QName::new(Namespace::package("flash.events"), "Event").into()),
+        Method::Native(instance_init),
+        Method::Native(class_init),
+        mc,
+    )
+}
@@ -0,0 +1,97 @@
+//! `Function` class impl
+
+use crate::avm2::activation::Activation;
+use crate::avm2::class::Class;
+use crate::avm2::globals::{method, public_name};
+use crate::avm2::method::Method;
+use crate::avm2::object::{Object, TObject};
+use crate::avm2::script_object::ScriptObject;
+use crate::avm2::value::Value;
+use crate::avm2::Error;
+use crate::context::UpdateContext;
+use gc_arena::{GcCell, MutationContext};
+
+/// Implements `Function`'s instance initializer.
+fn instance_init<'gc>(
+    _activation: &mut Activation<'gc>,
+    _context: &mut UpdateContext<'_, 'gc, '_>,
+    _this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    Ok(Value::Undefined)
+}
+
+/// Implements `Function`'s class initializer.
+fn class_init<'gc>(
+    _activation: &mut Activation<'gc>,
+    _context: &mut UpdateContext<'_, 'gc, '_>,
+    _this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    Ok(Value::Undefined)
+}
+
+/// Implements `Function.prototype.call`
+fn call<'gc>(
+    activation: &mut Activation<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    func: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let this = args.get(0).and_then(|this| this.as_object().ok());
+
+    match func {
+        Some(func) => func.call(this, args.get(1..).unwrap_or(&[]), activation, context),
+        None => Err(Error::TypeError(
+            "Called Function.prototype.call on something that isn't a function".to_string(),
+        )),
+    }
+}
+
+/// Implements `Function.prototype.apply`
+fn apply<'gc>(
+    activation: &mut Activation<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    func: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let this = args.get(0).and_then(|this| this.as_object().ok());
+    let arguments = match args.get(1) {
+        Some(Value::Object(array)) => array
+            .as_array_object()
+            .map(|array| array.elements())
+            .unwrap_or_default(),
+        _ => Vec::new(),
+    };
+
+    match func {
+        Some(func) => func.call(this, &arguments, activation, context),
+        None => Err(Error::TypeError(
+            "Called Function.prototype.apply on something that isn't a function".to_string(),
+        )),
+    }
+}
+
+/// Create `Function.prototype`.
+///
+/// This has to exist before any builtin methods can be created, so its own
+/// methods are created with it as their prototype.
+pub fn create_proto<'gc>(mc: MutationContext<'gc, '_>, object_proto: Object<'gc>) -> Object<'gc> {
+    let fn_proto = ScriptObject::object(mc, object_proto);
+
+    method(mc, fn_proto, "call", call, fn_proto);
+    method(mc, fn_proto, "apply", apply, fn_proto);
+
+    fn_proto
+}
+
+/// Construct `Function`'s class.
+pub fn create_class<'gc>(mc: MutationContext<'gc, '_>) -> GcCell<'gc, Class<'gc>> {
+    Class::new(
+        public_name("Function"),
+        Some(public_name("Object").into()),
+        Method::Native(instance_init),
+        Method::Native(class_init),
+        mc,
+    )
+}
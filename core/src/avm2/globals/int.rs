@@ -0,0 +1,117 @@
+//! `int` class impl
+
+use crate::avm2::activation::Activation;
+use crate::avm2::class::Class;
+use crate::avm2::globals::number::{number_to_radix_string, radix_arg, this_number};
+use crate::avm2::globals::{method, public_name, set_primitive};
+use crate::avm2::method::Method;
+use crate::avm2::object::Object;
+use crate::avm2::primitive_object::PrimitiveObject;
+use crate::avm2::value::Value;
+use crate::avm2::{AvmString, Error};
+use crate::context::UpdateContext;
+use gc_arena::{GcCell, MutationContext};
+
+/// Implements `int`'s instance initializer.
+fn instance_init<'gc>(
+    activation: &mut Activation<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let value = match args.get(0) {
+        Some(value) => value.coerce_to_i32(activation, context)?,
+        None => 0,
+    };
+    set_primitive(context.gc_context, this, value.into());
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `int`'s class initializer.
+fn class_init<'gc>(
+    _activation: &mut Activation<'gc>,
+    _context: &mut UpdateContext<'_, 'gc, '_>,
+    _this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    Ok(Value::Undefined)
+}
+
+/// Implements calling `int` as a function, which converts its argument.
+pub fn native_call<'gc>(
+    activation: &mut Activation<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    _this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    match args.get(0) {
+        Some(value) => Ok(value.coerce_to_i32(activation, context)?.into()),
+        None => Ok(0.into()),
+    }
+}
+
+/// Allocates instances of `int`.
+fn allocator<'gc>(
+    proto: Object<'gc>,
+    _activation: &mut Activation<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+) -> Result<Object<'gc>, Error<'gc>> {
+    Ok(PrimitiveObject::with_proto(
+        context.gc_context,
+        0.into(),
+        proto,
+    ))
+}
+
+/// Implements `int.prototype.toString`
+fn to_string<'gc>(
+    activation: &mut Activation<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let n = this_number(this)?;
+    let radix = radix_arg(activation, context, args)?;
+
+    Ok(AvmString::new(context.gc_context, number_to_radix_string(n, radix)).into())
+}
+
+/// Implements `int.prototype.valueOf`
+fn value_of<'gc>(
+    _activation: &mut Activation<'gc>,
+    _context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    Ok((this_number(this)? as i32).into())
+}
+
+/// Create `int.prototype`.
+pub fn create_proto<'gc>(
+    mc: MutationContext<'gc, '_>,
+    object_proto: Object<'gc>,
+    fn_proto: Object<'gc>,
+) -> Object<'gc> {
+    let int_proto = PrimitiveObject::with_proto(mc, 0.into(), object_proto);
+
+    method(mc, int_proto, "toString", to_string, fn_proto);
+    method(mc, int_proto, "valueOf", value_of, fn_proto);
+
+    int_proto
+}
+
+/// Construct `int`'s class.
+pub fn create_class<'gc>(mc: MutationContext<'gc, '_>) -> GcCell<'gc, Class<'gc>> {
+    let class = Class::new(
+        public_name("int"),
+        Some(public_name("Object").into()),
+        Method::Native(instance_init),
+        Method::Native(class_init),
+        mc,
+    );
+
+    class.write(mc).set_instance_allocator(allocator);
+
+    class
+}
@@ -0,0 +1,245 @@
+//! `Number` class impl
+
+use crate::avm2::activation::Activation;
+use crate::avm2::class::Class;
+use crate::avm2::globals::{first_arg, method, public_name, set_primitive};
+use crate::avm2::method::Method;
+use crate::avm2::names::QName;
+use crate::avm2::object::{Object, TObject};
+use crate::avm2::primitive_object::PrimitiveObject;
+use crate::avm2::property::Attribute;
+use crate::avm2::value::{number_to_string, Value};
+use crate::avm2::{AvmString, Error};
+use crate::context::UpdateContext;
+use gc_arena::{GcCell, MutationContext};
+
+/// Implements `Number`'s instance initializer.
+fn instance_init<'gc>(
+    activation: &mut Activation<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let value = match args.get(0) {
+        Some(value) => value.coerce_to_number(activation, context)?,
+        None => 0.0,
+    };
+    set_primitive(context.gc_context, this, value.into());
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `Number`'s class initializer.
+fn class_init<'gc>(
+    _activation: &mut Activation<'gc>,
+    _context: &mut UpdateContext<'_, 'gc, '_>,
+    _this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    Ok(Value::Undefined)
+}
+
+/// Implements calling `Number` as a function, which converts its argument.
+pub fn native_call<'gc>(
+    activation: &mut Activation<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    _this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    match args.get(0) {
+        Some(value) => Ok(value.coerce_to_number(activation, context)?.into()),
+        None => Ok(0.0.into()),
+    }
+}
+
+/// Allocates instances of `Number`.
+fn allocator<'gc>(
+    proto: Object<'gc>,
+    _activation: &mut Activation<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+) -> Result<Object<'gc>, Error<'gc>> {
+    Ok(PrimitiveObject::with_proto(
+        context.gc_context,
+        0.0.into(),
+        proto,
+    ))
+}
+
+/// The numeric value of `this`, which may be any of the number types.
+pub fn this_number(this: Option<Object<'_>>) -> Result<f64, Error<'_>> {
+    match this.and_then(|this| this.as_primitive()) {
+        Some(Value::Number(n)) => Ok(n),
+        Some(Value::Integer(i)) => Ok(f64::from(i)),
+        Some(Value::Unsigned(u)) => Ok(f64::from(u)),
+        _ => Err(Error::TypeError(
+            "Number method called on a value that isn't a number".to_string(),
+        )),
+    }
+}
+
+/// Print a number in the given radix, as done by `toString`.
+///
+/// Only the integer part of the number is printed in radixes other than 10.
+pub fn number_to_radix_string(n: f64, radix: u32) -> String {
+    if radix == 10 || !n.is_finite() {
+        return number_to_string(n);
+    }
+
+    let mut digits = Vec::new();
+    let mut remaining = n.abs().trunc();
+    while remaining >= 1.0 {
+        let digit = (remaining % f64::from(radix)) as u32;
+        digits.push(std::char::from_digit(digit, radix).unwrap_or('0'));
+        remaining = (remaining / f64::from(radix)).trunc();
+    }
+
+    if digits.is_empty() {
+        digits.push('0');
+    }
+    if n < 0.0 {
+        digits.push('-');
+    }
+
+    digits.iter().rev().collect()
+}
+
+/// The radix argument of `toString`, which must be between 2 and 36.
+pub fn radix_arg<'gc>(
+    activation: &mut Activation<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    args: &[Value<'gc>],
+) -> Result<u32, Error<'gc>> {
+    let radix = match args.get(0) {
+        None | Some(Value::Undefined) => 10,
+        Some(radix) => radix.coerce_to_i32(activation, context)?,
+    };
+
+    if !(2..=36).contains(&radix) {
+        return Err(Error::RangeError(format!(
+            "Error #1003: The radix argument must be between 2 and 36; got {}.",
+            radix
+        )));
+    }
+
+    Ok(radix as u32)
+}
+
+/// Implements `Number.prototype.toString`
+fn to_string<'gc>(
+    activation: &mut Activation<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let n = this_number(this)?;
+    let radix = radix_arg(activation, context, args)?;
+
+    Ok(AvmString::new(context.gc_context, number_to_radix_string(n, radix)).into())
+}
+
+/// Implements `Number.prototype.valueOf`
+fn value_of<'gc>(
+    _activation: &mut Activation<'gc>,
+    _context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    Ok(this_number(this)?.into())
+}
+
+/// Implements `Number.prototype.toFixed`
+fn to_fixed<'gc>(
+    activation: &mut Activation<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let n = this_number(this)?;
+    let digits = first_arg(args).coerce_to_i32(activation, context)?;
+    if !(0..=20).contains(&digits) {
+        return Err(Error::RangeError(format!(
+            "Error #1002: The precision argument must be between 0 and 20; got {}.",
+            digits
+        )));
+    }
+
+    let string = if n.is_finite() {
+        format!("{:.*}", digits as usize, n)
+    } else {
+        number_to_string(n)
+    };
+
+    Ok(AvmString::new(context.gc_context, string).into())
+}
+
+/// Define the constants of a number class on its class object.
+pub fn define_constants<'gc>(
+    mc: MutationContext<'gc, '_>,
+    class_object: Object<'gc>,
+    min_value: Value<'gc>,
+    max_value: Value<'gc>,
+) {
+    let constants = Attribute::DontEnum | Attribute::DontDelete | Attribute::ReadOnly;
+    class_object.define_value(mc, QName::dynamic_name("MIN_VALUE"), min_value, constants);
+    class_object.define_value(mc, QName::dynamic_name("MAX_VALUE"), max_value, constants);
+}
+
+/// Define the constants of `Number`, beyond its minimum and maximum.
+pub fn define_number_constants<'gc>(mc: MutationContext<'gc, '_>, class_object: Object<'gc>) {
+    let constants = Attribute::DontEnum | Attribute::DontDelete | Attribute::ReadOnly;
+    define_constants(
+        mc,
+        class_object,
+        // The smallest positive denormal number.
+        5e-324.into(),
+        std::f64::MAX.into(),
+    );
+    class_object.define_value(
+        mc,
+        QName::dynamic_name("NaN"),
+        std::f64::NAN.into(),
+        constants,
+    );
+    class_object.define_value(
+        mc,
+        QName::dynamic_name("POSITIVE_INFINITY"),
+        std::f64::INFINITY.into(),
+        constants,
+    );
+    class_object.define_value(
+        mc,
+        QName::dynamic_name("NEGATIVE_INFINITY"),
+        std::f64::NEG_INFINITY.into(),
+        constants,
+    );
+}
+
+/// Create `Number.prototype`.
+pub fn create_proto<'gc>(
+    mc: MutationContext<'gc, '_>,
+    object_proto: Object<'gc>,
+    fn_proto: Object<'gc>,
+) -> Object<'gc> {
+    let number_proto = PrimitiveObject::with_proto(mc, 0.0.into(), object_proto);
+
+    method(mc, number_proto, "toString", to_string, fn_proto);
+    method(mc, number_proto, "valueOf", value_of, fn_proto);
+    method(mc, number_proto, "toFixed", to_fixed, fn_proto);
+
+    number_proto
+}
+
+/// Construct `Number`'s class.
+pub fn create_class<'gc>(mc: MutationContext<'gc, '_>) -> GcCell<'gc, Class<'gc>> {
+    let class = Class::new(
+        public_name("Number"),
+        Some(public_name("Object").into()),
+        Method::Native(instance_init),
+        Method::Native(class_init),
+        mc,
+    );
+
+    class.write(mc).set_instance_allocator(allocator);
+
+    class
+}
@@ -0,0 +1,147 @@
+//! `Object` class impl
+
+use crate::avm2::activation::Activation;
+use crate::avm2::class::Class;
+use crate::avm2::globals::{method, public_name};
+use crate::avm2::method::Method;
+use crate::avm2::names::QName;
+use crate::avm2::object::{Object, TObject};
+use crate::avm2::value::Value;
+use crate::avm2::{AvmString, Error};
+use crate::context::UpdateContext;
+use gc_arena::{GcCell, MutationContext};
+
+/// Implements `Object`'s instance initializer.
+fn instance_init<'gc>(
+    _activation: &mut Activation<'gc>,
+    _context: &mut UpdateContext<'_, 'gc, '_>,
+    _this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    Ok(Value::Undefined)
+}
+
+/// Implements `Object`'s class initializer.
+fn class_init<'gc>(
+    _activation: &mut Activation<'gc>,
+    _context: &mut UpdateContext<'_, 'gc, '_>,
+    _this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    Ok(Value::Undefined)
+}
+
+/// Implements `Object.prototype.toString`
+///
+/// Instances of classes name their class, e.g. `[object Sprite]`.
+fn to_string<'gc>(
+    activation: &mut Activation<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let class_name = match this {
+        Some(this) => this
+            .get_property(
+                this,
+                &QName::dynamic_name("constructor"),
+                activation,
+                context,
+            )?
+            .as_object()
+            .ok()
+            .and_then(|constructor| constructor.as_class_object())
+            .map(|class| class.class().read().name().local_name()),
+        None => None,
+    };
+
+    Ok(AvmString::new(
+        context.gc_context,
+        format!("[object {}]", class_name.unwrap_or_else(|| "Object".into())),
+    )
+    .into())
+}
+
+/// Implements `Object.prototype.valueOf`
+fn value_of<'gc>(
+    _activation: &mut Activation<'gc>,
+    _context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    Ok(this.map(Value::Object).unwrap_or(Value::Undefined))
+}
+
+/// Implements `Object.prototype.hasOwnProperty`
+fn has_own_property<'gc>(
+    activation: &mut Activation<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let this = match this {
+        Some(this) => this,
+        None => return Ok(false.into()),
+    };
+    let name = args
+        .get(0)
+        .unwrap_or(&Value::Undefined)
+        .coerce_to_string(activation, context)?;
+
+    Ok(this.has_own_property(&QName::dynamic_name(name)).into())
+}
+
+/// Implements `Object.prototype.isPrototypeOf`
+fn is_prototype_of<'gc>(
+    _activation: &mut Activation<'gc>,
+    _context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let (this, object) = match (this, args.get(0)) {
+        (Some(this), Some(Value::Object(object))) => (this, *object),
+        _ => return Ok(false.into()),
+    };
+
+    let mut proto = object.proto();
+    while let Some(proto_object) = proto {
+        if Object::ptr_eq(proto_object, this) {
+            return Ok(true.into());
+        }
+
+        proto = proto_object.proto();
+    }
+
+    Ok(false.into())
+}
+
+/// Fill `Object.prototype`, which has to exist before any functions can be
+/// created.
+pub fn fill_proto<'gc>(
+    mc: MutationContext<'gc, '_>,
+    object_proto: Object<'gc>,
+    fn_proto: Object<'gc>,
+) {
+    method(mc, object_proto, "toString", to_string, fn_proto);
+    method(mc, object_proto, "toLocaleString", to_string, fn_proto);
+    method(mc, object_proto, "valueOf", value_of, fn_proto);
+    method(
+        mc,
+        object_proto,
+        "hasOwnProperty",
+        has_own_property,
+        fn_proto,
+    );
+    method(mc, object_proto, "isPrototypeOf", is_prototype_of, fn_proto);
+}
+
+/// Construct `Object`'s class.
+pub fn create_class<'gc>(mc: MutationContext<'gc, '_>) -> GcCell<'gc, Class<'gc>> {
+    Class::new(
+        public_name("Object"),
+        None,
+        Method::Native(instance_init),
+        Method::Native(class_init),
+        mc,
+    )
+}
@@ -0,0 +1,413 @@
+//! `String` class impl
+
+use crate::avm2::activation::Activation;
+use crate::avm2::array_object::ArrayObject;
+use crate::avm2::class::Class;
+use crate::avm2::globals::{first_arg, getter, method, public_name, set_primitive};
+use crate::avm2::method::Method;
+use crate::avm2::object::{Object, TObject};
+use crate::avm2::primitive_object::PrimitiveObject;
+use crate::avm2::value::Value;
+use crate::avm2::{AvmString, Error};
+use crate::context::UpdateContext;
+use crate::string_utils;
+use gc_arena::{GcCell, MutationContext};
+
+/// Implements `String`'s instance initializer.
+fn instance_init<'gc>(
+    activation: &mut Activation<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let value = match args.get(0) {
+        Some(value) => value.coerce_to_string(activation, context)?,
+        None => "".into(),
+    };
+    set_primitive(context.gc_context, this, value.into());
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `String`'s class initializer.
+fn class_init<'gc>(
+    _activation: &mut Activation<'gc>,
+    _context: &mut UpdateContext<'_, 'gc, '_>,
+    _this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    Ok(Value::Undefined)
+}
+
+/// Implements calling `String` as a function, which converts its argument.
+pub fn native_call<'gc>(
+    activation: &mut Activation<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    _this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    match args.get(0) {
+        Some(value) => Ok(value.coerce_to_string(activation, context)?.into()),
+        None => Ok("".into()),
+    }
+}
+
+/// Allocates instances of `String`.
+fn allocator<'gc>(
+    proto: Object<'gc>,
+    _activation: &mut Activation<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+) -> Result<Object<'gc>, Error<'gc>> {
+    Ok(PrimitiveObject::with_proto(
+        context.gc_context,
+        "".into(),
+        proto,
+    ))
+}
+
+/// The string value of `this`.
+///
+/// String methods can be moved onto other objects, so `this` is converted to
+/// a string if it isn't one.
+fn this_string<'gc>(
+    activation: &mut Activation<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+) -> Result<AvmString<'gc>, Error<'gc>> {
+    match this {
+        Some(this) => match this.as_primitive() {
+            Some(Value::String(s)) => Ok(s),
+            _ => Value::Object(this).coerce_to_string(activation, context),
+        },
+        None => Ok("undefined".into()),
+    }
+}
+
+/// Creates a `String` from an iterator of UTF-16 code units.
+/// TODO: Unpaired surrogates will get replaced with the Unicode replacement character.
+fn utf16_iter_to_string<I: Iterator<Item = u16>>(it: I) -> String {
+    std::char::decode_utf16(it)
+        .map(|c| c.unwrap_or(std::char::REPLACEMENT_CHARACTER))
+        .collect()
+}
+
+/// Normalizes an index parameter, counting negative values backwards from
+/// `len`. The returned index is within `[0, len]`.
+fn string_wrapping_index(i: i32, len: usize) -> usize {
+    if i >= 0 {
+        (i as usize).min(len)
+    } else {
+        len.saturating_sub((-i64::from(i)) as usize)
+    }
+}
+
+/// Implements `String.prototype.length`
+fn length<'gc>(
+    activation: &mut Activation<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let this = this_string(activation, context, this)?;
+
+    Ok((this.encode_utf16().count() as i32).into())
+}
+
+/// Implements `String.prototype.toString` and `String.prototype.valueOf`
+fn to_string<'gc>(
+    _activation: &mut Activation<'gc>,
+    _context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    match this.and_then(|this| this.as_primitive()) {
+        Some(Value::String(s)) => Ok(s.into()),
+        _ => Err(Error::TypeError(
+            "String.prototype.toString called on a value that isn't a String".to_string(),
+        )),
+    }
+}
+
+/// Implements `String.prototype.charAt`
+fn char_at<'gc>(
+    activation: &mut Activation<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let this = this_string(activation, context, this)?;
+    let i = first_arg(args).coerce_to_i32(activation, context)?;
+    let ret = if i >= 0 {
+        this.encode_utf16()
+            .nth(i as usize)
+            .map(|c| utf16_iter_to_string(std::iter::once(c)))
+            .unwrap_or_default()
+    } else {
+        String::new()
+    };
+
+    Ok(AvmString::new(context.gc_context, ret).into())
+}
+
+/// Implements `String.prototype.charCodeAt`
+fn char_code_at<'gc>(
+    activation: &mut Activation<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let this = this_string(activation, context, this)?;
+    let i = first_arg(args).coerce_to_i32(activation, context)?;
+    let ret = if i >= 0 {
+        this.encode_utf16()
+            .nth(i as usize)
+            .map(f64::from)
+            .unwrap_or(std::f64::NAN)
+    } else {
+        std::f64::NAN
+    };
+
+    Ok(ret.into())
+}
+
+/// Implements `String.prototype.concat`
+fn concat<'gc>(
+    activation: &mut Activation<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let mut ret = this_string(activation, context, this)?.to_string();
+    for arg in args {
+        ret.push_str(&arg.coerce_to_string(activation, context)?);
+    }
+
+    Ok(AvmString::new(context.gc_context, ret).into())
+}
+
+/// Implements `String.prototype.indexOf`
+fn index_of<'gc>(
+    activation: &mut Activation<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let this: Vec<u16> = this_string(activation, context, this)?
+        .encode_utf16()
+        .collect();
+    let pattern: Vec<u16> = first_arg(args)
+        .coerce_to_string(activation, context)?
+        .encode_utf16()
+        .collect();
+    let start = match args.get(1) {
+        None | Some(Value::Undefined) => 0,
+        Some(start) => start.coerce_to_i32(activation, context)?.max(0) as usize,
+    };
+
+    if start > this.len() {
+        return Ok((-1).into());
+    }
+    if pattern.is_empty() {
+        return Ok((start as i32).into());
+    }
+
+    let found = this[start..]
+        .windows(pattern.len())
+        .position(|window| window == &pattern[..])
+        .map(|i| (i + start) as i32)
+        .unwrap_or(-1);
+
+    Ok(found.into())
+}
+
+/// Implements `String.prototype.slice`
+fn slice<'gc>(
+    activation: &mut Activation<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let this = this_string(activation, context, this)?;
+    let this_len = this.encode_utf16().count();
+    let start = string_wrapping_index(
+        first_arg(args).coerce_to_i32(activation, context)?,
+        this_len,
+    );
+    let end = match args.get(1) {
+        None | Some(Value::Undefined) => this_len,
+        Some(end) => string_wrapping_index(end.coerce_to_i32(activation, context)?, this_len),
+    };
+
+    if start < end {
+        let ret = utf16_iter_to_string(this.encode_utf16().skip(start).take(end - start));
+        Ok(AvmString::new(context.gc_context, ret).into())
+    } else {
+        Ok("".into())
+    }
+}
+
+/// Implements `String.prototype.substr`
+fn substr<'gc>(
+    activation: &mut Activation<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let this = this_string(activation, context, this)?;
+    let this_len = this.encode_utf16().count();
+    let start = string_wrapping_index(
+        first_arg(args).coerce_to_i32(activation, context)?,
+        this_len,
+    );
+    let len = match args.get(1) {
+        None | Some(Value::Undefined) => this_len,
+        Some(len) => len.coerce_to_i32(activation, context)?.max(0) as usize,
+    };
+
+    let ret = utf16_iter_to_string(this.encode_utf16().skip(start).take(len));
+    Ok(AvmString::new(context.gc_context, ret).into())
+}
+
+/// Implements `String.prototype.substring`
+fn substring<'gc>(
+    activation: &mut Activation<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let this = this_string(activation, context, this)?;
+    let this_len = this.encode_utf16().count();
+    let mut start = first_arg(args).coerce_to_i32(activation, context)?.max(0) as usize;
+    let mut end = match args.get(1) {
+        None | Some(Value::Undefined) => this_len,
+        Some(end) => end.coerce_to_i32(activation, context)?.max(0) as usize,
+    };
+    start = start.min(this_len);
+    end = end.min(this_len);
+
+    // substring swaps the start and end if they are flipped.
+    if end < start {
+        std::mem::swap(&mut start, &mut end);
+    }
+
+    let ret = utf16_iter_to_string(this.encode_utf16().skip(start).take(end - start));
+    Ok(AvmString::new(context.gc_context, ret).into())
+}
+
+/// Implements `String.prototype.split`
+fn split<'gc>(
+    activation: &mut Activation<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let this = this_string(activation, context, this)?;
+    let limit = match args.get(1) {
+        None | Some(Value::Undefined) => std::usize::MAX,
+        Some(limit) => limit.coerce_to_u32(activation, context)? as usize,
+    };
+
+    let elements: Vec<Value<'gc>> = match args.get(0) {
+        None | Some(Value::Undefined) => vec![this.into()],
+        Some(delimiter) => {
+            let delimiter = delimiter.coerce_to_string(activation, context)?;
+            if delimiter.is_empty() {
+                this.encode_utf16()
+                    .take(limit)
+                    .map(|c| {
+                        AvmString::new(context.gc_context, utf16_iter_to_string(std::iter::once(c)))
+                            .into()
+                    })
+                    .collect()
+            } else {
+                this.split(&*delimiter)
+                    .take(limit)
+                    .map(|token| AvmString::new(context.gc_context, token.to_string()).into())
+                    .collect()
+            }
+        }
+    };
+
+    Ok(ArrayObject::from_elements(
+        context.gc_context,
+        context.avm2.prototypes().array,
+        elements,
+    )
+    .into())
+}
+
+/// Implements `String.prototype.toLowerCase`
+fn to_lower_case<'gc>(
+    activation: &mut Activation<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let this = this_string(activation, context, this)?;
+
+    Ok(AvmString::new(
+        context.gc_context,
+        this.chars()
+            .map(string_utils::swf_char_to_lowercase)
+            .collect::<String>(),
+    )
+    .into())
+}
+
+/// Implements `String.prototype.toUpperCase`
+fn to_upper_case<'gc>(
+    activation: &mut Activation<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let this = this_string(activation, context, this)?;
+
+    Ok(AvmString::new(
+        context.gc_context,
+        this.chars()
+            .map(string_utils::swf_char_to_uppercase)
+            .collect::<String>(),
+    )
+    .into())
+}
+
+/// Create `String.prototype`.
+pub fn create_proto<'gc>(
+    mc: MutationContext<'gc, '_>,
+    object_proto: Object<'gc>,
+    fn_proto: Object<'gc>,
+) -> Object<'gc> {
+    let string_proto = PrimitiveObject::with_proto(mc, "".into(), object_proto);
+
+    getter(mc, string_proto, "length", length, fn_proto);
+    method(mc, string_proto, "toString", to_string, fn_proto);
+    method(mc, string_proto, "valueOf", to_string, fn_proto);
+    method(mc, string_proto, "charAt", char_at, fn_proto);
+    method(mc, string_proto, "charCodeAt", char_code_at, fn_proto);
+    method(mc, string_proto, "concat", concat, fn_proto);
+    method(mc, string_proto, "indexOf", index_of, fn_proto);
+    method(mc, string_proto, "slice", slice, fn_proto);
+    method(mc, string_proto, "split", split, fn_proto);
+    method(mc, string_proto, "substr", substr, fn_proto);
+    method(mc, string_proto, "substring", substring, fn_proto);
+    method(mc, string_proto, "toLowerCase", to_lower_case, fn_proto);
+    method(mc, string_proto, "toUpperCase", to_upper_case, fn_proto);
+
+    string_proto
+}
+
+/// Construct `String`'s class.
+pub fn create_class<'gc>(mc: MutationContext<'gc, '_>) -> GcCell<'gc, Class<'gc>> {
+    let class = Class::new(
+        public_name("String"),
+        Some(public_name("Object").into()),
+        Method::Native(instance_init),
+        Method::Native(class_init),
+        mc,
+    );
+
+    class.write(mc).set_instance_allocator(allocator);
+
+    class
+}
@@ -0,0 +1,313 @@
+//! Dispatch of mouse and keyboard input as AVM2 events
+
+use crate::avm2::activation::Activation;
+use crate::avm2::event_object::EventObject;
+use crate::avm2::events::{
+    dispatch_event, is_on_stage, Event, EventData, KeyboardEventData, MouseEventData,
+};
+use crate::avm2::object::Object;
+use crate::avm2::stage_object::StageObject;
+use crate::context::UpdateContext;
+use crate::display_object::{object_under_point, DisplayObject, TDisplayObject};
+use crate::events::{KeyCode, PlayerEvent};
+use crate::keyboard::key_code_to_ascii;
+
+/// The longest time between two clicks, in milliseconds, for them to make a
+/// double click.
+const DOUBLE_CLICK_TIME: u64 = 500;
+
+/// Dispatch the `MouseEvent`s and `KeyboardEvent`s caused by an input event.
+///
+/// `button_down` is whether the mouse button was held down before this
+/// event. The mouse position must already have been updated for the event.
+pub fn handle_input_event<'gc>(
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    event: &PlayerEvent,
+    button_down: bool,
+) {
+    // Movies without any ABC code have no listeners to dispatch to.
+    if !context.avm2.has_scripts() {
+        return;
+    }
+
+    match *event {
+        PlayerEvent::MouseMove { .. } => {
+            let target = update_mouse_target(context, button_down);
+            dispatch_mouse_event(context, "mouseMove", target, None, button_down, 0);
+        }
+        PlayerEvent::MouseDown { .. } => {
+            let target = update_mouse_target(context, true);
+            context.avm2.mouse_down_target = target;
+            dispatch_mouse_event(context, "mouseDown", target, None, true, 0);
+        }
+        PlayerEvent::MouseUp { .. } => {
+            let target = update_mouse_target(context, false);
+            dispatch_mouse_event(context, "mouseUp", target, None, false, 0);
+
+            // A click is only made by pressing and releasing the mouse
+            // over the same object.
+            let pressed = context.avm2.mouse_down_target.take();
+            if is_same_target(pressed, target) {
+                dispatch_click(context, target);
+            }
+        }
+        PlayerEvent::MouseWheel { delta } => {
+            let target = context.avm2.mouse_target;
+            let delta = delta.lines() as i32;
+            dispatch_mouse_event(context, "mouseWheel", target, None, button_down, delta);
+        }
+        PlayerEvent::KeyDown { key_code } => dispatch_keyboard_event(context, "keyDown", key_code),
+        PlayerEvent::KeyUp { key_code } => dispatch_keyboard_event(context, "keyUp", key_code),
+        _ => (),
+    }
+}
+
+/// Whether two mouse targets are the same object, where `None` is the stage.
+fn is_same_target<'gc>(a: Option<DisplayObject<'gc>>, b: Option<DisplayObject<'gc>>) -> bool {
+    match (a, b) {
+        (Some(a), Some(b)) => DisplayObject::ptr_eq(a, b),
+        (None, None) => true,
+        _ => false,
+    }
+}
+
+/// Dispatch a `click`, or a `doubleClick` if the target was clicked just
+/// before and has double clicks enabled.
+fn dispatch_click<'gc>(
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    target: Option<DisplayObject<'gc>>,
+) {
+    let time = context.global_time;
+    let is_double_click = match (target, context.avm2.last_click_target) {
+        (Some(target), Some(last_target)) => {
+            target.double_click_enabled()
+                && DisplayObject::ptr_eq(target, last_target)
+                && time.saturating_sub(context.avm2.last_click_time) <= DOUBLE_CLICK_TIME
+        }
+        _ => false,
+    };
+
+    if is_double_click {
+        context.avm2.last_click_target = None;
+        dispatch_mouse_event(context, "doubleClick", target, None, false, 0);
+    } else {
+        context.avm2.last_click_target = target;
+        context.avm2.last_click_time = time;
+        dispatch_mouse_event(context, "click", target, None, false, 0);
+    }
+}
+
+/// Whether a display object can be the target of mouse events.
+fn is_interactive(display_object: DisplayObject<'_>) -> bool {
+    display_object.as_movie_clip().is_some()
+        || display_object.as_edit_text().is_some()
+        || display_object.as_button().is_some()
+}
+
+/// The interactive object that receives the mouse events that hit a display
+/// object, or `None` for the stage.
+///
+/// Hits go to the nearest interactive ancestor of the object that has
+/// `mouseEnabled` set. An ancestor with `mouseChildren` turned off takes
+/// the hits of all of its descendants.
+fn mouse_target_for_hit(hit: DisplayObject<'_>) -> Option<DisplayObject<'_>> {
+    let mut path = vec![hit];
+    while let Some(parent) = path.last().and_then(|node| node.parent()) {
+        path.push(parent);
+    }
+
+    let start = path
+        .iter()
+        .skip(1)
+        .rposition(|ancestor| !ancestor.mouse_children())
+        .map_or(0, |index| index + 1);
+    path[start..]
+        .iter()
+        .copied()
+        .find(|node| is_interactive(*node) && node.mouse_enabled())
+}
+
+/// The interactive object under the mouse, or `None` for the stage.
+fn mouse_target_under_point<'gc>(
+    context: &mut UpdateContext<'_, 'gc, '_>,
+) -> Option<DisplayObject<'gc>> {
+    let point = *context.mouse_position;
+    let levels: Vec<_> = context.levels.values().rev().copied().collect();
+    levels
+        .into_iter()
+        .find_map(|level| object_under_point(level, point, None))
+        .and_then(mouse_target_for_hit)
+}
+
+/// Find the object under the mouse, and dispatch the events for moving from
+/// the previous one.
+///
+/// The previous object and those of its ancestors that aren't also
+/// ancestors of the new one are rolled out of, from the inside out; then
+/// the new object and its ancestors are rolled over from the outside in.
+fn update_mouse_target<'gc>(
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    button_down: bool,
+) -> Option<DisplayObject<'gc>> {
+    let new_target = mouse_target_under_point(context);
+    let old_target = context
+        .avm2
+        .mouse_target
+        .filter(|old| is_on_stage(context, *old));
+    context.avm2.mouse_target = new_target;
+    if is_same_target(old_target, new_target) {
+        return new_target;
+    }
+
+    let ancestors_of = |node: Option<DisplayObject<'gc>>| {
+        let mut ancestors = Vec::new();
+        let mut node = node;
+        while let Some(object) = node {
+            ancestors.push(object);
+            node = object.parent();
+        }
+        ancestors
+    };
+    let old_ancestors = ancestors_of(old_target);
+    let new_ancestors = ancestors_of(new_target);
+    let is_shared = |node: &DisplayObject<'gc>, others: &[DisplayObject<'gc>]| {
+        others
+            .iter()
+            .any(|other| DisplayObject::ptr_eq(*node, *other))
+    };
+
+    if let Some(old_target) = old_target {
+        dispatch_mouse_event(
+            context,
+            "mouseOut",
+            Some(old_target),
+            new_target,
+            button_down,
+            0,
+        );
+        for node in old_ancestors
+            .iter()
+            .filter(|node| !is_shared(node, &new_ancestors))
+        {
+            dispatch_mouse_event(context, "rollOut", Some(*node), new_target, button_down, 0);
+        }
+    }
+
+    if let Some(new_target) = new_target {
+        for node in new_ancestors
+            .iter()
+            .rev()
+            .filter(|node| !is_shared(node, &old_ancestors))
+        {
+            dispatch_mouse_event(context, "rollOver", Some(*node), old_target, button_down, 0);
+        }
+        dispatch_mouse_event(
+            context,
+            "mouseOver",
+            Some(new_target),
+            old_target,
+            button_down,
+            0,
+        );
+    }
+
+    new_target
+}
+
+/// The object that represents an event target, where `None` is the stage.
+fn target_object<'gc>(
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    target: Option<DisplayObject<'gc>>,
+) -> Object<'gc> {
+    match target {
+        Some(target) => StageObject::for_display_object(context, target),
+        None => context.avm2.stage(),
+    }
+}
+
+/// Dispatch a new `MouseEvent` at the mouse position.
+///
+/// Roll events go to a single object; every other mouse event bubbles.
+fn dispatch_mouse_event<'gc>(
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    event_type: &'static str,
+    target: Option<DisplayObject<'gc>>,
+    related_object: Option<DisplayObject<'gc>>,
+    button_down: bool,
+    delta: i32,
+) {
+    let point = *context.mouse_position;
+    let (local_x, local_y) = target.map_or(point, |target| target.global_to_local(point));
+    let related_object = related_object.map(|related| target_object(context, Some(related)));
+    let keyboard = context.keyboard;
+
+    let mut event = Event::new(event_type);
+    event.set_bubbles(event_type != "rollOver" && event_type != "rollOut");
+    event.set_event_data(EventData::Mouse(MouseEventData {
+        local_x: local_x.to_pixels(),
+        local_y: local_y.to_pixels(),
+        related_object,
+        ctrl_key: keyboard.is_key_down(KeyCode::Control),
+        alt_key: keyboard.is_key_down(KeyCode::Alt),
+        shift_key: keyboard.is_key_down(KeyCode::Shift),
+        button_down,
+        delta,
+    }));
+
+    let proto = context.avm2.prototypes().mouse_event;
+    let target = target_object(context, target);
+    dispatch_input_event(
+        context,
+        target,
+        EventObject::from_event(context.gc_context, proto, event),
+    );
+}
+
+/// Dispatch a new `KeyboardEvent` to the object with keyboard focus, or the
+/// stage if there isn't one.
+fn dispatch_keyboard_event<'gc>(
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    event_type: &'static str,
+    key_code: KeyCode,
+) {
+    let focus = context
+        .focus_tracker
+        .get()
+        .filter(|focus| is_interactive(*focus) && is_on_stage(context, *focus));
+    let keyboard = context.keyboard;
+    let shift_key = keyboard.is_key_down(KeyCode::Shift);
+
+    let mut event = Event::new(event_type);
+    event.set_bubbles(true);
+    event.set_event_data(EventData::Keyboard(KeyboardEventData {
+        char_code: key_code_to_ascii(key_code, shift_key).into(),
+        key_code: u8::from(key_code).into(),
+        key_location: 0,
+        ctrl_key: keyboard.is_key_down(KeyCode::Control),
+        alt_key: keyboard.is_key_down(KeyCode::Alt),
+        shift_key,
+    }));
+
+    let proto = context.avm2.prototypes().keyboard_event;
+    let target = target_object(context, focus);
+    dispatch_input_event(
+        context,
+        target,
+        EventObject::from_event(context.gc_context, proto, event),
+    );
+}
+
+/// Dispatch an input event, logging any error thrown by its listeners.
+fn dispatch_input_event<'gc>(
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    target: Object<'gc>,
+    event: EventObject<'gc>,
+) {
+    if let Err(e) = dispatch_event(&mut Activation::from_nothing(), context, target, event) {
+        log::error!(
+            "Unhandled AVM2 exception in {} handler: {}",
+            event.event().event_type(),
+            e
+        );
+    }
+}
@@ -0,0 +1,126 @@
+//! AVM2 methods
+
+use crate::avm2::activation::Activation;
+use crate::avm2::object::Object;
+use crate::avm2::script::{CollectWrapper, TranslationUnit};
+use crate::avm2::value::Value;
+use crate::avm2::Error;
+use crate::context::UpdateContext;
+use gc_arena::{Collect, CollectionContext, Gc};
+use std::fmt;
+use std::rc::Rc;
+use swf::avm2::types::{AbcFile, Method as AbcMethod, MethodBody as AbcMethodBody};
+
+/// Represents a function defined in Ruffle's code.
+///
+/// Parameters are as follows:
+///
+///  * The current activation
+///  * The action context
+///  * The current `this` object
+///  * The arguments this function was called with
+pub type NativeMethod<'gc> = fn(
+    &mut Activation<'gc>,
+    &mut UpdateContext<'_, 'gc, '_>,
+    Option<Object<'gc>>,
+    &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>>;
+
+/// Represents a reference to an AVM2 method and body.
+#[derive(Collect, Clone, Debug)]
+#[collect(no_drop)]
+pub struct BytecodeMethod<'gc> {
+    /// The translation unit this function was defined in.
+    translation_unit: TranslationUnit<'gc>,
+
+    /// The underlying ABC file of the above translation unit.
+    abc: CollectWrapper<Rc<AbcFile>>,
+
+    /// The ABC method this function uses.
+    abc_method: u32,
+
+    /// The ABC method body this function uses.
+    abc_method_body: Option<u32>,
+}
+
+impl<'gc> BytecodeMethod<'gc> {
+    pub fn new(
+        translation_unit: TranslationUnit<'gc>,
+        abc: Rc<AbcFile>,
+        abc_method: u32,
+        abc_method_body: Option<u32>,
+    ) -> Self {
+        Self {
+            translation_unit,
+            abc: CollectWrapper(abc),
+            abc_method,
+            abc_method_body,
+        }
+    }
+
+    pub fn translation_unit(&self) -> TranslationUnit<'gc> {
+        self.translation_unit
+    }
+
+    /// Get the underlying ABC file.
+    pub fn abc(&self) -> &AbcFile {
+        &self.abc.0
+    }
+
+    /// Get a reference to the ABC method entry this refers to.
+    pub fn method(&self) -> &AbcMethod {
+        &self.abc.0.methods[self.abc_method as usize]
+    }
+
+    /// Get a reference to the ABC method body entry this refers to.
+    ///
+    /// Some methods, such as those of interfaces, have no body.
+    pub fn body(&self) -> Option<&AbcMethodBody> {
+        self.abc_method_body
+            .map(|body| &self.abc.0.method_bodies[body as usize])
+    }
+}
+
+/// An uninstantiated method that can either be natively implemented or sourced
+/// from an ABC file.
+#[derive(Clone)]
+pub enum Method<'gc> {
+    /// A native method.
+    Native(NativeMethod<'gc>),
+
+    /// An ABC-provided method entry.
+    Entry(Gc<'gc, BytecodeMethod<'gc>>),
+}
+
+unsafe impl<'gc> Collect for Method<'gc> {
+    fn trace(&self, cc: CollectionContext) {
+        match self {
+            Method::Native(_nf) => {}
+            Method::Entry(a2me) => a2me.trace(cc),
+        }
+    }
+}
+
+impl fmt::Debug for Method<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Method::Native(nf) => f
+                .debug_tuple("Method::Native")
+                .field(&format!("{:p}", nf))
+                .finish(),
+            Method::Entry(a2me) => f.debug_tuple("Method::Entry").field(a2me).finish(),
+        }
+    }
+}
+
+impl<'gc> From<NativeMethod<'gc>> for Method<'gc> {
+    fn from(nf: NativeMethod<'gc>) -> Self {
+        Self::Native(nf)
+    }
+}
+
+impl<'gc> From<Gc<'gc, BytecodeMethod<'gc>>> for Method<'gc> {
+    fn from(a2me: Gc<'gc, BytecodeMethod<'gc>>) -> Self {
+        Self::Entry(a2me)
+    }
+}
@@ -0,0 +1,360 @@
+//! AVM2 names & namespacing
+
+use crate::avm2::activation::Activation;
+use crate::avm2::script::TranslationUnit;
+use crate::avm2::{AvmString, Error};
+use crate::context::UpdateContext;
+use gc_arena::{Collect, MutationContext};
+use swf::avm2::types::{
+    Index, Multiname as AbcMultiname, Namespace as AbcNamespace, NamespaceSet as AbcNamespaceSet,
+};
+
+/// Represents the name of a namespace.
+///
+/// Namespaces are compared by kind and name only. ABC files may declare several
+/// distinct private namespaces with the same name; those are treated as one.
+#[derive(Clone, Copy, Collect, Debug, PartialEq, Eq, Hash)]
+#[collect(no_drop)]
+#[allow(clippy::enum_variant_names)]
+pub enum Namespace<'gc> {
+    Namespace(AvmString<'gc>),
+    Package(AvmString<'gc>),
+    PackageInternal(AvmString<'gc>),
+    Protected(AvmString<'gc>),
+    Explicit(AvmString<'gc>),
+    StaticProtected(AvmString<'gc>),
+    Private(AvmString<'gc>),
+
+    /// The wildcard namespace, which matches a name in any namespace.
+    Any,
+}
+
+impl<'gc> Namespace<'gc> {
+    /// Read a namespace declaration from the ABC constant pool.
+    ///
+    /// Namespace index zero refers to the wildcard namespace.
+    pub fn from_abc_namespace(
+        translation_unit: TranslationUnit<'gc>,
+        namespace_index: Index<AbcNamespace>,
+        mc: MutationContext<'gc, '_>,
+    ) -> Result<Self, Error<'gc>> {
+        if namespace_index.0 == 0 {
+            return Ok(Self::Any);
+        }
+
+        let abc = translation_unit.abc();
+        let abc_namespace = abc
+            .constant_pool
+            .namespaces
+            .get(namespace_index.0 as usize - 1)
+            .ok_or_else(|| Error::InvalidReference("namespace", namespace_index.0))?;
+
+        Ok(match abc_namespace {
+            AbcNamespace::Namespace(idx) => {
+                Self::Namespace(translation_unit.pool_string(idx.0, mc)?)
+            }
+            AbcNamespace::Package(idx) => Self::Package(translation_unit.pool_string(idx.0, mc)?),
+            AbcNamespace::PackageInternal(idx) => {
+                Self::PackageInternal(translation_unit.pool_string(idx.0, mc)?)
+            }
+            AbcNamespace::Protected(idx) => {
+                Self::Protected(translation_unit.pool_string(idx.0, mc)?)
+            }
+            AbcNamespace::Explicit(idx) => Self::Explicit(translation_unit.pool_string(idx.0, mc)?),
+            AbcNamespace::StaticProtected(idx) => {
+                Self::StaticProtected(translation_unit.pool_string(idx.0, mc)?)
+            }
+            AbcNamespace::Private(idx) => Self::Private(translation_unit.pool_string(idx.0, mc)?),
+        })
+    }
+
+    /// The public namespace, which holds all dynamic properties.
+    pub fn public() -> Self {
+        Self::Package("".into())
+    }
+
+    /// The public namespace of a package, e.g. `flash.display`.
+    pub fn package(package_name: &'static str) -> Self {
+        Self::Package(package_name.into())
+    }
+
+    pub fn is_public(&self) -> bool {
+        matches!(self, Self::Package(name) if name.is_empty())
+    }
+
+    pub fn is_any(&self) -> bool {
+        matches!(self, Self::Any)
+    }
+}
+
+/// A `QName`, likely "qualified name", consists of a namespace and name string.
+///
+/// A property cannot be retrieved or set without first being resolved into a
+/// `QName`. All other forms of names and multinames are either versions of
+/// `QName` with unspecified parameters, or multiple names to be checked in
+/// order.
+#[derive(Clone, Copy, Collect, Debug, PartialEq, Eq, Hash)]
+#[collect(no_drop)]
+pub struct QName<'gc> {
+    ns: Namespace<'gc>,
+    name: AvmString<'gc>,
+}
+
+impl<'gc> QName<'gc> {
+    pub fn new(ns: Namespace<'gc>, name: impl Into<AvmString<'gc>>) -> Self {
+        Self {
+            ns,
+            name: name.into(),
+        }
+    }
+
+    /// The name of a dynamic property, which always lives in the public namespace.
+    pub fn dynamic_name(local_part: impl Into<AvmString<'gc>>) -> Self {
+        Self::new(Namespace::public(), local_part)
+    }
+
+    /// Pull a `QName` from the multiname pool.
+    ///
+    /// This function returns an error if the multiname is not a `QName`, as
+    /// trait and class names are required to be.
+    pub fn from_abc_multiname(
+        translation_unit: TranslationUnit<'gc>,
+        multiname_index: Index<AbcMultiname>,
+        mc: MutationContext<'gc, '_>,
+    ) -> Result<Self, Error<'gc>> {
+        if multiname_index.0 == 0 {
+            return Err(Error::InvalidReference("QName", 0));
+        }
+
+        let abc = translation_unit.abc();
+        let abc_multiname = abc
+            .constant_pool
+            .multinames
+            .get(multiname_index.0 as usize - 1)
+            .ok_or_else(|| Error::InvalidReference("multiname", multiname_index.0))?;
+
+        match abc_multiname {
+            AbcMultiname::QName { namespace, name } | AbcMultiname::QNameA { namespace, name } => {
+                Ok(Self::new(
+                    Namespace::from_abc_namespace(translation_unit, namespace.clone(), mc)?,
+                    translation_unit.pool_string(name.0, mc)?,
+                ))
+            }
+            _ => Err(Error::InvalidReference("QName", multiname_index.0)),
+        }
+    }
+
+    /// Split a dotted class name, such as `flash.display.MovieClip`, into its
+    /// package namespace and local name, as found in `SymbolClass` tags.
+    pub fn from_qualified_name(name: &str, mc: MutationContext<'gc, '_>) -> Self {
+        match name.rfind('.') {
+            Some(dot) => Self::new(
+                Namespace::Package(AvmString::new(mc, name[..dot].to_string())),
+                AvmString::new(mc, name[dot + 1..].to_string()),
+            ),
+            None => Self::dynamic_name(AvmString::new(mc, name.to_string())),
+        }
+    }
+
+    pub fn local_name(&self) -> AvmString<'gc> {
+        self.name
+    }
+
+    pub fn namespace(&self) -> &Namespace<'gc> {
+        &self.ns
+    }
+}
+
+/// A `Multiname` consists of a name which could be resolved in one or more
+/// potential namespaces.
+///
+/// All unresolved names are of the form `Multiname`, and the name resolution
+/// process consists of searching each name space for a given name.
+///
+/// A missing name means the multiname matches any name, as in `*`.
+#[derive(Clone, Collect, Debug)]
+#[collect(no_drop)]
+pub struct Multiname<'gc> {
+    /// The list of namespaces that satisfy this multiname.
+    ns: Vec<Namespace<'gc>>,
+
+    /// The local name that satisfies this multiname, or `None` for any name.
+    name: Option<AvmString<'gc>>,
+}
+
+impl<'gc> Multiname<'gc> {
+    /// Read a namespace set from the ABC constant pool.
+    fn abc_namespace_set(
+        translation_unit: TranslationUnit<'gc>,
+        namespace_set_index: Index<AbcNamespaceSet>,
+        mc: MutationContext<'gc, '_>,
+    ) -> Result<Vec<Namespace<'gc>>, Error<'gc>> {
+        if namespace_set_index.0 == 0 {
+            return Err(Error::InvalidReference("namespace set", 0));
+        }
+
+        let abc = translation_unit.abc();
+        let ns_set = abc
+            .constant_pool
+            .namespace_sets
+            .get(namespace_set_index.0 as usize - 1)
+            .ok_or_else(|| Error::InvalidReference("namespace set", namespace_set_index.0))?;
+
+        let mut result = Vec::with_capacity(ns_set.len());
+        for ns in ns_set {
+            result.push(Namespace::from_abc_namespace(
+                translation_unit,
+                ns.clone(),
+                mc,
+            )?);
+        }
+
+        Ok(result)
+    }
+
+    /// Read a multiname from the ABC constant pool, popping any runtime name
+    /// or namespace it requires off of the activation's operand stack.
+    pub fn from_abc_multiname(
+        translation_unit: TranslationUnit<'gc>,
+        multiname_index: Index<AbcMultiname>,
+        activation: &mut Activation<'gc>,
+        context: &mut UpdateContext<'_, 'gc, '_>,
+    ) -> Result<Self, Error<'gc>> {
+        if multiname_index.0 == 0 {
+            return Ok(Self::any());
+        }
+
+        let abc = translation_unit.abc();
+        let abc_multiname = abc
+            .constant_pool
+            .multinames
+            .get(multiname_index.0 as usize - 1)
+            .ok_or_else(|| Error::InvalidReference("multiname", multiname_index.0))?;
+        let mc = context.gc_context;
+
+        Ok(match abc_multiname {
+            AbcMultiname::RTQName { name } | AbcMultiname::RTQNameA { name } => {
+                let ns = activation.pop().as_namespace()?;
+                Self {
+                    ns: vec![ns],
+                    name: Some(translation_unit.pool_string(name.0, mc)?),
+                }
+            }
+            AbcMultiname::RTQNameL | AbcMultiname::RTQNameLA => {
+                let name = activation.pop().coerce_to_string(activation, context)?;
+                let ns = activation.pop().as_namespace()?;
+                Self {
+                    ns: vec![ns],
+                    name: Some(name),
+                }
+            }
+            AbcMultiname::MultinameL { namespace_set }
+            | AbcMultiname::MultinameLA { namespace_set } => {
+                let name = activation.pop().coerce_to_string(activation, context)?;
+                Self {
+                    ns: Self::abc_namespace_set(translation_unit, namespace_set.clone(), mc)?,
+                    name: Some(name),
+                }
+            }
+            _ => Self::from_abc_multiname_static(translation_unit, multiname_index, mc)?,
+        })
+    }
+
+    /// Read a multiname that is known to have no runtime components, such as
+    /// a type name or a class's superclass name.
+    pub fn from_abc_multiname_static(
+        translation_unit: TranslationUnit<'gc>,
+        multiname_index: Index<AbcMultiname>,
+        mc: MutationContext<'gc, '_>,
+    ) -> Result<Self, Error<'gc>> {
+        if multiname_index.0 == 0 {
+            return Ok(Self::any());
+        }
+
+        let abc = translation_unit.abc();
+        let abc_multiname = abc
+            .constant_pool
+            .multinames
+            .get(multiname_index.0 as usize - 1)
+            .ok_or_else(|| Error::InvalidReference("multiname", multiname_index.0))?;
+
+        Ok(match abc_multiname {
+            AbcMultiname::QName { namespace, name } | AbcMultiname::QNameA { namespace, name } => {
+                Self {
+                    ns: vec![Namespace::from_abc_namespace(
+                        translation_unit,
+                        namespace.clone(),
+                        mc,
+                    )?],
+                    name: Self::abc_name(translation_unit, name.0, mc)?,
+                }
+            }
+            AbcMultiname::Multiname {
+                namespace_set,
+                name,
+            }
+            | AbcMultiname::MultinameA {
+                namespace_set,
+                name,
+            } => Self {
+                ns: Self::abc_namespace_set(translation_unit, namespace_set.clone(), mc)?,
+                name: Self::abc_name(translation_unit, name.0, mc)?,
+            },
+            _ => return Err(Error::Unimplemented("runtime multinames in this position")),
+        })
+    }
+
+    /// Name index zero is the wildcard name.
+    fn abc_name(
+        translation_unit: TranslationUnit<'gc>,
+        name_index: u32,
+        mc: MutationContext<'gc, '_>,
+    ) -> Result<Option<AvmString<'gc>>, Error<'gc>> {
+        if name_index == 0 {
+            Ok(None)
+        } else {
+            Ok(Some(translation_unit.pool_string(name_index, mc)?))
+        }
+    }
+
+    /// The multiname `*`, which matches any name in any namespace.
+    pub fn any() -> Self {
+        Self {
+            ns: vec![Namespace::Any],
+            name: None,
+        }
+    }
+
+    /// A multiname matching a single public name.
+    pub fn public(name: impl Into<AvmString<'gc>>) -> Self {
+        Self {
+            ns: vec![Namespace::public()],
+            name: Some(name.into()),
+        }
+    }
+
+    pub fn namespace_set(&self) -> impl Iterator<Item = &Namespace<'gc>> {
+        self.ns.iter()
+    }
+
+    pub fn local_name(&self) -> Option<AvmString<'gc>> {
+        self.name
+    }
+
+    /// Whether this multiname can only ever resolve to the given public name.
+    ///
+    /// This is how the names of builtin types are recognized in type
+    /// annotations and coercions.
+    pub fn is_public_name(&self, name: &str) -> bool {
+        self.name.map(|n| n == name).unwrap_or(false) && self.ns.iter().any(|ns| ns.is_public())
+    }
+}
+
+impl<'gc> From<QName<'gc>> for Multiname<'gc> {
+    fn from(q: QName<'gc>) -> Self {
+        Self {
+            ns: vec![q.ns],
+            name: Some(q.name),
+        }
+    }
+}
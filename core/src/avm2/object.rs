@@ -0,0 +1,553 @@
+//! AVM2 objects.
+
+use crate::avm2::activation::Activation;
+use crate::avm2::array_object::ArrayObject;
+use crate::avm2::class::Trait;
+use crate::avm2::class::TraitKind;
+use crate::avm2::class_object::ClassObject;
+use crate::avm2::dispatch_object::DispatchObject;
+use crate::avm2::event_object::EventObject;
+use crate::avm2::function::{Executable, FunctionObject};
+use crate::avm2::names::{Multiname, QName};
+use crate::avm2::primitive_object::PrimitiveObject;
+use crate::avm2::property::Attribute;
+use crate::avm2::scope::Scope;
+use crate::avm2::script_object::{ScriptObject, ScriptObjectData};
+use crate::avm2::stage_object::StageObject;
+use crate::avm2::value::Value;
+use crate::avm2::{AvmString, Error};
+use crate::context::UpdateContext;
+use crate::display_object::DisplayObject;
+use enumset::EnumSet;
+use gc_arena::{Collect, GcCell, MutationContext};
+use ruffle_macros::enum_trait_object;
+use std::fmt::Debug;
+
+/// Represents an object that can be directly interacted with by the AVM2
+/// runtime.
+#[enum_trait_object(
+    #[derive(Clone, Collect, Debug, Copy)]
+    #[collect(no_drop)]
+    pub enum Object<'gc> {
+        ScriptObject(ScriptObject<'gc>),
+        FunctionObject(FunctionObject<'gc>),
+        ClassObject(ClassObject<'gc>),
+        ArrayObject(ArrayObject<'gc>),
+        PrimitiveObject(PrimitiveObject<'gc>),
+        StageObject(StageObject<'gc>),
+        EventObject(EventObject<'gc>),
+        DispatchObject(DispatchObject<'gc>),
+    }
+)]
+pub trait TObject<'gc>: 'gc + Collect + Debug + Into<Object<'gc>> + Clone + Copy {
+    /// The script object that holds this object's properties, slots and
+    /// prototype.
+    ///
+    /// Object types built on top of a `ScriptObject` only need to implement
+    /// this; every other method defaults to operating on the base object.
+    fn base(&self) -> GcCell<'gc, ScriptObjectData<'gc>>;
+
+    /// Retrieve a property by its QName, without taking prototype lookups
+    /// into account.
+    ///
+    /// `receiver` is the object that the property was originally looked up
+    /// on, which is passed as `this` to getters.
+    fn get_property_local(
+        &self,
+        receiver: Object<'gc>,
+        name: &QName<'gc>,
+        activation: &mut Activation<'gc>,
+        context: &mut UpdateContext<'_, 'gc, '_>,
+    ) -> Result<Value<'gc>, Error<'gc>> {
+        let getter = self.base().read().getter(name);
+        match getter {
+            Some(getter) => getter.call(Some(receiver), &[], activation, context),
+            None => self.base().read().get_property_local(name),
+        }
+    }
+
+    /// Retrieve a property by its QName, searching the prototype chain.
+    ///
+    /// Properties that don't exist anywhere in the chain are `undefined`.
+    fn get_property(
+        &self,
+        receiver: Object<'gc>,
+        name: &QName<'gc>,
+        activation: &mut Activation<'gc>,
+        context: &mut UpdateContext<'_, 'gc, '_>,
+    ) -> Result<Value<'gc>, Error<'gc>> {
+        let mut current: Option<Object<'gc>> = Some((*self).into());
+        while let Some(object) = current {
+            if object.has_own_property(name) {
+                return object.get_property_local(receiver, name, activation, context);
+            }
+
+            current = object.proto();
+        }
+
+        Ok(Value::Undefined)
+    }
+
+    /// Set a property by its QName.
+    ///
+    /// Unlike getting a property, this never writes to the prototype chain:
+    /// properties that this object doesn't have are created on it as dynamic
+    /// properties. Writing to a read-only property is a `ReferenceError`.
+    fn set_property(
+        &self,
+        receiver: Object<'gc>,
+        name: &QName<'gc>,
+        value: Value<'gc>,
+        activation: &mut Activation<'gc>,
+        context: &mut UpdateContext<'_, 'gc, '_>,
+    ) -> Result<(), Error<'gc>> {
+        let setter = self.find_setter(name);
+        match setter {
+            Some(setter) => {
+                setter.call(Some(receiver), &[value], activation, context)?;
+                Ok(())
+            }
+            None => self
+                .base()
+                .write(context.gc_context)
+                .set_property_local(name, value, false),
+        }
+    }
+
+    /// Initialize a property by its QName.
+    ///
+    /// This is identical to setting a property, except that constants may be
+    /// initialized.
+    fn init_property(
+        &self,
+        receiver: Object<'gc>,
+        name: &QName<'gc>,
+        value: Value<'gc>,
+        activation: &mut Activation<'gc>,
+        context: &mut UpdateContext<'_, 'gc, '_>,
+    ) -> Result<(), Error<'gc>> {
+        let setter = self.find_setter(name);
+        match setter {
+            Some(setter) => {
+                setter.call(Some(receiver), &[value], activation, context)?;
+                Ok(())
+            }
+            None => self
+                .base()
+                .write(context.gc_context)
+                .set_property_local(name, value, true),
+        }
+    }
+
+    /// Find the setter that writing a property calls.
+    ///
+    /// Accessors may be inherited from the prototype chain, as done by
+    /// builtin classes, unless this object has its own property of the same
+    /// name.
+    fn find_setter(&self, name: &QName<'gc>) -> Option<Object<'gc>> {
+        let mut current: Option<Object<'gc>> = Some((*self).into());
+        while let Some(object) = current {
+            if object.has_own_property(name) {
+                return object.base().read().setter(name);
+            }
+
+            current = object.proto();
+        }
+
+        None
+    }
+
+    /// Delete a property by QName, returning `false` if it can't be deleted.
+    fn delete_property(&self, gc_context: MutationContext<'gc, '_>, name: &QName<'gc>) -> bool {
+        self.base().write(gc_context).delete_property(name)
+    }
+
+    /// Retrieve a slot by its index.
+    fn get_slot(&self, id: u32) -> Result<Value<'gc>, Error<'gc>> {
+        self.base().read().get_slot(id)
+    }
+
+    /// Set a slot by its index.
+    fn set_slot(
+        &self,
+        id: u32,
+        value: Value<'gc>,
+        gc_context: MutationContext<'gc, '_>,
+    ) -> Result<(), Error<'gc>> {
+        self.base().write(gc_context).set_slot(id, value)
+    }
+
+    /// Indicates whether or not a property exists on this object, without
+    /// checking the prototype chain.
+    fn has_own_property(&self, name: &QName<'gc>) -> bool {
+        self.base().read().has_own_property(name)
+    }
+
+    /// Indicates whether or not a property exists on this object or its
+    /// prototype chain.
+    fn has_property(&self, name: &QName<'gc>) -> bool {
+        if self.has_own_property(name) {
+            return true;
+        }
+
+        match self.proto() {
+            Some(proto) => proto.has_property(name),
+            None => false,
+        }
+    }
+
+    /// Find the name of a property of this object, or its prototype chain,
+    /// with the given local name in any namespace.
+    fn resolve_any(&self, local_name: AvmString<'gc>) -> Option<QName<'gc>> {
+        if let Some(name) = self.base().read().resolve_any(&local_name) {
+            return Some(name);
+        }
+
+        match self.proto() {
+            Some(proto) => proto.resolve_any(local_name),
+            None => None,
+        }
+    }
+
+    /// Resolve a multiname into the QName of a property that this object, or
+    /// its prototype chain, has.
+    ///
+    /// The namespaces of the multiname are tried in order. This yields `None`
+    /// if the property doesn't exist in any of them.
+    fn resolve_multiname(&self, multiname: &Multiname<'gc>) -> Option<QName<'gc>> {
+        let local_name = multiname.local_name()?;
+
+        for ns in multiname.namespace_set() {
+            if ns.is_any() {
+                if let Some(name) = self.resolve_any(local_name) {
+                    return Some(name);
+                }
+            } else {
+                let qname = QName::new(*ns, local_name);
+                if self.has_property(&qname) {
+                    return Some(qname);
+                }
+            }
+        }
+
+        None
+    }
+
+    /// Get the prototype of this object.
+    fn proto(&self) -> Option<Object<'gc>> {
+        self.base().read().proto()
+    }
+
+    /// Retrieve the index of the next enumerable property after the given
+    /// one.
+    ///
+    /// Indices start at one; zero starts the enumeration and is returned once
+    /// there are no properties left.
+    fn get_next_enumerant(&self, last_index: u32) -> u32 {
+        self.base().read().get_next_enumerant(last_index)
+    }
+
+    /// Retrieve the name of the property at the given enumeration index.
+    fn get_enumerant_name(
+        &self,
+        index: u32,
+        _gc_context: MutationContext<'gc, '_>,
+    ) -> Option<Value<'gc>> {
+        self.base().read().get_enumerant_name(index)
+    }
+
+    /// Retrieve the value of the property at the given enumeration index.
+    fn get_enumerant_value(
+        &self,
+        index: u32,
+        activation: &mut Activation<'gc>,
+        context: &mut UpdateContext<'_, 'gc, '_>,
+    ) -> Result<Value<'gc>, Error<'gc>> {
+        match self.get_enumerant_name(index, context.gc_context) {
+            Some(name) => {
+                let name = QName::dynamic_name(name.coerce_to_string(activation, context)?);
+                self.get_property((*self).into(), &name, activation, context)
+            }
+            None => Ok(Value::Undefined),
+        }
+    }
+
+    /// Define a property holding the given value, with the given attributes.
+    ///
+    /// This is used by native code to set up builtin objects.
+    fn define_value(
+        &self,
+        gc_context: MutationContext<'gc, '_>,
+        name: QName<'gc>,
+        value: Value<'gc>,
+        attributes: EnumSet<Attribute>,
+    ) {
+        self.base()
+            .write(gc_context)
+            .define_value(name, value, attributes)
+    }
+
+    /// Install a getter function into this object.
+    fn install_getter(
+        &self,
+        gc_context: MutationContext<'gc, '_>,
+        name: QName<'gc>,
+        function: Object<'gc>,
+    ) {
+        self.base().write(gc_context).install_getter(name, function)
+    }
+
+    /// Install a setter function into this object.
+    fn install_setter(
+        &self,
+        gc_context: MutationContext<'gc, '_>,
+        name: QName<'gc>,
+        function: Object<'gc>,
+    ) {
+        self.base().write(gc_context).install_setter(name, function)
+    }
+
+    /// Install a slot, holding the given value, into this object.
+    ///
+    /// A slot ID of zero assigns the next free slot.
+    fn install_slot(
+        &self,
+        gc_context: MutationContext<'gc, '_>,
+        name: QName<'gc>,
+        id: u32,
+        value: Value<'gc>,
+        is_const: bool,
+    ) {
+        self.base()
+            .write(gc_context)
+            .install_slot(name, id, value, is_const)
+    }
+
+    /// Install a trait from a class or script into this object.
+    ///
+    /// Methods and accessors are bound to this object, and close over the
+    /// given scope. `superclass` is the superclass of the class that
+    /// declared the trait, which `super` expressions in its methods refer to.
+    fn install_trait(
+        &self,
+        context: &mut UpdateContext<'_, 'gc, '_>,
+        trait_entry: &Trait<'gc>,
+        scope: Option<GcCell<'gc, Scope<'gc>>>,
+        superclass: Option<Object<'gc>>,
+    ) -> Result<(), Error<'gc>> {
+        let receiver: Object<'gc> = (*self).into();
+        let fn_proto = context.avm2.prototypes().function;
+        let name = *trait_entry.name();
+
+        match trait_entry.kind() {
+            TraitKind::Slot {
+                slot_id,
+                default_value,
+            } => self.install_slot(
+                context.gc_context,
+                name,
+                checked_slot_id(*slot_id)?,
+                default_value.clone(),
+                false,
+            ),
+            TraitKind::Const {
+                slot_id,
+                default_value,
+            } => self.install_slot(
+                context.gc_context,
+                name,
+                checked_slot_id(*slot_id)?,
+                default_value.clone(),
+                true,
+            ),
+            TraitKind::Method { method, .. } => {
+                let function = FunctionObject::from_method(
+                    context.gc_context,
+                    method.clone(),
+                    scope,
+                    fn_proto,
+                    Some(receiver),
+                    superclass,
+                );
+                self.define_value(
+                    context.gc_context,
+                    name,
+                    function.into(),
+                    Attribute::DontEnum | Attribute::DontDelete | Attribute::ReadOnly,
+                );
+            }
+            TraitKind::Getter { method, .. } => {
+                let function = FunctionObject::from_method(
+                    context.gc_context,
+                    method.clone(),
+                    scope,
+                    fn_proto,
+                    Some(receiver),
+                    superclass,
+                );
+                self.install_getter(context.gc_context, name, function);
+            }
+            TraitKind::Setter { method, .. } => {
+                let function = FunctionObject::from_method(
+                    context.gc_context,
+                    method.clone(),
+                    scope,
+                    fn_proto,
+                    Some(receiver),
+                    superclass,
+                );
+                self.install_setter(context.gc_context, name, function);
+            }
+            TraitKind::Class { slot_id, .. } => {
+                // The class object is created by `newclass` and stored into
+                // the slot by the script's initializer.
+                self.install_slot(
+                    context.gc_context,
+                    name,
+                    checked_slot_id(*slot_id)?,
+                    Value::Null,
+                    false,
+                );
+            }
+            TraitKind::Function { slot_id, function } => {
+                let function = FunctionObject::from_method(
+                    context.gc_context,
+                    function.clone(),
+                    scope,
+                    fn_proto,
+                    None,
+                    None,
+                );
+                self.install_slot(
+                    context.gc_context,
+                    name,
+                    checked_slot_id(*slot_id)?,
+                    function.into(),
+                    false,
+                );
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Call this object as a function.
+    ///
+    /// `receiver` is the `this` of the call. Functions bound to an object,
+    /// such as methods, ignore it.
+    fn call(
+        &self,
+        _receiver: Option<Object<'gc>>,
+        _arguments: &[Value<'gc>],
+        _activation: &mut Activation<'gc>,
+        _context: &mut UpdateContext<'_, 'gc, '_>,
+    ) -> Result<Value<'gc>, Error<'gc>> {
+        Err(Error::TypeError("Value is not a function".to_string()))
+    }
+
+    /// Construct a new object with this object as its constructor.
+    fn construct(
+        &self,
+        _arguments: &[Value<'gc>],
+        _activation: &mut Activation<'gc>,
+        _context: &mut UpdateContext<'_, 'gc, '_>,
+    ) -> Result<Object<'gc>, Error<'gc>> {
+        Err(Error::TypeError("Value is not a constructor".to_string()))
+    }
+
+    /// Determine if this object is an instance of the given constructor,
+    /// by searching its prototype chain for the constructor's prototype.
+    fn is_instance_of(
+        &self,
+        constructor: Object<'gc>,
+        activation: &mut Activation<'gc>,
+        context: &mut UpdateContext<'_, 'gc, '_>,
+    ) -> Result<bool, Error<'gc>> {
+        let prototype = constructor
+            .get_property(
+                constructor,
+                &QName::dynamic_name("prototype"),
+                activation,
+                context,
+            )?
+            .as_object()?;
+
+        let mut proto = self.proto();
+        while let Some(proto_object) = proto {
+            if Object::ptr_eq(proto_object, prototype) {
+                return Ok(true);
+            }
+
+            proto = proto_object.proto();
+        }
+
+        Ok(false)
+    }
+
+    /// Get the underlying executable for this object, if it's a function.
+    fn as_executable(&self) -> Option<Executable<'gc>> {
+        None
+    }
+
+    /// Get this object as a class object, if it is one.
+    fn as_class_object(&self) -> Option<ClassObject<'gc>> {
+        None
+    }
+
+    /// Get the primitive value boxed by this object, if it's a boxed
+    /// primitive.
+    fn as_primitive(&self) -> Option<Value<'gc>> {
+        None
+    }
+
+    /// Get this object as an array, if it is one.
+    fn as_array_object(&self) -> Option<ArrayObject<'gc>> {
+        None
+    }
+
+    /// Get this object as an event, if it is one.
+    fn as_event_object(&self) -> Option<EventObject<'gc>> {
+        None
+    }
+
+    /// Get this object as the listeners of an event dispatcher, if it is
+    /// one.
+    fn as_dispatch_object(&self) -> Option<DispatchObject<'gc>> {
+        None
+    }
+
+    /// Get the display object this object represents, if any.
+    fn as_display_object(&self) -> Option<DisplayObject<'gc>> {
+        None
+    }
+
+    /// Get a raw pointer value for this object.
+    fn as_ptr(&self) -> *const ScriptObjectData<'gc> {
+        self.base().as_ptr()
+    }
+}
+
+impl<'gc> Object<'gc> {
+    pub fn ptr_eq(a: Object<'gc>, b: Object<'gc>) -> bool {
+        a.as_ptr() == b.as_ptr()
+    }
+}
+
+/// The largest slot ID that a trait may request.
+///
+/// Slots are stored densely, so installing a slot allocates every slot below
+/// it. Real ABC files number their slots from 1 upwards, so larger IDs only
+/// come from malformed files.
+const MAX_SLOT_ID: u32 = 0xFFFF;
+
+/// Reject slot IDs from the ABC file that would allocate an absurd number of slots.
+fn checked_slot_id<'gc>(slot_id: u32) -> Result<u32, Error<'gc>> {
+    if slot_id > MAX_SLOT_ID {
+        return Err(Error::VerifyError(format!(
+            "Slot ID {} is out of range",
+            slot_id
+        )));
+    }
+
+    Ok(slot_id)
+}
@@ -0,0 +1,78 @@
+//! Boxed primitives
+
+use crate::avm2::object::{Object, TObject};
+use crate::avm2::script_object::{ScriptObject, ScriptObjectData};
+use crate::avm2::value::Value;
+use crate::context::UpdateContext;
+use gc_arena::{Collect, GcCell, MutationContext};
+
+/// An object that boxes a primitive value, so that methods can be called on
+/// it.
+#[derive(Collect, Debug, Clone, Copy)]
+#[collect(no_drop)]
+pub struct PrimitiveObject<'gc>(GcCell<'gc, PrimitiveObjectData<'gc>>);
+
+#[derive(Collect, Debug, Clone)]
+#[collect(no_drop)]
+pub struct PrimitiveObjectData<'gc> {
+    /// Base script object
+    base: ScriptObject<'gc>,
+
+    /// The primitive value this object represents.
+    primitive: Value<'gc>,
+}
+
+impl<'gc> PrimitiveObject<'gc> {
+    /// Box a primitive into an object.
+    ///
+    /// The prototype of the object is picked according to the type of the
+    /// primitive.
+    pub fn from_primitive(
+        primitive: Value<'gc>,
+        context: &mut UpdateContext<'_, 'gc, '_>,
+    ) -> Object<'gc> {
+        let prototypes = context.avm2.prototypes();
+        let proto = match primitive {
+            Value::Bool(_) => prototypes.boolean,
+            Value::Number(_) => prototypes.number,
+            Value::Integer(_) => prototypes.int,
+            Value::Unsigned(_) => prototypes.uint,
+            Value::String(_) => prototypes.string,
+            _ => prototypes.object,
+        };
+
+        Self::with_proto(context.gc_context, primitive, proto)
+    }
+
+    /// Box a primitive into an object with the given prototype.
+    pub fn with_proto(
+        mc: MutationContext<'gc, '_>,
+        primitive: Value<'gc>,
+        proto: Object<'gc>,
+    ) -> Object<'gc> {
+        PrimitiveObject(GcCell::allocate(
+            mc,
+            PrimitiveObjectData {
+                base: ScriptObject::base_object(mc, Some(proto)),
+                primitive,
+            },
+        ))
+        .into()
+    }
+
+    /// Replace the boxed value, as done by the constructors of primitive
+    /// classes.
+    pub fn set_primitive(self, mc: MutationContext<'gc, '_>, primitive: Value<'gc>) {
+        self.0.write(mc).primitive = primitive;
+    }
+}
+
+impl<'gc> TObject<'gc> for PrimitiveObject<'gc> {
+    fn base(&self) -> GcCell<'gc, ScriptObjectData<'gc>> {
+        self.0.read().base.base()
+    }
+
+    fn as_primitive(&self) -> Option<Value<'gc>> {
+        Some(self.0.read().primitive.clone())
+    }
+}
@@ -0,0 +1,189 @@
+//! Property data structures
+
+use crate::avm2::names::QName;
+use crate::avm2::object::Object;
+use crate::avm2::value::Value;
+use enumset::{EnumSet, EnumSetType};
+use gc_arena::{Collect, CollectionContext};
+use indexmap::IndexMap;
+
+/// Attributes of properties in the AVM runtime.
+#[derive(EnumSetType, Debug)]
+pub enum Attribute {
+    DontEnum,
+    DontDelete,
+    ReadOnly,
+}
+
+#[derive(Clone, Debug)]
+pub enum Property<'gc> {
+    /// An accessor property, backed by a getter and setter function.
+    Virtual {
+        get: Option<Object<'gc>>,
+        set: Option<Object<'gc>>,
+        attributes: EnumSet<Attribute>,
+    },
+
+    /// A property holding its value directly.
+    Stored {
+        value: Value<'gc>,
+        attributes: EnumSet<Attribute>,
+    },
+
+    /// A trait property whose value lives in one of the object's slots.
+    Slot {
+        slot_id: u32,
+        attributes: EnumSet<Attribute>,
+    },
+}
+
+unsafe impl<'gc> Collect for Property<'gc> {
+    fn trace(&self, cc: CollectionContext) {
+        match self {
+            Property::Virtual { get, set, .. } => {
+                get.trace(cc);
+                set.trace(cc);
+            }
+            Property::Stored { value, .. } => value.trace(cc),
+            Property::Slot { .. } => {}
+        }
+    }
+}
+
+impl<'gc> Property<'gc> {
+    /// Create a new stored property.
+    pub fn new_stored(value: impl Into<Value<'gc>>, attributes: EnumSet<Attribute>) -> Self {
+        Property::Stored {
+            value: value.into(),
+            attributes,
+        }
+    }
+
+    /// Create a new slot property.
+    pub fn new_slot(slot_id: u32, attributes: EnumSet<Attribute>) -> Self {
+        Property::Slot {
+            slot_id,
+            attributes,
+        }
+    }
+
+    /// Create a new accessor property with no getter or setter yet.
+    pub fn new_virtual(attributes: EnumSet<Attribute>) -> Self {
+        Property::Virtual {
+            get: None,
+            set: None,
+            attributes,
+        }
+    }
+
+    /// Install a getter into this property.
+    ///
+    /// Any value the property held before is replaced by the accessor.
+    pub fn install_virtual_getter(&mut self, getter_impl: Object<'gc>) {
+        match self {
+            Property::Virtual { get, .. } => *get = Some(getter_impl),
+            _ => {
+                *self = Property::Virtual {
+                    get: Some(getter_impl),
+                    set: None,
+                    attributes: self.attributes(),
+                }
+            }
+        }
+    }
+
+    /// Install a setter into this property.
+    ///
+    /// Any value the property held before is replaced by the accessor.
+    pub fn install_virtual_setter(&mut self, setter_impl: Object<'gc>) {
+        match self {
+            Property::Virtual { set, .. } => *set = Some(setter_impl),
+            _ => {
+                *self = Property::Virtual {
+                    get: None,
+                    set: Some(setter_impl),
+                    attributes: self.attributes(),
+                }
+            }
+        }
+    }
+
+    /// List this property's attributes.
+    pub fn attributes(&self) -> EnumSet<Attribute> {
+        match self {
+            Property::Virtual { attributes, .. } => *attributes,
+            Property::Stored { attributes, .. } => *attributes,
+            Property::Slot { attributes, .. } => *attributes,
+        }
+    }
+
+    pub fn is_enumerable(&self) -> bool {
+        !self.attributes().contains(Attribute::DontEnum)
+    }
+
+    pub fn can_delete(&self) -> bool {
+        !self.attributes().contains(Attribute::DontDelete)
+    }
+}
+
+/// A map from qualified names to properties.
+///
+/// This maintains the insertion order of properties, which is the order in
+/// which dynamic properties are enumerated.
+#[derive(Debug)]
+pub struct PropertyMap<'gc, V>(IndexMap<QName<'gc>, V>);
+
+unsafe impl<'gc, V: Collect> Collect for PropertyMap<'gc, V> {
+    fn trace(&self, cc: CollectionContext) {
+        for (name, value) in self.0.iter() {
+            name.trace(cc);
+            value.trace(cc);
+        }
+    }
+}
+
+impl<'gc, V> PropertyMap<'gc, V> {
+    pub fn new() -> Self {
+        Self(IndexMap::new())
+    }
+
+    pub fn get(&self, name: &QName<'gc>) -> Option<&V> {
+        self.0.get(name)
+    }
+
+    pub fn get_mut(&mut self, name: &QName<'gc>) -> Option<&mut V> {
+        self.0.get_mut(name)
+    }
+
+    /// Gets a property and its name by index, based on insertion order.
+    pub fn get_index(&self, index: usize) -> Option<(&QName<'gc>, &V)> {
+        self.0.get_index(index)
+    }
+
+    pub fn contains_key(&self, name: &QName<'gc>) -> bool {
+        self.0.contains_key(name)
+    }
+
+    pub fn insert(&mut self, name: QName<'gc>, value: V) -> Option<V> {
+        self.0.insert(name, value)
+    }
+
+    pub fn remove(&mut self, name: &QName<'gc>) -> Option<V> {
+        // Note that we must use shift_remove to maintain order in case this object is enumerated.
+        self.0.shift_remove(name)
+    }
+
+    /// Find the name of a property with the given local name, in any namespace.
+    pub fn find_local_name(&self, local_name: &str) -> Option<QName<'gc>> {
+        self.0
+            .keys()
+            .find(|name| name.local_name() == local_name)
+            .copied()
+    }
+}
+
+impl<'gc, V> Default for PropertyMap<'gc, V> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,58 @@
+//! Represents AVM2 scope chain resolution.
+
+use crate::avm2::names::Multiname;
+use crate::avm2::object::{Object, TObject};
+use gc_arena::{Collect, GcCell, MutationContext};
+
+/// Represents a scope chain for an AVM2 activation.
+///
+/// Both `pushscope` and `pushwith` add a scope to the chain. Functions and
+/// classes close over the chain that was active when they were created.
+#[derive(Debug, Collect)]
+#[collect(no_drop)]
+pub struct Scope<'gc> {
+    parent: Option<GcCell<'gc, Scope<'gc>>>,
+    values: Object<'gc>,
+}
+
+impl<'gc> Scope<'gc> {
+    /// Push a new scope onto an existing (or empty) scope chain.
+    pub fn push_scope(
+        scope_stack: Option<GcCell<'gc, Scope<'gc>>>,
+        object: Object<'gc>,
+        mc: MutationContext<'gc, '_>,
+    ) -> GcCell<'gc, Self> {
+        GcCell::allocate(
+            mc,
+            Self {
+                parent: scope_stack,
+                values: object,
+            },
+        )
+    }
+
+    /// Returns a reference to the current local scope object.
+    pub fn locals(&self) -> &Object<'gc> {
+        &self.values
+    }
+
+    /// Returns a reference to the parent scope object.
+    pub fn parent(&self) -> Option<GcCell<'gc, Scope<'gc>>> {
+        self.parent
+    }
+
+    /// Find an object that contains a given property in the scope stack.
+    ///
+    /// This function yields `None` if no such scope exists.
+    pub fn find(&self, name: &Multiname<'gc>) -> Option<Object<'gc>> {
+        if self.values.resolve_multiname(name).is_some() {
+            return Some(self.values);
+        }
+
+        if let Some(parent) = self.parent {
+            return parent.read().find(name);
+        }
+
+        None
+    }
+}
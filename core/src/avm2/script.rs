@@ -0,0 +1,312 @@
+//! Whole script representation
+
+use crate::avm2::class::{Class, Trait};
+use crate::avm2::method::{BytecodeMethod, Method};
+use crate::avm2::{AvmString, Error, Object};
+use fnv::FnvHashMap;
+use gc_arena::{Collect, Gc, GcCell, MutationContext};
+use std::rc::Rc;
+use swf::avm2::types::AbcFile;
+
+/// Wraps data that holds no garbage-collected pointers, so that it can be
+/// stored inside of garbage-collected structures.
+#[derive(Clone, Debug)]
+pub struct CollectWrapper<T>(pub T);
+
+unsafe impl<T: 'static> Collect for CollectWrapper<T> {
+    #[inline]
+    fn needs_trace() -> bool {
+        false
+    }
+}
+
+/// A loaded ABC file, along with the runtime structures built from it.
+///
+/// Strings, methods, classes and scripts are built the first time they are
+/// referenced and cached afterwards, so that every reference to them shares
+/// the same instance.
+#[derive(Copy, Clone, Debug, Collect)]
+#[collect(no_drop)]
+pub struct TranslationUnit<'gc>(GcCell<'gc, TranslationUnitData<'gc>>);
+
+#[derive(Clone, Debug, Collect)]
+#[collect(no_drop)]
+struct TranslationUnitData<'gc> {
+    /// The ABC file that all of the data in this unit comes from.
+    abc: CollectWrapper<Rc<AbcFile>>,
+
+    /// The index of each method's body in the ABC file, if it has one.
+    method_bodies: CollectWrapper<Vec<Option<u32>>>,
+
+    /// All classes loaded from the ABC's class list.
+    classes: FnvHashMap<u32, GcCell<'gc, Class<'gc>>>,
+
+    /// All methods loaded from the ABC's method list.
+    methods: FnvHashMap<u32, Method<'gc>>,
+
+    /// All scripts loaded from the ABC's scripts list.
+    scripts: FnvHashMap<u32, Script<'gc>>,
+
+    /// All strings loaded from the ABC's constant pool.
+    strings: FnvHashMap<u32, AvmString<'gc>>,
+}
+
+impl<'gc> TranslationUnit<'gc> {
+    pub fn from_abc(abc: Rc<AbcFile>, mc: MutationContext<'gc, '_>) -> Self {
+        let mut method_bodies = vec![None; abc.methods.len()];
+        for (body_index, body) in abc.method_bodies.iter().enumerate() {
+            if let Some(entry) = method_bodies.get_mut(body.method.0 as usize) {
+                *entry = Some(body_index as u32);
+            }
+        }
+
+        Self(GcCell::allocate(
+            mc,
+            TranslationUnitData {
+                abc: CollectWrapper(abc),
+                method_bodies: CollectWrapper(method_bodies),
+                classes: FnvHashMap::default(),
+                methods: FnvHashMap::default(),
+                scripts: FnvHashMap::default(),
+                strings: FnvHashMap::default(),
+            },
+        ))
+    }
+
+    /// Retrieve the underlying `AbcFile` for this translation unit.
+    pub fn abc(self) -> Rc<AbcFile> {
+        self.0.read().abc.0.clone()
+    }
+
+    /// Load a method from the ABC file and return its method definition.
+    pub fn load_method(
+        self,
+        method_index: u32,
+        mc: MutationContext<'gc, '_>,
+    ) -> Result<Method<'gc>, Error<'gc>> {
+        let read = self.0.read();
+        if let Some(method) = read.methods.get(&method_index) {
+            return Ok(method.clone());
+        }
+
+        let abc = read.abc.0.clone();
+        let body_index = *read
+            .method_bodies
+            .0
+            .get(method_index as usize)
+            .ok_or_else(|| Error::InvalidReference("method", method_index))?;
+        drop(read);
+
+        let method: Method<'gc> =
+            Gc::allocate(mc, BytecodeMethod::new(self, abc, method_index, body_index)).into();
+        self.0
+            .write(mc)
+            .methods
+            .insert(method_index, method.clone());
+
+        Ok(method)
+    }
+
+    /// Load a class from the ABC file and return its class definition.
+    pub fn load_class(
+        self,
+        class_index: u32,
+        mc: MutationContext<'gc, '_>,
+    ) -> Result<GcCell<'gc, Class<'gc>>, Error<'gc>> {
+        if let Some(class) = self.0.read().classes.get(&class_index) {
+            return Ok(*class);
+        }
+
+        let class = Class::from_abc_index(self, class_index, mc)?;
+        self.0.write(mc).classes.insert(class_index, class);
+
+        Ok(class)
+    }
+
+    /// Load a script from the ABC file and return its script definition.
+    ///
+    /// The script's traits are defined on the given global object.
+    pub fn load_script(
+        self,
+        script_index: u32,
+        globals: Object<'gc>,
+        mc: MutationContext<'gc, '_>,
+    ) -> Result<Script<'gc>, Error<'gc>> {
+        if let Some(script) = self.0.read().scripts.get(&script_index) {
+            return Ok(*script);
+        }
+
+        let script = Script::from_abc_index(self, script_index, globals, mc)?;
+        self.0.write(mc).scripts.insert(script_index, script);
+
+        Ok(script)
+    }
+
+    /// Load a string from the ABC's constant pool.
+    ///
+    /// String index zero is the empty string.
+    pub fn pool_string(
+        self,
+        string_index: u32,
+        mc: MutationContext<'gc, '_>,
+    ) -> Result<AvmString<'gc>, Error<'gc>> {
+        if string_index == 0 {
+            return Ok("".into());
+        }
+
+        let read = self.0.read();
+        if let Some(string) = read.strings.get(&string_index) {
+            return Ok(*string);
+        }
+
+        let string = AvmString::new(
+            mc,
+            read.abc
+                .0
+                .constant_pool
+                .strings
+                .get(string_index as usize - 1)
+                .ok_or_else(|| Error::InvalidReference("string", string_index))?
+                .clone(),
+        );
+        drop(read);
+
+        self.0.write(mc).strings.insert(string_index, string);
+
+        Ok(string)
+    }
+
+    /// Load an integer from the ABC's constant pool.
+    pub fn pool_int(self, int_index: u32) -> Result<i32, Error<'gc>> {
+        if int_index == 0 {
+            return Ok(0);
+        }
+
+        self.0
+            .read()
+            .abc
+            .0
+            .constant_pool
+            .ints
+            .get(int_index as usize - 1)
+            .copied()
+            .ok_or_else(|| Error::InvalidReference("int", int_index))
+    }
+
+    /// Load an unsigned integer from the ABC's constant pool.
+    pub fn pool_uint(self, uint_index: u32) -> Result<u32, Error<'gc>> {
+        if uint_index == 0 {
+            return Ok(0);
+        }
+
+        self.0
+            .read()
+            .abc
+            .0
+            .constant_pool
+            .uints
+            .get(uint_index as usize - 1)
+            .copied()
+            .ok_or_else(|| Error::InvalidReference("uint", uint_index))
+    }
+
+    /// Load a double from the ABC's constant pool.
+    pub fn pool_double(self, double_index: u32) -> Result<f64, Error<'gc>> {
+        if double_index == 0 {
+            return Ok(std::f64::NAN);
+        }
+
+        self.0
+            .read()
+            .abc
+            .0
+            .constant_pool
+            .doubles
+            .get(double_index as usize - 1)
+            .copied()
+            .ok_or_else(|| Error::InvalidReference("double", double_index))
+    }
+}
+
+/// A loaded script from an ABC file.
+///
+/// A script defines traits, usually classes, on the global object. Its
+/// initializer runs at most once: either when the ABC is loaded, for the last
+/// script in the file, or the first time one of its traits is looked up.
+#[derive(Copy, Clone, Debug, Collect)]
+#[collect(no_drop)]
+pub struct Script<'gc>(GcCell<'gc, ScriptData<'gc>>);
+
+#[derive(Clone, Debug, Collect)]
+#[collect(no_drop)]
+struct ScriptData<'gc> {
+    /// The global object the script's traits are defined on.
+    globals: Object<'gc>,
+
+    /// The initializer method to run for the script.
+    init: Method<'gc>,
+
+    /// Traits that this script uses.
+    traits: Vec<Trait<'gc>>,
+
+    /// Whether the initializer has started running.
+    initialized: bool,
+}
+
+impl<'gc> Script<'gc> {
+    /// Construct a script from an `AbcFile` and script index.
+    pub fn from_abc_index(
+        translation_unit: TranslationUnit<'gc>,
+        script_index: u32,
+        globals: Object<'gc>,
+        mc: MutationContext<'gc, '_>,
+    ) -> Result<Self, Error<'gc>> {
+        let abc = translation_unit.abc();
+        let script = abc
+            .scripts
+            .get(script_index as usize)
+            .ok_or_else(|| Error::InvalidReference("script", script_index))?;
+
+        let init = translation_unit.load_method(script.init_method.0, mc)?;
+        let mut traits = Vec::with_capacity(script.traits.len());
+        for abc_trait in script.traits.iter() {
+            traits.push(Trait::from_abc_trait(translation_unit, abc_trait, mc)?);
+        }
+
+        Ok(Self(GcCell::allocate(
+            mc,
+            ScriptData {
+                globals,
+                init,
+                traits,
+                initialized: false,
+            },
+        )))
+    }
+
+    pub fn globals(self) -> Object<'gc> {
+        self.0.read().globals
+    }
+
+    pub fn init(self) -> Method<'gc> {
+        self.0.read().init.clone()
+    }
+
+    pub fn traits(self) -> Vec<Trait<'gc>> {
+        self.0.read().traits.clone()
+    }
+
+    /// Mark the script as initialized, returning `false` if it already was.
+    ///
+    /// This is done before the initializer runs, so that scripts referring to
+    /// each other's definitions don't initialize each other forever.
+    pub fn begin_initialization(self, mc: MutationContext<'gc, '_>) -> bool {
+        let mut write = self.0.write(mc);
+        if write.initialized {
+            false
+        } else {
+            write.initialized = true;
+            true
+        }
+    }
+}
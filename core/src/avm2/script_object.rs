@@ -0,0 +1,262 @@
+//! Default AVM2 object impl
+
+use crate::avm2::names::QName;
+use crate::avm2::object::{Object, TObject};
+use crate::avm2::property::{Attribute, Property, PropertyMap};
+use crate::avm2::value::Value;
+use crate::avm2::Error;
+use enumset::EnumSet;
+use gc_arena::{Collect, GcCell, MutationContext};
+use std::fmt::Debug;
+
+/// A class instance or dynamic object, without any native representation.
+#[derive(Debug, Collect, Clone, Copy)]
+#[collect(no_drop)]
+pub struct ScriptObject<'gc>(GcCell<'gc, ScriptObjectData<'gc>>);
+
+/// The properties, slots and prototype of an object.
+///
+/// Every kind of object is built on top of one of these.
+#[derive(Debug, Collect)]
+#[collect(no_drop)]
+pub struct ScriptObjectData<'gc> {
+    /// Properties stored on this object.
+    values: PropertyMap<'gc, Property<'gc>>,
+
+    /// Slots stored on this object.
+    slots: Vec<Value<'gc>>,
+
+    /// Implicit prototype of this script object.
+    proto: Option<Object<'gc>>,
+}
+
+impl<'gc> TObject<'gc> for ScriptObject<'gc> {
+    fn base(&self) -> GcCell<'gc, ScriptObjectData<'gc>> {
+        self.0
+    }
+}
+
+impl<'gc> ScriptObject<'gc> {
+    /// Construct a bare object with no prototype.
+    ///
+    /// This is only used for the root of the prototype chain and for
+    /// activation objects.
+    pub fn bare_object(mc: MutationContext<'gc, '_>) -> Object<'gc> {
+        ScriptObject(GcCell::allocate(mc, ScriptObjectData::base_new(None))).into()
+    }
+
+    /// Construct an object with a prototype.
+    pub fn object(mc: MutationContext<'gc, '_>, proto: Object<'gc>) -> Object<'gc> {
+        ScriptObject(GcCell::allocate(
+            mc,
+            ScriptObjectData::base_new(Some(proto)),
+        ))
+        .into()
+    }
+
+    /// Construct the script object underlying some other kind of object.
+    pub fn base_object(mc: MutationContext<'gc, '_>, proto: Option<Object<'gc>>) -> Self {
+        ScriptObject(GcCell::allocate(mc, ScriptObjectData::base_new(proto)))
+    }
+}
+
+impl<'gc> ScriptObjectData<'gc> {
+    pub fn base_new(proto: Option<Object<'gc>>) -> Self {
+        ScriptObjectData {
+            values: PropertyMap::new(),
+            slots: Vec::new(),
+            proto,
+        }
+    }
+
+    /// The getter of an accessor property, if the property is one.
+    pub fn getter(&self, name: &QName<'gc>) -> Option<Object<'gc>> {
+        match self.values.get(name) {
+            Some(Property::Virtual { get, .. }) => *get,
+            _ => None,
+        }
+    }
+
+    /// The setter of an accessor property, if the property is one.
+    pub fn setter(&self, name: &QName<'gc>) -> Option<Object<'gc>> {
+        match self.values.get(name) {
+            Some(Property::Virtual { set, .. }) => *set,
+            _ => None,
+        }
+    }
+
+    /// Read the value of a non-accessor property.
+    pub fn get_property_local(&self, name: &QName<'gc>) -> Result<Value<'gc>, Error<'gc>> {
+        match self.values.get(name) {
+            Some(Property::Stored { value, .. }) => Ok(value.clone()),
+            Some(Property::Slot { slot_id, .. }) => self.get_slot(*slot_id),
+            Some(Property::Virtual { .. }) | None => Ok(Value::Undefined),
+        }
+    }
+
+    /// Write the value of a non-accessor property, creating it if it doesn't
+    /// exist.
+    ///
+    /// Read-only properties can only be written while they're initialized.
+    pub fn set_property_local(
+        &mut self,
+        name: &QName<'gc>,
+        value: Value<'gc>,
+        is_init: bool,
+    ) -> Result<(), Error<'gc>> {
+        let read_only = || {
+            Error::ReferenceError(format!(
+                "Illegal write to read-only property {}",
+                name.local_name()
+            ))
+        };
+
+        match self.values.get_mut(name) {
+            Some(Property::Stored {
+                value: stored,
+                attributes,
+            }) => {
+                if attributes.contains(Attribute::ReadOnly) && !is_init {
+                    return Err(read_only());
+                }
+
+                *stored = value;
+            }
+            Some(Property::Slot {
+                slot_id,
+                attributes,
+            }) => {
+                if attributes.contains(Attribute::ReadOnly) && !is_init {
+                    return Err(read_only());
+                }
+
+                let slot_id = *slot_id;
+                return self.set_slot(slot_id, value);
+            }
+            Some(Property::Virtual { .. }) => return Err(read_only()),
+            None => {
+                self.values
+                    .insert(*name, Property::new_stored(value, EnumSet::empty()));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Delete a property, returning `false` if it can't be deleted.
+    pub fn delete_property(&mut self, name: &QName<'gc>) -> bool {
+        let can_delete = match self.values.get(name) {
+            Some(prop) => prop.can_delete(),
+            None => false,
+        };
+
+        if can_delete {
+            self.values.remove(name);
+        }
+
+        can_delete
+    }
+
+    pub fn get_slot(&self, id: u32) -> Result<Value<'gc>, Error<'gc>> {
+        id.checked_sub(1)
+            .and_then(|index| self.slots.get(index as usize))
+            .cloned()
+            .ok_or_else(|| Error::RangeError(format!("Slot {} does not exist", id)))
+    }
+
+    pub fn set_slot(&mut self, id: u32, value: Value<'gc>) -> Result<(), Error<'gc>> {
+        match id
+            .checked_sub(1)
+            .and_then(|index| self.slots.get_mut(index as usize))
+        {
+            Some(slot) => {
+                *slot = value;
+                Ok(())
+            }
+            None => Err(Error::RangeError(format!("Slot {} does not exist", id))),
+        }
+    }
+
+    pub fn has_own_property(&self, name: &QName<'gc>) -> bool {
+        self.values.contains_key(name)
+    }
+
+    /// Find the name of a property with the given local name, in any
+    /// namespace.
+    pub fn resolve_any(&self, local_name: &str) -> Option<QName<'gc>> {
+        self.values.find_local_name(local_name)
+    }
+
+    pub fn proto(&self) -> Option<Object<'gc>> {
+        self.proto
+    }
+
+    pub fn get_next_enumerant(&self, last_index: u32) -> u32 {
+        let mut index = last_index as usize;
+        while let Some((_, prop)) = self.values.get_index(index) {
+            if prop.is_enumerable() {
+                return index as u32 + 1;
+            }
+
+            index += 1;
+        }
+
+        0
+    }
+
+    pub fn get_enumerant_name(&self, index: u32) -> Option<Value<'gc>> {
+        let (name, _) = self.values.get_index(index.checked_sub(1)? as usize)?;
+        Some(name.local_name().into())
+    }
+
+    pub fn define_value(
+        &mut self,
+        name: QName<'gc>,
+        value: Value<'gc>,
+        attributes: EnumSet<Attribute>,
+    ) {
+        self.values
+            .insert(name, Property::new_stored(value, attributes));
+    }
+
+    pub fn install_getter(&mut self, name: QName<'gc>, function: Object<'gc>) {
+        match self.values.get_mut(&name) {
+            Some(prop) => prop.install_virtual_getter(function),
+            None => {
+                let mut prop = Property::new_virtual(Attribute::DontEnum | Attribute::DontDelete);
+                prop.install_virtual_getter(function);
+                self.values.insert(name, prop);
+            }
+        }
+    }
+
+    pub fn install_setter(&mut self, name: QName<'gc>, function: Object<'gc>) {
+        match self.values.get_mut(&name) {
+            Some(prop) => prop.install_virtual_setter(function),
+            None => {
+                let mut prop = Property::new_virtual(Attribute::DontEnum | Attribute::DontDelete);
+                prop.install_virtual_setter(function);
+                self.values.insert(name, prop);
+            }
+        }
+    }
+
+    pub fn install_slot(&mut self, name: QName<'gc>, id: u32, value: Value<'gc>, is_const: bool) {
+        let id = if id == 0 {
+            self.slots.len() as u32 + 1
+        } else {
+            id
+        };
+
+        if self.slots.len() < id as usize {
+            self.slots.resize(id as usize, Value::Undefined);
+        }
+        self.slots[id as usize - 1] = value;
+
+        let mut attributes = Attribute::DontEnum | Attribute::DontDelete;
+        if is_const {
+            attributes |= Attribute::ReadOnly;
+        }
+        self.values.insert(name, Property::new_slot(id, attributes));
+    }
+}
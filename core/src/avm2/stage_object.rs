@@ -0,0 +1,124 @@
+//! AVM2 object type to represent objects on the stage.
+
+use crate::avm2::object::{Object, TObject};
+use crate::avm2::script_object::{ScriptObject, ScriptObjectData};
+use crate::avm2::value::Value;
+use crate::context::UpdateContext;
+use crate::display_object::{DisplayObject, TDisplayObject};
+use gc_arena::{Collect, GcCell, MutationContext};
+
+/// An object that is tied to a display node.
+#[derive(Collect, Debug, Clone, Copy)]
+#[collect(no_drop)]
+pub struct StageObject<'gc>(GcCell<'gc, StageObjectData<'gc>>);
+
+#[derive(Collect, Debug, Clone)]
+#[collect(no_drop)]
+pub struct StageObjectData<'gc> {
+    /// The base of this object.
+    base: ScriptObject<'gc>,
+
+    /// The associated display object, if one has been bound yet.
+    display_object: Option<DisplayObject<'gc>>,
+}
+
+impl<'gc> StageObject<'gc> {
+    /// Allocate a stage object, which isn't bound to any display node yet.
+    pub fn unbound(mc: MutationContext<'gc, '_>, proto: Object<'gc>) -> Object<'gc> {
+        StageObject(GcCell::allocate(
+            mc,
+            StageObjectData {
+                base: ScriptObject::base_object(mc, Some(proto)),
+                display_object: None,
+            },
+        ))
+        .into()
+    }
+
+    /// The object that represents a display node to scripts.
+    ///
+    /// Display objects placed by the timeline don't have an object until a
+    /// script first refers to them, at which point one is created from the
+    /// builtin class that matches the kind of display object.
+    pub fn for_display_object(
+        context: &mut UpdateContext<'_, 'gc, '_>,
+        mut display_object: DisplayObject<'gc>,
+    ) -> Object<'gc> {
+        if let Value::Object(object) = display_object.object2() {
+            return object;
+        }
+
+        let prototypes = context.avm2.prototypes();
+        let proto = match display_object {
+            DisplayObject::MovieClip(_) => prototypes.movie_clip,
+            DisplayObject::Graphic(_) => prototypes.shape,
+            _ => prototypes.display_object,
+        };
+        let object: Object<'gc> = StageObject(GcCell::allocate(
+            context.gc_context,
+            StageObjectData {
+                base: ScriptObject::base_object(context.gc_context, Some(proto)),
+                display_object: Some(display_object),
+            },
+        ))
+        .into();
+        display_object.set_object2(context.gc_context, object);
+
+        object
+    }
+
+    /// The `Graphics` object scripts use to draw on a display node.
+    pub fn graphics(
+        context: &mut UpdateContext<'_, 'gc, '_>,
+        display_object: DisplayObject<'gc>,
+    ) -> Object<'gc> {
+        StageObject(GcCell::allocate(
+            context.gc_context,
+            StageObjectData {
+                base: ScriptObject::base_object(
+                    context.gc_context,
+                    Some(context.avm2.prototypes().graphics),
+                ),
+                display_object: Some(display_object),
+            },
+        ))
+        .into()
+    }
+
+    /// The `LoaderInfo` object that describes the movie of a root display node.
+    pub fn loader_info(
+        context: &mut UpdateContext<'_, 'gc, '_>,
+        display_object: DisplayObject<'gc>,
+    ) -> Object<'gc> {
+        StageObject(GcCell::allocate(
+            context.gc_context,
+            StageObjectData {
+                base: ScriptObject::base_object(
+                    context.gc_context,
+                    Some(context.avm2.prototypes().loader_info),
+                ),
+                display_object: Some(display_object),
+            },
+        ))
+        .into()
+    }
+
+    /// Bind this object to the display node it represents.
+    pub fn set_display_object(
+        self,
+        mc: MutationContext<'gc, '_>,
+        display_object: DisplayObject<'gc>,
+    ) {
+        self.0.write(mc).display_object = Some(display_object);
+    }
+}
+
+impl<'gc> TObject<'gc> for StageObject<'gc> {
+    fn base(&self) -> GcCell<'gc, ScriptObjectData<'gc>> {
+        self.0.read().base.base()
+    }
+
+    fn as_display_object(&self) -> Option<DisplayObject<'gc>> {
+        self.0.read().display_object
+    }
+}
@@ -0,0 +1,549 @@
+//! AVM2 values
+
+use crate::avm1::value::{f64_to_wrapping_i32, f64_to_wrapping_u32};
+use crate::avm2::activation::Activation;
+use crate::avm2::names::{Multiname, Namespace, QName};
+use crate::avm2::object::{Object, TObject};
+use crate::avm2::primitive_object::PrimitiveObject;
+use crate::avm2::script::TranslationUnit;
+use crate::avm2::{AvmString, Error};
+use crate::context::UpdateContext;
+use gc_arena::{Collect, MutationContext};
+use std::f64::NAN;
+use swf::avm2::types::DefaultValue as AbcDefaultValue;
+
+/// An AVM2 value.
+///
+/// TODO: AVM2 also needs Scope, Namespace, and XML values.
+#[derive(Clone, Collect, Debug)]
+#[collect(no_drop)]
+pub enum Value<'gc> {
+    Undefined,
+    Null,
+    Bool(bool),
+    Number(f64),
+    Unsigned(u32),
+    Integer(i32),
+    String(AvmString<'gc>),
+    Namespace(Namespace<'gc>),
+    Object(Object<'gc>),
+}
+
+impl<'gc> From<AvmString<'gc>> for Value<'gc> {
+    fn from(string: AvmString<'gc>) -> Self {
+        Value::String(string)
+    }
+}
+
+impl<'gc> From<&'static str> for Value<'gc> {
+    fn from(string: &'static str) -> Self {
+        Value::String(string.into())
+    }
+}
+
+impl<'gc> From<bool> for Value<'gc> {
+    fn from(value: bool) -> Self {
+        Value::Bool(value)
+    }
+}
+
+impl<'gc, T> From<T> for Value<'gc>
+where
+    Object<'gc>: From<T>,
+{
+    fn from(value: T) -> Self {
+        Value::Object(Object::from(value))
+    }
+}
+
+impl<'gc> From<f64> for Value<'gc> {
+    fn from(value: f64) -> Self {
+        Value::Number(value)
+    }
+}
+
+impl<'gc> From<i32> for Value<'gc> {
+    fn from(value: i32) -> Self {
+        Value::Integer(value)
+    }
+}
+
+impl<'gc> From<u32> for Value<'gc> {
+    fn from(value: u32) -> Self {
+        Value::Unsigned(value)
+    }
+}
+
+impl<'gc> From<Namespace<'gc>> for Value<'gc> {
+    fn from(value: Namespace<'gc>) -> Self {
+        Value::Namespace(value)
+    }
+}
+
+/// The preferred type of a primitive that an object is converted to.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Hint {
+    String,
+    Number,
+}
+
+/// Retrieve a default value as an AVM2 `Value`.
+pub fn abc_default_value<'gc>(
+    translation_unit: TranslationUnit<'gc>,
+    default: &AbcDefaultValue,
+    mc: MutationContext<'gc, '_>,
+) -> Result<Value<'gc>, Error<'gc>> {
+    Ok(match default {
+        AbcDefaultValue::Int(i) => Value::Integer(translation_unit.pool_int(i.0)?),
+        AbcDefaultValue::Uint(u) => Value::Unsigned(translation_unit.pool_uint(u.0)?),
+        AbcDefaultValue::Double(d) => Value::Number(translation_unit.pool_double(d.0)?),
+        AbcDefaultValue::String(s) => Value::String(translation_unit.pool_string(s.0, mc)?),
+        AbcDefaultValue::True => Value::Bool(true),
+        AbcDefaultValue::False => Value::Bool(false),
+        AbcDefaultValue::Null => Value::Null,
+        AbcDefaultValue::Undefined => Value::Undefined,
+        AbcDefaultValue::Namespace(ns)
+        | AbcDefaultValue::Package(ns)
+        | AbcDefaultValue::PackageInternal(ns)
+        | AbcDefaultValue::Protected(ns)
+        | AbcDefaultValue::Explicit(ns)
+        | AbcDefaultValue::StaticProtected(ns)
+        | AbcDefaultValue::Private(ns) => Value::Namespace(Namespace::from_abc_namespace(
+            translation_unit,
+            ns.clone(),
+            mc,
+        )?),
+    })
+}
+
+/// Converts a number to a string the way AS3 does.
+///
+/// Unlike AVM1, AS3 prints the shortest representation that round-trips, so
+/// `0.1 + 0.2` prints as `0.30000000000000004`. Numbers of at least 1e21, or
+/// smaller than 1e-6, print in exponential notation.
+pub fn number_to_string(n: f64) -> String {
+    if n.is_nan() {
+        "NaN".to_string()
+    } else if n == std::f64::INFINITY {
+        "Infinity".to_string()
+    } else if n == std::f64::NEG_INFINITY {
+        "-Infinity".to_string()
+    } else if n == 0.0 {
+        // Includes -0.
+        "0".to_string()
+    } else if n.abs() >= 1e21 || n.abs() < 1e-6 {
+        // Flash always puts a sign in front of positive exponents, e.g. 1e+21.
+        let formatted = format!("{:e}", n);
+        if formatted.contains("e-") {
+            formatted
+        } else {
+            formatted.replace('e', "e+")
+        }
+    } else {
+        format!("{}", n)
+    }
+}
+
+/// Converts a string to a number with ECMAScript `ToNumber` semantics.
+///
+/// Surrounding whitespace is ignored, the empty string is zero, and anything
+/// that isn't a decimal or hexadecimal literal (or `Infinity`) is `NaN`.
+pub fn string_to_number(string: &str) -> f64 {
+    let string = string.trim();
+    if string.is_empty() {
+        return 0.0;
+    }
+
+    if string.starts_with("0x") || string.starts_with("0X") {
+        let mut n = 0.0;
+        for c in string[2..].chars() {
+            match c.to_digit(16) {
+                Some(digit) => n = n * 16.0 + f64::from(digit),
+                None => return NAN,
+            }
+        }
+        return n;
+    }
+
+    let (sign, unsigned) = if let Some(unsigned) = string.strip_prefix('-') {
+        (-1.0, unsigned)
+    } else if let Some(unsigned) = string.strip_prefix('+') {
+        (1.0, unsigned)
+    } else {
+        (1.0, string)
+    };
+    if unsigned == "Infinity" {
+        return sign * std::f64::INFINITY;
+    }
+
+    // Rust also accepts spellings like `inf` and `NaN`, which aren't numbers here.
+    if !unsigned
+        .chars()
+        .all(|c| c.is_ascii_digit() || c == '.' || c == 'e' || c == 'E' || c == '+' || c == '-')
+    {
+        return NAN;
+    }
+
+    unsigned.parse::<f64>().map(|n| sign * n).unwrap_or(NAN)
+}
+
+impl<'gc> Value<'gc> {
+    pub fn as_object(&self) -> Result<Object<'gc>, Error<'gc>> {
+        match self {
+            Value::Object(object) => Ok(*object),
+            Value::Undefined | Value::Null => Err(Error::TypeError(
+                "Cannot access a property or method of a null object reference.".to_string(),
+            )),
+            _ => Err(Error::TypeError("Value is not an object".to_string())),
+        }
+    }
+
+    /// Unwrap a runtime namespace, as required by `RTQName` multinames.
+    pub fn as_namespace(&self) -> Result<Namespace<'gc>, Error<'gc>> {
+        match self {
+            Value::Namespace(ns) => Ok(*ns),
+            _ => Err(Error::TypeError("Value is not a namespace".to_string())),
+        }
+    }
+
+    pub fn is_primitive(&self) -> bool {
+        !matches!(self, Value::Object(_))
+    }
+
+    /// The value of a number, if this is any kind of number.
+    fn number_value(&self) -> Option<f64> {
+        match self {
+            Value::Number(n) => Some(*n),
+            Value::Integer(i) => Some(f64::from(*i)),
+            Value::Unsigned(u) => Some(f64::from(*u)),
+            _ => None,
+        }
+    }
+
+    /// Coerce the value to a boolean.
+    ///
+    /// Boolean coercions happen according to the rules specified in the ES4
+    /// draft proposals, which appear to be identical to ECMA-262 Edition 3.
+    pub fn coerce_to_boolean(&self) -> bool {
+        match self {
+            Value::Undefined => false,
+            Value::Null => false,
+            Value::Bool(b) => *b,
+            Value::Number(f) => !f.is_nan() && *f != 0.0,
+            Value::Unsigned(u) => *u != 0,
+            Value::Integer(i) => *i != 0,
+            Value::String(s) => !s.is_empty(),
+            Value::Namespace(_) => true,
+            Value::Object(_) => true,
+        }
+    }
+
+    /// Coerce the value to a primitive.
+    ///
+    /// Objects are converted by calling their `valueOf` and `toString`
+    /// methods, in the order given by the hint, until one of them returns a
+    /// primitive.
+    pub fn coerce_to_primitive(
+        &self,
+        hint: Hint,
+        activation: &mut Activation<'gc>,
+        context: &mut UpdateContext<'_, 'gc, '_>,
+    ) -> Result<Value<'gc>, Error<'gc>> {
+        let object = match self {
+            Value::Object(object) => *object,
+            _ => return Ok(self.clone()),
+        };
+
+        let method_names = match hint {
+            Hint::String => ["toString", "valueOf"],
+            Hint::Number => ["valueOf", "toString"],
+        };
+        for method_name in method_names.iter() {
+            let method = object.get_property(
+                object,
+                &QName::dynamic_name(*method_name),
+                activation,
+                context,
+            )?;
+            if let Value::Object(method) = method {
+                if method.as_executable().is_some() {
+                    let result = method.call(Some(object), &[], activation, context)?;
+                    if result.is_primitive() {
+                        return Ok(result);
+                    }
+                }
+            }
+        }
+
+        Err(Error::TypeError(
+            "Cannot convert object to primitive value".to_string(),
+        ))
+    }
+
+    /// Coerce the value to a floating-point number.
+    pub fn coerce_to_number(
+        &self,
+        activation: &mut Activation<'gc>,
+        context: &mut UpdateContext<'_, 'gc, '_>,
+    ) -> Result<f64, Error<'gc>> {
+        Ok(match self {
+            Value::Undefined => NAN,
+            Value::Null => 0.0,
+            Value::Bool(true) => 1.0,
+            Value::Bool(false) => 0.0,
+            Value::Number(n) => *n,
+            Value::Unsigned(u) => f64::from(*u),
+            Value::Integer(i) => f64::from(*i),
+            Value::String(s) => string_to_number(s),
+            Value::Namespace(_) => NAN,
+            Value::Object(_) => self
+                .coerce_to_primitive(Hint::Number, activation, context)?
+                .coerce_to_number(activation, context)?,
+        })
+    }
+
+    /// Coerce the value to a 32-bit unsigned integer.
+    pub fn coerce_to_u32(
+        &self,
+        activation: &mut Activation<'gc>,
+        context: &mut UpdateContext<'_, 'gc, '_>,
+    ) -> Result<u32, Error<'gc>> {
+        match self {
+            Value::Unsigned(u) => Ok(*u),
+            Value::Integer(i) => Ok(*i as u32),
+            _ => Ok(f64_to_wrapping_u32(
+                self.coerce_to_number(activation, context)?,
+            )),
+        }
+    }
+
+    /// Coerce the value to a 32-bit signed integer.
+    pub fn coerce_to_i32(
+        &self,
+        activation: &mut Activation<'gc>,
+        context: &mut UpdateContext<'_, 'gc, '_>,
+    ) -> Result<i32, Error<'gc>> {
+        match self {
+            Value::Integer(i) => Ok(*i),
+            Value::Unsigned(u) => Ok(*u as i32),
+            _ => Ok(f64_to_wrapping_i32(
+                self.coerce_to_number(activation, context)?,
+            )),
+        }
+    }
+
+    /// Coerce the value to a string.
+    pub fn coerce_to_string(
+        &self,
+        activation: &mut Activation<'gc>,
+        context: &mut UpdateContext<'_, 'gc, '_>,
+    ) -> Result<AvmString<'gc>, Error<'gc>> {
+        Ok(match self {
+            Value::Undefined => "undefined".into(),
+            Value::Null => "null".into(),
+            Value::Bool(true) => "true".into(),
+            Value::Bool(false) => "false".into(),
+            Value::Number(n) => AvmString::new(context.gc_context, number_to_string(*n)),
+            Value::Unsigned(u) => AvmString::new(context.gc_context, u.to_string()),
+            Value::Integer(i) => AvmString::new(context.gc_context, i.to_string()),
+            Value::String(s) => *s,
+            Value::Namespace(ns) => match ns {
+                Namespace::Namespace(uri)
+                | Namespace::Package(uri)
+                | Namespace::PackageInternal(uri)
+                | Namespace::Protected(uri)
+                | Namespace::Explicit(uri)
+                | Namespace::StaticProtected(uri)
+                | Namespace::Private(uri) => *uri,
+                Namespace::Any => "*".into(),
+            },
+            Value::Object(_) => self
+                .coerce_to_primitive(Hint::String, activation, context)?
+                .coerce_to_string(activation, context)?,
+        })
+    }
+
+    /// Coerce the value to an object, boxing primitives.
+    pub fn coerce_to_object(
+        &self,
+        context: &mut UpdateContext<'_, 'gc, '_>,
+    ) -> Result<Object<'gc>, Error<'gc>> {
+        match self {
+            Value::Undefined | Value::Null => self.as_object(),
+            Value::Object(object) => Ok(*object),
+            _ => Ok(PrimitiveObject::from_primitive(self.clone(), context)),
+        }
+    }
+
+    /// Coerce the value to the type with the given name, as done when a value
+    /// is stored into a typed variable or passed as a typed parameter.
+    ///
+    /// Only the builtin primitive types convert the value; other types just
+    /// turn `undefined` into `null`.
+    pub fn coerce_to_type(
+        &self,
+        type_name: &Multiname<'gc>,
+        activation: &mut Activation<'gc>,
+        context: &mut UpdateContext<'_, 'gc, '_>,
+    ) -> Result<Value<'gc>, Error<'gc>> {
+        if type_name.local_name().is_none() {
+            return Ok(self.clone());
+        }
+
+        Ok(if type_name.is_public_name("int") {
+            self.coerce_to_i32(activation, context)?.into()
+        } else if type_name.is_public_name("uint") {
+            self.coerce_to_u32(activation, context)?.into()
+        } else if type_name.is_public_name("Number") {
+            self.coerce_to_number(activation, context)?.into()
+        } else if type_name.is_public_name("Boolean") {
+            self.coerce_to_boolean().into()
+        } else if type_name.is_public_name("String") {
+            match self {
+                Value::Undefined | Value::Null => Value::Null,
+                _ => self.coerce_to_string(activation, context)?.into(),
+            }
+        } else {
+            match self {
+                Value::Undefined => Value::Null,
+                _ => self.clone(),
+            }
+        })
+    }
+
+    /// Determine if two values are equal, as done by the `===` operator.
+    pub fn strict_eq(&self, other: &Value<'gc>) -> bool {
+        match (self, other) {
+            (Value::Undefined, Value::Undefined) => true,
+            (Value::Null, Value::Null) => true,
+            (Value::Bool(a), Value::Bool(b)) => a == b,
+            (Value::String(a), Value::String(b)) => a == b,
+            (Value::Namespace(a), Value::Namespace(b)) => a == b,
+            (Value::Object(a), Value::Object(b)) => Object::ptr_eq(*a, *b),
+            _ => match (self.number_value(), other.number_value()) {
+                (Some(a), Some(b)) => a == b,
+                _ => false,
+            },
+        }
+    }
+
+    /// Determine if two values are abstractly equal, as done by the `==`
+    /// operator.
+    ///
+    /// This follows the abstract equality comparison algorithm of ECMA-262
+    /// Edition 3.
+    pub fn abstract_eq(
+        &self,
+        other: &Value<'gc>,
+        activation: &mut Activation<'gc>,
+        context: &mut UpdateContext<'_, 'gc, '_>,
+    ) -> Result<bool, Error<'gc>> {
+        match (self, other) {
+            (Value::Undefined, Value::Undefined)
+            | (Value::Null, Value::Null)
+            | (Value::Undefined, Value::Null)
+            | (Value::Null, Value::Undefined) => return Ok(true),
+            (Value::Undefined, _) | (Value::Null, _) | (_, Value::Undefined) | (_, Value::Null) => {
+                return Ok(false)
+            }
+            (Value::Object(_), Value::Object(_))
+            | (Value::String(_), Value::String(_))
+            | (Value::Bool(_), Value::Bool(_))
+            | (Value::Namespace(_), Value::Namespace(_)) => return Ok(self.strict_eq(other)),
+            _ => {}
+        }
+
+        if let (Some(a), Some(b)) = (self.number_value(), other.number_value()) {
+            return Ok(a == b);
+        }
+
+        match (self, other) {
+            (Value::Object(_), _) => self
+                .coerce_to_primitive(Hint::Number, activation, context)?
+                .abstract_eq(other, activation, context),
+            (_, Value::Object(_)) => {
+                let other = other.coerce_to_primitive(Hint::Number, activation, context)?;
+                self.abstract_eq(&other, activation, context)
+            }
+            (Value::Namespace(_), _) | (_, Value::Namespace(_)) => Ok(false),
+            _ => {
+                let a = self.coerce_to_number(activation, context)?;
+                let b = other.coerce_to_number(activation, context)?;
+                Ok(a == b)
+            }
+        }
+    }
+
+    /// Determine if this value is less than another, as done by the `<`
+    /// operator.
+    ///
+    /// Yields `None` if either value is `NaN`, which makes every comparison
+    /// false.
+    pub fn abstract_lt(
+        &self,
+        other: &Value<'gc>,
+        activation: &mut Activation<'gc>,
+        context: &mut UpdateContext<'_, 'gc, '_>,
+    ) -> Result<Option<bool>, Error<'gc>> {
+        let prim_self = self.coerce_to_primitive(Hint::Number, activation, context)?;
+        let prim_other = other.coerce_to_primitive(Hint::Number, activation, context)?;
+
+        if let (Value::String(a), Value::String(b)) = (&prim_self, &prim_other) {
+            return Ok(Some(a.as_str() < b.as_str()));
+        }
+
+        let num_self = prim_self.coerce_to_number(activation, context)?;
+        let num_other = prim_other.coerce_to_number(activation, context)?;
+        if num_self.is_nan() || num_other.is_nan() {
+            Ok(None)
+        } else {
+            Ok(Some(num_self < num_other))
+        }
+    }
+
+    /// The result of the `typeof` operator.
+    pub fn type_of(&self) -> &'static str {
+        match self {
+            Value::Undefined => "undefined",
+            Value::Null => "object",
+            Value::Bool(_) => "boolean",
+            Value::Number(_) | Value::Integer(_) | Value::Unsigned(_) => "number",
+            Value::String(_) => "string",
+            Value::Namespace(_) => "object",
+            Value::Object(o) if o.as_executable().is_some() => "function",
+            Value::Object(_) => "object",
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{number_to_string, string_to_number};
+
+    #[test]
+    fn number_to_string_formats() {
+        assert_eq!(number_to_string(0.0), "0");
+        assert_eq!(number_to_string(-0.0), "0");
+        assert_eq!(number_to_string(42.0), "42");
+        assert_eq!(number_to_string(-1.5), "-1.5");
+        assert_eq!(number_to_string(0.1 + 0.2), "0.30000000000000004");
+        assert_eq!(number_to_string(1e20), "100000000000000000000");
+        assert_eq!(number_to_string(1e21), "1e+21");
+        assert_eq!(number_to_string(1.5e-7), "1.5e-7");
+        assert_eq!(number_to_string(0.000001), "0.000001");
+        assert_eq!(number_to_string(std::f64::NAN), "NaN");
+        assert_eq!(number_to_string(std::f64::NEG_INFINITY), "-Infinity");
+    }
+
+    #[test]
+    fn string_to_number_parses() {
+        assert_eq!(string_to_number(""), 0.0);
+        assert_eq!(string_to_number("  12  "), 12.0);
+        assert_eq!(string_to_number("-3.5"), -3.5);
+        assert_eq!(string_to_number("1e3"), 1000.0);
+        assert_eq!(string_to_number("0x1F"), 31.0);
+        assert_eq!(string_to_number("-Infinity"), std::f64::NEG_INFINITY);
+        assert!(string_to_number("inf").is_nan());
+        assert!(string_to_number("12px").is_nan());
+    }
+}
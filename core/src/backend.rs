@@ -1,5 +1,8 @@
 pub mod audio;
+pub mod font;
 pub mod input;
+pub mod log;
 pub mod navigator;
 pub mod render;
 pub mod storage;
+pub mod video;
@@ -1,3 +1,6 @@
+use crate::avm1::{Object, SoundObject};
+use crate::display_object::{DisplayObject, TDisplayObject};
+use gc_arena::{Collect, MutationContext};
 use generational_arena::{Arena, Index};
 
 pub mod decoders;
@@ -33,6 +36,25 @@ pub trait AudioBackend {
     }
     fn preload_sound_stream_end(&mut self, _clip_id: swf::CharacterId) {}
 
+    /// Registers a sound from the data of an MP3 file, such as one loaded by `Sound.loadSound`.
+    fn register_mp3(&mut self, data: &[u8]) -> Result<SoundHandle, Error> {
+        let metadata = decoders::mp3_metadata(data).ok_or("Invalid MP3 data")?;
+        // MP3 data in SWF files is prefixed by the number of samples to skip.
+        let mut sound_data = vec![0, 0];
+        sound_data.extend_from_slice(data);
+        self.register_sound(&swf::Sound {
+            id: 0,
+            format: swf::SoundFormat {
+                compression: swf::AudioCompression::Mp3,
+                sample_rate: metadata.sample_rate,
+                is_stereo: metadata.is_stereo,
+                is_16_bit: true,
+            },
+            num_samples: metadata.num_sample_frames,
+            data: sound_data,
+        })
+    }
+
     /// Starts playing a sound instance that is not tied to a MovieClip timeline.
     /// In Flash, this is known as an "Event" sound.
     fn start_sound(
@@ -60,6 +82,11 @@ pub trait AudioBackend {
     /// Good ol' stopAllSounds() :-)
     fn stop_all_sounds(&mut self);
 
+    /// Frees the data of a sound that will no longer be played, such as a
+    /// sound defined by an unloaded movie.
+    /// Any playing instances of the sound are stopped.
+    fn release_sound(&mut self, sound: SoundHandle);
+
     /// Stops all active sound instances of a particular sound.
     /// Used by SWF `StartSound` tag with `SoundEvent::Stop`.
     fn stop_sounds_with_handle(&mut self, handle: SoundHandle);
@@ -69,37 +96,88 @@ pub trait AudioBackend {
     /// which only plays a sound if that sound is not already playing.
     fn is_sound_playing_with_handle(&mut self, handle: SoundHandle) -> bool;
 
+    /// Takes the sound instances that have finished playing on their own since the last call.
+    ///
+    /// An event sound finishes after its last loop, and a stream sound after its last block.
+    /// Instances that were stopped explicitly must not be reported. The player drains this
+    /// every tick to clean up its sounds and call their `onSoundComplete` handlers.
+    fn take_completed_sounds(&mut self) -> Vec<SoundInstanceHandle>;
+
+    /// Sets the volume and panning of a playing sound instance.
+    /// No-op if the sound is not playing.
+    fn set_sound_transform(&mut self, instance: SoundInstanceHandle, transform: SoundTransform);
+
     /// Get the duration of a sound in milliseconds.
     /// Returns `None` if sound is not registered.
     fn get_sound_duration(&self, sound: SoundHandle) -> Option<u32>;
 
+    /// Get the current position of a playing sound instance in milliseconds.
+    /// This is based on how much audio has actually been played, not on the frame rate.
+    /// Returns `None` if the sound is not playing.
+    fn get_sound_position(&self, instance: SoundInstanceHandle) -> Option<u32>;
+
+    /// Get the time in milliseconds that a stream sound has been playing for.
+    /// The player uses this to keep the timeline of the stream's movie clip in step with
+    /// its audio.
+    /// Returns `None` if the stream is not playing, or if the backend can't tell how much
+    /// of it has played; the timeline then runs at the movie's frame rate.
+    fn get_stream_position(&self, _stream: AudioStreamHandle) -> Option<u32> {
+        None
+    }
+
     // TODO: Eventually remove this/move it to library.
     fn is_loading_complete(&self) -> bool {
         true
     }
     fn tick(&mut self) {}
 
+    /// Resumes audio output after a call to `pause`.
+    fn play(&mut self) {}
+
+    /// Pauses audio output, such as when the player is paused. Playing sounds
+    /// keep their positions, and continue from there when output resumes.
+    fn pause(&mut self) {}
+
     /// Inform the audio backend of the current stage frame rate.
     ///
     /// This is only necessary if your particular audio backend needs to know
     /// what the stage frame rate is. Otherwise, you are free to avoid
     /// implementing it.
     fn set_frame_rate(&mut self, _frame_rate: f64) {}
+
+    /// Inform the audio backend of how many seconds of a stream sound to buffer
+    /// before it starts playing, as set by `_soundbuftime`.
+    ///
+    /// Backends that decode streams as they play are free to ignore this.
+    fn set_stream_buffer_time(&mut self, _seconds: i32) {}
 }
 
 /// Audio backend that ignores all audio.
+///
+/// Every sound and stream finishes as soon as it starts.
 pub struct NullAudioBackend {
     sounds: Arena<()>,
-    streams: Arena<()>,
+    instances: Arena<()>,
+    completed_instances: Vec<SoundInstanceHandle>,
 }
 
 impl NullAudioBackend {
     pub fn new() -> NullAudioBackend {
         NullAudioBackend {
-            streams: Arena::new(),
             sounds: Arena::new(),
+            instances: Arena::new(),
+            completed_instances: Vec::new(),
         }
     }
+
+    /// Creates a new instance handle that has already finished playing.
+    fn finished_instance(&mut self) -> SoundInstanceHandle {
+        // The handle is freed immediately, but its generation keeps it unique.
+        let instance = self.instances.insert(());
+        self.instances.remove(instance);
+        self.completed_instances.push(instance);
+        instance
+    }
 }
 
 impl AudioBackend for NullAudioBackend {
@@ -112,7 +190,7 @@ impl AudioBackend for NullAudioBackend {
         _sound: SoundHandle,
         _sound_info: &swf::SoundInfo,
     ) -> Result<SoundInstanceHandle, Error> {
-        Ok(SoundInstanceHandle::from_raw_parts(0, 0))
+        Ok(self.finished_instance())
     }
 
     fn start_stream(
@@ -122,23 +200,31 @@ impl AudioBackend for NullAudioBackend {
         _clip_data: crate::tag_utils::SwfSlice,
         _handle: &swf::SoundStreamHead,
     ) -> Result<AudioStreamHandle, Error> {
-        Ok(self.streams.insert(()))
+        Ok(self.finished_instance())
     }
 
     fn stop_sound(&mut self, _sound: SoundInstanceHandle) {}
 
-    fn stop_stream(&mut self, stream: AudioStreamHandle) {
-        self.streams.remove(stream);
-    }
+    fn stop_stream(&mut self, _stream: AudioStreamHandle) {}
     fn stop_all_sounds(&mut self) {}
+    fn release_sound(&mut self, _sound: SoundHandle) {}
     fn stop_sounds_with_handle(&mut self, _handle: SoundHandle) {}
     fn is_sound_playing_with_handle(&mut self, _handle: SoundHandle) -> bool {
         false
     }
 
+    fn take_completed_sounds(&mut self) -> Vec<SoundInstanceHandle> {
+        std::mem::take(&mut self.completed_instances)
+    }
+    fn set_sound_transform(&mut self, _instance: SoundInstanceHandle, _transform: SoundTransform) {}
+
     fn get_sound_duration(&self, _sound: SoundHandle) -> Option<u32> {
         None
     }
+
+    fn get_sound_position(&self, _instance: SoundInstanceHandle) -> Option<u32> {
+        None
+    }
 }
 
 impl Default for NullAudioBackend {
@@ -146,3 +232,408 @@ impl Default for NullAudioBackend {
         NullAudioBackend::new()
     }
 }
+
+/// Calculates the position of a sound instance in milliseconds, given how long it has been playing.
+///
+/// This accounts for the start offset and end point of the sound and its number of loops;
+/// the position restarts from the start offset on each loop, and stays at the end of the
+/// sound once the last loop has finished.
+pub fn sound_position(settings: &swf::SoundInfo, duration: u32, elapsed: f64) -> u32 {
+    // Start and end points are in 44.1kHz samples, regardless of the sound's sample rate.
+    let start = settings
+        .in_sample
+        .map_or(0.0, |sample| f64::from(sample) / 44.1);
+    let end = settings
+        .out_sample
+        .map_or(f64::from(duration), |sample| f64::from(sample) / 44.1);
+    let loop_len = end - start;
+    if loop_len <= 0.0 {
+        return start as u32;
+    }
+
+    let total_len = loop_len * f64::from(settings.num_loops.max(1));
+    if elapsed >= total_len {
+        end as u32
+    } else {
+        (start + elapsed % loop_len) as u32
+    }
+}
+
+/// The volume and panning applied to a sound, set by the AVM1 `Sound` methods.
+///
+/// All values are percentages. The channel values describe how much of each input channel is
+/// mixed into each output channel; for example, `left_to_right` is the amount of the left input
+/// channel that is played in the right speaker.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SoundTransform {
+    pub volume: i32,
+    pub left_to_left: i32,
+    pub left_to_right: i32,
+    pub right_to_left: i32,
+    pub right_to_right: i32,
+}
+
+impl SoundTransform {
+    /// Applies another sound transform on top of this one.
+    /// Used to combine the transform of a sound's display object with those of its parents.
+    pub fn concat(&mut self, other: &SoundTransform) {
+        let (ll, lr, rl, rr) = (
+            self.left_to_left,
+            self.left_to_right,
+            self.right_to_left,
+            self.right_to_right,
+        );
+        self.volume = self.volume * other.volume / 100;
+        self.left_to_left = (other.left_to_left * ll + other.right_to_left * lr) / 100;
+        self.left_to_right = (other.left_to_right * ll + other.right_to_right * lr) / 100;
+        self.right_to_left = (other.left_to_left * rl + other.right_to_left * rr) / 100;
+        self.right_to_right = (other.left_to_right * rl + other.right_to_right * rr) / 100;
+    }
+
+    /// The stereo panning of this transform, from -100 (left) to 100 (right).
+    pub fn pan(&self) -> i32 {
+        if self.left_to_left != 100 {
+            100 - self.left_to_left
+        } else {
+            self.right_to_right - 100
+        }
+    }
+
+    /// Sets the stereo panning of this transform, from -100 (left) to 100 (right).
+    /// This resets any channel mixing set by `Sound.setTransform`.
+    pub fn set_pan(&mut self, pan: i32) {
+        let pan = pan.max(-100).min(100);
+        self.left_to_left = 100 - pan.max(0);
+        self.left_to_right = 0;
+        self.right_to_left = 0;
+        self.right_to_right = 100 + pan.min(0);
+    }
+
+    /// Returns the gains that each input channel contributes to each output channel,
+    /// as `[left_to_left, left_to_right, right_to_left, right_to_right]`, with the volume applied.
+    pub fn channel_gains(&self) -> [f32; 4] {
+        let volume = self.volume as f32 / 100.0;
+        [
+            self.left_to_left as f32 / 100.0 * volume,
+            self.left_to_right as f32 / 100.0 * volume,
+            self.right_to_left as f32 / 100.0 * volume,
+            self.right_to_right as f32 / 100.0 * volume,
+        ]
+    }
+}
+
+impl Default for SoundTransform {
+    fn default() -> Self {
+        Self {
+            volume: 100,
+            left_to_left: 100,
+            left_to_right: 0,
+            right_to_left: 0,
+            right_to_right: 100,
+        }
+    }
+}
+
+/// A sound instance started by the player, along with the objects that control it.
+struct SoundInstance<'gc> {
+    /// The sound that this instance is playing.
+    /// `None` for stream sounds.
+    sound: Option<SoundHandle>,
+
+    /// The handle of this instance in the audio backend.
+    instance: SoundInstanceHandle,
+
+    /// The display object whose sound transform (and those of its parents) applies to this sound.
+    display_object: Option<DisplayObject<'gc>>,
+
+    /// The AVM1 `Sound` object that started this sound, if any.
+    avm1_object: Option<SoundObject<'gc>>,
+
+    /// The frame of its movie clip's timeline that a stream sound started on.
+    /// `None` for event sounds.
+    stream_start_frame: Option<u16>,
+}
+
+unsafe impl<'gc> Collect for SoundInstance<'gc> {
+    fn trace(&self, cc: gc_arena::CollectionContext) {
+        self.display_object.trace(cc);
+        self.avm1_object.trace(cc);
+    }
+}
+
+/// Keeps track of the sounds playing in the audio backend, so that they can be controlled
+/// through the display objects and `Sound` objects that started them.
+pub struct AudioManager<'gc> {
+    sounds: Vec<SoundInstance<'gc>>,
+
+    /// The sound transform applied to all sounds, set by a `Sound` object with no target.
+    global_sound_transform: SoundTransform,
+
+    /// The number of seconds of stream sounds to buffer, set by `_soundbuftime`.
+    stream_buffer_time: i32,
+}
+
+unsafe impl<'gc> Collect for AudioManager<'gc> {
+    fn trace(&self, cc: gc_arena::CollectionContext) {
+        for sound in &self.sounds {
+            sound.trace(cc);
+        }
+    }
+}
+
+impl<'gc> AudioManager<'gc> {
+    pub fn new() -> Self {
+        Self {
+            sounds: Vec::new(),
+            global_sound_transform: SoundTransform::default(),
+            stream_buffer_time: 5,
+        }
+    }
+
+    /// The number of seconds of stream sounds to buffer before they play.
+    pub fn stream_buffer_time(&self) -> i32 {
+        self.stream_buffer_time
+    }
+
+    /// Sets the number of seconds of stream sounds to buffer, and passes it on to the backend.
+    pub fn set_stream_buffer_time(&mut self, audio: &mut dyn AudioBackend, seconds: i32) {
+        self.stream_buffer_time = seconds;
+        audio.set_stream_buffer_time(seconds);
+    }
+
+    /// The sound transform applied to all sounds.
+    pub fn global_sound_transform(&self) -> SoundTransform {
+        self.global_sound_transform
+    }
+
+    /// Sets the sound transform applied to all sounds and updates the playing sounds.
+    pub fn set_global_sound_transform(
+        &mut self,
+        audio: &mut dyn AudioBackend,
+        transform: SoundTransform,
+    ) {
+        self.global_sound_transform = transform;
+        self.update_sound_transforms(audio);
+    }
+
+    /// Starts an event sound owned by the given display object and/or AVM1 `Sound` object.
+    pub fn start_sound(
+        &mut self,
+        audio: &mut dyn AudioBackend,
+        sound: SoundHandle,
+        settings: &swf::SoundInfo,
+        display_object: Option<DisplayObject<'gc>>,
+        avm1_object: Option<SoundObject<'gc>>,
+    ) -> Option<SoundInstanceHandle> {
+        let instance = audio.start_sound(sound, settings).ok()?;
+        self.add_instance(
+            audio,
+            SoundInstance {
+                sound: Some(sound),
+                instance,
+                display_object,
+                avm1_object,
+                stream_start_frame: None,
+            },
+        );
+        Some(instance)
+    }
+
+    /// Starts the stream sound of the given movie clip.
+    pub fn start_stream(
+        &mut self,
+        audio: &mut dyn AudioBackend,
+        movie_clip: DisplayObject<'gc>,
+        clip_frame: u16,
+        clip_data: crate::tag_utils::SwfSlice,
+        stream_info: &swf::SoundStreamHead,
+    ) -> Option<AudioStreamHandle> {
+        let instance = audio
+            .start_stream(movie_clip.id(), clip_frame, clip_data, stream_info)
+            .ok()?;
+        self.add_instance(
+            audio,
+            SoundInstance {
+                sound: None,
+                instance,
+                display_object: Some(movie_clip),
+                avm1_object: None,
+                stream_start_frame: Some(clip_frame),
+            },
+        );
+        Some(instance)
+    }
+
+    /// How many frames the timeline playing a stream sound is ahead of the stream's audio,
+    /// or behind it if negative.
+    ///
+    /// Stream sounds set the pace of the timelines that play them, so that animations synced
+    /// to music stay in time with it. Only the first stream that is still playing is used.
+    /// Returns `None` if no stream is playing, or if the audio backend can't tell how much of
+    /// it has played.
+    pub fn stream_frame_offset(&self, audio: &dyn AudioBackend, frame_rate: f64) -> Option<f64> {
+        self.sounds.iter().find_map(|sound| {
+            let start_frame = sound.stream_start_frame?;
+            let movie_clip = sound.display_object?.as_movie_clip()?;
+            let position = audio.get_stream_position(sound.instance)?;
+            let timeline_frames = f64::from(movie_clip.current_frame()) - f64::from(start_frame);
+            let audio_frames = f64::from(position) * frame_rate / 1000.0;
+            Some(timeline_frames - audio_frames)
+        })
+    }
+
+    fn add_instance(&mut self, audio: &mut dyn AudioBackend, instance: SoundInstance<'gc>) {
+        let transform = self.transform_for(instance.display_object);
+        if transform != SoundTransform::default() {
+            audio.set_sound_transform(instance.instance, transform);
+        }
+        self.sounds.push(instance);
+    }
+
+    /// Stops a single sound instance.
+    pub fn stop_sound(&mut self, audio: &mut dyn AudioBackend, instance: SoundInstanceHandle) {
+        self.sounds.retain(|sound| sound.instance != instance);
+        audio.stop_sound(instance);
+    }
+
+    /// Stops the stream sound of a movie clip.
+    pub fn stop_stream(&mut self, audio: &mut dyn AudioBackend, stream: AudioStreamHandle) {
+        self.sounds.retain(|sound| sound.instance != stream);
+        audio.stop_stream(stream);
+    }
+
+    /// Stops all playing instances of the given sound.
+    pub fn stop_sounds_with_handle(&mut self, audio: &mut dyn AudioBackend, handle: SoundHandle) {
+        self.sounds.retain(|sound| sound.sound != Some(handle));
+        audio.stop_sounds_with_handle(handle);
+    }
+
+    /// Stops all sounds started by the given AVM1 `Sound` object.
+    pub fn stop_sounds_with_avm1_object(
+        &mut self,
+        audio: &mut dyn AudioBackend,
+        avm1_object: SoundObject<'gc>,
+    ) {
+        self.sounds.retain(|sound| match sound.avm1_object {
+            Some(object) if Object::ptr_eq(object.into(), avm1_object.into()) => {
+                audio.stop_sound(sound.instance);
+                false
+            }
+            _ => true,
+        });
+    }
+
+    /// Stops all sounds owned by the given display object or its descendants,
+    /// such as when the movie that they belong to is unloaded.
+    pub fn stop_sounds_with_display_object(
+        &mut self,
+        audio: &mut dyn AudioBackend,
+        display_object: DisplayObject<'gc>,
+    ) {
+        self.sounds.retain(|sound| {
+            let mut node = sound.display_object;
+            while let Some(owner) = node {
+                if DisplayObject::ptr_eq(owner, display_object) {
+                    if sound.sound.is_some() {
+                        audio.stop_sound(sound.instance);
+                    } else {
+                        audio.stop_stream(sound.instance);
+                    }
+                    return false;
+                }
+                node = owner.parent();
+            }
+            true
+        });
+    }
+
+    /// Stops all sounds, like the `stopAllSounds` action.
+    pub fn stop_all_sounds(&mut self, audio: &mut dyn AudioBackend) {
+        self.sounds.clear();
+        audio.stop_all_sounds();
+    }
+
+    /// Reapplies the sound transforms of all playing sounds.
+    /// Should be called whenever the sound transform of a display object changes.
+    pub fn update_sound_transforms(&self, audio: &mut dyn AudioBackend) {
+        for sound in &self.sounds {
+            audio.set_sound_transform(sound.instance, self.transform_for(sound.display_object));
+        }
+    }
+
+    /// Removes the sounds that the audio backend reports as completed.
+    ///
+    /// Returns the `Sound` objects whose sounds completed naturally, along with the display
+    /// object that owns them, so that their `onSoundComplete` handlers can be called.
+    /// Sounds that were stopped explicitly are no longer tracked, so they are never included,
+    /// even if the backend reports them.
+    ///
+    /// The completed instance is released from its `Sound` object, which then reports its full
+    /// duration as its position.
+    pub fn update_sounds(
+        &mut self,
+        audio: &mut dyn AudioBackend,
+        gc_context: MutationContext<'gc, '_>,
+    ) -> Vec<(SoundObject<'gc>, Option<DisplayObject<'gc>>)> {
+        let mut completed = vec![];
+        for instance in audio.take_completed_sounds() {
+            let index = match self
+                .sounds
+                .iter()
+                .position(|sound| sound.instance == instance)
+            {
+                Some(index) => index,
+                None => continue,
+            };
+            let sound = self.sounds.remove(index);
+            if let Some(avm1_object) = sound.avm1_object {
+                // The `Sound` may have started another instance since this one.
+                if avm1_object.sound_instance() == Some(sound.instance) {
+                    avm1_object.set_sound_instance(gc_context, None);
+                    avm1_object.set_position(gc_context, avm1_object.duration());
+                }
+                completed.push((avm1_object, sound.display_object));
+            }
+        }
+        completed
+    }
+
+    /// The combined sound transform of a display object, all of its parents,
+    /// and the global sound transform.
+    fn transform_for(&self, display_object: Option<DisplayObject<'gc>>) -> SoundTransform {
+        let mut transform = SoundTransform::default();
+        let mut node = display_object;
+        while let Some(display_object) = node {
+            transform.concat(&display_object.sound_transform());
+            node = display_object.parent();
+        }
+        transform.concat(&self.global_sound_transform);
+        transform
+    }
+}
+
+impl<'gc> Default for AudioManager<'gc> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sound_position_with_offset_and_loops() {
+        let settings = swf::SoundInfo {
+            event: swf::SoundEvent::Start,
+            in_sample: Some(44100),
+            out_sample: None,
+            num_loops: 2,
+            envelope: None,
+        };
+        assert_eq!(sound_position(&settings, 3000, 0.0), 1000);
+        assert_eq!(sound_position(&settings, 3000, 1500.0), 2500);
+        assert_eq!(sound_position(&settings, 3000, 2500.0), 1500);
+        assert_eq!(sound_position(&settings, 3000, 5000.0), 3000);
+    }
+}
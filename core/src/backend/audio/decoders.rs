@@ -5,7 +5,7 @@ mod mp3;
 mod pcm;
 
 pub use adpcm::AdpcmDecoder;
-pub use mp3::Mp3Decoder;
+pub use mp3::{id3_tags, mp3_metadata, Mp3Decoder, Mp3Metadata};
 pub use pcm::PcmDecoder;
 
 use crate::tag_utils::SwfSlice;
@@ -56,6 +56,15 @@ pub fn make_decoder<'a, R: 'a + Send + Read>(
             format.sample_rate.into(),
             data,
         )),
+        AudioCompression::Nellymoser
+        | AudioCompression::Nellymoser8Khz
+        | AudioCompression::Nellymoser16Khz => {
+            // TODO: Decode Nellymoser, and test it against clips encoded by
+            // Flash. Until then, these sounds fail to play.
+            let msg = "make_decoder: Nellymoser audio is not supported";
+            log::error!("{}", msg);
+            return Err(msg.into());
+        }
         _ => {
             let msg = format!(
                 "make_decoder: Unhandled audio compression {:?}",
@@ -2,139 +2,128 @@ use super::{Decoder, SeekableDecoder};
 use bitstream_io::{BigEndian, BitReader};
 use std::io::{Cursor, Read};
 
+/// The number of sample frames in each ADPCM packet: the initial sample, followed by 4095 codes.
+const SAMPLES_PER_PACKET: u16 = 4096;
+
+const INDEX_TABLE: [&[i16]; 4] = [
+    &[-1, 2],
+    &[-1, -1, 2, 4],
+    &[-1, -1, -1, -1, 2, 4, 6, 8],
+    &[-1, -1, -1, -1, -1, -1, -1, -1, 1, 2, 4, 6, 8, 10, 13, 16],
+];
+
+const STEP_TABLE: [i32; 89] = [
+    7, 8, 9, 10, 11, 12, 13, 14, 16, 17, 19, 21, 23, 25, 28, 31, 34, 37, 41, 45, 50, 55, 60, 66,
+    73, 80, 88, 97, 107, 118, 130, 143, 157, 173, 190, 209, 230, 253, 279, 307, 337, 371, 408, 449,
+    494, 544, 598, 658, 724, 796, 876, 963, 1060, 1166, 1282, 1411, 1552, 1707, 1878, 2066, 2272,
+    2499, 2749, 3024, 3327, 3660, 4026, 4428, 4871, 5358, 5894, 6484, 7132, 7845, 8630, 9493,
+    10442, 11487, 12635, 13899, 15289, 16818, 18500, 20350, 22385, 24623, 27086, 29794, 32767,
+];
+
+/// Decoder for the ADPCM compression used by SWF sounds.
+///
+/// The data starts with the size of the codes, from 2 to 5 bits, and is followed by packets
+/// of 4096 sample frames. Each packet starts with the initial sample and step index of each
+/// channel, followed by one code per channel for each of the remaining sample frames.
 pub struct AdpcmDecoder<R: Read> {
     inner: BitReader<R, BigEndian>,
     sample_rate: u16,
     is_stereo: bool,
-    bits_per_sample: usize,
+    bits_per_sample: u32,
     sample_num: u16,
-    left_sample: i32,
-    left_step_index: i16,
-    left_step: i32,
-    right_sample: i32,
-    right_step_index: i16,
-    right_step: i32,
+    left: AdpcmChannel,
+    right: AdpcmChannel,
 }
 
 impl<R: Read> AdpcmDecoder<R> {
-    const INDEX_TABLE: [&'static [i16]; 4] = [
-        &[-1, 2],
-        &[-1, -1, 2, 4],
-        &[-1, -1, -1, -1, 2, 4, 6, 8],
-        &[-1, -1, -1, -1, -1, -1, -1, -1, 1, 2, 4, 6, 8, 10, 13, 16],
-    ];
-
-    const STEP_TABLE: [i32; 89] = [
-        7, 8, 9, 10, 11, 12, 13, 14, 16, 17, 19, 21, 23, 25, 28, 31, 34, 37, 41, 45, 50, 55, 60,
-        66, 73, 80, 88, 97, 107, 118, 130, 143, 157, 173, 190, 209, 230, 253, 279, 307, 337, 371,
-        408, 449, 494, 544, 598, 658, 724, 796, 876, 963, 1060, 1166, 1282, 1411, 1552, 1707, 1878,
-        2066, 2272, 2499, 2749, 3024, 3327, 3660, 4026, 4428, 4871, 5358, 5894, 6484, 7132, 7845,
-        8630, 9493, 10442, 11487, 12635, 13899, 15289, 16818, 18500, 20350, 22385, 24623, 27086,
-        29794, 32767,
-    ];
-
     pub fn new(inner: R, is_stereo: bool, sample_rate: u16) -> Self {
         let mut reader = BitReader::new(inner);
-        let bits_per_sample = reader.read::<u8>(2).unwrap_or_else(|e| {
+        let bits_per_sample = reader.read::<u32>(2).unwrap_or_else(|e| {
             log::warn!("Invalid ADPCM stream: {}", e);
             0
-        }) as usize
-            + 2;
-
-        let left_sample = 0;
-        let left_step_index = 0;
-        let left_step = 0;
-        let right_sample = 0;
-        let right_step_index = 0;
-        let right_step = 0;
+        }) + 2;
+
         Self {
             inner: reader,
             sample_rate,
             is_stereo,
             bits_per_sample,
             sample_num: 0,
-            left_sample,
-            left_step,
-            left_step_index,
-            right_sample,
-            right_step,
-            right_step_index,
+            left: Default::default(),
+            right: Default::default(),
         }
     }
 
     pub fn next_sample(&mut self) -> Result<(), std::io::Error> {
         if self.sample_num == 0 {
             // The initial sample values are NOT byte-aligned.
-            self.left_sample = self.inner.read_signed(16)?;
-            self.left_step_index = self.inner.read::<u16>(6)? as i16;
-            self.left_step = Self::STEP_TABLE[self.left_step_index as usize];
+            self.left.read_header(&mut self.inner)?;
             if self.is_stereo {
-                self.right_sample = self.inner.read_signed(16)?;
-                self.right_step_index = self.inner.read::<u16>(6)? as i16;
-                self.right_step = Self::STEP_TABLE[self.right_step_index as usize];
+                self.right.read_header(&mut self.inner)?;
             }
-        }
-
-        self.sample_num = (self.sample_num + 1) % 4095;
-
-        let data: i32 = self.inner.read::<u32>(self.bits_per_sample as u32)? as i32;
-        self.left_step = Self::STEP_TABLE[self.left_step_index as usize];
-
-        // (data + 0.5) * step / 2^(bits_per_sample - 2)
-        // Data is sign-magnitude, NOT two's complement.
-        // TODO(Herschel): Other implementations use some bit-tricks for this.
-        let sign_mask = 1 << (self.bits_per_sample - 1);
-        let magnitude = data & !sign_mask;
-        let delta = (2 * magnitude + 1) * self.left_step / sign_mask;
-
-        if (data & sign_mask) != 0 {
-            self.left_sample -= delta;
         } else {
-            self.left_sample += delta;
-        }
-        if self.left_sample < -32767 {
-            self.left_sample = -32767;
-        } else if self.left_sample > 32767 {
-            self.left_sample = 32767;
-        }
-
-        let i = magnitude as usize;
-        self.left_step_index += Self::INDEX_TABLE[self.bits_per_sample - 2][i];
-        if self.left_step_index < 0 {
-            self.left_step_index = 0;
-        } else if self.left_step_index >= Self::STEP_TABLE.len() as i16 {
-            self.left_step_index = Self::STEP_TABLE.len() as i16 - 1;
+            // Read both codes before decoding, so that a truncated frame leaves no trace.
+            let left_code = self.inner.read::<u32>(self.bits_per_sample)?;
+            if self.is_stereo {
+                let right_code = self.inner.read::<u32>(self.bits_per_sample)?;
+                self.right.decode(right_code, self.bits_per_sample);
+            }
+            self.left.decode(left_code, self.bits_per_sample);
         }
 
-        if self.is_stereo {
-            let data = self.inner.read::<u32>(self.bits_per_sample as u32)? as i32;
-            self.right_step = Self::STEP_TABLE[self.right_step_index as usize];
+        self.sample_num = (self.sample_num + 1) % SAMPLES_PER_PACKET;
+        Ok(())
+    }
+}
 
-            let sign_mask = 1 << (self.bits_per_sample - 1);
-            let magnitude = data & !sign_mask;
-            let delta = (2 * magnitude + 1) * self.right_step / sign_mask;
+/// The decoding state of one channel of an ADPCM sound.
+#[derive(Default)]
+struct AdpcmChannel {
+    sample: i16,
+    step_index: usize,
+}
 
-            if (data & sign_mask) != 0 {
-                self.right_sample -= delta;
-            } else {
-                self.right_sample += delta;
-            }
-            if self.right_sample < -32767 {
-                self.right_sample = -32767;
-            } else if self.right_sample > 32767 {
-                self.right_sample = 32767;
-            }
+impl AdpcmChannel {
+    /// Reads the initial sample and step index at the start of a packet.
+    fn read_header<R: Read>(
+        &mut self,
+        reader: &mut BitReader<R, BigEndian>,
+    ) -> Result<(), std::io::Error> {
+        self.sample = reader.read_signed(16)?;
+        self.step_index = reader.read::<u8>(6)?.into();
+        Ok(())
+    }
 
-            let i = magnitude as usize;
-            self.right_step_index += Self::INDEX_TABLE[self.bits_per_sample - 2][i];
-            if self.right_step_index < 0 {
-                self.right_step_index = 0;
-            } else if self.right_step_index >= Self::STEP_TABLE.len() as i16 {
-                self.right_step_index = Self::STEP_TABLE.len() as i16 - 1;
+    /// Applies a code to the current sample.
+    fn decode(&mut self, code: u32, bits_per_sample: u32) {
+        // The code is sign-magnitude, NOT two's complement.
+        // Each bit of the magnitude adds a halving fraction of the step, which
+        // gives (magnitude + 0.5) * step / 2^(bits_per_sample - 2) with the same
+        // rounding as Flash.
+        let sign_mask = 1 << (bits_per_sample - 1);
+        let magnitude = code & !sign_mask;
+        let mut step = STEP_TABLE[self.step_index];
+        let mut delta = 0;
+        let mut bit = sign_mask >> 1;
+        while bit != 0 {
+            if magnitude & bit != 0 {
+                delta += step;
             }
+            step >>= 1;
+            bit >>= 1;
         }
+        delta += step;
 
-        Ok(())
+        let sample = if code & sign_mask != 0 {
+            i32::from(self.sample) - delta
+        } else {
+            i32::from(self.sample) + delta
+        };
+        self.sample = sample.clamp(-32768, 32767) as i16;
+
+        let step_index =
+            self.step_index as i16 + INDEX_TABLE[bits_per_sample as usize - 2][magnitude as usize];
+        self.step_index = step_index.clamp(0, STEP_TABLE.len() as i16 - 1) as usize;
     }
 }
 
@@ -143,9 +132,9 @@ impl<R: Read> Iterator for AdpcmDecoder<R> {
     fn next(&mut self) -> Option<Self::Item> {
         self.next_sample().ok()?;
         if self.is_stereo {
-            Some([self.left_sample as i16, self.right_sample as i16])
+            Some([self.left.sample, self.right.sample])
         } else {
-            Some([self.left_sample as i16, self.left_sample as i16])
+            Some([self.left.sample, self.left.sample])
         }
     }
 }
@@ -178,3 +167,75 @@ impl<R: AsRef<[u8]> + Default> SeekableDecoder for AdpcmDecoder<Cursor<R>> {
         *self = AdpcmDecoder::new(cursor, self.is_stereo, self.sample_rate());
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend::audio::decoders::make_decoder;
+    use swf::{Sound, Tag};
+
+    /// A two-tone chord encoded with each code size, in mono and stereo at each sample rate.
+    /// The expected samples of each clip were decoded by a port of FFmpeg's `adpcm_swf` decoder.
+    const CLIPS: &[u8] = include_bytes!("../../../../tests/audio/adpcm.swf");
+    const EXPECTED: [&[u8]; 4] = [
+        include_bytes!("../../../../tests/audio/adpcm_2bit.pcm"),
+        include_bytes!("../../../../tests/audio/adpcm_3bit.pcm"),
+        include_bytes!("../../../../tests/audio/adpcm_4bit.pcm"),
+        include_bytes!("../../../../tests/audio/adpcm_5bit.pcm"),
+    ];
+
+    fn sounds() -> Vec<Sound> {
+        let swf = swf::read_swf(CLIPS).unwrap();
+        swf.tags
+            .into_iter()
+            .filter_map(|tag| match tag {
+                Tag::DefineSound(sound) => Some(*sound),
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn decode_reference_clips() {
+        let sounds = sounds();
+        assert_eq!(sounds.len(), EXPECTED.len());
+        for (sound, expected) in sounds.iter().zip(&EXPECTED) {
+            let decoder = make_decoder(&sound.format, &sound.data[..]).unwrap();
+            let is_stereo = sound.format.is_stereo;
+            let samples: Vec<i16> = decoder
+                .flat_map(|[left, right]| {
+                    if is_stereo {
+                        vec![left, right]
+                    } else {
+                        vec![left]
+                    }
+                })
+                .collect();
+            let expected: Vec<i16> = expected
+                .chunks_exact(2)
+                .map(|bytes| i16::from_le_bytes([bytes[0], bytes[1]]))
+                .collect();
+            // Trailing padding bits decode as extra samples, as in Flash.
+            assert!(samples.len() >= sound.num_samples as usize * if is_stereo { 2 } else { 1 });
+            assert!(
+                samples == expected,
+                "sound {} differs from the reference decoding",
+                sound.id
+            );
+        }
+    }
+
+    #[test]
+    fn reset_restarts_decoding() {
+        let sound = &sounds()[1];
+        let mut decoder = AdpcmDecoder::new(
+            Cursor::new(sound.data.clone()),
+            sound.format.is_stereo,
+            sound.format.sample_rate,
+        );
+        let first: Vec<_> = decoder.by_ref().take(100).collect();
+        decoder.reset();
+        let second: Vec<_> = decoder.take(100).collect();
+        assert_eq!(first, second);
+    }
+}
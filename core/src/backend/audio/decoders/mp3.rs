@@ -170,3 +170,308 @@ impl<R: AsRef<[u8]> + Default> SeekableDecoder for Mp3Decoder<Cursor<R>> {
         *self = Mp3Decoder::new(self.num_channels, self.sample_rate, cursor);
     }
 }
+
+/// The format of an MP3 file, read from its frame headers.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Mp3Metadata {
+    pub sample_rate: u16,
+    pub is_stereo: bool,
+
+    /// The number of samples per channel in the file.
+    pub num_sample_frames: u32,
+}
+
+/// Scans the frame headers of an MP3 file to determine its format and length.
+/// Returns `None` if the data contains no MPEG layer III frames.
+pub fn mp3_metadata(data: &[u8]) -> Option<Mp3Metadata> {
+    let mut pos = id3v2_len(data);
+    let mut metadata: Option<Mp3Metadata> = None;
+    while pos + 4 <= data.len() {
+        let header = u32::from_be_bytes([data[pos], data[pos + 1], data[pos + 2], data[pos + 3]]);
+        if let Some(frame) = Mp3FrameHeader::parse(header) {
+            // A frame cut off by the end of the data is not counted.
+            if pos + frame.len > data.len() {
+                break;
+            }
+            let metadata = metadata.get_or_insert(Mp3Metadata {
+                sample_rate: frame.sample_rate,
+                is_stereo: frame.is_stereo,
+                num_sample_frames: 0,
+            });
+            metadata.num_sample_frames += frame.num_sample_frames;
+            pos += frame.len;
+        } else {
+            // Skip junk between frames.
+            pos += 1;
+        }
+    }
+    metadata
+}
+
+/// The fields of an MPEG audio frame header that we care about.
+struct Mp3FrameHeader {
+    sample_rate: u16,
+    is_stereo: bool,
+    num_sample_frames: u32,
+
+    /// The length of the frame in bytes, including the header.
+    len: usize,
+}
+
+impl Mp3FrameHeader {
+    fn parse(header: u32) -> Option<Self> {
+        const BITRATES_V1: [u32; 15] = [
+            0, 32, 40, 48, 56, 64, 80, 96, 112, 128, 160, 192, 224, 256, 320,
+        ];
+        const BITRATES_V2: [u32; 15] =
+            [0, 8, 16, 24, 32, 40, 48, 56, 64, 80, 96, 112, 128, 144, 160];
+        const SAMPLE_RATES: [[u16; 3]; 3] = [
+            [11025, 12000, 8000],  // MPEG 2.5
+            [22050, 24000, 16000], // MPEG 2
+            [44100, 48000, 32000], // MPEG 1
+        ];
+
+        let sync = header >> 21;
+        let version = (header >> 19) & 0b11;
+        let layer = (header >> 17) & 0b11;
+        let bitrate_index = ((header >> 12) & 0b1111) as usize;
+        let sample_rate_index = ((header >> 10) & 0b11) as usize;
+        let padding = (header >> 9) & 1;
+        let channel_mode = (header >> 6) & 0b11;
+
+        // Only layer III frames with a known bitrate are supported.
+        if sync != 0x7ff
+            || version == 0b01
+            || layer != 0b01
+            || bitrate_index == 0
+            || bitrate_index == 15
+            || sample_rate_index == 3
+        {
+            return None;
+        }
+
+        let is_mpeg1 = version == 0b11;
+        let version_index = if is_mpeg1 { 2 } else { version as usize };
+        let sample_rate = SAMPLE_RATES[version_index][sample_rate_index];
+        let (bitrate, num_sample_frames) = if is_mpeg1 {
+            (BITRATES_V1[bitrate_index] * 1000, 1152)
+        } else {
+            (BITRATES_V2[bitrate_index] * 1000, 576)
+        };
+        let len = (num_sample_frames / 8 * bitrate / u32::from(sample_rate) + padding) as usize;
+
+        Some(Self {
+            sample_rate,
+            is_stereo: channel_mode != 0b11,
+            num_sample_frames,
+            len,
+        })
+    }
+}
+
+/// Returns the length of the ID3v2 tag at the start of the data, or 0 if there is none.
+fn id3v2_len(data: &[u8]) -> usize {
+    if data.len() < 10 || &data[0..3] != b"ID3" {
+        return 0;
+    }
+    let has_footer = data[5] & 0x10 != 0;
+    10 + syncsafe_u32(&data[6..10]) as usize + if has_footer { 10 } else { 0 }
+}
+
+/// Reads a 28-bit "syncsafe" integer, which stores 7 bits in each byte.
+fn syncsafe_u32(bytes: &[u8]) -> u32 {
+    bytes
+        .iter()
+        .fold(0, |n, &byte| (n << 7) | u32::from(byte & 0x7f))
+}
+
+/// Reads the text tags of an MP3 file.
+///
+/// Tags are read from the ID3v2 header if there is one, or from the ID3v1 footer otherwise.
+/// Returns pairs of ID3v2 frame IDs (such as `TIT2` for the title) and values.
+pub fn id3_tags(data: &[u8]) -> Vec<(String, String)> {
+    let tags = id3v2_tags(data);
+    if tags.is_empty() {
+        id3v1_tags(data)
+    } else {
+        tags
+    }
+}
+
+fn id3v2_tags(data: &[u8]) -> Vec<(String, String)> {
+    let mut tags = vec![];
+    let tag_len = id3v2_len(data);
+    // ID3v2.2 uses a different frame layout, and is not supported.
+    if tag_len == 0 || data[3] < 3 {
+        return tags;
+    }
+    let version = data[3];
+    let end = tag_len.min(data.len());
+    let mut pos = 10;
+
+    // Skip the extended header.
+    if data[5] & 0x40 != 0 && pos + 4 <= end {
+        let len = if version >= 4 {
+            syncsafe_u32(&data[pos..pos + 4]) as usize
+        } else {
+            u32::from_be_bytes([data[pos], data[pos + 1], data[pos + 2], data[pos + 3]]) as usize
+                + 4
+        };
+        pos += len;
+    }
+
+    while pos + 10 <= end {
+        let id = &data[pos..pos + 4];
+        // The remainder of the tag is padding.
+        if id[0] == 0 {
+            break;
+        }
+        let len = if version >= 4 {
+            syncsafe_u32(&data[pos + 4..pos + 8]) as usize
+        } else {
+            u32::from_be_bytes([data[pos + 4], data[pos + 5], data[pos + 6], data[pos + 7]])
+                as usize
+        };
+        let body_start = pos + 10;
+        let body_end = (body_start + len).min(end);
+        pos = body_start + len;
+
+        let id = String::from_utf8_lossy(id).into_owned();
+        let body = &data[body_start..body_end];
+        let (encoding, text) = match body.split_first() {
+            Some((&encoding, text)) => (encoding, text),
+            None => continue,
+        };
+        let value = if id == "COMM" {
+            // Comments have a language code and a description before the text.
+            if text.len() < 3 {
+                continue;
+            }
+            let mut parts = split_id3_text(encoding, &text[3..]);
+            if parts.len() < 2 {
+                continue;
+            }
+            parts.swap_remove(1)
+        } else if id.starts_with('T') && id != "TXXX" {
+            decode_id3_text(encoding, text)
+        } else {
+            continue;
+        };
+        tags.push((id, value));
+    }
+    tags
+}
+
+fn id3v1_tags(data: &[u8]) -> Vec<(String, String)> {
+    let mut tags = vec![];
+    if data.len() < 128 {
+        return tags;
+    }
+    let tag = &data[data.len() - 128..];
+    if &tag[0..3] != b"TAG" {
+        return tags;
+    }
+
+    let text = |bytes: &[u8]| decode_id3_text(0, bytes).trim_end().to_string();
+    for &(id, start, end) in &[
+        ("TIT2", 3, 33),
+        ("TPE1", 33, 63),
+        ("TALB", 63, 93),
+        ("TYER", 93, 97),
+        ("COMM", 97, 127),
+    ] {
+        let value = text(&tag[start..end]);
+        if !value.is_empty() {
+            tags.push((id.to_string(), value));
+        }
+    }
+    // ID3v1.1 stores the track number in the last byte of the comment.
+    if tag[125] == 0 && tag[126] != 0 {
+        tags.push(("TRCK".to_string(), tag[126].to_string()));
+    }
+    if tag[127] != 0xff {
+        tags.push(("TCON".to_string(), tag[127].to_string()));
+    }
+    tags
+}
+
+/// Splits ID3v2 text into its null-terminated parts.
+fn split_id3_text(encoding: u8, text: &[u8]) -> Vec<String> {
+    if encoding == 1 || encoding == 2 {
+        // UTF-16 strings are terminated by two null bytes.
+        let mut parts = vec![];
+        let mut start = 0;
+        let mut i = 0;
+        while i + 1 < text.len() {
+            if text[i] == 0 && text[i + 1] == 0 {
+                parts.push(decode_id3_text(encoding, &text[start..i]));
+                start = i + 2;
+            }
+            i += 2;
+        }
+        parts.push(decode_id3_text(encoding, &text[start.min(text.len())..]));
+        parts
+    } else {
+        text.split(|&b| b == 0)
+            .map(|part| decode_id3_text(encoding, part))
+            .collect()
+    }
+}
+
+/// Decodes ID3v2 text in the given encoding.
+fn decode_id3_text(encoding: u8, text: &[u8]) -> String {
+    let text = match encoding {
+        // UTF-16 with a byte order mark, or big-endian UTF-16 without one.
+        1 | 2 => {
+            let (big_endian, text) = match text {
+                [0xff, 0xfe, rest @ ..] => (false, rest),
+                [0xfe, 0xff, rest @ ..] => (true, rest),
+                _ => (encoding == 2, text),
+            };
+            let units: Vec<u16> = text
+                .chunks_exact(2)
+                .map(|c| {
+                    if big_endian {
+                        u16::from_be_bytes([c[0], c[1]])
+                    } else {
+                        u16::from_le_bytes([c[0], c[1]])
+                    }
+                })
+                .collect();
+            String::from_utf16_lossy(&units)
+        }
+        3 => String::from_utf8_lossy(text).into_owned(),
+        // ISO-8859-1 maps directly to the first 256 code points.
+        _ => text.iter().map(|&b| char::from(b)).collect(),
+    };
+    text.trim_end_matches('\0').to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn read_mp3_metadata() {
+        // Two MPEG-1 layer III frames at 128kbps, 44.1kHz, joint stereo.
+        let mut data =
+            b"ID3\x03\x00\x00\x00\x00\x00\x11TIT2\x00\x00\x00\x07\x00\x00\x00Title!".to_vec();
+        for _ in 0..2 {
+            data.extend_from_slice(&[0xff, 0xfb, 0x90, 0x44]);
+            data.resize(data.len() + 417 - 4, 0);
+        }
+
+        assert_eq!(
+            mp3_metadata(&data),
+            Some(Mp3Metadata {
+                sample_rate: 44100,
+                is_stereo: true,
+                num_sample_frames: 2304,
+            })
+        );
+        assert_eq!(
+            id3_tags(&data),
+            vec![("TIT2".to_string(), "Title!".to_string())]
+        );
+    }
+}
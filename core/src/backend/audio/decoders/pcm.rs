@@ -35,10 +35,9 @@ impl<R: Read> Iterator for PcmDecoder<R> {
                 let right = i16::from_le_bytes(right);
                 Some([left, right])
             } else {
-                let mut bytes = [0u8];
+                let mut bytes = [0u8; 2];
                 self.inner.read_exact(&mut bytes).ok()?;
-                let sample = (i16::from(bytes[0]) - 127) * 128;
-                Some([sample, sample])
+                Some([sample_from_u8(bytes[0]), sample_from_u8(bytes[1])])
             }
         } else if self.is_16_bit {
             let mut bytes = [0u8; 2];
@@ -46,15 +45,20 @@ impl<R: Read> Iterator for PcmDecoder<R> {
             let sample = i16::from_le_bytes(bytes);
             Some([sample, sample])
         } else {
-            let mut bytes = [0u8; 2];
+            let mut bytes = [0u8];
             self.inner.read_exact(&mut bytes).ok()?;
-            let left = (i16::from(bytes[0]) - 127) * 128;
-            let right = (i16::from(bytes[1]) - 127) * 128;
-            Some([left, right])
+            let sample = sample_from_u8(bytes[0]);
+            Some([sample, sample])
         }
     }
 }
 
+/// Converts an unsigned 8-bit sample, centered on 128, to a signed 16-bit sample.
+#[inline]
+fn sample_from_u8(sample: u8) -> i16 {
+    (i16::from(sample) - 128) * 256
+}
+
 impl<R: Read> Decoder for PcmDecoder<R> {
     #[inline]
     fn num_channels(&self) -> u8 {
@@ -79,7 +83,42 @@ impl<R: AsRef<[u8]>> SeekableDecoder for PcmDecoder<Cursor<R>> {
 
     #[inline]
     fn seek_to_sample_frame(&mut self, frame: u32) {
-        let pos = u64::from(frame) * u64::from(self.num_channels()) * 2;
+        let bytes_per_sample = if self.is_16_bit { 2 } else { 1 };
+        let pos = u64::from(frame) * u64::from(self.num_channels()) * bytes_per_sample;
         self.inner.set_position(pos);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn decode_8_bit() {
+        let data = [0x00, 0x80, 0xff, 0x40];
+        let mono: Vec<_> = PcmDecoder::new(&data[..], false, 5512, false).collect();
+        assert_eq!(
+            mono,
+            [[-32768; 2], [0; 2], [32512; 2], [-16384; 2]].to_vec()
+        );
+        let stereo: Vec<_> = PcmDecoder::new(&data[..], true, 5512, false).collect();
+        assert_eq!(stereo, [[-32768, 0], [32512, -16384]].to_vec());
+    }
+
+    #[test]
+    fn decode_16_bit() {
+        let data = [0x00, 0x80, 0xff, 0x7f, 0x01, 0x00];
+        let mono: Vec<_> = PcmDecoder::new(&data[..], false, 44100, true).collect();
+        assert_eq!(mono, [[-32768; 2], [32767; 2], [1; 2]].to_vec());
+        let stereo: Vec<_> = PcmDecoder::new(&data[..], true, 44100, true).collect();
+        assert_eq!(stereo, [[-32768, 32767]].to_vec());
+    }
+
+    #[test]
+    fn seek_8_bit() {
+        let data = [0x00, 0x80, 0xff, 0x40];
+        let mut decoder = PcmDecoder::new(Cursor::new(data), true, 11025, false);
+        decoder.seek_to_sample_frame(1);
+        assert_eq!(decoder.next(), Some([32512, -16384]));
+    }
+}
@@ -0,0 +1,50 @@
+use downcast_rs::Downcast;
+
+/// Provides device fonts, the fonts installed on the user's system.
+///
+/// Text fields that don't embed their fonts are rendered with device fonts.
+/// The returned fonts are registered with the renderer and laid out exactly
+/// like embedded fonts, so a field may freely mix the two.
+pub trait FontBackend: Downcast {
+    /// Look up a device font by name and style.
+    ///
+    /// `name` is either the name of an installed font, or one of the generic
+    /// font families `_sans`, `_serif` and `_typewriter`. The font should be
+    /// returned as a `DefineFont3` tag: glyph outlines and layout metrics are
+    /// in a 20480 unit EM square, and every glyph needs a code and advance.
+    ///
+    /// Returns `None` if no such font exists, in which case text falls back to
+    /// the player's built-in font.
+    fn load_device_font(&mut self, name: &str, is_bold: bool, is_italic: bool)
+        -> Option<swf::Font>;
+}
+impl_downcast!(FontBackend);
+
+/// Font backend that has no device fonts.
+///
+/// All device text is rendered with the built-in font, which keeps text
+/// layout identical across platforms.
+pub struct NullFontBackend {}
+
+impl NullFontBackend {
+    pub fn new() -> Self {
+        Self {}
+    }
+}
+
+impl FontBackend for NullFontBackend {
+    fn load_device_font(
+        &mut self,
+        _name: &str,
+        _is_bold: bool,
+        _is_italic: bool,
+    ) -> Option<swf::Font> {
+        None
+    }
+}
+
+impl Default for NullFontBackend {
+    fn default() -> Self {
+        NullFontBackend::new()
+    }
+}
@@ -1,11 +1,7 @@
-use crate::events::KeyCode;
+use crate::backend::render::Bitmap;
 use downcast_rs::Downcast;
 
 pub trait InputBackend: Downcast {
-    fn is_key_down(&self, key: KeyCode) -> bool;
-
-    fn get_last_key_code(&self) -> KeyCode;
-
     fn mouse_visible(&self) -> bool;
 
     fn hide_mouse(&mut self);
@@ -17,6 +13,13 @@ pub trait InputBackend: Downcast {
 
     /// Set the clipboard to the given content
     fn set_clipboard_content(&mut self, content: String);
+
+    /// Begins a print job, returning the paper that the pages will be printed on.
+    /// Returns `None` if printing is unavailable or the user cancelled.
+    fn start_print_job(&mut self) -> Option<PrintPageFormat>;
+
+    /// Prints the pages of a job begun by `start_print_job`, as RGBA images.
+    fn print(&mut self, pages: Vec<Bitmap>);
 }
 impl_downcast!(InputBackend);
 
@@ -30,14 +33,6 @@ impl NullInputBackend {
 }
 
 impl InputBackend for NullInputBackend {
-    fn is_key_down(&self, _key: KeyCode) -> bool {
-        false
-    }
-
-    fn get_last_key_code(&self) -> KeyCode {
-        KeyCode::Unknown
-    }
-
     fn mouse_visible(&self) -> bool {
         true
     }
@@ -49,6 +44,12 @@ impl InputBackend for NullInputBackend {
     fn set_mouse_cursor(&mut self, _cursor: MouseCursor) {}
 
     fn set_clipboard_content(&mut self, _content: String) {}
+
+    fn start_print_job(&mut self) -> Option<PrintPageFormat> {
+        None
+    }
+
+    fn print(&mut self, _pages: Vec<Bitmap>) {}
 }
 
 impl Default for NullInputBackend {
@@ -77,3 +78,47 @@ pub enum MouseCursor {
     /// Equivalent to AS3 `MouseCursor.HAND`.
     Grab,
 }
+
+/// The paper of a print job, with sizes in points (1/72 inch).
+/// Reported to scripts by the `PrintJob` properties of the same names.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct PrintPageFormat {
+    /// The size of the paper.
+    pub paper_width: u32,
+    pub paper_height: u32,
+
+    /// The size of the printable area of the paper.
+    pub page_width: u32,
+    pub page_height: u32,
+
+    pub orientation: PrintOrientation,
+}
+
+impl PrintPageFormat {
+    /// US Letter paper with half-inch margins, in portrait orientation.
+    pub fn letter() -> Self {
+        Self {
+            paper_width: 612,
+            paper_height: 792,
+            page_width: 540,
+            page_height: 720,
+            orientation: PrintOrientation::Portrait,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PrintOrientation {
+    Portrait,
+    Landscape,
+}
+
+impl PrintOrientation {
+    /// The name of this orientation, as returned by `PrintJob.orientation`.
+    pub fn name(self) -> &'static str {
+        match self {
+            PrintOrientation::Portrait => "portrait",
+            PrintOrientation::Landscape => "landscape",
+        }
+    }
+}
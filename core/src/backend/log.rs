@@ -0,0 +1,34 @@
+/// Receives the output of the movie, such as the messages of `trace`.
+pub trait LogBackend {
+    /// Output a message traced by the movie.
+    fn avm_trace(&self, message: &str);
+
+    /// Output a warning about a problem with the movie's scripts, such as an
+    /// uncaught error.
+    fn avm_warning(&self, message: &str) {
+        log::warn!("{}", message);
+    }
+}
+
+/// Log backend that sends the movie's output to the `avm_trace` log target.
+///
+/// Warnings are logged as usual.
+pub struct NullLogBackend {}
+
+impl NullLogBackend {
+    pub fn new() -> Self {
+        Self {}
+    }
+}
+
+impl LogBackend for NullLogBackend {
+    fn avm_trace(&self, message: &str) {
+        log::info!(target: "avm_trace", "{}", message);
+    }
+}
+
+impl Default for NullLogBackend {
+    fn default() -> Self {
+        NullLogBackend::new()
+    }
+}
@@ -1,6 +1,7 @@
 //! Browser-related platform functions
 
 use crate::loader::Error;
+use generational_arena::{Arena, Index};
 use std::collections::{HashMap, VecDeque};
 use std::fs;
 use std::future::Future;
@@ -13,7 +14,7 @@ use std::time::Duration;
 use swf::avm1::types::SendVarsMethod;
 
 /// Enumerates all possible navigation methods.
-#[derive(Copy, Clone)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
 pub enum NavigationMethod {
     /// Indicates that navigation should generate a GET request.
     GET,
@@ -41,6 +42,54 @@ impl NavigationMethod {
     }
 }
 
+/// The window or frame that a URL is opened in, as named by the `window`
+/// parameter of `getURL`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum NavigationTarget {
+    /// The window or frame containing the movie (`_self`, or no window name).
+    SelfWindow,
+
+    /// A new, unnamed window (`_blank`).
+    Blank,
+
+    /// The parent of the frame containing the movie (`_parent`).
+    Parent,
+
+    /// The top-level window containing the movie (`_top`).
+    Top,
+
+    /// The window or frame with the given name, which is opened if it doesn't
+    /// exist yet.
+    Named(String),
+}
+
+impl NavigationTarget {
+    /// Convert a window name into a target.
+    ///
+    /// The special names are case-insensitive, as in HTML.
+    pub fn from_name(name: &str) -> Self {
+        match name.to_ascii_lowercase().as_str() {
+            "" | "_self" => Self::SelfWindow,
+            "_blank" => Self::Blank,
+            "_parent" => Self::Parent,
+            "_top" => Self::Top,
+            _ => Self::Named(name.to_string()),
+        }
+    }
+
+    /// The name of this target, as used by the `target` attribute of an HTML
+    /// `<a>nchor` or `<form>`.
+    pub fn name(&self) -> &str {
+        match self {
+            Self::SelfWindow => "_self",
+            Self::Blank => "_blank",
+            Self::Parent => "_parent",
+            Self::Top => "_top",
+            Self::Named(name) => name,
+        }
+    }
+}
+
 /// Represents request options to be sent as part of a fetch.
 pub struct RequestOptions {
     /// The HTTP method to be used to make the request.
@@ -51,6 +100,9 @@ pub struct RequestOptions {
     ///
     /// The body consists of data and a mime type.
     body: Option<(Vec<u8>, String)>,
+
+    /// Additional HTTP headers to be sent with the request, as name/value pairs.
+    headers: Vec<(String, String)>,
 }
 
 impl RequestOptions {
@@ -59,6 +111,7 @@ impl RequestOptions {
         Self {
             method: NavigationMethod::GET,
             body: None,
+            headers: Vec::new(),
         }
     }
 
@@ -67,9 +120,16 @@ impl RequestOptions {
         Self {
             method: NavigationMethod::POST,
             body,
+            headers: Vec::new(),
         }
     }
 
+    /// Add HTTP headers to be sent with this request.
+    pub fn with_headers(mut self, headers: Vec<(String, String)>) -> Self {
+        self.headers = headers;
+        self
+    }
+
     /// Retrieve the navigation method for this request.
     pub fn method(&self) -> NavigationMethod {
         self.method
@@ -79,6 +139,38 @@ impl RequestOptions {
     pub fn body(&self) -> &Option<(Vec<u8>, String)> {
         &self.body
     }
+
+    /// Retrieve the additional HTTP headers of this request.
+    pub fn headers(&self) -> &[(String, String)] {
+        &self.headers
+    }
+}
+
+/// A handle to a socket connection opened with `NavigatorBackend::connect_socket`.
+pub type SocketHandle = Index;
+
+/// An event that happened on a socket connection.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SocketEvent {
+    /// The connection attempt finished, either successfully or not.
+    Connected(SocketHandle, bool),
+
+    /// Data was received on the socket.
+    Data(SocketHandle, Vec<u8>),
+
+    /// The connection was closed by the remote host or by an error.
+    Closed(SocketHandle),
+}
+
+impl SocketEvent {
+    /// The socket this event happened on.
+    pub fn socket(&self) -> SocketHandle {
+        match self {
+            SocketEvent::Connected(socket, _) => *socket,
+            SocketEvent::Data(socket, _) => *socket,
+            SocketEvent::Closed(socket) => *socket,
+        }
+    }
 }
 
 /// Type alias for pinned, boxed, and owned futures that output a falliable
@@ -93,8 +185,8 @@ pub trait NavigatorBackend {
     /// be meaningful for all environments: for example, `javascript:` URLs may
     /// not be executable in a desktop context.
     ///
-    /// The `window` parameter, if provided, should be treated identically to
-    /// the `window` parameter on an HTML `<a>nchor` tag.
+    /// The `target` is the window or frame to open the URL in. `javascript:`
+    /// URLs always target the window containing the movie.
     ///
     /// This function may be used to send variables to an eligible target. If
     /// desired, the `vars_method` will be specified with a suitable
@@ -112,17 +204,45 @@ pub trait NavigatorBackend {
     fn navigate_to_url(
         &self,
         url: String,
-        window: Option<String>,
+        target: NavigationTarget,
         vars_method: Option<(NavigationMethod, HashMap<String, String>)>,
     );
 
     /// Fetch data at a given URL and return it some time in the future.
     fn fetch(&self, url: &str, request_options: RequestOptions) -> OwnedFuture<Vec<u8>, Error>;
 
+    /// Fetch data at a given URL, and return the body of the response once
+    /// it starts to arrive, so that it can be read as it loads.
+    ///
+    /// Backends that can't read responses as they arrive can rely on the
+    /// default implementation, which waits for `fetch` and yields the whole
+    /// response at once.
+    fn fetch_stream(
+        &self,
+        url: &str,
+        request_options: RequestOptions,
+    ) -> OwnedFuture<Box<dyn ResponseBody>, Error> {
+        let fetch = self.fetch(url, request_options);
+        Box::pin(async move {
+            let data = fetch.await?;
+            Ok(Box::new(CompleteBody::new(data)) as Box<dyn ResponseBody>)
+        })
+    }
+
     /// Get the amount of time since the SWF was launched.
     /// Used by the `getTimer` ActionScript call.
     fn time_since_launch(&mut self) -> Duration;
 
+    /// Get the current time, in milliseconds since the Unix epoch.
+    /// Used by the AVM1 `Date` class.
+    fn current_time(&self) -> f64;
+
+    /// Get the offset of the local timezone from UTC at the given time, in
+    /// minutes, as returned by `Date.getTimezoneOffset`.
+    ///
+    /// The offset is positive for timezones behind UTC.
+    fn timezone_offset(&self, time: f64) -> i32;
+
     /// Arrange for a future to be run at some point in the... well, future.
     ///
     /// This function must be called to ensure a future is actually computed.
@@ -132,6 +252,121 @@ pub trait NavigatorBackend {
     /// TODO: For some reason, `wasm_bindgen_futures` wants unpinnable futures.
     /// This seems highly limiting.
     fn spawn_future(&mut self, future: OwnedFuture<(), Error>);
+
+    /// Open a raw socket connection to the given host and port, as used by
+    /// AVM1 `XMLSocket`.
+    ///
+    /// The connection is made asynchronously; its result must be reported as
+    /// a `SocketEvent::Connected` event by `poll_socket_events`. Backends
+    /// that cannot make socket connections should report a failed connection.
+    fn connect_socket(&mut self, host: &str, port: u16) -> SocketHandle;
+
+    /// Send data over an open socket connection.
+    fn send_socket(&mut self, socket: SocketHandle, data: Vec<u8>);
+
+    /// Close a socket connection.
+    ///
+    /// No further events will be reported for the socket.
+    fn close_socket(&mut self, socket: SocketHandle);
+
+    /// Take all socket events that happened since the last call.
+    ///
+    /// This is called by the player on every frame, so that socket callbacks
+    /// run on the main update thread.
+    fn poll_socket_events(&mut self) -> Vec<SocketEvent>;
+}
+
+/// The body of a response returned by `NavigatorBackend::fetch_stream`, which
+/// is read in chunks as it arrives.
+pub trait ResponseBody {
+    /// The length of the whole body, if the response said what it is.
+    fn total_length(&self) -> Option<usize>;
+
+    /// Wait for the next chunk of the body, or `None` once all of it has been read.
+    fn next_chunk(&mut self) -> OwnedFuture<Option<Vec<u8>>, Error>;
+}
+
+/// A response body that has already arrived in full, which is read as a
+/// single chunk.
+pub struct CompleteBody(Option<Vec<u8>>);
+
+impl CompleteBody {
+    pub fn new(data: Vec<u8>) -> Self {
+        Self(Some(data))
+    }
+}
+
+impl ResponseBody for CompleteBody {
+    fn total_length(&self) -> Option<usize> {
+        self.0.as_ref().map(Vec::len)
+    }
+
+    fn next_chunk(&mut self) -> OwnedFuture<Option<Vec<u8>>, Error> {
+        let chunk = self.0.take();
+        Box::pin(async move { Ok(chunk) })
+    }
+}
+
+/// A response body that arrives in chunks of a fixed size, one each time the
+/// executor polls it, as if they were coming in over a slow connection.
+struct ChunkedBody {
+    data: Vec<u8>,
+    position: usize,
+    chunk_size: usize,
+}
+
+impl ResponseBody for ChunkedBody {
+    fn total_length(&self) -> Option<usize> {
+        Some(self.data.len())
+    }
+
+    fn next_chunk(&mut self) -> OwnedFuture<Option<Vec<u8>>, Error> {
+        let end = self.data.len().min(self.position + self.chunk_size);
+        let chunk = if self.position < end {
+            Some(self.data[self.position..end].to_vec())
+        } else {
+            None
+        };
+        self.position = end;
+
+        Box::pin(Delayed::new(chunk))
+    }
+}
+
+/// A future that is pending the first time it is polled, and ready with its
+/// value the next time.
+///
+/// This lets the player run between the chunks of a response body that are
+/// available right away, such as those of a local file.
+pub struct Delayed<T> {
+    value: Option<T>,
+    has_yielded: bool,
+}
+
+impl<T> Delayed<T> {
+    pub fn new(value: T) -> Self {
+        Self {
+            value: Some(value),
+            has_yielded: false,
+        }
+    }
+}
+
+impl<T: Unpin> Future for Delayed<T> {
+    type Output = Result<T, Error>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        if self.has_yielded {
+            Poll::Ready(Ok(self
+                .value
+                .take()
+                .expect("Delayed polled after completion")))
+        } else {
+            self.has_yielded = true;
+            cx.waker().wake_by_ref();
+            Poll::Pending
+        }
+    }
 }
 
 /// A null implementation of an event loop that only supports blocking.
@@ -244,6 +479,22 @@ pub struct NullNavigatorBackend {
 
     /// The base path for all relative fetches.
     relative_base_path: PathBuf,
+
+    /// Sockets opened with `connect_socket`, none of which ever connect.
+    sockets: Arena<()>,
+
+    /// Socket events waiting to be polled.
+    socket_events: Vec<SocketEvent>,
+
+    /// The time reported as the current time, in milliseconds since the Unix epoch.
+    current_time: f64,
+
+    /// The offset of the local timezone from UTC, in minutes.
+    timezone_offset: i32,
+
+    /// The size of the chunks that `fetch_stream` reads files in, if it
+    /// doesn't read them all at once.
+    chunk_size: Option<usize>,
 }
 
 impl NullNavigatorBackend {
@@ -253,6 +504,11 @@ impl NullNavigatorBackend {
         NullNavigatorBackend {
             channel: None,
             relative_base_path: PathBuf::new(),
+            sockets: Arena::new(),
+            socket_events: Vec::new(),
+            current_time: 0.0,
+            timezone_offset: 0,
+            chunk_size: None,
         }
     }
 
@@ -268,8 +524,28 @@ impl NullNavigatorBackend {
         NullNavigatorBackend {
             channel: Some(channel),
             relative_base_path,
+            sockets: Arena::new(),
+            socket_events: Vec::new(),
+            current_time: 0.0,
+            timezone_offset: 0,
+            chunk_size: None,
         }
     }
+
+    /// Sets the time and timezone reported by this backend.
+    ///
+    /// By default, the time is the Unix epoch and the timezone is UTC.
+    pub fn set_clock(&mut self, current_time: f64, timezone_offset: i32) {
+        self.current_time = current_time;
+        self.timezone_offset = timezone_offset;
+    }
+
+    /// Makes `fetch_stream` read files in chunks of the given size, one chunk
+    /// each time the executor polls the fetch, so that tests can watch movies
+    /// load gradually.
+    pub fn set_chunk_size(&mut self, chunk_size: usize) {
+        self.chunk_size = Some(chunk_size);
+    }
 }
 
 impl Default for NullNavigatorBackend {
@@ -282,7 +558,7 @@ impl NavigatorBackend for NullNavigatorBackend {
     fn navigate_to_url(
         &self,
         _url: String,
-        _window: Option<String>,
+        _target: NavigationTarget,
         _vars_method: Option<(NavigationMethod, HashMap<String, String>)>,
     ) {
     }
@@ -294,13 +570,59 @@ impl NavigatorBackend for NullNavigatorBackend {
         Box::pin(async move { fs::read(path).map_err(Error::NetworkError) })
     }
 
+    fn fetch_stream(
+        &self,
+        url: &str,
+        opts: RequestOptions,
+    ) -> OwnedFuture<Box<dyn ResponseBody>, Error> {
+        let fetch = self.fetch(url, opts);
+        let chunk_size = self.chunk_size;
+        Box::pin(async move {
+            let data = fetch.await?;
+            Ok(match chunk_size {
+                Some(chunk_size) => Box::new(ChunkedBody {
+                    data,
+                    position: 0,
+                    chunk_size,
+                }) as Box<dyn ResponseBody>,
+                None => Box::new(CompleteBody::new(data)),
+            })
+        })
+    }
+
     fn time_since_launch(&mut self) -> Duration {
         Duration::from_millis(0)
     }
 
+    fn current_time(&self) -> f64 {
+        self.current_time
+    }
+
+    fn timezone_offset(&self, _time: f64) -> i32 {
+        self.timezone_offset
+    }
+
     fn spawn_future(&mut self, future: OwnedFuture<(), Error>) {
         if let Some(channel) = self.channel.as_ref() {
             channel.send(future).unwrap();
         }
     }
+
+    fn connect_socket(&mut self, _host: &str, _port: u16) -> SocketHandle {
+        let socket = self.sockets.insert(());
+        self.socket_events
+            .push(SocketEvent::Connected(socket, false));
+        socket
+    }
+
+    fn send_socket(&mut self, _socket: SocketHandle, _data: Vec<u8>) {}
+
+    fn close_socket(&mut self, socket: SocketHandle) {
+        self.sockets.remove(socket);
+        self.socket_events.retain(|event| event.socket() != socket);
+    }
+
+    fn poll_socket_events(&mut self) -> Vec<SocketEvent> {
+        std::mem::take(&mut self.socket_events)
+    }
 }
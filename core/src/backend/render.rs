@@ -1,6 +1,8 @@
 use crate::shape_utils::DistilledShape;
+use crate::stage::StageQuality;
 pub use crate::{transform::Transform, Color};
 use downcast_rs::Downcast;
+use std::convert::TryInto;
 use std::io::Read;
 pub use swf;
 
@@ -9,6 +11,14 @@ pub trait RenderBackend: Downcast {
     fn register_shape(&mut self, shape: DistilledShape) -> ShapeHandle;
     fn replace_shape(&mut self, shape: DistilledShape, handle: ShapeHandle);
     fn register_glyph_shape(&mut self, shape: &swf::Glyph) -> ShapeHandle;
+    /// Frees the tessellated mesh of a shape that will no longer be rendered,
+    /// such as a shape defined by an unloaded movie.
+    /// Rendering the shape afterwards draws nothing.
+    fn release_shape(&mut self, shape: ShapeHandle);
+    /// Frees the texture of a bitmap that will no longer be rendered,
+    /// such as a bitmap defined by an unloaded movie.
+    /// Rendering the bitmap afterwards draws nothing.
+    fn release_bitmap(&mut self, bitmap: BitmapHandle);
     fn register_bitmap_jpeg(
         &mut self,
         id: swf::CharacterId,
@@ -30,15 +40,68 @@ pub trait RenderBackend: Downcast {
         &mut self,
         swf_tag: &swf::DefineBitsLossless,
     ) -> Result<BitmapInfo, Error>;
+    /// Registers a bitmap from raw RGBA pixels, such as those of a `BitmapData` object.
+    fn register_bitmap_rgba(
+        &mut self,
+        id: swf::CharacterId,
+        width: u32,
+        height: u32,
+        rgba: Vec<u8>,
+    ) -> Result<BitmapInfo, Error>;
+    /// Replaces the pixels of a bitmap registered with `register_bitmap_rgba`.
+    fn update_bitmap_rgba(
+        &mut self,
+        bitmap: BitmapHandle,
+        width: u32,
+        height: u32,
+        rgba: Vec<u8>,
+    ) -> Result<(), Error>;
+
+    /// Sets the rendering quality chosen by the movie. Lower qualities
+    /// disable bitmap smoothing.
+    fn set_quality(&mut self, quality: StageQuality);
 
     fn begin_frame(&mut self, clear: Color);
-    fn render_bitmap(&mut self, bitmap: BitmapHandle, transform: &Transform);
+    /// Draws a bitmap, scaling it with bilinear filtering if `smoothing` is set.
+    fn render_bitmap(&mut self, bitmap: BitmapHandle, transform: &Transform, smoothing: bool);
     fn render_shape(&mut self, shape: ShapeHandle, transform: &Transform);
     fn end_frame(&mut self);
     fn draw_letterbox(&mut self, letterbox: Letterbox);
     fn push_mask(&mut self);
     fn activate_mask(&mut self);
     fn pop_mask(&mut self);
+
+    /// Begins rendering an object with the given bitmap filters applied.
+    /// Subsequent draws until the matching `pop_filters` make up the filtered object.
+    fn push_filters(&mut self, filters: &[swf::Filter]);
+    fn pop_filters(&mut self);
+
+    /// Begins rendering an object that will be composited with the given blend mode.
+    /// Subsequent draws until the matching `pop_blend_mode` make up the blended object.
+    fn push_blend_mode(&mut self, blend_mode: swf::BlendMode);
+    fn pop_blend_mode(&mut self);
+
+    /// Creates an offscreen image used to cache the rendering of a display object.
+    fn create_bitmap_cache(&mut self) -> BitmapCacheHandle;
+    /// Draws a previously cached image. Returns `false` if the cached image can not be
+    /// reused, in which case the object must be rendered again.
+    fn render_bitmap_cache(&mut self, cache: BitmapCacheHandle) -> bool;
+    /// Begins rendering into a cached image. Subsequent draws until the matching
+    /// `end_bitmap_cache` are stored in the cache and then drawn.
+    fn begin_bitmap_cache(&mut self, cache: BitmapCacheHandle);
+    fn end_bitmap_cache(&mut self);
+    fn release_bitmap_cache(&mut self, cache: BitmapCacheHandle);
+
+    /// Fills a rectangle with a solid color.
+    fn draw_rect(&mut self, color: Color, rect: &swf::Rectangle, transform: &Transform);
+
+    /// Begins rendering into an offscreen image of the given size, such as a printed page.
+    /// Subsequent draws until the matching `end_capture` make up the image.
+    /// Returns `false` if this backend can't capture, in which case nothing is rendered.
+    fn begin_capture(&mut self, width: u32, height: u32, clear: Color) -> bool;
+    /// Finishes an image started by `begin_capture`, returning its RGBA pixels without
+    /// premultiplied alpha.
+    fn end_capture(&mut self) -> Option<Bitmap>;
 }
 impl_downcast!(RenderBackend);
 
@@ -50,6 +113,9 @@ pub struct ShapeHandle(pub usize);
 #[derive(Copy, Clone, Debug)]
 pub struct BitmapHandle(pub usize);
 
+#[derive(Copy, Clone, Debug)]
+pub struct BitmapCacheHandle(pub usize);
+
 /// Info returned by the `register_bitmap` methods.
 #[derive(Copy, Clone, Debug)]
 pub struct BitmapInfo {
@@ -88,59 +154,100 @@ impl RenderBackend for NullRenderer {
     fn register_glyph_shape(&mut self, _shape: &swf::Glyph) -> ShapeHandle {
         ShapeHandle(0)
     }
+    fn release_shape(&mut self, _shape: ShapeHandle) {}
+    fn release_bitmap(&mut self, _bitmap: BitmapHandle) {}
+    // The JPEG tags are decoded so that bitmaps have their real size, as
+    // they do with other renderers.
     fn register_bitmap_jpeg(
         &mut self,
-        _id: swf::CharacterId,
-        _data: &[u8],
-        _jpeg_tables: Option<&[u8]>,
+        id: swf::CharacterId,
+        data: &[u8],
+        jpeg_tables: Option<&[u8]>,
     ) -> Result<BitmapInfo, Error> {
-        Ok(BitmapInfo {
-            handle: BitmapHandle(0),
-            width: 0,
-            height: 0,
-        })
+        let data = glue_tables_to_jpeg(data, jpeg_tables);
+        self.register_bitmap_jpeg_2(id, &data[..])
     }
     fn register_bitmap_jpeg_2(
         &mut self,
         _id: swf::CharacterId,
-        _data: &[u8],
+        data: &[u8],
     ) -> Result<BitmapInfo, Error> {
+        let bitmap = decode_define_bits_jpeg(data, None)?;
         Ok(BitmapInfo {
             handle: BitmapHandle(0),
-            width: 0,
-            height: 0,
+            width: bitmap.width.try_into()?,
+            height: bitmap.height.try_into()?,
         })
     }
     fn register_bitmap_jpeg_3(
         &mut self,
-        _id: swf::CharacterId,
-        _data: &[u8],
+        id: swf::CharacterId,
+        data: &[u8],
         _alpha_data: &[u8],
+    ) -> Result<BitmapInfo, Error> {
+        self.register_bitmap_jpeg_2(id, data)
+    }
+    fn register_bitmap_png(
+        &mut self,
+        swf_tag: &swf::DefineBitsLossless,
     ) -> Result<BitmapInfo, Error> {
         Ok(BitmapInfo {
             handle: BitmapHandle(0),
-            width: 0,
-            height: 0,
+            width: swf_tag.width,
+            height: swf_tag.height,
         })
     }
-    fn register_bitmap_png(
+    fn register_bitmap_rgba(
         &mut self,
-        _swf_tag: &swf::DefineBitsLossless,
+        _id: swf::CharacterId,
+        width: u32,
+        height: u32,
+        _rgba: Vec<u8>,
     ) -> Result<BitmapInfo, Error> {
         Ok(BitmapInfo {
             handle: BitmapHandle(0),
-            width: 0,
-            height: 0,
+            width: width as u16,
+            height: height as u16,
         })
     }
+    fn update_bitmap_rgba(
+        &mut self,
+        _bitmap: BitmapHandle,
+        _width: u32,
+        _height: u32,
+        _rgba: Vec<u8>,
+    ) -> Result<(), Error> {
+        Ok(())
+    }
+    fn set_quality(&mut self, _quality: StageQuality) {}
     fn begin_frame(&mut self, _clear: Color) {}
     fn end_frame(&mut self) {}
-    fn render_bitmap(&mut self, _bitmap: BitmapHandle, _transform: &Transform) {}
+    fn render_bitmap(&mut self, _bitmap: BitmapHandle, _transform: &Transform, _smoothing: bool) {}
     fn render_shape(&mut self, _shape: ShapeHandle, _transform: &Transform) {}
     fn draw_letterbox(&mut self, _letterbox: Letterbox) {}
     fn push_mask(&mut self) {}
     fn activate_mask(&mut self) {}
     fn pop_mask(&mut self) {}
+    fn push_filters(&mut self, _filters: &[swf::Filter]) {}
+    fn pop_filters(&mut self) {}
+    fn push_blend_mode(&mut self, _blend_mode: swf::BlendMode) {}
+    fn pop_blend_mode(&mut self) {}
+    fn create_bitmap_cache(&mut self) -> BitmapCacheHandle {
+        BitmapCacheHandle(0)
+    }
+    fn render_bitmap_cache(&mut self, _cache: BitmapCacheHandle) -> bool {
+        false
+    }
+    fn begin_bitmap_cache(&mut self, _cache: BitmapCacheHandle) {}
+    fn end_bitmap_cache(&mut self) {}
+    fn release_bitmap_cache(&mut self, _cache: BitmapCacheHandle) {}
+    fn draw_rect(&mut self, _color: Color, _rect: &swf::Rectangle, _transform: &Transform) {}
+    fn begin_capture(&mut self, _width: u32, _height: u32, _clear: Color) -> bool {
+        false
+    }
+    fn end_capture(&mut self) -> Option<Bitmap> {
+        None
+    }
 }
 
 /// The format of image data in a DefineBitsJpeg2/3 tag.
@@ -176,7 +283,9 @@ pub fn determine_jpeg_tag_format(data: &[u8]) -> JpegTagFormat {
         [0xff, 0xd8, ..] => JpegTagFormat::Jpeg,
         [0xff, 0xd9, 0xff, 0xd8, ..] => JpegTagFormat::Jpeg, // erroneous header in SWF
         [0x89, 0x50, 0x4e, 0x47, 0x0d, 0x0a, 0x1a, 0x0a, ..] => JpegTagFormat::Png,
-        [0x47, 0x49, 0x46, 0x38, 0x39, 0x61, ..] => JpegTagFormat::Gif,
+        [0x47, 0x49, 0x46, 0x38, 0x37, 0x61, ..] | [0x47, 0x49, 0x46, 0x38, 0x39, 0x61, ..] => {
+            JpegTagFormat::Gif
+        }
         _ => JpegTagFormat::Unknown,
     }
 }
@@ -206,24 +315,24 @@ pub fn glue_swf_jpeg_to_tables(jpeg_tables: &[u8], jpeg_data: &[u8]) -> Vec<u8>
 
 /// Glues the JPEG encoding tables from a JPEGTables SWF tag to the JPEG data
 /// in a DefineBits tag, producing complete JPEG data suitable for a decoder.
+///
+/// Both the tables and the image data are complete JPEG streams, so joining
+/// them leaves an EOI/SOI pair in between, which is stripped along with any
+/// erroneous headers.
 pub fn glue_tables_to_jpeg<'a>(
     jpeg_data: &'a [u8],
     jpeg_tables: Option<&'a [u8]>,
 ) -> std::borrow::Cow<'a, [u8]> {
-    if let Some(jpeg_tables) = jpeg_tables {
-        if jpeg_tables.len() >= 2 {
+    match jpeg_tables {
+        Some(jpeg_tables) if !jpeg_tables.is_empty() => {
             let mut full_jpeg = Vec::with_capacity(jpeg_tables.len() + jpeg_data.len());
-            full_jpeg.extend_from_slice(&jpeg_tables[..jpeg_tables.len() - 2]);
-            if jpeg_data.len() >= 2 {
-                full_jpeg.extend_from_slice(&jpeg_data[2..]);
-            }
-
-            return std::borrow::Cow::from(full_jpeg);
+            full_jpeg.extend_from_slice(jpeg_tables);
+            full_jpeg.extend_from_slice(jpeg_data);
+            std::borrow::Cow::from(remove_invalid_jpeg_data(&full_jpeg).into_owned())
         }
+        // No JPEG tables; return JPEG data as is
+        _ => std::borrow::Cow::Borrowed(jpeg_data),
     }
-
-    // No JPEG tables or not enough data; return JPEG data as is
-    std::borrow::Cow::Borrowed(jpeg_data)
 }
 
 /// Removes potential invalid JPEG data from SWF DefineBitsJPEG tags.
@@ -231,20 +340,25 @@ pub fn glue_tables_to_jpeg<'a>(
 /// SWF19 p.138:
 /// "Before version 8 of the SWF file format, SWF files could contain an erroneous header of 0xFF, 0xD9, 0xFF, 0xD8 before the JPEG SOI marker."
 /// These bytes need to be removed for the JPEG to decode properly.
-pub fn remove_invalid_jpeg_data(mut data: &[u8]) -> std::borrow::Cow<[u8]> {
-    // TODO: Might be better to return an Box<Iterator<Item=u8>> instead of a Cow here,
-    // where the spliced iter is a data[..n].chain(data[n+4..])?
-    if data[..4] == [0xFF, 0xD9, 0xFF, 0xD8] {
-        data = &data[4..];
+/// Flash also tolerates these EOI/SOI pairs in the middle of the data, so
+/// every one of them is removed.
+pub fn remove_invalid_jpeg_data(data: &[u8]) -> std::borrow::Cow<[u8]> {
+    const INVALID_MARKERS: [u8; 4] = [0xFF, 0xD9, 0xFF, 0xD8];
+
+    if !data.windows(4).any(|window| window == INVALID_MARKERS) {
+        return std::borrow::Cow::Borrowed(data);
     }
-    if let Some(pos) = (0..data.len() - 4).find(|&n| data[n..n + 4] == [0xFF, 0xD9, 0xFF, 0xD8]) {
-        let mut out_data = Vec::with_capacity(data.len() - 4);
-        out_data.extend_from_slice(&data[..pos]);
-        out_data.extend_from_slice(&data[pos + 4..]);
-        std::borrow::Cow::from(out_data)
-    } else {
-        std::borrow::Cow::Borrowed(data)
+
+    // Removing a pair can join the bytes around it into another pair, such as
+    // when image data with an erroneous header follows JPEG tables.
+    let mut out_data = Vec::with_capacity(data.len());
+    for &byte in data {
+        out_data.push(byte);
+        if out_data.ends_with(&INVALID_MARKERS) {
+            out_data.truncate(out_data.len() - INVALID_MARKERS.len());
+        }
     }
+    std::borrow::Cow::from(out_data)
 }
 
 /// Decodes a JPEG with optional alpha data.
@@ -259,6 +373,21 @@ pub fn decode_jpeg(
     decoder.read_info()?;
     let metadata = decoder.info().ok_or("Unable to get image info")?;
     let decoded_data = decoder.decode()?;
+    let decoded_data = match metadata.pixel_format {
+        jpeg_decoder::PixelFormat::RGB24 => decoded_data,
+        jpeg_decoder::PixelFormat::L8 => decoded_data.iter().flat_map(|&l| vec![l; 3]).collect(),
+        jpeg_decoder::PixelFormat::CMYK32 => decoded_data
+            .chunks_exact(4)
+            .flat_map(|cmyk| {
+                let k = 255 - u16::from(cmyk[3]);
+                vec![
+                    ((255 - u16::from(cmyk[0])) * k / 255) as u8,
+                    ((255 - u16::from(cmyk[1])) * k / 255) as u8,
+                    ((255 - u16::from(cmyk[2])) * k / 255) as u8,
+                ]
+            })
+            .collect(),
+    };
 
     // Decompress the alpha data (DEFLATE compression).
     if let Some(alpha_data) = alpha_data {
@@ -274,10 +403,14 @@ pub fn decode_jpeg(
             let mut i = 0;
             let mut a = 0;
             while i < decoded_data.len() {
-                rgba.push(decoded_data[i]);
-                rgba.push(decoded_data[i + 1]);
-                rgba.push(decoded_data[i + 2]);
-                rgba.push(alpha_data[a]);
+                // The color data should already be premultiplied, but some SWFs
+                // have color in fully transparent pixels. Flash clamps the color
+                // to the alpha value, so that these pixels remain invisible.
+                let alpha = alpha_data[a];
+                rgba.push(decoded_data[i].min(alpha));
+                rgba.push(decoded_data[i + 1].min(alpha));
+                rgba.push(decoded_data[i + 2].min(alpha));
+                rgba.push(alpha);
                 i += 3;
                 a += 1;
             }
@@ -439,35 +572,47 @@ pub fn decode_define_bits_lossless(
     })
 }
 
-/// Decodes the bitmap data in DefineBitsLossless tag into RGBA.
-/// DefineBitsLossless is Zlib encoded pixel data (similar to PNG), possibly
-/// palletized.
+/// Decodes a PNG file embedded in a DefineBitsJPEG2/3 tag.
+/// The decoded bitmap will have pre-multiplied alpha.
 pub fn decode_png(data: &[u8]) -> Result<Bitmap, Error> {
     use png::{ColorType, Transformations};
 
     let mut decoder = png::Decoder::new(data);
-    // EXPAND expands palettized types to RGB.
-    decoder.set_transformations(Transformations::EXPAND);
+    // EXPAND expands palettized and low bit depth types to 8-bit channels,
+    // and STRIP_16 reduces 16-bit channels to 8 bits.
+    decoder.set_transformations(Transformations::EXPAND | Transformations::STRIP_16);
     let (info, mut reader) = decoder.read_info()?;
 
-    let mut data = vec![0; info.buffer_size()];
+    let mut data = vec![0; reader.output_buffer_size()];
     reader.next_frame(&mut data)?;
 
+    let data = match reader.output_color_type().0 {
+        ColorType::RGBA => {
+            premultiply_alpha_rgba(&mut data);
+            BitmapFormat::Rgba(data)
+        }
+        ColorType::GrayscaleAlpha => {
+            let mut rgba: Vec<u8> = data
+                .chunks_exact(2)
+                .flat_map(|la| vec![la[0], la[0], la[0], la[1]])
+                .collect();
+            premultiply_alpha_rgba(&mut rgba);
+            BitmapFormat::Rgba(rgba)
+        }
+        ColorType::Grayscale => BitmapFormat::Rgb(data.iter().flat_map(|&l| vec![l; 3]).collect()),
+        // EXPAND expands palettized types to RGB.
+        ColorType::RGB | ColorType::Indexed => BitmapFormat::Rgb(data),
+    };
+
     Ok(Bitmap {
         width: info.width,
         height: info.height,
-        data: if info.color_type == ColorType::RGBA {
-            BitmapFormat::Rgba(data)
-        } else {
-            // EXPAND expands other types to RGB.
-            BitmapFormat::Rgb(data)
-        },
+        data,
     })
 }
 
-/// Decodes the bitmap data in DefineBitsLossless tag into RGBA.
-/// DefineBitsLossless is Zlib encoded pixel data (similar to PNG), possibly
-/// palletized.
+/// Decodes the first frame of a GIF file embedded in a DefineBitsJPEG2/3 tag.
+/// The decoded bitmap will have pre-multiplied alpha.
 pub fn decode_gif(data: &[u8]) -> Result<Bitmap, Error> {
     use gif::SetParameter;
 
@@ -476,10 +621,23 @@ pub fn decode_gif(data: &[u8]) -> Result<Bitmap, Error> {
     let mut reader = decoder.read_info()?;
     let frame = reader.read_next_frame()?.ok_or("No frames in GIF")?;
 
+    let mut rgba = frame.buffer.to_vec();
+    premultiply_alpha_rgba(&mut rgba);
     Ok(Bitmap {
         width: frame.width.into(),
         height: frame.height.into(),
-        data: BitmapFormat::Rgba(frame.buffer.to_vec()),
+        data: BitmapFormat::Rgba(rgba),
+    })
+}
+
+/// Converts standard RGBA to RGBA premultiplied alpha, the format of
+/// `Bitmap`s.
+pub fn premultiply_alpha_rgba(rgba: &mut [u8]) {
+    rgba.chunks_exact_mut(4).for_each(|rgba| {
+        let a = u16::from(rgba[3]);
+        rgba[0] = (u16::from(rgba[0]) * a / 255) as u8;
+        rgba[1] = (u16::from(rgba[1]) * a / 255) as u8;
+        rgba[2] = (u16::from(rgba[2]) * a / 255) as u8;
     })
 }
 
@@ -512,3 +670,92 @@ pub fn srgb_to_linear(color: [f32; 4]) -> [f32; 4] {
         color[3],
     ]
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn encode_png(width: u32, height: u32, rgba: &[u8]) -> Vec<u8> {
+        let mut data = vec![];
+        let mut encoder = png::Encoder::new(&mut data, width, height);
+        encoder.set_color(png::ColorType::RGBA);
+        encoder.set_depth(png::BitDepth::Eight);
+        encoder
+            .write_header()
+            .unwrap()
+            .write_image_data(rgba)
+            .unwrap();
+        data
+    }
+
+    #[test]
+    fn sniff_jpeg_tag_format() {
+        assert_eq!(
+            determine_jpeg_tag_format(&[0xFF, 0xD8, 0xFF, 0xE0]),
+            JpegTagFormat::Jpeg
+        );
+        assert_eq!(
+            determine_jpeg_tag_format(&[0xFF, 0xD9, 0xFF, 0xD8, 0xFF, 0xD8]),
+            JpegTagFormat::Jpeg
+        );
+        assert_eq!(
+            determine_jpeg_tag_format(&encode_png(1, 1, &[0, 0, 0, 0])),
+            JpegTagFormat::Png
+        );
+        assert_eq!(determine_jpeg_tag_format(b"GIF87a"), JpegTagFormat::Gif);
+        assert_eq!(determine_jpeg_tag_format(b"GIF89a"), JpegTagFormat::Gif);
+        assert_eq!(determine_jpeg_tag_format(b"BM"), JpegTagFormat::Unknown);
+        assert_eq!(determine_jpeg_tag_format(&[]), JpegTagFormat::Unknown);
+    }
+
+    #[test]
+    fn remove_erroneous_jpeg_markers() {
+        assert_eq!(&remove_invalid_jpeg_data(&[])[..], &[]);
+        assert_eq!(&remove_invalid_jpeg_data(&[0xFF, 0xD8])[..], &[0xFF, 0xD8]);
+        assert_eq!(
+            &remove_invalid_jpeg_data(&[0xFF, 0xD9, 0xFF, 0xD8, 0xFF, 0xD8, 1, 0xFF, 0xD9])[..],
+            &[0xFF, 0xD8, 1, 0xFF, 0xD9]
+        );
+        assert_eq!(
+            &remove_invalid_jpeg_data(&[
+                0xFF, 0xD8, 1, 0xFF, 0xD9, 0xFF, 0xD8, 2, 0xFF, 0xD9, 0xFF, 0xD8, 3, 0xFF, 0xD9
+            ])[..],
+            &[0xFF, 0xD8, 1, 2, 3, 0xFF, 0xD9]
+        );
+    }
+
+    #[test]
+    fn glue_jpeg_tables() {
+        let tables = [0xFF, 0xD8, 1, 0xFF, 0xD9];
+        let data = [0xFF, 0xD8, 2, 0xFF, 0xD9];
+        assert_eq!(
+            &glue_tables_to_jpeg(&data, Some(&tables))[..],
+            &[0xFF, 0xD8, 1, 2, 0xFF, 0xD9]
+        );
+        // Old SWFs may have the erroneous header in the image data as well.
+        let data = [0xFF, 0xD9, 0xFF, 0xD8, 0xFF, 0xD8, 2, 0xFF, 0xD9];
+        assert_eq!(
+            &glue_tables_to_jpeg(&data, Some(&tables))[..],
+            &[0xFF, 0xD8, 1, 2, 0xFF, 0xD9]
+        );
+        assert_eq!(&glue_tables_to_jpeg(&data, None)[..], &data[..]);
+        assert_eq!(&glue_tables_to_jpeg(&data, Some(&[]))[..], &data[..]);
+    }
+
+    #[test]
+    fn png_in_jpeg_tag_is_premultiplied() {
+        let png = encode_png(2, 1, &[255, 128, 0, 128, 255, 255, 255, 0]);
+        let bitmap = decode_define_bits_jpeg(&png, None).unwrap();
+        assert_eq!((bitmap.width, bitmap.height), (2, 1));
+        match bitmap.data {
+            BitmapFormat::Rgba(rgba) => assert_eq!(rgba, [128, 64, 0, 128, 0, 0, 0, 0]),
+            BitmapFormat::Rgb(_) => panic!("Expected RGBA data"),
+        }
+    }
+
+    #[test]
+    fn unknown_jpeg_tag_data_is_an_error() {
+        assert!(decode_define_bits_jpeg(&[1, 2, 3, 4], None).is_err());
+        assert!(decode_define_bits_jpeg(&[0xFF, 0xD8, 0xFF], None).is_err());
+    }
+}
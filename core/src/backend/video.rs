@@ -0,0 +1,171 @@
+//! Video decoding
+
+use generational_arena::Index;
+
+pub mod software;
+pub mod swf {
+    pub use swf::{VideoCodec, VideoDeblocking};
+}
+
+pub type VideoStreamHandle = Index;
+
+type Error = Box<dyn std::error::Error>;
+
+/// An encoded frame of a video stream.
+pub struct EncodedFrame<'a> {
+    /// The codec that the frame is encoded with.
+    pub codec: swf::VideoCodec,
+
+    /// The frame data, in the format of the `VideoData` of a SWF `VideoFrame` tag.
+    pub data: &'a [u8],
+
+    /// Whether the frame can be decoded without the frames before it.
+    pub is_keyframe: bool,
+}
+
+/// A decoded frame of a video stream.
+pub struct DecodedFrame {
+    pub width: u32,
+    pub height: u32,
+
+    /// The pixels of the frame, in RGBA order.
+    pub rgba: Vec<u8>,
+}
+
+/// Decodes the frames of video streams, such as those played by an AVM1 `NetStream`.
+///
+/// Most frames only describe how they differ from the frame before them, so the frames
+/// of a stream must be decoded in order, starting from a keyframe.
+pub trait VideoBackend {
+    /// Registers a new video stream with the given codec.
+    ///
+    /// `size` is the size given by the `DefineVideoStream` tag, if known. Returns an error
+    /// if the codec is not supported.
+    fn register_video_stream(
+        &mut self,
+        codec: swf::VideoCodec,
+        size: Option<(u16, u16)>,
+        deblocking: swf::VideoDeblocking,
+    ) -> Result<VideoStreamHandle, Error>;
+
+    /// Decodes the next frame of a video stream.
+    fn decode_video_stream_frame(
+        &mut self,
+        stream: VideoStreamHandle,
+        frame: EncodedFrame<'_>,
+    ) -> Result<DecodedFrame, Error>;
+
+    /// Releases a video stream that is no longer played.
+    fn unregister_video_stream(&mut self, stream: VideoStreamHandle);
+}
+
+/// Video backend that can't decode any video.
+pub struct NullVideoBackend {}
+
+impl NullVideoBackend {
+    pub fn new() -> Self {
+        Self {}
+    }
+}
+
+impl VideoBackend for NullVideoBackend {
+    fn register_video_stream(
+        &mut self,
+        _codec: swf::VideoCodec,
+        _size: Option<(u16, u16)>,
+        _deblocking: swf::VideoDeblocking,
+    ) -> Result<VideoStreamHandle, Error> {
+        Err("Video decoding is not supported".into())
+    }
+
+    fn decode_video_stream_frame(
+        &mut self,
+        _stream: VideoStreamHandle,
+        _frame: EncodedFrame<'_>,
+    ) -> Result<DecodedFrame, Error> {
+        Err("Video decoding is not supported".into())
+    }
+
+    fn unregister_video_stream(&mut self, _stream: VideoStreamHandle) {}
+}
+
+impl Default for NullVideoBackend {
+    fn default() -> Self {
+        NullVideoBackend::new()
+    }
+}
+
+/// Returns whether the data of a SWF `VideoFrame` tag is a keyframe, which can be decoded
+/// without the frames before it.
+///
+/// Unlike FLV files, SWF files don't flag their keyframes, so this is read from the headers
+/// of each codec.
+pub fn is_keyframe(codec: swf::VideoCodec, data: &[u8]) -> bool {
+    use swf::VideoCodec;
+    match codec {
+        VideoCodec::H263 => {
+            let bit = |n: usize| data.get(n / 8).map_or(0, |byte| (byte >> (7 - n % 8)) & 1);
+            let bits = |start: usize, len: usize| {
+                (start..start + len).fold(0u32, |value, n| value << 1 | u32::from(bit(n)))
+            };
+            // The picture size follows the 17-bit start code, 5-bit version and 8-bit
+            // temporal reference, and is followed by the custom size, if any.
+            let picture_type_pos = match bits(30, 3) {
+                0 => 49,
+                1 => 65,
+                _ => 33,
+            };
+            // Intra frames have a picture type of 0.
+            bits(picture_type_pos, 2) == 0
+        }
+        // The frame mode bit is cleared for intra frames.
+        VideoCodec::VP6 => data.first().map_or(false, |byte| byte & 0x80 == 0),
+        // The frame follows the 24-bit offset to the alpha channel.
+        VideoCodec::VP6WithAlpha => data.get(3).map_or(false, |byte| byte & 0x80 == 0),
+        // Only keyframes store every block of the frame.
+        VideoCodec::ScreenVideo => {
+            let read_u16 = |pos: usize| {
+                data.get(pos..pos + 2)
+                    .map(|bytes| usize::from(u16::from_be_bytes([bytes[0], bytes[1]])))
+            };
+            let (horizontal, vertical) = match (read_u16(0), read_u16(2)) {
+                (Some(horizontal), Some(vertical)) => (horizontal, vertical),
+                _ => return false,
+            };
+            let block_width = ((horizontal >> 12) + 1) * 16;
+            let block_height = ((vertical >> 12) + 1) * 16;
+            let num_blocks = ((horizontal & 0xfff) + block_width - 1) / block_width
+                * (((vertical & 0xfff) + block_height - 1) / block_height);
+
+            let mut pos = 4;
+            for _ in 0..num_blocks {
+                match read_u16(pos) {
+                    Some(size) if size > 0 => pos += 2 + size,
+                    _ => return false,
+                }
+            }
+            true
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::swf::VideoCodec;
+    use super::*;
+
+    #[test]
+    fn keyframes() {
+        // A CIF picture, followed by an intra or inter picture type.
+        assert!(is_keyframe(VideoCodec::H263, &[0, 0, 0x80, 0x01, 0x00]));
+        assert!(!is_keyframe(VideoCodec::H263, &[0, 0, 0x80, 0x01, 0x20]));
+
+        assert!(is_keyframe(VideoCodec::VP6, &[0x00, 0x00]));
+        assert!(!is_keyframe(VideoCodec::VP6, &[0x80, 0x00]));
+        assert!(is_keyframe(VideoCodec::VP6WithAlpha, &[0, 0, 1, 0x00]));
+
+        // A 2x2 frame with a single block, which is either stored or unchanged.
+        assert!(is_keyframe(VideoCodec::ScreenVideo, &[0, 2, 0, 2, 0, 1, 0]));
+        assert!(!is_keyframe(VideoCodec::ScreenVideo, &[0, 2, 0, 2, 0, 0]));
+    }
+}
@@ -0,0 +1,226 @@
+//! Video decoders that run on the CPU
+
+use super::swf::{VideoCodec, VideoDeblocking};
+use super::{DecodedFrame, EncodedFrame, Error, VideoBackend, VideoStreamHandle};
+use generational_arena::Arena;
+use std::io::Read;
+
+/// Decodes the frames of a single video stream.
+trait VideoDecoder {
+    fn decode_frame(&mut self, frame: EncodedFrame<'_>) -> Result<DecodedFrame, Error>;
+}
+
+/// Video backend that decodes video in software.
+///
+/// Only Screen Video is supported so far. Registering a stream of any other codec fails.
+// TODO: Sorenson H.263 and On2 VP6, which most FLV files are encoded with.
+pub struct SoftwareVideoBackend {
+    streams: Arena<Box<dyn VideoDecoder>>,
+}
+
+impl SoftwareVideoBackend {
+    pub fn new() -> Self {
+        Self {
+            streams: Arena::new(),
+        }
+    }
+}
+
+impl VideoBackend for SoftwareVideoBackend {
+    fn register_video_stream(
+        &mut self,
+        codec: VideoCodec,
+        _size: Option<(u16, u16)>,
+        _deblocking: VideoDeblocking,
+    ) -> Result<VideoStreamHandle, Error> {
+        let decoder: Box<dyn VideoDecoder> = match codec {
+            VideoCodec::ScreenVideo => Box::new(ScreenVideoDecoder::new()),
+            VideoCodec::H263 => return Err("Sorenson H.263 video is not supported".into()),
+            VideoCodec::VP6 | VideoCodec::VP6WithAlpha => {
+                return Err("On2 VP6 video is not supported".into())
+            }
+        };
+        Ok(self.streams.insert(decoder))
+    }
+
+    fn decode_video_stream_frame(
+        &mut self,
+        stream: VideoStreamHandle,
+        frame: EncodedFrame<'_>,
+    ) -> Result<DecodedFrame, Error> {
+        self.streams
+            .get_mut(stream)
+            .ok_or("Unregistered video stream")?
+            .decode_frame(frame)
+    }
+
+    fn unregister_video_stream(&mut self, stream: VideoStreamHandle) {
+        self.streams.remove(stream);
+    }
+}
+
+impl Default for SoftwareVideoBackend {
+    fn default() -> Self {
+        SoftwareVideoBackend::new()
+    }
+}
+
+/// Decodes Screen Video, which divides each frame into blocks and stores the blocks that
+/// changed since the previous frame as zlib compressed BGR pixels.
+struct ScreenVideoDecoder {
+    width: usize,
+    height: usize,
+
+    /// The pixels of the previous frame, in RGBA order.
+    pixels: Vec<u8>,
+}
+
+impl ScreenVideoDecoder {
+    fn new() -> Self {
+        Self {
+            width: 0,
+            height: 0,
+            pixels: Vec::new(),
+        }
+    }
+}
+
+impl VideoDecoder for ScreenVideoDecoder {
+    fn decode_frame(&mut self, frame: EncodedFrame<'_>) -> Result<DecodedFrame, Error> {
+        let data = frame.data;
+        let read_u16 = |pos: usize| -> Result<usize, Error> {
+            match data.get(pos..pos + 2) {
+                Some(bytes) => Ok(usize::from(u16::from_be_bytes([bytes[0], bytes[1]]))),
+                None => Err("Truncated Screen Video frame".into()),
+            }
+        };
+
+        // The block sizes are stored in units of 16 pixels, minus one.
+        let horizontal = read_u16(0)?;
+        let vertical = read_u16(2)?;
+        let block_width = ((horizontal >> 12) + 1) * 16;
+        let width = horizontal & 0xfff;
+        let block_height = ((vertical >> 12) + 1) * 16;
+        let height = vertical & 0xfff;
+
+        if width != self.width || height != self.height {
+            self.width = width;
+            self.height = height;
+            self.pixels = [0, 0, 0, 255].repeat(width * height);
+        }
+
+        // Blocks are stored in rows from the bottom left of the frame, and the pixels of
+        // each block from bottom to top. Blocks on the top and right edges may be smaller.
+        let mut pos = 4;
+        let mut block_y = 0;
+        while block_y < height {
+            let block_h = block_height.min(height - block_y);
+            let mut block_x = 0;
+            while block_x < width {
+                let block_w = block_width.min(width - block_x);
+                let size = read_u16(pos)?;
+                pos += 2;
+
+                // Empty blocks are unchanged from the previous frame.
+                if size > 0 {
+                    let compressed = data
+                        .get(pos..pos + size)
+                        .ok_or("Truncated Screen Video frame")?;
+                    pos += size;
+
+                    let mut block = Vec::with_capacity(block_w * block_h * 3);
+                    libflate::zlib::Decoder::new(compressed)?.read_to_end(&mut block)?;
+                    if block.len() < block_w * block_h * 3 {
+                        return Err("Truncated Screen Video block".into());
+                    }
+
+                    for (row, bgr) in block.chunks_exact(block_w * 3).take(block_h).enumerate() {
+                        let y = height - 1 - (block_y + row);
+                        let start = (y * width + block_x) * 4;
+                        let rgba = &mut self.pixels[start..start + block_w * 4];
+                        for (rgba, bgr) in rgba.chunks_exact_mut(4).zip(bgr.chunks_exact(3)) {
+                            rgba.copy_from_slice(&[bgr[2], bgr[1], bgr[0], 255]);
+                        }
+                    }
+                }
+                block_x += block_w;
+            }
+            block_y += block_h;
+        }
+
+        Ok(DecodedFrame {
+            width: width as u32,
+            height: height as u32,
+            rgba: self.pixels.clone(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    fn compress(data: &[u8]) -> Vec<u8> {
+        let mut encoder = libflate::zlib::Encoder::new(Vec::new()).unwrap();
+        encoder.write_all(data).unwrap();
+        encoder.finish().into_result().unwrap()
+    }
+
+    #[test]
+    fn decode_screen_video() {
+        let mut backend = SoftwareVideoBackend::new();
+        let stream = backend
+            .register_video_stream(VideoCodec::ScreenVideo, None, VideoDeblocking::None)
+            .unwrap();
+
+        // A 2x2 frame in a single 16x16 block, whose rows are stored from the bottom up.
+        let block = compress(&[
+            0, 0, 255, 0, 255, 0, // Red, green
+            255, 0, 0, 255, 255, 255, // Blue, white
+        ]);
+        let mut data = vec![0x00, 0x02, 0x00, 0x02];
+        data.extend_from_slice(&(block.len() as u16).to_be_bytes());
+        data.extend_from_slice(&block);
+
+        let frame = backend
+            .decode_video_stream_frame(
+                stream,
+                EncodedFrame {
+                    codec: VideoCodec::ScreenVideo,
+                    data: &data,
+                    is_keyframe: true,
+                },
+            )
+            .unwrap();
+        let expected = vec![
+            0, 0, 255, 255, 255, 255, 255, 255, // Blue, white
+            255, 0, 0, 255, 0, 255, 0, 255, // Red, green
+        ];
+        assert_eq!((frame.width, frame.height), (2, 2));
+        assert_eq!(frame.rgba, expected);
+
+        // An empty block keeps the pixels of the previous frame.
+        let frame = backend
+            .decode_video_stream_frame(
+                stream,
+                EncodedFrame {
+                    codec: VideoCodec::ScreenVideo,
+                    data: &[0x00, 0x02, 0x00, 0x02, 0x00, 0x00],
+                    is_keyframe: false,
+                },
+            )
+            .unwrap();
+        assert_eq!(frame.rgba, expected);
+    }
+
+    #[test]
+    fn unsupported_codecs() {
+        let mut backend = SoftwareVideoBackend::new();
+        for &codec in &[VideoCodec::H263, VideoCodec::VP6, VideoCodec::VP6WithAlpha] {
+            assert!(backend
+                .register_video_stream(codec, Some((320, 240)), VideoDeblocking::None)
+                .is_err());
+        }
+    }
+}
@@ -0,0 +1,706 @@
+//! Pixel buffers for the ActionScript `BitmapData` class.
+
+use crate::backend::render::{
+    decode_define_bits_jpeg, decode_define_bits_lossless, Bitmap, BitmapFormat, BitmapHandle,
+    RenderBackend,
+};
+use crate::color_transform::ColorTransform;
+use gc_arena::Collect;
+use std::cell::Cell;
+use std::sync::atomic::{AtomicU16, Ordering};
+use swf::{BlendMode, CharacterId, Matrix};
+
+/// Bitmap fills refer to bitmaps by character ID, so `BitmapData` pixels are registered with
+/// the renderer using IDs counting down from the top of the ID range, which are very unlikely
+/// to be used by a movie.
+static NEXT_BITMAP_ID: AtomicU16 = AtomicU16::new(CharacterId::MAX);
+
+/// A rectangular buffer of 32-bit ARGB pixels.
+///
+/// Pixels are stored without premultiplied alpha. Opaque bitmaps always have an alpha of 0xFF.
+#[derive(Clone, Debug, Collect)]
+#[collect(require_static)]
+pub struct BitmapData {
+    width: u32,
+    height: u32,
+    transparent: bool,
+    pixels: Vec<u32>,
+    disposed: bool,
+
+    /// The ID and handle of these pixels in the renderer, once they have been registered.
+    render_id: Cell<Option<(CharacterId, BitmapHandle)>>,
+
+    /// Whether the pixels have changed since they were last uploaded to the renderer.
+    dirty: Cell<bool>,
+
+    /// Incremented every time the pixels change.
+    generation: u32,
+}
+
+/// How `BitmapData::draw` composites a source onto a bitmap.
+#[derive(Clone, Copy, Debug)]
+pub struct DrawOptions {
+    /// How the colors of the source are combined with the pixels below them.
+    pub blend_mode: BlendMode,
+
+    /// The rectangle of the bitmap that is drawn to, as `(x, y, width, height)`.
+    pub clip_rect: Option<(i32, i32, i32, i32)>,
+
+    /// Whether the source is sampled bilinearly when it is scaled or rotated.
+    pub smoothing: bool,
+}
+
+impl Default for DrawOptions {
+    fn default() -> Self {
+        Self {
+            blend_mode: BlendMode::Normal,
+            clip_rect: None,
+            smoothing: false,
+        }
+    }
+}
+
+impl BitmapData {
+    pub fn new(width: u32, height: u32, transparent: bool, fill_color: u32) -> Self {
+        let fill_color = if transparent {
+            fill_color
+        } else {
+            fill_color | 0xFF00_0000
+        };
+        Self {
+            width,
+            height,
+            transparent,
+            pixels: vec![fill_color; width as usize * height as usize],
+            disposed: false,
+            render_id: Cell::new(None),
+            dirty: Cell::new(true),
+            generation: 0,
+        }
+    }
+
+    /// Creates a bitmap from decoded image data.
+    /// If `premultiplied` is set, the color channels of the image are multiplied by its alpha.
+    pub fn from_bitmap(bitmap: Bitmap, premultiplied: bool) -> Self {
+        let (transparent, pixels) = match bitmap.data {
+            BitmapFormat::Rgb(rgb) => (
+                false,
+                rgb.chunks_exact(3)
+                    .map(|rgb| {
+                        0xFF00_0000
+                            | u32::from(rgb[0]) << 16
+                            | u32::from(rgb[1]) << 8
+                            | u32::from(rgb[2])
+                    })
+                    .collect(),
+            ),
+            BitmapFormat::Rgba(mut rgba) => {
+                if premultiplied {
+                    crate::backend::render::unmultiply_alpha_rgba(&mut rgba);
+                }
+                (
+                    true,
+                    rgba.chunks_exact(4)
+                        .map(|rgba| {
+                            u32::from(rgba[3]) << 24
+                                | u32::from(rgba[0]) << 16
+                                | u32::from(rgba[1]) << 8
+                                | u32::from(rgba[2])
+                        })
+                        .collect(),
+                )
+            }
+        };
+        Self {
+            width: bitmap.width,
+            height: bitmap.height,
+            transparent,
+            pixels,
+            disposed: false,
+            render_id: Cell::new(None),
+            dirty: Cell::new(true),
+            generation: 0,
+        }
+    }
+
+    /// Makes a copy of these pixels that will be registered with the renderer separately.
+    pub fn duplicate(&self) -> Self {
+        Self {
+            render_id: Cell::new(None),
+            dirty: Cell::new(true),
+            ..self.clone()
+        }
+    }
+
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    pub fn transparent(&self) -> bool {
+        self.transparent
+    }
+
+    pub fn disposed(&self) -> bool {
+        self.disposed
+    }
+
+    pub fn generation(&self) -> u32 {
+        self.generation
+    }
+
+    /// Frees the pixels. A disposed bitmap has no size and can no longer be drawn.
+    pub fn dispose(&mut self) {
+        self.width = 0;
+        self.height = 0;
+        self.pixels = Vec::new();
+        self.disposed = true;
+        self.invalidate();
+    }
+
+    fn invalidate(&mut self) {
+        self.dirty.set(true);
+        self.generation = self.generation.wrapping_add(1);
+    }
+
+    fn index(&self, x: i32, y: i32) -> Option<usize> {
+        if x >= 0 && y >= 0 && (x as u32) < self.width && (y as u32) < self.height {
+            Some(y as usize * self.width as usize + x as usize)
+        } else {
+            None
+        }
+    }
+
+    /// Returns the ARGB value of the given pixel, or `None` if it is out of bounds.
+    pub fn get_pixel32(&self, x: i32, y: i32) -> Option<u32> {
+        self.index(x, y).map(|i| self.pixels[i])
+    }
+
+    pub fn set_pixel32(&mut self, x: i32, y: i32, color: u32) {
+        if let Some(i) = self.index(x, y) {
+            self.pixels[i] = if self.transparent {
+                color
+            } else {
+                color | 0xFF00_0000
+            };
+            self.invalidate();
+        }
+    }
+
+    /// Sets the RGB value of the given pixel, keeping its alpha.
+    pub fn set_pixel(&mut self, x: i32, y: i32, rgb: u32) {
+        if let Some(i) = self.index(x, y) {
+            self.pixels[i] = (self.pixels[i] & 0xFF00_0000) | (rgb & 0x00FF_FFFF);
+            self.invalidate();
+        }
+    }
+
+    /// Fills a rectangle with the given ARGB color. The rectangle is clipped to the bitmap.
+    pub fn fill_rect(&mut self, x: i32, y: i32, width: i32, height: i32, color: u32) {
+        let color = if self.transparent {
+            color
+        } else {
+            color | 0xFF00_0000
+        };
+        let (x_min, x_max) = self.clip_span(x, width, self.width);
+        let (y_min, y_max) = self.clip_span(y, height, self.height);
+        for y in y_min..y_max {
+            let row = y * self.width as usize;
+            for pixel in &mut self.pixels[row + x_min..row + x_max] {
+                *pixel = color;
+            }
+        }
+        self.invalidate();
+    }
+
+    /// Clips the span `start..start + length` to `0..limit`.
+    fn clip_span(&self, start: i32, length: i32, limit: u32) -> (usize, usize) {
+        let min = start.max(0).min(limit as i32);
+        let max = start.saturating_add(length.max(0)).max(0).min(limit as i32);
+        (min as usize, max as usize)
+    }
+
+    /// Copies a rectangle of pixels from another bitmap to the given point in this bitmap.
+    /// If `merge_alpha` is set, the source pixels are blended onto the destination pixels
+    /// instead of replacing them.
+    pub fn copy_pixels(
+        &mut self,
+        source: &BitmapData,
+        (src_x, src_y, width, height): (i32, i32, i32, i32),
+        (dest_x, dest_y): (i32, i32),
+        merge_alpha: bool,
+    ) {
+        for y in 0..height.max(0) {
+            for x in 0..width.max(0) {
+                let src = source.get_pixel32(src_x + x, src_y + y);
+                let dest = self.index(dest_x + x, dest_y + y);
+                if let (Some(src), Some(dest)) = (src, dest) {
+                    self.pixels[dest] = if merge_alpha && source.transparent {
+                        self.blend(src, self.pixels[dest])
+                    } else if self.transparent {
+                        src
+                    } else {
+                        src | 0xFF00_0000
+                    };
+                }
+            }
+        }
+        self.invalidate();
+    }
+
+    /// Draws another bitmap onto this bitmap, transformed by the given matrix and color transform.
+    /// The matrix maps from the source bitmap's pixels to this bitmap's pixels.
+    pub fn draw(
+        &mut self,
+        source: &BitmapData,
+        matrix: &Matrix,
+        color_transform: &ColorTransform,
+        options: &DrawOptions,
+    ) {
+        let mut inverse = *matrix;
+        inverse.invert();
+        let (a, b, c, d) = (inverse.a, inverse.b, inverse.c, inverse.d);
+        let (tx, ty) = (inverse.tx.to_pixels() as f32, inverse.ty.to_pixels() as f32);
+
+        // Only visit the destination pixels covered by the transformed source.
+        let corners = [
+            (0.0, 0.0),
+            (source.width as f32, 0.0),
+            (0.0, source.height as f32),
+            (source.width as f32, source.height as f32),
+        ];
+        let (m_tx, m_ty) = (matrix.tx.to_pixels() as f32, matrix.ty.to_pixels() as f32);
+        let transformed = corners.iter().map(|&(x, y)| {
+            (
+                matrix.a * x + matrix.c * y + m_tx,
+                matrix.b * x + matrix.d * y + m_ty,
+            )
+        });
+        let (mut x_min, mut y_min, mut x_max, mut y_max) = (f32::MAX, f32::MAX, f32::MIN, f32::MIN);
+        for (x, y) in transformed {
+            x_min = x_min.min(x);
+            y_min = y_min.min(y);
+            x_max = x_max.max(x);
+            y_max = y_max.max(y);
+        }
+        let (x_min, x_max) = self.clip_span(
+            x_min.floor() as i32,
+            (x_max.ceil() - x_min.floor()) as i32,
+            self.width,
+        );
+        let (y_min, y_max) = self.clip_span(
+            y_min.floor() as i32,
+            (y_max.ceil() - y_min.floor()) as i32,
+            self.height,
+        );
+        let ((x_min, x_max), (y_min, y_max)) = match options.clip_rect {
+            Some((clip_x, clip_y, clip_width, clip_height)) => {
+                let (clip_x_min, clip_x_max) = self.clip_span(clip_x, clip_width, self.width);
+                let (clip_y_min, clip_y_max) = self.clip_span(clip_y, clip_height, self.height);
+                (
+                    (x_min.max(clip_x_min), x_max.min(clip_x_max)),
+                    (y_min.max(clip_y_min), y_max.min(clip_y_max)),
+                )
+            }
+            None => ((x_min, x_max), (y_min, y_max)),
+        };
+
+        // Smoothing only makes a difference when the source pixels don't line up with ours.
+        let is_pixel_aligned = matrix.a == 1.0
+            && matrix.b == 0.0
+            && matrix.c == 0.0
+            && matrix.d == 1.0
+            && m_tx.fract() == 0.0
+            && m_ty.fract() == 0.0;
+        let smoothing = options.smoothing && !is_pixel_aligned;
+
+        for y in y_min..y_max {
+            for x in x_min..x_max {
+                // Sample the source at the center of the destination pixel.
+                let (px, py) = (x as f32 + 0.5, y as f32 + 0.5);
+                let (src_x, src_y) = (a * px + c * py + tx, b * px + d * py + ty);
+                let src = if smoothing {
+                    source.sample_bilinear(src_x, src_y)
+                } else {
+                    source.get_pixel32(src_x.floor() as i32, src_y.floor() as i32)
+                };
+                if let Some(src) = src {
+                    let src = apply_color_transform(src, color_transform);
+                    let i = y * self.width as usize + x;
+                    self.pixels[i] = self.composite(src, self.pixels[i], options.blend_mode);
+                }
+            }
+        }
+        self.invalidate();
+    }
+
+    /// Returns the color at the given point, interpolated between the four nearest pixels,
+    /// or `None` if the point is outside of the bitmap.
+    fn sample_bilinear(&self, x: f32, y: f32) -> Option<u32> {
+        self.get_pixel32(x.floor() as i32, y.floor() as i32)?;
+
+        // Pixels past the edges repeat the edge pixels.
+        let (x, y) = (x - 0.5, y - 0.5);
+        let (x0, y0) = (x.floor(), y.floor());
+        let (fx, fy) = (x - x0, y - y0);
+        let pixel = |x: f32, y: f32| {
+            let x = (x as i32).max(0).min(self.width as i32 - 1);
+            let y = (y as i32).max(0).min(self.height as i32 - 1);
+            self.pixels[y as usize * self.width as usize + x as usize]
+        };
+        let samples = [
+            (pixel(x0, y0), (1.0 - fx) * (1.0 - fy)),
+            (pixel(x0 + 1.0, y0), fx * (1.0 - fy)),
+            (pixel(x0, y0 + 1.0), (1.0 - fx) * fy),
+            (pixel(x0 + 1.0, y0 + 1.0), fx * fy),
+        ];
+
+        // Colors are interpolated with premultiplied alpha, so that transparent pixels
+        // don't bleed into their neighbours.
+        let mut argb = [0.0f32; 4];
+        for &(color, weight) in &samples {
+            let alpha = (color >> 24) as f32 / 255.0;
+            argb[0] += alpha * weight;
+            for (i, shift) in [16, 8, 0].iter().enumerate() {
+                argb[i + 1] += ((color >> shift) & 0xFF) as f32 * alpha * weight;
+            }
+        }
+        if argb[0] <= 0.0 {
+            return Some(0);
+        }
+        let channel = |value: f32| (value / argb[0]).round().max(0.0).min(255.0) as u32;
+        let alpha = (argb[0] * 255.0).round().min(255.0) as u32;
+        Some(alpha << 24 | channel(argb[1]) << 16 | channel(argb[2]) << 8 | channel(argb[3]))
+    }
+
+    /// Composites an ARGB color over another with the given blend mode.
+    ///
+    /// The blended color is mixed in where both colors are opaque, following the
+    /// W3C compositing model.
+    fn composite(&self, src: u32, dest: u32, blend_mode: BlendMode) -> u32 {
+        let src_a = (src >> 24) as f32 / 255.0;
+        let dest_a = if self.transparent {
+            (dest >> 24) as f32 / 255.0
+        } else {
+            1.0
+        };
+        let out_a = match blend_mode {
+            BlendMode::Normal | BlendMode::Layer => return self.blend(src, dest),
+            // These only change the alpha of the pixels below.
+            BlendMode::Alpha | BlendMode::Erase if !self.transparent => return dest,
+            BlendMode::Alpha => dest_a * src_a,
+            BlendMode::Erase => dest_a * (1.0 - src_a),
+            _ => src_a + dest_a * (1.0 - src_a),
+        };
+        if out_a <= 0.0 {
+            return 0;
+        }
+
+        let channel = |shift: u32| {
+            let s = ((src >> shift) & 0xFF) as f32 / 255.0;
+            let d = ((dest >> shift) & 0xFF) as f32 / 255.0;
+            let value = match blend_mode {
+                BlendMode::Alpha | BlendMode::Erase => d,
+                _ => {
+                    let blended = blend_channel(blend_mode, s, d);
+                    (src_a * (1.0 - dest_a) * s
+                        + src_a * dest_a * blended
+                        + (1.0 - src_a) * dest_a * d)
+                        / out_a
+                }
+            };
+            ((value * 255.0).round().max(0.0).min(255.0) as u32) << shift
+        };
+        let out_a = if self.transparent {
+            (out_a * 255.0).round() as u32
+        } else {
+            0xFF
+        };
+        (out_a << 24) | channel(16) | channel(8) | channel(0)
+    }
+
+    /// Blends an ARGB color over another using its alpha.
+    fn blend(&self, src: u32, dest: u32) -> u32 {
+        let src_a = src >> 24;
+        if src_a == 0xFF {
+            return src;
+        }
+        let dest_a = if self.transparent { dest >> 24 } else { 0xFF };
+        let out_a = src_a + dest_a * (0xFF - src_a) / 0xFF;
+        if out_a == 0 {
+            return 0;
+        }
+        let channel = |shift: u32| {
+            let s = (src >> shift) & 0xFF;
+            let d = (dest >> shift) & 0xFF;
+            (s * src_a + d * dest_a * (0xFF - src_a) / 0xFF) / out_a
+        };
+        let out_a = if self.transparent { out_a } else { 0xFF };
+        (out_a << 24) | (channel(16) << 16) | (channel(8) << 8) | channel(0)
+    }
+
+    /// Returns the pixels as RGBA bytes with premultiplied alpha, as used by the renderer.
+    pub fn to_rgba(&self) -> Vec<u8> {
+        let mut rgba = Vec::with_capacity(self.pixels.len() * 4);
+        for &pixel in &self.pixels {
+            let alpha = pixel >> 24;
+            let premultiply = |shift: u32| (((pixel >> shift) & 0xFF) * alpha / 0xFF) as u8;
+            rgba.push(premultiply(16));
+            rgba.push(premultiply(8));
+            rgba.push(premultiply(0));
+            rgba.push(alpha as u8);
+        }
+        rgba
+    }
+
+    /// Registers these pixels with the renderer, or updates them if they have changed.
+    /// Returns the ID and handle of the bitmap, or `None` if there is nothing to draw.
+    pub fn register(
+        &self,
+        renderer: &mut dyn RenderBackend,
+    ) -> Option<(CharacterId, BitmapHandle)> {
+        if self.disposed || self.width == 0 || self.height == 0 {
+            return None;
+        }
+
+        match self.render_id.get() {
+            Some(render_id) if !self.dirty.get() => Some(render_id),
+            Some((id, handle)) => {
+                if let Err(e) =
+                    renderer.update_bitmap_rgba(handle, self.width, self.height, self.to_rgba())
+                {
+                    log::error!("Unable to update BitmapData pixels: {}", e);
+                }
+                self.dirty.set(false);
+                Some((id, handle))
+            }
+            None => {
+                let id = NEXT_BITMAP_ID.fetch_sub(1, Ordering::Relaxed);
+                match renderer.register_bitmap_rgba(id, self.width, self.height, self.to_rgba()) {
+                    Ok(info) => {
+                        let handle = info.handle;
+                        self.render_id.set(Some((id, handle)));
+                        self.dirty.set(false);
+                        Some((id, handle))
+                    }
+                    Err(e) => {
+                        log::error!("Unable to register BitmapData pixels: {}", e);
+                        None
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// The encoded image data of a bitmap character, kept so that it can be decoded into a
+/// `BitmapData` by `BitmapData.loadBitmap`.
+#[derive(Clone, Debug)]
+pub enum BitmapSource {
+    /// JPEG, PNG or GIF data from a DefineBits tag, already joined with any JPEG tables.
+    /// DefineBitsJPEG3 tags also have zlib-compressed alpha data.
+    Jpeg {
+        data: Vec<u8>,
+        alpha_data: Option<Vec<u8>>,
+    },
+    Lossless(swf::DefineBitsLossless),
+}
+
+impl BitmapSource {
+    pub fn decode(&self) -> Result<BitmapData, Box<dyn std::error::Error>> {
+        match self {
+            BitmapSource::Jpeg { data, alpha_data } => {
+                let bitmap = decode_define_bits_jpeg(data, alpha_data.as_deref())?;
+                Ok(BitmapData::from_bitmap(bitmap, true))
+            }
+            BitmapSource::Lossless(swf_tag) => {
+                let bitmap = decode_define_bits_lossless(swf_tag)?;
+                let mut bitmap_data = BitmapData::from_bitmap(bitmap, true);
+                // Only DefineBitsLossless2 tags have an alpha channel.
+                bitmap_data.transparent = swf_tag.version >= 2;
+                Ok(bitmap_data)
+            }
+        }
+    }
+}
+
+/// Blends a color channel of a source over the same channel of the color below it,
+/// both between 0 and 1.
+fn blend_channel(blend_mode: BlendMode, s: f32, d: f32) -> f32 {
+    let hard_light = |s: f32, d: f32| {
+        if s <= 0.5 {
+            2.0 * s * d
+        } else {
+            1.0 - 2.0 * (1.0 - s) * (1.0 - d)
+        }
+    };
+    match blend_mode {
+        BlendMode::Multiply => s * d,
+        BlendMode::Screen => s + d - s * d,
+        BlendMode::Lighten => s.max(d),
+        BlendMode::Darken => s.min(d),
+        BlendMode::Difference => (s - d).abs(),
+        BlendMode::Add => (s + d).min(1.0),
+        BlendMode::Subtract => (d - s).max(0.0),
+        BlendMode::Invert => 1.0 - d,
+        BlendMode::Overlay => hard_light(d, s),
+        BlendMode::HardLight => hard_light(s, d),
+        BlendMode::Normal | BlendMode::Layer | BlendMode::Alpha | BlendMode::Erase => s,
+    }
+}
+
+/// Applies a color transform to an ARGB color.
+fn apply_color_transform(color: u32, color_transform: &ColorTransform) -> u32 {
+    if color_transform.is_identity() {
+        return color;
+    }
+    let channel = |shift: u32, mult: f32, add: f32| {
+        let value = ((color >> shift) & 0xFF) as f32;
+        let value = value * mult + add * 255.0;
+        (value.max(0.0).min(255.0) as u32) << shift
+    };
+    channel(24, color_transform.a_mult, color_transform.a_add)
+        | channel(16, color_transform.r_mult, color_transform.r_add)
+        | channel(8, color_transform.g_mult, color_transform.g_add)
+        | channel(0, color_transform.b_mult, color_transform.b_add)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use swf::Twips;
+
+    #[test]
+    fn opaque_bitmaps_ignore_alpha() {
+        let mut bitmap = BitmapData::new(2, 2, false, 0x00FF_0000);
+        assert_eq!(bitmap.get_pixel32(0, 0), Some(0xFFFF_0000));
+        bitmap.set_pixel32(1, 1, 0x1200_00FF);
+        assert_eq!(bitmap.get_pixel32(1, 1), Some(0xFF00_00FF));
+        assert_eq!(bitmap.get_pixel32(2, 0), None);
+        assert_eq!(bitmap.get_pixel32(-1, 0), None);
+    }
+
+    #[test]
+    fn fill_rect_is_clipped() {
+        let mut bitmap = BitmapData::new(4, 4, true, 0);
+        bitmap.fill_rect(-2, 2, 4, 10, 0xFF00_FF00);
+        assert_eq!(bitmap.get_pixel32(0, 2), Some(0xFF00_FF00));
+        assert_eq!(bitmap.get_pixel32(1, 3), Some(0xFF00_FF00));
+        assert_eq!(bitmap.get_pixel32(2, 2), Some(0));
+        assert_eq!(bitmap.get_pixel32(0, 1), Some(0));
+    }
+
+    #[test]
+    fn copy_pixels_merges_alpha() {
+        let source = BitmapData::new(2, 2, true, 0x80FF_FFFF);
+        let mut dest = BitmapData::new(3, 3, false, 0xFF00_0000);
+        dest.copy_pixels(&source, (0, 0, 2, 2), (1, 1), true);
+        assert_eq!(dest.get_pixel32(0, 0), Some(0xFF00_0000));
+        assert_eq!(dest.get_pixel32(1, 1), Some(0xFF80_8080));
+
+        dest.copy_pixels(&source, (0, 0, 2, 2), (0, 0), false);
+        assert_eq!(dest.get_pixel32(0, 0), Some(0xFFFF_FFFF));
+    }
+
+    #[test]
+    fn draw_with_matrix() {
+        let mut source = BitmapData::new(2, 1, true, 0xFFFF_0000);
+        source.set_pixel32(1, 0, 0xFF00_00FF);
+        let mut dest = BitmapData::new(4, 2, true, 0);
+        let matrix = Matrix {
+            a: 2.0,
+            d: 2.0,
+            ..Default::default()
+        };
+        dest.draw(&source, &matrix, &Default::default(), &Default::default());
+        assert_eq!(dest.get_pixel32(1, 1), Some(0xFFFF_0000));
+        assert_eq!(dest.get_pixel32(2, 0), Some(0xFF00_00FF));
+
+        let translate = Matrix::translate(Twips::from_pixels(3.0), Twips::from_pixels(1.0));
+        let mut dest = BitmapData::new(4, 2, true, 0);
+        dest.draw(
+            &source,
+            &translate,
+            &Default::default(),
+            &Default::default(),
+        );
+        assert_eq!(dest.get_pixel32(3, 1), Some(0xFFFF_0000));
+        assert_eq!(dest.get_pixel32(0, 0), Some(0));
+    }
+
+    #[test]
+    fn draw_with_options() {
+        let source = BitmapData::new(4, 4, true, 0xFF80_8080);
+        let mut dest = BitmapData::new(4, 4, false, 0xFF40_FF00);
+        let options = DrawOptions {
+            blend_mode: BlendMode::Multiply,
+            clip_rect: Some((1, 1, 2, 10)),
+            smoothing: false,
+        };
+        dest.draw(&source, &Matrix::identity(), &Default::default(), &options);
+        assert_eq!(dest.get_pixel32(1, 1), Some(0xFF20_8000));
+        assert_eq!(dest.get_pixel32(2, 3), Some(0xFF20_8000));
+        assert_eq!(dest.get_pixel32(0, 1), Some(0xFF40_FF00));
+        assert_eq!(dest.get_pixel32(3, 3), Some(0xFF40_FF00));
+        assert_eq!(dest.get_pixel32(1, 0), Some(0xFF40_FF00));
+
+        let options = DrawOptions {
+            blend_mode: BlendMode::Subtract,
+            ..Default::default()
+        };
+        dest.draw(&source, &Matrix::identity(), &Default::default(), &options);
+        assert_eq!(dest.get_pixel32(0, 0), Some(0xFF00_7F00));
+
+        // A smoothed source is interpolated between its pixels when scaled.
+        let mut source = BitmapData::new(2, 1, true, 0xFF00_0000);
+        source.set_pixel32(1, 0, 0xFFFF_FFFF);
+        let matrix = Matrix {
+            a: 2.0,
+            d: 2.0,
+            ..Default::default()
+        };
+        let options = DrawOptions {
+            smoothing: true,
+            ..Default::default()
+        };
+        let mut dest = BitmapData::new(4, 1, true, 0);
+        dest.draw(&source, &matrix, &Default::default(), &options);
+        assert_eq!(dest.get_pixel32(0, 0), Some(0xFF00_0000));
+        assert_eq!(dest.get_pixel32(1, 0), Some(0xFF40_4040));
+        assert_eq!(dest.get_pixel32(2, 0), Some(0xFFBF_BFBF));
+        assert_eq!(dest.get_pixel32(3, 0), Some(0xFFFF_FFFF));
+    }
+
+    #[test]
+    fn from_premultiplied_bitmap() {
+        let bitmap = Bitmap {
+            width: 2,
+            height: 1,
+            data: BitmapFormat::Rgba(vec![0x40, 0x20, 0x00, 0x80, 0xFF, 0xFF, 0xFF, 0xFF]),
+        };
+        let bitmap_data = BitmapData::from_bitmap(bitmap, true);
+        assert!(bitmap_data.transparent());
+        assert_eq!(bitmap_data.get_pixel32(0, 0), Some(0x807F_3F00));
+        assert_eq!(bitmap_data.get_pixel32(1, 0), Some(0xFFFF_FFFF));
+
+        let bitmap = Bitmap {
+            width: 1,
+            height: 1,
+            data: BitmapFormat::Rgb(vec![0x12, 0x34, 0x56]),
+        };
+        let bitmap_data = BitmapData::from_bitmap(bitmap, true);
+        assert!(!bitmap_data.transparent());
+        assert_eq!(bitmap_data.get_pixel32(0, 0), Some(0xFF12_3456));
+    }
+
+    #[test]
+    fn dispose_clears_pixels() {
+        let mut bitmap = BitmapData::new(2, 2, true, 0xFFFF_FFFF);
+        bitmap.dispose();
+        assert!(bitmap.disposed());
+        assert_eq!(bitmap.get_pixel32(0, 0), None);
+    }
+}
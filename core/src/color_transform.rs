@@ -37,6 +37,18 @@ impl ColorTransform {
             && self.b_add == 0.0
             && self.a_add == 0.0
     }
+
+    /// Applies this transform to an RGBA color with components from 0 to 1.
+    /// Each component is clamped to that range afterwards, as in Flash.
+    pub fn transform_color(&self, color: [f32; 4]) -> [f32; 4] {
+        let channel = |value: f32, mult: f32, add: f32| (value * mult + add).max(0.0).min(1.0);
+        [
+            channel(color[0], self.r_mult, self.r_add),
+            channel(color[1], self.g_mult, self.g_add),
+            channel(color[2], self.b_mult, self.b_add),
+            channel(color[3], self.a_mult, self.a_add),
+        ]
+    }
 }
 
 impl std::default::Default for ColorTransform {
@@ -73,16 +85,48 @@ impl std::ops::Mul for ColorTransform {
 
 impl std::ops::MulAssign for ColorTransform {
     fn mul_assign(&mut self, rhs: Self) {
-        *self = ColorTransform {
-            r_mult: self.r_mult * rhs.r_mult,
-            g_mult: self.g_mult * rhs.g_mult,
-            b_mult: self.b_mult * rhs.b_mult,
-            a_mult: self.a_mult * rhs.a_mult,
+        *self = *self * rhs;
+    }
+}
 
-            r_add: self.r_mult * rhs.r_add + self.r_add,
-            g_add: self.g_mult * rhs.b_add + self.g_add,
-            b_add: self.b_mult * rhs.g_add + self.b_add,
-            a_add: self.a_mult * rhs.a_add + self.a_add,
-        }
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn mul_assign_matches_mul() {
+        let parent = ColorTransform {
+            r_mult: 0.5,
+            g_mult: 0.25,
+            b_mult: 2.0,
+            a_mult: 0.5,
+            r_add: 0.1,
+            g_add: 0.2,
+            b_add: 0.3,
+            a_add: 0.0,
+        };
+        let child = ColorTransform {
+            r_add: 0.4,
+            g_add: 0.2,
+            b_add: -0.2,
+            ..Default::default()
+        };
+        let mut combined = parent;
+        combined *= child;
+        assert_eq!(combined, parent * child);
+    }
+
+    #[test]
+    fn transform_color_clamps() {
+        let transform = ColorTransform {
+            r_mult: 2.0,
+            a_mult: 0.5,
+            b_add: -0.5,
+            ..Default::default()
+        };
+        assert_eq!(
+            transform.transform_color([0.75, 0.5, 0.25, 1.0]),
+            [1.0, 0.5, 0.0, 0.5]
+        );
     }
 }
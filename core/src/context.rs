@@ -1,17 +1,36 @@
 //! Contexts and helper types passed between functions.
 use crate::avm1;
 
+use crate::avm1::globals::external_interface::ExternalCallbacks;
+use crate::avm1::globals::local_connection::LocalConnections;
+use crate::avm1::globals::net_stream::NetStreams;
+use crate::avm1::globals::print_job::PrintJobs;
 use crate::avm1::globals::system::SystemProperties;
+use crate::avm1::globals::timer::Timers;
+use crate::avm1::globals::xml_socket::XmlSockets;
 use crate::avm1::listeners::SystemListener;
 use crate::avm1::{Object, Value};
+use crate::avm2::{Avm2, Object as Avm2Object, Value as Avm2Value};
+use crate::backend::font::FontBackend;
 use crate::backend::input::InputBackend;
+use crate::backend::log::LogBackend;
 use crate::backend::storage::StorageBackend;
-use crate::backend::{audio::AudioBackend, navigator::NavigatorBackend, render::RenderBackend};
+use crate::backend::video::VideoBackend;
+use crate::backend::{
+    audio::{AudioBackend, AudioManager},
+    navigator::NavigatorBackend,
+    render::RenderBackend,
+};
+use crate::context_menu::ContextMenuState;
 use crate::display_object::EditText;
+use crate::external::{ExternalInterfaceProvider, FsCommandHandler};
+use crate::focus_tracker::FocusTracker;
+use crate::keyboard::KeyboardState;
 use crate::library::Library;
 use crate::loader::LoadManager;
 use crate::player::Player;
 use crate::prelude::*;
+use crate::stage::StageProperties;
 use crate::tag_utils::{SwfMovie, SwfSlice};
 use crate::transform::TransformStack;
 use core::fmt;
@@ -57,9 +76,39 @@ pub struct UpdateContext<'a, 'gc, 'gc_context> {
     /// The audio backend, used by display objects and AVM to play audio.
     pub audio: &'a mut (dyn AudioBackend + 'a),
 
+    /// The sounds started by display objects and AVM1 `Sound` objects.
+    pub audio_manager: &'a mut AudioManager<'gc>,
+
     /// The navigator backend, used by the AVM to make HTTP requests and visit webpages.
     pub navigator: &'a mut (dyn NavigatorBackend + 'a),
 
+    /// The socket connections of AVM1 `XMLSocket` objects.
+    pub xml_sockets: &'a mut XmlSockets<'gc>,
+
+    /// The connection names and messages of AVM1 `LocalConnection` objects.
+    pub local_connections: &'a mut LocalConnections<'gc>,
+
+    /// The timers registered by AVM1 `setInterval` and `setTimeout`.
+    pub timers: &'a mut Timers<'gc>,
+
+    /// The host of the movie, called by AVM1 `ExternalInterface`.
+    pub external_interface: &'a mut Option<Box<dyn ExternalInterfaceProvider>>,
+
+    /// The functions exposed to the host by AVM1 `ExternalInterface.addCallback`.
+    pub external_callbacks: &'a mut ExternalCallbacks<'gc>,
+
+    /// The print job started by AVM1 `PrintJob.start`.
+    pub print_jobs: &'a mut PrintJobs<'gc>,
+
+    /// The streams played by AVM1 `NetStream` objects.
+    pub net_streams: &'a mut NetStreams<'gc>,
+
+    /// The context menu currently shown by the frontend.
+    pub current_context_menu: &'a mut Option<ContextMenuState<'gc>>,
+
+    /// Handles the `fscommand`s sent by the movie.
+    pub fs_command_handler: &'a mut Option<Box<FsCommandHandler>>,
+
     /// The renderer, used by the display objects to draw themselves.
     pub renderer: &'a mut dyn RenderBackend,
 
@@ -69,6 +118,15 @@ pub struct UpdateContext<'a, 'gc, 'gc_context> {
     /// The storage backend, used for storing persistent state
     pub storage: &'a mut dyn StorageBackend,
 
+    /// The font backend, used to look up device fonts.
+    pub fonts: &'a mut dyn FontBackend,
+
+    /// The video backend, used to decode the frames of videos.
+    pub video: &'a mut dyn VideoBackend,
+
+    /// The log backend, used to output the messages traced by the movie.
+    pub log: &'a mut dyn LogBackend,
+
     /// The RNG, used by the AVM `RandomNumber` opcode,  `Math.random(),` and `random()`.
     pub rng: &'a mut SmallRng,
 
@@ -79,18 +137,34 @@ pub struct UpdateContext<'a, 'gc, 'gc_context> {
     /// new built-in objects.
     pub system_prototypes: avm1::SystemPrototypes<'gc>,
 
+    /// The AVM2 interpreter, which runs the ABC code of AS3 movies.
+    pub avm2: &'a mut Avm2<'gc>,
+
     /// The display object that the mouse is currently hovering over.
     pub mouse_hovered_object: Option<DisplayObject<'gc>>,
 
+    /// The display object that the mouse button was pressed over, which receives the
+    /// release events even if the mouse moves off of it first.
+    pub mouse_pressed_object: Option<DisplayObject<'gc>>,
+
     /// The location of the mouse when it was last over the player.
     pub mouse_position: &'a (Twips, Twips),
 
+    /// The keys held down, and the last key pressed.
+    pub keyboard: &'a KeyboardState,
+
     /// The object being dragged via a `startDrag` action.
     pub drag_object: &'a mut Option<crate::player::DragObject<'gc>>,
 
+    /// The object with keyboard focus.
+    pub focus_tracker: &'a mut FocusTracker<'gc>,
+
     /// The dimensions of the stage.
     pub stage_size: (Twips, Twips),
 
+    /// The scale mode and alignment of the stage, and the viewport it is displayed in.
+    pub stage: &'a mut StageProperties,
+
     /// Weak reference to the player.
     ///
     /// Recipients of an update context may upgrade the reference to ensure
@@ -137,19 +211,27 @@ unsafe impl<'gc> Collect for QueuedActions<'gc> {
 }
 
 /// Action and gotos need to be queued up to execute at the end of the frame.
+///
+/// Timelines are advanced first, queueing their work here as they place objects
+/// and reach `DoAction` tags. The queue is then drained by priority: all pending
+/// work of a higher priority runs before any work of a lower one, and work of the
+/// same priority runs in the order it was queued.
 pub struct ActionQueue<'gc> {
-    change_prototype_queue: VecDeque<QueuedActions<'gc>>,
-    action_queue: VecDeque<QueuedActions<'gc>>,
+    /// Each priority is kept in a separate bucket, indexed by `ActionType::priority`.
+    action_queue: [VecDeque<QueuedActions<'gc>>; ActionQueue::NUM_PRIORITIES],
 }
 
 impl<'gc> ActionQueue<'gc> {
     const DEFAULT_CAPACITY: usize = 32;
+    const NUM_PRIORITIES: usize = 2;
 
     /// Crates a new `ActionQueue` with an empty queue.
     pub fn new() -> Self {
         Self {
-            change_prototype_queue: VecDeque::with_capacity(Self::DEFAULT_CAPACITY),
-            action_queue: VecDeque::with_capacity(Self::DEFAULT_CAPACITY),
+            action_queue: [
+                VecDeque::with_capacity(Self::DEFAULT_CAPACITY),
+                VecDeque::with_capacity(Self::DEFAULT_CAPACITY),
+            ],
         }
     }
 
@@ -162,29 +244,20 @@ impl<'gc> ActionQueue<'gc> {
         action_type: ActionType<'gc>,
         is_unload: bool,
     ) {
-        // Prototype change goes a higher priority queue.
-        if let ActionType::Construct { .. } = action_type {
-            self.change_prototype_queue.push_back(QueuedActions {
-                clip,
-                action_type,
-                is_unload,
-            })
-        } else {
-            self.action_queue.push_back(QueuedActions {
-                clip,
-                action_type,
-                is_unload,
-            })
-        }
+        let priority = action_type.priority();
+        self.action_queue[priority].push_back(QueuedActions {
+            clip,
+            action_type,
+            is_unload,
+        })
     }
 
-    /// Sorts and drains the actions from the queue.
+    /// Pops the next action to run, taking from the highest priority bucket that isn't empty.
     pub fn pop_action(&mut self) -> Option<QueuedActions<'gc>> {
-        if !self.change_prototype_queue.is_empty() {
-            self.change_prototype_queue.pop_front()
-        } else {
-            self.action_queue.pop_front()
-        }
+        self.action_queue
+            .iter_mut()
+            .rev()
+            .find_map(|queue| queue.pop_front())
     }
 }
 
@@ -197,8 +270,7 @@ impl<'gc> Default for ActionQueue<'gc> {
 unsafe impl<'gc> Collect for ActionQueue<'gc> {
     #[inline]
     fn trace(&self, cc: gc_arena::CollectionContext) {
-        self.change_prototype_queue.iter().for_each(|o| o.trace(cc));
-        self.action_queue.iter().for_each(|o| o.trace(cc));
+        self.action_queue.iter().flatten().for_each(|o| o.trace(cc));
     }
 }
 
@@ -245,6 +317,26 @@ pub enum ActionType<'gc> {
         method: &'static str,
         args: Vec<Value<'gc>>,
     },
+
+    /// An AVM2 function, such as a frame script registered with `addFrameScript`.
+    Callable2 {
+        callable: Avm2Object<'gc>,
+        receiver: Option<Avm2Object<'gc>>,
+        args: Vec<Avm2Value<'gc>>,
+    },
+}
+
+impl ActionType<'_> {
+    /// The priority of this action in the `ActionQueue`. Higher priorities run first.
+    ///
+    /// Constructors must run before any frame or event actions, so that scripts
+    /// always see clips with their registered class.
+    fn priority(&self) -> usize {
+        match self {
+            ActionType::Construct { .. } => 1,
+            _ => 0,
+        }
+    }
 }
 
 impl fmt::Debug for ActionType<'_> {
@@ -278,6 +370,16 @@ impl fmt::Debug for ActionType<'_> {
                 .field("method", method)
                 .field("args", args)
                 .finish(),
+            ActionType::Callable2 {
+                callable,
+                receiver,
+                args,
+            } => f
+                .debug_struct("ActionType::Callable2")
+                .field("callable", callable)
+                .field("receiver", receiver)
+                .field("args", args)
+                .finish(),
         }
     }
 }
@@ -296,6 +398,15 @@ unsafe impl<'gc> Collect for ActionType<'gc> {
             ActionType::NotifyListeners { args, .. } => {
                 args.trace(cc);
             }
+            ActionType::Callable2 {
+                callable,
+                receiver,
+                args,
+            } => {
+                callable.trace(cc);
+                receiver.trace(cc);
+                args.trace(cc);
+            }
             _ => {}
         }
     }
@@ -0,0 +1,350 @@
+//! The context menu shown when the user right-clicks the movie.
+//!
+//! The frontend asks the player for the items with `Player::prepare_context_menu`,
+//! displays them natively, and reports the chosen item with
+//! `Player::run_context_menu_callback`.
+
+use crate::avm1::activation::Activation;
+use crate::avm1::error::Error;
+use crate::avm1::globals::context_menu::BUILT_IN_ITEMS;
+use crate::avm1::{Object, TObject, Value};
+use crate::context::UpdateContext;
+use crate::display_object::{DisplayObject, TDisplayObject};
+use crate::stage::StageQuality;
+use gc_arena::Collect;
+use swf::Twips;
+
+/// An item of the context menu, as displayed by the frontend.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ContextMenuItem {
+    pub caption: String,
+
+    /// Whether the item can be chosen. Disabled items are shown greyed out.
+    pub enabled: bool,
+
+    /// Whether a separator is shown above the item.
+    pub separator_before: bool,
+
+    /// Whether the item is shown with a check mark, such as "Play" while the movie plays.
+    pub checked: bool,
+}
+
+/// What happens when an item of the context menu is chosen.
+#[derive(Clone)]
+enum ContextMenuCallback<'gc> {
+    Quality(StageQuality),
+    Play,
+    Loop,
+    Rewind,
+    Forward,
+    Back,
+
+    /// Calls the `onSelect` handler of an AVM1 `ContextMenuItem`.
+    Avm1 {
+        item: Object<'gc>,
+    },
+}
+
+/// The context menu currently shown by the frontend.
+pub struct ContextMenuState<'gc> {
+    items: Vec<ContextMenuItem>,
+    callbacks: Vec<ContextMenuCallback<'gc>>,
+
+    /// The display object that was right-clicked, which is passed to the `onSelect` handlers.
+    target: DisplayObject<'gc>,
+}
+
+unsafe impl<'gc> Collect for ContextMenuState<'gc> {
+    fn trace(&self, cc: gc_arena::CollectionContext) {
+        for callback in &self.callbacks {
+            if let ContextMenuCallback::Avm1 { item } = callback {
+                item.trace(cc);
+            }
+        }
+        self.target.trace(cc);
+    }
+}
+
+impl<'gc> ContextMenuState<'gc> {
+    /// Builds the menu for a right-click on the given display object.
+    ///
+    /// The items come from the AVM1 `ContextMenu` set as the `menu` of the object or of
+    /// its closest ancestor, whose `onSelect` handler is called first so that it can
+    /// update them. They are followed by the built-in items that are not hidden.
+    pub fn build(
+        activation: &mut Activation<'_, 'gc>,
+        context: &mut UpdateContext<'_, 'gc, '_>,
+        target: DisplayObject<'gc>,
+    ) -> Result<Self, Error<'gc>> {
+        let mut state = Self {
+            items: Vec::new(),
+            callbacks: Vec::new(),
+            target,
+        };
+        let target_object = target.object();
+
+        let menu = find_menu(activation, context, target)?;
+        if let Some(menu) = menu {
+            if let Value::Object(on_select) = menu.get("onSelect", activation, context)? {
+                on_select.call(
+                    "[Context Menu]",
+                    activation,
+                    context,
+                    menu,
+                    None,
+                    &[target_object.clone(), menu.into()],
+                )?;
+            }
+
+            if let Value::Object(custom_items) = menu.get("customItems", activation, context)? {
+                for i in 0..custom_items.length() {
+                    let item = match custom_items.array_element(i) {
+                        Value::Object(item) => item,
+                        _ => continue,
+                    };
+                    if !get_bool(activation, context, item, "visible", true)? {
+                        continue;
+                    }
+                    let caption = item
+                        .get("caption", activation, context)?
+                        .coerce_to_string(activation, context)?
+                        .to_string();
+                    let enabled = get_bool(activation, context, item, "enabled", true)?;
+                    let separator_before =
+                        get_bool(activation, context, item, "separatorBefore", false)?;
+                    state.push(
+                        caption,
+                        enabled,
+                        separator_before,
+                        false,
+                        ContextMenuCallback::Avm1 { item },
+                    );
+                }
+            }
+        }
+
+        let built_in_items = match menu {
+            Some(menu) => match menu.get("builtInItems", activation, context)? {
+                Value::Object(built_in_items) => Some(built_in_items),
+                _ => None,
+            },
+            None => None,
+        };
+        let mut shown = [true; BUILT_IN_ITEMS.len()];
+        for (name, shown) in BUILT_IN_ITEMS.iter().zip(shown.iter_mut()) {
+            *shown = context.stage.show_menu
+                && match built_in_items {
+                    Some(built_in_items) => {
+                        get_bool(activation, context, built_in_items, name, true)?
+                    }
+                    None => true,
+                };
+        }
+        let is_shown = |name: &str| {
+            BUILT_IN_ITEMS
+                .iter()
+                .position(|built_in| *built_in == name)
+                .map_or(false, |i| shown[i])
+        };
+
+        if is_shown("quality") {
+            let qualities = [
+                ("Low Quality", StageQuality::Low),
+                ("Medium Quality", StageQuality::Medium),
+                ("High Quality", StageQuality::High),
+            ];
+            for (i, (caption, quality)) in qualities.iter().enumerate() {
+                state.push(
+                    caption.to_string(),
+                    true,
+                    i == 0 && !state.items.is_empty(),
+                    context.stage.quality == *quality,
+                    ContextMenuCallback::Quality(*quality),
+                );
+            }
+        }
+
+        // The playback items are only shown for movies with more than one frame.
+        let root = context
+            .levels
+            .get(&0)
+            .and_then(|root| root.as_movie_clip())
+            .filter(|root| root.total_frames() > 1);
+        if let Some(root) = root {
+            let mut separator_before = !state.items.is_empty();
+            if is_shown("play") {
+                state.push(
+                    "Play".to_string(),
+                    true,
+                    separator_before,
+                    root.playing(),
+                    ContextMenuCallback::Play,
+                );
+                separator_before = false;
+            }
+            if is_shown("loop") {
+                state.push(
+                    "Loop".to_string(),
+                    true,
+                    separator_before,
+                    root.looping(),
+                    ContextMenuCallback::Loop,
+                );
+                separator_before = false;
+            }
+            if is_shown("rewind") {
+                state.push(
+                    "Rewind".to_string(),
+                    root.current_frame() > 1,
+                    separator_before,
+                    false,
+                    ContextMenuCallback::Rewind,
+                );
+                separator_before = false;
+            }
+            if is_shown("forward_back") {
+                state.push(
+                    "Forward".to_string(),
+                    root.current_frame() < root.total_frames(),
+                    separator_before,
+                    false,
+                    ContextMenuCallback::Forward,
+                );
+                state.push(
+                    "Back".to_string(),
+                    root.current_frame() > 1,
+                    false,
+                    false,
+                    ContextMenuCallback::Back,
+                );
+            }
+        }
+
+        Ok(state)
+    }
+
+    fn push(
+        &mut self,
+        caption: String,
+        enabled: bool,
+        separator_before: bool,
+        checked: bool,
+        callback: ContextMenuCallback<'gc>,
+    ) {
+        self.items.push(ContextMenuItem {
+            caption,
+            enabled,
+            separator_before,
+            checked,
+        });
+        self.callbacks.push(callback);
+    }
+
+    /// The items of the menu, in the order they are displayed.
+    pub fn items(&self) -> &[ContextMenuItem] {
+        &self.items
+    }
+
+    /// Runs the item at the given index of `items`.
+    /// Disabled items and invalid indices are ignored.
+    pub fn run(
+        &self,
+        index: usize,
+        activation: &mut Activation<'_, 'gc>,
+        context: &mut UpdateContext<'_, 'gc, '_>,
+    ) -> Result<(), Error<'gc>> {
+        match self.items.get(index) {
+            Some(item) if item.enabled => {}
+            _ => return Ok(()),
+        }
+
+        let root = context.levels.get(&0).and_then(|root| root.as_movie_clip());
+        match (&self.callbacks[index], root) {
+            (ContextMenuCallback::Quality(quality), _) => context.stage.quality = *quality,
+            (ContextMenuCallback::Play, Some(root)) => {
+                if root.playing() {
+                    root.stop(context);
+                } else {
+                    root.play(context);
+                }
+            }
+            (ContextMenuCallback::Loop, Some(root)) => {
+                root.set_looping(context.gc_context, !root.looping())
+            }
+            (ContextMenuCallback::Rewind, Some(root)) => {
+                root.goto_frame(activation.avm, context, 1, true)
+            }
+            (ContextMenuCallback::Forward, Some(root)) => root.next_frame(activation.avm, context),
+            (ContextMenuCallback::Back, Some(root)) => root.prev_frame(activation.avm, context),
+            (ContextMenuCallback::Avm1 { item }, _) => {
+                if let Value::Object(on_select) = item.get("onSelect", activation, context)? {
+                    on_select.call(
+                        "[Context Menu]",
+                        activation,
+                        context,
+                        *item,
+                        None,
+                        &[self.target.object(), (*item).into()],
+                    )?;
+                }
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+}
+
+/// Finds the topmost visible display object under a point on the stage,
+/// starting from the given object.
+pub fn object_under_point<'gc>(
+    object: DisplayObject<'gc>,
+    point: (Twips, Twips),
+) -> Option<DisplayObject<'gc>> {
+    if !object.visible() {
+        return None;
+    }
+    let children: Vec<_> = object.children().collect();
+    for child in children.into_iter().rev() {
+        if let Some(result) = object_under_point(child, point) {
+            return Some(result);
+        }
+    }
+    if object.hit_test_shape(point) {
+        Some(object)
+    } else {
+        None
+    }
+}
+
+/// Finds the AVM1 `ContextMenu` set as the `menu` of a display object or its closest
+/// ancestor.
+fn find_menu<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    target: DisplayObject<'gc>,
+) -> Result<Option<Object<'gc>>, Error<'gc>> {
+    let mut node = Some(target);
+    while let Some(display_object) = node {
+        if let Value::Object(object) = display_object.object() {
+            if let Value::Object(menu) = object.get("menu", activation, context)? {
+                return Ok(Some(menu));
+            }
+        }
+        node = display_object.parent();
+    }
+    Ok(None)
+}
+
+/// Reads a boolean property of a menu or menu item, or `default` if it was never set.
+fn get_bool<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    object: Object<'gc>,
+    name: &str,
+    default: bool,
+) -> Result<bool, Error<'gc>> {
+    Ok(match object.get(name, activation, context)? {
+        Value::Undefined => default,
+        value => value.as_bool(activation.current_swf_version()),
+    })
+}
@@ -1,13 +1,18 @@
 use crate::avm1::{Avm1, Object, TObject, Value};
+use crate::avm2::{Object as Avm2Object, Value as Avm2Value};
+use crate::backend::audio::SoundTransform;
+use crate::backend::render::BitmapCacheHandle;
 use crate::context::{RenderContext, UpdateContext};
+use crate::drawing::Drawing;
 use crate::player::NEWEST_PLAYER_VERSION;
 use crate::prelude::*;
+use crate::shape_utils::DrawCommand;
 use crate::tag_utils::SwfMovie;
 use crate::transform::Transform;
 use enumset::{EnumSet, EnumSetType};
 use gc_arena::{Collect, MutationContext};
 use ruffle_macros::enum_trait_object;
-use std::cell::{Ref, RefMut};
+use std::cell::{Cell, Ref, RefCell, RefMut};
 use std::cmp::min;
 use std::fmt::Debug;
 use std::sync::Arc;
@@ -19,16 +24,18 @@ mod graphic;
 mod morph_shape;
 mod movie_clip;
 mod text;
+mod video;
 
 use crate::avm1::activation::Activation;
 use crate::events::{ClipEvent, ClipEventResult};
 pub use bitmap::Bitmap;
 pub use button::Button;
-pub use edit_text::{AutoSizeMode, EditText};
+pub use edit_text::{AutoSizeMode, EditText, TextSelection};
 pub use graphic::Graphic;
 pub use morph_shape::{MorphShape, MorphShapeStatic};
 pub use movie_clip::MovieClip;
 pub use text::Text;
+pub use video::Video;
 
 #[derive(Clone, Debug)]
 pub struct DisplayObjectBase<'gc> {
@@ -57,8 +64,40 @@ pub struct DisplayObjectBase<'gc> {
     /// The next sibling of this display object in order of execution.
     next_sibling: Option<DisplayObject<'gc>>,
 
+    /// The display object masking this object, set by `setMask`.
+    masker: Option<DisplayObject<'gc>>,
+
+    /// The display object that this object is masking, set by `setMask`.
+    maskee: Option<DisplayObject<'gc>>,
+
+    /// The bitmap filters applied to this object when rendering.
+    filters: Vec<swf::Filter>,
+
+    /// The blend mode used when compositing this object onto its parent.
+    blend_mode: swf::BlendMode,
+
+    /// The color drawn behind this object, set by `opaqueBackground`.
+    opaque_background: Option<Color>,
+
+    /// The offscreen image of this object, used when it is cached as a bitmap.
+    bitmap_cache: BitmapCache,
+
+    /// The visible region of this object in its own coordinate space, set by `scrollRect`.
+    scroll_rect: Option<BoundingBox>,
+
+    /// The shape used to clip this object to its `scrollRect`.
+    scroll_rect_mask: Drawing,
+
+    /// The volume and panning of sounds played by this object and its children,
+    /// set by the AVM1 `Sound` object.
+    sound_transform: SoundTransform,
+
     /// Bit flags for various display object properites.
     flags: EnumSet<DisplayObjectFlags>,
+
+    /// Whether this object has changed since the stage was last rendered.
+    /// New objects start out dirty.
+    dirty: Cell<bool>,
 }
 
 impl<'gc> Default for DisplayObjectBase<'gc> {
@@ -77,7 +116,17 @@ impl<'gc> Default for DisplayObjectBase<'gc> {
             first_child: None,
             prev_sibling: None,
             next_sibling: None,
+            masker: None,
+            maskee: None,
+            filters: Vec::new(),
+            blend_mode: swf::BlendMode::Normal,
+            opaque_background: None,
+            bitmap_cache: Default::default(),
+            scroll_rect: None,
+            scroll_rect_mask: Drawing::new(),
+            sound_transform: Default::default(),
             flags: DisplayObjectFlags::Visible.into(),
+            dirty: Cell::new(true),
         }
     }
 }
@@ -89,6 +138,8 @@ unsafe impl<'gc> Collect for DisplayObjectBase<'gc> {
         self.first_child.trace(cc);
         self.prev_sibling.trace(cc);
         self.next_sibling.trace(cc);
+        self.masker.trace(cc);
+        self.maskee.trace(cc);
     }
 }
 
@@ -98,6 +149,7 @@ impl<'gc> DisplayObjectBase<'gc> {
     fn reset_for_movie_load(&mut self) {
         self.first_child = None;
         self.flags = DisplayObjectFlags::Visible.into();
+        self.dirty.set(true);
     }
 
     fn id(&self) -> CharacterId {
@@ -123,9 +175,11 @@ impl<'gc> DisplayObjectBase<'gc> {
         &self.transform.matrix
     }
     fn matrix_mut(&mut self, _context: MutationContext<'gc, '_>) -> &mut Matrix {
+        self.dirty.set(true);
         &mut self.transform.matrix
     }
     fn set_matrix(&mut self, _context: MutationContext<'gc, '_>, matrix: &Matrix) {
+        self.dirty.set(true);
         self.transform.matrix = *matrix;
         self.flags.remove(DisplayObjectFlags::ScaleRotationCached);
     }
@@ -133,6 +187,7 @@ impl<'gc> DisplayObjectBase<'gc> {
         &self.transform.color_transform
     }
     fn color_transform_mut(&mut self) -> &mut ColorTransform {
+        self.dirty.set(true);
         &mut self.transform.color_transform
     }
     fn set_color_transform(
@@ -140,6 +195,7 @@ impl<'gc> DisplayObjectBase<'gc> {
         _context: MutationContext<'gc, '_>,
         color_transform: &ColorTransform,
     ) {
+        self.dirty.set(true);
         self.transform.color_transform = *color_transform;
     }
     fn x(&self) -> f64 {
@@ -147,6 +203,7 @@ impl<'gc> DisplayObjectBase<'gc> {
     }
     fn set_x(&mut self, value: f64) {
         self.set_transformed_by_script(true);
+        self.dirty.set(true);
         self.transform.matrix.tx = Twips::from_pixels(value)
     }
     fn y(&self) -> f64 {
@@ -154,6 +211,7 @@ impl<'gc> DisplayObjectBase<'gc> {
     }
     fn set_y(&mut self, value: f64) {
         self.set_transformed_by_script(true);
+        self.dirty.set(true);
         self.transform.matrix.ty = Twips::from_pixels(value)
     }
 
@@ -196,6 +254,7 @@ impl<'gc> DisplayObjectBase<'gc> {
 
     fn set_scale(&mut self, scale_x: f32, scale_y: f32, rotation: f32) {
         self.cache_scale_rotation();
+        self.dirty.set(true);
         let mut matrix = &mut self.transform.matrix;
         let rotation = rotation.to_radians();
         let cos_x = f32::cos(rotation);
@@ -216,6 +275,7 @@ impl<'gc> DisplayObjectBase<'gc> {
     fn set_rotation(&mut self, radians: f64) {
         self.set_transformed_by_script(true);
         self.cache_scale_rotation();
+        self.dirty.set(true);
         self.rotation = radians;
         let cos_x = f64::cos(radians);
         let sin_x = f64::sin(radians);
@@ -234,6 +294,7 @@ impl<'gc> DisplayObjectBase<'gc> {
     fn set_scale_x(&mut self, value: f64) {
         self.set_transformed_by_script(true);
         self.cache_scale_rotation();
+        self.dirty.set(true);
         self.scale_x = value;
         let cos = f64::cos(self.rotation);
         let sin = f64::sin(self.rotation);
@@ -248,6 +309,7 @@ impl<'gc> DisplayObjectBase<'gc> {
     fn set_scale_y(&mut self, value: f64) {
         self.set_transformed_by_script(true);
         self.cache_scale_rotation();
+        self.dirty.set(true);
         self.scale_y = value;
         let cos = f64::cos(self.rotation + self.skew);
         let sin = f64::sin(self.rotation + self.skew);
@@ -273,6 +335,7 @@ impl<'gc> DisplayObjectBase<'gc> {
         self.clip_depth
     }
     fn set_clip_depth(&mut self, _context: MutationContext<'gc, '_>, depth: Depth) {
+        self.dirty.set(true);
         self.clip_depth = depth;
     }
     fn parent(&self) -> Option<DisplayObject<'gc>> {
@@ -315,6 +378,148 @@ impl<'gc> DisplayObjectBase<'gc> {
     ) {
         self.next_sibling = node;
     }
+    fn masker(&self) -> Option<DisplayObject<'gc>> {
+        self.masker
+    }
+    fn set_masker(&mut self, _context: MutationContext<'gc, '_>, node: Option<DisplayObject<'gc>>) {
+        self.dirty.set(true);
+        self.masker = node;
+    }
+    fn maskee(&self) -> Option<DisplayObject<'gc>> {
+        self.maskee
+    }
+    fn set_maskee(&mut self, _context: MutationContext<'gc, '_>, node: Option<DisplayObject<'gc>>) {
+        self.dirty.set(true);
+        self.maskee = node;
+    }
+    fn filters(&self) -> Vec<swf::Filter> {
+        self.filters.clone()
+    }
+    fn set_filters(&mut self, _context: MutationContext<'gc, '_>, filters: Vec<swf::Filter>) {
+        self.dirty.set(true);
+        self.filters = filters;
+    }
+    fn blend_mode(&self) -> swf::BlendMode {
+        self.blend_mode
+    }
+    fn set_blend_mode(&mut self, _context: MutationContext<'gc, '_>, blend_mode: swf::BlendMode) {
+        self.dirty.set(true);
+        self.blend_mode = blend_mode;
+    }
+    fn cache_as_bitmap(&self) -> bool {
+        self.flags.contains(DisplayObjectFlags::CacheAsBitmap)
+    }
+    fn set_cache_as_bitmap(&mut self, _context: MutationContext<'gc, '_>, value: bool) {
+        if value {
+            self.flags.insert(DisplayObjectFlags::CacheAsBitmap);
+        } else {
+            self.flags.remove(DisplayObjectFlags::CacheAsBitmap);
+        }
+        self.bitmap_cache.invalidate();
+        self.dirty.set(true);
+    }
+    fn lock_root(&self) -> bool {
+        self.flags.contains(DisplayObjectFlags::LockRoot)
+    }
+    fn set_lock_root(&mut self, value: bool) {
+        if value {
+            self.flags.insert(DisplayObjectFlags::LockRoot);
+        } else {
+            self.flags.remove(DisplayObjectFlags::LockRoot);
+        }
+    }
+    fn mouse_enabled(&self) -> bool {
+        !self.flags.contains(DisplayObjectFlags::MouseDisabled)
+    }
+    fn set_mouse_enabled(&mut self, value: bool) {
+        if value {
+            self.flags.remove(DisplayObjectFlags::MouseDisabled);
+        } else {
+            self.flags.insert(DisplayObjectFlags::MouseDisabled);
+        }
+    }
+    fn mouse_children(&self) -> bool {
+        !self
+            .flags
+            .contains(DisplayObjectFlags::MouseChildrenDisabled)
+    }
+    fn set_mouse_children(&mut self, value: bool) {
+        if value {
+            self.flags.remove(DisplayObjectFlags::MouseChildrenDisabled);
+        } else {
+            self.flags.insert(DisplayObjectFlags::MouseChildrenDisabled);
+        }
+    }
+    fn double_click_enabled(&self) -> bool {
+        self.flags.contains(DisplayObjectFlags::DoubleClickEnabled)
+    }
+    fn set_double_click_enabled(&mut self, value: bool) {
+        if value {
+            self.flags.insert(DisplayObjectFlags::DoubleClickEnabled);
+        } else {
+            self.flags.remove(DisplayObjectFlags::DoubleClickEnabled);
+        }
+    }
+    fn is_bitmap_cached(&self) -> bool {
+        // Filters are always rendered from a cached bitmap.
+        self.cache_as_bitmap() || !self.filters.is_empty()
+    }
+    fn opaque_background(&self) -> Option<Color> {
+        self.opaque_background.clone()
+    }
+    fn set_opaque_background(&mut self, _context: MutationContext<'gc, '_>, color: Option<Color>) {
+        self.opaque_background = color;
+        self.bitmap_cache.invalidate();
+        self.dirty.set(true);
+    }
+    fn bitmap_cache(&self) -> &BitmapCache {
+        &self.bitmap_cache
+    }
+    fn scroll_rect(&self) -> Option<BoundingBox> {
+        self.scroll_rect.clone()
+    }
+    fn set_scroll_rect(&mut self, _context: MutationContext<'gc, '_>, rect: Option<BoundingBox>) {
+        // The mask shape is redrawn rather than recreated so that the renderer can reuse it.
+        self.scroll_rect_mask.clear();
+        if let Some(rect) = &rect {
+            self.scroll_rect_mask
+                .set_fill_style(Some(swf::FillStyle::Color(Color {
+                    r: 255,
+                    g: 255,
+                    b: 255,
+                    a: 255,
+                })));
+            self.scroll_rect_mask.draw_command(DrawCommand::MoveTo {
+                x: rect.x_min,
+                y: rect.y_min,
+            });
+            for &(x, y) in &[
+                (rect.x_max, rect.y_min),
+                (rect.x_max, rect.y_max),
+                (rect.x_min, rect.y_max),
+                (rect.x_min, rect.y_min),
+            ] {
+                self.scroll_rect_mask
+                    .draw_command(DrawCommand::LineTo { x, y });
+            }
+        }
+        self.scroll_rect = rect;
+        self.bitmap_cache.invalidate();
+        self.dirty.set(true);
+    }
+    fn scroll_rect_mask(&self) -> &Drawing {
+        &self.scroll_rect_mask
+    }
+    fn sound_transform(&self) -> SoundTransform {
+        self.sound_transform
+    }
+    fn set_sound_transform(
+        &mut self,
+        _context: MutationContext<'gc, '_>,
+        sound_transform: SoundTransform,
+    ) {
+        self.sound_transform = sound_transform;
+    }
     fn removed(&self) -> bool {
         self.flags.contains(DisplayObjectFlags::Removed)
     }
@@ -331,6 +536,7 @@ impl<'gc> DisplayObjectBase<'gc> {
     }
 
     fn set_visible(&mut self, value: bool) {
+        self.dirty.set(true);
         if value {
             self.flags.insert(DisplayObjectFlags::Visible);
         } else {
@@ -350,6 +556,14 @@ impl<'gc> DisplayObjectBase<'gc> {
         }
     }
 
+    fn is_dirty(&self) -> bool {
+        self.dirty.get()
+    }
+
+    fn set_dirty(&self, value: bool) {
+        self.dirty.set(value);
+    }
+
     fn swf_version(&self) -> u8 {
         self.parent
             .map(|p| p.swf_version())
@@ -372,6 +586,7 @@ impl<'gc> DisplayObjectBase<'gc> {
         MorphShape(MorphShape<'gc>),
         MovieClip(MovieClip<'gc>),
         Text(Text<'gc>),
+        Video(Video<'gc>),
     }
 )]
 pub trait TDisplayObject<'gc>: 'gc + Collect + Debug + Into<DisplayObject<'gc>> {
@@ -395,7 +610,7 @@ pub trait TDisplayObject<'gc>: 'gc + Collect + Debug + Into<DisplayObject<'gc>>
 
     /// The local bounding box of this object including children, in its parent's coordinate system.
     fn local_bounds(&self) -> BoundingBox {
-        self.bounds_with_transform(&self.matrix())
+        self.bounds_with_transform(&self.scrolled_matrix())
     }
 
     /// The world bounding box of this object including children, relative to the stage.
@@ -408,9 +623,14 @@ pub trait TDisplayObject<'gc>: 'gc + Collect + Debug + Into<DisplayObject<'gc>>
     /// it to the bounding box. This gives a tighter AABB then if we simply transformed
     /// the overall AABB.
     fn bounds_with_transform(&self, matrix: &Matrix) -> BoundingBox {
+        // Objects with a `scrollRect` are clipped to it.
+        if let Some(scroll_rect) = self.scroll_rect() {
+            return scroll_rect.transform(matrix);
+        }
+
         let mut bounds = self.self_bounds().transform(matrix);
         for child in self.children() {
-            let matrix = *matrix * *child.matrix();
+            let matrix = *matrix * child.scrolled_matrix();
             bounds.union(&child.bounds_with_transform(&matrix));
         }
         bounds
@@ -424,7 +644,6 @@ pub trait TDisplayObject<'gc>: 'gc + Collect + Debug + Into<DisplayObject<'gc>>
     fn matrix_mut(&mut self, context: MutationContext<'gc, '_>) -> RefMut<Matrix>;
     fn set_matrix(&mut self, context: MutationContext<'gc, '_>, matrix: &Matrix);
     fn color_transform(&self) -> Ref<ColorTransform>;
-    fn color_transform_mut(&self, context: MutationContext<'gc, '_>) -> RefMut<ColorTransform>;
     fn set_color_transform(
         &mut self,
         context: MutationContext<'gc, '_>,
@@ -434,9 +653,9 @@ pub trait TDisplayObject<'gc>: 'gc + Collect + Debug + Into<DisplayObject<'gc>>
     /// Returns the matrix for transforming from this object's local space to global stage space.
     fn local_to_global_matrix(&self) -> Matrix {
         let mut node = self.parent();
-        let mut matrix = *self.matrix();
+        let mut matrix = self.scrolled_matrix();
         while let Some(display_object) = node {
-            matrix = *display_object.matrix() * matrix;
+            matrix = display_object.scrolled_matrix() * matrix;
             node = display_object.parent();
         }
 
@@ -446,9 +665,9 @@ pub trait TDisplayObject<'gc>: 'gc + Collect + Debug + Into<DisplayObject<'gc>>
     /// Returns the matrix for transforming from global stage to this object's local space.
     fn global_to_local_matrix(&self) -> Matrix {
         let mut node = self.parent();
-        let mut matrix = *self.matrix();
+        let mut matrix = self.scrolled_matrix();
         while let Some(display_object) = node {
-            matrix = *display_object.matrix() * matrix;
+            matrix = display_object.scrolled_matrix() * matrix;
             node = display_object.parent();
         }
 
@@ -652,6 +871,127 @@ pub trait TDisplayObject<'gc>: 'gc + Collect + Debug + Into<DisplayObject<'gc>>
         node: Option<DisplayObject<'gc>>,
     );
 
+    /// The display object masking this object, set by `setMask`.
+    fn masker(&self) -> Option<DisplayObject<'gc>>;
+    fn set_masker(&mut self, context: MutationContext<'gc, '_>, node: Option<DisplayObject<'gc>>);
+
+    /// The display object that this object is masking, set by `setMask`.
+    fn maskee(&self) -> Option<DisplayObject<'gc>>;
+    fn set_maskee(&mut self, context: MutationContext<'gc, '_>, node: Option<DisplayObject<'gc>>);
+
+    /// The bitmap filters applied to this display object, set by the `filters`
+    /// property or by a `PlaceObject3` tag.
+    fn filters(&self) -> Vec<swf::Filter>;
+    fn set_filters(&mut self, context: MutationContext<'gc, '_>, filters: Vec<swf::Filter>);
+
+    /// The blend mode of this display object, set by the `blendMode` property
+    /// or by a `PlaceObject3` tag.
+    fn blend_mode(&self) -> swf::BlendMode;
+    fn set_blend_mode(&mut self, context: MutationContext<'gc, '_>, blend_mode: swf::BlendMode);
+
+    /// Sets whether this display object is cached as a bitmap, by the `cacheAsBitmap`
+    /// property or by a `PlaceObject3` tag.
+    fn set_cache_as_bitmap(&mut self, context: MutationContext<'gc, '_>, value: bool);
+
+    /// Whether this display object is rendered from a cached bitmap.
+    /// This is the case if `cacheAsBitmap` is set or if the object has filters.
+    fn is_bitmap_cached(&self) -> bool;
+
+    /// Whether `_root` refers to this display object for code running inside it,
+    /// rather than to the root of the level. Set by the `_lockroot` property, which
+    /// lets a loaded movie keep working when hosted by another movie.
+    fn lock_root(&self) -> bool;
+    fn set_lock_root(&mut self, context: MutationContext<'gc, '_>, value: bool);
+
+    /// Whether this object is the target of mouse events that hit it, set by the AS3
+    /// `mouseEnabled` property. Events go to its nearest enabled ancestor otherwise.
+    fn mouse_enabled(&self) -> bool;
+    fn set_mouse_enabled(&mut self, context: MutationContext<'gc, '_>, value: bool);
+
+    /// Whether the descendants of this object are the targets of mouse events that hit them,
+    /// set by the AS3 `mouseChildren` property. This object receives those events otherwise.
+    fn mouse_children(&self) -> bool;
+    fn set_mouse_children(&mut self, context: MutationContext<'gc, '_>, value: bool);
+
+    /// Whether this object receives `doubleClick` events, set by the AS3
+    /// `doubleClickEnabled` property.
+    fn double_click_enabled(&self) -> bool;
+    fn set_double_click_enabled(&mut self, context: MutationContext<'gc, '_>, value: bool);
+
+    /// The color filling the bounds of this display object behind its content, set by the
+    /// `opaqueBackground` property or by a `PlaceObject3` tag.
+    fn opaque_background(&self) -> Option<Color>;
+    fn set_opaque_background(&mut self, context: MutationContext<'gc, '_>, color: Option<Color>);
+
+    /// The offscreen image used to render this display object when it is cached as a bitmap.
+    fn bitmap_cache(&self) -> Ref<BitmapCache>;
+
+    /// The region of this display object that is visible, set by the `scrollRect` property.
+    /// The contents of the object are clipped to this rectangle, and offset so that its
+    /// top-left corner is at the object's origin.
+    fn scroll_rect(&self) -> Option<BoundingBox>;
+    fn set_scroll_rect(&mut self, context: MutationContext<'gc, '_>, rect: Option<BoundingBox>);
+
+    /// The shape covering the `scrollRect` of this display object, used to clip it when rendering.
+    fn scroll_rect_mask(&self) -> Ref<Drawing>;
+
+    /// The volume and panning applied to sounds played by this display object and its children.
+    /// The sound transforms of all parents also apply.
+    fn sound_transform(&self) -> SoundTransform;
+    fn set_sound_transform(
+        &mut self,
+        context: MutationContext<'gc, '_>,
+        sound_transform: SoundTransform,
+    );
+
+    /// The matrix transforming from the coordinate space of this object's contents to its
+    /// parent's coordinate space. This is the object's matrix, offset by its `scrollRect`.
+    fn scrolled_matrix(&self) -> Matrix {
+        let matrix = *self.matrix();
+        if let Some(scroll_rect) = self.scroll_rect() {
+            matrix
+                * Matrix::translate(
+                    Twips::zero() - scroll_rect.x_min,
+                    Twips::zero() - scroll_rect.y_min,
+                )
+        } else {
+            matrix
+        }
+    }
+
+    /// Marks the cached bitmaps of this object and its ancestors as stale, so that they
+    /// are re-rendered the next time they are drawn, and marks this object as dirty.
+    /// Called whenever the appearance of this object changes.
+    fn invalidate_cached_bitmap(&self) {
+        self.set_dirty(true);
+        self.bitmap_cache().invalidate();
+        let mut parent = self.parent();
+        while let Some(ancestor) = parent {
+            ancestor.bitmap_cache().invalidate();
+            parent = ancestor.parent();
+        }
+    }
+
+    /// Frees the offscreen image of this object from the renderer.
+    fn release_bitmap_cache(&self, context: &mut UpdateContext<'_, 'gc, '_>) {
+        if let Some(handle) = self.bitmap_cache().take_handle() {
+            context.renderer.release_bitmap_cache(handle);
+        }
+    }
+
+    /// Breaks any `setMask` relationship this object takes part in, either as
+    /// the masker or as the maskee.
+    fn clear_mask_relationships(&mut self, context: MutationContext<'gc, '_>) {
+        if let Some(mut masker) = self.masker() {
+            masker.set_maskee(context, None);
+            self.set_masker(context, None);
+        }
+        if let Some(mut maskee) = self.maskee() {
+            maskee.set_masker(context, None);
+            self.set_maskee(context, None);
+        }
+    }
+
     /// Iterates over the children of this display object in execution order.
     /// This is different than render order.
     fn children(&self) -> ChildIter<'gc> {
@@ -662,7 +1002,6 @@ pub trait TDisplayObject<'gc>: 'gc + Collect + Debug + Into<DisplayObject<'gc>>
 
     /// Get a child display object by instance name.
     fn get_child_by_name(&self, name: &str, case_sensitive: bool) -> Option<DisplayObject<'gc>> {
-        // TODO: Make a HashMap from name -> child?
         use crate::string_utils::swf_string_eq_ignore_case;
         if case_sensitive {
             self.children().find(|child| &*child.name() == name)
@@ -672,6 +1011,15 @@ pub trait TDisplayObject<'gc>: 'gc + Collect + Debug + Into<DisplayObject<'gc>>
         }
     }
 
+    /// Called after the instance name of one of this object's children changes.
+    fn child_renamed(
+        &self,
+        _gc_context: MutationContext<'gc, '_>,
+        _child: DisplayObject<'gc>,
+        _old_name: &str,
+    ) {
+    }
+
     /// Get another level by level name.
     ///
     /// Since levels don't have instance names, this function instead parses
@@ -700,6 +1048,27 @@ pub trait TDisplayObject<'gc>: 'gc + Collect + Debug + Into<DisplayObject<'gc>>
     fn removed(&self) -> bool;
     fn set_removed(&mut self, context: MutationContext<'gc, '_>, value: bool);
 
+    /// Whether this display object has been removed from the display list for good.
+    ///
+    /// Script references to a dangling object are dead: its properties read as `undefined`
+    /// and its methods do nothing, even if a new object is placed at the same path.
+    /// Unlike `removed`, this is false while a removed clip is still kept on the display
+    /// list for its unload handlers.
+    fn dangling(&self) -> bool {
+        if !self.removed() {
+            return false;
+        }
+        if let Some(parent) = self.parent() {
+            let held_by_parent = parent
+                .as_movie_clip()
+                .and_then(|parent| parent.child_by_depth(self.depth()))
+                .map_or(false, |child| child.as_ptr() == self.as_ptr());
+            !held_by_parent || parent.dangling()
+        } else {
+            true
+        }
+    }
+
     /// Whether this display object is visible.
     /// Invisible objects are not rendered, but otherwise continue to exist normally.
     /// Returned by the `_visible`/`visible` ActionScript properties.
@@ -718,6 +1087,28 @@ pub trait TDisplayObject<'gc>: 'gc + Collect + Debug + Into<DisplayObject<'gc>>
     /// When this flag is set, changes from SWF `PlaceObject` tags are ignored.
     fn set_transformed_by_script(&self, context: MutationContext<'gc, '_>, value: bool);
 
+    /// Whether this display object has changed since the stage was last rendered.
+    /// This does not include changes to its children; see `is_tree_dirty`.
+    fn is_dirty(&self) -> bool;
+
+    /// Sets whether this display object has changed since the stage was last rendered.
+    fn set_dirty(&self, value: bool);
+
+    /// Whether this display object or any of its descendants has changed since the stage
+    /// was last rendered. When nothing on the stage is dirty, the player skips rendering.
+    fn is_tree_dirty(&self) -> bool {
+        self.is_dirty() || self.children().any(|child| child.is_tree_dirty())
+    }
+
+    /// Marks this display object and all of its descendants as clean.
+    /// Called after the stage has been rendered.
+    fn clear_dirty_tree(&self) {
+        self.set_dirty(false);
+        for child in self.children() {
+            child.clear_dirty_tree();
+        }
+    }
+
     /// Executes and propagates the given clip event.
     /// Events execute inside-out; the deepest child will react first, followed by its parent, and
     /// so forth.
@@ -746,6 +1137,15 @@ pub trait TDisplayObject<'gc>: 'gc + Collect + Debug + Into<DisplayObject<'gc>>
             }
         }
 
+        // Removed objects lose focus.
+        if let Value::Object(object) = self.object() {
+            if let Some(display_object) = object.as_display_object() {
+                context.focus_tracker.remove(display_object);
+            }
+        }
+
+        self.clear_mask_relationships(context.gc_context);
+        self.release_bitmap_cache(context);
         self.set_removed(context.gc_context, true);
     }
 
@@ -755,12 +1155,24 @@ pub trait TDisplayObject<'gc>: 'gc + Collect + Debug + Into<DisplayObject<'gc>>
     fn as_movie_clip(&self) -> Option<MovieClip<'gc>> {
         None
     }
+    fn as_bitmap(&self) -> Option<Bitmap<'gc>> {
+        None
+    }
     fn as_edit_text(&self) -> Option<EditText<'gc>> {
         None
     }
     fn as_morph_shape(&self) -> Option<MorphShape<'gc>> {
         None
     }
+    fn as_video(&self) -> Option<Video<'gc>> {
+        None
+    }
+
+    /// The drawing API graphics of this object, if it can be drawn on by scripts.
+    fn as_drawing(&self, _gc_context: MutationContext<'gc, '_>) -> Option<RefMut<'_, Drawing>> {
+        None
+    }
+
     fn apply_place_object(
         &mut self,
         gc_context: MutationContext<'gc, '_>,
@@ -780,13 +1192,24 @@ pub trait TDisplayObject<'gc>: 'gc + Collect + Debug + Into<DisplayObject<'gc>>
             if let Some(clip_depth) = place_object.clip_depth {
                 self.set_clip_depth(gc_context, clip_depth.into());
             }
+            if place_object.version >= 3 {
+                self.set_filters(gc_context, place_object.filters.clone());
+                self.set_blend_mode(gc_context, place_object.blend_mode);
+                self.set_cache_as_bitmap(gc_context, place_object.is_bitmap_cached);
+                self.set_opaque_background(gc_context, place_object.background_color.clone());
+            }
             if let Some(ratio) = place_object.ratio {
                 if let Some(mut morph_shape) = self.as_morph_shape() {
                     morph_shape.set_ratio(gc_context, ratio);
                 }
+                // The ratio of a video is the embedded frame that it shows.
+                if let Some(video) = self.as_video() {
+                    video.set_ratio(gc_context, ratio);
+                }
             }
-            // Clip events only apply to movie clips.
-            if let Some(clip) = self.as_movie_clip() {
+            // Clip events only apply to movie clips, and are only given when the clip is placed.
+            let is_modify = matches!(place_object.action, swf::PlaceObjectAction::Modify);
+            if let Some(clip) = self.as_movie_clip().filter(|_| !is_modify) {
                 // Convert from `swf::ClipAction` to Ruffle's `ClipAction`.
                 use crate::display_object::movie_clip::ClipAction;
                 clip.set_clip_actions(
@@ -827,11 +1250,50 @@ pub trait TDisplayObject<'gc>: 'gc + Collect + Debug + Into<DisplayObject<'gc>>
         Value::Undefined // todo: impl for every type and delete this fallback
     }
 
+    /// The AVM2 object that represents this display object, if one has been
+    /// created yet.
+    fn object2(&self) -> Avm2Value<'gc> {
+        Avm2Value::Undefined // todo: impl for every type and delete this fallback
+    }
+
+    /// Set the AVM2 object that represents this display object.
+    fn set_object2(&mut self, _mc: MutationContext<'gc, '_>, _to: Avm2Object<'gc>) {}
+
     /// Tests if a given stage position point intersects with the world bounds of this object.
     fn hit_test(&self, _pos: (Twips, Twips)) -> bool {
         false
     }
 
+    /// Tests if a given stage position point intersects with the actual shape of this object
+    /// or any of its children, rather than its bounding box.
+    /// Used by `MovieClip.hitTest` when `shapeFlag` is set.
+    ///
+    /// Objects without shape data test against their own untransformed bounds.
+    fn hit_test_shape(&self, point: (Twips, Twips)) -> bool {
+        let local_point = self.global_to_local(point);
+        if !self.scroll_rect_contains(point) {
+            return false;
+        }
+        if self.self_bounds().contains(local_point) {
+            return true;
+        }
+        self.children().any(|child| child.hit_test_shape(point))
+    }
+
+    /// Tests if a given stage position point hits the shapes drawn by this object itself,
+    /// not counting its children. Used by `object_under_point`.
+    fn hit_test_self_shape(&self, point: (Twips, Twips)) -> bool {
+        self.hit_test_shape(point)
+    }
+
+    /// Whether a stage position point is inside the `scrollRect` of this object.
+    /// Always true for objects without a `scrollRect`.
+    fn scroll_rect_contains(&self, point: (Twips, Twips)) -> bool {
+        self.scroll_rect().map_or(true, |scroll_rect| {
+            scroll_rect.contains(self.global_to_local(point))
+        })
+    }
+
     fn mouse_pick(
         &self,
         _avm: &mut Avm1<'gc>,
@@ -875,17 +1337,22 @@ pub trait TDisplayObject<'gc>: 'gc + Collect + Debug + Into<DisplayObject<'gc>>
     }
 
     /// Obtain the top-most parent of the display tree hierarchy.
+    /// The search stops early at the nearest object with `_lockroot` set.
     ///
     /// This function can panic in the rare case that a top-level display
     /// object has not been post-instantiated, or that a top-level display
     /// object does not implement `object`.
     fn root(&self) -> DisplayObject<'gc> {
-        let mut parent = self.parent();
+        let mut parent = if self.lock_root() {
+            None
+        } else {
+            self.parent()
+        };
 
         while let Some(p) = parent {
             let grandparent = p.parent();
 
-            if grandparent.is_none() {
+            if grandparent.is_none() || p.lock_root() {
                 break;
             }
 
@@ -967,12 +1434,6 @@ macro_rules! impl_display_object_sansbounds {
         fn color_transform(&self) -> std::cell::Ref<crate::color_transform::ColorTransform> {
             std::cell::Ref::map(self.0.read(), |o| o.$field.color_transform())
         }
-        fn color_transform_mut(
-            &self,
-            context: gc_arena::MutationContext<'gc, '_>,
-        ) -> std::cell::RefMut<crate::color_transform::ColorTransform> {
-            std::cell::RefMut::map(self.0.write(context), |o| o.$field.color_transform_mut())
-        }
         fn set_color_transform(
             &mut self,
             context: gc_arena::MutationContext<'gc, '_>,
@@ -1011,7 +1472,11 @@ macro_rules! impl_display_object_sansbounds {
             std::cell::Ref::map(self.0.read(), |o| o.$field.name())
         }
         fn set_name(&mut self, context: gc_arena::MutationContext<'gc, '_>, name: &str) {
-            self.0.write(context).$field.set_name(context, name)
+            let old_name = self.0.read().$field.name().to_string();
+            self.0.write(context).$field.set_name(context, name);
+            if let Some(parent) = self.parent() {
+                parent.child_renamed(context, (*self).into(), &old_name);
+            }
         }
         fn clip_depth(&self) -> crate::prelude::Depth {
             self.0.read().$field.clip_depth()
@@ -1063,6 +1528,132 @@ macro_rules! impl_display_object_sansbounds {
         ) {
             self.0.write(context).$field.set_next_sibling(context, node);
         }
+        fn masker(&self) -> Option<DisplayObject<'gc>> {
+            self.0.read().$field.masker()
+        }
+        fn set_masker(
+            &mut self,
+            context: gc_arena::MutationContext<'gc, '_>,
+            node: Option<DisplayObject<'gc>>,
+        ) {
+            self.0.write(context).$field.set_masker(context, node);
+        }
+        fn maskee(&self) -> Option<DisplayObject<'gc>> {
+            self.0.read().$field.maskee()
+        }
+        fn set_maskee(
+            &mut self,
+            context: gc_arena::MutationContext<'gc, '_>,
+            node: Option<DisplayObject<'gc>>,
+        ) {
+            self.0.write(context).$field.set_maskee(context, node);
+        }
+        fn filters(&self) -> Vec<swf::Filter> {
+            self.0.read().$field.filters()
+        }
+        fn set_filters(
+            &mut self,
+            context: gc_arena::MutationContext<'gc, '_>,
+            filters: Vec<swf::Filter>,
+        ) {
+            self.0.write(context).$field.set_filters(context, filters);
+        }
+        fn blend_mode(&self) -> swf::BlendMode {
+            self.0.read().$field.blend_mode()
+        }
+        fn set_blend_mode(
+            &mut self,
+            context: gc_arena::MutationContext<'gc, '_>,
+            blend_mode: swf::BlendMode,
+        ) {
+            self.0
+                .write(context)
+                .$field
+                .set_blend_mode(context, blend_mode);
+        }
+        fn set_cache_as_bitmap(
+            &mut self,
+            context: gc_arena::MutationContext<'gc, '_>,
+            value: bool,
+        ) {
+            self.0
+                .write(context)
+                .$field
+                .set_cache_as_bitmap(context, value);
+        }
+        fn is_bitmap_cached(&self) -> bool {
+            self.0.read().$field.is_bitmap_cached()
+        }
+        fn lock_root(&self) -> bool {
+            self.0.read().$field.lock_root()
+        }
+        fn set_lock_root(&mut self, context: gc_arena::MutationContext<'gc, '_>, value: bool) {
+            self.0.write(context).$field.set_lock_root(value);
+        }
+        fn mouse_enabled(&self) -> bool {
+            self.0.read().$field.mouse_enabled()
+        }
+        fn set_mouse_enabled(&mut self, context: gc_arena::MutationContext<'gc, '_>, value: bool) {
+            self.0.write(context).$field.set_mouse_enabled(value);
+        }
+        fn mouse_children(&self) -> bool {
+            self.0.read().$field.mouse_children()
+        }
+        fn set_mouse_children(&mut self, context: gc_arena::MutationContext<'gc, '_>, value: bool) {
+            self.0.write(context).$field.set_mouse_children(value);
+        }
+        fn double_click_enabled(&self) -> bool {
+            self.0.read().$field.double_click_enabled()
+        }
+        fn set_double_click_enabled(
+            &mut self,
+            context: gc_arena::MutationContext<'gc, '_>,
+            value: bool,
+        ) {
+            self.0.write(context).$field.set_double_click_enabled(value);
+        }
+        fn opaque_background(&self) -> Option<swf::Color> {
+            self.0.read().$field.opaque_background()
+        }
+        fn set_opaque_background(
+            &mut self,
+            context: gc_arena::MutationContext<'gc, '_>,
+            color: Option<swf::Color>,
+        ) {
+            self.0
+                .write(context)
+                .$field
+                .set_opaque_background(context, color);
+        }
+        fn bitmap_cache(&self) -> std::cell::Ref<crate::display_object::BitmapCache> {
+            std::cell::Ref::map(self.0.read(), |o| o.$field.bitmap_cache())
+        }
+        fn scroll_rect(&self) -> Option<crate::bounding_box::BoundingBox> {
+            self.0.read().$field.scroll_rect()
+        }
+        fn set_scroll_rect(
+            &mut self,
+            context: gc_arena::MutationContext<'gc, '_>,
+            rect: Option<crate::bounding_box::BoundingBox>,
+        ) {
+            self.0.write(context).$field.set_scroll_rect(context, rect);
+        }
+        fn scroll_rect_mask(&self) -> std::cell::Ref<crate::drawing::Drawing> {
+            std::cell::Ref::map(self.0.read(), |o| o.$field.scroll_rect_mask())
+        }
+        fn sound_transform(&self) -> crate::backend::audio::SoundTransform {
+            self.0.read().$field.sound_transform()
+        }
+        fn set_sound_transform(
+            &mut self,
+            context: gc_arena::MutationContext<'gc, '_>,
+            sound_transform: crate::backend::audio::SoundTransform,
+        ) {
+            self.0
+                .write(context)
+                .$field
+                .set_sound_transform(context, sound_transform);
+        }
         fn removed(&self) -> bool {
             self.0.read().$field.removed()
         }
@@ -1088,6 +1679,12 @@ macro_rules! impl_display_object_sansbounds {
                 .$field
                 .set_transformed_by_script(value)
         }
+        fn is_dirty(&self) -> bool {
+            self.0.read().$field.is_dirty()
+        }
+        fn set_dirty(&self, value: bool) {
+            self.0.read().$field.set_dirty(value)
+        }
         fn swf_version(&self) -> u8 {
             self.0.read().$field.swf_version()
         }
@@ -1159,9 +1756,11 @@ pub fn render_children<'gc>(
             context.renderer.push_mask();
             child.render(context);
             context.renderer.activate_mask();
+        } else if child.maskee().is_some() {
+            // Objects used as a mask by `setMask` are not rendered themselves.
         } else if child.visible() {
             // Normal child.
-            child.render(context);
+            render_with_masker(context, child);
         }
     }
 
@@ -1171,6 +1770,258 @@ pub fn render_children<'gc>(
     }
 }
 
+/// Whether this object can be hit at the given stage point at all, before testing its shapes.
+///
+/// Invisible objects and objects used as masks never hit, and points clipped away by the
+/// object's `scrollRect`, its `setMask` mask, or a timeline mask layer don't hit it.
+pub fn is_hit_candidate<'gc>(object: DisplayObject<'gc>, point: (Twips, Twips)) -> bool {
+    let is_mask = (object.clip_depth() > 0 && object.allow_as_mask()) || object.maskee().is_some();
+    if !object.visible() || is_mask || !object.scroll_rect_contains(point) {
+        return false;
+    }
+
+    if let Some(masker) = object.masker().filter(|masker| masker.allow_as_mask()) {
+        if !masker.hit_test_shape(point) {
+            return false;
+        }
+    }
+
+    // Timeline masks clip the siblings between their own depth and their clip depth.
+    if let Some(parent) = object.parent() {
+        let depth = object.depth();
+        let is_clipped = parent.children().any(|mask| {
+            mask.clip_depth() > 0
+                && mask.allow_as_mask()
+                && mask.depth() < depth
+                && depth < mask.clip_depth()
+                && !mask.hit_test_shape(point)
+        });
+        if is_clipped {
+            return false;
+        }
+    }
+
+    true
+}
+
+/// Finds the topmost object under the given stage point, searching `object` and its descendants.
+///
+/// This is the hit-test query shared by mouse picking and `_droptarget`. Objects that are not
+/// hit candidates are skipped along with their children. Buttons are hit through their hit
+/// area, and a movie clip with a `hitArea` is hit through that clip's shapes instead of its
+/// own. `exclude` and its descendants are never returned, so a dragged clip doesn't find
+/// itself.
+pub fn object_under_point<'gc>(
+    object: DisplayObject<'gc>,
+    point: (Twips, Twips),
+    exclude: Option<DisplayObject<'gc>>,
+) -> Option<DisplayObject<'gc>> {
+    if exclude.map_or(false, |exclude| DisplayObject::ptr_eq(exclude, object))
+        || !is_hit_candidate(object, point)
+    {
+        return None;
+    }
+
+    if object.as_button().is_some() {
+        return Some(object).filter(|button| button.hit_test_self_shape(point));
+    }
+
+    // The hit area may be invisible or elsewhere in the tree; only its shapes matter.
+    if let Some(hit_area) = object.as_movie_clip().and_then(|clip| clip.hit_area()) {
+        return Some(object).filter(|_| hit_area.hit_test_shape(point));
+    }
+
+    let mut children: Vec<_> = object.children().collect();
+    children.sort_by_key(|child| child.depth());
+    for child in children.into_iter().rev() {
+        if let Some(result) = object_under_point(child, point, exclude) {
+            return Some(result);
+        }
+    }
+
+    Some(object).filter(|object| object.hit_test_self_shape(point))
+}
+
+/// Renders a display object, clipping it to the mask set by `setMask` if there is one.
+fn render_with_masker<'gc>(context: &mut RenderContext<'_, 'gc>, child: DisplayObject<'gc>) {
+    let masker = child.masker().filter(|masker| masker.allow_as_mask());
+    if let Some(masker) = masker {
+        // The mask is rendered in its own coordinate space, which may be in a completely
+        // different part of the display tree. Transform from the child's parent space to
+        // the mask's parent space.
+        // The mask's own mask is ignored, as in Flash.
+        let to_global = masker
+            .parent()
+            .map(|p| p.local_to_global_matrix())
+            .unwrap_or_default();
+        let to_local = child
+            .parent()
+            .map(|p| p.global_to_local_matrix())
+            .unwrap_or_default();
+        context.renderer.push_mask();
+        context.transform_stack.push(&crate::transform::Transform {
+            matrix: to_local * to_global,
+            ..Default::default()
+        });
+        masker.render(context);
+        context.transform_stack.pop();
+        context.renderer.activate_mask();
+        render_with_effects(context, child);
+        context.renderer.pop_mask();
+    } else {
+        render_with_effects(context, child);
+    }
+}
+
+/// Renders a display object, applying its blend mode and bitmap filters.
+fn render_with_effects<'gc>(context: &mut RenderContext<'_, 'gc>, child: DisplayObject<'gc>) {
+    // The object is rendered offscreen and then composited with the effects applied.
+    let blend_mode = child.blend_mode();
+    let has_blend_mode = blend_mode != swf::BlendMode::Normal;
+    if has_blend_mode {
+        context.renderer.push_blend_mode(blend_mode);
+    }
+
+    if child.is_bitmap_cached() {
+        render_with_bitmap_cache(context, child);
+    } else {
+        render_with_filters(context, child);
+    }
+
+    if has_blend_mode {
+        context.renderer.pop_blend_mode();
+    }
+}
+
+/// Renders a display object that is cached as a bitmap.
+/// The cached image is reused as long as the object has not been invalidated, none of
+/// its descendants changed, and it is drawn with the same transform.
+fn render_with_bitmap_cache<'gc>(context: &mut RenderContext<'_, 'gc>, child: DisplayObject<'gc>) {
+    let transform = {
+        let parent = context.transform_stack.transform();
+        Transform {
+            matrix: parent.matrix * *child.matrix(),
+            color_transform: parent.color_transform * *child.color_transform(),
+        }
+    };
+
+    let handle = child.bitmap_cache().handle();
+    let handle = handle.unwrap_or_else(|| {
+        let handle = context.renderer.create_bitmap_cache();
+        child.bitmap_cache().set_handle(handle);
+        handle
+    });
+
+    let children_changed = child.children().any(|c| c.is_tree_dirty());
+    if !children_changed
+        && child.bitmap_cache().is_valid_for(&transform)
+        && context.renderer.render_bitmap_cache(handle)
+    {
+        return;
+    }
+
+    context.renderer.begin_bitmap_cache(handle);
+    if let Some(color) = child.opaque_background() {
+        let bounds = child.bounds();
+        if bounds.valid {
+            let rect = swf::Rectangle {
+                x_min: bounds.x_min,
+                x_max: bounds.x_max,
+                y_min: bounds.y_min,
+                y_max: bounds.y_max,
+            };
+            context.renderer.draw_rect(color, &rect, &transform);
+        }
+    }
+    render_with_filters(context, child);
+    context.renderer.end_bitmap_cache();
+    child.bitmap_cache().set_transform(transform);
+}
+
+/// Renders a display object, applying its bitmap filters if it has any.
+fn render_with_filters<'gc>(context: &mut RenderContext<'_, 'gc>, child: DisplayObject<'gc>) {
+    let filters = child.filters();
+    if filters.is_empty() {
+        render_with_scroll_rect(context, child);
+    } else {
+        context.renderer.push_filters(&filters);
+        render_with_scroll_rect(context, child);
+        context.renderer.pop_filters();
+    }
+}
+
+/// Renders a display object, clipping and offsetting it by its `scrollRect` if it has one.
+fn render_with_scroll_rect<'gc>(context: &mut RenderContext<'_, 'gc>, child: DisplayObject<'gc>) {
+    if child.scroll_rect().is_none() {
+        child.render(context);
+        return;
+    }
+
+    let scrolled_matrix = child.scrolled_matrix();
+    context.renderer.push_mask();
+    context.transform_stack.push(&Transform {
+        matrix: scrolled_matrix,
+        ..Default::default()
+    });
+    child.scroll_rect_mask().render(context);
+    context.transform_stack.pop();
+    context.renderer.activate_mask();
+
+    // The child applies its own matrix when it renders, so the scroll offset is
+    // inserted between the child's matrix and its contents.
+    let mut inverse = *child.matrix();
+    inverse.invert();
+    context.transform_stack.push(&Transform {
+        matrix: scrolled_matrix * inverse,
+        ..Default::default()
+    });
+    child.render(context);
+    context.transform_stack.pop();
+    context.renderer.pop_mask();
+}
+
+/// The offscreen image of a display object that is cached as a bitmap.
+/// The image is rendered in stage space, so it is only valid for the transform it was
+/// rendered with. It is also invalidated whenever the object or its children change.
+#[derive(Clone, Debug, Default)]
+pub struct BitmapCache {
+    handle: Cell<Option<BitmapCacheHandle>>,
+
+    /// The transform that the cached image was rendered with,
+    /// or `None` if the image must be re-rendered.
+    transform: RefCell<Option<Transform>>,
+}
+
+impl BitmapCache {
+    pub fn handle(&self) -> Option<BitmapCacheHandle> {
+        self.handle.get()
+    }
+
+    pub fn set_handle(&self, handle: BitmapCacheHandle) {
+        self.handle.set(Some(handle));
+    }
+
+    pub fn take_handle(&self) -> Option<BitmapCacheHandle> {
+        self.invalidate();
+        self.handle.take()
+    }
+
+    /// Whether the cached image is up to date and may be drawn with the given transform.
+    pub fn is_valid_for(&self, transform: &Transform) -> bool {
+        self.transform.borrow().as_ref().map_or(false, |cached| {
+            cached.matrix == transform.matrix && cached.color_transform == transform.color_transform
+        })
+    }
+
+    pub fn set_transform(&self, transform: Transform) {
+        *self.transform.borrow_mut() = Some(transform);
+    }
+
+    pub fn invalidate(&self) {
+        *self.transform.borrow_mut() = None;
+    }
+}
+
 impl<'gc> DisplayObject<'gc> {
     pub fn ptr_eq(a: DisplayObject<'gc>, b: DisplayObject<'gc>) -> bool {
         a.as_ptr() == b.as_ptr()
@@ -1194,6 +2045,22 @@ enum DisplayObjectFlags {
     /// Whether this object has been transformed by ActionScript.
     /// When this flag is set, changes from SWF `PlaceObject` tags are ignored.
     TransformedByScript,
+
+    /// Whether this object is cached as a bitmap (`cacheAsBitmap` property).
+    CacheAsBitmap,
+
+    /// Whether this object is the `_root` of its descendants (`_lockroot` property).
+    LockRoot,
+
+    /// Whether mouse events skip this object (AS3 `mouseEnabled` property, inverted).
+    MouseDisabled,
+
+    /// Whether mouse events skip the descendants of this object (AS3 `mouseChildren`
+    /// property, inverted).
+    MouseChildrenDisabled,
+
+    /// Whether this object receives `doubleClick` events (AS3 `doubleClickEnabled` property).
+    DoubleClickEnabled,
 }
 
 pub struct ChildIter<'gc> {
@@ -2,6 +2,7 @@
 
 use crate::avm1::Avm1;
 use crate::backend::render::BitmapHandle;
+use crate::bitmap_data::BitmapSource;
 use crate::context::{RenderContext, UpdateContext};
 use crate::display_object::{DisplayObjectBase, TDisplayObject};
 use crate::prelude::*;
@@ -14,6 +15,7 @@ use gc_arena::{Collect, Gc, GcCell};
 /// but starting in AVM2, a raw `Bitmap` display object can be crated
 /// with the `PlaceObject3` tag.
 /// It can also be crated in ActionScript using the `Bitmap` class.
+/// In AVM1, `MovieClip.attachBitmap` creates a `Bitmap` that displays a `BitmapData` object.
 #[derive(Clone, Debug, Collect, Copy)]
 #[collect(no_drop)]
 pub struct Bitmap<'gc>(GcCell<'gc, BitmapData<'gc>>);
@@ -22,6 +24,15 @@ pub struct Bitmap<'gc>(GcCell<'gc, BitmapData<'gc>>);
 pub struct BitmapData<'gc> {
     base: DisplayObjectBase<'gc>,
     static_data: Gc<'gc, BitmapStatic>,
+
+    /// The pixels displayed by this bitmap, if it was created from a `BitmapData` object.
+    bitmap_data: Option<GcCell<'gc, crate::bitmap_data::BitmapData>>,
+
+    /// The generation of `bitmap_data` when this bitmap was last rendered.
+    bitmap_data_generation: u32,
+
+    /// Whether the bitmap is smoothed when it is scaled or rotated.
+    smoothing: bool,
 }
 
 impl<'gc> Bitmap<'gc> {
@@ -31,6 +42,7 @@ impl<'gc> Bitmap<'gc> {
         bitmap_handle: BitmapHandle,
         width: u16,
         height: u16,
+        source: Option<BitmapSource>,
     ) -> Self {
         Bitmap(GcCell::allocate(
             context.gc_context,
@@ -43,23 +55,77 @@ impl<'gc> Bitmap<'gc> {
                         bitmap_handle,
                         width,
                         height,
+                        source,
                     },
                 ),
+                bitmap_data: None,
+                bitmap_data_generation: 0,
+                smoothing: true,
             },
         ))
     }
 
+    /// Creates a bitmap that displays the pixels of a `BitmapData` object.
+    pub fn new_with_bitmap_data(
+        context: &mut UpdateContext<'_, 'gc, '_>,
+        bitmap_data: GcCell<'gc, crate::bitmap_data::BitmapData>,
+        smoothing: bool,
+    ) -> Self {
+        let generation = bitmap_data.read().generation();
+        Bitmap(GcCell::allocate(
+            context.gc_context,
+            BitmapData {
+                base: Default::default(),
+                static_data: Gc::allocate(
+                    context.gc_context,
+                    BitmapStatic {
+                        id: 0,
+                        bitmap_handle: BitmapHandle(0),
+                        width: 0,
+                        height: 0,
+                        source: None,
+                    },
+                ),
+                bitmap_data: Some(bitmap_data),
+                bitmap_data_generation: generation,
+                smoothing,
+            },
+        ))
+    }
+
+    pub fn bitmap_data(self) -> Option<GcCell<'gc, crate::bitmap_data::BitmapData>> {
+        self.0.read().bitmap_data
+    }
+
+    /// Decodes the pixels of this bitmap's library character into a new `BitmapData`.
+    pub fn decode_bitmap_data(
+        self,
+    ) -> Result<crate::bitmap_data::BitmapData, Box<dyn std::error::Error>> {
+        match &self.0.read().static_data.source {
+            Some(source) => source.decode(),
+            None => Err("Bitmap has no image data".into()),
+        }
+    }
+
     #[allow(dead_code)]
     pub fn bitmap_handle(self) -> BitmapHandle {
         self.0.read().static_data.bitmap_handle
     }
 
     pub fn width(self) -> u16 {
-        self.0.read().static_data.width
+        let read = self.0.read();
+        match read.bitmap_data {
+            Some(bitmap_data) => bitmap_data.read().width() as u16,
+            None => read.static_data.width,
+        }
     }
 
     pub fn height(self) -> u16 {
-        self.0.read().static_data.height
+        let read = self.0.read();
+        match read.bitmap_data {
+            Some(bitmap_data) => bitmap_data.read().height() as u16,
+            None => read.static_data.height,
+        }
     }
 }
 
@@ -74,14 +140,26 @@ impl<'gc> TDisplayObject<'gc> for Bitmap<'gc> {
         BoundingBox {
             x_min: Twips::new(0),
             y_min: Twips::new(0),
-            x_max: Twips::new(Bitmap::width(*self)),
-            y_max: Twips::new(Bitmap::height(*self)),
+            x_max: Twips::from_pixels(Bitmap::width(*self).into()),
+            y_max: Twips::from_pixels(Bitmap::height(*self).into()),
             valid: true,
         }
     }
 
-    fn run_frame(&mut self, _avm: &mut Avm1<'gc>, _context: &mut UpdateContext) {
-        // Noop
+    fn as_bitmap(&self) -> Option<Bitmap<'gc>> {
+        Some(*self)
+    }
+
+    fn run_frame(&mut self, _avm: &mut Avm1<'gc>, context: &mut UpdateContext<'_, 'gc, '_>) {
+        // Cached ancestors must be redrawn when the pixels of our `BitmapData` change.
+        let bitmap_data = self.0.read().bitmap_data;
+        if let Some(bitmap_data) = bitmap_data {
+            let generation = bitmap_data.read().generation();
+            if generation != self.0.read().bitmap_data_generation {
+                self.0.write(context.gc_context).bitmap_data_generation = generation;
+                self.invalidate_cached_bitmap();
+            }
+        }
     }
 
     fn render(&self, context: &mut RenderContext) {
@@ -90,11 +168,20 @@ impl<'gc> TDisplayObject<'gc> for Bitmap<'gc> {
             return;
         }
 
+        let bitmap_handle = match self.0.read().bitmap_data {
+            Some(bitmap_data) => match bitmap_data.read().register(context.renderer) {
+                Some((_, handle)) => handle,
+                None => return,
+            },
+            None => self.0.read().static_data.bitmap_handle,
+        };
+
         context.transform_stack.push(&*self.transform());
 
         context.renderer.render_bitmap(
-            self.0.read().static_data.bitmap_handle,
+            bitmap_handle,
             context.transform_stack.transform(),
+            self.0.read().smoothing,
         );
 
         context.transform_stack.pop();
@@ -105,6 +192,7 @@ unsafe impl<'gc> gc_arena::Collect for BitmapData<'gc> {
     fn trace(&self, cc: gc_arena::CollectionContext) {
         self.base.trace(cc);
         self.static_data.trace(cc);
+        self.bitmap_data.trace(cc);
     }
 }
 
@@ -115,6 +203,9 @@ struct BitmapStatic {
     bitmap_handle: BitmapHandle,
     width: u16,
     height: u16,
+
+    /// The encoded image data of this bitmap.
+    source: Option<BitmapSource>,
 }
 
 unsafe impl<'gc> gc_arena::Collect for BitmapStatic {
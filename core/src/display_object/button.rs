@@ -1,6 +1,7 @@
-use crate::avm1::{Avm1, Object, StageObject, Value};
+use crate::avm1::activation::{Activation, ActivationIdentifier};
+use crate::avm1::{Avm1, Object, StageObject, TObject, Value};
 use crate::context::{ActionType, RenderContext, UpdateContext};
-use crate::display_object::{DisplayObjectBase, TDisplayObject};
+use crate::display_object::{is_hit_candidate, DisplayObjectBase, TDisplayObject};
 use crate::events::{ButtonKeyCode, ClipEvent, ClipEventResult};
 use crate::prelude::*;
 use crate::tag_utils::{SwfMovie, SwfSlice};
@@ -86,6 +87,20 @@ impl<'gc> Button<'gc> {
         static_data.over_to_up_sound = sounds.over_to_up_sound;
     }
 
+    /// Whether this button tracks the mouse as a menu, receiving roll over and release events
+    /// while the mouse button is held down over another object.
+    pub fn is_track_as_menu(self) -> bool {
+        self.0.read().tracking == ButtonTracking::Menu
+    }
+
+    pub fn set_track_as_menu(self, gc_context: MutationContext<'gc, '_>, is_track_as_menu: bool) {
+        self.0.write(gc_context).tracking = if is_track_as_menu {
+            ButtonTracking::Menu
+        } else {
+            ButtonTracking::Push
+        };
+    }
+
     /// Handles the ancient DefineButtonCxform SWF tag.
     /// Set the color transform for all children of each state.
     pub fn set_colors(
@@ -166,19 +181,55 @@ impl<'gc> TDisplayObject<'gc> for Button<'gc> {
         false
     }
 
+    fn hit_test_shape(&self, point: (Twips, Twips)) -> bool {
+        self.0
+            .read()
+            .children
+            .values()
+            .any(|child| child.hit_test_shape(point))
+    }
+
+    fn hit_test_self_shape(&self, point: (Twips, Twips)) -> bool {
+        self.0
+            .read()
+            .hit_area
+            .values()
+            .any(|child| child.hit_test_shape(point))
+    }
+
     fn mouse_pick(
         &self,
-        _avm: &mut Avm1<'gc>,
-        _context: &mut UpdateContext<'_, 'gc, '_>,
+        avm: &mut Avm1<'gc>,
+        context: &mut UpdateContext<'_, 'gc, '_>,
         self_node: DisplayObject<'gc>,
         point: (Twips, Twips),
     ) -> Option<DisplayObject<'gc>> {
-        // The button is hovered if the mouse is over any child nodes.
-        if self.hit_test(point) {
-            Some(self_node)
-        } else {
-            None
+        // The button is hovered if the mouse is over the shapes of its hit area.
+        // These are tested in their own space, so transformed parents are respected.
+        if !is_hit_candidate(self_node, point) || !self.hit_test_self_shape(point) {
+            return None;
         }
+
+        // Disabled buttons don't respond to the mouse.
+        let object = self.0.read().object;
+        if let Some(object) = object {
+            let mut activation = Activation::from_nothing(
+                avm,
+                ActivationIdentifier::root("[Mouse Pick]"),
+                context.swf.version(),
+                avm.global_object_cell(),
+                context.gc_context,
+                *context.levels.get(&0).unwrap(),
+            );
+            if let Ok(enabled) = object.get("enabled", &mut activation, context) {
+                if enabled != Value::Undefined && !enabled.as_bool(activation.current_swf_version())
+                {
+                    return None;
+                }
+            }
+        }
+
+        Some(self_node)
     }
 
     fn object(&self) -> Value<'gc> {
@@ -197,6 +248,23 @@ impl<'gc> TDisplayObject<'gc> for Button<'gc> {
         !self.0.read().children.is_empty()
     }
 
+    fn is_tree_dirty(&self) -> bool {
+        self.is_dirty()
+            || self
+                .0
+                .read()
+                .children
+                .values()
+                .any(|child| child.is_tree_dirty())
+    }
+
+    fn clear_dirty_tree(&self) {
+        self.set_dirty(false);
+        for child in self.0.read().children.values() {
+            child.clear_dirty_tree();
+        }
+    }
+
     /// Executes and propagates the given clip event.
     /// Events execute inside-out; the deepest child will react first, followed by its parent, and
     /// so forth.
@@ -229,6 +297,11 @@ impl<'gc> ButtonData<'gc> {
         state: ButtonState,
     ) {
         self.state = state;
+        self.base.set_dirty(true);
+        self.base.bitmap_cache().invalidate();
+        if let Some(parent) = self.base.parent() {
+            parent.invalidate_cached_bitmap();
+        }
         let swf_state = match state {
             ButtonState::Up => swf::ButtonState::Up,
             ButtonState::Over => swf::ButtonState::Over,
@@ -309,45 +382,61 @@ impl<'gc> ButtonData<'gc> {
         context: &mut crate::context::UpdateContext<'_, 'gc, '_>,
         event: ClipEvent,
     ) -> ClipEventResult {
+        use swf::ButtonActionCondition as Condition;
+
         let mut handled = ClipEventResult::NotHandled;
 
         // Translate the clip event to a button event, based on how the button state changes.
+        // While the mouse button is held down elsewhere, only menu buttons receive roll events,
+        // and they go straight to and from the down state.
+        let is_mouse_down = context.mouse_pressed_object.is_some();
         let cur_state = self.state;
-        let new_state = match event {
-            ClipEvent::RollOut => ButtonState::Up,
-            ClipEvent::RollOver => ButtonState::Over,
-            ClipEvent::Press => ButtonState::Down,
-            ClipEvent::Release => ButtonState::Over,
+        let (new_state, condition) = match event {
+            ClipEvent::RollOut if is_mouse_down => (ButtonState::Up, Condition::OverDownToIdle),
+            ClipEvent::RollOut => (ButtonState::Up, Condition::OverUpToIdle),
+            ClipEvent::RollOver if is_mouse_down => (ButtonState::Down, Condition::IdleToOverDown),
+            ClipEvent::RollOver => (ButtonState::Over, Condition::IdleToOverUp),
+            ClipEvent::Press => (ButtonState::Down, Condition::OverUpToOverDown),
+            ClipEvent::Release => (ButtonState::Over, Condition::OverDownToOverUp),
+            ClipEvent::DragOut if self.tracking == ButtonTracking::Menu => {
+                (ButtonState::Up, Condition::OverDownToIdle)
+            }
+            ClipEvent::DragOut => (ButtonState::Over, Condition::OverDownToOutDown),
+            ClipEvent::DragOver if self.tracking == ButtonTracking::Menu => {
+                (ButtonState::Down, Condition::IdleToOverDown)
+            }
+            ClipEvent::DragOver => (ButtonState::Down, Condition::OutDownToOverDown),
+            ClipEvent::ReleaseOutside => (ButtonState::Up, Condition::OutDownToIdle),
             ClipEvent::KeyPress { key_code } => {
-                handled = self.run_actions(
-                    context,
-                    swf::ButtonActionCondition::KeyPress,
-                    Some(key_code),
-                );
-                cur_state
+                handled = self.run_actions(context, Condition::KeyPress, Some(key_code));
+                (cur_state, Condition::KeyPress)
             }
             _ => return ClipEventResult::NotHandled,
         };
 
-        match (cur_state, new_state) {
+        if condition != Condition::KeyPress {
+            self.run_actions(context, condition, None);
+        }
+
+        let sound = match (cur_state, new_state) {
             (ButtonState::Up, ButtonState::Over) => {
-                self.run_actions(context, swf::ButtonActionCondition::IdleToOverUp, None);
-                self.play_sound(context, self.static_data.read().up_to_over_sound.as_ref());
+                self.static_data.read().up_to_over_sound.clone()
             }
             (ButtonState::Over, ButtonState::Up) => {
-                self.run_actions(context, swf::ButtonActionCondition::OverUpToIdle, None);
-                self.play_sound(context, self.static_data.read().over_to_up_sound.as_ref());
+                self.static_data.read().over_to_up_sound.clone()
             }
-            (ButtonState::Over, ButtonState::Down) => {
-                self.run_actions(context, swf::ButtonActionCondition::OverUpToOverDown, None);
-                self.play_sound(context, self.static_data.read().over_to_down_sound.as_ref());
+            (ButtonState::Over, ButtonState::Down) | (ButtonState::Up, ButtonState::Down) => {
+                self.static_data.read().over_to_down_sound.clone()
             }
             (ButtonState::Down, ButtonState::Over) => {
-                self.run_actions(context, swf::ButtonActionCondition::OverDownToOverUp, None);
-                self.play_sound(context, self.static_data.read().down_to_over_sound.as_ref());
+                self.static_data.read().down_to_over_sound.clone()
+            }
+            (ButtonState::Down, ButtonState::Up) => {
+                self.static_data.read().over_to_up_sound.clone()
             }
-            _ => (),
+            _ => None,
         };
+        self.play_sound(context, sound.as_ref());
 
         // Queue ActionScript-defined event handlers after the SWF defined ones.
         // (e.g., clip.onRelease = foo).
@@ -365,7 +454,9 @@ impl<'gc> ButtonData<'gc> {
             }
         }
 
-        self.set_state(self_display_object, avm, context, new_state);
+        if new_state != cur_state {
+            self.set_state(self_display_object, avm, context, new_state);
+        }
 
         handled
     }
@@ -381,7 +472,15 @@ impl<'gc> ButtonData<'gc> {
                 .library_for_movie_mut(self.movie())
                 .get_sound(*id)
             {
-                let _ = context.audio.start_sound(sound_handle, sound_info);
+                // Only movie clips can be the target of a `Sound` object, so button sounds
+                // are controlled by the button's parent.
+                context.audio_manager.start_sound(
+                    context.audio,
+                    sound_handle,
+                    sound_info,
+                    self.base.parent(),
+                    None,
+                );
             }
         }
     }
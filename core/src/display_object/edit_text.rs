@@ -1,11 +1,13 @@
 //! `EditText` display object and support code.
 use crate::avm1::activation::Activation;
 use crate::avm1::globals::text_field::attach_virtual_properties;
-use crate::avm1::{Avm1, Object, StageObject, TObject, Value};
-use crate::context::{RenderContext, UpdateContext};
-use crate::display_object::{DisplayObjectBase, TDisplayObject};
+use crate::avm1::style_sheet_object::StyleSheetObject;
+use crate::avm1::{Avm1, AvmString, Object, StageObject, TObject, Value};
+use crate::context::{ActionType, RenderContext, UpdateContext};
+use crate::display_object::{is_hit_candidate, DisplayObjectBase, TDisplayObject};
 use crate::drawing::Drawing;
-use crate::font::{round_down_to_pixel, Glyph};
+use crate::events::KeyCode;
+use crate::font::{round_down_to_pixel, EvalParameters, Font, Glyph};
 use crate::html::{BoxBounds, FormatSpans, LayoutBox, TextFormat};
 use crate::prelude::*;
 use crate::shape_utils::DrawCommand;
@@ -19,6 +21,20 @@ use swf::Twips;
 /// Boxed error type.
 pub type Error = Box<dyn std::error::Error>;
 
+/// How far faux italic text is slanted, as a fraction of the glyph height.
+const FAUX_ITALIC_SKEW: f32 = 0.2;
+
+/// Faux bold text is overstruck by the font height divided by this amount.
+const FAUX_BOLD_DIVISOR: i32 = 24;
+
+/// Styles that must be synthesized when rendering text, because the embedded
+/// font does not have a matching bold or italic variant.
+#[derive(Copy, Clone, Debug, Default)]
+struct FauxStyle {
+    bold: bool,
+    italic: bool,
+}
+
 /// The kind of autosizing behavior an `EditText` should have, if any
 #[derive(Copy, Clone, Debug, Collect)]
 #[collect(no_drop)]
@@ -29,6 +45,72 @@ pub enum AutoSizeMode {
     Right,
 }
 
+/// The caret position and selected text of an `EditText`.
+///
+/// Positions are byte offsets into the text, and always lie on character
+/// boundaries.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Collect)]
+#[collect(require_static)]
+pub struct TextSelection {
+    /// The end of the selection that stays put when the selection is extended.
+    anchor: usize,
+
+    /// The end of the selection that the caret is at.
+    caret: usize,
+}
+
+impl TextSelection {
+    pub fn for_position(position: usize) -> Self {
+        Self {
+            anchor: position,
+            caret: position,
+        }
+    }
+
+    pub fn for_range(anchor: usize, caret: usize) -> Self {
+        Self { anchor, caret }
+    }
+
+    pub fn anchor(self) -> usize {
+        self.anchor
+    }
+
+    pub fn caret(self) -> usize {
+        self.caret
+    }
+
+    /// The start of the selected text.
+    pub fn start(self) -> usize {
+        self.anchor.min(self.caret)
+    }
+
+    /// The end of the selected text.
+    pub fn end(self) -> usize {
+        self.anchor.max(self.caret)
+    }
+
+    /// Whether no text is selected.
+    pub fn is_caret(self) -> bool {
+        self.anchor == self.caret
+    }
+
+    /// Moves both ends of the selection back onto a character boundary of
+    /// `text`, if the text has changed underneath it.
+    fn clamp(self, text: &str) -> Self {
+        let clamp = |mut position: usize| {
+            position = position.min(text.len());
+            while !text.is_char_boundary(position) {
+                position -= 1;
+            }
+            position
+        };
+        Self {
+            anchor: clamp(self.anchor),
+            caret: clamp(self.caret),
+        }
+    }
+}
+
 /// A dynamic text field.
 /// The text in this text field can be changed dynamically.
 /// It may be selectable or editable by the user, depending on the text field properties.
@@ -78,13 +160,48 @@ pub struct EditTextData<'gc> {
     /// If the text field should have a border.
     has_border: bool,
 
+    /// The color of the border, as `0xRRGGBB`.
+    border_color: u32,
+
+    /// If the text field should have a background.
+    has_background: bool,
+
+    /// The color of the background, as `0xRRGGBB`.
+    background_color: u32,
+
+    /// If the text can be edited by the user.
+    is_editable: bool,
+
+    /// If the text can be selected by the user.
+    is_selectable: bool,
+
+    /// If the text is displayed as asterisks.
+    is_password: bool,
+
+    /// The maximum number of characters the user can type, or 0 for no limit.
+    max_chars: i32,
+
+    /// The characters the user can type, in the syntax of `TextField.restrict`.
+    /// `None` allows any character.
+    restrict: Option<String>,
+
+    /// If the text field has keyboard focus.
+    has_focus: bool,
+
+    /// The caret position and selected text, while the text field has focus.
+    selection: Option<TextSelection>,
+
     /// If the text field is required to use device fonts only.
     is_device_font: bool,
 
     /// If the text field renders as HTML.
     is_html: bool,
 
-    /// The current border drawing.
+    /// The CSS styles applied to the field's HTML text (AVM1 only).
+    /// A field with a style sheet can't be formatted or edited.
+    style_sheet: Option<StyleSheetObject<'gc>>,
+
+    /// The current border and background drawing.
     drawing: Drawing,
 
     /// Whether or not the width of the field should change in response to text
@@ -134,7 +251,7 @@ impl<'gc> EditText<'gc> {
         if is_html {
             document
                 .as_node()
-                .replace_with_str(context.gc_context, &text, false)
+                .replace_with_str(context.gc_context, &text, false, false)
                 .unwrap();
             text_spans.lower_from_html(document);
         } else {
@@ -154,6 +271,10 @@ impl<'gc> EditText<'gc> {
 
         let has_border = swf_tag.has_border;
         let is_device_font = swf_tag.is_device_font;
+        let is_editable = !swf_tag.is_read_only;
+        let is_selectable = swf_tag.is_selectable;
+        let is_password = swf_tag.is_password;
+        let max_chars = swf_tag.max_length.map(i32::from).unwrap_or(0);
 
         let mut base = DisplayObjectBase::default();
 
@@ -182,8 +303,20 @@ impl<'gc> EditText<'gc> {
                 is_multiline,
                 is_word_wrap,
                 has_border,
+                border_color: 0x000000,
+                // The `HasBorder` flag of `DefineEditText` also gives the field a white background.
+                has_background: has_border,
+                background_color: 0xFFFFFF,
+                is_editable,
+                is_selectable,
+                is_password,
+                max_chars,
+                restrict: None,
+                has_focus: false,
+                selection: None,
                 is_device_font,
                 is_html,
+                style_sheet: None,
                 drawing: Drawing::new(),
                 object: None,
                 layout,
@@ -227,7 +360,7 @@ impl<'gc> EditText<'gc> {
                 b: 0,
                 a: 0xFF,
             }),
-            max_length: Some(width as u16),
+            max_length: None,
             layout: Some(swf::TextLayout {
                 align: swf::TextAlign::Left,
                 left_margin: Twips::from_pixels(0.0),
@@ -313,7 +446,7 @@ impl<'gc> EditText<'gc> {
             if let Err(err) =
                 document
                     .as_node()
-                    .replace_with_str(context.gc_context, &html_string, false)
+                    .replace_with_str(context.gc_context, &html_string, false, false)
             {
                 log::warn!("Parsing error when setting TextField.htmlText: {}", err);
             }
@@ -343,13 +476,40 @@ impl<'gc> EditText<'gc> {
         let mut write = self.0.write(context.gc_context);
 
         write.document = doc;
-        write.text_spans.lower_from_html(doc);
+        let style_sheet = write.style_sheet;
+        match style_sheet {
+            Some(style_sheet) => {
+                let style_sheet = style_sheet.style_sheet();
+                write.text_spans.lower_from_css(doc, &style_sheet);
+            }
+            None => write.text_spans.lower_from_html(doc),
+        }
 
         drop(write);
 
         self.relayout(context);
     }
 
+    pub fn style_sheet(self) -> Option<StyleSheetObject<'gc>> {
+        self.0.read().style_sheet
+    }
+
+    /// Set the style sheet used to format the field's HTML text.
+    ///
+    /// The current HTML text is formatted again with the new styles.
+    pub fn set_style_sheet(
+        self,
+        style_sheet: Option<StyleSheetObject<'gc>>,
+        context: &mut UpdateContext<'_, 'gc, '_>,
+    ) {
+        self.0.write(context.gc_context).style_sheet = style_sheet;
+
+        if self.is_html() {
+            let document = self.0.read().document;
+            self.set_html_tree(document, context);
+        }
+    }
+
     pub fn text_length(self) -> usize {
         self.0.read().text_spans.text().len()
     }
@@ -365,8 +525,17 @@ impl<'gc> EditText<'gc> {
             .set_default_format(tf);
     }
 
+    /// Returns the formatting shared by all of the text in the range
+    /// `[from, to)`; properties that differ within the range are unset.
+    ///
+    /// An empty text field reports the format of newly added text.
     pub fn text_format(self, from: usize, to: usize) -> TextFormat {
-        self.0.read().text_spans.get_text_format(from, to)
+        let edit_text = self.0.read();
+        if edit_text.text_spans.text().is_empty() {
+            edit_text.text_spans.default_format().clone()
+        } else {
+            edit_text.text_spans.get_text_format(from, to)
+        }
     }
 
     pub fn set_text_format(
@@ -419,6 +588,343 @@ impl<'gc> EditText<'gc> {
         self.redraw_border(context);
     }
 
+    pub fn border_color(self) -> u32 {
+        self.0.read().border_color
+    }
+
+    pub fn set_border_color(self, context: MutationContext<'gc, '_>, border_color: u32) {
+        self.0.write(context).border_color = border_color;
+        self.redraw_border(context);
+    }
+
+    pub fn has_background(self) -> bool {
+        self.0.read().has_background
+    }
+
+    pub fn set_has_background(self, context: MutationContext<'gc, '_>, has_background: bool) {
+        self.0.write(context).has_background = has_background;
+        self.redraw_border(context);
+    }
+
+    pub fn background_color(self) -> u32 {
+        self.0.read().background_color
+    }
+
+    pub fn set_background_color(self, context: MutationContext<'gc, '_>, background_color: u32) {
+        self.0.write(context).background_color = background_color;
+        self.redraw_border(context);
+    }
+
+    /// The color of newly added text, as `0xRRGGBB`.
+    pub fn text_color(self) -> u32 {
+        let edit_text = self.0.read();
+        edit_text
+            .text_spans
+            .default_format()
+            .color
+            .as_ref()
+            .map(|c| (u32::from(c.r) << 16) | (u32::from(c.g) << 8) | u32::from(c.b))
+            .unwrap_or(0)
+    }
+
+    /// Sets the color of all of the text in this `EditText`, as well as any
+    /// text added later.
+    pub fn set_text_color(self, text_color: u32, context: &mut UpdateContext<'_, 'gc, '_>) {
+        let color = swf::Color::from_rgb(text_color, 0xFF);
+        let mut edit_text = self.0.write(context.gc_context);
+
+        let mut default_format = edit_text.text_spans.default_format().clone();
+        default_format.color = Some(color.clone());
+        edit_text.text_spans.set_default_format(default_format);
+
+        let len = edit_text.text_spans.text().len();
+        let tf = TextFormat {
+            color: Some(color),
+            ..Default::default()
+        };
+        edit_text.text_spans.set_text_format(0, len, &tf);
+
+        drop(edit_text);
+
+        self.relayout(context);
+    }
+
+    pub fn is_editable(self) -> bool {
+        self.0.read().is_editable
+    }
+
+    pub fn set_editable(self, context: MutationContext<'gc, '_>, is_editable: bool) {
+        self.0.write(context).is_editable = is_editable;
+    }
+
+    pub fn is_selectable(self) -> bool {
+        self.0.read().is_selectable
+    }
+
+    pub fn set_selectable(self, context: MutationContext<'gc, '_>, is_selectable: bool) {
+        self.0.write(context).is_selectable = is_selectable;
+    }
+
+    pub fn is_password(self) -> bool {
+        self.0.read().is_password
+    }
+
+    pub fn set_password(self, context: MutationContext<'gc, '_>, is_password: bool) {
+        self.0.write(context).is_password = is_password;
+        self.invalidate_cached_bitmap();
+    }
+
+    pub fn max_chars(self) -> i32 {
+        self.0.read().max_chars
+    }
+
+    pub fn set_max_chars(self, context: MutationContext<'gc, '_>, max_chars: i32) {
+        self.0.write(context).max_chars = max_chars;
+    }
+
+    pub fn restrict(&self) -> Option<Ref<str>> {
+        let edit_text = self.0.read();
+        if edit_text.restrict.is_some() {
+            Some(Ref::map(edit_text, |et| et.restrict.as_deref().unwrap()))
+        } else {
+            None
+        }
+    }
+
+    pub fn set_restrict(self, context: MutationContext<'gc, '_>, restrict: Option<String>) {
+        self.0.write(context).restrict = restrict;
+    }
+
+    pub fn has_focus(self) -> bool {
+        self.0.read().has_focus
+    }
+
+    /// Gives or removes keyboard focus.
+    ///
+    /// A text field that gains focus without a selection puts the caret after
+    /// the text.
+    pub fn set_has_focus(self, context: MutationContext<'gc, '_>, has_focus: bool) {
+        let mut edit_text = self.0.write(context);
+        edit_text.has_focus = has_focus;
+        if has_focus && edit_text.selection.is_none() {
+            let len = edit_text.text_spans.text().len();
+            edit_text.selection = Some(TextSelection::for_position(len));
+        }
+        drop(edit_text);
+        self.invalidate_cached_bitmap();
+    }
+
+    pub fn selection(self) -> Option<TextSelection> {
+        self.0.read().selection
+    }
+
+    pub fn set_selection(
+        self,
+        context: MutationContext<'gc, '_>,
+        selection: Option<TextSelection>,
+    ) {
+        let mut edit_text = self.0.write(context);
+        edit_text.selection = selection.map(|s| s.clamp(edit_text.text_spans.text()));
+        drop(edit_text);
+        self.invalidate_cached_bitmap();
+    }
+
+    /// Inserts a character typed by the user at the caret, replacing any
+    /// selected text.
+    ///
+    /// The character is dropped if the text field isn't editable, if it is
+    /// not allowed by `restrict`, or if the text is already `maxChars` long.
+    pub fn text_input(
+        self,
+        character: char,
+        activation: &mut Activation<'_, 'gc>,
+        context: &mut UpdateContext<'_, 'gc, '_>,
+    ) {
+        let edit_text = self.0.read();
+        let selection = match edit_text.selection {
+            Some(selection) if edit_text.is_editable && !character.is_control() => selection,
+            _ => return,
+        };
+
+        if let Some(restrict) = &edit_text.restrict {
+            if !is_character_allowed(restrict, character) {
+                return;
+            }
+        }
+
+        let text = edit_text.text_spans.text();
+        let selected_len = text[selection.start()..selection.end()].chars().count();
+        if edit_text.max_chars > 0
+            && text.chars().count() - selected_len >= edit_text.max_chars as usize
+        {
+            return;
+        }
+        drop(edit_text);
+
+        let mut buffer = [0; 4];
+        self.replace_selection(character.encode_utf8(&mut buffer), activation, context);
+    }
+
+    /// Handles a key that edits the text or moves the caret, such as
+    /// backspace or the arrow keys. Holding shift extends the selection.
+    pub fn text_control_input(
+        self,
+        key_code: KeyCode,
+        shift: bool,
+        activation: &mut Activation<'_, 'gc>,
+        context: &mut UpdateContext<'_, 'gc, '_>,
+    ) {
+        let edit_text = self.0.read();
+        let selection = match edit_text.selection {
+            Some(selection) => selection,
+            None => return,
+        };
+        let is_editable = edit_text.is_editable;
+        let is_multiline = edit_text.is_multiline;
+        let text = edit_text.text_spans.text();
+        let caret = selection.caret();
+
+        let new_caret = match key_code {
+            KeyCode::Left if !shift && !selection.is_caret() => Some(selection.start()),
+            KeyCode::Right if !shift && !selection.is_caret() => Some(selection.end()),
+            KeyCode::Left => Some(prev_char_boundary(text, caret)),
+            KeyCode::Right => Some(next_char_boundary(text, caret)),
+            KeyCode::Home => Some(text[..caret].rfind('\n').map_or(0, |i| i + 1)),
+            KeyCode::End => Some(text[caret..].find('\n').map_or(text.len(), |i| caret + i)),
+            _ => None,
+        };
+        if let Some(new_caret) = new_caret {
+            drop(edit_text);
+            let selection = if shift {
+                TextSelection::for_range(selection.anchor(), new_caret)
+            } else {
+                TextSelection::for_position(new_caret)
+            };
+            self.set_selection(context.gc_context, Some(selection));
+            return;
+        }
+
+        if !is_editable {
+            return;
+        }
+
+        let deleted = match key_code {
+            KeyCode::Backspace | KeyCode::Delete if !selection.is_caret() => Some(selection),
+            KeyCode::Backspace if caret > 0 => Some(TextSelection::for_range(
+                caret,
+                prev_char_boundary(text, caret),
+            )),
+            KeyCode::Delete if caret < text.len() => Some(TextSelection::for_range(
+                caret,
+                next_char_boundary(text, caret),
+            )),
+            _ => None,
+        };
+        drop(edit_text);
+
+        if let Some(deleted) = deleted {
+            self.set_selection(context.gc_context, Some(deleted));
+            self.replace_selection("", activation, context);
+        } else if key_code == KeyCode::Return && is_multiline {
+            self.replace_selection("\n", activation, context);
+        }
+    }
+
+    /// Replaces the selected text with text entered by the user, leaving the
+    /// caret after it, and notifies listeners of the change.
+    fn replace_selection(
+        self,
+        text: &str,
+        activation: &mut Activation<'_, 'gc>,
+        context: &mut UpdateContext<'_, 'gc, '_>,
+    ) {
+        let mut edit_text = self.0.write(context.gc_context);
+        let selection = match edit_text.selection {
+            Some(selection) if edit_text.style_sheet.is_none() => selection,
+            _ => return,
+        };
+
+        let tf = edit_text.text_spans.default_format().clone();
+        edit_text
+            .text_spans
+            .replace_text(selection.start(), selection.end(), text, Some(&tf));
+        edit_text.selection = Some(TextSelection::for_position(selection.start() + text.len()));
+        drop(edit_text);
+
+        self.relayout(context);
+        self.propagate_text_binding(activation, context);
+
+        // Only changes made by the user call `onChanged`, not changes made by scripts.
+        if let Value::Object(object) = self.object() {
+            context.action_queue.queue_actions(
+                self.into(),
+                ActionType::Method {
+                    object,
+                    name: "broadcastMessage",
+                    args: vec!["onChanged".into(), object.into()],
+                },
+                false,
+            );
+        }
+    }
+
+    /// Returns the position in the text closest to a point on the stage,
+    /// such as where the user clicked.
+    pub fn screen_position_to_index(self, position: (Twips, Twips)) -> usize {
+        let (x, y) = self.global_to_local(position);
+        let edit_text = self.0.read();
+        let padding = Twips::from_pixels(Self::INTERNAL_PADDING);
+        let x = x - edit_text.bounds.x_min - padding;
+        let y = y - edit_text.bounds.y_min - padding;
+        let text = edit_text.text_spans.text();
+
+        // Find the box of text nearest to the point, preferring the right line.
+        let distance = |from: Twips, to: Twips, point: Twips| {
+            if point < from {
+                from - point
+            } else if point > to {
+                point - to
+            } else {
+                Twips::new(0)
+            }
+        };
+        let nearest = edit_text
+            .layout
+            .iter()
+            .filter(|lbox| lbox.text_range().is_some())
+            .min_by_key(|lbox| {
+                let bounds = lbox.bounds();
+                (
+                    distance(bounds.offset_y(), bounds.extent_y(), y),
+                    distance(bounds.offset_x(), bounds.extent_x(), x),
+                )
+            });
+
+        let lbox = match nearest {
+            Some(lbox) => lbox,
+            None => return text.len(),
+        };
+        let (start, end) = lbox.text_range().unwrap();
+        let (_, _, font, params, _) = match lbox.as_renderable_text(text) {
+            Some(renderable) => renderable,
+            None => return start,
+        };
+
+        // Pick the character boundary nearest to the point.
+        let x = x - lbox.bounds().offset_x();
+        let mut last_width = Twips::new(0);
+        for (i, c) in text[start..end].char_indices() {
+            let width = font
+                .measure(&text[start..start + i + c.len_utf8()], params, false)
+                .0;
+            if x < last_width + (width - last_width) / 2 {
+                return start + i;
+            }
+            last_width = width;
+        }
+        end
+    }
+
     pub fn is_device_font(self) -> bool {
         self.0.read().is_device_font
     }
@@ -536,19 +1042,31 @@ impl<'gc> EditText<'gc> {
     /// The `text_transform` constitutes the base transform that all text is
     /// written into.
 
-    /// Redraw the border of this `EditText`.
+    /// Redraw the border and background of this `EditText`.
     fn redraw_border(self, context: MutationContext<'gc, '_>) {
         let mut write = self.0.write(context);
 
         write.drawing.clear();
 
-        if write.has_border {
+        if write.has_border || write.has_background {
             let bounds = write.bounds.clone();
 
-            write.drawing.set_line_style(Some(swf::LineStyle::new_v1(
-                Twips::new(1),
-                swf::Color::from_rgb(0, 0xFF),
-            )));
+            if write.has_border {
+                let border_color = write.border_color;
+                write.drawing.set_line_style(Some(swf::LineStyle::new_v1(
+                    Twips::new(1),
+                    swf::Color::from_rgb(border_color, 0xFF),
+                )));
+            }
+            if write.has_background {
+                let background_color = write.background_color;
+                write
+                    .drawing
+                    .set_fill_style(Some(swf::FillStyle::Color(swf::Color::from_rgb(
+                        background_color,
+                        0xFF,
+                    ))));
+            }
             write.drawing.draw_command(DrawCommand::MoveTo {
                 x: Twips::new(0),
                 y: Twips::new(0),
@@ -583,6 +1101,7 @@ impl<'gc> EditText<'gc> {
     /// have already been calculated and applied to HTML trees lowered into the
     /// text-span representation.
     fn relayout(self, context: &mut UpdateContext<'_, 'gc, '_>) {
+        self.invalidate_cached_bitmap();
         let mut edit_text = self.0.write(context.gc_context);
         let autosize = edit_text.autosize;
         let is_word_wrap = edit_text.is_word_wrap;
@@ -601,44 +1120,36 @@ impl<'gc> EditText<'gc> {
         edit_text.layout = new_layout;
         edit_text.intrinsic_bounds = intrinsic_bounds;
 
-        match autosize {
-            AutoSizeMode::None => {}
-            AutoSizeMode::Left => {
-                if !is_word_wrap {
-                    let old_x = edit_text.bounds.x_min;
-                    edit_text.bounds.set_x(old_x);
-                    edit_text.base.set_x(old_x.to_pixels());
-                    edit_text.bounds.set_width(intrinsic_bounds.width());
-                }
-
-                edit_text.bounds.set_height(intrinsic_bounds.height());
-                edit_text.base.set_transformed_by_script(true);
-            }
-            AutoSizeMode::Center => {
-                if !is_word_wrap {
-                    let old_x = edit_text.bounds.x_min;
-                    let new_x = (intrinsic_bounds.width() - old_x) / 2;
-                    edit_text.bounds.set_x(new_x);
-                    edit_text.base.set_x(new_x.to_pixels());
-                    edit_text.bounds.set_width(intrinsic_bounds.width());
-                }
+        // Keep the caret inside the text if a script changed it.
+        if let Some(selection) = edit_text.selection {
+            edit_text.selection = Some(selection.clamp(edit_text.text_spans.text()));
+        }
 
-                edit_text.bounds.set_height(intrinsic_bounds.height());
-                edit_text.base.set_transformed_by_script(true);
+        // Autosized fields wrap the text plus the internal padding. Without word wrap, the
+        // width changes as well, and the field grows away from the edge it is anchored to.
+        let padding = Twips::from_pixels(Self::INTERNAL_PADDING * 2.0);
+        if !matches!(autosize, AutoSizeMode::None) {
+            if !is_word_wrap {
+                let old_x = edit_text.bounds.x_min;
+                let old_width = edit_text.bounds.width();
+                let new_width = intrinsic_bounds.width() + padding;
+                let new_x = match autosize {
+                    AutoSizeMode::Center => old_x + (old_width - new_width) / 2,
+                    AutoSizeMode::Right => old_x + old_width - new_width,
+                    _ => old_x,
+                };
+                edit_text.bounds.set_x(new_x);
+                edit_text.bounds.set_width(new_width);
             }
-            AutoSizeMode::Right => {
-                if !is_word_wrap {
-                    let old_x = edit_text.bounds.x_min;
-                    let new_x = intrinsic_bounds.width() - old_x;
-                    edit_text.bounds.set_x(new_x);
-                    edit_text.base.set_x(new_x.to_pixels());
-                    edit_text.bounds.set_width(intrinsic_bounds.width());
-                }
 
-                edit_text.bounds.set_height(intrinsic_bounds.height());
-                edit_text.base.set_transformed_by_script(true);
-            }
+            edit_text
+                .bounds
+                .set_height(intrinsic_bounds.height() + padding);
+            edit_text.base.set_transformed_by_script(true);
         }
+
+        drop(edit_text);
+        self.redraw_border(context.gc_context);
     }
 
     /// Measure the width and height of the `EditText`'s current text load.
@@ -664,24 +1175,99 @@ impl<'gc> EditText<'gc> {
         // We're cheating a bit and not actually rendering text using the OS/web.
         // Instead, we embed an SWF version of Noto Sans to use as the "device font", and render
         // it the same as any other SWF outline text.
-        if let Some((text, _tf, font, params, color)) =
+        if let Some((text, tf, font, params, color)) =
             lbox.as_renderable_text(edit_text.text_spans.text())
         {
+            // Styles requested by the format but missing from the resolved
+            // font variant are synthesized at render time.
+            let descriptor = font.descriptor();
+            let style = FauxStyle {
+                bold: tf.bold == Some(true) && !descriptor.bold(),
+                italic: tf.italic == Some(true) && !descriptor.italic(),
+            };
+
             let baseline_adjustmnet =
                 font.get_baseline_for_height(params.height()) - params.height();
-            font.evaluate(
-                text,
-                self.text_transform(color, baseline_adjustmnet),
-                params,
-                |transform, glyph: &Glyph, _advance| {
-                    // Render glyph.
-                    context.transform_stack.push(transform);
-                    context
-                        .renderer
-                        .render_shape(glyph.shape, context.transform_stack.transform());
-                    context.transform_stack.pop();
-                },
-            );
+
+            // Password fields show an asterisk in place of each character.
+            let is_masked = edit_text.is_password && lbox.text_range().is_some();
+            let masked_text;
+            let display_text = if is_masked {
+                masked_text = "*".repeat(text.chars().count());
+                &masked_text[..]
+            } else {
+                text
+            };
+            let display_position = |position: usize| {
+                if is_masked {
+                    text[..position].chars().count()
+                } else {
+                    position
+                }
+            };
+
+            // The part of the selection inside this box, as positions in `display_text`.
+            let selected = match (edit_text.has_focus, edit_text.selection, lbox.text_range()) {
+                (true, Some(selection), Some((start, end)))
+                    if selection.start() < end && selection.end() > start =>
+                {
+                    let from = selection.start().max(start) - start;
+                    let to = selection.end().min(end) - start;
+                    Some((display_position(from), display_position(to)))
+                }
+                _ => None,
+            };
+
+            let transform = self.text_transform(color, baseline_adjustmnet);
+            if let Some((from, to)) = selected {
+                // Selected text is drawn white on black.
+                let start_x = font.measure(&display_text[..from], params, false).0;
+                let end_x = font.measure(&display_text[..to], params, false).0;
+                let highlight = swf::Rectangle {
+                    x_min: start_x,
+                    x_max: end_x,
+                    y_min: Twips::new(0),
+                    y_max: lbox.bounds().height(),
+                };
+                context.renderer.draw_rect(
+                    swf::Color::from_rgb(0, 0xFF),
+                    &highlight,
+                    context.transform_stack.transform(),
+                );
+
+                let mut selected_transform =
+                    self.text_transform(swf::Color::from_rgb(0xFFFFFF, 0xFF), baseline_adjustmnet);
+                selected_transform.matrix.tx += start_x;
+                let mut after_transform = transform.clone();
+                after_transform.matrix.tx += end_x;
+
+                Self::render_text(
+                    context,
+                    font,
+                    &display_text[..from],
+                    transform,
+                    params,
+                    style,
+                );
+                Self::render_text(
+                    context,
+                    font,
+                    &display_text[from..to],
+                    selected_transform,
+                    params,
+                    style,
+                );
+                Self::render_text(
+                    context,
+                    font,
+                    &display_text[to..],
+                    after_transform,
+                    params,
+                    style,
+                );
+            } else {
+                Self::render_text(context, font, display_text, transform, params, style);
+            }
         }
 
         if let Some(drawing) = lbox.as_renderable_drawing() {
@@ -691,6 +1277,93 @@ impl<'gc> EditText<'gc> {
         context.transform_stack.pop();
     }
 
+    /// Render a run of text on a single line, starting at `transform`.
+    fn render_text(
+        context: &mut RenderContext<'_, 'gc>,
+        font: Font<'gc>,
+        text: &str,
+        transform: Transform,
+        params: EvalParameters,
+        style: FauxStyle,
+    ) {
+        let bold_offset = Twips::new(params.height().get() / FAUX_BOLD_DIVISOR);
+
+        font.evaluate(
+            text,
+            transform,
+            params,
+            |transform, glyph: &Glyph, _advance| {
+                let mut transform = transform.clone();
+                if style.italic {
+                    // Slant the glyph around its baseline.
+                    transform.matrix.c = -FAUX_ITALIC_SKEW * transform.matrix.d;
+                }
+
+                // Render glyph.
+                context.transform_stack.push(&transform);
+                context
+                    .renderer
+                    .render_shape(glyph.shape, context.transform_stack.transform());
+                context.transform_stack.pop();
+
+                if style.bold {
+                    // Overstrike the glyph slightly to the right.
+                    transform.matrix.tx += bold_offset;
+                    context.transform_stack.push(&transform);
+                    context
+                        .renderer
+                        .render_shape(glyph.shape, context.transform_stack.transform());
+                    context.transform_stack.pop();
+                }
+            },
+        );
+    }
+
+    /// Render the caret of a focused `EditText`, unless text is selected.
+    fn render_caret(self, context: &mut RenderContext<'_, 'gc>) {
+        let edit_text = self.0.read();
+        let caret = match edit_text.selection {
+            Some(selection) if edit_text.has_focus && selection.is_caret() => selection.caret(),
+            _ => return,
+        };
+        let text = edit_text.text_spans.text();
+
+        // An empty text field shows the caret at the start of the first line.
+        let mut position = (Twips::new(0), Twips::new(0));
+        let mut height =
+            Twips::from_pixels(edit_text.text_spans.default_format().size.unwrap_or(12.0));
+        for lbox in edit_text.layout.iter() {
+            if let (Some((start, end)), Some((box_text, _, font, params, _))) =
+                (lbox.text_range(), lbox.as_renderable_text(text))
+            {
+                if start <= caret && caret <= end {
+                    let before_caret = &box_text[..caret - start];
+                    let width = if edit_text.is_password {
+                        let masked_text = "*".repeat(before_caret.chars().count());
+                        font.measure(&masked_text, params, false).0
+                    } else {
+                        font.measure(before_caret, params, false).0
+                    };
+                    position = (lbox.bounds().offset_x() + width, lbox.bounds().offset_y());
+                    height = lbox.bounds().height();
+                    break;
+                }
+            }
+        }
+
+        let caret_rect = swf::Rectangle {
+            x_min: position.0,
+            x_max: position.0 + Twips::from_pixels(1.0),
+            y_min: position.1,
+            y_max: position.1 + height,
+        };
+        context.renderer.draw_rect(
+            swf::Color::from_rgb(0, 0xFF),
+            &caret_rect,
+            context.transform_stack.transform(),
+        );
+    }
+
     /// Attempts to bind this text field to a property of a display object.
     /// If we find a parent display object matching the given path, we register oursevles and a property name with it.
     /// `set_text` will be called by the stage object whenever the property changes.
@@ -742,8 +1415,8 @@ impl<'gc> EditText<'gc> {
                                 let text = self.text();
                                 if !text.is_empty() {
                                     let _ = object.set(
-                                        property,
-                                        self.text().into(),
+                                        AvmString::new(context.gc_context, property.to_string()),
+                                        AvmString::new(context.gc_context, text).into(),
                                         activation,
                                         context,
                                     );
@@ -812,6 +1485,8 @@ impl<'gc> EditText<'gc> {
                         context.swf.header().version,
                         context,
                         |activation, context| {
+                            let property = AvmString::new(context.gc_context, property.to_string());
+                            let text = AvmString::new(context.gc_context, text);
                             let _ = object.set(property, text.into(), activation, context);
                         },
                     );
@@ -862,6 +1537,14 @@ impl<'gc> TDisplayObject<'gc> for EditText<'gc> {
 
             attach_virtual_properties(context.gc_context, object);
 
+            // A text field is a listener of itself, which is how its own `onChanged` is called.
+            let listeners = avm.broadcaster_functions().initialize(
+                context.gc_context,
+                object,
+                context.system_prototypes.array,
+            );
+            listeners.set_array_element(0, object.into(), context.gc_context);
+
             text.object = Some(object);
         }
 
@@ -1003,6 +1686,8 @@ impl<'gc> TDisplayObject<'gc> for EditText<'gc> {
             self.render_layout_box(context, layout_box);
         }
 
+        self.render_caret(context);
+
         context.transform_stack.pop();
         context.transform_stack.pop();
         context.transform_stack.pop();
@@ -1012,7 +1697,30 @@ impl<'gc> TDisplayObject<'gc> for EditText<'gc> {
         false
     }
 
+    fn mouse_pick(
+        &self,
+        _avm: &mut Avm1<'gc>,
+        _context: &mut UpdateContext<'_, 'gc, '_>,
+        self_node: DisplayObject<'gc>,
+        point: (Twips, Twips),
+    ) -> Option<DisplayObject<'gc>> {
+        // Only input text fields respond to the mouse, so that they can be clicked to focus them.
+        // TODO: Selectable dynamic text fields should also be picked once text can be selected
+        // with the mouse.
+        if self.is_editable()
+            && is_hit_candidate(self_node, point)
+            && self.hit_test_self_shape(point)
+        {
+            Some(self_node)
+        } else {
+            None
+        }
+    }
+
     fn unload(&mut self, context: &mut UpdateContext<'_, 'gc, '_>) {
+        // Removed text fields lose focus.
+        context.focus_tracker.remove((*self).into());
+
         // Unbind any display objects bound to this text.
         if let Some(stage_object) = self.0.write(context.gc_context).bound_stage_object.take() {
             stage_object.clear_text_field_binding(context.gc_context, *self);
@@ -1030,10 +1738,76 @@ impl<'gc> TDisplayObject<'gc> for EditText<'gc> {
                 .retain(|&text_field| !DisplayObject::ptr_eq(text_field.into(), (*self).into()));
         }
 
+        self.clear_mask_relationships(context.gc_context);
+        self.release_bitmap_cache(context);
         self.set_removed(context.gc_context, true);
     }
 }
 
+/// Returns the position of the character before `position` in `text`.
+fn prev_char_boundary(text: &str, position: usize) -> usize {
+    text[..position]
+        .char_indices()
+        .next_back()
+        .map_or(0, |(i, _)| i)
+}
+
+/// Returns the position of the character after `position` in `text`.
+fn next_char_boundary(text: &str, position: usize) -> usize {
+    text[position..]
+        .chars()
+        .next()
+        .map_or(position, |c| position + c.len_utf8())
+}
+
+/// Returns whether the user may type `character` into a text field with the
+/// given `TextField.restrict` string.
+///
+/// The string lists the allowed characters, with ranges such as `A-Z`. A `^`
+/// switches between listing allowed and disallowed characters, and a string
+/// starting with `^` allows everything not listed. `\` escapes the next
+/// character. When a character is listed more than once, the last listing wins.
+fn is_character_allowed(restrict: &str, character: char) -> bool {
+    let mut allowed = restrict.starts_with('^');
+    let mut include = true;
+    let mut chars = restrict.chars().peekable();
+    while let Some(c) = chars.next() {
+        let first = match c {
+            '^' => {
+                include = !include;
+                continue;
+            }
+            '\\' => match chars.next() {
+                Some(c) => c,
+                None => break,
+            },
+            c => c,
+        };
+
+        let last = if chars.peek() == Some(&'-') {
+            chars.next();
+            match chars.next() {
+                Some('\\') => chars.next().unwrap_or('\\'),
+                Some(c) => c,
+                None => {
+                    // A trailing dash is listed as itself.
+                    if character == '-' {
+                        allowed = include;
+                    }
+                    first
+                }
+            }
+        } else {
+            first
+        };
+
+        if first <= character && character <= last {
+            allowed = include;
+        }
+    }
+    allowed
+}
+
 /// Static data shared between all instances of a text object.
 #[allow(dead_code)]
 #[derive(Debug, Clone)]
@@ -1048,3 +1822,51 @@ unsafe impl<'gc> gc_arena::Collect for EditTextStatic {
         false
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn restrict_lists_allowed_characters() {
+        assert!(is_character_allowed("abc", 'b'));
+        assert!(!is_character_allowed("abc", 'd'));
+        assert!(!is_character_allowed("", 'a'));
+        assert!(is_character_allowed("A-Za-z0-9", 'q'));
+        assert!(is_character_allowed("A-Za-z0-9", '7'));
+        assert!(!is_character_allowed("A-Za-z0-9", ' '));
+    }
+
+    #[test]
+    fn restrict_excludes_after_caret() {
+        assert!(is_character_allowed("^0-9", 'x'));
+        assert!(!is_character_allowed("^0-9", '5'));
+        assert!(is_character_allowed("A-Z^Q", 'P'));
+        assert!(!is_character_allowed("A-Z^Q", 'Q'));
+    }
+
+    #[test]
+    fn restrict_escapes() {
+        assert!(is_character_allowed("\\^\\-", '^'));
+        assert!(is_character_allowed("\\^\\-", '-'));
+        assert!(!is_character_allowed("\\^\\-", 'a'));
+        assert!(is_character_allowed("a-", '-'));
+        assert!(is_character_allowed("0-9\\\\", '\\'));
+    }
+
+    #[test]
+    fn char_boundaries() {
+        let text = "aé😀";
+        assert_eq!(next_char_boundary(text, 0), 1);
+        assert_eq!(next_char_boundary(text, 1), 3);
+        assert_eq!(next_char_boundary(text, 3), 7);
+        assert_eq!(next_char_boundary(text, 7), 7);
+        assert_eq!(prev_char_boundary(text, 7), 3);
+        assert_eq!(prev_char_boundary(text, 3), 1);
+        assert_eq!(prev_char_boundary(text, 0), 0);
+        assert_eq!(
+            TextSelection::for_range(2, 10).clamp(text),
+            TextSelection::for_range(1, 7)
+        );
+    }
+}
@@ -1,9 +1,12 @@
 use crate::avm1::Avm1;
+use crate::avm2::{Object as Avm2Object, Value as Avm2Value};
 use crate::backend::render::ShapeHandle;
 use crate::context::{RenderContext, UpdateContext};
 use crate::display_object::{DisplayObjectBase, TDisplayObject};
+use crate::drawing::Drawing;
 use crate::prelude::*;
-use gc_arena::{Collect, GcCell};
+use gc_arena::{Collect, GcCell, MutationContext};
+use std::cell::RefMut;
 
 #[derive(Clone, Debug, Collect, Copy)]
 #[collect(no_drop)]
@@ -13,6 +16,11 @@ pub struct Graphic<'gc>(GcCell<'gc, GraphicData<'gc>>);
 pub struct GraphicData<'gc> {
     base: DisplayObjectBase<'gc>,
     static_data: gc_arena::Gc<'gc, GraphicStatic>,
+    avm2_object: Option<Avm2Object<'gc>>,
+
+    /// The drawing API graphics of a shape created by a script, which are
+    /// drawn instead of the static shape.
+    drawing: Option<Drawing>,
 }
 
 impl<'gc> Graphic<'gc> {
@@ -21,15 +29,56 @@ impl<'gc> Graphic<'gc> {
             id: swf_shape.id,
             render_handle: context.renderer.register_shape(swf_shape.into()),
             bounds: swf_shape.shape_bounds.clone().into(),
+            shape: swf_shape.clone(),
+        };
+        Graphic(GcCell::allocate(
+            context.gc_context,
+            GraphicData {
+                base: Default::default(),
+                static_data: gc_arena::Gc::allocate(context.gc_context, static_data),
+                avm2_object: None,
+                drawing: None,
+            },
+        ))
+    }
+
+    /// Creates an empty shape for scripts to draw on, such as an AS3 `Shape`.
+    pub fn new_with_drawing(context: &mut UpdateContext<'_, 'gc, '_>) -> Self {
+        let shape = swf::Shape {
+            version: 32,
+            id: 0,
+            shape_bounds: Default::default(),
+            edge_bounds: Default::default(),
+            has_fill_winding_rule: false,
+            has_non_scaling_strokes: false,
+            has_scaling_strokes: true,
+            styles: swf::ShapeStyles {
+                fill_styles: Vec::new(),
+                line_styles: Vec::new(),
+            },
+            shape: Vec::new(),
+        };
+        let static_data = GraphicStatic {
+            id: shape.id,
+            render_handle: context.renderer.register_shape((&shape).into()),
+            bounds: shape.shape_bounds.clone().into(),
+            shape,
         };
         Graphic(GcCell::allocate(
             context.gc_context,
             GraphicData {
                 base: Default::default(),
                 static_data: gc_arena::Gc::allocate(context.gc_context, static_data),
+                avm2_object: None,
+                drawing: Some(Drawing::new()),
             },
         ))
     }
+
+    /// The renderer's shape for this graphic, shared by every instance of the character.
+    pub fn shape_handle(self) -> ShapeHandle {
+        self.0.read().static_data.render_handle
+    }
 }
 
 impl<'gc> TDisplayObject<'gc> for Graphic<'gc> {
@@ -40,7 +89,11 @@ impl<'gc> TDisplayObject<'gc> for Graphic<'gc> {
     }
 
     fn self_bounds(&self) -> BoundingBox {
-        self.0.read().static_data.bounds.clone()
+        let read = self.0.read();
+        match &read.drawing {
+            Some(drawing) => drawing.self_bounds(),
+            None => read.static_data.bounds.clone(),
+        }
     }
 
     fn world_bounds(&self) -> BoundingBox {
@@ -54,6 +107,15 @@ impl<'gc> TDisplayObject<'gc> for Graphic<'gc> {
         bounds
     }
 
+    fn hit_test_shape(&self, point: (Twips, Twips)) -> bool {
+        let local_point = self.global_to_local(point);
+        let read = self.0.read();
+        match &read.drawing {
+            Some(drawing) => drawing.hit_test(local_point),
+            None => crate::shape_utils::shape_hit_test(&read.static_data.shape, local_point),
+        }
+    }
+
     fn run_frame(&mut self, _avm: &mut Avm1<'gc>, _context: &mut UpdateContext) {
         // Noop
     }
@@ -66,19 +128,48 @@ impl<'gc> TDisplayObject<'gc> for Graphic<'gc> {
 
         context.transform_stack.push(&*self.transform());
 
-        context.renderer.render_shape(
-            self.0.read().static_data.render_handle,
-            context.transform_stack.transform(),
-        );
+        let read = self.0.read();
+        match &read.drawing {
+            Some(drawing) => drawing.render(context),
+            None => context.renderer.render_shape(
+                read.static_data.render_handle,
+                context.transform_stack.transform(),
+            ),
+        }
 
         context.transform_stack.pop();
     }
+
+    fn as_drawing(&self, gc_context: MutationContext<'gc, '_>) -> Option<RefMut<'_, Drawing>> {
+        let write = self.0.write(gc_context);
+        if write.drawing.is_some() {
+            Some(RefMut::map(write, |graphic| {
+                graphic.drawing.as_mut().unwrap()
+            }))
+        } else {
+            None
+        }
+    }
+
+    fn object2(&self) -> Avm2Value<'gc> {
+        self.0
+            .read()
+            .avm2_object
+            .map(Avm2Value::from)
+            .unwrap_or(Avm2Value::Undefined)
+    }
+
+    fn set_object2(&mut self, mc: MutationContext<'gc, '_>, to: Avm2Object<'gc>) {
+        self.0.write(mc).avm2_object = Some(to);
+    }
 }
 
 unsafe impl<'gc> gc_arena::Collect for GraphicData<'gc> {
     fn trace(&self, cc: gc_arena::CollectionContext) {
         self.base.trace(cc);
         self.static_data.trace(cc);
+        self.avm2_object.trace(cc);
+        self.drawing.trace(cc);
     }
 }
 
@@ -88,6 +179,7 @@ struct GraphicStatic {
     id: CharacterId,
     render_handle: ShapeHandle,
     bounds: BoundingBox,
+    shape: swf::Shape,
 }
 
 unsafe impl<'gc> gc_arena::Collect for GraphicStatic {
@@ -3,7 +3,8 @@ use crate::backend::render::{RenderBackend, ShapeHandle};
 use crate::context::{RenderContext, UpdateContext};
 use crate::display_object::{DisplayObjectBase, TDisplayObject};
 use crate::prelude::*;
-use gc_arena::{Collect, Gc, GcCell, MutationContext};
+use gc_arena::{Collect, GcCell, MutationContext};
+use std::collections::BTreeMap;
 use swf::Twips;
 
 #[derive(Clone, Debug, Collect, Copy)]
@@ -13,7 +14,7 @@ pub struct MorphShape<'gc>(GcCell<'gc, MorphShapeData<'gc>>);
 #[derive(Clone, Debug)]
 pub struct MorphShapeData<'gc> {
     base: DisplayObjectBase<'gc>,
-    static_data: Gc<'gc, MorphShapeStatic>,
+    static_data: GcCell<'gc, MorphShapeStatic>,
     ratio: u16,
 }
 
@@ -26,7 +27,7 @@ impl<'gc> MorphShape<'gc> {
             gc_context,
             MorphShapeData {
                 base: Default::default(),
-                static_data: Gc::allocate(gc_context, static_data),
+                static_data: GcCell::allocate(gc_context, static_data),
                 ratio: 0,
             },
         ))
@@ -37,7 +38,33 @@ impl<'gc> MorphShape<'gc> {
     }
 
     pub fn set_ratio(&mut self, gc_context: MutationContext<'gc, '_>, ratio: u16) {
-        self.0.write(gc_context).ratio = ratio;
+        let mut write = self.0.write(gc_context);
+        write.ratio = ratio;
+        write.base.set_dirty(true);
+    }
+
+    /// Tessellates this morph shape at a ratio that the movie places it at.
+    ///
+    /// Every instance of the shape shares its tessellated ratios, so this also
+    /// applies to the instances that already exist.
+    pub fn register_ratio(self, context: &mut UpdateContext<'_, 'gc, '_>, ratio: u16) {
+        self.0
+            .read()
+            .static_data
+            .write(context.gc_context)
+            .register_ratio(context.renderer, ratio);
+    }
+
+    /// The renderer's shapes for every ratio of this morph shape that has been tessellated.
+    pub fn shape_handles(self) -> Vec<ShapeHandle> {
+        self.0
+            .read()
+            .static_data
+            .read()
+            .frames
+            .values()
+            .map(|frame| frame.shape)
+            .collect()
     }
 }
 
@@ -45,7 +72,7 @@ impl<'gc> TDisplayObject<'gc> for MorphShape<'gc> {
     impl_display_object!(base);
 
     fn id(&self) -> CharacterId {
-        self.0.read().static_data.id
+        self.0.read().static_data.read().id
     }
 
     fn as_morph_shape(&self) -> Option<Self> {
@@ -59,7 +86,7 @@ impl<'gc> TDisplayObject<'gc> for MorphShape<'gc> {
     fn render(&self, context: &mut RenderContext) {
         context.transform_stack.push(&*self.transform());
 
-        if let Some(frame) = self.0.read().static_data.frames.get(&self.ratio()) {
+        if let Some(frame) = self.0.read().static_data.read().frame(self.ratio()) {
             context
                 .renderer
                 .render_shape(frame.shape, context.transform_stack.transform());
@@ -71,8 +98,7 @@ impl<'gc> TDisplayObject<'gc> for MorphShape<'gc> {
     }
 
     fn self_bounds(&self) -> BoundingBox {
-        // TODO: Use the bounds of the current ratio.
-        if let Some(frame) = self.0.read().static_data.frames.get(&self.ratio()) {
+        if let Some(frame) = self.0.read().static_data.read().frame(self.ratio()) {
             frame.bounds.clone()
         } else {
             BoundingBox::default()
@@ -89,18 +115,24 @@ unsafe impl<'gc> gc_arena::Collect for MorphShapeData<'gc> {
 }
 
 /// A precalculated intermediate frame for a morph shape.
+#[derive(Debug)]
 struct Frame {
     shape: ShapeHandle,
     bounds: BoundingBox,
 }
 
 /// Static data shared between all instances of a morph shape.
-#[allow(dead_code)]
+#[derive(Debug)]
 pub struct MorphShapeStatic {
     id: CharacterId,
     start: swf::MorphShape,
     end: swf::MorphShape,
-    frames: fnv::FnvHashMap<u16, Frame>,
+    has_non_scaling_strokes: bool,
+    has_scaling_strokes: bool,
+
+    /// The tessellated shapes of every ratio that the movie places this shape at.
+    /// Instances at the same ratio share a frame.
+    frames: BTreeMap<u16, Frame>,
 }
 
 impl MorphShapeStatic {
@@ -109,7 +141,9 @@ impl MorphShapeStatic {
             id: swf_tag.id,
             start: swf_tag.start.clone(),
             end: swf_tag.end.clone(),
-            frames: fnv::FnvHashMap::default(),
+            has_non_scaling_strokes: swf_tag.has_non_scaling_strokes,
+            has_scaling_strokes: swf_tag.has_scaling_strokes,
+            frames: BTreeMap::new(),
         };
         // Pre-register the start and end states.
         morph_shape.register_ratio(renderer, 0);
@@ -124,54 +158,16 @@ impl MorphShapeStatic {
         }
 
         // Interpolate MorphShapes into a Shape.
-        use swf::{FillStyle, Gradient, LineStyle, ShapeRecord, ShapeStyles};
-        // Start shape is ratio 65535, end shape is ratio 0.
+        use swf::{LineStyle, ShapeRecord, ShapeStyles};
+        // Start shape is ratio 0, end shape is ratio 65535.
         let b = f32::from(ratio) / 65535.0;
         let a = 1.0 - b;
-        let fill_styles: Vec<FillStyle> = self
+        let fill_styles = self
             .start
             .fill_styles
             .iter()
             .zip(self.end.fill_styles.iter())
-            .map(|(start, end)| match (start, end) {
-                (FillStyle::Color(start), FillStyle::Color(end)) => FillStyle::Color(Color {
-                    r: (a * f32::from(start.r) + b * f32::from(end.r)) as u8,
-                    g: (a * f32::from(start.g) + b * f32::from(end.g)) as u8,
-                    b: (a * f32::from(start.b) + b * f32::from(end.b)) as u8,
-                    a: (a * f32::from(start.a) + b * f32::from(end.a)) as u8,
-                }),
-                (FillStyle::LinearGradient(start), FillStyle::LinearGradient(end)) => {
-                    let records: Vec<swf::GradientRecord> = start
-                        .records
-                        .iter()
-                        .zip(end.records.iter())
-                        .map(|(start, end)| swf::GradientRecord {
-                            ratio: (f32::from(start.ratio) * a + f32::from(end.ratio) * b) as u8,
-                            color: Color {
-                                r: (a * f32::from(start.color.r) + b * f32::from(end.color.r))
-                                    as u8,
-                                g: (a * f32::from(start.color.g) + b * f32::from(end.color.g))
-                                    as u8,
-                                b: (a * f32::from(start.color.b) + b * f32::from(end.color.b))
-                                    as u8,
-                                a: (a * f32::from(start.color.a) + b * f32::from(end.color.a))
-                                    as u8,
-                            },
-                        })
-                        .collect();
-
-                    FillStyle::LinearGradient(Gradient {
-                        matrix: start.matrix,
-                        spread: start.spread,
-                        interpolation: start.interpolation,
-                        records,
-                    })
-                }
-                _ => {
-                    log::info!("Unhandled morph shape combination: {:?} {:?}", start, end);
-                    start.clone()
-                }
-            })
+            .map(|(start, end)| lerp_fill(start, end, a, b))
             .collect();
         let line_styles: Vec<LineStyle> = self
             .start
@@ -179,19 +175,15 @@ impl MorphShapeStatic {
             .iter()
             .zip(self.end.line_styles.iter())
             .map(|(start, end)| LineStyle {
-                width: Twips::new(
-                    ((start.width.get() as f32) * a + (end.width.get() as f32) * b) as i32,
-                ),
-                color: Color {
-                    r: (a * f32::from(start.color.r) + b * f32::from(end.color.r)) as u8,
-                    g: (a * f32::from(start.color.g) + b * f32::from(end.color.g)) as u8,
-                    b: (a * f32::from(start.color.b) + b * f32::from(end.color.b)) as u8,
-                    a: (a * f32::from(start.color.a) + b * f32::from(end.color.a)) as u8,
-                },
+                width: lerp_twips(start.width, end.width, a, b),
+                color: lerp_color(&start.color, &end.color, a, b),
                 start_cap: start.start_cap,
                 end_cap: start.end_cap,
                 join_style: start.join_style,
-                fill_style: None,
+                fill_style: match (&start.fill_style, &end.fill_style) {
+                    (Some(start), Some(end)) => Some(lerp_fill(start, end, a, b)),
+                    _ => None,
+                },
                 allow_scale_x: start.allow_scale_x,
                 allow_scale_y: start.allow_scale_y,
                 is_pixel_hinted: start.is_pixel_hinted,
@@ -265,7 +257,7 @@ impl MorphShapeStatic {
                         ));
                     }
                     shape.push(ShapeRecord::StyleChange(style_change));
-                    Self::update_pos(&mut end_x, &mut end_y, s);
+                    Self::update_pos(&mut end_x, &mut end_y, e);
                     end = end_iter.next();
                     continue;
                 }
@@ -291,8 +283,8 @@ impl MorphShapeStatic {
             shape_bounds: bounds.clone(),
             edge_bounds: bounds.clone(),
             has_fill_winding_rule: false,
-            has_non_scaling_strokes: false,
-            has_scaling_strokes: true,
+            has_non_scaling_strokes: self.has_non_scaling_strokes,
+            has_scaling_strokes: self.has_scaling_strokes,
             styles,
             shape,
         };
@@ -304,6 +296,16 @@ impl MorphShapeStatic {
         self.frames.insert(ratio, frame);
     }
 
+    /// Returns the frame for the given ratio.
+    /// Ratios that were never registered use the closest registered frame below them.
+    fn frame(&self, ratio: u16) -> Option<&Frame> {
+        self.frames
+            .range(..=ratio)
+            .next_back()
+            .or_else(|| self.frames.iter().next())
+            .map(|(_, frame)| frame)
+    }
+
     fn update_pos(x: &mut Twips, y: &mut Twips, record: &swf::ShapeRecord) {
         use swf::ShapeRecord;
         match record {
@@ -447,9 +449,170 @@ impl MorphShapeStatic {
     }
 }
 
+fn lerp_twips(start: Twips, end: Twips, a: f32, b: f32) -> Twips {
+    Twips::new((start.get() as f32 * a + end.get() as f32 * b) as i32)
+}
+
+fn lerp_color(start: &Color, end: &Color, a: f32, b: f32) -> Color {
+    Color {
+        r: (a * f32::from(start.r) + b * f32::from(end.r)) as u8,
+        g: (a * f32::from(start.g) + b * f32::from(end.g)) as u8,
+        b: (a * f32::from(start.b) + b * f32::from(end.b)) as u8,
+        a: (a * f32::from(start.a) + b * f32::from(end.a)) as u8,
+    }
+}
+
+fn lerp_matrix(start: &swf::Matrix, end: &swf::Matrix, a: f32, b: f32) -> swf::Matrix {
+    swf::Matrix {
+        a: start.a * a + end.a * b,
+        b: start.b * a + end.b * b,
+        c: start.c * a + end.c * b,
+        d: start.d * a + end.d * b,
+        tx: lerp_twips(start.tx, end.tx, a, b),
+        ty: lerp_twips(start.ty, end.ty, a, b),
+    }
+}
+
+fn lerp_gradient(start: &swf::Gradient, end: &swf::Gradient, a: f32, b: f32) -> swf::Gradient {
+    let records = start
+        .records
+        .iter()
+        .zip(end.records.iter())
+        .map(|(start, end)| swf::GradientRecord {
+            ratio: (f32::from(start.ratio) * a + f32::from(end.ratio) * b) as u8,
+            color: lerp_color(&start.color, &end.color, a, b),
+        })
+        .collect();
+    swf::Gradient {
+        matrix: lerp_matrix(&start.matrix, &end.matrix, a, b),
+        spread: start.spread,
+        interpolation: start.interpolation,
+        records,
+    }
+}
+
+fn lerp_fill(start: &swf::FillStyle, end: &swf::FillStyle, a: f32, b: f32) -> swf::FillStyle {
+    use swf::FillStyle;
+    match (start, end) {
+        (FillStyle::Color(start), FillStyle::Color(end)) => {
+            FillStyle::Color(lerp_color(start, end, a, b))
+        }
+        (FillStyle::LinearGradient(start), FillStyle::LinearGradient(end)) => {
+            FillStyle::LinearGradient(lerp_gradient(start, end, a, b))
+        }
+        (FillStyle::RadialGradient(start), FillStyle::RadialGradient(end)) => {
+            FillStyle::RadialGradient(lerp_gradient(start, end, a, b))
+        }
+        (
+            FillStyle::FocalGradient {
+                gradient: start,
+                focal_point: start_focal_point,
+            },
+            FillStyle::FocalGradient {
+                gradient: end,
+                focal_point: end_focal_point,
+            },
+        ) => FillStyle::FocalGradient {
+            gradient: lerp_gradient(start, end, a, b),
+            focal_point: start_focal_point * a + end_focal_point * b,
+        },
+        (
+            FillStyle::Bitmap {
+                id,
+                matrix: start,
+                is_smoothed,
+                is_repeating,
+            },
+            FillStyle::Bitmap { matrix: end, .. },
+        ) => FillStyle::Bitmap {
+            id: *id,
+            matrix: lerp_matrix(start, end, a, b),
+            is_smoothed: *is_smoothed,
+            is_repeating: *is_repeating,
+        },
+        _ => {
+            log::info!("Unhandled morph shape combination: {:?} {:?}", start, end);
+            start.clone()
+        }
+    }
+}
+
 unsafe impl<'gc> gc_arena::Collect for MorphShapeStatic {
     #[inline]
     fn needs_trace() -> bool {
         false
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend::render::NullRenderer;
+    use swf::{FillStyle, Gradient, GradientInterpolation, GradientRecord, GradientSpread};
+
+    fn focal_gradient(x: i32, color: u8, focal_point: f32) -> FillStyle {
+        FillStyle::FocalGradient {
+            gradient: Gradient {
+                matrix: swf::Matrix {
+                    tx: Twips::new(x),
+                    ..swf::Matrix::identity()
+                },
+                spread: GradientSpread::Pad,
+                interpolation: GradientInterpolation::RGB,
+                records: vec![GradientRecord {
+                    ratio: color,
+                    color: Color {
+                        r: color,
+                        g: 0,
+                        b: 0,
+                        a: 255,
+                    },
+                }],
+            },
+            focal_point,
+        }
+    }
+
+    #[test]
+    fn lerp_focal_gradient() {
+        let start = focal_gradient(0, 0, -1.0);
+        let end = focal_gradient(200, 200, 1.0);
+        assert_eq!(lerp_fill(&start, &end, 1.0, 0.0), start);
+        assert_eq!(
+            lerp_fill(&start, &end, 0.5, 0.5),
+            focal_gradient(100, 100, 0.0)
+        );
+        assert_eq!(lerp_fill(&start, &end, 0.0, 1.0), end);
+    }
+
+    #[test]
+    fn unregistered_ratio_uses_closest_frame_below() {
+        let shape = swf::MorphShape {
+            shape_bounds: Default::default(),
+            edge_bounds: Default::default(),
+            fill_styles: vec![],
+            line_styles: vec![],
+            shape: vec![],
+        };
+        let mut renderer = NullRenderer::new();
+        let mut morph_shape = MorphShapeStatic::from_swf_tag(
+            &mut renderer,
+            &swf::DefineMorphShape {
+                version: 2,
+                id: 1,
+                has_non_scaling_strokes: true,
+                has_scaling_strokes: false,
+                start: shape.clone(),
+                end: shape,
+            },
+        );
+        morph_shape.register_ratio(&mut renderer, 1000);
+
+        let registered: Vec<_> = morph_shape.frames.keys().copied().collect();
+        assert_eq!(registered, vec![0, 1000, 65535]);
+        for &(ratio, expected) in &[(0, 0), (999, 0), (1000, 1000), (30000, 1000)] {
+            let frame = morph_shape.frame(ratio).unwrap();
+            assert!(std::ptr::eq(frame, &morph_shape.frames[&expected]));
+        }
+    }
+}
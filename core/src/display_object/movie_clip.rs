@@ -1,23 +1,28 @@
 //! `MovieClip` display object and support code.
-use crate::avm1::{Avm1, Object, StageObject, TObject, Value};
+use crate::avm1::{Avm1, AvmString, Object, StageObject, TObject, Value};
 use crate::backend::audio::AudioStreamHandle;
 
 use crate::avm1::activation::{Activation, ActivationIdentifier};
+use crate::avm2::{Avm2, Object as Avm2Object, Value as Avm2Value};
+use crate::bitmap_data::BitmapSource;
 use crate::character::Character;
 use crate::context::{ActionType, RenderContext, UpdateContext};
 use crate::display_object::{
-    Bitmap, Button, DisplayObjectBase, EditText, Graphic, MorphShapeStatic, TDisplayObject, Text,
+    is_hit_candidate, object_under_point, Bitmap, Button, DisplayObjectBase, EditText, Graphic,
+    MorphShape, MorphShapeStatic, TDisplayObject, Text, Video,
 };
 use crate::drawing::Drawing;
 use crate::events::{ButtonKeyCode, ClipEvent, ClipEventResult};
 use crate::font::Font;
 use crate::prelude::*;
 use crate::shape_utils::DrawCommand;
+use crate::string_utils::{swf_string_eq_ignore_case, swf_string_to_lowercase};
 use crate::tag_utils::{self, DecodeResult, SwfMovie, SwfSlice, SwfStream};
+use crate::transform::Transform;
 use enumset::{EnumSet, EnumSetType};
 use gc_arena::{Collect, Gc, GcCell, MutationContext};
 use smallvec::SmallVec;
-use std::cell::Ref;
+use std::cell::{Ref, RefMut};
 use std::collections::{BTreeMap, HashMap};
 use std::convert::TryFrom;
 use std::sync::Arc;
@@ -42,17 +47,42 @@ pub struct MovieClipData<'gc> {
     tag_stream_pos: u64,
     current_frame: FrameNumber,
     audio_stream: Option<AudioStreamHandle>,
+
+    /// The render list: children keyed by depth, drawn in ascending order.
     children: BTreeMap<Depth, DisplayObject<'gc>>,
+
+    /// Children in the execution list, keyed by their lowercased instance name.
+    /// Several children may share a name; they are kept in insertion order.
+    child_names: HashMap<String, SmallVec<[DisplayObject<'gc>; 1]>>,
+
     object: Option<Object<'gc>>,
     clip_actions: Vec<ClipAction>,
     has_button_clip_event: bool,
     flags: EnumSet<MovieClipFlags>,
     avm1_constructor: Option<Object<'gc>>,
     drawing: Drawing,
+
+    /// The clip assigned to `hitArea`, whose shapes replace this clip's own for mouse hit tests.
+    hit_area: Option<DisplayObject<'gc>>,
+
+    /// The 9-slice scaling grid set by `DefineScalingGrid` or `scale9Grid`, in local coordinates.
+    scaling_grid: Option<BoundingBox>,
+
+    /// A goto requested while this clip was still executing another goto.
+    /// It runs once the first goto finishes.
+    queued_goto_frame: Option<FrameNumber>,
+
+    /// The AVM2 object representing this clip, such as an instance of the document class.
+    object2: Option<Avm2Object<'gc>>,
+
+    /// The AVM2 functions registered by `addFrameScript`, run whenever their frame is entered.
+    frame_scripts: Vec<Avm2FrameScript<'gc>>,
+
+    /// How far the tags of this clip have been preloaded.
+    preload_progress: PreloadProgress,
 }
 
 impl<'gc> MovieClip<'gc> {
-    #[allow(dead_code)]
     pub fn new(swf: SwfSlice, gc_context: MutationContext<'gc, '_>) -> Self {
         MovieClip(GcCell::allocate(
             gc_context,
@@ -63,12 +93,19 @@ impl<'gc> MovieClip<'gc> {
                 current_frame: 0,
                 audio_stream: None,
                 children: BTreeMap::new(),
+                child_names: HashMap::new(),
                 object: None,
                 clip_actions: Vec::new(),
                 has_button_clip_event: false,
                 flags: EnumSet::empty(),
                 avm1_constructor: None,
                 drawing: Drawing::new(),
+                hit_area: None,
+                scaling_grid: None,
+                queued_goto_frame: None,
+                object2: None,
+                frame_scripts: Vec::new(),
+                preload_progress: Default::default(),
             },
         ))
     }
@@ -89,6 +126,7 @@ impl<'gc> MovieClip<'gc> {
                         id,
                         swf,
                         total_frames: num_frames,
+                        frames_loaded: num_frames,
                         audio_stream_info: None,
                         frame_labels: HashMap::new(),
                     },
@@ -97,12 +135,19 @@ impl<'gc> MovieClip<'gc> {
                 current_frame: 0,
                 audio_stream: None,
                 children: BTreeMap::new(),
+                child_names: HashMap::new(),
                 object: None,
                 clip_actions: Vec::new(),
                 has_button_clip_event: false,
                 flags: MovieClipFlags::Playing.into(),
                 avm1_constructor: None,
                 drawing: Drawing::new(),
+                hit_area: None,
+                scaling_grid: None,
+                queued_goto_frame: None,
+                object2: None,
+                frame_scripts: Vec::new(),
+                preload_progress: Default::default(),
             },
         ))
     }
@@ -132,20 +177,108 @@ impl<'gc> MovieClip<'gc> {
             .replace_with_movie(gc_context, movie)
     }
 
-    pub fn preload(
-        self,
-        avm: &mut Avm1<'gc>,
+    /// Unloads the movie playing in this clip, as done by `unloadMovie`, leaving an empty clip.
+    ///
+    /// Loads into this clip that are still in progress are cancelled.
+    pub fn unload_movie(&mut self, context: &mut UpdateContext<'_, 'gc, '_>) {
+        context.load_manager.cancel_movie_loads((*self).into());
+        self.replace_movie(context, None);
+    }
+
+    /// Replaces the movie playing in this clip with a new movie, or an empty one if `None`.
+    ///
+    /// The current movie is unloaded and its resources are freed.
+    pub fn replace_movie(
+        &mut self,
         context: &mut UpdateContext<'_, 'gc, '_>,
-        morph_shapes: &mut fnv::FnvHashMap<CharacterId, MorphShapeStatic>,
+        movie: Option<Arc<SwfMovie>>,
     ) {
+        self.unload(context);
+        self.release_movie(context);
+        self.replace_with_movie(context.gc_context, movie);
+    }
+
+    /// Marks this clip as a root of the movie loaded into it, keeping the movie's library
+    /// alive until the clip releases it with `release_movie`.
+    pub fn hold_movie(self, context: &mut UpdateContext<'_, 'gc, '_>) {
+        let mut mc = self.0.write(context.gc_context);
+        if !mc.flags.contains(MovieClipFlags::HoldsMovie) {
+            mc.flags.insert(MovieClipFlags::HoldsMovie);
+            context.library.hold_movie_library(mc.movie());
+        }
+    }
+
+    /// Whether this clip is a root of the movie loaded into it.
+    pub fn holds_movie(self) -> bool {
+        self.0.read().flags.contains(MovieClipFlags::HoldsMovie)
+    }
+
+    /// Frees the resources of the movie loaded into this clip: its sounds are stopped, and
+    /// once no other clip holds the movie, its library is dropped along with the meshes,
+    /// textures and sound data of its characters.
+    ///
+    /// Does nothing if this clip doesn't hold its movie, such as a clip inside the movie.
+    pub fn release_movie(self, context: &mut UpdateContext<'_, 'gc, '_>) {
+        let movie = {
+            let mut mc = self.0.write(context.gc_context);
+            if !mc.flags.contains(MovieClipFlags::HoldsMovie) {
+                return;
+            }
+            mc.flags.remove(MovieClipFlags::HoldsMovie);
+            mc.movie()
+        };
+        context
+            .audio_manager
+            .stop_sounds_with_display_object(context.audio, self.into());
+        if let Some(library) = context.library.release_movie_library(movie) {
+            library.release_resources(context.renderer, context.audio, context.audio_manager);
+        }
+    }
+
+    /// Defines the parameters of the movie loaded into this clip, such as its FlashVars, as
+    /// string variables of the clip.
+    pub fn set_movie_parameters(self, context: &mut UpdateContext<'_, 'gc, '_>) {
+        let movie = if let Some(movie) = self.movie() {
+            movie
+        } else {
+            return;
+        };
+        if let Value::Object(object) = self.object() {
+            for (name, value) in movie.parameters() {
+                object.define_value(
+                    context.gc_context,
+                    AvmString::new(context.gc_context, name.clone()),
+                    AvmString::new(context.gc_context, value.clone()).into(),
+                    EnumSet::empty(),
+                );
+            }
+        }
+    }
+
+    /// Preloads the tags of this clip, defining the characters in them and finding out
+    /// how many of its frames have loaded.
+    ///
+    /// The main timeline of a movie that is still loading is preloaded up to the end of
+    /// the data that has loaded. Preloading it again picks up where the last preload
+    /// stopped, taking in the data that has loaded since.
+    pub fn preload(self, avm: &mut Avm1<'gc>, context: &mut UpdateContext<'_, 'gc, '_>) {
         use swf::TagCode;
+        if self.is_preloaded() {
+            return;
+        }
+
         // TODO: Re-creating static data because preload step occurs after construction.
         // Should be able to hoist this up somewhere, or use MaybeUninit.
         let mut static_data = (&*self.0.read().static_data).clone();
-        let data = self.0.read().static_data.swf.clone();
-        let mut reader = data.read_from(self.0.read().tag_stream_pos);
-        let mut cur_frame = 1;
-        let mut ids = fnv::FnvHashMap::default();
+        let is_loading = self.holds_movie() && !static_data.swf.movie.is_loaded();
+        if self.holds_movie() {
+            // Checked after `is_loading`, so that no data is missed if the movie
+            // finishes loading in between.
+            static_data.swf = static_data.swf.movie.clone().into();
+        }
+        let data = static_data.swf.clone();
+        let mut progress = std::mem::take(&mut self.0.write(context.gc_context).preload_progress);
+        let mut reader = data.read_from(progress.next_tag_pos);
         let version = reader.version();
         let tag_callback = |reader: &mut SwfStream<&[u8]>, tag_code, tag_len| {
             let data = *reader.get_inner().get_ref();
@@ -156,7 +289,7 @@ impl<'gc> MovieClip<'gc> {
                 TagCode::FileAttributes => {
                     let attributes = reader.read_file_attributes()?;
                     if attributes.is_action_script_3 {
-                        log::warn!("This SWF contains ActionScript 3 which is not yet supported by Ruffle. The movie may not work as intended.");
+                        log::warn!("This SWF contains ActionScript 3, which Ruffle only partially supports. The movie may not work as intended.");
                     }
                     Ok(())
                 }
@@ -216,19 +349,18 @@ impl<'gc> MovieClip<'gc> {
                     .0
                     .write(context.gc_context)
                     .define_font_3(context, reader),
-                TagCode::DefineFont4 => unimplemented!(),
-                TagCode::DefineMorphShape => self.0.write(context.gc_context).define_morph_shape(
-                    context,
-                    reader,
-                    morph_shapes,
-                    1,
-                ),
-                TagCode::DefineMorphShape2 => self.0.write(context.gc_context).define_morph_shape(
-                    context,
-                    reader,
-                    morph_shapes,
-                    2,
-                ),
+                TagCode::DefineMorphShape => self
+                    .0
+                    .write(context.gc_context)
+                    .define_morph_shape(context, reader, 1),
+                TagCode::DefineMorphShape2 => self
+                    .0
+                    .write(context.gc_context)
+                    .define_morph_shape(context, reader, 2),
+                TagCode::DefineScalingGrid => self
+                    .0
+                    .write(context.gc_context)
+                    .define_scaling_grid(context, reader),
                 TagCode::DefineShape => self
                     .0
                     .write(context.gc_context)
@@ -249,13 +381,10 @@ impl<'gc> MovieClip<'gc> {
                     .0
                     .write(context.gc_context)
                     .define_sound(context, reader),
-                TagCode::DefineSprite => self.0.write(context.gc_context).define_sprite(
-                    avm,
-                    context,
-                    reader,
-                    tag_len,
-                    morph_shapes,
-                ),
+                TagCode::DefineSprite => self
+                    .0
+                    .write(context.gc_context)
+                    .define_sprite(avm, context, reader, tag_len),
                 TagCode::DefineText => self
                     .0
                     .write(context.gc_context)
@@ -264,7 +393,10 @@ impl<'gc> MovieClip<'gc> {
                     .0
                     .write(context.gc_context)
                     .define_text(context, reader, 2),
-                TagCode::DoInitAction => self.do_init_action(avm, context, reader, tag_len),
+                TagCode::DefineVideoStream => self
+                    .0
+                    .write(context.gc_context)
+                    .define_video_stream(context, reader),
                 TagCode::ExportAssets => self
                     .0
                     .write(context.gc_context)
@@ -273,7 +405,7 @@ impl<'gc> MovieClip<'gc> {
                     context,
                     reader,
                     tag_len,
-                    cur_frame,
+                    progress.cur_frame,
                     &mut static_data,
                 ),
                 TagCode::JpegTables => self
@@ -284,116 +416,153 @@ impl<'gc> MovieClip<'gc> {
                     context,
                     reader,
                     tag_len,
-                    &mut ids,
-                    morph_shapes,
+                    &mut progress.morph_shape_depths,
                     1,
                 ),
                 TagCode::PlaceObject2 => self.0.write(context.gc_context).preload_place_object(
                     context,
                     reader,
                     tag_len,
-                    &mut ids,
-                    morph_shapes,
+                    &mut progress.morph_shape_depths,
                     2,
                 ),
                 TagCode::PlaceObject3 => self.0.write(context.gc_context).preload_place_object(
                     context,
                     reader,
                     tag_len,
-                    &mut ids,
-                    morph_shapes,
+                    &mut progress.morph_shape_depths,
                     3,
                 ),
                 TagCode::PlaceObject4 => self.0.write(context.gc_context).preload_place_object(
                     context,
                     reader,
                     tag_len,
-                    &mut ids,
-                    morph_shapes,
+                    &mut progress.morph_shape_depths,
                     4,
                 ),
-                TagCode::RemoveObject => self
-                    .0
-                    .write(context.gc_context)
-                    .preload_remove_object(context, reader, &mut ids, 1),
-                TagCode::RemoveObject2 => self
-                    .0
-                    .write(context.gc_context)
-                    .preload_remove_object(context, reader, &mut ids, 2),
+                TagCode::RemoveObject => self.0.write(context.gc_context).preload_remove_object(
+                    context,
+                    reader,
+                    &mut progress.morph_shape_depths,
+                    1,
+                ),
+                TagCode::RemoveObject2 => self.0.write(context.gc_context).preload_remove_object(
+                    context,
+                    reader,
+                    &mut progress.morph_shape_depths,
+                    2,
+                ),
                 TagCode::ShowFrame => self.0.write(context.gc_context).preload_show_frame(
                     context,
                     reader,
-                    &mut cur_frame,
+                    &mut progress.cur_frame,
                 ),
-                TagCode::SoundStreamHead => self
-                    .0
-                    .write(context.gc_context)
-                    .preload_sound_stream_head(context, reader, cur_frame, &mut static_data, 1),
-                TagCode::SoundStreamHead2 => self
-                    .0
-                    .write(context.gc_context)
-                    .preload_sound_stream_head(context, reader, cur_frame, &mut static_data, 2),
+                TagCode::SoundStreamHead => {
+                    self.0.write(context.gc_context).preload_sound_stream_head(
+                        context,
+                        reader,
+                        progress.cur_frame,
+                        &mut static_data,
+                        1,
+                    )
+                }
+                TagCode::SoundStreamHead2 => {
+                    self.0.write(context.gc_context).preload_sound_stream_head(
+                        context,
+                        reader,
+                        progress.cur_frame,
+                        &mut static_data,
+                        2,
+                    )
+                }
                 TagCode::SoundStreamBlock => {
                     self.0.write(context.gc_context).preload_sound_stream_block(
                         context,
                         reader,
-                        cur_frame,
+                        progress.cur_frame,
                         &mut static_data,
                         tag_len,
                     )
                 }
+                TagCode::VideoFrame => self
+                    .0
+                    .write(context.gc_context)
+                    .preload_video_frame(context, reader),
                 _ => Ok(()),
             }
         };
-        let _ = tag_utils::decode_tags(&mut reader, tag_callback, TagCode::End);
-        self.0.write(context.gc_context).static_data =
-            Gc::allocate(context.gc_context, static_data);
+        let mut diagnostics = Vec::new();
+        let reached_end = tag_utils::decode_loaded_tags(
+            &mut reader,
+            tag_callback,
+            TagCode::End,
+            &mut diagnostics,
+        )
+        .unwrap_or(false);
+        progress.next_tag_pos = reader.get_ref().position();
+        if reached_end {
+            static_data.frames_loaded = static_data.total_frames;
+            progress.is_complete = true;
+        } else {
+            // Only the frames whose tags have all loaded can be played.
+            static_data.frames_loaded = (progress.cur_frame - 1).min(static_data.total_frames);
+            if !is_loading {
+                // The data ends early, so the rest of the frames never load.
+                diagnostics.extend(tag_utils::truncated_tag_diagnostic(&reader));
+                progress.is_complete = true;
+            }
+        }
+        let is_complete = progress.is_complete;
+        let mut write = self.0.write(context.gc_context);
+        write.static_data = Gc::allocate(context.gc_context, static_data);
+        write.preload_progress = progress;
+        drop(write);
+        if !diagnostics.is_empty() {
+            context
+                .library
+                .library_for_movie_mut(data.movie.clone())
+                .add_diagnostics(diagnostics);
+        }
 
         // Finalize audio stream.
-        if self.0.read().static_data.audio_stream_info.is_some() {
+        if is_complete && self.0.read().static_data.audio_stream_info.is_some() {
             context.audio.preload_sound_stream_end(self.0.read().id());
         }
     }
 
-    #[inline]
-    fn do_init_action(
+    /// Whether all of the tags of this clip have been preloaded, so that preloading it
+    /// again does nothing.
+    pub fn is_preloaded(self) -> bool {
+        self.0.read().preload_progress.is_complete
+    }
+
+    /// Runs the init actions of a sprite, unless they have already run.
+    ///
+    /// Init actions run in the context of this movie's `_root`, the first time the
+    /// frame containing them is reached, before any of that frame's other actions.
+    fn run_init_action(
         self,
+        self_display_object: DisplayObject<'gc>,
         avm: &mut Avm1<'gc>,
         context: &mut UpdateContext<'_, 'gc, '_>,
-        reader: &mut SwfStream<&[u8]>,
-        tag_len: usize,
-    ) -> DecodeResult {
-        // Queue the init actions.
-
-        // TODO: Init actions are supposed to be executed once, and it gives a
-        // sprite ID... how does that work?
-        let sprite_id = reader.read_u16()?;
-        log::info!("Init Action sprite ID {}", sprite_id);
-
-        let slice = self
-            .0
-            .read()
-            .static_data
-            .swf
-            .resize_to_reader(reader, tag_len)
-            .ok_or_else(|| {
-                std::io::Error::new(
-                    std::io::ErrorKind::Other,
-                    "Invalid source or tag length when running init action",
-                )
-            })?;
-
-        avm.run_stack_frame_for_init_action(
-            *context.levels.get(&0).unwrap(),
-            context.swf.header().version,
-            slice,
-            context,
-        );
-
-        Ok(())
+        sprite_id: CharacterId,
+        code: SwfSlice,
+    ) {
+        let movie = self.0.read().movie();
+        if context
+            .library
+            .library_for_movie_mut(movie.clone())
+            .mark_init_actions_run(sprite_id)
+        {
+            avm.run_stack_frame_for_init_action(
+                self_display_object.root(),
+                movie.version(),
+                code,
+                context,
+            );
+        }
     }
 
-    #[allow(dead_code)]
     pub fn playing(self) -> bool {
         self.0.read().playing()
     }
@@ -418,6 +587,20 @@ impl<'gc> MovieClip<'gc> {
         self.0.write(context.gc_context).stop(context)
     }
 
+    /// Whether this clip goes back to its first frame after its last frame.
+    pub fn looping(self) -> bool {
+        !self.0.read().flags.contains(MovieClipFlags::NoLoop)
+    }
+
+    pub fn set_looping(self, gc_context: MutationContext<'gc, '_>, looping: bool) {
+        let mut write = self.0.write(gc_context);
+        if looping {
+            write.flags.remove(MovieClipFlags::NoLoop);
+        } else {
+            write.flags.insert(MovieClipFlags::NoLoop);
+        }
+    }
+
     /// Queues up a goto to the specified frame.
     /// `frame` should be 1-based.
     pub fn goto_frame(
@@ -452,9 +635,39 @@ impl<'gc> MovieClip<'gc> {
         self.0.read().static_data.total_frames
     }
 
-    pub fn frames_loaded(self) -> FrameNumber {
-        // TODO(Herschel): root needs to progressively stream in frames.
-        self.0.read().static_data.total_frames
+    /// Returns the `(loaded, total)` byte progress of this clip's movie.
+    /// Clips that are the target of a pending `loadMovie` report the progress of the fetch;
+    /// all other clips report the progress of their movie, which is fully loaded unless it
+    /// is still streaming in.
+    pub fn load_progress(self, context: &UpdateContext<'_, 'gc, '_>) -> (usize, usize) {
+        context
+            .load_manager
+            .movie_clip_progress(self.into())
+            .unwrap_or_else(|| {
+                let movie = self.0.read().movie();
+                (movie.bytes_loaded(), movie.compressed_length())
+            })
+    }
+
+    pub fn bytes_loaded(self, context: &UpdateContext<'_, 'gc, '_>) -> usize {
+        self.load_progress(context).0
+    }
+
+    pub fn bytes_total(self, context: &UpdateContext<'_, 'gc, '_>) -> usize {
+        self.load_progress(context).1
+    }
+
+    /// Returns the number of frames of this clip that can be played, as reported by
+    /// `_framesloaded` and checked by `ifFrameLoaded`.
+    ///
+    /// Frames are loaded once all of their tags have been preloaded, so this grows as
+    /// the movie of the clip streams in. A clip that a `loadMovie` has yet to receive
+    /// any data for has no frames loaded.
+    pub fn frames_loaded(self, context: &UpdateContext<'_, 'gc, '_>) -> FrameNumber {
+        match context.load_manager.movie_clip_progress(self.into()) {
+            Some((0, _)) => 0,
+            _ => self.0.read().static_data.frames_loaded,
+        }
     }
 
     pub fn set_avm1_constructor(
@@ -476,6 +689,11 @@ impl<'gc> MovieClip<'gc> {
         self.0.read().children.keys().copied().rev().next()
     }
 
+    /// Returns the child placed at the given depth, if any.
+    pub fn child_by_depth(self, depth: Depth) -> Option<DisplayObject<'gc>> {
+        self.0.read().children.get(&depth).copied()
+    }
+
     /// Gets the clip events for this movieclip.
     pub fn clip_actions(&self) -> Ref<[ClipAction]> {
         Ref::map(self.0.read(), |mc| mc.clip_actions())
@@ -507,11 +725,14 @@ impl<'gc> MovieClip<'gc> {
         child.set_parent(context.gc_context, Some((*self).into()));
         child.set_place_frame(context.gc_context, 0);
         child.set_depth(context.gc_context, depth);
+        drop(parent);
+        self.invalidate_cached_bitmap();
     }
 
     /// Remove a child from this clip.
     pub fn remove_child_from_avm(
         &mut self,
+        activation: &mut Activation<'_, 'gc>,
         context: &mut UpdateContext<'_, 'gc, '_>,
         child: DisplayObject<'gc>,
     ) {
@@ -519,10 +740,64 @@ impl<'gc> MovieClip<'gc> {
             child.parent().unwrap(),
             (*self).into()
         ));
-        let mut parent = self.0.write(context.gc_context);
-        if let Some(child) = parent.children.remove(&child.depth()) {
-            parent.remove_child_from_exec_list(context, child);
+        self.remove_child(activation, context, child);
+    }
+
+    /// Removes a child from the display list, as done by `RemoveObject` tags and
+    /// `removeMovieClip`.
+    ///
+    /// A child with an unload handler isn't removed right away. Instead, it is unloaded
+    /// and moved to a negative depth, where it stays until this clip's next frame.
+    /// This lets its unload code still see the clip on the display list, and frees its
+    /// original depth for a new child.
+    fn remove_child(
+        self,
+        activation: &mut Activation<'_, 'gc>,
+        context: &mut UpdateContext<'_, 'gc, '_>,
+        mut child: DisplayObject<'gc>,
+    ) {
+        let depth = child.depth();
+        if has_unload_handler(child, activation, context) {
+            // Flash moves the clip from depth `d` to `-32769 - d`, relative to the AVM depth bias.
+            let pending_depth = -1 - depth;
+            child.unload(context);
+            child.set_depth(context.gc_context, pending_depth);
+            let mut mc = self.0.write(context.gc_context);
+            mc.children.remove(&depth);
+            if let Some(prev_child) = mc.children.insert(pending_depth, child) {
+                mc.remove_child_from_exec_list(context, prev_child);
+            }
+        } else {
+            let mut mc = self.0.write(context.gc_context);
+            if let Some(child) = mc.children.remove(&depth) {
+                mc.remove_child_from_exec_list(context, child);
+            }
+        }
+        self.invalidate_cached_bitmap();
+    }
+
+    /// Removes the children that were kept on the display list for their unload handlers.
+    fn remove_pending_children(self, context: &mut UpdateContext<'_, 'gc, '_>) {
+        // Pending children are the only ones at negative depths that have been unloaded.
+        let pending: SmallVec<[_; 4]> = self
+            .0
+            .read()
+            .children
+            .range(..0)
+            .filter(|(_, child)| child.removed())
+            .map(|(depth, child)| (*depth, *child))
+            .collect();
+        if pending.is_empty() {
+            return;
         }
+
+        let mut mc = self.0.write(context.gc_context);
+        for (depth, child) in pending {
+            mc.children.remove(&depth);
+            mc.remove_child_from_exec_list(context, child);
+        }
+        drop(mc);
+        self.invalidate_cached_bitmap();
     }
 
     /// Swaps a child to a target depth.
@@ -547,6 +822,97 @@ impl<'gc> MovieClip<'gc> {
         } else {
             parent.children.remove(&prev_depth);
         }
+        drop(parent);
+        self.invalidate_cached_bitmap();
+    }
+
+    /// The number of children on the display list of this clip.
+    pub fn num_children(self) -> usize {
+        self.0.read().children.len()
+    }
+
+    /// Returns the child at the given index of the display list, as used by AS3.
+    /// Index zero is the bottom-most child.
+    pub fn child_by_index(self, index: usize) -> Option<DisplayObject<'gc>> {
+        self.0.read().children.values().nth(index).copied()
+    }
+
+    /// Returns the index of a child in the display list, as used by AS3.
+    pub fn child_index(self, child: DisplayObject<'gc>) -> Option<usize> {
+        self.0
+            .read()
+            .children
+            .values()
+            .position(|c| DisplayObject::ptr_eq(*c, child))
+    }
+
+    /// Inserts a script-created child into the display list at the given index, as done by
+    /// AS3 `addChildAt`. Children at or above the index move up by one.
+    ///
+    /// The display list is ordered by depth, so the new child takes the depth just above the
+    /// child below it, and the children above it are moved to higher depths as needed.
+    pub fn insert_child_at_index(
+        self,
+        context: &mut UpdateContext<'_, 'gc, '_>,
+        mut child: DisplayObject<'gc>,
+        index: usize,
+    ) {
+        let mut mc = self.0.write(context.gc_context);
+        let above: Vec<_> = mc
+            .children
+            .iter()
+            .skip(index)
+            .map(|(depth, child)| (*depth, *child))
+            .collect();
+        let depth = match index.checked_sub(1) {
+            Some(below) => mc.children.keys().nth(below).map_or(0, |depth| depth + 1),
+            None => mc.children.keys().next().copied().unwrap_or(0),
+        };
+
+        let mut moved = Vec::new();
+        for ((old_depth, above_child), new_depth) in above.into_iter().zip(depth + 1..) {
+            if old_depth >= new_depth {
+                break;
+            }
+            mc.children.remove(&old_depth);
+            moved.push((new_depth, above_child));
+        }
+        for (new_depth, above_child) in moved {
+            above_child.set_depth(context.gc_context, new_depth);
+            mc.children.insert(new_depth, above_child);
+        }
+
+        mc.children.insert(depth, child);
+        mc.add_child_to_exec_list(context.gc_context, child);
+        drop(mc);
+        child.set_parent(context.gc_context, Some(self.into()));
+        child.set_place_frame(context.gc_context, 0);
+        child.set_depth(context.gc_context, depth);
+        self.invalidate_cached_bitmap();
+    }
+
+    /// Takes a child off the display list without unloading it, as done by AS3
+    /// `removeChild`. The child may be added to a display list again later.
+    pub fn take_child(
+        self,
+        context: &mut UpdateContext<'_, 'gc, '_>,
+        mut child: DisplayObject<'gc>,
+    ) {
+        let mut mc = self.0.write(context.gc_context);
+        let depth = mc
+            .children
+            .iter()
+            .find(|(_, c)| DisplayObject::ptr_eq(**c, child))
+            .map(|(depth, _)| *depth);
+        if let Some(depth) = depth {
+            mc.children.remove(&depth);
+            mc.unlink_child_from_exec_list(context.gc_context, child);
+        }
+        drop(mc);
+        child.set_prev_sibling(context.gc_context, None);
+        child.set_next_sibling(context.gc_context, None);
+        child.set_parent(context.gc_context, None);
+        self.invalidate_cached_bitmap();
     }
 
     /// Returns an iterator of AVM1 `DoAction` blocks on the given frame number.
@@ -586,18 +952,141 @@ impl<'gc> MovieClip<'gc> {
         actions.into_iter()
     }
 
+    /// Gets the drawing API graphics of this clip.
+    pub fn drawing(&self) -> Ref<Drawing> {
+        Ref::map(self.0.read(), |mc| &mc.drawing)
+    }
+
+    /// Replaces the drawing API graphics of this clip.
+    pub fn set_drawing(self, gc_context: MutationContext<'gc, '_>, drawing: Drawing) {
+        self.0.write(gc_context).drawing = drawing;
+        self.invalidate_cached_bitmap();
+    }
+
+    /// Whether this clip acts as a button, because it has `on` handlers for button
+    /// events or button event methods such as `onRelease`.
+    pub fn is_button_mode(
+        self,
+        activation: &mut Activation<'_, 'gc>,
+        context: &mut UpdateContext<'_, 'gc, '_>,
+    ) -> bool {
+        if self.0.read().has_button_clip_event {
+            return true;
+        }
+        let object = self.object().coerce_to_object(activation, context);
+        ClipEvent::BUTTON_EVENT_METHODS
+            .iter()
+            .any(|handler| object.has_property(activation, context, handler))
+    }
+
+    /// Gets the clip assigned to `hitArea`, if any.
+    pub fn hit_area(self) -> Option<DisplayObject<'gc>> {
+        self.0.read().hit_area
+    }
+
+    pub fn set_hit_area(
+        self,
+        gc_context: MutationContext<'gc, '_>,
+        hit_area: Option<DisplayObject<'gc>>,
+    ) {
+        self.0.write(gc_context).hit_area = hit_area;
+    }
+
+    /// Gets the 9-slice scaling grid of this clip, if any.
+    pub fn scaling_grid(self) -> Option<BoundingBox> {
+        self.0.read().scaling_grid.clone()
+    }
+
+    pub fn set_scaling_grid(
+        self,
+        gc_context: MutationContext<'gc, '_>,
+        scaling_grid: Option<BoundingBox>,
+    ) {
+        self.0.write(gc_context).scaling_grid = scaling_grid;
+    }
+
+    /// Splits this clip into the nine slices of its scaling grid.
+    ///
+    /// Each slice is returned as the rectangle it covers in local coordinates, together with
+    /// the matrix that maps it into the parent's coordinates. Corners keep their size, edges
+    /// stretch along one axis, and the center stretches along both. If the clip is scaled
+    /// smaller than its corners, the corners shrink to fit.
+    ///
+    /// As in Flash, the grid only applies when the clip is not rotated or skewed and holds
+    /// nothing but shapes; returns `None` otherwise.
+    fn scaling_grid_slices(self) -> Option<Vec<(BoundingBox, Matrix)>> {
+        let grid = self.scaling_grid().filter(|grid| grid.valid)?;
+        let matrix = *self.matrix();
+        if matrix.b != 0.0 || matrix.c != 0.0 || matrix.a <= 0.0 || matrix.d <= 0.0 {
+            return None;
+        }
+        let only_shapes = self.children().all(|child| {
+            matches!(
+                child,
+                DisplayObject::Graphic(_) | DisplayObject::MorphShape(_)
+            )
+        });
+        if !only_shapes {
+            return None;
+        }
+
+        let bounds = self.bounds();
+        if !bounds.valid
+            || grid.x_min < bounds.x_min
+            || grid.x_max > bounds.x_max
+            || grid.y_min < bounds.y_min
+            || grid.y_max > bounds.y_max
+        {
+            return None;
+        }
+
+        let columns = scaling_grid_axis(
+            [bounds.x_min, grid.x_min, grid.x_max, bounds.x_max],
+            matrix.a,
+        );
+        let rows = scaling_grid_axis(
+            [bounds.y_min, grid.y_min, grid.y_max, bounds.y_max],
+            matrix.d,
+        );
+        let mut slices = Vec::with_capacity(9);
+        for &(y_min, y_max, d, ty) in &rows {
+            for &(x_min, x_max, a, tx) in &columns {
+                let slice = BoundingBox {
+                    x_min,
+                    y_min,
+                    x_max,
+                    y_max,
+                    valid: true,
+                };
+                let slice_matrix = Matrix {
+                    a,
+                    b: 0.0,
+                    c: 0.0,
+                    d,
+                    tx: matrix.tx + tx,
+                    ty: matrix.ty + ty,
+                };
+                slices.push((slice, slice_matrix));
+            }
+        }
+        Some(slices)
+    }
+
     pub fn set_fill_style(
         self,
         context: &mut UpdateContext<'_, 'gc, '_>,
         style: Option<FillStyle>,
     ) {
-        let mut mc = self.0.write(context.gc_context);
-        mc.drawing.set_fill_style(style);
+        self.0
+            .write(context.gc_context)
+            .drawing
+            .set_fill_style(style);
+        self.invalidate_cached_bitmap();
     }
 
     pub fn clear(self, context: &mut UpdateContext<'_, 'gc, '_>) {
-        let mut mc = self.0.write(context.gc_context);
-        mc.drawing.clear();
+        self.0.write(context.gc_context).drawing.clear();
+        self.invalidate_cached_bitmap();
     }
 
     pub fn set_line_style(
@@ -605,13 +1094,27 @@ impl<'gc> MovieClip<'gc> {
         context: &mut UpdateContext<'_, 'gc, '_>,
         style: Option<LineStyle>,
     ) {
-        let mut mc = self.0.write(context.gc_context);
-        mc.drawing.set_line_style(style);
+        self.0
+            .write(context.gc_context)
+            .drawing
+            .set_line_style(style);
+        self.invalidate_cached_bitmap();
+    }
+
+    pub fn set_line_fill_style(self, context: &mut UpdateContext<'_, 'gc, '_>, style: FillStyle) {
+        self.0
+            .write(context.gc_context)
+            .drawing
+            .set_line_fill_style(style);
+        self.invalidate_cached_bitmap();
     }
 
     pub fn draw_command(self, context: &mut UpdateContext<'_, 'gc, '_>, command: DrawCommand) {
-        let mut mc = self.0.write(context.gc_context);
-        mc.drawing.draw_command(command);
+        self.0
+            .write(context.gc_context)
+            .drawing
+            .draw_command(command);
+        self.invalidate_cached_bitmap();
     }
 
     pub fn run_clip_event(
@@ -631,10 +1134,16 @@ impl<'gc> MovieClip<'gc> {
         context: &mut UpdateContext<'_, 'gc, '_>,
         run_display_actions: bool,
     ) {
+        // The playhead waits on the last loaded frame until the next one is available.
+        let frames_loaded = self.0.read().static_data.frames_loaded;
+        if self.current_frame() >= frames_loaded && frames_loaded < self.total_frames() {
+            return;
+        }
+
         // Advance frame number.
         if self.current_frame() < self.total_frames() {
             self.0.write(context.gc_context).current_frame += 1;
-        } else if self.total_frames() > 1 {
+        } else if self.total_frames() > 1 && self.looping() {
             // Looping acts exactly like a gotoAndPlay(1).
             // Specifically, object that existed on frame 1 should not be destroyed
             // and recreated.
@@ -650,10 +1159,38 @@ impl<'gc> MovieClip<'gc> {
         let data = mc.static_data.swf.clone();
         let mut reader = data.read_from(mc.tag_stream_pos);
         let mut has_stream_block = false;
-        drop(mc);
 
         let version = reader.version();
         use swf::TagCode;
+
+        // Init actions and ABC scripts run before the frame places its children, wherever
+        // they are in the frame.
+        let mut init_actions = vec![];
+        let mut avm2_tags = vec![];
+        let mut init_reader = data.read_from(mc.tag_stream_pos);
+        let init_callback = |reader: &mut SwfStream<&[u8]>, tag_code, tag_len| {
+            let data = *reader.get_inner().get_ref();
+            let tag_pos = reader.get_inner().position() as usize;
+            let tag_slice = &data[tag_pos..tag_pos + tag_len];
+            let reader = &mut SwfStream::new(std::io::Cursor::new(tag_slice), version);
+            match tag_code {
+                TagCode::DoInitAction => init_actions.push(mc.init_action(reader, tag_len)?),
+                TagCode::DoAbc | TagCode::DoAbcDefine | TagCode::SymbolClass => {
+                    avm2_tags.push(mc.avm2_tag(reader, tag_code, tag_pos, tag_len)?)
+                }
+                _ => (),
+            }
+            Ok(())
+        };
+        let _ = tag_utils::decode_tags(&mut init_reader, init_callback, TagCode::ShowFrame);
+        drop(mc);
+
+        for (sprite_id, code) in init_actions {
+            self.run_init_action(self_display_object, avm, context, sprite_id, code);
+        }
+
+        let symbol_classes = self.run_abc_tags(context, avm2_tags);
+
         let tag_callback = |reader: &mut SwfStream<&[u8]>, tag_code, tag_len| {
             let data = *reader.get_inner().get_ref();
             let tag_pos = reader.get_inner().position() as usize;
@@ -674,10 +1211,10 @@ impl<'gc> MovieClip<'gc> {
                     self.place_object(self_display_object, avm, context, reader, tag_len, 4)
                 }
                 TagCode::RemoveObject if run_display_actions => {
-                    self.remove_object(context, reader, 1)
+                    self.remove_object(avm, context, reader, 1)
                 }
                 TagCode::RemoveObject2 if run_display_actions => {
-                    self.remove_object(context, reader, 2)
+                    self.remove_object(avm, context, reader, 2)
                 }
                 TagCode::SetBackgroundColor => self.set_background_color(context, reader),
                 TagCode::StartSound => self.start_sound_1(context, reader),
@@ -692,10 +1229,63 @@ impl<'gc> MovieClip<'gc> {
 
         self.0.write(context.gc_context).tag_stream_pos = reader.get_ref().position();
 
+        // The document class is constructed once the frame has placed its children.
+        self.run_symbol_classes(context, symbol_classes);
+
         // If we are playing a streaming sound, there should(?) be a `SoundStreamBlock` on each frame.
         if !has_stream_block {
             self.0.write(context.gc_context).stop_audio_stream(context);
         }
+
+        self.queue_frame_scripts(self_display_object, context);
+    }
+
+    /// Queue the AVM2 frame scripts registered for the current frame.
+    fn queue_frame_scripts(
+        self,
+        self_display_object: DisplayObject<'gc>,
+        context: &mut UpdateContext<'_, 'gc, '_>,
+    ) {
+        let read = self.0.read();
+        let receiver = read.object2;
+        let callables: Vec<_> = read
+            .frame_scripts
+            .iter()
+            .filter(|script| script.frame_id == read.current_frame)
+            .map(|script| script.callable)
+            .collect();
+        drop(read);
+
+        for callable in callables {
+            context.action_queue.queue_actions(
+                self_display_object,
+                ActionType::Callable2 {
+                    callable,
+                    receiver,
+                    args: Vec::new(),
+                },
+                false,
+            );
+        }
+    }
+
+    /// Register the AVM2 function to run when this clip enters a frame,
+    /// replacing any earlier one. `None` removes the frame's script.
+    pub fn register_frame_script(
+        self,
+        frame_id: FrameNumber,
+        callable: Option<Avm2Object<'gc>>,
+        context: &mut UpdateContext<'_, 'gc, '_>,
+    ) {
+        let mut write = self.0.write(context.gc_context);
+        write
+            .frame_scripts
+            .retain(|script| script.frame_id != frame_id);
+        if let Some(callable) = callable {
+            write
+                .frame_scripts
+                .push(Avm2FrameScript { frame_id, callable });
+        }
     }
 
     #[allow(clippy::too_many_arguments)]
@@ -761,45 +1351,40 @@ impl<'gc> MovieClip<'gc> {
         //    so for rewinds, we must restart to frame 1 and play forward.
         // 3) Objects that would persist over the goto conceptually should not be
         //    destroyed and recreated; they should keep their properties.
-        //    Particularly for rewinds, the object should persist if it was created
-        //      *before* the frame we are going to. (DisplayObject::place_frame).
+        //    Particularly for rewinds, the object should persist if the frame we are
+        //      going to places it the same way (DisplayObject::place_frame).
         // 4) We want to avoid creating objects just to destroy them if they aren't on
         //    the goto frame, so we should instead aggregate the deltas into a final list
         //    of commands, and THEN modify the children as necessary.
 
+        // A goto issued while placing the children of another goto (for example, by a
+        // constructor) runs after it, so that the timeline isn't modified mid-way.
+        if self.0.read().flags.contains(MovieClipFlags::ExecutingGoto) {
+            self.0.write(context.gc_context).queued_goto_frame = Some(frame);
+            return;
+        }
+        self.0
+            .write(context.gc_context)
+            .flags
+            .insert(MovieClipFlags::ExecutingGoto);
+
         // This map will maintain a map of depth -> placement commands.
         // TODO: Move this to UpdateContext to avoid allocations.
         let mut goto_commands = vec![];
 
+        // Init actions and ABC scripts of the frames we pass over must still run, before any
+        // children are placed.
+        let mut init_actions = vec![];
+        let mut avm2_tags = vec![];
+
         self.0.write(context.gc_context).stop_audio_stream(context);
+        self.invalidate_cached_bitmap();
 
         let is_rewind = if frame < self.current_frame() {
             // Because we can only step forward, we have to start at frame 1
             // when rewinding.
             self.0.write(context.gc_context).tag_stream_pos = 0;
             self.0.write(context.gc_context).current_frame = 0;
-
-            // Remove all display objects that were created after the desination frame.
-            // TODO: We want to do something like self.children.retain here,
-            // but BTreeMap::retain does not exist.
-            let children: SmallVec<[_; 16]> = self
-                .0
-                .read()
-                .children
-                .iter()
-                .filter_map(|(depth, clip)| {
-                    if clip.place_frame() > frame {
-                        Some((*depth, *clip))
-                    } else {
-                        None
-                    }
-                })
-                .collect();
-            for (depth, child) in children {
-                let mut mc = self.0.write(context.gc_context);
-                mc.children.remove(&depth);
-                mc.remove_child_from_exec_list(context, child);
-            }
             true
         } else {
             false
@@ -814,12 +1399,7 @@ impl<'gc> MovieClip<'gc> {
 
         let len = mc.tag_stream_len() as u64;
         // Sanity; let's make sure we don't seek way too far.
-        // TODO: This should be self.frames_loaded() when we implement that.
-        let clamped_frame = if frame <= mc.total_frames() {
-            frame
-        } else {
-            mc.total_frames()
-        };
+        let clamped_frame = frame.min(mc.static_data.frames_loaded);
         drop(mc);
 
         while self.current_frame() < clamped_frame && frame_pos < len {
@@ -879,6 +1459,14 @@ impl<'gc> MovieClip<'gc> {
                             index,
                         )
                     }
+                    TagCode::DoInitAction => {
+                        init_actions.push(mc.init_action(reader, tag_len)?);
+                        Ok(())
+                    }
+                    TagCode::DoAbc | TagCode::DoAbcDefine | TagCode::SymbolClass => {
+                        avm2_tags.push(mc.avm2_tag(reader, tag_code, tag_pos, tag_len)?);
+                        Ok(())
+                    }
                     TagCode::RemoveObject => {
                         mc.goto_remove_object(reader, 1, context, &mut goto_commands, is_rewind)
                     }
@@ -892,6 +1480,42 @@ impl<'gc> MovieClip<'gc> {
         }
         let hit_target_frame = self.0.read().current_frame == frame;
 
+        for (sprite_id, code) in init_actions {
+            self.run_init_action(self_display_object, avm, context, sprite_id, code);
+        }
+
+        let symbol_classes = self.run_abc_tags(context, avm2_tags);
+        self.run_symbol_classes(context, symbol_classes);
+
+        if is_rewind {
+            // Diff the current display list against the target frame. A timeline object
+            // persists, keeping its properties, only if the target frame has the same
+            // placement of it; all other timeline objects are removed.
+            // Objects created by scripts (placed on frame 0) are not part of the timeline.
+            // TODO: We want to do something like self.children.retain here,
+            // but BTreeMap::retain does not exist.
+            let children: SmallVec<[_; 16]> = self
+                .0
+                .read()
+                .children
+                .iter()
+                .filter(|(depth, child)| {
+                    child.place_frame() > 0
+                        && !goto_commands.iter().any(|params| {
+                            params.depth() == **depth
+                                && params.frame == child.place_frame()
+                                && (params.id() == 0 || params.id() == child.id())
+                        })
+                })
+                .map(|(depth, child)| (*depth, *child))
+                .collect();
+            for (depth, child) in children {
+                let mut mc = self.0.write(context.gc_context);
+                mc.children.remove(&depth);
+                mc.remove_child_from_exec_list(context, child);
+            }
+        }
+
         // Run the list of goto commands to actually create and update the display objects.
         let run_goto_command = |clip: MovieClip<'gc>,
                                 avm: &mut Avm1<'gc>,
@@ -908,6 +1532,9 @@ impl<'gc> MovieClip<'gc> {
                 // modify the previous child.
                 Some(mut prev_child) if params.id() == 0 || is_rewind => {
                     prev_child.apply_place_object(context.gc_context, &params.place_object);
+                    if let Some(video) = prev_child.as_video() {
+                        video.update_embedded_frame(context);
+                    }
                 }
                 _ => {
                     if let Some(mut child) = clip.instantiate_child(
@@ -957,6 +1584,17 @@ impl<'gc> MovieClip<'gc> {
             .iter()
             .filter(|params| params.frame >= frame)
             .for_each(|goto| run_goto_command(self, avm, context, goto));
+
+        self.0
+            .write(context.gc_context)
+            .flags
+            .remove(MovieClipFlags::ExecutingGoto);
+        let queued_goto_frame = self.0.write(context.gc_context).queued_goto_frame.take();
+        if let Some(queued_goto_frame) = queued_goto_frame {
+            if queued_goto_frame != self.current_frame() {
+                self.run_goto(self_display_object, avm, context, queued_goto_frame);
+            }
+        }
     }
 }
 
@@ -971,9 +1609,54 @@ impl<'gc> TDisplayObject<'gc> for MovieClip<'gc> {
         Some(self.0.read().movie())
     }
 
+    fn get_child_by_name(&self, name: &str, case_sensitive: bool) -> Option<DisplayObject<'gc>> {
+        let unique_match = {
+            let mc = self.0.read();
+            let candidates = mc.child_names.get(&swf_string_to_lowercase(name))?;
+            let mut matches = candidates
+                .iter()
+                .filter(|child| !case_sensitive || &*child.name() == name);
+            let first = *matches.next()?;
+            if matches.next().is_none() {
+                Some(first)
+            } else {
+                None
+            }
+        };
+        if unique_match.is_some() {
+            return unique_match;
+        }
+
+        // Several children share this name; the one earliest in the execution list wins.
+        if case_sensitive {
+            self.children().find(|child| &*child.name() == name)
+        } else {
+            self.children()
+                .find(|child| swf_string_eq_ignore_case(&*child.name(), name))
+        }
+    }
+
+    fn child_renamed(
+        &self,
+        gc_context: MutationContext<'gc, '_>,
+        child: DisplayObject<'gc>,
+        old_name: &str,
+    ) {
+        let mut mc = self.0.write(gc_context);
+        // Only children in the execution list are indexed.
+        if mc.remove_child_name(child, old_name) {
+            mc.add_child_name(child, &child.name());
+        }
+    }
+
     fn run_frame(&mut self, avm: &mut Avm1<'gc>, context: &mut UpdateContext<'_, 'gc, '_>) {
+        // Children that stayed around for their unload handlers are removed for good.
+        self.remove_pending_children(context);
+
         // Children must run first.
-        for mut child in self.children() {
+        // Collect them up front, since a child's actions may remove its siblings.
+        let children: Vec<_> = self.children().collect();
+        for mut child in children {
             child.run_frame(avm, context);
         }
 
@@ -1003,6 +1686,34 @@ impl<'gc> TDisplayObject<'gc> for MovieClip<'gc> {
     }
 
     fn render(&self, context: &mut RenderContext<'_, 'gc>) {
+        if let Some(slices) = self.scaling_grid_slices() {
+            // Each slice renders the whole clip with its own matrix, masked to the slice.
+            let color_transform = self.transform().color_transform;
+            for (slice, matrix) in slices {
+                context.transform_stack.push(&Transform {
+                    matrix,
+                    color_transform,
+                });
+                context.renderer.push_mask();
+                context.renderer.draw_rect(
+                    Color::from_rgb(0, 0xFF),
+                    &swf::Rectangle {
+                        x_min: slice.x_min,
+                        x_max: slice.x_max,
+                        y_min: slice.y_min,
+                        y_max: slice.y_max,
+                    },
+                    context.transform_stack.transform(),
+                );
+                context.renderer.activate_mask();
+                crate::display_object::render_children(context, &self.0.read().children);
+                self.0.read().drawing.render(context);
+                context.renderer.pop_mask();
+                context.transform_stack.pop();
+            }
+            return;
+        }
+
         context.transform_stack.push(&*self.transform());
         crate::display_object::render_children(context, &self.0.read().children);
         self.0.read().drawing.render(context);
@@ -1013,8 +1724,24 @@ impl<'gc> TDisplayObject<'gc> for MovieClip<'gc> {
         self.0.read().drawing.self_bounds()
     }
 
-    fn hit_test(&self, point: (Twips, Twips)) -> bool {
-        self.world_bounds().contains(point)
+    fn hit_test(&self, point: (Twips, Twips)) -> bool {
+        self.world_bounds().contains(point)
+    }
+
+    fn hit_test_shape(&self, point: (Twips, Twips)) -> bool {
+        let local_point = self.global_to_local(point);
+        if !self.scroll_rect_contains(point) {
+            return false;
+        }
+        if self.0.read().drawing.hit_test(local_point) {
+            return true;
+        }
+        self.children().any(|child| child.hit_test_shape(point))
+    }
+
+    fn hit_test_self_shape(&self, point: (Twips, Twips)) -> bool {
+        let local_point = self.global_to_local(point);
+        self.0.read().drawing.hit_test(local_point)
     }
 
     fn mouse_pick(
@@ -1024,34 +1751,41 @@ impl<'gc> TDisplayObject<'gc> for MovieClip<'gc> {
         self_node: DisplayObject<'gc>,
         point: (Twips, Twips),
     ) -> Option<DisplayObject<'gc>> {
-        if self.visible() {
-            if self.world_bounds().contains(point) {
-                if self.0.read().has_button_clip_event {
-                    return Some(self_node);
-                }
-
-                let mut activation = Activation::from_nothing(
-                    avm,
-                    ActivationIdentifier::root("[Mouse Pick]"),
-                    context.swf.version(),
-                    avm.global_object_cell(),
-                    context.gc_context,
-                    *context.levels.get(&0).unwrap(),
-                );
-                let object = self.object().coerce_to_object(&mut activation, context);
+        if !is_hit_candidate(self_node, point) {
+            return None;
+        }
 
-                if ClipEvent::BUTTON_EVENT_METHODS
-                    .iter()
-                    .any(|handler| object.has_property(&mut activation, context, handler))
-                {
+        let is_hit = object_under_point(self_node, point, None).is_some();
+        if is_hit {
+            let mut activation = Activation::from_nothing(
+                avm,
+                ActivationIdentifier::root("[Mouse Pick]"),
+                context.swf.version(),
+                avm.global_object_cell(),
+                context.gc_context,
+                *context.levels.get(&0).unwrap(),
+            );
+            let object = self.object().coerce_to_object(&mut activation, context);
+            let is_button_mode = self.is_button_mode(&mut activation, context);
+
+            // Setting `enabled` to false stops the clip from acting as a button.
+            if is_button_mode {
+                let is_enabled = match object.get("enabled", &mut activation, context) {
+                    Ok(Value::Undefined) | Err(_) => true,
+                    Ok(enabled) => enabled.as_bool(activation.current_swf_version()),
+                };
+                if is_enabled {
                     return Some(self_node);
                 }
             }
+        }
 
-            // Maybe we could skip recursing down at all if !world_bounds.contains(point),
-            // but a child button can have an invisible hit area outside the parent's bounds.
-            for child in self.0.read().children.values().rev() {
-                let result = child.mouse_pick(avm, context, *child, point);
+        // Nothing beneath this clip is under the point unless it was hit, though children
+        // are still hit through their own shapes when a `hitArea` replaces this clip's.
+        if is_hit || self.0.read().hit_area.is_some() {
+            let children: Vec<_> = self.0.read().children.values().rev().copied().collect();
+            for child in children {
+                let result = child.mouse_pick(avm, context, child, point);
                 if result.is_some() {
                     return result;
                 }
@@ -1068,7 +1802,8 @@ impl<'gc> TDisplayObject<'gc> for MovieClip<'gc> {
         event: ClipEvent,
     ) -> ClipEventResult {
         if event.propagates() {
-            for child in self.children() {
+            let children: Vec<_> = self.children().collect();
+            for child in children {
                 if child.handle_clip_event(avm, context, event) == ClipEventResult::Handled {
                     return ClipEventResult::Handled;
                 }
@@ -1082,6 +1817,10 @@ impl<'gc> TDisplayObject<'gc> for MovieClip<'gc> {
         Some(*self)
     }
 
+    fn as_drawing(&self, gc_context: MutationContext<'gc, '_>) -> Option<RefMut<'_, Drawing>> {
+        Some(RefMut::map(self.0.write(gc_context), |mc| &mut mc.drawing))
+    }
+
     fn post_instantiation(
         &mut self,
         avm: &mut Avm1<'gc>,
@@ -1093,81 +1832,68 @@ impl<'gc> TDisplayObject<'gc> for MovieClip<'gc> {
         self.set_default_instance_name(context);
 
         if self.0.read().object.is_none() {
-            // If we are running within the AVM, this must be an immediate action.
-            // If we are not, then this must be queued to be ran first-thing
-            if instantiated_from_avm && self.0.read().avm1_constructor.is_some() {
-                let mut activation = Activation::from_nothing(
-                    avm,
-                    ActivationIdentifier::root("[Construct]"),
-                    context.swf.version(),
-                    avm.global_object_cell(),
-                    context.gc_context,
-                    *context.levels.get(&0).unwrap(),
-                );
+            let mut activation = Activation::from_nothing(
+                avm,
+                ActivationIdentifier::root("[Init]"),
+                context.swf.version(),
+                avm.global_object_cell(),
+                context.gc_context,
+                // The root clip is instantiated before it is placed on level 0.
+                context.levels.get(&0).copied().unwrap_or(display_object),
+            );
 
-                let constructor = self.0.read().avm1_constructor.unwrap();
-                if let Ok(prototype) = constructor
-                    .get("prototype", &mut activation, context)
-                    .map(|v| v.coerce_to_object(&mut activation, context))
-                {
-                    let object: Object<'gc> = StageObject::for_display_object(
-                        context.gc_context,
-                        (*self).into(),
-                        Some(prototype),
-                    )
-                    .into();
-                    if let Some(init_object) = init_object {
-                        for key in init_object.get_keys(&mut activation) {
-                            if let Ok(value) = init_object.get(&key, &mut activation, context) {
-                                let _ = object.set(&key, value, &mut activation, context);
-                            }
-                        }
-                    }
-                    self.0.write(context.gc_context).object = Some(object);
-                    let _ = constructor.call("[ctor]", &mut activation, context, object, None, &[]);
+            // If we are running within the AVM, the registered class constructor
+            // must run immediately, after the init object has been copied over.
+            // If we are not, then this must be queued to be ran first-thing.
+            let mut constructor = self.0.read().avm1_constructor;
+            let mut prototype = context.system_prototypes.movie_clip;
+            if instantiated_from_avm {
+                if let Some(class_prototype) = constructor.and_then(|c| {
+                    c.get("prototype", &mut activation, context)
+                        .ok()
+                        .map(|v| v.coerce_to_object(&mut activation, context))
+                }) {
+                    prototype = class_prototype;
                 }
-
-                return;
             }
 
-            let object = StageObject::for_display_object(
+            let object: Object<'gc> = StageObject::for_display_object(
                 context.gc_context,
                 display_object,
-                Some(context.system_prototypes.movie_clip),
-            );
+                Some(prototype),
+            )
+            .into();
+            self.0.write(context.gc_context).object = Some(object);
+
             if let Some(init_object) = init_object {
-                let mut activation = Activation::from_nothing(
-                    avm,
-                    ActivationIdentifier::root("[Init]"),
-                    context.swf.version(),
-                    avm.global_object_cell(),
-                    context.gc_context,
-                    *context.levels.get(&0).unwrap(),
-                );
+                copy_init_object(object, init_object, &mut activation, context);
+            }
 
-                for key in init_object.get_keys(&mut activation) {
-                    if let Ok(value) = init_object.get(&key, &mut activation, context) {
-                        let _ = object.set(&key, value, &mut activation, context);
-                    }
+            if instantiated_from_avm {
+                if let Some(constructor) = constructor.take() {
+                    let _ = constructor.call("[ctor]", &mut activation, context, object, None, &[]);
                 }
             }
-            let mut mc = self.0.write(context.gc_context);
-            mc.object = Some(object.into());
 
-            let mut events = Vec::new();
-
-            for clip_action in mc
-                .clip_actions()
-                .iter()
-                .filter(|action| action.event == ClipEvent::Construct)
-            {
-                events.push(clip_action.action_data.clone());
-            }
+            // `onClipEvent(construct)` handlers run first, then `onClipEvent(initialize)`
+            // handlers, and then the registered class constructor.
+            let events = {
+                let mc = self.0.read();
+                let actions = mc.clip_actions().iter();
+                let construct = actions
+                    .clone()
+                    .filter(|action| action.event == ClipEvent::Construct);
+                let initialize = actions.filter(|action| action.event == ClipEvent::Initialize);
+                construct
+                    .chain(initialize)
+                    .map(|action| action.action_data.clone())
+                    .collect()
+            };
 
             context.action_queue.queue_actions(
                 display_object,
                 ActionType::Construct {
-                    constructor: mc.avm1_constructor,
+                    constructor,
                     events,
                 },
                 false,
@@ -1193,6 +1919,18 @@ impl<'gc> TDisplayObject<'gc> for MovieClip<'gc> {
             .unwrap_or(Value::Undefined)
     }
 
+    fn object2(&self) -> Avm2Value<'gc> {
+        self.0
+            .read()
+            .object2
+            .map(Avm2Value::from)
+            .unwrap_or(Avm2Value::Undefined)
+    }
+
+    fn set_object2(&mut self, mc: MutationContext<'gc, '_>, to: Avm2Object<'gc>) {
+        self.0.write(mc).object2 = Some(to);
+    }
+
     fn unload(&mut self, context: &mut UpdateContext<'_, 'gc, '_>) {
         for mut child in self.children() {
             child.unload(context);
@@ -1210,24 +1948,142 @@ impl<'gc> TDisplayObject<'gc> for MovieClip<'gc> {
             mc.stop_audio_stream(context);
             mc.run_clip_event((*self).into(), context, ClipEvent::Unload);
         }
+        // A clip removed mid-drag stops being dragged.
+        let is_dragged = context.drag_object.as_ref().map_or(false, |drag_object| {
+            DisplayObject::ptr_eq(drag_object.display_object, (*self).into())
+        });
+        if is_dragged {
+            *context.drag_object = None;
+        }
+        context.focus_tracker.remove((*self).into());
+
+        self.clear_mask_relationships(context.gc_context);
+        self.release_bitmap_cache(context);
         self.set_removed(context.gc_context, true);
     }
 
     fn allow_as_mask(&self) -> bool {
-        !self.0.read().children.is_empty()
+        // Shapes drawn with the drawing API can mask, too.
+        let mc = self.0.read();
+        !mc.children.is_empty() || mc.drawing.self_bounds().valid
     }
 }
 
+/// Whether a display object or any of its descendants has code that runs when it is
+/// unloaded, either from an `onClipEvent(unload)` handler or an `onUnload` method.
+fn has_unload_handler<'gc>(
+    display_object: DisplayObject<'gc>,
+    activation: &mut Activation<'_, 'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+) -> bool {
+    if let Some(clip) = display_object.as_movie_clip() {
+        let has_clip_event = clip
+            .clip_actions()
+            .iter()
+            .any(|action| action.event == ClipEvent::Unload);
+        if has_clip_event {
+            return true;
+        }
+        if let Value::Object(object) = clip.object() {
+            if context.swf.version() >= 6 && object.has_property(activation, context, "onUnload") {
+                return true;
+            }
+        }
+    }
+    display_object
+        .children()
+        .any(|child| has_unload_handler(child, activation, context))
+}
+
+/// Copies the enumerable properties of an `attachMovie`/`duplicateMovieClip`
+/// init object onto a newly created clip's object.
+///
+/// This happens before the clip's registered class constructor and its
+/// first frame of actions run, so that both can see the copied values.
+fn copy_init_object<'gc>(
+    object: Object<'gc>,
+    init_object: Object<'gc>,
+    activation: &mut Activation<'_, 'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+) {
+    for key in init_object.get_keys(activation, context.gc_context) {
+        if let Ok(value) = init_object.get(&key, activation, context) {
+            let _ = object.set(key, value, activation, context);
+        }
+    }
+}
+
+/// Handles a bitmap tag whose image data can't be decoded.
+///
+/// Flash renders these bitmaps as nothing instead of failing the movie, so the
+/// character is left undefined and the rest of the tags are still parsed.
+fn undecodable_bitmap(
+    tag_name: &str,
+    id: CharacterId,
+    error: Box<dyn std::error::Error>,
+) -> DecodeResult {
+    log::warn!("Unable to decode {} character {}: {}", tag_name, id, error);
+    Ok(())
+}
+
+/// Splits one axis of a scaling grid into its slices.
+///
+/// `edges` holds the lower bound of the content, the two grid lines, and the upper bound of
+/// the content, in local coordinates. Each slice is returned as its local start and end,
+/// followed by the scale and offset that map it into the parent's coordinates. Empty slices
+/// are left out.
+fn scaling_grid_axis(edges: [Twips; 4], scale: f32) -> Vec<(Twips, Twips, f32, Twips)> {
+    let [lo, grid_lo, grid_hi, hi] = edges;
+    let scale = f64::from(scale);
+    let lo_corner = f64::from((grid_lo - lo).get());
+    let hi_corner = f64::from((hi - grid_hi).get());
+    let dst_lo = f64::from(lo.get()) * scale;
+    let dst_hi = f64::from(hi.get()) * scale;
+
+    // Corners keep their size unless the whole clip is scaled smaller than them.
+    let corner_scale = ((dst_hi - dst_lo) / (lo_corner + hi_corner)).min(1.0);
+    let dst_grid_lo = dst_lo + lo_corner * corner_scale;
+    let dst_grid_hi = dst_hi - hi_corner * corner_scale;
+
+    let slices = [
+        (lo, grid_lo, dst_lo, dst_grid_lo),
+        (grid_lo, grid_hi, dst_grid_lo, dst_grid_hi),
+        (grid_hi, hi, dst_grid_hi, dst_hi),
+    ];
+    slices
+        .iter()
+        .filter(|(src_lo, src_hi, dst_lo, dst_hi)| src_hi > src_lo && dst_hi > dst_lo)
+        .map(|&(src_lo, src_hi, dst_lo, dst_hi)| {
+            let scale = (dst_hi - dst_lo) / f64::from((src_hi - src_lo).get());
+            let offset = dst_lo - f64::from(src_lo.get()) * scale;
+            (
+                src_lo,
+                src_hi,
+                scale as f32,
+                Twips::new(offset.round() as i32),
+            )
+        })
+        .collect()
+}
+
 unsafe impl<'gc> Collect for MovieClipData<'gc> {
     #[inline]
     fn trace(&self, cc: gc_arena::CollectionContext) {
         for child in self.children.values() {
             child.trace(cc);
         }
+        for children in self.child_names.values() {
+            for child in children {
+                child.trace(cc);
+            }
+        }
         self.base.trace(cc);
         self.static_data.trace(cc);
         self.object.trace(cc);
         self.avm1_constructor.trace(cc);
+        self.hit_area.trace(cc);
+        self.object2.trace(cc);
+        self.frame_scripts.trace(cc);
     }
 }
 
@@ -1255,6 +2111,7 @@ impl<'gc> MovieClipData<'gc> {
                 id: 0,
                 swf: movie.into(),
                 total_frames,
+                frames_loaded: total_frames,
                 audio_stream_info: None,
                 frame_labels: HashMap::new(),
             },
@@ -1264,6 +2121,8 @@ impl<'gc> MovieClipData<'gc> {
         self.current_frame = 0;
         self.audio_stream = None;
         self.children = BTreeMap::new();
+        self.child_names = HashMap::new();
+        self.preload_progress = Default::default();
     }
 
     fn id(&self) -> CharacterId {
@@ -1324,6 +2183,8 @@ impl<'gc> MovieClipData<'gc> {
         gc_context: MutationContext<'gc, '_>,
         mut child: DisplayObject<'gc>,
     ) {
+        self.base.set_dirty(true);
+        self.add_child_name(child, &child.name());
         if let Some(mut head) = self.first_child() {
             head.set_prev_sibling(gc_context, Some(child));
             child.set_next_sibling(gc_context, Some(head));
@@ -1337,22 +2198,101 @@ impl<'gc> MovieClipData<'gc> {
         context: &mut UpdateContext<'_, 'gc, '_>,
         mut child: DisplayObject<'gc>,
     ) {
+        self.unlink_child_from_exec_list(context.gc_context, child);
+        // Flag child as removed.
+        // Children kept around for their unload handlers have already been unloaded.
+        if !child.removed() {
+            child.unload(context);
+        }
+    }
+
+    /// Removes a child from the execution list without unloading it.
+    /// This does not affect the render list.
+    fn unlink_child_from_exec_list(
+        &mut self,
+        gc_context: MutationContext<'gc, '_>,
+        child: DisplayObject<'gc>,
+    ) {
+        self.base.set_dirty(true);
+        self.remove_child_name(child, &child.name());
         // Remove from children linked list.
         let prev = child.prev_sibling();
         let next = child.next_sibling();
         if let Some(mut prev) = prev {
-            prev.set_next_sibling(context.gc_context, next);
+            prev.set_next_sibling(gc_context, next);
         }
         if let Some(mut next) = next {
-            next.set_prev_sibling(context.gc_context, prev);
+            next.set_prev_sibling(gc_context, prev);
         }
         if let Some(head) = self.first_child() {
             if DisplayObject::ptr_eq(head, child) {
-                self.set_first_child(context.gc_context, next);
+                self.set_first_child(gc_context, next);
             }
         }
-        // Flag child as removed.
-        child.unload(context);
+    }
+
+    /// Indexes a child of the execution list under the given instance name.
+    fn add_child_name(&mut self, child: DisplayObject<'gc>, name: &str) {
+        self.child_names
+            .entry(swf_string_to_lowercase(name))
+            .or_default()
+            .push(child);
+    }
+
+    /// Removes a child from the instance name index.
+    /// Returns `false` if the child was not indexed under the given name.
+    fn remove_child_name(&mut self, child: DisplayObject<'gc>, name: &str) -> bool {
+        let key = swf_string_to_lowercase(name);
+        if let Some(children) = self.child_names.get_mut(&key) {
+            if let Some(i) = children
+                .iter()
+                .position(|other| DisplayObject::ptr_eq(*other, child))
+            {
+                children.remove(i);
+                if children.is_empty() {
+                    self.child_names.remove(&key);
+                }
+                return true;
+            }
+        }
+        false
+    }
+
+    /// Reads a `DoInitAction` tag, returning the ID of its sprite and its actions.
+    fn init_action(
+        &self,
+        reader: &mut SwfStream<&[u8]>,
+        tag_len: usize,
+    ) -> Result<(CharacterId, SwfSlice), tag_utils::Error> {
+        let sprite_id = reader.read_u16()?;
+        let code = self
+            .static_data
+            .swf
+            .resize_to_reader(reader, tag_len.saturating_sub(2))
+            .ok_or_else(|| {
+                std::io::Error::new(
+                    std::io::ErrorKind::Other,
+                    "Invalid source or tag length when running init action",
+                )
+            })?;
+        Ok((sprite_id, code))
+    }
+
+    /// Reads a `DoAbc`, `DoAbcDefine` or `SymbolClass` tag, along with its position in the
+    /// movie, which identifies it when checking whether it has already run.
+    fn avm2_tag(
+        &self,
+        reader: &mut SwfStream<&[u8]>,
+        tag_code: swf::TagCode,
+        tag_pos: usize,
+        tag_len: usize,
+    ) -> Result<(usize, Avm2Tag), tag_utils::Error> {
+        let avm2_tag = match tag_code {
+            swf::TagCode::DoAbc => Avm2Tag::DoAbc(reader.read_do_abc(tag_len)?),
+            swf::TagCode::DoAbcDefine => Avm2Tag::DoAbc(reader.read_do_abc_define(tag_len)?),
+            _ => Avm2Tag::SymbolClass(reader.read_symbol_class()?),
+        };
+        Ok((self.static_data.swf.start + tag_pos, avm2_tag))
     }
 
     /// Handles a PlaceObject tag when running a goto action.
@@ -1409,7 +2349,7 @@ impl<'gc> MovieClipData<'gc> {
             // that existed before the goto, then we can remove that child right away.
             // Don't do this for rewinds, because they conceptually
             // start from an empty display list, and we also want to examine
-            // the old children to decide if they persist (place_frame <= goto_frame).
+            // the old children to decide if they persist.
             let child = self.children.remove(&depth);
             if let Some(child) = child {
                 self.remove_child_from_exec_list(context, child);
@@ -1449,12 +2389,13 @@ impl<'gc> MovieClipData<'gc> {
 
             // Queue ActionScript-defined event handlers after the SWF defined ones.
             // (e.g., clip.onEnterFrame = foo).
+            // Clips constructed by AVM2 have no AVM1 object to call them on.
             if context.swf.version() >= 6 {
-                if let Some(name) = event.method_name() {
+                if let (Some(name), Some(object)) = (event.method_name(), self.object) {
                     context.action_queue.queue_actions(
                         self_display_object,
                         ActionType::Method {
-                            object: self.object.unwrap(),
+                            object,
                             name,
                             args: vec![],
                         },
@@ -1515,7 +2456,9 @@ impl<'gc> MovieClipData<'gc> {
     /// Stops the audio stream if one is playing.
     fn stop_audio_stream(&mut self, context: &mut UpdateContext<'_, 'gc, '_>) {
         if let Some(audio_stream) = self.audio_stream.take() {
-            context.audio.stop_stream(audio_stream);
+            context
+                .audio_manager
+                .stop_stream(context.audio, audio_stream);
         }
     }
 
@@ -1534,20 +2477,23 @@ impl<'gc, 'a> MovieClipData<'gc> {
         version: u8,
     ) -> DecodeResult {
         let define_bits_lossless = reader.read_define_bits_lossless(version)?;
-        let bitmap_info = context
-            .renderer
-            .register_bitmap_png(&define_bits_lossless)?;
+        let id = define_bits_lossless.id;
+        let bitmap_info = match context.renderer.register_bitmap_png(&define_bits_lossless) {
+            Ok(bitmap_info) => bitmap_info,
+            Err(e) => return undecodable_bitmap("DefineBitsLossless", id, e),
+        };
         let bitmap = crate::display_object::Bitmap::new(
             context,
-            define_bits_lossless.id,
+            id,
             bitmap_info.handle,
             bitmap_info.width,
             bitmap_info.height,
+            Some(BitmapSource::Lossless(define_bits_lossless)),
         );
         context
             .library
             .library_for_movie_mut(self.movie())
-            .register_character(define_bits_lossless.id, Character::Bitmap(bitmap));
+            .register_character(id, Character::Bitmap(bitmap));
         Ok(())
     }
 
@@ -1556,13 +2502,19 @@ impl<'gc, 'a> MovieClipData<'gc> {
         &mut self,
         context: &mut UpdateContext<'_, 'gc, '_>,
         reader: &mut SwfStream<&'a [u8]>,
-        morph_shapes: &mut fnv::FnvHashMap<CharacterId, MorphShapeStatic>,
         version: u8,
     ) -> DecodeResult {
-        // Certain backends may have to preload morph shape frames, so defer registering until the end.
+        // The ratios that the movie places the shape at are tessellated as the
+        // placements are preloaded.
         let swf_shape = reader.read_define_morph_shape(version)?;
-        let morph_shape = MorphShapeStatic::from_swf_tag(context.renderer, &swf_shape);
-        morph_shapes.insert(swf_shape.id, morph_shape);
+        let morph_shape = MorphShape::new(
+            context.gc_context,
+            MorphShapeStatic::from_swf_tag(context.renderer, &swf_shape),
+        );
+        context
+            .library
+            .library_for_movie_mut(self.movie())
+            .register_character(swf_shape.id, Character::MorphShape(morph_shape));
         Ok(())
     }
 
@@ -1588,8 +2540,7 @@ impl<'gc, 'a> MovieClipData<'gc> {
         context: &mut UpdateContext<'_, 'gc, '_>,
         reader: &mut SwfStream<&'a [u8]>,
         tag_len: usize,
-        ids: &mut fnv::FnvHashMap<Depth, CharacterId>,
-        morph_shapes: &mut fnv::FnvHashMap<CharacterId, MorphShapeStatic>,
+        morph_shape_depths: &mut fnv::FnvHashMap<Depth, CharacterId>,
         version: u8,
     ) -> DecodeResult {
         use swf::PlaceObjectAction;
@@ -1598,36 +2549,30 @@ impl<'gc, 'a> MovieClipData<'gc> {
         } else {
             reader.read_place_object_2_or_3(version)
         }?;
-        match place_object.action {
-            PlaceObjectAction::Place(id) => {
-                if let Some(morph_shape) = morph_shapes.get_mut(&id) {
-                    ids.insert(place_object.depth.into(), id);
-                    if let Some(ratio) = place_object.ratio {
-                        morph_shape.register_ratio(context.renderer, ratio);
-                    }
-                }
-            }
-            PlaceObjectAction::Modify => {
-                if let Some(&id) = ids.get(&place_object.depth.into()) {
-                    if let Some(morph_shape) = morph_shapes.get_mut(&id) {
-                        ids.insert(place_object.depth.into(), id);
-                        if let Some(ratio) = place_object.ratio {
-                            morph_shape.register_ratio(context.renderer, ratio);
-                        }
-                    }
-                }
+        let depth = place_object.depth.into();
+        let id = match place_object.action {
+            PlaceObjectAction::Place(id) | PlaceObjectAction::Replace(id) => Some(id),
+            PlaceObjectAction::Modify => morph_shape_depths.get(&depth).copied(),
+        };
+        let morph_shape = id.and_then(|id| {
+            match context
+                .library
+                .library_for_movie_mut(self.movie())
+                .get_character_by_id(id)
+            {
+                Some(Character::MorphShape(morph_shape)) => Some(*morph_shape),
+                _ => None,
             }
-            PlaceObjectAction::Replace(id) => {
-                if let Some(morph_shape) = morph_shapes.get_mut(&id) {
-                    ids.insert(place_object.depth.into(), id);
-                    if let Some(ratio) = place_object.ratio {
-                        morph_shape.register_ratio(context.renderer, ratio);
-                    }
-                } else {
-                    ids.remove(&place_object.depth.into());
-                }
+        });
+
+        if let (Some(id), Some(morph_shape)) = (id, morph_shape) {
+            morph_shape_depths.insert(depth, id);
+            if let Some(ratio) = place_object.ratio {
+                morph_shape.register_ratio(context, ratio);
             }
-        };
+        } else if let PlaceObjectAction::Replace(_) = place_object.action {
+            morph_shape_depths.remove(&depth);
+        }
 
         Ok(())
     }
@@ -1653,6 +2598,27 @@ impl<'gc, 'a> MovieClipData<'gc> {
         Ok(())
     }
 
+    #[inline]
+    fn preload_video_frame(
+        &mut self,
+        context: &mut UpdateContext<'_, 'gc, '_>,
+        reader: &mut SwfStream<&'a [u8]>,
+    ) -> DecodeResult {
+        let tag = reader.read_video_frame()?;
+        match context
+            .library
+            .library_for_movie_mut(self.movie())
+            .get_character_by_id(tag.stream_id)
+        {
+            Some(Character::Video(video)) => video.preload_swf_frame(context.gc_context, tag),
+            _ => log::warn!(
+                "VideoFrame: Character ID {} doesn't exist or is not a video",
+                tag.stream_id
+            ),
+        }
+        Ok(())
+    }
+
     #[inline]
     fn preload_sound_stream_head(
         &mut self,
@@ -1682,20 +2648,28 @@ impl<'gc, 'a> MovieClipData<'gc> {
         let data_len = tag_len - 2;
         let mut jpeg_data = Vec::with_capacity(data_len);
         reader.get_mut().read_to_end(&mut jpeg_data)?;
-        let bitmap_info = context.renderer.register_bitmap_jpeg(
-            id,
-            &jpeg_data,
-            context
-                .library
-                .library_for_movie_mut(self.movie())
-                .jpeg_tables(),
-        )?;
+        let jpeg_tables = context
+            .library
+            .library_for_movie_mut(self.movie())
+            .jpeg_tables();
+        let bitmap_info = match context
+            .renderer
+            .register_bitmap_jpeg(id, &jpeg_data, jpeg_tables)
+        {
+            Ok(bitmap_info) => bitmap_info,
+            Err(e) => return undecodable_bitmap("DefineBits", id, e),
+        };
+        let source = BitmapSource::Jpeg {
+            data: crate::backend::render::glue_tables_to_jpeg(&jpeg_data, jpeg_tables).into_owned(),
+            alpha_data: None,
+        };
         let bitmap = crate::display_object::Bitmap::new(
             context,
             id,
             bitmap_info.handle,
             bitmap_info.width,
             bitmap_info.height,
+            Some(source),
         );
         context
             .library
@@ -1716,13 +2690,21 @@ impl<'gc, 'a> MovieClipData<'gc> {
         let data_len = tag_len - 2;
         let mut jpeg_data = Vec::with_capacity(data_len);
         reader.get_mut().read_to_end(&mut jpeg_data)?;
-        let bitmap_info = context.renderer.register_bitmap_jpeg_2(id, &jpeg_data)?;
+        let bitmap_info = match context.renderer.register_bitmap_jpeg_2(id, &jpeg_data) {
+            Ok(bitmap_info) => bitmap_info,
+            Err(e) => return undecodable_bitmap("DefineBitsJPEG2", id, e),
+        };
+        let source = BitmapSource::Jpeg {
+            data: jpeg_data,
+            alpha_data: None,
+        };
         let bitmap = crate::display_object::Bitmap::new(
             context,
             id,
             bitmap_info.handle,
             bitmap_info.width,
             bitmap_info.height,
+            Some(source),
         );
         context
             .library
@@ -1741,7 +2723,7 @@ impl<'gc, 'a> MovieClipData<'gc> {
         use std::io::Read;
         let id = reader.read_u16()?;
         let jpeg_len = reader.read_u32()? as usize;
-        let alpha_len = tag_len - 6 - jpeg_len;
+        let alpha_len = tag_len.saturating_sub(6 + jpeg_len);
         let mut jpeg_data = Vec::with_capacity(jpeg_len);
         let mut alpha_data = Vec::with_capacity(alpha_len);
         reader
@@ -1752,15 +2734,24 @@ impl<'gc, 'a> MovieClipData<'gc> {
             .get_mut()
             .take(alpha_len as u64)
             .read_to_end(&mut alpha_data)?;
-        let bitmap_info = context
+        let bitmap_info = match context
             .renderer
-            .register_bitmap_jpeg_3(id, &jpeg_data, &alpha_data)?;
+            .register_bitmap_jpeg_3(id, &jpeg_data, &alpha_data)
+        {
+            Ok(bitmap_info) => bitmap_info,
+            Err(e) => return undecodable_bitmap("DefineBitsJPEG3", id, e),
+        };
+        let source = BitmapSource::Jpeg {
+            data: jpeg_data,
+            alpha_data: Some(alpha_data),
+        };
         let bitmap = Bitmap::new(
             context,
             id,
             bitmap_info.handle,
             bitmap_info.width,
             bitmap_info.height,
+            Some(source),
         );
         context
             .library
@@ -1780,7 +2771,7 @@ impl<'gc, 'a> MovieClipData<'gc> {
         let id = reader.read_u16()?;
         let jpeg_len = reader.read_u32()? as usize;
         let _deblocking = reader.read_u16()?;
-        let alpha_len = tag_len - 6 - jpeg_len;
+        let alpha_len = tag_len.saturating_sub(8 + jpeg_len);
         let mut jpeg_data = Vec::with_capacity(jpeg_len);
         let mut alpha_data = Vec::with_capacity(alpha_len);
         reader
@@ -1791,15 +2782,24 @@ impl<'gc, 'a> MovieClipData<'gc> {
             .get_mut()
             .take(alpha_len as u64)
             .read_to_end(&mut alpha_data)?;
-        let bitmap_info = context
+        let bitmap_info = match context
             .renderer
-            .register_bitmap_jpeg_3(id, &jpeg_data, &alpha_data)?;
+            .register_bitmap_jpeg_3(id, &jpeg_data, &alpha_data)
+        {
+            Ok(bitmap_info) => bitmap_info,
+            Err(e) => return undecodable_bitmap("DefineBitsJPEG4", id, e),
+        };
+        let source = BitmapSource::Jpeg {
+            data: jpeg_data,
+            alpha_data: Some(alpha_data),
+        };
         let bitmap = Bitmap::new(
             context,
             id,
             bitmap_info.handle,
             bitmap_info.width,
             bitmap_info.height,
+            Some(source),
         );
         context
             .library
@@ -1954,7 +2954,7 @@ impl<'gc, 'a> MovieClipData<'gc> {
             is_bold: false,
             is_italic: false,
         };
-        let font_object = Font::from_swf_tag(context.gc_context, context.renderer, &font).unwrap();
+        let font_object = Font::from_swf_tag(context.gc_context, context.renderer, &font)?;
         context
             .library
             .library_for_movie_mut(self.movie())
@@ -1969,7 +2969,7 @@ impl<'gc, 'a> MovieClipData<'gc> {
         reader: &mut SwfStream<&'a [u8]>,
     ) -> DecodeResult {
         let font = reader.read_define_font_2(2)?;
-        let font_object = Font::from_swf_tag(context.gc_context, context.renderer, &font).unwrap();
+        let font_object = Font::from_swf_tag(context.gc_context, context.renderer, &font)?;
         context
             .library
             .library_for_movie_mut(self.movie())
@@ -1984,7 +2984,7 @@ impl<'gc, 'a> MovieClipData<'gc> {
         reader: &mut SwfStream<&'a [u8]>,
     ) -> DecodeResult {
         let font = reader.read_define_font_2(3)?;
-        let font_object = Font::from_swf_tag(context.gc_context, context.renderer, &font).unwrap();
+        let font_object = Font::from_swf_tag(context.gc_context, context.renderer, &font)?;
         context
             .library
             .library_for_movie_mut(self.movie())
@@ -2020,7 +3020,6 @@ impl<'gc, 'a> MovieClipData<'gc> {
         context: &mut UpdateContext<'_, 'gc, '_>,
         reader: &mut SwfStream<&'a [u8]>,
         tag_len: usize,
-        morph_shapes: &mut fnv::FnvHashMap<CharacterId, MorphShapeStatic>,
     ) -> DecodeResult {
         let id = reader.read_character_id()?;
         let num_frames = reader.read_u16()?;
@@ -2039,7 +3038,7 @@ impl<'gc, 'a> MovieClipData<'gc> {
             num_frames,
         );
 
-        movie_clip.preload(avm, context, morph_shapes);
+        movie_clip.preload(avm, context);
 
         context
             .library
@@ -2065,6 +3064,38 @@ impl<'gc, 'a> MovieClipData<'gc> {
         Ok(())
     }
 
+    #[inline]
+    fn define_scaling_grid(
+        &mut self,
+        context: &mut UpdateContext<'_, 'gc, '_>,
+        reader: &mut SwfStream<&'a [u8]>,
+    ) -> DecodeResult {
+        let id = reader.read_u16()?;
+        let splitter_rect = reader.read_rectangle()?;
+        let library = context.library.library_for_movie_mut(self.movie());
+        if let Some(Character::MovieClip(clip)) = library.get_character_by_id(id) {
+            clip.set_scaling_grid(context.gc_context, Some(splitter_rect.into()));
+        } else {
+            log::warn!("DefineScalingGrid: Character {} is not a movie clip", id);
+        }
+        Ok(())
+    }
+
+    #[inline]
+    fn define_video_stream(
+        &mut self,
+        context: &mut UpdateContext<'_, 'gc, '_>,
+        reader: &mut SwfStream<&'a [u8]>,
+    ) -> DecodeResult {
+        let tag = reader.read_define_video_stream()?;
+        let video = Video::from_swf_tag(context, self.movie(), &tag);
+        context
+            .library
+            .library_for_movie_mut(self.movie())
+            .register_character(tag.id, Character::Video(video));
+        Ok(())
+    }
+
     #[inline]
     fn export_assets(
         &mut self,
@@ -2126,7 +3157,7 @@ impl<'gc, 'a> MovieClipData<'gc> {
         &mut self,
         _context: &mut UpdateContext<'_, 'gc, '_>,
         reader: &mut SwfStream<&'a [u8]>,
-        ids: &mut fnv::FnvHashMap<Depth, CharacterId>,
+        morph_shape_depths: &mut fnv::FnvHashMap<Depth, CharacterId>,
         version: u8,
     ) -> DecodeResult {
         let remove_object = if version == 1 {
@@ -2134,7 +3165,7 @@ impl<'gc, 'a> MovieClipData<'gc> {
         } else {
             reader.read_remove_object_2()
         }?;
-        ids.remove(&remove_object.depth.into());
+        morph_shape_depths.remove(&remove_object.depth.into());
         Ok(())
     }
 
@@ -2152,6 +3183,62 @@ impl<'gc, 'a> MovieClipData<'gc> {
 
 // Control tags
 impl<'gc, 'a> MovieClip<'gc> {
+    /// Runs the ABC scripts of a frame, unless they have already run, and returns the
+    /// symbol classes of the frame, which are bound once its children are placed.
+    ///
+    /// Like init actions, ABC tags only run the first time their frame is reached.
+    fn run_abc_tags(
+        self,
+        context: &mut UpdateContext<'_, 'gc, '_>,
+        avm2_tags: Vec<(usize, Avm2Tag)>,
+    ) -> Vec<swf::SymbolClassLink> {
+        let movie = self.0.read().movie();
+        let mut symbol_classes = vec![];
+        for (tag_pos, avm2_tag) in avm2_tags {
+            if !context
+                .library
+                .library_for_movie_mut(movie.clone())
+                .mark_avm2_tag_run(tag_pos)
+            {
+                continue;
+            }
+
+            match avm2_tag {
+                Avm2Tag::DoAbc(do_abc) => {
+                    if let Err(e) = Avm2::load_abc(&do_abc.data, do_abc.is_lazy_initialize, context)
+                    {
+                        log::warn!("Error loading ABC file: {}", e);
+                    }
+                }
+                Avm2Tag::SymbolClass(links) => symbol_classes.extend(links),
+            }
+        }
+
+        symbol_classes
+    }
+
+    fn run_symbol_classes(
+        self,
+        context: &mut UpdateContext<'_, 'gc, '_>,
+        symbol_classes: Vec<swf::SymbolClassLink>,
+    ) {
+        for link in symbol_classes {
+            // Character zero is the main timeline, which is bound to the document class.
+            // TODO: Bind library symbols to their classes.
+            if link.id != 0 {
+                continue;
+            }
+
+            if let Err(e) = Avm2::construct_display_object(&link.class_name, self.into(), context) {
+                log::error!(
+                    "Error constructing document class {}: {}",
+                    link.class_name,
+                    e
+                );
+            }
+        }
+    }
+
     #[inline]
     fn do_action(
         self,
@@ -2217,14 +3304,19 @@ impl<'gc, 'a> MovieClip<'gc> {
                 }
             }
             PlaceObjectAction::Modify => {
-                if let Some(mut child) = self
+                // Don't hold a borrow of this clip while the child is modified;
+                // renaming the child updates our name index.
+                let child = self
                     .0
                     .read()
                     .children
                     .get(&place_object.depth.into())
-                    .copied()
-                {
+                    .copied();
+                if let Some(mut child) = child {
                     child.apply_place_object(context.gc_context, &place_object);
+                    if let Some(video) = child.as_video() {
+                        video.update_embedded_frame(context);
+                    }
                     child
                 } else {
                     return Ok(());
@@ -2238,6 +3330,7 @@ impl<'gc, 'a> MovieClip<'gc> {
     #[inline]
     fn remove_object(
         self,
+        avm: &mut Avm1<'gc>,
         context: &mut UpdateContext<'_, 'gc, '_>,
         reader: &mut SwfStream<&'a [u8]>,
         version: u8,
@@ -2247,10 +3340,17 @@ impl<'gc, 'a> MovieClip<'gc> {
         } else {
             reader.read_remove_object_2()
         }?;
-        let mut mc = self.0.write(context.gc_context);
-        let child = mc.children.remove(&remove_object.depth.into());
+        let child = self.child_by_depth(remove_object.depth.into());
         if let Some(child) = child {
-            mc.remove_child_from_exec_list(context, child);
+            let mut activation = Activation::from_nothing(
+                avm,
+                ActivationIdentifier::root("[Remove Object]"),
+                context.swf.version(),
+                avm.global_object_cell(),
+                context.gc_context,
+                *context.levels.get(&0).unwrap(),
+            );
+            self.remove_child(&mut activation, context, child);
         }
         Ok(())
     }
@@ -2271,23 +3371,36 @@ impl<'gc, 'a> MovieClip<'gc> {
         context: &mut UpdateContext<'_, 'gc, '_>,
         _reader: &mut SwfStream<&'a [u8]>,
     ) -> DecodeResult {
-        let mut mc = self.0.write(context.gc_context);
-        if let (Some(stream_info), None) = (&mc.static_data.audio_stream_info, mc.audio_stream) {
-            let slice = mc
-                .static_data
-                .swf
-                .to_start_and_end(mc.tag_stream_pos as usize, mc.tag_stream_len())
-                .ok_or_else(|| {
-                    std::io::Error::new(
-                        std::io::ErrorKind::Other,
-                        "Invalid slice generated when constructing sound stream block",
-                    )
-                })?;
-            let audio_stream =
-                context
-                    .audio
-                    .start_stream(mc.id(), mc.current_frame() + 1, slice, &stream_info);
-            mc.audio_stream = audio_stream.ok();
+        let mc = self.0.read();
+        let stream = match (&mc.static_data.audio_stream_info, mc.audio_stream) {
+            (Some(stream_info), None) => {
+                let slice = mc
+                    .static_data
+                    .swf
+                    .to_start_and_end(mc.tag_stream_pos as usize, mc.tag_stream_len())
+                    .ok_or_else(|| {
+                        std::io::Error::new(
+                            std::io::ErrorKind::Other,
+                            "Invalid slice generated when constructing sound stream block",
+                        )
+                    })?;
+                Some((stream_info.clone(), mc.current_frame(), slice))
+            }
+            _ => None,
+        };
+        drop(mc);
+
+        // The clip can't be borrowed while starting the stream, because the audio manager
+        // reads its sound transform.
+        if let Some((stream_info, clip_frame, slice)) = stream {
+            let audio_stream = context.audio_manager.start_stream(
+                context.audio,
+                self.into(),
+                clip_frame,
+                slice,
+                &stream_info,
+            );
+            self.0.write(context.gc_context).audio_stream = audio_stream;
         }
 
         Ok(())
@@ -2310,18 +3423,32 @@ impl<'gc, 'a> MovieClip<'gc> {
             match start_sound.sound_info.event {
                 // "Event" sounds always play, independent of the timeline.
                 SoundEvent::Event => {
-                    let _ = context.audio.start_sound(handle, &start_sound.sound_info);
+                    context.audio_manager.start_sound(
+                        context.audio,
+                        handle,
+                        &start_sound.sound_info,
+                        Some(self.into()),
+                        None,
+                    );
                 }
 
                 // "Start" sounds only play if an instance of the same sound is not already playing.
                 SoundEvent::Start => {
                     if !context.audio.is_sound_playing_with_handle(handle) {
-                        let _ = context.audio.start_sound(handle, &start_sound.sound_info);
+                        context.audio_manager.start_sound(
+                            context.audio,
+                            handle,
+                            &start_sound.sound_info,
+                            Some(self.into()),
+                            None,
+                        );
                     }
                 }
 
                 // "Stop" stops any active instances of a given sound.
-                SoundEvent::Stop => context.audio.stop_sounds_with_handle(handle),
+                SoundEvent::Stop => context
+                    .audio_manager
+                    .stop_sounds_with_handle(context.audio, handle),
             }
         }
         Ok(())
@@ -2337,6 +3464,11 @@ struct MovieClipStatic {
     frame_labels: HashMap<String, FrameNumber>,
     audio_stream_info: Option<swf::SoundStreamHead>,
     total_frames: FrameNumber,
+
+    /// The number of frames whose tags are all present in the movie data.
+    /// This is less than `total_frames` if the data ends early, e.g. because the
+    /// movie is still streaming in.
+    frames_loaded: FrameNumber,
 }
 
 impl MovieClipStatic {
@@ -2345,6 +3477,7 @@ impl MovieClipStatic {
             id: 0,
             swf,
             total_frames: 1,
+            frames_loaded: 1,
             frame_labels: HashMap::new(),
             audio_stream_info: None,
         }
@@ -2358,6 +3491,49 @@ unsafe impl<'gc> Collect for MovieClipStatic {
     }
 }
 
+/// Where the preload of a clip's tags stopped, so that it can pick up from there once
+/// more of the clip's movie has loaded.
+#[derive(Clone, Debug)]
+struct PreloadProgress {
+    /// The position of the next tag to preload in the clip's tag stream.
+    next_tag_pos: u64,
+
+    /// The frame that the next tag is in, starting at 1.
+    cur_frame: FrameNumber,
+
+    /// The ID of the morph shape at each depth, so that the ratios it is moved to
+    /// are tessellated.
+    morph_shape_depths: fnv::FnvHashMap<Depth, CharacterId>,
+
+    /// Whether all of the tags have been preloaded.
+    is_complete: bool,
+}
+
+impl Default for PreloadProgress {
+    fn default() -> Self {
+        Self {
+            next_tag_pos: 0,
+            cur_frame: 1,
+            morph_shape_depths: fnv::FnvHashMap::default(),
+            is_complete: false,
+        }
+    }
+}
+
+/// An AVM2 function registered to run whenever a clip enters a frame.
+#[derive(Clone, Debug, Collect)]
+#[collect(no_drop)]
+struct Avm2FrameScript<'gc> {
+    frame_id: FrameNumber,
+    callable: Avm2Object<'gc>,
+}
+
+/// A tag that runs ActionScript 3 code the first time its frame is reached.
+enum Avm2Tag {
+    DoAbc(swf::DoAbc),
+    SymbolClass(Vec<swf::SymbolClassLink>),
+}
+
 /// Stores the placement settings for display objects during a
 /// goto command.
 #[derive(Debug)]
@@ -2388,9 +3564,6 @@ impl GotoPlaceObject {
                 if place_object.ratio.is_none() {
                     place_object.ratio = Some(Default::default());
                 }
-                if place_object.name.is_none() {
-                    place_object.name = Some(Default::default());
-                }
                 if place_object.clip_depth.is_none() {
                     place_object.clip_depth = Some(Default::default());
                 }
@@ -2475,6 +3648,17 @@ enum MovieClipFlags {
 
     /// Whether this `MovieClip` is playing or stopped.
     Playing,
+
+    /// Whether this `MovieClip` stops on its last frame instead of looping.
+    /// Set on the root movie by the "Loop" item of the context menu.
+    NoLoop,
+
+    /// Whether this `MovieClip` is in the middle of a goto.
+    ExecutingGoto,
+
+    /// Whether this `MovieClip` is a root of its movie, keeping the movie's library loaded.
+    /// Set on clips that a movie is loaded into, and on their duplicates.
+    HoldsMovie,
 }
 
 /// Actions that are attached to a `MovieClip` event in
@@ -0,0 +1,360 @@
+//! Video display object
+
+use crate::avm1::{Avm1, Object, StageObject, Value};
+use crate::backend::render::BitmapInfo;
+use crate::backend::video::{self, DecodedFrame, EncodedFrame, VideoStreamHandle};
+use crate::context::{RenderContext, UpdateContext};
+use crate::display_object::{DisplayObject, DisplayObjectBase, TDisplayObject};
+use crate::prelude::*;
+use crate::tag_utils::SwfMovie;
+use crate::transform::Transform;
+use gc_arena::{Collect, GcCell, MutationContext};
+use std::collections::BTreeMap;
+use std::sync::Arc;
+use swf::{VideoCodec, VideoDeblocking};
+
+/// A Video display object shows the frames of a video, such as those of an AVM1
+/// `NetStream` attached with `Video.attachVideo`.
+///
+/// Videos are defined by the `DefineVideoStream` tag, which sets the size that the
+/// frames are scaled to. Videos embedded in the SWF store their frames in `VideoFrame`
+/// tags, and show the frame given by the ratio of their `PlaceObject` tags.
+#[derive(Clone, Debug, Collect, Copy)]
+#[collect(no_drop)]
+pub struct Video<'gc>(GcCell<'gc, VideoData<'gc>>);
+
+#[derive(Clone, Debug)]
+pub struct VideoData<'gc> {
+    base: DisplayObjectBase<'gc>,
+    static_data: GcCell<'gc, VideoStatic>,
+    object: Option<Object<'gc>>,
+
+    /// The most recently displayed frame, registered with the renderer.
+    frame: Option<BitmapInfo>,
+
+    /// The embedded frame to display, set by the ratio of a `PlaceObject` tag.
+    ratio: Option<u16>,
+
+    /// The stream that decodes the embedded frames, registered when they are first shown.
+    stream: Option<VideoStreamHandle>,
+
+    /// The last embedded frame that was decoded.
+    decoded_frame: Option<u16>,
+}
+
+impl<'gc> Video<'gc> {
+    pub fn from_swf_tag(
+        context: &mut UpdateContext<'_, 'gc, '_>,
+        swf: Arc<SwfMovie>,
+        tag: &swf::DefineVideoStream,
+    ) -> Self {
+        Video(GcCell::allocate(
+            context.gc_context,
+            VideoData {
+                base: Default::default(),
+                static_data: GcCell::allocate(
+                    context.gc_context,
+                    VideoStatic {
+                        swf,
+                        id: tag.id,
+                        width: tag.width,
+                        height: tag.height,
+                        is_smoothed: tag.is_smoothed,
+                        codec: tag.codec,
+                        deblocking: tag.deblocking,
+                        frames: BTreeMap::new(),
+                        is_unplayable: false,
+                    },
+                ),
+                object: None,
+                frame: None,
+                ratio: None,
+                stream: None,
+                decoded_frame: None,
+            },
+        ))
+    }
+
+    /// The size of the displayed frame, or `(0, 0)` if no frame is displayed.
+    pub fn frame_size(self) -> (u16, u16) {
+        self.0
+            .read()
+            .frame
+            .map_or((0, 0), |frame| (frame.width, frame.height))
+    }
+
+    /// Displays a decoded frame.
+    pub fn set_frame(self, context: &mut UpdateContext<'_, 'gc, '_>, frame: &DecodedFrame) {
+        let old_frame = self.0.read().frame;
+        let new_frame = match old_frame {
+            Some(old_frame)
+                if u32::from(old_frame.width) == frame.width
+                    && u32::from(old_frame.height) == frame.height =>
+            {
+                context
+                    .renderer
+                    .update_bitmap_rgba(
+                        old_frame.handle,
+                        frame.width,
+                        frame.height,
+                        frame.rgba.clone(),
+                    )
+                    .map(|_| old_frame)
+            }
+            _ => context.renderer.register_bitmap_rgba(
+                0,
+                frame.width,
+                frame.height,
+                frame.rgba.clone(),
+            ),
+        };
+        match new_frame {
+            Ok(new_frame) => {
+                self.0.write(context.gc_context).frame = Some(new_frame);
+                self.invalidate_cached_bitmap();
+            }
+            Err(e) => log::warn!("Unable to display video frame: {}", e),
+        }
+    }
+
+    /// Stores the data of an embedded frame, read from a `VideoFrame` tag.
+    pub fn preload_swf_frame(self, gc_context: MutationContext<'gc, '_>, tag: swf::VideoFrame) {
+        let static_data = self.0.read().static_data;
+        let mut static_data = static_data.write(gc_context);
+        let is_keyframe = video::is_keyframe(static_data.codec, &tag.data);
+        static_data.frames.insert(
+            tag.frame_num,
+            EmbeddedFrame {
+                data: tag.data,
+                is_keyframe,
+            },
+        );
+    }
+
+    /// Sets the embedded frame to display, which is shown by `update_embedded_frame`.
+    pub fn set_ratio(self, gc_context: MutationContext<'gc, '_>, ratio: u16) {
+        self.0.write(gc_context).ratio = Some(ratio);
+    }
+
+    /// Decodes and displays the embedded frame set by `set_ratio`.
+    ///
+    /// Frames that aren't keyframes are decoded forward from the keyframe before them,
+    /// or from the last decoded frame if it is in between.
+    pub fn update_embedded_frame(self, context: &mut UpdateContext<'_, 'gc, '_>) {
+        let (ratio, decoded_frame, stream) = {
+            let read = self.0.read();
+            (read.ratio, read.decoded_frame, read.stream)
+        };
+        let ratio = match ratio {
+            Some(ratio) if Some(ratio) != decoded_frame => ratio,
+            _ => return,
+        };
+        let static_data_cell = self.0.read().static_data;
+        let static_data = static_data_cell.read();
+        if static_data.frames.is_empty() || static_data.is_unplayable {
+            return;
+        }
+
+        let keyframe = static_data
+            .frames
+            .range(..=ratio)
+            .rev()
+            .find(|(_, frame)| frame.is_keyframe)
+            .map(|(frame_num, _)| *frame_num);
+        let start = match (keyframe, decoded_frame) {
+            (Some(keyframe), Some(decoded)) if keyframe <= decoded && decoded < ratio => {
+                decoded + 1
+            }
+            (Some(keyframe), _) => keyframe,
+            (None, _) => {
+                log::warn!("Video: No keyframe before frame {}", ratio);
+                return;
+            }
+        };
+
+        let stream = match stream {
+            Some(stream) => stream,
+            None => match context.video.register_video_stream(
+                static_data.codec,
+                Some((static_data.width, static_data.height)),
+                static_data.deblocking,
+            ) {
+                Ok(stream) => {
+                    self.0.write(context.gc_context).stream = Some(stream);
+                    stream
+                }
+                Err(e) => {
+                    // The video stays blank, so this is only reported once.
+                    log::error!("Video: Unable to play video {}: {}", static_data.id, e);
+                    drop(static_data);
+                    static_data_cell.write(context.gc_context).is_unplayable = true;
+                    return;
+                }
+            },
+        };
+
+        let mut decoded = None;
+        for frame in static_data
+            .frames
+            .range(start..=ratio)
+            .map(|(_, frame)| frame)
+        {
+            let encoded = EncodedFrame {
+                codec: static_data.codec,
+                data: &frame.data,
+                is_keyframe: frame.is_keyframe,
+            };
+            match context.video.decode_video_stream_frame(stream, encoded) {
+                Ok(frame) => decoded = Some(frame),
+                Err(e) => log::warn!("Video: Unable to decode frame: {}", e),
+            }
+        }
+        drop(static_data);
+
+        self.0.write(context.gc_context).decoded_frame = Some(ratio);
+        if let Some(decoded) = decoded {
+            self.set_frame(context, &decoded);
+        }
+    }
+
+    /// Stops displaying the current frame, as done by `Video.clear`.
+    pub fn clear(self, context: &mut UpdateContext<'_, 'gc, '_>) {
+        self.0.write(context.gc_context).frame = None;
+        self.invalidate_cached_bitmap();
+    }
+}
+
+impl<'gc> TDisplayObject<'gc> for Video<'gc> {
+    impl_display_object!(base);
+
+    fn id(&self) -> CharacterId {
+        self.0.read().static_data.read().id
+    }
+
+    fn movie(&self) -> Option<Arc<SwfMovie>> {
+        Some(self.0.read().static_data.read().swf.clone())
+    }
+
+    fn self_bounds(&self) -> BoundingBox {
+        let static_data = self.0.read().static_data;
+        let static_data = static_data.read();
+        BoundingBox {
+            x_min: Twips::new(0),
+            y_min: Twips::new(0),
+            x_max: Twips::from_pixels(static_data.width.into()),
+            y_max: Twips::from_pixels(static_data.height.into()),
+            valid: true,
+        }
+    }
+
+    fn as_video(&self) -> Option<Video<'gc>> {
+        Some(*self)
+    }
+
+    fn post_instantiation(
+        &mut self,
+        _avm: &mut Avm1<'gc>,
+        context: &mut UpdateContext<'_, 'gc, '_>,
+        display_object: DisplayObject<'gc>,
+        _init_object: Option<Object<'gc>>,
+        _instantiated_from_avm: bool,
+    ) {
+        self.set_default_instance_name(context);
+
+        let mut video = self.0.write(context.gc_context);
+        if video.object.is_none() {
+            let object = StageObject::for_display_object(
+                context.gc_context,
+                display_object,
+                Some(context.system_prototypes.video),
+            );
+            video.object = Some(object.into());
+        }
+    }
+
+    fn object(&self) -> Value<'gc> {
+        self.0
+            .read()
+            .object
+            .map(Value::from)
+            .unwrap_or(Value::Undefined)
+    }
+
+    fn run_frame(&mut self, _avm: &mut Avm1<'gc>, context: &mut UpdateContext<'_, 'gc, '_>) {
+        self.update_embedded_frame(context);
+    }
+
+    fn render(&self, context: &mut RenderContext) {
+        if !self.world_bounds().intersects(&context.view_bounds) {
+            // Off-screen; culled
+            return;
+        }
+
+        let read = self.0.read();
+        let frame = match read.frame {
+            Some(frame) if frame.width > 0 && frame.height > 0 => frame,
+            _ => return,
+        };
+        let static_data = read.static_data.read();
+
+        // Frames are stretched to the size of the video.
+        context.transform_stack.push(&*self.transform());
+        context.transform_stack.push(&Transform {
+            matrix: Matrix::scale(
+                f32::from(static_data.width) / f32::from(frame.width),
+                f32::from(static_data.height) / f32::from(frame.height),
+            ),
+            ..Default::default()
+        });
+
+        context.renderer.render_bitmap(
+            frame.handle,
+            context.transform_stack.transform(),
+            static_data.is_smoothed,
+        );
+
+        context.transform_stack.pop();
+        context.transform_stack.pop();
+    }
+}
+
+unsafe impl<'gc> gc_arena::Collect for VideoData<'gc> {
+    fn trace(&self, cc: gc_arena::CollectionContext) {
+        self.base.trace(cc);
+        self.static_data.trace(cc);
+        self.object.trace(cc);
+    }
+}
+
+/// Static data shared between all instances of a video.
+#[derive(Clone, Debug)]
+struct VideoStatic {
+    swf: Arc<SwfMovie>,
+    id: CharacterId,
+    width: u16,
+    height: u16,
+    is_smoothed: bool,
+    codec: VideoCodec,
+    deblocking: VideoDeblocking,
+
+    /// The frames embedded in the SWF, by frame number.
+    frames: BTreeMap<u16, EmbeddedFrame>,
+
+    /// Whether the video backend is unable to decode the frames, such as when it
+    /// doesn't support the codec.
+    is_unplayable: bool,
+}
+
+/// A frame of a `VideoFrame` tag.
+#[derive(Clone, Debug)]
+struct EmbeddedFrame {
+    data: Vec<u8>,
+    is_keyframe: bool,
+}
+
+unsafe impl gc_arena::Collect for VideoStatic {
+    #[inline]
+    fn needs_trace() -> bool {
+        false
+    }
+}
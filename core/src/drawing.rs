@@ -1,15 +1,34 @@
 use crate::backend::render::ShapeHandle;
 use crate::bounding_box::BoundingBox;
 use crate::context::RenderContext;
-use crate::shape_utils::{DistilledShape, DrawCommand, DrawPath};
+use crate::shape_utils::{
+    draw_command_fill_hit_test, draw_command_stroke_hit_test, DistilledShape, DrawCommand, DrawPath,
+};
 use gc_arena::Collect;
-use std::cell::Cell;
+use std::cell::{Cell, RefCell};
+use std::f64::consts::{FRAC_PI_2, FRAC_PI_4, PI};
 use swf::{FillStyle, LineStyle, Twips};
 
 #[derive(Clone, Debug, Collect)]
 #[collect(require_static)]
 pub struct Drawing {
-    render_handle: Cell<Option<ShapeHandle>>,
+    /// Meshes of the fills and strokes that were complete when the drawing was last rendered.
+    /// Each render tessellates only the newly completed ones into another mesh.
+    fill_meshes: RefCell<Vec<ShapeHandle>>,
+    line_meshes: RefCell<Vec<ShapeHandle>>,
+
+    /// How many of `fills` and `lines` have been tessellated into `fill_meshes` and `line_meshes`.
+    meshed_fills: Cell<usize>,
+    meshed_lines: Cell<usize>,
+
+    /// Meshes of the fill and stroke still being drawn, tessellated again whenever they change.
+    current_fill_mesh: Cell<Option<ShapeHandle>>,
+    current_line_mesh: Cell<Option<ShapeHandle>>,
+
+    /// Meshes that are no longer drawn since the drawing was cleared.
+    /// They are reused by later meshes rather than registering new shapes.
+    free_meshes: RefCell<Vec<ShapeHandle>>,
+
     shape_bounds: BoundingBox,
     edge_bounds: BoundingBox,
     dirty: Cell<bool>,
@@ -23,7 +42,13 @@ pub struct Drawing {
 impl Drawing {
     pub fn new() -> Self {
         Self {
-            render_handle: Cell::new(None),
+            fill_meshes: RefCell::new(Vec::new()),
+            line_meshes: RefCell::new(Vec::new()),
+            meshed_fills: Cell::new(0),
+            meshed_lines: Cell::new(0),
+            current_fill_mesh: Cell::new(None),
+            current_line_mesh: Cell::new(None),
+            free_meshes: RefCell::new(Vec::new()),
             shape_bounds: BoundingBox::default(),
             edge_bounds: BoundingBox::default(),
             dirty: Cell::new(false),
@@ -35,6 +60,22 @@ impl Drawing {
         }
     }
 
+    /// Creates a copy of this drawing that will register its own shape with
+    /// the renderer, rather than sharing (and overwriting) ours.
+    pub fn duplicate(&self) -> Self {
+        Self {
+            fill_meshes: RefCell::new(Vec::new()),
+            line_meshes: RefCell::new(Vec::new()),
+            meshed_fills: Cell::new(0),
+            meshed_lines: Cell::new(0),
+            current_fill_mesh: Cell::new(None),
+            current_line_mesh: Cell::new(None),
+            free_meshes: RefCell::new(Vec::new()),
+            dirty: Cell::new(true),
+            ..self.clone()
+        }
+    }
+
     pub fn set_fill_style(&mut self, style: Option<FillStyle>) {
         // TODO: If current_fill is not closed, we should close it and also close current_line
 
@@ -55,6 +96,14 @@ impl Drawing {
     }
 
     pub fn clear(&mut self) {
+        let free_meshes = self.free_meshes.get_mut();
+        free_meshes.append(self.fill_meshes.get_mut());
+        free_meshes.append(self.line_meshes.get_mut());
+        free_meshes.extend(self.current_fill_mesh.take());
+        free_meshes.extend(self.current_line_mesh.take());
+        self.meshed_fills.set(0);
+        self.meshed_lines.set(0);
+
         self.current_fill = None;
         self.current_line = None;
         self.fills.clear();
@@ -82,6 +131,26 @@ impl Drawing {
         self.dirty.set(true);
     }
 
+    /// Sets the fill of the current line style, such as a gradient from `lineGradientStyle`.
+    /// Has no effect if there is no current line style.
+    pub fn set_line_fill_style(&mut self, style: FillStyle) {
+        if let Some(existing) = self.current_line.take() {
+            let line_style = LineStyle {
+                fill_style: Some(style),
+                ..existing.0.clone()
+            };
+            self.lines.push(existing);
+            self.current_line = Some((
+                line_style,
+                vec![DrawCommand::MoveTo {
+                    x: self.cursor.0,
+                    y: self.cursor.1,
+                }],
+            ));
+            self.dirty.set(true);
+        }
+    }
+
     pub fn draw_command(&mut self, command: DrawCommand) {
         let mut include_last = false;
         let stroke_width = if let Some((style, _)) = &self.current_line {
@@ -136,68 +205,239 @@ impl Drawing {
         self.dirty.set(true);
     }
 
+    /// Draws the outline of a rectangle, starting and ending at its top-left corner.
+    pub fn draw_rect(&mut self, x: Twips, y: Twips, width: Twips, height: Twips) {
+        self.draw_command(DrawCommand::MoveTo { x, y });
+        self.draw_command(DrawCommand::LineTo { x: x + width, y });
+        self.draw_command(DrawCommand::LineTo {
+            x: x + width,
+            y: y + height,
+        });
+        self.draw_command(DrawCommand::LineTo { x, y: y + height });
+        self.draw_command(DrawCommand::LineTo { x, y });
+    }
+
+    /// Draws the outline of an ellipse that fits the given rectangle, as eight curves
+    /// starting and ending at its right-most point.
+    pub fn draw_ellipse(&mut self, x: Twips, y: Twips, width: Twips, height: Twips) {
+        let rx = f64::from(width.get()) / 2.0;
+        let ry = f64::from(height.get()) / 2.0;
+        let cx = f64::from(x.get()) + rx;
+        let cy = f64::from(y.get()) + ry;
+
+        self.draw_command(DrawCommand::MoveTo {
+            x: to_twips(cx + rx),
+            y: to_twips(cy),
+        });
+        self.draw_elliptical_arc((cx, cy), (rx, ry), 0.0, 8);
+    }
+
+    /// Draws the outline of a rectangle with corners rounded by quarters of an ellipse
+    /// of the given size, starting and ending at the bottom of its right edge.
+    ///
+    /// The ellipse is shrunk to fit the rectangle if it is too large.
+    pub fn draw_round_rect(
+        &mut self,
+        x: Twips,
+        y: Twips,
+        width: Twips,
+        height: Twips,
+        ellipse_width: Twips,
+        ellipse_height: Twips,
+    ) {
+        let left = f64::from(x.get());
+        let top = f64::from(y.get());
+        let right = left + f64::from(width.get());
+        let bottom = top + f64::from(height.get());
+        let rx = (f64::from(ellipse_width.get()) / 2.0).min((right - left).abs() / 2.0);
+        let ry = (f64::from(ellipse_height.get()) / 2.0).min((bottom - top).abs() / 2.0);
+        if rx <= 0.0 || ry <= 0.0 {
+            self.draw_rect(x, y, width, height);
+            return;
+        }
+
+        let radii = (rx, ry);
+        self.draw_command(DrawCommand::MoveTo {
+            x: to_twips(right),
+            y: to_twips(bottom - ry),
+        });
+        self.draw_elliptical_arc((right - rx, bottom - ry), radii, 0.0, 2);
+        self.draw_command(DrawCommand::LineTo {
+            x: to_twips(left + rx),
+            y: to_twips(bottom),
+        });
+        self.draw_elliptical_arc((left + rx, bottom - ry), radii, FRAC_PI_2, 2);
+        self.draw_command(DrawCommand::LineTo {
+            x: to_twips(left),
+            y: to_twips(top + ry),
+        });
+        self.draw_elliptical_arc((left + rx, top + ry), radii, PI, 2);
+        self.draw_command(DrawCommand::LineTo {
+            x: to_twips(right - rx),
+            y: to_twips(top),
+        });
+        self.draw_elliptical_arc((right - rx, top + ry), radii, PI + FRAC_PI_2, 2);
+        self.draw_command(DrawCommand::LineTo {
+            x: to_twips(right),
+            y: to_twips(bottom - ry),
+        });
+    }
+
+    /// Draws part of an ellipse from the point at `start_angle`, as curves of 45 degrees each.
+    ///
+    /// Each curve's control point lies where the tangents at its ends meet, which keeps
+    /// the curve within a fraction of a percent of the true ellipse.
+    fn draw_elliptical_arc(
+        &mut self,
+        (cx, cy): (f64, f64),
+        (rx, ry): (f64, f64),
+        start_angle: f64,
+        segments: u32,
+    ) {
+        let control_distance = 1.0 / (FRAC_PI_4 / 2.0).cos();
+        for i in 0..segments {
+            let control_angle = start_angle + FRAC_PI_4 * (f64::from(i) + 0.5);
+            let end_angle = start_angle + FRAC_PI_4 * f64::from(i + 1);
+            self.draw_command(DrawCommand::CurveTo {
+                x1: to_twips(cx + rx * control_distance * control_angle.cos()),
+                y1: to_twips(cy + ry * control_distance * control_angle.sin()),
+                x2: to_twips(cx + rx * end_angle.cos()),
+                y2: to_twips(cy + ry * end_angle.sin()),
+            });
+        }
+    }
+
     pub fn render(&self, context: &mut RenderContext) {
         if self.dirty.get() {
             self.dirty.set(false);
-            let mut paths = Vec::new();
 
-            for (style, commands) in &self.fills {
-                paths.push(DrawPath::Fill {
-                    style,
-                    commands: commands.to_owned(),
-                })
+            let new_fills = &self.fills[self.meshed_fills.get()..];
+            if !new_fills.is_empty() {
+                let paths = new_fills
+                    .iter()
+                    .map(|(style, commands)| DrawPath::Fill {
+                        style,
+                        commands: commands.to_owned(),
+                    })
+                    .collect();
+                let mesh = self.tessellate(context, None, paths);
+                self.fill_meshes.borrow_mut().push(mesh);
+                self.meshed_fills.set(self.fills.len());
             }
 
             // TODO: If the current_fill is not closed, we should automatically close current_line
 
-            if let Some((style, commands)) = &self.current_fill {
-                paths.push(DrawPath::Fill {
+            let current_fill = self.current_fill.as_ref().map(|(style, commands)| {
+                vec![DrawPath::Fill {
                     style,
                     commands: commands.to_owned(),
-                })
-            }
+                }]
+            });
+            self.update_current_mesh(context, &self.current_fill_mesh, current_fill);
 
-            for (style, commands) in &self.lines {
-                paths.push(DrawPath::Stroke {
-                    style,
-                    commands: commands.to_owned(),
-                    is_closed: false, // TODO: Determine this
-                })
+            let new_lines = &self.lines[self.meshed_lines.get()..];
+            if !new_lines.is_empty() {
+                let paths = new_lines
+                    .iter()
+                    .map(|(style, commands)| DrawPath::Stroke {
+                        style,
+                        commands: commands.to_owned(),
+                        is_closed: false, // TODO: Determine this
+                    })
+                    .collect();
+                let mesh = self.tessellate(context, None, paths);
+                self.line_meshes.borrow_mut().push(mesh);
+                self.meshed_lines.set(self.lines.len());
             }
 
-            if let Some((style, commands)) = &self.current_line {
-                paths.push(DrawPath::Stroke {
+            let current_line = self.current_line.as_ref().map(|(style, commands)| {
+                vec![DrawPath::Stroke {
                     style,
                     commands: commands.to_owned(),
                     is_closed: false, // TODO: Determine this
-                })
-            }
+                }]
+            });
+            self.update_current_mesh(context, &self.current_line_mesh, current_line);
+        }
 
-            let shape = DistilledShape {
-                paths,
-                shape_bounds: self.shape_bounds.clone(),
-                edge_bounds: self.edge_bounds.clone(),
-                id: 0,
-            };
+        let transform = context.transform_stack.transform();
+        let fill_meshes = self.fill_meshes.borrow();
+        let line_meshes = self.line_meshes.borrow();
+        let meshes = fill_meshes
+            .iter()
+            .copied()
+            .chain(self.current_fill_mesh.get())
+            .chain(line_meshes.iter().copied())
+            .chain(self.current_line_mesh.get());
+        for mesh in meshes {
+            context.renderer.render_shape(mesh, transform);
+        }
+    }
 
-            if let Some(handle) = self.render_handle.get() {
-                context.renderer.replace_shape(shape, handle);
-            } else {
-                self.render_handle
-                    .set(Some(context.renderer.register_shape(shape)));
-            }
+    /// Tessellates the mesh of a fill or stroke that is still being drawn,
+    /// or frees the mesh once there is nothing left to draw.
+    fn update_current_mesh(
+        &self,
+        context: &mut RenderContext,
+        mesh: &Cell<Option<ShapeHandle>>,
+        paths: Option<Vec<DrawPath>>,
+    ) {
+        match paths {
+            Some(paths) => mesh.set(Some(self.tessellate(context, mesh.get(), paths))),
+            None => self.free_meshes.borrow_mut().extend(mesh.take()),
         }
+    }
 
-        if let Some(handle) = self.render_handle.get() {
-            context
-                .renderer
-                .render_shape(handle, context.transform_stack.transform());
+    /// Tessellates the given paths into a mesh, replacing `mesh` or a free mesh
+    /// rather than registering a new shape when possible.
+    fn tessellate(
+        &self,
+        context: &mut RenderContext,
+        mesh: Option<ShapeHandle>,
+        paths: Vec<DrawPath>,
+    ) -> ShapeHandle {
+        let shape = DistilledShape {
+            paths,
+            shape_bounds: self.shape_bounds.clone(),
+            edge_bounds: self.edge_bounds.clone(),
+            id: 0,
+        };
+
+        let free_mesh = || self.free_meshes.borrow_mut().pop();
+        if let Some(mesh) = mesh.or_else(free_mesh) {
+            context.renderer.replace_shape(shape, mesh);
+            mesh
+        } else {
+            context.renderer.register_shape(shape)
         }
     }
 
     pub fn self_bounds(&self) -> BoundingBox {
         self.shape_bounds.clone()
     }
+
+    /// Returns whether the given point, in local space, hits any fill or stroke of this drawing.
+    pub fn hit_test(&self, point: (Twips, Twips)) -> bool {
+        if !self.shape_bounds.contains(point) {
+            return false;
+        }
+
+        let fill_hit = self
+            .fills
+            .iter()
+            .chain(self.current_fill.iter())
+            .any(|(_, commands)| draw_command_fill_hit_test(commands, point));
+        fill_hit
+            || self
+                .lines
+                .iter()
+                .chain(self.current_line.iter())
+                .any(|(style, commands)| draw_command_stroke_hit_test(commands, style.width, point))
+    }
+}
+
+fn to_twips(twips: f64) -> Twips {
+    Twips::new(twips.round() as i32)
 }
 
 fn stretch_bounding_box(
@@ -223,3 +463,74 @@ fn stretch_bounding_box(
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use swf::Color;
+
+    fn filled_drawing() -> Drawing {
+        let mut drawing = Drawing::new();
+        drawing.set_fill_style(Some(FillStyle::Color(Color::from_rgb(0xFF0000, 255))));
+        drawing
+    }
+
+    fn point(x: f64, y: f64) -> (Twips, Twips) {
+        (Twips::from_pixels(x), Twips::from_pixels(y))
+    }
+
+    #[test]
+    fn draw_ellipse_is_closed_and_filled() {
+        let mut drawing = filled_drawing();
+        drawing.draw_ellipse(
+            Twips::from_pixels(0.0),
+            Twips::from_pixels(0.0),
+            Twips::from_pixels(100.0),
+            Twips::from_pixels(50.0),
+        );
+
+        assert_eq!(drawing.cursor, point(100.0, 25.0));
+        assert!(drawing.hit_test(point(50.0, 25.0)));
+        assert!(drawing.hit_test(point(98.0, 25.0)));
+        assert!(drawing.hit_test(point(50.0, 2.0)));
+        assert!(!drawing.hit_test(point(5.0, 5.0)));
+        assert!(!drawing.hit_test(point(95.0, 45.0)));
+    }
+
+    #[test]
+    fn draw_round_rect_is_closed_and_filled() {
+        let mut drawing = filled_drawing();
+        drawing.draw_round_rect(
+            Twips::from_pixels(10.0),
+            Twips::from_pixels(10.0),
+            Twips::from_pixels(100.0),
+            Twips::from_pixels(100.0),
+            Twips::from_pixels(40.0),
+            Twips::from_pixels(40.0),
+        );
+
+        assert_eq!(drawing.cursor, point(110.0, 90.0));
+        assert!(drawing.hit_test(point(60.0, 60.0)));
+        assert!(drawing.hit_test(point(60.0, 11.0)));
+        assert!(drawing.hit_test(point(11.0, 60.0)));
+        assert!(!drawing.hit_test(point(11.0, 11.0)));
+        assert!(!drawing.hit_test(point(109.0, 109.0)));
+    }
+
+    #[test]
+    fn draw_round_rect_without_corners_is_rect() {
+        let mut drawing = filled_drawing();
+        drawing.draw_round_rect(
+            Twips::from_pixels(10.0),
+            Twips::from_pixels(10.0),
+            Twips::from_pixels(100.0),
+            Twips::from_pixels(100.0),
+            Twips::zero(),
+            Twips::zero(),
+        );
+
+        assert_eq!(drawing.cursor, point(10.0, 10.0));
+        assert!(drawing.hit_test(point(11.0, 11.0)));
+        assert!(drawing.hit_test(point(109.0, 109.0)));
+    }
+}
@@ -9,9 +9,35 @@ pub enum PlayerEvent {
     MouseUp { x: f64, y: f64 },
     MouseDown { x: f64, y: f64 },
     MouseLeft,
+    MouseWheel { delta: MouseWheelDelta },
     TextInput { codepoint: char },
 }
 
+/// The distance scrolled by a mouse wheel.
+/// Positive values scroll up, away from the user.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum MouseWheelDelta {
+    /// Scrolled by lines of text. A notch of a mouse wheel scrolls 3 lines.
+    Lines(f64),
+
+    /// Scrolled by pixels, as reported by touchpads and some browsers.
+    Pixels(f64),
+}
+
+impl MouseWheelDelta {
+    /// The number of pixels in a line of scrolling.
+    /// Browsers scroll about 100 pixels per notch of the mouse wheel.
+    const PIXELS_PER_LINE: f64 = 100.0 / 3.0;
+
+    /// The number of lines scrolled. This is the delta passed to `onMouseWheel`.
+    pub fn lines(self) -> f64 {
+        match self {
+            Self::Lines(lines) => lines,
+            Self::Pixels(pixels) => pixels / Self::PIXELS_PER_LINE,
+        }
+    }
+}
+
 /// Whether this button event was handled by some child.
 #[derive(Debug, PartialEq, Eq, Clone, Copy)]
 pub enum ClipEventResult {
@@ -61,13 +87,28 @@ impl ClipEvent {
     pub fn propagates(self) -> bool {
         matches!(
             self,
-            Self::MouseUp | Self::MouseDown | Self::MouseMove | Self::KeyPress { .. } | Self::KeyDown | Self::KeyUp
+            Self::MouseUp
+                | Self::MouseDown
+                | Self::MouseMove
+                | Self::KeyPress { .. }
+                | Self::KeyDown
+                | Self::KeyUp
         )
     }
 
     /// Indicates whether this is an event type used by Buttons (i.e., on that can be used in an `on` handler in Flash).
     pub fn is_button_event(self) -> bool {
-        matches!(self, Self::DragOut | Self::DragOver | Self::KeyPress { .. } | Self::Press | Self::RollOut | Self::RollOver | Self::Release | Self::ReleaseOutside)
+        matches!(
+            self,
+            Self::DragOut
+                | Self::DragOver
+                | Self::KeyPress { .. }
+                | Self::Press
+                | Self::RollOut
+                | Self::RollOver
+                | Self::Release
+                | Self::ReleaseOutside
+        )
     }
 
     /// Returns the method name of the event handler for this event.
@@ -97,11 +138,12 @@ impl ClipEvent {
 }
 
 /// Flash virtual keycode.
-#[derive(Debug, Copy, Clone, PartialEq, Eq, TryFromPrimitive, IntoPrimitive)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, TryFromPrimitive, IntoPrimitive)]
 #[repr(u8)]
 pub enum KeyCode {
     Unknown = 0,
     Backspace = 8,
+    Tab = 9,
     Return = 13,
     Shift = 16,
     Control = 17,
@@ -331,6 +373,7 @@ pub fn key_code_to_button_key_code(key_code: KeyCode) -> Option<ButtonKeyCode> {
         KeyCode::Down => ButtonKeyCode::Down,
         KeyCode::PgUp => ButtonKeyCode::PgUp,
         KeyCode::PgDown => ButtonKeyCode::PgDown,
+        KeyCode::Tab => ButtonKeyCode::Tab,
         KeyCode::Escape => ButtonKeyCode::Escape,
         _ => return None,
     };
@@ -0,0 +1,330 @@
+//! Communication between a movie and its host, through `ExternalInterface`.
+//!
+//! Values cross the boundary in the XML format used by the Flash Player, e.g.
+//! `<invoke name="jsFunc" returntype="xml"><arguments><string>hi</string></arguments></invoke>`.
+
+use quick_xml::events::{BytesStart, Event};
+use quick_xml::Reader;
+use std::collections::BTreeMap;
+
+type Error = Box<dyn std::error::Error>;
+
+/// A value passed between a movie and its host.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+    Undefined,
+    Null,
+    Bool(bool),
+    Number(f64),
+    String(String),
+    Object(BTreeMap<String, Value>),
+    List(Vec<Value>),
+}
+
+impl Value {
+    /// Encode this value in the Flash `ExternalInterface` XML format.
+    pub fn to_xml(&self) -> String {
+        let mut xml = String::new();
+        self.write_xml(&mut xml);
+        xml
+    }
+
+    fn write_xml(&self, xml: &mut String) {
+        match self {
+            Value::Undefined => xml.push_str("<undefined/>"),
+            Value::Null => xml.push_str("<null/>"),
+            Value::Bool(true) => xml.push_str("<true/>"),
+            Value::Bool(false) => xml.push_str("<false/>"),
+            Value::Number(number) => {
+                xml.push_str("<number>");
+                if number.is_nan() {
+                    xml.push_str("NaN");
+                } else if number.is_infinite() && *number > 0.0 {
+                    xml.push_str("Infinity");
+                } else if number.is_infinite() {
+                    xml.push_str("-Infinity");
+                } else {
+                    xml.push_str(&number.to_string());
+                }
+                xml.push_str("</number>");
+            }
+            Value::String(string) => {
+                xml.push_str("<string>");
+                push_escaped(xml, string);
+                xml.push_str("</string>");
+            }
+            Value::Object(properties) => {
+                xml.push_str("<object>");
+                for (name, value) in properties {
+                    write_property(xml, name, value);
+                }
+                xml.push_str("</object>");
+            }
+            Value::List(values) => {
+                xml.push_str("<array>");
+                for (index, value) in values.iter().enumerate() {
+                    write_property(xml, &index.to_string(), value);
+                }
+                xml.push_str("</array>");
+            }
+        }
+    }
+
+    /// Decode a value from the Flash `ExternalInterface` XML format.
+    pub fn from_xml(xml: &str) -> Result<Self, Error> {
+        Self::from_element(&Element::parse(xml)?)
+    }
+
+    fn from_element(element: &Element) -> Result<Self, Error> {
+        Ok(match element.name.as_str() {
+            "undefined" => Value::Undefined,
+            "null" => Value::Null,
+            "true" => Value::Bool(true),
+            "false" => Value::Bool(false),
+            "number" => Value::Number(element.text.trim().parse().unwrap_or(std::f64::NAN)),
+            "string" => Value::String(element.text.clone()),
+            "object" => {
+                let mut properties = BTreeMap::new();
+                for property in &element.children {
+                    let (name, value) = property.to_property()?;
+                    properties.insert(name, value);
+                }
+                Value::Object(properties)
+            }
+            "array" => {
+                let mut values = Vec::new();
+                for property in &element.children {
+                    let (index, value) = property.to_property()?;
+                    let index: usize = index.parse()?;
+                    if values.len() <= index {
+                        values.resize(index + 1, Value::Undefined);
+                    }
+                    values[index] = value;
+                }
+                Value::List(values)
+            }
+            name => return Err(format!("Unknown ExternalInterface value <{}>", name).into()),
+        })
+    }
+}
+
+/// A call of a named function, made from either side of the interface.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Invocation {
+    pub name: String,
+    pub args: Vec<Value>,
+}
+
+impl Invocation {
+    pub fn new(name: &str, args: Vec<Value>) -> Self {
+        Self {
+            name: name.to_string(),
+            args,
+        }
+    }
+
+    /// Encode this call as an `<invoke>` element.
+    pub fn to_xml(&self) -> String {
+        let mut xml = String::from("<invoke name=\"");
+        push_escaped(&mut xml, &self.name);
+        xml.push_str("\" returntype=\"xml\"><arguments>");
+        for arg in &self.args {
+            arg.write_xml(&mut xml);
+        }
+        xml.push_str("</arguments></invoke>");
+        xml
+    }
+
+    /// Decode a call from an `<invoke>` element.
+    pub fn from_xml(xml: &str) -> Result<Self, Error> {
+        let element = Element::parse(xml)?;
+        if element.name != "invoke" {
+            return Err(format!("Expected <invoke>, found <{}>", element.name).into());
+        }
+
+        let name = element
+            .attribute("name")
+            .ok_or("<invoke> is missing a name")?
+            .to_string();
+        let mut args = Vec::new();
+        if let Some(arguments) = element.children.iter().find(|e| e.name == "arguments") {
+            for arg in &arguments.children {
+                args.push(Value::from_element(arg)?);
+            }
+        }
+
+        Ok(Self { name, args })
+    }
+}
+
+/// The host of a movie, which can call into it and be called from it.
+pub trait ExternalInterfaceProvider {
+    /// Call a function of the host on behalf of `ExternalInterface.call`.
+    fn call_method(&mut self, name: &str, args: &[Value]) -> Value;
+
+    /// Called when the movie exposes a function to the host with
+    /// `ExternalInterface.addCallback`.
+    ///
+    /// The host calls it with `Player::call_internal_interface`.
+    fn on_callback_available(&mut self, name: &str);
+}
+
+/// Handles the `fscommand`s sent by a movie, given the command and its
+/// arguments. Returns whether the command was understood.
+pub type FsCommandHandler = dyn FnMut(&str, &str) -> bool;
+
+fn write_property(xml: &mut String, id: &str, value: &Value) {
+    xml.push_str("<property id=\"");
+    push_escaped(xml, id);
+    xml.push_str("\">");
+    value.write_xml(xml);
+    xml.push_str("</property>");
+}
+
+fn push_escaped(xml: &mut String, text: &str) {
+    for c in text.chars() {
+        match c {
+            '&' => xml.push_str("&amp;"),
+            '<' => xml.push_str("&lt;"),
+            '>' => xml.push_str("&gt;"),
+            '"' => xml.push_str("&quot;"),
+            '\'' => xml.push_str("&apos;"),
+            c => xml.push(c),
+        }
+    }
+}
+
+/// A parsed XML element, which is all the structure the format needs.
+struct Element {
+    name: String,
+    attributes: Vec<(String, String)>,
+    children: Vec<Element>,
+    text: String,
+}
+
+impl Element {
+    fn parse(xml: &str) -> Result<Self, Error> {
+        let mut reader = Reader::from_str(xml);
+        let mut buf = Vec::new();
+        let mut open: Vec<Element> = Vec::new();
+
+        loop {
+            match reader.read_event(&mut buf)? {
+                Event::Start(bs) => open.push(Self::from_start(&bs)?),
+                Event::Empty(bs) => {
+                    let element = Self::from_start(&bs)?;
+                    match open.last_mut() {
+                        Some(parent) => parent.children.push(element),
+                        None => return Ok(element),
+                    }
+                }
+                Event::End(_) => {
+                    let element = open.pop().ok_or("Unexpected closing tag")?;
+                    match open.last_mut() {
+                        Some(parent) => parent.children.push(element),
+                        None => return Ok(element),
+                    }
+                }
+                Event::Text(bt) => {
+                    if let Some(element) = open.last_mut() {
+                        element
+                            .text
+                            .push_str(&String::from_utf8(bt.unescaped()?.into_owned())?);
+                    }
+                }
+                Event::CData(bt) => {
+                    if let Some(element) = open.last_mut() {
+                        element.text.push_str(std::str::from_utf8(&bt)?);
+                    }
+                }
+                Event::Eof => return Err("Unexpected end of XML".into()),
+                _ => {}
+            }
+            buf.clear();
+        }
+    }
+
+    fn from_start(bs: &BytesStart) -> Result<Self, Error> {
+        let mut attributes = Vec::new();
+        for attribute in bs.attributes() {
+            let attribute = attribute?;
+            attributes.push((
+                String::from_utf8(attribute.key.to_vec())?,
+                String::from_utf8(attribute.unescaped_value()?.into_owned())?,
+            ));
+        }
+
+        Ok(Self {
+            name: String::from_utf8(bs.name().to_vec())?,
+            attributes,
+            children: Vec::new(),
+            text: String::new(),
+        })
+    }
+
+    fn attribute(&self, name: &str) -> Option<&str> {
+        self.attributes
+            .iter()
+            .find(|(key, _)| key == name)
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// Read a `<property id="...">value</property>` element.
+    fn to_property(&self) -> Result<(String, Value), Error> {
+        let id = self
+            .attribute("id")
+            .ok_or("<property> is missing an id")?
+            .to_string();
+        let value = self
+            .children
+            .first()
+            .map(Value::from_element)
+            .transpose()?
+            .unwrap_or(Value::Undefined);
+        Ok((id, value))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn value_round_trip() {
+        let mut object = BTreeMap::new();
+        object.insert("name".to_string(), Value::String("<Ruffle & co>".into()));
+        object.insert("scale".to_string(), Value::Number(1.5));
+        let value = Value::List(vec![
+            Value::Undefined,
+            Value::Null,
+            Value::Bool(true),
+            Value::Number(-3.0),
+            Value::Object(object),
+        ]);
+
+        let xml = value.to_xml();
+        assert_eq!(
+            xml,
+            "<array><property id=\"0\"><undefined/></property><property id=\"1\"><null/></property>\
+             <property id=\"2\"><true/></property><property id=\"3\"><number>-3</number></property>\
+             <property id=\"4\"><object><property id=\"name\"><string>&lt;Ruffle &amp; co&gt;</string>\
+             </property><property id=\"scale\"><number>1.5</number></property></object></property></array>"
+        );
+        assert_eq!(Value::from_xml(&xml).unwrap(), value);
+    }
+
+    #[test]
+    fn invocation_round_trip() {
+        let xml = "<invoke name=\"jsFunc\" returntype=\"xml\"><arguments><string>hi</string>\
+                   <number>2</number></arguments></invoke>";
+        let invocation = Invocation::from_xml(xml).unwrap();
+        assert_eq!(
+            invocation,
+            Invocation::new(
+                "jsFunc",
+                vec![Value::String("hi".into()), Value::Number(2.0)]
+            )
+        );
+        assert_eq!(invocation.to_xml(), xml);
+    }
+}
@@ -0,0 +1,320 @@
+//! FLV file parsing
+//!
+//! FLV files hold the audio and video streams played by an AVM1 `NetStream`. The file is a
+//! sequence of timestamped tags, each containing an audio packet, a video packet, or script
+//! data such as the `onMetaData` of the stream.
+
+use swf::{AudioCompression, SoundFormat, VideoCodec};
+use thiserror::Error;
+
+#[derive(Debug, Error, PartialEq)]
+pub enum Error {
+    #[error("Not an FLV file")]
+    InvalidSignature,
+
+    #[error("Unexpected end of FLV data")]
+    UnexpectedEof,
+}
+
+/// A tag of an FLV file.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FlvTag<'a> {
+    /// The time at which the tag is played, in milliseconds.
+    pub timestamp: u32,
+
+    pub data: FlvTagData<'a>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum FlvTagData<'a> {
+    /// An audio packet. `format` is `None` for codecs that SWF files can't contain,
+    /// such as AAC.
+    Audio {
+        format: Option<SoundFormat>,
+        data: &'a [u8],
+    },
+
+    /// A video packet. `codec` is `None` for codecs that SWF files can't contain,
+    /// such as H.264.
+    ///
+    /// The data is in the format of the `VideoData` of a SWF `VideoFrame` tag: the
+    /// size adjustment that precedes VP6 frames in FLV files is removed.
+    Video {
+        codec: Option<VideoCodec>,
+        is_keyframe: bool,
+        data: &'a [u8],
+    },
+
+    /// AMF0 encoded script data, which names a handler and the value passed to it.
+    ScriptData(&'a [u8]),
+
+    /// A tag of an unknown type, or a video info frame.
+    Unknown,
+}
+
+/// Reads the tags of an FLV file.
+pub struct FlvReader<'a> {
+    /// The data after the tags that have been read.
+    data: &'a [u8],
+
+    /// The length of the file data, including the tags that have been read.
+    len: usize,
+}
+
+impl<'a> FlvReader<'a> {
+    /// Reads the header of an FLV file, returning a reader for its tags.
+    ///
+    /// The header also flags whether the file has audio and video, but files don't
+    /// always set these correctly, so the tags themselves are used instead.
+    pub fn new(data: &'a [u8]) -> Result<Self, Error> {
+        if data.get(..3).map_or(false, |signature| signature != b"FLV") {
+            return Err(Error::InvalidSignature);
+        }
+        if data.len() < 9 {
+            return Err(Error::UnexpectedEof);
+        }
+
+        // The header is followed by the size of the (non-existent) tag before the first tag.
+        let header_len = u32::from_be_bytes([data[5], data[6], data[7], data[8]]) as usize;
+        let tags = data.get(header_len + 4..).ok_or(Error::UnexpectedEof)?;
+        Ok(Self {
+            data: tags,
+            len: data.len(),
+        })
+    }
+
+    /// Returns a reader that continues from the tag at `position`, as returned by
+    /// `position` on a reader of an earlier part of the same file.
+    ///
+    /// This picks up reading a file that is still loading once more of it has arrived.
+    pub fn resume(data: &'a [u8], position: usize) -> Self {
+        Self {
+            data: data.get(position..).unwrap_or(&[]),
+            len: data.len(),
+        }
+    }
+
+    /// The position of the next tag in the file.
+    pub fn position(&self) -> usize {
+        self.len - self.data.len()
+    }
+
+    fn read_bytes(&mut self, len: usize) -> Result<&'a [u8], Error> {
+        if self.data.len() < len {
+            return Err(Error::UnexpectedEof);
+        }
+        let (bytes, rest) = self.data.split_at(len);
+        self.data = rest;
+        Ok(bytes)
+    }
+
+    fn read_u24(&mut self) -> Result<u32, Error> {
+        let bytes = self.read_bytes(3)?;
+        Ok(u32::from_be_bytes([0, bytes[0], bytes[1], bytes[2]]))
+    }
+
+    /// Reads the next tag, or returns `None` at the end of the file.
+    ///
+    /// If the data ends partway through the tag, this returns `Error::UnexpectedEof`
+    /// and the reader stays at the start of the tag.
+    pub fn read_tag(&mut self) -> Result<Option<FlvTag<'a>>, Error> {
+        let data = self.data;
+        let tag = self.read_tag_data();
+        if tag.is_err() {
+            self.data = data;
+        }
+        tag
+    }
+
+    fn read_tag_data(&mut self) -> Result<Option<FlvTag<'a>>, Error> {
+        if self.data.is_empty() {
+            return Ok(None);
+        }
+
+        // The upper bits of the tag type mark filtered (encrypted) tags.
+        let tag_type = self.read_bytes(1)?[0] & 0b1_1111;
+        let len = self.read_u24()? as usize;
+        let timestamp = self.read_u24()? | u32::from(self.read_bytes(1)?[0]) << 24;
+        let _stream_id = self.read_u24()?;
+        let data = self.read_bytes(len)?;
+        let _previous_tag_size = self.read_bytes(4)?;
+
+        let data = match (tag_type, data.split_first()) {
+            (8, Some((&header, data))) => FlvTagData::Audio {
+                format: sound_format(header),
+                data,
+            },
+            (9, Some((&header, data))) => video_tag(header, data),
+            (18, _) => FlvTagData::ScriptData(data),
+            _ => FlvTagData::Unknown,
+        };
+        Ok(Some(FlvTag { timestamp, data }))
+    }
+}
+
+/// Reads the format of an audio tag from its first byte.
+fn sound_format(header: u8) -> Option<SoundFormat> {
+    let mut sample_rate = match (header >> 2) & 0b11 {
+        0 => 5512,
+        1 => 11025,
+        2 => 22050,
+        _ => 44100,
+    };
+    let compression = match header >> 4 {
+        0 => AudioCompression::UncompressedUnknownEndian,
+        1 => AudioCompression::Adpcm,
+        2 => AudioCompression::Mp3,
+        3 => AudioCompression::Uncompressed,
+        4 => AudioCompression::Nellymoser16Khz,
+        5 => AudioCompression::Nellymoser8Khz,
+        6 => AudioCompression::Nellymoser,
+        11 => AudioCompression::Speex,
+        14 => {
+            sample_rate = 8000;
+            AudioCompression::Mp3
+        }
+        _ => return None,
+    };
+    Some(SoundFormat {
+        compression,
+        sample_rate,
+        is_16_bit: header & 0b10 != 0,
+        is_stereo: header & 0b1 != 0,
+    })
+}
+
+/// Reads a video tag, given its first byte and the data after it.
+fn video_tag(header: u8, data: &[u8]) -> FlvTagData<'_> {
+    let is_keyframe = match header >> 4 {
+        1 | 4 => true,
+        2 | 3 => false,
+        _ => return FlvTagData::Unknown,
+    };
+    let codec = match header & 0b1111 {
+        2 => Some(VideoCodec::H263),
+        3 => Some(VideoCodec::ScreenVideo),
+        4 => Some(VideoCodec::VP6),
+        5 => Some(VideoCodec::VP6WithAlpha),
+        _ => None,
+    };
+    let data = match codec {
+        Some(VideoCodec::VP6) | Some(VideoCodec::VP6WithAlpha) => data.get(1..).unwrap_or(&[]),
+        _ => data,
+    };
+    FlvTagData::Video {
+        codec,
+        is_keyframe,
+        data,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tag(tag_type: u8, timestamp: u32, data: &[u8]) -> Vec<u8> {
+        let mut tag = vec![tag_type];
+        tag.extend_from_slice(&(data.len() as u32).to_be_bytes()[1..]);
+        tag.extend_from_slice(&timestamp.to_be_bytes()[1..]);
+        tag.push((timestamp >> 24) as u8);
+        tag.extend_from_slice(&[0, 0, 0]);
+        tag.extend_from_slice(data);
+        tag.extend_from_slice(&(data.len() as u32 + 11).to_be_bytes());
+        tag
+    }
+
+    #[test]
+    fn read_tags() {
+        let mut data = vec![b'F', b'L', b'V', 1, 0b101, 0, 0, 0, 9, 0, 0, 0, 0];
+        data.extend(tag(18, 0, &[2, 0, 1, b'a']));
+        data.extend(tag(9, 0, &[0x14, 0x00, 1, 2]));
+        data.extend(tag(8, 0x0100_0000, &[0x2f, 3, 4]));
+        data.extend(tag(9, 40, &[0x27, 5]));
+
+        let mut reader = FlvReader::new(&data).unwrap();
+        assert_eq!(
+            reader.read_tag(),
+            Ok(Some(FlvTag {
+                timestamp: 0,
+                data: FlvTagData::ScriptData(&[2, 0, 1, b'a']),
+            }))
+        );
+        assert_eq!(
+            reader.read_tag(),
+            Ok(Some(FlvTag {
+                timestamp: 0,
+                data: FlvTagData::Video {
+                    codec: Some(VideoCodec::VP6),
+                    is_keyframe: true,
+                    data: &[1, 2],
+                },
+            }))
+        );
+        assert_eq!(
+            reader.read_tag(),
+            Ok(Some(FlvTag {
+                timestamp: 0x0100_0000,
+                data: FlvTagData::Audio {
+                    format: Some(SoundFormat {
+                        compression: AudioCompression::Mp3,
+                        sample_rate: 44100,
+                        is_16_bit: true,
+                        is_stereo: true,
+                    }),
+                    data: &[3, 4],
+                },
+            }))
+        );
+        assert_eq!(
+            reader.read_tag(),
+            Ok(Some(FlvTag {
+                timestamp: 40,
+                data: FlvTagData::Video {
+                    codec: None,
+                    is_keyframe: false,
+                    data: &[5],
+                },
+            }))
+        );
+        assert_eq!(reader.read_tag(), Ok(None));
+    }
+
+    #[test]
+    fn invalid_data() {
+        assert_eq!(
+            FlvReader::new(b"FWS\x06").err(),
+            Some(Error::InvalidSignature)
+        );
+
+        let data = [b'F', b'L', b'V', 1, 1, 0, 0, 0, 9, 0, 0, 0, 0, 9, 0, 0, 10];
+        let mut reader = FlvReader::new(&data).unwrap();
+        assert_eq!(reader.read_tag(), Err(Error::UnexpectedEof));
+    }
+
+    #[test]
+    fn resume_reading() {
+        let mut data = vec![b'F', b'L', b'V', 1, 0b1, 0, 0, 0, 9, 0, 0, 0, 0];
+        data.extend(tag(9, 0, &[0x13, 1, 2]));
+        data.extend(tag(9, 40, &[0x23, 3]));
+
+        // The second tag is cut off, so the reader stays before it.
+        let mut reader = FlvReader::new(&data[..40]).unwrap();
+        assert!(matches!(reader.read_tag(), Ok(Some(_))));
+        assert_eq!(reader.read_tag(), Err(Error::UnexpectedEof));
+        assert_eq!(reader.position(), 31);
+
+        let mut reader = FlvReader::resume(&data, reader.position());
+        assert_eq!(
+            reader.read_tag(),
+            Ok(Some(FlvTag {
+                timestamp: 40,
+                data: FlvTagData::Video {
+                    codec: Some(VideoCodec::ScreenVideo),
+                    is_keyframe: false,
+                    data: &[3],
+                },
+            }))
+        );
+        assert_eq!(reader.read_tag(), Ok(None));
+    }
+}
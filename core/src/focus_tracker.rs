@@ -0,0 +1,279 @@
+//! Keyboard focus, and moving it between objects with the Tab key.
+//!
+//! Buttons, input text fields, and clips acting as buttons can take focus.
+//! Focus moved with the keyboard or `Selection.setFocus` is outlined by the
+//! yellow focus rectangle when `_focusrect` is enabled, and a focused button
+//! can be clicked with Enter or Space.
+
+use crate::avm1::activation::Activation;
+use crate::avm1::listeners::SystemListener;
+use crate::avm1::{TObject, Value};
+use crate::context::{ActionType, UpdateContext};
+use crate::display_object::{DisplayObject, EditText, TDisplayObject};
+use crate::events::ClipEvent;
+use gc_arena::Collect;
+
+#[derive(Clone, Copy, Collect, Debug, Default)]
+#[collect(no_drop)]
+pub struct FocusTracker<'gc> {
+    /// The object with keyboard focus.
+    focus: Option<DisplayObject<'gc>>,
+
+    /// Whether focus was moved with the keyboard or by a script, rather than by a click.
+    highlight: bool,
+}
+
+impl<'gc> FocusTracker<'gc> {
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// The object with keyboard focus, if any.
+    pub fn get(&self) -> Option<DisplayObject<'gc>> {
+        self.focus
+    }
+
+    /// The text field with keyboard focus, which receives text input.
+    pub fn focused_text_field(&self) -> Option<EditText<'gc>> {
+        self.focus.and_then(|focus| focus.as_edit_text())
+    }
+
+    /// The object to outline with the yellow focus rectangle, if any.
+    /// Text fields show their caret instead.
+    pub fn highlight(&self) -> Option<DisplayObject<'gc>> {
+        self.focus
+            .filter(|focus| self.highlight && focus.as_edit_text().is_none())
+    }
+
+    /// Drops focus from an object being removed from the display list.
+    /// No focus events are fired, because the object is gone.
+    pub fn remove(&mut self, object: DisplayObject<'gc>) {
+        if let Some(focus) = self.focus {
+            if DisplayObject::ptr_eq(focus, object) {
+                self.focus = None;
+            }
+        }
+    }
+}
+
+/// Moves keyboard focus to the given object, or removes focus if `None`.
+///
+/// This queues the `onKillFocus` and `onSetFocus` handlers of the objects losing
+/// and gaining focus, and notifies the `Selection` listeners.
+pub fn set_focus<'gc>(
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    focus: Option<DisplayObject<'gc>>,
+    highlight: bool,
+) {
+    let old_focus = context.focus_tracker.focus;
+    context.focus_tracker.highlight = highlight;
+    let is_same = match (old_focus, focus) {
+        (Some(old_focus), Some(focus)) => DisplayObject::ptr_eq(old_focus, focus),
+        (None, None) => true,
+        _ => false,
+    };
+    if is_same {
+        return;
+    }
+    context.focus_tracker.focus = focus;
+
+    if let Some(text_field) = old_focus.and_then(|o| o.as_edit_text()) {
+        text_field.set_has_focus(context.gc_context, false);
+    }
+    if let Some(text_field) = focus.and_then(|o| o.as_edit_text()) {
+        text_field.set_has_focus(context.gc_context, true);
+    }
+
+    let old_value = old_focus.map_or(Value::Null, |o| o.object());
+    let new_value = focus.map_or(Value::Null, |o| o.object());
+    if let Some(old_focus) = old_focus {
+        queue_focus_event(context, old_focus, "onKillFocus", new_value.clone());
+    }
+    if let Some(focus) = focus {
+        queue_focus_event(context, focus, "onSetFocus", old_value.clone());
+    }
+    if let Some(level0) = context.levels.get(&0).copied() {
+        context.action_queue.queue_actions(
+            level0,
+            ActionType::NotifyListeners {
+                listener: SystemListener::Selection,
+                method: "onSetFocus",
+                args: vec![old_value, new_value],
+            },
+            false,
+        );
+    }
+}
+
+fn queue_focus_event<'gc>(
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    target: DisplayObject<'gc>,
+    name: &'static str,
+    arg: Value<'gc>,
+) {
+    if let Value::Object(object) = target.object() {
+        context.action_queue.queue_actions(
+            target,
+            ActionType::Method {
+                object,
+                name,
+                args: vec![arg],
+            },
+            false,
+        );
+    }
+}
+
+/// Moves focus to the next object in the tab order, or the previous one if `reverse` is set.
+/// Focus wraps around at either end.
+pub fn cycle_focus<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    reverse: bool,
+) {
+    let order = tab_order(activation, context);
+    if order.is_empty() {
+        return;
+    }
+
+    let current = context
+        .focus_tracker
+        .focus
+        .and_then(|focus| order.iter().position(|&o| DisplayObject::ptr_eq(o, focus)));
+    let next = match (current, reverse) {
+        (Some(i), false) => (i + 1) % order.len(),
+        (Some(i), true) => (i + order.len() - 1) % order.len(),
+        (None, false) => 0,
+        (None, true) => order.len() - 1,
+    };
+    set_focus(context, Some(order[next]), true);
+}
+
+/// Clicks the focused object in response to Enter or Space, if it is a button.
+pub fn activate_focus<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+) {
+    let focus = match context.focus_tracker.focus {
+        Some(focus) if focus.as_edit_text().is_none() && !focus.removed() => focus,
+        _ => return,
+    };
+    focus.handle_clip_event(activation.avm, context, ClipEvent::Press);
+    focus.handle_clip_event(activation.avm, context, ClipEvent::Release);
+}
+
+/// Builds the order in which the Tab key moves focus between the objects on stage.
+///
+/// If any object has a `tabIndex`, only objects with one take part, in increasing order.
+/// Otherwise, all focusable objects take part, from top to bottom and left to right.
+pub fn tab_order<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+) -> Vec<DisplayObject<'gc>> {
+    let mut candidates = vec![];
+    let levels: Vec<_> = context.levels.values().copied().collect();
+    for level in levels {
+        collect_focusable(activation, context, level, &mut candidates);
+    }
+
+    if candidates.iter().any(|(tab_index, _)| tab_index.is_some()) {
+        let mut indexed: Vec<_> = candidates
+            .into_iter()
+            .filter_map(|(tab_index, object)| tab_index.map(|tab_index| (tab_index, object)))
+            .collect();
+        // The sort is stable, so objects sharing an index stay in display list order.
+        indexed.sort_by_key(|&(tab_index, _)| tab_index);
+        indexed.into_iter().map(|(_, object)| object).collect()
+    } else {
+        let mut objects: Vec<_> = candidates.into_iter().map(|(_, object)| object).collect();
+        objects.sort_by_key(|object| {
+            let bounds = object.world_bounds();
+            (bounds.y_min, bounds.x_min)
+        });
+        objects
+    }
+}
+
+/// Adds the focusable objects in the given subtree to `candidates`, in display list order,
+/// along with their `tabIndex`.
+fn collect_focusable<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    object: DisplayObject<'gc>,
+    candidates: &mut Vec<(Option<i32>, DisplayObject<'gc>)>,
+) {
+    if !object.visible() || object.removed() {
+        return;
+    }
+
+    if is_focusable(activation, context, object) {
+        let tab_index = match property(activation, context, object, "tabIndex") {
+            Value::Undefined | Value::Null => None,
+            value => value.coerce_to_i32(activation, context).ok(),
+        };
+        candidates.push((tab_index, object));
+    }
+
+    // Buttons are focused as a whole. `tabChildren = false` hides a clip's children.
+    if object.as_movie_clip().is_some()
+        && flag(activation, context, object, "tabChildren").unwrap_or(true)
+    {
+        let mut children: Vec<_> = object.children().collect();
+        children.sort_by_key(|child| child.depth());
+        for child in children {
+            collect_focusable(activation, context, child, candidates);
+        }
+    }
+}
+
+/// Whether the given object can take focus from the Tab key.
+fn is_focusable<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    object: DisplayObject<'gc>,
+) -> bool {
+    let tab_enabled = flag(activation, context, object, "tabEnabled");
+    if tab_enabled == Some(false) {
+        return false;
+    }
+    let is_enabled = flag(activation, context, object, "enabled").unwrap_or(true);
+
+    if let Some(text_field) = object.as_edit_text() {
+        text_field.is_editable()
+    } else if object.as_button().is_some() {
+        is_enabled
+    } else if let Some(movie_clip) = object.as_movie_clip() {
+        // Clips only take focus when asked to, or when they act as buttons.
+        tab_enabled == Some(true) || (is_enabled && movie_clip.is_button_mode(activation, context))
+    } else {
+        false
+    }
+}
+
+/// Reads a property of a display object, or `undefined` if it has no AVM1 object.
+fn property<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    object: DisplayObject<'gc>,
+    name: &str,
+) -> Value<'gc> {
+    match object.object() {
+        Value::Object(object) => object
+            .get(name, activation, context)
+            .unwrap_or(Value::Undefined),
+        _ => Value::Undefined,
+    }
+}
+
+/// Reads a boolean property of a display object, or `None` if it is undefined.
+fn flag<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    object: DisplayObject<'gc>,
+    name: &str,
+) -> Option<bool> {
+    match property(activation, context, object, name) {
+        Value::Undefined => None,
+        value => Some(value.as_bool(activation.current_swf_version())),
+    }
+}
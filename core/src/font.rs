@@ -200,6 +200,14 @@ impl<'gc> Font<'gc> {
         Twips::new((self.0.ascent as f32 * scale) as i32)
     }
 
+    /// Get the distance from the baseline to the bottom of the glyph at a
+    /// given height.
+    pub fn get_descent_for_height(self, height: Twips) -> Twips {
+        let scale = height.get() as f32 / self.scale();
+
+        Twips::new((self.0.descent as f32 * scale) as i32)
+    }
+
     /// Returns whether this font contains kerning information.
     pub fn has_kerning_info(self) -> bool {
         !self.0.kerning_pairs.is_empty()
@@ -420,12 +428,39 @@ impl FontDescriptor {
 
 #[cfg(test)]
 mod tests {
+    use crate::backend::font::FontBackend;
     use crate::backend::render::{NullRenderer, RenderBackend};
+    use crate::character::Character;
     use crate::font::{EvalParameters, Font};
+    use crate::library::{Library, MovieLibrary};
     use crate::player::{Player, DEVICE_FONT_TAG};
     use gc_arena::{rootless_arena, MutationContext};
     use swf::Twips;
 
+    /// A font backend that only has "_sans", and counts how often it is asked
+    /// for fonts.
+    struct TestFontBackend {
+        loads: usize,
+    }
+
+    impl FontBackend for TestFontBackend {
+        fn load_device_font(
+            &mut self,
+            name: &str,
+            _is_bold: bool,
+            _is_italic: bool,
+        ) -> Option<swf::Font> {
+            self.loads += 1;
+            if name == "_sans" {
+                swf::read::Reader::new(DEVICE_FONT_TAG, 8)
+                    .read_define_font_2(3)
+                    .ok()
+            } else {
+                None
+            }
+        }
+    }
+
     fn with_device_font<F>(callback: F)
     where
         F: for<'gc> FnOnce(MutationContext<'gc, '_>, Font<'gc>),
@@ -438,6 +473,101 @@ mod tests {
         })
     }
 
+    #[test]
+    fn measure_text_width() {
+        with_device_font(|_mc, df| {
+            let params = EvalParameters::from_parts(
+                Twips::from_pixels(12.0),
+                Twips::from_pixels(0.0),
+                false,
+            );
+
+            assert_eq!(Twips::new(579), df.measure("Hello", params, false).0);
+            assert_eq!(Twips::new(1117), df.measure("abcd efgh", params, false).0);
+            assert_eq!(
+                Twips::new(2282),
+                df.measure("The quick brown fox", params, false).0
+            );
+            assert_eq!(
+                Twips::from_pixels(114.0),
+                df.measure("The quick brown fox", params, true).0
+            );
+        });
+    }
+
+    #[test]
+    fn measure_text_width_letter_spacing() {
+        with_device_font(|_mc, df| {
+            let params = EvalParameters::from_parts(
+                Twips::from_pixels(12.0),
+                Twips::from_pixels(1.0),
+                false,
+            );
+
+            assert_eq!(Twips::new(679), df.measure("Hello", params, false).0);
+        });
+    }
+
+    #[test]
+    fn font_metrics_for_height() {
+        with_device_font(|_mc, df| {
+            let height = Twips::from_pixels(12.0);
+
+            assert_eq!(Twips::new(256), df.get_baseline_for_height(height));
+            assert_eq!(Twips::new(70), df.get_descent_for_height(height));
+            assert_eq!(Twips::new(86), df.get_leading_for_height(height));
+        });
+    }
+
+    #[test]
+    fn get_font_by_name_falls_back_to_other_variant() {
+        with_device_font(|_mc, df| {
+            let mut library = MovieLibrary::new();
+            library.register_character(1, Character::Font(df));
+
+            let bold = library.get_font_by_name("Noto Sans", true, false).unwrap();
+            assert!(!bold.descriptor().bold());
+
+            let bold_italic = library.get_font_by_name("Noto Sans", true, true).unwrap();
+            assert!(!bold_italic.descriptor().italic());
+
+            assert!(library.get_font_by_name("Arial", false, false).is_none());
+        });
+    }
+
+    #[test]
+    fn device_fonts_are_loaded_once() {
+        rootless_arena(|mc| {
+            let mut renderer = NullRenderer::new();
+            let mut backend = TestFontBackend { loads: 0 };
+            let mut library = Library::default();
+
+            for _ in 0..2 {
+                let sans = library.get_or_load_device_font(
+                    mc,
+                    &mut renderer,
+                    &mut backend,
+                    "_sans",
+                    false,
+                    false,
+                );
+                assert!(sans.map(|f| f.has_glyphs()).unwrap_or(false));
+
+                let missing = library.get_or_load_device_font(
+                    mc,
+                    &mut renderer,
+                    &mut backend,
+                    "Missing Font",
+                    false,
+                    false,
+                );
+                assert!(missing.is_none());
+            }
+
+            assert_eq!(2, backend.loads);
+        });
+    }
+
     #[test]
     fn wrap_line_no_breakpoint() {
         with_device_font(|_mc, df| {
@@ -397,13 +397,25 @@ impl<'a, 'gc> LayoutContext<'a, 'gc> {
     ) -> Option<Font<'gc>> {
         let library = context.library.library_for_movie_mut(self.movie.clone());
 
-        // If this text field is set to use device fonts, fallback to using our embedded Noto Sans.
+        // If this text field is set to use device fonts, or the movie doesn't embed the font, ask
+        // the font backend for a matching system font. If it has none, fallback to using our
+        // embedded Noto Sans.
         // Note that the SWF can still contain a DefineFont tag with no glyphs/layout info in this case (see #451).
-        // In an ideal world, device fonts would search for a matching font on the system and render it in some way.
+        let movie = self.movie.clone();
         if let Some(font) = library
             .get_font_by_name(&span.font, span.bold, span.italic)
             .filter(|f| !is_device_font && f.has_glyphs())
-            .or_else(|| library.device_font())
+            .or_else(|| {
+                context.library.get_or_load_device_font(
+                    context.gc_context,
+                    context.renderer,
+                    context.fonts,
+                    &span.font,
+                    span.bold,
+                    span.italic,
+                )
+            })
+            .or_else(|| context.library.library_for_movie_mut(movie).device_font())
         {
             self.font = Some(font);
             return self.font;
@@ -765,6 +777,15 @@ impl<'gc> LayoutBox<'gc> {
         self.bounds
     }
 
+    /// Returns the range of the source text that this box displays, if it
+    /// displays any.
+    pub fn text_range(&self) -> Option<(usize, usize)> {
+        match &self.content {
+            LayoutContent::Text { start, end, .. } => Some((*start, *end)),
+            _ => None,
+        }
+    }
+
     /// Returns a reference to the text this box contains, as well as font
     /// rendering parameters, if the layout box has any.
     pub fn as_renderable_text<'a>(
@@ -0,0 +1,348 @@
+//! CSS style sheets for HTML text
+
+use crate::html::TextFormat;
+use gc_arena::Collect;
+use std::collections::BTreeMap;
+
+/// The declarations of a single CSS rule.
+///
+/// Property names are stored in camelCase, as AVM1 exposes them: the CSS
+/// property `font-size` is stored as `fontSize`. Values are stored as they
+/// were written.
+pub type CssStyle = BTreeMap<String, String>;
+
+/// A set of named CSS styles that can be applied to HTML text.
+///
+/// Flash only understands a small subset of CSS: every selector is either a
+/// tag name (`p`) or a class name (`.heading`), and only properties that have
+/// a `TextFormat` equivalent have any effect. Selector names are not case
+/// sensitive.
+#[derive(Clone, Debug, Default, Collect)]
+#[collect(require_static)]
+pub struct StyleSheet {
+    styles: BTreeMap<String, CssStyle>,
+}
+
+impl StyleSheet {
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Parse a CSS document and add its rules to this style sheet.
+    ///
+    /// Rules for selectors that already have a style are merged into it.
+    /// Returns `false`, leaving the style sheet untouched, if the document
+    /// is malformed.
+    pub fn parse_css(&mut self, css: &str) -> bool {
+        match parse_rules(css) {
+            Some(rules) => {
+                for (selector, style) in rules {
+                    self.styles.entry(selector).or_default().extend(style);
+                }
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Get the style for a selector, if one exists.
+    pub fn style(&self, name: &str) -> Option<&CssStyle> {
+        self.styles.get(&name.to_lowercase())
+    }
+
+    /// Replace the style for a selector, or remove it if `style` is `None`.
+    pub fn set_style(&mut self, name: &str, style: Option<CssStyle>) {
+        let name = name.to_lowercase();
+        match style {
+            Some(style) => {
+                self.styles.insert(name, style);
+            }
+            None => {
+                self.styles.remove(&name);
+            }
+        }
+    }
+
+    /// List all selectors that have a style.
+    pub fn style_names(&self) -> impl Iterator<Item = &str> {
+        self.styles.keys().map(|name| name.as_str())
+    }
+
+    /// Remove every style from this style sheet.
+    pub fn clear(&mut self) {
+        self.styles.clear();
+    }
+
+    /// Apply the styles matching an HTML element on top of the format the
+    /// element inherits.
+    ///
+    /// The element's tag style is applied first, followed by the style of its
+    /// `class` attribute, if any.
+    pub fn apply_to_element(&self, tag: &str, class: Option<&str>, tf: TextFormat) -> TextFormat {
+        let mut tf = tf;
+
+        if let Some(style) = self.style(tag) {
+            tf = text_format_from_css(style).mix_with(tf);
+        }
+
+        if let Some(style) = class.and_then(|class| self.style(&format!(".{}", class))) {
+            tf = text_format_from_css(style).mix_with(tf);
+        }
+
+        tf
+    }
+}
+
+/// Convert a CSS style into the `TextFormat` it describes.
+///
+/// Properties that aren't supported, or that have invalid values, are left
+/// undefined.
+pub fn text_format_from_css(style: &CssStyle) -> TextFormat {
+    let mut tf = TextFormat::default();
+
+    for (name, value) in style {
+        let value = value.trim();
+        match name.as_str() {
+            "color" => tf.color = parse_css_color(value),
+            "fontFamily" => tf.font = parse_css_font_family(value),
+            "fontSize" => tf.size = parse_css_number(value),
+            "fontWeight" => {
+                tf.bold = match value {
+                    "bold" => Some(true),
+                    "normal" => Some(false),
+                    _ => None,
+                }
+            }
+            "fontStyle" => {
+                tf.italic = match value {
+                    "italic" => Some(true),
+                    "normal" => Some(false),
+                    _ => None,
+                }
+            }
+            "textDecoration" => {
+                tf.underline = match value {
+                    "underline" => Some(true),
+                    "none" => Some(false),
+                    _ => None,
+                }
+            }
+            "marginLeft" => tf.left_margin = parse_css_number(value),
+            "marginRight" => tf.right_margin = parse_css_number(value),
+            "textIndent" => tf.indent = parse_css_number(value),
+            "leading" => tf.leading = parse_css_number(value),
+            "letterSpacing" => tf.letter_spacing = parse_css_number(value),
+            "kerning" => {
+                tf.kerning = match value {
+                    "true" => Some(true),
+                    "false" => Some(false),
+                    _ => None,
+                }
+            }
+            "textAlign" => {
+                tf.align = match value {
+                    "left" => Some(swf::TextAlign::Left),
+                    "center" => Some(swf::TextAlign::Center),
+                    "right" => Some(swf::TextAlign::Right),
+                    "justify" => Some(swf::TextAlign::Justify),
+                    _ => None,
+                }
+            }
+            _ => {}
+        }
+    }
+
+    tf
+}
+
+/// Parse a CSS document into a list of selectors and their declarations.
+///
+/// Yields `None` if the document is malformed.
+fn parse_rules(css: &str) -> Option<Vec<(String, CssStyle)>> {
+    let css = strip_comments(css);
+    let mut rules = vec![];
+    let mut remaining = css.as_str();
+
+    while !remaining.trim().is_empty() {
+        let open = remaining.find('{')?;
+        let close = remaining.find('}')?;
+        if close < open {
+            return None;
+        }
+
+        let style = parse_declarations(&remaining[open + 1..close])?;
+        for selector in remaining[..open].split(',') {
+            let selector = selector.trim();
+            if selector.is_empty() {
+                return None;
+            }
+
+            rules.push((selector.to_lowercase(), style.clone()));
+        }
+
+        remaining = &remaining[close + 1..];
+    }
+
+    Some(rules)
+}
+
+/// Parse the declarations inside a CSS rule's braces.
+fn parse_declarations(block: &str) -> Option<CssStyle> {
+    let mut style = CssStyle::new();
+
+    for declaration in block.split(';') {
+        if declaration.trim().is_empty() {
+            continue;
+        }
+
+        let colon = declaration.find(':')?;
+        let name = declaration[..colon].trim();
+        if name.is_empty() {
+            return None;
+        }
+
+        let value = declaration[colon + 1..].trim();
+        style.insert(css_name_to_camel_case(name), value.to_string());
+    }
+
+    Some(style)
+}
+
+/// Remove all `/* ... */` comments from a CSS document.
+fn strip_comments(css: &str) -> String {
+    let mut result = String::with_capacity(css.len());
+    let mut remaining = css;
+
+    while let Some(start) = remaining.find("/*") {
+        result.push_str(&remaining[..start]);
+        remaining = match remaining[start + 2..].find("*/") {
+            Some(end) => &remaining[start + 2 + end + 2..],
+            None => "",
+        };
+    }
+
+    result.push_str(remaining);
+    result
+}
+
+/// Convert a hyphenated CSS property name into camelCase.
+fn css_name_to_camel_case(name: &str) -> String {
+    let mut result = String::with_capacity(name.len());
+    let mut capitalize_next = false;
+
+    for c in name.chars() {
+        if c == '-' {
+            capitalize_next = true;
+        } else if capitalize_next {
+            result.extend(c.to_uppercase());
+            capitalize_next = false;
+        } else {
+            result.extend(c.to_lowercase());
+        }
+    }
+
+    result
+}
+
+/// Parse a `#RRGGBB` color.
+fn parse_css_color(value: &str) -> Option<swf::Color> {
+    if !value.starts_with('#') || value.len() != 7 {
+        return None;
+    }
+
+    let hex = &value[1..];
+
+    let rgb = u32::from_str_radix(hex, 16).ok()?;
+    Some(swf::Color::from_rgb(rgb, 0xFF))
+}
+
+/// Parse the first font of a `font-family` list, translating the generic
+/// CSS families into Flash's device font names.
+fn parse_css_font_family(value: &str) -> Option<String> {
+    let family = value
+        .split(',')
+        .next()?
+        .trim()
+        .trim_matches(|c| c == '"' || c == '\'');
+
+    Some(
+        match family {
+            "" => return None,
+            "sans-serif" => "_sans",
+            "serif" => "_serif",
+            "mono" | "monospace" => "_typewriter",
+            family => family,
+        }
+        .to_string(),
+    )
+}
+
+/// Parse the leading number of a CSS length, ignoring any unit, like
+/// ActionScript's `parseInt`.
+fn parse_css_number(value: &str) -> Option<f64> {
+    let end = value
+        .char_indices()
+        .find(|&(i, c)| !(c.is_ascii_digit() || c == '.' || (i == 0 && (c == '-' || c == '+'))))
+        .map(|(i, _)| i)
+        .unwrap_or_else(|| value.len());
+
+    value[..end].parse::<f64>().ok().map(f64::trunc)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::html::style_sheet::{text_format_from_css, StyleSheet};
+
+    #[test]
+    fn parse_css_rules() {
+        let mut style_sheet = StyleSheet::new();
+        assert!(style_sheet.parse_css(
+            "/* headings */ h1, .Title { font-size: 24px; font-weight: bold }\n\
+             p { color: #FF0000; margin-left: 10 }"
+        ));
+
+        let names: Vec<&str> = style_sheet.style_names().collect();
+        assert_eq!(names, vec![".title", "h1", "p"]);
+
+        let title = style_sheet.style(".TITLE").unwrap();
+        assert_eq!(title.get("fontSize").map(String::as_str), Some("24px"));
+        assert_eq!(title.get("fontWeight").map(String::as_str), Some("bold"));
+
+        let p = style_sheet.style("p").unwrap();
+        assert_eq!(p.get("marginLeft").map(String::as_str), Some("10"));
+    }
+
+    #[test]
+    fn parse_css_malformed() {
+        let mut style_sheet = StyleSheet::new();
+        assert!(style_sheet.parse_css("p { color: #000000; }"));
+        assert!(!style_sheet.parse_css("h1 { font-size: 12px; "));
+        assert!(!style_sheet.parse_css("{ font-size: 12px; }"));
+        assert!(!style_sheet.parse_css("h1 { font-size }"));
+
+        let names: Vec<&str> = style_sheet.style_names().collect();
+        assert_eq!(names, vec!["p"]);
+    }
+
+    #[test]
+    fn css_to_text_format() {
+        let mut style_sheet = StyleSheet::new();
+        assert!(style_sheet.parse_css(
+            ".a { color: #00FF00; font-family: sans-serif, Arial; font-size: 14.5px; \
+             font-style: italic; text-decoration: underline; text-align: center; \
+             text-indent: 5; leading: 2; margin-right: 3 }"
+        ));
+
+        let tf = text_format_from_css(style_sheet.style(".a").unwrap());
+        assert_eq!(tf.color, Some(swf::Color::from_rgb(0x00FF00, 0xFF)));
+        assert_eq!(tf.font.as_deref(), Some("_sans"));
+        assert_eq!(tf.size, Some(14.0));
+        assert_eq!(tf.italic, Some(true));
+        assert_eq!(tf.underline, Some(true));
+        assert_eq!(tf.align, Some(swf::TextAlign::Center));
+        assert_eq!(tf.indent, Some(5.0));
+        assert_eq!(tf.leading, Some(2.0));
+        assert_eq!(tf.right_margin, Some(3.0));
+        assert_eq!(tf.bold, None);
+    }
+}
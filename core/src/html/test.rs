@@ -1,7 +1,10 @@
 //! Tests for HTML module
 
 use crate::html::dimensions::{BoxBounds, Position, Size};
+use crate::html::style_sheet::StyleSheet;
 use crate::html::text_format::{FormatSpans, TextFormat, TextSpan};
+use crate::xml::XMLDocument;
+use gc_arena::rootless_arena;
 use swf::{Rectangle, Twips};
 
 #[test]
@@ -457,6 +460,34 @@ fn formatspans_get_text_format() {
     assert_eq!(tf1.size, all.size);
 }
 
+#[test]
+fn formatspans_set_text_format_partial_range() {
+    let mut tf1 = TextFormat::default();
+    tf1.font = Some("Same!".to_string());
+    tf1.bold = Some(false);
+    tf1.bullet = Some(false);
+
+    let mut fs =
+        FormatSpans::from_str_and_spans("abcdefghi", &[TextSpan::with_length_and_format(9, tf1)]);
+
+    let mut tf2 = TextFormat::default();
+    tf2.bullet = Some(true);
+
+    fs.set_text_format(2, 4, &tf2);
+
+    let inside = fs.get_text_format(2, 4);
+    assert_eq!(Some(true), inside.bullet);
+    assert_eq!(Some(false), inside.bold);
+
+    let before = fs.get_text_format(0, 2);
+    assert_eq!(Some(false), before.bullet);
+
+    let overlapping = fs.get_text_format(0, 4);
+    assert_eq!(None, overlapping.bullet);
+    assert_eq!(Some(false), overlapping.bold);
+    assert_eq!(Some("Same!".to_string()), overlapping.font);
+}
+
 #[test]
 fn formatspans_normalize_no_spans() {
     let mut fs = FormatSpans::from_str_and_spans("abcdefghi", &[]);
@@ -793,3 +824,43 @@ fn formatspans_replace_text_degenerate() {
     assert_eq!((0, 1), fs.get_span_boundaries(0, 5));
     assert_eq!((1, 2), fs.get_span_boundaries(5, 9));
 }
+
+#[test]
+fn formatspans_lower_from_css() {
+    rootless_arena(|mc| {
+        let mut style_sheet = StyleSheet::new();
+        assert!(style_sheet.parse_css(
+            "p { font-size: 20px; color: #0000FF } .title { font-weight: bold; color: #FF0000 }"
+        ));
+
+        let document = XMLDocument::new(mc);
+        document
+            .as_node()
+            .replace_with_str(
+                mc,
+                "<p>Body</p><p class=\"title\">Title</p><b>Bold</b>",
+                false,
+                false,
+            )
+            .unwrap();
+
+        let mut fs = FormatSpans::new();
+        fs.lower_from_css(document, &style_sheet);
+
+        assert_eq!(fs.text(), "Body\nTitle\nBold");
+
+        let body = fs.get_text_format(0, 4);
+        assert_eq!(body.size, Some(20.0));
+        assert_eq!(body.color, Some(swf::Color::from_rgb(0x0000FF, 0xFF)));
+
+        // Class styles override the element's tag style.
+        let title = fs.get_text_format(5, 10);
+        assert_eq!(title.size, Some(20.0));
+        assert_eq!(title.bold, Some(true));
+        assert_eq!(title.color, Some(swf::Color::from_rgb(0xFF0000, 0xFF)));
+
+        let bold = fs.get_text_format(11, 15);
+        assert_eq!(bold.size, Some(12.0));
+        assert_eq!(bold.bold, Some(true));
+    });
+}
@@ -1,8 +1,9 @@
 //! Classes that store formatting options
 use crate::avm1::activation::Activation;
-use crate::avm1::{Object, ScriptObject, TObject, Value};
+use crate::avm1::{AvmString, Object, ScriptObject, TObject, Value};
 use crate::context::UpdateContext;
 use crate::html::iterators::TextSpanIter;
+use crate::html::StyleSheet;
 use crate::tag_utils::SwfMovie;
 use crate::xml::{Step, XMLDocument, XMLName, XMLNode};
 use gc_arena::{Collect, MutationContext};
@@ -360,7 +361,10 @@ impl TextFormat {
 
         object.set(
             "font",
-            self.font.clone().map(|v| v.into()).unwrap_or(Value::Null),
+            self.font
+                .clone()
+                .map(|v| AvmString::new(uc.gc_context, v).into())
+                .unwrap_or(Value::Null),
             activation,
             uc,
         )?;
@@ -463,13 +467,19 @@ impl TextFormat {
         )?;
         object.set(
             "url",
-            self.url.clone().map(|v| v.into()).unwrap_or(Value::Null),
+            self.url
+                .clone()
+                .map(|v| AvmString::new(uc.gc_context, v).into())
+                .unwrap_or(Value::Null),
             activation,
             uc,
         )?;
         object.set(
             "target",
-            self.target.clone().map(|v| v.into()).unwrap_or(Value::Null),
+            self.target
+                .clone()
+                .map(|v| AvmString::new(uc.gc_context, v).into())
+                .unwrap_or(Value::Null),
             activation,
             uc,
         )?;
@@ -815,7 +825,7 @@ impl TextSpan {
             leading: Some(self.leading),
             letter_spacing: Some(self.letter_spacing),
             tab_stops: Some(self.tab_stops.clone()),
-            bullet: Some(self.bold),
+            bullet: Some(self.bullet),
             url: Some(self.url.clone()),
             target: Some(self.target.clone()),
         }
@@ -1182,6 +1192,24 @@ impl FormatSpans {
     /// styling. There's also a `lower_from_css` that respects both
     /// presentational markup and CSS stylesheets.
     pub fn lower_from_html<'gc>(&mut self, tree: XMLDocument<'gc>) {
+        self.lower_from_html_with_styles(tree, None)
+    }
+
+    /// Lower an HTML tree into text-span representation, styled with a CSS
+    /// style sheet.
+    ///
+    /// Each element is formatted with its presentational markup first, after
+    /// which the style sheet's styles for the element's tag and `class`
+    /// attribute are applied on top.
+    pub fn lower_from_css<'gc>(&mut self, tree: XMLDocument<'gc>, style_sheet: &StyleSheet) {
+        self.lower_from_html_with_styles(tree, Some(style_sheet))
+    }
+
+    fn lower_from_html_with_styles<'gc>(
+        &mut self,
+        tree: XMLDocument<'gc>,
+        style_sheet: Option<&StyleSheet>,
+    ) {
         let mut format_stack = vec![self.default_format.clone()];
         let mut last_successful_format = None;
 
@@ -1199,13 +1227,26 @@ impl FormatSpans {
                 Step::Out(node)
                     if node.tag_name().unwrap().node_name() == "sbr"
                         || node.tag_name().unwrap().node_name() == "br" => {}
-                Step::In(node) => format_stack.push(TextFormat::from_presentational_markup(
-                    node,
-                    format_stack
-                        .last()
-                        .cloned()
-                        .unwrap_or_else(Default::default),
-                )),
+                Step::In(node) => {
+                    let mut tf = TextFormat::from_presentational_markup(
+                        node,
+                        format_stack
+                            .last()
+                            .cloned()
+                            .unwrap_or_else(Default::default),
+                    );
+
+                    if let (Some(style_sheet), Some(tag_name)) = (style_sheet, node.tag_name()) {
+                        let class = node.attribute_value(&XMLName::from_str("class"));
+                        tf = style_sheet.apply_to_element(
+                            &tag_name.node_name(),
+                            class.as_deref(),
+                            tf,
+                        );
+                    }
+
+                    format_stack.push(tf);
+                }
                 Step::Around(node) if node.is_text() => {
                     self.replace_text(
                         self.text.len(),
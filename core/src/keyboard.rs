@@ -0,0 +1,222 @@
+//! Keyboard state tracking
+
+use crate::events::{KeyCode, PlayerEvent};
+use std::collections::HashSet;
+
+/// The state of the keyboard, as reported by the `KeyDown` and `KeyUp` events
+/// the frontend sends to the player.
+///
+/// This backs `Key.isDown`, `Key.getCode` and `Key.getAscii`.
+#[derive(Debug)]
+pub struct KeyboardState {
+    /// The keys that are currently held down.
+    keys_down: HashSet<KeyCode>,
+
+    /// The key code of the most recently pressed or released key.
+    last_key_code: KeyCode,
+
+    /// The ASCII value of the most recently pressed key.
+    last_key_ascii: u8,
+}
+
+impl KeyboardState {
+    pub fn new() -> Self {
+        Self {
+            keys_down: HashSet::new(),
+            last_key_code: KeyCode::Unknown,
+            last_key_ascii: 0,
+        }
+    }
+
+    /// Updates the keyboard state from a player event.
+    pub fn handle_event(&mut self, event: &PlayerEvent) {
+        match *event {
+            PlayerEvent::KeyDown { key_code } => {
+                self.keys_down.insert(key_code);
+                self.last_key_code = key_code;
+                self.last_key_ascii = key_code_to_ascii(key_code, self.is_key_down(KeyCode::Shift));
+            }
+            PlayerEvent::KeyUp { key_code } => {
+                self.keys_down.remove(&key_code);
+                self.last_key_code = key_code;
+            }
+            _ => (),
+        }
+    }
+
+    pub fn is_key_down(&self, key_code: KeyCode) -> bool {
+        self.keys_down.contains(&key_code)
+    }
+
+    pub fn last_key_code(&self) -> KeyCode {
+        self.last_key_code
+    }
+
+    pub fn last_key_ascii(&self) -> u8 {
+        self.last_key_ascii
+    }
+}
+
+impl Default for KeyboardState {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Returns the ASCII value of the character typed by a key, or 0 if the key
+/// doesn't type a character.
+///
+/// TODO: This assumes a US keyboard layout.
+pub fn key_code_to_ascii(key_code: KeyCode, shift: bool) -> u8 {
+    let code: u8 = key_code.into();
+    match key_code {
+        KeyCode::A
+        | KeyCode::B
+        | KeyCode::C
+        | KeyCode::D
+        | KeyCode::E
+        | KeyCode::F
+        | KeyCode::G
+        | KeyCode::H
+        | KeyCode::I
+        | KeyCode::J
+        | KeyCode::K
+        | KeyCode::L
+        | KeyCode::M
+        | KeyCode::N
+        | KeyCode::O
+        | KeyCode::P
+        | KeyCode::Q
+        | KeyCode::R
+        | KeyCode::S
+        | KeyCode::T
+        | KeyCode::U
+        | KeyCode::V
+        | KeyCode::W
+        | KeyCode::X
+        | KeyCode::Y
+        | KeyCode::Z => {
+            if shift {
+                code
+            } else {
+                code.to_ascii_lowercase()
+            }
+        }
+        KeyCode::Key0
+        | KeyCode::Key1
+        | KeyCode::Key2
+        | KeyCode::Key3
+        | KeyCode::Key4
+        | KeyCode::Key5
+        | KeyCode::Key6
+        | KeyCode::Key7
+        | KeyCode::Key8
+        | KeyCode::Key9 => {
+            if shift {
+                b")!@#$%^&*("[usize::from(code - b'0')]
+            } else {
+                code
+            }
+        }
+        KeyCode::Numpad0
+        | KeyCode::Numpad1
+        | KeyCode::Numpad2
+        | KeyCode::Numpad3
+        | KeyCode::Numpad4
+        | KeyCode::Numpad5
+        | KeyCode::Numpad6
+        | KeyCode::Numpad7
+        | KeyCode::Numpad8
+        | KeyCode::Numpad9 => code - u8::from(KeyCode::Numpad0) + b'0',
+        KeyCode::Semicolon => shifted(shift, b';', b':'),
+        KeyCode::Equals => shifted(shift, b'=', b'+'),
+        KeyCode::Comma => shifted(shift, b',', b'<'),
+        KeyCode::Minus => shifted(shift, b'-', b'_'),
+        KeyCode::Period => shifted(shift, b'.', b'>'),
+        KeyCode::Slash => shifted(shift, b'/', b'?'),
+        KeyCode::Grave => shifted(shift, b'`', b'~'),
+        KeyCode::LBracket => shifted(shift, b'[', b'{'),
+        KeyCode::Backslash => shifted(shift, b'\\', b'|'),
+        KeyCode::RBracket => shifted(shift, b']', b'}'),
+        KeyCode::Apostrophe => shifted(shift, b'\'', b'"'),
+        KeyCode::Multiply => b'*',
+        KeyCode::Plus => b'+',
+        KeyCode::NumpadMinus => b'-',
+        KeyCode::NumpadPeriod => b'.',
+        KeyCode::NumpadSlash => b'/',
+        KeyCode::Backspace | KeyCode::Tab | KeyCode::Return | KeyCode::Escape | KeyCode::Space => {
+            code
+        }
+        KeyCode::Delete => 127,
+        _ => 0,
+    }
+}
+
+fn shifted(shift: bool, unshifted: u8, shifted: u8) -> u8 {
+    if shift {
+        shifted
+    } else {
+        unshifted
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn key_state() {
+        let mut keyboard = KeyboardState::new();
+        keyboard.handle_event(&PlayerEvent::KeyDown {
+            key_code: KeyCode::Left,
+        });
+        keyboard.handle_event(&PlayerEvent::KeyDown {
+            key_code: KeyCode::Space,
+        });
+        assert!(keyboard.is_key_down(KeyCode::Left));
+        assert!(keyboard.is_key_down(KeyCode::Space));
+        assert_eq!(keyboard.last_key_code(), KeyCode::Space);
+
+        keyboard.handle_event(&PlayerEvent::KeyUp {
+            key_code: KeyCode::Left,
+        });
+        assert!(!keyboard.is_key_down(KeyCode::Left));
+        assert!(keyboard.is_key_down(KeyCode::Space));
+        assert_eq!(keyboard.last_key_code(), KeyCode::Left);
+        assert_eq!(keyboard.last_key_ascii(), b' ');
+    }
+
+    #[test]
+    fn ascii_with_shift() {
+        let mut keyboard = KeyboardState::new();
+        keyboard.handle_event(&PlayerEvent::KeyDown {
+            key_code: KeyCode::A,
+        });
+        assert_eq!(keyboard.last_key_ascii(), b'a');
+        keyboard.handle_event(&PlayerEvent::KeyDown {
+            key_code: KeyCode::Key1,
+        });
+        assert_eq!(keyboard.last_key_ascii(), b'1');
+
+        keyboard.handle_event(&PlayerEvent::KeyDown {
+            key_code: KeyCode::Shift,
+        });
+        assert_eq!(keyboard.last_key_ascii(), 0);
+        keyboard.handle_event(&PlayerEvent::KeyDown {
+            key_code: KeyCode::A,
+        });
+        assert_eq!(keyboard.last_key_ascii(), b'A');
+        keyboard.handle_event(&PlayerEvent::KeyDown {
+            key_code: KeyCode::Key1,
+        });
+        assert_eq!(keyboard.last_key_ascii(), b'!');
+        keyboard.handle_event(&PlayerEvent::KeyDown {
+            key_code: KeyCode::Numpad7,
+        });
+        assert_eq!(keyboard.last_key_ascii(), b'7');
+        keyboard.handle_event(&PlayerEvent::KeyDown {
+            key_code: KeyCode::Slash,
+        });
+        assert_eq!(keyboard.last_key_ascii(), b'?');
+    }
+}
@@ -10,20 +10,28 @@ extern crate smallvec;
 extern crate downcast_rs;
 
 mod avm1;
+mod avm2;
+mod bitmap_data;
 mod bounding_box;
 mod character;
 pub mod color_transform;
 mod context;
+mod context_menu;
 mod drawing;
 pub mod events;
+pub mod external;
+mod flv;
+mod focus_tracker;
 mod font;
 mod html;
+mod keyboard;
 mod library;
 pub mod loader;
 mod player;
 mod prelude;
 mod property_map;
 pub mod shape_utils;
+mod stage;
 pub mod string_utils;
 pub mod tag_utils;
 mod transform;
@@ -31,7 +39,12 @@ mod xml;
 
 pub mod backend;
 
+pub use avm1::globals::system::{
+    Language, Manufacturer, OperatingSystem, PlayerType, SystemCapabilities, SystemProperties,
+};
+pub use context_menu::ContextMenuItem;
 pub use events::PlayerEvent;
-pub use player::Player;
+pub use player::{Player, ScriptTimeoutHandler};
+pub use stage::{StageQuality, StageScaleMode};
 pub use swf;
 pub use swf::Color;
@@ -1,11 +1,14 @@
-use crate::backend::audio::SoundHandle;
+use crate::avm1::Object;
+use crate::backend::audio::{AudioBackend, AudioManager, SoundHandle};
+use crate::backend::font::FontBackend;
+use crate::backend::render::RenderBackend;
 use crate::character::Character;
 use crate::display_object::TDisplayObject;
 use crate::font::{Font, FontDescriptor};
 use crate::prelude::*;
-use crate::tag_utils::SwfMovie;
+use crate::tag_utils::{SwfMovie, TagDiagnostic};
 use gc_arena::{Collect, MutationContext};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::sync::{Arc, Weak};
 use swf::CharacterId;
 use weak_table::PtrWeakKeyHashMap;
@@ -16,9 +19,14 @@ use weak_table::PtrWeakKeyHashMap;
 pub struct MovieLibrary<'gc> {
     characters: HashMap<CharacterId, Character<'gc>>,
     export_characters: HashMap<String, Character<'gc>>,
+    export_names: HashMap<CharacterId, String>,
+    avm1_constructor_registry: HashMap<String, Object<'gc>>,
+    init_actions_run: HashSet<CharacterId>,
+    avm2_tags_run: HashSet<usize>,
     jpeg_tables: Option<Vec<u8>>,
     device_font: Option<Font<'gc>>,
     fonts: HashMap<FontDescriptor, Font<'gc>>,
+    diagnostics: Vec<TagDiagnostic>,
 }
 
 impl<'gc> MovieLibrary<'gc> {
@@ -26,9 +34,14 @@ impl<'gc> MovieLibrary<'gc> {
         MovieLibrary {
             characters: HashMap::new(),
             export_characters: HashMap::new(),
+            export_names: HashMap::new(),
+            avm1_constructor_registry: HashMap::new(),
+            init_actions_run: HashSet::new(),
+            avm2_tags_run: HashSet::new(),
             jpeg_tables: None,
             device_font: None,
             fonts: HashMap::new(),
+            diagnostics: Vec::new(),
         }
     }
 
@@ -53,6 +66,7 @@ impl<'gc> MovieLibrary<'gc> {
             match self.export_characters.entry(export_name.to_string()) {
                 Entry::Vacant(e) => {
                     e.insert(character.clone());
+                    self.export_names.insert(id, export_name.to_string());
                 }
                 Entry::Occupied(_) => {
                     log::warn!(
@@ -74,16 +88,95 @@ impl<'gc> MovieLibrary<'gc> {
         self.characters.contains_key(&id)
     }
 
-    #[allow(dead_code)]
     pub fn get_character_by_id(&self, id: CharacterId) -> Option<&Character<'gc>> {
         self.characters.get(&id)
     }
 
-    #[allow(dead_code)]
     pub fn get_character_by_export_name(&self, name: &str) -> Option<&Character<'gc>> {
         self.export_characters.get(name)
     }
 
+    /// Frees the resources that the backends hold for the characters of this movie: the
+    /// meshes of its shapes and glyphs, the textures of its bitmaps, and its sound data.
+    ///
+    /// Every instance of a character shares these resources, so this must only be called
+    /// once the movie is unloaded and none of its display objects will be rendered.
+    pub fn release_resources(
+        &self,
+        renderer: &mut dyn RenderBackend,
+        audio: &mut dyn AudioBackend,
+        audio_manager: &mut AudioManager<'gc>,
+    ) {
+        for character in self.characters.values() {
+            match character {
+                Character::Graphic(graphic) => renderer.release_shape(graphic.shape_handle()),
+                Character::MorphShape(morph_shape) => {
+                    for shape in morph_shape.shape_handles() {
+                        renderer.release_shape(shape);
+                    }
+                }
+                Character::Font(font) => {
+                    let mut i = 0;
+                    while let Some(glyph) = font.get_glyph(i) {
+                        renderer.release_shape(glyph.shape);
+                        i += 1;
+                    }
+                }
+                Character::Bitmap(bitmap) => renderer.release_bitmap(bitmap.bitmap_handle()),
+                Character::Sound(sound) => {
+                    audio_manager.stop_sounds_with_handle(audio, *sound);
+                    audio.release_sound(*sound);
+                }
+                _ => (),
+            }
+        }
+    }
+
+    /// Registers an AVM1 class constructor (via `Object.registerClass`) to be
+    /// used for instances of the symbol with the given export name.
+    ///
+    /// Passing `None` unregisters the class, restoring the default `MovieClip`
+    /// behavior.
+    pub fn register_avm1_constructor(
+        &mut self,
+        export_name: &str,
+        constructor: Option<Object<'gc>>,
+    ) {
+        if let Some(constructor) = constructor {
+            self.avm1_constructor_registry
+                .insert(export_name.to_string(), constructor);
+        } else {
+            self.avm1_constructor_registry.remove(export_name);
+        }
+    }
+
+    /// Returns the AVM1 class constructor registered for the given export name.
+    pub fn get_avm1_constructor(&self, export_name: &str) -> Option<Object<'gc>> {
+        self.avm1_constructor_registry.get(export_name).copied()
+    }
+
+    /// Returns the export names and AVM1 class constructors registered with
+    /// `Object.registerClass`.
+    pub fn avm1_constructors(&self) -> impl Iterator<Item = (&str, Object<'gc>)> {
+        self.avm1_constructor_registry
+            .iter()
+            .map(|(name, constructor)| (name.as_str(), *constructor))
+    }
+
+    /// Marks the `DoInitAction` tags of the sprite with the given ID as run.
+    ///
+    /// Returns `false` if they had already run, as init actions only run once.
+    pub fn mark_init_actions_run(&mut self, id: CharacterId) -> bool {
+        self.init_actions_run.insert(id)
+    }
+
+    /// Marks the `DoAbc` or `SymbolClass` tag at the given offset of the movie as run.
+    ///
+    /// Returns `false` if it had already run, as these tags only run once.
+    pub fn mark_avm2_tag_run(&mut self, tag_pos: usize) -> bool {
+        self.avm2_tags_run.insert(tag_pos)
+    }
+
     /// Instantiates the library item with the given character ID into a display object.
     /// The object must then be post-instantiated before being used.
     pub fn instantiate_by_id(
@@ -92,7 +185,11 @@ impl<'gc> MovieLibrary<'gc> {
         gc_context: MutationContext<'gc, '_>,
     ) -> Result<DisplayObject<'gc>, Box<dyn std::error::Error>> {
         if let Some(character) = self.characters.get(&id) {
-            self.instantiate_display_object(character, gc_context)
+            let constructor = self
+                .export_names
+                .get(&id)
+                .and_then(|name| self.get_avm1_constructor(name));
+            self.instantiate_display_object(character, constructor, gc_context)
         } else {
             log::error!("Tried to instantiate non-registered character ID {}", id);
             Err("Character id doesn't exist".into())
@@ -107,7 +204,8 @@ impl<'gc> MovieLibrary<'gc> {
         gc_context: MutationContext<'gc, '_>,
     ) -> Result<DisplayObject<'gc>, Box<dyn std::error::Error>> {
         if let Some(character) = self.export_characters.get(export_name) {
-            self.instantiate_display_object(character, gc_context)
+            let constructor = self.get_avm1_constructor(export_name);
+            self.instantiate_display_object(character, constructor, gc_context)
         } else {
             log::error!(
                 "Tried to instantiate non-registered character {}",
@@ -119,9 +217,13 @@ impl<'gc> MovieLibrary<'gc> {
 
     /// Instantiates the given character into a display object.
     /// The object must then be post-instantiated before being used.
+    ///
+    /// Movie clips will be constructed with the given AVM1 class constructor,
+    /// if one was registered for their symbol.
     fn instantiate_display_object(
         &self,
         character: &Character<'gc>,
+        constructor: Option<Object<'gc>>,
         gc_context: MutationContext<'gc, '_>,
     ) -> Result<DisplayObject<'gc>, Box<dyn std::error::Error>> {
         match character {
@@ -129,9 +231,16 @@ impl<'gc> MovieLibrary<'gc> {
             Character::EditText(edit_text) => Ok(edit_text.instantiate(gc_context)),
             Character::Graphic(graphic) => Ok(graphic.instantiate(gc_context)),
             Character::MorphShape(morph_shape) => Ok(morph_shape.instantiate(gc_context)),
-            Character::MovieClip(movie_clip) => Ok(movie_clip.instantiate(gc_context)),
+            Character::MovieClip(movie_clip) => {
+                let instance = movie_clip.instantiate(gc_context);
+                if let Some(movie_clip) = instance.as_movie_clip() {
+                    movie_clip.set_avm1_constructor(gc_context, constructor);
+                }
+                Ok(instance)
+            }
             Character::Button(button) => Ok(button.instantiate(gc_context)),
             Character::Text(text) => Ok(text.instantiate(gc_context)),
+            Character::Video(video) => Ok(video.instantiate(gc_context)),
             _ => Err("Not a DisplayObject".into()),
         }
     }
@@ -145,15 +254,32 @@ impl<'gc> MovieLibrary<'gc> {
     }
 
     /// Find a font by it's name and parameters.
+    ///
+    /// If the exact style variant was not embedded, another variant of the
+    /// same font family is returned instead, preferring variants that drop a
+    /// requested style over ones that add an unrequested one. Callers can
+    /// compare the returned font's descriptor against the requested style to
+    /// decide whether to apply faux bold or italic.
     pub fn get_font_by_name(
         &self,
         name: &str,
         is_bold: bool,
         is_italic: bool,
     ) -> Option<Font<'gc>> {
-        let descriptor = FontDescriptor::from_parts(name, is_bold, is_italic);
+        let variants = [
+            (is_bold, is_italic),
+            (is_bold, false),
+            (false, is_italic),
+            (false, false),
+            (true, is_italic),
+            (is_bold, true),
+            (true, true),
+        ];
 
-        self.fonts.get(&descriptor).copied()
+        variants.iter().find_map(|&(is_bold, is_italic)| {
+            let descriptor = FontDescriptor::from_parts(name, is_bold, is_italic);
+            self.fonts.get(&descriptor).copied()
+        })
     }
 
     pub fn get_sound(&self, id: CharacterId) -> Option<SoundHandle> {
@@ -193,6 +319,17 @@ impl<'gc> MovieLibrary<'gc> {
     pub fn set_device_font(&mut self, font: Option<Font<'gc>>) {
         self.device_font = font;
     }
+
+    /// Records problems found in the tags of this movie.
+    pub fn add_diagnostics(&mut self, diagnostics: impl IntoIterator<Item = TagDiagnostic>) {
+        self.diagnostics.extend(diagnostics);
+    }
+
+    /// Returns the problems found in the tags of this movie, which were
+    /// skipped over.
+    pub fn diagnostics(&self) -> &[TagDiagnostic] {
+        &self.diagnostics
+    }
 }
 
 impl Default for MovieLibrary<'_> {
@@ -205,6 +342,14 @@ impl Default for MovieLibrary<'_> {
 pub struct Library<'gc> {
     /// All the movie libraries.
     movie_libraries: PtrWeakKeyHashMap<Weak<SwfMovie>, MovieLibrary<'gc>>,
+
+    /// The number of clips holding each movie as their root.
+    /// A movie's library is released once the last of them releases it.
+    movie_holders: PtrWeakKeyHashMap<Weak<SwfMovie>, usize>,
+
+    /// Device fonts loaded from the font backend, shared by all movies.
+    /// Fonts the backend doesn't have are cached as `None`.
+    device_fonts: HashMap<FontDescriptor, Option<Font<'gc>>>,
 }
 
 unsafe impl<'gc> gc_arena::Collect for Library<'gc> {
@@ -213,6 +358,9 @@ unsafe impl<'gc> gc_arena::Collect for Library<'gc> {
         for (_, val) in self.movie_libraries.iter() {
             val.trace(cc);
         }
+        for val in self.device_fonts.values() {
+            val.trace(cc);
+        }
     }
 }
 
@@ -221,6 +369,26 @@ impl<'gc> Library<'gc> {
         self.movie_libraries.get(&movie)
     }
 
+    /// Registers a clip holding a movie as its root, such as the clip the movie is loaded into.
+    pub fn hold_movie_library(&mut self, movie: Arc<SwfMovie>) {
+        *self.movie_holders.entry(movie).or_insert(0) += 1;
+    }
+
+    /// Unregisters a clip holding a movie, such as when the movie is unloaded from it.
+    ///
+    /// Once no clip holds the movie, its library is removed and returned so that its
+    /// resources can be freed. The characters of a movie keep the movie alive, so its library
+    /// is never dropped on its own.
+    pub fn release_movie_library(&mut self, movie: Arc<SwfMovie>) -> Option<MovieLibrary<'gc>> {
+        let holders = self.movie_holders.get_mut(&movie)?;
+        *holders -= 1;
+        if *holders > 0 {
+            return None;
+        }
+        self.movie_holders.remove(&movie);
+        self.movie_libraries.remove(&movie)
+    }
+
     pub fn library_for_movie_mut(&mut self, movie: Arc<SwfMovie>) -> &mut MovieLibrary<'gc> {
         if !self.movie_libraries.contains_key(&movie) {
             self.movie_libraries
@@ -229,12 +397,43 @@ impl<'gc> Library<'gc> {
 
         self.movie_libraries.get_mut(&movie).unwrap()
     }
+
+    /// Find a device font by its name and style, loading it from the font
+    /// backend the first time it is requested.
+    ///
+    /// Returns `None` if the backend has no such font; callers should then
+    /// fall back to the built-in device font.
+    pub fn get_or_load_device_font(
+        &mut self,
+        gc_context: MutationContext<'gc, '_>,
+        renderer: &mut dyn RenderBackend,
+        backend: &mut dyn FontBackend,
+        name: &str,
+        is_bold: bool,
+        is_italic: bool,
+    ) -> Option<Font<'gc>> {
+        let descriptor = FontDescriptor::from_parts(name, is_bold, is_italic);
+
+        *self.device_fonts.entry(descriptor).or_insert_with(|| {
+            let tag = backend.load_device_font(name, is_bold, is_italic)?;
+            match Font::from_swf_tag(gc_context, renderer, &tag) {
+                Ok(font) if font.has_glyphs() => Some(font),
+                Ok(_) => None,
+                Err(e) => {
+                    log::error!("Unable to load device font {}: {}", name, e);
+                    None
+                }
+            }
+        })
+    }
 }
 
 impl<'gc> Default for Library<'gc> {
     fn default() -> Self {
         Self {
             movie_libraries: PtrWeakKeyHashMap::new(),
+            movie_holders: PtrWeakKeyHashMap::new(),
+            device_fonts: HashMap::new(),
         }
     }
 }
@@ -1,19 +1,21 @@
 //! Management of async loaders
 
 use crate::avm1::activation::{Activation, ActivationIdentifier};
-use crate::avm1::{Object, TObject, Value};
-use crate::backend::navigator::OwnedFuture;
-use crate::context::{ActionQueue, ActionType};
-use crate::display_object::{DisplayObject, MorphShape, TDisplayObject};
+use crate::avm1::{Avm1, AvmString, Object, SoundObject, TObject, Value};
+use crate::backend::audio::decoders::{id3_tags, mp3_metadata};
+use crate::backend::audio::{SoundHandle, SoundInstanceHandle};
+use crate::backend::navigator::{OwnedFuture, ResponseBody};
+use crate::context::{ActionQueue, ActionType, UpdateContext};
+use crate::display_object::{DisplayObject, TDisplayObject};
+use crate::events::ClipEvent;
 use crate::player::{Player, NEWEST_PLAYER_VERSION};
-use crate::tag_utils::SwfMovie;
+use crate::tag_utils::{SwfMovie, SwfMovieStream};
 use crate::xml::XMLNode;
 use gc_arena::{Collect, CollectionContext};
 use generational_arena::{Arena, Index};
 use std::string::FromUtf8Error;
 use std::sync::{Arc, Mutex, Weak};
 use thiserror::Error;
-use url::form_urlencoded;
 
 pub type Handle = Index;
 
@@ -31,6 +33,15 @@ pub enum Error {
     #[error("Non-XML loader spawned as XML loader")]
     NotXmlLoader,
 
+    #[error("Non-sound loader spawned as sound loader")]
+    NotSoundLoader,
+
+    #[error("Non-LoadVars loader spawned as LoadVars loader")]
+    NotLoadVarsLoader,
+
+    #[error("Non-NetStream loader spawned as NetStream loader")]
+    NotNetStreamLoader,
+
     #[error("Invalid SWF")]
     InvalidSwf(#[from] crate::tag_utils::Error),
 
@@ -97,26 +108,47 @@ impl<'gc> LoadManager<'gc> {
 
     /// Kick off a movie clip load.
     ///
+    /// The loaded data may also be a JPEG, PNG or GIF image, which is shown
+    /// in a movie of its own.
+    ///
     /// Returns the loader's async process, which you will need to spawn.
     pub fn load_movie_into_clip(
         &mut self,
         player: Weak<Mutex<Player>>,
         target_clip: DisplayObject<'gc>,
-        fetch: OwnedFuture<Vec<u8>, Error>,
+        fetch: OwnedFuture<Box<dyn ResponseBody>, Error>,
+        url: String,
         target_broadcaster: Option<Object<'gc>>,
     ) -> OwnedFuture<(), Error> {
+        // A new load replaces any load into the clip that is still in progress.
+        self.cancel_movie_loads(target_clip);
+
         let loader = Loader::Movie {
             self_handle: None,
             target_clip,
             target_broadcaster,
             load_complete: false,
+            loaded_bytes: 0,
+            total_bytes: 0,
         };
         let handle = self.add_loader(loader);
 
         let loader = self.get_loader_mut(handle).unwrap();
         loader.introduce_loader_handle(handle);
 
-        loader.movie_loader(player, fetch)
+        loader.movie_loader(player, fetch, url)
+    }
+
+    /// Cancel the loads of movies into a clip that are still in progress, such
+    /// as when the clip is unloaded.
+    ///
+    /// The futures of cancelled loaders end with `Error::Cancelled` instead of
+    /// replacing the movie in the clip.
+    pub fn cancel_movie_loads(&mut self, clip: DisplayObject<'gc>) {
+        self.0.retain(|_, loader| match loader {
+            Loader::Movie { target_clip, .. } => !DisplayObject::ptr_eq(*target_clip, clip),
+            _ => true,
+        });
     }
 
     /// Indicates that a movie clip has initialized (ran it's first frame).
@@ -141,6 +173,14 @@ impl<'gc> LoadManager<'gc> {
         }
     }
 
+    /// Returns the `(loaded, total)` byte progress of a movie being loaded into
+    /// the given clip, or `None` if no load into that clip is in progress.
+    pub fn movie_clip_progress(&self, clip: DisplayObject<'gc>) -> Option<(usize, usize)> {
+        self.0
+            .iter()
+            .find_map(|(_, loader)| loader.movie_clip_progress(clip))
+    }
+
     /// Kick off a form data load into an AVM1 object.
     ///
     /// Returns the loader's async process, which you will need to spawn.
@@ -184,6 +224,78 @@ impl<'gc> LoadManager<'gc> {
 
         loader.xml_loader(player, fetch)
     }
+
+    /// Kick off an MP3 load into an AVM1 `Sound` object.
+    ///
+    /// Returns the loader's async process, which you will need to spawn.
+    pub fn load_sound_into_object(
+        &mut self,
+        player: Weak<Mutex<Player>>,
+        target_object: SoundObject<'gc>,
+        active_clip: DisplayObject<'gc>,
+        fetch: OwnedFuture<Box<dyn ResponseBody>, Error>,
+        is_streaming: bool,
+    ) -> OwnedFuture<(), Error> {
+        let loader = Loader::Sound {
+            self_handle: None,
+            target_object,
+            active_clip,
+            is_streaming,
+        };
+        let handle = self.add_loader(loader);
+
+        let loader = self.get_loader_mut(handle).unwrap();
+        loader.introduce_loader_handle(handle);
+
+        loader.sound_loader(player, fetch)
+    }
+
+    /// Kick off a form data load into an AVM1 `LoadVars` object.
+    ///
+    /// Returns the loader's async process, which you will need to spawn.
+    pub fn load_form_into_load_vars(
+        &mut self,
+        player: Weak<Mutex<Player>>,
+        target_object: Object<'gc>,
+        active_clip: DisplayObject<'gc>,
+        fetch: OwnedFuture<Vec<u8>, Error>,
+    ) -> OwnedFuture<(), Error> {
+        let loader = Loader::LoadVars {
+            self_handle: None,
+            target_object,
+            active_clip,
+        };
+        let handle = self.add_loader(loader);
+
+        let loader = self.get_loader_mut(handle).unwrap();
+        loader.introduce_loader_handle(handle);
+
+        loader.load_vars_loader(player, fetch)
+    }
+
+    /// Kick off a load of an FLV file into an AVM1 `NetStream` object.
+    ///
+    /// Returns the loader's handle, which the stream keeps to ignore loads that it
+    /// has since abandoned, and the loader's async process, which you will need to spawn.
+    pub fn load_flv_into_net_stream(
+        &mut self,
+        player: Weak<Mutex<Player>>,
+        target_object: Object<'gc>,
+        active_clip: DisplayObject<'gc>,
+        fetch: OwnedFuture<Box<dyn ResponseBody>, Error>,
+    ) -> (Handle, OwnedFuture<(), Error>) {
+        let loader = Loader::NetStream {
+            self_handle: None,
+            target_object,
+            active_clip,
+        };
+        let handle = self.add_loader(loader);
+
+        let loader = self.get_loader_mut(handle).unwrap();
+        loader.introduce_loader_handle(handle);
+
+        (handle, loader.net_stream_loader(player, fetch))
+    }
 }
 
 impl<'gc> Default for LoadManager<'gc> {
@@ -215,6 +327,12 @@ pub enum Loader<'gc> {
         /// or an error has occured (in which case we don't care about the
         /// loader anymore).
         load_complete: bool,
+
+        /// The number of bytes of the movie that have been fetched so far.
+        loaded_bytes: usize,
+
+        /// The total number of bytes in the movie, or 0 if not yet known.
+        total_bytes: usize,
     },
 
     /// Loader that is loading form data into an AVM1 object scope.
@@ -242,6 +360,45 @@ pub enum Loader<'gc> {
         /// The target node whose contents will be replaced with the parsed XML.
         target_node: XMLNode<'gc>,
     },
+
+    /// Loader that is loading an MP3 file into an AVM1 `Sound` object.
+    Sound {
+        /// The handle to refer to this loader instance.
+        self_handle: Option<Handle>,
+
+        /// The `Sound` object to load the sound into.
+        target_object: SoundObject<'gc>,
+
+        /// The active movie clip at the time of load invocation.
+        active_clip: DisplayObject<'gc>,
+
+        /// Whether the sound should start playing as soon as it is loaded.
+        is_streaming: bool,
+    },
+
+    /// Loader that is loading form data into an AVM1 `LoadVars` object.
+    LoadVars {
+        /// The handle to refer to this loader instance.
+        self_handle: Option<Handle>,
+
+        /// The `LoadVars` object to load the form data into.
+        target_object: Object<'gc>,
+
+        /// The active movie clip at the time of load invocation.
+        active_clip: DisplayObject<'gc>,
+    },
+
+    /// Loader that is loading an FLV file into an AVM1 `NetStream` object.
+    NetStream {
+        /// The handle to refer to this loader instance.
+        self_handle: Option<Handle>,
+
+        /// The `NetStream` object to play the file with.
+        target_object: Object<'gc>,
+
+        /// The active movie clip at the time of load invocation.
+        active_clip: DisplayObject<'gc>,
+    },
 }
 
 unsafe impl<'gc> Collect for Loader<'gc> {
@@ -257,6 +414,30 @@ unsafe impl<'gc> Collect for Loader<'gc> {
             }
             Loader::Form { target_object, .. } => target_object.trace(cc),
             Loader::XML { target_node, .. } => target_node.trace(cc),
+            Loader::Sound {
+                target_object,
+                active_clip,
+                ..
+            } => {
+                target_object.trace(cc);
+                active_clip.trace(cc);
+            }
+            Loader::LoadVars {
+                target_object,
+                active_clip,
+                ..
+            } => {
+                target_object.trace(cc);
+                active_clip.trace(cc);
+            }
+            Loader::NetStream {
+                target_object,
+                active_clip,
+                ..
+            } => {
+                target_object.trace(cc);
+                active_clip.trace(cc);
+            }
         }
     }
 }
@@ -271,6 +452,9 @@ impl<'gc> Loader<'gc> {
             Loader::Movie { self_handle, .. } => *self_handle = Some(handle),
             Loader::Form { self_handle, .. } => *self_handle = Some(handle),
             Loader::XML { self_handle, .. } => *self_handle = Some(handle),
+            Loader::Sound { self_handle, .. } => *self_handle = Some(handle),
+            Loader::LoadVars { self_handle, .. } => *self_handle = Some(handle),
+            Loader::NetStream { self_handle, .. } => *self_handle = Some(handle),
         }
     }
 
@@ -281,10 +465,14 @@ impl<'gc> Loader<'gc> {
     ///
     /// If the loader is not a movie then the returned future will yield an
     /// error immediately once spawned.
+    ///
+    /// The variables in the query string of `url` are passed to the loaded
+    /// movie.
     pub fn movie_loader(
         &mut self,
         player: Weak<Mutex<Player>>,
-        fetch: OwnedFuture<Vec<u8>, Error>,
+        fetch: OwnedFuture<Box<dyn ResponseBody>, Error>,
+        url: String,
     ) -> OwnedFuture<(), Error> {
         let handle = match self {
             Loader::Movie { self_handle, .. } => self_handle.expect("Loader not self-introduced"),
@@ -298,21 +486,9 @@ impl<'gc> Loader<'gc> {
         Box::pin(async move {
             player.lock().expect("Could not lock player!!").update(
                 |avm, uc| -> Result<(), Error> {
-                    let (clip, broadcaster) = match uc.load_manager.get_loader(handle) {
-                        Some(Loader::Movie {
-                            target_clip,
-                            target_broadcaster,
-                            ..
-                        }) => (*target_clip, *target_broadcaster),
-                        None => return Err(Error::Cancelled),
-                        _ => unreachable!(),
-                    };
-
-                    clip.as_movie_clip().unwrap().unload(uc);
+                    let (clip, broadcaster) = Loader::movie_loader_target(uc, handle)?;
 
-                    clip.as_movie_clip()
-                        .unwrap()
-                        .replace_with_movie(uc.gc_context, None);
+                    clip.as_movie_clip().unwrap().replace_movie(uc, None);
 
                     if let Some(broadcaster) = broadcaster {
                         avm.run_stack_frame_for_method(
@@ -321,7 +497,7 @@ impl<'gc> Loader<'gc> {
                             NEWEST_PLAYER_VERSION,
                             uc,
                             "broadcastMessage",
-                            &["onLoadStart".into(), Value::Object(broadcaster)],
+                            &["onLoadStart".into(), clip.object()],
                         );
                     }
 
@@ -329,124 +505,192 @@ impl<'gc> Loader<'gc> {
                 },
             )?;
 
-            let data = (fetch.await).and_then(|data| Ok((data.len(), SwfMovie::from_data(&data)?)));
-            if let Ok((length, movie)) = data {
-                let movie = Arc::new(movie);
+            // The movie starts playing as soon as its header has loaded, and
+            // takes in the rest of its data as it arrives.
+            let mut body = match fetch.await {
+                Ok(body) => body,
+                Err(_) => return Loader::movie_loader_error(&player, handle),
+            };
+            let mut stream = SwfMovieStream::new(Some(url), body.total_length());
+            let mut is_started = false;
+            let mut last_progress = (0, 0);
+            loop {
+                match body.next_chunk().await {
+                    Ok(Some(chunk)) => {
+                        if stream.append(&chunk).is_err() {
+                            return Loader::movie_loader_error(&player, handle);
+                        }
+                    }
+                    Ok(None) => break,
+                    Err(_) => return Loader::movie_loader_error(&player, handle),
+                }
 
+                let movie = if is_started { None } else { stream.movie() };
+                is_started |= movie.is_some();
+                last_progress = (stream.bytes_loaded(), stream.bytes_total());
                 player
                     .lock()
                     .expect("Could not lock player!!")
                     .update(|avm, uc| {
-                        let (clip, broadcaster) = match uc.load_manager.get_loader(handle) {
-                            Some(Loader::Movie {
-                                target_clip,
-                                target_broadcaster,
-                                ..
-                            }) => (*target_clip, *target_broadcaster),
-                            None => return Err(Error::Cancelled),
-                            _ => unreachable!(),
-                        };
+                        Loader::movie_loader_progress(avm, uc, handle, movie, last_progress, true)
+                    })?;
+            }
 
-                        if let Some(broadcaster) = broadcaster {
-                            avm.run_stack_frame_for_method(
-                                clip,
-                                broadcaster,
-                                NEWEST_PLAYER_VERSION,
-                                uc,
-                                "broadcastMessage",
-                                &[
-                                    "onLoadProgress".into(),
-                                    Value::Object(broadcaster),
-                                    length.into(),
-                                    length.into(),
-                                ],
-                            );
-                        }
+            //TODO: Inspect the fetch error.
+            //This requires cooperation from the backend to send abstract
+            //error types we can actually inspect.
+            //This also can get errors from decoding an invalid SWF file,
+            //too. We should distinguish those to player code.
+            let length = stream.bytes_loaded();
+            let movie = match stream.finish() {
+                Ok(movie) => movie,
+                Err(_) => return Loader::movie_loader_error(&player, handle),
+            };
+
+            player
+                .lock()
+                .expect("Could not lock player!!")
+                .update(|avm, uc| {
+                    // Images and LZMA compressed movies only start once all of their data
+                    // has loaded.
+                    let movie = if is_started { None } else { Some(movie) };
+                    let progress = (length, length);
+                    Loader::movie_loader_progress(
+                        avm,
+                        uc,
+                        handle,
+                        movie,
+                        progress,
+                        progress != last_progress,
+                    )?;
+
+                    let (clip, broadcaster) = Loader::movie_loader_target(uc, handle)?;
+                    if let Some(broadcaster) = broadcaster {
+                        avm.run_stack_frame_for_method(
+                            clip,
+                            broadcaster,
+                            NEWEST_PLAYER_VERSION,
+                            uc,
+                            "broadcastMessage",
+                            &["onLoadComplete".into(), clip.object()],
+                        );
+                    }
 
-                        let mut mc = clip
-                            .as_movie_clip()
-                            .expect("Attempted to load movie into not movie clip");
-
-                        mc.replace_with_movie(uc.gc_context, Some(movie.clone()));
-                        mc.post_instantiation(avm, uc, clip, None, false);
-
-                        let mut morph_shapes = fnv::FnvHashMap::default();
-                        mc.preload(avm, uc, &mut morph_shapes);
-
-                        // Finalize morph shapes.
-                        for (id, static_data) in morph_shapes {
-                            let morph_shape = MorphShape::new(uc.gc_context, static_data);
-                            uc.library
-                                .library_for_movie_mut(movie.clone())
-                                .register_character(
-                                    id,
-                                    crate::character::Character::MorphShape(morph_shape),
-                                );
-                        }
+                    if let Some(Loader::Movie { load_complete, .. }) =
+                        uc.load_manager.get_loader_mut(handle)
+                    {
+                        *load_complete = true;
+                    };
 
-                        if let Some(broadcaster) = broadcaster {
-                            avm.run_stack_frame_for_method(
-                                clip,
-                                broadcaster,
-                                NEWEST_PLAYER_VERSION,
-                                uc,
-                                "broadcastMessage",
-                                &["onLoadComplete".into(), Value::Object(broadcaster)],
-                            );
-                        }
+                    Ok(())
+                })
+        })
+    }
 
-                        if let Some(Loader::Movie { load_complete, .. }) =
-                            uc.load_manager.get_loader_mut(handle)
-                        {
-                            *load_complete = true;
-                        };
+    /// The clip and the broadcaster of a movie loader, or `Error::Cancelled` if
+    /// the load has been cancelled.
+    fn movie_loader_target<'a>(
+        uc: &mut UpdateContext<'a, 'gc, '_>,
+        handle: Handle,
+    ) -> Result<(DisplayObject<'gc>, Option<Object<'gc>>), Error> {
+        match uc.load_manager.get_loader(handle) {
+            Some(Loader::Movie {
+                target_clip,
+                target_broadcaster,
+                ..
+            }) => Ok((*target_clip, *target_broadcaster)),
+            None => Err(Error::Cancelled),
+            _ => unreachable!(),
+        }
+    }
 
-                        Ok(())
-                    })
-            } else {
-                //TODO: Inspect the fetch error.
-                //This requires cooperation from the backend to send abstract
-                //error types we can actually inspect.
-                //This also can get errors from decoding an invalid SWF file,
-                //too. We should distinguish those to player code.
-                player.lock().expect("Could not lock player!!").update(
-                    |avm, uc| -> Result<(), Error> {
-                        let (clip, broadcaster) = match uc.load_manager.get_loader(handle) {
-                            Some(Loader::Movie {
-                                target_clip,
-                                target_broadcaster,
-                                ..
-                            }) => (*target_clip, *target_broadcaster),
-                            None => return Err(Error::Cancelled),
-                            _ => unreachable!(),
-                        };
+    /// Updates a movie loader after more of its movie has loaded.
+    ///
+    /// `movie` is given once the movie can start playing, which replaces the
+    /// movie in the clip. The clip preloads the data that has loaded so far,
+    /// and `onLoadProgress` is broadcast with the `(loaded, total)` bytes if
+    /// `broadcast` is set.
+    fn movie_loader_progress(
+        avm: &mut Avm1<'gc>,
+        uc: &mut UpdateContext<'_, 'gc, '_>,
+        handle: Handle,
+        movie: Option<SwfMovie>,
+        (length, total_length): (usize, usize),
+        broadcast: bool,
+    ) -> Result<(), Error> {
+        let (clip, broadcaster) = Loader::movie_loader_target(uc, handle)?;
+        let mut mc = clip
+            .as_movie_clip()
+            .expect("Attempted to load movie into not movie clip");
+
+        if let Some(movie) = movie {
+            mc.replace_with_movie(uc.gc_context, Some(Arc::new(movie)));
+            mc.hold_movie(uc);
+            mc.post_instantiation(avm, uc, clip, None, false);
+            mc.set_movie_parameters(uc);
+        }
+        mc.preload(avm, uc);
+
+        if let Some(Loader::Movie {
+            loaded_bytes,
+            total_bytes,
+            ..
+        }) = uc.load_manager.get_loader_mut(handle)
+        {
+            *loaded_bytes = length;
+            *total_bytes = total_length;
+        };
 
-                        if let Some(broadcaster) = broadcaster {
-                            avm.run_stack_frame_for_method(
-                                clip,
-                                broadcaster,
-                                NEWEST_PLAYER_VERSION,
-                                uc,
-                                "broadcastMessage",
-                                &[
-                                    "onLoadError".into(),
-                                    Value::Object(broadcaster),
-                                    "LoadNeverCompleted".into(),
-                                ],
-                            );
-                        }
+        if let Some(broadcaster) = broadcaster.filter(|_| broadcast) {
+            avm.run_stack_frame_for_method(
+                clip,
+                broadcaster,
+                NEWEST_PLAYER_VERSION,
+                uc,
+                "broadcastMessage",
+                &[
+                    "onLoadProgress".into(),
+                    clip.object(),
+                    length.into(),
+                    total_length.into(),
+                ],
+            );
+        }
 
-                        if let Some(Loader::Movie { load_complete, .. }) =
-                            uc.load_manager.get_loader_mut(handle)
-                        {
-                            *load_complete = true;
-                        };
+        Ok(())
+    }
 
-                        Ok(())
-                    },
-                )
-            }
-        })
+    /// Ends a movie loader that failed to load its movie with `onLoadError`.
+    fn movie_loader_error(player: &Mutex<Player>, handle: Handle) -> Result<(), Error> {
+        player
+            .lock()
+            .expect("Could not lock player!!")
+            .update(|avm, uc| -> Result<(), Error> {
+                let (clip, broadcaster) = Loader::movie_loader_target(uc, handle)?;
+
+                if let Some(broadcaster) = broadcaster {
+                    avm.run_stack_frame_for_method(
+                        clip,
+                        broadcaster,
+                        NEWEST_PLAYER_VERSION,
+                        uc,
+                        "broadcastMessage",
+                        &[
+                            "onLoadError".into(),
+                            clip.object(),
+                            "LoadNeverCompleted".into(),
+                        ],
+                    );
+                }
+
+                if let Some(Loader::Movie { load_complete, .. }) =
+                    uc.load_manager.get_loader_mut(handle)
+                {
+                    *load_complete = true;
+                };
+
+                Ok(())
+            })
     }
 
     pub fn form_loader(
@@ -482,8 +726,20 @@ impl<'gc> Loader<'gc> {
                     uc.gc_context,
                     *uc.levels.get(&0).unwrap(),
                 );
-                for (k, v) in form_urlencoded::parse(&data) {
-                    that.set(&k, v.into_owned().into(), &mut activation, uc)?;
+                let swf_version = uc.swf.version();
+                let data = uc.system.decode_string(&data, swf_version);
+                for (k, v) in uc.system.decode_form_data(&data, swf_version) {
+                    that.set(
+                        AvmString::new(uc.gc_context, k),
+                        AvmString::new(uc.gc_context, v).into(),
+                        &mut activation,
+                        uc,
+                    )?;
+                }
+
+                // Movie clips are notified via `onClipEvent(data)` and `onData`.
+                if let Some(clip) = that.as_display_object().and_then(|o| o.as_movie_clip()) {
+                    clip.run_clip_event(uc, ClipEvent::Data);
                 }
 
                 Ok(())
@@ -534,6 +790,21 @@ impl<'gc> Loader<'gc> {
         }
     }
 
+    /// Returns the `(loaded, total)` byte progress of this loader if it is
+    /// still loading a movie into the given clip.
+    pub fn movie_clip_progress(&self, clip: DisplayObject<'gc>) -> Option<(usize, usize)> {
+        match self {
+            Loader::Movie {
+                target_clip,
+                load_complete: false,
+                loaded_bytes,
+                total_bytes,
+                ..
+            } if DisplayObject::ptr_eq(*target_clip, clip) => Some((*loaded_bytes, *total_bytes)),
+            _ => None,
+        }
+    }
+
     pub fn xml_loader(
         &mut self,
         player: Weak<Mutex<Player>>,
@@ -582,7 +853,7 @@ impl<'gc> Loader<'gc> {
                             NEWEST_PLAYER_VERSION,
                             uc,
                             "onData",
-                            &[xmlstring.into()],
+                            &[AvmString::new(uc.gc_context, xmlstring).into()],
                         );
 
                         Ok(())
@@ -629,4 +900,394 @@ impl<'gc> Loader<'gc> {
             Ok(())
         })
     }
+    pub fn load_vars_loader(
+        &mut self,
+        player: Weak<Mutex<Player>>,
+        fetch: OwnedFuture<Vec<u8>, Error>,
+    ) -> OwnedFuture<(), Error> {
+        let handle = match self {
+            Loader::LoadVars { self_handle, .. } => {
+                self_handle.expect("Loader not self-introduced")
+            }
+            _ => return Box::pin(async { Err(Error::NotLoadVarsLoader) }),
+        };
+
+        let player = player
+            .upgrade()
+            .expect("Could not upgrade weak reference to player");
+
+        Box::pin(async move {
+            let data = fetch.await;
+
+            player
+                .lock()
+                .expect("Could not lock player!!")
+                .update(|avm, uc| -> Result<(), Error> {
+                    let (that, active_clip) = match uc.load_manager.get_loader(handle) {
+                        Some(Loader::LoadVars {
+                            target_object,
+                            active_clip,
+                            ..
+                        }) => (*target_object, *active_clip),
+                        None => return Err(Error::Cancelled),
+                        _ => return Err(Error::NotLoadVarsLoader),
+                    };
+
+                    let mut activation = Activation::from_nothing(
+                        avm,
+                        ActivationIdentifier::root("[LoadVars Loader]"),
+                        uc.swf.version(),
+                        avm.global_object_cell(),
+                        uc.gc_context,
+                        active_clip,
+                    );
+
+                    // `onData` receives the raw response, and by default decodes it and
+                    // calls `onLoad`.
+                    match data {
+                        Ok(data) => {
+                            let length = data.len();
+                            that.set("_bytesLoaded", length.into(), &mut activation, uc)?;
+                            that.set("_bytesTotal", length.into(), &mut activation, uc)?;
+                            that.call_method("onHTTPStatus", &[200.into()], &mut activation, uc)?;
+
+                            let data = uc.system.decode_string(&data, uc.swf.version());
+                            let data = AvmString::new(uc.gc_context, data);
+                            that.call_method("onData", &[data.into()], &mut activation, uc)?;
+                        }
+                        Err(_) => {
+                            that.call_method("onHTTPStatus", &[404.into()], &mut activation, uc)?;
+                            that.call_method("onData", &[], &mut activation, uc)?;
+                        }
+                    }
+
+                    Ok(())
+                })
+        })
+    }
+
+    pub fn sound_loader(
+        &mut self,
+        player: Weak<Mutex<Player>>,
+        fetch: OwnedFuture<Box<dyn ResponseBody>, Error>,
+    ) -> OwnedFuture<(), Error> {
+        let handle = match self {
+            Loader::Sound { self_handle, .. } => self_handle.expect("Loader not self-introduced"),
+            _ => return Box::pin(async { Err(Error::NotSoundLoader) }),
+        };
+
+        let player = player
+            .upgrade()
+            .expect("Could not upgrade weak reference to player");
+
+        Box::pin(async move {
+            let mut body = match fetch.await {
+                Ok(body) => body,
+                Err(_) => return Loader::sound_loader_error(&player, handle),
+            };
+            let bytes_total = body.total_length().map(|length| length as u32);
+            let mut data = Vec::new();
+
+            // A streaming sound starts playing from the data that has arrived so far,
+            // and is swapped for the complete sound once the rest has loaded.
+            let mut partial_sound = None;
+            loop {
+                match body.next_chunk().await {
+                    Ok(Some(chunk)) => data.extend_from_slice(&chunk),
+                    Ok(None) => break,
+                    Err(_) => return Loader::sound_loader_error(&player, handle),
+                }
+
+                player.lock().expect("Could not lock player!!").update(
+                    |_avm, uc| -> Result<(), Error> {
+                        let (sound_object, _, is_streaming) =
+                            Loader::sound_loader_target(uc, handle)?;
+                        sound_object.set_load_progress(
+                            uc.gc_context,
+                            Some(data.len() as u32),
+                            bytes_total,
+                        );
+
+                        if is_streaming
+                            && partial_sound.is_none()
+                            && Loader::is_sound_buffered(uc, &data)
+                        {
+                            match uc.audio.register_mp3(&data) {
+                                Ok(sound) => {
+                                    sound_object.set_sound(uc.gc_context, Some(sound));
+                                    sound_object.set_duration(
+                                        uc.gc_context,
+                                        uc.audio.get_sound_duration(sound).unwrap_or(0),
+                                    );
+                                    let instance =
+                                        Loader::start_loaded_sound(uc, sound_object, sound, 0);
+                                    partial_sound = Some((sound, instance));
+                                }
+                                Err(e) => {
+                                    log::warn!("Sound.loadSound: Unable to decode sound: {}", e)
+                                }
+                            }
+                        }
+
+                        Ok(())
+                    },
+                )?;
+            }
+
+            player
+                .lock()
+                .expect("Could not lock player!!")
+                .update(|avm, uc| -> Result<(), Error> {
+                    let (sound_object, active_clip, is_streaming) =
+                        Loader::sound_loader_target(uc, handle)?;
+                    let object = Object::from(sound_object);
+
+                    // If the partial sound of a streaming load is still playing, it stops
+                    // where it has got to, and the complete sound carries on from there.
+                    // TODO: A partial sound that runs out of data before the load completes
+                    // finishes early, and calls `onSoundComplete`.
+                    let start_position = match partial_sound {
+                        Some((partial_sound, instance)) => {
+                            let position =
+                                instance.and_then(|instance| uc.audio.get_sound_position(instance));
+                            if let Some(instance) = instance {
+                                uc.audio_manager.stop_sound(uc.audio, instance);
+                            }
+                            uc.audio.release_sound(partial_sound);
+                            position
+                        }
+                        None => Some(0),
+                    };
+
+                    let sound = match uc.audio.register_mp3(&data) {
+                        Ok(sound) => sound,
+                        Err(e) => {
+                            log::warn!("Sound.loadSound: Unable to decode sound: {}", e);
+                            sound_object.set_sound(uc.gc_context, None);
+                            avm.run_stack_frame_for_method(
+                                active_clip,
+                                object,
+                                NEWEST_PLAYER_VERSION,
+                                uc,
+                                "onLoad",
+                                &[false.into()],
+                            );
+                            return Ok(());
+                        }
+                    };
+
+                    sound_object.set_sound(uc.gc_context, Some(sound));
+                    sound_object.set_duration(
+                        uc.gc_context,
+                        uc.audio.get_sound_duration(sound).unwrap_or(0),
+                    );
+                    sound_object.set_position(uc.gc_context, start_position.unwrap_or(0));
+                    sound_object.set_load_progress(
+                        uc.gc_context,
+                        Some(data.len() as u32),
+                        Some(data.len() as u32),
+                    );
+
+                    let tags = id3_tags(&data);
+                    if !tags.is_empty() {
+                        let id3 = crate::avm1::globals::sound::create_id3_object(
+                            uc.gc_context,
+                            avm.prototypes().object,
+                            &tags,
+                        );
+                        sound_object.set_id3(uc.gc_context, Some(id3));
+                        avm.run_stack_frame_for_method(
+                            active_clip,
+                            object,
+                            NEWEST_PLAYER_VERSION,
+                            uc,
+                            "onID3",
+                            &[],
+                        );
+                    }
+
+                    if let Some(position) = start_position.filter(|_| is_streaming) {
+                        Loader::start_loaded_sound(uc, sound_object, sound, position);
+                    }
+
+                    avm.run_stack_frame_for_method(
+                        active_clip,
+                        object,
+                        NEWEST_PLAYER_VERSION,
+                        uc,
+                        "onLoad",
+                        &[true.into()],
+                    );
+
+                    Ok(())
+                })
+        })
+    }
+
+    /// The `Sound` object, active clip and streaming flag of a sound loader, or
+    /// `Error::Cancelled` if the load has been cancelled.
+    fn sound_loader_target<'a>(
+        uc: &mut UpdateContext<'a, 'gc, '_>,
+        handle: Handle,
+    ) -> Result<(SoundObject<'gc>, DisplayObject<'gc>, bool), Error> {
+        match uc.load_manager.get_loader(handle) {
+            Some(Loader::Sound {
+                target_object,
+                active_clip,
+                is_streaming,
+                ..
+            }) => Ok((*target_object, *active_clip, *is_streaming)),
+            None => Err(Error::Cancelled),
+            _ => Err(Error::NotSoundLoader),
+        }
+    }
+
+    /// Whether enough of a streaming sound has loaded for it to start playing,
+    /// which is `_soundbuftime` seconds of complete MP3 frames.
+    fn is_sound_buffered(uc: &UpdateContext<'_, 'gc, '_>, data: &[u8]) -> bool {
+        let buffer_time = u64::from(uc.audio_manager.stream_buffer_time().max(0) as u32);
+        mp3_metadata(data).map_or(false, |metadata| {
+            u64::from(metadata.num_sample_frames) >= buffer_time * u64::from(metadata.sample_rate)
+        })
+    }
+
+    /// Plays a sound loaded into a `Sound` object, from the given position in milliseconds.
+    fn start_loaded_sound(
+        uc: &mut UpdateContext<'_, 'gc, '_>,
+        sound_object: SoundObject<'gc>,
+        sound: SoundHandle,
+        position: u32,
+    ) -> Option<SoundInstanceHandle> {
+        let sound_instance = uc.audio_manager.start_sound(
+            uc.audio,
+            sound,
+            &swf::SoundInfo {
+                event: swf::SoundEvent::Start,
+                in_sample: if position > 0 {
+                    Some((f64::from(position) * 44.1) as u32)
+                } else {
+                    None
+                },
+                out_sample: None,
+                num_loops: 1,
+                envelope: None,
+            },
+            sound_object.owner(),
+            Some(sound_object),
+        );
+        sound_object.set_sound_instance(uc.gc_context, sound_instance);
+        sound_instance
+    }
+
+    /// Reports a sound load that failed to `onLoad`.
+    fn sound_loader_error(player: &Mutex<Player>, handle: Handle) -> Result<(), Error> {
+        player
+            .lock()
+            .expect("Could not lock player!!")
+            .update(|avm, uc| -> Result<(), Error> {
+                let (sound_object, active_clip, _) = Loader::sound_loader_target(uc, handle)?;
+                sound_object.set_sound(uc.gc_context, None);
+                avm.run_stack_frame_for_method(
+                    active_clip,
+                    sound_object.into(),
+                    NEWEST_PLAYER_VERSION,
+                    uc,
+                    "onLoad",
+                    &[false.into()],
+                );
+                Ok(())
+            })
+    }
+
+    pub fn net_stream_loader(
+        &mut self,
+        player: Weak<Mutex<Player>>,
+        fetch: OwnedFuture<Box<dyn ResponseBody>, Error>,
+    ) -> OwnedFuture<(), Error> {
+        let handle = match self {
+            Loader::NetStream { self_handle, .. } => {
+                self_handle.expect("Loader not self-introduced")
+            }
+            _ => return Box::pin(async { Err(Error::NotNetStreamLoader) }),
+        };
+
+        let player = player
+            .upgrade()
+            .expect("Could not upgrade weak reference to player");
+
+        Box::pin(async move {
+            let mut body = match fetch.await {
+                Ok(body) => body,
+                Err(_) => return Loader::net_stream_data(&player, handle, None, None, true),
+            };
+            let bytes_total = body.total_length();
+
+            // The stream reads each chunk of the file as it arrives, and starts playing
+            // once it has buffered enough of it.
+            let mut data = Vec::new();
+            loop {
+                let is_complete = match body.next_chunk().await {
+                    Ok(Some(chunk)) => {
+                        data.extend_from_slice(&chunk);
+                        false
+                    }
+                    Ok(None) => true,
+                    Err(e) => {
+                        log::warn!("NetStream: Unable to load the rest of the stream: {}", e);
+                        true
+                    }
+                };
+                Loader::net_stream_data(&player, handle, Some(&data), bytes_total, is_complete)?;
+                if is_complete {
+                    return Ok(());
+                }
+            }
+        })
+    }
+
+    /// Passes the data of an FLV file that has loaded so far to the `NetStream` that
+    /// plays it.
+    fn net_stream_data(
+        player: &Mutex<Player>,
+        handle: Handle,
+        data: Option<&[u8]>,
+        bytes_total: Option<usize>,
+        is_complete: bool,
+    ) -> Result<(), Error> {
+        player
+            .lock()
+            .expect("Could not lock player!!")
+            .update(|avm, uc| -> Result<(), Error> {
+                let (that, active_clip) = match uc.load_manager.get_loader(handle) {
+                    Some(Loader::NetStream {
+                        target_object,
+                        active_clip,
+                        ..
+                    }) => (*target_object, *active_clip),
+                    None => return Err(Error::Cancelled),
+                    _ => return Err(Error::NotNetStreamLoader),
+                };
+
+                let mut activation = Activation::from_nothing(
+                    avm,
+                    ActivationIdentifier::root("[NetStream Loader]"),
+                    uc.swf.version(),
+                    avm.global_object_cell(),
+                    uc.gc_context,
+                    active_clip,
+                );
+
+                crate::avm1::globals::net_stream::stream_data(
+                    &mut activation,
+                    uc,
+                    that,
+                    handle,
+                    data,
+                    bytes_total,
+                    is_complete,
+                );
+
+                Ok(())
+            })
+    }
 }
@@ -1,21 +1,38 @@
 use crate::avm1::activation::{Activation, ActivationIdentifier};
 use crate::avm1::debug::VariableDumper;
+use crate::avm1::globals::external_interface::ExternalCallbacks;
+use crate::avm1::globals::local_connection::{self, LocalConnections};
+use crate::avm1::globals::net_stream::{self, NetStreams};
+use crate::avm1::globals::print_job::PrintJobs;
 use crate::avm1::globals::system::SystemProperties;
+use crate::avm1::globals::timer::{self, Timers};
+use crate::avm1::globals::xml_socket::XmlSockets;
 use crate::avm1::listeners::SystemListener;
 use crate::avm1::object::Object;
-use crate::avm1::{Avm1, TObject, Value};
+use crate::avm1::{Avm1, AvmString, TObject, Value};
+use crate::avm2::{self, Avm2};
+use crate::backend::font::FontBackend;
 use crate::backend::input::{InputBackend, MouseCursor};
+use crate::backend::log::{LogBackend, NullLogBackend};
 use crate::backend::storage::StorageBackend;
+use crate::backend::video::VideoBackend;
 use crate::backend::{
-    audio::AudioBackend, navigator::NavigatorBackend, render::Letterbox, render::RenderBackend,
+    audio::{AudioBackend, AudioManager},
+    navigator::NavigatorBackend,
+    render::{Bitmap, Letterbox, RenderBackend},
 };
 use crate::context::{ActionQueue, ActionType, RenderContext, UpdateContext};
-use crate::display_object::{EditText, MorphShape, MovieClip};
+use crate::context_menu::{self, ContextMenuItem, ContextMenuState};
+use crate::display_object::{EditText, MovieClip, TextSelection};
 use crate::events::{ButtonKeyCode, ClipEvent, ClipEventResult, KeyCode, PlayerEvent};
+use crate::external::{ExternalInterfaceProvider, FsCommandHandler, Value as ExternalValue};
+use crate::focus_tracker::{self, FocusTracker};
+use crate::keyboard::KeyboardState;
 use crate::library::Library;
 use crate::loader::LoadManager;
 use crate::prelude::*;
-use crate::tag_utils::SwfMovie;
+use crate::stage::{StageProperties, StageScaleMode};
+use crate::tag_utils::{SwfMovie, TagDiagnostic};
 use crate::transform::TransformStack;
 use enumset::EnumSet;
 use gc_arena::{make_arena, ArenaParameters, Collect, GcCell};
@@ -50,16 +67,47 @@ struct GcRootData<'gc> {
 
     mouse_hovered_object: Option<DisplayObject<'gc>>, // TODO: Remove GcCell wrapped inside GcCell.
 
+    /// The object that the mouse button was pressed over, which captures the mouse until release.
+    mouse_pressed_object: Option<DisplayObject<'gc>>,
+
     /// The object being dragged via a `startDrag` action.
     drag_object: Option<DragObject<'gc>>,
 
+    /// The object with keyboard focus.
+    focus_tracker: FocusTracker<'gc>,
+
     avm: Avm1<'gc>,
+    avm2: Avm2<'gc>,
     action_queue: ActionQueue<'gc>,
 
+    /// The sounds started by display objects and AVM1 `Sound` objects.
+    audio_manager: AudioManager<'gc>,
+
     /// Object which manages asynchronous processes that need to interact with
     /// data in the GC arena.
     load_manager: LoadManager<'gc>,
 
+    /// The socket connections of AVM1 `XMLSocket` objects.
+    xml_sockets: XmlSockets<'gc>,
+
+    /// The connection names and messages of AVM1 `LocalConnection` objects.
+    local_connections: LocalConnections<'gc>,
+
+    /// The timers registered by AVM1 `setInterval` and `setTimeout`.
+    timers: Timers<'gc>,
+
+    /// The functions exposed to the host by AVM1 `ExternalInterface.addCallback`.
+    external_callbacks: ExternalCallbacks<'gc>,
+
+    /// The print job started by AVM1 `PrintJob.start`.
+    print_jobs: PrintJobs<'gc>,
+
+    /// The streams played by AVM1 `NetStream` objects.
+    net_streams: NetStreams<'gc>,
+
+    /// The context menu currently shown by the frontend.
+    current_context_menu: Option<ContextMenuState<'gc>>,
+
     shared_objects: HashMap<String, Object<'gc>>,
 
     /// Text fields with unbound variable bindings.
@@ -77,8 +125,18 @@ impl<'gc> GcRootData<'gc> {
         &mut Library<'gc>,
         &mut ActionQueue<'gc>,
         &mut Avm1<'gc>,
+        &mut Avm2<'gc>,
+        &mut AudioManager<'gc>,
         &mut Option<DragObject<'gc>>,
+        &mut FocusTracker<'gc>,
         &mut LoadManager<'gc>,
+        &mut XmlSockets<'gc>,
+        &mut LocalConnections<'gc>,
+        &mut Timers<'gc>,
+        &mut ExternalCallbacks<'gc>,
+        &mut PrintJobs<'gc>,
+        &mut NetStreams<'gc>,
+        &mut Option<ContextMenuState<'gc>>,
         &mut HashMap<String, Object<'gc>>,
         &mut Vec<EditText<'gc>>,
     ) {
@@ -87,8 +145,18 @@ impl<'gc> GcRootData<'gc> {
             &mut self.library,
             &mut self.action_queue,
             &mut self.avm,
+            &mut self.avm2,
+            &mut self.audio_manager,
             &mut self.drag_object,
+            &mut self.focus_tracker,
             &mut self.load_manager,
+            &mut self.xml_sockets,
+            &mut self.local_connections,
+            &mut self.timers,
+            &mut self.external_callbacks,
+            &mut self.print_jobs,
+            &mut self.net_streams,
+            &mut self.current_context_menu,
             &mut self.shared_objects,
             &mut self.unbound_text_fields,
         )
@@ -103,6 +171,11 @@ type Navigator = Box<dyn NavigatorBackend>;
 type Renderer = Box<dyn RenderBackend>;
 type Input = Box<dyn InputBackend>;
 type Storage = Box<dyn StorageBackend>;
+type Fonts = Box<dyn FontBackend>;
+type Video = Box<dyn VideoBackend>;
+
+/// Called when a script runs for too long and is aborted.
+pub type ScriptTimeoutHandler = dyn FnMut();
 
 pub struct Player {
     /// The version of the player we're emulating.
@@ -122,6 +195,10 @@ pub struct Player {
     is_playing: bool,
     needs_render: bool,
 
+    /// The input events received while paused, handled when the player
+    /// resumes or steps a frame.
+    queued_events: Vec<PlayerEvent>,
+
     audio: Audio,
     renderer: Renderer,
     pub navigator: Navigator,
@@ -131,6 +208,8 @@ pub struct Player {
     inverse_view_matrix: Matrix,
 
     storage: Storage,
+    fonts: Fonts,
+    video: Video,
 
     rng: SmallRng,
 
@@ -141,20 +220,39 @@ pub struct Player {
     frame_accumulator: f64,
     global_time: u64,
 
-    viewport_width: u32,
-    viewport_height: u32,
+    /// The multiplier on the frame clock, set by `set_speed`.
+    speed: f64,
+
     movie_width: u32,
     movie_height: u32,
     letterbox: Letterbox,
 
+    /// The scale mode, alignment and viewport size of the stage.
+    stage: StageProperties,
+
     mouse_pos: (Twips, Twips),
     is_mouse_down: bool,
 
+    /// The keys held down, as reported by the frontend's key events.
+    keyboard: KeyboardState,
+
     /// The current mouse cursor icon.
     mouse_cursor: MouseCursor,
 
     system: SystemProperties,
 
+    /// The host of the movie, called by AVM1 `ExternalInterface`.
+    external_interface: Option<Box<dyn ExternalInterfaceProvider>>,
+
+    /// Handles the `fscommand`s sent by the movie.
+    fs_command_handler: Option<Box<FsCommandHandler>>,
+
+    /// Receives the messages traced by the movie.
+    log: Box<dyn LogBackend>,
+
+    /// Called when a script runs for too long and is aborted.
+    script_timeout_handler: Option<Box<ScriptTimeoutHandler>>,
+
     /// The current instance ID. Used to generate default `instanceN` names.
     instance_counter: i32,
 
@@ -174,6 +272,8 @@ impl Player {
         input: Input,
         movie: SwfMovie,
         storage: Storage,
+        fonts: Fonts,
+        video: Video,
     ) -> Result<Arc<Mutex<Self>>, Error> {
         let movie = Arc::new(movie);
 
@@ -194,6 +294,7 @@ impl Player {
 
             is_playing: false,
             needs_render: true,
+            queued_events: Vec::new(),
 
             background_color: Color {
                 r: 255,
@@ -230,10 +331,21 @@ impl Player {
                         library,
                         levels: BTreeMap::new(),
                         mouse_hovered_object: None,
+                        mouse_pressed_object: None,
                         drag_object: None,
+                        focus_tracker: FocusTracker::new(),
                         avm: Avm1::new(gc_context, NEWEST_PLAYER_VERSION),
+                        avm2: Avm2::new(gc_context),
                         action_queue: ActionQueue::new(),
+                        audio_manager: AudioManager::new(),
                         load_manager: LoadManager::new(),
+                        xml_sockets: XmlSockets::new(),
+                        local_connections: LocalConnections::new(),
+                        timers: Timers::new(),
+                        external_callbacks: ExternalCallbacks::new(),
+                        print_jobs: PrintJobs::new(),
+                        net_streams: NetStreams::new(),
+                        current_context_menu: None,
                         shared_objects: HashMap::new(),
                         unbound_text_fields: Vec::new(),
                     },
@@ -243,15 +355,16 @@ impl Player {
             frame_rate: movie.header().frame_rate.into(),
             frame_accumulator: 0.0,
             global_time: 0,
+            speed: 1.0,
 
             movie_width,
             movie_height,
-            viewport_width: movie_width,
-            viewport_height: movie_height,
             letterbox: Letterbox::None,
+            stage: StageProperties::new((movie_width, movie_height)),
 
             mouse_pos: (Twips::new(0), Twips::new(0)),
             is_mouse_down: false,
+            keyboard: KeyboardState::new(),
             mouse_cursor: MouseCursor::Arrow,
 
             renderer,
@@ -260,8 +373,14 @@ impl Player {
             input,
             self_reference: None,
             system: SystemProperties::default(),
+            external_interface: None,
+            fs_command_handler: None,
+            log: Box::new(NullLogBackend::new()),
+            script_timeout_handler: None,
             instance_counter: 0,
             storage,
+            fonts,
+            video,
         };
 
         player.mutate_with_update_context(|avm, context| {
@@ -270,24 +389,13 @@ impl Player {
             root.set_depth(context.gc_context, 0);
             root.post_instantiation(avm, context, root, None, false);
             root.set_name(context.gc_context, "");
+            root.as_movie_clip().unwrap().hold_movie(context);
             context.levels.insert(0, root);
 
-            let mut activation = Activation::from_nothing(
-                avm,
-                ActivationIdentifier::root("[Version Setter]"),
-                context.swf.version(),
-                avm.global_object_cell(),
-                context.gc_context,
-                *context.levels.get(&0).unwrap(),
-            );
-            let object = root.object().coerce_to_object(&mut activation, context);
-            object.define_value(
-                context.gc_context,
-                "$version",
-                context.system.get_version_string(&mut activation).into(),
-                EnumSet::empty(),
-            );
+            // The movie's parameters are defined before any of its actions run.
+            root.as_movie_clip().unwrap().set_movie_parameters(context);
         });
+        player.set_version_variable();
 
         player.build_matrices();
         player.preload();
@@ -308,10 +416,20 @@ impl Player {
         }
 
         if self.is_playing() {
+            let dt = dt * self.speed;
             self.frame_accumulator += dt;
             self.global_time += dt as u64;
             let frame_time = 1000.0 / self.frame_rate;
 
+            // A stream sound sets the pace of the timeline: frames are held while the timeline
+            // is ahead of the audio, and run without waiting while it is behind. Sounds keep
+            // their normal rate when the speed is changed, so only a normal speed is synced.
+            if (self.speed - 1.0).abs() < f64::EPSILON {
+                if let Some(offset) = self.stream_frame_offset() {
+                    self.frame_accumulator = -offset * frame_time;
+                }
+            }
+
             const MAX_FRAMES_PER_TICK: u32 = 5; // Sanity cap on frame tick.
             let mut frame = 0;
             while frame < MAX_FRAMES_PER_TICK && self.frame_accumulator >= frame_time {
@@ -326,13 +444,29 @@ impl Player {
                 self.frame_accumulator = 0.0;
             }
 
+            self.update_timers(dt);
+            self.update_net_streams(dt);
             self.audio.tick();
         }
     }
 
+    /// How many frames the timeline playing a stream sound is ahead of its audio.
+    /// See `AudioManager::stream_frame_offset`.
+    fn stream_frame_offset(&mut self) -> Option<f64> {
+        let audio = &*self.audio;
+        let frame_rate = self.frame_rate;
+        self.gc_arena.mutate(|_gc_context, gc_root| {
+            gc_root
+                .0
+                .read()
+                .audio_manager
+                .stream_frame_offset(audio, frame_rate)
+        })
+    }
+
     /// Returns the approximate duration of time until the next frame is due to run.
     /// This is only an approximation to be used for sleep durations.
-    pub fn time_til_next_frame(&self) -> std::time::Duration {
+    pub fn time_til_next_frame(&mut self) -> std::time::Duration {
         let frame_time = 1000.0 / self.frame_rate;
         let dt = if self.frame_accumulator <= 0.0 {
             frame_time
@@ -341,9 +475,84 @@ impl Player {
         } else {
             frame_time - self.frame_accumulator
         };
+        // Wake up early for any timers due before the next frame.
+        let dt = self
+            .gc_arena
+            .mutate(|_gc_context, gc_root| gc_root.0.read().timers.time_til_next_timer())
+            .map_or(dt, |timer_dt| dt.min(timer_dt));
+        let dt = dt / self.speed;
         std::time::Duration::from_micros(dt as u64 * 1000)
     }
 
+    /// Runs exactly one frame of the movie, including its scripts, timers and
+    /// rendering, whether or not the player is playing.
+    ///
+    /// Any input received while paused is handled first.
+    pub fn step_frame(&mut self) {
+        if !self.audio.is_loading_complete() {
+            return;
+        }
+
+        self.run_queued_events();
+
+        let frame_time = 1000.0 / self.frame_rate;
+        self.global_time += frame_time as u64;
+        self.run_frame();
+        self.update_timers(frame_time);
+        self.update_net_streams(frame_time);
+        self.render();
+    }
+
+    /// The multiplier on the frame clock, where `1.0` runs the movie at its
+    /// own frame rate.
+    pub fn speed(&self) -> f64 {
+        self.speed
+    }
+
+    /// Sets the multiplier on the frame clock, which also speeds up or slows
+    /// down timers. Sounds play at their normal rate.
+    pub fn set_speed(&mut self, speed: f64) {
+        if speed.is_finite() && speed > 0.0 {
+            self.speed = speed;
+        } else {
+            log::warn!("Ignoring invalid playback speed {}", speed);
+        }
+    }
+
+    /// The current frame of the root movie's timeline, starting at 1.
+    pub fn current_frame(&mut self) -> u16 {
+        self.gc_arena.mutate(|_gc_context, gc_root| {
+            gc_root
+                .0
+                .read()
+                .levels
+                .get(&0)
+                .and_then(|root| root.as_movie_clip())
+                .map_or(0, |root| root.current_frame())
+        })
+    }
+
+    /// The problems found in the tags of the root movie, such as unknown or
+    /// corrupt tags, which were skipped over.
+    pub fn diagnostics(&mut self) -> Vec<TagDiagnostic> {
+        let movie = self.swf.clone();
+        self.gc_arena.mutate(|_gc_context, gc_root| {
+            gc_root
+                .0
+                .read()
+                .library
+                .library_for_movie(movie)
+                .map_or_else(Vec::new, |library| library.diagnostics().to_vec())
+        })
+    }
+
+    /// How long the movie has been playing for, in milliseconds of movie time.
+    ///
+    /// This doesn't advance while the player is paused.
+    pub fn elapsed_time(&self) -> u64 {
+        self.global_time
+    }
+
     pub fn is_playing(&self) -> bool {
         self.is_playing
     }
@@ -352,8 +561,187 @@ impl Player {
         if v {
             // Allow auto-play after user gesture for web backends.
             self.audio.prime_audio();
+            self.audio.play();
+        } else {
+            self.audio.pause();
         }
         self.is_playing = v;
+
+        if v {
+            self.run_queued_events();
+        }
+    }
+
+    /// Replace the properties of the host system that are exposed to content,
+    /// such as the OS, screen size and language.
+    pub fn set_system_properties(&mut self, system: SystemProperties) {
+        self.system = system;
+        self.set_version_variable();
+    }
+
+    /// Set the host of the movie, which is called by `ExternalInterface.call`.
+    ///
+    /// The host is told about any functions the movie has already exposed.
+    pub fn set_external_interface(&mut self, provider: Box<dyn ExternalInterfaceProvider>) {
+        self.external_interface = Some(provider);
+        self.mutate_with_update_context(|_avm, context| {
+            if let Some(provider) = context.external_interface.as_mut() {
+                for name in context.external_callbacks.names() {
+                    provider.on_callback_available(name);
+                }
+            }
+        });
+    }
+
+    /// Set the handler of the `fscommand`s sent by the movie.
+    pub fn set_fs_command_handler(&mut self, handler: Box<FsCommandHandler>) {
+        self.fs_command_handler = Some(handler);
+    }
+
+    /// Set the log backend, which receives the messages traced by the movie.
+    pub fn set_log_backend(&mut self, log: Box<dyn LogBackend>) {
+        self.log = log;
+    }
+
+    /// Set the handler called when a script runs for too long, such as an
+    /// infinite loop, and is aborted.
+    ///
+    /// Only the aborted script stops; later frames and events of the movie
+    /// still run their scripts. The frontend may want to tell the user that
+    /// the movie is running slowly.
+    pub fn set_script_timeout_handler(&mut self, handler: Box<ScriptTimeoutHandler>) {
+        self.script_timeout_handler = Some(handler);
+    }
+
+    /// Set the number of AVM1 actions that the scripts run by a single event
+    /// may execute before they are aborted.
+    ///
+    /// Defaults to `avm1::DEFAULT_MAX_ACTIONS`.
+    pub fn set_max_actions(&mut self, max_actions: u64) {
+        self.mutate_with_update_context(|avm, _context| avm.set_max_actions(max_actions));
+    }
+
+    /// Call a function exposed by the movie with `ExternalInterface.addCallback`.
+    ///
+    /// Returns `undefined` if there is no such function.
+    pub fn call_internal_interface(&mut self, name: &str, args: &[ExternalValue]) -> ExternalValue {
+        self.update(|avm, context| {
+            let callback = match context.external_callbacks.get(name) {
+                Some(callback) => callback,
+                None => return ExternalValue::Undefined,
+            };
+            let root = *context.levels.get(&0).unwrap();
+            let mut activation = Activation::from_nothing(
+                avm,
+                ActivationIdentifier::root("[ExternalInterface]"),
+                context.swf.version(),
+                avm.global_object_cell(),
+                context.gc_context,
+                root,
+            );
+            callback.call(&mut activation, context, args)
+        })
+    }
+
+    /// Builds the context menu for a right-click at the current mouse position,
+    /// and returns its items for the frontend to display.
+    ///
+    /// This calls the `onSelect` handler of the movie's `ContextMenu`, if any.
+    pub fn prepare_context_menu(&mut self) -> Vec<ContextMenuItem> {
+        let mouse_pos = self.mouse_pos;
+        self.mutate_with_update_context(|avm, context| {
+            let root = *context.levels.get(&0).unwrap();
+            let target = context
+                .levels
+                .values()
+                .rev()
+                .find_map(|level| context_menu::object_under_point(*level, mouse_pos))
+                .unwrap_or(root);
+
+            let state = {
+                let mut activation = Activation::from_nothing(
+                    avm,
+                    ActivationIdentifier::root("[Context Menu]"),
+                    context.swf.version(),
+                    avm.global_object_cell(),
+                    context.gc_context,
+                    root,
+                );
+                ContextMenuState::build(&mut activation, context, target)
+            };
+            let items = match state {
+                Ok(state) => {
+                    let items = state.items().to_vec();
+                    *context.current_context_menu = Some(state);
+                    items
+                }
+                Err(e) => {
+                    log::warn!("Error building context menu: {}", e);
+                    *context.current_context_menu = None;
+                    vec![]
+                }
+            };
+
+            Self::run_actions(avm, context);
+            items
+        })
+    }
+
+    /// Runs the item at the given index of the menu returned by `prepare_context_menu`.
+    pub fn run_context_menu_callback(&mut self, index: usize) {
+        self.mutate_with_update_context(|avm, context| {
+            if let Some(state) = context.current_context_menu.take() {
+                let root = *context.levels.get(&0).unwrap();
+                let mut activation = Activation::from_nothing(
+                    avm,
+                    ActivationIdentifier::root("[Context Menu]"),
+                    context.swf.version(),
+                    avm.global_object_cell(),
+                    context.gc_context,
+                    root,
+                );
+                if let Err(e) = state.run(index, &mut activation, context) {
+                    log::warn!("Error in context menu callback: {}", e);
+                }
+            }
+
+            Self::run_actions(avm, context);
+        });
+        self.needs_render = true;
+    }
+
+    /// Forgets the menu returned by `prepare_context_menu`, once the frontend closes it
+    /// without choosing an item.
+    pub fn clear_custom_menu_items(&mut self) {
+        self.gc_arena.mutate(|gc_context, gc_root| {
+            gc_root.0.write(gc_context).current_context_menu = None;
+        });
+    }
+
+    /// Define `$version` on the root movie.
+    fn set_version_variable(&mut self) {
+        self.mutate_with_update_context(|avm, context| {
+            let root = *context.levels.get(&0).unwrap();
+            let mut activation = Activation::from_nothing(
+                avm,
+                ActivationIdentifier::root("[Version Setter]"),
+                context.swf.version(),
+                avm.global_object_cell(),
+                context.gc_context,
+                root,
+            );
+            let object = root.object().coerce_to_object(&mut activation, context);
+            object.define_value(
+                context.gc_context,
+                "$version",
+                AvmString::new(
+                    context.gc_context,
+                    context.system.get_version_string(&mut activation),
+                )
+                .into(),
+                EnumSet::empty(),
+            );
+        });
     }
 
     pub fn needs_render(&self) -> bool {
@@ -369,23 +757,73 @@ impl Player {
     }
 
     pub fn viewport_dimensions(&self) -> (u32, u32) {
-        (self.viewport_width, self.viewport_height)
+        self.stage.viewport_size
+    }
+
+    /// Change how the stage is scaled to fit the viewport.
+    pub fn set_scale_mode(&mut self, scale_mode: StageScaleMode) {
+        self.stage.scale_mode = scale_mode;
+        self.build_matrices();
     }
 
     pub fn set_viewport_dimensions(&mut self, width: u32, height: u32) {
-        self.viewport_width = width;
-        self.viewport_height = height;
+        let resized = self.stage.viewport_size != (width, height);
+        self.stage.viewport_size = (width, height);
         self.build_matrices();
+
+        // Flash only notifies `Stage` listeners of resizes when the stage isn't scaled.
+        if resized && self.stage.scale_mode == StageScaleMode::NoScale {
+            self.update(|_avm, context| {
+                context.action_queue.queue_actions(
+                    *context.levels.get(&0).expect("root level"),
+                    ActionType::NotifyListeners {
+                        listener: SystemListener::Stage,
+                        method: "onResize",
+                        args: vec![],
+                    },
+                    false,
+                );
+            });
+        }
     }
 
     pub fn handle_event(&mut self, event: PlayerEvent) {
+        // Hold input until the movie runs again, so that stepping through a
+        // paused movie still handles it in order.
+        if !self.is_playing {
+            // Only the latest position matters for a run of mouse moves.
+            if let (Some(PlayerEvent::MouseMove { .. }), PlayerEvent::MouseMove { .. }) =
+                (self.queued_events.last(), &event)
+            {
+                self.queued_events.pop();
+            }
+            self.queued_events.push(event);
+            return;
+        }
+
+        self.run_event(event);
+    }
+
+    /// Handles the input events received while paused.
+    fn run_queued_events(&mut self) {
+        for event in std::mem::take(&mut self.queued_events) {
+            self.run_event(event);
+        }
+    }
+
+    fn run_event(&mut self, event: PlayerEvent) {
         let mut needs_render = self.needs_render;
 
+        // Update the keyboard state before any handlers can query it.
+        self.keyboard.handle_event(&event);
+
         if let PlayerEvent::KeyDown {
             key_code: KeyCode::V,
         } = event
         {
-            if self.input.is_key_down(KeyCode::Control) && self.input.is_key_down(KeyCode::Alt) {
+            if self.keyboard.is_key_down(KeyCode::Control)
+                && self.keyboard.is_key_down(KeyCode::Alt)
+            {
                 self.mutate_with_update_context(|avm, context| {
                     let mut dumper = VariableDumper::new("  ");
 
@@ -423,6 +861,56 @@ impl Player {
             }
         }
 
+        // Tab moves keyboard focus, and Enter or Space clicks the focused button.
+        if let PlayerEvent::KeyDown { key_code } = event {
+            if let KeyCode::Tab | KeyCode::Return | KeyCode::Space = key_code {
+                let reverse = self.keyboard.is_key_down(KeyCode::Shift);
+                self.mutate_with_update_context(|avm, context| {
+                    let mut activation = Activation::from_nothing(
+                        avm,
+                        ActivationIdentifier::root("[Focus]"),
+                        context.swf.version(),
+                        avm.global_object_cell(),
+                        context.gc_context,
+                        *context.levels.get(&0).unwrap(),
+                    );
+                    if let KeyCode::Tab = key_code {
+                        focus_tracker::cycle_focus(&mut activation, context, reverse);
+                    } else {
+                        focus_tracker::activate_focus(&mut activation, context);
+                    }
+                });
+                needs_render = true;
+            }
+        }
+
+        // Send typing to the focused text field.
+        if let PlayerEvent::TextInput { .. } | PlayerEvent::KeyDown { .. } = event {
+            let shift = self.keyboard.is_key_down(KeyCode::Shift);
+            self.mutate_with_update_context(|avm, context| {
+                if let Some(text_field) = context.focus_tracker.focused_text_field() {
+                    let mut activation = Activation::from_nothing(
+                        avm,
+                        ActivationIdentifier::root("[Text Input]"),
+                        context.swf.version(),
+                        avm.global_object_cell(),
+                        context.gc_context,
+                        text_field.into(),
+                    );
+                    match event {
+                        PlayerEvent::TextInput { codepoint } => {
+                            text_field.text_input(codepoint, &mut activation, context)
+                        }
+                        PlayerEvent::KeyDown { key_code } => {
+                            text_field.text_control_input(key_code, shift, &mut activation, context)
+                        }
+                        _ => (),
+                    }
+                    needs_render = true;
+                }
+            });
+        }
+
         // Update mouse position from mouse events.
         if let PlayerEvent::MouseMove { x, y }
         | PlayerEvent::MouseDown { x, y }
@@ -430,11 +918,18 @@ impl Player {
         {
             self.mouse_pos =
                 self.inverse_view_matrix * (Twips::from_pixels(x), Twips::from_pixels(y));
+            self.update_drag();
             if self.update_roll_over() {
                 needs_render = true;
             }
         }
 
+        // Dispatch AVM2 mouse and keyboard events.
+        let button_down = self.is_mouse_down;
+        self.mutate_with_update_context(|_avm, context| {
+            avm2::handle_input_event(context, &event, button_down);
+        });
+
         // Propagate button events.
         let button_event = match event {
             // ASCII characters convert directly to keyPress button events.
@@ -472,16 +967,31 @@ impl Player {
         }
 
         // Propagte clip events.
-        let (clip_event, mouse_event_name) = match event {
-            PlayerEvent::KeyDown { .. } => (Some(ClipEvent::KeyDown), Some("onKeyDown")),
-            PlayerEvent::KeyUp { .. } => (Some(ClipEvent::KeyUp), Some("onKeyUp")),
-            PlayerEvent::MouseMove { .. } => (Some(ClipEvent::MouseMove), Some("onMouseMove")),
-            PlayerEvent::MouseUp { .. } => (Some(ClipEvent::MouseUp), Some("onMouseUp")),
-            PlayerEvent::MouseDown { .. } => (Some(ClipEvent::MouseDown), Some("onMouseDown")),
+        let (clip_event, listener) = match event {
+            PlayerEvent::KeyDown { .. } => (
+                Some(ClipEvent::KeyDown),
+                Some((SystemListener::Key, "onKeyDown")),
+            ),
+            PlayerEvent::KeyUp { .. } => (
+                Some(ClipEvent::KeyUp),
+                Some((SystemListener::Key, "onKeyUp")),
+            ),
+            PlayerEvent::MouseMove { .. } => (
+                Some(ClipEvent::MouseMove),
+                Some((SystemListener::Mouse, "onMouseMove")),
+            ),
+            PlayerEvent::MouseUp { .. } => (
+                Some(ClipEvent::MouseUp),
+                Some((SystemListener::Mouse, "onMouseUp")),
+            ),
+            PlayerEvent::MouseDown { .. } => (
+                Some(ClipEvent::MouseDown),
+                Some((SystemListener::Mouse, "onMouseDown")),
+            ),
             _ => (None, None),
         };
 
-        if clip_event.is_some() || mouse_event_name.is_some() {
+        if clip_event.is_some() || listener.is_some() {
             self.mutate_with_update_context(|avm, context| {
                 let levels: Vec<DisplayObject<'_>> = context.levels.values().copied().collect();
 
@@ -491,12 +1001,12 @@ impl Player {
                     }
                 }
 
-                if let Some(mouse_event_name) = mouse_event_name {
+                if let Some((listener, method)) = listener {
                     context.action_queue.queue_actions(
                         *context.levels.get(&0).expect("root level"),
                         ActionType::NotifyListeners {
-                            listener: SystemListener::Mouse,
-                            method: mouse_event_name,
+                            listener,
+                            method,
                             args: vec![],
                         },
                         false,
@@ -505,7 +1015,28 @@ impl Player {
             });
         }
 
+        if let PlayerEvent::MouseWheel { delta } = event {
+            self.mutate_with_update_context(|_avm, context| {
+                // TODO: `scrollTarget` should be the topmost object under the mouse, not only
+                // the hovered button. Text fields under the mouse should also scroll once they
+                // support scrolling.
+                let scroll_target = context
+                    .mouse_hovered_object
+                    .map_or(Value::Undefined, |target| target.object());
+                context.action_queue.queue_actions(
+                    *context.levels.get(&0).expect("root level"),
+                    ActionType::NotifyListeners {
+                        listener: SystemListener::Mouse,
+                        method: "onMouseWheel",
+                        args: vec![delta.lines().into(), scroll_target],
+                    },
+                    false,
+                );
+            });
+        }
+
         let mut is_mouse_down = self.is_mouse_down;
+        let mouse_pos = self.mouse_pos;
         self.mutate_with_update_context(|avm, context| {
             if let Some(node) = context.mouse_hovered_object {
                 if node.removed() {
@@ -517,16 +1048,53 @@ impl Player {
                 PlayerEvent::MouseDown { .. } => {
                     is_mouse_down = true;
                     needs_render = true;
+                    context.mouse_pressed_object = context.mouse_hovered_object;
                     if let Some(node) = context.mouse_hovered_object {
                         node.handle_clip_event(avm, context, ClipEvent::Press);
                     }
+
+                    // Clicking an input text field focuses it and moves the caret;
+                    // clicking anywhere else removes focus.
+                    let clicked_text_field = context
+                        .mouse_hovered_object
+                        .and_then(|node| node.as_edit_text())
+                        .filter(|text_field| text_field.is_editable());
+                    focus_tracker::set_focus(context, clicked_text_field.map(Into::into), false);
+                    if let Some(text_field) = clicked_text_field {
+                        let index = text_field.screen_position_to_index(mouse_pos);
+                        text_field.set_selection(
+                            context.gc_context,
+                            Some(TextSelection::for_position(index)),
+                        );
+                    }
                 }
 
                 PlayerEvent::MouseUp { .. } => {
                     is_mouse_down = false;
                     needs_render = true;
-                    if let Some(node) = context.mouse_hovered_object {
-                        node.handle_clip_event(avm, context, ClipEvent::Release);
+                    let pressed = context.mouse_pressed_object.take();
+                    let hovered = context.mouse_hovered_object;
+                    match (pressed, hovered) {
+                        (Some(pressed), Some(hovered))
+                            if DisplayObject::ptr_eq(pressed, hovered) =>
+                        {
+                            hovered.handle_clip_event(avm, context, ClipEvent::Release);
+                        }
+                        (pressed, hovered) => {
+                            // The mouse was released away from the pressed object.
+                            if let Some(pressed) = pressed.filter(|node| !node.removed()) {
+                                pressed.handle_clip_event(avm, context, ClipEvent::ReleaseOutside);
+                            }
+                            // Menu-tracking objects accept a release that started elsewhere;
+                            // anything else under the mouse only now gets rolled over.
+                            if let Some(hovered) = hovered {
+                                if Self::is_track_as_menu(avm, context, hovered) {
+                                    hovered.handle_clip_event(avm, context, ClipEvent::Release);
+                                } else {
+                                    hovered.handle_clip_event(avm, context, ClipEvent::RollOver);
+                                }
+                            }
+                        }
                     }
                 }
 
@@ -540,28 +1108,18 @@ impl Player {
     }
 
     /// Update dragged object, if any.
+    ///
+    /// This only runs when the mouse moves, so a script moving the dragged object keeps its
+    /// position until the next mouse movement.
     fn update_drag(&mut self) {
         let mouse_pos = self.mouse_pos;
         self.mutate_with_update_context(|_activation, context| {
-            if let Some(drag_object) = &mut context.drag_object {
+            if let Some(drag_object) = &context.drag_object {
                 if drag_object.display_object.removed() {
                     // Be sure to clear the drag if the object was removed.
                     *context.drag_object = None;
                 } else {
-                    let mut drag_point = (
-                        mouse_pos.0 + drag_object.offset.0,
-                        mouse_pos.1 + drag_object.offset.1,
-                    );
-                    if let Some(parent) = drag_object.display_object.parent() {
-                        drag_point = parent.global_to_local(drag_point);
-                    }
-                    drag_point = drag_object.constraint.clamp(drag_point);
-                    drag_object
-                        .display_object
-                        .set_x(context.gc_context, drag_point.0.to_pixels());
-                    drag_object
-                        .display_object
-                        .set_y(context.gc_context, drag_point.1.to_pixels());
+                    drag_object.update_position(context.gc_context, mouse_pos);
                 }
             }
         });
@@ -569,12 +1127,13 @@ impl Player {
 
     /// Checks to see if a recent update has caused the current mouse hover
     /// node to change.
+    ///
+    /// While the mouse button is held, the pressed object captures the mouse: it receives
+    /// `DragOut`/`DragOver` instead of `RollOut`/`RollOver`, and only objects tracking as
+    /// menus are rolled over.
     fn update_roll_over(&mut self) -> bool {
-        // TODO: While the mouse is down, maintain the hovered node.
-        if self.is_mouse_down {
-            return false;
-        }
         let mouse_pos = self.mouse_pos;
+        let is_mouse_down = self.is_mouse_down;
 
         let mut new_cursor = self.mouse_cursor;
         let hover_changed = self.mutate_with_update_context(|avm, context| {
@@ -590,20 +1149,37 @@ impl Player {
             }
 
             let cur_hovered = context.mouse_hovered_object;
+            let pressed = context.mouse_pressed_object.filter(|node| !node.removed());
 
             if cur_hovered.map(|d| d.as_ptr()) != new_hovered.map(|d| d.as_ptr()) {
+                let is_pressed = |node| pressed.map_or(false, |p| DisplayObject::ptr_eq(p, node));
+
                 // RollOut of previous node.
                 if let Some(node) = cur_hovered {
                     if !node.removed() {
-                        node.handle_clip_event(avm, context, ClipEvent::RollOut);
+                        if !is_mouse_down {
+                            node.handle_clip_event(avm, context, ClipEvent::RollOut);
+                        } else if is_pressed(node) {
+                            node.handle_clip_event(avm, context, ClipEvent::DragOut);
+                        } else if Self::is_track_as_menu(avm, context, node) {
+                            node.handle_clip_event(avm, context, ClipEvent::RollOut);
+                        }
                     }
                 }
 
                 // RollOver on new node.
                 new_cursor = MouseCursor::Arrow;
                 if let Some(node) = new_hovered {
-                    new_cursor = MouseCursor::Hand;
-                    node.handle_clip_event(avm, context, ClipEvent::RollOver);
+                    if !is_mouse_down {
+                        new_cursor = Self::mouse_cursor_for(avm, context, node);
+                        node.handle_clip_event(avm, context, ClipEvent::RollOver);
+                    } else if is_pressed(node) {
+                        new_cursor = Self::mouse_cursor_for(avm, context, node);
+                        node.handle_clip_event(avm, context, ClipEvent::DragOver);
+                    } else if Self::is_track_as_menu(avm, context, node) {
+                        new_cursor = Self::mouse_cursor_for(avm, context, node);
+                        node.handle_clip_event(avm, context, ClipEvent::RollOver);
+                    }
                 }
 
                 context.mouse_hovered_object = new_hovered;
@@ -624,30 +1200,77 @@ impl Player {
         hover_changed
     }
 
+    /// Reads a boolean property such as `useHandCursor` from a display object's AVM1 object.
+    /// Returns `None` if the property is undefined.
+    fn display_object_flag<'gc>(
+        avm: &mut Avm1<'gc>,
+        context: &mut UpdateContext<'_, 'gc, '_>,
+        node: DisplayObject<'gc>,
+        name: &str,
+    ) -> Option<bool> {
+        let object = match node.object() {
+            Value::Object(object) => object,
+            _ => return None,
+        };
+        let mut activation = Activation::from_nothing(
+            avm,
+            ActivationIdentifier::root("[Mouse Pick]"),
+            context.swf.version(),
+            avm.global_object_cell(),
+            context.gc_context,
+            node,
+        );
+        match object.get(name, &mut activation, context) {
+            Ok(Value::Undefined) | Err(_) => None,
+            Ok(value) => Some(value.as_bool(activation.current_swf_version())),
+        }
+    }
+
+    /// Whether the given object receives roll over and release events while the mouse
+    /// button is held down over a different object.
+    fn is_track_as_menu<'gc>(
+        avm: &mut Avm1<'gc>,
+        context: &mut UpdateContext<'_, 'gc, '_>,
+        node: DisplayObject<'gc>,
+    ) -> bool {
+        if let Some(button) = node.as_button() {
+            button.is_track_as_menu()
+        } else {
+            Self::display_object_flag(avm, context, node, "trackAsMenu").unwrap_or(false)
+        }
+    }
+
+    /// The mouse cursor to show while hovering over the given object.
+    fn mouse_cursor_for<'gc>(
+        avm: &mut Avm1<'gc>,
+        context: &mut UpdateContext<'_, 'gc, '_>,
+        node: DisplayObject<'gc>,
+    ) -> MouseCursor {
+        if node.as_edit_text().is_some() {
+            MouseCursor::IBeam
+        } else if Self::display_object_flag(avm, context, node, "useHandCursor").unwrap_or(true) {
+            MouseCursor::Hand
+        } else {
+            MouseCursor::Arrow
+        }
+    }
+
     /// Preload the first movie in the player.
     ///
-    /// This should only be called once. Further movie loads should preload the
-    /// specific `MovieClip` referenced.
+    /// A movie that is still loading is preloaded as far as it has loaded, and
+    /// `run_frame` preloads the rest as it comes in. Further movie loads should
+    /// preload the specific `MovieClip` referenced.
     fn preload(&mut self) {
         self.mutate_with_update_context(|activation, context| {
-            let mut morph_shapes = fnv::FnvHashMap::default();
             let root = *context.levels.get(&0).expect("root level");
-            root.as_movie_clip()
-                .unwrap()
-                .preload(activation, context, &mut morph_shapes);
-
-            // Finalize morph shapes.
-            for (id, static_data) in morph_shapes {
-                let morph_shape = MorphShape::new(context.gc_context, static_data);
-                context
-                    .library
-                    .library_for_movie_mut(root.as_movie_clip().unwrap().movie().unwrap())
-                    .register_character(id, crate::character::Character::MorphShape(morph_shape));
-            }
+            root.as_movie_clip().unwrap().preload(activation, context);
         });
     }
 
     pub fn run_frame(&mut self) {
+        let background_color = self.background_color.clone();
+        let (quality, focus_rect) = (self.stage.quality, self.stage.focus_rect);
+
         self.update(|avm, update_context| {
             // TODO: In what order are levels run?
             // NOTE: We have to copy all the layer pointers into a separate list
@@ -655,14 +1278,79 @@ impl Player {
             // want to run frames on
             let levels: Vec<_> = update_context.levels.values().copied().collect();
 
+            // Take in the data of movies that have loaded more since the last frame.
+            for level in &levels {
+                if let Some(clip) = level.as_movie_clip() {
+                    clip.preload(avm, update_context);
+                }
+            }
+
             for mut level in levels {
                 level.run_frame(avm, update_context);
             }
+
+            Avm2::broadcast_event(update_context, "enterFrame");
+
+            Self::update_sounds(update_context);
+            Self::update_sockets(update_context);
+            Self::update_local_connections(avm, update_context);
         });
-        self.needs_render = true;
+
+        let stage_dirty = self.gc_arena.mutate(|_gc_context, gc_root| {
+            let root_data = gc_root.0.read();
+            root_data.levels.values().any(|level| level.is_tree_dirty())
+        });
+
+        // Static frames are not re-rendered: only redraw when a display object changed
+        // or when script altered something else that affects the whole stage.
+        if stage_dirty
+            || self.background_color != background_color
+            || self.stage.quality != quality
+            || self.stage.focus_rect != focus_rect
+        {
+            self.needs_render = true;
+        }
     }
 
     pub fn render(&mut self) {
+        self.renderer.set_quality(self.stage.quality);
+        self.renderer.begin_frame(self.background_color.clone());
+
+        self.render_stage(self.view_matrix, true);
+
+        self.renderer.draw_letterbox(self.letterbox);
+        self.renderer.end_frame();
+        self.needs_render = false;
+    }
+
+    /// Renders the current frame of the stage into an offscreen image of the given size,
+    /// independent of the viewport, and returns its RGBA pixels. The stage is stretched to
+    /// fill the whole image.
+    ///
+    /// Returns `None` if the renderer can't render offscreen images.
+    pub fn capture_frame(&mut self, width: u32, height: u32) -> Option<Bitmap> {
+        self.renderer.set_quality(self.stage.quality);
+        if !self
+            .renderer
+            .begin_capture(width, height, self.background_color.clone())
+        {
+            return None;
+        }
+
+        let view_matrix = Matrix::scale(
+            width as f32 / self.movie_width.max(1) as f32,
+            height as f32 / self.movie_height.max(1) as f32,
+        );
+        self.render_stage(view_matrix, false);
+
+        self.renderer.end_capture()
+    }
+
+    /// Renders every level of the stage through the given view matrix.
+    ///
+    /// Only a render to the display draws the focus rectangle and marks the stage as clean;
+    /// an offscreen capture leaves no trace.
+    fn render_stage(&mut self, view_matrix: Matrix, is_display: bool) {
         let view_bounds = BoundingBox {
             x_min: Twips::new(0),
             y_min: Twips::new(0),
@@ -671,12 +1359,11 @@ impl Player {
             valid: true,
         };
 
-        self.renderer.begin_frame(self.background_color.clone());
-
         let (renderer, transform_stack) = (&mut self.renderer, &mut self.transform_stack);
+        let focus_rect = self.stage.focus_rect && is_display;
 
         transform_stack.push(&crate::transform::Transform {
-            matrix: self.view_matrix,
+            matrix: view_matrix,
             ..Default::default()
         });
         self.gc_arena.mutate(|_gc_context, gc_root| {
@@ -692,12 +1379,51 @@ impl Player {
             for (_depth, level) in root_data.levels.iter() {
                 level.render(&mut render_context);
             }
+
+            if let Some(object) = root_data.focus_tracker.highlight().filter(|_| focus_rect) {
+                Self::draw_focus_rect(&mut render_context, object.world_bounds());
+            }
+
+            if is_display {
+                for (_depth, level) in root_data.levels.iter() {
+                    level.clear_dirty_tree();
+                }
+            }
         });
         transform_stack.pop();
+    }
 
-        self.renderer.draw_letterbox(self.letterbox);
-        self.renderer.end_frame();
-        self.needs_render = false;
+    /// Outlines the given stage-space bounds with the yellow keyboard focus rectangle.
+    fn draw_focus_rect(context: &mut RenderContext<'_, '_>, bounds: BoundingBox) {
+        if !bounds.valid {
+            return;
+        }
+
+        let thickness = Twips::from_pixels(2.0);
+        let color = Color {
+            r: 255,
+            g: 255,
+            b: 0,
+            a: 255,
+        };
+        let (x_min, y_min) = (bounds.x_min - thickness, bounds.y_min - thickness);
+        let (x_max, y_max) = (bounds.x_max + thickness, bounds.y_max + thickness);
+        let edges = [
+            (x_min, x_max, y_min, bounds.y_min),
+            (x_min, x_max, bounds.y_max, y_max),
+            (x_min, bounds.x_min, bounds.y_min, bounds.y_max),
+            (bounds.x_max, x_max, bounds.y_min, bounds.y_max),
+        ];
+        let transform = context.transform_stack.transform().clone();
+        for &(x_min, x_max, y_min, y_max) in edges.iter() {
+            let rect = swf::Rectangle {
+                x_min,
+                x_max,
+                y_min,
+                y_max,
+            };
+            context.renderer.draw_rect(color.clone(), &rect, &transform);
+        }
     }
 
     pub fn audio(&self) -> &Audio {
@@ -729,6 +1455,88 @@ impl Player {
         self.input.deref_mut()
     }
 
+    /// Cleans up sounds that have finished playing, and queues the `onSoundComplete`
+    /// handlers of the `Sound` objects that started them.
+    fn update_sounds<'gc>(context: &mut UpdateContext<'_, 'gc, '_>) {
+        let completed = context
+            .audio_manager
+            .update_sounds(context.audio, context.gc_context);
+        for (sound_object, owner) in completed {
+            let clip = owner.unwrap_or_else(|| *context.levels.get(&0).unwrap());
+            context.action_queue.queue_actions(
+                clip,
+                ActionType::Method {
+                    object: sound_object.into(),
+                    name: "onSoundComplete",
+                    args: vec![],
+                },
+                false,
+            );
+        }
+    }
+
+    /// Queues the `XMLSocket` callbacks for any socket events reported by the navigator.
+    fn update_sockets<'gc>(context: &mut UpdateContext<'_, 'gc, '_>) {
+        let callbacks = context
+            .xml_sockets
+            .update(context.gc_context, context.navigator);
+        for (object, name, args) in callbacks {
+            let level0 = *context.levels.get(&0).unwrap();
+            context.action_queue.queue_actions(
+                level0,
+                ActionType::Method { object, name, args },
+                false,
+            );
+        }
+    }
+
+    /// Advances the AVM1 `setInterval` and `setTimeout` timers by `dt` milliseconds,
+    /// calling any that are due.
+    fn update_timers(&mut self, dt: f64) {
+        let fired = self.update(|avm, context| {
+            let mut activation = Activation::from_nothing(
+                avm,
+                ActivationIdentifier::root("[Timer]"),
+                context.swf.version(),
+                avm.global_object_cell(),
+                context.gc_context,
+                *context.levels.get(&0).unwrap(),
+            );
+            timer::update_timers(&mut activation, context, dt)
+        });
+        if fired {
+            self.needs_render = true;
+        }
+    }
+
+    /// Advances the playing AVM1 `NetStream`s by `dt` milliseconds.
+    fn update_net_streams(&mut self, dt: f64) {
+        let updated = self.update(|_avm, context| net_stream::update_net_streams(context, dt));
+        if updated {
+            self.needs_render = true;
+        }
+    }
+
+    /// Delivers the messages sent between AVM1 `LocalConnection` objects.
+    fn update_local_connections<'gc>(
+        avm: &mut Avm1<'gc>,
+        context: &mut UpdateContext<'_, 'gc, '_>,
+    ) {
+        if !context.local_connections.has_messages() {
+            return;
+        }
+
+        let mut activation = Activation::from_nothing(
+            avm,
+            ActivationIdentifier::root("[LocalConnection]"),
+            context.swf.version(),
+            avm.global_object_cell(),
+            context.gc_context,
+            *context.levels.get(&0).unwrap(),
+        );
+        local_connection::deliver_messages(&mut activation, context);
+    }
+
     fn run_actions<'gc>(avm: &mut Avm1<'gc>, context: &mut UpdateContext<'_, 'gc, '_>) {
         // Note that actions can queue further actions, so a while loop is necessary here.
         while let Some(actions) = context.action_queue.pop_action() {
@@ -737,6 +1545,9 @@ impl Player {
                 continue;
             }
 
+            // Each queued event gets its own budget of actions.
+            avm.reset_action_budget();
+
             match actions.action_type {
                 // DoAction/clip event code
                 ActionType::Normal { bytecode } => {
@@ -832,45 +1643,34 @@ impl Player {
                         &args,
                     );
                 }
+
+                // AVM2 function call, e.g. a frame script
+                ActionType::Callable2 {
+                    callable,
+                    receiver,
+                    args,
+                } => {
+                    if let Err(e) =
+                        Avm2::run_stack_frame_for_callable(callable, receiver, &args, context)
+                    {
+                        log::error!("Unhandled AVM2 exception in event handler: {}", e);
+                    }
+                }
             }
         }
     }
 
+    /// Rebuilds the view matrix and letterbox from the stage's scale mode,
+    /// alignment and viewport size.
     fn build_matrices(&mut self) {
-        // Create  view matrix to scale stage into viewport area.
-        let (movie_width, movie_height) = (self.movie_width as f32, self.movie_height as f32);
-        let (viewport_width, viewport_height) =
-            (self.viewport_width as f32, self.viewport_height as f32);
-        let movie_aspect = movie_width / movie_height;
-        let viewport_aspect = viewport_width / viewport_height;
-        let (scale, margin_width, margin_height) = if viewport_aspect > movie_aspect {
-            let scale = viewport_height / movie_height;
-            (scale, (viewport_width - movie_width * scale) / 2.0, 0.0)
-        } else {
-            let scale = viewport_width / movie_width;
-            (scale, 0.0, (viewport_height - movie_height * scale) / 2.0)
-        };
-        self.view_matrix = Matrix {
-            a: scale,
-            b: 0.0,
-            c: 0.0,
-            d: scale,
-            tx: Twips::from_pixels(margin_width.into()),
-            ty: Twips::from_pixels(margin_height.into()),
-        };
-        self.inverse_view_matrix = self.view_matrix;
-        self.inverse_view_matrix.invert();
-
-        // Calculate letterbox dimensions.
-        // TODO: Letterbox should be an option; the original Flash Player defaults to showing content
-        // in the extra margins.
-        self.letterbox = if margin_width > 0.0 {
-            Letterbox::Pillarbox(margin_width)
-        } else if margin_height > 0.0 {
-            Letterbox::Letterbox(margin_height)
-        } else {
-            Letterbox::None
-        };
+        let (view_matrix, letterbox) = self.stage.layout((self.movie_width, self.movie_height));
+        if view_matrix != self.view_matrix || letterbox != self.letterbox {
+            self.view_matrix = view_matrix;
+            self.inverse_view_matrix = view_matrix;
+            self.inverse_view_matrix.invert();
+            self.letterbox = letterbox;
+            self.needs_render = true;
+        }
     }
 
     /// Runs the closure `f` with an `UpdateContext`.
@@ -892,12 +1692,19 @@ impl Player {
             input,
             rng,
             mouse_position,
+            keyboard,
             stage_width,
             stage_height,
+            stage,
             player,
             system_properties,
             instance_counter,
             storage,
+            fonts,
+            video,
+            external_interface,
+            fs_command_handler,
+            log,
         ) = (
             self.player_version,
             self.global_time,
@@ -909,24 +1716,42 @@ impl Player {
             self.input.deref_mut(),
             &mut self.rng,
             &self.mouse_pos,
+            &self.keyboard,
             Twips::from_pixels(self.movie_width.into()),
             Twips::from_pixels(self.movie_height.into()),
+            &mut self.stage,
             self.self_reference.clone(),
             &mut self.system,
             &mut self.instance_counter,
             self.storage.deref_mut(),
+            self.fonts.deref_mut(),
+            self.video.deref_mut(),
+            &mut self.external_interface,
+            &mut self.fs_command_handler,
+            self.log.deref_mut(),
         );
 
-        self.gc_arena.mutate(|gc_context, gc_root| {
+        let (ret, timed_out) = self.gc_arena.mutate(|gc_context, gc_root| {
             let mut root_data = gc_root.0.write(gc_context);
             let mouse_hovered_object = root_data.mouse_hovered_object;
+            let mouse_pressed_object = root_data.mouse_pressed_object;
             let (
                 levels,
                 library,
                 action_queue,
                 avm,
+                avm2,
+                audio_manager,
                 drag_object,
+                focus_tracker,
                 load_manager,
+                xml_sockets,
+                local_connections,
+                timers,
+                external_callbacks,
+                print_jobs,
+                net_streams,
+                current_context_menu,
                 shared_objects,
                 unbound_text_fields,
             ) = root_data.update_context_params();
@@ -940,31 +1765,67 @@ impl Player {
                 rng,
                 renderer,
                 audio,
+                audio_manager,
                 navigator,
+                xml_sockets,
+                local_connections,
+                timers,
+                external_interface,
+                external_callbacks,
+                print_jobs,
+                net_streams,
+                current_context_menu,
+                fs_command_handler,
                 input,
                 action_queue,
                 gc_context,
                 levels,
                 mouse_hovered_object,
+                mouse_pressed_object,
                 mouse_position,
+                keyboard,
                 drag_object,
+                focus_tracker,
                 stage_size: (stage_width, stage_height),
+                stage,
                 system_prototypes: avm.prototypes().clone(),
+                avm2,
                 player,
                 load_manager,
                 system: system_properties,
                 instance_counter,
                 storage,
+                fonts,
+                video,
+                log,
                 shared_objects,
                 unbound_text_fields,
             };
 
+            avm.reset_action_budget();
             let ret = f(avm, &mut update_context);
+            let timed_out = avm.take_timed_out();
+
+            // Hovered and pressed objects may have been updated; copy them back to the GC root.
+            let mouse_hovered_object = update_context.mouse_hovered_object;
+            let mouse_pressed_object = update_context.mouse_pressed_object;
+            root_data.mouse_hovered_object = mouse_hovered_object;
+            root_data.mouse_pressed_object = mouse_pressed_object;
+            (ret, timed_out)
+        });
 
-            // Hovered object may have been updated; copy it back to the GC root.
-            root_data.mouse_hovered_object = update_context.mouse_hovered_object;
-            ret
-        })
+        if timed_out {
+            self.log
+                .avm_warning("A script has run for too long and has been aborted.");
+            if let Some(handler) = self.script_timeout_handler.as_mut() {
+                handler();
+            }
+        }
+
+        // The movie may have changed the stage's scale mode or alignment.
+        self.build_matrices();
+
+        ret
     }
 
     /// Loads font data from the given buffer.
@@ -1005,7 +1866,6 @@ impl Player {
         });
 
         // Update mouse state (check for new hovered button, etc.)
-        self.update_drag();
         self.update_roll_over();
 
         // GC
@@ -1040,10 +1900,29 @@ pub struct DragObject<'gc> {
     /// The offset from the mouse position to the center of the clip.
     pub offset: (Twips, Twips),
 
-    /// The bounding rectangle where the clip will be maintained.
+    /// The bounding rectangle where the clip will be maintained, in its parent's coordinates.
     pub constraint: BoundingBox,
 }
 
+impl<'gc> DragObject<'gc> {
+    /// Moves the dragged object to follow the mouse, keeping its position inside the
+    /// constraint rectangle.
+    pub fn update_position(
+        &self,
+        gc_context: gc_arena::MutationContext<'gc, '_>,
+        mouse_pos: (Twips, Twips),
+    ) {
+        let mut display_object = self.display_object;
+        let mut drag_point = (mouse_pos.0 + self.offset.0, mouse_pos.1 + self.offset.1);
+        if let Some(parent) = display_object.parent() {
+            drag_point = parent.global_to_local(drag_point);
+        }
+        drag_point = self.constraint.clamp(drag_point);
+        display_object.set_x(gc_context, drag_point.0.to_pixels());
+        display_object.set_y(gc_context, drag_point.1.to_pixels());
+    }
+}
+
 unsafe impl<'gc> gc_arena::Collect for DragObject<'gc> {
     fn trace(&self, cc: gc_arena::CollectionContext) {
         self.display_object.trace(cc);
@@ -4,16 +4,21 @@
 //! the insertion order of properties, which is necessary for accurate
 //! enumeration order.
 
+use crate::avm1::AvmString;
 use crate::string_utils;
 use gc_arena::Collect;
 use indexmap::{Equivalent, IndexMap};
 use std::hash::{Hash, Hasher};
 
 /// A map from property names to values.
+///
+/// Names are stored as `AvmString`s, so defining a property under a name
+/// that is already a GC string (such as a constant pool entry or a static
+/// built-in name) doesn't copy it.
 #[derive(Debug)]
-pub struct PropertyMap<V>(IndexMap<PropertyName, V>);
+pub struct PropertyMap<'gc, V>(IndexMap<PropertyName<'gc>, V>);
 
-impl<V> PropertyMap<V> {
+impl<'gc, V> PropertyMap<'gc, V> {
     pub fn new() -> Self {
         Self(IndexMap::new())
     }
@@ -26,7 +31,7 @@ impl<V> PropertyMap<V> {
         }
     }
 
-    pub fn entry<'a>(&'a mut self, key: &'a str, case_sensitive: bool) -> Entry<'a, V> {
+    pub fn entry<'a>(&'a mut self, key: AvmString<'gc>, case_sensitive: bool) -> Entry<'a, 'gc, V> {
         if case_sensitive {
             match self.0.get_full_mut(&CaseSensitiveStr(&key)) {
                 Some((index, _, _)) => Entry::Occupied(OccupiedEntry {
@@ -76,7 +81,7 @@ impl<V> PropertyMap<V> {
         self.0.get_index(index).map(|(_, v)| v)
     }
 
-    pub fn insert(&mut self, key: &str, value: V, case_sensitive: bool) -> Option<V> {
+    pub fn insert(&mut self, key: AvmString<'gc>, value: V, case_sensitive: bool) -> Option<V> {
         match self.entry(key, case_sensitive) {
             Entry::Occupied(entry) => Some(entry.insert(value)),
             Entry::Vacant(entry) => {
@@ -87,13 +92,13 @@ impl<V> PropertyMap<V> {
     }
 
     /// Returns the value tuples in Flash's iteration order (most recently added first).
-    pub fn iter(&self) -> impl Iterator<Item = (&String, &V)> {
-        self.0.iter().rev().map(|(k, v)| (&k.0, v))
+    pub fn iter(&self) -> impl Iterator<Item = (AvmString<'gc>, &V)> {
+        self.0.iter().rev().map(|(k, v)| (k.0, v))
     }
 
     /// Returns the key-value tuples in Flash's iteration order (most recently added first).
-    pub fn iter_mut(&mut self) -> impl Iterator<Item = (&String, &mut V)> {
-        self.0.iter_mut().rev().map(|(k, v)| (&k.0, v))
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = (AvmString<'gc>, &mut V)> {
+        self.0.iter_mut().rev().map(|(k, v)| (k.0, v))
     }
 
     pub fn remove(&mut self, key: &str, case_sensitive: bool) -> Option<V> {
@@ -106,26 +111,27 @@ impl<V> PropertyMap<V> {
     }
 }
 
-unsafe impl<V: Collect> Collect for PropertyMap<V> {
+unsafe impl<'gc, V: Collect> Collect for PropertyMap<'gc, V> {
     fn trace(&self, cc: gc_arena::CollectionContext) {
-        for value in self.0.values() {
+        for (key, value) in self.0.iter() {
+            key.0.trace(cc);
             value.trace(cc);
         }
     }
 }
 
-pub enum Entry<'a, V> {
-    Occupied(OccupiedEntry<'a, V>),
-    Vacant(VacantEntry<'a, V>),
+pub enum Entry<'a, 'gc, V> {
+    Occupied(OccupiedEntry<'a, 'gc, V>),
+    Vacant(VacantEntry<'a, 'gc, V>),
 }
 
-pub struct OccupiedEntry<'a, V> {
-    map: &'a mut IndexMap<PropertyName, V>,
+pub struct OccupiedEntry<'a, 'gc, V> {
+    map: &'a mut IndexMap<PropertyName<'gc>, V>,
     index: usize,
 }
 
-impl<'a, V> OccupiedEntry<'a, V> {
-    pub fn remove_entry(&mut self) -> (String, V) {
+impl<'a, 'gc, V> OccupiedEntry<'a, 'gc, V> {
+    pub fn remove_entry(&mut self) -> (AvmString<'gc>, V) {
         let (k, v) = self.map.shift_remove_index(self.index).unwrap();
         (k.0, v)
     }
@@ -139,14 +145,14 @@ impl<'a, V> OccupiedEntry<'a, V> {
     }
 }
 
-pub struct VacantEntry<'a, V> {
-    map: &'a mut IndexMap<PropertyName, V>,
-    key: &'a str,
+pub struct VacantEntry<'a, 'gc, V> {
+    map: &'a mut IndexMap<PropertyName<'gc>, V>,
+    key: AvmString<'gc>,
 }
 
-impl<'a, V> VacantEntry<'a, V> {
+impl<'a, 'gc, V> VacantEntry<'a, 'gc, V> {
     pub fn insert(self, value: V) {
-        self.map.insert(PropertyName(self.key.to_string()), value);
+        self.map.insert(PropertyName(self.key), value);
     }
 }
 
@@ -159,8 +165,8 @@ impl<'a> Hash for CaseInsensitiveStr<'a> {
     }
 }
 
-impl<'a> Equivalent<PropertyName> for CaseInsensitiveStr<'a> {
-    fn equivalent(&self, key: &PropertyName) -> bool {
+impl<'a> Equivalent<PropertyName<'_>> for CaseInsensitiveStr<'a> {
+    fn equivalent(&self, key: &PropertyName<'_>) -> bool {
         string_utils::swf_string_eq_ignore_case(&key.0, self.0)
     }
 }
@@ -175,8 +181,8 @@ impl<'a> Hash for CaseSensitiveStr<'a> {
     }
 }
 
-impl<'a> Equivalent<PropertyName> for CaseSensitiveStr<'a> {
-    fn equivalent(&self, key: &PropertyName) -> bool {
+impl<'a> Equivalent<PropertyName<'_>> for CaseSensitiveStr<'a> {
+    fn equivalent(&self, key: &PropertyName<'_>) -> bool {
         key.0 == self.0
     }
 }
@@ -186,12 +192,11 @@ impl<'a> Equivalent<PropertyName> for CaseSensitiveStr<'a> {
 /// SWFv6, which is case insensitve. The equality check is handled by the `Equivalent`
 /// impls above, which allow it to be either case-sensitive or insensitive.
 /// Note that the property of if key1 == key2 -> hash(key1) == hash(key2) still holds.
-#[derive(Debug, Clone, PartialEq, Eq, Collect)]
-#[collect(require_static)]
-struct PropertyName(String);
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct PropertyName<'gc>(AvmString<'gc>);
 
 #[allow(clippy::derive_hash_xor_eq)]
-impl Hash for PropertyName {
+impl Hash for PropertyName<'_> {
     fn hash<H: Hasher>(&self, state: &mut H) {
         swf_hash_string_ignore_case(&self.0, state);
     }
@@ -558,6 +558,120 @@ impl<'a> ShapeConverter<'a> {
     }
 }
 
+/// The number of line segments used to approximate a quadratic curve during hit testing.
+const CURVE_HIT_TEST_SEGMENTS: u32 = 16;
+
+/// Returns whether the given shape contains the point, in the shape's local coordinate space.
+/// Both the fill regions and the strokes of the shape are tested.
+pub fn shape_hit_test(shape: &swf::Shape, test_point: (Twips, Twips)) -> bool {
+    let bounds: BoundingBox = (&shape.shape_bounds).into();
+    if !bounds.contains(test_point) {
+        return false;
+    }
+
+    ShapeConverter::from_shape(shape)
+        .into_commands()
+        .iter()
+        .any(|path| match path {
+            DrawPath::Fill { commands, .. } => draw_command_fill_hit_test(commands, test_point),
+            DrawPath::Stroke {
+                style, commands, ..
+            } => draw_command_stroke_hit_test(commands, style.width, test_point),
+        })
+}
+
+/// Returns whether the given point lies inside the fill traced by `commands`.
+/// Fills use the even-odd rule, and each subpath is implicitly closed.
+pub fn draw_command_fill_hit_test(commands: &[DrawCommand], test_point: (Twips, Twips)) -> bool {
+    let (px, py) = (test_point.0.get() as f64, test_point.1.get() as f64);
+    let mut inside = false;
+    for_each_edge(commands, true, |(x0, y0), (x1, y1)| {
+        // Cast a ray in the +x direction and count the edges it crosses.
+        if (y0 > py) != (y1 > py) {
+            let x = x0 + (py - y0) * (x1 - x0) / (y1 - y0);
+            if x > px {
+                inside = !inside;
+            }
+        }
+    });
+    inside
+}
+
+/// Returns whether the given point lies within `stroke_width` of the path traced by `commands`.
+pub fn draw_command_stroke_hit_test(
+    commands: &[DrawCommand],
+    stroke_width: Twips,
+    test_point: (Twips, Twips),
+) -> bool {
+    // Hairline strokes are still drawn one pixel wide.
+    let stroke_width = stroke_width.get().max(Twips::from_pixels(1.0).get()) as f64;
+    let radius_sq = stroke_width * stroke_width / 4.0;
+    let (px, py) = (test_point.0.get() as f64, test_point.1.get() as f64);
+    let mut hit = false;
+    for_each_edge(commands, false, |(x0, y0), (x1, y1)| {
+        if !hit {
+            let (dx, dy) = (x1 - x0, y1 - y0);
+            let len_sq = dx * dx + dy * dy;
+            let t = if len_sq > 0.0 {
+                let t = ((px - x0) * dx + (py - y0) * dy) / len_sq;
+                t.max(0.0).min(1.0)
+            } else {
+                0.0
+            };
+            let (nx, ny) = (x0 + t * dx - px, y0 + t * dy - py);
+            hit = nx * nx + ny * ny <= radius_sq;
+        }
+    });
+    hit
+}
+
+/// Calls `f` with each line segment of the path traced by `commands`, flattening curves.
+/// If `close_paths` is set, each subpath is closed back to its starting point.
+fn for_each_edge(
+    commands: &[DrawCommand],
+    close_paths: bool,
+    mut f: impl FnMut((f64, f64), (f64, f64)),
+) {
+    let to_f64 = |x: Twips, y: Twips| (x.get() as f64, y.get() as f64);
+    let mut start = (0.0, 0.0);
+    let mut cursor = (0.0, 0.0);
+    for command in commands {
+        match *command {
+            DrawCommand::MoveTo { x, y } => {
+                if close_paths && cursor != start {
+                    f(cursor, start);
+                }
+                start = to_f64(x, y);
+                cursor = start;
+            }
+            DrawCommand::LineTo { x, y } => {
+                let end = to_f64(x, y);
+                f(cursor, end);
+                cursor = end;
+            }
+            DrawCommand::CurveTo { x1, y1, x2, y2 } => {
+                let control = to_f64(x1, y1);
+                let end = to_f64(x2, y2);
+                let mut prev = cursor;
+                for i in 1..=CURVE_HIT_TEST_SEGMENTS {
+                    let t = f64::from(i) / f64::from(CURVE_HIT_TEST_SEGMENTS);
+                    let u = 1.0 - t;
+                    let point = (
+                        u * u * cursor.0 + 2.0 * u * t * control.0 + t * t * end.0,
+                        u * u * cursor.1 + 2.0 * u * t * control.1 + t * t * end.1,
+                    );
+                    f(prev, point);
+                    prev = point;
+                }
+                cursor = end;
+            }
+        }
+    }
+    if close_paths && cursor != start {
+        f(cursor, start);
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -710,4 +824,57 @@ mod tests {
         }];
         assert_eq!(commands, expected);
     }
+
+    /// A square with a square hole cut out of it, using the even-odd rule.
+    #[test]
+    fn fill_hit_test_even_odd() {
+        let square = |x: f64, y: f64, size: f64| {
+            vec![
+                DrawCommand::MoveTo {
+                    x: Twips::from_pixels(x),
+                    y: Twips::from_pixels(y),
+                },
+                DrawCommand::LineTo {
+                    x: Twips::from_pixels(x + size),
+                    y: Twips::from_pixels(y),
+                },
+                DrawCommand::LineTo {
+                    x: Twips::from_pixels(x + size),
+                    y: Twips::from_pixels(y + size),
+                },
+                DrawCommand::LineTo {
+                    x: Twips::from_pixels(x),
+                    y: Twips::from_pixels(y + size),
+                },
+            ]
+        };
+        let mut commands = square(0.0, 0.0, 100.0);
+        commands.extend(square(25.0, 25.0, 50.0));
+        let point = |x, y| (Twips::from_pixels(x), Twips::from_pixels(y));
+        assert!(draw_command_fill_hit_test(&commands, point(10.0, 10.0)));
+        assert!(!draw_command_fill_hit_test(&commands, point(50.0, 50.0)));
+        assert!(!draw_command_fill_hit_test(&commands, point(150.0, 50.0)));
+    }
+
+    /// A straight stroke hits only within half its width.
+    #[test]
+    fn stroke_hit_test() {
+        let commands = vec![
+            DrawCommand::MoveTo {
+                x: Twips::from_pixels(0.0),
+                y: Twips::from_pixels(0.0),
+            },
+            DrawCommand::LineTo {
+                x: Twips::from_pixels(100.0),
+                y: Twips::from_pixels(0.0),
+            },
+        ];
+        let hit = |x, y| {
+            let point = (Twips::from_pixels(x), Twips::from_pixels(y));
+            draw_command_stroke_hit_test(&commands, Twips::from_pixels(10.0), point)
+        };
+        assert!(hit(50.0, 4.0));
+        assert!(hit(103.0, 0.0));
+        assert!(!hit(50.0, 6.0));
+    }
 }
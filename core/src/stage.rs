@@ -0,0 +1,384 @@
+//! Stage scaling and alignment
+
+use crate::backend::render::Letterbox;
+use crate::prelude::*;
+
+/// How the stage is scaled to fit the viewport.
+/// Set in AVM1 with `Stage.scaleMode`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StageScaleMode {
+    /// The whole stage is visible, keeping its aspect ratio.
+    /// Extra space in the viewport is letterboxed.
+    ShowAll,
+
+    /// The stage fills the viewport, keeping its aspect ratio.
+    /// Parts of the stage may be cropped.
+    NoBorder,
+
+    /// The stage is stretched to fill the viewport exactly.
+    ExactFit,
+
+    /// The stage is not scaled.
+    NoScale,
+}
+
+impl StageScaleMode {
+    /// Parses the name of a scale mode, as set by `Stage.scaleMode`.
+    /// Names are case-insensitive.
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_ascii_lowercase().as_str() {
+            "showall" => Some(Self::ShowAll),
+            "noborder" => Some(Self::NoBorder),
+            "exactfit" => Some(Self::ExactFit),
+            "noscale" => Some(Self::NoScale),
+            _ => None,
+        }
+    }
+
+    /// The name of the scale mode, as returned by `Stage.scaleMode`.
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::ShowAll => "showAll",
+            Self::NoBorder => "noBorder",
+            Self::ExactFit => "exactFit",
+            Self::NoScale => "noScale",
+        }
+    }
+}
+
+/// The rendering quality of the movie.
+/// Set from the context menu, or in AVM1 with `_quality`, `_highquality` and `toggleHighQuality`.
+/// TODO: The renderers only use this to choose bitmap smoothing; shapes are always anti-aliased.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StageQuality {
+    /// No anti-aliasing, and bitmaps are not smoothed.
+    Low,
+
+    /// Some anti-aliasing, and bitmaps are not smoothed.
+    Medium,
+
+    /// Full anti-aliasing, and bitmaps are smoothed on still frames.
+    High,
+
+    /// Full anti-aliasing, and bitmaps are always smoothed.
+    Best,
+}
+
+impl StageQuality {
+    /// Parses the name of a quality, as set by `_quality`.
+    /// Names are case-insensitive.
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_ascii_uppercase().as_str() {
+            "LOW" => Some(Self::Low),
+            "MEDIUM" => Some(Self::Medium),
+            "HIGH" => Some(Self::High),
+            "BEST" => Some(Self::Best),
+            _ => None,
+        }
+    }
+
+    /// The name of the quality, as returned by `_quality`.
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Low => "LOW",
+            Self::Medium => "MEDIUM",
+            Self::High => "HIGH",
+            Self::Best => "BEST",
+        }
+    }
+
+    /// Whether bitmaps are smoothed when drawn at this quality.
+    pub fn smooths_bitmaps(self) -> bool {
+        matches!(self, Self::High | Self::Best)
+    }
+}
+
+/// Which edges of the viewport the stage is aligned to.
+/// The stage is centered on any axis without an edge.
+/// Set in AVM1 with `Stage.align`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct StageAlign {
+    pub top: bool,
+    pub bottom: bool,
+    pub left: bool,
+    pub right: bool,
+}
+
+impl StageAlign {
+    /// Parses an alignment string such as `"TL"`, as set by `Stage.align`.
+    /// Each letter picks an edge; unknown letters are ignored.
+    pub fn from_name(name: &str) -> Self {
+        let mut align = Self::default();
+        for c in name.chars() {
+            match c.to_ascii_uppercase() {
+                'T' => align.top = true,
+                'B' => align.bottom = true,
+                'L' => align.left = true,
+                'R' => align.right = true,
+                _ => (),
+            }
+        }
+        align
+    }
+
+    /// The alignment string, as returned by `Stage.align`.
+    pub fn name(self) -> String {
+        let mut name = String::new();
+        if self.top {
+            name.push('T');
+        }
+        if self.bottom {
+            name.push('B');
+        }
+        if self.left {
+            name.push('L');
+        }
+        if self.right {
+            name.push('R');
+        }
+        name
+    }
+}
+
+/// The properties of the stage that movies can query and change with the
+/// AVM1 `Stage` object.
+#[derive(Debug, Clone)]
+pub struct StageProperties {
+    pub scale_mode: StageScaleMode,
+    pub align: StageAlign,
+
+    /// Whether the built-in items of the context menu are shown.
+    pub show_menu: bool,
+
+    pub quality: StageQuality,
+
+    /// Whether a yellow rectangle is drawn around the object with keyboard focus.
+    /// Set in AVM1 with `_focusrect`.
+    pub focus_rect: bool,
+
+    /// Either `"normal"` or `"fullScreen"`.
+    /// TODO: Stored, but full screen mode isn't supported yet.
+    pub display_state: String,
+
+    /// The size of the viewport that the stage is displayed in, in pixels.
+    pub viewport_size: (u32, u32),
+}
+
+impl StageProperties {
+    pub fn new(viewport_size: (u32, u32)) -> Self {
+        Self {
+            scale_mode: StageScaleMode::ShowAll,
+            align: StageAlign::default(),
+            show_menu: true,
+            quality: StageQuality::High,
+            focus_rect: true,
+            display_state: "normal".to_string(),
+            viewport_size,
+        }
+    }
+
+    /// Returns the size of the stage, as reported by `Stage.width` and
+    /// `Stage.height`, for a movie of the given size.
+    ///
+    /// When the stage isn't scaled, this is the size of the viewport.
+    pub fn stage_size(&self, movie_size: (u32, u32)) -> (u32, u32) {
+        if self.scale_mode == StageScaleMode::NoScale {
+            self.viewport_size
+        } else {
+            movie_size
+        }
+    }
+
+    /// Calculates the matrix that transforms the stage of a movie of the given
+    /// size into the viewport, and the letterbox to draw around it.
+    pub fn layout(&self, movie_size: (u32, u32)) -> (Matrix, Letterbox) {
+        let (movie_width, movie_height) = (movie_size.0 as f32, movie_size.1 as f32);
+        let (viewport_width, viewport_height) =
+            (self.viewport_size.0 as f32, self.viewport_size.1 as f32);
+        let scale_x = viewport_width / movie_width;
+        let scale_y = viewport_height / movie_height;
+
+        let (scale_x, scale_y) = match self.scale_mode {
+            StageScaleMode::ShowAll => {
+                let scale = scale_x.min(scale_y);
+                (scale, scale)
+            }
+            StageScaleMode::NoBorder => {
+                let scale = scale_x.max(scale_y);
+                (scale, scale)
+            }
+            StageScaleMode::ExactFit => (scale_x, scale_y),
+            StageScaleMode::NoScale => (1.0, 1.0),
+        };
+
+        // The space left over in the viewport; negative when the stage is cropped.
+        let margin_width = viewport_width - movie_width * scale_x;
+        let margin_height = viewport_height - movie_height * scale_y;
+        let tx = align_offset(margin_width, self.align.left, self.align.right);
+        let ty = align_offset(margin_height, self.align.top, self.align.bottom);
+
+        let matrix = Matrix {
+            a: scale_x,
+            b: 0.0,
+            c: 0.0,
+            d: scale_y,
+            tx: Twips::from_pixels(tx.into()),
+            ty: Twips::from_pixels(ty.into()),
+        };
+
+        // Only a centered `showAll` stage is letterboxed, as the margins are
+        // the same on both sides. Otherwise, content outside of the stage is
+        // visible, as in Flash Player.
+        // TODO: Letterbox should be an option; the original Flash Player defaults to showing
+        // content in the extra margins.
+        let letterbox = if self.scale_mode != StageScaleMode::ShowAll {
+            Letterbox::None
+        } else if margin_width > 0.0 && !self.align.left && !self.align.right {
+            Letterbox::Pillarbox(margin_width / 2.0)
+        } else if margin_height > 0.0 && !self.align.top && !self.align.bottom {
+            Letterbox::Letterbox(margin_height / 2.0)
+        } else {
+            Letterbox::None
+        };
+
+        (matrix, letterbox)
+    }
+}
+
+/// Returns the offset of the stage along an axis with the given margin.
+/// The stage is centered unless it is aligned to exactly one edge.
+fn align_offset(margin: f32, start: bool, end: bool) -> f32 {
+    match (start, end) {
+        (true, false) => 0.0,
+        (false, true) => margin,
+        _ => margin / 2.0,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn layout(
+        scale_mode: StageScaleMode,
+        align: &str,
+        viewport_size: (u32, u32),
+    ) -> (f32, f32, f64, f64, Letterbox) {
+        let mut stage = StageProperties::new(viewport_size);
+        stage.scale_mode = scale_mode;
+        stage.align = StageAlign::from_name(align);
+        let (matrix, letterbox) = stage.layout((550, 400));
+        (
+            matrix.a,
+            matrix.d,
+            matrix.tx.to_pixels(),
+            matrix.ty.to_pixels(),
+            letterbox,
+        )
+    }
+
+    #[test]
+    fn show_all() {
+        use StageScaleMode::ShowAll;
+        assert_eq!(
+            layout(ShowAll, "", (550, 400)),
+            (1.0, 1.0, 0.0, 0.0, Letterbox::None)
+        );
+        assert_eq!(
+            layout(ShowAll, "", (1100, 800)),
+            (2.0, 2.0, 0.0, 0.0, Letterbox::None)
+        );
+        // Wider viewport
+        assert_eq!(
+            layout(ShowAll, "", (800, 200)),
+            (0.5, 0.5, 262.5, 0.0, Letterbox::Pillarbox(262.5))
+        );
+        // Taller viewport
+        assert_eq!(
+            layout(ShowAll, "", (275, 400)),
+            (0.5, 0.5, 0.0, 100.0, Letterbox::Letterbox(100.0))
+        );
+        assert_eq!(
+            layout(ShowAll, "L", (800, 200)),
+            (0.5, 0.5, 0.0, 0.0, Letterbox::None)
+        );
+        assert_eq!(
+            layout(ShowAll, "BR", (275, 400)),
+            (0.5, 0.5, 0.0, 200.0, Letterbox::None)
+        );
+    }
+
+    #[test]
+    fn no_border() {
+        use StageScaleMode::NoBorder;
+        assert_eq!(
+            layout(NoBorder, "", (800, 200)),
+            (800.0 / 550.0, 800.0 / 550.0, 0.0, -190.9, Letterbox::None)
+        );
+        assert_eq!(
+            layout(NoBorder, "", (275, 400)),
+            (1.0, 1.0, -137.5, 0.0, Letterbox::None)
+        );
+        assert_eq!(
+            layout(NoBorder, "TR", (275, 400)),
+            (1.0, 1.0, -275.0, 0.0, Letterbox::None)
+        );
+    }
+
+    #[test]
+    fn exact_fit() {
+        use StageScaleMode::ExactFit;
+        assert_eq!(
+            layout(ExactFit, "", (1100, 200)),
+            (2.0, 0.5, 0.0, 0.0, Letterbox::None)
+        );
+        assert_eq!(
+            layout(ExactFit, "TL", (275, 800)),
+            (0.5, 2.0, 0.0, 0.0, Letterbox::None)
+        );
+    }
+
+    #[test]
+    fn no_scale() {
+        use StageScaleMode::NoScale;
+        assert_eq!(
+            layout(NoScale, "", (750, 300)),
+            (1.0, 1.0, 100.0, -50.0, Letterbox::None)
+        );
+        assert_eq!(
+            layout(NoScale, "TL", (750, 300)),
+            (1.0, 1.0, 0.0, 0.0, Letterbox::None)
+        );
+        assert_eq!(
+            layout(NoScale, "B", (750, 300)),
+            (1.0, 1.0, 100.0, -100.0, Letterbox::None)
+        );
+    }
+
+    #[test]
+    fn stage_size() {
+        let mut stage = StageProperties::new((800, 600));
+        assert_eq!(stage.stage_size((550, 400)), (550, 400));
+        stage.scale_mode = StageScaleMode::ExactFit;
+        assert_eq!(stage.stage_size((550, 400)), (550, 400));
+        stage.scale_mode = StageScaleMode::NoScale;
+        assert_eq!(stage.stage_size((550, 400)), (800, 600));
+    }
+
+    #[test]
+    fn parse_names() {
+        assert_eq!(
+            StageScaleMode::from_name("NOSCALE"),
+            Some(StageScaleMode::NoScale)
+        );
+        assert_eq!(StageScaleMode::from_name("fit"), None);
+        assert_eq!(StageAlign::from_name("rt").name(), "TR");
+        assert_eq!(StageAlign::from_name("").name(), "");
+        assert_eq!(
+            StageQuality::from_name("medium"),
+            Some(StageQuality::Medium)
+        );
+        assert_eq!(StageQuality::from_name("ultra"), None);
+        assert_eq!(StageQuality::Best.name(), "BEST");
+    }
+}
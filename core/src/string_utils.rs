@@ -44,6 +44,12 @@ pub fn swf_string_eq_ignore_case(a: &str, b: &str) -> bool {
         .eq(b.chars().map(swf_char_to_lowercase))
 }
 
+/// Converts a string to lowercase as done by the Flash Player.
+/// Two strings are equal ignoring case if their lowercase forms are equal.
+pub fn swf_string_to_lowercase(s: &str) -> String {
+    s.chars().map(swf_char_to_lowercase).collect()
+}
+
 /// Compares two strings, ignoring case as done by the Flash Player.
 /// Note that the case mapping is different than Rust's case mapping.
 pub fn swf_string_cmp_ignore_case(a: &str, b: &str) -> std::cmp::Ordering {
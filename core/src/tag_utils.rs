@@ -1,7 +1,15 @@
+use crate::backend::render::{determine_jpeg_tag_format, JpegTagFormat};
+use crate::player::NEWEST_PLAYER_VERSION;
 use gc_arena::Collect;
+use std::cell::UnsafeCell;
+use std::collections::VecDeque;
+use std::io::{self, Read};
 use std::path::Path;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
 use std::sync::Arc;
-use swf::{Header, TagCode};
+use swf::read::SwfRead;
+use swf::{Header, TagCode, Twips};
+use url::Url;
 
 pub type Error = Box<dyn std::error::Error>;
 pub type DecodeResult = Result<(), Error>;
@@ -9,6 +17,9 @@ pub type SwfStream<R> = swf::read::Reader<std::io::Cursor<R>>;
 
 /// An open, fully parsed SWF movie ready to play back, either in a Player or a
 /// MovieClip.
+///
+/// Movies built by a `SwfMovieStream` may still be loading, in which case
+/// their data grows as more of it loads.
 #[derive(Debug, Clone, Collect)]
 #[collect(require_static)]
 pub struct SwfMovie {
@@ -16,7 +27,17 @@ pub struct SwfMovie {
     header: Header,
 
     /// Uncompressed SWF data.
-    data: Vec<u8>,
+    data: MovieData,
+
+    /// The length of the SWF datastream as it was loaded, before decompression.
+    compressed_length: usize,
+
+    /// The URL the movie was loaded from, if it came from one.
+    url: Option<String>,
+
+    /// The variables passed to the movie, also known as FlashVars. Later
+    /// entries take precedence over earlier ones with the same name.
+    parameters: Vec<(String, String)>,
 }
 
 impl SwfMovie {
@@ -30,7 +51,10 @@ impl SwfMovie {
                 frame_rate: 1.0,
                 num_frames: 0,
             },
-            data: vec![],
+            data: MovieData::Complete(vec![]),
+            compressed_length: 0,
+            url: None,
+            parameters: Vec::new(),
         }
     }
 
@@ -38,18 +62,24 @@ impl SwfMovie {
     pub fn from_movie_and_subdata(&self, data: Vec<u8>) -> Self {
         Self {
             header: self.header.clone(),
-            data,
+            compressed_length: data.len(),
+            data: MovieData::Complete(data),
+            url: self.url.clone(),
+            parameters: self.parameters.clone(),
         }
     }
 
     /// Utility method to construct a movie from a file on disk.
     pub fn from_path<P: AsRef<Path>>(path: P) -> Result<Self, Error> {
         let data = std::fs::read(path)?;
-        Self::from_data(&data)
+        Self::from_data(&data, None)
     }
 
     /// Construct a movie based on the contents of the SWF datastream.
-    pub fn from_data(swf_data: &[u8]) -> Result<Self, Error> {
+    ///
+    /// The variables in the query string of the movie's `url` become its
+    /// parameters.
+    pub fn from_data(swf_data: &[u8], url: Option<String>) -> Result<Self, Error> {
         let swf_stream = swf::read::read_swf_header(&swf_data[..])?;
         let header = swf_stream.header;
         let mut reader = swf_stream.reader;
@@ -58,23 +88,80 @@ impl SwfMovie {
         // Sometimes SWFs will have an incorrectly compressed stream,
         // but will otherwise decompress fine up to the End tag.
         // So just warn on this case and try to continue gracefully.
-        let data = if header.compression == swf::Compression::Lzma {
-            // TODO: The LZMA decoder is still funky.
-            // It always errors, and doesn't return all the data if you use read_to_end,
-            // but read_exact at least returns the data... why?
-            // Does the decoder need to be flushed somehow?
-            let mut data = vec![0u8; swf_stream.uncompressed_length];
-            let _ = reader.get_mut().read_exact(&mut data);
-            data
-        } else {
-            let mut data = Vec::with_capacity(swf_stream.uncompressed_length);
-            if let Err(e) = reader.get_mut().read_to_end(&mut data) {
-                return Err(format!("Error decompressing SWF, may be corrupt: {}", e).into());
-            }
-            data
+        let mut data = Vec::with_capacity(swf_stream.uncompressed_length);
+        if let Err(e) = reader.get_mut().read_to_end(&mut data) {
+            return Err(format!("Error decompressing SWF, may be corrupt: {}", e).into());
+        }
+
+        let parameters = url.as_deref().map_or_else(Vec::new, url_parameters);
+
+        Ok(Self {
+            header,
+            data: MovieData::Complete(data),
+            compressed_length: swf_data.len(),
+            url,
+            parameters,
+        })
+    }
+
+    /// Construct a movie that displays a JPEG, PNG or GIF image, for images
+    /// loaded in place of an SWF such as by `loadMovie`.
+    ///
+    /// The movie is a single frame with the image placed as a bitmap at depth
+    /// 1, so the stage and the clip it is loaded into are the size of the
+    /// image. Only the first frame of a GIF is shown.
+    pub fn from_image_data(image_data: &[u8], url: Option<String>) -> Result<Self, Error> {
+        // Make sure that the image can be decoded, so that a broken image fails
+        // the load instead of becoming an empty movie.
+        let bitmap = crate::backend::render::decode_define_bits_jpeg(image_data, None)?;
+
+        let swf = swf::Swf {
+            header: Header {
+                version: NEWEST_PLAYER_VERSION,
+                compression: swf::Compression::None,
+                stage_size: swf::Rectangle {
+                    x_min: Twips::zero(),
+                    x_max: Twips::from_pixels(bitmap.width.into()),
+                    y_min: Twips::zero(),
+                    y_max: Twips::from_pixels(bitmap.height.into()),
+                },
+                frame_rate: 1.0,
+                num_frames: 1,
+            },
+            tags: vec![
+                swf::Tag::DefineBitsJpeg2 {
+                    id: 1,
+                    jpeg_data: image_data.to_vec(),
+                },
+                swf::Tag::PlaceObject(Box::new(swf::PlaceObject {
+                    version: 2,
+                    action: swf::PlaceObjectAction::Place(1),
+                    depth: 1,
+                    matrix: None,
+                    color_transform: None,
+                    ratio: None,
+                    name: None,
+                    clip_depth: None,
+                    class_name: None,
+                    filters: vec![],
+                    background_color: None,
+                    blend_mode: swf::BlendMode::Normal,
+                    clip_actions: vec![],
+                    is_image: false,
+                    is_bitmap_cached: false,
+                    is_visible: true,
+                    amf_data: None,
+                })),
+                swf::Tag::ShowFrame,
+                swf::Tag::End,
+            ],
         };
+        let mut swf_data = Vec::new();
+        swf::write_swf(&swf, &mut swf_data)?;
 
-        Ok(Self { header, data })
+        let mut movie = Self::from_data(&swf_data, url)?;
+        movie.compressed_length = image_data.len();
+        Ok(movie)
     }
 
     pub fn header(&self) -> &Header {
@@ -86,8 +173,44 @@ impl SwfMovie {
         self.header.version
     }
 
+    /// Get the uncompressed SWF data, as far as it has loaded.
     pub fn data(&self) -> &[u8] {
-        &self.data
+        match &self.data {
+            MovieData::Complete(data) => data,
+            MovieData::Streaming(data) => data.loaded(),
+        }
+    }
+
+    /// Get the length of the SWF datastream as it was loaded, before decompression.
+    /// Returned by `getBytesTotal` in AVM1.
+    ///
+    /// While the movie is loading, this is the length reported by its
+    /// `SwfMovieStream`, which may only be an estimate.
+    pub fn compressed_length(&self) -> usize {
+        match &self.data {
+            MovieData::Complete(_) => self.compressed_length,
+            MovieData::Streaming(data) => data.bytes_total.load(Ordering::Acquire),
+        }
+    }
+
+    /// Get the length of the SWF datastream that has loaded so far, before
+    /// decompression. Returned by `getBytesLoaded` in AVM1.
+    pub fn bytes_loaded(&self) -> usize {
+        match &self.data {
+            MovieData::Complete(_) => self.compressed_length,
+            MovieData::Streaming(data) => data.bytes_loaded.load(Ordering::Acquire),
+        }
+    }
+
+    /// Whether all of the movie has loaded.
+    ///
+    /// The data of a movie that is still loading only grows, so anything read
+    /// from it stays valid.
+    pub fn is_loaded(&self) -> bool {
+        match &self.data {
+            MovieData::Complete(_) => true,
+            MovieData::Streaming(data) => data.is_loaded.load(Ordering::Acquire),
+        }
     }
 
     pub fn width(&self) -> u32 {
@@ -97,6 +220,475 @@ impl SwfMovie {
     pub fn height(&self) -> u32 {
         (self.header.stage_size.y_max - self.header.stage_size.y_min).to_pixels() as u32
     }
+
+    /// The URL the movie was loaded from, if it came from one.
+    pub fn url(&self) -> Option<&str> {
+        self.url.as_deref()
+    }
+
+    /// The variables passed to the movie, in the order they are defined on
+    /// its root clip.
+    pub fn parameters(&self) -> &[(String, String)] {
+        &self.parameters
+    }
+
+    /// Pass more variables to the movie, such as the FlashVars given by the
+    /// embedder. They take precedence over the variables in its URL.
+    pub fn append_parameters(&mut self, parameters: impl IntoIterator<Item = (String, String)>) {
+        self.parameters.extend(parameters);
+    }
+}
+
+/// The variables in the query string of a movie URL.
+///
+/// Relative URLs have no base to resolve against here, so their query string
+/// is found by hand.
+fn url_parameters(url: &str) -> Vec<(String, String)> {
+    let query = match Url::parse(url) {
+        Ok(url) => url.query().map(str::to_owned),
+        Err(_) => url
+            .split('#')
+            .next()
+            .and_then(|url| url.splitn(2, '?').nth(1))
+            .map(str::to_owned),
+    };
+
+    query.map_or_else(Vec::new, |query| {
+        url::form_urlencoded::parse(query.as_bytes())
+            .into_owned()
+            .collect()
+    })
+}
+
+/// The uncompressed data of a movie.
+#[derive(Debug, Clone)]
+enum MovieData {
+    /// The data of a movie that was loaded all at once.
+    Complete(Vec<u8>),
+
+    /// The data of a movie built by a `SwfMovieStream`, which is shared with
+    /// the stream.
+    Streaming(Arc<StreamingData>),
+}
+
+/// The uncompressed data of a movie that is streaming in, which grows as the
+/// `SwfMovieStream` of the movie writes more of it.
+///
+/// The buffer is allocated at the uncompressed length given in the SWF header,
+/// so it never moves: slices of the data that has loaded stay valid while more
+/// data is written after it.
+struct StreamingData {
+    buffer: Box<[UnsafeCell<u8>]>,
+
+    /// The length of the data that has been written. The bytes before it are
+    /// never written again.
+    len: AtomicUsize,
+
+    /// The length of the SWF datastream that has loaded, before decompression.
+    bytes_loaded: AtomicUsize,
+
+    /// The length of the whole SWF datastream before decompression, as far as
+    /// it is known.
+    bytes_total: AtomicUsize,
+
+    /// Whether all of the data has loaded.
+    is_loaded: AtomicBool,
+}
+
+// Readers only ever look at the bytes before `len`, and only the bytes after
+// it are written, by the one `SwfMovieStream` that owns the movie.
+unsafe impl Sync for StreamingData {}
+
+impl StreamingData {
+    fn new(capacity: usize) -> Self {
+        Self {
+            buffer: std::iter::repeat_with(|| UnsafeCell::new(0))
+                .take(capacity)
+                .collect(),
+            len: AtomicUsize::new(0),
+            bytes_loaded: AtomicUsize::new(0),
+            bytes_total: AtomicUsize::new(0),
+            is_loaded: AtomicBool::new(false),
+        }
+    }
+
+    /// The data that has loaded so far.
+    fn loaded(&self) -> &[u8] {
+        let len = self.len.load(Ordering::Acquire);
+        // The bytes before `len` have been written, and are never written again.
+        unsafe { std::slice::from_raw_parts(self.buffer.as_ptr() as *const u8, len) }
+    }
+
+    /// Writes more data after the data that has loaded, and returns how much
+    /// of it fit into the buffer.
+    ///
+    /// # Safety
+    ///
+    /// Only the `SwfMovieStream` of the movie may write its data, so that
+    /// there is never more than one writer.
+    unsafe fn append(&self, data: &[u8]) -> usize {
+        let len = self.len.load(Ordering::Acquire);
+        let count = data.len().min(self.buffer.len() - len);
+        std::ptr::copy_nonoverlapping(
+            data.as_ptr(),
+            (self.buffer.as_ptr() as *mut u8).add(len),
+            count,
+        );
+        self.len.store(len + count, Ordering::Release);
+        count
+    }
+
+    /// Records the progress of the SWF datastream that the data is decompressed from.
+    fn set_progress(&self, bytes_loaded: usize, bytes_total: usize) {
+        self.bytes_loaded.store(bytes_loaded, Ordering::Release);
+        self.bytes_total.store(bytes_total, Ordering::Release);
+    }
+}
+
+impl std::fmt::Debug for StreamingData {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("StreamingData")
+            .field("len", &self.len)
+            .field("capacity", &self.buffer.len())
+            .field("bytes_loaded", &self.bytes_loaded)
+            .field("bytes_total", &self.bytes_total)
+            .field("is_loaded", &self.is_loaded)
+            .finish()
+    }
+}
+
+/// Builds a movie from its SWF datastream while the datastream loads, so that
+/// the movie can start playing before all of it has arrived.
+///
+/// The movie is available from `movie` as soon as its header has loaded, and
+/// takes in the rest of its data as it is appended to the stream. LZMA
+/// compressed movies and images can't be read until all of their data has
+/// loaded, so they are only available from `finish`.
+///
+/// If the stream is dropped before it is finished, its movie ends at the data
+/// that has loaded.
+pub struct SwfMovieStream {
+    /// The URL the movie is loaded from, if it comes from one.
+    url: Option<String>,
+
+    /// The variables passed to the movie.
+    parameters: Vec<(String, String)>,
+
+    /// The length of the whole datastream, if it was known before it loaded.
+    total_length: Option<usize>,
+
+    /// The length of the datastream that has loaded so far.
+    bytes_loaded: usize,
+
+    state: StreamState,
+}
+
+#[allow(clippy::large_enum_variant)]
+enum StreamState {
+    /// Waiting for the 8 byte SWF header, which tells what kind of data this is.
+    Start(Vec<u8>),
+
+    /// Decompressing an SWF as it loads.
+    Swf(StreamingSwf),
+
+    /// Collecting all of the datastream, to be read once it has loaded.
+    Buffered(Vec<u8>),
+}
+
+/// An SWF that is decompressed as it loads.
+struct StreamingSwf {
+    version: u8,
+
+    /// The uncompressed length from the SWF header, without the 8 bytes of the
+    /// header that come before the compressed data.
+    uncompressed_length: usize,
+
+    decompressor: Decompressor,
+
+    /// The decompressed data that has loaded before the rest of the header.
+    header_data: Vec<u8>,
+
+    /// The header and the data of the movie, once all of the header has loaded.
+    movie: Option<(Header, Arc<StreamingData>)>,
+}
+
+/// Decompresses the data of an SWF as it loads.
+enum Decompressor {
+    None(LoadedData),
+    Zlib(libflate::non_blocking::zlib::Decoder<LoadedData>),
+}
+
+/// Data that has loaded but has yet to be decompressed.
+///
+/// Reading more data than has loaded fails with `WouldBlock`, until all of the
+/// data has loaded.
+#[derive(Default)]
+struct LoadedData {
+    data: VecDeque<u8>,
+    is_complete: bool,
+}
+
+impl SwfMovieStream {
+    /// Start building a movie from the datastream loaded from `url`.
+    ///
+    /// `total_length` is the length of the whole datastream, if it is known
+    /// before the datastream loads, such as the length of a file.
+    pub fn new(url: Option<String>, total_length: Option<usize>) -> Self {
+        let parameters = url.as_deref().map_or_else(Vec::new, url_parameters);
+        Self {
+            url,
+            parameters,
+            total_length,
+            bytes_loaded: 0,
+            state: StreamState::Start(Vec::with_capacity(8)),
+        }
+    }
+
+    /// Pass more variables to the movie, like `SwfMovie::append_parameters`.
+    ///
+    /// They are given to the movies returned by `movie` and `finish` from then on.
+    pub fn append_parameters(&mut self, parameters: impl IntoIterator<Item = (String, String)>) {
+        self.parameters.extend(parameters);
+    }
+
+    /// How much of the datastream has loaded so far.
+    pub fn bytes_loaded(&self) -> usize {
+        self.bytes_loaded
+    }
+
+    /// The length of the whole datastream, as far as it is known, or 0 if it
+    /// isn't known yet.
+    ///
+    /// Unless the length was given to `new`, this is the length in the SWF
+    /// header, which is the uncompressed length of compressed movies.
+    pub fn bytes_total(&self) -> usize {
+        match (self.total_length, &self.state) {
+            (Some(total_length), _) => total_length,
+            (None, StreamState::Swf(swf)) => swf.uncompressed_length + 8,
+            _ => 0,
+        }
+    }
+
+    /// Append the next chunk of the datastream, decompressing as much of the
+    /// movie as it can.
+    pub fn append(&mut self, chunk: &[u8]) -> Result<(), Error> {
+        self.bytes_loaded += chunk.len();
+        match &mut self.state {
+            StreamState::Start(data) => {
+                data.extend_from_slice(chunk);
+                if data.len() >= 8 {
+                    let data = std::mem::take(data);
+                    self.start(data)?;
+                }
+            }
+            StreamState::Swf(swf) => {
+                swf.decompressor.input().data.extend(chunk);
+                swf.decompress()?;
+            }
+            StreamState::Buffered(data) => data.extend_from_slice(chunk),
+        }
+        self.update_progress();
+        Ok(())
+    }
+
+    /// The movie, once enough of it has loaded to start playing it.
+    ///
+    /// The movie shares its data with this stream, so it takes in the rest of
+    /// the data as it is appended.
+    pub fn movie(&self) -> Option<SwfMovie> {
+        match &self.state {
+            StreamState::Swf(StreamingSwf {
+                movie: Some((header, data)),
+                ..
+            }) => Some(SwfMovie {
+                header: header.clone(),
+                data: MovieData::Streaming(data.clone()),
+                compressed_length: 0,
+                url: self.url.clone(),
+                parameters: self.parameters.clone(),
+            }),
+            _ => None,
+        }
+    }
+
+    /// Finish the movie once all of the datastream has been appended, and
+    /// return it.
+    ///
+    /// If the movie was already available from `movie`, the returned movie
+    /// shares its data.
+    pub fn finish(mut self) -> Result<SwfMovie, Error> {
+        match std::mem::replace(&mut self.state, StreamState::Buffered(Vec::new())) {
+            StreamState::Start(data) | StreamState::Buffered(data) => {
+                let mut movie = match determine_jpeg_tag_format(&data) {
+                    JpegTagFormat::Unknown => SwfMovie::from_data(&data, None)?,
+                    _ => SwfMovie::from_image_data(&data, None)?,
+                };
+                movie.url = self.url.take();
+                movie.parameters = std::mem::take(&mut self.parameters);
+                Ok(movie)
+            }
+            StreamState::Swf(mut swf) => {
+                swf.decompressor.input().is_complete = true;
+                let result = swf.decompress();
+                self.total_length = Some(self.bytes_loaded);
+                self.state = StreamState::Swf(swf);
+                self.end();
+                result?;
+                self.movie()
+                    .ok_or_else(|| "Unexpected end of SWF header".into())
+            }
+        }
+    }
+
+    /// Reads the SWF header at the start of the datastream.
+    fn start(&mut self, data: Vec<u8>) -> Result<(), Error> {
+        let uncompressed_length = u32::from_le_bytes([data[4], data[5], data[6], data[7]]);
+        let mut input = LoadedData::default();
+        input.data.extend(&data[8..]);
+        let decompressor = match &data[..3] {
+            b"FWS" => Decompressor::None(input),
+            b"CWS" => Decompressor::Zlib(libflate::non_blocking::zlib::Decoder::new(input)),
+            _ => {
+                // LZMA compressed movies and images are read all at once.
+                self.state = StreamState::Buffered(data);
+                return Ok(());
+            }
+        };
+
+        let mut swf = StreamingSwf {
+            version: data[3],
+            uncompressed_length: (uncompressed_length as usize).saturating_sub(8),
+            decompressor,
+            header_data: Vec::new(),
+            movie: None,
+        };
+        let result = swf.decompress();
+        self.state = StreamState::Swf(swf);
+        result
+    }
+
+    /// Passes the progress of the datastream on to the movie.
+    fn update_progress(&self) {
+        if let StreamState::Swf(StreamingSwf {
+            movie: Some((_, data)),
+            ..
+        }) = &self.state
+        {
+            data.set_progress(self.bytes_loaded, self.bytes_total());
+        }
+    }
+
+    /// Marks the movie as loaded, with the data that has loaded so far.
+    fn end(&mut self) {
+        self.update_progress();
+        if let StreamState::Swf(StreamingSwf {
+            movie: Some((_, data)),
+            ..
+        }) = &self.state
+        {
+            data.is_loaded.store(true, Ordering::Release);
+        }
+    }
+}
+
+impl Drop for SwfMovieStream {
+    fn drop(&mut self) {
+        self.end();
+    }
+}
+
+impl StreamingSwf {
+    /// Decompresses as much of the data that has loaded as it can.
+    fn decompress(&mut self) -> Result<(), Error> {
+        let mut buffer = [0; 4096];
+        loop {
+            let len = match self.decompressor.read(&mut buffer) {
+                Ok(0) => return Ok(()),
+                Ok(len) => len,
+                Err(e) if e.kind() == io::ErrorKind::WouldBlock => return Ok(()),
+                Err(e) => {
+                    return Err(format!("Error decompressing SWF, may be corrupt: {}", e).into())
+                }
+            };
+
+            if let Some((_, data)) = &self.movie {
+                // Only the stream of a movie writes its data.
+                if unsafe { data.append(&buffer[..len]) } < len {
+                    log::warn!("SWF is longer than the length in its header, ignoring the rest");
+                }
+            } else {
+                self.header_data.extend_from_slice(&buffer[..len]);
+                self.read_header()?;
+            }
+        }
+    }
+
+    /// Reads the rest of the SWF header once it has loaded, and creates the
+    /// data of the movie.
+    fn read_header(&mut self) -> Result<(), Error> {
+        // The header ends with the stage size, which is a rectangle of
+        // variable size, followed by the frame rate and the number of frames.
+        let num_bits = usize::from(self.header_data[0] >> 3);
+        let header_length = (5 + 4 * num_bits + 7) / 8 + 4;
+        if self.header_data.len() < header_length {
+            return Ok(());
+        }
+
+        let mut reader = swf::read::Reader::new(&self.header_data[..], self.version);
+        let stage_size = reader.read_rectangle()?;
+        let frame_rate = reader.read_fixed8()?;
+        let num_frames = reader.read_u16()?;
+        let header = Header {
+            version: self.version,
+            compression: match self.decompressor {
+                Decompressor::None(_) => swf::Compression::None,
+                Decompressor::Zlib(_) => swf::Compression::Zlib,
+            },
+            stage_size,
+            frame_rate,
+            num_frames,
+        };
+
+        let data = StreamingData::new(self.uncompressed_length.saturating_sub(header_length));
+        // The data isn't shared with a movie yet.
+        unsafe { data.append(&self.header_data[header_length..]) };
+        self.header_data = Vec::new();
+        self.movie = Some((header, Arc::new(data)));
+        Ok(())
+    }
+}
+
+impl Decompressor {
+    /// The data waiting to be decompressed.
+    fn input(&mut self) -> &mut LoadedData {
+        match self {
+            Decompressor::None(input) => input,
+            Decompressor::Zlib(decoder) => decoder.as_inner_mut(),
+        }
+    }
+}
+
+impl Read for Decompressor {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        match self {
+            Decompressor::None(input) => input.read(buf),
+            Decompressor::Zlib(decoder) => decoder.read(buf),
+        }
+    }
+}
+
+impl Read for LoadedData {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if self.data.is_empty() && !self.is_complete {
+            return Err(io::ErrorKind::WouldBlock.into());
+        }
+
+        let len = buf.len().min(self.data.len());
+        for (byte, loaded) in buf.iter_mut().zip(self.data.drain(..len)) {
+            *byte = loaded;
+        }
+        Ok(len)
+    }
 }
 
 /// A shared-ownership reference to some portion of an SWF datastream.
@@ -194,7 +786,7 @@ impl SwfSlice {
 
             let len = self.movie.data().len();
 
-            if new_start < len && new_end < len {
+            if new_start < len && new_end <= len {
                 Some(SwfSlice {
                     movie: self.movie.clone(),
                     start: new_start,
@@ -245,19 +837,85 @@ impl SwfSlice {
     }
 }
 
+/// A problem with a tag of a movie, which was skipped over.
+#[derive(Debug, Clone, PartialEq, Collect)]
+#[collect(require_static)]
+pub struct TagDiagnostic {
+    /// The code of the tag, which may not be a known `TagCode`.
+    pub tag_code: u16,
+
+    /// What was wrong with the tag.
+    pub message: String,
+}
+
 pub fn decode_tags<'a, R, F>(
     reader: &'a mut SwfStream<R>,
-    mut tag_callback: F,
+    tag_callback: F,
+    stop_tag: TagCode,
+) -> Result<(), Box<dyn std::error::Error>>
+where
+    R: 'a + AsRef<[u8]>,
+    F: FnMut(&mut SwfStream<R>, TagCode, usize) -> DecodeResult,
+{
+    decode_tags_with_diagnostics(reader, tag_callback, stop_tag, &mut Vec::new())
+}
+
+/// Like `decode_tags`, but also collects the problems with the tags into
+/// `diagnostics`.
+///
+/// Unknown tags, and tags that `tag_callback` fails to parse, are skipped
+/// using their declared length. A tag that is cut off by the end of the data
+/// ends the decoding with an error.
+pub fn decode_tags_with_diagnostics<'a, R, F>(
+    reader: &'a mut SwfStream<R>,
+    tag_callback: F,
     stop_tag: TagCode,
+    diagnostics: &mut Vec<TagDiagnostic>,
 ) -> Result<(), Box<dyn std::error::Error>>
+where
+    R: 'a + AsRef<[u8]>,
+    F: FnMut(&mut SwfStream<R>, TagCode, usize) -> DecodeResult,
+{
+    if decode_loaded_tags(reader, tag_callback, stop_tag, diagnostics)? {
+        Ok(())
+    } else {
+        diagnostics.extend(truncated_tag_diagnostic(reader));
+        Err("Unexpected end of tag data".into())
+    }
+}
+
+/// Like `decode_tags_with_diagnostics`, but for data that may still be
+/// loading.
+///
+/// Returns whether `stop_tag` was reached. If the data ends first, the reader
+/// is left at the start of the tag that hasn't fully loaded yet, so decoding
+/// can carry on from there once more data has loaded.
+pub fn decode_loaded_tags<'a, R, F>(
+    reader: &'a mut SwfStream<R>,
+    mut tag_callback: F,
+    stop_tag: TagCode,
+    diagnostics: &mut Vec<TagDiagnostic>,
+) -> Result<bool, Box<dyn std::error::Error>>
 where
     R: 'a + AsRef<[u8]>,
     F: FnMut(&mut SwfStream<R>, TagCode, usize) -> DecodeResult,
 {
     use std::io::{Seek, SeekFrom};
     loop {
-        let (tag_code, tag_len) = reader.read_tag_code_and_length()?;
+        let tag_start = reader.get_ref().position();
+        let (tag_code, tag_len) = match reader.read_tag_code_and_length() {
+            Ok(tag_code_and_length) => tag_code_and_length,
+            Err(_) => {
+                reader.get_mut().set_position(tag_start);
+                return Ok(false);
+            }
+        };
         let end_pos = reader.get_ref().position() + tag_len as u64;
+        if end_pos > reader.get_ref().get_ref().as_ref().len() as u64 {
+            // The data ends partway through this tag.
+            reader.get_mut().set_position(tag_start);
+            return Ok(false);
+        }
 
         let tag = TagCode::from_u16(tag_code);
         if let Some(tag) = tag {
@@ -265,18 +923,90 @@ where
 
             if let Err(e) = result {
                 log::error!("Error running definition tag: {:?}, got {}", tag, e);
+                diagnostics.push(TagDiagnostic {
+                    tag_code,
+                    message: format!("Error running definition tag {:?}: {}", tag, e),
+                });
             }
 
             if stop_tag == tag {
                 reader.get_mut().seek(SeekFrom::Start(end_pos))?;
-                break;
+                return Ok(true);
             }
         } else {
             log::warn!("Unknown tag code: {:?}", tag_code);
+            diagnostics.push(TagDiagnostic {
+                tag_code,
+                message: format!("Unknown tag code {}", tag_code),
+            });
         }
 
         reader.get_mut().seek(SeekFrom::Start(end_pos))?;
     }
+}
 
-    Ok(())
+/// The diagnostic for a tag at the current position of `reader` that is cut
+/// off by the end of the data, if the header of the tag is there at all.
+pub fn truncated_tag_diagnostic<R: AsRef<[u8]>>(reader: &SwfStream<R>) -> Option<TagDiagnostic> {
+    let data = reader.get_ref().get_ref().as_ref();
+    let remaining = data.get(reader.get_ref().position() as usize..)?;
+    let (tag_code, _) = swf::read::Reader::new(remaining, reader.version())
+        .read_tag_code_and_length()
+        .ok()?;
+    Some(TagDiagnostic {
+        tag_code,
+        message: "Unexpected end of tag data".to_string(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pairs(parameters: &[(&str, &str)]) -> Vec<(String, String)> {
+        parameters
+            .iter()
+            .map(|(key, value)| (key.to_string(), value.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn parameters_from_url() {
+        assert_eq!(
+            url_parameters("https://example.com/game.swf?level=3&user=a%20b#top"),
+            pairs(&[("level", "3"), ("user", "a b")])
+        );
+        assert_eq!(
+            url_parameters("games/game.swf?level=3&user=abc"),
+            pairs(&[("level", "3"), ("user", "abc")])
+        );
+        assert!(url_parameters("game.swf").is_empty());
+    }
+
+    #[test]
+    fn stream_compressed_movie() {
+        let data = std::fs::read("tests/swfs/avm1/array_concat/test.swf").unwrap();
+        let expected = SwfMovie::from_data(&data, None).unwrap();
+        for &chunk_size in &[1, 7, 4096] {
+            let mut stream = SwfMovieStream::new(None, Some(data.len()));
+            let mut movie = None;
+            for chunk in data.chunks(chunk_size) {
+                stream.append(chunk).unwrap();
+                movie = movie.or_else(|| stream.movie());
+                if let Some(movie) = &movie {
+                    assert!(expected.data().starts_with(movie.data()));
+                    assert!(!movie.is_loaded());
+                }
+            }
+
+            let movie = movie.unwrap();
+            let finished = stream.finish().unwrap();
+            assert_eq!(finished.header(), expected.header());
+            assert_eq!(finished.data(), expected.data());
+            assert_eq!(movie.data(), expected.data());
+            assert!(movie.is_loaded());
+            assert_eq!(movie.bytes_loaded(), data.len());
+            assert_eq!(movie.compressed_length(), data.len());
+        }
+    }
 }
@@ -10,7 +10,7 @@ fn parse_single_element() {
     rootless_arena(|mc| {
         let xml = XMLDocument::new(mc);
         xml.as_node()
-            .replace_with_str(mc, "<test></test>", true)
+            .replace_with_str(mc, "<test></test>", true, false)
             .expect("Parsed document");
         let mut roots = xml
             .as_node()
@@ -38,6 +38,7 @@ fn double_ended_children() {
                 mc,
                 "<test></test><test2></test2><test3></test3><test4></test4><test5></test5>",
                 true,
+                false,
             )
             .expect("Parsed document");
 
@@ -82,6 +83,7 @@ fn walk() {
                 mc,
                 "<test><test2></test2></test><test3>test</test3><test4><test5></test5></test4>",
                 true,
+                false,
             )
             .expect("Parsed document");
 
@@ -163,7 +165,7 @@ fn round_trip_tostring() {
     rootless_arena(|mc| {
         let xml = XMLDocument::new(mc);
         xml.as_node()
-            .replace_with_str(mc, test_string, true)
+            .replace_with_str(mc, test_string, true, false)
             .expect("Parsed document");
 
         let result = xml
@@ -183,7 +185,7 @@ fn round_trip_filtered_tostring() {
     rootless_arena(|mc| {
         let xml = XMLDocument::new(mc);
         xml.as_node()
-            .replace_with_str(mc, test_string, true)
+            .replace_with_str(mc, test_string, true, false)
             .expect("Parsed document");
 
         let result = xml
@@ -194,3 +196,45 @@ fn round_trip_filtered_tostring() {
         assert_eq!("<test>This is a text node</test>", result);
     })
 }
+
+/// Tests that whitespace-only text nodes are discarded when requested.
+#[test]
+fn ignore_white() {
+    rootless_arena(|mc| {
+        let xml = XMLDocument::new(mc);
+        xml.as_node()
+            .replace_with_str(mc, "<test>\n  <test2> text </test2>\n</test>", true, true)
+            .expect("Parsed document");
+
+        let mut roots = xml
+            .as_node()
+            .children()
+            .expect("Parsed document should be capable of having child nodes");
+        let root = roots.next().expect("Parsed document should have a root");
+
+        let mut root_children = root.children().unwrap();
+        let child = root_children.next().expect("Root should have a child");
+        assert_eq!(child.tag_name(), Some(XMLName::from_str("test2")));
+        assert!(root_children.next().is_none());
+
+        let text = child.children().unwrap().next().expect("Text node");
+        assert_eq!(text.node_value().as_deref(), Some(" text "));
+    })
+}
+
+/// Tests that entities in attribute values are decoded.
+#[test]
+fn attribute_entities() {
+    rootless_arena(|mc| {
+        let xml = XMLDocument::new(mc);
+        xml.as_node()
+            .replace_with_str(mc, "<test attr=\"a &amp; &lt;b&gt;\" />", true, false)
+            .expect("Parsed document");
+
+        let root = xml.as_node().children().unwrap().next().unwrap();
+        assert_eq!(
+            root.attribute_value(&XMLName::from_str("attr")).as_deref(),
+            Some("a & <b>")
+        );
+    })
+}
@@ -222,11 +222,16 @@ impl<'gc> XMLNode<'gc> {
     /// If `process_entity` is `true`, then entities will be processed by this
     /// function. Invalid or unrecognized entities will cause parsing to fail
     /// with an `Err`.
+    ///
+    /// If `ignore_white` is `true`, then text nodes consisting only of
+    /// whitespace will be discarded, like the `ignoreWhite` property of AVM1
+    /// `XML` documents.
     pub fn replace_with_str(
         &mut self,
         mc: MutationContext<'gc, '_>,
         data: &str,
         process_entity: bool,
+        ignore_white: bool,
     ) -> Result<(), Error> {
         let mut parser = Reader::from_str(data);
         let mut buf = Vec::new();
@@ -242,13 +247,13 @@ impl<'gc> XMLNode<'gc> {
 
             match event {
                 Event::Start(bs) => {
-                    let child = XMLNode::from_start_event(mc, bs, document)?;
+                    let child = XMLNode::from_start_event(mc, bs, document, process_entity)?;
                     self.document().update_idmap(mc, child);
                     self.add_child_to_tree(mc, &mut open_tags, child)?;
                     open_tags.push(child);
                 }
                 Event::Empty(bs) => {
-                    let child = XMLNode::from_start_event(mc, bs, document)?;
+                    let child = XMLNode::from_start_event(mc, bs, document, process_entity)?;
                     self.document().update_idmap(mc, child);
                     self.add_child_to_tree(mc, &mut open_tags, child)?;
                 }
@@ -257,7 +262,12 @@ impl<'gc> XMLNode<'gc> {
                 }
                 Event::Text(bt) => {
                     let child = XMLNode::text_from_text_event(mc, bt, document, process_entity)?;
-                    if child.node_value().as_deref() != Some("") {
+                    let is_empty = match child.node_value() {
+                        Some(text) if ignore_white => text.trim().is_empty(),
+                        Some(text) => text.is_empty(),
+                        None => true,
+                    };
+                    if !is_empty {
                         self.add_child_to_tree(mc, &mut open_tags, child)?;
                     }
                 }
@@ -285,19 +295,28 @@ impl<'gc> XMLNode<'gc> {
     ///
     /// The returned node will always be an `Element`, and it must only contain
     /// valid encoded UTF-8 data. (Other encoding support is planned later.)
+    ///
+    /// If `process_entity` is `true`, then entities in attribute values will
+    /// be decoded.
     pub fn from_start_event<'a>(
         mc: MutationContext<'gc, '_>,
         bs: BytesStart<'a>,
         document: XMLDocument<'gc>,
+        process_entity: bool,
     ) -> Result<Self, Error> {
         let tag_name = XMLName::from_bytes(bs.name())?;
         let mut attributes = BTreeMap::new();
 
         for a in bs.attributes() {
             let attribute = a?;
+            let value = if process_entity {
+                attribute.unescaped_value()?.into_owned()
+            } else {
+                attribute.value.to_owned().to_vec()
+            };
             attributes.insert(
                 XMLName::from_bytes(attribute.key)?,
-                String::from_utf8(attribute.value.to_owned().to_vec())?,
+                String::from_utf8(value)?,
             );
         }
 
@@ -3,16 +3,38 @@
 //! Trace output can be compared with correct output from the official Flash Payer.
 
 use approx::assert_abs_diff_eq;
-use log::{Metadata, Record};
-use ruffle_core::backend::navigator::{NullExecutor, NullNavigatorBackend};
-use ruffle_core::backend::storage::MemoryStorageBackend;
+use ruffle_core::backend::log::LogBackend;
+use ruffle_core::backend::navigator::{
+    NavigationMethod, NavigationTarget, NavigatorBackend, NullExecutor, NullNavigatorBackend,
+    OwnedFuture, RequestOptions, SocketEvent, SocketHandle,
+};
+use ruffle_core::backend::render::{
+    Bitmap, BitmapCacheHandle, BitmapHandle, BitmapInfo, Color, Letterbox, NullRenderer,
+    RenderBackend, ShapeHandle, Transform,
+};
+use ruffle_core::backend::storage::{MemoryStorageBackend, StorageBackend};
 use ruffle_core::backend::{
-    audio::NullAudioBackend, input::NullInputBackend, render::NullRenderer,
+    audio::{
+        AudioBackend, AudioStreamHandle, NullAudioBackend, SoundHandle, SoundInstanceHandle,
+        SoundTransform,
+    },
+    font::NullFontBackend,
+    input::NullInputBackend,
+    video::{software::SoftwareVideoBackend, NullVideoBackend, VideoBackend},
 };
-use ruffle_core::tag_utils::SwfMovie;
-use ruffle_core::Player;
-use std::cell::RefCell;
+use ruffle_core::external::{ExternalInterfaceProvider, Value as ExternalValue};
+use ruffle_core::loader;
+use ruffle_core::shape_utils::{DistilledShape, DrawPath};
+use ruffle_core::swf;
+use ruffle_core::tag_utils::{SwfMovie, SwfMovieStream};
+use ruffle_core::StageQuality;
+use ruffle_core::{Player, PlayerEvent};
+use std::cell::{Cell, RefCell};
+use std::collections::{BTreeMap, HashMap};
 use std::path::Path;
+use std::rc::Rc;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
 
 type Error = Box<dyn std::error::Error>;
 
@@ -58,6 +80,7 @@ macro_rules! swf_tests_approx {
 swf_tests! {
     (add_property, "avm1/add_property", 1),
     (as_transformed_flag, "avm1/as_transformed_flag", 3),
+    (attach_bitmap, "avm1/attach_bitmap", 1),
     (attach_movie, "avm1/attach_movie", 1),
     (function_base_clip, "avm1/function_base_clip", 2),
     (call, "avm1/call", 2),
@@ -66,8 +89,11 @@ swf_tests! {
     (create_empty_movie_clip, "avm1/create_empty_movie_clip", 2),
     (empty_movieclip_can_attach_movies, "avm1/empty_movieclip_can_attach_movies", 1),
     (duplicate_movie_clip, "avm1/duplicate_movie_clip", 1),
+    (duplicate_movie_clip_drawing, "avm1/duplicate_movie_clip_drawing", 1),
     (mouse_listeners, "avm1/mouse_listeners", 1),
     (do_init_action, "avm1/do_init_action", 3),
+    (do_init_action_register_class, "avm1/do_init_action_register_class", 1),
+    (frames_loaded_truncated, "avm1/frames_loaded_truncated", 3),
     (execution_order1, "avm1/execution_order1", 3),
     (execution_order2, "avm1/execution_order2", 15),
     (execution_order3, "avm1/execution_order3", 5),
@@ -87,6 +113,7 @@ swf_tests! {
     (goto_rewind1, "avm1/goto_rewind1", 4),
     (goto_rewind2, "avm1/goto_rewind2", 5),
     (goto_rewind3, "avm1/goto_rewind3", 2),
+    (goto_rewind_persistence, "avm1/goto_rewind_persistence", 3),
     (goto_execution_order, "avm1/goto_execution_order", 3),
     (goto_execution_order2, "avm1/goto_execution_order2", 2),
     (greaterthan_swf5, "avm1/greaterthan_swf5", 1),
@@ -129,6 +156,7 @@ swf_tests! {
     (logical_ops_swf4, "avm1/logical_ops_swf4", 1),
     (logical_ops_swf8, "avm1/logical_ops_swf8", 1),
     (movieclip_depth_methods, "avm1/movieclip_depth_methods", 3),
+    (attach_remove_stress, "avm1/attach_remove_stress", 2),
     (get_variable_in_scope, "avm1/get_variable_in_scope", 1),
     (movieclip_init_object, "avm1/movieclip_init_object", 1),
     (greater_swf6, "avm1/greater_swf6", 1),
@@ -139,6 +167,7 @@ swf_tests! {
     (equals2_swf7, "avm1/equals2_swf7", 1),
     (register_class, "avm1/register_class", 1),
     (register_and_init_order, "avm1/register_and_init_order", 1),
+    (init_order, "avm1/init_order", 1),
     (on_construct, "avm1/on_construct", 1),
     (set_variable_scope, "avm1/set_variable_scope", 1),
     (slash_syntax, "avm1/slash_syntax", 2),
@@ -174,6 +203,7 @@ swf_tests! {
     (undefined_to_string_swf6, "avm1/undefined_to_string_swf6", 1),
     (define_function2_preload, "avm1/define_function2_preload", 1),
     (define_function2_preload_order, "avm1/define_function2_preload_order", 1),
+    (define_function2_flags, "avm1/define_function2_flags", 1),
     (mcl_as_broadcaster, "avm1/mcl_as_broadcaster", 1),
     (uncaught_exception, "avm1/uncaught_exception", 1),
     (uncaught_exception_bubbled, "avm1/uncaught_exception_bubbled", 1),
@@ -185,9 +215,13 @@ swf_tests! {
     (unloadmovie, "avm1/unloadmovie", 11),
     (unloadmovienum, "avm1/unloadmovienum", 11),
     (unloadmovie_method, "avm1/unloadmovie_method", 11),
+    (unload_deferred_removal, "avm1/unload_deferred_removal", 3),
+    (dead_clip_references, "avm1/dead_clip_references", 3),
     (mcl_loadclip, "avm1/mcl_loadclip", 11),
     (mcl_unloadclip, "avm1/mcl_unloadclip", 11),
+    (loadmovie_image, "avm1/loadmovie_image", 6),
     (mcl_getprogress, "avm1/mcl_getprogress", 6),
+    (loadmovie_progress, "avm1/loadmovie_progress", 2),
     (loadvariables, "avm1/loadvariables", 3),
     (loadvariablesnum, "avm1/loadvariablesnum", 3),
     (loadvariables_method, "avm1/loadvariables_method", 3),
@@ -199,10 +233,12 @@ swf_tests! {
     (swf7_case_sensitive, "avm1/swf7_case_sensitive", 1),
     (prototype_enumerate, "avm1/prototype_enumerate", 1),
     (stage_object_enumerate, "avm1/stage_object_enumerate", 1),
+    (for_in_display_object_children, "avm1/for_in_display_object_children", 2),
     (new_object_enumerate, "avm1/new_object_enumerate", 1),
     (as2_super_and_this_v6, "avm1/as2_super_and_this_v6", 1),
     (as2_super_and_this_v8, "avm1/as2_super_and_this_v8", 1),
     (as2_super_via_manual_prototype, "avm1/as2_super_via_manual_prototype", 1),
+    (as2_interfaces_and_casts, "avm1/as2_interfaces_and_casts", 1),
     (as1_constructor_v6, "avm1/as1_constructor_v6", 1),
     (as1_constructor_v7, "avm1/as1_constructor_v7", 1),
     (issue_710, "avm1/issue_710", 1),
@@ -219,6 +255,26 @@ swf_tests! {
     (with, "avm1/with", 1),
     (prototype_properties, "avm1/prototype_properties", 1),
     (stage_object_properties_get_var, "avm1/stage_object_properties_get_var", 1),
+    (bitmap_data, "avm1/bitmap_data", 1),
+    (color_removed_target, "avm1/color_removed_target", 1),
+    (sound_transform_owner, "avm1/sound_transform_owner", 1),
+    (global_sound_transform, "avm1/global_sound_transform", 1),
+    (sound_complete, "avm1/sound_complete", 5),
+    (load_vars_decode, "avm1/load_vars_decode", 1),
+    (local_connection_sends_copies, "avm1/local_connection_sends_copies", 2),
+    (string_methods_edge_cases, "avm1/string_methods_edge_cases", 1),
+    (print_job_unavailable, "avm1/print_job_unavailable", 1),
+    (net_connection_connect, "avm1/net_connection_connect", 1),
+    (try_catch_nested, "avm1/try_catch_nested", 1),
+    (with_scope, "avm1/with_scope", 1),
+    (function_apply_call, "avm1/function_apply_call", 1),
+    (as3_hello_world, "avm2/hello_world", 1),
+    (as3_document_class, "avm2/document_class", 5),
+    (as3_exceptions, "avm2/exceptions", 1),
+    (as3_coercions, "avm2/coercions", 1),
+    (as3_shape_enter_frame, "avm2/shape_enter_frame", 5),
+    (as3_event_phases, "avm2/event_phases", 1),
+    (as3_stage_events, "avm2/stage_events", 1),
 }
 
 // TODO: These tests have some inaccuracies currently, so we use approx_eq to test that numeric values are close enough.
@@ -290,7 +346,7 @@ fn test_swf_approx(
 ) -> Result<(), Error> {
     let trace_log = run_swf(swf_path, num_frames)?;
     let expected_data = std::fs::read_to_string(expected_output_path)?;
-    std::assert_eq!(
+    pretty_assertions::assert_eq!(
         trace_log.lines().count(),
         expected_data.lines().count(),
         "# of lines of output didn't match"
@@ -308,22 +364,906 @@ fn test_swf_approx(
     Ok(())
 }
 
-/// Loads an SWF and runs it through the Ruffle core for a number of frames.
-/// Tests that the trace output matches the given expected output.
-fn run_swf(swf_path: &str, num_frames: u32) -> Result<String, Error> {
-    let _ = log::set_logger(&TRACE_LOGGER).map(|()| log::set_max_level(log::LevelFilter::Info));
+/// Tests that frames in which nothing on the stage changes are not rendered again.
+#[test]
+fn static_frames_skip_render() -> Result<(), Error> {
+    // The clip's timeline moves its child on frames 1 and 3, and leaves it in place on
+    // frames 2 and 4.
+    let (player, mut executor) = create_player(
+        "tests/swfs/avm1/static_frames_skip_render/test.swf",
+        Box::new(NullRenderer),
+    )?;
 
-    let base_path = Path::new(swf_path).parent().unwrap();
-    let (mut executor, channel) = NullExecutor::new();
-    let movie = SwfMovie::from_path(swf_path)?;
-    let player = Player::new(
+    let mut rendered = vec![];
+    for _ in 0..8 {
+        let mut player = player.lock().unwrap();
+        player.run_frame();
+        rendered.push(player.needs_render());
+        if player.needs_render() {
+            player.render();
+        }
+        drop(player);
+        executor.poll_all().unwrap();
+    }
+
+    pretty_assertions::assert_eq!(
+        rendered,
+        vec![true, false, true, false, true, false, true, false]
+    );
+    Ok(())
+}
+
+/// Tests that a paused player only advances when stepped, and that the speed scales its clock.
+#[test]
+fn paused_player_steps_frames() -> Result<(), Error> {
+    let (player, _executor) = create_player(
+        "tests/swfs/avm1/static_frames_skip_render/test.swf",
         Box::new(NullRenderer),
-        Box::new(NullAudioBackend::new()),
-        Box::new(NullNavigatorBackend::with_base_path(base_path, channel)),
-        Box::new(NullInputBackend::new()),
+    )?;
+    let mut player = player.lock().unwrap();
+    let frame_time = (1000.0 / player.frame_rate()) as u64;
+
+    player.tick(1000.0);
+    pretty_assertions::assert_eq!(player.elapsed_time(), 0);
+
+    player.step_frame();
+    player.step_frame();
+    pretty_assertions::assert_eq!(player.elapsed_time(), 2 * frame_time);
+
+    player.set_speed(2.0);
+    player.set_is_playing(true);
+    player.tick(frame_time as f64);
+    pretty_assertions::assert_eq!(player.elapsed_time(), 4 * frame_time);
+    Ok(())
+}
+
+/// Tests that instances of a shape share its mesh, and that `unloadMovie` frees it.
+#[test]
+fn unload_movie_releases_shapes() -> Result<(), Error> {
+    let (player, mut executor) = create_player(
+        "tests/swfs/avm1/unload_movie_releases_shapes/test.swf",
+        Box::new(ShapeCountingRenderer::default()),
+    )?;
+    let shapes = |player: &Arc<Mutex<Player>>| {
+        let player = player.lock().unwrap();
+        let renderer = player
+            .renderer()
+            .downcast_ref::<ShapeCountingRenderer>()
+            .unwrap();
+        (renderer.registered.clone(), renderer.released.clone())
+    };
+
+    // The child movie attaches 1,000 clips showing the same shape.
+    for _ in 0..2 {
+        player.lock().unwrap().run_frame();
+        executor.poll_all().unwrap();
+    }
+    let (registered, released) = shapes(&player);
+    pretty_assertions::assert_eq!(registered.len(), 1);
+    assert!(released.is_empty());
+
+    // The child movie is unloaded.
+    player.lock().unwrap().run_frame();
+    let (_, released) = shapes(&player);
+    pretty_assertions::assert_eq!(released, registered);
+
+    assert_eq!(trace_log(), "attached\nunloaded\n");
+    Ok(())
+}
+
+/// Tests that loading movies into a clip over and over does not leak the resources
+/// of the movies it replaces.
+#[test]
+fn load_unload_releases_resources() -> Result<(), Error> {
+    let (player, mut executor) = create_player(
+        "tests/swfs/avm1/load_unload_resources/test.swf",
+        Box::new(ShapeCountingRenderer::default()),
+    )?;
+    let counts = |player: &Arc<Mutex<Player>>| {
+        let player = player.lock().unwrap();
+        let renderer = player
+            .renderer()
+            .downcast_ref::<ShapeCountingRenderer>()
+            .unwrap();
+        (
+            renderer.registered.len(),
+            renderer.released.len(),
+            renderer.registered_bitmaps.len(),
+            renderer.released_bitmaps.len(),
+        )
+    };
+
+    // The child movie, with a shape and a bitmap, is loaded into the same clip
+    // 100 times, so at most one copy of its resources is ever alive.
+    for _ in 0..250 {
+        player.lock().unwrap().run_frame();
+        executor.poll_all().unwrap();
+        let (shapes, released_shapes, bitmaps, released_bitmaps) = counts(&player);
+        assert!(shapes - released_shapes <= 1);
+        assert!(bitmaps - released_bitmaps <= 1);
+    }
+
+    // The clip is then unloaded, freeing the last copy.
+    pretty_assertions::assert_eq!(counts(&player), (100, 100, 100, 100));
+    assert_eq!(trace_log(), "done\n");
+    Ok(())
+}
+
+/// Tests that unloading a duplicate of a loaded movie keeps the resources that the
+/// original clip still uses.
+#[test]
+fn duplicate_loaded_movie_keeps_resources() -> Result<(), Error> {
+    let (player, mut executor) = create_player(
+        "tests/swfs/avm1/duplicate_loaded_movie/test.swf",
+        Box::new(ShapeCountingRenderer::default()),
+    )?;
+    let released = |player: &Arc<Mutex<Player>>| {
+        let player = player.lock().unwrap();
+        let renderer = player
+            .renderer()
+            .downcast_ref::<ShapeCountingRenderer>()
+            .unwrap();
+        (renderer.released.len(), renderer.released_bitmaps.len())
+    };
+
+    // The child movie is loaded into a clip.
+    player.lock().unwrap().run_frame();
+    executor.poll_all().unwrap();
+
+    // The clip is duplicated, and the duplicate is unloaded.
+    player.lock().unwrap().run_frame();
+    pretty_assertions::assert_eq!(released(&player), (0, 0));
+
+    // The original clip is unloaded.
+    player.lock().unwrap().run_frame();
+    pretty_assertions::assert_eq!(released(&player), (1, 1));
+    assert_eq!(trace_log(), "copy unloaded\nholder unloaded\n");
+    Ok(())
+}
+
+/// Tests that `attachBitmap` draws bitmaps with the smoothing they were attached with.
+#[test]
+fn attach_bitmap_smoothing() -> Result<(), Error> {
+    let (player, _executor) = create_player(
+        "tests/swfs/avm1/attach_bitmap_smoothing/test.swf",
+        Box::new(ShapeCountingRenderer::default()),
+    )?;
+    let mut player = player.lock().unwrap();
+    player.run_frame();
+    player.render();
+
+    let renderer = player
+        .renderer()
+        .downcast_ref::<ShapeCountingRenderer>()
+        .unwrap();
+    pretty_assertions::assert_eq!(renderer.rendered_bitmaps, vec![false, true, false]);
+    assert_eq!(trace_log(), "attached\n");
+    Ok(())
+}
+
+/// Tests that corrupt tags are skipped, and recorded as diagnostics of the movie.
+#[test]
+fn corrupt_tags_are_skipped() -> Result<(), Error> {
+    // Frame 1 has an unknown tag and a DefineShape with a cut-off body. Frame 2 has a
+    // DoAction with an invalid action. The last tag is cut off by the end of the file,
+    // so the movie stops after frame 2.
+    let (player, mut executor) = create_player(
+        "tests/swfs/avm1/corrupt_tags/test.swf",
+        Box::new(NullRenderer),
+    )?;
+    for _ in 0..3 {
+        player.lock().unwrap().run_frame();
+        executor.poll_all().unwrap();
+    }
+
+    assert_eq!(trace_log(), "frame 1\nframe 2\n");
+    pretty_assertions::assert_eq!(player.lock().unwrap().current_frame(), 2);
+    let tag_codes: Vec<u16> = player
+        .lock()
+        .unwrap()
+        .diagnostics()
+        .iter()
+        .map(|diagnostic| diagnostic.tag_code)
+        .collect();
+    pretty_assertions::assert_eq!(tag_codes, vec![1000, 2, 2]);
+    Ok(())
+}
+
+/// Tests that drawing API commands from earlier frames are not tessellated again.
+#[test]
+fn drawing_incremental_tessellation() -> Result<(), Error> {
+    // Every frame starts a new stroke and draws 10,000 segments onto it.
+    let (player, mut executor) = create_player(
+        "tests/swfs/avm1/drawing_incremental_tessellation/test.swf",
+        Box::new(ShapeCountingRenderer::default()),
+    )?;
+
+    let mut tessellated = 0;
+    for _ in 0..6 {
+        let mut player = player.lock().unwrap();
+        player.run_frame();
+        player.render();
+        let renderer = player
+            .renderer()
+            .downcast_ref::<ShapeCountingRenderer>()
+            .unwrap();
+
+        // At most the stroke completed on the previous frame and the current stroke.
+        let frame_commands = renderer.tessellated_commands - tessellated;
+        assert!(frame_commands <= 2 * 10_001, "{}", frame_commands);
+        tessellated = renderer.tessellated_commands;
+
+        drop(player);
+        executor.poll_all().unwrap();
+    }
+    Ok(())
+}
+
+/// Tests that the timeline keeps pace with its stream sound, and that seeking restarts the
+/// stream at the block of the new frame.
+#[test]
+fn stream_sound_sync() -> Result<(), Error> {
+    // Every frame has a block of the stream, and frame 8 runs `gotoAndPlay(3)`.
+    let clock = Arc::new(Mutex::new(StreamClock::default()));
+    let (player, _executor) = create_player_with_audio(
+        "tests/swfs/avm1/stream_sound_sync/test.swf",
+        Box::new(NullRenderer),
+        Box::new(StreamClockAudioBackend::new(Arc::clone(&clock))),
+    )?;
+    let mut player = player.lock().unwrap();
+    player.set_is_playing(true);
+    let frame_time = 1000.0 / player.frame_rate();
+    let tick = |player: &mut Player, time: u32| {
+        clock.lock().unwrap().time = time;
+        player.tick(frame_time);
+        player.current_frame()
+    };
+
+    // The stream starts on frame 1, and the timeline waits while its audio doesn't play.
+    pretty_assertions::assert_eq!(tick(&mut player, 0), 1);
+    pretty_assertions::assert_eq!(tick(&mut player, 0), 1);
+    // Once the audio is 5 frames ahead, the timeline skips ahead to catch up.
+    pretty_assertions::assert_eq!(tick(&mut player, 210), 6);
+    // Frame 8 seeks back to frame 3, which restarts the stream there.
+    pretty_assertions::assert_eq!(tick(&mut player, 300), 3);
+    pretty_assertions::assert_eq!(tick(&mut player, 300), 3);
+    pretty_assertions::assert_eq!(tick(&mut player, 342), 4);
+
+    pretty_assertions::assert_eq!(clock.lock().unwrap().started_frames, vec![1, 3]);
+    Ok(())
+}
+
+/// Tests which objects AS3 mouse events are dispatched to, the path they propagate along,
+/// and the clicks that are made from presses and releases.
+#[test]
+fn as3_mouse_events() -> Result<(), Error> {
+    // Every listener traces the current target, the event type, the target and the phase.
+    // The stage captures and handles clicks, and the root handles them.
+    let (player, _executor) = create_player(
+        "tests/swfs/avm2/mouse_events/test.swf",
+        Box::new(NullRenderer),
+    )?;
+    let mut player = player.lock().unwrap();
+    player.set_is_playing(true);
+    player.run_frame();
+
+    // A plain sprite.
+    click(&mut player, 25.0, 25.0);
+    // A sprite with `mouseEnabled` off, and its child.
+    click(&mut player, 125.0, 25.0);
+    click(&mut player, 125.0, 85.0);
+    // The child of a sprite with `mouseChildren` off.
+    click(&mut player, 225.0, 25.0);
+    // Nested sprites.
+    click(&mut player, 325.0, 25.0);
+    // Nested sprites, where the parent stops the propagation of clicks, then immediately.
+    click(&mut player, 425.0, 25.0);
+    click(&mut player, 425.0, 125.0);
+    // A sprite with `doubleClickEnabled` clicked three times, then another without it
+    // clicked twice.
+    for _ in 0..3 {
+        click(&mut player, 25.0, 125.0);
+    }
+    for _ in 0..2 {
+        click(&mut player, 125.0, 125.0);
+    }
+    // Clicks that are too far apart don't make a double click.
+    click(&mut player, 25.0, 125.0);
+    player.tick(600.0);
+    click(&mut player, 25.0, 125.0);
+    // Pressing on one sprite and releasing on another doesn't click either.
+    player.handle_event(PlayerEvent::MouseMove { x: 25.0, y: 25.0 });
+    player.handle_event(PlayerEvent::MouseDown { x: 25.0, y: 25.0 });
+    player.handle_event(PlayerEvent::MouseMove { x: 25.0, y: 125.0 });
+    player.handle_event(PlayerEvent::MouseUp { x: 25.0, y: 125.0 });
+    // The stage, outside of any object.
+    click(&mut player, 500.0, 350.0);
+
+    let expected_output =
+        std::fs::read_to_string("tests/swfs/avm2/mouse_events/output.txt")?.replace("\r\n", "\n");
+    assert_eq!(trace_log(), expected_output);
+    Ok(())
+}
+
+/// Tests that AS3 `loaderInfo.parameters` holds the variables from the query
+/// string of the movie's URL and its FlashVars.
+#[test]
+fn as3_loader_info_parameters() -> Result<(), Error> {
+    let swf_path = "tests/swfs/avm2/loader_info_parameters/test.swf";
+    let data = std::fs::read(swf_path)?;
+    let mut movie = SwfMovie::from_data(
+        &data,
+        Some("http://example.com/test.swf?fromUrl=query".to_string()),
+    )?;
+    movie.append_parameters(vec![("flashVar".to_string(), "flash vars".to_string())]);
+    let (player, _executor) = create_player_with_movie(
+        Path::new(swf_path).parent().unwrap(),
         movie,
+        Box::new(NullRenderer),
+        Box::new(NullAudioBackend::new()),
+    )?;
+    player.lock().unwrap().run_frame();
+
+    let expected_output =
+        std::fs::read_to_string("tests/swfs/avm2/loader_info_parameters/output.txt")?
+            .replace("\r\n", "\n");
+    assert_eq!(trace_log(), expected_output);
+    Ok(())
+}
+
+/// Tests that a movie starts playing while it streams in, and that its playhead
+/// waits on frames that haven't loaded yet.
+#[test]
+fn frames_loaded_streaming() -> Result<(), Error> {
+    let swf_path = "tests/swfs/avm1/frames_loaded_streaming/test.swf";
+    let data = std::fs::read(swf_path)?;
+    let mut chunks = data.chunks(32);
+    let mut stream = SwfMovieStream::new(None, Some(data.len()));
+    while stream.movie().is_none() {
+        stream.append(chunks.next().unwrap())?;
+    }
+    let (player, _executor) = create_player_with_movie(
+        Path::new(swf_path).parent().unwrap(),
+        stream.movie().unwrap(),
+        Box::new(NullRenderer),
+        Box::new(NullAudioBackend::new()),
+    )?;
+
+    // One more chunk of the movie loads on every frame.
+    let mut stream = Some(stream);
+    for _ in 0..16 {
+        player.lock().unwrap().run_frame();
+        match chunks.next() {
+            Some(chunk) => stream.as_mut().unwrap().append(chunk)?,
+            None => {
+                if let Some(stream) = stream.take() {
+                    stream.finish()?;
+                }
+            }
+        }
+    }
+
+    let expected_output =
+        std::fs::read_to_string("tests/swfs/avm1/frames_loaded_streaming/output.txt")?
+            .replace("\r\n", "\n");
+    assert_eq!(trace_log(), expected_output);
+    Ok(())
+}
+
+/// Tests that a movie loaded with `loadMovieNum` starts playing while its data
+/// arrives in chunks.
+#[test]
+fn loadmovie_streaming() -> Result<(), Error> {
+    let swf_path = "tests/swfs/avm1/loadmovie_streaming/test.swf";
+    let (executor, channel) = NullExecutor::new();
+    let mut navigator =
+        NullNavigatorBackend::with_base_path(Path::new(swf_path).parent().unwrap(), channel);
+    navigator.set_chunk_size(32);
+    let (player, mut executor) = create_player_with_navigator(
+        SwfMovie::from_path(swf_path)?,
+        Box::new(NullRenderer),
+        Box::new(NullAudioBackend::new()),
+        Box::new(NullVideoBackend::new()),
+        Box::new(MemoryStorageBackend::default()),
+        Box::new(navigator),
+        executor,
+    )?;
+
+    // The executor takes in one chunk of the child movie every frame.
+    for _ in 0..20 {
+        player.lock().unwrap().run_frame();
+        executor.poll_all().unwrap();
+    }
+
+    let expected_output =
+        std::fs::read_to_string("tests/swfs/avm1/loadmovie_streaming/output.txt")?
+            .replace("\r\n", "\n");
+    assert_eq!(trace_log(), expected_output);
+    Ok(())
+}
+
+/// Tests that a sound loaded with `loadSound` as a stream starts playing once
+/// `_soundbuftime` seconds of it have arrived, and carries on with the complete
+/// sound once it has loaded.
+#[test]
+fn loadsound_streaming() -> Result<(), Error> {
+    let swf_path = "tests/swfs/avm1/loadsound_streaming/test.swf";
+    let (executor, channel) = NullExecutor::new();
+    let mut navigator =
+        NullNavigatorBackend::with_base_path(Path::new(swf_path).parent().unwrap(), channel);
+    navigator.set_chunk_size(4096);
+    let clock = Arc::new(Mutex::new(SoundClock::default()));
+    let (player, mut executor) = create_player_with_navigator(
+        SwfMovie::from_path(swf_path)?,
+        Box::new(NullRenderer),
+        Box::new(SoundClockAudioBackend::new(Arc::clone(&clock))),
+        Box::new(NullVideoBackend::new()),
+        Box::new(MemoryStorageBackend::default()),
+        Box::new(navigator),
+        executor,
+    )?;
+
+    // The executor takes in one chunk of the sound every frame, and the clock
+    // moves on by 100ms.
+    for frame in 0..12 {
+        clock.lock().unwrap().time = frame * 100;
+        player.lock().unwrap().run_frame();
+        executor.poll_all().unwrap();
+    }
+
+    // The partial sound starts from the beginning, and the complete sound from
+    // where the partial sound had got to.
+    pretty_assertions::assert_eq!(
+        clock.lock().unwrap().started_sounds,
+        vec![(1018, None), (2011, Some(22050))]
+    );
+    let expected_output =
+        std::fs::read_to_string("tests/swfs/avm1/loadsound_streaming/output.txt")?
+            .replace("\r\n", "\n");
+    assert_eq!(trace_log(), expected_output);
+    Ok(())
+}
+
+/// Tests that a `NetStream` plays an FLV file while it loads, and that a stream of a
+/// codec that the video backend can't decode, such as Sorenson H.263, fails to play.
+#[test]
+fn netstream_streaming() -> Result<(), Error> {
+    let swf_path = "tests/swfs/avm1/netstream_streaming/test.swf";
+    let (executor, channel) = NullExecutor::new();
+    let mut navigator =
+        NullNavigatorBackend::with_base_path(Path::new(swf_path).parent().unwrap(), channel);
+    navigator.set_chunk_size(40);
+    let (player, mut executor) = create_player_with_navigator(
+        SwfMovie::from_path(swf_path)?,
+        Box::new(NullRenderer),
+        Box::new(NullAudioBackend::new()),
+        Box::new(SoftwareVideoBackend::new()),
+        Box::new(MemoryStorageBackend::default()),
+        Box::new(navigator),
+        executor,
+    )?;
+
+    // The executor takes in one chunk of each file every frame.
+    for _ in 0..12 {
+        player.lock().unwrap().step_frame();
+        executor.poll_all().unwrap();
+    }
+
+    let expected_output =
+        std::fs::read_to_string("tests/swfs/avm1/netstream_streaming/output.txt")?
+            .replace("\r\n", "\n");
+    assert_eq!(trace_log(), expected_output);
+    Ok(())
+}
+
+/// Tests that the data a movie saves in a shared object is read by the next session,
+/// which runs the same movie in a new player.
+#[test]
+fn shared_object_persists() -> Result<(), Error> {
+    let swf_path = "tests/swfs/avm1/shared_object_persists/test.swf";
+    let expected_output =
+        std::fs::read_to_string("tests/swfs/avm1/shared_object_persists/output.txt")?
+            .replace("\r\n", "\n");
+    let storage = SharedStorageBackend::default();
+
+    for session in 0..2 {
+        let (executor, channel) = NullExecutor::new();
+        let navigator =
+            NullNavigatorBackend::with_base_path(Path::new(swf_path).parent().unwrap(), channel);
+        let (player, _executor) = create_player_with_navigator(
+            SwfMovie::from_path(swf_path)?,
+            Box::new(NullRenderer),
+            Box::new(NullAudioBackend::new()),
+            Box::new(NullVideoBackend::new()),
+            Box::new(storage.clone()),
+            Box::new(navigator),
+            executor,
+        )?;
+        player.lock().unwrap().run_frame();
+
+        // The data is saved as a `.sol` file, and cleared by the second session.
+        let files = storage.0.lock().unwrap();
+        if session == 0 {
+            pretty_assertions::assert_eq!(files.len(), 1);
+            assert!(files.values().all(|sol| sol.starts_with(&[0x00, 0xBF])));
+        } else {
+            assert!(files.is_empty());
+        }
+    }
+
+    assert_eq!(trace_log(), expected_output);
+    Ok(())
+}
+
+/// Tests the values passed between a movie and its host with `ExternalInterface`.
+/// The movie has no host for the first frame, and a host that echoes its calls afterwards.
+#[test]
+fn external_interface_values() -> Result<(), Error> {
+    let expected_output =
+        std::fs::read_to_string("tests/swfs/avm1/external_interface_values/output.txt")?
+            .replace("\r\n", "\n");
+    let (player, _executor) = create_player(
+        "tests/swfs/avm1/external_interface_values/test.swf",
+        Box::new(NullRenderer),
+    )?;
+    let mut player = player.lock().unwrap();
+    player.run_frame();
+
+    let host = EchoExternalInterface::default();
+    player.set_external_interface(Box::new(host.clone()));
+    player.run_frame();
+    let result = player.call_internal_interface("double", &[ExternalValue::Number(21.0)]);
+    pretty_assertions::assert_eq!(result, ExternalValue::Number(42.0));
+
+    let mut properties = BTreeMap::new();
+    properties.insert("name".to_string(), ExternalValue::String("Ruffle".into()));
+    properties.insert(
+        "list".to_string(),
+        ExternalValue::List(vec![ExternalValue::Number(1.0), ExternalValue::Null]),
+    );
+    let host = host.0.lock().unwrap();
+    pretty_assertions::assert_eq!(
+        host.calls,
+        vec![("echo".to_string(), vec![ExternalValue::Object(properties)])]
+    );
+    pretty_assertions::assert_eq!(host.callbacks, vec!["double".to_string()]);
+
+    assert_eq!(trace_log(), expected_output);
+    Ok(())
+}
+
+/// Tests the requests made by `GetURL2` for each combination of its flags, as used by
+/// `getURL`, `loadMovie`, `loadMovieNum`, `loadVariables` and `loadVariablesNum`.
+#[test]
+fn get_url_2_flags() -> Result<(), Error> {
+    let swf_path = "tests/swfs/avm1/get_url_2_flags/test.swf";
+    let (executor, channel) = NullExecutor::new();
+    let navigator = RecordingNavigator::new(NullNavigatorBackend::with_base_path(
+        Path::new(swf_path).parent().unwrap(),
+        channel,
+    ));
+    let requests = navigator.requests.clone();
+    let (player, _executor) = create_player_with_navigator(
+        SwfMovie::from_path(swf_path)?,
+        Box::new(NullRenderer),
+        Box::new(NullAudioBackend::new()),
+        Box::new(NullVideoBackend::new()),
         Box::new(MemoryStorageBackend::default()),
+        Box::new(navigator),
+        executor,
     )?;
+    player.lock().unwrap().run_frame();
+
+    use NavigationMethod::{GET, POST};
+    use Request::*;
+    let vars = || r#"{"name": "Ruffle"}"#.to_string();
+    pretty_assertions::assert_eq!(
+        *requests.lock().unwrap(),
+        vec![
+            // getURL
+            Navigate(
+                "page.html".into(),
+                NavigationTarget::SelfWindow,
+                None,
+                String::new()
+            ),
+            Navigate(
+                "page.html".into(),
+                NavigationTarget::Blank,
+                Some(GET),
+                vars()
+            ),
+            Navigate(
+                "page.html".into(),
+                NavigationTarget::Named("frame".into()),
+                Some(POST),
+                vars()
+            ),
+            Navigate(
+                "javascript:alert(1)".into(),
+                NavigationTarget::SelfWindow,
+                None,
+                String::new()
+            ),
+            // loadMovieNum
+            Fetch("movie.swf?name=Ruffle".into(), GET, String::new()),
+            // loadMovie
+            Fetch("movie.swf?a=b&name=Ruffle".into(), GET, String::new()),
+            Fetch("movie.swf".into(), GET, String::new()),
+            // loadVariablesNum
+            Fetch("vars.txt".into(), POST, "name=Ruffle".into()),
+            // loadVariables
+            Fetch("vars.txt".into(), GET, String::new()),
+        ]
+    );
+    Ok(())
+}
+
+/// Tests that a script that runs for too long is aborted, even deep in the stack, and
+/// that the timeout is reported once. The movie carries on playing afterwards.
+#[test]
+fn script_timeout() -> Result<(), Error> {
+    let expected_output =
+        std::fs::read_to_string("tests/swfs/avm1/script_timeout/output.txt")?.replace("\r\n", "\n");
+    let (player, _executor) = create_player(
+        "tests/swfs/avm1/script_timeout/test.swf",
+        Box::new(NullRenderer),
+    )?;
+    let mut player = player.lock().unwrap();
+    player.set_max_actions(10_000);
+    let timeouts = Rc::new(Cell::new(0));
+    let handler_timeouts = timeouts.clone();
+    player.set_script_timeout_handler(Box::new(move || {
+        handler_timeouts.set(handler_timeouts.get() + 1)
+    }));
+
+    player.run_frame();
+    pretty_assertions::assert_eq!(timeouts.get(), 1);
+    player.run_frame();
+    pretty_assertions::assert_eq!(timeouts.get(), 1);
+
+    pretty_assertions::assert_eq!(
+        warning_log(),
+        vec!["A script has run for too long and has been aborted.".to_string()]
+    );
+    assert_eq!(trace_log(), expected_output);
+    Ok(())
+}
+
+/// Moves the mouse to a point of the stage and clicks there.
+fn click(player: &mut Player, x: f64, y: f64) {
+    player.handle_event(PlayerEvent::MouseMove { x, y });
+    player.handle_event(PlayerEvent::MouseDown { x, y });
+    player.handle_event(PlayerEvent::MouseUp { x, y });
+}
+
+/// A renderer that records the shapes and bitmaps that are registered and
+/// released, and counts the drawing commands that it tessellates.
+#[derive(Default)]
+struct ShapeCountingRenderer {
+    registered: Vec<usize>,
+    released: Vec<usize>,
+    registered_bitmaps: Vec<usize>,
+    released_bitmaps: Vec<usize>,
+    tessellated_commands: usize,
+    /// Whether each bitmap that was drawn was smoothed.
+    rendered_bitmaps: Vec<bool>,
+}
+
+impl ShapeCountingRenderer {
+    fn register_bitmap(&mut self, info: Result<BitmapInfo, Error>) -> Result<BitmapInfo, Error> {
+        let mut info = info?;
+        info.handle = BitmapHandle(self.registered_bitmaps.len());
+        self.registered_bitmaps.push(info.handle.0);
+        Ok(info)
+    }
+
+    fn tessellate(&mut self, shape: DistilledShape) {
+        for path in shape.paths {
+            self.tessellated_commands += match path {
+                DrawPath::Fill { commands, .. } => commands.len(),
+                DrawPath::Stroke { commands, .. } => commands.len(),
+            };
+        }
+    }
+}
+
+impl RenderBackend for ShapeCountingRenderer {
+    fn set_viewport_dimensions(&mut self, _width: u32, _height: u32) {}
+    fn register_shape(&mut self, shape: DistilledShape) -> ShapeHandle {
+        self.tessellate(shape);
+        let handle = ShapeHandle(self.registered.len());
+        self.registered.push(handle.0);
+        handle
+    }
+    fn replace_shape(&mut self, shape: DistilledShape, _handle: ShapeHandle) {
+        self.tessellate(shape);
+    }
+    fn register_glyph_shape(&mut self, _shape: &swf::Glyph) -> ShapeHandle {
+        ShapeHandle(0)
+    }
+    fn release_shape(&mut self, shape: ShapeHandle) {
+        self.released.push(shape.0);
+    }
+    fn release_bitmap(&mut self, bitmap: BitmapHandle) {
+        self.released_bitmaps.push(bitmap.0);
+    }
+    fn register_bitmap_jpeg(
+        &mut self,
+        id: swf::CharacterId,
+        data: &[u8],
+        jpeg_tables: Option<&[u8]>,
+    ) -> Result<BitmapInfo, Error> {
+        let info = NullRenderer.register_bitmap_jpeg(id, data, jpeg_tables);
+        self.register_bitmap(info)
+    }
+    fn register_bitmap_jpeg_2(
+        &mut self,
+        id: swf::CharacterId,
+        data: &[u8],
+    ) -> Result<BitmapInfo, Error> {
+        let info = NullRenderer.register_bitmap_jpeg_2(id, data);
+        self.register_bitmap(info)
+    }
+    fn register_bitmap_jpeg_3(
+        &mut self,
+        id: swf::CharacterId,
+        jpeg_data: &[u8],
+        alpha_data: &[u8],
+    ) -> Result<BitmapInfo, Error> {
+        let info = NullRenderer.register_bitmap_jpeg_3(id, jpeg_data, alpha_data);
+        self.register_bitmap(info)
+    }
+    fn register_bitmap_png(
+        &mut self,
+        swf_tag: &swf::DefineBitsLossless,
+    ) -> Result<BitmapInfo, Error> {
+        let info = NullRenderer.register_bitmap_png(swf_tag);
+        self.register_bitmap(info)
+    }
+    fn register_bitmap_rgba(
+        &mut self,
+        id: swf::CharacterId,
+        width: u32,
+        height: u32,
+        rgba: Vec<u8>,
+    ) -> Result<BitmapInfo, Error> {
+        let info = NullRenderer.register_bitmap_rgba(id, width, height, rgba);
+        self.register_bitmap(info)
+    }
+    fn update_bitmap_rgba(
+        &mut self,
+        _bitmap: BitmapHandle,
+        _width: u32,
+        _height: u32,
+        _rgba: Vec<u8>,
+    ) -> Result<(), Error> {
+        Ok(())
+    }
+    fn set_quality(&mut self, _quality: StageQuality) {}
+    fn begin_frame(&mut self, _clear: Color) {}
+    fn render_bitmap(&mut self, _bitmap: BitmapHandle, _transform: &Transform, smoothing: bool) {
+        self.rendered_bitmaps.push(smoothing);
+    }
+    fn render_shape(&mut self, _shape: ShapeHandle, _transform: &Transform) {}
+    fn end_frame(&mut self) {}
+    fn draw_letterbox(&mut self, _letterbox: Letterbox) {}
+    fn push_mask(&mut self) {}
+    fn activate_mask(&mut self) {}
+    fn pop_mask(&mut self) {}
+    fn push_filters(&mut self, _filters: &[swf::Filter]) {}
+    fn pop_filters(&mut self) {}
+    fn push_blend_mode(&mut self, _blend_mode: swf::BlendMode) {}
+    fn pop_blend_mode(&mut self) {}
+    fn create_bitmap_cache(&mut self) -> BitmapCacheHandle {
+        BitmapCacheHandle(0)
+    }
+    fn render_bitmap_cache(&mut self, _cache: BitmapCacheHandle) -> bool {
+        false
+    }
+    fn begin_bitmap_cache(&mut self, _cache: BitmapCacheHandle) {}
+    fn end_bitmap_cache(&mut self) {}
+    fn release_bitmap_cache(&mut self, _cache: BitmapCacheHandle) {}
+    fn draw_rect(&mut self, _color: Color, _rect: &swf::Rectangle, _transform: &Transform) {}
+    fn begin_capture(&mut self, _width: u32, _height: u32, _clear: Color) -> bool {
+        false
+    }
+    fn end_capture(&mut self) -> Option<Bitmap> {
+        None
+    }
+}
+
+/// The time that the streams of a `StreamClockAudioBackend` play to, and the frames that
+/// they started on.
+#[derive(Default)]
+struct StreamClock {
+    time: u32,
+    started_frames: Vec<u16>,
+}
+
+/// An audio backend whose streams play to a clock set by the test.
+struct StreamClockAudioBackend {
+    clock: Arc<Mutex<StreamClock>>,
+    /// The clock time that each playing stream started at.
+    streams: HashMap<AudioStreamHandle, u32>,
+    null: NullAudioBackend,
+}
+
+impl StreamClockAudioBackend {
+    fn new(clock: Arc<Mutex<StreamClock>>) -> Self {
+        Self {
+            clock,
+            streams: HashMap::new(),
+            null: NullAudioBackend::new(),
+        }
+    }
+}
+
+impl AudioBackend for StreamClockAudioBackend {
+    fn register_sound(&mut self, sound: &swf::Sound) -> Result<SoundHandle, Error> {
+        self.null.register_sound(sound)
+    }
+    fn start_sound(
+        &mut self,
+        sound: SoundHandle,
+        settings: &swf::SoundInfo,
+    ) -> Result<SoundInstanceHandle, Error> {
+        self.null.start_sound(sound, settings)
+    }
+    fn start_stream(
+        &mut self,
+        clip_id: swf::CharacterId,
+        clip_frame: u16,
+        clip_data: ruffle_core::tag_utils::SwfSlice,
+        stream_info: &swf::SoundStreamHead,
+    ) -> Result<AudioStreamHandle, Error> {
+        let stream = self
+            .null
+            .start_stream(clip_id, clip_frame, clip_data, stream_info)?;
+        let mut clock = self.clock.lock().unwrap();
+        clock.started_frames.push(clip_frame);
+        self.streams.insert(stream, clock.time);
+        Ok(stream)
+    }
+    fn stop_sound(&mut self, _sound: SoundInstanceHandle) {}
+    fn stop_stream(&mut self, stream: AudioStreamHandle) {
+        self.streams.remove(&stream);
+        self.null.stop_stream(stream);
+    }
+    fn stop_all_sounds(&mut self) {
+        self.streams.clear();
+    }
+    fn release_sound(&mut self, _sound: SoundHandle) {}
+    fn stop_sounds_with_handle(&mut self, _handle: SoundHandle) {}
+    fn is_sound_playing_with_handle(&mut self, _handle: SoundHandle) -> bool {
+        false
+    }
+    fn take_completed_sounds(&mut self) -> Vec<SoundInstanceHandle> {
+        // The streams play until they are stopped.
+        let streams = &self.streams;
+        self.null
+            .take_completed_sounds()
+            .into_iter()
+            .filter(|instance| !streams.contains_key(instance))
+            .collect()
+    }
+    fn set_sound_transform(&mut self, _instance: SoundInstanceHandle, _transform: SoundTransform) {}
+    fn get_sound_duration(&self, _sound: SoundHandle) -> Option<u32> {
+        None
+    }
+    fn get_sound_position(&self, _instance: SoundInstanceHandle) -> Option<u32> {
+        None
+    }
+    fn get_stream_position(&self, stream: AudioStreamHandle) -> Option<u32> {
+        let start_time = self.streams.get(&stream)?;
+        Some(self.clock.lock().unwrap().time - start_time)
+    }
+}
+
+/// Loads an SWF and runs it through the Ruffle core for a number of frames.
+/// Tests that the trace output matches the given expected output.
+fn run_swf(swf_path: &str, num_frames: u32) -> Result<String, Error> {
+    let (player, mut executor) = create_player(swf_path, Box::new(NullRenderer))?;
 
     for _ in 0..num_frames {
         player.lock().unwrap().run_frame();
@@ -335,29 +1275,331 @@ fn run_swf(swf_path: &str, num_frames: u32) -> Result<String, Error> {
     Ok(trace_log())
 }
 
+/// Creates a headless player for the given SWF, with trace output going to the trace log.
+fn create_player(
+    swf_path: &str,
+    renderer: Box<dyn RenderBackend>,
+) -> Result<(Arc<Mutex<Player>>, NullExecutor), Error> {
+    create_player_with_audio(swf_path, renderer, Box::new(NullAudioBackend::new()))
+}
+
+/// Creates a headless player like `create_player`, with the given audio backend.
+fn create_player_with_audio(
+    swf_path: &str,
+    renderer: Box<dyn RenderBackend>,
+    audio: Box<dyn AudioBackend>,
+) -> Result<(Arc<Mutex<Player>>, NullExecutor), Error> {
+    let base_path = Path::new(swf_path).parent().unwrap();
+    let movie = SwfMovie::from_path(swf_path)?;
+    create_player_with_movie(base_path, movie, renderer, audio)
+}
+
+/// Creates a headless player like `create_player_with_audio`, for a movie that
+/// has already been loaded.
+fn create_player_with_movie(
+    base_path: &Path,
+    movie: SwfMovie,
+    renderer: Box<dyn RenderBackend>,
+    audio: Box<dyn AudioBackend>,
+) -> Result<(Arc<Mutex<Player>>, NullExecutor), Error> {
+    let (executor, channel) = NullExecutor::new();
+    let navigator = NullNavigatorBackend::with_base_path(base_path, channel);
+    create_player_with_navigator(
+        movie,
+        renderer,
+        audio,
+        Box::new(NullVideoBackend::new()),
+        Box::new(MemoryStorageBackend::default()),
+        Box::new(navigator),
+        executor,
+    )
+}
+
+/// Creates a headless player like `create_player_with_movie`, with the given video
+/// backend, storage backend, navigator backend and the executor that it spawns futures on.
+fn create_player_with_navigator(
+    movie: SwfMovie,
+    renderer: Box<dyn RenderBackend>,
+    audio: Box<dyn AudioBackend>,
+    video: Box<dyn VideoBackend>,
+    storage: Box<dyn StorageBackend>,
+    navigator: Box<dyn NavigatorBackend>,
+    executor: NullExecutor,
+) -> Result<(Arc<Mutex<Player>>, NullExecutor), Error> {
+    let player = Player::new(
+        renderer,
+        audio,
+        navigator,
+        Box::new(NullInputBackend::new()),
+        movie,
+        storage,
+        Box::new(NullFontBackend::new()),
+        video,
+    )?;
+    player
+        .lock()
+        .unwrap()
+        .set_log_backend(Box::new(TraceLogBackend));
+
+    Ok((player, executor))
+}
+
+/// The time that the sounds of a `SoundClockAudioBackend` play to, and the durations
+/// and start samples of the sounds that have been started.
+#[derive(Default)]
+struct SoundClock {
+    time: u32,
+    started_sounds: Vec<(u32, Option<u32>)>,
+}
+
+/// An audio backend whose event sounds play to a clock set by the test.
+struct SoundClockAudioBackend {
+    clock: Arc<Mutex<SoundClock>>,
+    /// The duration of each registered sound in milliseconds.
+    durations: HashMap<SoundHandle, u32>,
+    /// The position that each playing sound started from, and the clock time it started at.
+    instances: HashMap<SoundInstanceHandle, (u32, u32)>,
+    null: NullAudioBackend,
+}
+
+impl SoundClockAudioBackend {
+    fn new(clock: Arc<Mutex<SoundClock>>) -> Self {
+        Self {
+            clock,
+            durations: HashMap::new(),
+            instances: HashMap::new(),
+            null: NullAudioBackend::new(),
+        }
+    }
+}
+
+impl AudioBackend for SoundClockAudioBackend {
+    fn register_sound(&mut self, sound: &swf::Sound) -> Result<SoundHandle, Error> {
+        let handle = self.null.register_sound(sound)?;
+        let duration = u64::from(sound.num_samples) * 1000 / u64::from(sound.format.sample_rate);
+        self.durations.insert(handle, duration as u32);
+        Ok(handle)
+    }
+    fn start_sound(
+        &mut self,
+        sound: SoundHandle,
+        settings: &swf::SoundInfo,
+    ) -> Result<SoundInstanceHandle, Error> {
+        let instance = self.null.start_sound(sound, settings)?;
+        let mut clock = self.clock.lock().unwrap();
+        clock
+            .started_sounds
+            .push((self.durations[&sound], settings.in_sample));
+        let start_position = (f64::from(settings.in_sample.unwrap_or(0)) / 44.1) as u32;
+        self.instances
+            .insert(instance, (start_position, clock.time));
+        Ok(instance)
+    }
+    fn start_stream(
+        &mut self,
+        clip_id: swf::CharacterId,
+        clip_frame: u16,
+        clip_data: ruffle_core::tag_utils::SwfSlice,
+        stream_info: &swf::SoundStreamHead,
+    ) -> Result<AudioStreamHandle, Error> {
+        self.null
+            .start_stream(clip_id, clip_frame, clip_data, stream_info)
+    }
+    fn stop_sound(&mut self, sound: SoundInstanceHandle) {
+        self.instances.remove(&sound);
+    }
+    fn stop_stream(&mut self, _stream: AudioStreamHandle) {}
+    fn stop_all_sounds(&mut self) {
+        self.instances.clear();
+    }
+    fn release_sound(&mut self, sound: SoundHandle) {
+        self.durations.remove(&sound);
+    }
+    fn stop_sounds_with_handle(&mut self, _handle: SoundHandle) {}
+    fn is_sound_playing_with_handle(&mut self, _handle: SoundHandle) -> bool {
+        false
+    }
+    fn take_completed_sounds(&mut self) -> Vec<SoundInstanceHandle> {
+        // The sounds play until they are stopped.
+        let instances = &self.instances;
+        self.null
+            .take_completed_sounds()
+            .into_iter()
+            .filter(|instance| !instances.contains_key(instance))
+            .collect()
+    }
+    fn set_sound_transform(&mut self, _instance: SoundInstanceHandle, _transform: SoundTransform) {}
+    fn get_sound_duration(&self, sound: SoundHandle) -> Option<u32> {
+        self.durations.get(&sound).copied()
+    }
+    fn get_sound_position(&self, instance: SoundInstanceHandle) -> Option<u32> {
+        let (start_position, start_time) = self.instances.get(&instance)?;
+        Some(start_position + self.clock.lock().unwrap().time - start_time)
+    }
+}
+
 thread_local! {
     static TRACE_LOG: RefCell<String> = RefCell::new(String::new());
+    static WARNING_LOG: RefCell<Vec<String>> = RefCell::new(Vec::new());
 }
 
-static TRACE_LOGGER: TraceLogger = TraceLogger;
+/// A storage backend whose data outlives the player, so that a later player can read
+/// what an earlier one saved.
+#[derive(Clone, Default)]
+struct SharedStorageBackend(Arc<Mutex<HashMap<String, Vec<u8>>>>);
 
-/// `TraceLogger` captures output from AVM trace actions into a String.
-struct TraceLogger;
+impl StorageBackend for SharedStorageBackend {
+    fn get(&self, name: &str) -> Option<Vec<u8>> {
+        self.0.lock().unwrap().get(name).cloned()
+    }
 
-fn trace_log() -> String {
-    TRACE_LOG.with(|log| log.borrow().clone())
+    fn put(&mut self, name: &str, value: &[u8]) -> bool {
+        self.0
+            .lock()
+            .unwrap()
+            .insert(name.to_string(), value.to_vec());
+        true
+    }
+
+    fn remove_key(&mut self, name: &str) {
+        self.0.lock().unwrap().remove(name);
+    }
+}
+
+/// The calls made by a movie to an `EchoExternalInterface`, and the names of the
+/// functions that the movie exposed to it.
+#[derive(Default)]
+struct ExternalCalls {
+    calls: Vec<(String, Vec<ExternalValue>)>,
+    callbacks: Vec<String>,
 }
 
-impl log::Log for TraceLogger {
-    fn enabled(&self, metadata: &Metadata) -> bool {
-        metadata.target() == "avm_trace"
+/// A host for `ExternalInterface` that records the calls made to it, and returns
+/// the first argument of each.
+#[derive(Clone, Default)]
+struct EchoExternalInterface(Arc<Mutex<ExternalCalls>>);
+
+impl ExternalInterfaceProvider for EchoExternalInterface {
+    fn call_method(&mut self, name: &str, args: &[ExternalValue]) -> ExternalValue {
+        let mut external = self.0.lock().unwrap();
+        external.calls.push((name.to_string(), args.to_vec()));
+        args.first().cloned().unwrap_or(ExternalValue::Undefined)
+    }
+
+    fn on_callback_available(&mut self, name: &str) {
+        self.0.lock().unwrap().callbacks.push(name.to_string());
     }
+}
+
+/// A request made by a movie to a `RecordingNavigator`.
+#[derive(Debug, PartialEq)]
+enum Request {
+    /// The URL, window, method and variables of a navigation.
+    Navigate(String, NavigationTarget, Option<NavigationMethod>, String),
+    /// The URL, method and body of a fetch.
+    Fetch(String, NavigationMethod, String),
+}
+
+/// A navigator that records the navigations and fetches made by the movie, and
+/// otherwise acts like the `NullNavigatorBackend` it wraps.
+struct RecordingNavigator {
+    requests: Arc<Mutex<Vec<Request>>>,
+    inner: NullNavigatorBackend,
+}
 
-    fn log(&self, record: &Record) {
-        if self.enabled(record.metadata()) {
-            TRACE_LOG.with(|log| log.borrow_mut().push_str(&format!("{}\n", record.args())));
+impl RecordingNavigator {
+    fn new(inner: NullNavigatorBackend) -> Self {
+        Self {
+            requests: Arc::new(Mutex::new(vec![])),
+            inner,
         }
     }
+}
+
+impl NavigatorBackend for RecordingNavigator {
+    fn navigate_to_url(
+        &self,
+        url: String,
+        target: NavigationTarget,
+        vars_method: Option<(NavigationMethod, HashMap<String, String>)>,
+    ) {
+        let (method, vars) = match vars_method {
+            Some((method, vars)) => {
+                let vars: BTreeMap<_, _> = vars.into_iter().collect();
+                (Some(method), format!("{:?}", vars))
+            }
+            None => (None, String::new()),
+        };
+        self.requests
+            .lock()
+            .unwrap()
+            .push(Request::Navigate(url, target, method, vars));
+    }
+
+    fn fetch(&self, url: &str, options: RequestOptions) -> OwnedFuture<Vec<u8>, loader::Error> {
+        let body = options
+            .body()
+            .as_ref()
+            .map(|(body, _)| String::from_utf8_lossy(body).into_owned())
+            .unwrap_or_default();
+        self.requests
+            .lock()
+            .unwrap()
+            .push(Request::Fetch(url.to_string(), options.method(), body));
+        self.inner.fetch(url, options)
+    }
+
+    fn time_since_launch(&mut self) -> Duration {
+        self.inner.time_since_launch()
+    }
+
+    fn current_time(&self) -> f64 {
+        self.inner.current_time()
+    }
+
+    fn timezone_offset(&self, time: f64) -> i32 {
+        self.inner.timezone_offset(time)
+    }
+
+    fn spawn_future(&mut self, future: OwnedFuture<(), loader::Error>) {
+        self.inner.spawn_future(future)
+    }
+
+    fn connect_socket(&mut self, host: &str, port: u16) -> SocketHandle {
+        self.inner.connect_socket(host, port)
+    }
+
+    fn send_socket(&mut self, socket: SocketHandle, data: Vec<u8>) {
+        self.inner.send_socket(socket, data)
+    }
+
+    fn close_socket(&mut self, socket: SocketHandle) {
+        self.inner.close_socket(socket)
+    }
+
+    fn poll_socket_events(&mut self) -> Vec<SocketEvent> {
+        self.inner.poll_socket_events()
+    }
+}
+
+/// `TraceLogBackend` captures output from AVM trace actions into a String,
+/// and the warnings about the movie's scripts into a list.
+struct TraceLogBackend;
+
+fn trace_log() -> String {
+    TRACE_LOG.with(|log| log.borrow().clone())
+}
+
+fn warning_log() -> Vec<String> {
+    WARNING_LOG.with(|log| log.borrow().clone())
+}
+
+impl LogBackend for TraceLogBackend {
+    fn avm_trace(&self, message: &str) {
+        TRACE_LOG.with(|log| log.borrow_mut().push_str(&format!("{}\n", message)));
+    }
 
-    fn flush(&self) {}
+    fn avm_warning(&self, message: &str) {
+        WARNING_LOG.with(|log| log.borrow_mut().push(message.to_string()));
+    }
 }
@@ -0,0 +1,35 @@
+// SWF version 7.
+// The extends, implements and cast statements below have no syntax of their own outside
+// of AS2 class files. Each is marked with the action it was compiled to.
+
+// Frame 1
+IA = function () {};
+IB = function () {};
+A = function () {};
+B = function () {};
+C = function () {};
+IB extends IA;    // ActionExtends
+A implements IB;  // ActionImplementsOp
+B extends A;      // ActionExtends
+
+o = new B();
+trace(o instanceof IB);
+trace(o instanceof IA);
+trace(o instanceof A);
+trace(o instanceof C);
+trace(IA(o) == o);  // ActionCastOp
+trace(C(o));        // ActionCastOp
+trace(A(5));        // ActionCastOp
+
+P = function () {};
+P.prototype.name = "parent";
+Q = function () {
+    this.name = "own";
+};
+Q extends P;  // ActionExtends
+Q.prototype.show = function () {
+    trace(this.name);
+    trace(super.name);
+};
+new Q().show();
+stop();
@@ -0,0 +1,30 @@
+// SWF version 8.
+
+// Frame 1
+var bmp = new flash.display.BitmapData(20, 10, false, 0xFF0000);
+trace(bmp.width + "x" + bmp.height);
+bmp.setPixel(0, 0, 0x00FF00);
+trace(bmp.getPixel(0, 0) == 0x00FF00);
+
+this.createEmptyMovieClip("holder", 1);
+holder.attachBitmap(bmp, 2);
+trace(holder._width + "x" + holder._height);
+
+// A new bitmap replaces the one at the same depth.
+holder.attachBitmap(new flash.display.BitmapData(5, 8), 2);
+trace(holder._width + "x" + holder._height);
+
+// The bitmap is added next to existing children.
+holder.attachBitmap(bmp, 3);
+trace(holder._width + "x" + holder._height);
+
+// Only BitmapData objects can be attached.
+this.createEmptyMovieClip("other", 2);
+other.attachBitmap({width: 10, height: 10}, 1);
+other.attachBitmap(undefined, 2);
+trace(other._width + "x" + other._height);
+
+// Depths outside of the range usable by scripts are ignored.
+other.attachBitmap(bmp, -16385);
+other.attachBitmap(bmp, 2130690046);
+trace(other._width + "x" + other._height);
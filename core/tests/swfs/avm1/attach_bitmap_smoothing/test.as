@@ -0,0 +1,9 @@
+// SWF version 8.
+
+// Frame 1
+bmp = new flash.display.BitmapData(4, 4);
+this.attachBitmap(bmp, 1);
+this.attachBitmap(bmp, 2, "auto", true);
+this.attachBitmap(bmp, 3, "auto", false);
+trace("attached");
+stop();
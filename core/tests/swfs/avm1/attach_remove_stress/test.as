@@ -0,0 +1,46 @@
+// SWF version 7, 2 frames.
+// The library has an empty movie clip exported as "bullet".
+
+// Frame 1
+for (i = 0; i < 10000; i++) {
+    this.attachMovie("bullet", "b" + i, i);
+}
+trace(this.getNextHighestDepth());
+trace(this.getInstanceAtDepth(9999)._name);
+trace(this.b5000._name);
+
+for (i = 0; i < 10000; i++) {
+    this["b" + i].removeMovieClip();
+}
+trace(this.getNextHighestDepth());
+trace(this.b5000);
+
+// A clip attached to an occupied depth replaces the clip there.
+this.attachMovie("bullet", "first", 5);
+this.attachMovie("bullet", "second", 5);
+trace(this.first);
+trace(this.getInstanceAtDepth(5)._name);
+this.second.removeMovieClip();
+trace(this.getNextHighestDepth());
+
+// Frame 2
+for (i = 0; i < 10000; i++) {
+    this.attachMovie("bullet", "b" + i, i);
+}
+trace(this.getNextHighestDepth());
+trace(this.getInstanceAtDepth(9999)._name);
+trace(this.b5000._name);
+
+for (i = 0; i < 10000; i++) {
+    this["b" + i].removeMovieClip();
+}
+trace(this.getNextHighestDepth());
+trace(this.b5000);
+
+this.attachMovie("bullet", "first", 5);
+this.attachMovie("bullet", "second", 5);
+trace(this.first);
+trace(this.getInstanceAtDepth(5)._name);
+this.second.removeMovieClip();
+trace(this.getNextHighestDepth());
+stop();
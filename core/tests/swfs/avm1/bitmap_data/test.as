@@ -0,0 +1,16 @@
+// SWF version 8.
+
+// Frame 1
+var bitmap = new flash.display.BitmapData(4, 2, false, 0x00FF00);
+trace(bitmap.width);
+trace(bitmap.height);
+trace(bitmap.transparent);
+trace(bitmap.getPixel32(0, 0));
+bitmap.setPixel(3, 1, 0x123456);
+trace(bitmap.getPixel(3, 1));
+trace(bitmap.getPixel(4, 1));
+trace(bitmap.getPixel(0, -1));
+bitmap.dispose();
+trace(bitmap.width);
+trace(bitmap.height);
+trace(bitmap.getPixel(0, 0));
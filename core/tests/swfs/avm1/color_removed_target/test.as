@@ -0,0 +1,11 @@
+// SWF version 8.
+
+// Frame 1
+createEmptyMovieClip("clip", 1);
+var color = new Color(clip);
+color.setRGB(0x336699);
+trace(color.getRGB());
+clip.removeMovieClip();
+color.setRGB(0xFF0000);
+trace(color.getRGB());
+trace(typeof color.getTransform());
@@ -0,0 +1,12 @@
+// SWF version 8, 3 frames.
+// The file was assembled by hand, with these broken tags:
+// - Frame 1 has an unknown tag with code 1000, and a DefineShape that only holds a
+//   character ID.
+// - Frame 2 has a DoAction whose Push action has a value of the unknown type 0x20.
+// - The file ends with a DefineShape whose header claims 100 bytes, but only 4 follow.
+
+// Frame 1
+trace("frame 1");
+
+// Frame 2
+trace("frame 2");
@@ -0,0 +1,41 @@
+// SWF version 7, 3 frames.
+// Frame 1 places an instance of an empty movie clip named "t" on depth 2.
+// Frame 2 removes it, and frame 3 places a new instance named "t" on the same depth.
+
+// Frame 1
+this.createEmptyMovieClip("foo", 1);
+foo._x = 10;
+foo.bar = "x";
+ref = foo;
+tref = t;
+
+trace("// before removal");
+trace(ref._x);
+trace(ref.bar);
+trace(ref);
+foo.removeMovieClip();
+
+trace("// after removal");
+trace(ref._x);
+trace(ref.bar);
+trace(ref);
+trace(typeof ref);
+trace(ref.getDepth());
+ref._x = 5;
+trace(ref._x);
+trace(typeof foo);
+
+trace("// after re-creation");
+this.createEmptyMovieClip("foo", 1);
+trace(foo._x);
+trace(ref._x);
+trace(ref == foo);
+
+// Frame 2
+
+// Frame 3
+trace("// timeline clip re-placed");
+trace(t._name);
+trace(tref._name);
+trace(tref == t);
+stop();
@@ -0,0 +1,36 @@
+// SWF version 8.
+// All functions are defined with DefineFunction2. Their flags were set by hand, so
+// that some of them suppress the arguments object even though they read it:
+// - f suppresses super.
+// - g suppresses arguments.
+// - h preloads arguments into register 1, and suppresses the arguments variable.
+// - sum suppresses this, arguments and super, and keeps n, i and total in
+//   registers 1 to 3.
+
+// Frame 1
+function f(a, b) {
+    trace(arguments.length);
+}
+function g(a) {
+    trace(typeof arguments);
+}
+function h(a) {
+    // Reads the arguments from register 1.
+    trace(arguments.length);
+    trace(typeof arguments);
+}
+function sum(n) {
+    var i = 0;
+    var total = 0;
+    while (i < n) {
+        total += i;
+        i++;
+    }
+    return total;
+}
+
+f(1, 2);
+g(1);
+h(1);
+trace(sum(100000));
+stop();
@@ -0,0 +1,21 @@
+// SWF version 8.
+// The library has an empty movie clip exported as "Widget".
+// An instance of "Widget" named "w" is placed on the stage in frame 1.
+
+// Actions of the "Widget" symbol.
+#initclip
+Widget = function () {
+};
+Widget.prototype.hello = function () {
+    trace("hello from " + this._name);
+};
+Object.registerClass("Widget", Widget);
+trace("init");
+#endinitclip
+
+// Frame 1
+trace("frame 1");
+w.hello();
+trace(w instanceof Widget);
+_root.attachMovie("Widget", "w2", 5);
+w2.hello();
@@ -0,0 +1,13 @@
+// SWF version 8, 2 frames.
+
+// Frame 1
+this.lineStyle(1, 0);
+for (i = 0; i < 10000; i++) {
+    this.lineTo(i % 100, i % 37);
+}
+
+// Frame 2
+this.lineStyle(1, 0);
+for (i = 0; i < 10000; i++) {
+    this.lineTo(i % 100, i % 37);
+}
@@ -0,0 +1,16 @@
+// SWF version 8, 3 frames.
+// child.swf has no scripts. Its only frame shows a 10x10 shape filled with a 1x1 bitmap.
+
+// Frame 1
+this.createEmptyMovieClip("holder", 1);
+holder.loadMovie("child.swf");
+
+// Frame 2
+holder.duplicateMovieClip("copy", 2);
+copy.unloadMovie();
+trace("copy unloaded");
+
+// Frame 3
+holder.unloadMovie();
+trace("holder unloaded");
+stop();
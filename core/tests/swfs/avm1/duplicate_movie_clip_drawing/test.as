@@ -0,0 +1,31 @@
+// SWF version 8.
+
+// Frame 1
+this.createEmptyMovieClip("src", 1);
+src.beginFill(0xFF0000);
+src.moveTo(0, 0);
+src.lineTo(40, 0);
+src.lineTo(0, 40);
+src.lineTo(0, 0);
+src.endFill();
+src.duplicateMovieClip("dup", 2);
+dup._x = 100;
+
+trace("src");
+b = src.getBounds(src);
+trace(b.xMin);
+trace(b.xMax);
+trace(b.yMin);
+trace(b.yMax);
+trace(src.hitTest(10, 10, 1));
+trace(src.hitTest(30, 30, 1));
+
+trace("dup");
+b = dup.getBounds(dup);
+trace(b.xMin);
+trace(b.xMax);
+trace(b.yMin);
+trace(b.yMax);
+trace(dup.hitTest(110, 10, 1));
+trace(dup.hitTest(130, 30, 1));
+stop();
@@ -0,0 +1,22 @@
+// SWF version 8, 1 fps.
+
+// Frame 1
+var EI = flash.external.ExternalInterface;
+// The movie has no host yet.
+trace("available: " + EI.available);
+trace("call: " + EI.call("echo", 1));
+trace("addCallback: " + EI.addCallback("double", this, function(x) {
+    trace("double: " + x);
+    return x * 2;
+}));
+
+// Frame 2
+// The host echoes the first argument back.
+trace("available: " + EI.available);
+var value = EI.call("echo", {name: "Ruffle", list: [1, null]});
+trace(value.name);
+trace(value.list instanceof Array);
+trace(value.list.length);
+trace(value.list[0]);
+trace(value.list[1]);
+stop();
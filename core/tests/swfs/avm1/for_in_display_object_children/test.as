@@ -0,0 +1,37 @@
+// SWF version 6, 2 frames.
+// An instance of a movie clip named "mc" is placed on the stage. Its timeline has a
+// 10x10 shape on depth 1, and an empty movie clip named "inner" on depth 2.
+
+// Frame 2
+mc.createEmptyMovieClip("a", 10);
+mc.createEmptyMovieClip("b", 5);
+mc.createEmptyMovieClip("c", 20);
+mc.foo = 1;
+mc.bar = 2;
+mc.A = "prop";
+mc.hidden = 4;
+ASSetPropFlags(mc, "hidden", 1);
+
+trace("// for (k in mc)");
+for (k in mc) {
+    trace(k);
+}
+
+// Compiled to the SWF 4 ActionEnumerate, which takes the path of the object.
+trace("// Enumerate '/mc'");
+for (k in "/mc") {
+    trace(k);
+}
+
+o = new Object();
+o.first = 1;
+o.second = 2;
+o.third = 3;
+delete o.second;
+o.second = 4;
+o.first = 5;
+trace("// for (k in o)");
+for (k in o) {
+    trace(k);
+}
+stop();
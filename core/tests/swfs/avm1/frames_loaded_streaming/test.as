@@ -0,0 +1,22 @@
+// SWF version 8, 4 frames.
+// Frame 3 has a 200 byte DefineBinaryData tag, so that it takes several chunks to load.
+
+// Frame 1
+onEnterFrame = function () {
+    trace("enter " + _currentframe + " " + _framesloaded);
+};
+trace("frame 1: " + _framesloaded);
+ifFrameLoaded (4) {
+    trace("frame 4 loaded");
+}
+
+// Frame 2
+trace("frame 2: " + _framesloaded);
+
+// Frame 3
+trace("frame 3: " + _framesloaded);
+
+// Frame 4
+trace("frame 4: " + _framesloaded);
+delete onEnterFrame;
+stop();
@@ -0,0 +1,17 @@
+// SWF version 8, 3 frames.
+// The file is cut off in the middle of a PlaceObject2 tag that follows the actions of
+// frame 2, so only frame 1 is ever loaded.
+
+// Frame 1
+trace("frame 1");
+trace(_framesloaded);
+trace(_totalframes);
+ifFrameLoaded (1) {
+    trace("frame 1 loaded");
+}
+ifFrameLoaded (2) {
+    trace("frame 2 loaded");
+}
+
+// Frame 2
+trace("frame 2");
@@ -0,0 +1,34 @@
+// SWF version 8.
+
+// Frame 1
+function f(a, b) {
+    return arguments;
+}
+function g() {
+    return f();
+}
+function getThis() {
+    return this;
+}
+trace(f.length);
+trace(f);
+
+var args = f.apply(null, [0, 1, 2]);
+trace(args.length);
+trace(args.join(","));
+trace(args.callee == f);
+trace(args.caller);
+
+// Argument lists that aren't objects pass no arguments.
+trace(f.apply(null, undefined).length);
+trace(f.apply(null, null).length);
+trace(f.apply(null, 5).length);
+
+trace(f.call(null, "x").join(","));
+args = g.call();
+trace(args.callee == f);
+trace(args.caller == g);
+
+// Primitive this values are boxed, and null calls on _global.
+trace(typeof getThis.call(5));
+trace(getThis.call(null) == _global);
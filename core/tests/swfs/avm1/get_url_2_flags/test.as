@@ -0,0 +1,19 @@
+// SWF version 8.
+
+// Frame 1
+// Only send one variable, so that its encoding is predictable.
+delete $version;
+var name = "Ruffle";
+getURL("page.html");
+getURL("page.html", "_BLANK", "GET");
+getURL("page.html", "frame", "POST");
+// javascript: URLs never get variables, and run in the movie's own window.
+getURL("javascript:alert(1)", "_top", "POST");
+loadMovieNum("movie.swf", 1, "GET");
+loadMovie("movie.swf?a=b", "_level0", "GET");
+// Levels that don't exist yet can be loaded into, but other clips can't.
+loadMovie("movie.swf", "_level2");
+loadMovie("movie.swf", "missing");
+loadVariablesNum("vars.txt", 0, "POST");
+loadVariablesNum("vars.txt", 3);
+loadVariables("vars.txt", "_root");
@@ -0,0 +1,9 @@
+// SWF version 8.
+
+// Frame 1
+var global = new Sound();
+global.setVolume(25);
+trace(global.getVolume());
+trace(new Sound().getVolume());
+createEmptyMovieClip("clip", 1);
+trace(new Sound(clip).getVolume());
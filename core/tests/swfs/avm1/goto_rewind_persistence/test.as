@@ -0,0 +1,27 @@
+// SWF version 8, 3 frames.
+// The library has an empty movie clip, and the timeline places instances of it:
+// - Frame 1 places "a" at x=10 on depth 1, "b" at x=20 on depth 2 and "d" at x=50 on depth 4.
+// - Frame 2 replaces "b" with a new instance at x=30, places "c" at x=40 on depth 3,
+//   and moves "d" to x=60.
+
+// Frame 1
+trace("frame 1");
+
+// Frame 2
+a._x = 100;
+b.foo = "new b";
+d.foo = "kept";
+
+// Frame 3
+trace(a._x);
+trace(b._x);
+trace(c._x);
+trace(d._x);
+trace("gotoAndStop(1)");
+gotoAndStop(1);
+trace(a._x);
+trace(b._x);
+trace(b.foo);
+trace(typeof c);
+trace(d._x);
+trace(d.foo);
@@ -0,0 +1,30 @@
+// SWF version 8.
+// The library has a movie clip exported as "clip", which runs this on its first frame:
+//     trace(this._name + " frame 1 " + this.value);
+// An instance of "clip" named "p" is placed on the stage in frame 1.
+
+// Actions of the "clip" symbol.
+#initclip
+Ctor = function () {
+    trace(this._name + " constructor " + this.value);
+};
+Object.registerClass("clip", Ctor);
+#endinitclip
+
+// Actions of the "p" instance.
+onClipEvent (construct) {
+    trace("p construct");
+}
+onClipEvent (initialize) {
+    value = "initialize";
+    trace("p initialize");
+}
+onClipEvent (load) {
+    trace("p load");
+}
+
+// Frame 1
+trace("root frame 1");
+this.attachMovie("clip", "a", 2, {value: "init"});
+trace("attached");
+stop();
@@ -0,0 +1,19 @@
+// SWF version 8, 3 frames.
+// child.swf has no scripts. Its only frame shows a 10x10 shape filled with a 1x1 bitmap.
+
+// Frame 1
+this.createEmptyMovieClip("holder", 1);
+i = 0;
+
+// Frame 2
+holder.loadMovie("child.swf");
+
+// Frame 3
+i = i + 1;
+if (i >= 100) {
+    holder.unloadMovie();
+    trace("done");
+    stop();
+} else {
+    gotoAndPlay(2);
+}
@@ -0,0 +1,17 @@
+// SWF version 8.
+
+// Frame 1
+var vars = new LoadVars();
+vars.onLoad = function(success) {
+    trace("onLoad: " + success);
+};
+vars.onData("name=Ruffle+Player&version=1%2E0");
+trace(vars.name);
+trace(vars.version);
+trace(vars.loaded);
+// toString encodes the variables, but not the properties of LoadVars itself.
+var copy = new LoadVars();
+copy.decode(vars.toString());
+trace(copy.name);
+trace(copy.version);
+trace(copy.loaded);
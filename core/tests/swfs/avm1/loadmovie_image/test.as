@@ -0,0 +1,36 @@
+// SWF version 8, 4 frames.
+// image.png is a 3x2 PNG image. broken.jpg is not a valid image.
+
+// Frame 1
+mcl = new MovieClipLoader();
+listener = new Object();
+listener.onLoadStart = function (target) {
+    trace("onLoadStart " + target);
+};
+listener.onLoadComplete = function (target) {
+    trace("onLoadComplete " + target);
+};
+listener.onLoadInit = function (target) {
+    trace("onLoadInit " + target);
+    trace(target._width);
+    trace(target._height);
+};
+mcl.addListener(listener);
+
+errorMcl = new MovieClipLoader();
+errorListener = new Object();
+errorListener.onLoadStart = function (target) {
+    trace("onLoadStart " + target);
+};
+errorListener.onLoadError = function (target, error) {
+    trace("onLoadError " + target + " " + error);
+};
+errorMcl.addListener(errorListener);
+
+this.createEmptyMovieClip("image", 1);
+this.createEmptyMovieClip("broken", 2);
+mcl.loadClip("image.png", image);
+
+// Frame 4
+errorMcl.loadClip("broken.jpg", broken);
+stop();
@@ -0,0 +1,15 @@
+// SWF version 8.
+// child.swf has no scripts. Its only frame shows a 10x10 shape filled with a 1x1 bitmap.
+
+// Frame 1
+this.createEmptyMovieClip("holder", 1);
+holder.loadMovie("child.swf");
+trace(holder.getBytesLoaded());
+trace(holder.getBytesTotal());
+trace(holder._framesloaded);
+
+// Frame 2
+trace(holder.getBytesLoaded());
+trace(holder.getBytesTotal());
+trace(holder._framesloaded);
+stop();
@@ -0,0 +1,6 @@
+// SWF version 8.
+// child.swf is a copy of the frames_loaded_streaming test movie.
+
+// Frame 1
+loadMovieNum("child.swf", 1);
+stop();
@@ -0,0 +1,18 @@
+// SWF version 8.
+
+// Frame 1
+_soundbuftime = 1;
+var s = new Sound();
+s.onLoad = function(success) {
+    trace("onLoad: " + success + " " + s.getBytesLoaded() + "/" + s.getBytesTotal() + " " + s.duration);
+};
+s.onSoundComplete = function() {
+    trace("onSoundComplete");
+};
+s.loadSound("test.mp3", true);
+
+// Frame 2
+trace(s.getBytesLoaded() + "/" + s.getBytesTotal() + " " + s.duration + " " + s.position);
+
+// Frame 3
+gotoAndPlay(2);
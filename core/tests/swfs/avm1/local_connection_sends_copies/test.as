@@ -0,0 +1,28 @@
+// SWF version 8.
+
+// Frame 1
+var arg = {x: 1};
+var receiver = new LocalConnection();
+receiver.receive = function(value) {
+    trace("received x: " + value.x);
+    trace("same object: " + (value === arg));
+};
+trace("connect: " + receiver.connect("_test"));
+// Connection names are case insensitive.
+var other = new LocalConnection();
+trace("connect again: " + other.connect("_TEST"));
+
+var sender = new LocalConnection();
+sender.onStatus = function(info) {
+    trace("onStatus: " + info.level);
+};
+trace("send: " + sender.send("_test", "receive", arg));
+sender.send("missing", "receive");
+// The methods of LocalConnection itself can't be called.
+trace("send close: " + sender.send("_test", "close"));
+// Messages are delivered at the end of the frame.
+trace("end of frame 1");
+
+// Frame 2
+trace("frame 2");
+stop();
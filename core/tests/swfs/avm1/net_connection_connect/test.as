@@ -0,0 +1,11 @@
+// SWF version 8.
+
+// Frame 1
+var connection = new NetConnection();
+// Only connecting to null, for progressive downloads, is supported.
+trace("connect: " + connection.connect("rtmp://example.com/app"));
+trace("isConnected: " + connection.isConnected);
+trace("connect null: " + connection.connect(null));
+trace("isConnected: " + connection.isConnected);
+connection.close();
+trace("isConnected: " + connection.isConnected);
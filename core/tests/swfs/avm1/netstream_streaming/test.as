@@ -0,0 +1,27 @@
+// SWF version 8, 10 fps.
+
+// Frame 1
+var nc = new NetConnection();
+nc.connect(null);
+
+var ns = new NetStream(nc);
+ns.onStatus = function(info) {
+    trace(info.level + ": " + info.code + " (" + ns.bytesLoaded + "/" + ns.bytesTotal + " bytes, time " + ns.time + ")");
+};
+ns.onMetaData = function(data) {
+    trace("onMetaData: duration " + data.duration);
+};
+ns.setBufferTime(0.2);
+ns.play("screen.flv");
+
+var h263 = new NetStream(nc);
+h263.onStatus = function(info) {
+    trace("h263 " + info.level + ": " + info.code);
+};
+h263.play("h263.flv");
+
+// Frame 2
+trace("time " + ns.time + ", " + ns.bytesLoaded + " bytes loaded");
+
+// Frame 3
+gotoAndPlay(2);
@@ -0,0 +1,9 @@
+// SWF version 8.
+
+// Frame 1
+var job = new PrintJob();
+// The player can't print, so the job never starts.
+trace("start: " + job.start());
+trace("paperWidth: " + job.paperWidth);
+trace("addPage: " + job.addPage(this));
+trace("send: " + job.send());
@@ -0,0 +1,19 @@
+// SWF version 8.
+
+// Frame 1
+trace("frame 1");
+function recurse(depth) {
+    if (depth > 0) {
+        recurse(depth - 1);
+    }
+    while (true) {
+    }
+}
+// The loop is aborted deep in the stack, and the rest of the script is skipped.
+recurse(20);
+trace("not reached");
+
+// Frame 2
+// Playback continues, and later scripts get a new budget.
+trace("frame 2");
+stop();
@@ -0,0 +1,20 @@
+// SWF version 8.
+
+// Frame 1
+var so = SharedObject.getLocal("save");
+trace("level: " + so.data.level);
+trace("name: " + so.data.name);
+trace("has missing: " + so.data.hasOwnProperty("missing"));
+if (so.data.level == undefined) {
+    // The first session saves some data.
+    so.data.level = 3;
+    so.data.name = "ruffle";
+    so.data.missing = undefined;
+    trace("same object: " + (SharedObject.getLocal("save") == so));
+    trace("flush: " + so.flush());
+    trace("has size: " + (so.getSize() > 0));
+} else {
+    // The second session reads it, and clears it.
+    so.clear();
+    trace("cleared: " + so.data.level);
+}
@@ -0,0 +1,26 @@
+// SWF version 8, 2 fps.
+
+// Frame 1
+var s = new Sound();
+s.onLoad = function(success) {
+    trace("onLoad: " + success);
+    s.start();
+};
+s.onSoundComplete = function() {
+    trace("onSoundComplete");
+};
+s.loadSound("test.mp3", false);
+
+// Frame 2
+
+// Frame 3
+// A sound that is stopped doesn't report that it completed.
+s.start();
+s.stop();
+trace("started and stopped");
+
+// Frame 4
+
+// Frame 5
+trace("done");
+stop();
@@ -0,0 +1,18 @@
+// SWF version 8.
+
+// Frame 1
+createEmptyMovieClip("clip", 1);
+createEmptyMovieClip("other", 2);
+var sound = new Sound(clip);
+sound.setVolume(50);
+sound.setPan(-30);
+trace(sound.getVolume());
+trace(sound.getPan());
+var t = sound.getTransform();
+trace(t.ll + " " + t.lr + " " + t.rr + " " + t.rl);
+// The transform belongs to the clip, so it is shared by every sound object targeting it.
+var same = new Sound(clip);
+trace(same.getVolume());
+trace(same.getPan());
+trace(new Sound(other).getVolume());
+trace(new Sound().getVolume());
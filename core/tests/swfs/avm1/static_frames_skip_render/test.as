@@ -0,0 +1,4 @@
+// SWF version 8.
+// This movie has no scripts. An instance of a 4 frame movie clip named "clip" is
+// placed on the stage. The clip's timeline places an empty movie clip at x=0 in
+// frame 1 and moves it to x=10 in frame 3; frames 2 and 4 don't change anything.
@@ -0,0 +1,6 @@
+// SWF version 8, 10 frames.
+// The movie has a stream sound of silent 5.5 kHz, 16-bit mono uncompressed audio.
+// Every frame has a block of 230 samples of it.
+
+// Frame 8
+gotoAndPlay(3);
@@ -0,0 +1,19 @@
+// SWF version 8.
+
+// Frame 1
+var s = "Hello";
+trace(s.substr(0, -1));
+trace("[" + s.substr(1, -10) + "]");
+trace(s.substr(-10, 2));
+trace(s.slice(-10));
+trace(s.slice(-10, -3));
+// substring swaps its arguments when the start is after the end.
+trace(s.substring(4, 1));
+// A limit of 0 gives an empty array.
+trace(s.split(undefined, 0).length);
+// An undefined delimiter doesn't split on "undefined".
+var parts = "a undefined b".split(undefined);
+trace(parts.length);
+trace(parts[0]);
+// Surrogate pairs are combined.
+trace(String.fromCharCode(55357, 56843, 33));
@@ -0,0 +1,71 @@
+// SWF version 8.
+
+// Frame 1
+function nested() {
+    var local = "local";
+    try {
+        try {
+            throw "inner";
+        } catch (r) {
+            // The catch block runs in the function, so it sees its locals.
+            trace("inner catch: " + r + ", " + local);
+            throw r;
+        } finally {
+            trace("inner finally");
+        }
+    } catch (e) {
+        trace("outer catch: " + e);
+        local = "changed in catch";
+    }
+    trace(local);
+}
+nested();
+
+// A throw in a finally block replaces the exception being thrown.
+function replaced() {
+    try {
+        throw "a";
+    } finally {
+        throw "b";
+    }
+}
+try {
+    replaced();
+} catch (e) {
+    trace("caught: " + e);
+}
+
+function returnsFromTry() {
+    try {
+        return "returned from try";
+    } finally {
+        trace("finally after return");
+    }
+}
+trace(returnsFromTry());
+
+function returnsFromCatch() {
+    try {
+        throw "x";
+    } catch (e) {
+        return "returned from catch " + e;
+    }
+    return "returned after catch";
+}
+trace(returnsFromCatch());
+
+// The values pushed before a throw are discarded, so they can't be mistaken
+// for the names being enumerated.
+function thrower() {
+    throw "thrown";
+}
+var obj = {a: 1, b: 2};
+var count = 0;
+for (var k in obj) {
+    count++;
+    try {
+        trace(1 + thrower());
+    } catch (e) {
+    }
+}
+trace("iterations: " + count);
@@ -0,0 +1,39 @@
+// SWF version 7, 3 frames.
+// Frame 1 places two instances of an empty movie clip, named "a" and "b", on depths 1
+// and 2. Frame 2 removes both of them from the timeline before its actions run.
+
+// Frame 1
+a.onUnload = function () {
+    trace("a.onUnload " + this.getDepth());
+};
+this.createEmptyMovieClip("c", 5);
+c.onUnload = function () {
+    trace("c.onUnload " + this.getDepth());
+};
+this.createEmptyMovieClip("d", 7);
+this.createEmptyMovieClip("u", 6);
+u._x = 50;
+u.createEmptyMovieClip("inner", 1);
+
+// Frame 2
+trace("// frame 2");
+trace(a.getDepth());
+trace(typeof b);
+trace(this.getInstanceAtDepth(-16386) == a);
+c.removeMovieClip();
+trace(c.getDepth());
+trace(typeof c);
+d.removeMovieClip();
+trace(typeof d);
+u.unloadMovie();
+trace(u._name);
+trace(u._x);
+trace(typeof u.inner);
+trace(u == this.u);
+
+// Frame 3
+trace("// frame 3");
+trace(typeof a);
+trace(typeof c);
+trace(typeof this.getInstanceAtDepth(-16386));
+stop();
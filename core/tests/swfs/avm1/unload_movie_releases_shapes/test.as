@@ -0,0 +1,17 @@
+// SWF version 8, 3 frames.
+// child.swf exports a movie clip containing a 10x10 shape as "particle", and runs
+// this in its first frame:
+//     for (i = 0; i < 1000; i++) {
+//         this.attachMovie("particle", "p" + i, i);
+//     }
+//     trace("attached");
+
+// Frame 1
+loadMovieNum("child.swf", 1);
+
+// Frame 2
+
+// Frame 3
+unloadMovieNum(1);
+trace("unloaded");
+stop();
@@ -0,0 +1,22 @@
+// SWF version 8.
+
+// Frame 1
+createEmptyMovieClip("clip", 5);
+clip.existing = 1;
+with (clip) {
+    existing = 2;
+    created = 3;
+    // Bare function calls are made on the object.
+    depth = getDepth();
+}
+trace(clip.existing);
+trace(clip.created);
+trace(created);
+trace(depth);
+
+// With blocks nested more than 15 deep are skipped.
+with (clip) { with (clip) { with (clip) { with (clip) { with (clip) { with (clip) { with (clip) { with (clip) { with (clip) { with (clip) { with (clip) { with (clip) { with (clip) { with (clip) { with (clip) { fifteen = true; }}}}}}}}}}}}}}}
+with (clip) { with (clip) { with (clip) { with (clip) { with (clip) { with (clip) { with (clip) { with (clip) { with (clip) { with (clip) { with (clip) { with (clip) { with (clip) { with (clip) { with (clip) { with (clip) { sixteen = true; } }}}}}}}}}}}}}}}
+trace(fifteen);
+trace(sixteen);
+trace(clip.sixteen);
@@ -0,0 +1,136 @@
+// This test was assembled by hand, to use each conversion opcode directly; this is the
+// equivalent ActionScript. The labels name the opcode or operator that is tested.
+package {
+	public class Test {
+	}
+}
+
+function isNullString(value:*):Boolean {
+	var s:String = value;
+	return s === null;
+}
+
+function asInt(value:*):* { var i:int = value; return i; }
+function asUint(value:*):* { var u:uint = value; return u; }
+function asNumber(value:*):* { var n:Number = value; return n; }
+function asBoolean(value:*):* { var b:Boolean = value; return b; }
+function asObject(value:*):* { var o:Object = value; return o; }
+
+function typed(a:int, b:String, c:Boolean):void {
+	trace(a, b, c);
+	trace(b === null);
+}
+
+var i:int = 2147483647;
+var j:int = 65536;
+
+trace("convert_i 3.7", int(3.7));
+trace("convert_i -3.7", int(-3.7));
+trace("convert_i '0x1F'", int("0x1F"));
+trace("convert_i 4294967297", int(4294967297));
+trace("convert_i 2147483648", int(2147483648));
+trace("convert_i NaN", int(NaN));
+trace("convert_i 'abc'", int("abc"));
+trace("convert_i undefined", int(undefined));
+trace("convert_i null", int(null));
+trace("convert_i true", int(true));
+trace("convert_u -1", uint(-1));
+trace("convert_u 3.9", uint(3.9));
+trace("convert_u -0.5", uint(-0.5));
+trace("convert_u 4294967296", uint(4294967296));
+trace("convert_d '  12.5  '", Number("  12.5  "));
+trace("convert_d ''", Number(""));
+trace("convert_d '1e3'", Number("1e3"));
+trace("convert_d '12px'", Number("12px"));
+trace("convert_d null", Number(null));
+trace("convert_d undefined", Number(undefined));
+trace("convert_d true", Number(true));
+trace("convert_d 'Infinity'", Number("Infinity"));
+trace("convert_s 0.1", String(0.1));
+trace("convert_s 1e+21", String(1e+21));
+trace("convert_s 123456789012", String(123456789012));
+trace("convert_s 1e-6", String(1e-6));
+trace("convert_s 1e-7", String(1e-7));
+trace("convert_s -0", String(-0));
+trace("convert_s 1.5e+300", String(1.5e+300));
+trace("convert_s -2.5", String(-2.5));
+trace("convert_s [1,2]", String([1,2]));
+trace("convert_b ''", Boolean(""));
+trace("convert_b 'false'", Boolean("false"));
+trace("convert_b 0", Boolean(0));
+trace("convert_b NaN", Boolean(NaN));
+trace("convert_b {}", Boolean({}));
+trace("convert_b null", Boolean(null));
+trace("convert_b undefined", Boolean(undefined));
+trace("convert_b -1", Boolean(-1));
+trace("coerce_s null", isNullString(null));
+trace("coerce_s undefined", isNullString(undefined));
+trace("coerce_s 5", isNullString(5));
+trace("coerce int '7.5'", asInt("7.5"));
+trace("coerce uint -2", asUint(-2));
+trace("coerce Number '0x10'", asNumber("0x10"));
+trace("coerce Boolean 'x'", asBoolean("x"));
+trace("coerce Object undefined", asObject(undefined));
+trace("int('-12.9')", int("-12.9"));
+trace("uint(-1)", uint(-1));
+trace("Number(' 42 ')", Number(" 42 "));
+trace("String(null)", String(null));
+trace("Boolean('')", Boolean(""));
+typed("42.9", undefined, "yes");
+trace("typeof 1", typeof 1);
+trace("typeof 1.5", typeof 1.5);
+trace("typeof 's'", typeof "s");
+trace("typeof true", typeof true);
+trace("typeof undefined", typeof undefined);
+trace("typeof null", typeof null);
+trace("typeof {}", typeof {});
+trace("typeof function", typeof function() {});
+trace("typeof [1,2]", typeof [1,2]);
+trace("5 is int", 5 is int);
+trace("5.5 is int", 5.5 is int);
+trace("-1 is uint", -1 is uint);
+trace("5 is uint", 5 is uint);
+trace("5 is Number", 5 is Number);
+trace("'5' is String", "5" is String);
+trace("'5' is Number", "5" is Number);
+trace("null is Object", null is Object);
+trace("{} is Object", {} is Object);
+trace("2147483648 is int", 2147483648 is int);
+trace("5.5 as int", 5.5 as int);
+trace("5.0 as int", 5.0 as int);
+trace("'x' as String", "x" as String);
+trace("1 as String", 1 as String);
+trace("null == undefined", null == undefined);
+trace("'1' == 1", "1" == 1);
+trace("true == 1", true == 1);
+trace("'' == 0", "" == 0);
+trace("NaN == NaN", NaN == NaN);
+trace("null == 0", null == 0);
+trace("undefined == false", undefined == false);
+trace("'1e1' == 10", "1e1" == 10);
+trace("1 == 1", 1 == 1);
+trace("'1' === 1", "1" === 1);
+trace("1 === 1", 1 === 1);
+trace("null === undefined", null === undefined);
+trace("-1 === 4294967295", -1 === 4294967295);
+trace("'1' + 2", "1" + 2);
+trace("1 + 2", 1 + 2);
+trace("true + 1", true + 1);
+trace("null + 1", null + 1);
+trace("undefined + 1", undefined + 1);
+trace("'a' + null", "a" + null);
+trace("[1,2] + 3", [1,2] + 3);
+trace("0.1 + 0.2", 0.1 + 0.2);
+trace("'10' < '9'", "10" < "9");
+trace("10 < '9'", 10 < "9");
+trace("undefined < 1", undefined < 1);
+trace("null < 1", null < 1);
+trace("'a' < 'b'", "a" < "b");
+trace("2147483647 add_i 1", int(i + 1));
+trace("65536 multiply_i 65536", int(j * j));
+trace("-1 >>> 28", -1 >>> 28);
+trace("1 << 33", 1 << 33);
+trace("-16 >> 2", -16 >> 2);
+trace("~5", ~5);
+trace("7 % -3", 7 % -3);
+trace("1 / 0", 1 / 0);
@@ -0,0 +1,24 @@
+package {
+	import flash.display.MovieClip;
+
+	public class Main extends MovieClip {
+		trace("Main class initialized");
+
+		public function Main() {
+			trace("Main constructed");
+			trace(currentFrame);
+			trace(totalFrames);
+			addFrameScript(0, frame1, 2, frame3);
+		}
+
+		public function frame1():void {
+			trace("frame 1: " + currentFrame);
+		}
+
+		// The timeline then loops back to frame 1, without running the ABC or
+		// constructing the document class again.
+		public function frame3():void {
+			trace("frame 3: " + currentFrame);
+		}
+	}
+}
@@ -0,0 +1,50 @@
+package {
+	import flash.display.MovieClip;
+	import flash.display.Sprite;
+	import flash.events.Event;
+
+	public class Main extends MovieClip {
+		public function Main() {
+			var outer = new Sprite();
+			outer.name = "outer";
+			var inner = new Sprite();
+			inner.name = "inner";
+			outer.addChild(inner);
+			addChild(outer);
+
+			stage.addEventListener("custom", onStage, true);
+			stage.addEventListener("custom", onStage);
+			addEventListener("custom", onRoot, true);
+			addEventListener("custom", onRoot);
+			outer.addEventListener("custom", onOuter, true);
+			outer.addEventListener("custom", onOuter);
+			// Capture listeners on the target itself aren't called.
+			inner.addEventListener("custom", onInner, true);
+			inner.addEventListener("custom", onInner);
+
+			trace("bubbling");
+			inner.dispatchEvent(new Event("custom", true));
+			// Events that don't bubble are still captured.
+			trace("not bubbling");
+			inner.dispatchEvent(new Event("custom", false));
+			trace("dispatched on the root");
+			dispatchEvent(new Event("custom", true));
+		}
+
+		public function onStage(e) {
+			trace("stage " + e.eventPhase + " " + (e.currentTarget === e.target));
+		}
+
+		public function onRoot(e) {
+			trace("root " + e.eventPhase + " " + (e.currentTarget === e.target));
+		}
+
+		public function onOuter(e) {
+			trace("outer " + e.eventPhase + " " + (e.currentTarget === e.target));
+		}
+
+		public function onInner(e) {
+			trace("inner " + e.eventPhase + " " + (e.currentTarget === e.target));
+		}
+	}
+}
@@ -0,0 +1,57 @@
+package {
+	public class Test {
+	}
+}
+
+function thrower(value:*):void {
+	throw value;
+}
+
+try {
+	trace("before");
+	throw "a string";
+	trace("not reached");
+} catch (e:*) {
+	trace("caught " + e);
+}
+
+try {
+	throw 42;
+} catch (e:String) {
+	trace("string " + e);
+} catch (e:Number) {
+	trace("number " + e);
+}
+
+try {
+	thrower("from a function");
+} catch (e:*) {
+	trace("caught " + e);
+}
+
+try {
+	try {
+		throw "inner";
+	} catch (e:Number) {
+		trace("wrong " + e);
+	}
+} catch (e:*) {
+	trace("outer caught " + e);
+}
+
+try {
+	throw {message: "an object"};
+} catch (err:*) {
+	trace(err.message);
+}
+
+// Values left on the stack and scopes pushed in the try block are discarded.
+try {
+	with ({}) {
+		throw "thrown";
+	}
+} catch (e:*) {
+	trace("clean " + e);
+}
+
+trace("done");
@@ -0,0 +1,7 @@
+package {
+	public class Test {
+	}
+}
+
+trace("Hello from ActionScript 3!");
+trace("Two", "arguments");
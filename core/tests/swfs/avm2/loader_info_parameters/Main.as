@@ -0,0 +1,16 @@
+package {
+	import flash.display.MovieClip;
+
+	// Loaded from "http://example.com/test.swf?fromUrl=query",
+	// with the FlashVars "flashVar=flash vars".
+	public class Main extends MovieClip {
+		public function Main() {
+			trace("Main constructed");
+			trace(loaderInfo.parameters.fromUrl);
+			trace(loaderInfo.parameters.flashVar);
+			trace(loaderInfo.parameters.missing);
+			trace(loaderInfo.url);
+			trace(loaderInfo.swfVersion);
+		}
+	}
+}
@@ -0,0 +1,86 @@
+package {
+	import flash.display.MovieClip;
+	import flash.display.Sprite;
+
+	public class Main extends MovieClip {
+		public function Main() {
+			stage.addEventListener("click", onMouse, true);
+			stage.addEventListener("click", onMouse);
+			addEventListener("click", onMouse);
+
+			// A plain sprite is the target of its clicks.
+			var plain = box("plain", 0, 0, this);
+			plain.addEventListener("mouseDown", onMouse);
+			// Hits on a sprite with mouseEnabled off go to its parent, but its children
+			// still receive their own.
+			var holder = box("holder", 100, 0, this, false);
+			var disabled = box("disabled", 0, 0, holder);
+			disabled.mouseEnabled = false;
+			box("enabledChild", 0, 60, disabled);
+			// A sprite with mouseChildren off takes the hits of its children.
+			var noChildren = box("noChildren", 200, 0, this, false);
+			noChildren.mouseChildren = false;
+			box("hidden", 0, 0, noChildren);
+			// Events bubble up from the target through its ancestors.
+			var outer = box("outer", 300, 0, this, false);
+			box("inner", 0, 0, outer);
+			// stopPropagation lets the other listeners of the current object run.
+			var stopOuter = box("stopOuter", 400, 0, this, false, onStop);
+			box("stopInner", 0, 0, stopOuter);
+			// stopImmediatePropagation doesn't.
+			var immediateOuter = box("immediateOuter", 400, 100, this, false, onStopImmediate);
+			box("immediateInner", 0, 0, immediateOuter);
+			// Two quick clicks make a double click on objects with doubleClickEnabled.
+			var double = box("double", 0, 100, this);
+			double.doubleClickEnabled = true;
+			double.addEventListener("mouseUp", onMouse);
+			box("single", 100, 100, this);
+		}
+
+		function box(name, x, y, parent, fill = true, clickListener = null) {
+			var sprite = new Sprite();
+			sprite.name = name;
+			if (fill) {
+				sprite.graphics.beginFill(0x0000FF);
+				sprite.graphics.drawRect(0, 0, 50, 50);
+			}
+			sprite.x = x;
+			sprite.y = y;
+			parent.addChild(sprite);
+			if (clickListener != null) {
+				sprite.addEventListener("click", clickListener);
+			}
+			sprite.addEventListener("click", onMouse);
+			sprite.addEventListener("doubleClick", onMouse);
+			return sprite;
+		}
+
+		public function label(object) {
+			if (object === stage) {
+				return "stage";
+			}
+			if (object === this) {
+				return "root";
+			}
+			return object.name;
+		}
+
+		function describe(e) {
+			return label(e.currentTarget) + " " + e.type + " " + label(e.target) + " " + e.eventPhase;
+		}
+
+		public function onMouse(e) {
+			trace(describe(e));
+		}
+
+		public function onStop(e) {
+			trace("stopPropagation: " + describe(e));
+			e.stopPropagation();
+		}
+
+		public function onStopImmediate(e) {
+			trace("stopImmediatePropagation: " + describe(e));
+			e.stopImmediatePropagation();
+		}
+	}
+}
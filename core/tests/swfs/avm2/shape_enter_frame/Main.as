@@ -0,0 +1,44 @@
+package {
+	import flash.display.MovieClip;
+	import flash.display.Shape;
+	import flash.events.Event;
+
+	public class Main extends MovieClip {
+		var shape;
+
+		public function Main() {
+			var shape = new Shape();
+			shape.name = "shape";
+			this.shape = shape;
+			shape.graphics.beginFill(0xFF0000);
+			shape.graphics.drawRect(0, 0, 20, 10);
+			shape.graphics.endFill();
+			trace(shape.width);
+			trace(shape.height);
+
+			shape.addEventListener("added", onShapeEvent);
+			shape.addEventListener("addedToStage", onShapeEvent);
+			shape.addEventListener("removed", onShapeEvent);
+			shape.addEventListener("removedFromStage", onShapeEvent);
+			addChild(shape);
+			trace(numChildren);
+
+			addEventListener("enterFrame", onEnterFrame);
+		}
+
+		public function onShapeEvent(e) {
+			trace("shape " + e.type + " " + e.eventPhase + " " + e.target.name);
+		}
+
+		// The shape moves on every frame, until it's taken off the stage on the third.
+		public function onEnterFrame(e) {
+			shape.x = shape.x + 10;
+			trace("x " + shape.x);
+			if (shape.x == 30) {
+				removeChild(shape);
+				removeEventListener("enterFrame", onEnterFrame);
+				trace(numChildren);
+			}
+		}
+	}
+}
@@ -0,0 +1,50 @@
+package {
+	import flash.display.MovieClip;
+	import flash.display.Sprite;
+
+	public class Main extends MovieClip {
+		public function Main() {
+			var a = new Sprite();
+			a.name = "a";
+			var b = new Sprite();
+			b.name = "b";
+			var c = new Sprite();
+			c.name = "c";
+			b.addChild(c);
+			a.addChild(b);
+
+			for each (var child in [a, b, c]) {
+				child.addEventListener("added", onChildEvent);
+				child.addEventListener("addedToStage", onChildEvent);
+				child.addEventListener("removed", onChildEvent);
+				child.addEventListener("removedFromStage", onChildEvent);
+			}
+			addEventListener("added", onRootEvent);
+			addEventListener("removed", onRootEvent);
+
+			// `added` bubbles from the added object, and `addedToStage` is sent to it and
+			// then its descendants.
+			trace("add a");
+			addChild(a);
+			trace("add d to a");
+			var d = new Sprite();
+			d.name = "d";
+			d.addEventListener("addedToStage", onChildEvent);
+			a.addChild(d);
+			// The removal events are sent while the objects are still on the stage.
+			trace("remove a");
+			removeChild(a);
+			// Objects moved from a container off the stage are only added to the stage.
+			trace("add c to the root");
+			addChild(c);
+		}
+
+		public function onChildEvent(e) {
+			trace(e.currentTarget.name + " " + e.type + " " + e.target.name + " " + (e.currentTarget.stage != null));
+		}
+
+		public function onRootEvent(e) {
+			trace("root " + e.type + " " + e.target.name);
+		}
+	}
+}
@@ -4,10 +4,12 @@ use ruffle_core::backend::audio::decoders::{
     self, AdpcmDecoder, Mp3Decoder, PcmDecoder, SeekableDecoder,
 };
 use ruffle_core::backend::audio::{
-    swf, AudioBackend, AudioStreamHandle, SoundHandle, SoundInstanceHandle,
+    sound_position, swf, AudioBackend, AudioStreamHandle, SoundHandle, SoundInstanceHandle,
+    SoundTransform,
 };
 use ruffle_core::tag_utils::SwfSlice;
 use std::io::Cursor;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::{Arc, Mutex};
 use swf::AudioCompression;
 
@@ -19,6 +21,12 @@ pub struct CpalAudioBackend {
 
     sounds: Arena<Sound>,
     sound_instances: Arc<Mutex<Arena<SoundInstance>>>,
+
+    /// Sound instances that the audio thread found to have finished playing on their own.
+    completed_instances: Arc<Mutex<Vec<SoundInstanceHandle>>>,
+
+    /// Whether the audio thread outputs silence instead of mixing sounds.
+    is_paused: Arc<AtomicBool>,
 }
 
 type Signal = Box<dyn Send + sample::signal::Signal<Frame = [i16; 2]>>;
@@ -60,6 +68,17 @@ struct SoundInstance {
     /// If this flag is false, the sound will be cleaned up during the
     /// next loop of the sound thread.
     active: bool,
+
+    /// The gains applied to the left and right channels,
+    /// from `SoundTransform::channel_gains`.
+    channel_gains: [f32; 4],
+
+    /// The settings and duration in milliseconds of this event sound, used to calculate
+    /// its position. `None` if this is a stream sound.
+    position_info: Option<(swf::SoundInfo, u32)>,
+
+    /// The number of sample frames that have been output for this sound.
+    num_frames_played: u64,
 }
 
 impl CpalAudioBackend {
@@ -105,10 +124,14 @@ impl CpalAudioBackend {
             .map_err(|_| "Unable to start audio stream")?;
 
         let sound_instances: Arc<Mutex<Arena<SoundInstance>>> = Arc::new(Mutex::new(Arena::new()));
+        let completed_instances = Arc::new(Mutex::new(Vec::new()));
+        let is_paused = Arc::new(AtomicBool::new(false));
 
         // Start the audio thread.
         let audio_thread_handle = {
             let sound_instances = Arc::clone(&sound_instances);
+            let completed_instances = Arc::clone(&completed_instances);
+            let is_paused = Arc::clone(&is_paused);
             std::thread::spawn(move || {
                 event_loop.run(move |stream_id, stream_result| {
                     use cpal::{StreamData, UnknownTypeOutputBuffer};
@@ -122,21 +145,41 @@ impl CpalAudioBackend {
                     };
 
                     let mut sound_instances = sound_instances.lock().unwrap();
+                    let mut completed_instances = completed_instances.lock().unwrap();
+                    let is_paused = is_paused.load(Ordering::Relaxed);
                     match stream_data {
                         StreamData::Output {
                             buffer: UnknownTypeOutputBuffer::U16(buffer),
                         } => {
-                            Self::mix_audio(&mut sound_instances, &output_format, buffer);
+                            Self::mix_audio(
+                                &mut sound_instances,
+                                &mut completed_instances,
+                                &output_format,
+                                buffer,
+                                is_paused,
+                            );
                         }
                         StreamData::Output {
                             buffer: UnknownTypeOutputBuffer::I16(buffer),
                         } => {
-                            Self::mix_audio(&mut sound_instances, &output_format, buffer);
+                            Self::mix_audio(
+                                &mut sound_instances,
+                                &mut completed_instances,
+                                &output_format,
+                                buffer,
+                                is_paused,
+                            );
                         }
                         StreamData::Output {
                             buffer: UnknownTypeOutputBuffer::F32(buffer),
                         } => {
-                            Self::mix_audio(&mut sound_instances, &output_format, buffer);
+                            Self::mix_audio(
+                                &mut sound_instances,
+                                &mut completed_instances,
+                                &output_format,
+                                buffer,
+                                is_paused,
+                            );
                         }
                         _ => (),
                     }
@@ -150,6 +193,8 @@ impl CpalAudioBackend {
             audio_thread_handle,
             sounds: Arena::new(),
             sound_instances,
+            completed_instances,
+            is_paused,
         })
     }
 
@@ -159,6 +204,18 @@ impl CpalAudioBackend {
         data: Cursor<VecAsRef>,
     ) -> Result<Box<dyn Send + SeekableDecoder>, Error> {
         let decoder: Box<dyn Send + SeekableDecoder> = match format.compression {
+            AudioCompression::UncompressedUnknownEndian => {
+                // Cross fingers that it's little endian.
+                log::warn!(
+                    "make_seekable_decoder: PCM sound is unknown endian; assuming little endian"
+                );
+                Box::new(PcmDecoder::new(
+                    data,
+                    format.is_stereo,
+                    format.sample_rate,
+                    format.is_16_bit,
+                ))
+            }
             AudioCompression::Uncompressed => Box::new(PcmDecoder::new(
                 data,
                 format.is_stereo,
@@ -175,6 +232,15 @@ impl CpalAudioBackend {
                 format.sample_rate.into(),
                 data,
             )),
+            AudioCompression::Nellymoser
+            | AudioCompression::Nellymoser8Khz
+            | AudioCompression::Nellymoser16Khz => {
+                // TODO: Decode Nellymoser, and test it against clips encoded by
+                // Flash. Until then, these sounds fail to play.
+                let msg = "make_seekable_decoder: Nellymoser audio is not supported";
+                log::error!("{}", msg);
+                return Err(msg.into());
+            }
             _ => {
                 let msg = format!(
                     "start_stream: Unhandled audio compression {:?}",
@@ -264,10 +330,14 @@ impl CpalAudioBackend {
     /// Callback to the audio thread.
     /// Refill the output buffer by stepping through all active sounds
     /// and mixing in their output.
+    /// While paused, the buffer is filled with silence and no sounds advance.
+    /// Sounds that finish are removed and queued in `completed_instances`.
     fn mix_audio<'a, T>(
         sound_instances: &mut Arena<SoundInstance>,
+        completed_instances: &mut Vec<SoundInstanceHandle>,
         output_format: &cpal::Format,
         mut output_buffer: cpal::OutputBuffer<'a, T>,
+        is_paused: bool,
     ) where
         T: 'a + cpal::Sample + Default + sample::Sample,
         T::Signed: sample::conv::FromSample<i16>,
@@ -284,13 +354,22 @@ impl CpalAudioBackend {
             .chunks_exact_mut(output_format.channels.into())
         {
             let mut output_frame = Stereo::<T::Signed>::equilibrium();
-            for (_, sound) in sound_instances.iter_mut() {
-                if sound.active && !sound.signal.is_exhausted() {
-                    let sound_frame = sound.signal.next();
-                    let sound_frame: Stereo<T::Signed> = sound_frame.map(Sample::to_sample);
-                    output_frame = output_frame.add_amp(sound_frame);
-                } else {
-                    sound.active = false;
+            if !is_paused {
+                for (_, sound) in sound_instances.iter_mut() {
+                    if sound.active && !sound.signal.is_exhausted() {
+                        let [left, right] = sound.signal.next();
+                        sound.num_frames_played += 1;
+                        let (left, right) = (f32::from(left), f32::from(right));
+                        let [ll, lr, rl, rr] = sound.channel_gains;
+                        let sound_frame = [
+                            (left * ll + right * rl) as i16,
+                            (left * lr + right * rr) as i16,
+                        ];
+                        let sound_frame: Stereo<T::Signed> = sound_frame.map(Sample::to_sample);
+                        output_frame = output_frame.add_amp(sound_frame);
+                    } else {
+                        sound.active = false;
+                    }
                 }
             }
 
@@ -300,7 +379,12 @@ impl CpalAudioBackend {
         }
 
         // Remove all dead sounds.
-        sound_instances.retain(|_, sound| sound.active);
+        sound_instances.retain(|handle, sound| {
+            if !sound.active {
+                completed_instances.push(handle);
+            }
+            sound.active
+        });
     }
 }
 
@@ -344,6 +428,9 @@ impl AudioBackend for CpalAudioBackend {
             clip_id: Some(clip_id),
             signal,
             active: true,
+            channel_gains: SoundTransform::default().channel_gains(),
+            position_info: None,
+            num_frames_played: 0,
         });
         Ok(handle)
     }
@@ -358,7 +445,10 @@ impl AudioBackend for CpalAudioBackend {
         sound_handle: SoundHandle,
         settings: &swf::SoundInfo,
     ) -> Result<SoundInstanceHandle, Error> {
-        let sound = &self.sounds[sound_handle];
+        let sound = self
+            .sounds
+            .get(sound_handle)
+            .ok_or("Unknown sound handle")?;
         let data = Cursor::new(VecAsRef(Arc::clone(&sound.data)));
         // Create a signal that decodes and resamples the sound.
         let signal = if sound.skip_sample_frames == 0
@@ -374,6 +464,8 @@ impl AudioBackend for CpalAudioBackend {
             self.make_signal_from_event_sound(&sound, settings, data)?
         };
 
+        let duration = self.get_sound_duration(sound_handle).unwrap_or(0);
+
         // Add sound instance to active list.
         let mut sound_instances = self.sound_instances.lock().unwrap();
         let handle = sound_instances.insert(SoundInstance {
@@ -381,6 +473,9 @@ impl AudioBackend for CpalAudioBackend {
             clip_id: None,
             signal,
             active: true,
+            channel_gains: SoundTransform::default().channel_gains(),
+            position_info: Some((settings.clone(), duration)),
+            num_frames_played: 0,
         });
         Ok(handle)
     }
@@ -390,6 +485,14 @@ impl AudioBackend for CpalAudioBackend {
         sound_instances.remove(sound);
     }
 
+    fn play(&mut self) {
+        self.is_paused.store(false, Ordering::Relaxed);
+    }
+
+    fn pause(&mut self) {
+        self.is_paused.store(true, Ordering::Relaxed);
+    }
+
     fn stop_all_sounds(&mut self) {
         let mut sound_instances = self.sound_instances.lock().unwrap();
         sound_instances.clear();
@@ -401,6 +504,11 @@ impl AudioBackend for CpalAudioBackend {
         sound_instances.retain(|_, instance| instance.handle != handle);
     }
 
+    fn release_sound(&mut self, sound: SoundHandle) {
+        self.stop_sounds_with_handle(sound);
+        self.sounds.remove(sound);
+    }
+
     fn get_sound_duration(&self, sound: SoundHandle) -> Option<u32> {
         if let Some(sound) = self.sounds.get(sound) {
             // AS duration does not subtract skip_sample_frames.
@@ -412,6 +520,25 @@ impl AudioBackend for CpalAudioBackend {
         }
     }
 
+    fn get_sound_position(&self, instance: SoundInstanceHandle) -> Option<u32> {
+        let sound_instances = self.sound_instances.lock().unwrap();
+        let instance = sound_instances.get(instance)?;
+        let elapsed = instance.num_frames_played as f64 * 1000.0
+            / f64::from(self.output_format.sample_rate.0);
+        match &instance.position_info {
+            Some((settings, duration)) => Some(sound_position(settings, *duration, elapsed)),
+            None => Some(elapsed as u32),
+        }
+    }
+
+    fn get_stream_position(&self, stream: AudioStreamHandle) -> Option<u32> {
+        let sound_instances = self.sound_instances.lock().unwrap();
+        let instance = sound_instances.get(stream)?;
+        let elapsed = instance.num_frames_played as f64 * 1000.0
+            / f64::from(self.output_format.sample_rate.0);
+        Some(elapsed as u32)
+    }
+
     fn is_sound_playing_with_handle(&mut self, handle: SoundHandle) -> bool {
         let sound_instances = self.sound_instances.lock().unwrap();
         let handle = Some(handle);
@@ -420,6 +547,18 @@ impl AudioBackend for CpalAudioBackend {
             .any(|(_, instance)| instance.handle == handle && instance.active)
     }
 
+    fn take_completed_sounds(&mut self) -> Vec<SoundInstanceHandle> {
+        let mut completed_instances = self.completed_instances.lock().unwrap();
+        std::mem::take(&mut *completed_instances)
+    }
+
+    fn set_sound_transform(&mut self, instance: SoundInstanceHandle, transform: SoundTransform) {
+        let mut sound_instances = self.sound_instances.lock().unwrap();
+        if let Some(instance) = sound_instances.get_mut(instance) {
+            instance.channel_gains = transform.channel_gains();
+        }
+    }
+
     fn tick(&mut self) {}
 }
 
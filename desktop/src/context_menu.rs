@@ -0,0 +1,36 @@
+//! Context menu for desktop ruffle
+
+use ruffle_core::ContextMenuItem;
+
+/// Asks the user to choose an item of the context menu, in place of a popup menu.
+/// Returns the index of the chosen item, or `None` if the dialog was cancelled.
+///
+/// The enabled items are listed by number, and the user types the number of one.
+// TODO: Show a native popup menu.
+pub fn choose_item(items: &[ContextMenuItem]) -> Option<usize> {
+    let enabled: Vec<usize> = items
+        .iter()
+        .enumerate()
+        .filter(|(_, item)| item.enabled)
+        .map(|(index, _)| index)
+        .collect();
+    if enabled.is_empty() {
+        return None;
+    }
+
+    let mut message = String::new();
+    for (number, &index) in enabled.iter().enumerate() {
+        let item = &items[index];
+        if item.separator_before && number > 0 {
+            message.push('\n');
+        }
+        let check = if item.checked { " (on)" } else { "" };
+        // The dialog can't display quotes.
+        let caption = item.caption.replace(&['"', '\''][..], "");
+        message.push_str(&format!("{}. {}{}\n", number + 1, caption, check));
+    }
+
+    let choice = tinyfiledialogs::input_box("Ruffle", &message, "")?;
+    let number: usize = choice.trim().parse().ok()?;
+    enabled.get(number.checked_sub(1)?).copied()
+}
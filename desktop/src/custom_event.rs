@@ -4,4 +4,7 @@
 pub enum RuffleEvent {
     /// Indicates that one or more tasks are ready to poll on our executor.
     TaskPoll,
+
+    /// The movie sent an `fscommand` for the player window to handle.
+    FsCommand { command: String, args: String },
 }
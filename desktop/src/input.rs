@@ -1,180 +1,55 @@
 use clipboard::{ClipboardContext, ClipboardProvider};
-use ruffle_core::backend::input::{InputBackend, MouseCursor};
+use image::RgbaImage;
+use ruffle_core::backend::input::{InputBackend, MouseCursor, PrintPageFormat};
+use ruffle_core::backend::render::{Bitmap, BitmapFormat};
 use ruffle_core::events::{KeyCode, PlayerEvent};
-use std::collections::HashSet;
+use std::path::{Path, PathBuf};
 use std::rc::Rc;
 use winit::event::{ElementState, VirtualKeyCode, WindowEvent};
 use winit::window::Window;
 
 pub struct WinitInputBackend {
-    keys_down: HashSet<VirtualKeyCode>,
     window: Rc<Window>,
     cursor_visible: bool,
-    last_key: KeyCode,
     clipboard: ClipboardContext,
+
+    /// The file that the user chose to save the pages of the current print job to.
+    print_path: Option<PathBuf>,
 }
 
 impl WinitInputBackend {
     pub fn new(window: Rc<Window>) -> Self {
         Self {
-            keys_down: HashSet::new(),
             cursor_visible: true,
-            last_key: KeyCode::Unknown,
             window,
             clipboard: ClipboardProvider::new().unwrap(),
+            print_path: None,
         }
     }
 
     /// Process an input event, and returns an event that should be forward to the player, if any.
     pub fn handle_event(&mut self, event: WindowEvent) -> Option<PlayerEvent> {
         match event {
-            WindowEvent::KeyboardInput { input, .. } => match input.state {
-                ElementState::Pressed => {
-                    if let Some(key) = input.virtual_keycode {
-                        self.keys_down.insert(key);
-                        if let Some(key_code) = winit_to_ruffle_key_code(key) {
-                            self.last_key = key_code;
-                            return Some(PlayerEvent::KeyDown { key_code });
-                        } else {
-                            self.last_key = KeyCode::Unknown;
-                        }
-                    }
-                }
-                ElementState::Released => {
-                    if let Some(key) = input.virtual_keycode {
-                        self.keys_down.remove(&key);
-                        if let Some(key_code) = winit_to_ruffle_key_code(key) {
-                            self.last_key = key_code;
-                            return Some(PlayerEvent::KeyUp { key_code });
-                        } else {
-                            self.last_key = KeyCode::Unknown;
-                        }
-                    }
+            WindowEvent::KeyboardInput { input, .. } => {
+                let key_code = input.virtual_keycode.and_then(winit_to_ruffle_key_code)?;
+                match input.state {
+                    ElementState::Pressed => Some(PlayerEvent::KeyDown { key_code }),
+                    ElementState::Released => Some(PlayerEvent::KeyUp { key_code }),
                 }
-            },
-            WindowEvent::ReceivedCharacter(codepoint) => {
-                return Some(PlayerEvent::TextInput { codepoint });
             }
-            _ => (),
+            WindowEvent::ReceivedCharacter(codepoint) => Some(PlayerEvent::TextInput { codepoint }),
+            _ => None,
         }
-        None
     }
-}
 
-impl InputBackend for WinitInputBackend {
-    fn is_key_down(&self, key: KeyCode) -> bool {
-        match key {
-            KeyCode::Unknown => false,
-            KeyCode::Backspace => self.keys_down.contains(&VirtualKeyCode::Back),
-            KeyCode::Return => self.keys_down.contains(&VirtualKeyCode::Return),
-            KeyCode::Shift => {
-                self.keys_down.contains(&VirtualKeyCode::LShift)
-                    || self.keys_down.contains(&VirtualKeyCode::RShift)
-            }
-            KeyCode::Control => {
-                self.keys_down.contains(&VirtualKeyCode::LControl)
-                    || self.keys_down.contains(&VirtualKeyCode::RControl)
-            }
-            KeyCode::Alt => {
-                self.keys_down.contains(&VirtualKeyCode::LAlt)
-                    || self.keys_down.contains(&VirtualKeyCode::RAlt)
-            }
-            KeyCode::CapsLock => self.keys_down.contains(&VirtualKeyCode::Capital),
-            KeyCode::Escape => self.keys_down.contains(&VirtualKeyCode::Escape),
-            KeyCode::Space => self.keys_down.contains(&VirtualKeyCode::Space),
-            KeyCode::Key0 => self.keys_down.contains(&VirtualKeyCode::Key0),
-            KeyCode::Key1 => self.keys_down.contains(&VirtualKeyCode::Key1),
-            KeyCode::Key2 => self.keys_down.contains(&VirtualKeyCode::Key2),
-            KeyCode::Key3 => self.keys_down.contains(&VirtualKeyCode::Key3),
-            KeyCode::Key4 => self.keys_down.contains(&VirtualKeyCode::Key4),
-            KeyCode::Key5 => self.keys_down.contains(&VirtualKeyCode::Key5),
-            KeyCode::Key6 => self.keys_down.contains(&VirtualKeyCode::Key6),
-            KeyCode::Key7 => self.keys_down.contains(&VirtualKeyCode::Key7),
-            KeyCode::Key8 => self.keys_down.contains(&VirtualKeyCode::Key8),
-            KeyCode::Key9 => self.keys_down.contains(&VirtualKeyCode::Key9),
-            KeyCode::A => self.keys_down.contains(&VirtualKeyCode::A),
-            KeyCode::B => self.keys_down.contains(&VirtualKeyCode::B),
-            KeyCode::C => self.keys_down.contains(&VirtualKeyCode::C),
-            KeyCode::D => self.keys_down.contains(&VirtualKeyCode::D),
-            KeyCode::E => self.keys_down.contains(&VirtualKeyCode::E),
-            KeyCode::F => self.keys_down.contains(&VirtualKeyCode::F),
-            KeyCode::G => self.keys_down.contains(&VirtualKeyCode::G),
-            KeyCode::H => self.keys_down.contains(&VirtualKeyCode::H),
-            KeyCode::I => self.keys_down.contains(&VirtualKeyCode::I),
-            KeyCode::J => self.keys_down.contains(&VirtualKeyCode::J),
-            KeyCode::K => self.keys_down.contains(&VirtualKeyCode::K),
-            KeyCode::L => self.keys_down.contains(&VirtualKeyCode::L),
-            KeyCode::M => self.keys_down.contains(&VirtualKeyCode::M),
-            KeyCode::N => self.keys_down.contains(&VirtualKeyCode::N),
-            KeyCode::O => self.keys_down.contains(&VirtualKeyCode::O),
-            KeyCode::P => self.keys_down.contains(&VirtualKeyCode::P),
-            KeyCode::Q => self.keys_down.contains(&VirtualKeyCode::Q),
-            KeyCode::R => self.keys_down.contains(&VirtualKeyCode::R),
-            KeyCode::S => self.keys_down.contains(&VirtualKeyCode::S),
-            KeyCode::T => self.keys_down.contains(&VirtualKeyCode::T),
-            KeyCode::U => self.keys_down.contains(&VirtualKeyCode::U),
-            KeyCode::V => self.keys_down.contains(&VirtualKeyCode::V),
-            KeyCode::W => self.keys_down.contains(&VirtualKeyCode::W),
-            KeyCode::X => self.keys_down.contains(&VirtualKeyCode::X),
-            KeyCode::Y => self.keys_down.contains(&VirtualKeyCode::Y),
-            KeyCode::Z => self.keys_down.contains(&VirtualKeyCode::Z),
-            KeyCode::Semicolon => self.keys_down.contains(&VirtualKeyCode::Semicolon),
-            KeyCode::Equals => self.keys_down.contains(&VirtualKeyCode::Equals),
-            KeyCode::Comma => self.keys_down.contains(&VirtualKeyCode::Comma),
-            KeyCode::Minus => self.keys_down.contains(&VirtualKeyCode::Minus),
-            KeyCode::Period => self.keys_down.contains(&VirtualKeyCode::Period),
-            KeyCode::Slash => self.keys_down.contains(&VirtualKeyCode::Slash),
-            KeyCode::Grave => self.keys_down.contains(&VirtualKeyCode::Grave),
-            KeyCode::LBracket => self.keys_down.contains(&VirtualKeyCode::LBracket),
-            KeyCode::Backslash => self.keys_down.contains(&VirtualKeyCode::Backslash),
-            KeyCode::RBracket => self.keys_down.contains(&VirtualKeyCode::RBracket),
-            KeyCode::Apostrophe => self.keys_down.contains(&VirtualKeyCode::Apostrophe),
-            KeyCode::Numpad0 => self.keys_down.contains(&VirtualKeyCode::Numpad0),
-            KeyCode::Numpad1 => self.keys_down.contains(&VirtualKeyCode::Numpad1),
-            KeyCode::Numpad2 => self.keys_down.contains(&VirtualKeyCode::Numpad2),
-            KeyCode::Numpad3 => self.keys_down.contains(&VirtualKeyCode::Numpad3),
-            KeyCode::Numpad4 => self.keys_down.contains(&VirtualKeyCode::Numpad4),
-            KeyCode::Numpad5 => self.keys_down.contains(&VirtualKeyCode::Numpad5),
-            KeyCode::Numpad6 => self.keys_down.contains(&VirtualKeyCode::Numpad6),
-            KeyCode::Numpad7 => self.keys_down.contains(&VirtualKeyCode::Numpad7),
-            KeyCode::Numpad8 => self.keys_down.contains(&VirtualKeyCode::Numpad8),
-            KeyCode::Numpad9 => self.keys_down.contains(&VirtualKeyCode::Numpad9),
-            KeyCode::Multiply => self.keys_down.contains(&VirtualKeyCode::Multiply),
-            KeyCode::Plus => self.keys_down.contains(&VirtualKeyCode::Add),
-            KeyCode::NumpadMinus => self.keys_down.contains(&VirtualKeyCode::Subtract),
-            KeyCode::NumpadPeriod => self.keys_down.contains(&VirtualKeyCode::Decimal),
-            KeyCode::NumpadSlash => self.keys_down.contains(&VirtualKeyCode::Divide),
-            KeyCode::PgUp => self.keys_down.contains(&VirtualKeyCode::PageUp),
-            KeyCode::PgDown => self.keys_down.contains(&VirtualKeyCode::PageDown),
-            KeyCode::End => self.keys_down.contains(&VirtualKeyCode::End),
-            KeyCode::Home => self.keys_down.contains(&VirtualKeyCode::Home),
-            KeyCode::Left => self.keys_down.contains(&VirtualKeyCode::Left),
-            KeyCode::Up => self.keys_down.contains(&VirtualKeyCode::Up),
-            KeyCode::Right => self.keys_down.contains(&VirtualKeyCode::Right),
-            KeyCode::Down => self.keys_down.contains(&VirtualKeyCode::Down),
-            KeyCode::Insert => self.keys_down.contains(&VirtualKeyCode::Insert),
-            KeyCode::Delete => self.keys_down.contains(&VirtualKeyCode::Delete),
-            KeyCode::Pause => self.keys_down.contains(&VirtualKeyCode::Pause),
-            KeyCode::ScrollLock => self.keys_down.contains(&VirtualKeyCode::Scroll),
-            KeyCode::F1 => self.keys_down.contains(&VirtualKeyCode::F1),
-            KeyCode::F2 => self.keys_down.contains(&VirtualKeyCode::F2),
-            KeyCode::F3 => self.keys_down.contains(&VirtualKeyCode::F3),
-            KeyCode::F4 => self.keys_down.contains(&VirtualKeyCode::F4),
-            KeyCode::F5 => self.keys_down.contains(&VirtualKeyCode::F5),
-            KeyCode::F6 => self.keys_down.contains(&VirtualKeyCode::F6),
-            KeyCode::F7 => self.keys_down.contains(&VirtualKeyCode::F7),
-            KeyCode::F8 => self.keys_down.contains(&VirtualKeyCode::F8),
-            KeyCode::F9 => self.keys_down.contains(&VirtualKeyCode::F9),
-            KeyCode::F10 => self.keys_down.contains(&VirtualKeyCode::F10),
-            KeyCode::F11 => self.keys_down.contains(&VirtualKeyCode::F11),
-            KeyCode::F12 => self.keys_down.contains(&VirtualKeyCode::F12),
-        }
-    }
-
-    fn get_last_key_code(&self) -> KeyCode {
-        self.last_key
+    /// Reapplies the cursor visibility, which some platforms reset while the
+    /// window doesn't have focus.
+    pub fn refresh_cursor(&self) {
+        self.window.set_cursor_visible(self.cursor_visible);
     }
+}
 
+impl InputBackend for WinitInputBackend {
     fn mouse_visible(&self) -> bool {
         self.cursor_visible
     }
@@ -203,6 +78,63 @@ impl InputBackend for WinitInputBackend {
     fn set_clipboard_content(&mut self, content: String) {
         self.clipboard.set_contents(content).unwrap();
     }
+
+    /// Asks the user where to save the printed pages, in place of a print dialog.
+    /// Cancelling the dialog cancels the print job.
+    // TODO: Send the pages to a printer.
+    fn start_print_job(&mut self) -> Option<PrintPageFormat> {
+        let default_path = dirs::document_dir()
+            .or_else(dirs::home_dir)
+            .unwrap_or_default()
+            .join("Ruffle print.png");
+        let path = tinyfiledialogs::save_file_dialog_with_filter(
+            "Save printed pages",
+            &default_path.to_string_lossy(),
+            &["*.png"],
+            "PNG images",
+        )?;
+        self.print_path = Some(PathBuf::from(path));
+        Some(PrintPageFormat::letter())
+    }
+
+    /// Saves the pages as PNG files where the user chose in `start_print_job`.
+    /// When there are several pages, each file name gets the page number.
+    fn print(&mut self, pages: Vec<Bitmap>) {
+        let path = match self.print_path.take() {
+            Some(path) => path,
+            None => {
+                log::warn!("Printing pages without a print job");
+                return;
+            }
+        };
+        let page_count = pages.len();
+        for (i, page) in pages.into_iter().enumerate() {
+            let image = match page.data {
+                BitmapFormat::Rgba(data) => RgbaImage::from_raw(page.width, page.height, data),
+                BitmapFormat::Rgb(_) => None,
+            };
+            let path = if page_count > 1 {
+                page_path(&path, i + 1)
+            } else {
+                path.clone()
+            };
+            match image.map(|image| image.save(&path)) {
+                Some(Ok(())) => log::info!("Saved printed page to {}", path.display()),
+                Some(Err(e)) => log::warn!("Unable to save printed page: {}", e),
+                None => log::warn!("Unable to save printed page: invalid image"),
+            }
+        }
+    }
+}
+
+/// Returns the path that a page of a print job is saved to, by adding the page
+/// number to the file name that the user chose.
+fn page_path(path: &Path, page: usize) -> PathBuf {
+    let stem = path
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    path.with_file_name(format!("{} page {}.png", stem, page))
 }
 
 /// Converts a winit `VirtualKeyCode` into a Ruffle `KeyCode`.
@@ -210,6 +142,7 @@ impl InputBackend for WinitInputBackend {
 fn winit_to_ruffle_key_code(key_code: VirtualKeyCode) -> Option<KeyCode> {
     let out = match key_code {
         VirtualKeyCode::Back => KeyCode::Backspace,
+        VirtualKeyCode::Tab => KeyCode::Tab,
         VirtualKeyCode::Return => KeyCode::Return,
         VirtualKeyCode::LShift | VirtualKeyCode::RShift => KeyCode::Shift,
         VirtualKeyCode::LControl | VirtualKeyCode::RControl => KeyCode::Control,
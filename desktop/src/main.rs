@@ -2,6 +2,7 @@
 #![allow(clippy::unneeded_field_pattern)]
 
 mod audio;
+mod context_menu;
 mod custom_event;
 mod executor;
 mod input;
@@ -13,7 +14,11 @@ use crate::custom_event::RuffleEvent;
 use crate::executor::GlutinAsyncExecutor;
 use ruffle_core::{
     backend::audio::{AudioBackend, NullAudioBackend},
-    Player,
+    backend::font::NullFontBackend,
+    backend::video::software::SoftwareVideoBackend,
+    events::MouseWheelDelta,
+    Language, Manufacturer, OperatingSystem, Player, PlayerType, StageScaleMode,
+    SystemCapabilities, SystemProperties,
 };
 use ruffle_render_wgpu::WgpuRenderBackend;
 use std::path::PathBuf;
@@ -24,15 +29,37 @@ use crate::storage::DiskStorageBackend;
 use ruffle_core::tag_utils::SwfMovie;
 use std::rc::Rc;
 use winit::dpi::{LogicalSize, PhysicalPosition};
-use winit::event::{ElementState, MouseButton, WindowEvent};
+use winit::event::{ElementState, MouseButton, MouseScrollDelta, WindowEvent};
 use winit::event_loop::{ControlFlow, EventLoop};
-use winit::window::{Icon, WindowBuilder};
+use winit::window::{Fullscreen, Icon, Window, WindowBuilder};
 
 #[derive(StructOpt, Debug)]
 #[structopt(name = "basic")]
 struct Opt {
     #[structopt(name = "FILE", parse(from_os_str))]
     input_path: PathBuf,
+
+    /// The Flash Player version to report to the movie, such as "WIN 9,0,115,0".
+    #[structopt(long = "spoof-version")]
+    spoof_version: Option<String>,
+
+    /// A variable to pass to the movie, as "name=value", like the FlashVars of a web page.
+    /// May be given more than once.
+    #[structopt(
+        long = "parameter",
+        parse(try_from_str = parse_parameter),
+        number_of_values = 1
+    )]
+    parameters: Vec<(String, String)>,
+}
+
+/// Split a `--parameter` flag into the name and value of the variable.
+fn parse_parameter(parameter: &str) -> Result<(String, String), String> {
+    let mut split = parameter.splitn(2, '=');
+    match (split.next(), split.next()) {
+        (Some(name), Some(value)) if !name.is_empty() => Ok((name.to_owned(), value.to_owned())),
+        _ => Err(format!("Expected name=value, got \"{}\"", parameter)),
+    }
 }
 
 fn main() {
@@ -40,7 +67,7 @@ fn main() {
 
     let opt = Opt::from_args();
 
-    let ret = run_player(opt.input_path);
+    let ret = run_player(opt.input_path, opt.spoof_version, opt.parameters);
 
     if let Err(e) = ret {
         eprintln!("Fatal error:\n{}", e);
@@ -48,8 +75,41 @@ fn main() {
     }
 }
 
-fn run_player(input_path: PathBuf) -> Result<(), Box<dyn std::error::Error>> {
-    let movie = SwfMovie::from_path(&input_path)?;
+/// Describe the host system to the movie.
+fn system_properties(window: &Window, version: Option<String>) -> SystemProperties {
+    let (os, manufacturer) = if cfg!(windows) {
+        (OperatingSystem::WindowsUnknown, Manufacturer::Windows)
+    } else if cfg!(target_os = "macos") {
+        (OperatingSystem::MacOS, Manufacturer::Macintosh)
+    } else {
+        (OperatingSystem::Linux, Manufacturer::Linux)
+    };
+    let language = std::env::var("LANG")
+        .map(|lang| Language::from_language_code(&lang))
+        .unwrap_or(Language::English);
+    let screen_size = window.current_monitor().size();
+
+    SystemProperties {
+        capabilities: SystemCapabilities::Audio
+            | SystemCapabilities::MP3
+            | SystemCapabilities::StreamingAudio,
+        player_type: PlayerType::StandAlone,
+        language,
+        screen_resolution: (screen_size.width, screen_size.height),
+        manufacturer,
+        os,
+        version,
+        ..Default::default()
+    }
+}
+
+fn run_player(
+    input_path: PathBuf,
+    spoof_version: Option<String>,
+    parameters: Vec<(String, String)>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let mut movie = SwfMovie::from_path(&input_path)?;
+    movie.append_parameters(parameters);
     let movie_size = LogicalSize::new(movie.width(), movie.height());
 
     let icon_bytes = include_bytes!("../assets/favicon-32.rgba");
@@ -91,8 +151,35 @@ fn run_player(input_path: PathBuf) -> Result<(), Box<dyn std::error::Error>> {
     let storage = Box::new(DiskStorageBackend::new(
         input_path.file_name().unwrap_or_default().as_ref(),
     ));
-    let player = Player::new(renderer, audio, navigator, input, movie, storage)?;
+    let fonts = Box::new(NullFontBackend::new()); //TODO: look up system fonts
+    let video = Box::new(SoftwareVideoBackend::new());
+    let player = Player::new(
+        renderer, audio, navigator, input, movie, storage, fonts, video,
+    )?;
     player.lock().unwrap().set_is_playing(true); // Desktop player will auto-play.
+    player
+        .lock()
+        .unwrap()
+        .set_system_properties(system_properties(&window, spoof_version));
+
+    // The window acts on the commands of a standalone projector.
+    let fs_command_proxy = event_loop.create_proxy();
+    player
+        .lock()
+        .unwrap()
+        .set_fs_command_handler(Box::new(move |command, args| {
+            let command = command.to_ascii_lowercase();
+            match command.as_str() {
+                "fullscreen" | "quit" | "allowscale" => {
+                    let _ = fs_command_proxy.send_event(RuffleEvent::FsCommand {
+                        command,
+                        args: args.to_string(),
+                    });
+                    true
+                }
+                _ => false,
+            }
+        }));
 
     player
         .lock()
@@ -172,6 +259,46 @@ fn run_player(input_path: PathBuf) -> Result<(), Box<dyn std::error::Error>> {
                             window.request_redraw();
                         }
                     }
+                    WindowEvent::MouseInput {
+                        button: MouseButton::Right,
+                        state: ElementState::Pressed,
+                        ..
+                    } => {
+                        let mut player_lock = player.lock().unwrap();
+                        let items = player_lock.prepare_context_menu();
+                        match context_menu::choose_item(&items) {
+                            Some(index) => player_lock.run_context_menu_callback(index),
+                            None => player_lock.clear_custom_menu_items(),
+                        }
+                        if player_lock.needs_render() {
+                            window.request_redraw();
+                        }
+                    }
+                    WindowEvent::MouseWheel { delta, .. } => {
+                        let mut player_lock = player.lock().unwrap();
+                        let delta = match delta {
+                            // winit reports a notch of the wheel as one line.
+                            MouseScrollDelta::LineDelta(_, dy) => {
+                                MouseWheelDelta::Lines(f64::from(dy) * 3.0)
+                            }
+                            MouseScrollDelta::PixelDelta(position) => {
+                                MouseWheelDelta::Pixels(position.y)
+                            }
+                        };
+                        player_lock.handle_event(ruffle_core::PlayerEvent::MouseWheel { delta });
+                        if player_lock.needs_render() {
+                            window.request_redraw();
+                        }
+                    }
+                    WindowEvent::Focused(true) => {
+                        player
+                            .lock()
+                            .unwrap()
+                            .input_mut()
+                            .downcast_mut::<input::WinitInputBackend>()
+                            .unwrap()
+                            .refresh_cursor();
+                    }
                     WindowEvent::CursorLeft { .. } => {
                         let mut player_lock = player.lock().unwrap();
                         player_lock.handle_event(ruffle_core::PlayerEvent::MouseLeft);
@@ -200,6 +327,25 @@ fn run_player(input_path: PathBuf) -> Result<(), Box<dyn std::error::Error>> {
                     .lock()
                     .expect("active executor reference")
                     .poll_all(),
+                winit::event::Event::UserEvent(RuffleEvent::FsCommand { command, args }) => {
+                    let enabled = args.eq_ignore_ascii_case("true");
+                    match command.as_str() {
+                        "fullscreen" if enabled => window
+                            .set_fullscreen(Some(Fullscreen::Borderless(window.current_monitor()))),
+                        "fullscreen" => window.set_fullscreen(None),
+                        "quit" => *control_flow = ControlFlow::Exit,
+                        "allowscale" => {
+                            let scale_mode = if enabled {
+                                StageScaleMode::ShowAll
+                            } else {
+                                StageScaleMode::NoScale
+                            };
+                            player.lock().unwrap().set_scale_mode(scale_mode);
+                            window.request_redraw();
+                        }
+                        _ => (),
+                    }
+                }
                 _ => (),
             }
 
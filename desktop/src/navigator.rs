@@ -1,15 +1,22 @@
 //! Navigator backend for web
 
 use crate::custom_event::RuffleEvent;
+use chrono::{Local, NaiveDateTime, TimeZone};
+use generational_arena::Arena;
 use ruffle_core::backend::navigator::{
-    NavigationMethod, NavigatorBackend, OwnedFuture, RequestOptions,
+    Delayed, NavigationMethod, NavigationTarget, NavigatorBackend, OwnedFuture, RequestOptions,
+    ResponseBody, SocketEvent, SocketHandle,
 };
 use ruffle_core::loader::Error;
 use std::collections::HashMap;
 use std::fs;
+use std::io::{Read, Write};
+use std::net::{Shutdown, TcpStream};
 use std::path::{Path, PathBuf};
-use std::sync::mpsc::Sender;
-use std::time::{Duration, Instant};
+use std::sync::mpsc::{channel, Receiver, Sender};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 use url::Url;
 use winit::event_loop::EventLoopProxy;
 
@@ -27,6 +34,48 @@ pub struct ExternalNavigatorBackend {
 
     /// The time that the SWF was launched.
     start_time: Instant,
+
+    /// Open socket connections, and the channels used to send commands to their threads.
+    sockets: Arena<Sender<SocketCommand>>,
+
+    /// Socket events reported by the socket threads, waiting to be polled.
+    socket_events: Arc<Mutex<Vec<SocketEvent>>>,
+}
+
+/// The number of bytes of a local file read by each chunk of `FileBody`.
+const FILE_CHUNK_SIZE: u64 = 64 * 1024;
+
+/// The body of a local file fetched by `fetch_stream`.
+///
+/// Each chunk is returned after yielding to the event loop, so that the player
+/// keeps running while a large file loads.
+struct FileBody {
+    file: fs::File,
+    total_length: Option<usize>,
+}
+
+impl ResponseBody for FileBody {
+    fn total_length(&self) -> Option<usize> {
+        self.total_length
+    }
+
+    fn next_chunk(&mut self) -> OwnedFuture<Option<Vec<u8>>, Error> {
+        let mut chunk = Vec::new();
+        let result = (&mut self.file)
+            .take(FILE_CHUNK_SIZE)
+            .read_to_end(&mut chunk);
+        match result {
+            Ok(0) => Box::pin(async { Ok(None) }),
+            Ok(_) => Box::pin(Delayed::new(Some(chunk))),
+            Err(e) => Box::pin(async move { Err(Error::NetworkError(e)) }),
+        }
+    }
+}
+
+/// A command sent to the thread of a socket connection.
+enum SocketCommand {
+    Send(Vec<u8>),
+    Close,
 }
 
 impl ExternalNavigatorBackend {
@@ -40,6 +89,8 @@ impl ExternalNavigatorBackend {
             event_loop,
             relative_base_path: PathBuf::new(),
             start_time: Instant::now(),
+            sockets: Arena::new(),
+            socket_events: Arc::new(Mutex::new(Vec::new())),
         }
     }
 
@@ -58,7 +109,72 @@ impl ExternalNavigatorBackend {
             event_loop,
             relative_base_path,
             start_time: Instant::now(),
+            sockets: Arena::new(),
+            socket_events: Arc::new(Mutex::new(Vec::new())),
+        }
+    }
+
+    /// Connects a socket and runs its connection, writing the data it is sent.
+    /// Received data is read on a separate thread.
+    fn run_socket(
+        socket: SocketHandle,
+        address: String,
+        commands: Receiver<SocketCommand>,
+        events: Arc<Mutex<Vec<SocketEvent>>>,
+    ) {
+        let push_event = |event| events.lock().unwrap().push(event);
+
+        let mut stream = match TcpStream::connect(&address) {
+            Ok(stream) => stream,
+            Err(e) => {
+                log::warn!("Could not connect socket to {}: {}", address, e);
+                push_event(SocketEvent::Connected(socket, false));
+                return;
+            }
+        };
+        let mut reader = match stream.try_clone() {
+            Ok(reader) => reader,
+            Err(e) => {
+                log::warn!("Could not read from socket connected to {}: {}", address, e);
+                push_event(SocketEvent::Connected(socket, false));
+                return;
+            }
+        };
+        push_event(SocketEvent::Connected(socket, true));
+
+        let reader_events = Arc::clone(&events);
+        thread::spawn(move || {
+            let mut buffer = [0; 4096];
+            loop {
+                match reader.read(&mut buffer) {
+                    Ok(0) | Err(_) => {
+                        reader_events
+                            .lock()
+                            .unwrap()
+                            .push(SocketEvent::Closed(socket));
+                        break;
+                    }
+                    Ok(len) => reader_events
+                        .lock()
+                        .unwrap()
+                        .push(SocketEvent::Data(socket, buffer[..len].to_vec())),
+                }
+            }
+        });
+
+        for command in commands {
+            match command {
+                SocketCommand::Send(data) => {
+                    if let Err(e) = stream.write_all(&data) {
+                        log::warn!("Could not send data over socket to {}: {}", address, e);
+                    }
+                }
+                SocketCommand::Close => break,
+            }
         }
+
+        // Also stops the reader thread.
+        let _ = stream.shutdown(Shutdown::Both);
     }
 }
 
@@ -66,11 +182,20 @@ impl NavigatorBackend for ExternalNavigatorBackend {
     fn navigate_to_url(
         &self,
         url: String,
-        _window_spec: Option<String>,
+        _target: NavigationTarget,
         vars_method: Option<(NavigationMethod, HashMap<String, String>)>,
     ) {
         //TODO: Should we return a result for failed opens? Does Flash care?
 
+        // There's no page to run scripts in.
+        if url
+            .get(..11)
+            .map_or(false, |scheme| scheme.eq_ignore_ascii_case("javascript:"))
+        {
+            log::warn!("Ignoring navigation to a javascript: URL: {}", url);
+            return;
+        }
+
         //NOTE: Flash desktop players / projectors ignore the window parameter,
         //      unless it's a `_layer`, and we shouldn't handle that anyway.
         let mut parsed_url = match Url::parse(&url) {
@@ -111,6 +236,23 @@ impl NavigatorBackend for ExternalNavigatorBackend {
         Instant::now().duration_since(self.start_time)
     }
 
+    fn current_time(&self) -> f64 {
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|time| time.as_secs_f64() * 1000.0)
+            .unwrap_or_default()
+    }
+
+    fn timezone_offset(&self, time: f64) -> i32 {
+        let seconds = (time / 1000.0).floor();
+        if !seconds.is_finite() || seconds.abs() > 8.64e12 {
+            return 0;
+        }
+        let utc = NaiveDateTime::from_timestamp(seconds as i64, 0);
+        let offset = Local.offset_from_utc_datetime(&utc);
+        -offset.local_minus_utc() / 60
+    }
+
     fn fetch(&self, url: &str, _options: RequestOptions) -> OwnedFuture<Vec<u8>, Error> {
         // Load from local filesystem.
         // TODO: Support network loads, honor sandbox type (local-with-filesystem, local-with-network, remote, ...)
@@ -120,6 +262,22 @@ impl NavigatorBackend for ExternalNavigatorBackend {
         Box::pin(async move { fs::read(path).map_err(Error::NetworkError) })
     }
 
+    fn fetch_stream(
+        &self,
+        url: &str,
+        _options: RequestOptions,
+    ) -> OwnedFuture<Box<dyn ResponseBody>, Error> {
+        // Load from local filesystem, a chunk at a time.
+        let mut path = self.relative_base_path.clone();
+        path.push(url);
+
+        Box::pin(async move {
+            let file = fs::File::open(path).map_err(Error::NetworkError)?;
+            let total_length = file.metadata().ok().map(|metadata| metadata.len() as usize);
+            Ok(Box::new(FileBody { file, total_length }) as Box<dyn ResponseBody>)
+        })
+    }
+
     fn spawn_future(&mut self, future: OwnedFuture<(), Error>) {
         self.channel.send(future).expect("working channel send");
 
@@ -129,4 +287,44 @@ impl NavigatorBackend for ExternalNavigatorBackend {
             );
         }
     }
+
+    fn connect_socket(&mut self, host: &str, port: u16) -> SocketHandle {
+        let (sender, receiver) = channel();
+        let socket = self.sockets.insert(sender);
+        let address = format!("{}:{}", host, port);
+        let events = Arc::clone(&self.socket_events);
+        thread::spawn(move || Self::run_socket(socket, address, receiver, events));
+        socket
+    }
+
+    fn send_socket(&mut self, socket: SocketHandle, data: Vec<u8>) {
+        if let Some(sender) = self.sockets.get(socket) {
+            let _ = sender.send(SocketCommand::Send(data));
+        }
+    }
+
+    fn close_socket(&mut self, socket: SocketHandle) {
+        if let Some(sender) = self.sockets.remove(socket) {
+            let _ = sender.send(SocketCommand::Close);
+        }
+    }
+
+    fn poll_socket_events(&mut self) -> Vec<SocketEvent> {
+        let events = std::mem::take(&mut *self.socket_events.lock().unwrap());
+        let mut polled_events = Vec::with_capacity(events.len());
+        for event in events {
+            // Ignore events of sockets that were closed by the player.
+            if !self.sockets.contains(event.socket()) {
+                continue;
+            }
+            match event {
+                SocketEvent::Connected(socket, false) | SocketEvent::Closed(socket) => {
+                    self.sockets.remove(socket);
+                }
+                _ => (),
+            }
+            polled_events.push(event);
+        }
+        polled_events
+    }
 }
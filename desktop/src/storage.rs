@@ -1,7 +1,6 @@
 use ruffle_core::backend::storage::StorageBackend;
 use std::fs;
-use std::fs::File;
-use std::io::{Read, Write};
+use std::io::ErrorKind;
 use std::path::{Path, PathBuf};
 
 pub struct DiskStorageBackend {
@@ -25,50 +24,35 @@ impl DiskStorageBackend {
 
         DiskStorageBackend { base_path }
     }
+
+    /// Returns the path of the `.sol` file that a value is stored in.
+    fn path(&self, name: &str) -> PathBuf {
+        self.base_path.join(Path::new(&format!("{}.sol", name)))
+    }
 }
 
 impl StorageBackend for DiskStorageBackend {
-    fn get_string(&self, name: &str) -> Option<String> {
-        let full_path = self.base_path.join(Path::new(name));
-
-        match File::open(full_path) {
-            Ok(mut file) => {
-                let mut buffer = String::new();
-                if let Err(r) = file.read_to_string(&mut buffer) {
-                    log::warn!("Unable to read file content {:?}", r);
-                    None
-                } else {
-                    Some(buffer)
-                }
-            }
+    fn get(&self, name: &str) -> Option<Vec<u8>> {
+        match fs::read(self.path(name)) {
+            Ok(data) => Some(data),
+            Err(r) if r.kind() == ErrorKind::NotFound => None,
             Err(r) => {
-                log::warn!("Unable to open file {:?}", r);
+                log::warn!("Unable to read file {:?}", r);
                 None
             }
         }
     }
 
-    fn put_string(&mut self, name: &str, value: String) -> bool {
-        let full_path = self.base_path.join(Path::new(name));
-
-        match File::create(full_path) {
-            Ok(mut file) => {
-                if let Err(r) = file.write_all(value.as_bytes()) {
-                    log::warn!("Unable to write file content {:?}", r);
-                    false
-                } else {
-                    true
-                }
-            }
-            Err(r) => {
-                log::warn!("Unable to save file {:?}", r);
-                false
-            }
+    fn put(&mut self, name: &str, value: &[u8]) -> bool {
+        if let Err(r) = fs::write(self.path(name), value) {
+            log::warn!("Unable to save file {:?}", r);
+            false
+        } else {
+            true
         }
     }
 
     fn remove_key(&mut self, name: &str) {
-        let full_path = self.base_path.join(Path::new(name));
-        let _ = fs::remove_file(full_path);
+        let _ = fs::remove_file(self.path(name));
     }
 }
@@ -2,9 +2,12 @@ use futures::executor::block_on;
 use image::RgbaImage;
 use indicatif::{ProgressBar, ProgressStyle};
 use ruffle_core::backend::audio::NullAudioBackend;
+use ruffle_core::backend::font::NullFontBackend;
 use ruffle_core::backend::input::NullInputBackend;
 use ruffle_core::backend::navigator::NullNavigatorBackend;
+use ruffle_core::backend::render::BitmapFormat;
 use ruffle_core::backend::storage::MemoryStorageBackend;
+use ruffle_core::backend::video::NullVideoBackend;
 use ruffle_core::tag_utils::SwfMovie;
 use ruffle_core::Player;
 use ruffle_render_wgpu::target::TextureTarget;
@@ -86,6 +89,8 @@ fn take_screenshot(
         Box::new(NullInputBackend::new()),
         movie,
         Box::new(MemoryStorageBackend::default()),
+        Box::new(NullFontBackend::new()),
+        Box::new(NullVideoBackend::new()),
     )?;
 
     player
@@ -106,14 +111,17 @@ fn take_screenshot(
         }
         player.lock().unwrap().run_frame();
         if i >= skipframes {
-            player.lock().unwrap().render();
-            let mut player = player.lock().unwrap();
-            let renderer = player
-                .renderer_mut()
-                .downcast_mut::<WgpuRenderBackend<TextureTarget>>()
-                .unwrap();
-            let target = renderer.target();
-            if let Some(image) = target.capture(renderer.device()) {
+            let image = player
+                .lock()
+                .unwrap()
+                .capture_frame(width, height)
+                .and_then(|bitmap| match bitmap.data {
+                    BitmapFormat::Rgba(data) => {
+                        RgbaImage::from_raw(bitmap.width, bitmap.height, data)
+                    }
+                    BitmapFormat::Rgb(_) => None,
+                });
+            if let Some(image) = image {
                 result.push(image);
             } else {
                 return Err(format!("Unable to capture frame {} of {:?}", i, swf_path).into());
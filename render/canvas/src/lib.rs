@@ -1,10 +1,11 @@
 use ruffle_core::backend::render::{
     swf::{self, CharacterId, GradientInterpolation, GradientSpread},
-    Bitmap, BitmapFormat, BitmapHandle, BitmapInfo, Color, JpegTagFormat, Letterbox, RenderBackend,
-    ShapeHandle, Transform,
+    Bitmap, BitmapCacheHandle, BitmapFormat, BitmapHandle, BitmapInfo, Color, JpegTagFormat,
+    Letterbox, RenderBackend, ShapeHandle, Transform,
 };
 use ruffle_core::color_transform::ColorTransform;
 use ruffle_core::shape_utils::{DistilledShape, DrawCommand};
+use ruffle_core::StageQuality;
 use ruffle_web_common::JsResult;
 use std::collections::HashMap;
 use std::convert::TryInto;
@@ -22,6 +23,10 @@ pub struct WebCanvasRenderBackend {
     root_canvas: HtmlCanvasElement,
     render_targets: Vec<(HtmlCanvasElement, CanvasRenderingContext2d)>,
     cur_render_target: usize,
+    filter_stack: Vec<String>,
+    blend_mode_stack: Vec<swf::BlendMode>,
+    bitmap_caches: Vec<Option<HtmlCanvasElement>>,
+    bitmap_cache_stack: Vec<BitmapCacheHandle>,
     color_matrix: Element,
     shapes: Vec<ShapeData>,
     bitmaps: Vec<BitmapData>,
@@ -30,6 +35,11 @@ pub struct WebCanvasRenderBackend {
     viewport_height: u32,
     use_color_transform_hack: bool,
     pixelated_property_value: &'static str,
+    quality: StageQuality,
+
+    /// The viewport size to restore at the end of the image being rendered offscreen
+    /// by `begin_capture`.
+    capture: Option<(u32, u32)>,
 }
 
 /// Canvas-drawable shape data extracted from an SWF file.
@@ -204,6 +214,10 @@ impl WebCanvasRenderBackend {
             root_canvas: canvas.clone(),
             render_targets,
             cur_render_target: 0,
+            filter_stack: vec![],
+            blend_mode_stack: vec![],
+            bitmap_caches: vec![],
+            bitmap_cache_stack: vec![],
             color_matrix,
             context,
             shapes: vec![],
@@ -220,6 +234,8 @@ impl WebCanvasRenderBackend {
             } else {
                 "pixelated"
             },
+            quality: StageQuality::High,
+            capture: None,
         };
         Ok(renderer)
     }
@@ -375,8 +391,8 @@ impl WebCanvasRenderBackend {
     ) -> Result<BitmapInfo, Error> {
         let data = ruffle_core::backend::render::remove_invalid_jpeg_data(data);
         let mut decoder = jpeg_decoder::Decoder::new(&data[..]);
-        decoder.read_info().unwrap();
-        let metadata = decoder.info().unwrap();
+        decoder.read_info()?;
+        let metadata = decoder.info().ok_or("Unable to get image info")?;
 
         let image = HtmlImageElement::new().unwrap();
         let jpeg_encoded = format!("data:image/jpeg;base64,{}", &base64::encode(&data[..]));
@@ -510,6 +526,25 @@ impl RenderBackend for WebCanvasRenderBackend {
         self.register_shape((&shape).into())
     }
 
+    fn release_shape(&mut self, shape: ShapeHandle) {
+        if let Some(data) = self.shapes.get_mut(shape.0) {
+            data.0.clear();
+        }
+    }
+
+    fn release_bitmap(&mut self, bitmap: BitmapHandle) {
+        self.id_to_bitmap.retain(|_, handle| handle.0 != bitmap.0);
+        // Handles index into `bitmaps`, so the slot is kept but its image is dropped.
+        if let Some(bitmap) = self.bitmaps.get_mut(bitmap.0) {
+            *bitmap = BitmapData {
+                image: HtmlImageElement::new().unwrap(),
+                width: 0,
+                height: 0,
+                data: String::new(),
+            };
+        }
+    }
+
     fn register_bitmap_jpeg(
         &mut self,
         id: CharacterId,
@@ -570,6 +605,63 @@ impl RenderBackend for WebCanvasRenderBackend {
         })
     }
 
+    fn register_bitmap_rgba(
+        &mut self,
+        id: CharacterId,
+        width: u32,
+        height: u32,
+        rgba: Vec<u8>,
+    ) -> Result<BitmapInfo, Error> {
+        self.register_bitmap_raw(
+            id,
+            Bitmap {
+                width,
+                height,
+                data: BitmapFormat::Rgba(rgba),
+            },
+        )
+    }
+
+    fn update_bitmap_rgba(
+        &mut self,
+        bitmap: BitmapHandle,
+        width: u32,
+        height: u32,
+        rgba: Vec<u8>,
+    ) -> Result<(), Error> {
+        // TODO: Shapes with bitmap fills embed the image when they are registered,
+        // so they will not see the updated pixels.
+        let png = Self::bitmap_to_png_data_uri(Bitmap {
+            width,
+            height,
+            data: BitmapFormat::Rgba(rgba),
+        })?;
+
+        let image = HtmlImageElement::new().unwrap();
+        image.set_src(&png);
+
+        if let Some(bitmap) = self.bitmaps.get_mut(bitmap.0) {
+            *bitmap = BitmapData {
+                image,
+                width,
+                height,
+                data: png,
+            };
+        }
+        Ok(())
+    }
+
+    fn set_quality(&mut self, quality: StageQuality) {
+        if self.quality != quality {
+            self.quality = quality;
+            // Cached images were rendered at the old quality; shrinking them
+            // forces a redraw on the next frame.
+            for canvas in self.bitmap_caches.iter().flatten() {
+                canvas.set_width(0);
+            }
+        }
+    }
+
     fn begin_frame(&mut self, clear: Color) {
         // Reset canvas transform in case it was left in a dirty state.
         self.context.reset_transform().unwrap();
@@ -587,13 +679,16 @@ impl RenderBackend for WebCanvasRenderBackend {
         // Noop
     }
 
-    fn render_bitmap(&mut self, bitmap: BitmapHandle, transform: &Transform) {
+    fn render_bitmap(&mut self, bitmap: BitmapHandle, transform: &Transform, smoothing: bool) {
         self.set_transform(transform);
         self.set_color_filter(transform);
         if let Some(bitmap) = self.bitmaps.get(bitmap.0) {
+            self.context
+                .set_image_smoothing_enabled(smoothing && self.quality.smooths_bitmaps());
             let _ = self
                 .context
                 .draw_image_with_html_image_element(&bitmap.image, 0.0, 0.0);
+            self.context.set_image_smoothing_enabled(true);
         }
         self.clear_color_filter();
     }
@@ -736,6 +831,313 @@ impl RenderBackend for WebCanvasRenderBackend {
             .draw_image_with_html_canvas_element(&maskee_canvas, 0.0, 0.0)
             .unwrap();
     }
+
+    fn push_filters(&mut self, filters: &[swf::Filter]) {
+        // The filtered object is rendered to its own render target,
+        // which is then drawn to the parent target with a CSS filter applied.
+        self.filter_stack.push(filters_to_css(filters));
+        self.push_render_target();
+    }
+
+    fn pop_filters(&mut self) {
+        let (filtered_canvas, _filtered_context) = self.pop_render_target();
+        let filter = self
+            .filter_stack
+            .pop()
+            .unwrap_or_else(|| "none".to_string());
+
+        self.context.reset_transform().warn_on_error();
+        self.context.set_filter(&filter);
+        self.context
+            .draw_image_with_html_canvas_element(&filtered_canvas, 0.0, 0.0)
+            .warn_on_error();
+        self.context.set_filter("none");
+    }
+
+    fn push_blend_mode(&mut self, blend_mode: swf::BlendMode) {
+        // The blended object is rendered to its own render target,
+        // which is then drawn to the parent target with the composite operation.
+        self.blend_mode_stack.push(blend_mode);
+        self.push_render_target();
+    }
+
+    fn pop_blend_mode(&mut self) {
+        let blend_mode = self
+            .blend_mode_stack
+            .pop()
+            .unwrap_or(swf::BlendMode::Normal);
+        let (width, height) = (self.viewport_width, self.viewport_height);
+
+        match blend_mode {
+            swf::BlendMode::Subtract => {
+                // Canvas can't subtract, but `dst - src` is `invert(invert(dst) + src)`.
+                // This is worked out on a copy of what is behind the object, which then
+                // replaces the parent's pixels where the object was drawn.
+                let blended_canvas = self.canvas.clone();
+                let parent_canvas = self.render_targets[self.cur_render_target - 1].0.clone();
+                self.push_render_target();
+                draw_composited(&self.context, &parent_canvas, "source-over");
+                fill_white(&self.context, width, height, "difference");
+                draw_composited(&self.context, &blended_canvas, "lighter");
+                fill_white(&self.context, width, height, "difference");
+                draw_composited(&self.context, &blended_canvas, "destination-in");
+                let (subtracted_canvas, _) = self.pop_render_target();
+                self.pop_render_target();
+
+                draw_composited(&self.context, &blended_canvas, "destination-out");
+                draw_composited(&self.context, &subtracted_canvas, "lighter");
+            }
+            swf::BlendMode::Invert => {
+                // A white silhouette of the object inverts the colors behind it.
+                let (blended_canvas, blended_context) = self.pop_render_target();
+                fill_white(&blended_context, width, height, "source-in");
+                draw_composited(&self.context, &blended_canvas, "difference");
+            }
+            blend_mode => {
+                let (blended_canvas, _blended_context) = self.pop_render_target();
+                let operation = blend_mode_to_composite_operation(blend_mode);
+                draw_composited(&self.context, &blended_canvas, operation);
+            }
+        }
+    }
+
+    fn create_bitmap_cache(&mut self) -> BitmapCacheHandle {
+        let window = web_sys::window().unwrap();
+        let document = window.document().unwrap();
+        let canvas: HtmlCanvasElement = document
+            .create_element("canvas")
+            .unwrap()
+            .dyn_into()
+            .unwrap();
+
+        // Reuse a released slot if there is one.
+        if let Some(index) = self.bitmap_caches.iter().position(Option::is_none) {
+            self.bitmap_caches[index] = Some(canvas);
+            BitmapCacheHandle(index)
+        } else {
+            self.bitmap_caches.push(Some(canvas));
+            BitmapCacheHandle(self.bitmap_caches.len() - 1)
+        }
+    }
+
+    fn render_bitmap_cache(&mut self, cache: BitmapCacheHandle) -> bool {
+        // The cached image is in stage space, so it is stale if the viewport was resized.
+        match self.bitmap_caches.get(cache.0) {
+            Some(Some(canvas))
+                if canvas.width() == self.viewport_width
+                    && canvas.height() == self.viewport_height =>
+            {
+                self.context.reset_transform().warn_on_error();
+                self.context.set_global_alpha(1.0);
+                self.context
+                    .draw_image_with_html_canvas_element(canvas, 0.0, 0.0)
+                    .warn_on_error();
+                true
+            }
+            _ => false,
+        }
+    }
+
+    fn begin_bitmap_cache(&mut self, cache: BitmapCacheHandle) {
+        self.bitmap_cache_stack.push(cache);
+        self.push_render_target();
+    }
+
+    fn end_bitmap_cache(&mut self) {
+        let (rendered_canvas, _rendered_context) = self.pop_render_target();
+
+        // Copy the rendered image into the cache.
+        let cache = self.bitmap_cache_stack.pop();
+        if let Some(Some(canvas)) = cache.and_then(|cache| self.bitmap_caches.get(cache.0)) {
+            // Resizing the canvas also clears it.
+            canvas.set_width(self.viewport_width);
+            canvas.set_height(self.viewport_height);
+            let context: Option<CanvasRenderingContext2d> = canvas
+                .get_context("2d")
+                .ok()
+                .flatten()
+                .and_then(|context| context.dyn_into().ok());
+            if let Some(context) = context {
+                context
+                    .draw_image_with_html_canvas_element(&rendered_canvas, 0.0, 0.0)
+                    .warn_on_error();
+            }
+        }
+
+        self.context.reset_transform().warn_on_error();
+        self.context.set_global_alpha(1.0);
+        self.context
+            .draw_image_with_html_canvas_element(&rendered_canvas, 0.0, 0.0)
+            .warn_on_error();
+    }
+
+    fn release_bitmap_cache(&mut self, cache: BitmapCacheHandle) {
+        if let Some(slot) = self.bitmap_caches.get_mut(cache.0) {
+            *slot = None;
+        }
+    }
+
+    fn draw_rect(&mut self, color: Color, rect: &swf::Rectangle, transform: &Transform) {
+        self.set_transform(transform);
+        let CanvasColor(color, ..) = CanvasColor(String::new(), color.r, color.g, color.b, color.a)
+            .color_transform(&transform.color_transform);
+        self.context.set_fill_style(&color.into());
+        self.context.fill_rect(
+            rect.x_min.to_pixels(),
+            rect.y_min.to_pixels(),
+            (rect.x_max - rect.x_min).to_pixels(),
+            (rect.y_max - rect.y_min).to_pixels(),
+        );
+    }
+
+    fn begin_capture(&mut self, width: u32, height: u32, clear: Color) -> bool {
+        if self.capture.is_some() {
+            log::warn!("Can't begin a capture while another is in progress");
+            return false;
+        }
+
+        // The image is rendered into an offscreen canvas the size of the capture.
+        self.capture = Some((self.viewport_width, self.viewport_height));
+        self.viewport_width = width.max(1);
+        self.viewport_height = height.max(1);
+        self.push_render_target();
+        self.begin_frame(clear);
+        true
+    }
+
+    fn end_capture(&mut self) -> Option<Bitmap> {
+        let (viewport_width, viewport_height) = self.capture.take()?;
+        let (width, height) = (self.viewport_width, self.viewport_height);
+        let (_canvas, context) = self.pop_render_target();
+        self.viewport_width = viewport_width;
+        self.viewport_height = viewport_height;
+
+        let image_data = context
+            .get_image_data(0.0, 0.0, width.into(), height.into())
+            .into_js_result()
+            .map_err(|e| log::error!("Unable to read the captured image: {}", e))
+            .ok()?;
+        Some(Bitmap {
+            width,
+            height,
+            data: BitmapFormat::Rgba(image_data.data().0),
+        })
+    }
+}
+
+/// Converts a SWF blend mode to the equivalent canvas `globalCompositeOperation`.
+/// Subtract and Invert have no canvas equivalent, and are drawn in several steps by
+/// `pop_blend_mode`.
+fn blend_mode_to_composite_operation(blend_mode: swf::BlendMode) -> &'static str {
+    use swf::BlendMode;
+    match blend_mode {
+        BlendMode::Normal | BlendMode::Layer => "source-over",
+        BlendMode::Multiply => "multiply",
+        BlendMode::Screen => "screen",
+        BlendMode::Lighten => "lighten",
+        BlendMode::Darken => "darken",
+        BlendMode::Difference => "difference",
+        BlendMode::Add => "lighter",
+        BlendMode::Alpha => "destination-in",
+        BlendMode::Erase => "destination-out",
+        BlendMode::Overlay => "overlay",
+        BlendMode::HardLight => "hard-light",
+        BlendMode::Subtract | BlendMode::Invert => "source-over",
+    }
+}
+
+/// Draws an image over the whole of a canvas with the given composite operation.
+fn draw_composited(context: &CanvasRenderingContext2d, image: &HtmlCanvasElement, operation: &str) {
+    context.reset_transform().warn_on_error();
+    context
+        .set_global_composite_operation(operation)
+        .warn_on_error();
+    context
+        .draw_image_with_html_canvas_element(image, 0.0, 0.0)
+        .warn_on_error();
+    context
+        .set_global_composite_operation("source-over")
+        .warn_on_error();
+}
+
+/// Fills the whole of a canvas with white using the given composite operation.
+fn fill_white(context: &CanvasRenderingContext2d, width: u32, height: u32, operation: &str) {
+    context.reset_transform().warn_on_error();
+    context
+        .set_global_composite_operation(operation)
+        .warn_on_error();
+    context.set_fill_style(&"white".into());
+    context.fill_rect(0.0, 0.0, width.into(), height.into());
+    context
+        .set_global_composite_operation("source-over")
+        .warn_on_error();
+}
+
+/// The most drop shadows stacked to draw a strong glow, which are costly to render.
+const MAX_GLOW_COPIES: usize = 3;
+
+/// Converts a list of SWF filters to an equivalent CSS `filter` string.
+///
+/// Flash blur amounts are the width of the blur box, whereas CSS uses a
+/// standard deviation, so they are halved.
+/// TODO: Inner and knockout glows/shadows aren't supported by CSS filters.
+fn filters_to_css(filters: &[swf::Filter]) -> String {
+    use swf::Filter;
+
+    fn css_color(color: &Color) -> String {
+        format!(
+            "rgba({},{},{},{})",
+            color.r,
+            color.g,
+            color.b,
+            f32::from(color.a) / 255.0
+        )
+    }
+
+    let mut css = vec![];
+    for filter in filters {
+        match filter {
+            Filter::BlurFilter(blur) => {
+                css.push(format!("blur({}px)", blur.blur_x.max(blur.blur_y) / 2.0));
+            }
+            Filter::GlowFilter(glow) => {
+                // Approximate the glow strength by stacking a few copies of the glow, and
+                // spreading the rest of the strength over their opacity.
+                if glow.strength <= 0.0 {
+                    continue;
+                }
+                let copies = glow.strength.ceil().min(MAX_GLOW_COPIES as f32);
+                let alpha = (f32::from(glow.color.a) * glow.strength / copies).min(255.0);
+                let glow_css = format!(
+                    "drop-shadow(0 0 {}px {})",
+                    glow.blur_x.max(glow.blur_y) / 2.0,
+                    css_color(&Color {
+                        a: alpha as u8,
+                        ..glow.color.clone()
+                    })
+                );
+                for _ in 0..copies as usize {
+                    css.push(glow_css.clone());
+                }
+            }
+            Filter::DropShadowFilter(shadow) => {
+                css.push(format!(
+                    "drop-shadow({}px {}px {}px {})",
+                    shadow.distance * shadow.angle.cos(),
+                    shadow.distance * shadow.angle.sin(),
+                    shadow.blur_x.max(shadow.blur_y) / 2.0,
+                    css_color(&shadow.color)
+                ));
+            }
+            _ => log::warn!("Unsupported filter {:?}", filter),
+        }
+    }
+
+    if css.is_empty() {
+        "none".to_string()
+    } else {
+        css.join(" ")
+    }
 }
 
 #[allow(clippy::cognitive_complexity)]
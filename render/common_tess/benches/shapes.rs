@@ -0,0 +1,198 @@
+//! Benchmarks of shape tessellation for movies with many instances of a shape.
+//!
+//! `benches/swfs/particles.swf` attaches 1,000 clips showing the same
+//! `DefineShape`, and `benches/swfs/particles_host.swf` loads it into `_level1`
+//! and unloads it again two frames later.
+
+use bencher::{benchmark_group, benchmark_main, Bencher};
+use ruffle_core::backend::navigator::NullNavigatorBackend;
+use ruffle_core::backend::render::{
+    Bitmap, BitmapCacheHandle, BitmapHandle, BitmapInfo, Color, Letterbox, NullRenderer,
+    RenderBackend, ShapeHandle, Transform,
+};
+use ruffle_core::backend::storage::MemoryStorageBackend;
+use ruffle_core::backend::{
+    audio::NullAudioBackend, font::NullFontBackend, input::NullInputBackend,
+    navigator::NullExecutor, video::NullVideoBackend,
+};
+use ruffle_core::shape_utils::DistilledShape;
+use ruffle_core::swf;
+use ruffle_core::tag_utils::SwfMovie;
+use ruffle_core::{Player, StageQuality};
+use ruffle_render_common_tess::{Draw, ShapeTessellator};
+use std::path::Path;
+
+type Error = Box<dyn std::error::Error>;
+
+/// Loads the particle movie, renders its 1,000 clips and unloads it again.
+///
+/// Every clip shares the mesh of the shape's character, so the shape is
+/// tessellated once, and the mesh is freed when the movie is unloaded.
+fn shared_mesh(bench: &mut Bencher) {
+    bench.iter(|| {
+        let (mut executor, channel) = NullExecutor::new();
+        let player = Player::new(
+            Box::new(TessellatingRenderer::default()),
+            Box::new(NullAudioBackend::new()),
+            Box::new(NullNavigatorBackend::with_base_path(
+                Path::new("benches/swfs"),
+                channel,
+            )),
+            Box::new(NullInputBackend::new()),
+            SwfMovie::from_path("benches/swfs/particles_host.swf").unwrap(),
+            Box::new(MemoryStorageBackend::default()),
+            Box::new(NullFontBackend::new()),
+            Box::new(NullVideoBackend::new()),
+        )
+        .unwrap();
+
+        // The loader locks the player, so it can't stay locked while the
+        // executor runs.
+        player.lock().unwrap().run_frame();
+        executor.poll_all().unwrap();
+        let mut player = player.lock().unwrap();
+        player.run_frame();
+        player.render();
+        player.run_frame();
+
+        let renderer = player
+            .renderer()
+            .downcast_ref::<TessellatingRenderer>()
+            .unwrap();
+        assert_eq!(renderer.tessellated, 1);
+        assert!(renderer.meshes.iter().all(Option::is_none));
+    });
+}
+
+/// Tessellates the particle shape 1,000 times, which is what rendering the
+/// clips of `shared_mesh` would add if every instance had its own mesh.
+fn mesh_per_instance(bench: &mut Bencher) {
+    let data = std::fs::read("benches/swfs/particles.swf").unwrap();
+    let swf = swf::read_swf(&data[..]).unwrap();
+    let shape = swf
+        .tags
+        .iter()
+        .find_map(|tag| match tag {
+            swf::Tag::DefineShape(shape) => Some(shape),
+            _ => None,
+        })
+        .unwrap();
+
+    let mut tessellator = ShapeTessellator::new();
+    bench.iter(|| {
+        for _ in 0..1000 {
+            tessellator.tessellate_shape(DistilledShape::from(shape), |_| None);
+        }
+    });
+}
+
+/// A renderer that tessellates its shapes, but doesn't draw them.
+#[derive(Default)]
+struct TessellatingRenderer {
+    tessellator: ShapeTessellator,
+    meshes: Vec<Option<Vec<Draw>>>,
+    tessellated: usize,
+}
+
+impl RenderBackend for TessellatingRenderer {
+    fn set_viewport_dimensions(&mut self, _width: u32, _height: u32) {}
+    fn register_shape(&mut self, shape: DistilledShape) -> ShapeHandle {
+        let handle = ShapeHandle(self.meshes.len());
+        self.meshes
+            .push(Some(self.tessellator.tessellate_shape(shape, |_| None)));
+        self.tessellated += 1;
+        handle
+    }
+    fn replace_shape(&mut self, shape: DistilledShape, handle: ShapeHandle) {
+        self.meshes[handle.0] = Some(self.tessellator.tessellate_shape(shape, |_| None));
+        self.tessellated += 1;
+    }
+    fn register_glyph_shape(&mut self, _shape: &swf::Glyph) -> ShapeHandle {
+        let handle = ShapeHandle(self.meshes.len());
+        self.meshes.push(None);
+        handle
+    }
+    fn release_shape(&mut self, shape: ShapeHandle) {
+        self.meshes[shape.0] = None;
+    }
+    fn release_bitmap(&mut self, _bitmap: BitmapHandle) {}
+    fn register_bitmap_jpeg(
+        &mut self,
+        id: swf::CharacterId,
+        data: &[u8],
+        jpeg_tables: Option<&[u8]>,
+    ) -> Result<BitmapInfo, Error> {
+        NullRenderer.register_bitmap_jpeg(id, data, jpeg_tables)
+    }
+    fn register_bitmap_jpeg_2(
+        &mut self,
+        id: swf::CharacterId,
+        data: &[u8],
+    ) -> Result<BitmapInfo, Error> {
+        NullRenderer.register_bitmap_jpeg_2(id, data)
+    }
+    fn register_bitmap_jpeg_3(
+        &mut self,
+        id: swf::CharacterId,
+        jpeg_data: &[u8],
+        alpha_data: &[u8],
+    ) -> Result<BitmapInfo, Error> {
+        NullRenderer.register_bitmap_jpeg_3(id, jpeg_data, alpha_data)
+    }
+    fn register_bitmap_png(
+        &mut self,
+        swf_tag: &swf::DefineBitsLossless,
+    ) -> Result<BitmapInfo, Error> {
+        NullRenderer.register_bitmap_png(swf_tag)
+    }
+    fn register_bitmap_rgba(
+        &mut self,
+        id: swf::CharacterId,
+        width: u32,
+        height: u32,
+        rgba: Vec<u8>,
+    ) -> Result<BitmapInfo, Error> {
+        NullRenderer.register_bitmap_rgba(id, width, height, rgba)
+    }
+    fn update_bitmap_rgba(
+        &mut self,
+        _bitmap: BitmapHandle,
+        _width: u32,
+        _height: u32,
+        _rgba: Vec<u8>,
+    ) -> Result<(), Error> {
+        Ok(())
+    }
+    fn set_quality(&mut self, _quality: StageQuality) {}
+    fn begin_frame(&mut self, _clear: Color) {}
+    fn render_bitmap(&mut self, _bitmap: BitmapHandle, _transform: &Transform, _smoothing: bool) {}
+    fn render_shape(&mut self, _shape: ShapeHandle, _transform: &Transform) {}
+    fn end_frame(&mut self) {}
+    fn draw_letterbox(&mut self, _letterbox: Letterbox) {}
+    fn push_mask(&mut self) {}
+    fn activate_mask(&mut self) {}
+    fn pop_mask(&mut self) {}
+    fn push_filters(&mut self, _filters: &[swf::Filter]) {}
+    fn pop_filters(&mut self) {}
+    fn push_blend_mode(&mut self, _blend_mode: swf::BlendMode) {}
+    fn pop_blend_mode(&mut self) {}
+    fn create_bitmap_cache(&mut self) -> BitmapCacheHandle {
+        BitmapCacheHandle(0)
+    }
+    fn render_bitmap_cache(&mut self, _cache: BitmapCacheHandle) -> bool {
+        false
+    }
+    fn begin_bitmap_cache(&mut self, _cache: BitmapCacheHandle) {}
+    fn end_bitmap_cache(&mut self) {}
+    fn release_bitmap_cache(&mut self, _cache: BitmapCacheHandle) {}
+    fn draw_rect(&mut self, _color: Color, _rect: &swf::Rectangle, _transform: &Transform) {}
+    fn begin_capture(&mut self, _width: u32, _height: u32, _clear: Color) -> bool {
+        false
+    }
+    fn end_capture(&mut self) -> Option<Bitmap> {
+        None
+    }
+}
+
+benchmark_group!(shapes, shared_mesh, mesh_per_instance);
+benchmark_main!(shapes);
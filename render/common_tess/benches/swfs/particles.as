@@ -0,0 +1,11 @@
+// SWF version 8.
+// The library has a movie clip exported as "particle", which contains a 45x45 shape
+// centered on its origin. The script keeps p and i in registers.
+
+// Frame 1
+for (var i = 0; i < 1000; i++) {
+    var p = this.attachMovie("particle", "p" + i, i);
+    p._x = (i % 50) * 11;
+    p._y = int(i / 50) * 20;
+}
+trace("attached");
@@ -0,0 +1,8 @@
+// SWF version 8, 3 frames.
+
+// Frame 1
+loadMovieNum("particles.swf", 1);
+
+// Frame 3
+unloadMovieNum(1);
+stop();
@@ -82,28 +82,8 @@ impl ShapeTessellator {
                             continue;
                         }
 
-                        let mut colors: Vec<[f32; 4]> = Vec::with_capacity(8);
-                        let mut ratios: Vec<f32> = Vec::with_capacity(8);
-                        for record in &gradient.records {
-                            colors.push([
-                                f32::from(record.color.r) / 255.0,
-                                f32::from(record.color.g) / 255.0,
-                                f32::from(record.color.b) / 255.0,
-                                f32::from(record.color.a) / 255.0,
-                            ]);
-                            ratios.push(f32::from(record.ratio) / 255.0);
-                        }
-
-                        let gradient = Gradient {
-                            gradient_type: GradientType::Linear,
-                            ratios,
-                            colors,
-                            num_colors: gradient.records.len() as u32,
-                            matrix: swf_to_gl_matrix(gradient.matrix),
-                            repeat_mode: gradient.spread,
-                            focal_point: 0.0,
-                            interpolation: gradient.interpolation,
-                        };
+                        let gradient =
+                            swf_gradient_to_uniforms(GradientType::Linear, gradient, 0.0);
 
                         flush_draw(DrawType::Gradient(gradient), &mut mesh, &mut lyon_mesh);
                     }
@@ -125,28 +105,8 @@ impl ShapeTessellator {
                             continue;
                         }
 
-                        let mut colors: Vec<[f32; 4]> = Vec::with_capacity(8);
-                        let mut ratios: Vec<f32> = Vec::with_capacity(8);
-                        for record in &gradient.records {
-                            colors.push([
-                                f32::from(record.color.r) / 255.0,
-                                f32::from(record.color.g) / 255.0,
-                                f32::from(record.color.b) / 255.0,
-                                f32::from(record.color.a) / 255.0,
-                            ]);
-                            ratios.push(f32::from(record.ratio) / 255.0);
-                        }
-
-                        let gradient = Gradient {
-                            gradient_type: GradientType::Radial,
-                            ratios,
-                            colors,
-                            num_colors: gradient.records.len() as u32,
-                            matrix: swf_to_gl_matrix(gradient.matrix),
-                            repeat_mode: gradient.spread,
-                            focal_point: 0.0,
-                            interpolation: gradient.interpolation,
-                        };
+                        let gradient =
+                            swf_gradient_to_uniforms(GradientType::Radial, gradient, 0.0);
 
                         flush_draw(DrawType::Gradient(gradient), &mut mesh, &mut lyon_mesh);
                     }
@@ -171,28 +131,8 @@ impl ShapeTessellator {
                             continue;
                         }
 
-                        let mut colors: Vec<[f32; 4]> = Vec::with_capacity(8);
-                        let mut ratios: Vec<f32> = Vec::with_capacity(8);
-                        for record in &gradient.records {
-                            colors.push([
-                                f32::from(record.color.r) / 255.0,
-                                f32::from(record.color.g) / 255.0,
-                                f32::from(record.color.b) / 255.0,
-                                f32::from(record.color.a) / 255.0,
-                            ]);
-                            ratios.push(f32::from(record.ratio) / 255.0);
-                        }
-
-                        let gradient = Gradient {
-                            gradient_type: GradientType::Focal,
-                            ratios,
-                            colors,
-                            num_colors: gradient.records.len() as u32,
-                            matrix: swf_to_gl_matrix(gradient.matrix),
-                            repeat_mode: gradient.spread,
-                            focal_point: *focal_point,
-                            interpolation: gradient.interpolation,
-                        };
+                        let gradient =
+                            swf_gradient_to_uniforms(GradientType::Focal, gradient, *focal_point);
 
                         flush_draw(DrawType::Gradient(gradient), &mut mesh, &mut lyon_mesh);
                     }
@@ -237,10 +177,35 @@ impl ShapeTessellator {
                     commands,
                     is_closed,
                 } => {
-                    let color = ((style.color.a as u32) << 24)
-                        | ((style.color.b as u32) << 16)
-                        | ((style.color.g as u32) << 8)
-                        | (style.color.r as u32);
+                    // Gradient strokes (from `lineGradientStyle`) are drawn in their own draw call,
+                    // in the same way as gradient fills.
+                    let gradient = match &style.fill_style {
+                        Some(FillStyle::LinearGradient(gradient)) => Some(
+                            swf_gradient_to_uniforms(GradientType::Linear, gradient, 0.0),
+                        ),
+                        Some(FillStyle::RadialGradient(gradient)) => Some(
+                            swf_gradient_to_uniforms(GradientType::Radial, gradient, 0.0),
+                        ),
+                        Some(FillStyle::FocalGradient {
+                            gradient,
+                            focal_point,
+                        }) => Some(swf_gradient_to_uniforms(
+                            GradientType::Focal,
+                            gradient,
+                            *focal_point,
+                        )),
+                        _ => None,
+                    };
+
+                    let color = if gradient.is_some() {
+                        flush_draw(DrawType::Color, &mut mesh, &mut lyon_mesh);
+                        0xffff_ffff
+                    } else {
+                        ((style.color.a as u32) << 24)
+                            | ((style.color.b as u32) << 16)
+                            | ((style.color.g as u32) << 8)
+                            | (style.color.r as u32)
+                    };
 
                     let mut buffers_builder =
                         BuffersBuilder::new(&mut lyon_mesh, RuffleVertexCtor { color });
@@ -283,6 +248,10 @@ impl ShapeTessellator {
                         log::error!("Tessellation failure: {:?}", e);
                         continue;
                     }
+
+                    if let Some(gradient) = gradient {
+                        flush_draw(DrawType::Gradient(gradient), &mut mesh, &mut lyon_mesh);
+                    }
                 }
             }
         }
@@ -340,6 +309,35 @@ pub struct Bitmap {
     pub is_repeating: bool,
 }
 
+fn swf_gradient_to_uniforms(
+    gradient_type: GradientType,
+    gradient: &swf::Gradient,
+    focal_point: f32,
+) -> Gradient {
+    let mut colors: Vec<[f32; 4]> = Vec::with_capacity(8);
+    let mut ratios: Vec<f32> = Vec::with_capacity(8);
+    for record in &gradient.records {
+        colors.push([
+            f32::from(record.color.r) / 255.0,
+            f32::from(record.color.g) / 255.0,
+            f32::from(record.color.b) / 255.0,
+            f32::from(record.color.a) / 255.0,
+        ]);
+        ratios.push(f32::from(record.ratio) / 255.0);
+    }
+
+    Gradient {
+        gradient_type,
+        ratios,
+        colors,
+        num_colors: gradient.records.len() as u32,
+        matrix: swf_to_gl_matrix(gradient.matrix),
+        repeat_mode: gradient.spread,
+        focal_point,
+        interpolation: gradient.interpolation,
+    }
+}
+
 #[allow(clippy::many_single_char_names)]
 fn swf_to_gl_matrix(m: swf::Matrix) -> [[f32; 3]; 3] {
     let tx = m.tx.get() as f32;
@@ -0,0 +1,30 @@
+#version 100
+precision mediump float;
+
+uniform mat4 view_matrix;
+uniform mat4 world_matrix;
+uniform mat3 u_matrix;
+
+uniform sampler2D u_texture;
+
+// The distance between two neighboring texels along the blur, in texture coordinates.
+uniform vec2 u_direction;
+
+// The width of the blur box, in texels.
+uniform float u_blur_size;
+
+varying vec2 frag_uv;
+
+void main() {
+    // Average the texels of the box centered on this one.
+    float taps = max(floor(u_blur_size), 1.0);
+    vec4 sum = vec4(0.0);
+    for (int i = 0; i < 256; i++) {
+        if (float(i) >= taps) {
+            break;
+        }
+        float offset = float(i) - (taps - 1.0) / 2.0;
+        sum += texture2D(u_texture, frag_uv + u_direction * offset);
+    }
+    gl_FragColor = sum / taps;
+}
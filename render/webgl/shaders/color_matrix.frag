@@ -0,0 +1,33 @@
+#version 100
+precision mediump float;
+
+uniform mat4 view_matrix;
+uniform mat4 world_matrix;
+uniform mat3 u_matrix;
+
+uniform sampler2D u_texture;
+
+// The 4x5 color matrix, row by row. The last column is an offset in the 0-255 range.
+uniform float u_color_matrix[20];
+
+varying vec2 frag_uv;
+
+void main() {
+    vec4 color = texture2D(u_texture, frag_uv);
+
+    // The matrix applies to unmultiplied colors.
+    if (color.a > 0.0) {
+        color.rgb /= color.a;
+    }
+
+    vec4 result = vec4(
+        u_color_matrix[0] * color.r + u_color_matrix[1] * color.g + u_color_matrix[2] * color.b + u_color_matrix[3] * color.a + u_color_matrix[4] / 255.0,
+        u_color_matrix[5] * color.r + u_color_matrix[6] * color.g + u_color_matrix[7] * color.b + u_color_matrix[8] * color.a + u_color_matrix[9] / 255.0,
+        u_color_matrix[10] * color.r + u_color_matrix[11] * color.g + u_color_matrix[12] * color.b + u_color_matrix[13] * color.a + u_color_matrix[14] / 255.0,
+        u_color_matrix[15] * color.r + u_color_matrix[16] * color.g + u_color_matrix[17] * color.b + u_color_matrix[18] * color.a + u_color_matrix[19] / 255.0
+    );
+    result = clamp(result, 0.0, 1.0);
+    result.rgb *= result.a;
+
+    gl_FragColor = result;
+}
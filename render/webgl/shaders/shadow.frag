@@ -0,0 +1,42 @@
+#version 100
+precision mediump float;
+
+uniform mat4 view_matrix;
+uniform mat4 world_matrix;
+uniform mat3 u_matrix;
+
+// The blurred object, whose alpha is the shape of the glow or shadow.
+uniform sampler2D u_texture;
+
+// The object being filtered.
+uniform sampler2D u_source;
+
+uniform vec4 u_shadow_color;
+uniform float u_strength;
+
+// The offset of the shadow from the object, in texture coordinates.
+uniform vec2 u_offset;
+
+uniform bool u_inner;
+uniform bool u_knockout;
+
+varying vec2 frag_uv;
+
+void main() {
+    vec4 source = texture2D(u_source, frag_uv);
+    float alpha = texture2D(u_texture, frag_uv - u_offset).a;
+
+    // An inner shadow fills the parts of the object that the blurred object doesn't cover.
+    if (u_inner) {
+        alpha = 1.0 - alpha;
+    }
+    alpha = clamp(alpha * u_strength, 0.0, 1.0) * u_shadow_color.a;
+    vec4 shadow = vec4(u_shadow_color.rgb * alpha, alpha);
+
+    if (u_inner) {
+        shadow *= source.a;
+        gl_FragColor = u_knockout ? shadow : shadow + source * (1.0 - shadow.a);
+    } else {
+        gl_FragColor = u_knockout ? shadow * (1.0 - source.a) : source + shadow * (1.0 - source.a);
+    }
+}
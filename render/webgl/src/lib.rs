@@ -1,9 +1,11 @@
 use ruffle_core::backend::render::swf::{self, FillStyle};
 use ruffle_core::backend::render::{
-    srgb_to_linear, Bitmap, BitmapFormat, BitmapHandle, BitmapInfo, Color, Letterbox,
-    RenderBackend, ShapeHandle, Transform,
+    srgb_to_linear, unmultiply_alpha_rgba, Bitmap, BitmapCacheHandle, BitmapFormat, BitmapHandle,
+    BitmapInfo, Color, Letterbox, RenderBackend, ShapeHandle, Transform,
 };
+use ruffle_core::color_transform::ColorTransform;
 use ruffle_core::shape_utils::DistilledShape;
+use ruffle_core::StageQuality;
 use ruffle_render_common_tess::{GradientSpread, GradientType, ShapeTessellator, Vertex};
 use ruffle_web_common::JsResult;
 use wasm_bindgen::{JsCast, JsValue};
@@ -20,6 +22,9 @@ const COLOR_FRAGMENT_GLSL: &str = include_str!("../shaders/color.frag");
 const TEXTURE_VERTEX_GLSL: &str = include_str!("../shaders/texture.vert");
 const GRADIENT_FRAGMENT_GLSL: &str = include_str!("../shaders/gradient.frag");
 const BITMAP_FRAGMENT_GLSL: &str = include_str!("../shaders/bitmap.frag");
+const BLUR_FRAGMENT_GLSL: &str = include_str!("../shaders/blur.frag");
+const SHADOW_FRAGMENT_GLSL: &str = include_str!("../shaders/shadow.frag");
+const COLOR_MATRIX_FRAGMENT_GLSL: &str = include_str!("../shaders/color_matrix.frag");
 const NUM_VERTEX_ATTRIBUTES: u32 = 2;
 
 pub struct WebGlRenderBackend {
@@ -32,6 +37,10 @@ pub struct WebGlRenderBackend {
     /// In WebGL1, VAOs are only available as an extension.
     vao_ext: OesVertexArrayObject,
 
+    /// Whether the `MIN` and `MAX` blend equations are available, which are an extension
+    /// in WebGL1.
+    has_blend_minmax: bool,
+
     // The frame buffers used for resolving MSAA.
     msaa_buffers: Option<MsaaBuffers>,
     msaa_sample_count: u32,
@@ -40,13 +49,27 @@ pub struct WebGlRenderBackend {
     bitmap_program: ShaderProgram,
     gradient_program: ShaderProgram,
 
+    // The programs of the passes that apply bitmap filters.
+    blur_program: ShaderProgram,
+    shadow_program: ShaderProgram,
+    color_matrix_program: ShaderProgram,
+
     shape_tessellator: ShapeTessellator,
 
-    textures: Vec<(swf::CharacterId, Texture)>,
+    textures: Vec<Option<(swf::CharacterId, Texture)>>,
     meshes: Vec<Mesh>,
 
+    /// Slots in `textures` freed by `release_bitmap`, to be reused by the next bitmap.
+    free_textures: Vec<usize>,
+
+    /// Slots in `meshes` freed by `release_shape`, to be reused by the next shape.
+    free_meshes: Vec<usize>,
+
     quad_shape: ShapeHandle,
 
+    /// A white 1x1 pixel square, scaled and colored to draw rectangles.
+    rect_shape: ShapeHandle,
+
     num_masks: u32,
     num_masks_active: u32,
     write_stencil_mask: u32,
@@ -54,6 +77,25 @@ pub struct WebGlRenderBackend {
     next_stencil_mask: u32,
     mask_stack: Vec<(u32, u32)>,
 
+    /// The offscreen framebuffers that draws are redirected to, innermost last.
+    render_targets: Vec<RenderTarget>,
+
+    /// Whether the outermost render target is an image started by `begin_capture`.
+    is_capturing: bool,
+
+    /// The images of objects cached as bitmaps, indexed by `BitmapCacheHandle`.
+    bitmap_caches: Vec<Option<Option<Texture>>>,
+    /// The caches being rendered, or `None` where a framebuffer couldn't be created.
+    bitmap_cache_stack: Vec<Option<BitmapCacheHandle>>,
+
+    /// The filters of the objects being rendered, or `None` where the object is drawn
+    /// directly.
+    filter_stack: Vec<Option<Vec<swf::Filter>>>,
+
+    /// The blend modes of the objects being rendered, or `None` where the object is drawn
+    /// directly.
+    blend_mode_stack: Vec<Option<swf::BlendMode>>,
+
     active_program: *const ShaderProgram,
     mask_state_dirty: bool,
     blend_func: (u32, u32),
@@ -63,6 +105,8 @@ pub struct WebGlRenderBackend {
     viewport_width: f32,
     viewport_height: f32,
     view_matrix: [[f32; 4]; 4],
+
+    quality: StageQuality,
 }
 
 impl WebGlRenderBackend {
@@ -138,19 +182,40 @@ impl WebGlRenderBackend {
             }
         };
 
+        let has_blend_minmax = gl2.is_some()
+            || gl
+                .get_extension("EXT_blend_minmax")
+                .ok()
+                .flatten()
+                .is_some();
+
         let color_vertex = Self::compile_shader(&gl, Gl::VERTEX_SHADER, COLOR_VERTEX_GLSL)?;
         let texture_vertex = Self::compile_shader(&gl, Gl::VERTEX_SHADER, TEXTURE_VERTEX_GLSL)?;
         let color_fragment = Self::compile_shader(&gl, Gl::FRAGMENT_SHADER, COLOR_FRAGMENT_GLSL)?;
         let bitmap_fragment = Self::compile_shader(&gl, Gl::FRAGMENT_SHADER, BITMAP_FRAGMENT_GLSL)?;
         let gradient_fragment =
             Self::compile_shader(&gl, Gl::FRAGMENT_SHADER, GRADIENT_FRAGMENT_GLSL)?;
+        let blur_fragment = Self::compile_shader(&gl, Gl::FRAGMENT_SHADER, BLUR_FRAGMENT_GLSL)?;
+        let shadow_fragment = Self::compile_shader(&gl, Gl::FRAGMENT_SHADER, SHADOW_FRAGMENT_GLSL)?;
+        let color_matrix_fragment =
+            Self::compile_shader(&gl, Gl::FRAGMENT_SHADER, COLOR_MATRIX_FRAGMENT_GLSL)?;
 
         let color_program = ShaderProgram::new(&gl, &color_vertex, &color_fragment)?;
         let bitmap_program = ShaderProgram::new(&gl, &texture_vertex, &bitmap_fragment)?;
         let gradient_program = ShaderProgram::new(&gl, &texture_vertex, &gradient_fragment)?;
+        let blur_program = ShaderProgram::new(&gl, &texture_vertex, &blur_fragment)?;
+        let shadow_program = ShaderProgram::new(&gl, &texture_vertex, &shadow_fragment)?;
+        let color_matrix_program =
+            ShaderProgram::new(&gl, &texture_vertex, &color_matrix_fragment)?;
 
+        // Alpha is always accumulated so that offscreen render targets end up premultiplied.
         gl.enable(Gl::BLEND);
-        gl.blend_func(Gl::SRC_ALPHA, Gl::ONE_MINUS_SRC_ALPHA);
+        gl.blend_func_separate(
+            Gl::SRC_ALPHA,
+            Gl::ONE_MINUS_SRC_ALPHA,
+            Gl::ONE,
+            Gl::ONE_MINUS_SRC_ALPHA,
+        );
 
         // Necessary to load RGB textures (alignment defaults to 4).
         gl.pixel_storei(Gl::UNPACK_ALIGNMENT, 1);
@@ -159,6 +224,7 @@ impl WebGlRenderBackend {
             gl,
             gl2,
             vao_ext,
+            has_blend_minmax,
 
             msaa_buffers: None,
             msaa_sample_count,
@@ -166,12 +232,18 @@ impl WebGlRenderBackend {
             color_program,
             gradient_program,
             bitmap_program,
+            blur_program,
+            shadow_program,
+            color_matrix_program,
 
             shape_tessellator: ShapeTessellator::new(),
 
             meshes: vec![],
             quad_shape: ShapeHandle(0),
+            rect_shape: ShapeHandle(0),
             textures: vec![],
+            free_textures: vec![],
+            free_meshes: vec![],
             viewport_width: 500.0,
             viewport_height: 500.0,
             view_matrix: [[0.0; 4]; 4],
@@ -181,16 +253,25 @@ impl WebGlRenderBackend {
             test_stencil_mask: 0,
             next_stencil_mask: 1,
             mask_stack: vec![],
+            render_targets: vec![],
+            is_capturing: false,
+            bitmap_caches: vec![],
+            bitmap_cache_stack: vec![],
+            filter_stack: vec![],
+            blend_mode_stack: vec![],
 
             active_program: std::ptr::null(),
             mask_state_dirty: true,
             blend_func: (Gl::SRC_ALPHA, Gl::ONE_MINUS_SRC_ALPHA),
             mult_color: None,
             add_color: None,
+
+            quality: StageQuality::High,
         };
 
         let quad_mesh = renderer.build_quad_mesh()?;
         renderer.meshes.push(quad_mesh);
+        renderer.rect_shape = renderer.register_rect_shape();
         renderer.build_msaa_buffers()?;
         renderer.build_matrices();
 
@@ -422,6 +503,41 @@ impl WebGlRenderBackend {
         Ok(())
     }
 
+    fn register_rect_shape(&mut self) -> ShapeHandle {
+        use swf::{ShapeRecord, StyleChangeData, Twips};
+
+        let (zero, one) = (Twips::new(0), Twips::from_pixels(1.0));
+        let edge = |delta_x, delta_y| ShapeRecord::StraightEdge { delta_x, delta_y };
+        let shape = swf::Shape {
+            version: 1,
+            id: 0,
+            shape_bounds: Default::default(),
+            edge_bounds: Default::default(),
+            has_fill_winding_rule: false,
+            has_non_scaling_strokes: false,
+            has_scaling_strokes: true,
+            styles: swf::ShapeStyles {
+                fill_styles: vec![FillStyle::Color(Color::from_rgb(0xFFFFFF, 0xFF))],
+                line_styles: vec![],
+            },
+            shape: vec![
+                ShapeRecord::StyleChange(StyleChangeData {
+                    move_to: Some((zero, zero)),
+                    fill_style_0: None,
+                    fill_style_1: Some(1),
+                    line_style: None,
+                    new_styles: None,
+                }),
+                edge(one, zero),
+                edge(zero, one),
+                edge(zero - one, zero),
+                edge(zero, zero - one),
+            ],
+        };
+        let mesh = self.register_shape_internal((&shape).into());
+        self.insert_mesh(mesh)
+    }
+
     fn register_shape_internal(&mut self, shape: DistilledShape) -> Mesh {
         use ruffle_render_common_tess::DrawType as TessDrawType;
 
@@ -429,6 +545,7 @@ impl WebGlRenderBackend {
         let lyon_mesh = self.shape_tessellator.tessellate_shape(shape, |id| {
             textures
                 .iter()
+                .flatten()
                 .find(|(other_id, _tex)| *other_id == id)
                 .map(|tex| (tex.1.width, tex.1.height))
         });
@@ -483,12 +600,6 @@ impl WebGlRenderBackend {
                     let num_colors = gradient.num_colors as usize;
                     ratios[..num_colors].copy_from_slice(&gradient.ratios[..num_colors]);
                     colors[..num_colors].copy_from_slice(&gradient.colors[..num_colors]);
-                    // Convert to linear color space if this is a linear-interpolated gradient.
-                    if gradient.interpolation == swf::GradientInterpolation::LinearRGB {
-                        for color in &mut colors[..num_colors] {
-                            *color = srgb_to_linear(*color);
-                        }
-                    }
                     for i in num_colors..8 {
                         ratios[i] = ratios[i - 1];
                         colors[i] = colors[i - 1];
@@ -615,6 +726,15 @@ impl WebGlRenderBackend {
         };
     }
 
+    /// Deletes a VAO.
+    fn delete_vertex_array(&self, vao: &WebGlVertexArrayObject) {
+        if let Some(gl2) = &self.gl2 {
+            gl2.delete_vertex_array(Some(vao));
+        } else {
+            self.vao_ext.delete_vertex_array_oes(Some(vao));
+        };
+    }
+
     fn set_stencil_state(&mut self) {
         // Set stencil state for masking, if neccessary.
         if self.mask_state_dirty {
@@ -644,6 +764,451 @@ impl WebGlRenderBackend {
         }
     }
 
+    /// Stores a mesh, reusing a slot freed by `release_shape` if there is one.
+    fn insert_mesh(&mut self, mesh: Mesh) -> ShapeHandle {
+        if let Some(index) = self.free_meshes.pop() {
+            self.meshes[index] = mesh;
+            ShapeHandle(index)
+        } else {
+            self.meshes.push(mesh);
+            ShapeHandle(self.meshes.len() - 1)
+        }
+    }
+
+    /// Stores a texture, reusing a slot freed by `release_bitmap` if there is one.
+    fn insert_texture(&mut self, id: swf::CharacterId, texture: Texture) -> BitmapHandle {
+        if let Some(index) = self.free_textures.pop() {
+            self.textures[index] = Some((id, texture));
+            BitmapHandle(index)
+        } else {
+            self.textures.push(Some((id, texture)));
+            BitmapHandle(self.textures.len() - 1)
+        }
+    }
+
+    /// Redirects all draws to a new offscreen framebuffer of the given size, cleared to
+    /// `clear`, until the matching `pop_render_target`.
+    ///
+    /// The target starts without any masks; the masks of the target below apply when
+    /// its texture is drawn back.
+    fn push_render_target(&mut self, width: u32, height: u32, clear: Color) -> Result<(), Error> {
+        let gl = &self.gl;
+        let (width, height) = (width.max(1), height.max(1));
+
+        let texture = gl.create_texture().ok_or("Unable to create texture")?;
+        gl.bind_texture(Gl::TEXTURE_2D, Some(&texture));
+        gl.tex_parameteri(Gl::TEXTURE_2D, Gl::TEXTURE_MAG_FILTER, Gl::NEAREST as i32);
+        gl.tex_parameteri(Gl::TEXTURE_2D, Gl::TEXTURE_MIN_FILTER, Gl::NEAREST as i32);
+        gl.tex_parameteri(Gl::TEXTURE_2D, Gl::TEXTURE_WRAP_S, Gl::CLAMP_TO_EDGE as i32);
+        gl.tex_parameteri(Gl::TEXTURE_2D, Gl::TEXTURE_WRAP_T, Gl::CLAMP_TO_EDGE as i32);
+        gl.tex_image_2d_with_i32_and_i32_and_i32_and_format_and_type_and_opt_u8_array(
+            Gl::TEXTURE_2D,
+            0,
+            Gl::RGBA as i32,
+            width as i32,
+            height as i32,
+            0,
+            Gl::RGBA,
+            Gl::UNSIGNED_BYTE,
+            None,
+        )
+        .into_js_result()?;
+        gl.bind_texture(Gl::TEXTURE_2D, None);
+
+        let stencil_renderbuffer = gl
+            .create_renderbuffer()
+            .ok_or("Unable to create renderbuffer")?;
+        gl.bind_renderbuffer(Gl::RENDERBUFFER, Some(&stencil_renderbuffer));
+        gl.renderbuffer_storage(
+            Gl::RENDERBUFFER,
+            Gl::STENCIL_INDEX8,
+            width as i32,
+            height as i32,
+        );
+        gl.bind_renderbuffer(Gl::RENDERBUFFER, None);
+
+        let framebuffer = gl
+            .create_framebuffer()
+            .ok_or("Unable to create framebuffer")?;
+        gl.bind_framebuffer(Gl::FRAMEBUFFER, Some(&framebuffer));
+        gl.framebuffer_texture_2d(
+            Gl::FRAMEBUFFER,
+            Gl::COLOR_ATTACHMENT0,
+            Gl::TEXTURE_2D,
+            Some(&texture),
+            0,
+        );
+        gl.framebuffer_renderbuffer(
+            Gl::FRAMEBUFFER,
+            Gl::STENCIL_ATTACHMENT,
+            Gl::RENDERBUFFER,
+            Some(&stencil_renderbuffer),
+        );
+
+        self.render_targets.push(RenderTarget {
+            framebuffer,
+            texture,
+            stencil_renderbuffer,
+            width,
+            height,
+            parent_viewport: (self.viewport_width, self.viewport_height),
+            parent_view_matrix: self.view_matrix,
+            parent_masks: (
+                self.num_masks,
+                self.num_masks_active,
+                self.write_stencil_mask,
+                self.test_stencil_mask,
+                self.next_stencil_mask,
+                std::mem::take(&mut self.mask_stack),
+            ),
+        });
+        self.viewport_width = width as f32;
+        self.viewport_height = height as f32;
+        self.build_matrices();
+        self.gl.viewport(0, 0, width as i32, height as i32);
+
+        self.num_masks = 0;
+        self.num_masks_active = 0;
+        self.write_stencil_mask = 0;
+        self.test_stencil_mask = 0;
+        self.next_stencil_mask = 1;
+        self.mask_state_dirty = true;
+        // The view matrix is only uploaded when the program changes.
+        self.active_program = std::ptr::null();
+
+        self.set_stencil_state();
+        self.gl.clear_color(
+            clear.r as f32 / 255.0,
+            clear.g as f32 / 255.0,
+            clear.b as f32 / 255.0,
+            clear.a as f32 / 255.0,
+        );
+        self.gl.stencil_mask(0xff);
+        self.gl.clear(Gl::COLOR_BUFFER_BIT | Gl::STENCIL_BUFFER_BIT);
+        Ok(())
+    }
+
+    /// Stops drawing to the innermost render target and returns to the one below it.
+    /// The returned target must be freed with `delete_render_target` once it has been used.
+    fn pop_render_target(&mut self) -> Option<RenderTarget> {
+        let mut target = self.render_targets.pop()?;
+
+        let framebuffer = match self.render_targets.last() {
+            Some(parent) => Some(&parent.framebuffer),
+            None => self.msaa_buffers.as_ref().map(|b| &b.render_framebuffer),
+        };
+        self.gl.bind_framebuffer(Gl::FRAMEBUFFER, framebuffer);
+
+        let (viewport_width, viewport_height) = target.parent_viewport;
+        self.viewport_width = viewport_width;
+        self.viewport_height = viewport_height;
+        self.view_matrix = target.parent_view_matrix;
+        self.gl
+            .viewport(0, 0, viewport_width as i32, viewport_height as i32);
+
+        let (num_masks, num_masks_active, write, test, next, _) = target.parent_masks;
+        self.num_masks = num_masks;
+        self.num_masks_active = num_masks_active;
+        self.write_stencil_mask = write;
+        self.test_stencil_mask = test;
+        self.next_stencil_mask = next;
+        self.mask_stack = std::mem::take(&mut target.parent_masks.5);
+        self.mask_state_dirty = true;
+        self.active_program = std::ptr::null();
+
+        Some(target)
+    }
+
+    /// Draws a texture of the size of the viewport over the whole viewport, such as a popped
+    /// render target. The texture must be premultiplied.
+    fn draw_viewport_texture(&mut self, texture: &WebGlTexture) {
+        self.draw_viewport_quad(
+            |this| &this.bitmap_program,
+            &[texture],
+            swf::BlendMode::Normal,
+            |gl, program| {
+                program.uniform4fv(gl, ShaderUniform::MultColor, &[1.0, 1.0, 1.0, 1.0]);
+                program.uniform4fv(gl, ShaderUniform::AddColor, &[0.0, 0.0, 0.0, 0.0]);
+            },
+        );
+    }
+
+    /// Draws a quad over the whole viewport with the given program, which samples textures of
+    /// the size of the viewport and outputs premultiplied colors, composited with the given
+    /// blend mode.
+    ///
+    /// The first texture is bound to `u_texture` and the second to `u_source`.
+    fn draw_viewport_quad(
+        &mut self,
+        program: fn(&Self) -> &ShaderProgram,
+        textures: &[&WebGlTexture],
+        blend_mode: swf::BlendMode,
+        set_uniforms: impl FnOnce(&Gl, &ShaderProgram),
+    ) {
+        self.set_stencil_state();
+        self.set_blend_mode(blend_mode);
+
+        let program = program(self);
+        self.gl.use_program(Some(&program.program));
+
+        // The quad is placed directly in clip space, where the bottom row of the texture
+        // is the bottom of the viewport.
+        program.uniform_matrix4fv(
+            &self.gl,
+            ShaderUniform::WorldMatrix,
+            &[
+                [2.0, 0.0, 0.0, 0.0],
+                [0.0, 2.0, 0.0, 0.0],
+                [0.0, 0.0, 1.0, 0.0],
+                [-1.0, -1.0, 0.0, 1.0],
+            ],
+        );
+        program.uniform_matrix4fv(
+            &self.gl,
+            ShaderUniform::ViewMatrix,
+            &[
+                [1.0, 0.0, 0.0, 0.0],
+                [0.0, 1.0, 0.0, 0.0],
+                [0.0, 0.0, 1.0, 0.0],
+                [0.0, 0.0, 0.0, 1.0],
+            ],
+        );
+        program.uniform_matrix3fv(
+            &self.gl,
+            ShaderUniform::TextureMatrix,
+            &[[1.0, 0.0, 0.0], [0.0, 1.0, 0.0], [0.0, 0.0, 1.0]],
+        );
+
+        let samplers = [ShaderUniform::BitmapTexture, ShaderUniform::SourceTexture];
+        for (i, (texture, sampler)) in textures.iter().zip(samplers.iter()).enumerate() {
+            self.gl.active_texture(Gl::TEXTURE0 + i as u32);
+            self.gl.bind_texture(Gl::TEXTURE_2D, Some(texture));
+            program.uniform1i(&self.gl, *sampler, i as i32);
+        }
+        self.gl.active_texture(Gl::TEXTURE0);
+        set_uniforms(&self.gl, program);
+
+        let quad = &self.meshes[self.quad_shape.0];
+        self.bind_vertex_array(Some(&quad.draws[0].vao));
+        self.gl.draw_elements_with_i32(
+            Gl::TRIANGLES,
+            quad.draws[0].num_indices,
+            Gl::UNSIGNED_SHORT,
+            0,
+        );
+
+        self.active_program = std::ptr::null();
+        self.mult_color = None;
+        self.add_color = None;
+        if blend_mode != swf::BlendMode::Normal {
+            self.set_blend_mode(swf::BlendMode::Normal);
+        }
+    }
+
+    /// Sets the blend state that composites premultiplied colors with the given blend mode.
+    ///
+    /// Difference, Overlay and HardLight can't be expressed with blend functions, and are
+    /// composited normally.
+    fn set_blend_mode(&mut self, blend_mode: swf::BlendMode) {
+        use swf::BlendMode;
+        let over = (Gl::ONE, Gl::ONE_MINUS_SRC_ALPHA);
+        let (equation, color_func, alpha_func) = match blend_mode {
+            BlendMode::Multiply => (Gl::FUNC_ADD, (Gl::DST_COLOR, Gl::ONE_MINUS_SRC_ALPHA), over),
+            BlendMode::Screen => (Gl::FUNC_ADD, (Gl::ONE, Gl::ONE_MINUS_SRC_COLOR), over),
+            BlendMode::Lighten if self.has_blend_minmax => (Gl2::MAX, (Gl::ONE, Gl::ONE), over),
+            BlendMode::Darken if self.has_blend_minmax => (Gl2::MIN, (Gl::ONE, Gl::ONE), over),
+            BlendMode::Add => (Gl::FUNC_ADD, (Gl::ONE, Gl::ONE), over),
+            BlendMode::Subtract => (Gl::FUNC_REVERSE_SUBTRACT, (Gl::ONE, Gl::ONE), over),
+            // The source is drawn as its alpha in every channel, see `pop_blend_mode`.
+            BlendMode::Invert => (
+                Gl::FUNC_ADD,
+                (Gl::ONE_MINUS_DST_COLOR, Gl::ONE_MINUS_SRC_ALPHA),
+                over,
+            ),
+            BlendMode::Alpha => (
+                Gl::FUNC_ADD,
+                (Gl::ZERO, Gl::SRC_ALPHA),
+                (Gl::ZERO, Gl::SRC_ALPHA),
+            ),
+            BlendMode::Erase => (
+                Gl::FUNC_ADD,
+                (Gl::ZERO, Gl::ONE_MINUS_SRC_ALPHA),
+                (Gl::ZERO, Gl::ONE_MINUS_SRC_ALPHA),
+            ),
+            _ => (Gl::FUNC_ADD, over, over),
+        };
+
+        // Shape draws only check the color blend functions, so a blended draw is always
+        // followed by setting the normal blend mode again.
+        self.gl.blend_equation(equation);
+        self.gl
+            .blend_func_separate(color_func.0, color_func.1, alpha_func.0, alpha_func.1);
+        self.blend_func = color_func;
+    }
+
+    /// Renders a pass of a filter into a new render target of the size of the viewport.
+    fn filter_pass(
+        &mut self,
+        program: fn(&Self) -> &ShaderProgram,
+        textures: &[&WebGlTexture],
+        set_uniforms: impl FnOnce(&Gl, &ShaderProgram),
+    ) -> Option<RenderTarget> {
+        let (width, height) = (self.viewport_width as u32, self.viewport_height as u32);
+        let clear = Color {
+            r: 0,
+            g: 0,
+            b: 0,
+            a: 0,
+        };
+        if let Err(e) = self.push_render_target(width, height, clear) {
+            log::error!("Unable to create a framebuffer for a filter: {}", e);
+            return None;
+        }
+        self.draw_viewport_quad(program, textures, swf::BlendMode::Normal, set_uniforms);
+        self.pop_render_target()
+    }
+
+    /// Blurs a texture of the size of the viewport with the given number of box blurs.
+    /// Returns `None` if the blur is too small to change the texture.
+    fn blur(
+        &mut self,
+        texture: &WebGlTexture,
+        blur_x: f64,
+        blur_y: f64,
+        num_passes: u8,
+    ) -> Option<RenderTarget> {
+        let directions = [
+            (blur_x, [1.0 / self.viewport_width, 0.0]),
+            (blur_y, [0.0, 1.0 / self.viewport_height]),
+        ];
+        let mut blurred: Option<RenderTarget> = None;
+        for _ in 0..num_passes.max(1) {
+            for &(blur_size, direction) in directions.iter().filter(|(size, _)| *size > 1.0) {
+                let input = blurred.as_ref().map_or(texture, |target| &target.texture);
+                let output = self.filter_pass(
+                    |this| &this.blur_program,
+                    &[input],
+                    |gl, program| {
+                        program.uniform2fv(gl, ShaderUniform::BlurDirection, &direction);
+                        program.uniform1f(gl, ShaderUniform::BlurSize, blur_size as f32);
+                    },
+                );
+                if let Some(output) = output {
+                    if let Some(input) = blurred.replace(output) {
+                        self.delete_render_target(input, true);
+                    }
+                }
+            }
+        }
+        blurred
+    }
+
+    /// Draws a glow or a drop shadow of an object, offset by the given distance in pixels.
+    #[allow(clippy::too_many_arguments)]
+    fn shadow(
+        &mut self,
+        texture: &WebGlTexture,
+        color: &Color,
+        (blur_x, blur_y): (f64, f64),
+        strength: f32,
+        (offset_x, offset_y): (f64, f64),
+        is_inner: bool,
+        is_knockout: bool,
+        num_passes: u8,
+    ) -> Option<RenderTarget> {
+        let blurred = self.blur(texture, blur_x, blur_y, num_passes);
+        // Texture rows start at the bottom, so the vertical offset is flipped.
+        let offset = [
+            (offset_x / f64::from(self.viewport_width)) as f32,
+            (-offset_y / f64::from(self.viewport_height)) as f32,
+        ];
+        let color = [
+            f32::from(color.r) / 255.0,
+            f32::from(color.g) / 255.0,
+            f32::from(color.b) / 255.0,
+            f32::from(color.a) / 255.0,
+        ];
+        let shape = blurred.as_ref().map_or(texture, |target| &target.texture);
+        let output = self.filter_pass(
+            |this| &this.shadow_program,
+            &[shape, texture],
+            |gl, program| {
+                program.uniform4fv(gl, ShaderUniform::ShadowColor, &color);
+                program.uniform1f(gl, ShaderUniform::ShadowStrength, strength);
+                program.uniform2fv(gl, ShaderUniform::ShadowOffset, &offset);
+                program.uniform1i(gl, ShaderUniform::ShadowInner, is_inner as i32);
+                program.uniform1i(gl, ShaderUniform::ShadowKnockout, is_knockout as i32);
+            },
+        );
+        if let Some(blurred) = blurred {
+            self.delete_render_target(blurred, true);
+        }
+        output
+    }
+
+    /// Applies a filter to a texture of the size of the viewport, returning the filtered
+    /// image. Returns `None` if the filter leaves the texture unchanged.
+    fn apply_filter(
+        &mut self,
+        texture: &WebGlTexture,
+        filter: &swf::Filter,
+    ) -> Option<RenderTarget> {
+        use swf::Filter;
+        match filter {
+            Filter::BlurFilter(blur) => {
+                self.blur(texture, blur.blur_x, blur.blur_y, blur.num_passes)
+            }
+            Filter::GlowFilter(glow) => self.shadow(
+                texture,
+                &glow.color,
+                (glow.blur_x, glow.blur_y),
+                glow.strength,
+                (0.0, 0.0),
+                glow.is_inner,
+                glow.is_knockout,
+                glow.num_passes,
+            ),
+            Filter::DropShadowFilter(shadow) => self.shadow(
+                texture,
+                &shadow.color,
+                (shadow.blur_x, shadow.blur_y),
+                shadow.strength,
+                (
+                    shadow.distance * shadow.angle.cos(),
+                    shadow.distance * shadow.angle.sin(),
+                ),
+                shadow.is_inner,
+                shadow.is_knockout,
+                shadow.num_passes,
+            ),
+            Filter::ColorMatrixFilter(color_matrix) => {
+                let mut matrix = [0.0; 20];
+                for (value, &filter_value) in matrix.iter_mut().zip(color_matrix.matrix.iter()) {
+                    *value = filter_value as f32;
+                }
+                self.filter_pass(
+                    |this| &this.color_matrix_program,
+                    &[texture],
+                    |gl, program| {
+                        program.uniform1fv(gl, ShaderUniform::ColorMatrix, &matrix);
+                    },
+                )
+            }
+            _ => None,
+        }
+    }
+
+    /// Frees the framebuffer of a popped render target, along with its texture unless it
+    /// has been kept.
+    fn delete_render_target(&self, target: RenderTarget, delete_texture: bool) {
+        self.gl.delete_framebuffer(Some(&target.framebuffer));
+        self.gl
+            .delete_renderbuffer(Some(&target.stencil_renderbuffer));
+        if delete_texture {
+            self.gl.delete_texture(Some(&target.texture));
+        }
+    }
+
     fn register_bitmap(
         &mut self,
         id: swf::CharacterId,
@@ -692,15 +1257,14 @@ impl WebGlRenderBackend {
         self.gl
             .tex_parameteri(Gl::TEXTURE_2D, Gl::TEXTURE_MAG_FILTER, Gl::LINEAR as i32);
 
-        let handle = BitmapHandle(self.textures.len());
-        self.textures.push((
+        let handle = self.insert_texture(
             id,
             Texture {
                 texture,
                 width: bitmap.width,
                 height: bitmap.height,
             },
-        ));
+        );
 
         Ok(BitmapInfo {
             handle,
@@ -720,10 +1284,8 @@ impl RenderBackend for WebGlRenderBackend {
     }
 
     fn register_shape(&mut self, shape: DistilledShape) -> ShapeHandle {
-        let handle = ShapeHandle(self.meshes.len());
         let mesh = self.register_shape_internal(shape);
-        self.meshes.push(mesh);
-        handle
+        self.insert_mesh(mesh)
     }
 
     fn replace_shape(&mut self, shape: DistilledShape, handle: ShapeHandle) {
@@ -751,10 +1313,29 @@ impl RenderBackend for WebGlRenderBackend {
             },
             shape: glyph.shape_records.clone(),
         };
-        let handle = ShapeHandle(self.meshes.len());
         let mesh = self.register_shape_internal((&shape).into());
-        self.meshes.push(mesh);
-        handle
+        self.insert_mesh(mesh)
+    }
+
+    fn release_shape(&mut self, shape: ShapeHandle) {
+        let draws = match self.meshes.get_mut(shape.0) {
+            Some(mesh) => std::mem::take(&mut mesh.draws),
+            None => return,
+        };
+        for draw in draws {
+            self.gl.delete_buffer(Some(&draw.vertex_buffer));
+            self.gl.delete_buffer(Some(&draw.index_buffer));
+            self.delete_vertex_array(&draw.vao);
+        }
+        self.free_meshes.push(shape.0);
+    }
+
+    fn release_bitmap(&mut self, bitmap: BitmapHandle) {
+        // Handles index into `textures`, so the slot is emptied and reused by a later bitmap.
+        if let Some((_id, texture)) = self.textures.get_mut(bitmap.0).and_then(Option::take) {
+            self.gl.delete_texture(Some(&texture.texture));
+            self.free_textures.push(bitmap.0);
+        }
     }
 
     fn register_bitmap_jpeg(
@@ -795,6 +1376,58 @@ impl RenderBackend for WebGlRenderBackend {
         self.register_bitmap(swf_tag.id, bitmap)
     }
 
+    fn register_bitmap_rgba(
+        &mut self,
+        id: swf::CharacterId,
+        width: u32,
+        height: u32,
+        rgba: Vec<u8>,
+    ) -> Result<BitmapInfo, Error> {
+        self.register_bitmap(
+            id,
+            Bitmap {
+                width,
+                height,
+                data: BitmapFormat::Rgba(rgba),
+            },
+        )
+    }
+
+    fn update_bitmap_rgba(
+        &mut self,
+        bitmap: BitmapHandle,
+        width: u32,
+        height: u32,
+        rgba: Vec<u8>,
+    ) -> Result<(), Error> {
+        if let Some(Some((id, _))) = self.textures.get(bitmap.0) {
+            let id = *id;
+            let new_bitmap = self.register_bitmap(
+                id,
+                Bitmap {
+                    width,
+                    height,
+                    data: BitmapFormat::Rgba(rgba),
+                },
+            )?;
+            // Move the new texture into the existing handle and free its slot.
+            let texture = self.textures[new_bitmap.handle.0].take();
+            if let Some(Some((_id, old_texture))) = self
+                .textures
+                .get_mut(bitmap.0)
+                .map(|slot| std::mem::replace(slot, texture))
+            {
+                self.gl.delete_texture(Some(&old_texture.texture));
+            }
+            self.free_textures.push(new_bitmap.handle.0);
+        }
+        Ok(())
+    }
+
+    fn set_quality(&mut self, quality: StageQuality) {
+        self.quality = quality;
+    }
+
     fn begin_frame(&mut self, clear: Color) {
         self.num_masks = 0;
         self.num_masks_active = 0;
@@ -902,18 +1535,24 @@ impl RenderBackend for WebGlRenderBackend {
         }
     }
 
-    fn render_bitmap(&mut self, bitmap: BitmapHandle, transform: &Transform) {
+    fn render_bitmap(&mut self, bitmap: BitmapHandle, transform: &Transform, smoothing: bool) {
         // TODO: Might be better to make this separate code to render the bitmap
         // instead of going through render_shape. But render_shape already handles
         // masking etc.
-        if let Some((id, bitmap)) = self.textures.get(bitmap.0) {
+        if let Some(Some((id, bitmap))) = self.textures.get(bitmap.0) {
             // Adjust the quad draw to use the target bitmap.
             let mesh = &mut self.meshes[self.quad_shape.0];
             let draw = &mut mesh.draws[0];
             let width = bitmap.width as f32;
             let height = bitmap.height as f32;
-            if let DrawType::Bitmap(BitmapDraw { id: draw_id, .. }) = &mut draw.draw_type {
+            if let DrawType::Bitmap(BitmapDraw {
+                id: draw_id,
+                is_smoothed,
+                ..
+            }) = &mut draw.draw_type
+            {
                 *draw_id = *id;
+                *is_smoothed = smoothing && self.quality.smooths_bitmaps();
             }
 
             // Scale the quad to the bitmap's dimensions.
@@ -990,11 +1629,22 @@ impl RenderBackend for WebGlRenderBackend {
                 self.add_color = None;
 
                 if (src_blend, dst_blend) != self.blend_func {
-                    self.gl.blend_func(src_blend, dst_blend);
+                    self.gl.blend_func_separate(
+                        src_blend,
+                        dst_blend,
+                        Gl::ONE,
+                        Gl::ONE_MINUS_SRC_ALPHA,
+                    );
                     self.blend_func = (src_blend, dst_blend);
                 }
             }
 
+            // Gradients have the color transform applied to each of their stops instead.
+            let (mult_color, add_color) = match &draw.draw_type {
+                DrawType::Gradient(_) => ([1.0, 1.0, 1.0, 1.0], [0.0, 0.0, 0.0, 0.0]),
+                _ => (mult_color, add_color),
+            };
+
             program.uniform_matrix4fv(&self.gl, ShaderUniform::WorldMatrix, &world_matrix);
             if Some(mult_color) != self.mult_color {
                 program.uniform4fv(&self.gl, ShaderUniform::MultColor, &mult_color);
@@ -1020,8 +1670,16 @@ impl RenderBackend for WebGlRenderBackend {
                         gradient.gradient_type,
                     );
                     program.uniform1fv(&self.gl, ShaderUniform::GradientRatios, &gradient.ratios);
-                    let colors =
-                        unsafe { std::slice::from_raw_parts(gradient.colors[0].as_ptr(), 32) };
+                    // Flash transforms and clamps each stop before interpolating, and
+                    // linear RGB gradients interpolate the transformed stops in linear space.
+                    let mut colors = gradient.colors;
+                    for color in &mut colors {
+                        *color = transform.color_transform.transform_color(*color);
+                        if gradient.interpolation == swf::GradientInterpolation::LinearRGB {
+                            *color = srgb_to_linear(*color);
+                        }
+                    }
+                    let colors = unsafe { std::slice::from_raw_parts(colors[0].as_ptr(), 32) };
                     program.uniform4fv(&self.gl, ShaderUniform::GradientColors, &colors);
                     program.uniform1i(
                         &self.gl,
@@ -1045,12 +1703,15 @@ impl RenderBackend for WebGlRenderBackend {
                     );
                 }
                 DrawType::Bitmap(bitmap) => {
-                    let texture = &self
+                    let texture = match self
                         .textures
                         .iter()
+                        .flatten()
                         .find(|(id, _tex)| *id == bitmap.id)
-                        .unwrap()
-                        .1;
+                    {
+                        Some((_id, texture)) => texture,
+                        None => continue,
+                    };
 
                     program.uniform_matrix3fv(
                         &self.gl,
@@ -1180,6 +1841,282 @@ impl RenderBackend for WebGlRenderBackend {
             log::warn!("Mask stack underflow\n");
         }
     }
+
+    fn push_filters(&mut self, filters: &[swf::Filter]) {
+        use swf::Filter;
+        let filters: Vec<_> = filters
+            .iter()
+            .filter(|filter| match filter {
+                Filter::BlurFilter(_)
+                | Filter::GlowFilter(_)
+                | Filter::DropShadowFilter(_)
+                | Filter::ColorMatrixFilter(_) => true,
+                _ => {
+                    log::warn!("Unsupported filter {:?}", filter);
+                    false
+                }
+            })
+            .cloned()
+            .collect();
+        if filters.is_empty() {
+            self.filter_stack.push(None);
+            return;
+        }
+
+        // The object is rendered offscreen, then each filter renders a new image from it.
+        let (width, height) = (self.viewport_width as u32, self.viewport_height as u32);
+        let clear = Color {
+            r: 0,
+            g: 0,
+            b: 0,
+            a: 0,
+        };
+        match self.push_render_target(width, height, clear) {
+            Ok(()) => self.filter_stack.push(Some(filters)),
+            Err(e) => {
+                log::error!("Unable to create a framebuffer for a filter: {}", e);
+                self.filter_stack.push(None);
+            }
+        }
+    }
+
+    fn pop_filters(&mut self) {
+        let filters = match self.filter_stack.pop() {
+            Some(Some(filters)) => filters,
+            _ => return,
+        };
+        let mut image = match self.pop_render_target() {
+            Some(target) => target,
+            None => return,
+        };
+        for filter in &filters {
+            if let Some(filtered) = self.apply_filter(&image.texture, filter) {
+                self.delete_render_target(std::mem::replace(&mut image, filtered), true);
+            }
+        }
+        self.draw_viewport_texture(&image.texture);
+        self.delete_render_target(image, true);
+    }
+
+    fn push_blend_mode(&mut self, blend_mode: swf::BlendMode) {
+        match blend_mode {
+            swf::BlendMode::Difference | swf::BlendMode::Overlay | swf::BlendMode::HardLight => {
+                log::warn!("Unsupported blend mode {:?}", blend_mode)
+            }
+            swf::BlendMode::Lighten | swf::BlendMode::Darken if !self.has_blend_minmax => {
+                log::warn!(
+                    "Unsupported blend mode {:?} without EXT_blend_minmax",
+                    blend_mode
+                )
+            }
+            _ => (),
+        }
+
+        // The object is rendered offscreen, then composited with the blend mode's
+        // blend functions.
+        let (width, height) = (self.viewport_width as u32, self.viewport_height as u32);
+        let clear = Color {
+            r: 0,
+            g: 0,
+            b: 0,
+            a: 0,
+        };
+        match self.push_render_target(width, height, clear) {
+            Ok(()) => self.blend_mode_stack.push(Some(blend_mode)),
+            Err(e) => {
+                log::error!("Unable to create a framebuffer for a blend mode: {}", e);
+                self.blend_mode_stack.push(None);
+            }
+        }
+    }
+
+    fn pop_blend_mode(&mut self) {
+        let blend_mode = match self.blend_mode_stack.pop() {
+            Some(Some(blend_mode)) => blend_mode,
+            _ => return,
+        };
+        let image = match self.pop_render_target() {
+            Some(target) => target,
+            None => return,
+        };
+        // Invert only needs the object's coverage, drawn as its alpha in every channel.
+        let (mult_color, add_color) = if blend_mode == swf::BlendMode::Invert {
+            ([0.0, 0.0, 0.0, 1.0], [1.0, 1.0, 1.0, 0.0])
+        } else {
+            ([1.0, 1.0, 1.0, 1.0], [0.0, 0.0, 0.0, 0.0])
+        };
+        self.draw_viewport_quad(
+            |this| &this.bitmap_program,
+            &[&image.texture],
+            blend_mode,
+            |gl, program| {
+                program.uniform4fv(gl, ShaderUniform::MultColor, &mult_color);
+                program.uniform4fv(gl, ShaderUniform::AddColor, &add_color);
+            },
+        );
+        self.delete_render_target(image, true);
+    }
+
+    fn create_bitmap_cache(&mut self) -> BitmapCacheHandle {
+        // Reuse a released slot if there is one.
+        if let Some(index) = self.bitmap_caches.iter().position(Option::is_none) {
+            self.bitmap_caches[index] = Some(None);
+            BitmapCacheHandle(index)
+        } else {
+            self.bitmap_caches.push(Some(None));
+            BitmapCacheHandle(self.bitmap_caches.len() - 1)
+        }
+    }
+
+    fn render_bitmap_cache(&mut self, cache: BitmapCacheHandle) -> bool {
+        // The cached image is in stage space, so it is stale if the viewport was resized.
+        let texture = match self.bitmap_caches.get(cache.0) {
+            Some(Some(Some(texture)))
+                if texture.width == self.viewport_width as u32
+                    && texture.height == self.viewport_height as u32 =>
+            {
+                texture.texture.clone()
+            }
+            _ => return false,
+        };
+        self.draw_viewport_texture(&texture);
+        true
+    }
+
+    fn begin_bitmap_cache(&mut self, cache: BitmapCacheHandle) {
+        let (width, height) = (self.viewport_width as u32, self.viewport_height as u32);
+        let clear = Color {
+            r: 0,
+            g: 0,
+            b: 0,
+            a: 0,
+        };
+        match self.push_render_target(width, height, clear) {
+            Ok(()) => self.bitmap_cache_stack.push(Some(cache)),
+            Err(e) => {
+                log::error!("Unable to create a framebuffer for a cached bitmap: {}", e);
+                self.bitmap_cache_stack.push(None);
+            }
+        }
+    }
+
+    fn end_bitmap_cache(&mut self) {
+        // The object was drawn directly if its framebuffer couldn't be created.
+        let cache = match self.bitmap_cache_stack.pop() {
+            Some(Some(cache)) => cache,
+            _ => return,
+        };
+        let target = match self.pop_render_target() {
+            Some(target) => target,
+            None => return,
+        };
+        self.draw_viewport_texture(&target.texture);
+
+        // The rendered image is kept as the new cached image.
+        let texture = Texture {
+            width: target.width,
+            height: target.height,
+            texture: target.texture.clone(),
+        };
+        match self.bitmap_caches.get_mut(cache.0) {
+            Some(Some(slot)) => {
+                if let Some(old) = slot.replace(texture) {
+                    self.gl.delete_texture(Some(&old.texture));
+                }
+                self.delete_render_target(target, false);
+            }
+            _ => self.delete_render_target(target, true),
+        }
+    }
+
+    fn release_bitmap_cache(&mut self, cache: BitmapCacheHandle) {
+        if let Some(slot) = self.bitmap_caches.get_mut(cache.0) {
+            if let Some(Some(texture)) = slot.take() {
+                self.gl.delete_texture(Some(&texture.texture));
+            }
+        }
+    }
+
+    fn draw_rect(&mut self, color: Color, rect: &swf::Rectangle, transform: &Transform) {
+        let rect_transform = Transform {
+            matrix: transform.matrix
+                * swf::Matrix {
+                    a: (rect.x_max - rect.x_min).to_pixels() as f32,
+                    d: (rect.y_max - rect.y_min).to_pixels() as f32,
+                    tx: rect.x_min,
+                    ty: rect.y_min,
+                    ..Default::default()
+                },
+            color_transform: transform.color_transform
+                * ColorTransform {
+                    r_mult: f32::from(color.r) / 255.0,
+                    g_mult: f32::from(color.g) / 255.0,
+                    b_mult: f32::from(color.b) / 255.0,
+                    a_mult: f32::from(color.a) / 255.0,
+                    ..Default::default()
+                },
+        };
+        self.render_shape(self.rect_shape, &rect_transform);
+    }
+
+    fn begin_capture(&mut self, width: u32, height: u32, clear: Color) -> bool {
+        if self.is_capturing || !self.render_targets.is_empty() {
+            log::warn!("Can't begin a capture in the middle of a frame");
+            return false;
+        }
+
+        // The image is rendered into a framebuffer the size of the capture.
+        if let Err(e) = self.push_render_target(width, height, clear) {
+            log::error!("Unable to create a framebuffer for the capture: {}", e);
+            return false;
+        }
+        self.is_capturing = true;
+        true
+    }
+
+    fn end_capture(&mut self) -> Option<Bitmap> {
+        if !self.is_capturing {
+            return None;
+        }
+        self.is_capturing = false;
+
+        // Read the image while its framebuffer is still bound.
+        let (width, height) = self
+            .render_targets
+            .last()
+            .map(|target| (target.width, target.height))?;
+        let mut data = vec![0; (width * height * 4) as usize];
+        let result = self.gl.read_pixels_with_opt_u8_array(
+            0,
+            0,
+            width as i32,
+            height as i32,
+            Gl::RGBA,
+            Gl::UNSIGNED_BYTE,
+            Some(&mut data),
+        );
+        let target = self.pop_render_target()?;
+        self.delete_render_target(target, true);
+        if let Err(e) = result.into_js_result() {
+            log::error!("Unable to read the captured image: {}", e);
+            return None;
+        }
+
+        // GL rows start at the bottom of the image.
+        let row_len = (width * 4) as usize;
+        let mut data: Vec<u8> = data
+            .chunks_exact(row_len)
+            .rev()
+            .flatten()
+            .copied()
+            .collect();
+        unmultiply_alpha_rgba(&mut data);
+        Some(Bitmap {
+            width,
+            height,
+            data: BitmapFormat::Rgba(data),
+        })
+    }
 }
 
 struct Texture {
@@ -1227,6 +2164,19 @@ enum DrawType {
     Bitmap(BitmapDraw),
 }
 
+/// An offscreen framebuffer pushed by `push_render_target`, along with the viewport and
+/// masks of the target below it.
+struct RenderTarget {
+    framebuffer: WebGlFramebuffer,
+    texture: WebGlTexture,
+    stencil_renderbuffer: WebGlRenderbuffer,
+    width: u32,
+    height: u32,
+    parent_viewport: (f32, f32),
+    parent_view_matrix: [[f32; 4]; 4],
+    parent_masks: (u32, u32, u32, u32, u32, Vec<(u32, u32)>),
+}
+
 struct MsaaBuffers {
     color_renderbuffer: WebGlRenderbuffer,
     stencil_renderbuffer: WebGlRenderbuffer,
@@ -1247,7 +2197,7 @@ struct ShaderProgram {
 }
 
 // These should match the uniform names in the shaders.
-const NUM_UNIFORMS: usize = 13;
+const NUM_UNIFORMS: usize = 22;
 const UNIFORM_NAMES: [&str; NUM_UNIFORMS] = [
     "world_matrix",
     "view_matrix",
@@ -1262,8 +2212,18 @@ const UNIFORM_NAMES: [&str; NUM_UNIFORMS] = [
     "u_focal_point",
     "u_interpolation",
     "u_texture",
+    "u_source",
+    "u_direction",
+    "u_blur_size",
+    "u_shadow_color",
+    "u_strength",
+    "u_offset",
+    "u_inner",
+    "u_knockout",
+    "u_color_matrix",
 ];
 
+#[derive(Clone, Copy)]
 enum ShaderUniform {
     WorldMatrix = 0,
     ViewMatrix,
@@ -1278,6 +2238,15 @@ enum ShaderUniform {
     GradientFocalPoint,
     GradientInterpolation,
     BitmapTexture,
+    SourceTexture,
+    BlurDirection,
+    BlurSize,
+    ShadowColor,
+    ShadowStrength,
+    ShadowOffset,
+    ShadowInner,
+    ShadowKnockout,
+    ColorMatrix,
 }
 
 impl ShaderProgram {
@@ -1343,6 +2312,10 @@ impl ShaderProgram {
         gl.uniform1i(self.uniforms[uniform as usize].as_ref(), value);
     }
 
+    fn uniform2fv(&self, gl: &Gl, uniform: ShaderUniform, values: &[f32]) {
+        gl.uniform2fv_with_f32_array(self.uniforms[uniform as usize].as_ref(), values);
+    }
+
     fn uniform4fv(&self, gl: &Gl, uniform: ShaderUniform, values: &[f32]) {
         gl.uniform4fv_with_f32_array(self.uniforms[uniform as usize].as_ref(), values);
     }
@@ -0,0 +1,26 @@
+#version 450
+
+layout(set = 0, binding = 2) uniform Blur {
+    // The distance between two neighboring texels along the blur, in texture coordinates.
+    vec2 direction;
+    // The width of the blur box, in texels.
+    float blur_size;
+};
+
+layout(set = 0, binding = 3) uniform texture2D t_color;
+layout(set = 0, binding = 4) uniform sampler s_color;
+
+layout(location=0) in vec2 frag_uv;
+
+layout(location=0) out vec4 out_color;
+
+void main() {
+    // Average the texels of the box centered on this one.
+    int taps = max(int(blur_size), 1);
+    vec4 sum = vec4(0.0);
+    for (int i = 0; i < taps; i++) {
+        float offset = float(i) - float(taps - 1) / 2.0;
+        sum += texture(sampler2D(t_color, s_color), frag_uv + direction * offset);
+    }
+    out_color = sum / float(taps);
+}
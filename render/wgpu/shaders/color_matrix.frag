@@ -0,0 +1,29 @@
+#version 450
+
+layout(set = 0, binding = 2) uniform ColorMatrix {
+    // The first four columns of the 4x5 color matrix.
+    mat4 matrix;
+    // The last column of the color matrix, scaled to the 0-1 range.
+    vec4 offset;
+};
+
+layout(set = 0, binding = 3) uniform texture2D t_color;
+layout(set = 0, binding = 4) uniform sampler s_color;
+
+layout(location=0) in vec2 frag_uv;
+
+layout(location=0) out vec4 out_color;
+
+void main() {
+    vec4 color = texture(sampler2D(t_color, s_color), frag_uv);
+
+    // The matrix applies to unmultiplied colors.
+    if (color.a > 0.0) {
+        color.rgb /= color.a;
+    }
+
+    vec4 result = clamp(matrix * color + offset, 0.0, 1.0);
+    result.rgb *= result.a;
+
+    out_color = result;
+}
@@ -0,0 +1,40 @@
+#version 450
+
+layout(set = 0, binding = 2) uniform Shadow {
+    vec4 shadow_color;
+    // The offset of the shadow from the object, in texture coordinates.
+    vec2 offset;
+    float strength;
+    int inner;
+    int knockout;
+};
+
+// The blurred object, whose alpha is the shape of the glow or shadow.
+layout(set = 0, binding = 3) uniform texture2D t_color;
+layout(set = 0, binding = 4) uniform sampler s_color;
+
+// The object being filtered.
+layout(set = 0, binding = 5) uniform texture2D t_source;
+
+layout(location=0) in vec2 frag_uv;
+
+layout(location=0) out vec4 out_color;
+
+void main() {
+    vec4 source = texture(sampler2D(t_source, s_color), frag_uv);
+    float alpha = texture(sampler2D(t_color, s_color), frag_uv - offset).a;
+
+    // An inner shadow fills the parts of the object that the blurred object doesn't cover.
+    if (inner != 0) {
+        alpha = 1.0 - alpha;
+    }
+    alpha = clamp(alpha * strength, 0.0, 1.0) * shadow_color.a;
+    vec4 shadow = vec4(shadow_color.rgb * alpha, alpha);
+
+    if (inner != 0) {
+        shadow *= source.a;
+        out_color = knockout != 0 ? shadow : shadow + source * (1.0 - shadow.a);
+    } else {
+        out_color = knockout != 0 ? shadow * (1.0 - source.a) : source + shadow * (1.0 - source.a);
+    }
+}
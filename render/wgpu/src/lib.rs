@@ -5,10 +5,11 @@ use lyon::tessellation::{
 };
 use ruffle_core::backend::render::swf::{self, FillStyle};
 use ruffle_core::backend::render::{
-    srgb_to_linear, Bitmap, BitmapFormat, BitmapHandle, BitmapInfo, Color, Letterbox,
-    RenderBackend, ShapeHandle, Transform,
+    srgb_to_linear, unmultiply_alpha_rgba, Bitmap, BitmapCacheHandle, BitmapFormat, BitmapHandle,
+    BitmapInfo, Color, Letterbox, RenderBackend, ShapeHandle, Transform,
 };
 use ruffle_core::shape_utils::{DistilledShape, DrawPath};
+use ruffle_core::StageQuality;
 use std::convert::TryInto;
 use swf::{CharacterId, DefineBitsLossless, Glyph, GradientInterpolation};
 
@@ -16,9 +17,11 @@ use bytemuck::{Pod, Zeroable};
 use futures::executor::block_on;
 use raw_window_handle::HasRawWindowHandle;
 
-use crate::pipelines::Pipelines;
+use crate::pipelines::{FilterPipeline, Pipelines};
 use crate::shapes::{Draw, DrawType, GradientUniforms, IncompleteDrawType, Mesh};
-use crate::target::{RenderTarget, RenderTargetFrame, SwapChainTarget};
+use crate::target::{
+    RenderTarget, RenderTargetFrame, SwapChainTarget, TextureTarget, TextureTargetFrame,
+};
 use crate::utils::{
     build_view_matrix, create_buffer_with_data, gradient_spread_mode_index,
     ruffle_path_to_lyon_path, swf_bitmap_to_gl_matrix, swf_to_gl_matrix,
@@ -44,13 +47,27 @@ pub struct WgpuRenderBackend<T: RenderTarget> {
     pipelines: Pipelines,
     frame_buffer_view: wgpu::TextureView,
     depth_texture_view: wgpu::TextureView,
-    current_frame: Option<(T::Frame, wgpu::CommandEncoder)>,
+    current_frame: Option<(Frame<T::Frame>, wgpu::CommandEncoder)>,
+    capture: Option<Capture>,
+    /// The offscreen textures that draws are redirected to, innermost last.
+    offscreen_targets: Vec<OffscreenTarget<T::Frame>>,
+    /// The images of objects cached as bitmaps, indexed by `BitmapCacheHandle`.
+    bitmap_caches: Vec<Option<Option<Texture>>>,
+    bitmap_cache_stack: Vec<BitmapCacheHandle>,
+    /// The filters of the objects being rendered, or `None` where the object is drawn directly.
+    filter_stack: Vec<Option<Vec<swf::Filter>>>,
+    /// The blend modes of the objects being rendered, or `None` where the object is drawn directly.
+    blend_mode_stack: Vec<Option<swf::BlendMode>>,
     register_encoder: wgpu::CommandEncoder,
     meshes: Vec<Mesh>,
+    /// Slots in `meshes` freed by `release_shape`, to be reused by the next shape.
+    free_meshes: Vec<usize>,
     viewport_width: f32,
     viewport_height: f32,
     view_matrix: [[f32; 4]; 4],
-    textures: Vec<(swf::CharacterId, Texture)>,
+    textures: Vec<Option<(swf::CharacterId, Texture)>>,
+    /// Slots in `textures` freed by `release_bitmap`, to be reused by the next bitmap.
+    free_textures: Vec<usize>,
     num_masks: u32,
     num_masks_active: u32,
     write_stencil_mask: u32,
@@ -60,6 +77,48 @@ pub struct WgpuRenderBackend<T: RenderTarget> {
     quad_vbo: wgpu::Buffer,
     quad_ibo: wgpu::Buffer,
     quad_tex_transforms: wgpu::Buffer,
+    quality: StageQuality,
+}
+
+/// The frame being rendered, either to the display or to an image started by `begin_capture`.
+#[derive(Debug)]
+enum Frame<F> {
+    Target(F),
+    Capture(TextureTargetFrame),
+    Offscreen(wgpu::TextureView),
+}
+
+impl<F: RenderTargetFrame> RenderTargetFrame for Frame<F> {
+    fn view(&self) -> &wgpu::TextureView {
+        match self {
+            Frame::Target(frame) => frame.view(),
+            Frame::Capture(frame) => frame.view(),
+            Frame::Offscreen(view) => view,
+        }
+    }
+}
+
+/// An image being rendered offscreen by `begin_capture`, along with the framebuffers
+/// and viewport of the display, which are restored by `end_capture`.
+struct Capture {
+    target: TextureTarget,
+    width: u32,
+    height: u32,
+    frame_buffer_view: wgpu::TextureView,
+    depth_texture_view: wgpu::TextureView,
+    viewport_width: f32,
+    viewport_height: f32,
+    view_matrix: [[f32; 4]; 4],
+}
+
+/// A texture of the size of the viewport that draws are redirected to by
+/// `push_render_target`, along with the frame, framebuffers and masks of the target below it.
+struct OffscreenTarget<F> {
+    texture: wgpu::Texture,
+    parent_frame: Frame<F>,
+    parent_frame_buffer_view: wgpu::TextureView,
+    parent_depth_texture_view: wgpu::TextureView,
+    parent_masks: (u32, u32, u32, u32, u32, Vec<(u32, u32)>),
 }
 
 #[repr(C)]
@@ -110,6 +169,41 @@ impl From<ColorTransform> for ColorAdjustments {
 unsafe impl Pod for ColorAdjustments {}
 unsafe impl Zeroable for ColorAdjustments {}
 
+#[repr(C)]
+#[derive(Copy, Clone, Debug)]
+struct BlurUniforms {
+    direction: [f32; 2],
+    blur_size: f32,
+    _padding: f32,
+}
+
+unsafe impl Pod for BlurUniforms {}
+unsafe impl Zeroable for BlurUniforms {}
+
+#[repr(C)]
+#[derive(Copy, Clone, Debug)]
+struct ShadowUniforms {
+    color: [f32; 4],
+    offset: [f32; 2],
+    strength: f32,
+    inner: i32,
+    knockout: i32,
+    _padding: [i32; 3],
+}
+
+unsafe impl Pod for ShadowUniforms {}
+unsafe impl Zeroable for ShadowUniforms {}
+
+#[repr(C)]
+#[derive(Copy, Clone, Debug)]
+struct ColorMatrixUniforms {
+    matrix: [[f32; 4]; 4],
+    offset: [f32; 4],
+}
+
+unsafe impl Pod for ColorMatrixUniforms {}
+unsafe impl Zeroable for ColorMatrixUniforms {}
+
 #[repr(C)]
 #[derive(Copy, Clone, Debug)]
 struct GPUVertex {
@@ -154,44 +248,19 @@ impl<T: RenderTarget> WgpuRenderBackend<T> {
 
         let pipelines = Pipelines::new(&device, msaa_sample_count)?;
 
-        let extent = wgpu::Extent3d {
-            width: target.width(),
-            height: target.height(),
-            depth: 1,
-        };
-
-        let frame_buffer_label = create_debug_label!("Framebuffer texture");
-        let frame_buffer = device.create_texture(&wgpu::TextureDescriptor {
-            label: frame_buffer_label.as_deref(),
-            size: extent,
-            array_layer_count: 1,
-            mip_level_count: 1,
-            sample_count: msaa_sample_count,
-            dimension: wgpu::TextureDimension::D2,
-            format: target.format(),
-            usage: wgpu::TextureUsage::OUTPUT_ATTACHMENT,
-        });
-        let frame_buffer_view = frame_buffer.create_default_view();
-
-        let depth_label = create_debug_label!("Depth texture");
-        let depth_texture = device.create_texture(&wgpu::TextureDescriptor {
-            label: depth_label.as_deref(),
-            size: extent,
-            array_layer_count: 1,
-            mip_level_count: 1,
-            sample_count: msaa_sample_count,
-            dimension: wgpu::TextureDimension::D2,
-            format: wgpu::TextureFormat::Depth24PlusStencil8,
-            usage: wgpu::TextureUsage::OUTPUT_ATTACHMENT,
-        });
+        let (frame_buffer_view, depth_texture_view) = create_frame_buffers(
+            &device,
+            target.format(),
+            msaa_sample_count,
+            target.width(),
+            target.height(),
+        );
 
         let register_encoder_label = create_debug_label!("Register encoder");
         let register_encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
             label: register_encoder_label.as_deref(),
         });
 
-        let depth_texture_view = depth_texture.create_default_view();
-
         let (quad_vbo, quad_ibo, quad_tex_transforms) = create_quad_buffers(&device);
 
         let viewport_width = target.width() as f32;
@@ -207,12 +276,20 @@ impl<T: RenderTarget> WgpuRenderBackend<T> {
             frame_buffer_view,
             depth_texture_view,
             current_frame: None,
+            capture: None,
+            offscreen_targets: Vec::new(),
+            bitmap_caches: Vec::new(),
+            bitmap_cache_stack: Vec::new(),
+            filter_stack: Vec::new(),
+            blend_mode_stack: Vec::new(),
             register_encoder,
             meshes: Vec::new(),
+            free_meshes: Vec::new(),
             viewport_width,
             viewport_height,
             view_matrix,
             textures: Vec::new(),
+            free_textures: Vec::new(),
             num_masks: 0,
             num_masks_active: 0,
             write_stencil_mask: 0,
@@ -222,6 +299,7 @@ impl<T: RenderTarget> WgpuRenderBackend<T> {
             quad_vbo,
             quad_ibo,
             quad_tex_transforms,
+            quality: StageQuality::High,
         })
     }
 
@@ -500,6 +578,7 @@ impl<T: RenderTarget> WgpuRenderBackend<T> {
                         let texture = match self
                             .textures
                             .iter()
+                            .flatten()
                             .find(|(other_id, _tex)| *other_id == *id)
                         {
                             None => {
@@ -609,6 +688,28 @@ impl<T: RenderTarget> WgpuRenderBackend<T> {
         }
     }
 
+    /// Stores a mesh, reusing a slot freed by `release_shape` if there is one.
+    fn insert_mesh(&mut self, mesh: Mesh) -> ShapeHandle {
+        if let Some(index) = self.free_meshes.pop() {
+            self.meshes[index] = mesh;
+            ShapeHandle(index)
+        } else {
+            self.meshes.push(mesh);
+            ShapeHandle(self.meshes.len() - 1)
+        }
+    }
+
+    /// Stores a texture, reusing a slot freed by `release_bitmap` if there is one.
+    fn insert_texture(&mut self, id: swf::CharacterId, texture: Texture) -> BitmapHandle {
+        if let Some(index) = self.free_textures.pop() {
+            self.textures[index] = Some((id, texture));
+            BitmapHandle(index)
+        } else {
+            self.textures.push(Some((id, texture)));
+            BitmapHandle(self.textures.len() - 1)
+        }
+    }
+
     fn register_bitmap(
         &mut self,
         id: swf::CharacterId,
@@ -672,15 +773,14 @@ impl<T: RenderTarget> WgpuRenderBackend<T> {
             extent,
         );
 
-        let handle = BitmapHandle(self.textures.len());
-        self.textures.push((
+        let handle = self.insert_texture(
             id,
             Texture {
                 texture,
                 width: bitmap.width,
                 height: bitmap.height,
             },
-        ));
+        );
 
         Ok(BitmapInfo {
             handle,
@@ -698,13 +798,6 @@ impl<T: RenderTarget> WgpuRenderBackend<T> {
     }
 
     fn draw_rect(&mut self, x: f32, y: f32, width: f32, height: f32, color: Color) {
-        let (frame_output, encoder) = if let Some((frame_output, encoder)) = &mut self.current_frame
-        {
-            (frame_output, encoder)
-        } else {
-            return;
-        };
-
         let world_matrix = [
             [width, 0.0, 0.0, 0.0],
             [0.0, height, 0.0, 0.0],
@@ -721,6 +814,24 @@ impl<T: RenderTarget> WgpuRenderBackend<T> {
 
         let add_color = [0.0, 0.0, 0.0, 0.0];
 
+        self.draw_quad(
+            world_matrix,
+            ColorAdjustments {
+                mult_color,
+                add_color,
+            },
+        );
+    }
+
+    /// Draws the unit quad with the given world matrix and colors, as a solid color fill.
+    fn draw_quad(&mut self, world_matrix: [[f32; 4]; 4], colors: ColorAdjustments) {
+        let (frame_output, encoder) = if let Some((frame_output, encoder)) = &mut self.current_frame
+        {
+            (frame_output, encoder)
+        } else {
+            return;
+        };
+
         let transforms_ubo = create_buffer_with_data(
             &self.device,
             bytemuck::cast_slice(&[Transforms {
@@ -733,10 +844,7 @@ impl<T: RenderTarget> WgpuRenderBackend<T> {
 
         let colors_ubo = create_buffer_with_data(
             &self.device,
-            bytemuck::cast_slice(&[ColorAdjustments {
-                mult_color,
-                add_color,
-            }]),
+            bytemuck::cast_slice(&[colors]),
             wgpu::BufferUsage::UNIFORM,
             create_debug_label!("Rectangle colors transfer buffer"),
         );
@@ -805,18 +913,189 @@ impl<T: RenderTarget> WgpuRenderBackend<T> {
 
         render_pass.draw_indexed(0..6, 0, 0..1);
     }
-}
 
-impl<T: RenderTarget + 'static> RenderBackend for WgpuRenderBackend<T> {
-    fn set_viewport_dimensions(&mut self, width: u32, height: u32) {
-        // Avoid panics from creating 0-sized framebuffers.
-        let width = std::cmp::max(width, 1);
-        let height = std::cmp::max(height, 1);
+    /// Draws the unit quad with the given world matrix and colors, filled with a
+    /// premultiplied texture and composited with the given blend mode.
+    fn draw_texture(
+        &mut self,
+        texture_view: &wgpu::TextureView,
+        world_matrix: [[f32; 4]; 4],
+        colors: ColorAdjustments,
+        filter: wgpu::FilterMode,
+        blend_mode: swf::BlendMode,
+    ) {
+        let (frame_output, encoder) = if let Some((frame_output, encoder)) = &mut self.current_frame
+        {
+            (frame_output, encoder)
+        } else {
+            return;
+        };
 
-        self.target.resize(&self.device, width, height);
+        let transforms_ubo = create_buffer_with_data(
+            &self.device,
+            bytemuck::cast_slice(&[Transforms {
+                view_matrix: self.view_matrix,
+                world_matrix,
+            }]),
+            wgpu::BufferUsage::UNIFORM,
+            create_debug_label!("Texture transforms transfer buffer"),
+        );
+
+        let colors_ubo = create_buffer_with_data(
+            &self.device,
+            bytemuck::cast_slice(&[colors]),
+            wgpu::BufferUsage::UNIFORM,
+            create_debug_label!("Texture colors transfer buffer"),
+        );
+
+        let sampler = self.device.create_sampler(&wgpu::SamplerDescriptor {
+            address_mode_u: wgpu::AddressMode::ClampToEdge,
+            address_mode_v: wgpu::AddressMode::ClampToEdge,
+            address_mode_w: wgpu::AddressMode::ClampToEdge,
+            mag_filter: filter,
+            min_filter: filter,
+            mipmap_filter: filter,
+            lod_min_clamp: 0.0,
+            lod_max_clamp: 100.0,
+            compare: wgpu::CompareFunction::Undefined,
+        });
+
+        let bind_group_label = create_debug_label!("Texture bind group");
+        let bind_group = self.device.create_bind_group(&wgpu::BindGroupDescriptor {
+            layout: &self.pipelines.bitmap.bind_layout,
+            bindings: &[
+                wgpu::Binding {
+                    binding: 0,
+                    resource: wgpu::BindingResource::Buffer {
+                        buffer: &transforms_ubo,
+                        range: 0..std::mem::size_of::<Transforms>() as u64,
+                    },
+                },
+                wgpu::Binding {
+                    binding: 1,
+                    resource: wgpu::BindingResource::Buffer {
+                        buffer: &self.quad_tex_transforms,
+                        range: 0..std::mem::size_of::<TextureTransforms>() as u64,
+                    },
+                },
+                wgpu::Binding {
+                    binding: 2,
+                    resource: wgpu::BindingResource::Buffer {
+                        buffer: &colors_ubo,
+                        range: 0..std::mem::size_of::<ColorAdjustments>() as u64,
+                    },
+                },
+                wgpu::Binding {
+                    binding: 3,
+                    resource: wgpu::BindingResource::TextureView(texture_view),
+                },
+                wgpu::Binding {
+                    binding: 4,
+                    resource: wgpu::BindingResource::Sampler(&sampler),
+                },
+            ],
+            label: bind_group_label.as_deref(),
+        });
+
+        let (color_attachment, resolve_target) = if self.msaa_sample_count >= 2 {
+            (&self.frame_buffer_view, Some(frame_output.view()))
+        } else {
+            (frame_output.view(), None)
+        };
+        let mut render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+            color_attachments: &[wgpu::RenderPassColorAttachmentDescriptor {
+                attachment: color_attachment,
+                load_op: wgpu::LoadOp::Load,
+                store_op: wgpu::StoreOp::Store,
+                clear_color: wgpu::Color::WHITE,
+                resolve_target,
+            }],
+            depth_stencil_attachment: Some(wgpu::RenderPassDepthStencilAttachmentDescriptor {
+                attachment: &self.depth_texture_view,
+                depth_load_op: wgpu::LoadOp::Load,
+                depth_store_op: wgpu::StoreOp::Store,
+                stencil_load_op: wgpu::LoadOp::Load,
+                stencil_store_op: wgpu::StoreOp::Store,
+                clear_depth: 0.0,
+                clear_stencil: 0,
+            }),
+        });
+
+        // Blend modes don't matter when drawing a mask, which only writes to the stencil buffer.
+        if blend_mode == swf::BlendMode::Normal || self.num_masks_active < self.num_masks {
+            render_pass.set_pipeline(&self.pipelines.bitmap.pipeline_for(
+                self.num_masks,
+                self.num_masks_active,
+                self.test_stencil_mask,
+                self.write_stencil_mask,
+            ));
+        } else {
+            render_pass.set_pipeline(self.pipelines.blend.pipeline_for(
+                &self.device,
+                blend_mode,
+                self.test_stencil_mask,
+            ));
+        }
+        render_pass.set_bind_group(0, &bind_group, &[]);
+        render_pass.set_vertex_buffer(0, &self.quad_vbo, 0, 0);
+        render_pass.set_index_buffer(&self.quad_ibo, 0, 0);
+
+        if self.num_masks_active < self.num_masks {
+            render_pass.set_stencil_reference(self.write_stencil_mask);
+        } else {
+            render_pass.set_stencil_reference(self.test_stencil_mask);
+        }
+
+        render_pass.draw_indexed(0..6, 0, 0..1);
+    }
+
+    /// Draws a texture of the size of the viewport over the whole viewport, such as a popped
+    /// render target.
+    fn draw_viewport_texture(&mut self, texture_view: &wgpu::TextureView) {
+        self.draw_viewport_texture_blended(
+            texture_view,
+            ColorTransform::default(),
+            swf::BlendMode::Normal,
+        );
+    }
+
+    /// Draws a texture of the size of the viewport over the whole viewport with the given
+    /// colors, composited with the given blend mode.
+    fn draw_viewport_texture_blended(
+        &mut self,
+        texture_view: &wgpu::TextureView,
+        color_transform: ColorTransform,
+        blend_mode: swf::BlendMode,
+    ) {
+        let world_matrix = [
+            [self.viewport_width, 0.0, 0.0, 0.0],
+            [0.0, self.viewport_height, 0.0, 0.0],
+            [0.0, 0.0, 1.0, 0.0],
+            [0.0, 0.0, 0.0, 1.0],
+        ];
+        self.draw_texture(
+            texture_view,
+            world_matrix,
+            ColorAdjustments::from(color_transform),
+            wgpu::FilterMode::Nearest,
+            blend_mode,
+        );
+    }
+
+    /// Redirects all draws to a new transparent texture of the size of the viewport, until
+    /// the matching `pop_render_target`. Returns `false` outside of a frame.
+    ///
+    /// The target starts without any masks; the masks of the target below apply when
+    /// its texture is drawn back.
+    fn push_render_target(&mut self) -> bool {
+        let (parent_frame, encoder) = match self.current_frame.take() {
+            Some(frame) => frame,
+            None => return false,
+        };
 
-        let label = create_debug_label!("Framebuffer texture");
-        let frame_buffer = self.device.create_texture(&wgpu::TextureDescriptor {
+        let (width, height) = (self.viewport_width as u32, self.viewport_height as u32);
+        let label = create_debug_label!("Offscreen texture");
+        let texture = self.device.create_texture(&wgpu::TextureDescriptor {
             label: label.as_deref(),
             size: wgpu::Extent3d {
                 width,
@@ -825,51 +1104,417 @@ impl<T: RenderTarget + 'static> RenderBackend for WgpuRenderBackend<T> {
             },
             array_layer_count: 1,
             mip_level_count: 1,
-            sample_count: self.msaa_sample_count,
+            sample_count: 1,
             dimension: wgpu::TextureDimension::D2,
             format: self.target.format(),
-            usage: wgpu::TextureUsage::OUTPUT_ATTACHMENT,
+            usage: wgpu::TextureUsage::OUTPUT_ATTACHMENT | wgpu::TextureUsage::SAMPLED,
+        });
+        let (frame_buffer_view, depth_texture_view) = create_frame_buffers(
+            &self.device,
+            self.target.format(),
+            self.msaa_sample_count,
+            width,
+            height,
+        );
+
+        let frame = Frame::Offscreen(texture.create_default_view());
+        self.offscreen_targets.push(OffscreenTarget {
+            texture,
+            parent_frame,
+            parent_frame_buffer_view: replace(&mut self.frame_buffer_view, frame_buffer_view),
+            parent_depth_texture_view: replace(&mut self.depth_texture_view, depth_texture_view),
+            parent_masks: (
+                self.num_masks,
+                self.num_masks_active,
+                self.write_stencil_mask,
+                self.test_stencil_mask,
+                self.next_stencil_mask,
+                std::mem::take(&mut self.mask_stack),
+            ),
+        });
+        self.current_frame = Some((frame, encoder));
+        self.clear_frame(Color {
+            r: 0,
+            g: 0,
+            b: 0,
+            a: 0,
         });
-        self.frame_buffer_view = frame_buffer.create_default_view();
+        true
+    }
+
+    /// Stops drawing to the innermost render target and returns to the one below it,
+    /// returning the rendered texture.
+    fn pop_render_target(&mut self) -> Option<wgpu::Texture> {
+        let mut target = self.offscreen_targets.pop()?;
+        if let Some((_frame, encoder)) = self.current_frame.take() {
+            self.current_frame = Some((target.parent_frame, encoder));
+        }
+        self.frame_buffer_view = target.parent_frame_buffer_view;
+        self.depth_texture_view = target.parent_depth_texture_view;
+
+        let (num_masks, num_masks_active, write, test, next, _) = target.parent_masks;
+        self.num_masks = num_masks;
+        self.num_masks_active = num_masks_active;
+        self.write_stencil_mask = write;
+        self.test_stencil_mask = test;
+        self.next_stencil_mask = next;
+        self.mask_stack = std::mem::take(&mut target.parent_masks.5);
+
+        Some(target.texture)
+    }
+
+    /// Renders a pass of a filter into a new texture of the size of the viewport.
+    ///
+    /// The first texture is sampled as `t_color` and the second as `t_source`.
+    fn filter_pass(
+        &mut self,
+        pipeline: fn(&Pipelines) -> &FilterPipeline,
+        (texture_view, source_view): (&wgpu::TextureView, &wgpu::TextureView),
+        uniforms: &[u8],
+    ) -> Option<wgpu::Texture> {
+        let encoder = match &mut self.current_frame {
+            Some((_frame, encoder)) => encoder,
+            None => return None,
+        };
+        let pipeline = pipeline(&self.pipelines);
 
-        let label = create_debug_label!("Depth texture");
-        let depth_texture = self.device.create_texture(&wgpu::TextureDescriptor {
+        let label = create_debug_label!("Filter texture");
+        let texture = self.device.create_texture(&wgpu::TextureDescriptor {
             label: label.as_deref(),
             size: wgpu::Extent3d {
-                width,
-                height,
+                width: self.viewport_width as u32,
+                height: self.viewport_height as u32,
                 depth: 1,
             },
             array_layer_count: 1,
             mip_level_count: 1,
-            sample_count: self.msaa_sample_count,
+            sample_count: 1,
             dimension: wgpu::TextureDimension::D2,
-            format: wgpu::TextureFormat::Depth24PlusStencil8,
-            usage: wgpu::TextureUsage::OUTPUT_ATTACHMENT,
+            format: self.target.format(),
+            usage: wgpu::TextureUsage::OUTPUT_ATTACHMENT | wgpu::TextureUsage::SAMPLED,
         });
-        self.depth_texture_view = depth_texture.create_default_view();
+        let view = texture.create_default_view();
 
-        self.viewport_width = width as f32;
-        self.viewport_height = height as f32;
-        self.view_matrix = build_view_matrix(width, height);
-    }
+        let transforms_ubo = create_buffer_with_data(
+            &self.device,
+            bytemuck::cast_slice(&[Transforms {
+                view_matrix: self.view_matrix,
+                world_matrix: [
+                    [self.viewport_width, 0.0, 0.0, 0.0],
+                    [0.0, self.viewport_height, 0.0, 0.0],
+                    [0.0, 0.0, 1.0, 0.0],
+                    [0.0, 0.0, 0.0, 1.0],
+                ],
+            }]),
+            wgpu::BufferUsage::UNIFORM,
+            create_debug_label!("Filter transforms transfer buffer"),
+        );
 
-    fn register_shape(&mut self, shape: DistilledShape) -> ShapeHandle {
-        let handle = ShapeHandle(self.meshes.len());
-        let mesh = self.register_shape_internal(shape);
-        self.meshes.push(mesh);
-        handle
-    }
+        let filter_ubo = create_buffer_with_data(
+            &self.device,
+            uniforms,
+            wgpu::BufferUsage::UNIFORM,
+            create_debug_label!("Filter uniforms transfer buffer"),
+        );
 
-    fn replace_shape(&mut self, shape: DistilledShape, handle: ShapeHandle) {
-        let mesh = self.register_shape_internal(shape);
-        self.meshes[handle.0] = mesh;
-    }
+        let sampler = self.device.create_sampler(&wgpu::SamplerDescriptor {
+            address_mode_u: wgpu::AddressMode::ClampToEdge,
+            address_mode_v: wgpu::AddressMode::ClampToEdge,
+            address_mode_w: wgpu::AddressMode::ClampToEdge,
+            mag_filter: wgpu::FilterMode::Linear,
+            min_filter: wgpu::FilterMode::Linear,
+            mipmap_filter: wgpu::FilterMode::Linear,
+            lod_min_clamp: 0.0,
+            lod_max_clamp: 100.0,
+            compare: wgpu::CompareFunction::Undefined,
+        });
 
-    fn register_glyph_shape(&mut self, glyph: &Glyph) -> ShapeHandle {
-        let shape = swf::Shape {
-            version: 2,
-            id: 0,
+        let bind_group_label = create_debug_label!("Filter bind group");
+        let bind_group = self.device.create_bind_group(&wgpu::BindGroupDescriptor {
+            layout: &pipeline.bind_layout,
+            bindings: &[
+                wgpu::Binding {
+                    binding: 0,
+                    resource: wgpu::BindingResource::Buffer {
+                        buffer: &transforms_ubo,
+                        range: 0..std::mem::size_of::<Transforms>() as u64,
+                    },
+                },
+                wgpu::Binding {
+                    binding: 1,
+                    resource: wgpu::BindingResource::Buffer {
+                        buffer: &self.quad_tex_transforms,
+                        range: 0..std::mem::size_of::<TextureTransforms>() as u64,
+                    },
+                },
+                wgpu::Binding {
+                    binding: 2,
+                    resource: wgpu::BindingResource::Buffer {
+                        buffer: &filter_ubo,
+                        range: 0..uniforms.len() as u64,
+                    },
+                },
+                wgpu::Binding {
+                    binding: 3,
+                    resource: wgpu::BindingResource::TextureView(texture_view),
+                },
+                wgpu::Binding {
+                    binding: 4,
+                    resource: wgpu::BindingResource::Sampler(&sampler),
+                },
+                wgpu::Binding {
+                    binding: 5,
+                    resource: wgpu::BindingResource::TextureView(source_view),
+                },
+            ],
+            label: bind_group_label.as_deref(),
+        });
+
+        let mut render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+            color_attachments: &[wgpu::RenderPassColorAttachmentDescriptor {
+                attachment: &view,
+                load_op: wgpu::LoadOp::Clear,
+                store_op: wgpu::StoreOp::Store,
+                clear_color: wgpu::Color::TRANSPARENT,
+                resolve_target: None,
+            }],
+            depth_stencil_attachment: None,
+        });
+        render_pass.set_pipeline(&pipeline.pipeline);
+        render_pass.set_bind_group(0, &bind_group, &[]);
+        render_pass.set_vertex_buffer(0, &self.quad_vbo, 0, 0);
+        render_pass.set_index_buffer(&self.quad_ibo, 0, 0);
+        render_pass.draw_indexed(0..6, 0, 0..1);
+        drop(render_pass);
+
+        Some(texture)
+    }
+
+    /// Blurs a texture of the size of the viewport with the given number of box blurs.
+    /// Returns `None` if the blur is too small to change the texture.
+    fn blur(
+        &mut self,
+        texture_view: &wgpu::TextureView,
+        blur_x: f64,
+        blur_y: f64,
+        num_passes: u8,
+    ) -> Option<wgpu::Texture> {
+        let directions = [
+            (blur_x, [1.0 / self.viewport_width, 0.0]),
+            (blur_y, [0.0, 1.0 / self.viewport_height]),
+        ];
+        let mut blurred: Option<(wgpu::Texture, wgpu::TextureView)> = None;
+        for _ in 0..num_passes.max(1) {
+            for &(blur_size, direction) in directions.iter().filter(|(size, _)| *size > 1.0) {
+                let uniforms = BlurUniforms {
+                    direction,
+                    blur_size: blur_size as f32,
+                    _padding: 0.0,
+                };
+                let input = blurred.as_ref().map_or(texture_view, |(_, view)| view);
+                let output = self.filter_pass(
+                    |pipelines| &pipelines.blur,
+                    (input, input),
+                    bytemuck::cast_slice(&[uniforms]),
+                );
+                if let Some(output) = output {
+                    let view = output.create_default_view();
+                    blurred = Some((output, view));
+                }
+            }
+        }
+        blurred.map(|(texture, _)| texture)
+    }
+
+    /// Draws a glow or a drop shadow of an object, offset by the given distance in pixels.
+    #[allow(clippy::too_many_arguments)]
+    fn shadow(
+        &mut self,
+        texture_view: &wgpu::TextureView,
+        color: &Color,
+        (blur_x, blur_y): (f64, f64),
+        strength: f32,
+        (offset_x, offset_y): (f64, f64),
+        is_inner: bool,
+        is_knockout: bool,
+        num_passes: u8,
+    ) -> Option<wgpu::Texture> {
+        let blurred = self
+            .blur(texture_view, blur_x, blur_y, num_passes)
+            .map(|texture| texture.create_default_view());
+        let uniforms = ShadowUniforms {
+            color: [
+                f32::from(color.r) / 255.0,
+                f32::from(color.g) / 255.0,
+                f32::from(color.b) / 255.0,
+                f32::from(color.a) / 255.0,
+            ],
+            offset: [
+                (offset_x / f64::from(self.viewport_width)) as f32,
+                (offset_y / f64::from(self.viewport_height)) as f32,
+            ],
+            strength,
+            inner: is_inner as i32,
+            knockout: is_knockout as i32,
+            _padding: [0; 3],
+        };
+        let shape = blurred.as_ref().unwrap_or(texture_view);
+        self.filter_pass(
+            |pipelines| &pipelines.shadow,
+            (shape, texture_view),
+            bytemuck::cast_slice(&[uniforms]),
+        )
+    }
+
+    /// Applies a filter to a texture of the size of the viewport, returning the filtered
+    /// image. Returns `None` if the filter leaves the texture unchanged.
+    fn apply_filter(
+        &mut self,
+        texture_view: &wgpu::TextureView,
+        filter: &swf::Filter,
+    ) -> Option<wgpu::Texture> {
+        use swf::Filter;
+        match filter {
+            Filter::BlurFilter(blur) => {
+                self.blur(texture_view, blur.blur_x, blur.blur_y, blur.num_passes)
+            }
+            Filter::GlowFilter(glow) => self.shadow(
+                texture_view,
+                &glow.color,
+                (glow.blur_x, glow.blur_y),
+                glow.strength,
+                (0.0, 0.0),
+                glow.is_inner,
+                glow.is_knockout,
+                glow.num_passes,
+            ),
+            Filter::DropShadowFilter(shadow) => self.shadow(
+                texture_view,
+                &shadow.color,
+                (shadow.blur_x, shadow.blur_y),
+                shadow.strength,
+                (
+                    shadow.distance * shadow.angle.cos(),
+                    shadow.distance * shadow.angle.sin(),
+                ),
+                shadow.is_inner,
+                shadow.is_knockout,
+                shadow.num_passes,
+            ),
+            Filter::ColorMatrixFilter(color_matrix) => {
+                // The matrix is given row by row, with an offset in the 0-255 range at the
+                // end of each row.
+                let m = &color_matrix.matrix;
+                let mut uniforms = ColorMatrixUniforms {
+                    matrix: [[0.0; 4]; 4],
+                    offset: [0.0; 4],
+                };
+                for row in 0..4 {
+                    for column in 0..4 {
+                        uniforms.matrix[column][row] = m[row * 5 + column] as f32;
+                    }
+                    uniforms.offset[row] = m[row * 5 + 4] as f32 / 255.0;
+                }
+                self.filter_pass(
+                    |pipelines| &pipelines.color_matrix,
+                    (texture_view, texture_view),
+                    bytemuck::cast_slice(&[uniforms]),
+                )
+            }
+            _ => None,
+        }
+    }
+
+    /// Finishes the commands that upload registered shapes and bitmaps, which must be
+    /// submitted before the frame that uses them.
+    fn finish_register_encoder(&mut self) -> wgpu::CommandBuffer {
+        let register_encoder_label = create_debug_label!("Register encoder");
+        let new_register_encoder =
+            self.device
+                .create_command_encoder(&wgpu::CommandEncoderDescriptor {
+                    label: register_encoder_label.as_deref(),
+                });
+        replace(&mut self.register_encoder, new_register_encoder).finish()
+    }
+
+    /// Resets the masks and clears the current frame, at the start of a frame or capture.
+    fn clear_frame(&mut self, clear: Color) {
+        self.num_masks = 0;
+        self.num_masks_active = 0;
+        self.write_stencil_mask = 0;
+        self.test_stencil_mask = 0;
+        self.next_stencil_mask = 1;
+
+        if let Some((frame_output, encoder)) = &mut self.current_frame {
+            let (color_attachment, resolve_target) = if self.msaa_sample_count >= 2 {
+                (&self.frame_buffer_view, Some(frame_output.view()))
+            } else {
+                (frame_output.view(), None)
+            };
+            encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+                color_attachments: &[wgpu::RenderPassColorAttachmentDescriptor {
+                    attachment: color_attachment,
+                    load_op: wgpu::LoadOp::Clear,
+                    store_op: wgpu::StoreOp::Store,
+                    clear_color: wgpu::Color {
+                        r: f64::from(clear.r) / 255.0,
+                        g: f64::from(clear.g) / 255.0,
+                        b: f64::from(clear.b) / 255.0,
+                        a: f64::from(clear.a) / 255.0,
+                    },
+                    resolve_target,
+                }],
+                depth_stencil_attachment: Some(wgpu::RenderPassDepthStencilAttachmentDescriptor {
+                    attachment: &self.depth_texture_view,
+                    depth_load_op: wgpu::LoadOp::Clear,
+                    depth_store_op: wgpu::StoreOp::Store,
+                    stencil_load_op: wgpu::LoadOp::Clear,
+                    stencil_store_op: wgpu::StoreOp::Store,
+                    clear_depth: 0.0,
+                    clear_stencil: 0,
+                }),
+            });
+        }
+    }
+}
+
+impl<T: RenderTarget + 'static> RenderBackend for WgpuRenderBackend<T> {
+    fn set_viewport_dimensions(&mut self, width: u32, height: u32) {
+        // Avoid panics from creating 0-sized framebuffers.
+        let width = std::cmp::max(width, 1);
+        let height = std::cmp::max(height, 1);
+
+        self.target.resize(&self.device, width, height);
+
+        let (frame_buffer_view, depth_texture_view) = create_frame_buffers(
+            &self.device,
+            self.target.format(),
+            self.msaa_sample_count,
+            width,
+            height,
+        );
+        self.frame_buffer_view = frame_buffer_view;
+        self.depth_texture_view = depth_texture_view;
+
+        self.viewport_width = width as f32;
+        self.viewport_height = height as f32;
+        self.view_matrix = build_view_matrix(width, height);
+    }
+
+    fn register_shape(&mut self, shape: DistilledShape) -> ShapeHandle {
+        let mesh = self.register_shape_internal(shape);
+        self.insert_mesh(mesh)
+    }
+
+    fn replace_shape(&mut self, shape: DistilledShape, handle: ShapeHandle) {
+        let mesh = self.register_shape_internal(shape);
+        self.meshes[handle.0] = mesh;
+    }
+
+    fn register_glyph_shape(&mut self, glyph: &Glyph) -> ShapeHandle {
+        let shape = swf::Shape {
+            version: 2,
+            id: 0,
             shape_bounds: Default::default(),
             edge_bounds: Default::default(),
             has_fill_winding_rule: false,
@@ -886,10 +1531,24 @@ impl<T: RenderTarget + 'static> RenderBackend for WgpuRenderBackend<T> {
             },
             shape: glyph.shape_records.clone(),
         };
-        let handle = ShapeHandle(self.meshes.len());
         let mesh = self.register_shape_internal((&shape).into());
-        self.meshes.push(mesh);
-        handle
+        self.insert_mesh(mesh)
+    }
+
+    fn release_shape(&mut self, shape: ShapeHandle) {
+        // Dropping the draws frees their vertex and index buffers.
+        // The rest of the mesh is dropped once its slot is reused.
+        if let Some(mesh) = self.meshes.get_mut(shape.0) {
+            mesh.draws.clear();
+            self.free_meshes.push(shape.0);
+        }
+    }
+
+    fn release_bitmap(&mut self, bitmap: BitmapHandle) {
+        // Handles index into `textures`, so the slot is emptied and reused by a later bitmap.
+        if let Some(Some(_)) = self.textures.get_mut(bitmap.0).map(Option::take) {
+            self.free_textures.push(bitmap.0);
+        }
     }
 
     fn register_bitmap_jpeg(
@@ -923,13 +1582,61 @@ impl<T: RenderTarget + 'static> RenderBackend for WgpuRenderBackend<T> {
         self.register_bitmap(swf_tag.id, bitmap, "PNG")
     }
 
+    fn register_bitmap_rgba(
+        &mut self,
+        id: CharacterId,
+        width: u32,
+        height: u32,
+        rgba: Vec<u8>,
+    ) -> Result<BitmapInfo, Error> {
+        self.register_bitmap(
+            id,
+            Bitmap {
+                width,
+                height,
+                data: BitmapFormat::Rgba(rgba),
+            },
+            "BitmapData",
+        )
+    }
+
+    fn update_bitmap_rgba(
+        &mut self,
+        bitmap: BitmapHandle,
+        width: u32,
+        height: u32,
+        rgba: Vec<u8>,
+    ) -> Result<(), Error> {
+        if let Some(Some((id, _))) = self.textures.get(bitmap.0) {
+            let id = *id;
+            let new_bitmap = self.register_bitmap(
+                id,
+                Bitmap {
+                    width,
+                    height,
+                    data: BitmapFormat::Rgba(rgba),
+                },
+                "BitmapData",
+            )?;
+            // Move the new texture into the existing handle and free its slot.
+            let texture = self.textures[new_bitmap.handle.0].take();
+            self.textures[bitmap.0] = texture;
+            self.free_textures.push(new_bitmap.handle.0);
+        }
+        Ok(())
+    }
+
+    fn set_quality(&mut self, quality: StageQuality) {
+        self.quality = quality;
+    }
+
     fn begin_frame(&mut self, clear: Color) {
         assert!(self.current_frame.is_none());
         self.current_frame = match self.target.get_next_texture() {
             Ok(frame) => {
                 let label = create_debug_label!("Frame encoder");
                 Some((
-                    frame,
+                    Frame::Target(frame),
                     self.device
                         .create_command_encoder(&wgpu::CommandEncoderDescriptor {
                             label: label.as_deref(),
@@ -941,185 +1648,54 @@ impl<T: RenderTarget + 'static> RenderBackend for WgpuRenderBackend<T> {
                 None
             }
         };
-        self.num_masks = 0;
-        self.num_masks_active = 0;
-        self.write_stencil_mask = 0;
-        self.test_stencil_mask = 0;
-        self.next_stencil_mask = 1;
-
-        if let Some((frame_output, encoder)) = &mut self.current_frame {
-            let (color_attachment, resolve_target) = if self.msaa_sample_count >= 2 {
-                (&self.frame_buffer_view, Some(frame_output.view()))
-            } else {
-                (frame_output.view(), None)
-            };
-            encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
-                color_attachments: &[wgpu::RenderPassColorAttachmentDescriptor {
-                    attachment: color_attachment,
-                    load_op: wgpu::LoadOp::Clear,
-                    store_op: wgpu::StoreOp::Store,
-                    clear_color: wgpu::Color {
-                        r: f64::from(clear.r) / 255.0,
-                        g: f64::from(clear.g) / 255.0,
-                        b: f64::from(clear.b) / 255.0,
-                        a: f64::from(clear.a) / 255.0,
-                    },
-                    resolve_target,
-                }],
-                depth_stencil_attachment: Some(wgpu::RenderPassDepthStencilAttachmentDescriptor {
-                    attachment: &self.depth_texture_view,
-                    depth_load_op: wgpu::LoadOp::Clear,
-                    depth_store_op: wgpu::StoreOp::Store,
-                    stencil_load_op: wgpu::LoadOp::Clear,
-                    stencil_store_op: wgpu::StoreOp::Store,
-                    clear_depth: 0.0,
-                    clear_stencil: 0,
-                }),
-            });
-        }
+        self.clear_frame(clear);
     }
 
-    fn render_bitmap(&mut self, bitmap: BitmapHandle, transform: &Transform) {
-        if let Some((_id, texture)) = self.textures.get(bitmap.0) {
-            let (frame_output, encoder) =
-                if let Some((frame_output, encoder)) = &mut self.current_frame {
-                    (frame_output, encoder)
-                } else {
-                    return;
-                };
-
-            use ruffle_core::swf::Matrix;
-            let transform = Transform {
-                matrix: transform.matrix
-                    * Matrix {
-                        a: texture.width as f32,
-                        d: texture.height as f32,
-                        ..Default::default()
-                    },
-                ..*transform
-            };
-
-            let world_matrix = [
-                [transform.matrix.a, transform.matrix.b, 0.0, 0.0],
-                [transform.matrix.c, transform.matrix.d, 0.0, 0.0],
-                [0.0, 0.0, 1.0, 0.0],
-                [
-                    transform.matrix.tx.to_pixels() as f32,
-                    transform.matrix.ty.to_pixels() as f32,
-                    0.0,
-                    1.0,
-                ],
-            ];
-
-            let transforms_ubo = create_buffer_with_data(
-                &self.device,
-                bytemuck::cast_slice(&[Transforms {
-                    view_matrix: self.view_matrix,
-                    world_matrix,
-                }]),
-                wgpu::BufferUsage::UNIFORM,
-                create_debug_label!("Bitmap {} transforms transfer buffer", bitmap.0),
-            );
-
-            let colors_ubo = create_buffer_with_data(
-                &self.device,
-                bytemuck::cast_slice(&[ColorAdjustments::from(transform.color_transform)]),
-                wgpu::BufferUsage::UNIFORM,
-                create_debug_label!("Bitmap {} colors transfer buffer", bitmap.0),
-            );
-
-            let texture_view = texture.texture.create_default_view();
-            let sampler = self.device.create_sampler(&wgpu::SamplerDescriptor {
-                address_mode_u: wgpu::AddressMode::ClampToEdge,
-                address_mode_v: wgpu::AddressMode::ClampToEdge,
-                address_mode_w: wgpu::AddressMode::ClampToEdge,
-                mag_filter: wgpu::FilterMode::Linear,
-                min_filter: wgpu::FilterMode::Linear,
-                mipmap_filter: wgpu::FilterMode::Linear,
-                lod_min_clamp: 0.0,
-                lod_max_clamp: 100.0,
-                compare: wgpu::CompareFunction::Undefined,
-            });
-
-            let bind_group_label = create_debug_label!("Bitmap {} bind group", bitmap.0);
-            let bind_group = self.device.create_bind_group(&wgpu::BindGroupDescriptor {
-                layout: &self.pipelines.bitmap.bind_layout,
-                bindings: &[
-                    wgpu::Binding {
-                        binding: 0,
-                        resource: wgpu::BindingResource::Buffer {
-                            buffer: &transforms_ubo,
-                            range: 0..std::mem::size_of::<Transforms>() as u64,
-                        },
-                    },
-                    wgpu::Binding {
-                        binding: 1,
-                        resource: wgpu::BindingResource::Buffer {
-                            buffer: &self.quad_tex_transforms,
-                            range: 0..std::mem::size_of::<TextureTransforms>() as u64,
-                        },
-                    },
-                    wgpu::Binding {
-                        binding: 2,
-                        resource: wgpu::BindingResource::Buffer {
-                            buffer: &colors_ubo,
-                            range: 0..std::mem::size_of::<ColorAdjustments>() as u64,
-                        },
-                    },
-                    wgpu::Binding {
-                        binding: 3,
-                        resource: wgpu::BindingResource::TextureView(&texture_view),
-                    },
-                    wgpu::Binding {
-                        binding: 4,
-                        resource: wgpu::BindingResource::Sampler(&sampler),
-                    },
-                ],
-                label: bind_group_label.as_deref(),
-            });
-
-            let (color_attachment, resolve_target) = if self.msaa_sample_count >= 2 {
-                (&self.frame_buffer_view, Some(frame_output.view()))
-            } else {
-                (frame_output.view(), None)
-            };
-            let mut render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
-                color_attachments: &[wgpu::RenderPassColorAttachmentDescriptor {
-                    attachment: color_attachment,
-                    load_op: wgpu::LoadOp::Load,
-                    store_op: wgpu::StoreOp::Store,
-                    clear_color: wgpu::Color::WHITE,
-                    resolve_target,
-                }],
-                depth_stencil_attachment: Some(wgpu::RenderPassDepthStencilAttachmentDescriptor {
-                    attachment: &self.depth_texture_view,
-                    depth_load_op: wgpu::LoadOp::Load,
-                    depth_store_op: wgpu::StoreOp::Store,
-                    stencil_load_op: wgpu::LoadOp::Load,
-                    stencil_store_op: wgpu::StoreOp::Store,
-                    clear_depth: 0.0,
-                    clear_stencil: 0,
-                }),
-            });
+    fn render_bitmap(&mut self, bitmap: BitmapHandle, transform: &Transform, smoothing: bool) {
+        let (texture_view, width, height) = match self.textures.get(bitmap.0) {
+            Some(Some((_id, texture))) => (
+                texture.texture.create_default_view(),
+                texture.width,
+                texture.height,
+            ),
+            _ => return,
+        };
 
-            render_pass.set_pipeline(&self.pipelines.bitmap.pipeline_for(
-                self.num_masks,
-                self.num_masks_active,
-                self.test_stencil_mask,
-                self.write_stencil_mask,
-            ));
-            render_pass.set_bind_group(0, &bind_group, &[]);
-            render_pass.set_vertex_buffer(0, &self.quad_vbo, 0, 0);
-            render_pass.set_index_buffer(&self.quad_ibo, 0, 0);
+        use ruffle_core::swf::Matrix;
+        let transform = Transform {
+            matrix: transform.matrix
+                * Matrix {
+                    a: width as f32,
+                    d: height as f32,
+                    ..Default::default()
+                },
+            ..*transform
+        };
 
-            if self.num_masks_active < self.num_masks {
-                render_pass.set_stencil_reference(self.write_stencil_mask);
-            } else {
-                render_pass.set_stencil_reference(self.test_stencil_mask);
-            }
+        let world_matrix = [
+            [transform.matrix.a, transform.matrix.b, 0.0, 0.0],
+            [transform.matrix.c, transform.matrix.d, 0.0, 0.0],
+            [0.0, 0.0, 1.0, 0.0],
+            [
+                transform.matrix.tx.to_pixels() as f32,
+                transform.matrix.ty.to_pixels() as f32,
+                0.0,
+                1.0,
+            ],
+        ];
 
-            render_pass.draw_indexed(0..6, 0, 0..1);
-        }
+        let filter = if smoothing && self.quality.smooths_bitmaps() {
+            wgpu::FilterMode::Linear
+        } else {
+            wgpu::FilterMode::Nearest
+        };
+        self.draw_texture(
+            &texture_view,
+            world_matrix,
+            ColorAdjustments::from(transform.color_transform),
+            filter,
+            swf::BlendMode::Normal,
+        );
     }
 
     fn render_shape(&mut self, shape: ShapeHandle, transform: &Transform) {
@@ -1249,14 +1825,7 @@ impl<T: RenderTarget + 'static> RenderBackend for WgpuRenderBackend<T> {
 
     fn end_frame(&mut self) {
         if let Some((_frame, encoder)) = self.current_frame.take() {
-            let register_encoder_label = create_debug_label!("Register encoder");
-            let new_register_encoder =
-                self.device
-                    .create_command_encoder(&wgpu::CommandEncoderDescriptor {
-                        label: register_encoder_label.as_deref(),
-                    });
-            let register_buffer =
-                replace(&mut self.register_encoder, new_register_encoder).finish();
+            let register_buffer = self.finish_register_encoder();
             self.target.submit(
                 &self.device,
                 &self.queue,
@@ -1385,6 +1954,309 @@ impl<T: RenderTarget + 'static> RenderBackend for WgpuRenderBackend<T> {
             self.test_stencil_mask = test;
         }
     }
+
+    fn push_filters(&mut self, filters: &[swf::Filter]) {
+        use swf::Filter;
+        let filters: Vec<_> = filters
+            .iter()
+            .filter(|filter| match filter {
+                Filter::BlurFilter(_)
+                | Filter::GlowFilter(_)
+                | Filter::DropShadowFilter(_)
+                | Filter::ColorMatrixFilter(_) => true,
+                _ => {
+                    log::warn!("Unsupported filter {:?}", filter);
+                    false
+                }
+            })
+            .cloned()
+            .collect();
+
+        // The object is rendered offscreen, then each filter renders a new image from it.
+        // Outside of a frame, there is nothing to render and the object is drawn directly.
+        if !filters.is_empty() && self.push_render_target() {
+            self.filter_stack.push(Some(filters));
+        } else {
+            self.filter_stack.push(None);
+        }
+    }
+
+    fn pop_filters(&mut self) {
+        let filters = match self.filter_stack.pop() {
+            Some(Some(filters)) => filters,
+            _ => return,
+        };
+        let mut image = match self.pop_render_target() {
+            Some(texture) => texture.create_default_view(),
+            None => return,
+        };
+        for filter in &filters {
+            if let Some(filtered) = self.apply_filter(&image, filter) {
+                image = filtered.create_default_view();
+            }
+        }
+        self.draw_viewport_texture(&image);
+    }
+
+    fn push_blend_mode(&mut self, blend_mode: swf::BlendMode) {
+        if let swf::BlendMode::Difference | swf::BlendMode::Overlay | swf::BlendMode::HardLight =
+            blend_mode
+        {
+            log::warn!("Unsupported blend mode {:?}", blend_mode);
+        }
+
+        // The object is rendered offscreen, then composited with the blend mode's pipeline.
+        // Outside of a frame, there is nothing to render and the object is drawn directly.
+        if self.push_render_target() {
+            self.blend_mode_stack.push(Some(blend_mode));
+        } else {
+            self.blend_mode_stack.push(None);
+        }
+    }
+
+    fn pop_blend_mode(&mut self) {
+        let blend_mode = match self.blend_mode_stack.pop() {
+            Some(Some(blend_mode)) => blend_mode,
+            _ => return,
+        };
+        let image = match self.pop_render_target() {
+            Some(texture) => texture.create_default_view(),
+            None => return,
+        };
+        let color_transform = if blend_mode == swf::BlendMode::Invert {
+            // Invert only needs the object's coverage, drawn as its alpha in every channel.
+            ColorTransform {
+                r_mult: 0.0,
+                g_mult: 0.0,
+                b_mult: 0.0,
+                a_mult: 1.0,
+                r_add: 1.0,
+                g_add: 1.0,
+                b_add: 1.0,
+                a_add: 0.0,
+            }
+        } else {
+            ColorTransform::default()
+        };
+        self.draw_viewport_texture_blended(&image, color_transform, blend_mode);
+    }
+
+    fn create_bitmap_cache(&mut self) -> BitmapCacheHandle {
+        // Reuse a released slot if there is one.
+        if let Some(index) = self.bitmap_caches.iter().position(Option::is_none) {
+            self.bitmap_caches[index] = Some(None);
+            BitmapCacheHandle(index)
+        } else {
+            self.bitmap_caches.push(Some(None));
+            BitmapCacheHandle(self.bitmap_caches.len() - 1)
+        }
+    }
+
+    fn render_bitmap_cache(&mut self, cache: BitmapCacheHandle) -> bool {
+        // The cached image is in stage space, so it is stale if the viewport was resized.
+        let texture_view = match self.bitmap_caches.get(cache.0) {
+            Some(Some(Some(texture)))
+                if texture.width == self.viewport_width as u32
+                    && texture.height == self.viewport_height as u32 =>
+            {
+                texture.texture.create_default_view()
+            }
+            _ => return false,
+        };
+        self.draw_viewport_texture(&texture_view);
+        true
+    }
+
+    fn begin_bitmap_cache(&mut self, cache: BitmapCacheHandle) {
+        // Outside of a frame, there is nothing to render and the object is drawn directly.
+        if self.push_render_target() {
+            self.bitmap_cache_stack.push(cache);
+        }
+    }
+
+    fn end_bitmap_cache(&mut self) {
+        let cache = match self.bitmap_cache_stack.pop() {
+            Some(cache) => cache,
+            None => return,
+        };
+        let texture = match self.pop_render_target() {
+            Some(texture) => texture,
+            None => return,
+        };
+        self.draw_viewport_texture(&texture.create_default_view());
+
+        // The rendered image is kept as the new cached image.
+        if let Some(Some(slot)) = self.bitmap_caches.get_mut(cache.0) {
+            *slot = Some(Texture {
+                width: self.viewport_width as u32,
+                height: self.viewport_height as u32,
+                texture,
+            });
+        }
+    }
+
+    fn release_bitmap_cache(&mut self, cache: BitmapCacheHandle) {
+        if let Some(slot) = self.bitmap_caches.get_mut(cache.0) {
+            *slot = None;
+        }
+    }
+
+    fn draw_rect(&mut self, color: Color, rect: &swf::Rectangle, transform: &Transform) {
+        let matrix = transform.matrix
+            * swf::Matrix {
+                a: (rect.x_max - rect.x_min).to_pixels() as f32,
+                d: (rect.y_max - rect.y_min).to_pixels() as f32,
+                tx: rect.x_min,
+                ty: rect.y_min,
+                ..Default::default()
+            };
+        let world_matrix = [
+            [matrix.a, matrix.b, 0.0, 0.0],
+            [matrix.c, matrix.d, 0.0, 0.0],
+            [0.0, 0.0, 1.0, 0.0],
+            [
+                matrix.tx.to_pixels() as f32,
+                matrix.ty.to_pixels() as f32,
+                0.0,
+                1.0,
+            ],
+        ];
+
+        let mut colors = ColorAdjustments::from(transform.color_transform);
+        let channels = [color.r, color.g, color.b, color.a];
+        for (mult, &channel) in colors.mult_color.iter_mut().zip(&channels) {
+            *mult *= f32::from(channel) / 255.0;
+        }
+
+        self.draw_quad(world_matrix, colors);
+    }
+
+    fn begin_capture(&mut self, width: u32, height: u32, clear: Color) -> bool {
+        if self.current_frame.is_some() {
+            log::warn!("Can't begin a capture in the middle of a frame");
+            return false;
+        }
+
+        let width = std::cmp::max(width, 1);
+        let height = std::cmp::max(height, 1);
+        // Rows of the texture are copied into the readback buffer, which requires
+        // them to be a multiple of 256 bytes. The extra columns are cropped later.
+        let texture_width = (width + 63) / 64 * 64;
+        let mut target = TextureTarget::new(&self.device, (texture_width, height));
+        let frame = match target.get_next_texture() {
+            Ok(frame) => frame,
+            Err(wgpu::TimeOut) => return false,
+        };
+
+        let (frame_buffer_view, depth_texture_view) = create_frame_buffers(
+            &self.device,
+            target.format(),
+            self.msaa_sample_count,
+            texture_width,
+            height,
+        );
+        self.capture = Some(Capture {
+            target,
+            width,
+            height,
+            frame_buffer_view: replace(&mut self.frame_buffer_view, frame_buffer_view),
+            depth_texture_view: replace(&mut self.depth_texture_view, depth_texture_view),
+            viewport_width: self.viewport_width,
+            viewport_height: self.viewport_height,
+            view_matrix: self.view_matrix,
+        });
+        self.viewport_width = texture_width as f32;
+        self.viewport_height = height as f32;
+        self.view_matrix = build_view_matrix(texture_width, height);
+
+        let label = create_debug_label!("Capture encoder");
+        self.current_frame = Some((
+            Frame::Capture(frame),
+            self.device
+                .create_command_encoder(&wgpu::CommandEncoderDescriptor {
+                    label: label.as_deref(),
+                }),
+        ));
+        self.clear_frame(clear);
+        true
+    }
+
+    fn end_capture(&mut self) -> Option<Bitmap> {
+        let capture = self.capture.take()?;
+        let image = match self.current_frame.take() {
+            Some((Frame::Capture(_frame), encoder)) => {
+                let register_buffer = self.finish_register_encoder();
+                capture.target.submit(
+                    &self.device,
+                    &self.queue,
+                    &[register_buffer, encoder.finish()],
+                );
+                capture.target.capture(&self.device)
+            }
+            _ => None,
+        };
+
+        self.frame_buffer_view = capture.frame_buffer_view;
+        self.depth_texture_view = capture.depth_texture_view;
+        self.viewport_width = capture.viewport_width;
+        self.viewport_height = capture.viewport_height;
+        self.view_matrix = capture.view_matrix;
+
+        let mut image = image?;
+        let image = image::imageops::crop(&mut image, 0, 0, capture.width, capture.height);
+        let mut data = image.to_image().into_raw();
+        unmultiply_alpha_rgba(&mut data);
+        Some(Bitmap {
+            width: capture.width,
+            height: capture.height,
+            data: BitmapFormat::Rgba(data),
+        })
+    }
+}
+
+/// Creates the multisampled framebuffer and the depth/stencil buffer to render a frame of
+/// the given size with.
+fn create_frame_buffers(
+    device: &wgpu::Device,
+    format: wgpu::TextureFormat,
+    msaa_sample_count: u32,
+    width: u32,
+    height: u32,
+) -> (wgpu::TextureView, wgpu::TextureView) {
+    let extent = wgpu::Extent3d {
+        width,
+        height,
+        depth: 1,
+    };
+
+    let frame_buffer_label = create_debug_label!("Framebuffer texture");
+    let frame_buffer = device.create_texture(&wgpu::TextureDescriptor {
+        label: frame_buffer_label.as_deref(),
+        size: extent,
+        array_layer_count: 1,
+        mip_level_count: 1,
+        sample_count: msaa_sample_count,
+        dimension: wgpu::TextureDimension::D2,
+        format,
+        usage: wgpu::TextureUsage::OUTPUT_ATTACHMENT,
+    });
+
+    let depth_label = create_debug_label!("Depth texture");
+    let depth_texture = device.create_texture(&wgpu::TextureDescriptor {
+        label: depth_label.as_deref(),
+        size: extent,
+        array_layer_count: 1,
+        mip_level_count: 1,
+        sample_count: msaa_sample_count,
+        dimension: wgpu::TextureDimension::D2,
+        format: wgpu::TextureFormat::Depth24PlusStencil8,
+        usage: wgpu::TextureUsage::OUTPUT_ATTACHMENT,
+    });
+
+    (
+        frame_buffer.create_default_view(),
+        depth_texture.create_default_view(),
+    )
 }
 
 fn create_quad_buffers(device: &wgpu::Device) -> (wgpu::Buffer, wgpu::Buffer, wgpu::Buffer) {
@@ -1,4 +1,6 @@
 use crate::{Error, GPUVertex};
+use ruffle_core::backend::render::swf::BlendMode;
+use std::collections::HashMap;
 use wgpu::vertex_attr_array;
 
 #[derive(Debug)]
@@ -8,11 +10,33 @@ pub struct ShapePipeline {
     pub bind_layout: wgpu::BindGroupLayout,
 }
 
+/// A pass of a bitmap filter, which renders a whole texture into a new one.
+#[derive(Debug)]
+pub struct FilterPipeline {
+    pub pipeline: wgpu::RenderPipeline,
+    pub bind_layout: wgpu::BindGroupLayout,
+}
+
+/// The pipelines that composite the texture of a blended object onto the target below it.
+/// There is one for each blend mode and stencil mask, so they are only created when first used.
+#[derive(Debug)]
+pub struct BlendPipelines {
+    pipelines: HashMap<(BlendMode, u32), wgpu::RenderPipeline>,
+    pipeline_layout: wgpu::PipelineLayout,
+    vertex_shader: wgpu::ShaderModule,
+    fragment_shader: wgpu::ShaderModule,
+    msaa_sample_count: u32,
+}
+
 #[derive(Debug)]
 pub struct Pipelines {
     pub color: ShapePipeline,
     pub bitmap: ShapePipeline,
     pub gradient: ShapePipeline,
+    pub blur: FilterPipeline,
+    pub shadow: FilterPipeline,
+    pub color_matrix: FilterPipeline,
+    pub blend: BlendPipelines,
 }
 
 impl ShapePipeline {
@@ -31,6 +55,58 @@ impl ShapePipeline {
     }
 }
 
+impl BlendPipelines {
+    /// Returns the pipeline that draws a premultiplied texture with the given blend mode,
+    /// where the stencil buffer matches `read_mask`.
+    pub fn pipeline_for(
+        &mut self,
+        device: &wgpu::Device,
+        blend_mode: BlendMode,
+        read_mask: u32,
+    ) -> &wgpu::RenderPipeline {
+        let pipeline_layout = &self.pipeline_layout;
+        let vertex_shader = &self.vertex_shader;
+        let fragment_shader = &self.fragment_shader;
+        let msaa_sample_count = self.msaa_sample_count;
+        self.pipelines
+            .entry((blend_mode, read_mask))
+            .or_insert_with(|| {
+                let (color_blend, alpha_blend) = blend_mode_descriptors(blend_mode);
+                device.create_render_pipeline(&create_pipeline_descriptor(
+                    vertex_shader,
+                    fragment_shader,
+                    pipeline_layout,
+                    Some(wgpu::DepthStencilStateDescriptor {
+                        format: wgpu::TextureFormat::Depth24PlusStencil8,
+                        depth_write_enabled: true,
+                        depth_compare: wgpu::CompareFunction::Always,
+                        stencil_front: wgpu::StencilStateFaceDescriptor {
+                            compare: wgpu::CompareFunction::Equal,
+                            fail_op: wgpu::StencilOperation::Keep,
+                            depth_fail_op: wgpu::StencilOperation::Keep,
+                            pass_op: wgpu::StencilOperation::Keep,
+                        },
+                        stencil_back: wgpu::StencilStateFaceDescriptor {
+                            compare: wgpu::CompareFunction::Equal,
+                            fail_op: wgpu::StencilOperation::Keep,
+                            depth_fail_op: wgpu::StencilOperation::Keep,
+                            pass_op: wgpu::StencilOperation::Keep,
+                        },
+                        stencil_read_mask: read_mask,
+                        stencil_write_mask: 0,
+                    }),
+                    &[wgpu::ColorStateDescriptor {
+                        format: wgpu::TextureFormat::Bgra8Unorm,
+                        color_blend,
+                        alpha_blend,
+                        write_mask: wgpu::ColorWrite::ALL,
+                    }],
+                    msaa_sample_count,
+                ))
+            })
+    }
+}
+
 impl Pipelines {
     pub fn new(device: &wgpu::Device, msaa_sample_count: u32) -> Result<Self, Error> {
         let color_vs_bytes = include_bytes!("../shaders/color.vert.spv");
@@ -53,20 +129,132 @@ impl Pipelines {
         let bitmap_fs = device.create_shader_module(&wgpu::read_spirv(std::io::Cursor::new(
             &bitmap_fs_bytes[..],
         ))?);
+        let blur_fs_bytes = include_bytes!("../shaders/blur.frag.spv");
+        let blur_fs = device
+            .create_shader_module(&wgpu::read_spirv(std::io::Cursor::new(&blur_fs_bytes[..]))?);
+        let shadow_fs_bytes = include_bytes!("../shaders/shadow.frag.spv");
+        let shadow_fs = device.create_shader_module(&wgpu::read_spirv(std::io::Cursor::new(
+            &shadow_fs_bytes[..],
+        ))?);
+        let color_matrix_fs_bytes = include_bytes!("../shaders/color_matrix.frag.spv");
+        let color_matrix_fs = device.create_shader_module(&wgpu::read_spirv(
+            std::io::Cursor::new(&color_matrix_fs_bytes[..]),
+        )?);
+
+        let bitmap = create_bitmap_pipeline(&device, &texture_vs, &bitmap_fs, msaa_sample_count);
 
         Ok(Self {
             color: create_color_pipelines(&device, &color_vs, &color_fs, msaa_sample_count),
-            bitmap: create_bitmap_pipeline(&device, &texture_vs, &bitmap_fs, msaa_sample_count),
             gradient: create_gradient_pipeline(
                 &device,
                 &texture_vs,
                 &gradient_fs,
                 msaa_sample_count,
             ),
+            blur: create_filter_pipeline(&device, &texture_vs, &blur_fs),
+            shadow: create_filter_pipeline(&device, &texture_vs, &shadow_fs),
+            color_matrix: create_filter_pipeline(&device, &texture_vs, &color_matrix_fs),
+            blend: BlendPipelines {
+                pipelines: HashMap::new(),
+                pipeline_layout: device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+                    bind_group_layouts: &[&bitmap.bind_layout],
+                }),
+                vertex_shader: texture_vs,
+                fragment_shader: bitmap_fs,
+                msaa_sample_count,
+            },
+            bitmap,
         })
     }
 }
 
+/// Returns the color and alpha blend states that composite a premultiplied source
+/// with the given blend mode.
+///
+/// Difference, Overlay and HardLight can't be expressed with blend states, and are
+/// composited normally.
+fn blend_mode_descriptors(blend_mode: BlendMode) -> (wgpu::BlendDescriptor, wgpu::BlendDescriptor) {
+    use wgpu::{BlendDescriptor, BlendFactor, BlendOperation};
+    let blend = |src_factor, dst_factor, operation| BlendDescriptor {
+        src_factor,
+        dst_factor,
+        operation,
+    };
+    let over = blend(
+        BlendFactor::One,
+        BlendFactor::OneMinusSrcAlpha,
+        BlendOperation::Add,
+    );
+    match blend_mode {
+        BlendMode::Normal
+        | BlendMode::Layer
+        | BlendMode::Difference
+        | BlendMode::Overlay
+        | BlendMode::HardLight => (over.clone(), over),
+        BlendMode::Multiply => (
+            blend(
+                BlendFactor::DstColor,
+                BlendFactor::OneMinusSrcAlpha,
+                BlendOperation::Add,
+            ),
+            over,
+        ),
+        BlendMode::Screen => (
+            blend(
+                BlendFactor::One,
+                BlendFactor::OneMinusSrcColor,
+                BlendOperation::Add,
+            ),
+            over,
+        ),
+        BlendMode::Lighten => (
+            blend(BlendFactor::One, BlendFactor::One, BlendOperation::Max),
+            over,
+        ),
+        BlendMode::Darken => (
+            blend(BlendFactor::One, BlendFactor::One, BlendOperation::Min),
+            over,
+        ),
+        BlendMode::Add => (
+            blend(BlendFactor::One, BlendFactor::One, BlendOperation::Add),
+            over,
+        ),
+        BlendMode::Subtract => (
+            blend(
+                BlendFactor::One,
+                BlendFactor::One,
+                BlendOperation::ReverseSubtract,
+            ),
+            over,
+        ),
+        // The source is drawn as its alpha in every channel, see `pop_blend_mode`.
+        BlendMode::Invert => (
+            blend(
+                BlendFactor::OneMinusDstColor,
+                BlendFactor::OneMinusSrcAlpha,
+                BlendOperation::Add,
+            ),
+            over,
+        ),
+        BlendMode::Alpha => {
+            let alpha = blend(
+                BlendFactor::Zero,
+                BlendFactor::SrcAlpha,
+                BlendOperation::Add,
+            );
+            (alpha.clone(), alpha)
+        }
+        BlendMode::Erase => {
+            let erase = blend(
+                BlendFactor::Zero,
+                BlendFactor::OneMinusSrcAlpha,
+                BlendOperation::Add,
+            );
+            (erase.clone(), erase)
+        }
+    }
+}
+
 fn create_pipeline_descriptor<'a>(
     vertex_shader: &'a wgpu::ShaderModule,
     fragment_shader: &'a wgpu::ShaderModule,
@@ -216,7 +404,7 @@ fn create_color_pipelines(
                     operation: wgpu::BlendOperation::Add,
                 },
                 alpha_blend: wgpu::BlendDescriptor {
-                    src_factor: wgpu::BlendFactor::SrcAlpha,
+                    src_factor: wgpu::BlendFactor::One,
                     dst_factor: wgpu::BlendFactor::OneMinusSrcAlpha,
                     operation: wgpu::BlendOperation::Add,
                 },
@@ -356,7 +544,7 @@ fn create_bitmap_pipeline(
                     operation: wgpu::BlendOperation::Add,
                 },
                 alpha_blend: wgpu::BlendDescriptor {
-                    src_factor: wgpu::BlendFactor::SrcAlpha,
+                    src_factor: wgpu::BlendFactor::One,
                     dst_factor: wgpu::BlendFactor::OneMinusSrcAlpha,
                     operation: wgpu::BlendOperation::Add,
                 },
@@ -490,7 +678,7 @@ fn create_gradient_pipeline(
                     operation: wgpu::BlendOperation::Add,
                 },
                 alpha_blend: wgpu::BlendDescriptor {
-                    src_factor: wgpu::BlendFactor::SrcAlpha,
+                    src_factor: wgpu::BlendFactor::One,
                     dst_factor: wgpu::BlendFactor::OneMinusSrcAlpha,
                     operation: wgpu::BlendOperation::Add,
                 },
@@ -506,3 +694,79 @@ fn create_gradient_pipeline(
         bind_layout,
     }
 }
+
+/// Creates the pipeline of a filter pass. Passes render into a new single-sampled texture,
+/// without masks or blending.
+fn create_filter_pipeline(
+    device: &wgpu::Device,
+    vertex_shader: &wgpu::ShaderModule,
+    fragment_shader: &wgpu::ShaderModule,
+) -> FilterPipeline {
+    let label = create_debug_label!("Filter bind group");
+    let bind_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+        bindings: &[
+            wgpu::BindGroupLayoutEntry {
+                binding: 0,
+                visibility: wgpu::ShaderStage::VERTEX,
+                ty: wgpu::BindingType::UniformBuffer { dynamic: false },
+            },
+            wgpu::BindGroupLayoutEntry {
+                binding: 1,
+                visibility: wgpu::ShaderStage::VERTEX,
+                ty: wgpu::BindingType::UniformBuffer { dynamic: false },
+            },
+            wgpu::BindGroupLayoutEntry {
+                binding: 2,
+                visibility: wgpu::ShaderStage::FRAGMENT,
+                ty: wgpu::BindingType::UniformBuffer { dynamic: false },
+            },
+            wgpu::BindGroupLayoutEntry {
+                binding: 3,
+                visibility: wgpu::ShaderStage::FRAGMENT,
+                ty: wgpu::BindingType::SampledTexture {
+                    multisampled: false,
+                    component_type: wgpu::TextureComponentType::Float,
+                    dimension: wgpu::TextureViewDimension::D2,
+                },
+            },
+            wgpu::BindGroupLayoutEntry {
+                binding: 4,
+                visibility: wgpu::ShaderStage::FRAGMENT,
+                ty: wgpu::BindingType::Sampler { comparison: false },
+            },
+            wgpu::BindGroupLayoutEntry {
+                binding: 5,
+                visibility: wgpu::ShaderStage::FRAGMENT,
+                ty: wgpu::BindingType::SampledTexture {
+                    multisampled: false,
+                    component_type: wgpu::TextureComponentType::Float,
+                    dimension: wgpu::TextureViewDimension::D2,
+                },
+            },
+        ],
+        label: label.as_deref(),
+    });
+
+    let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+        bind_group_layouts: &[&bind_layout],
+    });
+
+    let pipeline = device.create_render_pipeline(&create_pipeline_descriptor(
+        vertex_shader,
+        fragment_shader,
+        &pipeline_layout,
+        None,
+        &[wgpu::ColorStateDescriptor {
+            format: wgpu::TextureFormat::Bgra8Unorm,
+            color_blend: wgpu::BlendDescriptor::REPLACE,
+            alpha_blend: wgpu::BlendDescriptor::REPLACE,
+            write_mask: wgpu::ColorWrite::ALL,
+        }],
+        1,
+    ));
+
+    FilterPipeline {
+        pipeline,
+        bind_layout,
+    }
+}
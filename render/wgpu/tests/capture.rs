@@ -0,0 +1,195 @@
+//! Renders SWFs offscreen with a headless device and compares the captured frames
+//! against reference images.
+
+use futures::executor::block_on;
+use image::RgbaImage;
+use ruffle_core::backend::audio::NullAudioBackend;
+use ruffle_core::backend::font::NullFontBackend;
+use ruffle_core::backend::input::NullInputBackend;
+use ruffle_core::backend::navigator::NullNavigatorBackend;
+use ruffle_core::backend::render::BitmapFormat;
+use ruffle_core::backend::storage::MemoryStorageBackend;
+use ruffle_core::backend::video::NullVideoBackend;
+use ruffle_core::tag_utils::SwfMovie;
+use ruffle_core::Player;
+use ruffle_render_wgpu::target::TextureTarget;
+use ruffle_render_wgpu::WgpuRenderBackend;
+use std::path::Path;
+use std::rc::Rc;
+use std::sync::{Arc, Mutex};
+
+type Error = Box<dyn std::error::Error>;
+
+// This macro generates test cases for a given list of SWFs using `test_swf_image`.
+macro_rules! image_tests {
+    ($($(#[$attr:meta])* ($name:ident, $path:expr, $num_frames:literal, $tolerance:literal),)*) => {
+        $(
+        #[test]
+        $(#[$attr])*
+        fn $name() -> Result<(), Error> {
+            test_swf_image(
+                concat!("tests/swfs/", $path, "/test.swf"),
+                $num_frames,
+                concat!("tests/swfs/", $path, "/expected.png"),
+                $tolerance,
+            )
+        }
+        )*
+    };
+}
+
+// List of SWFs to test.
+// Format: (test_name, test_folder, number_of_frames_to_run, tolerance)
+// The test folder is a relative to render/wgpu/tests/swfs
+// Inside the folder is expected to be "test.swf" and "expected.png" with the correct rendering.
+// The frame is captured at the size of "expected.png", and every color channel of every pixel
+// may differ from it by at most the tolerance.
+image_tests! {
+    (capture, "capture", 1, 2),
+    (bitmap_cache, "bitmap_cache", 1, 2),
+    (as3_shape_enter_frame, "as3_shape_enter_frame", 2, 2),
+}
+
+/// Loads an SWF and runs it through the Ruffle core for a number of frames.
+/// Tests that the captured frame matches the given reference image.
+///
+/// The frame is captured twice, so that anything cached by the first render is checked
+/// when it's reused. A failing capture is saved as "actual.png" next to the reference image.
+fn test_swf_image(
+    swf_path: &str,
+    num_frames: u32,
+    expected_image_path: &str,
+    tolerance: u8,
+) -> Result<(), Error> {
+    let player = match create_player(swf_path)? {
+        Some(player) => player,
+        None => return Ok(()),
+    };
+    let mut player = player.lock().unwrap();
+    for _ in 0..num_frames {
+        player.run_frame();
+    }
+
+    let expected = image::open(expected_image_path)?.to_rgba();
+    for _ in 0..2 {
+        let actual = capture_image(&mut player, expected.width(), expected.height())?;
+        if let Err(message) = compare_images(&actual, &expected, tolerance) {
+            let actual_image_path = Path::new(expected_image_path).with_file_name("actual.png");
+            actual.save(&actual_image_path)?;
+            panic!(
+                "{}: {} (saved to {})",
+                swf_path,
+                message,
+                actual_image_path.display()
+            );
+        }
+    }
+    Ok(())
+}
+
+/// Checks that every color channel of every pixel of `actual` is within `tolerance` of
+/// `expected`. Returns a description of the differences if it isn't.
+fn compare_images(actual: &RgbaImage, expected: &RgbaImage, tolerance: u8) -> Result<(), String> {
+    if actual.dimensions() != expected.dimensions() {
+        return Err(format!(
+            "Captured image is {:?}, expected {:?}",
+            actual.dimensions(),
+            expected.dimensions()
+        ));
+    }
+
+    let mut num_different = 0;
+    let mut first_difference = None;
+    for ((x, y, actual), expected) in actual.enumerate_pixels().zip(expected.pixels()) {
+        let is_different = actual
+            .0
+            .iter()
+            .zip(expected.0.iter())
+            .any(|(a, e)| (i16::from(*a) - i16::from(*e)).abs() > i16::from(tolerance));
+        if is_different {
+            num_different += 1;
+            first_difference.get_or_insert((x, y, actual.0, expected.0));
+        }
+    }
+
+    match first_difference {
+        Some((x, y, actual, expected)) => Err(format!(
+            "{} pixels differ by more than {}; the first is at ({}, {}): {:?} != {:?}",
+            num_different, tolerance, x, y, actual, expected
+        )),
+        None => Ok(()),
+    }
+}
+
+/// Tests that images compare equal only when every channel is within the tolerance.
+#[test]
+fn image_tolerance() -> Result<(), Error> {
+    let expected = image::open("tests/swfs/capture/expected.png")?.to_rgba();
+    assert_eq!(compare_images(&expected, &expected, 0), Ok(()));
+
+    let mut actual = expected.clone();
+    actual.get_pixel_mut(30, 20).0[1] += 2;
+    assert_eq!(compare_images(&actual, &expected, 2), Ok(()));
+    assert!(compare_images(&actual, &expected, 1).is_err());
+
+    let cropped = image::imageops::crop(&mut actual, 0, 0, 10, 10).to_image();
+    assert!(compare_images(&cropped, &expected, 255).is_err());
+    Ok(())
+}
+
+/// Captures the current frame of the player as an image of the given size.
+fn capture_image(player: &mut Player, width: u32, height: u32) -> Result<RgbaImage, Error> {
+    let bitmap = player
+        .capture_frame(width, height)
+        .ok_or("Unable to capture the frame")?;
+    let data = match bitmap.data {
+        BitmapFormat::Rgba(data) => data,
+        BitmapFormat::Rgb(_) => return Err("Captured frames are RGBA".into()),
+    };
+    RgbaImage::from_raw(bitmap.width, bitmap.height, data)
+        .ok_or_else(|| "Captured frame has the wrong size".into())
+}
+
+/// Creates a player for the given movie with a 100x100 headless renderer.
+/// Returns `None` if there is no graphics device to render with, unless the
+/// `RUFFLE_REQUIRE_GPU` environment variable is set, in which case it's an error.
+fn create_player(path: &str) -> Result<Option<Arc<Mutex<Player>>>, Error> {
+    let adapter = match block_on(wgpu::Adapter::request(
+        &wgpu::RequestAdapterOptions {
+            power_preference: wgpu::PowerPreference::Default,
+            compatible_surface: None,
+        },
+        wgpu::BackendBit::PRIMARY,
+    )) {
+        Some(adapter) => adapter,
+        None if std::env::var_os("RUFFLE_REQUIRE_GPU").is_some() => {
+            return Err("No compatible graphics device was found".into());
+        }
+        None => {
+            eprintln!("Skipping {}: no compatible graphics device was found", path);
+            return Ok(None);
+        }
+    };
+    let (device, queue) = block_on(adapter.request_device(&wgpu::DeviceDescriptor {
+        extensions: wgpu::Extensions {
+            anisotropic_filtering: false,
+        },
+        limits: wgpu::Limits::default(),
+    }));
+    let device = Rc::new(device);
+
+    let movie = SwfMovie::from_path(path)?;
+    let target = TextureTarget::new(&device, (100, 100));
+    let player = Player::new(
+        Box::new(WgpuRenderBackend::new(device, Rc::new(queue), target)?),
+        Box::new(NullAudioBackend::new()),
+        Box::new(NullNavigatorBackend::new()),
+        Box::new(NullInputBackend::new()),
+        movie,
+        Box::new(MemoryStorageBackend::default()),
+        Box::new(NullFontBackend::new()),
+        Box::new(NullVideoBackend::new()),
+    )?;
+    player.lock().unwrap().set_viewport_dimensions(100, 100);
+    Ok(Some(player))
+}
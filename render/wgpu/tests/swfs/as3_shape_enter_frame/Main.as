@@ -0,0 +1,24 @@
+package {
+	import flash.display.MovieClip;
+	import flash.display.Shape;
+
+	public class Main extends MovieClip {
+		var shape;
+
+		public function Main() {
+			var shape = new Shape();
+			shape.name = "shape";
+			this.shape = shape;
+			shape.graphics.beginFill(0xFF0000);
+			shape.graphics.drawRect(0, 0, 50, 50);
+			shape.graphics.endFill();
+			addChild(shape);
+
+			addEventListener("enterFrame", onEnterFrame);
+		}
+
+		public function onEnterFrame(e) {
+			shape.x += 25;
+		}
+	}
+}
@@ -0,0 +1,3 @@
+// SWF version 10, 100x100 stage.
+// This movie has no scripts. On a blue background, it places a red 50x50 square at
+// (25, 25), with cacheAsBitmap set by its PlaceObject3 tag.
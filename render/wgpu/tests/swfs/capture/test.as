@@ -0,0 +1,3 @@
+// SWF version 8, 100x100 stage.
+// This movie has no scripts. On a blue background, it places a red 50x50 square at
+// the top left corner of the stage.
@@ -280,13 +280,12 @@ impl<W: Write> Writer<W> {
                         0
                     };
                 }
-                let len = 7
-                    + action_buf.len()
-                    + if let Some((CatchVar::Var(ref name), _)) = try_block.catch {
-                        name.len() + 1
-                    } else {
-                        1
-                    };
+                // The try, catch and finally blocks aren't included in the action length.
+                let len = 7 + if let Some((CatchVar::Var(ref name), _)) = try_block.catch {
+                    name.len() + 1
+                } else {
+                    1
+                };
                 self.write_action_header(OpCode::Try, len)?;
                 self.write_u8(
                     if let Some((CatchVar::Register(_), _)) = try_block.catch {
@@ -302,7 +301,8 @@ impl<W: Write> Writer<W> {
                 match try_block.catch {
                     Some((CatchVar::Var(ref name), _)) => self.write_c_string(name)?,
                     Some((CatchVar::Register(i), _)) => self.write_u8(i)?,
-                    _ => (),
+                    // An empty catch variable name.
+                    None => self.write_u8(0)?,
                 }
                 self.inner.write_all(&action_buf)?;
             }
@@ -322,7 +322,9 @@ impl<W: Write> Writer<W> {
                 self.write_u8(num_actions_to_skip)?;
             }
             Action::With { ref actions } => {
-                self.write_action_header(OpCode::With, actions.len())?;
+                // The body isn't included in the action length.
+                self.write_action_header(OpCode::With, 2)?;
+                self.write_u16(actions.len() as u16)?;
                 self.inner.write_all(&actions)?;
             }
             Action::Unknown { opcode, ref data } => {
@@ -1,7 +1,8 @@
 use crate::avm2::types::*;
 use crate::error::{Error, Result};
 use crate::read::SwfRead;
-use std::io::Read;
+use byteorder::{LittleEndian, ReadBytesExt};
+use std::io::{self, Read};
 
 pub struct Reader<R: Read> {
     inner: R,
@@ -11,6 +12,12 @@ impl<R: Read> SwfRead<R> for Reader<R> {
     fn get_inner(&mut self) -> &mut R {
         &mut self.inner
     }
+
+    /// Unlike the doubles of AVM1 actions, those of ABC files are plain
+    /// little-endian values.
+    fn read_f64(&mut self) -> io::Result<f64> {
+        self.inner.read_f64::<LittleEndian>()
+    }
 }
 
 impl<R: Read> Reader<R> {
@@ -18,6 +25,18 @@ impl<R: Read> Reader<R> {
         Reader { inner }
     }
 
+    /// Returns a reference to the underlying `Reader`.
+    pub fn get_ref(&self) -> &R {
+        &self.inner
+    }
+
+    /// Returns a mutable reference to the underlying `Reader`.
+    ///
+    /// This can be used to seek to the target of a branch when reading method bodies.
+    pub fn get_mut(&mut self) -> &mut R {
+        &mut self.inner
+    }
+
     pub fn read(&mut self) -> Result<AbcFile> {
         let minor_version = self.read_u16()?;
         let major_version = self.read_u16()?;
@@ -72,13 +91,12 @@ impl<R: Read> Reader<R> {
         })
     }
 
+    /// Reads a variable-length integer, which is at most 5 bytes long.
     fn read_u30(&mut self) -> Result<u32> {
         let mut n = 0;
-        let mut i = 0;
-        loop {
+        for i in 0..5 {
             let byte: u32 = self.read_u8()?.into();
-            n |= (byte & 0b0111_1111) << i;
-            i += 7;
+            n |= (byte & 0b0111_1111) << (i * 7);
             if byte & 0b1000_0000 == 0 {
                 break;
             }
@@ -96,16 +114,19 @@ impl<R: Read> Reader<R> {
             | (i32::from(self.read_u8()?) << 8)
             | (i32::from(self.read_u8()?) << 16))
     }
+    /// Reads a variable-length integer, sign-extending it if it is shorter
+    /// than 5 bytes.
     fn read_i32(&mut self) -> Result<i32> {
         let mut n: i32 = 0;
-        let mut i = 0;
-        loop {
+        for i in 0..5 {
             let byte: i32 = self.read_u8()?.into();
-            n |= (byte & 0b0111_1111) << i;
-            i += 7;
+            n |= (byte & 0b0111_1111) << (i * 7);
             if byte & 0b1000_0000 == 0 {
-                n <<= 32 - i;
-                n >>= 32 - i;
+                let unused_bits = 32 - (i + 1) * 7;
+                if unused_bits > 0 {
+                    n <<= unused_bits;
+                    n >>= unused_bits;
+                }
                 break;
             }
         }
@@ -517,8 +538,8 @@ impl<R: Read> Reader<R> {
         })
     }
 
-    #[allow(dead_code)]
-    fn read_op(&mut self) -> Result<Option<Op>> {
+    /// Reads a single instruction from the code of a method body.
+    pub fn read_op(&mut self) -> Result<Option<Op>> {
         use crate::avm2::opcode::OpCode;
         use num_traits::FromPrimitive;
 
@@ -889,4 +910,25 @@ pub mod tests {
             }
         }
     }
+
+    #[test]
+    fn read_numbers() {
+        let read_i32 = |bytes: &[u8]| Reader::new(bytes).read_i32().unwrap();
+        assert_eq!(read_i32(&[0x7F]), -1);
+        assert_eq!(read_i32(&[0x3F]), 63);
+        assert_eq!(read_i32(&[0x80, 0x01]), 128);
+        assert_eq!(read_i32(&[0xFF, 0xFF, 0xFF, 0xFF, 0x0F]), -1);
+        assert_eq!(read_i32(&[0x80, 0x80, 0x80, 0x80, 0x08]), i32::min_value());
+
+        let read_u30 = |bytes: &[u8]| Reader::new(bytes).read_u30().unwrap();
+        assert_eq!(read_u30(&[0xFF, 0xFF, 0xFF, 0xFF, 0x0F]), 0xFFFF_FFFF);
+
+        let mut reader = Reader::new(&[0, 0, 0, 0, 0, 0, 0xF8, 0x3F][..]);
+        assert_eq!(reader.read_f64().unwrap(), 1.5);
+
+        // A malformed integer ends after 5 bytes.
+        let mut reader = Reader::new(&[0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0x01][..]);
+        reader.read_u30().unwrap();
+        assert_eq!(reader.read_u8().unwrap(), 0x01);
+    }
 }
@@ -14,9 +14,9 @@ extern crate flate2;
 extern crate libflate;
 #[macro_use]
 extern crate num_derive;
-extern crate num_traits;
 #[cfg(feature = "lzma")]
-extern crate xz2;
+extern crate lzma_rs;
+extern crate num_traits;
 
 pub mod avm1;
 pub mod avm2;
@@ -30,21 +30,10 @@ pub fn read_swf<R: Read>(input: R) -> Result<Swf> {
     let mut reader = swf_stream.reader;
 
     // Decompress all of SWF into memory at once.
-    let mut data = if header.compression == Compression::Lzma {
-        // TODO: The LZMA decoder is still funky.
-        // It always errors, and doesn't return all the data if you use read_to_end,
-        // but read_exact at least returns the data... why?
-        // Does the decoder need to be flushed somehow?
-        let mut data = vec![0u8; swf_stream.uncompressed_length];
-        let _ = reader.get_mut().read_exact(&mut data);
-        data
-    } else {
-        let mut data = Vec::with_capacity(swf_stream.uncompressed_length);
-        if let Err(e) = reader.get_mut().read_to_end(&mut data) {
-            log::error!("Error decompressing SWF, may be corrupt: {}", e);
-        }
-        data
-    };
+    let mut data = Vec::with_capacity(swf_stream.uncompressed_length);
+    if let Err(e) = reader.get_mut().read_to_end(&mut data) {
+        log::error!("Error decompressing SWF, may be corrupt: {}", e);
+    }
     let version = header.version;
 
     // Some SWF streams may not be compressed correctly,
@@ -152,13 +141,9 @@ fn make_lzma_reader<'a, R: Read + 'a>(
     mut input: R,
     uncompressed_length: u32,
 ) -> Result<Box<dyn Read + 'a>> {
-    use byteorder::WriteBytesExt;
-    use std::io::{Cursor, Write};
-    use xz2::{
-        read::XzDecoder,
-        stream::{Action, Stream},
-    };
-    // Flash uses a mangled LZMA header, so we have to massage it into the normal format.
+    use lzma_rs::decompress::{Options, UnpackedSize};
+    use std::io::{BufReader, Cursor};
+    // Flash uses a mangled LZMA header, without the uncompressed length of the normal format.
     // https://helpx.adobe.com/flash-player/kb/exception-thrown-you-decompress-lzma-compressed.html
     // LZMA SWF header:
     // Bytes 0..3: ZWS header
@@ -171,26 +156,22 @@ fn make_lzma_reader<'a, R: Read + 'a>(
     // Bytes 0..5: LZMA properties
     // Bytes 5..13: Uncompressed length
 
-    // Read compressed length
-    let _ = input.read_u32::<LittleEndian>()?;
-
-    // Read LZMA propreties to decoder
-    let mut lzma_properties = [0u8; 5];
-    input.read_exact(&mut lzma_properties)?;
+    // The compressed length doesn't include the LZMA properties.
+    let compressed_length = input.read_u32::<LittleEndian>()?;
+    let mut input = BufReader::new(input.take(u64::from(compressed_length) + 5));
 
-    // Rearrange above into LZMA format
-    let mut lzma_header = Cursor::new(Vec::with_capacity(13));
-    lzma_header.write_all(&lzma_properties)?;
-    lzma_header.write_u64::<LittleEndian>(uncompressed_length.into())?;
-
-    // Create LZMA decoder stream and write header
-    let mut lzma_stream = Stream::new_lzma_decoder(u64::max_value()).unwrap();
-    lzma_stream
-        .process(&lzma_header.into_inner(), &mut [0u8; 1], Action::Run)
-        .unwrap();
+    // The decoder is given the uncompressed length from the SWF header,
+    // and fails if the stream doesn't decompress to exactly that length.
+    let options = Options {
+        unpacked_size: UnpackedSize::UseProvided(Some(uncompressed_length.into())),
+        memlimit: None,
+        allow_incomplete: false,
+    };
+    let mut data = Vec::new();
+    lzma_rs::lzma_decompress_with_options(&mut input, &mut data, &options)
+        .map_err(|e| Error::invalid_data(format!("Corrupt LZMA compressed SWF: {}", e)))?;
 
-    // Decoder is ready
-    Ok(Box::new(XzDecoder::new_stream(input, lzma_stream)))
+    Ok(Box::new(Cursor::new(data)))
 }
 
 #[cfg(not(feature = "lzma"))]
@@ -452,7 +433,9 @@ impl<R: Read> Reader<R> {
             Some(TagCode::DefineText2) => {
                 Tag::DefineText(Box::new(tag_reader.read_define_text(2)?))
             }
-            Some(TagCode::DefineVideoStream) => tag_reader.read_define_video_stream()?,
+            Some(TagCode::DefineVideoStream) => {
+                Tag::DefineVideoStream(tag_reader.read_define_video_stream()?)
+            }
             Some(TagCode::EnableTelemetry) => {
                 tag_reader.read_u16()?; // Reserved
                 let password_hash = if length > 2 {
@@ -543,17 +526,8 @@ impl<R: Read> Reader<R> {
                 splitter_rect: tag_reader.read_rectangle()?,
             },
 
-            Some(TagCode::DoAbc) => {
-                let flags = tag_reader.read_u32()?;
-                let name = tag_reader.read_c_string()?;
-                let mut abc_data = Vec::with_capacity(length - 4 - name.len());
-                tag_reader.input.read_to_end(&mut abc_data)?;
-                Tag::DoAbc(DoAbc {
-                    name,
-                    is_lazy_initialize: flags & 1 != 0,
-                    data: abc_data,
-                })
-            }
+            Some(TagCode::DoAbc) => Tag::DoAbc(tag_reader.read_do_abc(length)?),
+            Some(TagCode::DoAbcDefine) => Tag::DoAbc(tag_reader.read_do_abc_define(length)?),
 
             Some(TagCode::DoAction) => {
                 let mut action_data = Vec::with_capacity(length);
@@ -584,17 +558,7 @@ impl<R: Read> Reader<R> {
                 tab_index: tag_reader.read_u16()?,
             },
 
-            Some(TagCode::SymbolClass) => {
-                let num_symbols = tag_reader.read_u16()?;
-                let mut symbols = Vec::with_capacity(num_symbols as usize);
-                for _ in 0..num_symbols {
-                    symbols.push(SymbolClassLink {
-                        id: tag_reader.read_u16()?,
-                        class_name: tag_reader.read_c_string()?,
-                    });
-                }
-                Tag::SymbolClass(symbols)
-            }
+            Some(TagCode::SymbolClass) => Tag::SymbolClass(tag_reader.read_symbol_class()?),
 
             Some(TagCode::ExportAssets) => Tag::ExportAssets(tag_reader.read_export_assets()?),
 
@@ -643,7 +607,7 @@ impl<R: Read> Reader<R> {
 
             Some(TagCode::RemoveObject2) => Tag::RemoveObject(tag_reader.read_remove_object_2()?),
 
-            Some(TagCode::VideoFrame) => tag_reader.read_video_frame()?,
+            Some(TagCode::VideoFrame) => Tag::VideoFrame(tag_reader.read_video_frame()?),
             Some(TagCode::ProductInfo) => Tag::ProductInfo(tag_reader.read_product_info()?),
             _ => {
                 let size = length as usize;
@@ -2043,6 +2007,41 @@ impl<R: Read> Reader<R> {
         }))
     }
 
+    pub fn read_do_abc(&mut self, length: usize) -> Result<DoAbc> {
+        let flags = self.read_u32()?;
+        let name = self.read_c_string()?;
+        let mut abc_data = Vec::with_capacity(length.saturating_sub(4 + name.len()));
+        self.input.read_to_end(&mut abc_data)?;
+        Ok(DoAbc {
+            name,
+            is_lazy_initialize: flags & 1 != 0,
+            data: abc_data,
+        })
+    }
+
+    /// Reads the older form of `DoAbc`, which has no flags or name.
+    pub fn read_do_abc_define(&mut self, length: usize) -> Result<DoAbc> {
+        let mut abc_data = Vec::with_capacity(length);
+        self.input.read_to_end(&mut abc_data)?;
+        Ok(DoAbc {
+            name: String::new(),
+            is_lazy_initialize: false,
+            data: abc_data,
+        })
+    }
+
+    pub fn read_symbol_class(&mut self) -> Result<Vec<SymbolClassLink>> {
+        let num_symbols = self.read_u16()?;
+        let mut symbols = Vec::with_capacity(num_symbols as usize);
+        for _ in 0..num_symbols {
+            symbols.push(SymbolClassLink {
+                id: self.read_u16()?,
+                class_name: self.read_c_string()?,
+            });
+        }
+        Ok(symbols)
+    }
+
     pub fn read_file_attributes(&mut self) -> Result<FileAttributes> {
         let flags = self.read_u32()?;
         Ok(FileAttributes {
@@ -2723,7 +2722,7 @@ impl<R: Read> Reader<R> {
         })
     }
 
-    fn read_define_video_stream(&mut self) -> Result<Tag> {
+    pub fn read_define_video_stream(&mut self) -> Result<DefineVideoStream> {
         let id = self.read_character_id()?;
         let num_frames = self.read_u16()?;
         let width = self.read_u16()?;
@@ -2737,14 +2736,14 @@ impl<R: Read> Reader<R> {
             5 => VideoCodec::VP6WithAlpha,
             _ => return Err(Error::invalid_data("Invalid video codec.")),
         };
-        Ok(Tag::DefineVideoStream(DefineVideoStream {
+        Ok(DefineVideoStream {
             id,
             num_frames,
             width,
             height,
             is_smoothed: flags & 0b1 != 0,
             codec,
-            deblocking: match flags & 0b100_0 {
+            deblocking: match flags & 0b111_0 {
                 0b000_0 => VideoDeblocking::UseVideoPacketValue,
                 0b001_0 => VideoDeblocking::None,
                 0b010_0 => VideoDeblocking::Level1,
@@ -2753,19 +2752,19 @@ impl<R: Read> Reader<R> {
                 0b101_0 => VideoDeblocking::Level4,
                 _ => return Err(Error::invalid_data("Invalid video deblocking value.")),
             },
-        }))
+        })
     }
 
-    fn read_video_frame(&mut self) -> Result<Tag> {
+    pub fn read_video_frame(&mut self) -> Result<VideoFrame> {
         let stream_id = self.read_character_id()?;
         let frame_num = self.read_u16()?;
         let mut data = vec![];
         self.input.read_to_end(&mut data)?;
-        Ok(Tag::VideoFrame(VideoFrame {
+        Ok(VideoFrame {
             stream_id,
             frame_num,
             data,
-        }))
+        })
     }
 
     fn read_define_bits_jpeg_3(&mut self, version: u8) -> Result<Tag> {
@@ -2941,6 +2940,28 @@ pub mod tests {
         }
     }
 
+    #[test]
+    #[cfg(feature = "lzma")]
+    fn read_lzma_swf() {
+        // The LZMA and zlib test movies have the same contents.
+        let lzma = read_from_file("tests/swfs/lzma.swf");
+        let zlib = read_from_file("tests/swfs/zlib.swf");
+        assert_eq!(lzma.header.num_frames, zlib.header.num_frames);
+        assert_eq!(lzma.tags.len(), zlib.tags.len());
+
+        // The movie can be written out and read back in with LZMA compression.
+        let mut data = Vec::new();
+        crate::write::write_swf(&lzma, &mut data).unwrap();
+        let rewritten = read_swf(&data[..]).unwrap();
+        assert_eq!(rewritten.header.compression, Compression::Lzma);
+        assert_eq!(rewritten.tags.len(), lzma.tags.len());
+
+        // A truncated stream doesn't decompress to the length in the header.
+        let data = std::fs::read("tests/swfs/lzma.swf").unwrap();
+        let result = read_swf_header(&data[..data.len() / 2]);
+        assert!(matches!(result, Err(Error::InvalidData(_))));
+    }
+
     #[test]
     fn read_invalid_swf() {
         let junk = [0u8; 128];
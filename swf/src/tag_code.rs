@@ -68,6 +68,7 @@ pub enum TagCode {
 
     PlaceObject3 = 70,
     ImportAssets2 = 71,
+    DoAbcDefine = 72,
 
     DefineFontAlignZones = 73,
     CsmTextSettings = 74,
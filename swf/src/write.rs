@@ -106,18 +106,14 @@ fn write_zlib_swf<W: Write>(_output: W, _swf_body: &[u8]) -> Result<()> {
 
 #[cfg(feature = "lzma")]
 fn write_lzma_swf<W: Write>(mut output: W, swf_body: &[u8]) -> Result<()> {
-    use xz2::{
-        stream::{Action, LzmaOptions, Stream},
-        write::XzEncoder,
-    };
-    let mut stream = Stream::new_lzma_encoder(&LzmaOptions::new_preset(9).unwrap()).unwrap();
-    let mut lzma_header = [0; 13];
-    stream.process(&[], &mut lzma_header, Action::Run).unwrap();
-    // Compressed length. We just write out a dummy value.
-    output.write_u32::<LittleEndian>(0xffffffff)?;
-    output.write_all(&lzma_header[0..5])?; // LZMA property bytes.
-    let mut encoder = XzEncoder::new_stream(&mut output, stream);
-    encoder.write_all(&swf_body)?;
+    let mut lzma_data = Vec::new();
+    lzma_rs::lzma_compress(&mut &swf_body[..], &mut lzma_data)?;
+    // The standard LZMA header is the 5 property bytes followed by the 8 byte
+    // uncompressed length, which the SWF header already has.
+    let (lzma_properties, compressed_data) = (&lzma_data[0..5], &lzma_data[13..]);
+    output.write_u32::<LittleEndian>(compressed_data.len() as u32)?;
+    output.write_all(lzma_properties)?;
+    output.write_all(compressed_data)?;
     Ok(())
 }
 
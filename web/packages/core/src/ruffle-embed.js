@@ -17,7 +17,14 @@ module.exports = class RuffleEmbed extends RufflePlayer {
 
     connectedCallback() {
         super.connectedCallback();
-        this.stream_swf_url(this.attributes.src.value);
+        this.stream_swf_url(this.attributes.src.value, this.flashvars);
+    }
+
+    get flashvars() {
+        if (this.attributes.flashvars) {
+            return this.attributes.flashvars.value;
+        }
+        return undefined;
     }
 
     get src() {
@@ -36,7 +43,7 @@ module.exports = class RuffleEmbed extends RufflePlayer {
         super.attributeChangedCallback(name, oldValue, newValue);
         console.log(name + " " + oldValue + " " + newValue);
         if (this.isConnected && name === "src") {
-            this.stream_swf_url(this.attributes.src.value);
+            this.stream_swf_url(this.attributes.src.value, this.flashvars);
         }
     }
 
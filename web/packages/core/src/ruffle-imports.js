@@ -21,3 +21,138 @@ exports.copy_to_audio_buffer = function copy_to_audio_buffer(
         dst_buffer.set(right_data);
     }
 };
+
+/**
+ * Calls a function of the page on behalf of `ExternalInterface.call`.
+ *
+ * The call and its result are encoded in the Flash Player's XML format:
+ * `<invoke name="..." returntype="xml"><arguments>...</arguments></invoke>`.
+ */
+exports.call_external_interface = function call_external_interface(request) {
+    const invoke = new DOMParser().parseFromString(request, "text/xml")
+        .documentElement;
+    const args = Array.from(
+        invoke.getElementsByTagName("arguments")[0].children,
+        from_external_xml
+    );
+
+    // The name may be a path to a function, such as `console.log`.
+    let owner = null;
+    let func = window;
+    for (const part of invoke.getAttribute("name").split(".")) {
+        owner = func;
+        func = func[part];
+    }
+
+    return to_external_xml(func.apply(owner, args));
+};
+
+/**
+ * Defines a function on the player element that calls a function exposed by
+ * the movie with `ExternalInterface.addCallback`.
+ */
+exports.expose_external_callback = function expose_external_callback(
+    player,
+    name
+) {
+    player[name] = function (...args) {
+        const request =
+            `<invoke name="${escape_xml(name)}" returntype="xml"><arguments>` +
+            args.map(to_external_xml).join("") +
+            "</arguments></invoke>";
+        const response = player.CallFunction(request);
+        return from_external_xml(
+            new DOMParser().parseFromString(response, "text/xml")
+                .documentElement
+        );
+    };
+};
+
+function escape_xml(text) {
+    return String(text)
+        .replace(/&/g, "&amp;")
+        .replace(/</g, "&lt;")
+        .replace(/>/g, "&gt;")
+        .replace(/"/g, "&quot;")
+        .replace(/'/g, "&apos;");
+}
+
+function to_external_xml(value) {
+    if (value === undefined) {
+        return "<undefined/>";
+    } else if (value === null || typeof value === "function") {
+        return "<null/>";
+    } else if (value === true) {
+        return "<true/>";
+    } else if (value === false) {
+        return "<false/>";
+    } else if (typeof value === "number") {
+        return `<number>${value}</number>`;
+    } else if (typeof value === "string") {
+        return `<string>${escape_xml(value)}</string>`;
+    } else if (Array.isArray(value)) {
+        return (
+            "<array>" +
+            value
+                .map(
+                    (item, i) =>
+                        `<property id="${i}">${to_external_xml(item)}</property>`
+                )
+                .join("") +
+            "</array>"
+        );
+    } else {
+        return (
+            "<object>" +
+            Object.keys(value)
+                .map(
+                    (key) =>
+                        `<property id="${escape_xml(key)}">` +
+                        to_external_xml(value[key]) +
+                        "</property>"
+                )
+                .join("") +
+            "</object>"
+        );
+    }
+}
+
+function from_external_xml(element) {
+    switch (element && element.tagName) {
+        case "null":
+            return null;
+        case "true":
+            return true;
+        case "false":
+            return false;
+        case "number":
+            return Number(element.textContent);
+        case "string":
+            return element.textContent;
+        case "array":
+        case "object": {
+            const value = element.tagName === "array" ? [] : {};
+            for (const property of element.children) {
+                value[property.getAttribute("id")] = from_external_xml(
+                    property.firstElementChild
+                );
+            }
+            return value;
+        }
+        default:
+            return undefined;
+    }
+}
+
+/**
+ * Tells the page that a script of the movie ran for too long and was aborted.
+ *
+ * No more scripts of the movie will run, so a `scripttimeout` event is
+ * dispatched on the player element for the page to tell the user.
+ */
+exports.report_script_timeout = function report_script_timeout(player) {
+    console.warn(
+        "A script in this movie ran for too long and has been stopped."
+    );
+    player.dispatchEvent(new CustomEvent("scripttimeout"));
+};
@@ -20,10 +20,11 @@ module.exports = class RuffleObject extends RufflePlayer {
         this.params = RuffleObject.params_of(this);
 
         //Kick off the SWF download.
+        let flashvars = RuffleObject.param_of(this.params, "flashvars");
         if (this.attributes.data) {
-            this.stream_swf_url(this.attributes.data.value);
+            this.stream_swf_url(this.attributes.data.value, flashvars);
         } else if (this.params.movie) {
-            this.stream_swf_url(this.params.movie);
+            this.stream_swf_url(this.params.movie, flashvars);
         }
     }
 
@@ -86,6 +87,18 @@ module.exports = class RuffleObject extends RufflePlayer {
         return params;
     }
 
+    /*
+     * Finds a param by name, ignoring case as Flash Player does.
+     */
+    static param_of(params, name) {
+        for (let key of Object.keys(params)) {
+            if (key.toLowerCase() === name) {
+                return params[key];
+            }
+        }
+        return undefined;
+    }
+
     static from_native_object_element(elem) {
         let external_name = register_element("ruffle-object", RuffleObject);
         let ruffle_obj = document.createElement(external_name);
@@ -27,6 +27,13 @@ exports.RufflePlayer = class RufflePlayer extends HTMLElement {
             );
         }
 
+        self.context_menu = self.shadow.getElementById("context_menu");
+        self.container.addEventListener(
+            "contextmenu",
+            self.open_context_menu.bind(self)
+        );
+        window.addEventListener("click", self.hide_context_menu.bind(self));
+
         self.instance = null;
 
         self.Ruffle = load_ruffle();
@@ -116,7 +123,12 @@ exports.RufflePlayer = class RufflePlayer extends HTMLElement {
         return false;
     }
 
-    async stream_swf_url(url) {
+    /*
+     * Loads and plays the movie at `url`.
+     * The URL-encoded `flashvars` are passed to the movie, along with the
+     * variables in the query string of `url`.
+     */
+    async stream_swf_url(url, flashvars) {
         //TODO: Actually stream files...
         try {
             if (this.isConnected && !this.is_unused_fallback_object()) {
@@ -127,7 +139,7 @@ exports.RufflePlayer = class RufflePlayer extends HTMLElement {
 
                 if (response.ok) {
                     let data = await response.arrayBuffer();
-                    await this.play_swf_data(data);
+                    await this.play_swf_data(data, abs_url, flashvars);
                     console.log("Playing " + url);
                 } else {
                     console.error(
@@ -159,7 +171,69 @@ exports.RufflePlayer = class RufflePlayer extends HTMLElement {
         }
     }
 
-    async play_swf_data(data) {
+    /*
+     * Shows the context menu of the movie in place of the browser's menu.
+     * The items are provided by the movie, and choosing one runs it in the movie.
+     */
+    open_context_menu(e) {
+        e.preventDefault();
+        if (!this.instance) {
+            return;
+        }
+
+        const items = this.instance.prepare_context_menu();
+        this.context_menu.textContent = "";
+        items.forEach((item, index) => {
+            if (item.separatorBefore) {
+                const separator = document.createElement("li");
+                separator.className = "menu_separator";
+                this.context_menu.appendChild(separator);
+            }
+
+            const menu_item = document.createElement("li");
+            menu_item.className = "menu_item";
+            menu_item.textContent =
+                (item.checked ? "\u2713 " : "") + item.caption;
+            if (item.enabled) {
+                menu_item.addEventListener("click", () => {
+                    this.context_menu.style.display = "none";
+                    this.instance.run_context_menu_callback(index);
+                });
+            } else {
+                menu_item.classList.add("disabled");
+            }
+            this.context_menu.appendChild(menu_item);
+        });
+
+        if (items.length === 0) {
+            this.instance.clear_custom_menu_items();
+            return;
+        }
+
+        const rect = this.container.getBoundingClientRect();
+        this.context_menu.style.left = e.clientX - rect.left + "px";
+        this.context_menu.style.top = e.clientY - rect.top + "px";
+        this.context_menu.style.display = "block";
+    }
+
+    /*
+     * Hides the context menu when the user clicks elsewhere.
+     */
+    hide_context_menu() {
+        if (this.context_menu.style.display === "block") {
+            this.context_menu.style.display = "none";
+            if (this.instance) {
+                this.instance.clear_custom_menu_items();
+            }
+        }
+    }
+
+    /*
+     * Plays the movie in `data`.
+     * The optional `url` is where the movie came from, and the variables in its
+     * query string are passed to the movie along with the URL-encoded `flashvars`.
+     */
+    async play_swf_data(data, url, flashvars) {
         if (this.isConnected && !this.is_unused_fallback_object()) {
             console.log("Got SWF data");
 
@@ -174,7 +248,13 @@ exports.RufflePlayer = class RufflePlayer extends HTMLElement {
                 throw e;
             });
 
-            this.instance = Ruffle.new(this.container, new Uint8Array(data));
+            this.instance = Ruffle.new(
+                this.container,
+                new Uint8Array(data),
+                this,
+                url,
+                flashvars
+            );
             console.log("New Ruffle instance created.");
 
             if (this.play_button) {
@@ -187,6 +267,18 @@ exports.RufflePlayer = class RufflePlayer extends HTMLElement {
         }
     }
 
+    /*
+     * Calls a function exposed by the movie with `ExternalInterface.addCallback`,
+     * given an `<invoke>` request in the Flash Player's XML format.
+     * Returns the result in the same format.
+     */
+    CallFunction(request) {
+        if (this.instance) {
+            return this.instance.call_exposed_callback(request);
+        }
+        return "<undefined/>";
+    }
+
     /*
      * Copies attributes and children from another element to this player element.
      * Used by the polyfill elements, RuffleObject and RuffleEmbed.
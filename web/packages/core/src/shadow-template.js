@@ -42,11 +42,46 @@ ruffle_shadow_tmpl.innerHTML = `
         #play_button:hover .icon {
             filter: brightness(1.3);
         }
+
+        #context_menu {
+            position: absolute;
+            display: none;
+            margin: 0;
+            padding: 4px 0;
+            list-style: none;
+            background: #fafafa;
+            border: 1px solid #999;
+            box-shadow: 1px 1px 4px rgba(0, 0, 0, 0.3);
+            font: 12px sans-serif;
+            color: #000;
+            white-space: nowrap;
+            z-index: 1;
+        }
+
+        #context_menu .menu_item {
+            padding: 4px 16px;
+            cursor: default;
+        }
+
+        #context_menu .menu_item:hover {
+            background: #ddd;
+        }
+
+        #context_menu .menu_item.disabled {
+            color: #999;
+            background: none;
+        }
+
+        #context_menu .menu_separator {
+            margin: 4px 0;
+            border-top: 1px solid #ccc;
+        }
     </style>
     <style id="dynamic_styles"></style>
 
     <div id="container">
         <div id="play_button"><div class="icon"><svg xmlns="http://www.w3.org/2000/svg" xmlns:xlink="http://www.w3.org/1999/xlink" preserveAspectRatio="xMidYMid" viewBox="0 0 250 250" style="width:100%;height:100%;"><defs><linearGradient id="a" gradientUnits="userSpaceOnUse" x1="125" y1="0" x2="125" y2="250" spreadMethod="pad"><stop offset="0%" stop-color="#FDA138"/><stop offset="100%" stop-color="#FD3A40"/></linearGradient><g id="b"><path fill="url(#a)" d="M250 125q0-52-37-88-36-37-88-37T37 37Q0 73 0 125t37 88q36 37 88 37t88-37q37-36 37-88M87 195V55l100 70-100 70z"/><path fill="#FFF" d="M87 55v140l100-70L87 55z"/></g></defs><use xlink:href="#b"/></svg></div></div>
+        <ul id="context_menu"></ul>
     </div>
 `;
 
@@ -3,13 +3,14 @@ use generational_arena::Arena;
 use ruffle_core::backend::audio::decoders::{AdpcmDecoder, Mp3Decoder};
 use ruffle_core::backend::audio::swf::{self, AudioCompression};
 use ruffle_core::backend::audio::{
-    AudioBackend, AudioStreamHandle, SoundHandle, SoundInstanceHandle,
+    sound_position, AudioBackend, AudioStreamHandle, SoundHandle, SoundInstanceHandle,
+    SoundTransform,
 };
 use ruffle_web_common::JsResult;
 use std::cell::{Cell, RefCell};
 use std::rc::Rc;
 use wasm_bindgen::{closure::Closure, prelude::*, JsCast};
-use web_sys::AudioContext;
+use web_sys::{AudioContext, AudioContextState};
 
 pub struct WebAudioBackend {
     context: AudioContext,
@@ -25,6 +26,8 @@ pub struct WebAudioBackend {
 thread_local! {
     static SOUND_INSTANCES: RefCell<Arena<SoundInstance>> = RefCell::new(Arena::new());
     static NUM_SOUNDS_LOADING: Cell<u32> = Cell::new(0);
+    /// Sound instances that finished playing on their own since the player last checked.
+    static COMPLETED_SOUNDS: RefCell<Vec<SoundInstanceHandle>> = RefCell::new(Vec::new());
 }
 
 #[derive(Clone)]
@@ -86,6 +89,17 @@ struct SoundInstance {
     /// either decoded on the fly with Decoder, or pre-decoded
     /// and played with and AudioBufferSourceNode.
     instance_type: SoundInstanceType,
+
+    /// The gain nodes used to apply a sound transform, in the order
+    /// `[left_to_left, left_to_right, right_to_left, right_to_right]`.
+    /// These are only created once a transform is applied to the sound.
+    channel_gains: Option<[web_sys::GainNode; 4]>,
+
+    /// The `AudioContext` time at which this sound started playing, in seconds.
+    start_time: f64,
+
+    /// The settings and duration in milliseconds of this sound, used to calculate its position.
+    position_info: Option<(swf::SoundInfo, u32)>,
 }
 
 #[allow(dead_code)]
@@ -128,12 +142,18 @@ impl WebAudioBackend {
         handle: SoundHandle,
         settings: Option<&swf::SoundInfo>,
     ) -> Result<SoundInstanceHandle, Error> {
-        let sound = self.sounds.get(handle).unwrap();
+        let start_time = self.context.current_time();
+        let position_info = settings.map(|settings| {
+            let duration = self.get_sound_duration(handle).unwrap_or(0);
+            (settings.clone(), duration)
+        });
+        let sound = self.sounds.get(handle).ok_or("Unknown sound handle")?;
         let handle = match &sound.source {
             SoundSource::AudioBuffer(audio_buffer) => {
                 let audio_buffer = audio_buffer.borrow();
                 let node = self.context.create_buffer_source().unwrap();
                 node.set_buffer(Some(&*audio_buffer));
+                let buffer_source_node = node.clone();
 
                 let sound_sample_rate = f64::from(sound.format.sample_rate);
                 let node: web_sys::AudioNode = match settings {
@@ -201,10 +221,27 @@ impl WebAudioBackend {
                     handle: Some(handle),
                     format: sound.format.clone(),
                     instance_type: SoundInstanceType::AudioBuffer(node),
+                    channel_gains: None,
+                    start_time,
+                    position_info,
                 };
                 SOUND_INSTANCES.with(|instances| {
                     let mut instances = instances.borrow_mut();
-                    instances.insert(instance)
+                    let instance_handle = instances.insert(instance);
+                    // Remove the instance once it finishes playing, and report it as complete.
+                    // A stopped sound also fires `onended`, but it was already removed.
+                    let closure = Closure::wrap(Box::new(move || {
+                        SOUND_INSTANCES.with(|instances| {
+                            if instances.borrow_mut().remove(instance_handle).is_some() {
+                                COMPLETED_SOUNDS
+                                    .with(|completed| completed.borrow_mut().push(instance_handle));
+                            }
+                        })
+                    }) as Box<dyn FnMut()>);
+                    buffer_source_node.set_onended(Some(closure.as_ref().unchecked_ref()));
+                    // TODO: This will leak memory per playing sound. Remember and properly drop the closure.
+                    closure.forget();
+                    instance_handle
                 })
             }
             SoundSource::Decoder(audio_data) => {
@@ -239,6 +276,9 @@ impl WebAudioBackend {
                     handle: Some(handle),
                     format: sound.format.clone(),
                     instance_type: SoundInstanceType::Decoder(decoder),
+                    channel_gains: None,
+                    start_time,
+                    position_info,
                 };
                 SOUND_INSTANCES.with(|instances| {
                     let mut instances = instances.borrow_mut();
@@ -252,6 +292,9 @@ impl WebAudioBackend {
                                 let complete = WebAudioBackend::update_script_processor(instance, event);
                                 if complete {
                                     instances.remove(instance_handle);
+                                    COMPLETED_SOUNDS.with(|completed| {
+                                        completed.borrow_mut().push(instance_handle)
+                                    });
                                     script_node.disconnect().unwrap();
                                 }
                             })
@@ -341,6 +384,53 @@ impl WebAudioBackend {
         Ok(merger)
     }
 
+    /// Routes the output of a sound through `ChannelSplitter`, `Gain`, and `ChannelMerger` nodes,
+    /// so that its channels can be mixed by a sound transform.
+    fn create_channel_gains(
+        &self,
+        node: &web_sys::AudioNode,
+        is_stereo: bool,
+    ) -> Result<[web_sys::GainNode; 4], Box<dyn std::error::Error>> {
+        let splitter = self
+            .context
+            .create_channel_splitter_with_number_of_outputs(2)
+            .into_js_result()?;
+        let merger: web_sys::AudioNode = self
+            .context
+            .create_channel_merger_with_number_of_inputs(2)
+            .into_js_result()?
+            .into();
+
+        let gains = [
+            self.context.create_gain().into_js_result()?,
+            self.context.create_gain().into_js_result()?,
+            self.context.create_gain().into_js_result()?,
+            self.context.create_gain().into_js_result()?,
+        ];
+
+        node.disconnect().into_js_result()?;
+        node.connect_with_audio_node(&splitter).into_js_result()?;
+        // Each gain node takes one input channel and outputs into one output channel.
+        // Note that for mono tracks, we want to use channel 0 (left) for both the left and right.
+        let right_input = if is_stereo { 1 } else { 0 };
+        for (gain, &(input, output)) in
+            gains
+                .iter()
+                .zip(&[(0, 0), (0, 1), (right_input, 0), (right_input, 1)])
+        {
+            splitter
+                .connect_with_audio_node_and_output(gain, input)
+                .into_js_result()?;
+            gain.connect_with_audio_node_and_output_and_input(&merger, 0, output)
+                .into_js_result()?;
+        }
+        merger
+            .connect_with_audio_node(&self.context.destination())
+            .into_js_result()?;
+
+        Ok(gains)
+    }
+
     fn decompress_to_audio_buffer(
         &mut self,
         format: &swf::SoundFormat,
@@ -757,6 +847,14 @@ impl AudioBackend for WebAudioBackend {
         let _ = self.context.resume();
     }
 
+    fn play(&mut self) {
+        let _ = self.context.resume();
+    }
+
+    fn pause(&mut self) {
+        let _ = self.context.suspend();
+    }
+
     fn stop_all_sounds(&mut self) {
         SOUND_INSTANCES.with(|instances| {
             let mut instances = instances.borrow_mut();
@@ -787,6 +885,11 @@ impl AudioBackend for WebAudioBackend {
         })
     }
 
+    fn release_sound(&mut self, sound: SoundHandle) {
+        self.stop_sounds_with_handle(sound);
+        self.sounds.remove(sound);
+    }
+
     fn is_sound_playing_with_handle(&mut self, handle: SoundHandle) -> bool {
         SOUND_INSTANCES.with(|instances| {
             let instances = instances.borrow();
@@ -797,6 +900,61 @@ impl AudioBackend for WebAudioBackend {
         })
     }
 
+    fn take_completed_sounds(&mut self) -> Vec<SoundInstanceHandle> {
+        COMPLETED_SOUNDS.with(|completed| completed.replace(Vec::new()))
+    }
+
+    fn get_sound_position(&self, instance: SoundInstanceHandle) -> Option<u32> {
+        SOUND_INSTANCES.with(|instances| {
+            let instances = instances.borrow();
+            let instance = instances.get(instance)?;
+            let elapsed = (self.context.current_time() - instance.start_time) * 1000.0;
+            match &instance.position_info {
+                Some((settings, duration)) => Some(sound_position(settings, *duration, elapsed)),
+                None => Some(elapsed as u32),
+            }
+        })
+    }
+
+    fn get_stream_position(&self, stream: AudioStreamHandle) -> Option<u32> {
+        // The audio context doesn't advance while it is suspended, such as before the
+        // user has interacted with the page, so the timeline shouldn't wait on it.
+        if self.context.state() != AudioContextState::Running {
+            return None;
+        }
+        SOUND_INSTANCES.with(|instances| {
+            let instances = instances.borrow();
+            let instance = instances.get(stream)?;
+            let elapsed = (self.context.current_time() - instance.start_time) * 1000.0;
+            Some(elapsed.max(0.0) as u32)
+        })
+    }
+
+    fn set_sound_transform(&mut self, instance: SoundInstanceHandle, transform: SoundTransform) {
+        SOUND_INSTANCES.with(|instances| {
+            let mut instances = instances.borrow_mut();
+            if let Some(instance) = instances.get_mut(instance) {
+                if let SoundInstanceType::AudioBuffer(ref node) = instance.instance_type {
+                    if instance.channel_gains.is_none() {
+                        match self.create_channel_gains(node, instance.format.is_stereo) {
+                            Ok(gains) => instance.channel_gains = Some(gains),
+                            Err(e) => log::error!("Unable to apply sound transform: {}", e),
+                        }
+                    }
+                } else {
+                    // TODO: Apply transforms to sounds decoded on the fly.
+                    log::warn!("Sound transforms are unimplemented for decoded sounds");
+                }
+
+                if let Some(gains) = &instance.channel_gains {
+                    for (gain, &value) in gains.iter().zip(&transform.channel_gains()) {
+                        gain.gain().set_value(value);
+                    }
+                }
+            }
+        })
+    }
+
     fn get_sound_duration(&self, sound: SoundHandle) -> Option<u32> {
         if let Some(sound) = self.sounds.get(sound) {
             // AS duration does not subtract skip_sample_frames.
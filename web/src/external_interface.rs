@@ -0,0 +1,47 @@
+//! `ExternalInterface` calls between a movie and its page.
+use ruffle_core::external::{ExternalInterfaceProvider, Invocation, Value};
+use wasm_bindgen::prelude::*;
+
+#[wasm_bindgen(module = "/packages/core/src/ruffle-imports.js")]
+extern "C" {
+    /// Imported JS method to call a function of the page, given an `<invoke>`
+    /// request. Returns the result as XML.
+    #[wasm_bindgen(catch)]
+    fn call_external_interface(request: &str) -> Result<String, JsValue>;
+
+    /// Imported JS method to define a function on the player element which
+    /// calls a function exposed by the movie.
+    fn expose_external_callback(player: &JsValue, name: &str);
+}
+
+/// Calls into the page with the Flash Player's XML calling convention.
+pub struct WebExternalInterfaceProvider {
+    /// The player element, on which the movie's functions are exposed.
+    player: JsValue,
+}
+
+impl WebExternalInterfaceProvider {
+    pub fn new(player: JsValue) -> Self {
+        Self { player }
+    }
+}
+
+impl ExternalInterfaceProvider for WebExternalInterfaceProvider {
+    fn call_method(&mut self, name: &str, args: &[Value]) -> Value {
+        let request = Invocation::new(name, args.to_vec()).to_xml();
+        match call_external_interface(&request) {
+            Ok(response) => Value::from_xml(&response).unwrap_or_else(|e| {
+                log::warn!("ExternalInterface: Invalid result from {}: {}", name, e);
+                Value::Null
+            }),
+            Err(e) => {
+                log::warn!("ExternalInterface: Error calling {}: {:?}", name, e);
+                Value::Null
+            }
+        }
+    }
+
+    fn on_callback_available(&mut self, name: &str) {
+        expose_external_callback(&self.player, name);
+    }
+}
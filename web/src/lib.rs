@@ -1,24 +1,37 @@
 //! Ruffle web frontend.
 mod audio;
+mod external_interface;
 mod input;
+mod log_adapter;
 mod navigator;
 mod storage;
 
+use crate::external_interface::WebExternalInterfaceProvider;
+use crate::log_adapter::{report_script_timeout, WebLogBackend};
 use crate::storage::LocalStorageBackend;
 use crate::{audio::WebAudioBackend, input::WebInputBackend, navigator::WebNavigatorBackend};
 use generational_arena::{Arena, Index};
-use js_sys::Uint8Array;
+use js_sys::{Array, Object, Reflect, Uint8Array};
+use ruffle_core::backend::font::NullFontBackend;
 use ruffle_core::backend::render::RenderBackend;
 use ruffle_core::backend::storage::MemoryStorageBackend;
 use ruffle_core::backend::storage::StorageBackend;
+use ruffle_core::backend::video::software::SoftwareVideoBackend;
+use ruffle_core::events::MouseWheelDelta;
+use ruffle_core::external::{Invocation, Value as ExternalValue};
 use ruffle_core::tag_utils::SwfMovie;
-use ruffle_core::PlayerEvent;
+use ruffle_core::{
+    Language, Manufacturer, OperatingSystem, PlayerEvent, PlayerType, SystemCapabilities,
+    SystemProperties,
+};
 use ruffle_web_common::JsResult;
 use std::mem::drop;
 use std::sync::{Arc, Mutex};
 use std::{cell::RefCell, error::Error, num::NonZeroI32};
 use wasm_bindgen::{prelude::*, JsCast, JsValue};
-use web_sys::{Element, EventTarget, HtmlCanvasElement, HtmlElement, KeyboardEvent, PointerEvent};
+use web_sys::{
+    Element, EventTarget, HtmlCanvasElement, HtmlElement, KeyboardEvent, PointerEvent, WheelEvent,
+};
 
 thread_local! {
     /// We store the actual instances of the ruffle core in a static pool.
@@ -42,6 +55,7 @@ struct RuffleInstance {
     mouse_move_callback: Option<Closure<dyn FnMut(PointerEvent)>>,
     mouse_down_callback: Option<Closure<dyn FnMut(PointerEvent)>>,
     mouse_up_callback: Option<Closure<dyn FnMut(PointerEvent)>>,
+    mouse_wheel_callback: Option<Closure<dyn FnMut(WheelEvent)>>,
     window_mouse_down_callback: Option<Closure<dyn FnMut(PointerEvent)>>,
     key_down_callback: Option<Closure<dyn FnMut(KeyboardEvent)>>,
     key_up_callback: Option<Closure<dyn FnMut(KeyboardEvent)>>,
@@ -57,8 +71,20 @@ pub struct Ruffle(Index);
 
 #[wasm_bindgen]
 impl Ruffle {
-    pub fn new(parent: HtmlElement, swf_data: Uint8Array) -> Result<Ruffle, JsValue> {
-        Ruffle::new_internal(parent, swf_data).map_err(|_| "Error creating player".into())
+    /// Create a player for the movie in `swf_data`.
+    ///
+    /// The variables in the query string of the movie's `url` and in the
+    /// URL-encoded `flashvars` are passed to the movie, with those in
+    /// `flashvars` taking precedence.
+    pub fn new(
+        parent: HtmlElement,
+        swf_data: Uint8Array,
+        js_player: JsValue,
+        url: Option<String>,
+        flashvars: Option<String>,
+    ) -> Result<Ruffle, JsValue> {
+        Ruffle::new_internal(parent, swf_data, js_player, url, flashvars)
+            .map_err(|_| "Error creating player".into())
     }
 
     pub fn play(&mut self) {
@@ -71,6 +97,78 @@ impl Ruffle {
         });
     }
 
+    /// Call a function exposed by the movie with `ExternalInterface.addCallback`,
+    /// given an `<invoke>` request. Returns the result as XML.
+    ///
+    /// The movie can't be called back while it is running, such as from a
+    /// function it called with `ExternalInterface.call`; such calls return
+    /// `undefined`.
+    pub fn call_exposed_callback(&self, request: &str) -> String {
+        let invocation = match Invocation::from_xml(request) {
+            Ok(invocation) => invocation,
+            Err(e) => {
+                log::warn!("ExternalInterface: Invalid call: {}", e);
+                return ExternalValue::Undefined.to_xml();
+            }
+        };
+
+        let core = INSTANCES.with(|instances| {
+            let instances = instances.try_borrow().ok()?;
+            Some(instances.get(self.0)?.core.clone())
+        });
+        let result = match core.as_ref().map(|core| core.try_lock()) {
+            Some(Ok(mut core)) => core.call_internal_interface(&invocation.name, &invocation.args),
+            _ => {
+                log::warn!(
+                    "ExternalInterface: Unable to call {} while the movie is busy",
+                    invocation.name
+                );
+                ExternalValue::Undefined
+            }
+        };
+        result.to_xml()
+    }
+
+    /// Builds the context menu for a right-click at the current mouse position.
+    /// Returns an array of `{ caption, enabled, separatorBefore, checked }` items.
+    pub fn prepare_context_menu(&mut self) -> JsValue {
+        let items = self
+            .core()
+            .map(|core| core.lock().unwrap().prepare_context_menu())
+            .unwrap_or_default();
+
+        let array = Array::new();
+        for item in items {
+            let object = Object::new();
+            let properties: [(&str, JsValue); 4] = [
+                ("caption", item.caption.into()),
+                ("enabled", item.enabled.into()),
+                ("separatorBefore", item.separator_before.into()),
+                ("checked", item.checked.into()),
+            ];
+            for (name, value) in properties.iter() {
+                Reflect::set(&object, &JsValue::from_str(name), value).warn_on_error();
+            }
+            array.push(&object);
+        }
+        array.into()
+    }
+
+    /// Runs the item at the given index of the menu returned by `prepare_context_menu`.
+    pub fn run_context_menu_callback(&mut self, index: usize) {
+        if let Some(core) = self.core() {
+            core.lock().unwrap().run_context_menu_callback(index);
+        }
+    }
+
+    /// Forgets the menu returned by `prepare_context_menu` when it is closed
+    /// without choosing an item.
+    pub fn clear_custom_menu_items(&mut self) {
+        if let Some(core) = self.core() {
+            core.lock().unwrap().clear_custom_menu_items();
+        }
+    }
+
     pub fn destroy(&mut self) -> Result<(), JsValue> {
         // Remove instance from the active list.
         if let Some(mut instance) = INSTANCES.with(|instances| {
@@ -90,6 +188,7 @@ impl Ruffle {
             instance.mouse_down_callback = None;
             instance.mouse_move_callback = None;
             instance.mouse_up_callback = None;
+            instance.mouse_wheel_callback = None;
             instance.window_mouse_down_callback = None;
 
             // Cancel the animation handler, if it's still active.
@@ -106,14 +205,34 @@ impl Ruffle {
 }
 
 impl Ruffle {
-    fn new_internal(parent: HtmlElement, swf_data: Uint8Array) -> Result<Ruffle, Box<dyn Error>> {
+    /// The player of this instance, unless it was destroyed.
+    fn core(&self) -> Option<Arc<Mutex<ruffle_core::Player>>> {
+        INSTANCES.with(|instances| {
+            let instances = instances.borrow();
+            Some(instances.get(self.0)?.core.clone())
+        })
+    }
+
+    fn new_internal(
+        parent: HtmlElement,
+        swf_data: Uint8Array,
+        js_player: JsValue,
+        url: Option<String>,
+        flashvars: Option<String>,
+    ) -> Result<Ruffle, Box<dyn Error>> {
         console_error_panic_hook::set_once();
         let _ = console_log::init_with_level(log::Level::Trace);
 
         let movie = {
             let mut data = vec![0; swf_data.length() as usize];
             swf_data.copy_to(&mut data[..]);
-            SwfMovie::from_data(&data)?
+            let mut movie = SwfMovie::from_data(&data, url)?;
+            if let Some(flashvars) = flashvars {
+                movie.append_parameters(
+                    url::form_urlencoded::parse(flashvars.as_bytes()).into_owned(),
+                );
+            }
+            movie
         };
 
         let window = web_sys::window().ok_or_else(|| "Expected window")?;
@@ -138,9 +257,28 @@ impl Ruffle {
             })
             .unwrap_or_else(|| Box::new(MemoryStorageBackend::default()));
 
-        let core =
-            ruffle_core::Player::new(renderer, audio, navigator, input, movie, local_storage)?;
+        let fonts = Box::new(NullFontBackend::new()); //TODO: render device text with canvas fonts
+
+        let video = Box::new(SoftwareVideoBackend::new());
+
+        let core = ruffle_core::Player::new(
+            renderer,
+            audio,
+            navigator,
+            input,
+            movie,
+            local_storage,
+            fonts,
+            video,
+        )?;
         let mut core_lock = core.lock().unwrap();
+        core_lock.set_system_properties(system_properties(&window));
+        core_lock.set_log_backend(Box::new(WebLogBackend));
+        core_lock.set_script_timeout_handler({
+            let js_player = js_player.clone();
+            Box::new(move || report_script_timeout(&js_player))
+        });
+        core_lock.set_external_interface(Box::new(WebExternalInterfaceProvider::new(js_player)));
         let frame_rate = core_lock.frame_rate();
         core_lock.audio_mut().set_frame_rate(frame_rate);
         drop(core_lock);
@@ -158,6 +296,7 @@ impl Ruffle {
             mouse_down_callback: None,
             window_mouse_down_callback: None,
             mouse_up_callback: None,
+            mouse_wheel_callback: None,
             key_down_callback: None,
             key_up_callback: None,
             timestamp: None,
@@ -304,6 +443,42 @@ impl Ruffle {
                 instance.mouse_up_callback = Some(mouse_up_callback);
             }
 
+            // Create mouse wheel handler.
+            {
+                let mouse_wheel_callback = Closure::wrap(Box::new(move |js_event: WheelEvent| {
+                    INSTANCES.with(move |instances| {
+                        let mut instances = instances.borrow_mut();
+                        if let Some(instance) = instances.get_mut(index) {
+                            // Browsers scroll down for positive deltas, while Flash scrolls up.
+                            let delta = match js_event.delta_mode() {
+                                WheelEvent::DOM_DELTA_LINE => {
+                                    MouseWheelDelta::Lines(-js_event.delta_y())
+                                }
+                                _ => MouseWheelDelta::Pixels(-js_event.delta_y()),
+                            };
+                            instance
+                                .core
+                                .lock()
+                                .unwrap()
+                                .handle_event(PlayerEvent::MouseWheel { delta });
+                            if instance.has_focus {
+                                js_event.prevent_default();
+                            }
+                        }
+                    });
+                })
+                    as Box<dyn FnMut(WheelEvent)>);
+                let canvas_events: &EventTarget = canvas.as_ref();
+                canvas_events
+                    .add_event_listener_with_callback(
+                        "wheel",
+                        mouse_wheel_callback.as_ref().unchecked_ref(),
+                    )
+                    .unwrap();
+                let instance = instances.get_mut(index).unwrap();
+                instance.mouse_wheel_callback = Some(mouse_wheel_callback);
+            }
+
             // Create click event handler.
             // {
             //     let click_callback = Closure::wrap(Box::new(move |_| {
@@ -335,14 +510,6 @@ impl Ruffle {
                         if let Some(instance) = instances.borrow_mut().get_mut(index) {
                             if instance.has_focus {
                                 let code = js_event.code();
-                                instance
-                                    .core
-                                    .lock()
-                                    .unwrap()
-                                    .input_mut()
-                                    .downcast_mut::<WebInputBackend>()
-                                    .unwrap()
-                                    .keydown(code.clone());
 
                                 if let Some(codepoint) =
                                     input::web_key_to_codepoint(&js_event.key())
@@ -386,14 +553,6 @@ impl Ruffle {
                         if let Some(instance) = instances.borrow_mut().get_mut(index) {
                             if instance.has_focus {
                                 let code = js_event.code();
-                                instance
-                                    .core
-                                    .lock()
-                                    .unwrap()
-                                    .input_mut()
-                                    .downcast_mut::<WebInputBackend>()
-                                    .unwrap()
-                                    .keyup(code.clone());
 
                                 if let Some(key_code) = input::web_to_ruffle_key_code(&code) {
                                     instance
@@ -502,6 +661,40 @@ impl Ruffle {
     }
 }
 
+/// Describe the user's system to the movie.
+fn system_properties(window: &web_sys::Window) -> SystemProperties {
+    let navigator = window.navigator();
+    let platform = navigator.platform().unwrap_or_default();
+    let (os, manufacturer) = if platform.starts_with("Win") {
+        (OperatingSystem::WindowsUnknown, Manufacturer::Windows)
+    } else if platform.starts_with("Mac") {
+        (OperatingSystem::MacOS, Manufacturer::Macintosh)
+    } else {
+        (OperatingSystem::Linux, Manufacturer::Linux)
+    };
+    let language = navigator
+        .language()
+        .map(|language| Language::from_language_code(&language))
+        .unwrap_or(Language::English);
+    let screen_resolution = window
+        .screen()
+        .and_then(|screen| Ok((screen.width()?, screen.height()?)))
+        .map(|(width, height)| (width as u32, height as u32))
+        .unwrap_or_default();
+
+    SystemProperties {
+        capabilities: SystemCapabilities::Audio
+            | SystemCapabilities::MP3
+            | SystemCapabilities::StreamingAudio,
+        player_type: PlayerType::PlugIn,
+        language,
+        screen_resolution,
+        manufacturer,
+        os,
+        ..Default::default()
+    }
+}
+
 fn create_renderer(
     document: &web_sys::Document,
 ) -> Result<(HtmlCanvasElement, Box<dyn RenderBackend>), Box<dyn Error>> {
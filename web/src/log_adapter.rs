@@ -0,0 +1,23 @@
+//! Output of the movie's scripts to the browser console.
+use ruffle_core::backend::log::LogBackend;
+use wasm_bindgen::prelude::*;
+
+#[wasm_bindgen(module = "/packages/core/src/ruffle-imports.js")]
+extern "C" {
+    /// Imported JS method to tell the player element that a script of the
+    /// movie ran for too long and was aborted.
+    pub fn report_script_timeout(player: &JsValue);
+}
+
+/// Sends the messages traced by the movie to the browser console.
+pub struct WebLogBackend;
+
+impl LogBackend for WebLogBackend {
+    fn avm_trace(&self, message: &str) {
+        web_sys::console::log_1(&JsValue::from_str(message));
+    }
+
+    fn avm_warning(&self, message: &str) {
+        web_sys::console::warn_1(&JsValue::from_str(message));
+    }
+}
@@ -1,19 +1,45 @@
 //! Navigator backend for web
 
-use js_sys::{Array, ArrayBuffer, Uint8Array};
+use generational_arena::Arena;
+use js_sys::{Array, ArrayBuffer, Date, Promise, Reflect, Uint8Array};
 use ruffle_core::backend::navigator::{
-    NavigationMethod, NavigatorBackend, OwnedFuture, RequestOptions,
+    CompleteBody, NavigationMethod, NavigationTarget, NavigatorBackend, OwnedFuture,
+    RequestOptions, ResponseBody, SocketEvent, SocketHandle,
 };
 use ruffle_core::loader::Error;
+use std::cell::{Cell, RefCell};
 use std::collections::HashMap;
+use std::rc::Rc;
 use std::time::Duration;
-use wasm_bindgen::JsCast;
+use wasm_bindgen::{closure::Closure, prelude::wasm_bindgen, JsCast, JsValue};
 use wasm_bindgen_futures::{spawn_local, JsFuture};
-use web_sys::{window, Blob, BlobPropertyBag, Performance, Request, RequestInit, Response};
+use web_sys::{
+    window, BinaryType, Blob, BlobPropertyBag, MessageEvent, Performance, Request, RequestInit,
+    Response, WebSocket,
+};
 
 pub struct WebNavigatorBackend {
     performance: Performance,
     start_time: f64,
+
+    /// The URL of the WebSocket proxy used to make socket connections, from the
+    /// `socketProxy` option of the Ruffle config. `{host}` and `{port}` in the URL
+    /// are replaced with the host and port to connect to.
+    socket_proxy: Option<String>,
+
+    /// Open socket connections. `None` if the connection could not be opened.
+    sockets: Arena<Option<WebSocketConnection>>,
+
+    /// Socket events reported by WebSocket callbacks, waiting to be polled.
+    socket_events: Rc<RefCell<Vec<SocketEvent>>>,
+}
+
+/// A WebSocket used for a socket connection, along with its callbacks.
+struct WebSocketConnection {
+    web_socket: WebSocket,
+    _on_open: Closure<dyn FnMut()>,
+    _on_message: Closure<dyn FnMut(MessageEvent)>,
+    _on_close: Closure<dyn FnMut()>,
 }
 
 impl WebNavigatorBackend {
@@ -21,9 +47,99 @@ impl WebNavigatorBackend {
         let window = web_sys::window().expect("window()");
         let performance = window.performance().expect("window.performance()");
 
+        // Read `window.RufflePlayer.config.socketProxy`, if it exists.
+        let socket_proxy = ["RufflePlayer", "config", "socketProxy"]
+            .iter()
+            .try_fold(JsValue::from(window), |object, key| {
+                Reflect::get(&object, &JsValue::from_str(key)).ok()
+            })
+            .and_then(|socket_proxy| socket_proxy.as_string());
+
         WebNavigatorBackend {
             start_time: performance.now(),
             performance,
+            socket_proxy,
+            sockets: Arena::new(),
+            socket_events: Rc::new(RefCell::new(Vec::new())),
+        }
+    }
+
+    /// Opens a WebSocket to the socket proxy for a socket connection.
+    fn open_web_socket(
+        &self,
+        socket: SocketHandle,
+        host: &str,
+        port: u16,
+    ) -> Result<WebSocketConnection, String> {
+        let socket_proxy = self
+            .socket_proxy
+            .as_ref()
+            .ok_or("No socket proxy is configured")?;
+        let url = socket_proxy
+            .replace("{host}", host)
+            .replace("{port}", &port.to_string());
+        let web_socket =
+            WebSocket::new(&url).map_err(|_| format!("Invalid WebSocket URL {}", url))?;
+        web_socket.set_binary_type(BinaryType::Arraybuffer);
+
+        let is_open = Rc::new(Cell::new(false));
+        let on_open = {
+            let events = Rc::clone(&self.socket_events);
+            let is_open = Rc::clone(&is_open);
+            Closure::wrap(Box::new(move || {
+                is_open.set(true);
+                events
+                    .borrow_mut()
+                    .push(SocketEvent::Connected(socket, true));
+            }) as Box<dyn FnMut()>)
+        };
+        let on_message = {
+            let events = Rc::clone(&self.socket_events);
+            Closure::wrap(Box::new(move |event: MessageEvent| {
+                let data = event.data();
+                let data = if let Some(text) = data.as_string() {
+                    text.into_bytes()
+                } else {
+                    let array = Uint8Array::new(&data);
+                    let mut bytes = vec![0; array.length() as usize];
+                    array.copy_to(&mut bytes);
+                    bytes
+                };
+                events.borrow_mut().push(SocketEvent::Data(socket, data));
+            }) as Box<dyn FnMut(MessageEvent)>)
+        };
+        let on_close = {
+            let events = Rc::clone(&self.socket_events);
+            Closure::wrap(Box::new(move || {
+                // A WebSocket that fails to connect is also closed.
+                let event = if is_open.get() {
+                    SocketEvent::Closed(socket)
+                } else {
+                    SocketEvent::Connected(socket, false)
+                };
+                events.borrow_mut().push(event);
+            }) as Box<dyn FnMut()>)
+        };
+        web_socket.set_onopen(Some(on_open.as_ref().unchecked_ref()));
+        web_socket.set_onmessage(Some(on_message.as_ref().unchecked_ref()));
+        web_socket.set_onclose(Some(on_close.as_ref().unchecked_ref()));
+
+        Ok(WebSocketConnection {
+            web_socket,
+            _on_open: on_open,
+            _on_message: on_message,
+            _on_close: on_close,
+        })
+    }
+
+    /// Closes a socket connection and removes its callbacks.
+    fn remove_socket(&mut self, socket: SocketHandle) {
+        if let Some(Some(connection)) = self.sockets.remove(socket) {
+            let web_socket = &connection.web_socket;
+            web_socket.set_onopen(None);
+            web_socket.set_onmessage(None);
+            web_socket.set_onclose(None);
+            let _ = web_socket.close();
         }
     }
 }
@@ -32,14 +148,14 @@ impl NavigatorBackend for WebNavigatorBackend {
     fn navigate_to_url(
         &self,
         url: String,
-        window_spec: Option<String>,
+        target: NavigationTarget,
         vars_method: Option<(NavigationMethod, HashMap<String, String>)>,
     ) {
         if let Some(window) = window() {
             //TODO: Should we return a result for failed opens? Does Flash care?
             #[allow(unused_must_use)]
-            match (vars_method, window_spec) {
-                (Some((navmethod, formvars)), window_spec) => {
+            match (vars_method, target) {
+                (Some((navmethod, formvars)), target) => {
                     let document = match window.document() {
                         Some(document) => document,
                         None => return,
@@ -61,12 +177,10 @@ impl NavigatorBackend for WebNavigatorBackend {
 
                     form.set_attribute("action", &url);
 
-                    if let Some(target) = window_spec {
-                        form.set_attribute("target", &target);
-                    }
+                    form.set_attribute("target", target.name());
 
                     for (k, v) in formvars.iter() {
-                        let hidden = document.create_element("hidden").unwrap();
+                        let hidden = document.create_element("input").unwrap();
 
                         hidden.set_attribute("type", "hidden");
                         hidden.set_attribute("name", k);
@@ -78,12 +192,12 @@ impl NavigatorBackend for WebNavigatorBackend {
                     document.body().unwrap().append_child(&form);
                     form.submit();
                 }
-                (_, Some(ref window_name)) if window_name != "" => {
-                    window.open_with_url_and_target(&url, window_name);
-                }
-                _ => {
+                (None, NavigationTarget::SelfWindow) => {
                     window.location().assign(&url);
                 }
+                (None, target) => {
+                    window.open_with_url_and_target(&url, target.name());
+                }
             };
         }
     }
@@ -93,51 +207,23 @@ impl NavigatorBackend for WebNavigatorBackend {
         Duration::from_millis(dt as u64)
     }
 
+    fn current_time(&self) -> f64 {
+        Date::now()
+    }
+
+    fn timezone_offset(&self, time: f64) -> i32 {
+        let offset = Date::new(&JsValue::from_f64(time)).get_timezone_offset();
+        if offset.is_finite() {
+            offset as i32
+        } else {
+            0
+        }
+    }
+
     fn fetch(&self, url: &str, options: RequestOptions) -> OwnedFuture<Vec<u8>, Error> {
         let url = url.to_string();
         Box::pin(async move {
-            let mut init = RequestInit::new();
-
-            init.method(match options.method() {
-                NavigationMethod::GET => "GET",
-                NavigationMethod::POST => "POST",
-            });
-
-            if let Some((data, mime)) = options.body() {
-                let arraydata = ArrayBuffer::new(data.len() as u32);
-                let u8data = Uint8Array::new(&arraydata);
-
-                for (i, byte) in data.iter().enumerate() {
-                    u8data.fill(*byte, i as u32, i as u32 + 1);
-                }
-
-                let blobparts = Array::new();
-                blobparts.push(&arraydata);
-
-                let mut blobprops = BlobPropertyBag::new();
-                blobprops.type_(mime);
-
-                let datablob =
-                    Blob::new_with_buffer_source_sequence_and_options(&blobparts, &blobprops)
-                        .unwrap()
-                        .dyn_into()
-                        .unwrap();
-
-                init.body(Some(&datablob));
-            }
-
-            let request = Request::new_with_str_and_init(&url, &init).unwrap();
-
-            let window = web_sys::window().unwrap();
-            let fetchval = JsFuture::from(window.fetch_with_request(&request)).await;
-            if fetchval.is_err() {
-                return Err(Error::NetworkError(std::io::Error::new(
-                    std::io::ErrorKind::Other,
-                    "Could not fetch, got JS Error",
-                )));
-            }
-
-            let resp: Response = fetchval.unwrap().dyn_into().unwrap();
+            let resp = fetch_response(&url, options).await?;
             let data: ArrayBuffer = JsFuture::from(resp.array_buffer().unwrap())
                 .await
                 .unwrap()
@@ -151,6 +237,30 @@ impl NavigatorBackend for WebNavigatorBackend {
         })
     }
 
+    fn fetch_stream(
+        &self,
+        url: &str,
+        options: RequestOptions,
+    ) -> OwnedFuture<Box<dyn ResponseBody>, Error> {
+        let url = url.to_string();
+        Box::pin(async move {
+            let resp = fetch_response(&url, options).await?;
+            let total_length = resp
+                .headers()
+                .get("Content-Length")
+                .ok()
+                .flatten()
+                .and_then(|length| length.parse().ok());
+            match resp.body() {
+                Some(body) => Ok(Box::new(StreamBody {
+                    reader: body.unchecked_into::<BodyStream>().get_reader(),
+                    total_length,
+                }) as Box<dyn ResponseBody>),
+                None => Ok(Box::new(CompleteBody::new(Vec::new())) as Box<dyn ResponseBody>),
+            }
+        })
+    }
+
     fn spawn_future(&mut self, future: OwnedFuture<(), Error>) {
         spawn_local(async move {
             if let Err(e) = future.await {
@@ -158,4 +268,148 @@ impl NavigatorBackend for WebNavigatorBackend {
             }
         })
     }
+
+    fn connect_socket(&mut self, host: &str, port: u16) -> SocketHandle {
+        let socket = self.sockets.insert(None);
+        match self.open_web_socket(socket, host, port) {
+            Ok(connection) => self.sockets[socket] = Some(connection),
+            Err(e) => {
+                log::warn!("Could not connect socket to {}:{}: {}", host, port, e);
+                self.socket_events
+                    .borrow_mut()
+                    .push(SocketEvent::Connected(socket, false));
+            }
+        }
+        socket
+    }
+
+    fn send_socket(&mut self, socket: SocketHandle, mut data: Vec<u8>) {
+        if let Some(Some(connection)) = self.sockets.get(socket) {
+            if connection.web_socket.send_with_u8_array(&mut data).is_err() {
+                log::warn!("Could not send data over socket");
+            }
+        }
+    }
+
+    fn close_socket(&mut self, socket: SocketHandle) {
+        self.remove_socket(socket);
+        self.socket_events
+            .borrow_mut()
+            .retain(|event| event.socket() != socket);
+    }
+
+    fn poll_socket_events(&mut self) -> Vec<SocketEvent> {
+        let events = std::mem::take(&mut *self.socket_events.borrow_mut());
+        for event in &events {
+            match event {
+                SocketEvent::Connected(socket, false) | SocketEvent::Closed(socket) => {
+                    self.remove_socket(*socket);
+                }
+                _ => (),
+            }
+        }
+        events
+    }
+}
+
+/// Sends a request, returning the response once its headers have arrived.
+async fn fetch_response(url: &str, options: RequestOptions) -> Result<Response, Error> {
+    let mut init = RequestInit::new();
+
+    init.method(match options.method() {
+        NavigationMethod::GET => "GET",
+        NavigationMethod::POST => "POST",
+    });
+
+    if let Some((data, mime)) = options.body() {
+        let arraydata = ArrayBuffer::new(data.len() as u32);
+        let u8data = Uint8Array::new(&arraydata);
+
+        for (i, byte) in data.iter().enumerate() {
+            u8data.fill(*byte, i as u32, i as u32 + 1);
+        }
+
+        let blobparts = Array::new();
+        blobparts.push(&arraydata);
+
+        let mut blobprops = BlobPropertyBag::new();
+        blobprops.type_(mime);
+
+        let datablob = Blob::new_with_buffer_source_sequence_and_options(&blobparts, &blobprops)
+            .unwrap()
+            .dyn_into()
+            .unwrap();
+
+        init.body(Some(&datablob));
+    }
+
+    let request = Request::new_with_str_and_init(url, &init).unwrap();
+    for (name, value) in options.headers() {
+        if request.headers().set(name, value).is_err() {
+            log::warn!("Unable to set request header {}: {}", name, value);
+        }
+    }
+
+    let window = web_sys::window().unwrap();
+    let fetchval = JsFuture::from(window.fetch_with_request(&request)).await;
+    if fetchval.is_err() {
+        return Err(Error::NetworkError(std::io::Error::new(
+            std::io::ErrorKind::Other,
+            "Could not fetch, got JS Error",
+        )));
+    }
+
+    Ok(fetchval.unwrap().dyn_into().unwrap())
+}
+
+#[wasm_bindgen]
+extern "C" {
+    /// The `ReadableStream` of a response body, with the `getReader` method
+    /// that web-sys doesn't bind yet.
+    type BodyStream;
+
+    #[wasm_bindgen(method, js_name = getReader)]
+    fn get_reader(this: &BodyStream) -> BodyReader;
+
+    /// The `ReadableStreamDefaultReader` of a response body.
+    type BodyReader;
+
+    #[wasm_bindgen(method)]
+    fn read(this: &BodyReader) -> Promise;
+}
+
+/// The body of a response fetched by `fetch_stream`, which is read as it arrives.
+struct StreamBody {
+    reader: BodyReader,
+    total_length: Option<usize>,
+}
+
+impl ResponseBody for StreamBody {
+    fn total_length(&self) -> Option<usize> {
+        self.total_length
+    }
+
+    fn next_chunk(&mut self) -> OwnedFuture<Option<Vec<u8>>, Error> {
+        let read = JsFuture::from(self.reader.read());
+        Box::pin(async move {
+            let result = read.await.map_err(|_| {
+                Error::NetworkError(std::io::Error::new(
+                    std::io::ErrorKind::Other,
+                    "Could not read response body, got JS Error",
+                ))
+            })?;
+            let is_done = Reflect::get(&result, &JsValue::from_str("done"))
+                .ok()
+                .and_then(|done| done.as_bool())
+                .unwrap_or(true);
+            if is_done {
+                return Ok(None);
+            }
+            let chunk: Uint8Array = Reflect::get(&result, &JsValue::from_str("value"))
+                .unwrap()
+                .dyn_into()
+                .unwrap();
+            Ok(Some(chunk.to_vec()))
+        })
+    }
 }
@@ -1,5 +1,5 @@
 use ruffle_core::backend::storage::StorageBackend;
-use web_sys::Storage;
+use web_sys::{window, Storage};
 
 pub struct LocalStorageBackend {
     storage: Storage,
@@ -13,15 +13,24 @@ impl LocalStorageBackend {
 }
 
 impl StorageBackend for LocalStorageBackend {
-    fn get_string(&self, name: &str) -> Option<String> {
-        self.storage
+    fn get(&self, name: &str) -> Option<Vec<u8>> {
+        let value = self
+            .storage
             .get(&format!("{}-{}", self.prefix, name))
-            .unwrap_or_default()
+            .unwrap_or_default()?;
+        // Values are stored as base64, which `atob` decodes to a string of one char per byte.
+        let decoded = window()?.atob(&value).ok()?;
+        Some(decoded.chars().map(|c| c as u8).collect())
     }
 
-    fn put_string(&mut self, name: &str, value: String) -> bool {
+    fn put(&mut self, name: &str, value: &[u8]) -> bool {
+        let binary: String = value.iter().map(|&b| char::from(b)).collect();
+        let encoded = match window().map(|window| window.btoa(&binary)) {
+            Some(Ok(encoded)) => encoded,
+            _ => return false,
+        };
         self.storage
-            .set(&format!("{}-{}", self.prefix, name), &value)
+            .set(&format!("{}-{}", self.prefix, name), &encoded)
             .is_ok()
     }
 